@@ -0,0 +1,70 @@
+use super::{find_tool, run_tool, PreTransport};
+use crate::websocket_transport::{WebSocketHeader, WebSocketTransport};
+use anyhow::Result;
+use async_trait::async_trait;
+use tokio::io::DuplexStream;
+
+/// An SSH host published as a Cloudflare Access application. SSH runs over a
+/// WebSocket to the hostname, carrying the Access token `cloudflared` issues,
+/// the same way `cloudflared access ssh` does.
+pub(super) struct CloudflareAccess {
+    pub hostname: String,
+}
+
+impl CloudflareAccess {
+    fn app_url(&self) -> String {
+        format!("https://{}", self.hostname)
+    }
+
+    fn bridge(&self, token: String) -> WebSocketTransport {
+        WebSocketTransport {
+            url: format!("wss://{}", self.hostname),
+            headers: vec![WebSocketHeader {
+                name: "cf-access-token".to_string(),
+                value: token,
+            }],
+        }
+    }
+
+    /// The cached token, or a new one after a browser login when it expired.
+    async fn token(&self) -> Result<String> {
+        let cloudflared = find_tool("cloudflared")?;
+        let app = format!("-app={}", self.app_url());
+        if let Ok(token) = run_tool(&cloudflared, &["access", "token", &app]).await {
+            if !token.is_empty() {
+                return Ok(token);
+            }
+        }
+        println!(
+            "[SSH] Logging in to Cloudflare Access for {}",
+            self.hostname
+        );
+        run_tool(&cloudflared, &["access", "login", &self.app_url()]).await?;
+        run_tool(&cloudflared, &["access", "token", &app]).await
+    }
+}
+
+#[async_trait]
+impl PreTransport for CloudflareAccess {
+    async fn open(&self) -> Result<DuplexStream> {
+        let token = self.token().await?;
+        crate::websocket_transport::connect(&self.bridge(token)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sends_the_token_to_the_hostname() {
+        let access = CloudflareAccess {
+            hostname: "ssh.example.com".to_string(),
+        };
+        assert_eq!(access.app_url(), "https://ssh.example.com");
+        let bridge = access.bridge("jwt".to_string());
+        assert_eq!(bridge.url, "wss://ssh.example.com");
+        assert_eq!(bridge.headers[0].name, "cf-access-token");
+        assert_eq!(bridge.headers[0].value, "jwt");
+    }
+}
@@ -0,0 +1,104 @@
+use super::{find_tool, PreTransport};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use std::process::Stdio;
+use tokio::io::{AsyncBufReadExt, BufReader, DuplexStream};
+
+const BUFFER: usize = 64 * 1024;
+
+/// A Compute Engine instance reached through Identity-Aware Proxy TCP
+/// forwarding. `gcloud` signs the tunnel with the active account's token and
+/// relays it on its stdin and stdout.
+pub(super) struct GcpIap {
+    pub instance: String,
+    pub zone: String,
+    pub project: Option<String>,
+    pub port: u16,
+}
+
+impl GcpIap {
+    fn args(&self) -> Vec<String> {
+        let mut args = vec![
+            "compute".to_string(),
+            "start-iap-tunnel".to_string(),
+            self.instance.clone(),
+            self.port.to_string(),
+            "--listen-on-stdin".to_string(),
+            format!("--zone={}", self.zone),
+        ];
+        if let Some(project) = &self.project {
+            args.push(format!("--project={project}"));
+        }
+        args
+    }
+}
+
+#[async_trait]
+impl PreTransport for GcpIap {
+    async fn open(&self) -> Result<DuplexStream> {
+        if self.zone.is_empty() {
+            return Err(anyhow!("GCP IAP needs the instance's zone"));
+        }
+        let gcloud = find_tool("gcloud")?;
+        let mut child = tokio::process::Command::new(&gcloud)
+            .args(self.args())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| anyhow!("Failed to run {}: {e}", gcloud.display()))?;
+        let (Some(mut stdin), Some(mut stdout), Some(stderr)) =
+            (child.stdin.take(), child.stdout.take(), child.stderr.take())
+        else {
+            return Err(anyhow!("gcloud start-iap-tunnel has no stdio"));
+        };
+
+        let instance = self.instance.clone();
+        tauri::async_runtime::spawn(async move {
+            let mut lines = BufReader::new(stderr).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                eprintln!("[IAP] {instance}: {line}");
+            }
+        });
+
+        let (ssh_side, tunnel_side) = tokio::io::duplex(BUFFER);
+        tauri::async_runtime::spawn(async move {
+            let (mut reader, mut writer) = tokio::io::split(tunnel_side);
+            tokio::select! {
+                _ = tokio::io::copy(&mut reader, &mut stdin) => {}
+                _ = tokio::io::copy(&mut stdout, &mut writer) => {}
+            }
+            // Dropping the child (kill_on_drop) ends the tunnel.
+            drop(child);
+        });
+        Ok(ssh_side)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tunnels_to_the_ssh_port() {
+        let iap = GcpIap {
+            instance: "web-1".to_string(),
+            zone: "europe-west1-b".to_string(),
+            project: Some("acme-prod".to_string()),
+            port: 22,
+        };
+        assert_eq!(
+            iap.args(),
+            [
+                "compute",
+                "start-iap-tunnel",
+                "web-1",
+                "22",
+                "--listen-on-stdin",
+                "--zone=europe-west1-b",
+                "--project=acme-prod",
+            ]
+        );
+    }
+}
@@ -0,0 +1,119 @@
+//! Zero-trust proxies in front of SSH hosts (Cloudflare Access, GCP IAP).
+//!
+//! A connection with an [`AccessProxy`] runs that provider's hook before SSH
+//! starts: it gets a short-lived credential from the provider's CLI, logging
+//! in first when needed, and opens the proxied byte stream the SSH handshake
+//! then runs over. Everything after that (host keys, auth, channels) is the
+//! same as a direct connection. Providers implement [`PreTransport`].
+
+mod cloudflare;
+mod gcp_iap;
+
+use crate::types::ConnectionConfig;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tokio::io::DuplexStream;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "provider", rename_all = "camelCase")]
+pub enum AccessProxy {
+    /// `cloudflared access`; `hostname` is the Access application's SSH
+    /// hostname, the connection's host when unset.
+    #[serde(rename_all = "camelCase")]
+    Cloudflare { hostname: Option<String> },
+    /// `gcloud compute start-iap-tunnel`; `instance` defaults to the host.
+    #[serde(rename_all = "camelCase")]
+    GcpIap {
+        instance: Option<String>,
+        zone: String,
+        project: Option<String>,
+    },
+}
+
+/// Sets up the stream a connection's SSH session runs over.
+#[async_trait]
+trait PreTransport: Send + Sync {
+    async fn open(&self) -> Result<DuplexStream>;
+}
+
+impl AccessProxy {
+    fn hook(&self, config: &ConnectionConfig) -> Box<dyn PreTransport> {
+        let or_host = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .unwrap_or(&config.host)
+                .to_string()
+        };
+        match self {
+            Self::Cloudflare { hostname } => Box::new(cloudflare::CloudflareAccess {
+                hostname: or_host(hostname),
+            }),
+            Self::GcpIap {
+                instance,
+                zone,
+                project,
+            } => Box::new(gcp_iap::GcpIap {
+                instance: or_host(instance),
+                zone: zone.trim().to_string(),
+                project: project.clone().filter(|project| !project.trim().is_empty()),
+                port: config.port,
+            }),
+        }
+    }
+}
+
+/// Run the proxy's hook for `config` and return the stream to hand to
+/// `russh::client::connect_stream`.
+pub async fn connect(proxy: &AccessProxy, config: &ConnectionConfig) -> Result<DuplexStream> {
+    proxy.hook(config).open().await
+}
+
+/// `name` on `PATH`, or where its installer usually puts it; GUI apps on
+/// macOS don't inherit the shell's `PATH`.
+fn find_tool(name: &str) -> Result<PathBuf> {
+    let file = if cfg!(target_os = "windows") && name == "gcloud" {
+        "gcloud.cmd".to_string()
+    } else if cfg!(target_os = "windows") {
+        format!("{name}.exe")
+    } else {
+        name.to_string()
+    };
+    let home = dirs::home_dir().unwrap_or_default();
+    let mut dirs: Vec<PathBuf> = std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).collect())
+        .unwrap_or_default();
+    dirs.extend([
+        PathBuf::from("/opt/homebrew/bin"),
+        PathBuf::from("/usr/local/bin"),
+        home.join("google-cloud-sdk/bin"),
+    ]);
+    dirs.into_iter()
+        .map(|dir| dir.join(&file))
+        .find(|candidate| candidate.is_file())
+        .ok_or_else(|| anyhow!("`{name}` was not found; install it and make sure it is on PATH"))
+}
+
+/// Run a provider CLI to completion, failing with its stderr.
+async fn run_tool(program: &PathBuf, args: &[&str]) -> Result<String> {
+    let output = tokio::process::Command::new(program)
+        .args(args)
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|e| anyhow!("Failed to run {}: {e}", program.display()))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!(
+            "{} {} failed: {}",
+            program.display(),
+            args.first().copied().unwrap_or_default(),
+            stderr.trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
@@ -0,0 +1,96 @@
+use super::{
+    add_user_command, authorize_key_command, parse_report, read_script, validate_public_key,
+    validate_shell, validate_username, AccountsReport,
+};
+use crate::commands::{
+    ensure_connection_online, exec_on_connection, exec_on_connection_with_stdin, AppState,
+};
+use crate::host_capabilities::{elevation, Elevation};
+use tauri::State;
+
+/// Users, groups, the last logins and current sessions of a host.
+#[tauri::command]
+pub async fn accounts_report(
+    state: State<'_, AppState>,
+    connection_id: String,
+) -> Result<AccountsReport, String> {
+    ensure_connection_online(&state, &connection_id).await?;
+    if crate::remote_os::connection_is_windows(&state, &connection_id).await {
+        return Err("Account auditing is not supported on Windows hosts".to_string());
+    }
+    let output = exec_on_connection(&state, &connection_id, &read_script()).await?;
+    Ok(parse_report(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Run `command` as root; stdout is returned, a failure carries stderr.
+async fn run_as_root(
+    state: &AppState,
+    connection_id: &str,
+    command: &str,
+    sudo_password: Option<String>,
+) -> Result<String, String> {
+    ensure_connection_online(state, connection_id).await?;
+    let capabilities = crate::host_capabilities::probe(state, connection_id, false).await?;
+    if capabilities.windows {
+        return Err("Adding users is not supported on Windows hosts".to_string());
+    }
+    let elevation = elevation(&capabilities, sudo_password.is_some())?;
+    let command = format!("{}{command}", elevation.prefix());
+    let output = match (elevation, sudo_password) {
+        (Elevation::SudoWithPassword, Some(password)) => {
+            let input = format!("{password}\n");
+            exec_on_connection_with_stdin(state, connection_id, &command, input.as_bytes()).await?
+        }
+        _ => exec_on_connection(state, connection_id, &command).await?,
+    };
+    if output.exit_status != 0 {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Create a login user with a home directory, optionally authorizing one
+/// SSH public key. Fails if the user exists or `shell` isn't in `/etc/shells`.
+#[tauri::command]
+pub async fn accounts_add_ssh_user(
+    state: State<'_, AppState>,
+    connection_id: String,
+    username: String,
+    public_key: Option<String>,
+    shell: Option<String>,
+    sudo_password: Option<String>,
+) -> Result<(), String> {
+    validate_username(&username)?;
+    let key = public_key
+        .filter(|key| !key.trim().is_empty())
+        .map(|key| validate_public_key(&key))
+        .transpose()?;
+    if let Some(shell) = &shell {
+        validate_shell(shell)?;
+    }
+    let command = add_user_command(&username, shell.as_deref(), key.as_deref());
+    run_as_root(&state, &connection_id, &command, sudo_password).await?;
+    Ok(())
+}
+
+/// Add `public_key` to an existing user's `authorized_keys`. Returns false
+/// when the key was already there.
+#[tauri::command]
+pub async fn accounts_authorize_key(
+    state: State<'_, AppState>,
+    connection_id: String,
+    username: String,
+    public_key: String,
+    sudo_password: Option<String>,
+) -> Result<bool, String> {
+    validate_username(&username)?;
+    let key = validate_public_key(&public_key)?;
+    let output = run_as_root(
+        &state,
+        &connection_id,
+        &authorize_key_command(&username, &key),
+        sudo_password,
+    )
+    .await?;
+    Ok(output == "added")
+}
@@ -0,0 +1,433 @@
+//! Accounts on a remote host: users, groups, recent logins (`last`) and who
+//! is logged in right now (`who`), read in one exec for basic auditing.
+//!
+//! Adding an SSH user or authorizing a key runs as root through the usual
+//! sudo flow. Every argument is validated here and passed as a positional
+//! parameter, never spliced into the script.
+
+pub mod commands;
+
+use crate::ai::tool_exec_support::sh_command;
+use serde::Serialize;
+use std::collections::HashMap;
+
+const SECTION_MARKER: &str = "__ZYNC_ACCOUNTS__ ";
+const DEFAULT_UID_MIN: u32 = 1000;
+const RECENT_LOGINS: usize = 50;
+
+/// `getent` also covers LDAP/SSSD accounts; BusyBox systems only have the files.
+pub fn read_script() -> String {
+    format!(
+        r#"echo "__ZYNC_ACCOUNTS__ passwd"
+getent passwd 2>/dev/null || cat /etc/passwd
+echo "__ZYNC_ACCOUNTS__ group"
+getent group 2>/dev/null || cat /etc/group
+echo "__ZYNC_ACCOUNTS__ uid_min"
+awk '$1 == "UID_MIN" {{ print $2 }}' /etc/login.defs 2>/dev/null
+echo "__ZYNC_ACCOUNTS__ last"
+last -w -F -i -n {limit} 2>/dev/null || last 2>/dev/null | head -n {limit}
+echo "__ZYNC_ACCOUNTS__ who"
+who 2>/dev/null
+exit 0"#,
+        limit = RECENT_LOGINS
+    )
+}
+
+/// Creates user `$1` with login shell `$2` and a home directory, and stops
+/// there unless key `$3` is set; [`add_user_command`] appends the key step.
+/// Runs as root.
+const ADD_USER_SCRIPT: &str = r#"set -e
+u=$1 shell=$2 key=$3
+if id "$u" >/dev/null 2>&1; then echo "User $u already exists" >&2; exit 3; fi
+if [ -n "$shell" ] && [ -f /etc/shells ] && ! grep -qx "$shell" /etc/shells; then echo "$shell is not listed in /etc/shells" >&2; exit 4; fi
+if command -v useradd >/dev/null 2>&1; then useradd -m ${shell:+-s "$shell"} "$u"
+elif command -v adduser >/dev/null 2>&1; then adduser -D ${shell:+-s "$shell"} "$u"
+else echo "Neither useradd nor adduser is available" >&2; exit 5; fi
+[ -z "$key" ] && exit 0
+"#;
+
+/// Appends key `$2` to `~$1/.ssh/authorized_keys` unless it is already there,
+/// fixing ownership and modes. Runs as root.
+const AUTHORIZE_KEY_SCRIPT: &str = r#"set -e
+u=$1 key=$2
+home=$(awk -F: -v u="$u" '$1 == u { print $6; exit }' /etc/passwd)
+[ -n "$home" ] || home=$(getent passwd "$u" 2>/dev/null | cut -d: -f6)
+if [ -z "$home" ] || [ ! -d "$home" ]; then echo "No home directory for $u" >&2; exit 3; fi
+group=$(id -g "$u")
+mkdir -p "$home/.ssh"
+touch "$home/.ssh/authorized_keys"
+if grep -qxF "$key" "$home/.ssh/authorized_keys"; then echo "unchanged"; else printf '%s\n' "$key" >> "$home/.ssh/authorized_keys"; echo "added"; fi
+chown "$u:$group" "$home/.ssh" "$home/.ssh/authorized_keys"
+chmod 700 "$home/.ssh"
+chmod 600 "$home/.ssh/authorized_keys"
+"#;
+
+/// Command creating `username`; with a key, the key step runs in the same shell.
+pub fn add_user_command(username: &str, shell: Option<&str>, key: Option<&str>) -> String {
+    let script = format!("{ADD_USER_SCRIPT}set -- \"$u\" \"$key\"\n{AUTHORIZE_KEY_SCRIPT}");
+    sh_command(&script, &[username, shell.unwrap_or(""), key.unwrap_or("")])
+}
+
+pub fn authorize_key_command(username: &str, key: &str) -> String {
+    sh_command(AUTHORIZE_KEY_SCRIPT, &[username, key])
+}
+
+/// Portable user names: lower-case letter or `_` first, at most 32 characters.
+pub fn validate_username(username: &str) -> Result<(), String> {
+    let valid = username.len() <= 32
+        && username
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+        && username
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid user name: {username:?}"))
+    }
+}
+
+/// One OpenSSH public key line: `[options] type base64 [comment]`. Options
+/// are refused; they are easy to get subtly wrong from a text box.
+pub fn validate_public_key(key: &str) -> Result<String, String> {
+    let key = key.trim();
+    if key.contains(['\n', '\r']) {
+        return Err("Paste a single public key".to_string());
+    }
+    let mut words = key.split_whitespace();
+    let key_type = words.next().unwrap_or_default();
+    let known_type = key_type.starts_with("ssh-")
+        || key_type.starts_with("ecdsa-sha2-")
+        || key_type.starts_with("sk-");
+    let payload = words.next().unwrap_or_default();
+    let base64_payload = payload.len() >= 16
+        && payload
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '='));
+    if known_type && base64_payload {
+        Ok(key.to_string())
+    } else {
+        Err("Not an OpenSSH public key (expected e.g. \"ssh-ed25519 AAAA... comment\")".to_string())
+    }
+}
+
+pub fn validate_shell(shell: &str) -> Result<(), String> {
+    let valid = shell.starts_with('/')
+        && shell
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | '_' | '-' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid shell path: {shell:?}"))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteUser {
+    pub name: String,
+    pub uid: u32,
+    pub gid: u32,
+    pub primary_group: Option<String>,
+    /// Supplementary groups.
+    pub groups: Vec<String>,
+    /// Full name / comment field.
+    pub gecos: String,
+    pub home: String,
+    pub shell: String,
+    /// Shell isn't `nologin`/`false`.
+    pub can_login: bool,
+    /// UID below `UID_MIN`: a service account.
+    pub system: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteGroup {
+    pub name: String,
+    pub gid: u32,
+    pub members: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginRecord {
+    pub user: String,
+    pub tty: String,
+    /// Remote address; `None` for local logins.
+    pub host: Option<String>,
+    /// As printed by `last`.
+    pub started: String,
+    pub ended: Option<String>,
+    pub still_logged_in: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveSession {
+    pub user: String,
+    pub tty: String,
+    /// Login time as printed by `who`.
+    pub since: String,
+    pub host: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountsReport {
+    pub users: Vec<RemoteUser>,
+    pub groups: Vec<RemoteGroup>,
+    pub recent_logins: Vec<LoginRecord>,
+    pub sessions: Vec<ActiveSession>,
+    pub uid_min: u32,
+}
+
+fn can_login(shell: &str) -> bool {
+    let name = shell.rsplit('/').next().unwrap_or(shell);
+    !shell.is_empty() && !matches!(name, "nologin" | "false" | "sync" | "shutdown" | "halt")
+}
+
+fn parse_groups(section: &[&str]) -> Vec<RemoteGroup> {
+    section
+        .iter()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split(':').collect();
+            let [name, _, gid, members] = fields[..] else {
+                return None;
+            };
+            Some(RemoteGroup {
+                name: name.to_string(),
+                gid: gid.parse().ok()?,
+                members: members
+                    .split(',')
+                    .filter(|member| !member.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+fn parse_users(section: &[&str], groups: &[RemoteGroup], uid_min: u32) -> Vec<RemoteUser> {
+    let group_names: HashMap<u32, &str> = groups
+        .iter()
+        .map(|group| (group.gid, group.name.as_str()))
+        .collect();
+    section
+        .iter()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split(':').collect();
+            let [name, _, uid, gid, gecos, home, shell] = fields[..] else {
+                return None;
+            };
+            let uid: u32 = uid.parse().ok()?;
+            let gid: u32 = gid.parse().ok()?;
+            Some(RemoteUser {
+                name: name.to_string(),
+                uid,
+                gid,
+                primary_group: group_names.get(&gid).map(|group| group.to_string()),
+                groups: groups
+                    .iter()
+                    .filter(|group| group.members.iter().any(|member| member == name))
+                    .map(|group| group.name.clone())
+                    .collect(),
+                gecos: gecos.to_string(),
+                home: home.to_string(),
+                shell: shell.to_string(),
+                can_login: can_login(shell),
+                // 65534 is `nobody` everywhere.
+                system: uid < uid_min || uid == 65534,
+            })
+        })
+        .collect()
+}
+
+const WEEKDAYS: &[&str] = &["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// `last -F -i` lines, or plain `last` where those flags don't exist:
+/// `alice pts/0 10.0.0.5 Thu Oct 16 10:00:12 2026 - Thu Oct 16 11:02:40 2026 (01:02)`.
+fn parse_last(section: &[&str]) -> Vec<LoginRecord> {
+    section
+        .iter()
+        .filter_map(|line| {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            if words.len() < 5 || matches!(words[0], "reboot" | "shutdown" | "runlevel" | "wtmp") {
+                return None;
+            }
+            let (host, rest) = if WEEKDAYS.contains(&words[2]) {
+                (None, &words[2..])
+            } else {
+                let host = Some(words[2])
+                    .filter(|host| !matches!(*host, "0.0.0.0" | "::" | ":0"))
+                    .map(str::to_string);
+                (host, &words[3..])
+            };
+            let end = rest
+                .iter()
+                .position(|word| *word == "-" || *word == "still" || word.starts_with('('))
+                .unwrap_or(rest.len());
+            let started = rest[..end].join(" ");
+            let still_logged_in = rest.get(end) == Some(&"still");
+            let ended = (rest.get(end) == Some(&"-")).then(|| {
+                rest[end + 1..]
+                    .iter()
+                    .take_while(|word| !word.starts_with('('))
+                    .copied()
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            });
+            Some(LoginRecord {
+                user: words[0].to_string(),
+                tty: words[1].to_string(),
+                host,
+                started,
+                ended,
+                still_logged_in,
+            })
+        })
+        .collect()
+}
+
+/// `alice pts/0 2026-10-16 10:00 (10.0.0.5)`
+fn parse_who(section: &[&str]) -> Vec<ActiveSession> {
+    section
+        .iter()
+        .filter_map(|line| {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            if words.len() < 3 {
+                return None;
+            }
+            let (since, host) = match words.last() {
+                Some(last) if last.starts_with('(') && words.len() > 3 => (
+                    &words[2..words.len() - 1],
+                    Some(last.trim_matches(|c| c == '(' || c == ')').to_string())
+                        .filter(|host| !host.is_empty() && !host.starts_with(':')),
+                ),
+                _ => (&words[2..], None),
+            };
+            Some(ActiveSession {
+                user: words[0].to_string(),
+                tty: words[1].to_string(),
+                since: since.join(" "),
+                host,
+            })
+        })
+        .collect()
+}
+
+/// Split [`read_script`] output into its sections.
+pub fn parse_report(output: &str) -> AccountsReport {
+    let mut sections: HashMap<&str, Vec<&str>> = HashMap::new();
+    let mut current = "";
+    for line in output.lines() {
+        match line.strip_prefix(SECTION_MARKER) {
+            Some(name) => current = name.trim(),
+            None if !line.trim().is_empty() => sections.entry(current).or_default().push(line),
+            None => {}
+        }
+    }
+    let section = |name: &str| sections.get(name).cloned().unwrap_or_default();
+    let uid_min = section("uid_min")
+        .first()
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(DEFAULT_UID_MIN);
+    let groups = parse_groups(&section("group"));
+    AccountsReport {
+        users: parse_users(&section("passwd"), &groups, uid_min),
+        groups,
+        recent_logins: parse_last(&section("last")),
+        sessions: parse_who(&section("who")),
+        uid_min,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUTPUT: &str = "__ZYNC_ACCOUNTS__ passwd
+root:x:0:0:root:/root:/bin/bash
+www-data:x:33:33:www-data:/var/www:/usr/sbin/nologin
+alice:x:1000:1000:Alice,,,:/home/alice:/bin/bash
+__ZYNC_ACCOUNTS__ group
+root:x:0:
+sudo:x:27:alice
+alice:x:1000:
+__ZYNC_ACCOUNTS__ uid_min
+1000
+__ZYNC_ACCOUNTS__ last
+alice    pts/1        10.0.0.5         Thu Oct 16 12:00:12 2026   still logged in
+alice    pts/0        10.0.0.5         Thu Oct 16 10:00:12 2026 - Thu Oct 16 11:02:40 2026  (01:02)
+root     tty1         0.0.0.0          Wed Oct 15 08:00:00 2026 - crash                     (00:10)
+reboot   system boot  0.0.0.0          Wed Oct 15 07:59:00 2026   still running
+
+wtmp begins Wed Oct  1 00:00:01 2026
+__ZYNC_ACCOUNTS__ who
+alice    pts/1        2026-10-16 12:00 (10.0.0.5)
+root     tty1         2026-10-16 09:00
+";
+
+    #[test]
+    fn parses_users_and_groups() {
+        let report = parse_report(OUTPUT);
+        assert_eq!(report.users.len(), 3);
+        let alice = &report.users[2];
+        assert_eq!(alice.primary_group.as_deref(), Some("alice"));
+        assert_eq!(alice.groups, vec!["sudo"]);
+        assert!(alice.can_login && !alice.system);
+        let www = &report.users[1];
+        assert!(!www.can_login && www.system);
+        assert_eq!(report.groups[1].members, vec!["alice"]);
+    }
+
+    #[test]
+    fn parses_logins_and_sessions() {
+        let report = parse_report(OUTPUT);
+        assert_eq!(report.recent_logins.len(), 3);
+        assert!(report.recent_logins[0].still_logged_in);
+        assert_eq!(
+            report.recent_logins[1].ended.as_deref(),
+            Some("Thu Oct 16 11:02:40 2026")
+        );
+        assert_eq!(report.recent_logins[2].host, None);
+        assert_eq!(report.recent_logins[2].ended.as_deref(), Some("crash"));
+        assert_eq!(
+            report.sessions[0],
+            ActiveSession {
+                user: "alice".to_string(),
+                tty: "pts/1".to_string(),
+                since: "2026-10-16 12:00".to_string(),
+                host: Some("10.0.0.5".to_string()),
+            }
+        );
+        assert_eq!(report.sessions[1].host, None);
+
+        // BusyBox/BSD `last` without -F/-i; a console login has no host column.
+        let plain =
+            parse_last(&["bob      ttyS0                     Thu Oct 16 10:00 - 10:05  (00:05)"]);
+        assert_eq!(plain[0].host, None);
+        assert_eq!(plain[0].started, "Thu Oct 16 10:00");
+        assert_eq!(plain[0].ended.as_deref(), Some("10:05"));
+    }
+
+    #[test]
+    fn validates_new_account_input() {
+        assert!(validate_username("deploy_2").is_ok());
+        assert!(validate_username("Root").is_err());
+        assert!(validate_username("-rf").is_err());
+        assert!(validate_username("a;b").is_err());
+        assert!(
+            validate_public_key("ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIHk alice@laptop").is_ok()
+        );
+        assert!(
+            validate_public_key("command=\"sh\" ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIHk").is_err()
+        );
+        assert!(validate_public_key("ssh-ed25519 AAAA\nssh-rsa BBBB").is_err());
+        assert!(validate_shell("/bin/bash").is_ok());
+        assert!(validate_shell("bash; reboot").is_err());
+    }
+}
@@ -10,7 +10,7 @@ mod policy;
 mod providers;
 mod prompts;
 mod tool_command_exec;
-mod tool_exec_support;
+pub(crate) mod tool_exec_support;
 mod tool_file_ops;
 mod tool_schemas;
 mod translate;
@@ -1,6 +1,12 @@
 use crate::ai::tool_exec_support::{cap_output, emit_output};
 use crate::ai::tools::ToolContext;
 use crate::ai::util::COMMAND_TIMEOUT;
+use crate::session_channels::{open_session_channel, SessionChannelPermit};
+use crate::ssh::Client;
+use russh::client::{Handle, Msg};
+use russh::Channel;
+use std::sync::Arc;
+use tokio::sync::Mutex;
 
 pub(crate) async fn exec_command(
     ctx: &ToolContext<'_>,
@@ -19,8 +25,7 @@ pub(crate) async fn exec_command(
                 .ok_or_else(|| "SSH session is not active. Reconnect and try again.".to_string())?
                 .clone()
         };
-        let session = session_arc.lock().await;
-        return exec_ssh(ctx.app, ctx.session_dir.as_deref(), &session, cmd, ctx.run_id, tool_call_id).await;
+        return exec_ssh(ctx, conn_id, &session_arc, cmd, tool_call_id).await;
     }
 
     exec_local(ctx.app, ctx.session_dir.as_deref(), cmd, ctx.run_id, tool_call_id).await
@@ -39,25 +44,32 @@ pub(crate) async fn exec_silent(ctx: &ToolContext<'_>, cmd: &str) -> Result<Stri
                 .ok_or_else(|| "SSH session is not active.".to_string())?
                 .clone()
         };
-        let session = session_arc.lock().await;
-        return exec_ssh_silent(&session, cmd).await;
+        return exec_ssh_silent(ctx.app, conn_id, &session_arc, cmd).await;
     }
 
     exec_local_silent(cmd).await
 }
 
-async fn exec_ssh(
+/// Open an exec channel through the connection's `MaxSessions` gate.
+async fn open_channel(
     app: &tauri::AppHandle,
-    session_dir: Option<&std::path::Path>,
-    session: &russh::client::Handle<crate::ssh::Client>,
+    conn_id: &str,
+    session: &Arc<Mutex<Handle<Client>>>,
+) -> Result<(Channel<Msg>, SessionChannelPermit), String> {
+    open_session_channel(app, conn_id, session)
+        .await
+        .map_err(|e| format!("SSH channel error: {}", e))
+}
+
+async fn exec_ssh(
+    ctx: &ToolContext<'_>,
+    conn_id: &str,
+    session: &Arc<Mutex<Handle<Client>>>,
     cmd: &str,
-    run_id: &str,
     tool_call_id: &str,
 ) -> Result<String, String> {
-    let mut channel = session
-        .channel_open_session()
-        .await
-        .map_err(|e| format!("SSH channel error: {}", e))?;
+    let (app, session_dir, run_id) = (ctx.app, ctx.session_dir.as_deref(), ctx.run_id);
+    let (mut channel, _permit) = open_channel(app, conn_id, session).await?;
 
     channel
         .exec(true, cmd)
@@ -105,13 +117,12 @@ async fn exec_ssh(
 }
 
 pub(crate) async fn exec_ssh_silent(
-    session: &russh::client::Handle<crate::ssh::Client>,
+    app: &tauri::AppHandle,
+    conn_id: &str,
+    session: &Arc<Mutex<Handle<Client>>>,
     cmd: &str,
 ) -> Result<String, String> {
-    let mut channel = session
-        .channel_open_session()
-        .await
-        .map_err(|e| format!("SSH channel error: {}", e))?;
+    let (mut channel, _permit) = open_channel(app, conn_id, session).await?;
 
     channel
         .exec(true, cmd)
@@ -122,14 +133,13 @@ pub(crate) async fn exec_ssh_silent(
 }
 
 pub(crate) async fn exec_ssh_silent_with_stdin(
-    session: &russh::client::Handle<crate::ssh::Client>,
+    app: &tauri::AppHandle,
+    conn_id: &str,
+    session: &Arc<Mutex<Handle<Client>>>,
     cmd: &str,
     input: &[u8],
 ) -> Result<String, String> {
-    let mut channel = session
-        .channel_open_session()
-        .await
-        .map_err(|e| format!("SSH channel error: {}", e))?;
+    let (mut channel, _permit) = open_channel(app, conn_id, session).await?;
 
     channel
         .exec(true, cmd)
@@ -250,6 +260,12 @@ async fn run_local_process(cmd: &str) -> Result<String, String> {
 mod tests {
     use super::{combine_process_output, run_local_process};
     use crate::ai::util::COMMAND_TIMEOUT;
+use crate::session_channels::{open_session_channel, SessionChannelPermit};
+use crate::ssh::Client;
+use russh::client::{Handle, Msg};
+use russh::Channel;
+use std::sync::Arc;
+use tokio::sync::Mutex;
 
     #[test]
     fn combines_successful_stdout_and_stderr() {
@@ -101,10 +101,18 @@ pub(crate) fn validate_command(cmd: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Single-quote `value` for a POSIX shell command line.
 pub(crate) fn shell_quote(value: &str) -> String {
     format!("'{}'", value.replace('\'', "'\\''"))
 }
 
+/// `sh -c <script> sh <args...>`: the script reads its arguments as `$1`, `$2`, ...
+/// so nothing from `args` is ever parsed as shell syntax.
+pub(crate) fn sh_command(script: &str, args: &[&str]) -> String {
+    let args: Vec<String> = args.iter().map(|arg| shell_quote(arg)).collect();
+    format!("sh -c {} sh {}", shell_quote(script), args.join(" "))
+}
+
 /// Truncates overly large terminal outputs to prevent blowing out the AI context window.
 /// If `session_dir` and `tool_call_id` are provided, the full output is securely written 
 /// to the session's artifact folder before truncation, and a file path reference is 
@@ -101,22 +101,24 @@ async fn do_write_file(
     content: &str,
 ) -> Result<(), String> {
     if let Some(conn_id) = ctx.connection_id {
-        let conns = ctx.connections.lock().await;
-        let handle = conns
-            .get(conn_id)
-            .ok_or_else(|| format!("SSH connection '{}' not found. Reconnect and try again.", conn_id))?;
+        let (session, sftp) = {
+            let conns = ctx.connections.lock().await;
+            let handle = conns.get(conn_id).ok_or_else(|| {
+                format!("SSH connection '{}' not found. Reconnect and try again.", conn_id)
+            })?;
+            (handle.session.clone(), handle.sftp_session.clone())
+        };
 
         if let Some(parent) = std::path::Path::new(path).parent() {
             if let Some(parent_str) = parent.to_str().filter(|value| !value.is_empty()) {
-                if let Some(session_arc) = &handle.session {
+                if let Some(session) = &session {
                     let mkdir_cmd = format!("mkdir -p {}", shell_quote(parent_str));
-                    let session = session_arc.lock().await;
-                    let _ = exec_ssh_silent(&session, &mkdir_cmd).await;
+                    let _ = exec_ssh_silent(ctx.app, conn_id, session, &mkdir_cmd).await;
                 }
             }
         }
 
-        if let Some(sftp) = &handle.sftp_session {
+        if let Some(sftp) = &sftp {
             use tokio::io::AsyncWriteExt;
 
             let mut file = sftp
@@ -135,23 +137,21 @@ async fn do_write_file(
             return Ok(());
         }
 
-        let session_arc = handle
-            .session
-            .as_ref()
+        let session = session
             .ok_or_else(|| "SSH session is not active. Reconnect and try again.".to_string())?;
         use base64::{engine::general_purpose, Engine as _};
         let encoded = general_purpose::STANDARD.encode(content.as_bytes());
-        let session = session_arc.lock().await;
         if should_stream_remote_write(content.len()) {
             let cmd = build_streaming_write_command(path);
-            exec_ssh_silent_with_stdin(&session, &cmd, encoded.as_bytes()).await?;
+            exec_ssh_silent_with_stdin(ctx.app, conn_id, &session, &cmd, encoded.as_bytes())
+                .await?;
         } else {
             let cmd = format!(
                 "printf '%s' '{}' | base64 -d > {}",
                 encoded,
                 shell_quote(path)
             );
-            exec_ssh_silent(&session, &cmd).await?;
+            exec_ssh_silent(ctx.app, conn_id, &session, &cmd).await?;
         }
         return Ok(());
     }
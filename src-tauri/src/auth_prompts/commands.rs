@@ -0,0 +1,11 @@
+use super::respond;
+
+/// Answer an `ssh:auth-prompt`, in the order its fields were asked;
+/// `responses: null` cancels that hop's login.
+#[tauri::command]
+pub async fn ssh_auth_respond(
+    prompt_id: String,
+    responses: Option<Vec<String>>,
+) -> Result<(), String> {
+    respond(&prompt_id, responses)
+}
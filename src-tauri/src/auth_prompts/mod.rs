@@ -0,0 +1,154 @@
+//! Keyboard-interactive authentication (one-time codes, PAM passwords)
+//! answered in the UI.
+//!
+//! Every hop of a connection authenticates on its own, so a jump host asking
+//! for a 2FA code doesn't change how the key-based target behind it logs in.
+//! When a hop's configured method is refused, or the server wants a second
+//! factor after it, the server's questions go out as `ssh:auth-prompt` naming
+//! the hop, and `ssh_auth_respond` answers them. A rejected answer asks the
+//! same hop again, up to [`MAX_ATTEMPTS`] times, while the other hops stay
+//! connected. `ssh:auth-prompt-done` withdraws a prompt however it ended:
+//! answered, timed out, or its connect cancelled.
+//!
+//! Servers that take passwords only through PAM ask for them this way too; a
+//! lone hidden `Password:` question is answered once with the saved password
+//! (see [`is_password_prompt`]) before anything is shown.
+
+pub mod commands;
+
+use crate::prompt_broker::{PromptBroker, Validator};
+use serde::Serialize;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+/// Rounds of questions per hop before giving up.
+pub const MAX_ATTEMPTS: u32 = 3;
+const PROMPT_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Answers are `None` when the user cancels that hop's login.
+static BROKER: LazyLock<PromptBroker<Option<Vec<String>>>> =
+    LazyLock::new(|| PromptBroker::new("ssh:auth-prompt", "ssh:auth-prompt-done", PROMPT_TIMEOUT));
+
+/// The host asking, as shown above its questions.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Hop {
+    pub connection_id: String,
+    pub name: String,
+    /// `user@host:port`.
+    pub endpoint: String,
+    /// Name of the connection this hop is the jump host for, if it is one.
+    pub jump_for: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptField {
+    pub prompt: String,
+    /// Whether the answer may be shown while typed.
+    pub echo: bool,
+}
+
+/// Whether a question asks for the login password: hidden input naming a
+/// password, and not a new one being set or a one-time code.
+pub fn is_password_prompt(field: &PromptField) -> bool {
+    let prompt = field.prompt.to_ascii_lowercase();
+    !field.echo
+        && prompt.contains("password")
+        && !["new", "again", "retype", "one-time", "otp", "code", "token"]
+            .iter()
+            .any(|word| prompt.contains(word))
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AuthPrompt {
+    prompt_id: String,
+    hop: Hop,
+    /// Server-supplied title, often empty.
+    title: String,
+    instructions: String,
+    fields: Vec<PromptField>,
+    /// 1 for the first round; later rounds follow a rejected answer.
+    attempt: u32,
+}
+
+/// Answers must cover every field; cancelling (`None`) always goes through.
+fn answer_count(expected: usize) -> Validator<Option<Vec<String>>> {
+    Box::new(move |responses| match responses {
+        Some(responses) if responses.len() != expected => Err(format!(
+            "Expected {expected} answer(s), got {}",
+            responses.len()
+        )),
+        _ => Ok(()),
+    })
+}
+
+/// Ask the user `fields` on behalf of `hop`. `None` when cancelled, timed
+/// out, or there is no window to ask in.
+pub async fn ask(
+    hop: &Hop,
+    title: String,
+    instructions: String,
+    fields: Vec<PromptField>,
+    attempt: u32,
+) -> Option<Vec<String>> {
+    let validate = answer_count(fields.len());
+    let payload = |prompt_id| AuthPrompt {
+        prompt_id,
+        hop: hop.clone(),
+        title,
+        instructions,
+        fields,
+        attempt,
+    };
+    BROKER.ask(payload, Some(validate)).await.flatten()
+}
+
+/// Answer prompt `prompt_id`; `None` cancels it.
+pub fn respond(prompt_id: &str, responses: Option<Vec<String>>) -> Result<(), String> {
+    BROKER.respond(prompt_id, responses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wait_for(
+        prompt_id: &str,
+        fields: usize,
+    ) -> tokio::sync::oneshot::Receiver<Option<Vec<String>>> {
+        BROKER.register(prompt_id, Some(answer_count(fields)))
+    }
+
+    #[tokio::test]
+    async fn delivers_answers_once() {
+        let answer = wait_for("code", 1);
+        assert!(respond("code", Some(vec![])).is_err());
+        respond("code", Some(vec!["123456".to_string()])).unwrap();
+        assert_eq!(answer.await.unwrap(), Some(vec!["123456".to_string()]));
+        assert!(respond("code", Some(vec!["123456".to_string()])).is_err());
+    }
+
+    #[test]
+    fn recognizes_only_login_password_prompts() {
+        let field = |prompt: &str, echo| PromptField {
+            prompt: prompt.to_string(),
+            echo,
+        };
+        assert!(is_password_prompt(&field("Password: ", false)));
+        assert!(is_password_prompt(&field("(deploy@web) Password:", false)));
+        assert!(!is_password_prompt(&field("Password: ", true)));
+        assert!(!is_password_prompt(&field("New password: ", false)));
+        assert!(!is_password_prompt(&field("Verification code: ", false)));
+        assert!(!is_password_prompt(&field("Passcode or option (1-3): ", false)));
+    }
+
+    #[tokio::test]
+    async fn cancelling_answers_none() {
+        let answer = wait_for("cancelled", 2);
+        respond("cancelled", None).unwrap();
+        assert_eq!(answer.await.unwrap(), None);
+        assert!(respond("unknown", None).is_err());
+    }
+}
@@ -0,0 +1,59 @@
+use super::report::{ConnectivityReport, ReportFormat, ReportGroupBy};
+use super::{
+    build_report, load_connections, now_ms, probe_all, read_availability_config, render_report,
+    snapshot, HostAvailability,
+};
+use tauri::AppHandle;
+
+/// Latest status and history for every probed host.
+#[tauri::command]
+pub async fn availability_status() -> Result<Vec<HostAvailability>, String> {
+    Ok(snapshot())
+}
+
+/// Probe now instead of waiting for the next round; works even when background
+/// probing is disabled. Probes every saved host when `connection_id` is omitted.
+#[tauri::command]
+pub async fn availability_probe_now(
+    app: AppHandle,
+    connection_id: Option<String>,
+) -> Result<Vec<HostAvailability>, String> {
+    let config = read_availability_config(&app);
+    let connections: Vec<_> = load_connections(&app)
+        .into_iter()
+        .filter(|connection| connection_id.as_ref().is_none_or(|id| *id == connection.id))
+        .collect();
+    if connections.is_empty() {
+        if let Some(id) = connection_id {
+            return Err(format!("Connection {id} not found"));
+        }
+    }
+    Ok(probe_all(connections, &config).await)
+}
+
+/// Uptime and tunnel stability between `from_ms` and `to_ms` (default: last 7 days).
+#[tauri::command]
+pub async fn availability_report(
+    app: AppHandle,
+    group_by: ReportGroupBy,
+    from_ms: Option<u64>,
+    to_ms: Option<u64>,
+) -> Result<ConnectivityReport, String> {
+    let to_ms = to_ms.unwrap_or_else(now_ms);
+    let from_ms = from_ms.unwrap_or(to_ms.saturating_sub(7 * 86_400_000));
+    Ok(build_report(&app, group_by, from_ms, to_ms))
+}
+
+#[tauri::command]
+pub async fn availability_export_report(
+    app: AppHandle,
+    path: String,
+    format: ReportFormat,
+    group_by: ReportGroupBy,
+    from_ms: Option<u64>,
+    to_ms: Option<u64>,
+) -> Result<(), String> {
+    let report = availability_report(app, group_by, from_ms, to_ms).await?;
+    let content = render_report(&report, format)?;
+    std::fs::write(&path, content).map_err(|e| format!("Failed to write {path}: {e}"))
+}
@@ -0,0 +1,179 @@
+//! Append-only connectivity log backing the reports: one JSON record per line
+//! in `connectivity_log.jsonl`, holding probe samples and tunnel up/down events.
+
+use super::{HostAvailability, HostStatus};
+use crate::commands::get_data_dir;
+use crate::events::ConnectionEvent;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex as StdMutex};
+use tauri::AppHandle;
+use tokio::sync::broadcast::error::RecvError;
+
+const LOG_FILE: &str = "connectivity_log.jsonl";
+
+/// Serializes appends and pruning so lines never interleave.
+static LOG_LOCK: LazyLock<StdMutex<()>> = LazyLock::new(|| StdMutex::new(()));
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case", rename_all_fields = "camelCase")]
+pub enum LogRecord {
+    Probe {
+        timestamp_ms: u64,
+        connection_id: String,
+        status: HostStatus,
+        latency_ms: Option<u64>,
+    },
+    Tunnel {
+        timestamp_ms: u64,
+        connection_id: String,
+        tunnel_id: String,
+        up: bool,
+        error: Option<String>,
+    },
+}
+
+impl LogRecord {
+    pub fn timestamp_ms(&self) -> u64 {
+        match self {
+            Self::Probe { timestamp_ms, .. } | Self::Tunnel { timestamp_ms, .. } => *timestamp_ms,
+        }
+    }
+}
+
+pub fn log_path(app: &AppHandle) -> PathBuf {
+    get_data_dir(app).join(LOG_FILE)
+}
+
+fn lock_log() -> std::sync::MutexGuard<'static, ()> {
+    match LOG_LOCK.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+pub fn append(path: &Path, records: &[LogRecord]) -> Result<(), String> {
+    if records.is_empty() {
+        return Ok(());
+    }
+    let mut buffer = String::new();
+    for record in records {
+        buffer.push_str(&serde_json::to_string(record).map_err(|e| e.to_string())?);
+        buffer.push('\n');
+    }
+    let _guard = lock_log();
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open connectivity log: {e}"))?;
+    file.write_all(buffer.as_bytes())
+        .map_err(|e| format!("Failed to write connectivity log: {e}"))
+}
+
+pub fn probe_records(results: &[HostAvailability]) -> Vec<LogRecord> {
+    results
+        .iter()
+        .map(|host| LogRecord::Probe {
+            timestamp_ms: host.last_checked_ms,
+            connection_id: host.connection_id.clone(),
+            status: host.status,
+            latency_ms: host.latency_ms,
+        })
+        .collect()
+}
+
+/// Records with `from_ms <= timestamp < to_ms`; unreadable lines are skipped.
+pub fn read_range(path: &Path, from_ms: u64, to_ms: u64) -> Vec<LogRecord> {
+    let _guard = lock_log();
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<LogRecord>(line).ok())
+        .filter(|record| (from_ms..to_ms).contains(&record.timestamp_ms()))
+        .collect()
+}
+
+/// Drop records older than `before_ms`.
+pub fn prune(path: &Path, before_ms: u64) -> Result<(), String> {
+    let _guard = lock_log();
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Ok(());
+    };
+    let kept: String = content
+        .lines()
+        .filter(|line| {
+            serde_json::from_str::<LogRecord>(line)
+                .is_ok_and(|record| record.timestamp_ms() >= before_ms)
+        })
+        .flat_map(|line| [line, "\n"])
+        .collect();
+    if kept.len() == content.len() {
+        return Ok(());
+    }
+    crate::atomic_io::durable_replace(path, kept.as_bytes()).map_err(|e| e.to_string())
+}
+
+/// Log tunnel up/down transitions while availability tracking is enabled.
+pub fn start_tunnel_recorder(app: AppHandle) {
+    let mut events = crate::events::subscribe();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let envelope = match events.recv().await {
+                Ok(envelope) => envelope,
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            };
+            let (tunnel_id, up, error) = match envelope.event {
+                ConnectionEvent::TunnelUp { tunnel_id } => (tunnel_id, true, None),
+                ConnectionEvent::TunnelDown { tunnel_id, error } => (tunnel_id, false, error),
+                _ => continue,
+            };
+            if !super::read_availability_config(&app).enabled {
+                continue;
+            }
+            let record = LogRecord::Tunnel {
+                timestamp_ms: envelope.timestamp_ms,
+                connection_id: envelope.connection_id,
+                tunnel_id,
+                up,
+                error,
+            };
+            if let Err(error) = append(&log_path(&app), &[record]) {
+                eprintln!("[AVAILABILITY] {error}");
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe(timestamp_ms: u64) -> LogRecord {
+        LogRecord::Probe {
+            timestamp_ms,
+            connection_id: "c1".to_string(),
+            status: HostStatus::Up,
+            latency_ms: Some(12),
+        }
+    }
+
+    #[test]
+    fn append_read_and_prune_round_trip() {
+        let dir = std::env::temp_dir().join(format!("zync-connectivity-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("temp dir");
+        let path = dir.join(LOG_FILE);
+        let _ = std::fs::remove_file(&path);
+
+        append(&path, &[probe(10), probe(20), probe(30)]).expect("append");
+        assert_eq!(read_range(&path, 15, 30), vec![probe(20)]);
+
+        prune(&path, 20).expect("prune");
+        assert_eq!(read_range(&path, 0, u64::MAX), vec![probe(20), probe(30)]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,408 @@
+//! Background reachability probing for saved hosts.
+//!
+//! Every interval each saved host's SSH port gets a plain TCP connect (no SSH
+//! handshake, no auth attempts), and the outcome is kept as a short per-host
+//! history for the availability dots in the connection list. Hosts reached
+//! through a jump server cannot be probed directly and are reported as skipped.
+//! Opt-in via `settings.json`: `"availability": { "enabled": true }`.
+//!
+//! While enabled, samples and tunnel transitions are also logged (see
+//! `history`) so `report` can summarize uptime over longer periods, optionally
+//! on a daily or weekly schedule.
+
+pub mod commands;
+pub mod history;
+pub mod report;
+
+use crate::commands::{get_data_dir, read_effective_settings};
+use crate::sync::domain_hosts::{load_saved_data, CONNECTIONS_MUTATION_LOCK};
+use crate::types::SavedConnection;
+use report::{ConnectivityReport, ReportFormat, ReportGroupBy, ReportPeriod};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, LazyLock, Mutex as StdMutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Semaphore;
+
+/// How often a disabled prober re-checks the setting.
+const DISABLED_POLL: Duration = Duration::from_secs(30);
+const MAX_CONCURRENT_PROBES: usize = 16;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AvailabilityConfig {
+    pub enabled: bool,
+    pub interval_seconds: u64,
+    pub timeout_ms: u64,
+    /// Samples kept per host.
+    pub history_size: usize,
+    /// How long the connectivity log keeps records.
+    pub retention_days: u64,
+    pub reports: ReportSchedule,
+}
+
+impl Default for AvailabilityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_seconds: 60,
+            timeout_ms: 3000,
+            history_size: 120,
+            retention_days: 90,
+            reports: ReportSchedule::default(),
+        }
+    }
+}
+
+/// `"availability": { "reports": { "enabled": true, "period": "weekly" } }`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ReportSchedule {
+    pub enabled: bool,
+    pub period: ReportPeriod,
+    pub format: ReportFormat,
+    pub group_by: ReportGroupBy,
+    /// Defaults to `reports/` in the app data directory.
+    pub directory: Option<String>,
+}
+
+impl Default for ReportSchedule {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            period: ReportPeriod::Weekly,
+            format: ReportFormat::Json,
+            group_by: ReportGroupBy::Host,
+            directory: None,
+        }
+    }
+}
+
+pub fn read_availability_config(app: &AppHandle) -> AvailabilityConfig {
+    read_effective_settings(app)
+        .ok()
+        .and_then(|settings| settings.get("availability").cloned())
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HostStatus {
+    Up,
+    Down,
+    /// Behind a jump server; not directly reachable from this machine.
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProbeSample {
+    pub checked_at_ms: u64,
+    pub status: HostStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostAvailability {
+    pub connection_id: String,
+    pub host: String,
+    pub port: u16,
+    pub status: HostStatus,
+    pub latency_ms: Option<u64>,
+    pub last_checked_ms: u64,
+    /// Percentage of `Up` samples in `history`, ignoring skipped samples.
+    pub uptime_percent: Option<f64>,
+    pub history: VecDeque<ProbeSample>,
+}
+
+static AVAILABILITY: LazyLock<StdMutex<HashMap<String, HostAvailability>>> =
+    LazyLock::new(|| StdMutex::new(HashMap::new()));
+
+fn lock_availability() -> std::sync::MutexGuard<'static, HashMap<String, HostAvailability>> {
+    match AVAILABILITY.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+fn uptime_percent(history: &VecDeque<ProbeSample>) -> Option<f64> {
+    let probed = history
+        .iter()
+        .filter(|sample| sample.status != HostStatus::Skipped)
+        .count();
+    if probed == 0 {
+        return None;
+    }
+    let up = history
+        .iter()
+        .filter(|sample| sample.status == HostStatus::Up)
+        .count();
+    Some(up as f64 * 100.0 / probed as f64)
+}
+
+fn record_sample(
+    entries: &mut HashMap<String, HostAvailability>,
+    connection: &SavedConnection,
+    sample: ProbeSample,
+    history_size: usize,
+) -> HostAvailability {
+    let entry = entries
+        .entry(connection.id.clone())
+        .or_insert_with(|| HostAvailability {
+            connection_id: connection.id.clone(),
+            host: connection.host.clone(),
+            port: connection.port,
+            status: sample.status,
+            latency_ms: None,
+            last_checked_ms: 0,
+            uptime_percent: None,
+            history: VecDeque::new(),
+        });
+    entry.host = connection.host.clone();
+    entry.port = connection.port;
+    entry.status = sample.status;
+    entry.latency_ms = sample.latency_ms;
+    entry.last_checked_ms = sample.checked_at_ms;
+    entry.history.push_back(sample);
+    while entry.history.len() > history_size.max(1) {
+        entry.history.pop_front();
+    }
+    entry.uptime_percent = uptime_percent(&entry.history);
+    entry.clone()
+}
+
+/// TCP-connect to the host's SSH port.
+pub async fn probe(connection: &SavedConnection, timeout: Duration) -> ProbeSample {
+    let checked_at_ms = now_ms();
+    if connection.jump_server_id.as_deref().is_some_and(|id| !id.is_empty()) {
+        return ProbeSample {
+            checked_at_ms,
+            status: HostStatus::Skipped,
+            latency_ms: None,
+            error: None,
+        };
+    }
+    let started = Instant::now();
+    let address = (connection.host.as_str(), connection.port);
+    match tokio::time::timeout(timeout, tokio::net::TcpStream::connect(address)).await {
+        Ok(Ok(_stream)) => ProbeSample {
+            checked_at_ms,
+            status: HostStatus::Up,
+            latency_ms: Some(started.elapsed().as_millis() as u64),
+            error: None,
+        },
+        Ok(Err(error)) => ProbeSample {
+            checked_at_ms,
+            status: HostStatus::Down,
+            latency_ms: None,
+            error: Some(error.to_string()),
+        },
+        Err(_) => ProbeSample {
+            checked_at_ms,
+            status: HostStatus::Down,
+            latency_ms: None,
+            error: Some(format!("timed out after {} ms", timeout.as_millis())),
+        },
+    }
+}
+
+fn load_connections(app: &AppHandle) -> Vec<SavedConnection> {
+    let Ok(_guard) = CONNECTIONS_MUTATION_LOCK.lock() else {
+        return Vec::new();
+    };
+    load_saved_data(&get_data_dir(app).join("connections.json"))
+        .map(|data| data.connections)
+        .unwrap_or_default()
+}
+
+/// Probe `connections` concurrently and record the results.
+pub async fn probe_all(
+    connections: Vec<SavedConnection>,
+    config: &AvailabilityConfig,
+) -> Vec<HostAvailability> {
+    let timeout = Duration::from_millis(config.timeout_ms.max(100));
+    let permits = Arc::new(Semaphore::new(MAX_CONCURRENT_PROBES));
+    let mut tasks = tokio::task::JoinSet::new();
+    for connection in connections {
+        let permits = permits.clone();
+        tasks.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            let sample = probe(&connection, timeout).await;
+            (connection, sample)
+        });
+    }
+
+    let mut results = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        let Ok((connection, sample)) = joined else {
+            continue;
+        };
+        results.push((connection, sample));
+    }
+
+    let mut entries = lock_availability();
+    results
+        .into_iter()
+        .map(|(connection, sample)| {
+            record_sample(&mut entries, &connection, sample, config.history_size)
+        })
+        .collect()
+}
+
+pub(crate) fn connections_by_id(app: &AppHandle) -> HashMap<String, SavedConnection> {
+    load_connections(app)
+        .into_iter()
+        .map(|connection| (connection.id.clone(), connection))
+        .collect()
+}
+
+pub fn build_report(
+    app: &AppHandle,
+    group_by: ReportGroupBy,
+    from_ms: u64,
+    to_ms: u64,
+) -> ConnectivityReport {
+    let records = history::read_range(&history::log_path(app), from_ms, to_ms);
+    report::build(&records, &connections_by_id(app), group_by, from_ms, to_ms, now_ms())
+}
+
+pub fn render_report(report: &ConnectivityReport, format: ReportFormat) -> Result<String, String> {
+    match format {
+        ReportFormat::Json => serde_json::to_string_pretty(report).map_err(|e| e.to_string()),
+        ReportFormat::Csv => Ok(report::to_csv(report)),
+    }
+}
+
+/// Write the report for the last complete period unless it already exists.
+fn write_scheduled_report(app: &AppHandle, schedule: &ReportSchedule) -> Result<(), String> {
+    let directory = schedule
+        .directory
+        .as_deref()
+        .filter(|dir| !dir.trim().is_empty())
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|| get_data_dir(app).join("reports"));
+    let (from_ms, to_ms, label) = report::previous_period(schedule.period, now_ms());
+    let extension = match schedule.format {
+        ReportFormat::Json => "json",
+        ReportFormat::Csv => "csv",
+    };
+    let path = directory.join(format!("connectivity-{label}.{extension}"));
+    if path.exists() {
+        return Ok(());
+    }
+    let report = build_report(app, schedule.group_by, from_ms, to_ms);
+    // Nothing was logged for the period (e.g. probing was just enabled).
+    if report.rows.is_empty() {
+        return Ok(());
+    }
+    std::fs::create_dir_all(&directory).map_err(|e| e.to_string())?;
+    let content = render_report(&report, schedule.format)?;
+    crate::atomic_io::durable_replace(&path, content.as_bytes()).map_err(|e| e.to_string())
+}
+
+pub fn snapshot() -> Vec<HostAvailability> {
+    let mut hosts: Vec<_> = lock_availability().values().cloned().collect();
+    hosts.sort_by(|a, b| a.connection_id.cmp(&b.connection_id));
+    hosts
+}
+
+pub fn start(app: AppHandle) {
+    history::start_tunnel_recorder(app.clone());
+    crate::crash_reports::spawn_supervised("availability", async move {
+        let mut last_maintenance_day = None;
+        loop {
+            // Re-read each round so toggling the setting takes effect without restart.
+            let config = read_availability_config(&app);
+            if !config.enabled {
+                tokio::time::sleep(DISABLED_POLL).await;
+                continue;
+            }
+
+            let connections = load_connections(&app);
+            let known: HashSet<String> = connections.iter().map(|c| c.id.clone()).collect();
+            lock_availability().retain(|id, _| known.contains(id));
+
+            let results = probe_all(connections, &config).await;
+            let _ = app.emit("availability:update", &results);
+            let log_path = history::log_path(&app);
+            if let Err(error) = history::append(&log_path, &history::probe_records(&results)) {
+                eprintln!("[AVAILABILITY] {error}");
+            }
+
+            // Pruning and scheduled reports run at most once per UTC day.
+            let today = now_ms() / 86_400_000;
+            if last_maintenance_day != Some(today) {
+                last_maintenance_day = Some(today);
+                let cutoff = now_ms().saturating_sub(config.retention_days.max(1) * 86_400_000);
+                if let Err(error) = history::prune(&log_path, cutoff) {
+                    eprintln!("[AVAILABILITY] Failed to prune connectivity log: {error}");
+                }
+                if config.reports.enabled {
+                    if let Err(error) = write_scheduled_report(&app, &config.reports) {
+                        eprintln!("[AVAILABILITY] Failed to write scheduled report: {error}");
+                    }
+                }
+            }
+            tokio::time::sleep(Duration::from_secs(config.interval_seconds.max(10))).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn connection() -> SavedConnection {
+        SavedConnection {
+            id: "c1".to_string(),
+            name: "web".to_string(),
+            host: "web.example.com".to_string(),
+            port: 22,
+            username: "deploy".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn sample(status: HostStatus) -> ProbeSample {
+        ProbeSample {
+            checked_at_ms: 1,
+            status,
+            latency_ms: None,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn history_is_capped_and_uptime_ignores_skipped() {
+        let mut entries = HashMap::new();
+        let conn = connection();
+        for status in [HostStatus::Down, HostStatus::Up, HostStatus::Skipped, HostStatus::Up] {
+            record_sample(&mut entries, &conn, sample(status), 3);
+        }
+        let entry = &entries["c1"];
+        assert_eq!(entry.history.len(), 3);
+        assert_eq!(entry.status, HostStatus::Up);
+        assert_eq!(entry.uptime_percent, Some(100.0));
+    }
+
+    #[tokio::test]
+    async fn jump_hosts_are_skipped_without_connecting() {
+        let mut conn = connection();
+        conn.jump_server_id = Some("bastion".to_string());
+        let result = probe(&conn, Duration::from_millis(10)).await;
+        assert_eq!(result.status, HostStatus::Skipped);
+    }
+}
@@ -0,0 +1,265 @@
+//! Connectivity reports: uptime, latency and tunnel stability per host or tag
+//! over a period, rendered as JSON or CSV.
+
+use super::history::LogRecord;
+use super::HostStatus;
+use crate::types::SavedConnection;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportGroupBy {
+    Host,
+    Tag,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectivityRow {
+    pub key: String,
+    pub probes: u64,
+    pub up: u64,
+    pub down: u64,
+    pub uptime_percent: Option<f64>,
+    pub avg_latency_ms: Option<f64>,
+    pub max_latency_ms: Option<u64>,
+    pub tunnel_starts: u64,
+    /// Tunnel-down transitions that carried an error (not user stops).
+    pub tunnel_drops: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectivityReport {
+    pub from_ms: u64,
+    pub to_ms: u64,
+    pub generated_at_ms: u64,
+    pub group_by: ReportGroupBy,
+    pub rows: Vec<ConnectivityRow>,
+}
+
+#[derive(Default)]
+struct Totals {
+    up: u64,
+    down: u64,
+    latency_sum: u64,
+    latency_count: u64,
+    max_latency_ms: Option<u64>,
+    tunnel_starts: u64,
+    tunnel_drops: u64,
+}
+
+fn group_keys(
+    connection_id: &str,
+    connections: &HashMap<String, SavedConnection>,
+    group_by: ReportGroupBy,
+) -> Vec<String> {
+    let connection = connections.get(connection_id);
+    match group_by {
+        ReportGroupBy::Host => vec![match connection {
+            Some(connection) if !connection.name.trim().is_empty() => {
+                format!("{} ({})", connection.name, connection.host)
+            }
+            Some(connection) => connection.host.clone(),
+            None => connection_id.to_string(),
+        }],
+        // A host with several tags counts toward each tag.
+        ReportGroupBy::Tag => connection
+            .and_then(|connection| connection.tags.clone())
+            .filter(|tags| !tags.is_empty())
+            .unwrap_or_else(|| vec!["(untagged)".to_string()]),
+    }
+}
+
+pub fn build(
+    records: &[LogRecord],
+    connections: &HashMap<String, SavedConnection>,
+    group_by: ReportGroupBy,
+    from_ms: u64,
+    to_ms: u64,
+    generated_at_ms: u64,
+) -> ConnectivityReport {
+    let mut totals: BTreeMap<String, Totals> = BTreeMap::new();
+    for record in records {
+        let connection_id = match record {
+            LogRecord::Probe { connection_id, .. } | LogRecord::Tunnel { connection_id, .. } => {
+                connection_id
+            }
+        };
+        for key in group_keys(connection_id, connections, group_by) {
+            let entry = totals.entry(key).or_default();
+            match record {
+                LogRecord::Probe {
+                    status: HostStatus::Up,
+                    latency_ms,
+                    ..
+                } => {
+                    entry.up += 1;
+                    if let Some(latency) = latency_ms {
+                        entry.latency_sum += latency;
+                        entry.latency_count += 1;
+                        entry.max_latency_ms = entry.max_latency_ms.max(Some(*latency));
+                    }
+                }
+                LogRecord::Probe {
+                    status: HostStatus::Down,
+                    ..
+                } => entry.down += 1,
+                LogRecord::Probe { .. } => {}
+                LogRecord::Tunnel { up: true, .. } => entry.tunnel_starts += 1,
+                LogRecord::Tunnel { error: Some(_), .. } => entry.tunnel_drops += 1,
+                LogRecord::Tunnel { .. } => {}
+            }
+        }
+    }
+
+    let rows = totals
+        .into_iter()
+        .map(|(key, totals)| {
+            let probes = totals.up + totals.down;
+            ConnectivityRow {
+                key,
+                probes,
+                up: totals.up,
+                down: totals.down,
+                uptime_percent: (probes > 0).then(|| totals.up as f64 * 100.0 / probes as f64),
+                avg_latency_ms: (totals.latency_count > 0)
+                    .then(|| totals.latency_sum as f64 / totals.latency_count as f64),
+                max_latency_ms: totals.max_latency_ms,
+                tunnel_starts: totals.tunnel_starts,
+                tunnel_drops: totals.tunnel_drops,
+            }
+        })
+        .collect();
+
+    ConnectivityReport {
+        from_ms,
+        to_ms,
+        generated_at_ms,
+        group_by,
+        rows,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportPeriod {
+    Daily,
+    Weekly,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    Json,
+    Csv,
+}
+
+const DAY_MS: u64 = 86_400_000;
+
+/// Bounds of the last complete UTC day or ISO week (Monday start) before `now_ms`,
+/// plus a label for the file name.
+pub fn previous_period(period: ReportPeriod, now_ms: u64) -> (u64, u64, String) {
+    let today = now_ms / DAY_MS;
+    let (start_day, days, prefix) = match period {
+        ReportPeriod::Daily => (today.saturating_sub(1), 1, ""),
+        // 1970-01-01 was a Thursday, so Monday-based weekday is (days + 3) % 7.
+        ReportPeriod::Weekly => ((today - (today + 3) % 7).saturating_sub(7), 7, "week-of-"),
+    };
+    let from_ms = start_day * DAY_MS;
+    let label = format!("{prefix}{}", crate::time_tracking::utc_date(from_ms / 1000));
+    (from_ms, from_ms + days * DAY_MS, label)
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+pub fn to_csv(report: &ConnectivityReport) -> String {
+    let mut csv = String::from(
+        "key,probes,up,down,uptime_percent,avg_latency_ms,max_latency_ms,tunnel_starts,tunnel_drops\n",
+    );
+    for row in &report.rows {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{}\n",
+            csv_field(&row.key),
+            row.probes,
+            row.up,
+            row.down,
+            optional(row.uptime_percent.map(|value| format!("{value:.2}"))),
+            optional(row.avg_latency_ms.map(|value| format!("{value:.1}"))),
+            optional(row.max_latency_ms),
+            row.tunnel_starts,
+            row.tunnel_drops,
+        ));
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe(connection_id: &str, status: HostStatus, latency_ms: Option<u64>) -> LogRecord {
+        LogRecord::Probe {
+            timestamp_ms: 1,
+            connection_id: connection_id.to_string(),
+            status,
+            latency_ms,
+        }
+    }
+
+    fn tunnel(up: bool, error: Option<&str>) -> LogRecord {
+        LogRecord::Tunnel {
+            timestamp_ms: 1,
+            connection_id: "c1".to_string(),
+            tunnel_id: "t1".to_string(),
+            up,
+            error: error.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn previous_period_bounds() {
+        // 2024-03-06 (a Wednesday) 12:00 UTC.
+        let now = 1_709_726_400_000;
+        let (from, to, label) = previous_period(ReportPeriod::Daily, now);
+        assert_eq!(label, "2024-03-05");
+        assert_eq!(to - from, DAY_MS);
+        let (from, to, label) = previous_period(ReportPeriod::Weekly, now);
+        assert_eq!(label, "week-of-2024-02-26");
+        assert_eq!(to - from, 7 * DAY_MS);
+    }
+
+    #[test]
+    fn summarizes_uptime_latency_and_tunnel_drops() {
+        let records = vec![
+            probe("c1", HostStatus::Up, Some(10)),
+            probe("c1", HostStatus::Up, Some(30)),
+            probe("c1", HostStatus::Down, None),
+            probe("c1", HostStatus::Up, Some(20)),
+            probe("c1", HostStatus::Skipped, None),
+            tunnel(true, None),
+            tunnel(false, Some("transport lost")),
+            tunnel(false, None),
+        ];
+        let report = build(&records, &HashMap::new(), ReportGroupBy::Host, 0, 10, 10);
+        let row = &report.rows[0];
+        assert_eq!(row.key, "c1");
+        assert_eq!((row.probes, row.up, row.down), (4, 3, 1));
+        assert_eq!(row.uptime_percent, Some(75.0));
+        assert_eq!(row.avg_latency_ms, Some(20.0));
+        assert_eq!(row.max_latency_ms, Some(30));
+        assert_eq!((row.tunnel_starts, row.tunnel_drops), (1, 1));
+        assert!(to_csv(&report).ends_with("c1,4,3,1,75.00,20.0,30,1,1\n"));
+    }
+}
@@ -0,0 +1,210 @@
+use super::{
+    ensure_enabled, payload_bytes, payload_chunk, relay_loopback, LatencySummary, Throughput,
+};
+use crate::commands::{get_sftp_or_reconnect, AppState};
+use crate::session_channels::open_session_channel;
+use serde::Serialize;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tauri::State;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TunnelBenchResult {
+    /// Local relay over loopback TCP, no SSH involved.
+    pub relay: Throughput,
+    /// Raw SSH channel into `cat > /dev/null`; the ceiling for any forward on
+    /// this connection. Present when a connection id was given.
+    pub ssh_channel: Option<Throughput>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpBenchResult {
+    pub remote_path: String,
+    pub upload: Throughput,
+    pub download: Throughput,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventBenchResult {
+    pub samples: usize,
+    pub subscribers: usize,
+    pub delivered: usize,
+    pub lagged: u64,
+    pub latency: LatencySummary,
+}
+
+/// Throughput of the tunnel relay, and optionally of an SSH channel on `connection_id`.
+#[tauri::command]
+pub async fn bench_tunnel_throughput(
+    connection_id: Option<String>,
+    megabytes: Option<u64>,
+    state: State<'_, AppState>,
+) -> Result<TunnelBenchResult, String> {
+    ensure_enabled()?;
+    let bytes = payload_bytes(megabytes);
+    let relay = relay_loopback(bytes)
+        .await
+        .map_err(|error| format!("Relay benchmark failed: {error}"))?;
+    let ssh_channel = match connection_id {
+        Some(id) => Some(ssh_channel_throughput(&state, &id, bytes).await?),
+        None => None,
+    };
+    Ok(TunnelBenchResult { relay, ssh_channel })
+}
+
+async fn ssh_channel_throughput(
+    state: &AppState,
+    connection_id: &str,
+    bytes: u64,
+) -> Result<Throughput, String> {
+    let session = {
+        let connections = state.connections.lock().await;
+        connections
+            .get(connection_id)
+            .and_then(|conn| conn.session.clone())
+    }
+    .ok_or_else(|| format!("Connection {connection_id} not found"))?;
+    let (mut channel, _channel_permit) =
+        open_session_channel(&state.app_handle, connection_id, &session)
+            .await
+            .map_err(|error| error.to_string())?;
+    channel
+        .exec(true, "cat > /dev/null")
+        .await
+        .map_err(|error| error.to_string())?;
+
+    let chunk = payload_chunk();
+    let started = Instant::now();
+    let mut remaining = bytes;
+    while remaining > 0 {
+        let len = remaining.min(chunk.len() as u64) as usize;
+        channel
+            .data(&chunk[..len])
+            .await
+            .map_err(|error| format!("SSH channel write failed: {error}"))?;
+        remaining -= len as u64;
+    }
+    channel.eof().await.map_err(|error| error.to_string())?;
+    // The exit status arrives once the remote side has consumed everything.
+    let mut exit_status = None;
+    while let Some(message) = channel.wait().await {
+        if let russh::ChannelMsg::ExitStatus { exit_status: code } = message {
+            exit_status = Some(code);
+        }
+    }
+    let elapsed = started.elapsed();
+    match exit_status {
+        Some(0) | None => Ok(Throughput::new(bytes, elapsed)),
+        Some(code) => Err(format!("Remote sink exited with status {code}")),
+    }
+}
+
+/// Upload then download a synthetic file over SFTP, removing it afterwards.
+#[tauri::command]
+pub async fn bench_sftp_throughput(
+    connection_id: String,
+    megabytes: Option<u64>,
+    remote_dir: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<SftpBenchResult, String> {
+    use russh_sftp::protocol::OpenFlags;
+
+    ensure_enabled()?;
+    crate::commands::ensure_connection_online(&state, &connection_id).await?;
+    let bytes = payload_bytes(megabytes);
+    let sftp = get_sftp_or_reconnect(&state, &connection_id).await?;
+    let remote_dir = remote_dir.unwrap_or_else(|| "/tmp".to_string());
+    let remote_path = format!(
+        "{}/.zync-bench-{}-{}",
+        remote_dir.trim_end_matches('/'),
+        std::process::id(),
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis())
+            .unwrap_or(0)
+    );
+
+    let chunk = payload_chunk();
+    let upload = async {
+        let mut file = sftp
+            .open_with_flags(
+                &remote_path,
+                OpenFlags::WRITE | OpenFlags::CREATE | OpenFlags::TRUNCATE,
+            )
+            .await
+            .map_err(|error| format!("Open {remote_path} failed: {error}"))?;
+        let started = Instant::now();
+        let mut remaining = bytes;
+        while remaining > 0 {
+            let len = remaining.min(chunk.len() as u64) as usize;
+            file.write_all(&chunk[..len])
+                .await
+                .map_err(|error| format!("SFTP write failed: {error}"))?;
+            remaining -= len as u64;
+        }
+        file.shutdown()
+            .await
+            .map_err(|error| format!("SFTP close failed: {error}"))?;
+        Ok::<_, String>(Throughput::new(bytes, started.elapsed()))
+    }
+    .await;
+
+    let download = match &upload {
+        Ok(_) => {
+            async {
+                let mut file = sftp
+                    .open_with_flags(&remote_path, OpenFlags::READ)
+                    .await
+                    .map_err(|error| format!("Open {remote_path} failed: {error}"))?;
+                let mut buffer = vec![0u8; 256 * 1024];
+                let started = Instant::now();
+                let mut received = 0u64;
+                loop {
+                    let read = file
+                        .read(&mut buffer)
+                        .await
+                        .map_err(|error| format!("SFTP read failed: {error}"))?;
+                    if read == 0 {
+                        break;
+                    }
+                    received += read as u64;
+                }
+                Ok::<_, String>(Throughput::new(received, started.elapsed()))
+            }
+            .await
+        }
+        Err(error) => Err(error.clone()),
+    };
+
+    // Best effort: a leftover temp file is not worth failing the benchmark over.
+    if let Err(error) = sftp.remove_file(&remote_path).await {
+        eprintln!("[Bench] Failed to remove {remote_path}: {error}");
+    }
+    Ok(SftpBenchResult {
+        remote_path,
+        upload: upload?,
+        download: download?,
+    })
+}
+
+/// Publish-to-delivery latency of the event bus under a synthetic burst.
+#[tauri::command]
+pub async fn bench_event_latency(
+    samples: Option<usize>,
+    subscribers: Option<usize>,
+) -> Result<EventBenchResult, String> {
+    ensure_enabled()?;
+    let samples = samples.unwrap_or(10_000).clamp(1, 1_000_000);
+    let subscribers = subscribers.unwrap_or(4).clamp(1, 64);
+    let (latencies, lagged) = crate::events::measure_fanout(samples, subscribers).await;
+    Ok(EventBenchResult {
+        samples,
+        subscribers,
+        delivered: latencies.len(),
+        lagged,
+        latency: LatencySummary::from_micros(latencies),
+    })
+}
@@ -0,0 +1,164 @@
+//! Developer benchmarks for the data paths that regress silently: tunnel
+//! relaying, SFTP transfers and the connection event bus.
+//!
+//! The commands are not surfaced in the UI. They run in debug builds, or in
+//! release builds started with `ZYNC_ENABLE_BENCHMARKS=1`, and are invoked from
+//! the devtools console, e.g. `invoke('bench_tunnel_throughput', { megabytes: 256 })`.
+//! Results are plain JSON so they can be pasted into a bug report.
+
+pub mod commands;
+
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+pub const BENCHMARKS_ENV: &str = "ZYNC_ENABLE_BENCHMARKS";
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+pub(crate) fn ensure_enabled() -> Result<(), String> {
+    if cfg!(debug_assertions) || std::env::var_os(BENCHMARKS_ENV).is_some() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Benchmarks are disabled; restart with {BENCHMARKS_ENV}=1 to enable them"
+        ))
+    }
+}
+
+/// Clamp a requested payload size to 1 MiB..=1 GiB (64 MiB by default).
+pub(crate) fn payload_bytes(megabytes: Option<u64>) -> u64 {
+    megabytes.unwrap_or(64).clamp(1, 1024) * 1024 * 1024
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Throughput {
+    pub bytes: u64,
+    pub elapsed_ms: u64,
+    pub mib_per_sec: f64,
+}
+
+impl Throughput {
+    pub fn new(bytes: u64, elapsed: Duration) -> Self {
+        let seconds = elapsed.as_secs_f64().max(f64::EPSILON);
+        Self {
+            bytes,
+            elapsed_ms: elapsed.as_millis() as u64,
+            mib_per_sec: (bytes as f64 / (1024.0 * 1024.0) / seconds * 100.0).round() / 100.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencySummary {
+    pub count: usize,
+    pub min_us: u64,
+    pub p50_us: u64,
+    pub p95_us: u64,
+    pub p99_us: u64,
+    pub max_us: u64,
+}
+
+impl LatencySummary {
+    pub fn from_micros(mut samples: Vec<u64>) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        samples.sort_unstable();
+        let percentile = |p: usize| samples[((samples.len() - 1) * p) / 100];
+        Self {
+            count: samples.len(),
+            min_us: samples[0],
+            p50_us: percentile(50),
+            p95_us: percentile(95),
+            p99_us: percentile(99),
+            max_us: samples[samples.len() - 1],
+        }
+    }
+}
+
+/// Pattern bytes for synthetic payloads; not all zeroes so compression on the
+/// SSH transport cannot flatter the numbers too much.
+pub(crate) fn payload_chunk() -> Vec<u8> {
+    (0..CHUNK_SIZE)
+        .map(|index| (index.wrapping_mul(31) ^ (index >> 7)) as u8)
+        .collect()
+}
+
+/// Push `bytes` through the tunnel relay over loopback TCP: client → relay → sink.
+/// Measures the relay itself, without any SSH transport in the way.
+pub(crate) async fn relay_loopback(bytes: u64) -> std::io::Result<Throughput> {
+    let sink = TcpListener::bind("127.0.0.1:0").await?;
+    let sink_addr = sink.local_addr()?;
+    let front = TcpListener::bind("127.0.0.1:0").await?;
+    let front_addr = front.local_addr()?;
+
+    let sink_task = tokio::spawn(async move {
+        let (mut stream, _) = sink.accept().await?;
+        let mut buffer = vec![0u8; CHUNK_SIZE];
+        let mut received = 0u64;
+        loop {
+            let read = stream.read(&mut buffer).await?;
+            if read == 0 {
+                break;
+            }
+            received += read as u64;
+        }
+        Ok::<u64, std::io::Error>(received)
+    });
+    let relay_task = tokio::spawn(async move {
+        let (mut inbound, _) = front.accept().await?;
+        let mut outbound = TcpStream::connect(sink_addr).await?;
+        crate::tunnels::relay::copy_bidirectional(&mut inbound, &mut outbound).await
+    });
+
+    let chunk = payload_chunk();
+    let started = Instant::now();
+    let mut client = TcpStream::connect(front_addr).await?;
+    let mut remaining = bytes;
+    while remaining > 0 {
+        let len = remaining.min(chunk.len() as u64) as usize;
+        client.write_all(&chunk[..len]).await?;
+        remaining -= len as u64;
+    }
+    client.shutdown().await?;
+    let received = sink_task.await.map_err(std::io::Error::other)??;
+    let elapsed = started.elapsed();
+    relay_task.await.map_err(std::io::Error::other)??;
+
+    if received != bytes {
+        return Err(std::io::Error::other(format!(
+            "relay delivered {received} of {bytes} bytes"
+        )));
+    }
+    Ok(Throughput::new(received, elapsed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latency_summary_picks_percentiles() {
+        let summary = LatencySummary::from_micros((1..=100).rev().collect());
+        assert_eq!(summary.count, 100);
+        assert_eq!((summary.min_us, summary.max_us), (1, 100));
+        assert_eq!(summary.p50_us, 50);
+        assert_eq!(summary.p95_us, 95);
+        assert_eq!(
+            LatencySummary::from_micros(Vec::new()),
+            LatencySummary::default()
+        );
+    }
+
+    #[tokio::test]
+    async fn loopback_relay_delivers_every_byte() {
+        let result = relay_loopback(3 * CHUNK_SIZE as u64 + 17)
+            .await
+            .expect("relay benchmark");
+        assert_eq!(result.bytes, 3 * CHUNK_SIZE as u64 + 17);
+    }
+}
@@ -0,0 +1,126 @@
+//! OpenSSH hostname canonicalization (`CanonicalizeHostname`,
+//! `CanonicalDomains`, `CanonicalizeMaxDots`, `CanonicalizeFallbackLocal`).
+//!
+//! A short name is tried under each canonical domain in order and the first
+//! that resolves replaces it, so the connection and its known_hosts records use
+//! the same FQDN `ssh` would. CNAME rewriting (`CanonicalizePermittedCNAMEs`) is
+//! not supported.
+
+use crate::types::{CanonicalizeHostname, SshTransportOptions};
+
+/// OpenSSH's default `CanonicalizeMaxDots`.
+const DEFAULT_MAX_DOTS: u32 = 1;
+
+/// Names to try for `host`, in order; empty when it is an address, has more
+/// dots than allowed, or no domains are configured.
+pub fn candidates(host: &str, options: &SshTransportOptions) -> Vec<String> {
+    if host.parse::<std::net::IpAddr>().is_ok() {
+        return Vec::new();
+    }
+    let dots = host.matches('.').count() as u32;
+    if dots > options.canonicalize_max_dots.unwrap_or(DEFAULT_MAX_DOTS) {
+        return Vec::new();
+    }
+    options
+        .canonical_domains
+        .iter()
+        .flatten()
+        .map(|domain| format!("{host}.{domain}"))
+        .collect()
+}
+
+/// The name to connect to. `host` is returned unchanged when canonicalization
+/// is off or nothing resolves, unless `CanonicalizeFallbackLocal no` makes that
+/// an error.
+pub async fn canonicalize(
+    host: &str,
+    port: u16,
+    options: &SshTransportOptions,
+    via_jump_host: bool,
+) -> Result<String, String> {
+    match options.canonicalize_hostname.unwrap_or_default() {
+        CanonicalizeHostname::No => return Ok(host.to_string()),
+        CanonicalizeHostname::Yes if via_jump_host => return Ok(host.to_string()),
+        CanonicalizeHostname::Yes | CanonicalizeHostname::Always => {}
+    }
+    // A trailing dot marks a name that is already fully qualified.
+    if let Some(fqdn) = host.strip_suffix('.') {
+        return Ok(fqdn.to_string());
+    }
+    let names = candidates(host, options);
+    if names.is_empty() {
+        return Ok(host.to_string());
+    }
+    for name in names {
+        let resolved = tokio::net::lookup_host((name.as_str(), port))
+            .await
+            .is_ok_and(|mut addrs| addrs.next().is_some());
+        if resolved {
+            return Ok(name);
+        }
+    }
+    if options.canonicalize_fallback_local.unwrap_or(true) {
+        Ok(host.to_string())
+    } else {
+        Err(format!(
+            "Could not resolve {host} in any canonical domain and CanonicalizeFallbackLocal is off"
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(domains: &[&str], max_dots: Option<u32>) -> SshTransportOptions {
+        SshTransportOptions {
+            canonicalize_hostname: Some(CanonicalizeHostname::Yes),
+            canonical_domains: Some(domains.iter().map(|domain| domain.to_string()).collect()),
+            canonicalize_max_dots: max_dots,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn short_names_are_tried_under_each_domain() {
+        let options = options(&["example.com", "corp.example.com"], None);
+        assert_eq!(
+            candidates("web", &options),
+            vec!["web.example.com", "web.corp.example.com"]
+        );
+        assert_eq!(
+            candidates("web.eu", &options),
+            vec!["web.eu.example.com", "web.eu.corp.example.com"]
+        );
+        assert!(candidates("web.eu.example", &options).is_empty());
+        assert!(candidates("10.0.0.5", &options).is_empty());
+        assert!(candidates("::1", &options).is_empty());
+    }
+
+    #[test]
+    fn max_dots_zero_only_takes_bare_names() {
+        let options = options(&["example.com"], Some(0));
+        assert_eq!(candidates("db", &options), vec!["db.example.com"]);
+        assert!(candidates("db.eu", &options).is_empty());
+    }
+
+    #[tokio::test]
+    async fn names_are_kept_when_canonicalization_does_not_apply() {
+        let mut options = options(&["example.com"], None);
+        assert_eq!(
+            canonicalize("web", 22, &options, true).await.unwrap(),
+            "web"
+        );
+        assert_eq!(
+            canonicalize("web.example.org.", 22, &options, false)
+                .await
+                .unwrap(),
+            "web.example.org"
+        );
+        options.canonicalize_hostname = Some(CanonicalizeHostname::No);
+        assert_eq!(
+            canonicalize("web", 22, &options, false).await.unwrap(),
+            "web"
+        );
+    }
+}
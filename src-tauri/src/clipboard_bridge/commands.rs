@@ -0,0 +1,119 @@
+use super::{
+    pull_from_path_script, push_to_path_script, split_method, ClipboardTransfer,
+    MAX_CLIPBOARD_BYTES, PULL_SCRIPT, PUSH_SCRIPT,
+};
+use crate::commands::{
+    ensure_connection_online, exec_on_connection, exec_on_connection_with_stdin, AppState,
+};
+use tauri::{AppHandle, State};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+const FALLBACK_FILE: &str = "~/.zync/clipboard";
+
+fn check_size(bytes: usize) -> Result<(), String> {
+    if bytes > MAX_CLIPBOARD_BYTES {
+        return Err(format!(
+            "Clipboard text is too large ({bytes} bytes; limit {MAX_CLIPBOARD_BYTES})"
+        ));
+    }
+    Ok(())
+}
+
+fn remote_error(stderr: &str, exit_status: u32) -> String {
+    if stderr.is_empty() {
+        format!("Remote command failed with exit code {exit_status}")
+    } else {
+        stderr.to_string()
+    }
+}
+
+/// Send text to the remote clipboard, or to the remote file `path`.
+/// Without `text`, the local clipboard is sent.
+#[tauri::command]
+pub async fn clipboard_push_remote(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    connection_id: String,
+    text: Option<String>,
+    path: Option<String>,
+) -> Result<ClipboardTransfer, String> {
+    let text = match text {
+        Some(text) => text,
+        None => app
+            .clipboard()
+            .read_text()
+            .map_err(|e| format!("Failed to read the local clipboard: {e}"))?,
+    };
+    check_size(text.len())?;
+    let path = path
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty());
+    let script = path
+        .as_deref()
+        .map(push_to_path_script)
+        .unwrap_or_else(|| PUSH_SCRIPT.to_string());
+
+    ensure_connection_online(&state, &connection_id).await?;
+    let output =
+        exec_on_connection_with_stdin(&state, &connection_id, &script, text.as_bytes()).await?;
+    let (method, stderr) = split_method(&String::from_utf8_lossy(&output.stderr));
+    if output.exit_status != 0 {
+        return Err(remote_error(&stderr, output.exit_status));
+    }
+    let method = if path.is_some() {
+        "path".to_string()
+    } else {
+        method.unwrap_or_else(|| "unknown".to_string())
+    };
+    let remote_path = path.or_else(|| (method == "file").then(|| FALLBACK_FILE.to_string()));
+    Ok(ClipboardTransfer {
+        method,
+        bytes: text.len(),
+        remote_path,
+        text: String::new(),
+    })
+}
+
+/// Fetch the remote clipboard (or the remote file `path`) into the local
+/// clipboard. The text is returned as well.
+#[tauri::command]
+pub async fn clipboard_pull_remote(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    connection_id: String,
+    path: Option<String>,
+) -> Result<ClipboardTransfer, String> {
+    let path = path
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty());
+    let script = path
+        .as_deref()
+        .map(pull_from_path_script)
+        .unwrap_or_else(|| PULL_SCRIPT.to_string());
+
+    ensure_connection_online(&state, &connection_id).await?;
+    let output = exec_on_connection(&state, &connection_id, &script).await?;
+    let (method, stderr) = split_method(&String::from_utf8_lossy(&output.stderr));
+    if output.exit_status != 0 {
+        return Err(remote_error(&stderr, output.exit_status));
+    }
+    check_size(output.stdout.len())?;
+    let text = String::from_utf8(output.stdout)
+        .map_err(|_| "The remote clipboard does not contain UTF-8 text".to_string())?;
+    app.clipboard()
+        .write_text(text.clone())
+        .map_err(|e| format!("Failed to write the local clipboard: {e}"))?;
+
+    let method = if path.is_some() {
+        "path".to_string()
+    } else {
+        method.unwrap_or_else(|| "unknown".to_string())
+    };
+    let remote_path = path.or_else(|| (method == "file").then(|| FALLBACK_FILE.to_string()));
+    Ok(ClipboardTransfer {
+        method,
+        bytes: text.len(),
+        remote_path,
+        text,
+    })
+}
@@ -0,0 +1,99 @@
+//! Text transfer between the local clipboard and a remote host.
+//!
+//! Pushing feeds the text to the first remote clipboard tool that can work from
+//! an SSH exec channel (`pbcopy`, then `wl-copy`/`xclip`/`xsel` when a display
+//! is reachable) and falls back to `~/.zync/clipboard`. Pulling reads from the
+//! same tools in the same order. Either direction can target a remote file
+//! instead, which is what "paste this config into that file" usually means.
+//!
+//! The scripts report the tool they used on stderr as `zync-clipboard:<method>`.
+
+pub mod commands;
+
+use crate::ai::tool_exec_support::shell_quote;
+use serde::Serialize;
+
+/// Refuse transfers larger than this; clipboards aren't for bulk copies.
+pub(crate) const MAX_CLIPBOARD_BYTES: usize = 1024 * 1024;
+const METHOD_MARKER: &str = "zync-clipboard:";
+
+/// Reads stdin into the remote clipboard (or the fallback file).
+pub(crate) const PUSH_SCRIPT: &str = r#"if command -v pbcopy >/dev/null 2>&1; then pbcopy && echo "zync-clipboard:pbcopy" >&2
+elif [ -n "$WAYLAND_DISPLAY" ] && command -v wl-copy >/dev/null 2>&1; then wl-copy >/dev/null 2>&1 && echo "zync-clipboard:wl-copy" >&2
+elif [ -n "$DISPLAY" ] && command -v xclip >/dev/null 2>&1; then xclip -selection clipboard >/dev/null 2>&1 && echo "zync-clipboard:xclip" >&2
+elif [ -n "$DISPLAY" ] && command -v xsel >/dev/null 2>&1; then xsel --clipboard --input >/dev/null 2>&1 && echo "zync-clipboard:xsel" >&2
+else mkdir -p "$HOME/.zync" && (umask 077; cat > "$HOME/.zync/clipboard") && echo "zync-clipboard:file" >&2
+fi"#;
+
+/// Writes the remote clipboard (or the fallback file) to stdout.
+pub(crate) const PULL_SCRIPT: &str = r#"if command -v pbpaste >/dev/null 2>&1; then pbpaste && echo "zync-clipboard:pbpaste" >&2
+elif [ -n "$WAYLAND_DISPLAY" ] && command -v wl-paste >/dev/null 2>&1; then wl-paste --no-newline && echo "zync-clipboard:wl-paste" >&2
+elif [ -n "$DISPLAY" ] && command -v xclip >/dev/null 2>&1; then xclip -selection clipboard -o && echo "zync-clipboard:xclip" >&2
+elif [ -n "$DISPLAY" ] && command -v xsel >/dev/null 2>&1; then xsel --clipboard --output && echo "zync-clipboard:xsel" >&2
+elif [ -f "$HOME/.zync/clipboard" ]; then cat "$HOME/.zync/clipboard" && echo "zync-clipboard:file" >&2
+else echo "Nothing to pull: no clipboard tool is usable over SSH and ~/.zync/clipboard does not exist" >&2; exit 3
+fi"#;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipboardTransfer {
+    /// Remote tool used (`pbcopy`, `wl-paste`, `xclip`, ...), `file` for the
+    /// `~/.zync/clipboard` fallback or `path` for an explicit remote file.
+    pub method: String,
+    pub bytes: usize,
+    /// Remote file involved, for `file` and `path` transfers.
+    pub remote_path: Option<String>,
+    /// Pulled text (empty for pushes).
+    pub text: String,
+}
+
+/// Quote a remote path for `sh`, keeping a leading `~/` expandable.
+pub(crate) fn remote_path_arg(path: &str) -> String {
+    match path.strip_prefix("~/") {
+        Some(rest) => format!("\"$HOME\"/{}", shell_quote(rest)),
+        None => shell_quote(path),
+    }
+}
+
+pub(crate) fn push_to_path_script(path: &str) -> String {
+    format!("cat > {}", remote_path_arg(path))
+}
+
+pub(crate) fn pull_from_path_script(path: &str) -> String {
+    format!("cat {}", remote_path_arg(path))
+}
+
+/// The method the script reported, and stderr with the marker removed.
+pub(crate) fn split_method(stderr: &str) -> (Option<String>, String) {
+    let mut method = None;
+    let mut rest = Vec::new();
+    for line in stderr.lines() {
+        match line.trim().strip_prefix(METHOD_MARKER) {
+            Some(found) => method = Some(found.to_string()),
+            None => rest.push(line),
+        }
+    }
+    (method, rest.join("\n").trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remote_paths_are_quoted_with_home_expansion() {
+        assert_eq!(remote_path_arg("/etc/app.conf"), "'/etc/app.conf'");
+        assert_eq!(
+            remote_path_arg("~/it's here.yml"),
+            "\"$HOME\"/'it'\\''s here.yml'"
+        );
+    }
+
+    #[test]
+    fn method_marker_is_separated_from_errors() {
+        let (method, rest) = split_method("Warning: no DISPLAY\nzync-clipboard:file\n");
+        assert_eq!(method.as_deref(), Some("file"));
+        assert_eq!(rest, "Warning: no DISPLAY");
+        assert_eq!(split_method("boom").0, None);
+    }
+}
@@ -0,0 +1,229 @@
+use super::{
+    ClipboardEntry, ClipboardHistory, ClipboardHistorySettings, ENCRYPTED_FILE, PLAIN_FILE,
+};
+use crate::commands::{get_data_dir, read_effective_settings};
+use crate::vault::crypto::{
+    decrypt_record, encrypt_record, generate_vek, EncryptedEnvelope, SecretKey,
+};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+const KEYRING_SERVICE: &str = "Zync Clipboard History";
+const ENVELOPE_AAD: &[u8] = b"zync:clipboard-history:v1";
+const DEFAULT_LIST_LIMIT: usize = 100;
+
+static HISTORY_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+/// Runtime only: incognito never survives a restart.
+static INCOGNITO: AtomicBool = AtomicBool::new(false);
+
+#[derive(Serialize, Deserialize)]
+struct EncryptedHistoryFile {
+    version: u32,
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipboardHistoryStatus {
+    pub enabled: bool,
+    pub incognito: bool,
+    pub encrypted: bool,
+    pub count: usize,
+}
+
+fn read_settings(app: &AppHandle) -> ClipboardHistorySettings {
+    read_effective_settings(app)
+        .ok()
+        .and_then(|settings| settings.get("clipboardHistory").cloned())
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// One key per profile, created on first use.
+fn history_key(create: bool) -> Result<Option<SecretKey>, String> {
+    let account = format!("history:{}", crate::user_profiles::active_id());
+    let entry = keyring::Entry::new(KEYRING_SERVICE, &account)
+        .map_err(|e| format!("Keychain unavailable: {e}"))?;
+    match entry.get_password() {
+        Ok(encoded) => {
+            let bytes: [u8; 32] = STANDARD
+                .decode(encoded)
+                .ok()
+                .and_then(|bytes| bytes.as_slice().try_into().ok())
+                .ok_or_else(|| "Clipboard history key is corrupt".to_string())?;
+            Ok(Some(SecretKey::from_bytes(bytes)))
+        }
+        Err(keyring::Error::NoEntry) if create => {
+            let key = generate_vek();
+            entry
+                .set_password(&STANDARD.encode(key.as_bytes()))
+                .map_err(|e| format!("Failed to store clipboard history key: {e}"))?;
+            Ok(Some(key))
+        }
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read clipboard history key: {e}")),
+    }
+}
+
+fn read_encrypted(path: &Path) -> Result<ClipboardHistory, String> {
+    let file: EncryptedHistoryFile =
+        serde_json::from_slice(&std::fs::read(path).map_err(|e| e.to_string())?)
+            .map_err(|e| e.to_string())?;
+    let key = history_key(false)?
+        .ok_or_else(|| "Clipboard history key is missing from the keychain".to_string())?;
+    let nonce: [u8; 24] = STANDARD
+        .decode(&file.nonce)
+        .ok()
+        .and_then(|bytes| bytes.as_slice().try_into().ok())
+        .ok_or_else(|| "Clipboard history file is corrupt".to_string())?;
+    let envelope = EncryptedEnvelope {
+        nonce,
+        ciphertext: STANDARD
+            .decode(&file.ciphertext)
+            .map_err(|_| "Clipboard history file is corrupt".to_string())?,
+    };
+    let plaintext = decrypt_record(&key, &envelope, ENVELOPE_AAD)
+        .map_err(|_| "Clipboard history could not be decrypted".to_string())?;
+    serde_json::from_slice(&plaintext).map_err(|e| e.to_string())
+}
+
+/// Whichever file exists is read, so toggling `encrypt` keeps the history;
+/// the next write moves it to the configured format.
+fn load(dir: &Path) -> Result<ClipboardHistory, String> {
+    let encrypted = dir.join(ENCRYPTED_FILE);
+    if encrypted.exists() {
+        return read_encrypted(&encrypted);
+    }
+    match std::fs::read_to_string(dir.join(PLAIN_FILE)) {
+        Ok(content) => serde_json::from_str(&content).map_err(|e| e.to_string()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(ClipboardHistory::default()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn save(dir: &Path, history: &ClipboardHistory, encrypt: bool) -> Result<(), String> {
+    let json = serde_json::to_vec(history).map_err(|e| e.to_string())?;
+    let (target, stale, bytes) = if encrypt {
+        let key = history_key(true)?
+            .ok_or_else(|| "Clipboard history key is missing from the keychain".to_string())?;
+        let envelope = encrypt_record(&key, &json, ENVELOPE_AAD)
+            .map_err(|_| "Failed to encrypt clipboard history".to_string())?;
+        let file = EncryptedHistoryFile {
+            version: 1,
+            nonce: STANDARD.encode(envelope.nonce),
+            ciphertext: STANDARD.encode(&envelope.ciphertext),
+        };
+        let bytes = serde_json::to_vec(&file).map_err(|e| e.to_string())?;
+        (ENCRYPTED_FILE, PLAIN_FILE, bytes)
+    } else {
+        (PLAIN_FILE, ENCRYPTED_FILE, json)
+    };
+    crate::atomic_io::durable_replace(&dir.join(target), &bytes).map_err(|e| e.to_string())?;
+    let _ = std::fs::remove_file(dir.join(stale));
+    Ok(())
+}
+
+fn mutate<T>(
+    app: &AppHandle,
+    change: impl FnOnce(&mut ClipboardHistory, &ClipboardHistorySettings) -> Result<T, String>,
+) -> Result<T, String> {
+    let _guard = HISTORY_LOCK.lock().map_err(|e| e.to_string())?;
+    let settings = read_settings(app);
+    let dir = get_data_dir(app);
+    let mut history = load(&dir)?;
+    let result = change(&mut history, &settings)?;
+    save(&dir, &history, settings.encrypt)?;
+    Ok(result)
+}
+
+/// Mask `secrets` in the stored history; returns how many entries changed.
+pub(crate) fn scrub_secrets(app: &AppHandle, secrets: &[String]) -> Result<usize, String> {
+    mutate(app, |history, _| Ok(history.scrub(secrets)))
+}
+
+/// Record text copied from a terminal. Returns `None` when nothing was
+/// recorded: history disabled, incognito on, or blank text.
+#[tauri::command]
+pub async fn clipboard_history_add(
+    app: AppHandle,
+    text: String,
+    connection_id: Option<String>,
+) -> Result<Option<ClipboardEntry>, String> {
+    if INCOGNITO.load(Ordering::SeqCst) || !read_settings(&app).enabled {
+        return Ok(None);
+    }
+    mutate(&app, |history, settings| {
+        Ok(history.add(&text, connection_id, now_ms(), settings.max_entries))
+    })
+}
+
+#[tauri::command]
+pub async fn clipboard_history_list(
+    app: AppHandle,
+    query: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<ClipboardEntry>, String> {
+    let _guard = HISTORY_LOCK.lock().map_err(|e| e.to_string())?;
+    let history = load(&get_data_dir(&app))?;
+    Ok(history.search(query.as_deref(), limit.unwrap_or(DEFAULT_LIST_LIMIT)))
+}
+
+#[tauri::command]
+pub async fn clipboard_history_pin(
+    app: AppHandle,
+    id: String,
+    pinned: bool,
+) -> Result<ClipboardEntry, String> {
+    mutate(&app, |history, _| history.set_pinned(&id, pinned))
+}
+
+#[tauri::command]
+pub async fn clipboard_history_delete(app: AppHandle, id: String) -> Result<(), String> {
+    mutate(&app, |history, _| {
+        history.remove(&id);
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub async fn clipboard_history_clear(app: AppHandle, keep_pinned: bool) -> Result<(), String> {
+    mutate(&app, |history, _| {
+        history.clear(keep_pinned);
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub async fn clipboard_history_set_incognito(enabled: bool) -> Result<(), String> {
+    INCOGNITO.store(enabled, Ordering::SeqCst);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn clipboard_history_status(app: AppHandle) -> Result<ClipboardHistoryStatus, String> {
+    let settings = read_settings(&app);
+    let _guard = HISTORY_LOCK.lock().map_err(|e| e.to_string())?;
+    let count = load(&get_data_dir(&app))
+        .map(|history| history.entries.len())
+        .unwrap_or(0);
+    Ok(ClipboardHistoryStatus {
+        enabled: settings.enabled,
+        incognito: INCOGNITO.load(Ordering::SeqCst),
+        encrypted: settings.encrypt,
+        count,
+    })
+}
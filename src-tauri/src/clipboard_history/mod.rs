@@ -0,0 +1,224 @@
+//! History of text copied from terminals, so output copied an hour ago can
+//! still be found. Bounded in entries and bytes; pinned entries are never
+//! evicted. Stored in `clipboard_history.json` in the data dir, or encrypted
+//! in `clipboard_history.enc` with a key kept in the OS keychain when
+//! `clipboardHistory.encrypt` is set. Nothing is recorded while incognito
+//! mode is on.
+
+pub mod commands;
+
+use serde::{Deserialize, Serialize};
+
+pub const PLAIN_FILE: &str = "clipboard_history.json";
+pub const ENCRYPTED_FILE: &str = "clipboard_history.enc";
+/// Longer copies are kept truncated to this many bytes.
+pub const MAX_ENTRY_BYTES: usize = 256 * 1024;
+/// Oldest unpinned entries go first once the history holds more than this.
+const MAX_TOTAL_BYTES: usize = 16 * 1024 * 1024;
+const MAX_ENTRIES_LIMIT: usize = 5000;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ClipboardHistorySettings {
+    pub enabled: bool,
+    pub max_entries: usize,
+    /// Encrypt the stored history at rest.
+    pub encrypt: bool,
+}
+
+impl Default for ClipboardHistorySettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_entries: 200,
+            encrypt: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipboardEntry {
+    pub id: String,
+    pub text: String,
+    /// Connection whose terminal the text was copied from; `None` for local.
+    #[serde(default)]
+    pub connection_id: Option<String>,
+    pub copied_at: u64,
+    /// How often the same text was copied again.
+    #[serde(default)]
+    pub copy_count: u32,
+    #[serde(default)]
+    pub pinned: bool,
+    /// The original text was longer than `MAX_ENTRY_BYTES`.
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+/// Newest first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ClipboardHistory {
+    pub entries: Vec<ClipboardEntry>,
+}
+
+fn truncate_to(text: &str, max_bytes: usize) -> (String, bool) {
+    if text.len() <= max_bytes {
+        return (text.to_string(), false);
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    (text[..end].to_string(), true)
+}
+
+impl ClipboardHistory {
+    /// Record a copy. Copying text that is already in the history moves that
+    /// entry to the top instead of adding a duplicate.
+    pub fn add(
+        &mut self,
+        text: &str,
+        connection_id: Option<String>,
+        now: u64,
+        max_entries: usize,
+    ) -> Option<ClipboardEntry> {
+        if text.trim().is_empty() {
+            return None;
+        }
+        let (text, truncated) = truncate_to(text, MAX_ENTRY_BYTES);
+        let entry = match self.entries.iter().position(|entry| entry.text == text) {
+            Some(index) => {
+                let mut entry = self.entries.remove(index);
+                entry.copied_at = now;
+                entry.copy_count += 1;
+                entry.connection_id = connection_id;
+                entry
+            }
+            None => ClipboardEntry {
+                id: uuid::Uuid::new_v4().to_string(),
+                text,
+                connection_id,
+                copied_at: now,
+                copy_count: 1,
+                pinned: false,
+                truncated,
+            },
+        };
+        self.entries.insert(0, entry.clone());
+        self.prune(max_entries);
+        Some(entry)
+    }
+
+    /// Drop the oldest unpinned entries beyond `max_entries` or the byte budget.
+    pub fn prune(&mut self, max_entries: usize) {
+        let max_entries = max_entries.clamp(1, MAX_ENTRIES_LIMIT);
+        let mut kept = 0usize;
+        let mut bytes = 0usize;
+        self.entries.retain(|entry| {
+            if entry.pinned {
+                return true;
+            }
+            kept += 1;
+            bytes += entry.text.len();
+            kept <= max_entries && bytes <= MAX_TOTAL_BYTES
+        });
+    }
+
+    /// Entries containing `query` (case-insensitive), pinned ones first, then
+    /// newest first.
+    pub fn search(&self, query: Option<&str>, limit: usize) -> Vec<ClipboardEntry> {
+        let query = query
+            .map(|query| query.trim().to_lowercase())
+            .filter(|query| !query.is_empty());
+        let mut matches: Vec<ClipboardEntry> = self
+            .entries
+            .iter()
+            .filter(|entry| match &query {
+                Some(query) => entry.text.to_lowercase().contains(query),
+                None => true,
+            })
+            .cloned()
+            .collect();
+        matches.sort_by(|a, b| b.pinned.cmp(&a.pinned).then(b.copied_at.cmp(&a.copied_at)));
+        matches.truncate(limit);
+        matches
+    }
+
+    pub fn set_pinned(&mut self, id: &str, pinned: bool) -> Result<ClipboardEntry, String> {
+        let entry = self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.id == id)
+            .ok_or_else(|| "Clipboard entry not found".to_string())?;
+        entry.pinned = pinned;
+        Ok(entry.clone())
+    }
+
+    pub fn remove(&mut self, id: &str) {
+        self.entries.retain(|entry| entry.id != id);
+    }
+
+    pub fn clear(&mut self, keep_pinned: bool) {
+        self.entries.retain(|entry| keep_pinned && entry.pinned);
+    }
+
+    /// Mask `secrets` in every entry; returns how many entries changed.
+    pub fn scrub(&mut self, secrets: &[String]) -> usize {
+        self.entries
+            .iter_mut()
+            .map(|entry| crate::redaction::scrub_literals(&mut entry.text, secrets))
+            .filter(|changed| *changed)
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dedupes_bounds_and_keeps_pins() {
+        let mut history = ClipboardHistory::default();
+        let first = history.add("uptime", None, 1, 3).unwrap();
+        history.set_pinned(&first.id, true).unwrap();
+        history.add("df -h", Some("web".into()), 2, 3);
+        history.add("free -m", None, 3, 3);
+        history.add("ls", None, 4, 3);
+        history.add("ps aux", None, 5, 3);
+        assert!(history.add("   ", None, 6, 3).is_none());
+
+        let texts: Vec<&str> = history.entries.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(texts, ["ps aux", "ls", "free -m", "uptime"]);
+
+        let again = history.add("free -m", Some("db".into()), 7, 3).unwrap();
+        assert_eq!(again.copy_count, 2);
+        assert_eq!(again.connection_id.as_deref(), Some("db"));
+        assert_eq!(history.entries[0].text, "free -m");
+        assert_eq!(history.entries.len(), 4);
+
+        history.clear(true);
+        assert_eq!(history.entries.len(), 1);
+        assert!(history.entries[0].pinned);
+    }
+
+    #[test]
+    fn searches_with_pins_first_and_truncates_long_copies() {
+        let mut history = ClipboardHistory::default();
+        let pinned = history.add("Error: disk full", None, 1, 10).unwrap();
+        history.set_pinned(&pinned.id, true).unwrap();
+        history.add("error 500 from upstream", None, 2, 10);
+        history.add("all good", None, 3, 10);
+        let found: Vec<String> = history
+            .search(Some("ERROR"), 10)
+            .into_iter()
+            .map(|e| e.text)
+            .collect();
+        assert_eq!(found, ["Error: disk full", "error 500 from upstream"]);
+        assert_eq!(history.search(None, 1).len(), 1);
+
+        let long = "é".repeat(MAX_ENTRY_BYTES);
+        let entry = history.add(&long, None, 4, 10).unwrap();
+        assert!(entry.truncated);
+        assert!(entry.text.len() <= MAX_ENTRY_BYTES);
+    }
+}
@@ -1,5 +1,11 @@
+use crate::ai::tool_exec_support::shell_quote;
+use crate::data_validation::DataFile;
+use crate::events::ConnectionEvent;
 use crate::fs::{FileEntry, FileSystem};
+use crate::operations::OperationKind;
 use crate::pty::PtyManager;
+use crate::session_channels::{open_session_channel, SessionChannelError, SessionChannelPermit};
+use crate::session_refs::{Consumer, SessionRef};
 use crate::ssh::{Client, SshManager};
 use crate::types::*;
 use anyhow::Result;
@@ -15,12 +21,13 @@ use tauri::{AppHandle, Manager, State};
 use tauri_plugin_store::StoreExt;
 use tokio::sync::Mutex;
 
+use crate::tunnels::runtime_notice::{spawn_tunnel_notice_watcher, tunnel_notice_channel};
 use crate::tunnels::session_failure::{session_failure_channel, spawn_session_failure_watcher};
 use crate::tunnels::TunnelManager;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-const MAX_IMPORT_TEXT_BYTES: usize = 1_048_576; // 1 MiB
+pub(crate) const MAX_IMPORT_TEXT_BYTES: usize = 1_048_576; // 1 MiB
 const MAX_CONNECTION_IMPORT_BYTES: u64 = 5 * 1024 * 1024; // 5 MiB
 const SETTINGS_CHANGED_ON_DISK_ERROR_CODE: &str = "SETTINGS_CHANGED_ON_DISK";
 const MAX_SFTP_RETRIES: u8 = 3;
@@ -110,10 +117,12 @@ fn merge_json_values(base: Value, overlay: Value) -> Value {
 }
 
 /// Resolve the native, user-scoped settings directory (OS config convention).
-/// Example: `%APPDATA%/Zync/User` on Windows, `~/.config/Zync/User` on Linux.
+/// Example: `%APPDATA%/Zync/User` on Windows, `~/.config/Zync/User` on Linux;
+/// profiles other than the default one have their own (see `user_profiles`).
 fn get_native_settings_dir(app: &AppHandle) -> Result<std::path::PathBuf, String> {
     let config_root = app.path().config_dir().map_err(|e| e.to_string())?;
-    let dir = config_root.join("Zync").join("User");
+    let profile_id = crate::user_profiles::active_id();
+    let dir = crate::user_profiles::settings_dir(&config_root, &profile_id);
     if !dir.exists() {
         std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
     }
@@ -133,6 +142,9 @@ fn get_last_known_good_settings_path(app: &AppHandle) -> Result<std::path::PathB
 /// Legacy settings locations that we still read once for migration compatibility.
 fn get_legacy_settings_candidates(app: &AppHandle) -> Vec<std::path::PathBuf> {
     let mut candidates = Vec::new();
+    if crate::user_profiles::active_id() != crate::user_profiles::DEFAULT_PROFILE_ID {
+        return candidates;
+    }
     if let Ok(app_data_dir) = app.path().app_data_dir() {
         candidates.push(app_data_dir.join("settings.json"));
     }
@@ -376,10 +388,13 @@ pub fn get_data_dir(app: &AppHandle) -> std::path::PathBuf {
         }
     }
 
-    let default_dir = app
-        .path()
-        .app_data_dir()
-        .unwrap_or_else(|_| std::path::PathBuf::from("."));
+    let default_dir = crate::user_profiles::default_data_dir(
+        &app
+            .path()
+            .app_data_dir()
+            .unwrap_or_else(|_| std::path::PathBuf::from(".")),
+        &crate::user_profiles::active_id(),
+    );
     let merged_settings =
         read_effective_settings(app).unwrap_or_else(|_| Value::Object(serde_json::Map::new()));
 
@@ -464,7 +479,6 @@ pub struct AppState {
     pub ssh_manager: Arc<SshManager>,
     pub tunnel_manager: Arc<TunnelManager>,
     pub snippets_manager: Arc<crate::snippets::SnippetsManager>,
-    pub transfers: Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>,
     // Agent v2: active run cancellation tokens
     pub agent_runs: Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>,
     // Agent v2: pending checkpoint responders (ask_user tool)
@@ -479,8 +493,11 @@ pub struct AppState {
 
 impl AppState {
     pub fn new(data_dir: std::path::PathBuf, app_handle: tauri::AppHandle) -> Self {
+        crate::events::attach(app_handle.clone());
         let (failure_tx, failure_rx) = session_failure_channel();
         spawn_session_failure_watcher(app_handle.clone(), failure_rx);
+        let (notice_tx, notice_rx) = tunnel_notice_channel();
+        spawn_tunnel_notice_watcher(app_handle.clone(), notice_rx);
 
         Self {
             app_handle,
@@ -488,9 +505,8 @@ impl AppState {
             pty_manager: Arc::new(PtyManager::new()),
             file_system: Arc::new(FileSystem::new()),
             ssh_manager: Arc::new(SshManager::new()),
-            tunnel_manager: Arc::new(TunnelManager::new(failure_tx)),
+            tunnel_manager: Arc::new(TunnelManager::new(failure_tx, notice_tx)),
             snippets_manager: Arc::new(crate::snippets::SnippetsManager::new(data_dir.clone())),
-            transfers: Arc::new(Mutex::new(HashMap::new())),
             agent_runs: Arc::new(Mutex::new(HashMap::new())),
             agent_checkpoints: Arc::new(Mutex::new(HashMap::new())),
             command_whitelist: Arc::new(Mutex::new(HashMap::new())),
@@ -506,6 +522,8 @@ pub struct ConnectionHandle {
     pub config: ConnectionConfig,
     pub session: Option<Arc<Mutex<Handle<Client>>>>,
     pub sftp_session: Option<Arc<russh_sftp::client::SftpSession>>,
+    /// MaxSessions slot held by the long-lived SFTP channel.
+    pub sftp_channel_permit: Option<SessionChannelPermit>,
     pub detected_os: Option<String>,
     pub detected_shell: Option<String>,
     pub uses_vault_auth: bool,
@@ -515,136 +533,124 @@ pub struct ConnectionHandle {
     pub reconnect_lock: Arc<tokio::sync::Mutex<()>>,
 }
 
+/// How long an OS/shell probe waits for a session slot before the probe is skipped.
+const DETECTION_PROBE_WAIT: Duration = Duration::from_secs(5);
+
+/// Runs one detection command on its own gated session channel and returns its output.
+async fn detection_probe(
+    app: &AppHandle,
+    connection_id: &str,
+    session: &Arc<Mutex<Handle<Client>>>,
+    command: &str,
+) -> Option<String> {
+    let open = open_session_channel(app, connection_id, session);
+    let (mut channel, _permit) = tokio::time::timeout(DETECTION_PROBE_WAIT, open)
+        .await
+        .ok()?
+        .ok()?;
+    channel.exec(true, command).await.ok()?;
+    let mut output = String::new();
+    while let Some(msg) = channel.wait().await {
+        match msg {
+            russh::ChannelMsg::Data { data } => output.push_str(&String::from_utf8_lossy(&data)),
+            russh::ChannelMsg::ExitStatus { .. } => break,
+            _ => {}
+        }
+    }
+    Some(output)
+}
+
 /// Internal helper: establishes a full SSH connection (session + SFTP + OS detection)
 /// and returns a fresh `ConnectionHandle`. Used for initial `ssh_connect` and reactive reconnection.
 async fn reconnect_connection(
+    app: &AppHandle,
     config: &ConnectionConfig,
     ssh_manager: &crate::ssh::SshManager,
     tunnel_manager: &crate::tunnels::TunnelManager,
 ) -> Result<ConnectionHandle, String> {
+    crate::network::ensure_config_reachable(config).await?;
     let session = ssh_manager
         .connect(config.clone(), Arc::new(tunnel_manager.clone()))
         .await
         .map_err(|e| format!("Failed to connect: {}", e))?;
+    let session = Arc::new(Mutex::new(session));
 
     // Initialize SFTP session
-    let sftp_session = match session.channel_open_session().await {
-        Ok(channel) => {
-            if let Err(e) = channel.request_subsystem(true, "sftp").await {
-                eprintln!("[SSH] Failed to request SFTP subsystem: {}", e);
-                None
-            } else {
-                let stream = channel.into_stream();
-                match russh_sftp::client::SftpSession::new(stream).await {
-                    Ok(sftp) => Some(Arc::new(sftp)),
-                    Err(e) => {
-                        eprintln!("[SSH] Failed to initialize SFTP: {}", e);
-                        None
+    let (sftp_session, sftp_channel_permit) =
+        match open_session_channel(app, &config.id, &session).await {
+            Ok((channel, permit)) => {
+                if let Err(e) = channel.request_subsystem(true, "sftp").await {
+                    eprintln!("[SSH] Failed to request SFTP subsystem: {}", e);
+                    (None, None)
+                } else {
+                    let stream = channel.into_stream();
+                    match russh_sftp::client::SftpSession::new(stream).await {
+                        Ok(sftp) => (Some(Arc::new(sftp)), Some(permit)),
+                        Err(e) => {
+                            eprintln!("[SSH] Failed to initialize SFTP: {}", e);
+                            (None, None)
+                        }
                     }
                 }
             }
-        }
-        Err(e) => {
-            eprintln!("[SSH] Failed to open channel for SFTP: {}", e);
-            None
-        }
-    };
+            Err(e) => {
+                eprintln!("[SSH] Failed to open channel for SFTP: {}", e);
+                (None, None)
+            }
+        };
 
     // Detect OS (best-effort — reuse cached value if already known via caller)
     let mut detected_os = None;
-    if let Ok(mut channel) = session.channel_open_session().await {
-        if channel.exec(true, "cat /etc/os-release").await.is_ok() {
-            let mut output = String::new();
-            while let Some(msg) = channel.wait().await {
-                match msg {
-                    russh::ChannelMsg::Data { data } => {
-                        output.push_str(&String::from_utf8_lossy(&data))
-                    }
-                    russh::ChannelMsg::ExitStatus { .. } => break,
-                    _ => {}
-                }
-            }
-            for line in output.lines() {
-                if line.starts_with("ID=") {
-                    let id = line.trim_start_matches("ID=").trim_matches('"');
-                    detected_os = Some(id.to_string());
-                    break;
-                }
+    if let Some(output) = detection_probe(app, &config.id, &session, "cat /etc/os-release").await
+    {
+        for line in output.lines() {
+            if line.starts_with("ID=") {
+                let id = line.trim_start_matches("ID=").trim_matches('"');
+                detected_os = Some(id.to_string());
+                break;
             }
         }
     }
     if detected_os.is_none() {
-        if let Ok(mut channel) = session.channel_open_session().await {
-            if channel.exec(true, "uname -s").await.is_ok() {
-                let mut output = String::new();
-                while let Some(msg) = channel.wait().await {
-                    match msg {
-                        russh::ChannelMsg::Data { data } => {
-                            output.push_str(&String::from_utf8_lossy(&data))
-                        }
-                        russh::ChannelMsg::ExitStatus { .. } => break,
-                        _ => {}
-                    }
-                }
-                let sys_name = output.trim().to_lowercase();
-                if sys_name == "darwin" {
-                    detected_os = Some("macos".to_string());
-                } else if !sys_name.is_empty() {
-                    detected_os = Some(sys_name);
-                }
+        if let Some(output) = detection_probe(app, &config.id, &session, "uname -s").await {
+            let sys_name = output.trim().to_lowercase();
+            if sys_name == "darwin" {
+                detected_os = Some("macos".to_string());
+            } else if !sys_name.is_empty() {
+                detected_os = Some(sys_name);
             }
         }
     }
     if detected_os.is_none() {
-        if let Ok(mut channel) = session.channel_open_session().await {
-            if channel.exec(true, "cmd /c ver").await.is_ok() {
-                let mut output = String::new();
-                while let Some(msg) = channel.wait().await {
-                    match msg {
-                        russh::ChannelMsg::Data { data } => {
-                            output.push_str(&String::from_utf8_lossy(&data))
-                        }
-                        russh::ChannelMsg::ExitStatus { .. } => break,
-                        _ => {}
-                    }
-                }
-                if output.to_lowercase().contains("windows") {
-                    detected_os = Some("windows".to_string());
-                }
+        if let Some(output) = detection_probe(app, &config.id, &session, "cmd /c ver").await {
+            if output.to_lowercase().contains("windows") {
+                detected_os = Some("windows".to_string());
             }
         }
     }
 
     // Detect login shell (best-effort)
     let mut detected_shell = None;
-    if detected_os
-        .as_deref()
-        .map(|os| os.eq_ignore_ascii_case("windows"))
-        .unwrap_or(false)
+    if crate::remote_os::is_windows(detected_os.as_deref()) {
+        // OpenSSH for Windows starts `DefaultShell` (cmd.exe when unset) for
+        // both interactive shells and exec requests.
+        let query = crate::remote_os::DEFAULT_SHELL_QUERY;
+        if let Some(output) = detection_probe(app, &config.id, &session, query).await {
+            detected_shell = Some(crate::remote_os::parse_default_shell(&output));
+        }
+    } else if let Some(output) =
+        detection_probe(app, &config.id, &session, "basename \"${SHELL:-}\"").await
     {
-        detected_shell = Some("powershell".to_string());
-    } else if let Ok(mut channel) = session.channel_open_session().await {
-        if channel.exec(true, "basename \"${SHELL:-}\"").await.is_ok() {
-            let mut output = String::new();
-            while let Some(msg) = channel.wait().await {
-                match msg {
-                    russh::ChannelMsg::Data { data } => {
-                        output.push_str(&String::from_utf8_lossy(&data))
-                    }
-                    russh::ChannelMsg::ExitStatus { .. } => break,
-                    _ => {}
-                }
-            }
-            let shell_name = output.trim().to_string();
-            if !shell_name.is_empty() {
-                detected_shell = Some(shell_name);
-            }
+        let shell_name = output.trim().to_string();
+        if !shell_name.is_empty() {
+            detected_shell = Some(shell_name);
         }
     }
 
     Ok(ConnectionHandle {
         config: config.clone(),
-        session: Some(Arc::new(Mutex::new(session))),
+        session: Some(session),
+        sftp_channel_permit,
         sftp_session,
         detected_os,
         detected_shell,
@@ -761,7 +767,8 @@ fn persist_relinked_vault_refs(
         .lock()
         .map_err(|e| e.to_string())?;
     let data = std::fs::read_to_string(&file_path).map_err(|e| e.to_string())?;
-    let mut saved_data: SavedData = serde_json::from_str(&data).map_err(|e| e.to_string())?;
+    let mut saved_data: SavedData =
+        crate::data_validation::parse_or_repair(DataFile::Connections, &file_path, &data)?;
     let mut changed = false;
 
     for update in updates {
@@ -791,62 +798,174 @@ fn persist_relinked_vault_refs(
     }
 
     if changed {
-        let json = serde_json::to_string_pretty(&saved_data).map_err(|e| e.to_string())?;
+        let json = crate::data_format::to_versioned_json(
+            DataFile::Connections,
+            &file_path,
+            &saved_data,
+        )?;
         write_atomic_file(&file_path, &json)?;
     }
 
     Ok(())
 }
 
+/// Live session another connection holds to the same target as `config`, and a
+/// handle for `config` that shares it (see `session_guard`).
+async fn find_duplicate_session(
+    state: &AppState,
+    config: &ConnectionConfig,
+) -> Option<(ConnectionConfig, ConnectionHandle)> {
+    let connections = state.connections.lock().await;
+    connections.iter().find_map(|(id, handle)| {
+        if id == &config.id || !crate::session_guard::same_target(&handle.config, config) {
+            return None;
+        }
+        let session = handle.session.as_ref()?;
+        // A held lock means the session is busy opening a channel, so it is alive.
+        if session.try_lock().is_ok_and(|guard| guard.is_closed()) {
+            return None;
+        }
+        let shared = ConnectionHandle {
+            config: config.clone(),
+            session: Some(session.clone()),
+            sftp_session: handle.sftp_session.clone(),
+            sftp_channel_permit: None,
+            detected_os: handle.detected_os.clone(),
+            detected_shell: handle.detected_shell.clone(),
+            uses_vault_auth: handle.uses_vault_auth,
+            reconnect_generation: 0,
+            reconnect_lock: Arc::new(tokio::sync::Mutex::new(())),
+        };
+        Some((handle.config.clone(), shared))
+    })
+}
+
 #[tauri::command]
 pub async fn ssh_connect(
     app: AppHandle,
     mut config: ConnectionConfig,
+    on_duplicate: Option<crate::session_guard::DuplicateChoice>,
     state: State<'_, AppState>,
     vault: State<'_, tokio::sync::Mutex<crate::vault::store::VaultService>>,
 ) -> Result<ConnectionResponse, String> {
+    use crate::session_guard::DuplicateChoice;
+
     let original_config = config.clone();
+    let attempt = crate::session_state::begin_connect(&original_config.id);
     let uses_vault_auth = config_uses_vault_auth(&original_config);
-    let relinked = resolve_vault_refs(&mut config, &vault).await?;
-    if !relinked.is_empty() {
-        let app_handle = app.clone();
-        let persist_result =
-            tokio::task::spawn_blocking(move || persist_relinked_vault_refs(&app_handle, &relinked))
-                .await;
-        match persist_result {
-            Ok(Ok(())) => {}
-            Ok(Err(error)) => return Err(format!("Failed to persist relinked vault refs: {error}")),
-            Err(join_error) => {
-                return Err(format!(
-                    "Failed to persist relinked vault refs: task join error: {join_error}"
-                ))
+
+    let mut shared = None;
+    if let Some((existing, handle)) = find_duplicate_session(&state, &original_config).await {
+        match on_duplicate {
+            None => return Err(crate::session_guard::duplicate_error(&existing)),
+            Some(DuplicateChoice::FocusExisting) => {
+                return Ok(ConnectionResponse {
+                    success: false,
+                    message: format!("Already connected as {}", existing.name),
+                    term_id: None,
+                    detected_os: handle.detected_os,
+                    profile: None,
+                    focus_connection_id: Some(existing.id),
+                })
+            }
+            Some(DuplicateChoice::NewChannel) => {
+                crate::session_channels::share_gate(&existing.id, &original_config.id);
+                shared = Some(handle);
             }
+            Some(DuplicateChoice::SeparateConnection) => {}
         }
     }
-    match reconnect_connection(&config, &state.ssh_manager, &state.tunnel_manager).await {
+
+    let opens_session = shared.is_none();
+    let result = match shared {
+        Some(handle) => Ok(handle),
+        None => {
+            let relinked = resolve_vault_refs(&mut config, &vault).await?;
+            if !relinked.is_empty() {
+                let app_handle = app.clone();
+                let persist_result = tokio::task::spawn_blocking(move || {
+                    persist_relinked_vault_refs(&app_handle, &relinked)
+                })
+                .await;
+                match persist_result {
+                    Ok(Ok(())) => {}
+                    Ok(Err(error)) => {
+                        return Err(format!("Failed to persist relinked vault refs: {error}"))
+                    }
+                    Err(join_error) => {
+                        return Err(format!(
+                            "Failed to persist relinked vault refs: task join error: {join_error}"
+                        ))
+                    }
+                }
+            }
+            attempt
+                .run(reconnect_connection(
+                    &app,
+                    &config,
+                    &state.ssh_manager,
+                    &state.tunnel_manager,
+                ))
+                .await
+        }
+    };
+    match result {
         Ok(mut handle) => {
             let detected_os = handle.detected_os.clone();
             // Do not keep decrypted vault secrets in the long-lived handle config.
             // The handle keeps the original VaultRef config so future reconnects
             // require the vault to be explicitly unlocked again.
             handle.config = original_config.clone();
-            handle.uses_vault_auth = uses_vault_auth;
+            // A shared session stays vault-backed if the connection that opened it was.
+            handle.uses_vault_auth |= uses_vault_auth;
             let mut connections = state.connections.lock().await;
             handle.reconnect_generation = connections
                 .get(&original_config.id)
                 .map(|existing| existing.reconnect_generation.wrapping_add(1))
                 .unwrap_or(0);
             connections.insert(original_config.id.clone(), handle);
+            drop(connections);
+            crate::session_refs::connected(&original_config.id, original_config.session_lifetime);
+            attempt.finish(true);
+            crate::host_power::forget_disconnect(&original_config.id);
+            crate::keepalive::watch(&app, &original_config.id);
+
+            let profile = crate::profiles::resolve_profile(&app, &original_config.id);
+            crate::events::publish(
+                &original_config.id,
+                ConnectionEvent::Connected {
+                    host: original_config.host.clone(),
+                    port: original_config.port,
+                    username: original_config.username.clone(),
+                    profile: profile.clone(),
+                },
+            );
+            if opens_session {
+                crate::local_command::spawn_after_connect(&app, &original_config);
+            }
 
             Ok(ConnectionResponse {
                 success: true,
                 message: "Connected".to_string(),
                 term_id: Some(original_config.id.clone()),
                 detected_os,
+                profile: Some(profile),
+                focus_connection_id: None,
             })
         }
         Err(e) => {
-            eprintln!("[SSH] Connection failed: {}", e);
+            attempt.finish(false);
+            // The user stopped it; not a failure worth reporting or diagnosing.
+            if e.starts_with(crate::session_state::CANCELLED_PREFIX) {
+                return Err(e);
+            }
+            let event = if e.contains("Authentication failed") {
+                ConnectionEvent::AuthFailed { error: e.clone() }
+            } else {
+                ConnectionEvent::ConnectFailed { error: e.clone() }
+            };
+            crate::events::publish(&original_config.id, event);
+            crate::diagnostics::emit_connection_error_hint(&app, &original_config, &e);
             Err(e)
         }
     }
@@ -865,7 +984,8 @@ pub async fn ssh_test_connection(
         .await
     {
         Ok(session) => {
-            // Try a simple command to verify session
+            // Try a simple command to verify session. This transport is a throwaway
+            // of its own, so there is no connection gate for it to count against.
             let result = match session.channel_open_session().await {
                 Ok(mut channel) => {
                     if channel.exec(true, "echo success").await.is_ok() {
@@ -1009,7 +1129,7 @@ pub async fn ssh_migrate_all_keys(app_handle: tauri::AppHandle) -> Result<usize,
         .map_err(|e| e.to_string())?;
     let data = std::fs::read_to_string(&connections_path).map_err(|e| e.to_string())?;
     let mut saved_data: crate::types::SavedData =
-        serde_json::from_str(&data).map_err(|e| e.to_string())?;
+        crate::data_validation::parse_or_repair(DataFile::Connections, &connections_path, &data)?;
     let mut migrated_count = 0;
     let mut changed = false;
 
@@ -1097,7 +1217,11 @@ pub async fn ssh_migrate_all_keys(app_handle: tauri::AppHandle) -> Result<usize,
     }
 
     if changed {
-        let json = serde_json::to_string_pretty(&saved_data).map_err(|e| e.to_string())?;
+        let json = crate::data_format::to_versioned_json(
+            DataFile::Connections,
+            &connections_path,
+            &saved_data,
+        )?;
 
         // Use OpenOptions to truncate and write, then sync_all to ensure durability
         use std::fs::OpenOptions;
@@ -1131,6 +1255,8 @@ pub async fn ssh_transport_lost(
     id: String,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
+    crate::session_refs::forget(&id);
+    crate::tunnels::restore::remember_running(&app, &state, &id).await;
     if let Err(error) = crate::tunnels::stop_tunnels_for_connections(&app, &state, &[id.clone()]).await {
         eprintln!("[TUNNEL] stop on transport lost for {id}: {error}");
     }
@@ -1146,18 +1272,35 @@ pub async fn ssh_disconnect(
     id: String,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
+    disconnect_connection(&app, &state, &id).await
+}
+
+/// Close a connection's terminals and tunnels and drop its session.
+pub(crate) async fn disconnect_connection(
+    app: &AppHandle,
+    state: &AppState,
+    id: &str,
+) -> Result<(), String> {
+    // Terminals and tunnels closed below must not count as going idle.
+    crate::session_refs::forget(id);
     state
         .pty_manager
-        .close_by_connection(&id)
+        .close_by_connection(id)
         .await
         .map_err(|e| e.to_string())?;
 
-    if let Err(error) = crate::tunnels::stop_tunnels_for_connections(&app, &state, &[id.clone()]).await {
+    let ids = [id.to_string()];
+    if let Err(error) = crate::tunnels::stop_tunnels_for_connections(app, state, &ids).await {
         eprintln!("[TUNNEL] stop on disconnect for {id}: {error}");
     }
+    // Disconnecting on purpose ends any pending restore from an earlier drop.
+    state.tunnel_manager.take_for_restore(id).await;
 
     let mut connections = state.connections.lock().await;
-    connections.remove(&id);
+    connections.remove(id);
+    drop(connections);
+    crate::session_channels::forget_gate(id);
+    crate::events::publish(id, ConnectionEvent::Disconnected);
 
     Ok(())
 }
@@ -1177,6 +1320,7 @@ pub async fn ssh_disconnect_vault_backed(
 
     let mut errors = Vec::new();
     for id in &ids {
+        crate::session_refs::forget(id);
         if let Err(error) = state.pty_manager.close_by_connection(id).await {
             errors.push(format!("PTY close failed for {id}: {error}"));
         }
@@ -1210,6 +1354,13 @@ pub async fn terminal_write(
         .map_err(|e| e.to_string())
 }
 
+/// The frontend has rendered `bytes` of a terminal's output (see `pty_flow`).
+#[tauri::command]
+pub async fn terminal_ack(term_id: String, generation: u32, bytes: usize) -> Result<(), String> {
+    crate::pty_flow::ack(&term_id, generation, bytes);
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn terminal_resize(
     term_id: String,
@@ -1259,8 +1410,14 @@ pub async fn connections_get(
         });
     }
 
-    let data = std::fs::read_to_string(file_path).map_err(|e| e.to_string())?;
-    let saved_data: SavedData = serde_json::from_str(&data).map_err(|e| e.to_string())?;
+    let mut saved_data: SavedData = {
+        let _connections_guard = CONNECTIONS_MUTATION_LOCK
+            .lock()
+            .map_err(|e| e.to_string())?;
+        let data = std::fs::read_to_string(&file_path).map_err(|e| e.to_string())?;
+        crate::data_validation::parse_or_repair(DataFile::Connections, &file_path, &data)?
+    };
+    crate::secret_split::commands::hydrate(&mut saved_data);
 
     Ok(saved_data)
 }
@@ -1271,7 +1428,7 @@ pub async fn connections_save(
     connections: Vec<SavedConnection>,
     folders: Vec<Folder>,
 ) -> Result<(), String> {
-    let data = SavedData {
+    let mut data = SavedData {
         connections,
         folders,
     };
@@ -1282,12 +1439,20 @@ pub async fn connections_save(
     }
 
     let file_path = data_dir.join("connections.json");
-    let json = serde_json::to_string_pretty(&data).map_err(|e| e.to_string())?;
 
-    let _connections_guard = CONNECTIONS_MUTATION_LOCK
-        .lock()
-        .map_err(|e| e.to_string())?;
-    write_atomic_file(&file_path, &json)?;
+    {
+        let _connections_guard = CONNECTIONS_MUTATION_LOCK
+            .lock()
+            .map_err(|e| e.to_string())?;
+        let previous = crate::sync::domain_hosts::load_saved_data(&file_path)
+            .map(|saved| saved.connections)
+            .unwrap_or_default();
+        crate::secret_split::commands::prepare_for_save(&app, &previous, &mut data);
+        let json =
+            crate::data_format::to_versioned_json(DataFile::Connections, &file_path, &data)?;
+        write_atomic_file(&file_path, &json)?;
+    }
+    crate::smart_groups::publish_membership(&app, &data);
 
     Ok(())
 }
@@ -1351,7 +1516,7 @@ fn normalize_folder_path(value: &str) -> String {
         .join("/")
 }
 
-fn build_host_alias(connection: &SavedConnection) -> String {
+pub(crate) fn build_host_alias(connection: &SavedConnection) -> String {
     let mut alias = connection
         .name
         .trim()
@@ -1587,6 +1752,15 @@ fn parse_csv_connections(content: &str) -> Result<Vec<SavedConnection>, String>
                 Some(pinned_features)
             },
             auth_ref: None,
+            strict_host_key_checking: None,
+            auto_connect: None,
+            encoding: None,
+            locale: None,
+            ssh_options: None,
+            host_key_alias: None,
+            user_known_hosts_file: None,
+            secret_ref: None,
+            session_lifetime: None,
         });
     }
 
@@ -1656,6 +1830,7 @@ pub async fn connections_export_to_file(
         selected_connections.iter_mut().for_each(|connection| {
             connection.password = None;
             connection.private_key_path = None;
+            connection.secret_ref = None;
         });
     }
 
@@ -1812,12 +1987,19 @@ pub async fn terminal_create(
             .map_err(|e| e.to_string())?;
         Ok(term_id)
     } else {
-        let channel = open_ssh_channel_with_single_reconnect(&connection_id, &state).await?;
-        let remote_os = {
+        let (channel, channel_permit) =
+            open_ssh_channel_with_single_reconnect(&connection_id, &state).await?;
+        let (remote_os, default_shell, charset) = {
             let connections = state.connections.lock().await;
-            connections
-                .get(&connection_id)
-                .and_then(|c| c.detected_os.clone())
+            let connection = connections.get(&connection_id);
+            (
+                connection.and_then(|c| c.detected_os.clone()),
+                connection.and_then(|c| c.detected_shell.clone()),
+                connection
+                    .map(|c| crate::remote_charset::TerminalCharset::from_config(&c.config))
+                    .transpose()?
+                    .unwrap_or_default(),
+            )
         };
 
         state
@@ -1827,13 +2009,16 @@ pub async fn terminal_create(
                 connection_id,
                 generation,
                 channel,
+                channel_permit,
                 cols,
                 rows,
                 app,
                 output_channel,
                 shell,
                 remote_os,
+                default_shell,
                 cwd,
+                charset,
             )
             .await
             .map_err(|e| e.to_string())?;
@@ -1896,6 +2081,7 @@ async fn reconnect_stored_connection(
     }
 
     let mut new_handle = reconnect_connection(
+        &state.app_handle,
         &connect_config,
         &state.ssh_manager,
         &state.tunnel_manager,
@@ -1910,6 +2096,8 @@ async fn reconnect_stored_connection(
             // Preserve the *same* reconnect_lock Arc so any concurrent waiters on the old handle continue to serialize against this instance.
             new_handle.reconnect_lock = reconnect_lock.clone();
             connections.insert(connection_id.to_string(), new_handle);
+            drop(connections);
+            crate::events::publish(connection_id, ConnectionEvent::Reconnected);
             Ok(())
         }
         Some(_) => Err(format!(
@@ -1961,14 +2149,13 @@ async fn get_live_ssh_session(
 async fn open_ssh_channel_with_single_reconnect(
     connection_id: &str,
     state: &State<'_, AppState>,
-) -> Result<Channel<Msg>, String> {
+) -> Result<(Channel<Msg>, SessionChannelPermit), String> {
     let session = get_live_ssh_session(connection_id, state).await?;
-    let first_try = {
-        let guard = session.lock().await;
-        guard.channel_open_session().await
-    };
-    if let Ok(channel) = first_try {
-        return Ok(channel);
+    match open_session_channel(&state.app_handle, connection_id, &session).await {
+        Ok(opened) => return Ok(opened),
+        // The transport is fine; the server's MaxSessions is exhausted.
+        Err(SessionChannelError::Saturated(message)) => return Err(message),
+        Err(SessionChannelError::Ssh(_)) => {}
     }
 
     // First channel open failed; clear stale session and re-use centralized
@@ -1980,9 +2167,7 @@ async fn open_ssh_channel_with_single_reconnect(
         }
     }
     let new_session = get_live_ssh_session(connection_id, state).await?;
-    let guard = new_session.lock().await;
-    guard
-        .channel_open_session()
+    open_session_channel(&state.app_handle, connection_id, &new_session)
         .await
         .map_err(|e| format!("Channel open failed after reconnect: {}", e))
 }
@@ -2006,7 +2191,19 @@ pub async fn terminal_has_active_processes(
 
 // Helper to get SFTP session - reconnects automatically if session is dead.
 // Zero overhead for healthy connections; only re-establishes when needed.
-async fn get_sftp_or_reconnect(
+/// Fail fast with an `OFFLINE:` error instead of starting a transfer that can only time out.
+pub(crate) async fn ensure_connection_online(state: &AppState, connection_id: &str) -> Result<(), String> {
+    let config = {
+        let connections = state.connections.lock().await;
+        connections.get(connection_id).map(|conn| conn.config.clone())
+    };
+    match config {
+        Some(config) => crate::network::ensure_config_reachable(&config).await,
+        None => Ok(()),
+    }
+}
+
+pub(crate) async fn get_sftp_or_reconnect(
     state: &AppState,
     id: &str,
 ) -> Result<Arc<russh_sftp::client::SftpSession>, String> {
@@ -2057,6 +2254,15 @@ async fn get_sftp_or_reconnect(
     Ok(sftp)
 }
 
+/// Windows hosts accept `C:\dir` from the UI but SFTP wants `/C:/dir`.
+async fn remote_fs_path(state: &AppState, connection_id: &str, path: String) -> String {
+    if crate::remote_os::connection_is_windows(state, connection_id).await {
+        crate::remote_os::sftp_path(&path)
+    } else {
+        path
+    }
+}
+
 #[tauri::command]
 pub async fn fs_list(
     connection_id: String,
@@ -2069,6 +2275,7 @@ pub async fn fs_list(
             .list_local(&path)
             .map_err(|e| e.to_string())
     } else {
+        let path = remote_fs_path(&state, &connection_id, path).await;
         let sftp = get_sftp_or_reconnect(&state, &connection_id).await?;
 
         let timeout_duration = std::time::Duration::from_secs(10);
@@ -2207,10 +2414,39 @@ pub async fn fs_read_file(
             .await
             .map_err(|e| e.to_string())
     } else {
+        let path = remote_fs_path(&state, &connection_id, path).await;
         read_remote_connection_file(&state, &connection_id, &path, 10).await
     }
 }
 
+/// How much of an existing file `fs_write_file` reads to pick its line endings.
+const LINE_ENDING_SAMPLE_BYTES: u64 = 8 * 1024;
+
+/// The first [`LINE_ENDING_SAMPLE_BYTES`] of remote `path`, `None` when it
+/// doesn't exist yet.
+async fn read_remote_line_ending_sample(
+    sftp: &russh_sftp::client::SftpSession,
+    path: &str,
+) -> Result<Option<Vec<u8>>, String> {
+    use russh_sftp::client::error::Error as SftpError;
+    use russh_sftp::protocol::StatusCode;
+    use tokio::io::AsyncReadExt;
+
+    let file = match sftp.open(path).await {
+        Ok(file) => file,
+        Err(SftpError::Status(status)) if status.status_code == StatusCode::NoSuchFile => {
+            return Ok(None)
+        }
+        Err(e) => return Err(format!("Failed to read remote file '{}': {}", path, e)),
+    };
+    let mut sample = Vec::new();
+    file.take(LINE_ENDING_SAMPLE_BYTES)
+        .read_to_end(&mut sample)
+        .await
+        .map_err(|e| format!("Failed to read remote file '{}': {}", path, e))?;
+    Ok(Some(sample))
+}
+
 #[tauri::command]
 pub async fn fs_write_file(
     connection_id: String,
@@ -2225,8 +2461,37 @@ pub async fn fs_write_file(
             .await
             .map_err(|e| e.to_string())
     } else {
+        let path = remote_fs_path(&state, &connection_id, path).await;
         let sftp = get_sftp_or_reconnect(&state, &connection_id).await?;
         let timeout_duration = std::time::Duration::from_secs(10);
+        let content = if crate::remote_os::connection_is_windows(&state, &connection_id).await {
+            // Editors hand us LF; keep the file's own line endings, and use
+            // CRLF for new files as Windows tools expect.
+            let ending = match tokio::time::timeout(
+                timeout_duration,
+                read_remote_line_ending_sample(&sftp, &path),
+            )
+            .await
+            {
+                Ok(Ok(Some(sample))) => {
+                    crate::remote_os::detect_line_ending(&String::from_utf8_lossy(&sample))
+                }
+                Ok(Ok(None)) => Some(crate::remote_os::LineEnding::CrLf),
+                Ok(Err(e)) => return Err(e),
+                Err(_) => {
+                    return Err(format!(
+                        "DISCONNECTED: SFTP read timed out after {}s",
+                        timeout_duration.as_secs()
+                    ))
+                }
+            };
+            match ending {
+                Some(ending) => crate::remote_os::with_line_ending(&content, ending),
+                None => content,
+            }
+        } else {
+            content
+        };
 
         match tokio::time::timeout(
             timeout_duration,
@@ -2446,11 +2711,6 @@ async fn wsl_get_cwd_impl(_wsl_distro: Option<String>) -> Result<String, String>
     Err("WSL is only available on Windows".to_string())
 }
 
-#[cfg(target_os = "windows")]
-fn shell_single_quote(path: &str) -> String {
-    format!("'{}'", path.replace('\'', "'\"'\"'"))
-}
-
 #[cfg(target_os = "windows")]
 fn wsl_list_path_shell(path: &str) -> String {
     let trimmed = path.trim();
@@ -2461,9 +2721,9 @@ fn wsl_list_path_shell(path: &str) -> String {
         if rest.is_empty() {
             return "\"$HOME\"".to_string();
         }
-        return format!("\"$HOME\"/{}", shell_single_quote(rest));
+        return format!("\"$HOME\"/{}", shell_quote(rest));
     }
-    shell_single_quote(trimmed)
+    shell_quote(trimmed)
 }
 
 #[cfg(target_os = "windows")]
@@ -2991,7 +3251,9 @@ pub async fn fs_delete(
             let conn = connections.get(&connection_id);
             (
                 conn.and_then(|c| c.session.clone()),
-                conn.map(|c| c.detected_os.is_some()).unwrap_or(false),
+                // Server-side `rm`/`cp` only exist on Unix hosts.
+                conn.and_then(|c| c.detected_os.as_deref())
+                    .is_some_and(|os| !crate::remote_os::is_windows(Some(os))),
             )
         };
 
@@ -3002,8 +3264,8 @@ pub async fn fs_delete(
 
                 let timeout_duration = std::time::Duration::from_secs(10);
                 let optimize_fut = async {
-                    match session.lock().await.channel_open_session().await {
-                        Ok(mut channel) => {
+                    match open_session_channel(&state.app_handle, &connection_id, &session).await {
+                        Ok((mut channel, _permit)) => {
                             if channel.exec(true, cmd).await.is_ok() {
                                 let mut success = false;
                                 let mut output_log = String::new();
@@ -3131,7 +3393,9 @@ pub async fn fs_delete_batch(
             let conn = connections.get(&connection_id);
             (
                 conn.and_then(|c| c.session.clone()),
-                conn.map(|c| c.detected_os.is_some()).unwrap_or(false),
+                // Server-side `rm`/`cp` only exist on Unix hosts.
+                conn.and_then(|c| c.detected_os.as_deref())
+                    .is_some_and(|os| !crate::remote_os::is_windows(Some(os))),
             )
         };
 
@@ -3140,12 +3404,10 @@ pub async fn fs_delete_batch(
                 let timeout_duration = std::time::Duration::from_secs(15);
 
                 let ssh_optimize_fut = async {
-                    let mut channel = session
-                        .lock()
-                        .await
-                        .channel_open_session()
-                        .await
-                        .map_err(|e| format!("Failed to open channel: {}", e))?;
+                    let (mut channel, _permit) =
+                        open_session_channel(&state.app_handle, &connection_id, &session)
+                            .await
+                            .map_err(|e| format!("Failed to open channel: {}", e))?;
 
                     let paths_str = paths
                         .iter()
@@ -3264,27 +3526,29 @@ pub async fn fs_copy(
             .await
             .map_err(|e| e.to_string())
     } else {
-        // Optimization: Try server-side copy first (cp -r) to avoid download/upload
+        // Optimization: Try server-side copy first (cp -R) to avoid download/upload
         let (session_opt, should_optimize) = {
             let connections = state.connections.lock().await;
             let conn = connections.get(&connection_id);
             (
                 conn.and_then(|c| c.session.clone()),
-                conn.map(|c| c.detected_os.is_some()).unwrap_or(false),
+                // Server-side `rm`/`cp` only exist on Unix hosts.
+                conn.and_then(|c| c.detected_os.as_deref())
+                    .is_some_and(|os| !crate::remote_os::is_windows(Some(os))),
             )
         };
 
         if should_optimize {
             if let Some(session) = session_opt {
                 // Simple quoting for paths (Linux/Unix assumptions for now, robust enough for typical usage)
-                // We use standard "cp -r" which works on most Unix-likes.
+                // POSIX "cp -R" also works with BusyBox and BSD cp.
                 // If it fails (e.g. Windows), we fall back to SFTP.
-                let cmd = format!("cp -r {} {}", shell_quote(&from), shell_quote(&to));
+                let cmd = format!("cp -R {} {}", shell_quote(&from), shell_quote(&to));
                 println!("[FS] Attempting server-side copy: {}", cmd);
                 let timeout_duration = std::time::Duration::from_secs(10);
                 let optimize_fut = async {
-                    match session.lock().await.channel_open_session().await {
-                        Ok(mut channel) => {
+                    match open_session_channel(&state.app_handle, &connection_id, &session).await {
+                        Ok((mut channel, _permit)) => {
                             if channel.exec(true, cmd).await.is_ok() {
                                 // Wait for exit status
                                 let mut success = false;
@@ -3402,28 +3666,28 @@ pub async fn fs_copy_batch(
             let conn = connections.get(&connection_id);
             (
                 conn.and_then(|c| c.session.clone()),
-                conn.map(|c| c.detected_os.is_some()).unwrap_or(false),
+                // Server-side `rm`/`cp` only exist on Unix hosts.
+                conn.and_then(|c| c.detected_os.as_deref())
+                    .is_some_and(|os| !crate::remote_os::is_windows(Some(os))),
             )
         };
 
         if should_optimize && session_opt.is_some() {
             if let Some(session) = session_opt {
-                // Build a multi-command string: cp -r 'a' 'b' && cp -r 'c' 'd' ...
+                // Build a multi-command string: cp -R 'a' 'b' && cp -R 'c' 'd' ...
                 let cmd = operations
                     .iter()
-                    .map(|op| format!("cp -r {} {}", shell_quote(&op.from), shell_quote(&op.to)))
+                    .map(|op| format!("cp -R {} {}", shell_quote(&op.from), shell_quote(&op.to)))
                     .collect::<Vec<_>>()
                     .join(" && ");
 
                 println!("[FS] Attempting batch server-side copy: {}", cmd);
                 let timeout_duration = std::time::Duration::from_secs(10);
                 let optimize_fut = async {
-                    let mut channel = session
-                        .lock()
-                        .await
-                        .channel_open_session()
-                        .await
-                        .map_err(|e| format!("Failed to open channel: {}", e))?;
+                    let (mut channel, _permit) =
+                        open_session_channel(&state.app_handle, &connection_id, &session)
+                            .await
+                            .map_err(|e| format!("Failed to open channel: {}", e))?;
                     channel
                         .exec(true, cmd)
                         .await
@@ -3722,8 +3986,10 @@ pub async fn window_close(app: AppHandle) -> Result<(), String> {
 
 #[tauri::command]
 pub async fn ssh_exec(
+    app: AppHandle,
     connection_id: String,
     command: String,
+    confirmation: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<String, String> {
     if connection_id == "local" {
@@ -3747,51 +4013,147 @@ pub async fn ssh_exec(
             Err(format!("Command failed: {}", stderr))
         }
     } else {
-        // Execute SSH command
-        let connections = state.connections.lock().await;
-        if let Some(conn) = connections.get(&connection_id) {
-            if let Some(session) = &conn.session {
-                let mut channel = session
-                    .lock()
-                    .await
-                    .channel_open_session()
-                    .await
-                    .map_err(|e| e.to_string())?;
-                channel
-                    .exec(true, command)
-                    .await
-                    .map_err(|e| e.to_string())?;
+        crate::safety::enforce(
+            &app,
+            &connection_id,
+            crate::safety::SafetyAction::Exec,
+            &command,
+            confirmation.as_deref(),
+        )?;
+
+        let output = exec_on_connection(&state, &connection_id, &command).await?;
+        if output.exit_status == 0 {
+            String::from_utf8(output.stdout).map_err(|e| e.to_string())
+        } else {
+            let err_str = String::from_utf8_lossy(&output.stderr);
+            Err(format!(
+                "Remote command failed (Exit {}): {}",
+                output.exit_status, err_str
+            ))
+        }
+    }
+}
 
-                let mut stdout = Vec::new();
-                let mut stderr = Vec::new();
-                let mut exit_status = 0;
+pub(crate) struct ExecOutput {
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+    pub exit_status: u32,
+}
 
-                while let Some(msg) = channel.wait().await {
-                    match msg {
-                        russh::ChannelMsg::Data { ref data } => stdout.extend_from_slice(data),
-                        russh::ChannelMsg::ExtendedData { ref data, .. } => {
-                            stderr.extend_from_slice(data)
-                        }
-                        russh::ChannelMsg::ExitStatus { exit_status: code } => {
-                            exit_status = code;
-                        }
-                        _ => {}
-                    }
-                }
+/// Run `command` on a new exec channel of an established connection and collect
+/// its output. Callers are responsible for safety checks.
+pub(crate) async fn exec_on_connection(
+    state: &AppState,
+    connection_id: &str,
+    command: &str,
+) -> Result<ExecOutput, String> {
+    exec_on_connection_inner(state, connection_id, command, None, None).await
+}
 
-                if exit_status == 0 {
-                    return String::from_utf8(stdout).map_err(|e| e.to_string());
-                } else {
-                    let err_str = String::from_utf8_lossy(&stderr);
-                    return Err(format!(
-                        "Remote command failed (Exit {}): {}",
-                        exit_status, err_str
-                    ));
-                }
+/// [`exec_on_connection`] with `input` written to the command's stdin, followed by EOF.
+pub(crate) async fn exec_on_connection_with_stdin(
+    state: &AppState,
+    connection_id: &str,
+    command: &str,
+    input: &[u8],
+) -> Result<ExecOutput, String> {
+    exec_on_connection_inner(state, connection_id, command, Some(input), None).await
+}
+
+/// [`exec_on_connection`] that stops when `cancel` fires: a running command's
+/// channel is closed, which hangs it up on the server.
+pub(crate) async fn exec_on_connection_cancellable(
+    state: &AppState,
+    connection_id: &str,
+    command: &str,
+    cancel: &crate::operations::CancelToken,
+) -> Result<ExecOutput, String> {
+    exec_on_connection_inner(state, connection_id, command, None, Some(cancel)).await
+}
+
+async fn exec_on_connection_inner(
+    state: &AppState,
+    connection_id: &str,
+    command: &str,
+    input: Option<&[u8]>,
+    cancel: Option<&crate::operations::CancelToken>,
+) -> Result<ExecOutput, String> {
+    // Release the connections lock first: the channel open may queue behind
+    // the server's MaxSessions limit.
+    let session = {
+        let connections = state.connections.lock().await;
+        connections
+            .get(connection_id)
+            .and_then(|conn| conn.session.clone())
+    };
+    let Some(session) = session else {
+        return Err("Connection not found".to_string());
+    };
+    let _session_ref = SessionRef::acquire(connection_id, Consumer::Exec);
+    let open = async {
+        open_session_channel(&state.app_handle, connection_id, &session)
+            .await
+            .map_err(|e| e.to_string())
+    };
+    let (mut channel, _channel_permit) = match cancel {
+        Some(cancel) => cancel.run(open).await?,
+        None => open.await?,
+    };
+    let started = std::time::Instant::now();
+    let command_summary: String = command.chars().take(200).collect();
+    channel
+        .exec(true, command)
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Some(input) = input {
+        channel.data(input).await.map_err(|e| e.to_string())?;
+        channel.eof().await.map_err(|e| e.to_string())?;
+    }
+
+    let mut output = ExecOutput {
+        stdout: Vec::new(),
+        stderr: Vec::new(),
+        exit_status: 0,
+    };
+    loop {
+        let msg = match cancel {
+            Some(cancel) => {
+                let next = tokio::select! {
+                    msg = channel.wait() => Some(msg),
+                    _ = cancel.cancelled() => None,
+                };
+                let Some(msg) = next else {
+                    let _ = channel.close().await;
+                    return Err(crate::operations::CANCELLED.to_string());
+                };
+                msg
             }
+            None => channel.wait().await,
+        };
+        let Some(msg) = msg else {
+            break;
+        };
+        match msg {
+            russh::ChannelMsg::Data { ref data } => output.stdout.extend_from_slice(data),
+            russh::ChannelMsg::ExtendedData { ref data, .. } => {
+                output.stderr.extend_from_slice(data)
+            }
+            russh::ChannelMsg::ExitStatus { exit_status: code } => {
+                output.exit_status = code;
+            }
+            _ => {}
         }
-        Err("Connection not found".to_string())
     }
+
+    crate::events::publish(
+        connection_id,
+        ConnectionEvent::CommandFinished {
+            command: command_summary,
+            exit_code: output.exit_status,
+            duration_ms: started.elapsed().as_millis() as u64,
+        },
+    );
+    Ok(output)
 }
 
 #[tauri::command]
@@ -3799,7 +4161,7 @@ pub async fn ssh_import_config(
     app: AppHandle,
 ) -> Result<Vec<crate::ssh_config::ParsedSshConnection>, String> {
     let home = app.path().home_dir().map_err(|e| e.to_string())?;
-    let config_path = home.join(".ssh/config");
+    let config_path = crate::user_profiles::ssh_dir(&home).join("config");
 
     // println!("[SSH] Importing config from: {:?}", config_path);
 
@@ -4011,6 +4373,8 @@ pub async fn settings_set(app: AppHandle, settings: serde_json::Value) -> Result
     if current_data_path != next_data_path {
         clear_data_dir_cache();
     }
+    crate::data_format::configure(&app);
+    crate::tunnels::relay::configure(&app);
     Ok(())
 }
 
@@ -4146,6 +4510,34 @@ struct TransferProgress {
     total: u64,
 }
 
+fn publish_transfer_started(connection_id: &str, transfer_id: &str, total: u64) {
+    crate::events::publish(
+        connection_id,
+        ConnectionEvent::TransferProgress {
+            transfer_id: transfer_id.to_string(),
+            transferred: 0,
+            total,
+        },
+    );
+}
+
+/// Per-chunk progress stays on `transfer-progress`; the bus only records start and outcome.
+fn publish_transfer_finished<T>(connection_id: &str, transfer_id: &str, result: &Result<T, String>) {
+    let (outcome, error) = match result {
+        Ok(_) => ("completed", None),
+        Err(e) if e == "Cancelled" => ("cancelled", None),
+        Err(e) => ("failed", Some(e.clone())),
+    };
+    crate::events::publish(
+        connection_id,
+        ConnectionEvent::TransferFinished {
+            transfer_id: transfer_id.to_string(),
+            outcome: outcome.to_string(),
+            error,
+        },
+    );
+}
+
 #[derive(Clone, serde::Serialize)]
 struct TransferSuccess {
     id: String,
@@ -4280,6 +4672,46 @@ fn upload_recursive<'a>(
 }
 
 // Helper to calculate local size or directory size recursively
+/// Upload `local` (file or directory) to `remote` on one connection, emitting
+/// `transfer-progress` under `transfer_id`. Returns the number of bytes sent.
+pub(crate) async fn upload_path(
+    app: &AppHandle,
+    state: &AppState,
+    connection_id: &str,
+    local: &std::path::Path,
+    remote: &str,
+    transfer_id: &str,
+    cancel_token: &std::sync::atomic::AtomicBool,
+) -> Result<u64, String> {
+    let sftp = get_sftp_or_reconnect(state, connection_id).await?;
+    let mut total_size = get_local_size(local).max(1);
+    let mut transferred = 0;
+    let _ = app.emit(
+        "transfer-progress",
+        TransferProgress {
+            id: transfer_id.to_string(),
+            transferred: 0,
+            total: total_size,
+        },
+    );
+    publish_transfer_started(connection_id, transfer_id, total_size);
+    let result = upload_recursive(
+        &sftp,
+        local,
+        remote,
+        &state.file_system,
+        app,
+        transfer_id,
+        &mut total_size,
+        &mut transferred,
+        cancel_token,
+    )
+    .await;
+    let result = result.map(|_| transferred);
+    publish_transfer_finished(connection_id, transfer_id, &result);
+    result
+}
+
 fn get_local_size(path: &std::path::Path) -> u64 {
     if path.is_dir() {
         match std::fs::read_dir(path) {
@@ -4303,6 +4735,7 @@ pub async fn sftp_put(
     transfer_id: String,
     _state: State<'_, AppState>,
 ) -> Result<(), String> {
+    ensure_connection_online(&_state, &id).await?;
     // Spawn background task
     let app_handle = app.clone();
     let connection_id = id.clone();
@@ -4310,14 +4743,10 @@ pub async fn sftp_put(
     let remote = remote_path.clone();
     let tid = transfer_id.clone();
 
-    // Create cancellation token
-    let cancel_token = Arc::new(std::sync::atomic::AtomicBool::new(false));
-
-    // Register token
-    {
-        let mut transfers = _state.transfers.lock().await;
-        transfers.insert(tid.clone(), cancel_token.clone());
-    }
+    // Register the transfer so it can be cancelled
+    let operation = crate::operations::begin(OperationKind::Transfer, Some(tid.clone()), &local);
+    let cancel_token = operation.token().flag();
+    let sftp_ref = SessionRef::acquire(&connection_id, Consumer::Sftp);
 
     tauri::async_runtime::spawn(async move {
         // Retrieve state inside task
@@ -4352,6 +4781,7 @@ pub async fn sftp_put(
                         total: total_size,
                     },
                 );
+                publish_transfer_started(&connection_id, &tid, total_size);
 
                 upload_recursive(
                     &sftp,
@@ -4369,11 +4799,10 @@ pub async fn sftp_put(
             Ok(())
         }
         .await;
-        // Cleanup
-        {
-            let mut transfers = state.transfers.lock().await;
-            transfers.remove(&tid);
-        }
+        drop(operation);
+        drop(sftp_ref);
+
+        publish_transfer_finished(&connection_id, &tid, &result);
 
         match result {
             Ok(_) => {
@@ -4406,13 +4835,9 @@ pub async fn sftp_put(
 
 #[tauri::command]
 pub async fn sftp_cancel_transfer(
-    state: State<'_, AppState>,
     transfer_id: String,
 ) -> Result<(), String> {
-    let transfers = state.transfers.lock().await;
-    if let Some(token) = transfers.get(&transfer_id) {
-        token.store(true, std::sync::atomic::Ordering::Relaxed);
-    }
+    crate::operations::cancel(&transfer_id);
     Ok(())
 }
 
@@ -4427,6 +4852,8 @@ pub async fn sftp_copy_to_server(
     mode: Option<String>, // "standard" or "turbo" (Ignored, always standard now)
     _state: State<'_, AppState>, // kept for signature compatibility if needed, but we use app_handle.state()
 ) -> Result<(), String> {
+    ensure_connection_online(&_state, &source_connection_id).await?;
+    ensure_connection_online(&_state, &destination_connection_id).await?;
     let app_handle = app.clone();
     let src_id = source_connection_id.clone();
     let src_path = source_path.clone();
@@ -4438,12 +4865,10 @@ pub async fn sftp_copy_to_server(
     tauri::async_runtime::spawn(async move {
         let state = app_handle.state::<AppState>();
 
-        // Create cancellation token
-        let cancel_token = Arc::new(std::sync::atomic::AtomicBool::new(false));
-        {
-            let mut transfers = state.transfers.lock().await;
-            transfers.insert(tid.clone(), cancel_token.clone());
-        }
+        let operation =
+            crate::operations::begin(OperationKind::Transfer, Some(tid.clone()), &src_path);
+        let cancel_token = operation.token().flag();
+        let sftp_refs = [&src_id, &dst_id].map(|id| SessionRef::acquire(id, Consumer::Sftp));
 
         let result: Result<(u64, u64), String> = async {
             // Shared SFTP session for size calculation
@@ -4462,6 +4887,7 @@ pub async fn sftp_copy_to_server(
                     total: total_size,
                 },
             );
+            publish_transfer_started(&dst_id, &tid, total_size);
 
             // Check cancellation early
             if cancel_token.load(std::sync::atomic::Ordering::Relaxed) {
@@ -4489,11 +4915,10 @@ pub async fn sftp_copy_to_server(
         }
         .await;
 
-        // Cleanup cancellation token
-        {
-            let mut transfers = state.transfers.lock().await;
-            transfers.remove(&tid);
-        }
+        drop(operation);
+        drop(sftp_refs);
+
+        publish_transfer_finished(&dst_id, &tid, &result);
 
         match result {
             Ok((transferred, total)) => {
@@ -4878,6 +5303,7 @@ pub async fn sftp_get(
     transfer_id: String,
     _state: State<'_, AppState>,
 ) -> Result<(), String> {
+    ensure_connection_online(&_state, &id).await?;
     let app_handle = app.clone();
     let connection_id = id.clone();
     let remote = remote_path.clone();
@@ -4899,14 +5325,10 @@ pub async fn sftp_get(
             }
             let mut transferred = 0;
 
-            let tid_clone = tid.clone();
-            let cancel_token = Arc::new(std::sync::atomic::AtomicBool::new(false));
-
-            // Register token
-            {
-                let mut transfers = state.transfers.lock().await;
-                transfers.insert(tid_clone.clone(), cancel_token.clone());
-            }
+            let operation =
+                crate::operations::begin(OperationKind::Transfer, Some(tid.clone()), &remote);
+            let cancel_token = operation.token().flag();
+            let sftp_ref = SessionRef::acquire(&connection_id, Consumer::Sftp);
 
             // Emit start
             let _ = app_handle.emit(
@@ -4917,6 +5339,7 @@ pub async fn sftp_get(
                     total: total_size,
                 },
             );
+            publish_transfer_started(&connection_id, &tid, total_size);
 
             let res = download_recursive(
                 &sftp,
@@ -4930,16 +5353,15 @@ pub async fn sftp_get(
             )
             .await;
 
-            // Cleanup
-            {
-                let mut transfers = state.transfers.lock().await;
-                transfers.remove(&tid_clone);
-            }
+            drop(operation);
+            drop(sftp_ref);
 
             res
         }
         .await;
 
+        publish_transfer_finished(&connection_id, &tid, &result);
+
         match result {
             Ok(_) => {
                 let _ = app_handle.emit(
@@ -5111,7 +5533,8 @@ async fn query_remote_windows_shells(
 ) -> Result<Vec<DetectedShell>, String> {
     const WINDOWS_SHELL_QUERY_TIMEOUT: Duration = Duration::from_secs(10);
 
-    let mut channel = open_ssh_channel_with_single_reconnect(connection_id, state).await?;
+    let (mut channel, _channel_permit) =
+        open_ssh_channel_with_single_reconnect(connection_id, state).await?;
     let list_cmd = "cmd /c \"where powershell.exe >nul 2>nul && echo powershell & where pwsh.exe >nul 2>nul && echo pwsh & where cmd.exe >nul 2>nul && echo cmd\"";
     channel
         .exec(true, list_cmd)
@@ -5386,14 +5809,23 @@ pub async fn shell_get_connection_shells(
         .map(|os| os.eq_ignore_ascii_case("windows"))
         .unwrap_or(false)
     {
-        let shells = query_remote_windows_shells(&connection_id, &state).await?;
+        let mut shells = query_remote_windows_shells(&connection_id, &state).await?;
+        // Pin the OpenSSH default shell, which is what "default" launches.
+        if let Some(index) = detected_default
+            .as_deref()
+            .and_then(|default| shells.iter().position(|shell| shell.id == default))
+        {
+            let default = shells.remove(index);
+            shells.insert(0, default);
+        }
         return Ok(shells);
     }
 
     let mut output = String::new();
     let mut stderr = String::new();
     let query_result: Result<(), String> = match tokio::time::timeout(Duration::from_secs(10), async {
-        let mut channel = open_ssh_channel_with_single_reconnect(&connection_id, &state).await?;
+        let (mut channel, _channel_permit) =
+            open_ssh_channel_with_single_reconnect(&connection_id, &state).await?;
         // Compound query: cat /etc/shells (canonical login-shell registry) plus
         // a disk probe of well-known shell paths. The probe catches shells that
         // exist on disk but aren't registered in /etc/shells (rare but real).
@@ -5515,6 +5947,13 @@ pub async fn app_get_exe_dir() -> Result<String, String> {
 
 #[tauri::command]
 pub async fn app_exit(app: tauri::AppHandle) {
+    // Don't leave the OS proxy pointing at a tunnel that is about to close.
+    if let Err(error) = crate::tunnels::system_proxy::restore(&get_data_dir(&app)).await {
+        eprintln!("[TUNNEL][PROXY] {error}");
+    }
+    if let Err(error) = crate::tunnels::hostnames::clear().await {
+        eprintln!("[TUNNEL][HOSTS] {error}");
+    }
     app.exit(0);
 }
 #[tauri::command]
@@ -5794,13 +6233,36 @@ pub async fn ssh_parse_command(command: String) -> Result<crate::ssh_parser::Par
     Ok(crate::ssh_parser::parse_ssh_command(&command))
 }
 
-// â”€â”€â”€ Download as Tar (SSH exec + tar streaming) â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€
+/// Validate a quick-connect string as the user types.
+#[tauri::command]
+pub async fn quick_connect_parse(input: String) -> Result<crate::quick_connect::QuickTarget, String> {
+    crate::quick_connect::parse_target(&input)
+}
 
-/// Shell-quote a path so it can be safely embedded in a remote command string.
-fn shell_quote(s: &str) -> String {
-    format!("'{}'", s.replace('\'', "'\\''"))
+/// Connect from a quick-connect string without saving a profile.
+/// The connection id is ephemeral (`quick_*`) and is not written to connections.json.
+#[tauri::command]
+pub async fn quick_connect(
+    app: AppHandle,
+    input: String,
+    password: Option<String>,
+    state: State<'_, AppState>,
+    vault: State<'_, tokio::sync::Mutex<crate::vault::store::VaultService>>,
+) -> Result<ConnectionResponse, String> {
+    let target = crate::quick_connect::parse_target(&input)?;
+    let home = app.path().home_dir().ok();
+    let ssh_dir = home.as_deref().map(crate::user_profiles::ssh_dir);
+    let ssh_hosts = ssh_dir
+        .as_ref()
+        .and_then(|dir| crate::ssh_config::parse_config(&dir.join("config")).ok())
+        .unwrap_or_default();
+    let config =
+        crate::quick_connect::build_config(&target, &ssh_hosts, password, ssh_dir.as_deref())?;
+    ssh_connect(app, config, state, vault).await
 }
 
+// â”€â”€â”€ Download as Tar (SSH exec + tar streaming) â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€
+
 /// Download selected remote files/directories as a .tar.gz archive.
 ///
 /// Uses SSH exec to run `tar -czf - -C <parent> <name> ...` on the server and
@@ -5818,16 +6280,16 @@ pub async fn sftp_download_as_zip(
     if remote_paths.is_empty() {
         return Err("No files selected for download".to_string());
     }
+    ensure_connection_online(&state, &id).await?;
 
     let app_handle = app.clone();
     let connection_id = id.clone();
     let tid = transfer_id.clone();
 
-    let cancel_token = Arc::new(std::sync::atomic::AtomicBool::new(false));
-    {
-        let mut transfers = state.transfers.lock().await;
-        transfers.insert(tid.clone(), cancel_token.clone());
-    }
+    let operation =
+        crate::operations::begin(OperationKind::Transfer, Some(tid.clone()), &local_path);
+    let cancel_token = operation.token().flag();
+    let sftp_ref = SessionRef::acquire(&connection_id, Consumer::Sftp);
 
     // Estimate total size using SFTP (already connected) for progress reporting.
     let total_size = {
@@ -5892,12 +6354,10 @@ pub async fn sftp_download_as_zip(
             let tar_cmd = format!("tar -czf -{}", tar_args);
 
             // Open SSH exec channel.
-            let mut channel = session
-                .lock()
-                .await
-                .channel_open_session()
-                .await
-                .map_err(|e| format!("Failed to open SSH channel: {}", e))?;
+            let (mut channel, _permit) =
+                open_session_channel(&app_handle, &connection_id, &session)
+                    .await
+                    .map_err(|e| format!("Failed to open SSH channel: {}", e))?;
             channel
                 .exec(true, tar_cmd.as_str())
                 .await
@@ -5923,6 +6383,7 @@ pub async fn sftp_download_as_zip(
                     total: total_size,
                 },
             );
+            publish_transfer_started(&connection_id, &tid, total_size);
 
             let mut bytes_written: u64 = 0;
             let mut exit_status: u32 = 0;
@@ -5987,10 +6448,10 @@ pub async fn sftp_download_as_zip(
         }
         .await;
 
-        {
-            let mut transfers = state_ref.transfers.lock().await;
-            transfers.remove(&tid);
-        }
+        drop(operation);
+        drop(sftp_ref);
+
+        publish_transfer_finished(&connection_id, &tid, &result);
 
         match result {
             Ok(_) => {
@@ -0,0 +1,53 @@
+use super::{
+    app_summary, clear_reports, list_reports, log_tail, redact, reports_dir, write_bundle,
+    CrashReportSummary,
+};
+use serde::Serialize;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReportList {
+    pub dir: String,
+    pub reports: Vec<CrashReportSummary>,
+}
+
+/// Crash reports on disk, newest first.
+#[tauri::command]
+pub async fn crash_reports_list(app: AppHandle) -> Result<CrashReportList, String> {
+    let dir = reports_dir(&app);
+    Ok(CrashReportList {
+        dir: dir.to_string_lossy().to_string(),
+        reports: list_reports(&dir)
+            .iter()
+            .map(CrashReportSummary::from)
+            .collect(),
+    })
+}
+
+/// Zip the crash reports, the current app summary and the recent log tail
+/// into `destination` for attaching to an issue. Returns the number of
+/// crash reports included.
+#[tauri::command]
+pub async fn crash_reports_bundle(app: AppHandle, destination: String) -> Result<usize, String> {
+    let dir = reports_dir(&app);
+    let summary = serde_json::to_string_pretty(&app_summary()).map_err(|e| e.to_string())?;
+    let log: String = log_tail().iter().map(|line| redact(line) + "\n").collect();
+    let destination = PathBuf::from(destination);
+    tokio::task::spawn_blocking(move || {
+        write_bundle(
+            &dir,
+            &destination,
+            &[("app-summary.json", summary), ("recent-log.txt", log)],
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Delete all crash reports; returns how many were removed.
+#[tauri::command]
+pub async fn crash_reports_clear(app: AppHandle) -> Result<usize, String> {
+    Ok(clear_reports(&reports_dir(&app)))
+}
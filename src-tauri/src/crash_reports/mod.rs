@@ -0,0 +1,445 @@
+//! Local crash reports: a panic hook writes a JSON report (message, location,
+//! backtrace, app summary, recent log tail) to `<app data>/diagnostics`.
+//! Nothing is uploaded; `crash_reports_bundle` zips the reports so a user can
+//! attach them to an issue.
+//!
+//! The log tail is a ring buffer fed by the `log` facade (russh, tauri) and by
+//! connection events. Panics in tasks started with [`spawn_supervised`] are
+//! attributed to the task. Report text goes through the redaction rules.
+
+pub mod commands;
+
+use crate::redaction::Redactor;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex, OnceLock, TryLockError};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::broadcast::error::RecvError;
+
+pub const REPORTS_DIR: &str = "diagnostics";
+pub const CRASH_REPORT_EVENT: &str = "crash:report";
+const REPORT_PREFIX: &str = "crash-";
+const MAX_REPORTS: usize = 50;
+const LOG_TAIL_LINES: usize = 300;
+const MAX_LINE_CHARS: usize = 500;
+
+static LOG_TAIL: LazyLock<Mutex<LogTail>> =
+    LazyLock::new(|| Mutex::new(LogTail::new(LOG_TAIL_LINES)));
+static REPORTS_PATH: OnceLock<PathBuf> = OnceLock::new();
+static APP: OnceLock<AppHandle> = OnceLock::new();
+static REDACTOR: OnceLock<Redactor> = OnceLock::new();
+static STARTED: OnceLock<Instant> = OnceLock::new();
+/// Set while a report is being written, so a panic inside the hook can't recurse.
+static REPORTING: AtomicBool = AtomicBool::new(false);
+static LOGGER: TailLogger = TailLogger;
+
+tokio::task_local! {
+    static TASK_NAME: &'static str;
+}
+
+/// The last `capacity` log lines, oldest first.
+pub struct LogTail {
+    lines: VecDeque<String>,
+    capacity: usize,
+}
+
+impl LogTail {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, line: &str) {
+        let mut kept: String = line.chars().take(MAX_LINE_CHARS).collect();
+        if kept.len() < line.len() {
+            kept.push('…');
+        }
+        if self.lines.len() == self.capacity {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(kept);
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        self.lines.iter().cloned().collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppSummary {
+    pub version: String,
+    pub os: String,
+    pub arch: String,
+    pub profile_id: String,
+    pub uptime_secs: u64,
+    /// Open SSH sessions, when the state could be read without blocking.
+    pub open_sessions: Option<usize>,
+    pub active_transfers: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+    pub id: String,
+    pub created_at: u64,
+    pub message: String,
+    /// `file:line:column` of the panic.
+    pub location: Option<String>,
+    pub thread: Option<String>,
+    /// Background task the panic happened in (see [`spawn_supervised`]).
+    pub task: Option<String>,
+    pub app: AppSummary,
+    pub backtrace: Vec<String>,
+    pub log_tail: Vec<String>,
+}
+
+/// What a settings list or a `crash:report` event shows.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReportSummary {
+    pub id: String,
+    pub created_at: u64,
+    pub message: String,
+    pub location: Option<String>,
+    pub task: Option<String>,
+}
+
+impl From<&CrashReport> for CrashReportSummary {
+    fn from(report: &CrashReport) -> Self {
+        Self {
+            id: report.id.clone(),
+            created_at: report.created_at,
+            message: report.message.clone(),
+            location: report.location.clone(),
+            task: report.task.clone(),
+        }
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn report_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{REPORT_PREFIX}{id}.json"))
+}
+
+fn is_report_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with(REPORT_PREFIX) && name.ends_with(".json"))
+}
+
+/// Reports in `dir`, newest first. Unreadable files are skipped.
+pub fn list_reports(dir: &Path) -> Vec<CrashReport> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut reports: Vec<CrashReport> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| is_report_file(path))
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .filter_map(|raw| serde_json::from_str(&raw).ok())
+        .collect();
+    reports.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(b.id.cmp(&a.id)));
+    reports
+}
+
+/// Write `report` and drop the oldest beyond `keep`.
+pub fn write_report(dir: &Path, report: &CrashReport, keep: usize) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let path = report_path(dir, &report.id);
+    let json = serde_json::to_string_pretty(report).map_err(|e| e.to_string())?;
+    crate::atomic_io::durable_replace(&path, json.as_bytes()).map_err(|e| e.to_string())?;
+    for stale in list_reports(dir).iter().skip(keep) {
+        let _ = std::fs::remove_file(report_path(dir, &stale.id));
+    }
+    Ok(path)
+}
+
+/// Delete every report; returns how many were removed.
+pub fn clear_reports(dir: &Path) -> usize {
+    list_reports(dir)
+        .iter()
+        .filter(|report| std::fs::remove_file(report_path(dir, &report.id)).is_ok())
+        .count()
+}
+
+/// Zip the reports in `dir` plus `extra` files into `destination`.
+/// Returns the number of crash reports included.
+pub fn write_bundle(
+    dir: &Path,
+    destination: &Path,
+    extra: &[(&str, String)],
+) -> Result<usize, String> {
+    let reports = list_reports(dir);
+    let file = std::fs::File::create(destination)
+        .map_err(|e| format!("Failed to create {}: {e}", destination.display()))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default();
+    for report in &reports {
+        let json = serde_json::to_string_pretty(report).map_err(|e| e.to_string())?;
+        zip.start_file(format!("{REPORT_PREFIX}{}.json", report.id), options)
+            .map_err(|e| e.to_string())?;
+        zip.write_all(json.as_bytes()).map_err(|e| e.to_string())?;
+    }
+    for (name, contents) in extra {
+        zip.start_file(*name, options).map_err(|e| e.to_string())?;
+        zip.write_all(contents.as_bytes())
+            .map_err(|e| e.to_string())?;
+    }
+    zip.finish().map_err(|e| e.to_string())?;
+    Ok(reports.len())
+}
+
+/// Append a line to the log tail kept for crash reports.
+pub fn note(line: &str) {
+    let line = format!("{} {line}", now_ms());
+    LOG_TAIL
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .push(&line);
+}
+
+/// The log tail, without blocking (empty when another thread holds it).
+fn log_tail() -> Vec<String> {
+    match LOG_TAIL.try_lock() {
+        Ok(tail) => tail.lines(),
+        Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner().lines(),
+        Err(TryLockError::WouldBlock) => Vec::new(),
+    }
+}
+
+struct TailLogger;
+
+impl log::Log for TailLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::Level::Info
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            note(&format!(
+                "{} {}: {}",
+                record.level(),
+                record.target(),
+                record.args()
+            ));
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+fn redact(text: &str) -> String {
+    match REDACTOR.get() {
+        Some(redactor) => redactor.redact(text),
+        None => text.to_string(),
+    }
+}
+
+/// App state for a report, read without waiting on any lock the panicking
+/// thread might hold.
+pub fn app_summary() -> AppSummary {
+    let state = APP
+        .get()
+        .and_then(|app| app.try_state::<crate::commands::AppState>());
+    AppSummary {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        profile_id: crate::user_profiles::active_id(),
+        uptime_secs: STARTED
+            .get()
+            .map_or(0, |started| started.elapsed().as_secs()),
+        open_sessions: state
+            .as_ref()
+            .and_then(|state| state.connections.try_lock().ok().map(|c| c.len())),
+        active_transfers: state
+            .as_ref()
+            .and_then(|state| state.transfers.try_lock().ok().map(|t| t.len())),
+    }
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Box<dyn Any>".to_string()
+    }
+}
+
+fn record_panic(message: String, location: Option<String>) {
+    let Some(dir) = REPORTS_PATH.get() else {
+        return;
+    };
+    let thread = std::thread::current().name().map(str::to_string);
+    let task = TASK_NAME.try_with(|name| name.to_string()).ok();
+    let backtrace = std::backtrace::Backtrace::force_capture().to_string();
+    let report = CrashReport {
+        id: format!(
+            "{}-{}",
+            now_ms(),
+            &uuid::Uuid::new_v4().simple().to_string()[..8]
+        ),
+        created_at: now_ms(),
+        message: redact(&message),
+        location,
+        thread,
+        task,
+        app: app_summary(),
+        backtrace: backtrace.lines().map(str::to_string).collect(),
+        log_tail: log_tail().iter().map(|line| redact(line)).collect(),
+    };
+    match write_report(dir, &report, MAX_REPORTS) {
+        Ok(path) => eprintln!("[Crash] Report written to {}", path.display()),
+        Err(error) => eprintln!("[Crash] Failed to write report: {error}"),
+    }
+}
+
+/// Install the panic hook and the log tail. Call first thing in `run`; the
+/// previous hook still runs, so panics are printed as before.
+pub fn install() {
+    let _ = STARTED.set(Instant::now());
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(log::LevelFilter::Info);
+    }
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if !REPORTING.swap(true, Ordering::SeqCst) {
+            let message = panic_message(info.payload());
+            let location = info
+                .location()
+                .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+            note(&format!("PANIC {message}"));
+            record_panic(message, location);
+            REPORTING.store(false, Ordering::SeqCst);
+        }
+        previous(info);
+    }));
+}
+
+pub fn reports_dir(app: &AppHandle) -> PathBuf {
+    app.path()
+        .app_data_dir()
+        .unwrap_or_else(|_| PathBuf::from("."))
+        .join(REPORTS_DIR)
+}
+
+/// Point reports at the app data dir and feed connection events into the log tail.
+pub fn start(app: AppHandle) {
+    let _ = REPORTS_PATH.set(reports_dir(&app));
+    let _ = REDACTOR.set(Redactor::from_app(&app));
+    let _ = APP.set(app);
+    note(&format!("INFO zync: started {}", env!("CARGO_PKG_VERSION")));
+
+    let mut events = crate::events::subscribe();
+    spawn_supervised("crash-log-events", async move {
+        loop {
+            match events.recv().await {
+                Ok(envelope) => note(&format!(
+                    "EVENT {}: {}",
+                    envelope.connection_id,
+                    crate::events::event_type(&envelope.event)
+                )),
+                Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+/// Spawn a long-lived background task. A panic in it is written up with the
+/// task's name and announced to the frontend as `crash:report`.
+pub fn spawn_supervised<F>(name: &'static str, task: F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let handle = tauri::async_runtime::spawn(TASK_NAME.scope(name, task));
+    tauri::async_runtime::spawn(async move {
+        if handle.await.is_err() {
+            eprintln!("[Crash] Background task {name} stopped after a panic");
+            let latest = REPORTS_PATH
+                .get()
+                .and_then(|dir| list_reports(dir).into_iter().next());
+            if let (Some(app), Some(report)) = (APP.get(), latest) {
+                let _ = app.emit(CRASH_REPORT_EVENT, CrashReportSummary::from(&report));
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(id: &str, created_at: u64) -> CrashReport {
+        CrashReport {
+            id: id.to_string(),
+            created_at,
+            message: "boom".to_string(),
+            location: Some("src/lib.rs:1:1".to_string()),
+            thread: Some("main".to_string()),
+            task: None,
+            app: AppSummary {
+                version: "0.0.0".to_string(),
+                os: "linux".to_string(),
+                arch: "x86_64".to_string(),
+                profile_id: "default".to_string(),
+                uptime_secs: 1,
+                open_sessions: None,
+                active_transfers: None,
+            },
+            backtrace: Vec::new(),
+            log_tail: vec!["INFO started".to_string()],
+        }
+    }
+
+    #[test]
+    fn log_tail_keeps_the_newest_lines() {
+        let mut tail = LogTail::new(3);
+        for i in 0..5 {
+            tail.push(&format!("line {i}"));
+        }
+        tail.push(&"x".repeat(MAX_LINE_CHARS * 2));
+        let lines = tail.lines();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "line 3");
+        assert!(lines[2].chars().count() <= MAX_LINE_CHARS + 1);
+    }
+
+    #[test]
+    fn reports_are_pruned_listed_and_bundled() {
+        let dir = std::env::temp_dir().join(format!("zync-crash-test-{}", uuid::Uuid::new_v4()));
+        for i in 0..4 {
+            write_report(&dir, &report(&format!("r{i}"), i), 3).unwrap();
+        }
+        std::fs::write(dir.join("notes.txt"), "not a report").unwrap();
+        let ids: Vec<String> = list_reports(&dir).into_iter().map(|r| r.id).collect();
+        assert_eq!(ids, ["r3", "r2", "r1"]);
+
+        let bundle = dir.join("bundle.zip");
+        let count = write_bundle(&dir, &bundle, &[("recent-log.txt", "tail".to_string())]).unwrap();
+        assert_eq!(count, 3);
+        let archive = zip::ZipArchive::new(std::fs::File::open(&bundle).unwrap()).unwrap();
+        assert_eq!(archive.len(), 4);
+
+        assert_eq!(clear_reports(&dir), 3);
+        assert!(list_reports(&dir).is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,150 @@
+//! Minimal OpenSSH certificate reader (`PROTOCOL.certkeys`): just enough to
+//! get the identity and validity window out of a `*-cert.pub` file.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CertificateInfo {
+    pub cert_type: String,
+    pub serial: u64,
+    pub key_id: String,
+    pub principals: Vec<String>,
+    /// Unix seconds.
+    pub valid_after: u64,
+    /// Unix seconds; `None` when the certificate never expires.
+    pub valid_before: Option<u64>,
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        if self.data.len() < len {
+            return Err("Certificate is truncated".to_string());
+        }
+        let (head, tail) = self.data.split_at(len);
+        self.data = tail;
+        Ok(head)
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn u64(&mut self) -> Result<u64, String> {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(self.take(8)?);
+        Ok(u64::from_be_bytes(bytes))
+    }
+
+    fn string(&mut self) -> Result<&'a [u8], String> {
+        let len = self.u32()? as usize;
+        self.take(len)
+    }
+
+    fn text(&mut self) -> Result<String, String> {
+        Ok(String::from_utf8_lossy(self.string()?).into_owned())
+    }
+}
+
+/// Length-prefixed public key fields between the nonce and the serial.
+fn public_key_fields(cert_type: &str) -> Option<usize> {
+    let base = cert_type.strip_suffix("-cert-v01@openssh.com")?;
+    Some(match base {
+        "ssh-rsa" => 2,
+        "ssh-dss" => 4,
+        "ssh-ed25519" => 1,
+        "sk-ssh-ed25519@openssh.com" => 2,
+        "sk-ecdsa-sha2-nistp256@openssh.com" => 3,
+        other if other.starts_with("ecdsa-sha2-") => 2,
+        _ => return None,
+    })
+}
+
+/// Parse one `<type> <base64> [comment]` certificate line.
+pub fn parse_certificate(line: &str) -> Result<CertificateInfo, String> {
+    let encoded = line
+        .split_whitespace()
+        .nth(1)
+        .ok_or("Not an OpenSSH certificate line")?;
+    let blob = STANDARD
+        .decode(encoded)
+        .map_err(|error| format!("Certificate is not valid base64: {error}"))?;
+    let mut reader = Reader { data: &blob };
+    let cert_type = reader.text()?;
+    let fields = public_key_fields(&cert_type)
+        .ok_or_else(|| format!("Unsupported certificate type {cert_type}"))?;
+    reader.string()?; // nonce
+    for _ in 0..fields {
+        reader.string()?;
+    }
+    let serial = reader.u64()?;
+    reader.u32()?; // user/host
+    let key_id = reader.text()?;
+    let mut packed = Reader {
+        data: reader.string()?,
+    };
+    let mut principals = Vec::new();
+    while !packed.data.is_empty() {
+        principals.push(packed.text()?);
+    }
+    let valid_after = reader.u64()?;
+    let valid_before = reader.u64()?;
+    Ok(CertificateInfo {
+        cert_type,
+        serial,
+        key_id,
+        principals,
+        valid_after,
+        valid_before: (valid_before != u64::MAX).then_some(valid_before),
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    fn string(out: &mut Vec<u8>, value: &[u8]) {
+        out.extend_from_slice(&(value.len() as u32).to_be_bytes());
+        out.extend_from_slice(value);
+    }
+
+    pub(crate) fn certificate_line(principals: &[&str], valid_after: u64, valid_before: u64) -> String {
+        let mut blob = Vec::new();
+        string(&mut blob, b"ssh-ed25519-cert-v01@openssh.com");
+        string(&mut blob, &[1; 32]);
+        string(&mut blob, &[2; 32]);
+        blob.extend_from_slice(&7u64.to_be_bytes());
+        blob.extend_from_slice(&1u32.to_be_bytes());
+        string(&mut blob, b"alice@ca");
+        let mut packed = Vec::new();
+        for principal in principals {
+            string(&mut packed, principal.as_bytes());
+        }
+        string(&mut blob, &packed);
+        blob.extend_from_slice(&valid_after.to_be_bytes());
+        blob.extend_from_slice(&valid_before.to_be_bytes());
+        // Critical options, extensions, reserved, CA key and signature follow;
+        // the parser stops before them.
+        format!("ssh-ed25519-cert-v01@openssh.com {} alice", STANDARD.encode(blob))
+    }
+
+    #[test]
+    fn reads_identity_and_validity() {
+        let info = parse_certificate(&certificate_line(&["alice", "deploy"], 100, 200)).expect("parse");
+        assert_eq!(info.serial, 7);
+        assert_eq!(info.key_id, "alice@ca");
+        assert_eq!(info.principals, vec!["alice", "deploy"]);
+        assert_eq!((info.valid_after, info.valid_before), (100, Some(200)));
+
+        let forever = parse_certificate(&certificate_line(&[], 0, u64::MAX)).expect("parse");
+        assert_eq!(forever.valid_before, None);
+        assert!(parse_certificate("ssh-ed25519 AAAAC3NzaC1lZDI1NTE5").is_err());
+    }
+}
@@ -0,0 +1,86 @@
+use super::{lock_tracked, read_credential_expiry_config, snapshot, status_of, CredentialStatus, ExpiryState};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+const REISSUE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Remaining validity of the certificates behind open sessions.
+#[tauri::command]
+pub async fn credential_expiry_status(app: AppHandle) -> Result<Vec<CredentialStatus>, String> {
+    Ok(snapshot(&read_credential_expiry_config(&app)))
+}
+
+/// Run the configured `reissueCommand` for a session's certificate and return
+/// the refreshed status. The open session keeps running; the new certificate
+/// is used by the next authentication (reconnects, new connections).
+#[tauri::command]
+pub async fn credential_expiry_reissue(
+    app: AppHandle,
+    connection_id: String,
+) -> Result<CredentialStatus, String> {
+    let config = read_credential_expiry_config(&app);
+    let command = config
+        .reissue_command
+        .clone()
+        .filter(|command| !command.trim().is_empty())
+        .ok_or("No reissueCommand is configured under credentialExpiry in settings")?;
+    let (certificate_path, key_path, username, host, principals) = {
+        let tracked = lock_tracked();
+        let entry = tracked
+            .get(&connection_id)
+            .ok_or("This session does not use a tracked certificate")?;
+        let principals = status_of(&connection_id, entry, &config)
+            .map(|status| status.certificate.principals.join(","))
+            .unwrap_or_default();
+        (
+            entry.certificate_path.clone(),
+            entry.key_path.clone(),
+            entry.username.clone(),
+            entry.host.clone(),
+            principals,
+        )
+    };
+
+    let mut process = if cfg!(target_os = "windows") {
+        let mut process = tokio::process::Command::new("cmd");
+        process.arg("/C");
+        process
+    } else {
+        let mut process = tokio::process::Command::new("sh");
+        process.arg("-c");
+        process
+    };
+    process
+        .arg(&command)
+        .env("ZYNC_KEY_PATH", &key_path)
+        .env("ZYNC_CERT_PATH", &certificate_path)
+        .env("ZYNC_USER", &username)
+        .env("ZYNC_HOST", &host)
+        .env("ZYNC_PRINCIPALS", &principals)
+        .kill_on_drop(true);
+    let output = tokio::time::timeout(REISSUE_TIMEOUT, process.output())
+        .await
+        .map_err(|_| "Reissue command timed out".to_string())?
+        .map_err(|error| format!("Failed to run reissue command: {error}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Reissue command failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let status = {
+        let mut tracked = lock_tracked();
+        let entry = tracked
+            .get_mut(&connection_id)
+            .ok_or("The session was closed while reissuing")?;
+        let status = status_of(&connection_id, entry, &config)?;
+        entry.announced = Some(status.state);
+        status
+    };
+    let _ = app.emit("credential:expiry", &status);
+    if status.state == ExpiryState::Expired {
+        return Err("Reissue command succeeded but the certificate is still expired".to_string());
+    }
+    Ok(status)
+}
@@ -0,0 +1,260 @@
+//! Validity tracking for short-lived SSH certificates.
+//!
+//! When a connection authenticates with `key_path`, the OpenSSH-style
+//! certificate next to it (`<key_path>-cert.pub`) is watched for the lifetime
+//! of the session. The file is re-read on every check, so a certificate
+//! re-issued outside Zync is picked up without reconnecting. Crossing into
+//! the warning window or past expiry emits `credential:expiry` once per state.
+//!
+//! Configured via `settings.json`:
+//! `"credentialExpiry": { "warnMinutes": 15, "reissueCommand": "step ssh renew ..." }`.
+
+pub mod cert;
+pub mod commands;
+
+use crate::commands::{read_effective_settings, AppState};
+use crate::events::ConnectionEvent;
+use crate::types::{AuthMethod, ConnectionConfig};
+use cert::{parse_certificate, CertificateInfo};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex as StdMutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::broadcast::error::RecvError;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CredentialExpiryConfig {
+    pub warn_minutes: u64,
+    /// Local shell command that renews the certificate in place. Runs with
+    /// `ZYNC_KEY_PATH`, `ZYNC_CERT_PATH`, `ZYNC_USER`, `ZYNC_HOST` and
+    /// `ZYNC_PRINCIPALS` (comma separated) in its environment.
+    pub reissue_command: Option<String>,
+}
+
+impl Default for CredentialExpiryConfig {
+    fn default() -> Self {
+        Self {
+            warn_minutes: 15,
+            reissue_command: None,
+        }
+    }
+}
+
+pub fn read_credential_expiry_config(app: &AppHandle) -> CredentialExpiryConfig {
+    read_effective_settings(app)
+        .ok()
+        .and_then(|settings| settings.get("credentialExpiry").cloned())
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ExpiryState {
+    NotYetValid,
+    Valid,
+    Expiring,
+    Expired,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialStatus {
+    pub connection_id: String,
+    pub certificate_path: String,
+    #[serde(flatten)]
+    pub certificate: CertificateInfo,
+    /// Seconds until `valid_before`; negative once expired, `None` if it never expires.
+    pub remaining_seconds: Option<i64>,
+    pub state: ExpiryState,
+    pub can_reissue: bool,
+}
+
+struct Tracked {
+    certificate_path: PathBuf,
+    key_path: String,
+    username: String,
+    host: String,
+    /// Last state announced, so each transition is emitted once.
+    announced: Option<ExpiryState>,
+}
+
+static TRACKED: LazyLock<StdMutex<HashMap<String, Tracked>>> =
+    LazyLock::new(|| StdMutex::new(HashMap::new()));
+
+fn lock_tracked() -> std::sync::MutexGuard<'static, HashMap<String, Tracked>> {
+    TRACKED.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// `<key>-cert.pub`, as `ssh` looks it up next to an identity file.
+pub(crate) fn certificate_path_for(key_path: &str) -> PathBuf {
+    let mut path = expand_home(key_path).into_os_string();
+    path.push("-cert.pub");
+    PathBuf::from(path)
+}
+
+pub fn expiry_state(info: &CertificateInfo, now: u64, warn_secs: u64) -> ExpiryState {
+    if now < info.valid_after {
+        return ExpiryState::NotYetValid;
+    }
+    match info.valid_before {
+        Some(before) if now >= before => ExpiryState::Expired,
+        Some(before) if before - now <= warn_secs => ExpiryState::Expiring,
+        _ => ExpiryState::Valid,
+    }
+}
+
+/// Start watching the certificate for `config`, if it has one.
+fn track(connection_id: &str, config: &ConnectionConfig) {
+    let AuthMethod::PrivateKey { key_path, .. } = &config.auth_method else {
+        return;
+    };
+    let certificate_path = certificate_path_for(key_path);
+    if !certificate_path.is_file() {
+        return;
+    }
+    lock_tracked().insert(
+        connection_id.to_string(),
+        Tracked {
+            certificate_path,
+            key_path: key_path.clone(),
+            username: config.username.clone(),
+            host: config.host.clone(),
+            announced: None,
+        },
+    );
+}
+
+fn status_of(
+    connection_id: &str,
+    tracked: &Tracked,
+    config: &CredentialExpiryConfig,
+) -> Result<CredentialStatus, String> {
+    let line = std::fs::read_to_string(&tracked.certificate_path)
+        .map_err(|error| format!("Failed to read {}: {error}", tracked.certificate_path.display()))?;
+    let certificate = parse_certificate(&line)?;
+    let now = now_secs();
+    Ok(CredentialStatus {
+        connection_id: connection_id.to_string(),
+        certificate_path: tracked.certificate_path.to_string_lossy().into_owned(),
+        remaining_seconds: certificate
+            .valid_before
+            .map(|before| before as i64 - now as i64),
+        state: expiry_state(&certificate, now, config.warn_minutes * 60),
+        can_reissue: config
+            .reissue_command
+            .as_deref()
+            .is_some_and(|command| !command.trim().is_empty()),
+        certificate,
+    })
+}
+
+/// Current validity of every tracked session credential.
+pub(crate) fn snapshot(config: &CredentialExpiryConfig) -> Vec<CredentialStatus> {
+    let tracked = lock_tracked();
+    let mut statuses: Vec<_> = tracked
+        .iter()
+        .filter_map(|(id, entry)| status_of(id, entry, config).ok())
+        .collect();
+    statuses.sort_by(|a, b| a.connection_id.cmp(&b.connection_id));
+    statuses
+}
+
+/// Statuses whose state changed since the last announcement.
+fn transitions(config: &CredentialExpiryConfig) -> Vec<CredentialStatus> {
+    let mut tracked = lock_tracked();
+    let mut changed = Vec::new();
+    for (id, entry) in tracked.iter_mut() {
+        let Ok(status) = status_of(id, entry, config) else {
+            continue;
+        };
+        if entry.announced != Some(status.state) {
+            // Staying quiet about healthy certificates on first sight.
+            let first_and_valid = entry.announced.is_none() && status.state == ExpiryState::Valid;
+            entry.announced = Some(status.state);
+            if !first_and_valid {
+                changed.push(status);
+            }
+        }
+    }
+    changed
+}
+
+async fn on_event(app: &AppHandle, connection_id: &str, event: &ConnectionEvent) {
+    match event {
+        ConnectionEvent::Connected { .. } | ConnectionEvent::Reconnected => {
+            let config = {
+                let state = app.state::<AppState>();
+                let connections = state.connections.lock().await;
+                connections.get(connection_id).map(|conn| conn.config.clone())
+            };
+            if let Some(config) = config {
+                track(connection_id, &config);
+            }
+        }
+        ConnectionEvent::Disconnected => {
+            lock_tracked().remove(connection_id);
+        }
+        _ => {}
+    }
+}
+
+pub fn start(app: AppHandle) {
+    let mut events = crate::events::subscribe();
+    let listener = app.clone();
+    crate::crash_reports::spawn_supervised("credential-expiry-events", async move {
+        loop {
+            match events.recv().await {
+                Ok(envelope) => on_event(&listener, &envelope.connection_id, &envelope.event).await,
+                Err(RecvError::Lagged(skipped)) => {
+                    eprintln!("[CERT] Event subscriber lagged; {skipped} events dropped");
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+
+    crate::crash_reports::spawn_supervised("credential-expiry", async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let config = read_credential_expiry_config(&app);
+            for status in transitions(&config) {
+                let _ = app.emit("credential:expiry", &status);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_validity_window() {
+        let info = parse_certificate(&cert::tests::certificate_line(&["u"], 100, 1_000)).expect("parse");
+        assert_eq!(expiry_state(&info, 50, 60), ExpiryState::NotYetValid);
+        assert_eq!(expiry_state(&info, 500, 60), ExpiryState::Valid);
+        assert_eq!(expiry_state(&info, 940, 60), ExpiryState::Expiring);
+        assert_eq!(expiry_state(&info, 1_000, 60), ExpiryState::Expired);
+    }
+}
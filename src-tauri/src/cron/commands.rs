@@ -0,0 +1,95 @@
+use super::{
+    backup_path, parse_crontab, parse_listing, validate, validate_system_path,
+    write_system_command, Crontab, CrontabKind, ParsedCrontab, LIST_SCRIPT, WRITE_USER_SCRIPT,
+};
+use crate::commands::{
+    ensure_connection_online, exec_on_connection, exec_on_connection_with_stdin, AppState,
+};
+use crate::host_capabilities::{elevation, Elevation};
+use serde::Serialize;
+use tauri::State;
+
+/// The login user's crontab followed by every system crontab.
+#[tauri::command]
+pub async fn cron_list(
+    state: State<'_, AppState>,
+    connection_id: String,
+) -> Result<Vec<Crontab>, String> {
+    ensure_connection_online(&state, &connection_id).await?;
+    let output = exec_on_connection(&state, &connection_id, LIST_SCRIPT).await?;
+    Ok(parse_listing(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse crontab text without touching the host, for live validation while editing.
+#[tauri::command]
+pub async fn cron_parse(content: String, kind: CrontabKind) -> Result<ParsedCrontab, String> {
+    Ok(parse_crontab(&content, kind))
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CronWriteResult {
+    /// Where the previous version was saved; `None` when there was none.
+    pub backup_path: Option<String>,
+}
+
+/// Validate and replace a crontab. `path` selects a system crontab (root
+/// needed, see `packages_install` for the sudo flow); without it the login
+/// user's crontab is written.
+#[tauri::command]
+pub async fn cron_write(
+    state: State<'_, AppState>,
+    connection_id: String,
+    path: Option<String>,
+    content: String,
+    sudo_password: Option<String>,
+) -> Result<CronWriteResult, String> {
+    let kind = if path.is_some() {
+        CrontabKind::System
+    } else {
+        CrontabKind::User
+    };
+    validate(&content, kind)?;
+    let mut content = content;
+    // cron ignores a last line without a newline.
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    ensure_connection_online(&state, &connection_id).await?;
+
+    let output = match path {
+        None => {
+            exec_on_connection_with_stdin(
+                &state,
+                &connection_id,
+                WRITE_USER_SCRIPT,
+                content.as_bytes(),
+            )
+            .await?
+        }
+        Some(path) => {
+            validate_system_path(&path)?;
+            let capabilities =
+                crate::host_capabilities::probe(&state, &connection_id, false).await?;
+            let elevation = elevation(&capabilities, sudo_password.is_some())?;
+            let command = format!("{}{}", elevation.prefix(), write_system_command(&path));
+            // `sudo -S` reads the password line; the rest of stdin is the file.
+            let input = match (elevation, sudo_password) {
+                (Elevation::SudoWithPassword, Some(password)) => format!("{password}\n{content}"),
+                _ => content,
+            };
+            exec_on_connection_with_stdin(&state, &connection_id, &command, input.as_bytes())
+                .await?
+        }
+    };
+    if output.exit_status != 0 {
+        return Err(format!(
+            "Writing the crontab failed (exit {}): {}",
+            output.exit_status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(CronWriteResult {
+        backup_path: backup_path(&String::from_utf8_lossy(&output.stdout)),
+    })
+}
@@ -0,0 +1,534 @@
+//! Crontabs on a remote host: the login user's crontab plus the system files
+//! (`/etc/crontab`, `/etc/cron.d/*`), parsed into schedules and commands.
+//!
+//! Writes are validated first and refused on any syntax error. The previous
+//! version is backed up (`~/.zync/cron-backups` for the user crontab,
+//! `/var/backups/zync/cron` for system files) before the new one replaces it,
+//! and system files are swapped in with a rename so cron never reads half a
+//! file.
+
+pub mod commands;
+
+use crate::ai::tool_exec_support::shell_quote;
+use serde::{Deserialize, Serialize};
+
+const SECTION_MARKER: &str = "__ZYNC_CRON__ ";
+pub const SYSTEM_CRONTAB: &str = "/etc/crontab";
+pub const CRON_D: &str = "/etc/cron.d/";
+
+/// Prints every crontab after a `__ZYNC_CRON__ <kind> <path>` header line.
+/// Unreadable system files get an `unreadable` header and no content.
+pub const LIST_SCRIPT: &str = r#"echo "__ZYNC_CRON__ user -"
+crontab -l 2>/dev/null
+for f in /etc/crontab /etc/cron.d/*; do
+  [ -f "$f" ] || continue
+  if [ -r "$f" ]; then echo "__ZYNC_CRON__ system $f"; cat "$f"; echo
+  else echo "__ZYNC_CRON__ unreadable $f"; fi
+done
+exit 0"#;
+
+/// Replaces the user crontab with stdin; prints `backup=<path>` first when
+/// there was one to back up.
+pub const WRITE_USER_SCRIPT: &str = r#"set -e
+d="$HOME/.zync/cron-backups"
+mkdir -p "$d"
+b="$d/crontab-$(date +%Y%m%d%H%M%S)"
+if crontab -l > "$b" 2>/dev/null; then echo "backup=$b"; else rm -f "$b"; fi
+crontab -"#;
+
+/// Replaces system crontab `$1` with stdin. Runs as root.
+const WRITE_SYSTEM_SCRIPT: &str = r#"set -e
+f=$1
+d=/var/backups/zync/cron
+mkdir -p "$d"
+if [ -f "$f" ]; then b="$d/$(basename "$f")-$(date +%Y%m%d%H%M%S)"; cp -p "$f" "$b"; echo "backup=$b"; fi
+t=$(mktemp /etc/.zync-cron.XXXXXX)
+cat > "$t"
+chmod 644 "$t"
+mv "$t" "$f""#;
+
+/// `sh -c` invocation of [`WRITE_SYSTEM_SCRIPT`] for `path`, to run behind
+/// a privilege prefix.
+pub fn write_system_command(path: &str) -> String {
+    format!(
+        "sh -c {} sh {}",
+        shell_quote(WRITE_SYSTEM_SCRIPT),
+        shell_quote(path)
+    )
+}
+
+/// Only `/etc/crontab` and `/etc/cron.d/<name>` are writable, with the file
+/// names cron itself accepts there.
+pub fn validate_system_path(path: &str) -> Result<(), String> {
+    if path == SYSTEM_CRONTAB {
+        return Ok(());
+    }
+    let valid = path.strip_prefix(CRON_D).is_some_and(|name| {
+        !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    });
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Not a system crontab: {path} (expected {SYSTEM_CRONTAB} or {CRON_D}<name>)"
+        ))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CrontabKind {
+    /// `crontab -l` of the login user.
+    User,
+    /// `/etc/crontab` or a file in `/etc/cron.d`; entries carry a user column.
+    System,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CronField {
+    /// As written, e.g. `*/15` or `mon-fri`.
+    pub expression: String,
+    /// Every value the field matches, ascending. Sunday is always 0.
+    pub values: Vec<u8>,
+    /// Written as `*`, which matters for how day-of-month and day-of-week combine.
+    pub any: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CronFields {
+    pub minute: CronField,
+    pub hour: CronField,
+    pub day_of_month: CronField,
+    pub month: CronField,
+    pub day_of_week: CronField,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum CronSchedule {
+    Fields(Box<CronFields>),
+    /// `@reboot`, `@daily`, ...
+    Special {
+        name: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CronEntry {
+    /// 1-based line in the crontab.
+    pub line: usize,
+    pub schedule: CronSchedule,
+    /// Run-as user; system crontabs only.
+    pub user: Option<String>,
+    pub command: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CronVariable {
+    pub line: usize,
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CronSyntaxError {
+    pub line: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParsedCrontab {
+    pub entries: Vec<CronEntry>,
+    pub variables: Vec<CronVariable>,
+    pub errors: Vec<CronSyntaxError>,
+}
+
+const SPECIALS: &[&str] = &[
+    "@reboot",
+    "@yearly",
+    "@annually",
+    "@monthly",
+    "@weekly",
+    "@daily",
+    "@midnight",
+    "@hourly",
+];
+const MONTHS: &[&str] = &[
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAYS: &[&str] = &["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+struct FieldSpec {
+    name: &'static str,
+    min: u8,
+    max: u8,
+    /// Names for `min`, `min + 1`, ...
+    names: &'static [&'static str],
+}
+
+const FIELDS: [FieldSpec; 5] = [
+    FieldSpec {
+        name: "minute",
+        min: 0,
+        max: 59,
+        names: &[],
+    },
+    FieldSpec {
+        name: "hour",
+        min: 0,
+        max: 23,
+        names: &[],
+    },
+    FieldSpec {
+        name: "day of month",
+        min: 1,
+        max: 31,
+        names: &[],
+    },
+    FieldSpec {
+        name: "month",
+        min: 1,
+        max: 12,
+        names: MONTHS,
+    },
+    // 7 is Sunday too.
+    FieldSpec {
+        name: "day of week",
+        min: 0,
+        max: 7,
+        names: WEEKDAYS,
+    },
+];
+
+impl FieldSpec {
+    fn value(&self, token: &str) -> Result<u8, String> {
+        let lower = token.to_ascii_lowercase();
+        if let Some(index) = self.names.iter().position(|name| *name == lower) {
+            return Ok(self.min + index as u8);
+        }
+        match token.parse::<u8>() {
+            Ok(value) if (self.min..=self.max).contains(&value) => Ok(value),
+            _ => Err(format!(
+                "{} {token:?} is outside {}-{}",
+                self.name, self.min, self.max
+            )),
+        }
+    }
+
+    fn parse(&self, expression: &str) -> Result<CronField, String> {
+        let mut values = Vec::new();
+        for item in expression.split(',') {
+            let (range, step) = match item.split_once('/') {
+                Some((range, step)) => {
+                    let step = step
+                        .parse::<u8>()
+                        .ok()
+                        .filter(|step| *step > 0)
+                        .ok_or_else(|| format!("invalid step in {} {item:?}", self.name))?;
+                    (range, step)
+                }
+                None => (item, 1),
+            };
+            let (start, end) = if range == "*" {
+                (self.min, self.max)
+            } else if let Some((start, end)) = range.split_once('-') {
+                (self.value(start)?, self.value(end)?)
+            } else {
+                let start = self.value(range)?;
+                // `5/10` means "from 5, every 10" to the end of the range.
+                (start, if step > 1 { self.max } else { start })
+            };
+            if start > end {
+                return Err(format!("{} range {range:?} runs backwards", self.name));
+            }
+            values.extend((start..=end).step_by(step as usize));
+        }
+        if self.names == WEEKDAYS {
+            for value in &mut values {
+                if *value == 7 {
+                    *value = 0;
+                }
+            }
+        }
+        values.sort_unstable();
+        values.dedup();
+        Ok(CronField {
+            expression: expression.to_string(),
+            values,
+            any: expression == "*",
+        })
+    }
+}
+
+/// Split off the first whitespace-delimited word.
+fn next_word(text: &str) -> Option<(&str, &str)> {
+    let text = text.trim_start();
+    if text.is_empty() {
+        return None;
+    }
+    let end = text.find(char::is_whitespace).unwrap_or(text.len());
+    Some((&text[..end], &text[end..]))
+}
+
+fn parse_variable(line: &str) -> Option<(String, String)> {
+    let (name, value) = line.split_once('=')?;
+    let name = name.trim();
+    let is_identifier = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !is_identifier {
+        return None;
+    }
+    let value = value.trim();
+    let value = value
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .or_else(|| {
+            value
+                .strip_prefix('\'')
+                .and_then(|rest| rest.strip_suffix('\''))
+        })
+        .unwrap_or(value);
+    Some((name.to_string(), value.to_string()))
+}
+
+fn parse_entry(line: usize, text: &str, kind: CrontabKind) -> Result<CronEntry, String> {
+    let (first, mut rest) = next_word(text).ok_or("empty line")?;
+    let schedule = if first.starts_with('@') {
+        let name = first.to_ascii_lowercase();
+        if !SPECIALS.contains(&name.as_str()) {
+            return Err(format!("unknown schedule {first:?}"));
+        }
+        CronSchedule::Special { name }
+    } else {
+        let mut fields = Vec::with_capacity(FIELDS.len());
+        let mut word = first;
+        for (index, spec) in FIELDS.iter().enumerate() {
+            if index > 0 {
+                let (next, remaining) =
+                    next_word(rest).ok_or_else(|| format!("missing {} field", spec.name))?;
+                word = next;
+                rest = remaining;
+            }
+            fields.push(spec.parse(word)?);
+        }
+        let Ok([minute, hour, day_of_month, month, day_of_week]) =
+            <[CronField; 5]>::try_from(fields)
+        else {
+            return Err("expected five schedule fields".to_string());
+        };
+        CronSchedule::Fields(Box::new(CronFields {
+            minute,
+            hour,
+            day_of_month,
+            month,
+            day_of_week,
+        }))
+    };
+    let user = match kind {
+        CrontabKind::User => None,
+        CrontabKind::System => {
+            let (user, remaining) = next_word(rest).ok_or("missing user field")?;
+            rest = remaining;
+            Some(user.to_string())
+        }
+    };
+    let command = rest.trim();
+    if command.is_empty() {
+        return Err("missing command".to_string());
+    }
+    Ok(CronEntry {
+        line,
+        schedule,
+        user,
+        command: command.to_string(),
+    })
+}
+
+/// Jobs, variable assignments and per-line syntax errors of a crontab.
+pub fn parse_crontab(content: &str, kind: CrontabKind) -> ParsedCrontab {
+    let mut parsed = ParsedCrontab::default();
+    for (index, raw) in content.lines().enumerate() {
+        let line = index + 1;
+        let text = raw.trim();
+        if text.is_empty() || text.starts_with('#') {
+            continue;
+        }
+        let starts_like_job =
+            text.starts_with(|c: char| c.is_ascii_digit() || c == '*' || c == '@');
+        if !starts_like_job {
+            if let Some((name, value)) = parse_variable(text) {
+                parsed.variables.push(CronVariable { line, name, value });
+                continue;
+            }
+        }
+        match parse_entry(line, text, kind) {
+            Ok(entry) => parsed.entries.push(entry),
+            Err(message) => parsed.errors.push(CronSyntaxError { line, message }),
+        }
+    }
+    parsed
+}
+
+/// `Err` lists every syntax error; cron would skip or reject those lines.
+pub fn validate(content: &str, kind: CrontabKind) -> Result<(), String> {
+    let errors = parse_crontab(content, kind).errors;
+    if errors.is_empty() {
+        return Ok(());
+    }
+    let details: Vec<String> = errors
+        .iter()
+        .map(|error| format!("line {}: {}", error.line, error.message))
+        .collect();
+    Err(format!("Invalid crontab: {}", details.join("; ")))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Crontab {
+    pub kind: CrontabKind,
+    /// File path for system crontabs.
+    pub path: Option<String>,
+    /// False for system files the login user can't read.
+    pub readable: bool,
+    pub content: String,
+    #[serde(flatten)]
+    pub parsed: ParsedCrontab,
+}
+
+/// Split [`LIST_SCRIPT`] output into crontabs. The user crontab is always
+/// first, empty when the user has none.
+pub fn parse_listing(output: &str) -> Vec<Crontab> {
+    let mut crontabs: Vec<Crontab> = Vec::new();
+    let mut lines: Vec<&str> = Vec::new();
+    let flush = |crontabs: &mut Vec<Crontab>, lines: &mut Vec<&str>| {
+        if let Some(last) = crontabs.last_mut() {
+            let mut content = lines.join("\n");
+            if !content.is_empty() {
+                content.push('\n');
+            }
+            // The listing adds a blank line after each system file.
+            if last.kind == CrontabKind::System && content.ends_with("\n\n") {
+                content.pop();
+            }
+            last.parsed = parse_crontab(&content, last.kind);
+            last.content = content;
+        }
+        lines.clear();
+    };
+    for line in output.lines() {
+        let Some(header) = line.strip_prefix(SECTION_MARKER) else {
+            lines.push(line);
+            continue;
+        };
+        flush(&mut crontabs, &mut lines);
+        let (kind, path) = header.split_once(' ').unwrap_or((header, "-"));
+        crontabs.push(Crontab {
+            kind: if kind == "user" {
+                CrontabKind::User
+            } else {
+                CrontabKind::System
+            },
+            path: (kind != "user").then(|| path.to_string()),
+            readable: kind != "unreadable",
+            content: String::new(),
+            parsed: ParsedCrontab::default(),
+        });
+    }
+    flush(&mut crontabs, &mut lines);
+    crontabs
+}
+
+/// `backup=<path>` from the write scripts.
+pub fn backup_path(output: &str) -> Option<String> {
+    output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("backup="))
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_schedules_and_variables() {
+        let parsed = parse_crontab(
+            "# nightly jobs\nMAILTO=\"ops@example.com\"\n*/15 9-17 * * mon-fri /usr/local/bin/sync --quiet\n@reboot ~/start.sh\n0 0 1 jan,jul 7 echo % date\n",
+            CrontabKind::User,
+        );
+        assert!(parsed.errors.is_empty(), "{:?}", parsed.errors);
+        assert_eq!(
+            parsed.variables,
+            vec![CronVariable {
+                line: 2,
+                name: "MAILTO".to_string(),
+                value: "ops@example.com".to_string(),
+            }]
+        );
+        let CronSchedule::Fields(fields) = &parsed.entries[0].schedule else {
+            panic!("expected fields");
+        };
+        assert_eq!(fields.minute.values, vec![0, 15, 30, 45]);
+        assert_eq!(fields.hour.values, (9..=17).collect::<Vec<u8>>());
+        assert_eq!(fields.day_of_week.values, vec![1, 2, 3, 4, 5]);
+        assert_eq!(parsed.entries[0].command, "/usr/local/bin/sync --quiet");
+        assert_eq!(
+            parsed.entries[1].schedule,
+            CronSchedule::Special {
+                name: "@reboot".to_string()
+            }
+        );
+        let CronSchedule::Fields(fields) = &parsed.entries[2].schedule else {
+            panic!("expected fields");
+        };
+        assert_eq!(fields.month.values, vec![1, 7]);
+        assert_eq!(fields.day_of_week.values, vec![0]);
+    }
+
+    #[test]
+    fn reports_syntax_errors_by_line() {
+        let content = "61 * * * * too-late\n* * * *\n@often run\n5-1 * * * * backwards\n";
+        let parsed = parse_crontab(content, CrontabKind::User);
+        let lines: Vec<usize> = parsed.errors.iter().map(|error| error.line).collect();
+        assert_eq!(lines, vec![1, 2, 3, 4]);
+        assert!(validate(content, CrontabKind::User)
+            .unwrap_err()
+            .starts_with("Invalid crontab: line 1: minute \"61\""));
+        assert!(validate("0 3 * * * backup.sh\n", CrontabKind::System).is_err());
+        assert!(validate("0 3 * * * root backup.sh\n", CrontabKind::System).is_ok());
+    }
+
+    #[test]
+    fn splits_listing_into_crontabs() {
+        let output = "__ZYNC_CRON__ user -\n0 * * * * date\n__ZYNC_CRON__ system /etc/crontab\nSHELL=/bin/sh\n17 * * * * root cd / && run-parts /etc/cron.hourly\n\n__ZYNC_CRON__ unreadable /etc/cron.d/secret\n";
+        let crontabs = parse_listing(output);
+        assert_eq!(crontabs.len(), 3);
+        assert_eq!(crontabs[0].kind, CrontabKind::User);
+        assert_eq!(crontabs[0].content, "0 * * * * date\n");
+        assert_eq!(crontabs[1].path.as_deref(), Some("/etc/crontab"));
+        assert_eq!(crontabs[1].parsed.entries[0].user.as_deref(), Some("root"));
+        assert!(crontabs[1].content.ends_with("hourly\n"));
+        assert!(!crontabs[2].readable);
+    }
+
+    #[test]
+    fn only_cron_paths_are_writable() {
+        assert!(validate_system_path("/etc/crontab").is_ok());
+        assert!(validate_system_path("/etc/cron.d/backup_db").is_ok());
+        assert!(validate_system_path("/etc/cron.d/../passwd").is_err());
+        assert!(validate_system_path("/etc/cron.d/app.conf").is_err());
+        assert!(validate_system_path("/etc/passwd").is_err());
+    }
+}
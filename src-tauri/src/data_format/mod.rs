@@ -0,0 +1,306 @@
+//! Versioned on-disk formats for the data files.
+//!
+//! Each file carries a top-level `formatVersion`; files without one are the
+//! legacy format 0. Loading upgrades older files in memory through the
+//! forward migrations below, and every save writes the current version.
+//! A file written by a newer build (a beta, say) is still read as far as
+//! this build understands it, but saving over it would silently drop what
+//! the newer build added, so writes go through a downgrade guard that
+//! either refuses or first keeps the newer file as `<name>.format-N.json`.
+
+use crate::data_validation::DataFile;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::AppHandle;
+
+/// The format this build reads and writes.
+pub const CURRENT_VERSION: u32 = 1;
+pub const VERSION_KEY: &str = "formatVersion";
+
+/// What a migration changed, for validation reports.
+pub type Notes = Vec<String>;
+
+/// `MIGRATIONS[n]` upgrades a document from format `n` to `n + 1`.
+const MIGRATIONS: [fn(DataFile, &mut Value, &mut Notes); CURRENT_VERSION as usize] = [v0_to_v1];
+
+/// Format 0 had no version key. Older builds wrote a bare top-level list,
+/// folders as plain names and snippet scopes as `connection_id`.
+fn v0_to_v1(file: DataFile, root: &mut Value, notes: &mut Notes) {
+    let primary = match file {
+        DataFile::Connections => "connections",
+        DataFile::Tunnels => "tunnels",
+        DataFile::Snippets => "snippets",
+        DataFile::Macros => "macros",
+    };
+    if root.is_array() {
+        let mut wrapped = Map::new();
+        wrapped.insert(primary.to_string(), root.take());
+        *root = Value::Object(wrapped);
+        notes.push(format!("Top-level list wrapped as `{primary}`"));
+    }
+    let Some(object) = root.as_object_mut() else {
+        return;
+    };
+    match file {
+        DataFile::Connections => {
+            let folders = object.get_mut("folders").and_then(Value::as_array_mut);
+            for folder in folders.into_iter().flatten() {
+                if let Value::String(name) = folder {
+                    notes.push(format!("Folder `{name}` stored as an object"));
+                    *folder = serde_json::json!({ "name": name });
+                }
+            }
+        }
+        DataFile::Snippets => {
+            let snippets = object.get_mut("snippets").and_then(Value::as_array_mut);
+            for snippet in snippets
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_object_mut)
+            {
+                let Some(scope) = snippet.remove("connection_id") else {
+                    continue;
+                };
+                if !snippet.contains_key("connectionId") {
+                    snippet.insert("connectionId".to_string(), scope);
+                }
+                notes.push("Snippet `connection_id` renamed to `connectionId`".to_string());
+            }
+        }
+        DataFile::Tunnels | DataFile::Macros => {}
+    }
+}
+
+/// The version a document was written in; 0 when it has none.
+pub fn format_version(root: &Value) -> u32 {
+    root.get(VERSION_KEY)
+        .and_then(Value::as_u64)
+        .map(|version| version.min(u64::from(u32::MAX)) as u32)
+        .unwrap_or(0)
+}
+
+/// A document written by a newer build than this one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewerFormat {
+    pub file_name: &'static str,
+    pub version: u32,
+}
+
+impl NewerFormat {
+    pub fn copy_path(&self, path: &Path) -> PathBuf {
+        let stem = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("data");
+        path.with_file_name(format!("{stem}.format-{}.json", self.version))
+    }
+}
+
+impl std::fmt::Display for NewerFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} was saved by a newer version of Zync (data format {}; this version uses format {CURRENT_VERSION})",
+            self.file_name, self.version
+        )
+    }
+}
+
+/// Bring a document up to the current format. Returns what changed; a
+/// document from a newer build is left untouched.
+pub fn upgrade(file: DataFile, root: &mut Value) -> Result<Notes, NewerFormat> {
+    let version = format_version(root);
+    if version > CURRENT_VERSION {
+        return Err(NewerFormat {
+            file_name: file.file_name(),
+            version,
+        });
+    }
+    let mut notes = Notes::new();
+    for migration in &MIGRATIONS[version as usize..] {
+        migration(file, root, &mut notes);
+    }
+    if let Some(object) = root.as_object_mut() {
+        object.insert(VERSION_KEY.to_string(), Value::from(CURRENT_VERSION));
+    }
+    Ok(notes)
+}
+
+/// What to do when saving over a file from a newer build.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NewerDataFiles {
+    /// Fail the save and leave the file alone.
+    #[default]
+    Refuse,
+    /// Keep the newer file as `<name>.format-N.json`, then save.
+    KeepCopy,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct FormatSettings {
+    newer_data_files: NewerDataFiles,
+}
+
+static KEEP_NEWER_COPY: AtomicBool = AtomicBool::new(false);
+
+/// Apply `storage.newerDataFiles` from settings.
+pub fn configure(app: &AppHandle) {
+    let settings: FormatSettings = crate::commands::read_effective_settings(app)
+        .ok()
+        .and_then(|settings| settings.get("storage").cloned())
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default();
+    KEEP_NEWER_COPY.store(
+        settings.newer_data_files == NewerDataFiles::KeepCopy,
+        Ordering::Relaxed,
+    );
+}
+
+fn policy() -> NewerDataFiles {
+    if KEEP_NEWER_COPY.load(Ordering::Relaxed) {
+        NewerDataFiles::KeepCopy
+    } else {
+        NewerDataFiles::Refuse
+    }
+}
+
+/// Check the file about to be replaced. Unreadable or unparsable files are
+/// not this guard's concern.
+fn guard_downgrade(file: DataFile, path: &Path, policy: NewerDataFiles) -> Result<(), String> {
+    let Ok(raw) = std::fs::read(path) else {
+        return Ok(());
+    };
+    let Ok(existing) = serde_json::from_slice::<Value>(&raw) else {
+        return Ok(());
+    };
+    let version = format_version(&existing);
+    if version <= CURRENT_VERSION {
+        return Ok(());
+    }
+    let newer = NewerFormat {
+        file_name: file.file_name(),
+        version,
+    };
+    let copy = newer.copy_path(path);
+    match policy {
+        NewerDataFiles::Refuse => Err(format!(
+            "{newer}. Saving would drop what the newer version added, so nothing was written. \
+             Update Zync, or set Settings → General → Storage → Newer data files to \"Keep a copy\" \
+             to save anyway and keep the newer file as {}.",
+            copy.display()
+        )),
+        NewerDataFiles::KeepCopy => {
+            crate::atomic_io::durable_replace(&copy, &raw)
+                .map_err(|e| format!("Failed to keep a copy of {}: {e}", path.display()))?;
+            eprintln!(
+                "[Data format] {newer}; kept it as {} before saving",
+                copy.display()
+            );
+            Ok(())
+        }
+    }
+}
+
+fn versioned_json<T: Serialize>(data: &T) -> Result<String, String> {
+    let mut value = serde_json::to_value(data).map_err(|e| e.to_string())?;
+    if let Some(object) = value.as_object_mut() {
+        object.insert(VERSION_KEY.to_string(), Value::from(CURRENT_VERSION));
+    }
+    serde_json::to_string_pretty(&value).map_err(|e| e.to_string())
+}
+
+/// Serialize `data` in the current format for writing to `path`, after
+/// the downgrade guard has approved replacing what is there.
+pub fn to_versioned_json<T: Serialize>(
+    file: DataFile,
+    path: &Path,
+    data: &T,
+) -> Result<String, String> {
+    guard_downgrade(file, path, policy())?;
+    versioned_json(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn upgrades_legacy_documents() {
+        let mut connections = json!([{ "id": "a" }]);
+        let notes = upgrade(DataFile::Connections, &mut connections).expect("upgrade");
+        assert_eq!(notes.len(), 1);
+        assert_eq!(
+            connections,
+            json!({ "connections": [{ "id": "a" }], "formatVersion": 1 })
+        );
+
+        let mut folders = json!({ "connections": [], "folders": ["Prod", { "name": "Dev" }] });
+        upgrade(DataFile::Connections, &mut folders).expect("upgrade");
+        assert_eq!(
+            folders["folders"],
+            json!([{ "name": "Prod" }, { "name": "Dev" }])
+        );
+
+        let mut snippets = json!({ "snippets": [
+            { "id": "s1", "connection_id": "c1" },
+            { "id": "s2", "connection_id": "old", "connectionId": "c2" },
+        ] });
+        upgrade(DataFile::Snippets, &mut snippets).expect("upgrade");
+        assert_eq!(
+            snippets["snippets"][0],
+            json!({ "id": "s1", "connectionId": "c1" })
+        );
+        assert_eq!(
+            snippets["snippets"][1],
+            json!({ "id": "s2", "connectionId": "c2" })
+        );
+
+        let mut current = json!({ "tunnels": [], "formatVersion": 1 });
+        assert!(upgrade(DataFile::Tunnels, &mut current)
+            .expect("upgrade")
+            .is_empty());
+    }
+
+    #[test]
+    fn leaves_newer_documents_alone() {
+        let mut newer = json!({ "tunnels": [], "formatVersion": 7, "extra": true });
+        let before = newer.clone();
+        let error = upgrade(DataFile::Tunnels, &mut newer).unwrap_err();
+        assert_eq!(error.version, 7);
+        assert_eq!(newer, before);
+        assert_eq!(
+            error.copy_path(Path::new("/data/tunnels.json")),
+            PathBuf::from("/data/tunnels.format-7.json")
+        );
+    }
+
+    #[test]
+    fn downgrade_guard_refuses_or_keeps_a_copy() {
+        let dir = std::env::temp_dir().join(format!("zync-data-format-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).expect("create dir");
+        let path = dir.join("connections.json");
+        let newer = r#"{"connections":[],"folders":[],"formatVersion":2}"#;
+        std::fs::write(&path, newer).expect("write");
+
+        assert!(guard_downgrade(DataFile::Connections, &path, NewerDataFiles::Refuse).is_err());
+        assert!(!dir.join("connections.format-2.json").exists());
+
+        guard_downgrade(DataFile::Connections, &path, NewerDataFiles::KeepCopy).expect("copy");
+        let copy = std::fs::read_to_string(dir.join("connections.format-2.json")).expect("read");
+        assert_eq!(copy, newer);
+
+        std::fs::write(&path, r#"{"connections":[],"folders":[]}"#).expect("write");
+        assert!(guard_downgrade(DataFile::Connections, &path, NewerDataFiles::Refuse).is_ok());
+        let json = versioned_json(&json!({ "tunnels": [] })).expect("serialize");
+        assert_eq!(
+            format_version(&serde_json::from_str(&json).expect("parse")),
+            1
+        );
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+}
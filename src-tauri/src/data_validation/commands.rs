@@ -0,0 +1,63 @@
+use super::{validate, write_repaired, DataFile, FileReport};
+use crate::commands::get_data_dir;
+use std::sync::{LazyLock, Mutex};
+use tauri::AppHandle;
+
+fn mutation_lock(file: DataFile) -> &'static LazyLock<Mutex<()>> {
+    match file {
+        DataFile::Connections => &crate::sync::domain_hosts::CONNECTIONS_MUTATION_LOCK,
+        DataFile::Tunnels => &crate::sync::domain_tunnels::TUNNELS_MUTATION_LOCK,
+        DataFile::Snippets => &crate::snippets::SNIPPETS_MUTATION_LOCK,
+        DataFile::Macros => &crate::macros::MACROS_MUTATION_LOCK,
+    }
+}
+
+fn check(app: &AppHandle, file: DataFile) -> Result<(FileReport, String), String> {
+    let path = get_data_dir(app).join(file.file_name());
+    if !path.exists() {
+        let report = FileReport {
+            file,
+            file_name: file.file_name().to_string(),
+            exists: false,
+            problems: Vec::new(),
+            can_repair: false,
+            repaired: None,
+        };
+        return Ok((report, String::new()));
+    }
+    let raw = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {e}", file.file_name()))?;
+    Ok((validate(file, &raw), raw))
+}
+
+/// Check every data file without changing anything.
+#[tauri::command]
+pub async fn data_files_validate(app: AppHandle) -> Result<Vec<FileReport>, String> {
+    DataFile::ALL
+        .into_iter()
+        .map(|file| {
+            let _guard = mutation_lock(file).lock().map_err(|e| e.to_string())?;
+            check(&app, file).map(|(report, _)| report)
+        })
+        .collect()
+}
+
+/// Apply the automatic repairs to one file, keeping the original as
+/// `<name>.pre-repair.json`. Returns the report for the repaired file.
+#[tauri::command]
+pub async fn data_files_repair(app: AppHandle, file: DataFile) -> Result<FileReport, String> {
+    let _guard = mutation_lock(file).lock().map_err(|e| e.to_string())?;
+    let (report, raw) = check(&app, file)?;
+    if report.has_errors() {
+        return Err(format!(
+            "{} can't be repaired automatically; fix the errors listed and try again",
+            report.file_name
+        ));
+    }
+    let Some(repaired) = report.repaired.clone() else {
+        return Ok(report);
+    };
+    let path = get_data_dir(&app).join(file.file_name());
+    write_repaired(&path, &raw, &repaired)?;
+    Ok(validate(file, &repaired))
+}
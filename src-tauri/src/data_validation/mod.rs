@@ -0,0 +1,679 @@
+//! Validation and repair of hand-edited data files (`connections.json`,
+//! `tunnels.json`, `snippets.json`, `macros.json`).
+//!
+//! Every item is checked by deserializing it into the type the app loads,
+//! so the rules never drift from the real structs. Problems are reported with
+//! a JSON pointer (`/connections/3/port`) and, where it can be found, a line.
+//! Common slips are repaired: trailing commas, numbers or booleans written as
+//! strings, `null` lists, a missing top-level list, and anything an older
+//! format needs (see `data_format`). Repairs edit the parsed document in
+//! place, so fields the app doesn't know are kept.
+
+pub mod commands;
+
+use crate::macros::TerminalMacro;
+use crate::snippets::Snippet;
+use crate::types::{Folder, SavedConnection, SavedTunnel};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+use std::path::Path;
+
+const MAX_REPAIRS_PER_ITEM: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DataFile {
+    Connections,
+    Tunnels,
+    Snippets,
+    Macros,
+}
+
+type ItemCheck = fn(&Value) -> Result<Value, String>;
+
+/// Deserialize into `T` and serialize back, so callers can see which keys
+/// the type knows about.
+fn roundtrip<T: DeserializeOwned + Serialize>(value: &Value) -> Result<Value, String> {
+    let item: T = serde_json::from_value(value.clone()).map_err(|e| e.to_string())?;
+    serde_json::to_value(item).map_err(|e| e.to_string())
+}
+
+impl DataFile {
+    pub const ALL: [DataFile; 4] = [
+        DataFile::Connections,
+        DataFile::Tunnels,
+        DataFile::Snippets,
+        DataFile::Macros,
+    ];
+
+    pub fn file_name(self) -> &'static str {
+        match self {
+            DataFile::Connections => "connections.json",
+            DataFile::Tunnels => "tunnels.json",
+            DataFile::Snippets => "snippets.json",
+            DataFile::Macros => crate::macros::MACROS_FILE,
+        }
+    }
+
+    /// Top-level lists and how their items are checked.
+    fn lists(self) -> &'static [(&'static str, ItemCheck)] {
+        match self {
+            DataFile::Connections => &[
+                ("connections", roundtrip::<SavedConnection>),
+                ("folders", roundtrip::<Folder>),
+            ],
+            DataFile::Tunnels => &[("tunnels", roundtrip::<SavedTunnel>)],
+            DataFile::Snippets => &[("snippets", roundtrip::<Snippet>)],
+            DataFile::Macros => &[("macros", roundtrip::<TerminalMacro>)],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProblemSeverity {
+    /// The file won't load until this is fixed by hand.
+    Error,
+    Warning,
+    /// Fixed by the repair.
+    Repaired,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Problem {
+    pub severity: ProblemSeverity,
+    /// JSON pointer to the offending value.
+    pub pointer: String,
+    /// 1-based line in the file, when known.
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileReport {
+    pub file: DataFile,
+    pub file_name: String,
+    pub exists: bool,
+    pub problems: Vec<Problem>,
+    /// Every error can be repaired automatically.
+    pub can_repair: bool,
+    #[serde(skip)]
+    pub repaired: Option<String>,
+}
+
+impl FileReport {
+    fn has_errors(&self) -> bool {
+        self.problems
+            .iter()
+            .any(|problem| problem.severity == ProblemSeverity::Error)
+    }
+
+    /// One line per error, for load failures.
+    pub fn error_summary(&self) -> String {
+        let errors: Vec<String> = self
+            .problems
+            .iter()
+            .filter(|problem| problem.severity == ProblemSeverity::Error)
+            .map(|problem| match problem.line {
+                Some(line) => format!("{} (line {line}): {}", problem.pointer, problem.message),
+                None => format!("{}: {}", problem.pointer, problem.message),
+            })
+            .collect();
+        format!("{} is invalid: {}", self.file_name, errors.join("; "))
+    }
+}
+
+fn line_at(raw: &str, offset: usize) -> usize {
+    raw[..offset.min(raw.len())].matches('\n').count() + 1
+}
+
+/// Line of the item with this id, found by its quoted id.
+fn item_line(raw: &str, item: &Value) -> Option<usize> {
+    let id = item.get("id")?.as_str()?;
+    let needle = serde_json::to_string(id).ok()?;
+    raw.find(&needle).map(|offset| line_at(raw, offset))
+}
+
+/// Drop commas directly before `]` or `}`, outside strings.
+fn strip_trailing_commas(raw: &str) -> String {
+    let chars: Vec<char> = raw.chars().collect();
+    let mut out = String::with_capacity(raw.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, &c) in chars.iter().enumerate() {
+        if in_string {
+            out.push(c);
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        if c == '"' {
+            in_string = true;
+        } else if c == ',' {
+            let next = chars[i + 1..].iter().find(|c| !c.is_whitespace());
+            if matches!(next, Some(']') | Some('}')) {
+                continue;
+            }
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// The key an item error is about: named by a `missing field` error, or the
+/// key whose removal changes the error (`from_value` errors carry no path).
+fn culprit(item: &Map<String, Value>, error: &str, check: ItemCheck) -> Option<String> {
+    if let Some(rest) = error.strip_prefix("missing field `") {
+        return rest.split('`').next().map(str::to_string);
+    }
+    item.keys()
+        .find(|key| {
+            let mut without = item.clone();
+            without.remove(*key);
+            check(&Value::Object(without)).err().as_deref() != Some(error)
+        })
+        .cloned()
+}
+
+/// Likely intended values for a mistyped one, best guess first. `None`
+/// means "remove the key".
+fn candidates(value: &Value) -> Vec<Option<Value>> {
+    let mut out = Vec::new();
+    match value {
+        Value::String(s) => {
+            let trimmed = s.trim();
+            if let Ok(n) = trimmed.parse::<u64>() {
+                out.push(Some(Value::from(n)));
+            } else if let Ok(n) = trimmed.parse::<i64>() {
+                out.push(Some(Value::from(n)));
+            } else if let Ok(n) = trimmed.parse::<f64>() {
+                if let Some(n) = serde_json::Number::from_f64(n) {
+                    out.push(Some(Value::Number(n)));
+                }
+            }
+            match trimmed.to_ascii_lowercase().as_str() {
+                "true" | "yes" => out.push(Some(Value::Bool(true))),
+                "false" | "no" => out.push(Some(Value::Bool(false))),
+                _ => {}
+            }
+            let parts: Vec<Value> = trimmed
+                .split(',')
+                .map(str::trim)
+                .filter(|part| !part.is_empty())
+                .map(|part| Value::String(part.to_string()))
+                .collect();
+            out.push(Some(Value::Array(parts)));
+            if trimmed.is_empty() {
+                out.push(None);
+            }
+        }
+        Value::Number(n) => out.push(Some(Value::String(n.to_string()))),
+        Value::Bool(b) => out.push(Some(Value::String(b.to_string()))),
+        Value::Null => {
+            out.push(Some(Value::Array(Vec::new())));
+            out.push(None);
+        }
+        _ => {}
+    }
+    out
+}
+
+fn describe(value: &Option<Value>) -> String {
+    match value {
+        Some(value) => format!("set to {value}"),
+        None => "removed (default used)".to_string(),
+    }
+}
+
+fn normalized(key: &str) -> String {
+    key.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                prev
+            } else {
+                prev.min(current).min(row[j]) + 1
+            };
+            prev = current;
+        }
+    }
+    row[b.len()]
+}
+
+/// Warn about keys the type ignored, suggesting a known key when close.
+fn unknown_keys(
+    original: &Map<String, Value>,
+    known: &Value,
+    pointer: &str,
+    line: Option<usize>,
+    problems: &mut Vec<Problem>,
+) {
+    let Some(known) = known.as_object() else {
+        return;
+    };
+    // Keys filled from an alias (`connection_id` for `connectionId`).
+    let aliased: HashSet<String> = known
+        .keys()
+        .filter(|key| !original.contains_key(*key))
+        .map(|key| normalized(key))
+        .collect();
+    for (key, value) in original {
+        if known.contains_key(key) || value.is_null() || aliased.contains(&normalized(key)) {
+            continue;
+        }
+        let suggestion = known
+            .keys()
+            .filter(|candidate| edit_distance(key, candidate) <= 2)
+            .min_by_key(|candidate| edit_distance(key, candidate))
+            .map(|candidate| format!("; did you mean `{candidate}`?"))
+            .unwrap_or_default();
+        problems.push(Problem {
+            severity: ProblemSeverity::Warning,
+            pointer: format!("{pointer}/{key}"),
+            line,
+            message: format!("Unknown field `{key}` is ignored{suggestion}"),
+        });
+    }
+}
+
+/// Check one item, repairing it in place where possible.
+fn check_item(
+    item: &mut Value,
+    check: ItemCheck,
+    pointer: &str,
+    line: Option<usize>,
+    problems: &mut Vec<Problem>,
+) -> bool {
+    let mut changed = false;
+    for _ in 0..MAX_REPAIRS_PER_ITEM {
+        let error = match check(item) {
+            Ok(known) => {
+                if let Some(original) = item.as_object() {
+                    unknown_keys(original, &known, pointer, line, problems);
+                }
+                return changed;
+            }
+            Err(error) => error,
+        };
+        let Some(object) = item.as_object_mut() else {
+            problems.push(Problem {
+                severity: ProblemSeverity::Error,
+                pointer: pointer.to_string(),
+                line,
+                message: format!("Expected an object: {error}"),
+            });
+            return changed;
+        };
+        let Some(key) = culprit(object, &error, check) else {
+            problems.push(Problem {
+                severity: ProblemSeverity::Error,
+                pointer: pointer.to_string(),
+                line,
+                message: error,
+            });
+            return changed;
+        };
+        let field_pointer = format!("{pointer}/{key}");
+        let fix = object.get(&key).and_then(|value| {
+            candidates(value).into_iter().find(|candidate| {
+                let mut trial = object.clone();
+                match candidate {
+                    Some(value) => trial.insert(key.clone(), value.clone()),
+                    None => trial.remove(&key),
+                };
+                match check(&Value::Object(trial.clone())) {
+                    Ok(_) => true,
+                    Err(next) => culprit(&trial, &next, check).as_deref() != Some(key.as_str()),
+                }
+            })
+        });
+        match fix {
+            Some(candidate) => {
+                problems.push(Problem {
+                    severity: ProblemSeverity::Repaired,
+                    pointer: field_pointer,
+                    line,
+                    message: format!("{error}; {}", describe(&candidate)),
+                });
+                match candidate {
+                    Some(value) => object.insert(key, value),
+                    None => object.remove(&key),
+                };
+                changed = true;
+            }
+            None => {
+                problems.push(Problem {
+                    severity: ProblemSeverity::Error,
+                    pointer: field_pointer,
+                    line,
+                    message: error,
+                });
+                return changed;
+            }
+        }
+    }
+    changed
+}
+
+/// Validate the contents of a data file and work out a repaired version.
+pub fn validate(file: DataFile, raw: &str) -> FileReport {
+    let mut report = FileReport {
+        file,
+        file_name: file.file_name().to_string(),
+        exists: true,
+        problems: Vec::new(),
+        can_repair: false,
+        repaired: None,
+    };
+    let mut changed = false;
+    let mut text = raw.to_string();
+    let mut root: Value = match serde_json::from_str(raw) {
+        Ok(root) => root,
+        Err(error) => {
+            let stripped = strip_trailing_commas(raw);
+            match serde_json::from_str(&stripped) {
+                Ok(root) => {
+                    report.problems.push(Problem {
+                        severity: ProblemSeverity::Repaired,
+                        pointer: String::new(),
+                        line: Some(error.line()),
+                        message: "Trailing comma removed".to_string(),
+                    });
+                    changed = true;
+                    text = stripped;
+                    root
+                }
+                Err(_) => {
+                    report.problems.push(Problem {
+                        severity: ProblemSeverity::Error,
+                        pointer: String::new(),
+                        line: Some(error.line()),
+                        message: format!("Not valid JSON: {error}"),
+                    });
+                    return report;
+                }
+            }
+        }
+    };
+
+    let newer = match crate::data_format::upgrade(file, &mut root) {
+        Ok(notes) => {
+            changed |= !notes.is_empty();
+            report
+                .problems
+                .extend(notes.into_iter().map(|message| Problem {
+                    severity: ProblemSeverity::Repaired,
+                    pointer: String::new(),
+                    line: None,
+                    message,
+                }));
+            false
+        }
+        Err(newer) => {
+            report.problems.push(Problem {
+                severity: ProblemSeverity::Warning,
+                pointer: String::new(),
+                line: None,
+                message: format!(
+                    "{newer}; checked as far as this version understands it and never repaired"
+                ),
+            });
+            true
+        }
+    };
+
+    let lists = file.lists();
+    let Some(object) = root.as_object_mut() else {
+        report.problems.push(Problem {
+            severity: ProblemSeverity::Error,
+            pointer: String::new(),
+            line: Some(1),
+            message: "Expected a JSON object".to_string(),
+        });
+        return report;
+    };
+
+    for (key, check) in lists {
+        let pointer = format!("/{key}");
+        let list = object.entry(key.to_string()).or_insert(Value::Null);
+        if list.is_null() {
+            *list = Value::Array(Vec::new());
+            report.problems.push(Problem {
+                severity: ProblemSeverity::Repaired,
+                pointer,
+                line: None,
+                message: format!("Missing `{key}` list; defaulted to []"),
+            });
+            changed = true;
+            continue;
+        }
+        let Some(items) = list.as_array_mut() else {
+            report.problems.push(Problem {
+                severity: ProblemSeverity::Error,
+                pointer,
+                line: None,
+                message: format!("`{key}` must be a list"),
+            });
+            continue;
+        };
+        let mut ids = HashSet::new();
+        for (index, item) in items.iter_mut().enumerate() {
+            let item_pointer = format!("{pointer}/{index}");
+            let line = item_line(&text, item);
+            changed |= check_item(item, *check, &item_pointer, line, &mut report.problems);
+            if let Some(id) = item.get("id").and_then(Value::as_str) {
+                if !ids.insert(id.to_string()) {
+                    report.problems.push(Problem {
+                        severity: ProblemSeverity::Warning,
+                        pointer: format!("{item_pointer}/id"),
+                        line,
+                        message: format!("Id `{id}` is used more than once"),
+                    });
+                }
+            }
+        }
+    }
+
+    report.can_repair = changed && !newer && !report.has_errors();
+    if report.can_repair {
+        report.repaired = serde_json::to_string_pretty(&root).ok();
+    }
+    report
+}
+
+/// Where the original is kept when a file is repaired.
+pub fn backup_path(path: &Path) -> std::path::PathBuf {
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("data");
+    path.with_file_name(format!("{stem}.pre-repair.json"))
+}
+
+/// Write a repaired file, keeping the original next to it.
+pub fn write_repaired(path: &Path, raw: &str, repaired: &str) -> Result<(), String> {
+    crate::atomic_io::durable_replace(&backup_path(path), raw.as_bytes())
+        .map_err(|e| format!("Failed to back up {}: {e}", path.display()))?;
+    crate::atomic_io::durable_replace(path, repaired.as_bytes())
+        .map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+/// Parse a data file that was just read, upgrading it from an older format
+/// (see `data_format`). When it doesn't load as written but every problem
+/// can be repaired, the repaired version is written (the original is kept
+/// as `<name>.pre-repair.json`) and loaded; otherwise the error names each
+/// problem's location. Files from a newer build are never repaired.
+pub fn parse_or_repair<T: DeserializeOwned>(
+    file: DataFile,
+    path: &Path,
+    raw: &str,
+) -> Result<T, String> {
+    if let Ok(mut root) = serde_json::from_str::<Value>(raw) {
+        match crate::data_format::upgrade(file, &mut root) {
+            Ok(_) => {
+                if let Ok(data) = serde_json::from_value::<T>(root) {
+                    return Ok(data);
+                }
+            }
+            Err(newer) => {
+                return serde_json::from_value::<T>(root)
+                    .map_err(|e| format!("{newer} and can't be read here ({e}). Update Zync to open it."));
+            }
+        }
+    }
+    let error = match serde_json::from_str::<T>(raw) {
+        Ok(data) => return Ok(data),
+        Err(error) => error,
+    };
+    if path.file_name().and_then(|name| name.to_str()) != Some(file.file_name()) {
+        return Err(error.to_string());
+    }
+    let report = validate(file, raw);
+    match report.repaired.as_deref() {
+        Some(repaired) if report.can_repair => {
+            let data = serde_json::from_str::<T>(repaired).map_err(|e| e.to_string())?;
+            write_repaired(path, raw, repaired)?;
+            eprintln!(
+                "[Data validation] Repaired {} ({} fix(es)); original kept as {}",
+                report.file_name,
+                report
+                    .problems
+                    .iter()
+                    .filter(|problem| problem.severity == ProblemSeverity::Repaired)
+                    .count(),
+                backup_path(path).display()
+            );
+            Ok(data)
+        }
+        _ if report.has_errors() => Err(report.error_summary()),
+        _ => Err(error.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn severities(report: &FileReport) -> Vec<(ProblemSeverity, &str)> {
+        report
+            .problems
+            .iter()
+            .map(|problem| (problem.severity, problem.pointer.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn repairs_common_slips_and_keeps_unknown_fields() {
+        let raw = r#"{
+  "connections": [
+    {
+      "id": "web",
+      "name": "Web",
+      "host": "web.example.com",
+      "port": "2222",
+      "username": "deploy",
+      "tags": "prod, eu",
+      "isFavorite": "true",
+      "hots": "typo",
+      "team": "platform",
+    },
+  ]
+}"#;
+        let report = validate(DataFile::Connections, raw);
+        assert!(report.can_repair, "{:?}", report.problems);
+        let found = severities(&report);
+        assert!(found.contains(&(ProblemSeverity::Repaired, "")));
+        assert!(found.contains(&(ProblemSeverity::Repaired, "/connections/0/port")));
+        assert!(found.contains(&(ProblemSeverity::Repaired, "/connections/0/tags")));
+        assert!(found.contains(&(ProblemSeverity::Repaired, "/connections/0/isFavorite")));
+        assert!(found.contains(&(ProblemSeverity::Repaired, "/folders")));
+        let typo = report
+            .problems
+            .iter()
+            .find(|problem| problem.pointer == "/connections/0/hots")
+            .unwrap();
+        assert_eq!(typo.severity, ProblemSeverity::Warning);
+        assert!(
+            typo.message.contains("did you mean `host`"),
+            "{}",
+            typo.message
+        );
+        assert_eq!(typo.line, Some(4));
+
+        let repaired: Value = serde_json::from_str(report.repaired.as_deref().unwrap()).unwrap();
+        let connection = &repaired["connections"][0];
+        assert_eq!(connection["port"], 2222);
+        assert_eq!(connection["tags"], serde_json::json!(["prod", "eu"]));
+        assert_eq!(connection["team"], "platform");
+        let data: crate::types::SavedData =
+            serde_json::from_value(repaired).expect("repaired file loads");
+        assert_eq!(data.connections[0].port, 2222);
+    }
+
+    #[test]
+    fn reports_what_cannot_be_repaired() {
+        let report = validate(
+            DataFile::Snippets,
+            r#"{"snippets": [{"id": "a", "name": "uptime", "command": "uptime"},
+{"id": "b", "name": "broken"}]}"#,
+        );
+        assert!(!report.can_repair);
+        assert_eq!(
+            severities(&report),
+            [(ProblemSeverity::Error, "/snippets/1/command")]
+        );
+        assert_eq!(report.problems[0].line, Some(2));
+        assert!(report
+            .error_summary()
+            .contains("/snippets/1/command (line 2)"));
+
+        let syntax = validate(DataFile::Tunnels, "{\n  \"tunnels\": [\n    {\"id\": }\n");
+        assert_eq!(syntax.problems[0].severity, ProblemSeverity::Error);
+        assert_eq!(syntax.problems[0].line, Some(3));
+    }
+
+    #[test]
+    fn parse_or_repair_rewrites_the_file_and_keeps_the_original() {
+        let dir =
+            std::env::temp_dir().join(format!("zync-validation-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("snippets.json");
+        let raw =
+            r#"{"snippets": [{"id": "a", "name": "n", "command": "ls", "createdAt": "17"},]}"#;
+        std::fs::write(&path, raw).unwrap();
+
+        let data: crate::snippets::SnippetsData =
+            parse_or_repair(DataFile::Snippets, &path, raw).unwrap();
+        assert_eq!(data.snippets[0].created_at, Some(17));
+        assert_eq!(std::fs::read_to_string(backup_path(&path)).unwrap(), raw);
+        let rewritten = std::fs::read_to_string(&path).unwrap();
+        assert!(serde_json::from_str::<crate::snippets::SnippetsData>(&rewritten).is_ok());
+
+        let other = dir.join("snippets.json.bak");
+        assert!(
+            parse_or_repair::<crate::snippets::SnippetsData>(DataFile::Snippets, &other, raw)
+                .is_err()
+        );
+        assert!(!backup_path(&other).exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
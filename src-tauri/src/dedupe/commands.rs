@@ -0,0 +1,186 @@
+use super::{find_duplicates, merge_connection, DuplicateGroup, EndpointKey};
+use crate::commands::{get_data_dir, AppState};
+use crate::snippets::{read_snippets_data, write_snippets_atomic, SNIPPETS_MUTATION_LOCK};
+use crate::sync::domain_hosts::{load_saved_data, save_saved_data_atomic, CONNECTIONS_MUTATION_LOCK};
+use crate::sync::domain_tunnels::{
+    load_saved_tunnels, write_saved_tunnels_atomic, TUNNELS_MUTATION_LOCK,
+};
+use serde::Serialize;
+use std::collections::HashSet;
+use tauri::{AppHandle, Manager, State};
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeReport {
+    pub primary_id: String,
+    pub removed_ids: Vec<String>,
+    pub tunnels_moved: usize,
+    pub snippets_moved: usize,
+    pub jump_references_updated: usize,
+    pub session_entries_updated: usize,
+    pub time_entries_moved: usize,
+    pub history_commands_moved: usize,
+}
+
+/// Find saved connections and ssh-config hosts that point at the same endpoint.
+#[tauri::command]
+pub async fn connections_find_duplicates(app: AppHandle) -> Result<Vec<DuplicateGroup>, String> {
+    let saved = {
+        let _guard = CONNECTIONS_MUTATION_LOCK
+            .lock()
+            .map_err(|error| error.to_string())?;
+        load_saved_data(&get_data_dir(&app).join("connections.json"))
+            .map_err(|error| error.to_string())?
+            .connections
+    };
+    // A missing or unreadable ssh config just means there is nothing to compare against.
+    let ssh_hosts = app
+        .path()
+        .home_dir()
+        .ok()
+        .and_then(|home| crate::ssh_config::parse_config(&home.join(".ssh/config")).ok())
+        .unwrap_or_default();
+    Ok(find_duplicates(&saved, &ssh_hosts))
+}
+
+/// Merge `duplicate_ids` into `primary_id`.
+///
+/// Tunnels, snippets, jump-host references, the saved session, tracked time and
+/// command history of the duplicates are repointed to the primary before the
+/// duplicates are removed.
+#[tauri::command]
+pub async fn connections_merge(
+    app: AppHandle,
+    primary_id: String,
+    duplicate_ids: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<MergeReport, String> {
+    let duplicates: HashSet<String> = duplicate_ids
+        .into_iter()
+        .filter(|id| *id != primary_id)
+        .collect();
+    if duplicates.is_empty() {
+        return Err("Select at least one duplicate to merge".to_string());
+    }
+    {
+        let connections = state.connections.lock().await;
+        if let Some(id) = duplicates.iter().find(|id| connections.contains_key(*id)) {
+            return Err(format!("Disconnect {id} before merging it"));
+        }
+    }
+
+    let data_dir = get_data_dir(&app);
+    let mut report = MergeReport {
+        primary_id: primary_id.clone(),
+        ..Default::default()
+    };
+
+    {
+        let _hosts_guard = CONNECTIONS_MUTATION_LOCK
+            .lock()
+            .map_err(|error| error.to_string())?;
+        let hosts_path = data_dir.join("connections.json");
+        let mut data = load_saved_data(&hosts_path).map_err(|error| error.to_string())?;
+
+        let primary_index = data
+            .connections
+            .iter()
+            .position(|connection| connection.id == primary_id)
+            .ok_or_else(|| format!("Connection {primary_id} not found"))?;
+        let endpoint = EndpointKey::from(&data.connections[primary_index]);
+        let merged: Vec<_> = data
+            .connections
+            .iter()
+            .filter(|connection| duplicates.contains(&connection.id))
+            .cloned()
+            .collect();
+        if merged.len() != duplicates.len() {
+            return Err("One or more duplicates no longer exist".to_string());
+        }
+        if let Some(other) = merged.iter().find(|c| EndpointKey::from(*c) != endpoint) {
+            return Err(format!(
+                "{} does not point at the same endpoint as the primary connection",
+                other.name
+            ));
+        }
+
+        for duplicate in &merged {
+            merge_connection(&mut data.connections[primary_index], duplicate);
+        }
+        data.connections
+            .retain(|connection| !duplicates.contains(&connection.id));
+        for connection in data.connections.iter_mut() {
+            if connection
+                .jump_server_id
+                .as_ref()
+                .is_some_and(|id| duplicates.contains(id))
+            {
+                // The primary cannot jump through itself.
+                connection.jump_server_id =
+                    (connection.id != primary_id).then(|| primary_id.clone());
+                report.jump_references_updated += 1;
+            }
+        }
+
+        // Repoint dependents before removing the duplicates so a failure never
+        // leaves tunnels or snippets pointing at a deleted connection.
+        {
+            let _guard = TUNNELS_MUTATION_LOCK
+                .lock()
+                .map_err(|error| error.to_string())?;
+            let tunnels_path = data_dir.join("tunnels.json");
+            let mut tunnels = load_saved_tunnels(&tunnels_path).map_err(|error| error.to_string())?;
+            for tunnel in tunnels.tunnels.iter_mut() {
+                if duplicates.contains(&tunnel.connection_id) {
+                    tunnel.connection_id = primary_id.clone();
+                    report.tunnels_moved += 1;
+                }
+            }
+            if report.tunnels_moved > 0 {
+                write_saved_tunnels_atomic(&tunnels_path, &tunnels)
+                    .map_err(|error| error.to_string())?;
+            }
+        }
+        {
+            let _guard = SNIPPETS_MUTATION_LOCK
+                .lock()
+                .map_err(|error| error.to_string())?;
+            let snippets_path = data_dir.join("snippets.json");
+            let mut snippets = read_snippets_data(&snippets_path)?;
+            for snippet in snippets.snippets.iter_mut() {
+                if snippet
+                    .connection_id
+                    .as_ref()
+                    .is_some_and(|id| duplicates.contains(id))
+                {
+                    snippet.connection_id = Some(primary_id.clone());
+                    report.snippets_moved += 1;
+                }
+            }
+            if report.snippets_moved > 0 {
+                write_snippets_atomic(&snippets_path, &snippets)?;
+            }
+        }
+
+        save_saved_data_atomic(&hosts_path, &data).map_err(|error| error.to_string())?;
+        crate::smart_groups::publish_membership(&app, &data);
+    }
+
+    // Per-connection history is best effort: the merge itself already succeeded.
+    for id in &duplicates {
+        match crate::session::reassign_connection(&app, id, &primary_id) {
+            Ok(count) => report.session_entries_updated += count,
+            Err(error) => eprintln!("[Dedupe] Failed to update session for {id}: {error}"),
+        }
+        match crate::time_tracking::reassign_connection(&app, id, &primary_id) {
+            Ok(count) => report.time_entries_moved += count,
+            Err(error) => eprintln!("[Dedupe] Failed to move tracked time for {id}: {error}"),
+        }
+        report.history_commands_moved += state.ghost_manager.merge_scope(id, &primary_id).await;
+    }
+
+    let mut removed_ids: Vec<String> = duplicates.into_iter().collect();
+    removed_ids.sort();
+    report.removed_ids = removed_ids;
+    Ok(report)
+}
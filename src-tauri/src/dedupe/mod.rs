@@ -0,0 +1,274 @@
+//! Duplicate-connection detection and merging.
+//!
+//! Two entries are duplicates when they resolve to the same endpoint: host
+//! (case-insensitive), port and username. Saved connections and hosts parsed
+//! from `~/.ssh/config` are analysed together; only saved connections can be
+//! merged, since ssh-config entries are read-only imports.
+
+pub mod commands;
+
+use crate::ssh_config::ParsedSshConnection;
+use crate::types::SavedConnection;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EndpointKey {
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+}
+
+impl EndpointKey {
+    pub fn new(host: &str, port: u16, username: &str) -> Self {
+        Self {
+            host: host.trim().trim_end_matches('.').to_ascii_lowercase(),
+            port: if port == 0 { 22 } else { port },
+            username: username.trim().to_string(),
+        }
+    }
+
+    fn label(&self) -> String {
+        if self.username.is_empty() {
+            format!("{}:{}", self.host, self.port)
+        } else {
+            format!("{}@{}:{}", self.username, self.host, self.port)
+        }
+    }
+}
+
+impl From<&SavedConnection> for EndpointKey {
+    fn from(connection: &SavedConnection) -> Self {
+        Self::new(&connection.host, connection.port, &connection.username)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DuplicateSource {
+    Saved,
+    SshConfig,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateMember {
+    pub source: DuplicateSource,
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateGroup {
+    /// `user@host:port` the members resolve to.
+    pub endpoint: String,
+    pub members: Vec<DuplicateMember>,
+    /// Saved connection to keep when merging; `None` if no member is saved.
+    pub suggested_primary_id: Option<String>,
+}
+
+/// Group entries by endpoint and return every group with more than one member.
+pub fn find_duplicates(
+    saved: &[SavedConnection],
+    ssh_hosts: &[ParsedSshConnection],
+) -> Vec<DuplicateGroup> {
+    let mut groups: BTreeMap<EndpointKey, (Vec<DuplicateMember>, Vec<&SavedConnection>)> =
+        BTreeMap::new();
+
+    for connection in saved {
+        let (members, saved_members) = groups.entry(EndpointKey::from(connection)).or_default();
+        members.push(DuplicateMember {
+            source: DuplicateSource::Saved,
+            id: connection.id.clone(),
+            name: connection.name.clone(),
+        });
+        saved_members.push(connection);
+    }
+    for host in ssh_hosts {
+        let key = EndpointKey::new(&host.host, host.port, &host.username);
+        groups.entry(key).or_default().0.push(DuplicateMember {
+            source: DuplicateSource::SshConfig,
+            id: host.id.clone(),
+            name: host.name.clone(),
+        });
+    }
+
+    groups
+        .into_iter()
+        .filter(|(_, (members, _))| members.len() > 1)
+        .map(|(key, (members, saved_members))| DuplicateGroup {
+            endpoint: key.label(),
+            members,
+            suggested_primary_id: suggest_primary(&saved_members).map(|c| c.id.clone()),
+        })
+        .collect()
+}
+
+/// Prefer the most recently used connection, then the oldest one.
+fn suggest_primary<'a>(candidates: &[&'a SavedConnection]) -> Option<&'a SavedConnection> {
+    candidates.iter().copied().max_by(|a, b| {
+        a.last_connected
+            .unwrap_or(0)
+            .cmp(&b.last_connected.unwrap_or(0))
+            .then_with(|| {
+                b.created_at
+                    .unwrap_or(u64::MAX)
+                    .cmp(&a.created_at.unwrap_or(u64::MAX))
+            })
+    })
+}
+
+/// Fold `duplicate` into `primary`: union tags, keep the favorite flag and the
+/// widest created/last-connected range, and fill any settings the primary lacks.
+pub fn merge_connection(primary: &mut SavedConnection, duplicate: &SavedConnection) {
+    if let Some(extra) = duplicate.tags.as_ref() {
+        let tags = primary.tags.get_or_insert_with(Vec::new);
+        for tag in extra {
+            if !tags.iter().any(|existing| existing.eq_ignore_ascii_case(tag)) {
+                tags.push(tag.clone());
+            }
+        }
+    }
+    if let Some(extra) = duplicate.pinned_features.as_ref() {
+        let pinned = primary.pinned_features.get_or_insert_with(Vec::new);
+        for feature in extra {
+            if !pinned.contains(feature) {
+                pinned.push(feature.clone());
+            }
+        }
+    }
+    if duplicate.is_favorite == Some(true) {
+        primary.is_favorite = Some(true);
+    }
+    if duplicate.auto_connect == Some(true) {
+        primary.auto_connect = Some(true);
+    }
+    primary.created_at = match (primary.created_at, duplicate.created_at) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    };
+    primary.last_connected = primary.last_connected.max(duplicate.last_connected);
+
+    if primary.password.is_none() {
+        primary.password = duplicate.password.clone();
+    }
+    if primary.private_key_path.is_none() {
+        primary.private_key_path = duplicate.private_key_path.clone();
+    }
+    if primary.auth_ref.is_none() {
+        primary.auth_ref = duplicate.auth_ref.clone();
+    }
+    if primary.jump_server_id.is_none() && duplicate.jump_server_id.as_deref() != Some(&primary.id) {
+        primary.jump_server_id = duplicate.jump_server_id.clone();
+    }
+    if primary.folder.is_none() {
+        primary.folder = duplicate.folder.clone();
+    }
+    if primary.icon.is_none() {
+        primary.icon = duplicate.icon.clone();
+    }
+    if primary.theme.is_none() {
+        primary.theme = duplicate.theme.clone();
+    }
+    if primary.strict_host_key_checking.is_none() {
+        primary.strict_host_key_checking = duplicate.strict_host_key_checking;
+    }
+    if primary.encoding.is_none() {
+        primary.encoding = duplicate.encoding.clone();
+    }
+    if primary.locale.is_none() {
+        primary.locale = duplicate.locale.clone();
+    }
+    if primary.ssh_options.is_none() {
+        primary.ssh_options = duplicate.ssh_options.clone();
+    }
+    if primary.host_key_alias.is_none() {
+        primary.host_key_alias = duplicate.host_key_alias.clone();
+    }
+    if primary.user_known_hosts_file.is_none() {
+        primary.user_known_hosts_file = duplicate.user_known_hosts_file.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn saved(id: &str, host: &str, port: u16, user: &str) -> SavedConnection {
+        SavedConnection {
+            id: id.to_string(),
+            name: id.to_string(),
+            host: host.to_string(),
+            port,
+            username: user.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn parsed(id: &str, host: &str, port: u16, user: &str) -> ParsedSshConnection {
+        ParsedSshConnection {
+            id: id.to_string(),
+            name: id.to_string(),
+            host: host.to_string(),
+            username: user.to_string(),
+            port,
+            private_key_path: None,
+            jump_server_alias: None,
+            jump_server_id: None,
+            aliases: vec![id.to_string()],
+            ssh_options: None,
+            host_key_alias: None,
+            user_known_hosts_file: None,
+        }
+    }
+
+    #[test]
+    fn groups_saved_and_ssh_config_entries_by_endpoint() {
+        let mut recent = saved("b", "DB.example.com", 22, "deploy");
+        recent.last_connected = Some(50);
+        let groups = find_duplicates(
+            &[
+                saved("a", "db.example.com", 22, "deploy"),
+                recent,
+                saved("c", "db.example.com", 22, "root"),
+            ],
+            &[parsed("db", "db.example.com.", 22, "deploy")],
+        );
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].endpoint, "deploy@db.example.com:22");
+        assert_eq!(groups[0].members.len(), 3);
+        assert_eq!(groups[0].suggested_primary_id.as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn ssh_config_only_groups_have_no_primary() {
+        let groups = find_duplicates(
+            &[],
+            &[parsed("x", "h", 2222, "u"), parsed("y", "H", 2222, "u")],
+        );
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].suggested_primary_id, None);
+    }
+
+    #[test]
+    fn merge_unions_tags_and_keeps_widest_history() {
+        let mut primary = saved("a", "h", 22, "u");
+        primary.tags = Some(vec!["prod".to_string()]);
+        primary.created_at = Some(20);
+        primary.last_connected = Some(30);
+        let mut duplicate = saved("b", "h", 22, "u");
+        duplicate.tags = Some(vec!["PROD".to_string(), "eu".to_string()]);
+        duplicate.created_at = Some(10);
+        duplicate.last_connected = Some(40);
+        duplicate.is_favorite = Some(true);
+        duplicate.folder = Some("Databases".to_string());
+
+        merge_connection(&mut primary, &duplicate);
+        assert_eq!(primary.tags, Some(vec!["prod".to_string(), "eu".to_string()]));
+        assert_eq!(primary.created_at, Some(10));
+        assert_eq!(primary.last_connected, Some(40));
+        assert_eq!(primary.is_favorite, Some(true));
+        assert_eq!(primary.folder.as_deref(), Some("Databases"));
+    }
+}
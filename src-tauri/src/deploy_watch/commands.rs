@@ -0,0 +1,57 @@
+use super::{list_watches, start_watch, stop_watch, DeployWatchConfig, DeployWatchInfo};
+use crate::commands::{ensure_connection_online, AppState};
+use crate::host_capabilities::{self, invokes_sudo};
+use tauri::{AppHandle, State};
+
+/// Start watching `config.local_dir`. The command is checked against the
+/// safety rules once, here, since later runs happen unattended.
+#[tauri::command]
+pub async fn deploy_watch_start(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    config: DeployWatchConfig,
+    confirmation: Option<String>,
+) -> Result<DeployWatchInfo, String> {
+    if config.remote_dir.trim().is_empty() {
+        return Err("Remote directory is empty".to_string());
+    }
+    ensure_connection_online(&state, &config.connection_id).await?;
+    if let Some(command) = config
+        .command
+        .as_deref()
+        .filter(|command| !command.trim().is_empty())
+    {
+        crate::safety::enforce(
+            &app,
+            &config.connection_id,
+            crate::safety::SafetyAction::Exec,
+            command,
+            confirmation.as_deref(),
+        )?;
+        if invokes_sudo(command) {
+            let capabilities =
+                host_capabilities::probe(&state, &config.connection_id, false).await?;
+            if let Some(reason) = host_capabilities::requires_sudo(&capabilities)
+                .and_then(|blocked| blocked.describe())
+            {
+                return Err(reason);
+            }
+        }
+    }
+    start_watch(app, config)
+}
+
+#[tauri::command]
+pub async fn deploy_watch_stop(id: String) -> Result<(), String> {
+    if stop_watch(&id) {
+        Ok(())
+    } else {
+        Err(format!("Deploy watcher {id} not found"))
+    }
+}
+
+/// Every watcher with its recent runs, including ones that stopped on an error.
+#[tauri::command]
+pub async fn deploy_watch_list() -> Result<Vec<DeployWatchInfo>, String> {
+    Ok(list_watches())
+}
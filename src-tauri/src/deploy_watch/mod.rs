@@ -0,0 +1,477 @@
+//! Hot-deploy loop: watch a local directory and, once edits settle, upload
+//! the changed files to a remote directory and optionally run a command
+//! there (e.g. `systemctl --user restart app`).
+//!
+//! The directory is polled (size and mtime per file) rather than watched
+//! through OS notifications, which keeps editors' save-via-rename and network
+//! drives working the same everywhere. Only changed and new files are
+//! uploaded; deletions are not mirrored. Each watcher keeps a short run
+//! history and announces runs on `deploy-watch:run`.
+
+pub mod commands;
+
+use crate::commands::{exec_on_connection, upload_path, AppState};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex as StdMutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+
+pub const DEFAULT_DEBOUNCE_MS: u64 = 800;
+const MIN_DEBOUNCE_MS: u64 = 100;
+const MAX_DEBOUNCE_MS: u64 = 60_000;
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Larger trees should be narrowed with `exclude` or a deeper `local_dir`.
+const MAX_WATCHED_FILES: usize = 20_000;
+const HISTORY_SIZE: usize = 20;
+/// Files listed per run; the count is always exact.
+const MAX_LISTED_FILES: usize = 50;
+/// Skipped unless the config lists its own excludes.
+pub const DEFAULT_EXCLUDES: &[&str] = &[".git", "node_modules", ".DS_Store", "*.swp", "*~"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeployWatchConfig {
+    pub connection_id: String,
+    pub local_dir: String,
+    pub remote_dir: String,
+    /// Runs after a successful upload, as given, in the login directory.
+    #[serde(default)]
+    pub command: Option<String>,
+    #[serde(default)]
+    pub debounce_ms: Option<u64>,
+    /// Path component names (`.git`) or `*.ext` suffix patterns.
+    #[serde(default)]
+    pub exclude: Option<Vec<String>>,
+}
+
+impl DeployWatchConfig {
+    fn debounce(&self) -> Duration {
+        Duration::from_millis(
+            self.debounce_ms
+                .unwrap_or(DEFAULT_DEBOUNCE_MS)
+                .clamp(MIN_DEBOUNCE_MS, MAX_DEBOUNCE_MS),
+        )
+    }
+
+    fn excludes(&self) -> Vec<String> {
+        self.exclude.clone().unwrap_or_else(|| {
+            DEFAULT_EXCLUDES
+                .iter()
+                .map(|pattern| pattern.to_string())
+                .collect()
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeployRun {
+    pub started_at_ms: u64,
+    pub duration_ms: u64,
+    pub success: bool,
+    /// Relative paths uploaded, at most `MAX_LISTED_FILES`.
+    pub files: Vec<String>,
+    pub file_count: usize,
+    pub bytes: u64,
+    /// First upload failure; the command doesn't run after one.
+    pub upload_error: Option<String>,
+    pub command_exit_code: Option<u32>,
+    pub command_stdout: String,
+    pub command_stderr: String,
+    /// The command couldn't be started at all.
+    pub command_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeployWatchInfo {
+    pub id: String,
+    pub config: DeployWatchConfig,
+    pub started_at_ms: u64,
+    /// A deploy is uploading or running its command right now.
+    pub deploying: bool,
+    /// Set when the watcher stopped on its own, e.g. the directory vanished.
+    pub error: Option<String>,
+    /// Newest last.
+    pub runs: VecDeque<DeployRun>,
+}
+
+struct WatchEntry {
+    info: DeployWatchInfo,
+    cancel: Arc<AtomicBool>,
+}
+
+static WATCHES: LazyLock<StdMutex<HashMap<String, WatchEntry>>> =
+    LazyLock::new(|| StdMutex::new(HashMap::new()));
+
+fn lock_watches() -> std::sync::MutexGuard<'static, HashMap<String, WatchEntry>> {
+    match WATCHES.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+fn update_watch(id: &str, update: impl FnOnce(&mut DeployWatchInfo)) {
+    if let Some(entry) = lock_watches().get_mut(id) {
+        update(&mut entry.info);
+    }
+}
+
+pub fn list_watches() -> Vec<DeployWatchInfo> {
+    let mut watches: Vec<_> = lock_watches()
+        .values()
+        .map(|entry| entry.info.clone())
+        .collect();
+    watches.sort_by_key(|info| info.started_at_ms);
+    watches
+}
+
+/// Cancels the loop and any upload in flight. Returns false for unknown ids.
+pub fn stop_watch(id: &str) -> bool {
+    match lock_watches().remove(id) {
+        Some(entry) => {
+            entry.cancel.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Size and modification time per relative file path.
+type Snapshot = HashMap<PathBuf, (u64, SystemTime)>;
+
+fn excluded(name: &str, patterns: &[String]) -> bool {
+    patterns
+        .iter()
+        .any(|pattern| match pattern.strip_prefix('*') {
+            Some(suffix) => name.ends_with(suffix),
+            None => name == pattern,
+        })
+}
+
+/// Walk `root`, skipping excluded files and directories. Symlinks are not
+/// followed.
+fn snapshot(root: &Path, excludes: &[String]) -> Result<Snapshot, String> {
+    let mut files = Snapshot::new();
+    let mut pending = vec![PathBuf::new()];
+    while let Some(relative) = pending.pop() {
+        let entries = std::fs::read_dir(root.join(&relative))
+            .map_err(|e| format!("Failed to read {}: {e}", root.join(&relative).display()))?;
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if excluded(&name, excludes) {
+                continue;
+            }
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let path = relative.join(&name);
+            if file_type.is_dir() {
+                pending.push(path);
+            } else if file_type.is_file() {
+                // A file removed mid-walk just drops out of this snapshot.
+                let Ok(metadata) = entry.metadata() else {
+                    continue;
+                };
+                let modified = metadata.modified().unwrap_or(UNIX_EPOCH);
+                files.insert(path, (metadata.len(), modified));
+                if files.len() > MAX_WATCHED_FILES {
+                    return Err(format!(
+                        "More than {MAX_WATCHED_FILES} files to watch; narrow the directory or add excludes"
+                    ));
+                }
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Files that are new or differ in size or mtime.
+fn changed_files(previous: &Snapshot, current: &Snapshot) -> Vec<PathBuf> {
+    current
+        .iter()
+        .filter(|(path, stamp)| previous.get(*path) != Some(stamp))
+        .map(|(path, _)| path.clone())
+        .collect()
+}
+
+/// Remote path for `relative`, always with `/` separators.
+fn remote_path(remote_dir: &str, relative: &Path) -> String {
+    let relative: Vec<String> = relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    format!(
+        "{}/{}",
+        remote_dir.trim_end_matches('/'),
+        relative.join("/")
+    )
+}
+
+/// Remote directories `files` need, parents first.
+fn remote_parents(remote_dir: &str, files: &BTreeSet<PathBuf>) -> Vec<String> {
+    let mut directories = BTreeSet::new();
+    for file in files {
+        let mut parent = file.parent();
+        while let Some(directory) = parent.filter(|directory| !directory.as_os_str().is_empty()) {
+            directories.insert(remote_path(remote_dir, directory));
+            parent = directory.parent();
+        }
+    }
+    // Sorting puts every directory after its parent.
+    directories.into_iter().collect()
+}
+
+/// Start polling `config.local_dir`; the first snapshot is the baseline, so
+/// nothing is deployed until something changes.
+pub fn start_watch(app: AppHandle, config: DeployWatchConfig) -> Result<DeployWatchInfo, String> {
+    let root = PathBuf::from(&config.local_dir);
+    if !root.is_dir() {
+        return Err(format!("Not a directory: {}", config.local_dir));
+    }
+    let baseline = snapshot(&root, &config.excludes())?;
+    let id = uuid::Uuid::new_v4().to_string();
+    let cancel = Arc::new(AtomicBool::new(false));
+    let info = DeployWatchInfo {
+        id: id.clone(),
+        config,
+        started_at_ms: now_ms(),
+        deploying: false,
+        error: None,
+        runs: VecDeque::new(),
+    };
+    lock_watches().insert(
+        id.clone(),
+        WatchEntry {
+            info: info.clone(),
+            cancel: cancel.clone(),
+        },
+    );
+    let config = info.config.clone();
+    tauri::async_runtime::spawn(async move {
+        let error = watch_loop(&app, &id, &config, baseline, &cancel)
+            .await
+            .err();
+        if !cancel.load(Ordering::Relaxed) {
+            update_watch(&id, |info| info.error = error.clone());
+            let _ = app.emit(
+                "deploy-watch:stopped",
+                serde_json::json!({ "id": id, "error": error }),
+            );
+        }
+    });
+    Ok(info)
+}
+
+async fn watch_loop(
+    app: &AppHandle,
+    id: &str,
+    config: &DeployWatchConfig,
+    mut previous: Snapshot,
+    cancel: &Arc<AtomicBool>,
+) -> Result<(), String> {
+    let root = PathBuf::from(&config.local_dir);
+    let excludes = config.excludes();
+    let debounce = config.debounce();
+    let mut pending: BTreeSet<PathBuf> = BTreeSet::new();
+    let mut last_change = Instant::now();
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        let (walk_root, walk_excludes) = (root.clone(), excludes.clone());
+        let current = tokio::task::spawn_blocking(move || snapshot(&walk_root, &walk_excludes))
+            .await
+            .map_err(|e| e.to_string())??;
+        let changed = changed_files(&previous, &current);
+        previous = current;
+        if !changed.is_empty() {
+            pending.extend(changed);
+            last_change = Instant::now();
+            continue;
+        }
+        if pending.is_empty() || last_change.elapsed() < debounce {
+            continue;
+        }
+        // Files deleted while the edits settled have nothing left to upload.
+        let files: BTreeSet<PathBuf> = std::mem::take(&mut pending)
+            .into_iter()
+            .filter(|file| previous.contains_key(file))
+            .collect();
+        if files.is_empty() {
+            continue;
+        }
+        update_watch(id, |info| info.deploying = true);
+        let run = deploy(app, id, config, &files, cancel).await;
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        update_watch(id, |info| {
+            info.deploying = false;
+            info.runs.push_back(run.clone());
+            while info.runs.len() > HISTORY_SIZE {
+                info.runs.pop_front();
+            }
+        });
+        let _ = app.emit(
+            "deploy-watch:run",
+            serde_json::json!({ "id": id, "run": run }),
+        );
+    }
+}
+
+async fn deploy(
+    app: &AppHandle,
+    id: &str,
+    config: &DeployWatchConfig,
+    files: &BTreeSet<PathBuf>,
+    cancel: &AtomicBool,
+) -> DeployRun {
+    let started = Instant::now();
+    let mut run = DeployRun {
+        started_at_ms: now_ms(),
+        duration_ms: 0,
+        success: false,
+        files: files
+            .iter()
+            .take(MAX_LISTED_FILES)
+            .map(|file| file.to_string_lossy().replace('\\', "/"))
+            .collect(),
+        file_count: files.len(),
+        bytes: 0,
+        upload_error: None,
+        command_exit_code: None,
+        command_stdout: String::new(),
+        command_stderr: String::new(),
+        command_error: None,
+    };
+    let state = app.state::<AppState>();
+    match upload(app, &state, id, config, files, cancel).await {
+        Ok(bytes) => run.bytes = bytes,
+        Err(error) => run.upload_error = Some(error),
+    }
+    let command = config
+        .command
+        .as_deref()
+        .filter(|command| !command.trim().is_empty());
+    if let (None, Some(command)) = (&run.upload_error, command) {
+        match exec_on_connection(&state, &config.connection_id, command).await {
+            Ok(output) => {
+                run.command_exit_code = Some(output.exit_status);
+                run.command_stdout = crate::fleet::run::capture(&output.stdout).0;
+                run.command_stderr = crate::fleet::run::capture(&output.stderr).0;
+            }
+            Err(error) => run.command_error = Some(error),
+        }
+    }
+    run.success = run.upload_error.is_none()
+        && run.command_error.is_none()
+        && run.command_exit_code.unwrap_or(0) == 0;
+    run.duration_ms = started.elapsed().as_millis() as u64;
+    run
+}
+
+/// Upload `files` under `remote_dir`, creating missing directories first.
+async fn upload(
+    app: &AppHandle,
+    state: &AppState,
+    id: &str,
+    config: &DeployWatchConfig,
+    files: &BTreeSet<PathBuf>,
+    cancel: &AtomicBool,
+) -> Result<u64, String> {
+    let sftp = crate::commands::get_sftp_or_reconnect(state, &config.connection_id).await?;
+    let mut created: HashSet<String> = HashSet::new();
+    for directory in remote_parents(&config.remote_dir, files) {
+        if created.insert(directory.clone()) {
+            // Already existing is the common case; a real problem surfaces on upload.
+            let _ = state.file_system.create_dir_remote(&sftp, &directory).await;
+        }
+    }
+    let root = Path::new(&config.local_dir);
+    let mut bytes = 0;
+    for file in files {
+        if cancel.load(Ordering::Relaxed) {
+            return Err("Cancelled".to_string());
+        }
+        let remote = remote_path(&config.remote_dir, file);
+        bytes += upload_path(
+            app,
+            state,
+            &config.connection_id,
+            &root.join(file),
+            &remote,
+            &format!("deploy-watch:{id}"),
+            cancel,
+        )
+        .await
+        .map_err(|error| format!("{remote}: {error}"))?;
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn excludes() -> Vec<String> {
+        DEFAULT_EXCLUDES.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn snapshots_skip_excluded_names_and_detect_changes() {
+        let root = std::env::temp_dir().join(format!("zync-deploy-watch-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("src/nested")).unwrap();
+        std::fs::create_dir_all(root.join("node_modules/pkg")).unwrap();
+        std::fs::write(root.join("src/app.js"), "one").unwrap();
+        std::fs::write(root.join("src/.app.js.swp"), "swap").unwrap();
+        std::fs::write(root.join("node_modules/pkg/index.js"), "dep").unwrap();
+
+        let before = snapshot(&root, &excludes()).unwrap();
+        assert_eq!(before.len(), 1);
+
+        std::fs::write(root.join("src/app.js"), "two!").unwrap();
+        std::fs::write(root.join("src/nested/new.css"), "body{}").unwrap();
+        let after = snapshot(&root, &excludes()).unwrap();
+        let mut changed = changed_files(&before, &after);
+        changed.sort();
+        assert_eq!(
+            changed,
+            vec![
+                PathBuf::from("src/app.js"),
+                PathBuf::from("src/nested/new.css")
+            ]
+        );
+        assert!(changed_files(&after, &after).is_empty());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn remote_paths_use_forward_slashes_and_parents_come_first() {
+        let files: BTreeSet<PathBuf> = [
+            Path::new("src").join("nested").join("new.css"),
+            PathBuf::from("index.html"),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            remote_path("/srv/app/", &Path::new("src").join("app.js")),
+            "/srv/app/src/app.js"
+        );
+        assert_eq!(
+            remote_parents("/srv/app", &files),
+            vec!["/srv/app/src", "/srv/app/src/nested"]
+        );
+    }
+}
@@ -0,0 +1,11 @@
+use super::hints::{match_error, ErrorHint, HintContext};
+
+/// Explain an SSH error string. Returns `None` when the error is not recognized,
+/// so the UI can fall back to showing the raw message.
+#[tauri::command]
+pub async fn diagnostics_explain_error(
+    message: String,
+    context: Option<HintContext>,
+) -> Result<Option<ErrorHint>, String> {
+    Ok(match_error(&message, &context.unwrap_or_default()))
+}
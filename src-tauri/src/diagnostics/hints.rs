@@ -0,0 +1,252 @@
+//! Known SSH failure strings → structured, actionable hints.
+//!
+//! Matching is substring-based on the lowercased error text so it works for
+//! russh errors, our own `anyhow!` messages, and OpenSSH output pasted by users.
+
+use serde::{Deserialize, Serialize};
+
+/// Optional connection details used to fill remediation command templates.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HintContext {
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub username: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RemediationStep {
+    pub description: String,
+    /// Copy-pasteable command, already filled in with host/user when known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorHint {
+    /// Stable machine-readable identifier (e.g. `auth_publickey_rejected`).
+    pub code: String,
+    pub title: String,
+    pub explanation: String,
+    pub remediation: Vec<RemediationStep>,
+    /// Original error text, kept so the UI can offer "show details".
+    pub raw: String,
+}
+
+struct ErrorPattern {
+    code: &'static str,
+    /// Any of these (lowercase) substrings selects the pattern.
+    needles: &'static [&'static str],
+    title: &'static str,
+    explanation: &'static str,
+    /// `(description, command template)`; templates use `{host}`, `{port}`, `{user}`, `{target}`.
+    steps: &'static [(&'static str, Option<&'static str>)],
+}
+
+/// Ordered most-specific first: the first matching pattern wins.
+const PATTERNS: &[ErrorPattern] = &[
+    ErrorPattern {
+        code: "host_key_mismatch",
+        needles: &[
+            "host key verification failed",
+            "remote host identification has changed",
+            "key changed",
+            "keychanged",
+            "host key mismatch",
+        ],
+        title: "Host key does not match known_hosts",
+        explanation: "The server presented a different host key than the one recorded for this host. This happens after a server reinstall or IP reuse, but can also indicate a man-in-the-middle attack.",
+        steps: &[
+            ("Confirm the new fingerprint with the server administrator before trusting it.", None),
+            ("Remove the stale entry from known_hosts, then reconnect to record the new key.", Some("ssh-keygen -R {known_host}")),
+        ],
+    },
+    ErrorPattern {
+        code: "too_many_auth_failures",
+        needles: &["too many authentication failures"],
+        title: "Too many authentication failures",
+        explanation: "The server closed the connection after too many keys were offered. An SSH agent holding many keys is the usual cause, since each key counts as one attempt against MaxAuthTries.",
+        steps: &[
+            ("Offer only the key configured for this host.", Some("ssh -o IdentitiesOnly=yes -i ~/.ssh/id_ed25519 {target}")),
+            ("Persist the setting for this host in ~/.ssh/config.", Some("Host {host}\n  IdentitiesOnly yes")),
+        ],
+    },
+    ErrorPattern {
+        code: "forwarding_prohibited",
+        needles: &["administratively prohibited", "administrativelyprohibited"],
+        title: "Server refused the channel (administratively prohibited)",
+        explanation: "sshd rejected the channel by policy. For tunnels this usually means AllowTcpForwarding is disabled; for terminals it can mean the MaxSessions limit was reached.",
+        steps: &[
+            ("Check the effective forwarding and session settings on the server.", Some("sudo sshd -T | grep -Ei 'allowtcpforwarding|maxsessions|permitopen'")),
+            ("Enable forwarding in /etc/ssh/sshd_config (AllowTcpForwarding yes), then reload sshd.", Some("sudo systemctl reload sshd")),
+        ],
+    },
+    ErrorPattern {
+        code: "auth_publickey_rejected",
+        needles: &["permission denied (publickey", "authentication failed (publickey"],
+        title: "Server rejected the private key",
+        explanation: "The key was offered but the server does not accept it for this user. The public key is probably missing from authorized_keys, or file permissions on the server are too open.",
+        steps: &[
+            ("Install your public key on the server.", Some("ssh-copy-id -i ~/.ssh/id_ed25519.pub {target}")),
+            ("Fix permissions on the server; sshd ignores keys in group/world-writable files.", Some("chmod 700 ~/.ssh && chmod 600 ~/.ssh/authorized_keys")),
+            ("See which keys are offered and why they fail.", Some("ssh -v {target}")),
+        ],
+    },
+    ErrorPattern {
+        code: "auth_failed",
+        needles: &["permission denied", "authentication failed"],
+        title: "Authentication failed",
+        explanation: "The server did not accept the supplied credentials for this user.",
+        steps: &[
+            ("Double-check the username and password or key configured for this host.", None),
+            ("Check which authentication methods the server allows.", Some("ssh -v -o PreferredAuthentications=none {target}")),
+        ],
+    },
+    ErrorPattern {
+        code: "connection_refused",
+        needles: &["connection refused"],
+        title: "Connection refused",
+        explanation: "The host is reachable but nothing is listening on the SSH port, or a firewall actively rejected the connection.",
+        steps: &[
+            ("Verify the port is open from this machine.", Some("nc -vz {host} {port}")),
+            ("On the server, make sure sshd is running.", Some("sudo systemctl status sshd")),
+        ],
+    },
+    ErrorPattern {
+        code: "connection_timeout",
+        needles: &["timed out", "timeout", "deadline has elapsed"],
+        title: "Connection timed out",
+        explanation: "No response was received from the host. It may be offline, behind a firewall dropping packets, or require a VPN or jump host.",
+        steps: &[
+            ("Check basic reachability.", Some("ping -c 3 {host}")),
+            ("Check whether the SSH port answers.", Some("nc -vz -w 5 {host} {port}")),
+        ],
+    },
+    ErrorPattern {
+        code: "dns_failure",
+        needles: &[
+            "failed to lookup address",
+            "could not resolve",
+            "name or service not known",
+            "nodename nor servname",
+            "no such host",
+        ],
+        title: "Hostname could not be resolved",
+        explanation: "DNS lookup for the host failed. The name may be misspelled, only resolvable on a VPN, or defined as an alias in ~/.ssh/config.",
+        steps: &[("Check how the name resolves from this machine.", Some("nslookup {host}"))],
+    },
+    ErrorPattern {
+        code: "network_unreachable",
+        needles: &["no route to host", "network is unreachable"],
+        title: "Host unreachable",
+        explanation: "The network has no route to the host. Check VPN connectivity or whether the host is on a private network.",
+        steps: &[("Trace the route to the host.", Some("traceroute {host}"))],
+    },
+];
+
+/// Match `message` against the known patterns and build a hint.
+/// Returns `None` when the error is not recognized.
+pub fn match_error(message: &str, context: &HintContext) -> Option<ErrorHint> {
+    let lower = message.to_ascii_lowercase();
+    let pattern = PATTERNS
+        .iter()
+        .find(|pattern| pattern.needles.iter().any(|needle| lower.contains(needle)))?;
+
+    Some(ErrorHint {
+        code: pattern.code.to_string(),
+        title: pattern.title.to_string(),
+        explanation: pattern.explanation.to_string(),
+        remediation: pattern
+            .steps
+            .iter()
+            .map(|(description, command)| RemediationStep {
+                description: description.to_string(),
+                command: command.map(|template| fill_template(template, context)),
+            })
+            .collect(),
+        raw: message.to_string(),
+    })
+}
+
+fn fill_template(template: &str, context: &HintContext) -> String {
+    let host = context
+        .host
+        .as_deref()
+        .filter(|value| !value.is_empty())
+        .unwrap_or("<host>");
+    let port = context.port.unwrap_or(22);
+    let target = match context.username.as_deref().filter(|value| !value.is_empty()) {
+        Some(user) if port != 22 => format!("-p {port} {user}@{host}"),
+        Some(user) => format!("{user}@{host}"),
+        None if port != 22 => format!("-p {port} {host}"),
+        None => host.to_string(),
+    };
+    // known_hosts records non-default ports as `[host]:port`.
+    let known_host = if port == 22 {
+        host.to_string()
+    } else {
+        format!("'[{host}]:{port}'")
+    };
+
+    template
+        .replace("{known_host}", &known_host)
+        .replace("{target}", &target)
+        .replace("{host}", host)
+        .replace("{port}", &port.to_string())
+        .replace("{user}", context.username.as_deref().unwrap_or("<user>"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> HintContext {
+        HintContext {
+            host: Some("db.example.com".to_string()),
+            port: Some(2222),
+            username: Some("deploy".to_string()),
+        }
+    }
+
+    #[test]
+    fn publickey_rejection_is_more_specific_than_generic_auth() {
+        let hint = match_error("Permission denied (publickey).", &context()).expect("hint");
+        assert_eq!(hint.code, "auth_publickey_rejected");
+        assert_eq!(
+            hint.remediation[0].command.as_deref(),
+            Some("ssh-copy-id -i ~/.ssh/id_ed25519.pub -p 2222 deploy@db.example.com")
+        );
+    }
+
+    #[test]
+    fn host_key_mismatch_uses_bracketed_known_hosts_entry_for_custom_port() {
+        let hint = match_error("Host key verification failed.", &context()).expect("hint");
+        assert_eq!(hint.code, "host_key_mismatch");
+        assert_eq!(
+            hint.remediation[1].command.as_deref(),
+            Some("ssh-keygen -R '[db.example.com]:2222'")
+        );
+    }
+
+    #[test]
+    fn recognizes_prohibited_channel_and_auth_limit() {
+        let ctx = HintContext::default();
+        assert_eq!(
+            match_error("channel open failure: administratively prohibited: open failed", &ctx)
+                .map(|hint| hint.code),
+            Some("forwarding_prohibited".to_string())
+        );
+        assert_eq!(
+            match_error("Received disconnect: Too many authentication failures", &ctx)
+                .map(|hint| hint.code),
+            Some("too_many_auth_failures".to_string())
+        );
+    }
+
+    #[test]
+    fn unknown_errors_return_none() {
+        assert!(match_error("something unexpected", &HintContext::default()).is_none());
+    }
+}
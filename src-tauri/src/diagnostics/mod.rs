@@ -0,0 +1,36 @@
+//! Diagnostics — turning raw SSH/transport failures into actionable guidance.
+
+pub mod commands;
+pub mod hints;
+
+pub use hints::{match_error, ErrorHint, HintContext};
+
+use crate::types::ConnectionConfig;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ConnectionErrorHintEvent {
+    connection_id: String,
+    hint: ErrorHint,
+}
+
+/// Emit `connection:error-hint` when a connect failure matches a known pattern.
+/// The raw error is still returned to the caller; this only adds guidance.
+pub(crate) fn emit_connection_error_hint(app: &AppHandle, config: &ConnectionConfig, error: &str) {
+    let context = HintContext {
+        host: Some(config.host.clone()),
+        port: Some(config.port),
+        username: Some(config.username.clone()),
+    };
+    if let Some(hint) = match_error(error, &context) {
+        let _ = app.emit(
+            "connection:error-hint",
+            ConnectionErrorHintEvent {
+                connection_id: config.id.clone(),
+                hint,
+            },
+        );
+    }
+}
@@ -0,0 +1,470 @@
+use super::{ssh_version, DoctorCheck, DoctorReport};
+use crate::commands::get_data_dir;
+use crate::data_validation::{validate, DataFile, ProblemSeverity};
+use crate::snippets::SnippetsData;
+use crate::types::{SavedData, SavedTunnelsData};
+use serde::de::DeserializeOwned;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+const KEYRING_SERVICE: &str = "Zync Doctor";
+const VALIDATE_HINT: &str = "Open Settings → General → Storage and run Validate Data Files.";
+
+fn data_file_check(dir: &Path, file: DataFile) -> DoctorCheck {
+    let id = format!("data-{}", file.file_name().trim_end_matches(".json"));
+    let title = file.file_name();
+    let path = dir.join(file.file_name());
+    if !path.exists() {
+        return DoctorCheck::pass(&id, "data", title, "Not created yet");
+    }
+    let raw = match std::fs::read_to_string(&path) {
+        Ok(raw) => raw,
+        Err(error) => {
+            return DoctorCheck::fail(
+                &id,
+                "data",
+                title,
+                format!("Can't be read: {error}"),
+                "Check the file's permissions and that it isn't locked by another program.",
+            )
+        }
+    };
+    let report = validate(file, &raw);
+    let count = |severity: ProblemSeverity| {
+        report
+            .problems
+            .iter()
+            .filter(|problem| problem.severity == severity)
+            .count()
+    };
+    if let Some(error) = report
+        .problems
+        .iter()
+        .find(|problem| problem.severity == ProblemSeverity::Error)
+    {
+        let line = error
+            .line
+            .map(|line| format!(" (line {line})"))
+            .unwrap_or_default();
+        return DoctorCheck::fail(
+            &id,
+            "data",
+            title,
+            format!("{}{line}: {}", error.pointer, error.message),
+            format!("Fix the entry by hand. {VALIDATE_HINT}"),
+        );
+    }
+    let repairable = count(ProblemSeverity::Repaired);
+    let warnings = count(ProblemSeverity::Warning);
+    if repairable > 0 {
+        return DoctorCheck::warn(
+            &id,
+            "data",
+            title,
+            format!("{repairable} problem(s) can be repaired automatically"),
+            VALIDATE_HINT,
+        );
+    }
+    if warnings > 0 {
+        return DoctorCheck::warn(
+            &id,
+            "data",
+            title,
+            format!("{warnings} warning(s), such as unknown fields or duplicate ids"),
+            VALIDATE_HINT,
+        );
+    }
+    DoctorCheck::pass(&id, "data", title, "Valid")
+}
+
+/// Parse without the load-time repair, so the doctor never rewrites a file.
+fn read_json<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let raw = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&raw)
+        .map(Some)
+        .map_err(|e| format!("{}: {e}", path.display()))
+}
+
+/// Skipped when a file doesn't load as written; that is reported by its own check.
+fn references_check(dir: &Path, split: bool) -> Option<DoctorCheck> {
+    use crate::secret_split::{check_integrity, IssueSeverity, SecretStore};
+    const TITLE: &str = "References between data files";
+    let loaded = (|| -> Result<_, String> {
+        let data = read_json::<SavedData>(&dir.join("connections.json"))?.unwrap_or(SavedData {
+            connections: Vec::new(),
+            folders: Vec::new(),
+        });
+        let tunnels = read_json::<SavedTunnelsData>(&dir.join("tunnels.json"))?
+            .map(|data| data.tunnels)
+            .unwrap_or_default();
+        let snippets = read_json::<SnippetsData>(&dir.join("snippets.json"))?
+            .map(|data| data.snippets)
+            .unwrap_or_default();
+        Ok((data, tunnels, snippets))
+    })();
+    let (data, tunnels, snippets) = loaded.ok()?;
+    let store = crate::secret_split::commands::KeychainStore;
+    let issues = check_integrity(
+        &data,
+        &tunnels,
+        &snippets,
+        split,
+        |id| store.get(id).ok().map(|secret| secret.is_some()),
+        |_| None,
+    );
+    let Some(first) = issues.first() else {
+        return Some(DoctorCheck::pass(
+            "data-references",
+            "data",
+            TITLE,
+            "No broken references",
+        ));
+    };
+    let errors = issues
+        .iter()
+        .filter(|issue| issue.severity == IssueSeverity::Error)
+        .count();
+    let detail = format!("{} issue(s); first: {}", issues.len(), first.message);
+    let fix = "Open Settings → General → Storage and run Check Integrity for the full list.";
+    Some(if errors > 0 {
+        DoctorCheck::fail("data-references", "data", TITLE, detail, fix)
+    } else {
+        DoctorCheck::warn("data-references", "data", TITLE, detail, fix)
+    })
+}
+
+fn data_dir_check(dir: &Path) -> DoctorCheck {
+    const TITLE: &str = "Data folder is writable";
+    let probe = dir.join(format!(".zync-doctor-{}", uuid::Uuid::new_v4()));
+    let result = std::fs::create_dir_all(dir)
+        .and_then(|()| std::fs::write(&probe, b"ok"))
+        .and_then(|()| std::fs::remove_file(&probe));
+    match result {
+        Ok(()) => DoctorCheck::pass("data-dir", "data", TITLE, dir.display().to_string()),
+        Err(error) => DoctorCheck::fail(
+            "data-dir",
+            "data",
+            TITLE,
+            format!("{}: {error}", dir.display()),
+            "Check the folder's permissions and free space, or pick another data folder in Settings.",
+        ),
+    }
+}
+
+fn keychain_check() -> DoctorCheck {
+    const TITLE: &str = "OS keychain";
+    let fix = if cfg!(target_os = "linux") {
+        "Install and unlock a Secret Service provider (GNOME Keyring or KWallet) and make sure a D-Bus session is running."
+    } else if cfg!(target_os = "macos") {
+        "Unlock the login keychain in Keychain Access and allow Zync when prompted."
+    } else {
+        "Make sure Windows Credential Manager is available for your account."
+    };
+    let account = format!("probe-{}", uuid::Uuid::new_v4());
+    let result = keyring::Entry::new(KEYRING_SERVICE, &account).and_then(|entry| {
+        entry.set_password("ok")?;
+        let read = entry.get_password();
+        let _ = entry.delete_credential();
+        read
+    });
+    match result {
+        Ok(value) if value == "ok" => DoctorCheck::pass(
+            "keychain",
+            "keychain",
+            TITLE,
+            "Secrets can be stored and read back",
+        ),
+        Ok(_) => DoctorCheck::fail(
+            "keychain",
+            "keychain",
+            TITLE,
+            "A stored secret read back differently",
+            fix,
+        ),
+        Err(error) => DoctorCheck::fail(
+            "keychain",
+            "keychain",
+            TITLE,
+            format!(
+                "Unavailable: {error}. Saved passwords, sync tokens and unlock caching won't work."
+            ),
+            fix,
+        ),
+    }
+}
+
+async fn ssh_binary_check() -> DoctorCheck {
+    const TITLE: &str = "OpenSSH client";
+    let output = tokio::time::timeout(
+        PROBE_TIMEOUT,
+        tokio::process::Command::new("ssh").arg("-V").output(),
+    )
+    .await;
+    match output {
+        Ok(Ok(output)) => {
+            let text = format!(
+                "{}{}",
+                String::from_utf8_lossy(&output.stderr),
+                String::from_utf8_lossy(&output.stdout)
+            );
+            DoctorCheck::pass(
+                "ssh-binary",
+                "ssh",
+                TITLE,
+                ssh_version(&text).unwrap_or_else(|| "Found".to_string()),
+            )
+        }
+        Ok(Err(error)) if error.kind() == std::io::ErrorKind::NotFound => DoctorCheck::warn(
+            "ssh-binary",
+            "ssh",
+            TITLE,
+            "`ssh` isn't on PATH. Zync connects without it, but local terminals and scripts can't use ssh.",
+            if cfg!(target_os = "windows") {
+                "Add the OpenSSH Client optional feature in Windows Settings → Apps → Optional features."
+            } else {
+                "Install the OpenSSH client with your package manager."
+            },
+        ),
+        Ok(Err(error)) => DoctorCheck::warn(
+            "ssh-binary",
+            "ssh",
+            TITLE,
+            format!("`ssh -V` failed: {error}"),
+            "Check the OpenSSH installation.",
+        ),
+        Err(_) => DoctorCheck::warn(
+            "ssh-binary",
+            "ssh",
+            TITLE,
+            "`ssh -V` didn't finish in time",
+            "Check the OpenSSH installation.",
+        ),
+    }
+}
+
+/// Ask the agent for its identities; returns how many keys it holds.
+#[cfg(unix)]
+fn query_agent(socket: &str) -> std::io::Result<Option<u32>> {
+    use std::io::{Read, Write};
+    const SSH_AGENTC_REQUEST_IDENTITIES: u8 = 11;
+    let mut stream = std::os::unix::net::UnixStream::connect(socket)?;
+    stream.set_read_timeout(Some(PROBE_TIMEOUT))?;
+    stream.set_write_timeout(Some(PROBE_TIMEOUT))?;
+    stream.write_all(&[0, 0, 0, 1, SSH_AGENTC_REQUEST_IDENTITIES])?;
+    let mut length = [0u8; 4];
+    stream.read_exact(&mut length)?;
+    let length = u32::from_be_bytes(length).min(256 * 1024) as usize;
+    let mut message = vec![0u8; length];
+    stream.read_exact(&mut message)?;
+    Ok(super::parse_identities_answer(&message))
+}
+
+#[cfg(unix)]
+fn agent_check() -> DoctorCheck {
+    const TITLE: &str = "SSH agent";
+    const FIX: &str = "Start ssh-agent and add your keys with `ssh-add` if you rely on agent keys; otherwise this can be ignored.";
+    let Some(socket) = std::env::var("SSH_AUTH_SOCK")
+        .ok()
+        .filter(|s| !s.is_empty())
+    else {
+        return DoctorCheck::warn("ssh-agent", "ssh", TITLE, "SSH_AUTH_SOCK isn't set", FIX);
+    };
+    match query_agent(&socket) {
+        Ok(Some(0)) => {
+            DoctorCheck::warn("ssh-agent", "ssh", TITLE, "Running, but holds no keys", FIX)
+        }
+        Ok(Some(keys)) => DoctorCheck::pass(
+            "ssh-agent",
+            "ssh",
+            TITLE,
+            format!("Running with {keys} key(s)"),
+        ),
+        Ok(None) => DoctorCheck::warn(
+            "ssh-agent",
+            "ssh",
+            TITLE,
+            "The agent gave an unexpected answer",
+            FIX,
+        ),
+        Err(error) => DoctorCheck::warn(
+            "ssh-agent",
+            "ssh",
+            TITLE,
+            format!("Can't reach the agent at {socket}: {error}"),
+            FIX,
+        ),
+    }
+}
+
+#[cfg(windows)]
+fn agent_check() -> DoctorCheck {
+    const TITLE: &str = "SSH agent";
+    const PIPE: &str = r"\\.\pipe\openssh-ssh-agent";
+    if Path::new(PIPE).exists() {
+        DoctorCheck::pass(
+            "ssh-agent",
+            "ssh",
+            TITLE,
+            "The OpenSSH Authentication Agent service is running",
+        )
+    } else {
+        DoctorCheck::warn(
+            "ssh-agent",
+            "ssh",
+            TITLE,
+            "The OpenSSH Authentication Agent service isn't running",
+            "Start the \"OpenSSH Authentication Agent\" service (Services → Startup type: Automatic) if you rely on agent keys.",
+        )
+    }
+}
+
+#[cfg(target_os = "windows")]
+async fn windows_checks() -> Vec<DoctorCheck> {
+    let build = sysinfo::System::kernel_version()
+        .as_deref()
+        .and_then(super::windows_build);
+    let conpty = match build {
+        Some(build) if build >= super::CONPTY_MIN_BUILD => DoctorCheck::pass(
+            "conpty",
+            "platform",
+            "ConPTY",
+            format!("Windows build {build}"),
+        ),
+        Some(build) => DoctorCheck::fail(
+            "conpty",
+            "platform",
+            "ConPTY",
+            format!(
+                "Windows build {build} predates ConPTY ({})",
+                super::CONPTY_MIN_BUILD
+            ),
+            "Update to Windows 10 version 1809 or later for local terminals.",
+        ),
+        None => DoctorCheck::warn(
+            "conpty",
+            "platform",
+            "ConPTY",
+            "Couldn't determine the Windows build",
+            "Local terminals need Windows 10 version 1809 or later.",
+        ),
+    };
+    let wsl = match crate::commands::shell_get_wsl_distros().await {
+        Ok(distros) if !distros.is_empty() => DoctorCheck::pass(
+            "wsl",
+            "platform",
+            "WSL",
+            format!("{} distribution(s): {}", distros.len(), distros.join(", ")),
+        ),
+        Ok(_) => DoctorCheck::warn(
+            "wsl",
+            "platform",
+            "WSL",
+            "No WSL distributions found",
+            "Optional: run `wsl --install` to open WSL shells in Zync.",
+        ),
+        Err(error) => DoctorCheck::warn(
+            "wsl",
+            "platform",
+            "WSL",
+            format!("Couldn't list distributions: {error}"),
+            "Optional: check `wsl -l` in a terminal.",
+        ),
+    };
+    vec![conpty, wsl]
+}
+
+async fn network_checks() -> Vec<DoctorCheck> {
+    const FIX: &str = "Check your network connection, VPN and proxy settings.";
+    if !crate::network::status().online {
+        return vec![DoctorCheck::fail(
+            "network-route",
+            "network",
+            "Network",
+            "No route to the internet; LAN hosts may still work",
+            FIX,
+        )];
+    }
+    let mut checks = vec![DoctorCheck::pass(
+        "network-route",
+        "network",
+        "Network",
+        "Online",
+    )];
+    let resolved =
+        tokio::time::timeout(PROBE_TIMEOUT, tokio::net::lookup_host(("github.com", 443))).await;
+    let address = match resolved {
+        Ok(Ok(mut addrs)) => addrs.next(),
+        _ => None,
+    };
+    let Some(address) = address else {
+        checks.push(DoctorCheck::fail(
+            "network-dns",
+            "network",
+            "DNS",
+            "github.com doesn't resolve",
+            "Check your DNS settings; hosts saved by name won't connect.",
+        ));
+        return checks;
+    };
+    checks.push(DoctorCheck::pass(
+        "network-dns",
+        "network",
+        "DNS",
+        "Names resolve",
+    ));
+    let connected =
+        tokio::time::timeout(PROBE_TIMEOUT, tokio::net::TcpStream::connect(address)).await;
+    checks.push(match connected {
+        Ok(Ok(_)) => DoctorCheck::pass(
+            "network-https",
+            "network",
+            "HTTPS",
+            "github.com:443 is reachable (updates and plugins)",
+        ),
+        _ => DoctorCheck::warn(
+            "network-https",
+            "network",
+            "HTTPS",
+            "github.com:443 isn't reachable; update checks and plugin downloads will fail",
+            FIX,
+        ),
+    });
+    checks
+}
+
+/// Run every check. Nothing is changed apart from a probe file in the
+/// data folder and a probe keychain entry, both removed again.
+#[tauri::command]
+pub async fn run_doctor(app: AppHandle) -> Result<DoctorReport, String> {
+    let dir = get_data_dir(&app);
+    let split = crate::secret_split::commands::read_storage_settings(&app).split_secrets;
+    let mut checks = tokio::task::spawn_blocking(move || {
+        let mut checks = vec![data_dir_check(&dir)];
+        checks.extend(
+            DataFile::ALL
+                .into_iter()
+                .map(|file| data_file_check(&dir, file)),
+        );
+        checks.extend(references_check(&dir, split));
+        checks.push(keychain_check());
+        checks.push(agent_check());
+        checks
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    checks.push(ssh_binary_check().await);
+    #[cfg(target_os = "windows")]
+    checks.extend(windows_checks().await);
+    checks.extend(network_checks().await);
+
+    let generated_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    Ok(DoctorReport::new(generated_at, checks))
+}
@@ -0,0 +1,165 @@
+//! Self-diagnostics: `run_doctor` checks the data files, the OS keychain,
+//! OpenSSH tooling, Windows terminal support and the network, and reports
+//! each as pass/warn/fail with a suggested fix.
+
+pub mod commands;
+
+use serde::Serialize;
+
+/// First Windows 10 build with the ConPTY API (1809).
+#[cfg_attr(not(windows), allow(dead_code))]
+pub const CONPTY_MIN_BUILD: u32 = 17763;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DoctorCheck {
+    pub id: String,
+    /// `data`, `keychain`, `ssh`, `platform` or `network`.
+    pub category: &'static str,
+    pub title: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    pub fix: Option<String>,
+}
+
+impl DoctorCheck {
+    pub fn pass(id: &str, category: &'static str, title: &str, detail: impl Into<String>) -> Self {
+        Self {
+            id: id.to_string(),
+            category,
+            title: title.to_string(),
+            status: CheckStatus::Pass,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    pub fn warn(
+        id: &str,
+        category: &'static str,
+        title: &str,
+        detail: impl Into<String>,
+        fix: impl Into<String>,
+    ) -> Self {
+        Self {
+            status: CheckStatus::Warn,
+            fix: Some(fix.into()),
+            ..Self::pass(id, category, title, detail)
+        }
+    }
+
+    pub fn fail(
+        id: &str,
+        category: &'static str,
+        title: &str,
+        detail: impl Into<String>,
+        fix: impl Into<String>,
+    ) -> Self {
+        Self {
+            status: CheckStatus::Fail,
+            fix: Some(fix.into()),
+            ..Self::pass(id, category, title, detail)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DoctorReport {
+    pub generated_at: u64,
+    /// The worst status of any check.
+    pub overall: CheckStatus,
+    pub passed: usize,
+    pub warnings: usize,
+    pub failures: usize,
+    pub checks: Vec<DoctorCheck>,
+}
+
+impl DoctorReport {
+    pub fn new(generated_at: u64, checks: Vec<DoctorCheck>) -> Self {
+        let count = |status: CheckStatus| checks.iter().filter(|c| c.status == status).count();
+        Self {
+            generated_at,
+            overall: checks
+                .iter()
+                .map(|check| check.status)
+                .max()
+                .unwrap_or(CheckStatus::Pass),
+            passed: count(CheckStatus::Pass),
+            warnings: count(CheckStatus::Warn),
+            failures: count(CheckStatus::Fail),
+            checks,
+        }
+    }
+}
+
+/// Number of keys in an ssh-agent `SSH_AGENT_IDENTITIES_ANSWER` message
+/// (type byte onwards, without the length prefix).
+#[cfg_attr(not(unix), allow(dead_code))]
+pub fn parse_identities_answer(message: &[u8]) -> Option<u32> {
+    const SSH_AGENT_IDENTITIES_ANSWER: u8 = 12;
+    match message {
+        [SSH_AGENT_IDENTITIES_ANSWER, a, b, c, d, ..] => Some(u32::from_be_bytes([*a, *b, *c, *d])),
+        _ => None,
+    }
+}
+
+/// Windows build number from a version string such as `22631` or
+/// `10.0.19045`.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn windows_build(version: &str) -> Option<u32> {
+    version
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|part| !part.is_empty())
+        .filter_map(|part| part.parse::<u32>().ok())
+        .find(|build| *build >= 1000)
+}
+
+/// `OpenSSH_9.6p1, LibreSSL 3.3.6` → `OpenSSH_9.6p1`.
+pub fn ssh_version(output: &str) -> Option<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(|line| line.split(',').next().unwrap_or(line).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_takes_the_worst_status() {
+        let report = DoctorReport::new(
+            1,
+            vec![
+                DoctorCheck::pass("a", "data", "A", "ok"),
+                DoctorCheck::warn("b", "ssh", "B", "meh", "do this"),
+            ],
+        );
+        assert_eq!(report.overall, CheckStatus::Warn);
+        assert_eq!((report.passed, report.warnings, report.failures), (1, 1, 0));
+        assert_eq!(DoctorReport::new(1, Vec::new()).overall, CheckStatus::Pass);
+    }
+
+    #[test]
+    fn parses_probe_output() {
+        assert_eq!(parse_identities_answer(&[12, 0, 0, 0, 2, 9, 9]), Some(2));
+        assert_eq!(parse_identities_answer(&[5]), None);
+        assert_eq!(windows_build("10.0.19045"), Some(19045));
+        assert_eq!(windows_build("22631"), Some(22631));
+        assert_eq!(windows_build("unknown"), None);
+        assert_eq!(
+            ssh_version("OpenSSH_9.6p1, LibreSSL 3.3.6\n").as_deref(),
+            Some("OpenSSH_9.6p1")
+        );
+    }
+}
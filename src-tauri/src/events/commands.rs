@@ -0,0 +1,10 @@
+use super::{events_since, ConnectionEventEnvelope};
+
+/// Events still in the in-memory history with `seq > after_seq` (all of them when omitted).
+/// A gap between `after_seq` and the first returned `seq` means older events were dropped.
+#[tauri::command]
+pub async fn connection_events_since(
+    after_seq: Option<u64>,
+) -> Result<Vec<ConnectionEventEnvelope>, String> {
+    Ok(events_since(after_seq.unwrap_or(0)))
+}
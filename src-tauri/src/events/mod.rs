@@ -0,0 +1,266 @@
+//! Connection event bus — one typed, sequenced stream of lifecycle events.
+//!
+//! Every event is emitted to the frontend as `connection:event`, kept in a short
+//! history so a UI that missed events can catch up by sequence number, and fanned
+//! out to in-process subscribers.
+
+pub mod commands;
+
+use crate::profiles::ConnectionProfile;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex as StdMutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::broadcast;
+
+pub const CONNECTION_EVENT: &str = "connection:event";
+
+const HISTORY_CAPACITY: usize = 512;
+const SUBSCRIBER_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "kebab-case", rename_all_fields = "camelCase")]
+pub enum ConnectionEvent {
+    Connected {
+        host: String,
+        port: u16,
+        username: String,
+        profile: ConnectionProfile,
+    },
+    ConnectFailed {
+        error: String,
+    },
+    AuthFailed {
+        error: String,
+    },
+    Disconnected,
+    /// The session was re-established after the transport dropped.
+    Reconnected,
+    /// The SSH transport died underneath open channels.
+    TransportLost,
+    /// A liveness probe got no answer within its deadline.
+    KeepaliveTimeout,
+    ChannelOpened {
+        kind: String,
+        open_channels: usize,
+    },
+    TunnelUp {
+        tunnel_id: String,
+    },
+    TunnelDown {
+        tunnel_id: String,
+        error: Option<String>,
+    },
+    TransferProgress {
+        transfer_id: String,
+        transferred: u64,
+        total: u64,
+    },
+    /// A backend `ssh_exec` command returned.
+    CommandFinished {
+        /// Truncated to keep payloads small.
+        command: String,
+        exit_code: u32,
+        duration_ms: u64,
+    },
+    TransferFinished {
+        transfer_id: String,
+        /// `completed`, `failed`, or `cancelled`.
+        outcome: String,
+        error: Option<String>,
+    },
+    /// The session lifecycle moved (see `session_state`).
+    StateChanged {
+        state: crate::session_state::SessionState,
+        previous: crate::session_state::SessionState,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionEventEnvelope {
+    /// Monotonic across all connections for the lifetime of the process.
+    pub seq: u64,
+    pub timestamp_ms: u64,
+    pub connection_id: String,
+    #[serde(flatten)]
+    pub event: ConnectionEvent,
+}
+
+struct EventBus {
+    /// Set once at startup; publishers deep in tunnel/pty tasks don't carry a handle.
+    app: OnceLock<AppHandle>,
+    next_seq: AtomicU64,
+    history: StdMutex<VecDeque<ConnectionEventEnvelope>>,
+    subscribers: broadcast::Sender<ConnectionEventEnvelope>,
+}
+
+static EVENT_BUS: LazyLock<EventBus> = LazyLock::new(|| EventBus {
+    app: OnceLock::new(),
+    next_seq: AtomicU64::new(1),
+    history: StdMutex::new(VecDeque::with_capacity(HISTORY_CAPACITY)),
+    subscribers: broadcast::channel(SUBSCRIBER_CAPACITY).0,
+});
+
+/// Route published events to the frontend. Called once from `AppState::new`.
+pub(crate) fn attach(app: AppHandle) {
+    let _ = EVENT_BUS.app.set(app);
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn record(connection_id: &str, event: ConnectionEvent) -> ConnectionEventEnvelope {
+    let mut history = match EVENT_BUS.history.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    // Assign the sequence under the history lock so history stays ordered.
+    let envelope = ConnectionEventEnvelope {
+        seq: EVENT_BUS.next_seq.fetch_add(1, Ordering::Relaxed),
+        timestamp_ms: now_ms(),
+        connection_id: connection_id.to_string(),
+        event,
+    };
+    if history.len() == HISTORY_CAPACITY {
+        history.pop_front();
+    }
+    history.push_back(envelope.clone());
+    envelope
+}
+
+/// Publish an event for `connection_id`. Returns its sequence number.
+pub(crate) fn publish(connection_id: &str, event: ConnectionEvent) -> u64 {
+    let envelope = record(connection_id, event);
+    if let Some(app) = EVENT_BUS.app.get() {
+        let _ = app.emit(CONNECTION_EVENT, &envelope);
+    }
+    // No receivers is the normal case when nothing has subscribed.
+    let _ = EVENT_BUS.subscribers.send(envelope.clone());
+    envelope.seq
+}
+
+/// In-process subscription (metrics, notifications). Slow receivers lag, never block publishers.
+pub(crate) fn subscribe() -> broadcast::Receiver<ConnectionEventEnvelope> {
+    EVENT_BUS.subscribers.subscribe()
+}
+
+/// Buffered events with `seq > after_seq`, oldest first.
+/// The kebab-case `type` tag an event serializes with (used for filtering).
+pub fn event_type(event: &ConnectionEvent) -> String {
+    serde_json::to_value(event)
+        .ok()
+        .and_then(|value| value.get("type").and_then(|tag| tag.as_str()).map(str::to_string))
+        .unwrap_or_default()
+}
+
+pub(crate) fn events_since(after_seq: u64) -> Vec<ConnectionEventEnvelope> {
+    let history = match EVENT_BUS.history.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    history
+        .iter()
+        .filter(|envelope| envelope.seq > after_seq)
+        .cloned()
+        .collect()
+}
+
+/// Push `samples` synthetic envelopes through a private bus with the production
+/// subscriber capacity and payload shape. Returns per-delivery latency in
+/// microseconds and the number of deliveries lost to lag. The real bus, its
+/// history and the frontend are never touched.
+pub(crate) async fn measure_fanout(samples: usize, subscribers: usize) -> (Vec<u64>, u64) {
+    let (sender, _) = broadcast::channel::<(std::time::Instant, ConnectionEventEnvelope)>(
+        SUBSCRIBER_CAPACITY,
+    );
+    let receivers: Vec<_> = (0..subscribers.max(1))
+        .map(|_| {
+            let mut receiver = sender.subscribe();
+            tokio::spawn(async move {
+                let mut latencies = Vec::new();
+                let mut lagged = 0u64;
+                loop {
+                    match receiver.recv().await {
+                        Ok((sent_at, _envelope)) => {
+                            latencies.push(sent_at.elapsed().as_micros() as u64)
+                        }
+                        Err(broadcast::error::RecvError::Lagged(skipped)) => lagged += skipped,
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    }
+                }
+                (latencies, lagged)
+            })
+        })
+        .collect();
+
+    for sample in 0..samples {
+        let envelope = ConnectionEventEnvelope {
+            seq: sample as u64,
+            timestamp_ms: now_ms(),
+            connection_id: "benchmark".to_string(),
+            event: ConnectionEvent::TransferProgress {
+                transfer_id: "benchmark".to_string(),
+                transferred: sample as u64,
+                total: samples as u64,
+            },
+        };
+        let sent_at = std::time::Instant::now();
+        // Mirror `publish`: serialize for the frontend, then fan out.
+        let _ = serde_json::to_string(&envelope);
+        let _ = sender.send((sent_at, envelope));
+        if sample % 64 == 63 {
+            tokio::task::yield_now().await;
+        }
+    }
+    drop(sender);
+
+    let mut latencies = Vec::with_capacity(samples * receivers.len());
+    let mut lagged = 0;
+    for receiver in receivers {
+        if let Ok((mut received, skipped)) = receiver.await {
+            latencies.append(&mut received);
+            lagged += skipped;
+        }
+    }
+    (latencies, lagged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn envelope_flattens_tagged_event() {
+        let envelope = ConnectionEventEnvelope {
+            seq: 7,
+            timestamp_ms: 1,
+            connection_id: "conn-1".to_string(),
+            event: ConnectionEvent::TunnelDown {
+                tunnel_id: "t1".to_string(),
+                error: None,
+            },
+        };
+        let value = serde_json::to_value(&envelope).expect("serialize");
+        assert_eq!(value["type"], "tunnel-down");
+        assert_eq!(value["tunnelId"], "t1");
+        assert_eq!(value["connectionId"], "conn-1");
+        assert_eq!(value["seq"], 7);
+    }
+
+    #[test]
+    fn history_is_ordered_and_filtered_by_seq() {
+        let first = record("conn-a", ConnectionEvent::Disconnected).seq;
+        let second = record("conn-a", ConnectionEvent::TransportLost).seq;
+        assert!(second > first);
+        let since = events_since(first);
+        assert!(since.iter().any(|envelope| envelope.seq == second));
+        assert!(since.iter().all(|envelope| envelope.seq > first));
+    }
+}
@@ -0,0 +1,223 @@
+use super::{
+    compile, describe_send, mask_secrets, CompiledStep, ExpectReport, ExpectScript, OutputBuffer,
+    StepOutcome, MAX_TRANSCRIPT_BYTES,
+};
+use crate::commands::{ensure_connection_online, AppState};
+use crate::events::ConnectionEvent;
+use russh::ChannelMsg;
+use std::time::Instant;
+use tauri::{AppHandle, State};
+use tokio::time::{timeout_at, Duration};
+
+/// How long the command may take to exit after the last step.
+const EXIT_GRACE: Duration = Duration::from_secs(10);
+
+/// Run `script.command` on a fresh PTY channel and drive it with the script's
+/// expect/send steps. The command goes through the safety rules like any other
+/// exec; a failed or timed-out step stops the run and is reported, not
+/// returned as an error.
+#[tauri::command]
+pub async fn expect_run(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    connection_id: String,
+    script: ExpectScript,
+    confirmation: Option<String>,
+) -> Result<ExpectReport, String> {
+    let steps = compile(&script)?;
+    ensure_connection_online(&state, &connection_id).await?;
+    crate::safety::enforce(
+        &app,
+        &connection_id,
+        crate::safety::SafetyAction::Exec,
+        &script.command,
+        confirmation.as_deref(),
+    )?;
+
+    let session = {
+        let connections = state.connections.lock().await;
+        connections
+            .get(&connection_id)
+            .and_then(|conn| conn.session.clone())
+            .ok_or_else(|| "Connection not found".to_string())?
+    };
+    let (mut channel, _channel_permit) =
+        crate::session_channels::open_session_channel(&app, &connection_id, &session)
+            .await
+            .map_err(|e| e.to_string())?;
+    channel
+        .request_pty(false, "xterm-256color", 200, 50, 0, 0, &[])
+        .await
+        .map_err(|e| format!("Failed to request PTY: {e}"))?;
+    channel
+        .exec(true, script.command.as_str())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let started = Instant::now();
+    let mut report = ExpectReport::default();
+    let mut buffer = OutputBuffer::new();
+    let mut transcript = String::new();
+    let mut stream = StreamState::default();
+    let secrets: Vec<String> = script
+        .steps
+        .iter()
+        .filter_map(|step| match step {
+            super::ExpectStep::Send { text, secret: true } => Some(text.clone()),
+            _ => None,
+        })
+        .collect();
+
+    for (index, step) in steps.iter().enumerate() {
+        let step_started = Instant::now();
+        let result = match step {
+            CompiledStep::Send { bytes, secret } => {
+                let outcome = StepOutcome {
+                    index,
+                    kind: "send",
+                    detail: describe_send(&String::from_utf8_lossy(bytes), *secret),
+                    matched: None,
+                    elapsed_ms: 0,
+                };
+                if stream.output_done {
+                    Err((outcome, "The command exited before this input".to_string()))
+                } else {
+                    match channel.data(&bytes[..]).await {
+                        Ok(()) => Ok(outcome),
+                        Err(e) => Err((outcome, format!("Failed to send input: {e}"))),
+                    }
+                }
+            }
+            CompiledStep::Expect { regex, timeout } => {
+                let mut outcome = StepOutcome {
+                    index,
+                    kind: "expect",
+                    detail: regex.as_str().to_string(),
+                    matched: None,
+                    elapsed_ms: 0,
+                };
+                let deadline = tokio::time::Instant::now() + *timeout;
+                loop {
+                    if let Some(matched) = buffer.take_match(regex) {
+                        outcome.matched = Some(matched);
+                        break Ok(outcome);
+                    }
+                    if stream.output_done {
+                        break Err((
+                            outcome,
+                            "The command exited before the pattern appeared".to_string(),
+                        ));
+                    }
+                    match timeout_at(deadline, channel.wait()).await {
+                        Err(_) => {
+                            break Err((
+                                outcome,
+                                format!("Timed out after {}s", timeout.as_secs_f32()),
+                            ));
+                        }
+                        Ok(msg) => handle_message(
+                            msg,
+                            &mut buffer,
+                            &mut transcript,
+                            &mut report,
+                            &mut stream,
+                        ),
+                    }
+                }
+            }
+        };
+        match result {
+            Ok(mut outcome) => {
+                outcome.elapsed_ms = step_started.elapsed().as_millis() as u64;
+                report.steps.push(outcome);
+            }
+            Err((mut outcome, error)) => {
+                outcome.elapsed_ms = step_started.elapsed().as_millis() as u64;
+                report.steps.push(outcome);
+                report.failed_step = Some(index);
+                report.error = Some(error);
+                break;
+            }
+        }
+    }
+
+    if report.failed_step.is_none() {
+        let deadline = tokio::time::Instant::now() + EXIT_GRACE;
+        while !stream.closed && !(stream.output_done && report.exit_status.is_some()) {
+            match timeout_at(deadline, channel.wait()).await {
+                Ok(msg) => {
+                    handle_message(msg, &mut buffer, &mut transcript, &mut report, &mut stream)
+                }
+                Err(_) => break,
+            }
+        }
+    }
+    let _ = channel.close().await;
+
+    report.ok = report.failed_step.is_none() && report.exit_status.is_none_or(|code| code == 0);
+    if let (false, None, Some(code)) = (report.ok, &report.error, report.exit_status) {
+        report.error = Some(format!("The command exited with status {code}"));
+    }
+    if transcript.len() > MAX_TRANSCRIPT_BYTES {
+        keep_tail(&mut transcript, MAX_TRANSCRIPT_BYTES);
+        report.transcript_truncated = true;
+    }
+    let redactor = crate::redaction::Redactor::from_app(&app);
+    report.transcript = redactor.redact(&mask_secrets(&transcript, &secrets));
+    for step in report.steps.iter_mut() {
+        step.detail = redactor.redact(&step.detail);
+        step.matched = step.matched.as_deref().map(|text| redactor.redact(text));
+    }
+
+    crate::events::publish(
+        &connection_id,
+        ConnectionEvent::CommandFinished {
+            command: script.command.chars().take(200).collect(),
+            exit_code: report.exit_status.unwrap_or(if report.ok { 0 } else { 1 }),
+            duration_ms: started.elapsed().as_millis() as u64,
+        },
+    );
+    Ok(report)
+}
+
+#[derive(Default)]
+struct StreamState {
+    /// No more output will arrive (EOF or channel closed).
+    output_done: bool,
+    closed: bool,
+}
+
+fn handle_message(
+    msg: Option<ChannelMsg>,
+    buffer: &mut OutputBuffer,
+    transcript: &mut String,
+    report: &mut ExpectReport,
+    stream: &mut StreamState,
+) {
+    match msg {
+        Some(ChannelMsg::Data { ref data }) | Some(ChannelMsg::ExtendedData { ref data, .. }) => {
+            transcript.push_str(&buffer.feed(data));
+            // Bound memory while the command runs; the report keeps only the tail.
+            if transcript.len() > MAX_TRANSCRIPT_BYTES * 2 {
+                keep_tail(transcript, MAX_TRANSCRIPT_BYTES);
+                report.transcript_truncated = true;
+            }
+        }
+        Some(ChannelMsg::ExitStatus { exit_status }) => report.exit_status = Some(exit_status),
+        // The exit status may still follow EOF.
+        Some(ChannelMsg::Eof) => stream.output_done = true,
+        Some(ChannelMsg::Close) | None => {
+            stream.output_done = true;
+            stream.closed = true;
+        }
+        _ => {}
+    }
+}
+
+fn keep_tail(text: &mut String, max_bytes: usize) {
+    let mut cut = text.len().saturating_sub(max_bytes);
+    while !text.is_char_boundary(cut) {
+        cut += 1;
+    }
+    text.drain(..cut);
+}
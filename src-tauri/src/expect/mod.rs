@@ -0,0 +1,327 @@
+//! Expect-style automation for interactive programs (`passwd`, installers,
+//! `mysql_secure_installation`) that prompt on a terminal and can't be fed
+//! through plain exec stdin.
+//!
+//! A script names a command and alternating steps: wait for a regular
+//! expression to appear in the output (with a timeout), send text. The command
+//! runs on its own PTY channel, never in a user's terminal tab. Output is
+//! matched with ANSI escapes removed, and each match consumes the output up to
+//! its end so the next step can't match the same prompt again.
+
+pub mod commands;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub const DEFAULT_EXPECT_TIMEOUT_MS: u64 = 30_000;
+const MAX_EXPECT_TIMEOUT_MS: u64 = 30 * 60 * 1000;
+const MAX_STEPS: usize = 200;
+const MAX_PATTERN_LEN: usize = 1024;
+/// Unmatched output kept for matching; older text can no longer match.
+const MATCH_WINDOW_BYTES: usize = 64 * 1024;
+/// Transcript returned with the report, tail only.
+pub const MAX_TRANSCRIPT_BYTES: usize = 128 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ExpectStep {
+    /// Wait until `pattern` (a regular expression) matches new output.
+    #[serde(rename_all = "camelCase")]
+    Expect {
+        pattern: String,
+        #[serde(default)]
+        timeout_ms: Option<u64>,
+    },
+    /// Send `text` as typed; include `\n` (or `\r`) to press Enter. Secret
+    /// text is masked in the report and transcript.
+    #[serde(rename_all = "camelCase")]
+    Send {
+        text: String,
+        #[serde(default)]
+        secret: bool,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpectScript {
+    pub command: String,
+    pub steps: Vec<ExpectStep>,
+    /// Used by `expect` steps that don't set their own timeout.
+    #[serde(default)]
+    pub default_timeout_ms: Option<u64>,
+}
+
+#[derive(Debug)]
+pub enum CompiledStep {
+    Expect { regex: Regex, timeout: Duration },
+    Send { bytes: Vec<u8>, secret: bool },
+}
+
+/// Validate a script and compile its patterns, so a typo fails before the
+/// command starts rather than halfway through it.
+pub fn compile(script: &ExpectScript) -> Result<Vec<CompiledStep>, String> {
+    if script.command.trim().is_empty() {
+        return Err("Command is empty".to_string());
+    }
+    if script.steps.is_empty() {
+        return Err("Script has no steps".to_string());
+    }
+    if script.steps.len() > MAX_STEPS {
+        return Err(format!("Scripts are limited to {MAX_STEPS} steps"));
+    }
+    let default_timeout = script
+        .default_timeout_ms
+        .unwrap_or(DEFAULT_EXPECT_TIMEOUT_MS);
+    script
+        .steps
+        .iter()
+        .enumerate()
+        .map(|(index, step)| match step {
+            ExpectStep::Expect {
+                pattern,
+                timeout_ms,
+            } => {
+                if pattern.is_empty() || pattern.len() > MAX_PATTERN_LEN {
+                    return Err(format!("Step {}: pattern is empty or too long", index + 1));
+                }
+                let regex = Regex::new(pattern)
+                    .map_err(|e| format!("Step {}: invalid pattern: {e}", index + 1))?;
+                let timeout_ms = timeout_ms
+                    .unwrap_or(default_timeout)
+                    .clamp(100, MAX_EXPECT_TIMEOUT_MS);
+                Ok(CompiledStep::Expect {
+                    regex,
+                    timeout: Duration::from_millis(timeout_ms),
+                })
+            }
+            ExpectStep::Send { text, secret } => Ok(CompiledStep::Send {
+                bytes: text.as_bytes().to_vec(),
+                secret: *secret,
+            }),
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EscapeState {
+    Text,
+    Escape,
+    Csi,
+    /// OSC/DCS strings, ended by BEL or `ESC \`.
+    String,
+    StringEscape,
+}
+
+/// Decoded, escape-free output waiting to be matched.
+pub struct OutputBuffer {
+    text: String,
+    /// Bytes of a UTF-8 character split across reads.
+    partial: Vec<u8>,
+    state: EscapeState,
+}
+
+impl Default for OutputBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OutputBuffer {
+    pub fn new() -> Self {
+        Self {
+            text: String::new(),
+            partial: Vec::new(),
+            state: EscapeState::Text,
+        }
+    }
+
+    /// Append output and return the text it added, escapes removed.
+    pub fn feed(&mut self, bytes: &[u8]) -> String {
+        self.partial.extend_from_slice(bytes);
+        let decoded = match std::str::from_utf8(&self.partial) {
+            Ok(text) => {
+                let text = text.to_string();
+                self.partial.clear();
+                text
+            }
+            Err(error) if error.error_len().is_none() => {
+                // Incomplete trailing character: keep it for the next read.
+                let valid = error.valid_up_to();
+                let text = String::from_utf8_lossy(&self.partial[..valid]).into_owned();
+                self.partial.drain(..valid);
+                text
+            }
+            Err(_) => {
+                let text = String::from_utf8_lossy(&self.partial).into_owned();
+                self.partial.clear();
+                text
+            }
+        };
+        let before = self.text.len();
+        for c in decoded.chars() {
+            self.push_char(c);
+        }
+        let added = self.text[before..].to_string();
+        if self.text.len() > MATCH_WINDOW_BYTES {
+            let mut cut = self.text.len() - MATCH_WINDOW_BYTES;
+            while !self.text.is_char_boundary(cut) {
+                cut += 1;
+            }
+            self.text.drain(..cut);
+        }
+        added
+    }
+
+    fn push_char(&mut self, c: char) {
+        self.state = match (self.state, c) {
+            (EscapeState::Text, '\x1b') => EscapeState::Escape,
+            // Line ends arrive as `\r\n` on a PTY; keep only the `\n`.
+            (EscapeState::Text, '\r') => EscapeState::Text,
+            (EscapeState::Text, c) => {
+                self.text.push(c);
+                EscapeState::Text
+            }
+            (EscapeState::Escape, '[') => EscapeState::Csi,
+            (EscapeState::Escape, ']' | 'P' | '_' | '^') => EscapeState::String,
+            (EscapeState::Escape, _) => EscapeState::Text,
+            (EscapeState::Csi, '\x40'..='\x7e') => EscapeState::Text,
+            (EscapeState::Csi, _) => EscapeState::Csi,
+            (EscapeState::String, '\x07') => EscapeState::Text,
+            (EscapeState::String, '\x1b') => EscapeState::StringEscape,
+            (EscapeState::String, _) => EscapeState::String,
+            (EscapeState::StringEscape, '\\') => EscapeState::Text,
+            (EscapeState::StringEscape, _) => EscapeState::String,
+        };
+    }
+
+    /// Find `regex` in unmatched output. On a match, everything up to its end
+    /// is consumed and the matched text is returned.
+    pub fn take_match(&mut self, regex: &Regex) -> Option<String> {
+        let found = regex.find(&self.text)?;
+        let matched = found.as_str().to_string();
+        let end = found.end();
+        self.text.drain(..end);
+        Some(matched)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepOutcome {
+    pub index: usize,
+    /// `expect` or `send`.
+    pub kind: &'static str,
+    /// The pattern, or the sent text (masked when secret).
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matched: Option<String>,
+    pub elapsed_ms: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpectReport {
+    pub ok: bool,
+    pub steps: Vec<StepOutcome>,
+    /// Zero-based index of the step that failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failed_step: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_status: Option<u32>,
+    /// Output without escape sequences, secrets masked; the tail when it
+    /// grew too long.
+    pub transcript: String,
+    pub transcript_truncated: bool,
+}
+
+/// Visible form of sent text: secrets become bullets, Enter becomes `⏎`.
+pub fn describe_send(text: &str, secret: bool) -> String {
+    if secret {
+        return "••••••".to_string();
+    }
+    text.replace("\r\n", "⏎").replace(['\r', '\n'], "⏎")
+}
+
+/// Replace every echo of a secret in the transcript.
+pub fn mask_secrets(transcript: &str, secrets: &[String]) -> String {
+    secrets
+        .iter()
+        .map(|secret| secret.trim_end_matches(['\r', '\n']))
+        .filter(|secret| !secret.is_empty())
+        .fold(transcript.to_string(), |text, secret| {
+            text.replace(secret, "••••••")
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expect(pattern: &str) -> ExpectStep {
+        ExpectStep::Expect {
+            pattern: pattern.to_string(),
+            timeout_ms: None,
+        }
+    }
+
+    #[test]
+    fn compiles_scripts_and_reports_the_bad_step() {
+        let script = ExpectScript {
+            command: "passwd".to_string(),
+            steps: vec![
+                expect("(?i)current password:"),
+                ExpectStep::Send {
+                    text: "old\n".to_string(),
+                    secret: true,
+                },
+            ],
+            default_timeout_ms: Some(5),
+        };
+        let steps = compile(&script).unwrap();
+        assert!(matches!(
+            &steps[0],
+            CompiledStep::Expect { timeout, .. } if *timeout == Duration::from_millis(100)
+        ));
+
+        let broken = ExpectScript {
+            steps: vec![expect("ok"), expect("(unclosed")],
+            ..script
+        };
+        let error = compile(&broken).unwrap_err();
+        assert!(error.starts_with("Step 2:"), "{error}");
+    }
+
+    #[test]
+    fn matches_across_reads_without_escapes_and_consumes_prompts() {
+        let regex = Regex::new("password: $").unwrap();
+        let mut buffer = OutputBuffer::new();
+        buffer.feed(b"\x1b[1mNew pass");
+        assert_eq!(buffer.take_match(&regex), None);
+        buffer.feed(b"word\x1b[0m: ");
+        assert_eq!(buffer.take_match(&regex).as_deref(), Some("password: "));
+        // The same prompt must not satisfy the next wait.
+        assert_eq!(buffer.take_match(&regex), None);
+
+        let retype = Regex::new("Retype .+: $").unwrap();
+        buffer.feed(b"\r\n\x1b]0;title\x07Retype n\xc3");
+        buffer.feed(b"\xa9w password: ");
+        assert_eq!(
+            buffer.take_match(&retype).as_deref(),
+            Some("Retype néw password: ")
+        );
+    }
+
+    #[test]
+    fn masks_secrets() {
+        assert_eq!(describe_send("yes\n", false), "yes⏎");
+        assert_eq!(describe_send("hunter2\n", true), "••••••");
+        assert_eq!(
+            mask_secrets("pw: hunter2\r\nok", &["hunter2\n".to_string()]),
+            "pw: ••••••\r\nok"
+        );
+    }
+}
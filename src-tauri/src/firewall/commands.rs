@@ -0,0 +1,59 @@
+use super::{check_port, parse_report, FirewallReport, PortCheck, Protocol, READ_SCRIPT};
+use crate::ai::tool_exec_support::shell_quote;
+use crate::commands::{
+    ensure_connection_online, exec_on_connection, exec_on_connection_with_stdin, AppState,
+};
+use crate::host_capabilities::{elevation, Elevation};
+use tauri::State;
+
+/// Runs the read script as root when the host allows it. Without sudo the
+/// script still runs and each tool's permission error lands in the report.
+async fn read_report(
+    state: &AppState,
+    connection_id: &str,
+    sudo_password: Option<String>,
+) -> Result<FirewallReport, String> {
+    ensure_connection_online(state, connection_id).await?;
+    let capabilities = crate::host_capabilities::probe(state, connection_id, false).await?;
+    if capabilities.windows {
+        return Err("Firewall rules can't be read on Windows hosts".to_string());
+    }
+    let elevation = match elevation(&capabilities, sudo_password.is_some()) {
+        Ok(elevation) => elevation,
+        Err(error) if error.starts_with("SUDO_PASSWORD_REQUIRED") => return Err(error),
+        Err(_) => Elevation::None,
+    };
+    let command = format!("{}sh -c {}", elevation.prefix(), shell_quote(READ_SCRIPT));
+    let output = match (elevation, sudo_password) {
+        (Elevation::SudoWithPassword, Some(password)) => {
+            let input = format!("{password}\n");
+            exec_on_connection_with_stdin(state, connection_id, &command, input.as_bytes()).await?
+        }
+        _ => exec_on_connection(state, connection_id, &command).await?,
+    };
+    Ok(parse_report(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// iptables, nftables and ufw rules of a host, read-only.
+#[tauri::command]
+pub async fn firewall_rules(
+    state: State<'_, AppState>,
+    connection_id: String,
+    sudo_password: Option<String>,
+) -> Result<FirewallReport, String> {
+    read_report(&state, &connection_id, sudo_password).await
+}
+
+/// Whether the host's firewall would drop a new connection to `port`, e.g.
+/// before binding a remote forward on it. TCP unless `protocol` says otherwise.
+#[tauri::command]
+pub async fn firewall_check_port(
+    state: State<'_, AppState>,
+    connection_id: String,
+    port: u16,
+    protocol: Option<Protocol>,
+    sudo_password: Option<String>,
+) -> Result<PortCheck, String> {
+    let report = read_report(&state, &connection_id, sudo_password).await?;
+    Ok(check_port(&report, port, protocol.unwrap_or(Protocol::Tcp)))
+}
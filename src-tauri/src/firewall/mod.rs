@@ -0,0 +1,979 @@
+//! Read-only view of a host's packet filter: `ufw status verbose`, `nft list
+//! ruleset` and `iptables-save`, parsed into one rule model, plus a
+//! best-effort answer to "would a new connection to this port be dropped?"
+//! before a remote forward is bound on it.
+//!
+//! Only the input path is evaluated. Rules that depend on the source address,
+//! interface or similar can't be decided for an arbitrary client; the check
+//! skips them and lists them instead of guessing.
+
+pub mod commands;
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+const SECTION_MARKER: &str = "__ZYNC_FW__ ";
+/// User chains jumping to user chains; deeper than this is treated as unknown.
+const MAX_JUMP_DEPTH: usize = 16;
+
+/// Each tool runs only when installed. Reading rules needs root; without it
+/// the tools print their permission error, which the parser reports.
+pub const READ_SCRIPT: &str = r#"PATH=$PATH:/usr/sbin:/sbin
+if command -v ufw >/dev/null 2>&1; then echo "__ZYNC_FW__ ufw"; ufw status verbose 2>&1; fi
+if command -v nft >/dev/null 2>&1; then echo "__ZYNC_FW__ nftables"; nft list ruleset 2>&1; fi
+if command -v iptables-save >/dev/null 2>&1; then echo "__ZYNC_FW__ iptables"; iptables-save 2>&1; fi
+exit 0"#;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FirewallBackend {
+    Ufw,
+    Nftables,
+    Iptables,
+}
+
+impl FirewallBackend {
+    fn from_marker(name: &str) -> Option<Self> {
+        match name {
+            "ufw" => Some(Self::Ufw),
+            "nftables" => Some(Self::Nftables),
+            "iptables" => Some(Self::Iptables),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleAction {
+    Accept,
+    Drop,
+    Reject,
+    /// ufw `LIMIT`: accepted unless the source connects too often.
+    Limit,
+    /// Continues in the chain named by `target`.
+    Jump,
+    Return,
+    /// Logging, counting, NAT and anything else that doesn't decide.
+    Other,
+}
+
+impl RuleAction {
+    fn from_verdict(word: &str) -> Option<Self> {
+        match word.to_ascii_lowercase().as_str() {
+            "accept" | "allow" => Some(Self::Accept),
+            "drop" | "deny" => Some(Self::Drop),
+            "reject" => Some(Self::Reject),
+            "limit" => Some(Self::Limit),
+            "return" => Some(Self::Return),
+            _ => None,
+        }
+    }
+
+    fn admits(self) -> bool {
+        matches!(self, Self::Accept | Self::Limit)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Protocol {
+    Tcp,
+    Udp,
+}
+
+impl Protocol {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Tcp => "tcp",
+            Self::Udp => "udp",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PortRange {
+    pub start: u16,
+    pub end: u16,
+}
+
+impl PortRange {
+    /// `22`, `8000:8100` (iptables, ufw) or `8000-8100` (nft).
+    fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().trim_end_matches(',');
+        let (start, end) = value
+            .split_once(':')
+            .or_else(|| value.split_once('-'))
+            .unwrap_or((value, value));
+        let start = start.parse().ok()?;
+        let end = end.parse().ok()?;
+        (start <= end).then_some(Self { start, end })
+    }
+
+    fn contains(self, port: u16) -> bool {
+        (self.start..=self.end).contains(&port)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FirewallChain {
+    pub backend: FirewallBackend,
+    /// `filter`, `inet filter`, …; empty for ufw.
+    pub table: String,
+    pub name: String,
+    /// Built-in `INPUT`, an nft chain hooked on input, or ufw's incoming rules.
+    pub input: bool,
+    /// `None` for user chains, which fall back to their caller.
+    pub policy: Option<RuleAction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FirewallRule {
+    pub backend: FirewallBackend,
+    pub table: String,
+    pub chain: String,
+    pub action: RuleAction,
+    /// Chain jumped to, for [`RuleAction::Jump`].
+    pub target: Option<String>,
+    /// `None` matches every protocol.
+    pub protocol: Option<String>,
+    /// Destination ports; empty matches every port.
+    pub ports: Vec<PortRange>,
+    /// Matches the port check can't evaluate (source, interface, …).
+    pub conditions: Vec<String>,
+    /// Connection-state match without `new`: never applies to a new connection.
+    pub established_only: bool,
+    /// The line as printed by the tool.
+    pub raw: String,
+}
+
+impl FirewallRule {
+    fn new(backend: FirewallBackend, table: &str, chain: &str, raw: &str) -> Self {
+        Self {
+            backend,
+            table: table.to_string(),
+            chain: chain.to_string(),
+            action: RuleAction::Other,
+            target: None,
+            protocol: None,
+            ports: Vec::new(),
+            conditions: Vec::new(),
+            established_only: false,
+            raw: raw.to_string(),
+        }
+    }
+
+    fn matches(&self, port: u16, protocol: Protocol) -> bool {
+        !self.established_only
+            && self
+                .protocol
+                .as_deref()
+                .is_none_or(|name| name == protocol.name() || name == "all")
+            && (self.ports.is_empty() || self.ports.iter().any(|range| range.contains(port)))
+    }
+
+    fn apply_states(&mut self, states: &str) {
+        self.established_only = !states
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .any(|state| state.eq_ignore_ascii_case("new"));
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FirewallSection {
+    pub backend: FirewallBackend,
+    /// Why the rules couldn't be read, usually missing root.
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FirewallReport {
+    /// One per installed tool.
+    pub sections: Vec<FirewallSection>,
+    pub chains: Vec<FirewallChain>,
+    pub rules: Vec<FirewallRule>,
+    /// `None` when ufw isn't installed or its status couldn't be read.
+    pub ufw_active: Option<bool>,
+}
+
+impl FirewallReport {
+    fn readable(&self, backend: FirewallBackend) -> bool {
+        self.sections
+            .iter()
+            .any(|section| section.backend == backend && section.error.is_none())
+    }
+}
+
+/// Split the read script's output and parse each tool's section.
+pub fn parse_report(output: &str) -> FirewallReport {
+    let mut report = FirewallReport::default();
+    let mut sections: Vec<(FirewallBackend, String)> = Vec::new();
+    for line in output.lines() {
+        if let Some(name) = line.strip_prefix(SECTION_MARKER) {
+            if let Some(backend) = FirewallBackend::from_marker(name.trim()) {
+                sections.push((backend, String::new()));
+            }
+        } else if let Some((_, body)) = sections.last_mut() {
+            body.push_str(line);
+            body.push('\n');
+        }
+    }
+    for (backend, body) in sections {
+        let error = section_error(&body);
+        if error.is_none() {
+            match backend {
+                FirewallBackend::Ufw => parse_ufw(&body, &mut report),
+                FirewallBackend::Nftables => parse_nft(&body, &mut report),
+                FirewallBackend::Iptables => parse_iptables_save(&body, &mut report),
+            }
+        }
+        report.sections.push(FirewallSection { backend, error });
+    }
+    report
+}
+
+/// The tool's first output line when it refused to run.
+fn section_error(body: &str) -> Option<String> {
+    let lower = body.to_ascii_lowercase();
+    let refused = [
+        "permission denied",
+        "operation not permitted",
+        "need to be root",
+        "must be root",
+    ]
+    .iter()
+    .any(|needle| lower.contains(needle));
+    if !refused {
+        return None;
+    }
+    body.lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
+/// Whitespace split that keeps `"quoted comments"` together.
+fn tokenize(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in line.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                current.push(c);
+            }
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+fn parse_iptables_save(body: &str, report: &mut FirewallReport) {
+    let mut table = String::new();
+    let mut declared: HashSet<String> = HashSet::new();
+    for line in body.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix('*') {
+            table = name.to_string();
+            declared.clear();
+        } else if let Some(declaration) = line.strip_prefix(':') {
+            // `:INPUT DROP [0:0]`; user chains have policy `-`.
+            let mut parts = declaration.split_whitespace();
+            let Some(name) = parts.next() else { continue };
+            let policy = parts.next().and_then(RuleAction::from_verdict);
+            declared.insert(name.to_string());
+            report.chains.push(FirewallChain {
+                backend: FirewallBackend::Iptables,
+                table: table.clone(),
+                name: name.to_string(),
+                input: name == "INPUT",
+                policy,
+            });
+        } else if line.starts_with("-A ") {
+            report
+                .rules
+                .push(parse_iptables_rule(line, &table, &declared));
+        }
+    }
+}
+
+fn parse_iptables_rule(line: &str, table: &str, chains: &HashSet<String>) -> FirewallRule {
+    let tokens = tokenize(line);
+    let chain = tokens.get(1).map(String::as_str).unwrap_or_default();
+    let mut rule = FirewallRule::new(FirewallBackend::Iptables, table, chain, line);
+    let mut negated = false;
+    let mut index = 2;
+    while index < tokens.len() {
+        let option = tokens[index].as_str();
+        let value = tokens
+            .get(index + 1)
+            .map(String::as_str)
+            .unwrap_or_default();
+        if option == "!" {
+            negated = true;
+            index += 1;
+            continue;
+        }
+        if negated {
+            rule.conditions.push(format!("! {option} {value}"));
+        } else {
+            match option {
+                "-p" | "--protocol" => rule.protocol = Some(value.to_ascii_lowercase()),
+                "--dport" | "--destination-port" | "--dports" | "--destination-ports" => {
+                    rule.ports
+                        .extend(value.split(',').filter_map(PortRange::parse));
+                }
+                "--ctstate" | "--state" => rule.apply_states(value),
+                "-s" | "--source" | "-d" | "--destination" | "-i" | "--in-interface"
+                | "--sport" | "--source-port" | "--sports" | "--src-range" | "--dst-range" => {
+                    rule.conditions.push(format!("{option} {value}"));
+                }
+                "-j" | "--jump" | "-g" | "--goto" => {
+                    if let Some(action) = RuleAction::from_verdict(value) {
+                        rule.action = action;
+                    } else if chains.contains(value) {
+                        rule.action = RuleAction::Jump;
+                        rule.target = Some(value.to_string());
+                    }
+                }
+                _ => {
+                    // Flags and match modules (`-m tcp`) that don't narrow the check.
+                    if !value.starts_with('-') && option.starts_with('-') {
+                        index += 2;
+                    } else {
+                        index += 1;
+                    }
+                    continue;
+                }
+            }
+        }
+        negated = false;
+        index += 2;
+    }
+    rule
+}
+
+fn parse_nft(body: &str, report: &mut FirewallReport) {
+    let mut table: Option<String> = None;
+    let mut chain: Option<String> = None;
+    // Braces opened inside a table by something other than a chain (sets, maps).
+    let mut other_depth = 0usize;
+    for line in body.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let opens = line.matches('{').count();
+        let closes = line.matches('}').count();
+        if other_depth > 0 {
+            other_depth = (other_depth + opens).saturating_sub(closes);
+            continue;
+        }
+        if line == "}" {
+            if chain.take().is_none() {
+                table = None;
+            }
+            continue;
+        }
+        let Some(current_table) = table.clone() else {
+            if let Some(rest) = line.strip_prefix("table ") {
+                table = Some(rest.trim_end_matches('{').trim().to_string());
+            }
+            continue;
+        };
+        let Some(current_chain) = chain.clone() else {
+            if let Some(rest) = line.strip_prefix("chain ") {
+                let name = rest.trim_end_matches('{').trim().to_string();
+                report.chains.push(FirewallChain {
+                    backend: FirewallBackend::Nftables,
+                    table: current_table,
+                    name: name.clone(),
+                    input: false,
+                    policy: None,
+                });
+                chain = Some(name);
+            } else {
+                other_depth = opens.saturating_sub(closes);
+            }
+            continue;
+        };
+        if line.starts_with("type ") {
+            if let Some(declared) = report.chains.last_mut() {
+                apply_nft_base_chain(line, declared);
+            }
+            continue;
+        }
+        report
+            .rules
+            .push(parse_nft_rule(line, &current_table, &current_chain));
+    }
+}
+
+/// `type filter hook input priority filter; policy drop;`
+fn apply_nft_base_chain(line: &str, chain: &mut FirewallChain) {
+    let words: Vec<&str> = line
+        .split(|c: char| c.is_whitespace() || c == ';')
+        .filter(|word| !word.is_empty())
+        .collect();
+    let after = |key: &str| {
+        words
+            .iter()
+            .position(|word| *word == key)
+            .and_then(|index| words.get(index + 1).copied())
+    };
+    let family = chain.table.split_whitespace().next().unwrap_or_default();
+    chain.input = after("type") == Some("filter")
+        && after("hook") == Some("input")
+        && matches!(family, "ip" | "inet");
+    // Base chains accept by default.
+    chain.policy = Some(
+        after("policy")
+            .and_then(RuleAction::from_verdict)
+            .unwrap_or(RuleAction::Accept),
+    );
+}
+
+fn parse_nft_rule(line: &str, table: &str, chain: &str) -> FirewallRule {
+    let mut rule = FirewallRule::new(FirewallBackend::Nftables, table, chain, line);
+    let tokens = tokenize(line);
+    let mut index = 0;
+    while index < tokens.len() {
+        let word = tokens[index].as_str();
+        let next = tokens
+            .get(index + 1)
+            .map(String::as_str)
+            .unwrap_or_default();
+        match (word, next) {
+            ("tcp" | "udp" | "th", "dport") => {
+                if word != "th" {
+                    rule.protocol = Some(word.to_string());
+                }
+                let (values, used) = nft_values(&tokens[index + 2..]);
+                if values
+                    .iter()
+                    .any(|value| value.starts_with('@') || value == "!=")
+                {
+                    rule.conditions
+                        .push(format!("{word} dport {}", values.join(" ")));
+                } else {
+                    rule.ports
+                        .extend(values.iter().filter_map(|value| PortRange::parse(value)));
+                }
+                index += 2 + used;
+                continue;
+            }
+            ("meta", "l4proto") | ("ip", "protocol") | ("ip6", "nexthdr") => {
+                let (values, used) = nft_values(&tokens[index + 2..]);
+                if let [protocol] = values.as_slice() {
+                    rule.protocol = Some(protocol.to_ascii_lowercase());
+                }
+                index += 2 + used;
+                continue;
+            }
+            ("ct", "state") => {
+                let (values, used) = nft_values(&tokens[index + 2..]);
+                rule.apply_states(&values.join(","));
+                index += 2 + used;
+                continue;
+            }
+            ("tcp" | "udp" | "th", "sport")
+            | ("ip" | "ip6", "saddr" | "daddr")
+            | ("meta", "iif" | "iifname" | "skuid" | "skgid") => {
+                let (values, used) = nft_values(&tokens[index + 2..]);
+                rule.conditions
+                    .push(format!("{word} {next} {}", values.join(" ")));
+                index += 2 + used;
+                continue;
+            }
+            ("iif" | "iifname", _) => {
+                let (values, used) = nft_values(&tokens[index + 1..]);
+                rule.conditions.push(format!("{word} {}", values.join(" ")));
+                index += 1 + used;
+                continue;
+            }
+            ("jump" | "goto", target) => {
+                rule.action = RuleAction::Jump;
+                rule.target = Some(target.to_string());
+                break;
+            }
+            _ => {}
+        }
+        if let Some(action) =
+            RuleAction::from_verdict(word).filter(|action| *action != RuleAction::Limit)
+        {
+            rule.action = action;
+            break;
+        }
+        index += 1;
+    }
+    rule
+}
+
+/// One nft value or an anonymous `{ a, b }` set, with the tokens consumed.
+/// An inequality keeps its `!=` so callers can tell.
+fn nft_values(tokens: &[String]) -> (Vec<String>, usize) {
+    let mut values = Vec::new();
+    let mut used = 0;
+    if tokens.first().is_some_and(|token| token == "!=") {
+        values.push("!=".to_string());
+        used = 1;
+    }
+    match tokens.get(used).map(String::as_str) {
+        Some("{") => {
+            used += 1;
+            for token in &tokens[used..] {
+                used += 1;
+                if token == "}" {
+                    break;
+                }
+                values.extend(
+                    token
+                        .split(',')
+                        .filter(|value| !value.is_empty())
+                        .map(str::to_string),
+                );
+            }
+        }
+        Some(value) => {
+            values.push(value.trim_matches('"').to_string());
+            used += 1;
+        }
+        None => {}
+    }
+    (values, used)
+}
+
+fn parse_ufw(body: &str, report: &mut FirewallReport) {
+    let mut columns: Option<(usize, usize)> = None;
+    for line in body.lines() {
+        if let Some(status) = line.strip_prefix("Status:") {
+            report.ufw_active = Some(status.trim() == "active");
+        } else if let Some(defaults) = line.strip_prefix("Default:") {
+            // `deny (incoming), allow (outgoing), disabled (routed)`
+            let incoming = defaults
+                .split(',')
+                .find(|part| part.contains("(incoming)"))
+                .and_then(|part| part.split_whitespace().next())
+                .and_then(RuleAction::from_verdict);
+            report.chains.push(FirewallChain {
+                backend: FirewallBackend::Ufw,
+                table: String::new(),
+                name: "incoming".to_string(),
+                input: true,
+                policy: incoming,
+            });
+        } else if line.starts_with("To ") {
+            columns = line.find("Action").zip(line.find("From"));
+        } else if let Some((action_at, from_at)) = columns {
+            if line.trim().is_empty() || line.starts_with("--") || line.len() <= action_at {
+                continue;
+            }
+            if let Some(rule) = parse_ufw_rule(line, action_at, from_at) {
+                report.rules.push(rule);
+            }
+        }
+    }
+}
+
+fn parse_ufw_rule(line: &str, action_at: usize, from_at: usize) -> Option<FirewallRule> {
+    let to = line.get(..action_at)?.trim();
+    let action = line.get(action_at..from_at.min(line.len()))?.trim();
+    let from = line.get(from_at..).unwrap_or_default();
+    let from = from.split(" #").next().unwrap_or_default().trim();
+
+    let mut words = action.split_whitespace();
+    let verdict = words.next().and_then(RuleAction::from_verdict)?;
+    let chain = match words.next() {
+        Some("OUT") => "outgoing",
+        Some("FWD") => "routed",
+        _ => "incoming",
+    };
+    let mut rule = FirewallRule::new(FirewallBackend::Ufw, "", chain, line.trim_end());
+    rule.action = verdict;
+
+    let to = to.trim_end_matches("(v6)").trim();
+    let (address, spec) = match to.rsplit_once(' ') {
+        Some((address, spec)) => (Some(address.trim()), spec),
+        None => (None, to),
+    };
+    if let Some(address) = address {
+        rule.conditions.push(format!("to {address}"));
+    }
+    let (ports, protocol) = spec.split_once('/').unwrap_or((spec, ""));
+    if !protocol.is_empty() {
+        rule.protocol = Some(protocol.to_string());
+    }
+    if ports.starts_with("Anywhere") {
+        // Every port.
+    } else if ports.chars().next().is_some_and(|c| c.is_ascii_digit()) {
+        rule.ports
+            .extend(ports.split(',').filter_map(PortRange::parse));
+    } else {
+        // Application profiles list their ports in /etc/ufw/applications.d.
+        rule.conditions.push(format!("application profile {ports}"));
+    }
+    if !from.starts_with("Anywhere") {
+        rule.conditions.push(format!("from {from}"));
+    }
+    Some(rule)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PortVerdict {
+    Open,
+    Blocked,
+    /// Rules couldn't be read or the depth limit was hit.
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortCheck {
+    pub port: u16,
+    pub protocol: Protocol,
+    pub verdict: PortVerdict,
+    pub backend: Option<FirewallBackend>,
+    /// The deciding rule, or `policy <action> (<chain>)` when none matched.
+    pub decided_by: Option<String>,
+    /// Matching rules skipped because they depend on the client or interface;
+    /// any of them could change the outcome for a particular client.
+    pub conditional_rules: Vec<String>,
+}
+
+struct Decision {
+    admits: bool,
+    by: String,
+}
+
+enum Walk {
+    Decided(Decision),
+    /// Fell off the end of the chain or hit `RETURN`.
+    Continue,
+    TooDeep,
+}
+
+struct Evaluator<'a> {
+    report: &'a FirewallReport,
+    port: u16,
+    protocol: Protocol,
+    conditional: Vec<String>,
+}
+
+impl Evaluator<'_> {
+    fn walk(&mut self, backend: FirewallBackend, table: &str, chain: &str, depth: usize) -> Walk {
+        if depth > MAX_JUMP_DEPTH {
+            return Walk::TooDeep;
+        }
+        let report = self.report;
+        let rules = report
+            .rules
+            .iter()
+            .filter(|rule| rule.backend == backend && rule.table == table && rule.chain == chain);
+        for rule in rules {
+            if !rule.matches(self.port, self.protocol) {
+                continue;
+            }
+            if !rule.conditions.is_empty() {
+                if rule.action != RuleAction::Other {
+                    self.conditional.push(rule.raw.clone());
+                }
+                continue;
+            }
+            match rule.action {
+                RuleAction::Accept | RuleAction::Limit | RuleAction::Drop | RuleAction::Reject => {
+                    return Walk::Decided(Decision {
+                        admits: rule.action.admits(),
+                        by: rule.raw.clone(),
+                    });
+                }
+                RuleAction::Jump => {
+                    let target = rule.target.as_deref().unwrap_or_default();
+                    match self.walk(backend, table, target, depth + 1) {
+                        Walk::Continue => {}
+                        decided => return decided,
+                    }
+                }
+                RuleAction::Return => return Walk::Continue,
+                RuleAction::Other => {}
+            }
+        }
+        Walk::Continue
+    }
+
+    /// Walk an input chain, falling back to its policy.
+    fn input_chain(&mut self, chain: &FirewallChain) -> Option<Decision> {
+        match self.walk(chain.backend, &chain.table, &chain.name, 0) {
+            Walk::Decided(decision) => Some(decision),
+            Walk::TooDeep => None,
+            Walk::Continue => {
+                let policy = chain.policy.unwrap_or(RuleAction::Accept);
+                Some(Decision {
+                    admits: policy.admits(),
+                    by: format!(
+                        "policy {} ({})",
+                        format!("{policy:?}").to_lowercase(),
+                        chain.name
+                    ),
+                })
+            }
+        }
+    }
+}
+
+/// Whether a new connection to `port` from an arbitrary client would get
+/// through every readable input chain. ufw is only consulted on its own
+/// when iptables (where ufw keeps its rules) couldn't be read.
+pub fn check_port(report: &FirewallReport, port: u16, protocol: Protocol) -> PortCheck {
+    let mut evaluator = Evaluator {
+        report,
+        port,
+        protocol,
+        conditional: Vec::new(),
+    };
+    let mut backends = Vec::new();
+    if report.readable(FirewallBackend::Iptables) {
+        backends.push(FirewallBackend::Iptables);
+    } else if report.ufw_active == Some(true) {
+        backends.push(FirewallBackend::Ufw);
+    }
+    if report.readable(FirewallBackend::Nftables) {
+        backends.push(FirewallBackend::Nftables);
+    }
+    let unreadable = report
+        .sections
+        .iter()
+        .any(|section| section.error.is_some());
+
+    let mut verdict = if unreadable && backends.is_empty() {
+        PortVerdict::Unknown
+    } else {
+        PortVerdict::Open
+    };
+    let mut decided: Option<(FirewallBackend, String)> = None;
+    let chains = report
+        .chains
+        .iter()
+        .filter(|chain| chain.input && backends.contains(&chain.backend))
+        .filter(|chain| chain.backend != FirewallBackend::Iptables || chain.table == "filter");
+    for chain in chains {
+        match evaluator.input_chain(chain) {
+            Some(decision) if !decision.admits => {
+                verdict = PortVerdict::Blocked;
+                decided = Some((chain.backend, decision.by));
+                break;
+            }
+            Some(decision) => {
+                decided.get_or_insert((chain.backend, decision.by));
+            }
+            None => verdict = PortVerdict::Unknown,
+        }
+    }
+    let (backend, decided_by) = decided.unzip();
+    PortCheck {
+        port,
+        protocol,
+        verdict,
+        backend,
+        decided_by,
+        conditional_rules: evaluator.conditional,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IPTABLES: &str = "# Generated by iptables-save v1.8.7
+*filter
+:INPUT DROP [0:0]
+:FORWARD DROP [0:0]
+:OUTPUT ACCEPT [12:840]
+:ufw-user-input - [0:0]
+-A INPUT -i lo -j ACCEPT
+-A INPUT -m conntrack --ctstate RELATED,ESTABLISHED -j ACCEPT
+-A INPUT -s 10.0.0.0/8 -p tcp -m tcp --dport 5432 -j ACCEPT
+-A INPUT -j ufw-user-input
+-A ufw-user-input -p tcp -m tcp --dport 22 -j ACCEPT
+-A ufw-user-input -p tcp -m multiport --dports 80,443,8000:8100 -m comment --comment \"web apps\" -j ACCEPT
+-A ufw-user-input -p udp -m udp --dport 53 -j REJECT --reject-with icmp-port-unreachable
+COMMIT
+*nat
+:PREROUTING ACCEPT [0:0]
+-A PREROUTING -p tcp --dport 80 -j REDIRECT --to-ports 8080
+COMMIT
+";
+
+    fn report(sections: &[(&str, &str)]) -> FirewallReport {
+        let output: String = sections
+            .iter()
+            .map(|(name, body)| format!("{SECTION_MARKER}{name}\n{body}"))
+            .collect();
+        parse_report(&output)
+    }
+
+    #[test]
+    fn follows_iptables_jumps_and_falls_back_to_the_policy() {
+        let report = report(&[("iptables", IPTABLES)]);
+        let web = report
+            .rules
+            .iter()
+            .find(|rule| rule.raw.contains("web apps"))
+            .unwrap();
+        assert_eq!(web.chain, "ufw-user-input");
+        assert_eq!(web.ports.len(), 3);
+        assert_eq!(
+            web.ports[2],
+            PortRange {
+                start: 8000,
+                end: 8100
+            }
+        );
+
+        let ssh = check_port(&report, 22, Protocol::Tcp);
+        assert_eq!(ssh.verdict, PortVerdict::Open);
+        assert!(ssh.decided_by.unwrap().contains("--dport 22"));
+
+        let tunnel = check_port(&report, 8050, Protocol::Tcp);
+        assert_eq!(tunnel.verdict, PortVerdict::Open);
+
+        let postgres = check_port(&report, 5432, Protocol::Tcp);
+        assert_eq!(postgres.verdict, PortVerdict::Blocked);
+        assert_eq!(postgres.decided_by.as_deref(), Some("policy drop (INPUT)"));
+        // The loopback and source-restricted rules could still let some clients in.
+        assert_eq!(postgres.conditional_rules.len(), 2);
+
+        let dns = check_port(&report, 53, Protocol::Udp);
+        assert_eq!(dns.verdict, PortVerdict::Blocked);
+        assert_eq!(dns.backend, Some(FirewallBackend::Iptables));
+    }
+
+    #[test]
+    fn parses_nft_base_chains_sets_and_anonymous_port_sets() {
+        let ruleset = r#"table inet filter {
+	set blocked {
+		type ipv4_addr
+		elements = { 192.0.2.1, 192.0.2.2 }
+	}
+
+	chain input {
+		type filter hook input priority filter; policy drop;
+		ct state established,related accept
+		ct state invalid drop
+		iifname "lo" accept
+		ip saddr @blocked drop
+		tcp dport { 22, 80, 3000-3010 } counter packets 12 bytes 720 accept
+		udp dport 51820 accept
+		jump services
+	}
+
+	chain services {
+		tcp dport 9000 accept
+	}
+
+	chain forward {
+		type filter hook forward priority filter; policy drop;
+	}
+}
+"#;
+        let report = report(&[("nftables", ruleset)]);
+        let input = report
+            .chains
+            .iter()
+            .find(|chain| chain.name == "input")
+            .unwrap();
+        assert!(input.input);
+        assert_eq!(input.table, "inet filter");
+        assert_eq!(input.policy, Some(RuleAction::Drop));
+        assert!(!report.chains.iter().any(|chain| chain.name == "blocked"));
+        assert_eq!(report.rules.len(), 8);
+
+        assert_eq!(
+            check_port(&report, 3005, Protocol::Tcp).verdict,
+            PortVerdict::Open
+        );
+        assert_eq!(
+            check_port(&report, 9000, Protocol::Tcp).verdict,
+            PortVerdict::Open
+        );
+        let closed = check_port(&report, 8080, Protocol::Tcp);
+        assert_eq!(closed.verdict, PortVerdict::Blocked);
+        assert_eq!(closed.backend, Some(FirewallBackend::Nftables));
+        assert_eq!(closed.decided_by.as_deref(), Some("policy drop (input)"));
+        assert_eq!(
+            closed.conditional_rules,
+            vec!["iifname \"lo\" accept", "ip saddr @blocked drop"]
+        );
+    }
+
+    #[test]
+    fn uses_ufw_when_iptables_needs_root() {
+        let ufw = "Status: active
+Logging: on (low)
+Default: deny (incoming), allow (outgoing), disabled (routed)
+New profiles: skip
+
+To                         Action      From
+--                         ------      ----
+22/tcp                     LIMIT IN    Anywhere
+80,443/tcp                 ALLOW IN    Anywhere                   # web
+8000:8100/tcp              ALLOW IN    203.0.113.0/24
+OpenSSH                    ALLOW IN    Anywhere
+6000                       DENY IN     Anywhere
+22/tcp (v6)                LIMIT IN    Anywhere (v6)
+";
+        let iptables = "iptables-save v1.8.7 (nf_tables): Could not fetch rule set generation id: Permission denied (you must be root)\n";
+        let report = report(&[("ufw", ufw), ("iptables", iptables)]);
+        assert_eq!(report.ufw_active, Some(true));
+        assert_eq!(report.sections[1].error.as_deref(), Some(iptables.trim()));
+        assert_eq!(report.rules.len(), 6);
+        assert_eq!(
+            report.rules[3].conditions,
+            vec!["application profile OpenSSH"]
+        );
+
+        let ssh = check_port(&report, 22, Protocol::Tcp);
+        assert_eq!(ssh.verdict, PortVerdict::Open);
+        assert_eq!(ssh.backend, Some(FirewallBackend::Ufw));
+        assert_eq!(
+            check_port(&report, 443, Protocol::Tcp).verdict,
+            PortVerdict::Open
+        );
+        assert_eq!(
+            check_port(&report, 6000, Protocol::Udp).verdict,
+            PortVerdict::Blocked
+        );
+        let restricted = check_port(&report, 8080, Protocol::Tcp);
+        assert_eq!(restricted.verdict, PortVerdict::Blocked);
+        assert_eq!(restricted.conditional_rules.len(), 2);
+    }
+
+    #[test]
+    fn unreadable_rules_give_an_unknown_verdict() {
+        let report = report(&[(
+            "nftables",
+            "Error: Could not process rule: Operation not permitted\n",
+        )]);
+        assert!(report.rules.is_empty());
+        assert_eq!(
+            check_port(&report, 22, Protocol::Tcp).verdict,
+            PortVerdict::Unknown
+        );
+        // No firewall tooling at all means nothing filters the port.
+        assert_eq!(
+            check_port(&parse_report(""), 22, Protocol::Tcp).verdict,
+            PortVerdict::Open
+        );
+    }
+}
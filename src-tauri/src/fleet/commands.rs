@@ -0,0 +1,225 @@
+use super::push::{
+    clamp_retries, host_transfer_id, is_retryable, retry_delay, PushHostResult, PushReport,
+};
+use super::run::{capture, cluster, HostRunResult, RunReport};
+use super::run_bounded;
+use super::status::{
+    failed_status, parse_status, HostMaintenanceStatus, STATUS_SCRIPT, WINDOWS_STATUS_SCRIPT,
+};
+use crate::commands::{exec_on_connection_cancellable, upload_path, AppState};
+use crate::host_capabilities::{self, invokes_sudo};
+use crate::operations::{self, OperationKind};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Uptime, pending updates and reboot-required flag for each connected host.
+/// Hosts that are not connected or fail the check carry an `error`; after
+/// `cancel_operation(operation_id)` the remaining hosts report `Cancelled`.
+#[tauri::command]
+pub async fn fleet_maintenance_summary(
+    app: AppHandle,
+    connection_ids: Vec<String>,
+    concurrency: Option<usize>,
+    operation_id: Option<String>,
+) -> Result<Vec<HostMaintenanceStatus>, String> {
+    let operation = operations::begin(
+        OperationKind::FleetRun,
+        operation_id,
+        format!("Maintenance check on {} host(s)", connection_ids.len()),
+    );
+    let cancel = operation.token().clone();
+    Ok(run_bounded(&app, connection_ids, concurrency, move |app, connection_id| {
+        let cancel = cancel.clone();
+        async move {
+            let state = app.state::<AppState>();
+            let script = if crate::remote_os::connection_is_windows(&state, &connection_id).await {
+                crate::remote_os::powershell_command(WINDOWS_STATUS_SCRIPT, &[])
+            } else {
+                STATUS_SCRIPT.to_string()
+            };
+            match exec_on_connection_cancellable(&state, &connection_id, &script, &cancel).await {
+                Ok(output) => {
+                    parse_status(&connection_id, &String::from_utf8_lossy(&output.stdout))
+                }
+                Err(error) => failed_status(&connection_id, error),
+            }
+        }
+    })
+    .await)
+}
+
+/// Run `command` on every host, then cluster identical outputs and diff the
+/// divergent ones against the majority. Safety rules apply per host; a host that
+/// needs confirmation reports the usual `CONFIRMATION_REQUIRED:` error.
+/// `cancel_operation(operation_id)` hangs up the commands still running.
+#[tauri::command]
+pub async fn fleet_run_command(
+    app: AppHandle,
+    connection_ids: Vec<String>,
+    command: String,
+    confirmation: Option<String>,
+    concurrency: Option<usize>,
+    operation_id: Option<String>,
+) -> Result<RunReport, String> {
+    if command.trim().is_empty() {
+        return Err("Command is empty".to_string());
+    }
+    let operation = operations::begin(
+        OperationKind::FleetRun,
+        operation_id,
+        format!("`{}` on {} host(s)", command.trim(), connection_ids.len()),
+    );
+    let shared = Arc::new((command, confirmation, operation.token().clone()));
+    let results = run_bounded(&app, connection_ids, concurrency, move |app, connection_id| {
+        let shared = shared.clone();
+        async move {
+            let (command, confirmation, cancel) = &*shared;
+            let started = std::time::Instant::now();
+            let outcome = match crate::safety::enforce(
+                &app,
+                &connection_id,
+                crate::safety::SafetyAction::Exec,
+                command,
+                confirmation.as_deref(),
+            ) {
+                Ok(()) => {
+                    let state = app.state::<AppState>();
+                    let blocker = cancel
+                        .run(async { Ok(sudo_blocker(&state, &connection_id, command).await) });
+                    match blocker.await {
+                        Err(cancelled) => Err(cancelled),
+                        Ok(Some(reason)) => Err(reason),
+                        Ok(None) => {
+                            exec_on_connection_cancellable(&state, &connection_id, command, cancel)
+                                .await
+                        }
+                    }
+                }
+                Err(error) => Err(error),
+            };
+            match outcome {
+                Ok(output) => {
+                    let (stdout, stdout_truncated) = capture(&output.stdout);
+                    let (stderr, stderr_truncated) = capture(&output.stderr);
+                    HostRunResult {
+                        connection_id,
+                        exit_code: Some(output.exit_status),
+                        stdout,
+                        stderr,
+                        duration_ms: started.elapsed().as_millis() as u64,
+                        truncated: stdout_truncated || stderr_truncated,
+                        error: None,
+                    }
+                }
+                Err(error) => HostRunResult {
+                    connection_id,
+                    exit_code: None,
+                    stdout: String::new(),
+                    stderr: String::new(),
+                    duration_ms: started.elapsed().as_millis() as u64,
+                    truncated: false,
+                    error: Some(error),
+                },
+            }
+        }
+    })
+    .await;
+    Ok(cluster(results))
+}
+
+/// Over an exec channel `sudo` can't prompt, so a host without passwordless
+/// sudo would only print "a terminal is required". Say why up front instead.
+async fn sudo_blocker(state: &AppState, connection_id: &str, command: &str) -> Option<String> {
+    if !invokes_sudo(command) {
+        return None;
+    }
+    let capabilities = host_capabilities::probe(state, connection_id, false)
+        .await
+        .ok()?;
+    host_capabilities::requires_sudo(&capabilities).and_then(|blocked| blocked.describe())
+}
+
+/// Upload one local file or directory to `remote_path` on every host, with
+/// per-host progress (`transfer-progress`), retries and a final report. Each
+/// finished host is also announced on `fleet:push-host-finished`. Each host's
+/// upload can be cancelled by its transfer id, the whole push by `push_id`.
+#[tauri::command]
+pub async fn fleet_push(
+    app: AppHandle,
+    connection_ids: Vec<String>,
+    local_path: String,
+    remote_path: String,
+    push_id: String,
+    retries: Option<u32>,
+    concurrency: Option<usize>,
+) -> Result<PushReport, String> {
+    if !std::path::Path::new(&local_path).exists() {
+        return Err(format!("Local path not found: {local_path}"));
+    }
+    let retries = clamp_retries(retries);
+    let push = operations::begin(
+        OperationKind::FleetRun,
+        Some(push_id.clone()),
+        format!("Push {local_path} to {} host(s)", connection_ids.len()),
+    );
+    let shared = Arc::new((local_path, remote_path, push_id.clone(), push.token().clone()));
+    let hosts = run_bounded(&app, connection_ids, concurrency, move |app, connection_id| {
+        let shared = shared.clone();
+        async move {
+            let (local_path, remote_path, push_id, push_cancel) = &*shared;
+            let state = app.state::<AppState>();
+            let transfer_id = host_transfer_id(push_id, &connection_id);
+            let transfer = operations::begin_under(
+                push_cancel,
+                OperationKind::Transfer,
+                Some(transfer_id.clone()),
+                local_path,
+            );
+            let cancel_token = transfer.token().flag();
+
+            let started = std::time::Instant::now();
+            let mut attempts = 0;
+            let outcome = loop {
+                attempts += 1;
+                let result = upload_path(
+                    &app,
+                    &state,
+                    &connection_id,
+                    std::path::Path::new(local_path),
+                    remote_path,
+                    &transfer_id,
+                    &cancel_token,
+                )
+                .await;
+                match result {
+                    Err(error)
+                        if attempts <= retries
+                            && is_retryable(&error)
+                            && !transfer.token().is_cancelled() =>
+                    {
+                        eprintln!(
+                            "[FLEET] Push to {connection_id} failed (attempt {attempts}): {error}"
+                        );
+                        tokio::time::sleep(retry_delay(attempts)).await;
+                    }
+                    other => break other,
+                }
+            };
+            drop(transfer);
+
+            let result = PushHostResult {
+                connection_id,
+                transfer_id,
+                success: outcome.is_ok(),
+                attempts,
+                bytes: *outcome.as_ref().unwrap_or(&0),
+                duration_ms: started.elapsed().as_millis() as u64,
+                error: outcome.err(),
+            };
+            let _ = app.emit("fleet:push-host-finished", &result);
+            result
+        }
+    })
+    .await;
+    Ok(PushReport::new(push_id, hosts))
+}
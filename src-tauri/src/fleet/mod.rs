@@ -0,0 +1,54 @@
+//! Operations that fan out over several established connections at once.
+
+pub mod commands;
+pub mod push;
+pub mod run;
+pub mod status;
+
+use std::future::Future;
+use std::sync::Arc;
+use tauri::AppHandle;
+use tokio::sync::Semaphore;
+
+pub const DEFAULT_CONCURRENCY: usize = 8;
+const MAX_CONCURRENCY: usize = 64;
+
+/// Run `task` once per connection with at most `concurrency` in flight.
+/// Results come back in the order of `connection_ids`.
+pub(crate) async fn run_bounded<T, F, Fut>(
+    app: &AppHandle,
+    connection_ids: Vec<String>,
+    concurrency: Option<usize>,
+    task: F,
+) -> Vec<T>
+where
+    F: Fn(AppHandle, String) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = T> + Send + 'static,
+    T: Send + 'static,
+{
+    let limit = concurrency
+        .unwrap_or(DEFAULT_CONCURRENCY)
+        .clamp(1, MAX_CONCURRENCY);
+    let permits = Arc::new(Semaphore::new(limit));
+    let task = Arc::new(task);
+    let mut tasks = tokio::task::JoinSet::new();
+    for (index, connection_id) in connection_ids.into_iter().enumerate() {
+        let permits = permits.clone();
+        let task = task.clone();
+        let app = app.clone();
+        tasks.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            (index, task(app, connection_id).await)
+        });
+    }
+
+    let mut results = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok(result) => results.push(result),
+            Err(error) => eprintln!("[FLEET] Host task failed: {error}"),
+        }
+    }
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
@@ -0,0 +1,78 @@
+//! Push one local file or directory to many connections.
+//!
+//! Each host gets its own transfer id (`<push id>:<connection id>`) so the
+//! existing `transfer-progress` events and `sftp_cancel_transfer` work per host.
+//! Uploads truncate and rewrite, so a failed attempt can simply be retried.
+
+use serde::Serialize;
+use std::time::Duration;
+
+pub const DEFAULT_RETRIES: u32 = 2;
+const MAX_RETRIES: u32 = 5;
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PushHostResult {
+    pub connection_id: String,
+    pub transfer_id: String,
+    pub success: bool,
+    pub attempts: u32,
+    pub bytes: u64,
+    pub duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PushReport {
+    pub push_id: String,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub hosts: Vec<PushHostResult>,
+}
+
+impl PushReport {
+    pub fn new(push_id: String, hosts: Vec<PushHostResult>) -> Self {
+        let succeeded = hosts.iter().filter(|host| host.success).count();
+        Self {
+            push_id,
+            succeeded,
+            failed: hosts.len() - succeeded,
+            hosts,
+        }
+    }
+}
+
+pub fn host_transfer_id(push_id: &str, connection_id: &str) -> String {
+    format!("{push_id}:{connection_id}")
+}
+
+pub fn clamp_retries(retries: Option<u32>) -> u32 {
+    retries.unwrap_or(DEFAULT_RETRIES).min(MAX_RETRIES)
+}
+
+/// Exponential backoff before retry number `attempt` (1-based).
+pub fn retry_delay(attempt: u32) -> Duration {
+    RETRY_BASE_DELAY * 2u32.saturating_pow(attempt.saturating_sub(1))
+}
+
+/// Cancellation and missing local files will not get better with another attempt.
+pub fn is_retryable(error: &str) -> bool {
+    error != "Cancelled" && !error.starts_with("Local open failed")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_and_retries_are_capped() {
+        assert_eq!(retry_delay(1), Duration::from_secs(1));
+        assert_eq!(retry_delay(3), Duration::from_secs(4));
+        assert_eq!(clamp_retries(Some(50)), MAX_RETRIES);
+        assert!(!is_retryable("Cancelled"));
+        assert!(is_retryable("Failed to open remote file '/etc/x': timeout"));
+    }
+}
@@ -0,0 +1,197 @@
+//! "Run on many": execute one command across hosts, then cluster identical
+//! outputs and diff each divergent cluster against the majority so config
+//! drift stands out.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Per-host output kept for comparison; longer output is truncated.
+pub const MAX_OUTPUT_BYTES: usize = 1024 * 1024;
+/// Outputs longer than this many lines are clustered but not diffed.
+const MAX_DIFF_LINES: usize = 2000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostRunResult {
+    pub connection_id: String,
+    pub exit_code: Option<u32>,
+    pub stdout: String,
+    pub stderr: String,
+    pub duration_ms: u64,
+    pub truncated: bool,
+    /// Set when the command could not run (not connected, blocked by safety rules, ...).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffLine {
+    /// `' '` unchanged, `'-'` only in the majority output, `'+'` only in this cluster.
+    pub op: char,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputCluster {
+    pub connection_ids: Vec<String>,
+    pub exit_code: Option<u32>,
+    pub output: String,
+    pub is_majority: bool,
+    /// Against the majority cluster; `None` for the majority itself or when too large.
+    pub diff: Option<Vec<DiffLine>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunReport {
+    pub results: Vec<HostRunResult>,
+    /// Largest first; hosts that failed to run are not clustered.
+    pub clusters: Vec<OutputCluster>,
+    /// Every host that ran produced the same output and exit code.
+    pub identical: bool,
+}
+
+/// Trailing whitespace and trailing blank lines never count as drift.
+fn normalize(output: &str) -> String {
+    let lines: Vec<&str> = output.lines().map(str::trim_end).collect();
+    let end = lines
+        .iter()
+        .rposition(|line| !line.is_empty())
+        .map_or(0, |last| last + 1);
+    lines[..end].join("\n")
+}
+
+/// Line diff via longest common subsequence.
+pub fn diff_lines(base: &str, other: &str) -> Option<Vec<DiffLine>> {
+    let a: Vec<&str> = base.lines().collect();
+    let b: Vec<&str> = other.lines().collect();
+    if a.len() > MAX_DIFF_LINES || b.len() > MAX_DIFF_LINES {
+        return None;
+    }
+    let mut lcs = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let line = |op: char, text: &str| DiffLine {
+        op,
+        text: text.to_string(),
+    };
+    let (mut i, mut j) = (0, 0);
+    let mut diff = Vec::new();
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            diff.push(line(' ', a[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            diff.push(line('-', a[i]));
+            i += 1;
+        } else {
+            diff.push(line('+', b[j]));
+            j += 1;
+        }
+    }
+    diff.extend(a[i..].iter().map(|text| line('-', text)));
+    diff.extend(b[j..].iter().map(|text| line('+', text)));
+    Some(diff)
+}
+
+pub fn cluster(results: Vec<HostRunResult>) -> RunReport {
+    let mut groups: BTreeMap<(Option<u32>, String), Vec<String>> = BTreeMap::new();
+    for result in results.iter().filter(|result| result.error.is_none()) {
+        groups
+            .entry((result.exit_code, normalize(&result.stdout)))
+            .or_default()
+            .push(result.connection_id.clone());
+    }
+
+    let mut clusters: Vec<OutputCluster> = groups
+        .into_iter()
+        .map(|((exit_code, output), connection_ids)| OutputCluster {
+            connection_ids,
+            exit_code,
+            output,
+            is_majority: false,
+            diff: None,
+        })
+        .collect();
+    // Stable sort keeps the BTreeMap order for ties, so results are deterministic.
+    clusters.sort_by(|a, b| b.connection_ids.len().cmp(&a.connection_ids.len()));
+
+    if let Some((majority, rest)) = clusters.split_first_mut() {
+        majority.is_majority = true;
+        for cluster in rest {
+            cluster.diff = diff_lines(&majority.output, &cluster.output);
+        }
+    }
+
+    RunReport {
+        identical: clusters.len() <= 1,
+        clusters,
+        results,
+    }
+}
+
+/// Decode captured output, truncating at `MAX_OUTPUT_BYTES`.
+pub fn capture(bytes: &[u8]) -> (String, bool) {
+    if bytes.len() <= MAX_OUTPUT_BYTES {
+        return (String::from_utf8_lossy(bytes).into_owned(), false);
+    }
+    (String::from_utf8_lossy(&bytes[..MAX_OUTPUT_BYTES]).into_owned(), true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(id: &str, stdout: &str) -> HostRunResult {
+        HostRunResult {
+            connection_id: id.to_string(),
+            exit_code: Some(0),
+            stdout: stdout.to_string(),
+            stderr: String::new(),
+            duration_ms: 1,
+            truncated: false,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn clusters_by_normalized_output_and_diffs_against_majority() {
+        let report = cluster(vec![
+            result("a", "PermitRootLogin no\nPasswordAuthentication no\n"),
+            result("b", "PermitRootLogin no  \nPasswordAuthentication no\n\n"),
+            result("c", "PermitRootLogin yes\nPasswordAuthentication no\n"),
+            HostRunResult {
+                error: Some("Connection not found".to_string()),
+                ..result("d", "")
+            },
+        ]);
+        assert!(!report.identical);
+        assert_eq!(report.clusters.len(), 2);
+        assert_eq!(report.clusters[0].connection_ids, vec!["a", "b"]);
+        assert!(report.clusters[0].is_majority);
+        let diff = report.clusters[1].diff.as_ref().expect("diff");
+        assert_eq!(
+            diff.iter().map(|line| line.op).collect::<String>(),
+            "-+ "
+        );
+        assert_eq!(diff[1].text, "PermitRootLogin yes");
+    }
+
+    #[test]
+    fn identical_when_single_cluster() {
+        let report = cluster(vec![result("a", "ok"), result("b", "ok\n")]);
+        assert!(report.identical);
+        assert_eq!(report.clusters[0].diff, None);
+    }
+}
@@ -0,0 +1,170 @@
+//! Read-only maintenance checks: uptime, pending package updates and whether
+//! the host asks for a reboot. Nothing here needs root or changes host state.
+
+use crate::host_capabilities::{FeatureAvailability, PACKAGE_TOOLS, WINDOWS_PACKAGE_TOOLS};
+use serde::Serialize;
+
+/// One POSIX `sh` script so each host costs a single exec channel. Prints
+/// `key=value` lines; unknown values are left empty.
+pub const STATUS_SCRIPT: &str = r#"
+echo "uptime_seconds=$(cut -d. -f1 /proc/uptime 2>/dev/null)"
+echo "kernel=$(uname -r 2>/dev/null)"
+echo "os=$( (. /etc/os-release 2>/dev/null && echo "$PRETTY_NAME") || uname -s)"
+if command -v apt-get >/dev/null 2>&1; then
+  echo "package_manager=apt"
+  echo "pending_updates=$(apt-get -s -o Debug::NoLocking=1 upgrade 2>/dev/null | grep -c '^Inst ')"
+elif command -v dnf >/dev/null 2>&1; then
+  echo "package_manager=dnf"
+  echo "pending_updates=$(dnf -q -C check-update 2>/dev/null | grep -c '^[A-Za-z0-9]')"
+elif command -v yum >/dev/null 2>&1; then
+  echo "package_manager=yum"
+  echo "pending_updates=$(yum -q -C check-update 2>/dev/null | grep -c '^[A-Za-z0-9]')"
+elif command -v apk >/dev/null 2>&1; then
+  echo "package_manager=apk"
+  echo "pending_updates=$(apk -u list 2>/dev/null | wc -l)"
+elif command -v opkg >/dev/null 2>&1; then
+  echo "package_manager=opkg"
+  echo "pending_updates=$(opkg list-upgradable 2>/dev/null | wc -l)"
+elif command -v checkupdates >/dev/null 2>&1; then
+  echo "package_manager=pacman"
+  echo "pending_updates=$(checkupdates 2>/dev/null | wc -l)"
+fi
+if [ -f /var/run/reboot-required ]; then
+  echo "reboot_required=1"
+elif command -v needs-restarting >/dev/null 2>&1; then
+  needs-restarting -r >/dev/null 2>&1
+  [ $? -eq 1 ] && echo "reboot_required=1" || echo "reboot_required=0"
+else
+  echo "reboot_required="
+fi
+"#;
+
+/// PowerShell counterpart of [`STATUS_SCRIPT`] for Windows hosts. Pending
+/// updates are left empty: asking Windows Update can take minutes.
+pub const WINDOWS_STATUS_SCRIPT: &str = r#"$os = Get-CimInstance Win32_OperatingSystem
+"platform=windows"
+"uptime_seconds=$([int64]((Get-Date) - $os.LastBootUpTime).TotalSeconds)"
+"kernel=$($os.Version)"
+"os=$($os.Caption)"
+foreach ($pm in 'winget', 'choco') {
+  if (Get-Command $pm -ErrorAction SilentlyContinue) { "package_manager=$pm"; break }
+}
+$pending = (Test-Path 'HKLM:\SOFTWARE\Microsoft\Windows\CurrentVersion\WindowsUpdate\Auto Update\RebootRequired') -or
+  (Test-Path 'HKLM:\SOFTWARE\Microsoft\Windows\CurrentVersion\Component Based Servicing\RebootPending')
+"reboot_required=$(if ($pending) { 1 } else { 0 })"
+"#;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostMaintenanceStatus {
+    pub connection_id: String,
+    pub uptime_seconds: Option<u64>,
+    pub kernel: Option<String>,
+    pub os: Option<String>,
+    pub package_manager: Option<String>,
+    pub pending_updates: Option<u32>,
+    /// `tool-missing` when no supported package manager was found, so the UI
+    /// can say so instead of showing an empty count.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package_status: Option<FeatureAvailability>,
+    pub reboot_required: Option<bool>,
+    /// Pending updates or a requested reboot.
+    pub needs_attention: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+pub fn parse_status(connection_id: &str, output: &str) -> HostMaintenanceStatus {
+    let mut status = HostMaintenanceStatus {
+        connection_id: connection_id.to_string(),
+        ..Default::default()
+    };
+    let mut windows = false;
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        if value.is_empty() {
+            continue;
+        }
+        match key.trim() {
+            "platform" => windows = value == "windows",
+            "uptime_seconds" => status.uptime_seconds = value.parse().ok(),
+            "kernel" => status.kernel = Some(value.to_string()),
+            "os" => status.os = Some(value.to_string()),
+            "package_manager" => status.package_manager = Some(value.to_string()),
+            "pending_updates" => status.pending_updates = value.parse().ok(),
+            "reboot_required" => status.reboot_required = Some(value == "1"),
+            _ => {}
+        }
+    }
+    status.package_status = Some(if status.package_manager.is_some() {
+        FeatureAvailability::Available
+    } else {
+        FeatureAvailability::ToolMissing {
+            tools: if windows {
+                WINDOWS_PACKAGE_TOOLS
+            } else {
+                PACKAGE_TOOLS
+            }
+            .iter()
+            .map(|tool| tool.to_string())
+            .collect(),
+        }
+    });
+    status.needs_attention =
+        status.reboot_required == Some(true) || status.pending_updates.is_some_and(|count| count > 0);
+    status
+}
+
+pub fn failed_status(connection_id: &str, error: String) -> HostMaintenanceStatus {
+    HostMaintenanceStatus {
+        connection_id: connection_id.to_string(),
+        error: Some(error),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_debian_host_needing_reboot() {
+        let output = "uptime_seconds=864000\nkernel=6.1.0-18-amd64\nos=Debian GNU/Linux 12 (bookworm)\npackage_manager=apt\npending_updates=7\nreboot_required=1\n";
+        let status = parse_status("c1", output);
+        assert_eq!(status.uptime_seconds, Some(864_000));
+        assert_eq!(status.pending_updates, Some(7));
+        assert_eq!(status.reboot_required, Some(true));
+        assert_eq!(status.os.as_deref(), Some("Debian GNU/Linux 12 (bookworm)"));
+        assert!(status.needs_attention);
+    }
+
+    #[test]
+    fn parses_windows_status() {
+        let output = "platform=windows\r\nuptime_seconds=3600\r\nkernel=10.0.20348\r\nos=Microsoft Windows Server 2022 Standard\r\nreboot_required=1\r\n";
+        let status = parse_status("w1", output);
+        assert_eq!(status.kernel.as_deref(), Some("10.0.20348"));
+        assert_eq!(status.reboot_required, Some(true));
+        assert_eq!(
+            status.package_status,
+            Some(FeatureAvailability::ToolMissing {
+                tools: vec!["winget".to_string(), "choco".to_string()]
+            })
+        );
+    }
+
+    #[test]
+    fn unknown_values_stay_empty() {
+        let status = parse_status("c1", "uptime_seconds=\nkernel=23.4.0\nos=Darwin\nreboot_required=\n");
+        assert_eq!(status.uptime_seconds, None);
+        assert_eq!(status.pending_updates, None);
+        assert_eq!(status.reboot_required, None);
+        assert!(!status.needs_attention);
+        assert!(matches!(
+            status.package_status,
+            Some(FeatureAvailability::ToolMissing { .. })
+        ));
+    }
+}
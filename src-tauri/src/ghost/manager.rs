@@ -236,6 +236,47 @@ impl GhostManager {
         data.imported_scopes.contains(&scope_key)
     }
 
+    /// Fold the `from` scope into `into` (used when merging duplicate connections).
+    /// Commands already known in `into` keep their score; returns the number moved.
+    pub async fn merge_scope(&self, from: &str, into: &str) -> usize {
+        let from_key = Self::normalize_scope(Some(from));
+        let into_key = Self::normalize_scope(Some(into));
+        if from_key == into_key {
+            return 0;
+        }
+        let mut data = self.data.lock().await;
+        let Some(source) = data.scopes.remove(&from_key) else {
+            return 0;
+        };
+        let was_imported = data.imported_scopes.remove(&from_key);
+
+        let target = data.scopes.entry(into_key.clone()).or_default();
+        let mut known: HashSet<String> = target.history.iter().cloned().collect();
+        let mut moved = 0;
+        for command in source.history {
+            if !known.insert(command.clone()) {
+                continue;
+            }
+            if let Some(score) = source.scores.get(&command) {
+                target.scores.insert(command.clone(), score.clone());
+            }
+            target.history.push(command);
+            moved += 1;
+        }
+        if target.history.len() > MAX_HISTORY {
+            target.history.truncate(MAX_HISTORY);
+            Self::prune_evicted_scores(target);
+        }
+        if was_imported {
+            data.imported_scopes.insert(into_key);
+        }
+
+        let snapshot = data.clone();
+        drop(data);
+        self.save_inner(&snapshot).await;
+        moved
+    }
+
     /// One-time seed from parsed remote shell history (P7). Never logs command text.
     pub async fn seed_shell_history(&self, scope: Option<&str>, commands: &[String]) -> u32 {
         let scope_key = Self::normalize_scope(scope);
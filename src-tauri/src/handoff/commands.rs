@@ -0,0 +1,83 @@
+use super::{apply_recipe, build_recipe, decode_token, encode_token, HandoffImport, SessionRecipe};
+use crate::commands::get_data_dir;
+use crate::sync::domain_hosts::{
+    load_saved_data, save_saved_data_atomic, CONNECTIONS_MUTATION_LOCK,
+};
+use crate::sync::domain_tunnels::{
+    load_saved_tunnels, write_saved_tunnels_atomic, TUNNELS_MUTATION_LOCK,
+};
+use serde::Serialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HandoffExport {
+    pub token: String,
+    pub recipe: SessionRecipe,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Pack `connection_id` with its tabs from the last saved session and its
+/// saved tunnels into a handoff token.
+#[tauri::command]
+pub async fn handoff_export(
+    app: AppHandle,
+    connection_id: String,
+) -> Result<HandoffExport, String> {
+    let data_dir = get_data_dir(&app);
+    let data = load_saved_data(&data_dir.join("connections.json")).map_err(|e| e.to_string())?;
+    let tunnels = load_saved_tunnels(&data_dir.join("tunnels.json")).map_err(|e| e.to_string())?;
+    let session = crate::session::read_session(&app);
+    let recipe = build_recipe(
+        &connection_id,
+        &data.connections,
+        session.as_ref(),
+        &tunnels.tunnels,
+        now_ms(),
+    )?;
+    let token = encode_token(&recipe)?;
+    Ok(HandoffExport { token, recipe })
+}
+
+/// Decode a token without importing it, so the user can see what it holds.
+#[tauri::command]
+pub async fn handoff_preview(token: String) -> Result<SessionRecipe, String> {
+    decode_token(&token)
+}
+
+/// Save the connections and tunnels a token describes. Opening the terminals
+/// is left to the frontend.
+#[tauri::command]
+pub async fn handoff_import(app: AppHandle, token: String) -> Result<HandoffImport, String> {
+    let recipe = decode_token(&token)?;
+    let data_dir = get_data_dir(&app);
+    let now = now_ms();
+
+    let _hosts_guard = CONNECTIONS_MUTATION_LOCK
+        .lock()
+        .map_err(|e| e.to_string())?;
+    let _tunnels_guard = TUNNELS_MUTATION_LOCK.lock().map_err(|e| e.to_string())?;
+    let hosts_path = data_dir.join("connections.json");
+    let tunnels_path = data_dir.join("tunnels.json");
+    let mut data = load_saved_data(&hosts_path).map_err(|e| e.to_string())?;
+    let mut tunnels = load_saved_tunnels(&tunnels_path).map_err(|e| e.to_string())?;
+
+    let imported = apply_recipe(&recipe, &mut data, &mut tunnels, now);
+    // Connections first: a tunnel must never point at a connection that
+    // wasn't saved.
+    if imported.connections_created > 0 {
+        save_saved_data_atomic(&hosts_path, &data).map_err(|e| e.to_string())?;
+        crate::smart_groups::publish_membership(&app, &data);
+    }
+    if imported.tunnels_added > 0 {
+        write_saved_tunnels_atomic(&tunnels_path, &tunnels).map_err(|e| e.to_string())?;
+    }
+    Ok(imported)
+}
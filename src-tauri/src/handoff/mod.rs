@@ -0,0 +1,491 @@
+//! Session handoff between devices: a "session recipe" — one connection, its
+//! jump hosts, open terminal tabs with their working directories and saved
+//! tunnels — packed into a token another Zync instance imports to get the
+//! same working state.
+//!
+//! Recipes never carry secrets: no passwords, key paths or vault references.
+//! The importing machine authenticates with its own keys. Tokens are
+//! `zync-handoff1.<base64url JSON>.<checksum>` and capped at a length that
+//! still fits a QR code.
+
+pub mod commands;
+
+use crate::session::SessionData;
+use crate::types::{SavedConnection, SavedData, SavedTunnel, SavedTunnelsData};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+
+pub const TOKEN_PREFIX: &str = "zync-handoff1.";
+/// Byte-mode capacity of a version 40 QR code at low error correction is 2953.
+pub const MAX_TOKEN_LEN: usize = 2900;
+/// Hex digits of the payload's SHA-256 appended to catch truncated pastes.
+const CHECKSUM_LEN: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeEndpoint {
+    pub name: String,
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+}
+
+impl RecipeEndpoint {
+    fn from_saved(connection: &SavedConnection) -> Self {
+        Self {
+            name: connection.name.clone(),
+            host: connection.host.clone(),
+            port: connection.port,
+            username: connection.username.clone(),
+        }
+    }
+
+    fn matches(&self, connection: &SavedConnection) -> bool {
+        self.host.eq_ignore_ascii_case(&connection.host)
+            && self.port == connection.port
+            && self.username == connection.username
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeTerminal {
+    pub title: String,
+    pub cwd: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shell_override: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeTunnel {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub tunnel_type: String,
+    pub local_port: u16,
+    pub remote_host: String,
+    pub remote_port: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bind_address: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bind_to_any: Option<bool>,
+}
+
+impl RecipeTunnel {
+    fn matches(&self, tunnel: &SavedTunnel) -> bool {
+        self.tunnel_type == tunnel.tunnel_type
+            && self.local_port == tunnel.local_port
+            && self.remote_host == tunnel.remote_host
+            && self.remote_port == tunnel.remote_port
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionRecipe {
+    pub created_at_ms: u64,
+    pub connection: RecipeEndpoint,
+    /// The connection's jump host first, then that host's jump host, and so on.
+    #[serde(default)]
+    pub jump_chain: Vec<RecipeEndpoint>,
+    #[serde(default)]
+    pub terminals: Vec<RecipeTerminal>,
+    /// Index into `terminals`.
+    #[serde(default)]
+    pub active_terminal: Option<usize>,
+    /// View of the connection's sidebar tab (`terminal`, `files`, …).
+    #[serde(default)]
+    pub view: Option<String>,
+    #[serde(default)]
+    pub tunnels: Vec<RecipeTunnel>,
+}
+
+/// Assemble the recipe for `connection_id` from saved connections, the saved
+/// session (tabs and working directories) and saved tunnels.
+pub fn build_recipe(
+    connection_id: &str,
+    connections: &[SavedConnection],
+    session: Option<&SessionData>,
+    tunnels: &[SavedTunnel],
+    created_at_ms: u64,
+) -> Result<SessionRecipe, String> {
+    let find = |id: &str| connections.iter().find(|connection| connection.id == id);
+    let connection =
+        find(connection_id).ok_or_else(|| format!("Connection {connection_id} not found"))?;
+
+    let mut jump_chain = Vec::new();
+    let mut seen = HashSet::from([connection.id.as_str()]);
+    let mut next = connection.jump_server_id.as_deref();
+    while let Some(id) = next.filter(|id| !id.is_empty()) {
+        if !seen.insert(id) {
+            return Err("Jump host chain loops back on itself".to_string());
+        }
+        let hop = find(id).ok_or_else(|| format!("Jump host {id} no longer exists"))?;
+        jump_chain.push(RecipeEndpoint::from_saved(hop));
+        next = hop.jump_server_id.as_deref();
+    }
+
+    let saved_terminals = session
+        .and_then(|session| session.terminals.get(connection_id))
+        .cloned()
+        .unwrap_or_default();
+    let active_id = session.and_then(|session| session.active_terminal_ids.get(connection_id));
+    let active_terminal =
+        active_id.and_then(|active| saved_terminals.iter().position(|tab| &tab.id == active));
+    let terminals = saved_terminals
+        .into_iter()
+        .map(|tab| RecipeTerminal {
+            title: tab.title,
+            cwd: tab.cwd.or(tab.initial_path),
+            shell_override: tab.shell_override,
+        })
+        .collect();
+    let view = session.and_then(|session| {
+        session
+            .tabs
+            .iter()
+            .find(|tab| tab.connection_id.as_deref() == Some(connection_id))
+            .map(|tab| tab.view.clone())
+    });
+
+    Ok(SessionRecipe {
+        created_at_ms,
+        connection: RecipeEndpoint::from_saved(connection),
+        jump_chain,
+        terminals,
+        active_terminal,
+        view,
+        tunnels: tunnels
+            .iter()
+            .filter(|tunnel| tunnel.connection_id == connection_id)
+            .map(|tunnel| RecipeTunnel {
+                name: tunnel.name.clone(),
+                tunnel_type: tunnel.tunnel_type.clone(),
+                local_port: tunnel.local_port,
+                remote_host: tunnel.remote_host.clone(),
+                remote_port: tunnel.remote_port,
+                bind_address: tunnel.bind_address.clone(),
+                bind_to_any: tunnel.bind_to_any,
+            })
+            .collect(),
+    })
+}
+
+fn checksum(payload: &str) -> String {
+    Sha256::digest(payload.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .take(CHECKSUM_LEN / 2)
+        .collect()
+}
+
+pub fn encode_token(recipe: &SessionRecipe) -> Result<String, String> {
+    let json = serde_json::to_vec(recipe).map_err(|e| e.to_string())?;
+    let payload = URL_SAFE_NO_PAD.encode(json);
+    let token = format!("{TOKEN_PREFIX}{payload}.{}", checksum(&payload));
+    if token.len() > MAX_TOKEN_LEN {
+        return Err(format!(
+            "The session is too large to hand off ({} of {MAX_TOKEN_LEN} characters); close some tabs and try again",
+            token.len()
+        ));
+    }
+    Ok(token)
+}
+
+pub fn decode_token(token: &str) -> Result<SessionRecipe, String> {
+    // QR scanners and chat apps like to add whitespace and line breaks.
+    let token: String = token.chars().filter(|c| !c.is_whitespace()).collect();
+    let body = token
+        .strip_prefix(TOKEN_PREFIX)
+        .ok_or("Not a Zync handoff token")?;
+    let (payload, sum) = body
+        .rsplit_once('.')
+        .ok_or("The handoff token is incomplete")?;
+    if checksum(payload) != sum {
+        return Err("The handoff token is damaged or incomplete".to_string());
+    }
+    let json = URL_SAFE_NO_PAD
+        .decode(payload)
+        .map_err(|_| "The handoff token is damaged".to_string())?;
+    serde_json::from_slice(&json).map_err(|e| format!("Unsupported handoff token: {e}"))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HandoffImport {
+    /// Saved connection the recipe resolved to, existing or created.
+    pub connection_id: String,
+    /// Connections (including jump hosts) that had to be created.
+    pub connections_created: usize,
+    pub tunnels_added: usize,
+    /// For the frontend to reopen, as in a restored session.
+    pub terminals: Vec<RecipeTerminal>,
+    pub active_terminal: Option<usize>,
+    pub view: Option<String>,
+}
+
+fn new_connection(
+    endpoint: &RecipeEndpoint,
+    jump_server_id: Option<String>,
+    now: u64,
+) -> SavedConnection {
+    SavedConnection {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: endpoint.name.clone(),
+        host: endpoint.host.clone(),
+        port: endpoint.port,
+        username: endpoint.username.clone(),
+        password: None,
+        private_key_path: None,
+        jump_server_id,
+        last_connected: None,
+        icon: None,
+        folder: None,
+        theme: None,
+        tags: None,
+        created_at: Some(now),
+        is_favorite: None,
+        pinned_features: None,
+        auth_ref: None,
+        strict_host_key_checking: None,
+        auto_connect: None,
+        encoding: None,
+        locale: None,
+        ssh_options: None,
+        host_key_alias: None,
+        user_known_hosts_file: None,
+        secret_ref: None,
+        session_lifetime: None,
+    }
+}
+
+/// Reuse saved connections with the same user, host and port; create the rest,
+/// farthest jump host first so each new connection can point at its hop.
+/// Existing connections are left as they are. Tunnels already saved on the
+/// connection aren't added twice.
+pub fn apply_recipe(
+    recipe: &SessionRecipe,
+    data: &mut SavedData,
+    tunnels: &mut SavedTunnelsData,
+    now: u64,
+) -> HandoffImport {
+    let mut connections_created = 0;
+    let mut previous: Option<String> = None;
+    for endpoint in recipe
+        .jump_chain
+        .iter()
+        .rev()
+        .chain(std::iter::once(&recipe.connection))
+    {
+        let id = match data
+            .connections
+            .iter()
+            .find(|saved| endpoint.matches(saved))
+        {
+            Some(existing) => existing.id.clone(),
+            None => {
+                let created = new_connection(endpoint, previous.take(), now);
+                connections_created += 1;
+                let id = created.id.clone();
+                data.connections.push(created);
+                id
+            }
+        };
+        previous = Some(id);
+    }
+    let connection_id = previous.unwrap_or_default();
+
+    let mut tunnels_added = 0;
+    for tunnel in &recipe.tunnels {
+        let exists = tunnels
+            .tunnels
+            .iter()
+            .any(|saved| saved.connection_id == connection_id && tunnel.matches(saved));
+        if exists {
+            continue;
+        }
+        tunnels.tunnels.push(SavedTunnel {
+            id: uuid::Uuid::new_v4().to_string(),
+            connection_id: connection_id.clone(),
+            name: tunnel.name.clone(),
+            tunnel_type: tunnel.tunnel_type.clone(),
+            local_port: tunnel.local_port,
+            remote_host: tunnel.remote_host.clone(),
+            remote_port: tunnel.remote_port,
+            bind_address: tunnel.bind_address.clone(),
+            bind_to_any: tunnel.bind_to_any,
+            auto_start: None,
+            status: None,
+            original_port: None,
+            group: None,
+            created_at: Some(now),
+            updated_at: Some(now),
+            web: None,
+            pac_domains: None,
+            system_proxy: None,
+            schedule: None,
+        });
+        tunnels_added += 1;
+    }
+
+    HandoffImport {
+        connection_id,
+        connections_created,
+        tunnels_added,
+        terminals: recipe.terminals.clone(),
+        active_terminal: recipe.active_terminal,
+        view: recipe.view.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::{TabSnapshot, TerminalTabSnapshot};
+    use std::collections::HashMap;
+
+    fn saved(id: &str, host: &str, jump: Option<&str>) -> SavedConnection {
+        let endpoint = RecipeEndpoint {
+            name: host.to_string(),
+            host: host.to_string(),
+            port: 22,
+            username: "deploy".to_string(),
+        };
+        SavedConnection {
+            id: id.to_string(),
+            password: Some("hunter2".to_string()),
+            private_key_path: Some("/home/me/.ssh/id_ed25519".to_string()),
+            ..new_connection(&endpoint, jump.map(str::to_string), 0)
+        }
+    }
+
+    fn tunnel(connection_id: &str, local_port: u16) -> SavedTunnel {
+        SavedTunnel {
+            id: format!("t{local_port}"),
+            connection_id: connection_id.to_string(),
+            name: "db".to_string(),
+            tunnel_type: "local".to_string(),
+            local_port,
+            remote_host: "127.0.0.1".to_string(),
+            remote_port: 5432,
+            auto_start: Some(true),
+            ..Default::default()
+        }
+    }
+
+    fn session() -> SessionData {
+        SessionData {
+            tabs: vec![TabSnapshot {
+                id: "tab".to_string(),
+                tab_type: "connection".to_string(),
+                title: "app".to_string(),
+                connection_id: Some("app".to_string()),
+                view: "files".to_string(),
+            }],
+            terminals: HashMap::from([(
+                "app".to_string(),
+                vec![
+                    TerminalTabSnapshot {
+                        id: "t1".to_string(),
+                        title: "shell".to_string(),
+                        cwd: Some("/srv/app".to_string()),
+                        ..Default::default()
+                    },
+                    TerminalTabSnapshot {
+                        id: "t2".to_string(),
+                        title: "logs".to_string(),
+                        initial_path: Some("/var/log".to_string()),
+                        ..Default::default()
+                    },
+                ],
+            )]),
+            active_terminal_ids: HashMap::from([("app".to_string(), "t2".to_string())]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn recipe_round_trips_through_a_token_without_secrets() {
+        let connections = vec![
+            saved("bastion", "bastion.example.com", None),
+            saved("app", "app.internal", Some("bastion")),
+        ];
+        let recipe = build_recipe(
+            "app",
+            &connections,
+            Some(&session()),
+            &[tunnel("app", 15432), tunnel("other", 1)],
+            7,
+        )
+        .unwrap();
+        assert_eq!(recipe.jump_chain[0].host, "bastion.example.com");
+        assert_eq!(recipe.terminals[0].cwd.as_deref(), Some("/srv/app"));
+        assert_eq!(recipe.terminals[1].cwd.as_deref(), Some("/var/log"));
+        assert_eq!(recipe.active_terminal, Some(1));
+        assert_eq!(recipe.view.as_deref(), Some("files"));
+        assert_eq!(recipe.tunnels.len(), 1);
+
+        let token = encode_token(&recipe).unwrap();
+        assert!(token.starts_with(TOKEN_PREFIX));
+        let json = String::from_utf8(
+            URL_SAFE_NO_PAD
+                .decode(token[TOKEN_PREFIX.len()..].rsplit_once('.').unwrap().0)
+                .unwrap(),
+        )
+        .unwrap();
+        assert!(!json.contains("hunter2") && !json.contains("id_ed25519"));
+
+        let wrapped = format!("{}\n{}", &token[..40], &token[40..]);
+        assert_eq!(decode_token(&wrapped).unwrap(), recipe);
+        assert!(decode_token(&token[..token.len() - 3]).is_err());
+        assert!(decode_token("hello").is_err());
+    }
+
+    #[test]
+    fn import_reuses_matching_connections_and_creates_the_rest() {
+        let source = vec![
+            saved("bastion", "bastion.example.com", None),
+            saved("app", "app.internal", Some("bastion")),
+        ];
+        let recipe =
+            build_recipe("app", &source, Some(&session()), &[tunnel("app", 15432)], 7).unwrap();
+
+        let mut data = SavedData {
+            connections: vec![saved("local-bastion", "BASTION.example.com", None)],
+            folders: Vec::new(),
+        };
+        let mut tunnels = SavedTunnelsData {
+            tunnels: Vec::new(),
+        };
+        let imported = apply_recipe(&recipe, &mut data, &mut tunnels, 9);
+        assert_eq!(imported.connections_created, 1);
+        assert_eq!(imported.tunnels_added, 1);
+        let app = data
+            .connections
+            .iter()
+            .find(|connection| connection.id == imported.connection_id)
+            .unwrap();
+        assert_eq!(app.host, "app.internal");
+        assert_eq!(app.jump_server_id.as_deref(), Some("local-bastion"));
+        assert_eq!(app.password, None);
+        assert_eq!(tunnels.tunnels[0].connection_id, imported.connection_id);
+        assert_eq!(tunnels.tunnels[0].auto_start, None);
+
+        // Importing again changes nothing.
+        let again = apply_recipe(&recipe, &mut data, &mut tunnels, 10);
+        assert_eq!(again.connection_id, imported.connection_id);
+        assert_eq!((again.connections_created, again.tunnels_added), (0, 0));
+    }
+
+    #[test]
+    fn refuses_broken_jump_chains() {
+        let looped = vec![saved("a", "a", Some("b")), saved("b", "b", Some("a"))];
+        assert!(build_recipe("a", &looped, None, &[], 0).is_err());
+        let dangling = vec![saved("a", "a", Some("gone"))];
+        assert!(build_recipe("a", &dangling, None, &[], 0).is_err());
+        assert!(build_recipe("missing", &dangling, None, &[], 0).is_err());
+    }
+}
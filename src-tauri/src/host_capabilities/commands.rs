@@ -0,0 +1,34 @@
+use super::{features, FeatureReport, HostCapabilities};
+use crate::commands::{ensure_connection_online, AppState};
+use serde::Serialize;
+use tauri::{AppHandle, State};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostCapabilityReport {
+    #[serde(flatten)]
+    pub capabilities: HostCapabilities,
+    pub features: Vec<FeatureReport>,
+}
+
+/// Privileges, available tools and the resulting feature statuses of a host.
+/// Cached per connection; `refresh` probes again (e.g. after sudoers changed).
+#[tauri::command]
+pub async fn host_capabilities(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    connection_id: String,
+    refresh: Option<bool>,
+) -> Result<HostCapabilityReport, String> {
+    ensure_connection_online(&state, &connection_id).await?;
+    let tags = crate::profiles::load_saved_connection(&app, &connection_id)
+        .and_then(|connection| connection.tags)
+        .unwrap_or_default();
+    let capabilities = super::probe(&state, &connection_id, refresh.unwrap_or(false))
+        .await?
+        .with_tags(&tags);
+    Ok(HostCapabilityReport {
+        features: features(&capabilities),
+        capabilities,
+    })
+}
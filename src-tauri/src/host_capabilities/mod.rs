@@ -0,0 +1,484 @@
+//! What the remote user is allowed to do, and with which tools.
+//!
+//! One probe per host records the uid, whether `sudo` works without a prompt
+//! and which admin tools are on `PATH`. Subsystems ask [`feature`] before
+//! running anything privileged and surface `requires-sudo` / `tool-missing`
+//! instead of forwarding whatever error the command would have printed.
+//! Results are cached per connection for [`CACHE_TTL`]. Windows hosts are
+//! probed with PowerShell; there an Administrator account stands in for sudo.
+//!
+//! Routers, NAS boxes and other embedded devices usually ship BusyBox applets
+//! in place of coreutils/procps. The probe detects that and reports the
+//! [`HostProfile::Constrained`] profile (a connection tagged `embedded` gets it
+//! too), so callers stick to POSIX flags and skip features whose binaries are
+//! missing.
+
+pub mod commands;
+
+use crate::commands::{exec_on_connection, AppState};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex as StdMutex};
+use std::time::{Duration, Instant};
+
+pub const CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Tools the dependent subsystems look for.
+const PROBED_TOOLS: &[&str] = &[
+    "systemctl",
+    "service",
+    "ps",
+    "kill",
+    "pkill",
+    "apt-get",
+    "dnf",
+    "yum",
+    "apk",
+    "opkg",
+    "checkupdates",
+    "pacman",
+    "ss",
+    "netstat",
+];
+pub const PACKAGE_TOOLS: &[&str] = &["apt-get", "dnf", "yum", "apk", "opkg", "checkupdates"];
+const SERVICE_TOOLS: &[&str] = &["systemctl", "service"];
+const SOCKET_TOOLS: &[&str] = &["ss", "netstat"];
+/// Connections carrying this tag always get the constrained profile.
+pub const EMBEDDED_TAG: &str = "embedded";
+pub const WINDOWS_PACKAGE_TOOLS: &[&str] = &["winget", "choco"];
+
+/// PowerShell counterpart of [`probe_script`], with the same output format.
+const WINDOWS_PROBE_SCRIPT: &str = r#"$id = [Security.Principal.WindowsIdentity]::GetCurrent()
+"os=windows"
+"user=$($id.Name)"
+if (([Security.Principal.WindowsPrincipal]$id).IsInRole([Security.Principal.WindowsBuiltInRole]::Administrator)) { "sudo=root" } else { "sudo=not-administrator" }
+foreach ($t in 'Restart-Service', 'Stop-Process', 'winget', 'choco', 'netstat') {
+  if (Get-Command $t -ErrorAction SilentlyContinue) { "tool=$t" }
+}"#;
+
+static CACHE: LazyLock<StdMutex<HashMap<String, (Instant, HostCapabilities)>>> =
+    LazyLock::new(|| StdMutex::new(HashMap::new()));
+
+/// Prints `key=value` lines; `tool=` once per tool found. `sudo -n` never
+/// prompts, so a password requirement shows up as an error message instead.
+/// BusyBox applets name themselves in their usage text.
+pub fn probe_script() -> String {
+    format!(
+        r#"case "$(ls --help 2>&1)" in *BusyBox*) echo "busybox=yes" ;; esac
+echo "uid=$(id -u 2>/dev/null)"
+echo "user=$(id -un 2>/dev/null)"
+if [ "$(id -u 2>/dev/null)" = "0" ]; then echo "sudo=root"
+elif ! command -v sudo >/dev/null 2>&1; then echo "sudo=missing"
+elif sudo -n true >/dev/null 2>&1; then echo "sudo=nopasswd"
+else
+  case "$(sudo -n true 2>&1)" in
+    *password*) echo "sudo=password" ;;
+    *) echo "sudo=denied" ;;
+  esac
+fi
+for t in {tools}; do command -v "$t" >/dev/null 2>&1 && echo "tool=$t"; done
+exit 0"#,
+        tools = PROBED_TOOLS.join(" ")
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SudoAccess {
+    /// Logged in as root; no `sudo` needed.
+    Root,
+    /// `sudo` works without a password.
+    Nopasswd,
+    /// `sudo` would prompt, which background commands can't answer.
+    Password,
+    /// Not in sudoers.
+    Denied,
+    /// `sudo` isn't installed.
+    Missing,
+    /// Windows account outside the Administrators group.
+    NotAdministrator,
+    #[default]
+    Unknown,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostCapabilities {
+    pub connection_id: String,
+    pub user: Option<String>,
+    pub uid: Option<u32>,
+    pub sudo: SudoAccess,
+    pub tools: Vec<String>,
+    pub windows: bool,
+    /// Core utilities are BusyBox applets.
+    pub busybox: bool,
+    pub profile: HostProfile,
+}
+
+/// Which command variants are safe to send to a host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HostProfile {
+    /// GNU/BSD userland; the usual flags work.
+    #[default]
+    Standard,
+    /// BusyBox or similarly trimmed userland: POSIX flags only, and features
+    /// whose binaries are missing are reported as `tool-missing`.
+    Constrained,
+}
+
+impl HostCapabilities {
+    pub fn has_tool(&self, tool: &str) -> bool {
+        self.tools.iter().any(|found| found == tool)
+    }
+
+    /// Switch to the constrained profile when the connection is tagged
+    /// [`EMBEDDED_TAG`], for devices the probe can't recognise.
+    pub fn with_tags(mut self, tags: &[String]) -> Self {
+        if tags
+            .iter()
+            .any(|tag| tag.eq_ignore_ascii_case(EMBEDDED_TAG))
+        {
+            self.profile = HostProfile::Constrained;
+        }
+        self
+    }
+}
+
+pub fn parse_probe(connection_id: &str, output: &str) -> HostCapabilities {
+    let mut capabilities = HostCapabilities {
+        connection_id: connection_id.to_string(),
+        ..Default::default()
+    };
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        if value.is_empty() {
+            continue;
+        }
+        match key.trim() {
+            "os" => capabilities.windows = value == "windows",
+            "busybox" if value == "yes" => {
+                capabilities.busybox = true;
+                capabilities.profile = HostProfile::Constrained;
+            }
+            "uid" => capabilities.uid = value.parse().ok(),
+            "user" => capabilities.user = Some(value.to_string()),
+            "sudo" => {
+                capabilities.sudo = match value {
+                    "root" => SudoAccess::Root,
+                    "nopasswd" => SudoAccess::Nopasswd,
+                    "password" => SudoAccess::Password,
+                    "denied" => SudoAccess::Denied,
+                    "missing" => SudoAccess::Missing,
+                    "not-administrator" => SudoAccess::NotAdministrator,
+                    _ => SudoAccess::Unknown,
+                }
+            }
+            "tool" => capabilities.tools.push(value.to_string()),
+            _ => {}
+        }
+    }
+    capabilities
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Feature {
+    /// Start/stop/restart system services.
+    ServiceControl,
+    /// Signal processes owned by other users.
+    ProcessKill,
+    /// Count pending package updates.
+    PackageStatus,
+    /// List listening TCP ports (tunnel suggestions).
+    ListeningPorts,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "kebab-case")]
+pub enum FeatureAvailability {
+    Available,
+    RequiresSudo {
+        reason: String,
+    },
+    ToolMissing {
+        /// Any one of these would do.
+        tools: Vec<String>,
+    },
+}
+
+impl FeatureAvailability {
+    /// Message for an error string or a status line.
+    pub fn describe(&self) -> Option<String> {
+        match self {
+            Self::Available => None,
+            Self::RequiresSudo { reason } => Some(format!("Requires sudo: {reason}")),
+            Self::ToolMissing { tools } => {
+                Some(format!("Tool missing: needs {}", tools.join(" or ")))
+            }
+        }
+    }
+}
+
+pub(crate) fn requires_sudo(capabilities: &HostCapabilities) -> Option<FeatureAvailability> {
+    let reason = match capabilities.sudo {
+        SudoAccess::Root | SudoAccess::Nopasswd => return None,
+        SudoAccess::Password => "sudo asks for a password, which background actions can't answer",
+        SudoAccess::Denied => "this user is not allowed to use sudo",
+        SudoAccess::Missing => "sudo is not installed and this user is not root",
+        SudoAccess::NotAdministrator => "this Windows account is not an Administrator",
+        SudoAccess::Unknown => "privileges could not be determined",
+    };
+    Some(FeatureAvailability::RequiresSudo {
+        reason: reason.to_string(),
+    })
+}
+
+fn tool_missing(capabilities: &HostCapabilities, tools: &[&str]) -> Option<FeatureAvailability> {
+    (!tools.iter().any(|tool| capabilities.has_tool(tool))).then(|| {
+        FeatureAvailability::ToolMissing {
+            tools: tools.iter().map(|tool| tool.to_string()).collect(),
+        }
+    })
+}
+
+/// Missing tools are reported before missing privileges: sudo won't help then.
+pub fn feature(capabilities: &HostCapabilities, feature: Feature) -> FeatureAvailability {
+    let tools: &[&str] = match (feature, capabilities.windows) {
+        (Feature::ServiceControl, false) => SERVICE_TOOLS,
+        (Feature::ServiceControl, true) => &["Restart-Service"],
+        (Feature::ProcessKill, false) => &["kill"],
+        (Feature::ProcessKill, true) => &["Stop-Process"],
+        (Feature::PackageStatus, false) => PACKAGE_TOOLS,
+        (Feature::PackageStatus, true) => WINDOWS_PACKAGE_TOOLS,
+        (Feature::ListeningPorts, false) => SOCKET_TOOLS,
+        (Feature::ListeningPorts, true) => &["netstat"],
+    };
+    let unavailable = match feature {
+        Feature::ServiceControl | Feature::ProcessKill => {
+            tool_missing(capabilities, tools).or_else(|| requires_sudo(capabilities))
+        }
+        Feature::PackageStatus | Feature::ListeningPorts => tool_missing(capabilities, tools),
+    };
+    unavailable.unwrap_or(FeatureAvailability::Available)
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureReport {
+    pub feature: Feature,
+    #[serde(flatten)]
+    pub availability: FeatureAvailability,
+}
+
+pub fn features(capabilities: &HostCapabilities) -> Vec<FeatureReport> {
+    [
+        Feature::ServiceControl,
+        Feature::ProcessKill,
+        Feature::PackageStatus,
+        Feature::ListeningPorts,
+    ]
+    .into_iter()
+    .map(|name| FeatureReport {
+        feature: name,
+        availability: feature(capabilities, name),
+    })
+    .collect()
+}
+
+/// Capabilities of the host behind `connection_id`, probing when the cached
+/// entry is missing, stale or `refresh` is set.
+pub(crate) async fn probe(
+    state: &AppState,
+    connection_id: &str,
+    refresh: bool,
+) -> Result<HostCapabilities, String> {
+    if !refresh {
+        let cache = CACHE
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some((at, capabilities)) = cache.get(connection_id) {
+            if at.elapsed() < CACHE_TTL {
+                return Ok(capabilities.clone());
+            }
+        }
+    }
+    let script = if crate::remote_os::connection_is_windows(state, connection_id).await {
+        crate::remote_os::powershell_command(WINDOWS_PROBE_SCRIPT, &[])
+    } else {
+        probe_script()
+    };
+    let output = exec_on_connection(state, connection_id, &script).await?;
+    let capabilities = parse_probe(connection_id, &String::from_utf8_lossy(&output.stdout));
+    CACHE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .insert(
+            connection_id.to_string(),
+            (Instant::now(), capabilities.clone()),
+        );
+    Ok(capabilities)
+}
+
+/// How a privileged command gets root on this host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Elevation {
+    None,
+    /// `sudo -n`: passwordless sudo.
+    Sudo,
+    /// `sudo -S`: the password is written to stdin.
+    SudoWithPassword,
+}
+
+impl Elevation {
+    pub fn prefix(self) -> &'static str {
+        match self {
+            Self::None => "",
+            Self::Sudo => "sudo -n ",
+            Self::SudoWithPassword => "sudo -S -p '' ",
+        }
+    }
+}
+
+/// `Err` carries the reason the action can't run, for the UI to show.
+pub fn elevation(capabilities: &HostCapabilities, has_password: bool) -> Result<Elevation, String> {
+    match capabilities.sudo {
+        SudoAccess::Root => Ok(Elevation::None),
+        SudoAccess::Nopasswd => Ok(Elevation::Sudo),
+        SudoAccess::Password if has_password => Ok(Elevation::SudoWithPassword),
+        SudoAccess::Password => Err("SUDO_PASSWORD_REQUIRED: sudo asks for a password".to_string()),
+        _ => Err(requires_sudo(capabilities)
+            .and_then(|blocked| blocked.describe())
+            .unwrap_or_else(|| "Requires sudo".to_string())),
+    }
+}
+
+/// Whether `command` asks for `sudo` itself (possibly after `env`/assignments).
+pub fn invokes_sudo(command: &str) -> bool {
+    command
+        .split_whitespace()
+        .find(|word| *word != "env" && !word.contains('='))
+        .is_some_and(|word| word == "sudo")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_probe_output() {
+        let capabilities = parse_probe(
+            "c1",
+            "uid=1000\nuser=deploy\nsudo=password\ntool=systemctl\ntool=ps\ntool=kill\ntool=apt-get\n",
+        );
+        assert_eq!(capabilities.uid, Some(1000));
+        assert_eq!(capabilities.user.as_deref(), Some("deploy"));
+        assert_eq!(capabilities.sudo, SudoAccess::Password);
+        assert!(capabilities.has_tool("apt-get"));
+        assert!(requires_sudo(&capabilities).is_some());
+        assert_eq!(parse_probe("c1", "sudo=\n").sudo, SudoAccess::Unknown);
+    }
+
+    #[test]
+    fn features_degrade_to_explicit_statuses() {
+        let mut capabilities = parse_probe("c1", "uid=1000\nsudo=denied\ntool=kill\n");
+        assert_eq!(
+            feature(&capabilities, Feature::ServiceControl),
+            FeatureAvailability::ToolMissing {
+                tools: vec!["systemctl".to_string(), "service".to_string()]
+            }
+        );
+        assert!(matches!(
+            feature(&capabilities, Feature::ProcessKill),
+            FeatureAvailability::RequiresSudo { .. }
+        ));
+        assert_eq!(
+            feature(&capabilities, Feature::PackageStatus)
+                .describe()
+                .as_deref(),
+            Some("Tool missing: needs apt-get or dnf or yum or apk or opkg or checkupdates")
+        );
+
+        capabilities.sudo = SudoAccess::Root;
+        capabilities.tools.push("systemctl".to_string());
+        assert_eq!(
+            feature(&capabilities, Feature::ServiceControl),
+            FeatureAvailability::Available
+        );
+    }
+
+    #[test]
+    fn windows_hosts_use_their_own_tools_and_admin_check() {
+        let capabilities = parse_probe(
+            "w1",
+            "os=windows\r\nuser=HOST\\ops\r\nsudo=not-administrator\r\ntool=Restart-Service\r\ntool=Stop-Process\r\ntool=winget\r\n",
+        );
+        assert!(capabilities.windows);
+        assert_eq!(capabilities.sudo, SudoAccess::NotAdministrator);
+        assert!(matches!(
+            feature(&capabilities, Feature::ServiceControl),
+            FeatureAvailability::RequiresSudo { .. }
+        ));
+        assert_eq!(
+            feature(&capabilities, Feature::PackageStatus),
+            FeatureAvailability::Available
+        );
+    }
+
+    #[test]
+    fn busybox_hosts_get_the_constrained_profile() {
+        let capabilities = parse_probe(
+            "r1",
+            "busybox=yes\nuid=0\nuser=root\nsudo=root\ntool=kill\ntool=opkg\ntool=netstat\n",
+        );
+        assert!(capabilities.busybox);
+        assert_eq!(capabilities.profile, HostProfile::Constrained);
+        assert_eq!(
+            feature(&capabilities, Feature::PackageStatus),
+            FeatureAvailability::Available
+        );
+        assert_eq!(
+            feature(&capabilities, Feature::ServiceControl),
+            FeatureAvailability::ToolMissing {
+                tools: vec!["systemctl".to_string(), "service".to_string()]
+            }
+        );
+
+        let plain = parse_probe("n1", "uid=1000\nsudo=denied\n");
+        assert_eq!(plain.profile, HostProfile::Standard);
+        assert_eq!(
+            plain.with_tags(&["Embedded".to_string()]).profile,
+            HostProfile::Constrained
+        );
+    }
+
+    #[test]
+    fn elevation_follows_sudo_access() {
+        let mut capabilities = HostCapabilities {
+            sudo: SudoAccess::Password,
+            ..Default::default()
+        };
+        assert!(elevation(&capabilities, false)
+            .unwrap_err()
+            .starts_with("SUDO_PASSWORD_REQUIRED:"));
+        assert_eq!(
+            elevation(&capabilities, true),
+            Ok(Elevation::SudoWithPassword)
+        );
+        capabilities.sudo = SudoAccess::Denied;
+        assert!(elevation(&capabilities, true).is_err());
+        capabilities.sudo = SudoAccess::Root;
+        assert_eq!(elevation(&capabilities, false), Ok(Elevation::None));
+    }
+
+    #[test]
+    fn detects_sudo_invocations() {
+        assert!(invokes_sudo("sudo systemctl restart nginx"));
+        assert!(invokes_sudo("env LANG=C sudo -n true"));
+        assert!(!invokes_sudo("echo sudo"));
+        assert!(!invokes_sudo("  "));
+    }
+}
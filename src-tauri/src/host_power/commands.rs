@@ -0,0 +1,119 @@
+use super::{
+    expect_disconnect, forget_disconnect, power_command, watch, PowerAction, PowerIssued,
+    PowerWatchReport, PROGRESS_EVENT,
+};
+use crate::commands::{
+    ensure_connection_online, exec_on_connection, exec_on_connection_with_stdin, AppState,
+};
+use crate::host_capabilities::{elevation, Elevation};
+use crate::operations::OperationKind;
+use crate::safety::{SafetyVerdict, CONFIRMATION_REQUIRED_PREFIX, SAFETY_BLOCKED_PREFIX};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+
+const DEFAULT_WATCH_MINUTES: u64 = 10;
+const MAX_WATCH_MINUTES: u64 = 60;
+
+/// Reboot or shut down a connected host. `confirmation` must be the host name
+/// typed by the user; blocked-command safety rules still apply. Without
+/// passwordless sudo the call fails with `SUDO_PASSWORD_REQUIRED:` until
+/// `sudo_password` is given.
+#[tauri::command]
+pub async fn host_power_issue(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    connection_id: String,
+    action: PowerAction,
+    confirmation: String,
+    sudo_password: Option<String>,
+) -> Result<PowerIssued, String> {
+    ensure_connection_online(&state, &connection_id).await?;
+    let target = {
+        let connections = state.connections.lock().await;
+        let handle = connections
+            .get(&connection_id)
+            .ok_or_else(|| format!("Connection {connection_id} is not connected"))?;
+        let config = &handle.config;
+        config
+            .jump_host
+            .is_none()
+            .then(|| (config.host.clone(), config.port))
+    };
+    let watchable = target.is_some();
+    let capabilities = crate::host_capabilities::probe(&state, &connection_id, false).await?;
+    let elevation = if capabilities.windows {
+        Elevation::None
+    } else {
+        elevation(&capabilities, sudo_password.is_some())?
+    };
+    let command = power_command(action, capabilities.windows, elevation.prefix());
+
+    let (verdict, hostname) = crate::safety::check(&app, &connection_id, &command);
+    if let SafetyVerdict::Block { reason } = verdict {
+        return Err(format!("{SAFETY_BLOCKED_PREFIX}{reason}"));
+    }
+    if !confirmation.trim().eq_ignore_ascii_case(&hostname) {
+        return Err(format!(
+            "{CONFIRMATION_REQUIRED_PREFIX}Type `{hostname}` to {} it.",
+            action.verb()
+        ));
+    }
+
+    expect_disconnect(&connection_id, action, target);
+    let output = match (elevation, sudo_password) {
+        (Elevation::SudoWithPassword, Some(password)) => {
+            let input = format!("{password}\n");
+            exec_on_connection_with_stdin(&state, &connection_id, &command, input.as_bytes()).await
+        }
+        _ => exec_on_connection(&state, &connection_id, &command).await,
+    };
+    let output = output.inspect_err(|_| forget_disconnect(&connection_id))?;
+    if output.exit_status != 0 {
+        forget_disconnect(&connection_id);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "The {} command failed (exit {}): {}",
+            action.verb(),
+            output.exit_status,
+            stderr.trim()
+        ));
+    }
+    Ok(PowerIssued {
+        action,
+        command,
+        watchable,
+    })
+}
+
+/// Wait until a host told to reboot is back (or one told to shut down is
+/// gone), emitting `host:power-progress`. Cancel with `operation_id`.
+#[tauri::command]
+pub async fn host_power_watch(
+    app: AppHandle,
+    connection_id: String,
+    operation_id: Option<String>,
+    timeout_minutes: Option<u64>,
+) -> Result<PowerWatchReport, String> {
+    let minutes = timeout_minutes
+        .unwrap_or(DEFAULT_WATCH_MINUTES)
+        .clamp(1, MAX_WATCH_MINUTES);
+    let operation = crate::operations::begin(
+        OperationKind::PowerWatch,
+        operation_id,
+        format!("Waiting for {connection_id}"),
+    );
+    let result = operation
+        .run(watch(
+            &connection_id,
+            Duration::from_secs(minutes * 60),
+            |progress| {
+                let _ = app.emit(PROGRESS_EVENT, progress);
+            },
+        ))
+        .await;
+    // A rebooted host stays expected to drop until it is connected again.
+    if !matches!(&result, Ok(report) if report.back_after_ms.is_some()) {
+        forget_disconnect(&connection_id);
+    }
+    result
+}
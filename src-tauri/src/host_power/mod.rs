@@ -0,0 +1,276 @@
+//! Rebooting or shutting down a connected host without leaving a dead terminal.
+//!
+//! `host_power_issue` starts the command detached, so its exec channel closes
+//! before the host goes down, and marks the connection as expecting to drop:
+//! the transport loss that follows isn't sent to notification webhooks.
+//! `host_power_watch` then follows the SSH port from here, first until it
+//! stops answering and, for a reboot, until it greets with an SSH banner
+//! again, emitting `host:power-progress` along the way. The frontend
+//! reconnects through its normal connect path once the host is back.
+//!
+//! Hosts behind a jump server can't be watched directly (see `availability`);
+//! the command is still issued and the user reconnects by hand.
+
+pub mod commands;
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex as StdMutex};
+use std::time::{Duration, Instant};
+use tokio::io::AsyncReadExt;
+
+pub const PROGRESS_EVENT: &str = "host:power-progress";
+
+/// How long after issuing a transport loss counts as expected.
+const EXPECT_WINDOW: Duration = Duration::from_secs(15 * 60);
+/// Delay before the command runs, so its exec channel can report success.
+const ISSUE_DELAY_SECONDS: u32 = 2;
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// A host still answering after this long didn't start going down.
+const GOING_DOWN_LIMIT: Duration = Duration::from_secs(180);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PowerAction {
+    Reboot,
+    Shutdown,
+}
+
+impl PowerAction {
+    pub fn verb(self) -> &'static str {
+        match self {
+            Self::Reboot => "reboot",
+            Self::Shutdown => "shut down",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PowerPhase {
+    /// The command was issued and the SSH port still answers.
+    GoingDown,
+    /// The port stopped answering.
+    Down,
+    /// The port answers with an SSH banner again.
+    Up,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PowerProgress {
+    pub connection_id: String,
+    pub phase: PowerPhase,
+    /// Since the command was issued.
+    pub elapsed_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PowerIssued {
+    pub action: PowerAction,
+    /// The command as run, including any `sudo` prefix.
+    pub command: String,
+    /// `false` behind a jump server, where the SSH port can't be watched from here.
+    pub watchable: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PowerWatchReport {
+    pub action: PowerAction,
+    /// From issuing the command until the SSH port stopped answering.
+    pub went_down_after_ms: u64,
+    /// From issuing the command until SSH answered again; `None` for a shutdown.
+    pub back_after_ms: Option<u64>,
+}
+
+#[derive(Clone)]
+struct Expected {
+    action: PowerAction,
+    issued_at: Instant,
+    /// SSH `host:port` to watch; `None` behind a jump server.
+    target: Option<(String, u16)>,
+}
+
+static EXPECTED: LazyLock<StdMutex<HashMap<String, Expected>>> =
+    LazyLock::new(|| StdMutex::new(HashMap::new()));
+
+fn lock_expected() -> std::sync::MutexGuard<'static, HashMap<String, Expected>> {
+    EXPECTED
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+pub(crate) fn expect_disconnect(
+    connection_id: &str,
+    action: PowerAction,
+    target: Option<(String, u16)>,
+) {
+    lock_expected().insert(
+        connection_id.to_string(),
+        Expected {
+            action,
+            issued_at: Instant::now(),
+            target,
+        },
+    );
+}
+
+pub(crate) fn forget_disconnect(connection_id: &str) {
+    lock_expected().remove(connection_id);
+}
+
+/// Whether `connection_id` was just told to reboot or shut down, so losing
+/// its transport is the expected outcome.
+pub fn expecting_disconnect(connection_id: &str) -> bool {
+    lock_expected()
+        .get(connection_id)
+        .is_some_and(|expected| expected.issued_at.elapsed() < EXPECT_WINDOW)
+}
+
+fn expected(connection_id: &str) -> Option<Expected> {
+    lock_expected().get(connection_id).cloned()
+}
+
+/// Shell command for `action`, detached so it outlives the exec channel.
+/// `prefix` is the elevation prefix (`sudo -n ` and the like).
+pub fn power_command(action: PowerAction, windows: bool, prefix: &str) -> String {
+    if windows {
+        let flag = match action {
+            PowerAction::Reboot => "/r",
+            PowerAction::Shutdown => "/s",
+        };
+        return format!("shutdown {flag} /t {ISSUE_DELAY_SECONDS}");
+    }
+    let run = match action {
+        PowerAction::Reboot => "systemctl reboot || shutdown -r now || reboot",
+        PowerAction::Shutdown => "systemctl poweroff || shutdown -h now || poweroff",
+    };
+    format!(
+        "{prefix}sh -c 'nohup sh -c \"sleep {ISSUE_DELAY_SECONDS}; {run}\" \
+         </dev/null >/dev/null 2>&1 &'"
+    )
+}
+
+/// Whether the first bytes a server sent are an SSH identification line.
+pub fn is_ssh_banner(greeting: &[u8]) -> bool {
+    // RFC 4253 lets servers send other lines before the identification.
+    String::from_utf8_lossy(greeting)
+        .lines()
+        .any(|line| line.starts_with("SSH-"))
+}
+
+/// Whether `host:port` accepts a connection and greets like an SSH server.
+/// A bare TCP accept isn't enough: some hosts open the port before sshd runs.
+async fn ssh_answers(host: &str, port: u16) -> bool {
+    let greet = async {
+        let mut stream = tokio::net::TcpStream::connect((host, port)).await.ok()?;
+        let mut greeting = Vec::with_capacity(256);
+        let mut buffer = [0u8; 256];
+        while greeting.len() < 4096 {
+            let read = stream.read(&mut buffer).await.ok()?;
+            if read == 0 {
+                break;
+            }
+            greeting.extend_from_slice(&buffer[..read]);
+            if greeting.contains(&b'\n') && is_ssh_banner(&greeting) {
+                return Some(true);
+            }
+        }
+        Some(is_ssh_banner(&greeting))
+    };
+    matches!(
+        tokio::time::timeout(PROBE_TIMEOUT, greet).await,
+        Ok(Some(true))
+    )
+}
+
+/// Follow the SSH port of a host after its power command; see the module docs.
+/// The session itself may be gone by now. `emit` receives each phase change.
+pub async fn watch(
+    connection_id: &str,
+    timeout: Duration,
+    mut emit: impl FnMut(PowerProgress),
+) -> Result<PowerWatchReport, String> {
+    let Expected {
+        action,
+        issued_at,
+        target,
+    } = expected(connection_id)
+        .ok_or_else(|| format!("No reboot or shutdown was issued for {connection_id}"))?;
+    let (host, port) = target.ok_or("Hosts behind a jump server can't be watched from here")?;
+    let host = host.as_str();
+    let elapsed_ms = || issued_at.elapsed().as_millis() as u64;
+    let mut report = |phase| {
+        emit(PowerProgress {
+            connection_id: connection_id.to_string(),
+            phase,
+            elapsed_ms: elapsed_ms(),
+        })
+    };
+
+    report(PowerPhase::GoingDown);
+    while ssh_answers(host, port).await {
+        if issued_at.elapsed() > GOING_DOWN_LIMIT {
+            return Err(format!(
+                "{host} still answers after {} seconds; the {} may not have started",
+                GOING_DOWN_LIMIT.as_secs(),
+                action.verb()
+            ));
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+    let went_down_after_ms = elapsed_ms();
+    report(PowerPhase::Down);
+    if action == PowerAction::Shutdown {
+        return Ok(PowerWatchReport {
+            action,
+            went_down_after_ms,
+            back_after_ms: None,
+        });
+    }
+
+    while !ssh_answers(host, port).await {
+        if issued_at.elapsed() > timeout {
+            return Err(format!(
+                "{host} didn't come back within {} minutes",
+                timeout.as_secs() / 60
+            ));
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+    report(PowerPhase::Up);
+    Ok(PowerWatchReport {
+        action,
+        went_down_after_ms,
+        back_after_ms: Some(elapsed_ms()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_detach_and_keep_the_elevation_outside() {
+        let reboot = power_command(PowerAction::Reboot, false, "sudo -n ");
+        assert!(reboot.starts_with("sudo -n sh -c 'nohup sh -c \"sleep 2; systemctl reboot"));
+        assert!(reboot.ends_with(">/dev/null 2>&1 &'"));
+        assert_eq!(
+            power_command(PowerAction::Shutdown, true, "sudo -n "),
+            "shutdown /s /t 2"
+        );
+    }
+
+    #[test]
+    fn recognizes_ssh_banners_after_preamble_lines() {
+        assert!(is_ssh_banner(b"SSH-2.0-OpenSSH_9.6\r\n"));
+        assert!(is_ssh_banner(
+            b"Authorized use only\r\nSSH-2.0-dropbear\r\n"
+        ));
+        assert!(!is_ssh_banner(b"HTTP/1.1 400 Bad Request\r\n"));
+        assert!(!is_ssh_banner(b""));
+    }
+}
@@ -0,0 +1,67 @@
+use super::{
+    diff, load_snapshot, parse_snapshot, save_snapshot, saved_snapshots, snapshot_command,
+    HostSnapshot, SnapshotDiff,
+};
+use crate::commands::{ensure_connection_online, exec_on_connection, get_data_dir, AppState};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, State};
+
+fn snapshots_dir(app: &AppHandle) -> PathBuf {
+    get_data_dir(app).join("host_snapshots")
+}
+
+/// Capture packages, config file hashes and service states. `extra_files` are
+/// hashed besides the defaults; `save` keeps the snapshot for later diffs.
+#[tauri::command]
+pub async fn host_snapshot_capture(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    connection_id: String,
+    extra_files: Option<Vec<String>>,
+    save: Option<bool>,
+) -> Result<HostSnapshot, String> {
+    let command = snapshot_command(&extra_files.unwrap_or_default())?;
+    ensure_connection_online(&state, &connection_id).await?;
+    if crate::remote_os::connection_is_windows(&state, &connection_id).await {
+        return Err("Environment snapshots are not supported on Windows hosts".to_string());
+    }
+    let output = exec_on_connection(&state, &connection_id, &command).await?;
+    let captured_at_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    let snapshot = parse_snapshot(
+        &connection_id,
+        captured_at_ms,
+        &String::from_utf8_lossy(&output.stdout),
+    );
+    if save.unwrap_or(false) {
+        save_snapshot(&snapshots_dir(&app), &snapshot)?;
+    }
+    Ok(snapshot)
+}
+
+/// Capture times of a host's saved snapshots, newest first.
+#[tauri::command]
+pub async fn host_snapshot_list(app: AppHandle, connection_id: String) -> Result<Vec<u64>, String> {
+    Ok(saved_snapshots(&snapshots_dir(&app), &connection_id))
+}
+
+#[tauri::command]
+pub async fn host_snapshot_load(
+    app: AppHandle,
+    connection_id: String,
+    captured_at_ms: u64,
+) -> Result<HostSnapshot, String> {
+    load_snapshot(&snapshots_dir(&app), &connection_id, captured_at_ms)
+}
+
+/// Drift between two snapshots: two hosts, or one host then and now.
+#[tauri::command]
+pub async fn host_snapshot_diff(
+    left: HostSnapshot,
+    right: HostSnapshot,
+) -> Result<SnapshotDiff, String> {
+    Ok(diff(&left, &right))
+}
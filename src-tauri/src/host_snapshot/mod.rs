@@ -0,0 +1,446 @@
+//! Normalized environment snapshots of a host — installed packages, hashes of
+//! key config files and service states — and a differ that compares two of
+//! them, either two hosts or one host at two points in time.
+//!
+//! Saved snapshots live in `host_snapshots/<connection id>/<captured at>.json`
+//! in the app data directory; the newest `MAX_SAVED_PER_HOST` are kept.
+
+pub mod commands;
+
+use crate::ai::tool_exec_support::shell_quote;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const SECTION_MARKER: &str = "__ZYNC_SNAPSHOT__ ";
+pub const MAX_SAVED_PER_HOST: usize = 20;
+const MAX_EXTRA_FILES: usize = 100;
+/// File value when the path doesn't exist.
+pub const FILE_MISSING: &str = "missing";
+/// File value when it exists but this user can't read it.
+pub const FILE_UNREADABLE: &str = "unreadable";
+
+/// Hashed in every snapshot, alongside any files the caller adds.
+pub const DEFAULT_FILES: &[&str] = &[
+    "/etc/ssh/sshd_config",
+    "/etc/hosts",
+    "/etc/resolv.conf",
+    "/etc/fstab",
+    "/etc/passwd",
+    "/etc/group",
+    "/etc/sudoers",
+    "/etc/crontab",
+    "/etc/sysctl.conf",
+    "/etc/nginx/nginx.conf",
+];
+
+/// Files to hash are the positional parameters. The package section starts
+/// with the name of the package database it came from.
+const SNAPSHOT_SCRIPT: &str = r#"echo "__ZYNC_SNAPSHOT__ system"
+hostname 2>/dev/null || cat /etc/hostname 2>/dev/null || echo
+( . /etc/os-release 2>/dev/null; echo "${PRETTY_NAME:-}" )
+uname -r
+echo "__ZYNC_SNAPSHOT__ packages"
+if command -v dpkg-query >/dev/null 2>&1; then
+  echo dpkg; dpkg-query -W -f '${db:Status-Abbrev}\t${binary:Package}\t${Version}\n' 2>/dev/null | awk -F '\t' '$1 ~ /^ii/ { print $2 "\t" $3 }'
+elif command -v rpm >/dev/null 2>&1; then
+  echo rpm; rpm -qa --qf '%{NAME}.%{ARCH}\t%{VERSION}-%{RELEASE}\n' 2>/dev/null
+elif command -v apk >/dev/null 2>&1; then
+  echo apk; apk info -v 2>/dev/null
+elif command -v pacman >/dev/null 2>&1; then
+  echo pacman; pacman -Q 2>/dev/null
+elif command -v opkg >/dev/null 2>&1; then
+  echo opkg; opkg list-installed 2>/dev/null
+fi
+echo "__ZYNC_SNAPSHOT__ services"
+systemctl list-units --type=service --all --no-legend --plain --no-pager 2>/dev/null
+echo "__ZYNC_SNAPSHOT__ files"
+for f in "$@"; do
+  if [ ! -e "$f" ]; then echo "missing $f"
+  elif [ -r "$f" ] && sum=$(sha256sum "$f" 2>/dev/null); then echo "$sum"
+  else echo "unreadable $f"; fi
+done
+exit 0"#;
+
+/// The snapshot command hashing the default files plus `extra_files`.
+pub fn snapshot_command(extra_files: &[String]) -> Result<String, String> {
+    if extra_files.len() > MAX_EXTRA_FILES {
+        return Err(format!(
+            "At most {MAX_EXTRA_FILES} extra files can be hashed"
+        ));
+    }
+    let mut files: Vec<&str> = DEFAULT_FILES.to_vec();
+    for file in extra_files {
+        let file = file.trim();
+        if !file.starts_with('/') || file.contains(['\n', '\r', '\0']) {
+            return Err(format!("Not an absolute path: {file}"));
+        }
+        if !files.contains(&file) {
+            files.push(file);
+        }
+    }
+    let args: Vec<String> = files.iter().map(|file| shell_quote(file)).collect();
+    Ok(format!(
+        "sh -c {} sh {}",
+        shell_quote(SNAPSHOT_SCRIPT),
+        args.join(" ")
+    ))
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostSnapshot {
+    pub connection_id: String,
+    pub captured_at_ms: u64,
+    pub hostname: String,
+    /// `PRETTY_NAME` from `/etc/os-release`.
+    pub os: String,
+    pub kernel: String,
+    /// `dpkg`, `rpm`, `apk`, `pacman` or `opkg`; `None` when none was found.
+    pub package_source: Option<String>,
+    /// Name to version. rpm names carry the architecture (`glibc.x86_64`).
+    pub packages: BTreeMap<String, String>,
+    /// Path to SHA-256, or [`FILE_MISSING`] / [`FILE_UNREADABLE`].
+    pub files: BTreeMap<String, String>,
+    /// systemd unit to `active/sub` state, e.g. `active/running`.
+    pub services: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DriftKind {
+    OnlyLeft,
+    OnlyRight,
+    Changed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Drift {
+    pub key: String,
+    pub kind: DriftKind,
+    pub left: Option<String>,
+    pub right: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotDiff {
+    /// `os`, `kernel`; hostnames are expected to differ and left out.
+    pub system: Vec<Drift>,
+    pub packages: Vec<Drift>,
+    pub files: Vec<Drift>,
+    pub services: Vec<Drift>,
+}
+
+impl SnapshotDiff {
+    pub fn is_empty(&self) -> bool {
+        self.system.is_empty()
+            && self.packages.is_empty()
+            && self.files.is_empty()
+            && self.services.is_empty()
+    }
+}
+
+/// Keys whose values differ or exist on one side only, in key order.
+fn diff_maps(left: &BTreeMap<String, String>, right: &BTreeMap<String, String>) -> Vec<Drift> {
+    let mut keys: Vec<&String> = left.keys().chain(right.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .filter_map(|key| {
+            let (left, right) = (left.get(key), right.get(key));
+            let kind = match (left, right) {
+                (Some(a), Some(b)) if a == b => return None,
+                (Some(_), Some(_)) => DriftKind::Changed,
+                (Some(_), None) => DriftKind::OnlyLeft,
+                (None, Some(_)) => DriftKind::OnlyRight,
+                (None, None) => return None,
+            };
+            Some(Drift {
+                key: key.clone(),
+                kind,
+                left: left.cloned(),
+                right: right.cloned(),
+            })
+        })
+        .collect()
+}
+
+pub fn diff(left: &HostSnapshot, right: &HostSnapshot) -> SnapshotDiff {
+    let system = |snapshot: &HostSnapshot| {
+        BTreeMap::from([
+            ("os".to_string(), snapshot.os.clone()),
+            ("kernel".to_string(), snapshot.kernel.clone()),
+        ])
+    };
+    // A file only one side hashed (an extra file) isn't drift by itself.
+    let files = |own: &HostSnapshot, other: &HostSnapshot| -> BTreeMap<String, String> {
+        own.files
+            .iter()
+            .filter(|(path, _)| other.files.contains_key(*path))
+            .map(|(path, value)| (path.clone(), value.clone()))
+            .collect()
+    };
+    SnapshotDiff {
+        system: diff_maps(&system(left), &system(right)),
+        packages: diff_maps(&left.packages, &right.packages),
+        files: diff_maps(&files(left, right), &files(right, left)),
+        services: diff_maps(&left.services, &right.services),
+    }
+}
+
+fn parse_package_line(source: &str, line: &str) -> Option<(String, String)> {
+    let pair = |(name, version): (&str, &str)| Some((name.to_string(), version.to_string()));
+    match source {
+        "dpkg" | "rpm" => pair(line.split_once('\t')?),
+        // `musl-1.2.4-r3`
+        "apk" => pair(crate::packages::split_apk_package(line.trim())?),
+        // `bash 5.2.026-1`
+        "pacman" => pair(line.split_once(' ')?),
+        // `busybox - 1.36.1-1`
+        "opkg" => pair(line.split_once(" - ")?),
+        _ => None,
+    }
+}
+
+/// `ssh.service loaded active running OpenBSD Secure Shell server`
+fn parse_service_line(line: &str) -> Option<(String, String)> {
+    let mut fields = line.split_whitespace();
+    let (unit, load, active, sub) = (
+        fields.next()?,
+        fields.next()?,
+        fields.next()?,
+        fields.next()?,
+    );
+    if load == "not-found" {
+        return None;
+    }
+    Some((unit.to_string(), format!("{active}/{sub}")))
+}
+
+/// `<sha256>  <path>`, `missing <path>` or `unreadable <path>`.
+fn parse_file_line(line: &str) -> Option<(String, String)> {
+    let (value, path) = line.split_once(' ')?;
+    let path = path.trim_start();
+    if path.is_empty() {
+        return None;
+    }
+    Some((path.to_string(), value.to_string()))
+}
+
+pub fn parse_snapshot(connection_id: &str, captured_at_ms: u64, output: &str) -> HostSnapshot {
+    let mut snapshot = HostSnapshot {
+        connection_id: connection_id.to_string(),
+        captured_at_ms,
+        ..HostSnapshot::default()
+    };
+    let mut section = String::new();
+    let mut system_lines = Vec::new();
+    for line in output.lines() {
+        if let Some(name) = line.strip_prefix(SECTION_MARKER) {
+            section = name.trim().to_string();
+            continue;
+        }
+        match section.as_str() {
+            "system" => system_lines.push(line.trim().to_string()),
+            "packages" => match &snapshot.package_source {
+                None if !line.trim().is_empty() => {
+                    snapshot.package_source = Some(line.trim().to_string());
+                }
+                Some(source) => {
+                    if let Some((name, version)) = parse_package_line(source, line) {
+                        snapshot.packages.insert(name, version);
+                    }
+                }
+                None => {}
+            },
+            "services" => snapshot.services.extend(parse_service_line(line)),
+            "files" => snapshot.files.extend(parse_file_line(line)),
+            _ => {}
+        }
+    }
+    let mut system = system_lines.into_iter();
+    snapshot.hostname = system.next().unwrap_or_default();
+    snapshot.os = system.next().unwrap_or_default();
+    snapshot.kernel = system.next().unwrap_or_default();
+    snapshot
+}
+
+/// Connection ids become directory names.
+fn host_dir(root: &Path, connection_id: &str) -> PathBuf {
+    let safe: String = connection_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    root.join(safe)
+}
+
+/// Save `snapshot` under `root` and drop the oldest beyond the limit.
+pub fn save_snapshot(root: &Path, snapshot: &HostSnapshot) -> Result<(), String> {
+    let dir = host_dir(root, &snapshot.connection_id);
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let json = serde_json::to_vec(snapshot).map_err(|e| e.to_string())?;
+    crate::atomic_io::durable_replace(
+        &dir.join(format!("{}.json", snapshot.captured_at_ms)),
+        &json,
+    )
+    .map_err(|e| e.to_string())?;
+    let saved = saved_snapshots(root, &snapshot.connection_id);
+    for captured_at_ms in saved.iter().skip(MAX_SAVED_PER_HOST) {
+        let _ = std::fs::remove_file(dir.join(format!("{captured_at_ms}.json")));
+    }
+    Ok(())
+}
+
+/// Capture times of the saved snapshots of a host, newest first.
+pub fn saved_snapshots(root: &Path, connection_id: &str) -> Vec<u64> {
+    let mut saved: Vec<u64> = std::fs::read_dir(host_dir(root, connection_id))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| {
+                    entry
+                        .file_name()
+                        .to_str()?
+                        .strip_suffix(".json")?
+                        .parse()
+                        .ok()
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    saved.sort_unstable_by(|a, b| b.cmp(a));
+    saved
+}
+
+pub fn load_snapshot(
+    root: &Path,
+    connection_id: &str,
+    captured_at_ms: u64,
+) -> Result<HostSnapshot, String> {
+    let path = host_dir(root, connection_id).join(format!("{captured_at_ms}.json"));
+    let content = std::fs::read(&path)
+        .map_err(|_| format!("No snapshot of {connection_id} taken at {captured_at_ms}"))?;
+    serde_json::from_slice(&content).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUTPUT: &str = "__ZYNC_SNAPSHOT__ system
+web-1
+Ubuntu 24.04.1 LTS
+6.8.0-45-generic
+__ZYNC_SNAPSHOT__ packages
+dpkg
+openssl\t3.0.13-0ubuntu3.4
+libc6:amd64\t2.39-0ubuntu8.3
+__ZYNC_SNAPSHOT__ services
+nginx.service loaded active running A high performance web server
+cron.service loaded active running Regular background program processing daemon
+plymouth.service not-found inactive dead plymouth.service
+__ZYNC_SNAPSHOT__ files
+3b1c0f2e9d  /etc/ssh/sshd_config
+missing /etc/nginx/nginx.conf
+unreadable /etc/sudoers
+";
+
+    #[test]
+    fn parses_every_section() {
+        let snapshot = parse_snapshot("web-1", 42, OUTPUT);
+        assert_eq!(snapshot.hostname, "web-1");
+        assert_eq!(snapshot.os, "Ubuntu 24.04.1 LTS");
+        assert_eq!(snapshot.kernel, "6.8.0-45-generic");
+        assert_eq!(snapshot.package_source.as_deref(), Some("dpkg"));
+        assert_eq!(snapshot.packages["libc6:amd64"], "2.39-0ubuntu8.3");
+        assert_eq!(snapshot.services.len(), 2);
+        assert_eq!(snapshot.services["nginx.service"], "active/running");
+        assert_eq!(snapshot.files["/etc/ssh/sshd_config"], "3b1c0f2e9d");
+        assert_eq!(snapshot.files["/etc/nginx/nginx.conf"], FILE_MISSING);
+        assert_eq!(snapshot.files["/etc/sudoers"], FILE_UNREADABLE);
+    }
+
+    #[test]
+    fn parses_other_package_databases() {
+        assert_eq!(
+            parse_package_line("apk", "musl-1.2.4-r3"),
+            Some(("musl".to_string(), "1.2.4-r3".to_string()))
+        );
+        assert_eq!(
+            parse_package_line("opkg", "busybox - 1.36.1-1"),
+            Some(("busybox".to_string(), "1.36.1-1".to_string()))
+        );
+        assert_eq!(
+            parse_package_line("pacman", "bash 5.2.026-1"),
+            Some(("bash".to_string(), "5.2.026-1".to_string()))
+        );
+    }
+
+    #[test]
+    fn diff_reports_drift_per_section() {
+        let left = parse_snapshot("web-1", 1, OUTPUT);
+        let mut right = parse_snapshot("web-2", 2, OUTPUT);
+        right.hostname = "web-2".to_string();
+        right.kernel = "6.8.0-47-generic".to_string();
+        right
+            .packages
+            .insert("openssl".into(), "3.0.13-0ubuntu3.5".into());
+        right
+            .packages
+            .insert("redis-server".into(), "7.0.15-1".into());
+        right.services.remove("cron.service");
+        right
+            .files
+            .insert("/etc/ssh/sshd_config".into(), "ffff".into());
+        right.files.insert("/etc/app.env".into(), "aaaa".into());
+
+        let diff = diff(&left, &right);
+        assert_eq!(diff.system.len(), 1);
+        assert_eq!(diff.system[0].key, "kernel");
+        assert_eq!(
+            diff.packages,
+            vec![
+                Drift {
+                    key: "openssl".into(),
+                    kind: DriftKind::Changed,
+                    left: Some("3.0.13-0ubuntu3.4".into()),
+                    right: Some("3.0.13-0ubuntu3.5".into()),
+                },
+                Drift {
+                    key: "redis-server".into(),
+                    kind: DriftKind::OnlyRight,
+                    left: None,
+                    right: Some("7.0.15-1".into()),
+                },
+            ]
+        );
+        assert_eq!(diff.services[0].kind, DriftKind::OnlyLeft);
+        // The extra file only the right side hashed is not drift.
+        assert_eq!(diff.files.len(), 1);
+        assert!(crate::host_snapshot::diff(&left, &left).is_empty());
+    }
+
+    #[test]
+    fn saves_and_prunes_snapshots_per_host() {
+        let root = std::env::temp_dir().join(format!("zync-snapshots-{}", uuid::Uuid::new_v4()));
+        for captured_at_ms in 0..(MAX_SAVED_PER_HOST as u64 + 3) {
+            let snapshot = parse_snapshot("ssh_a/b", captured_at_ms, OUTPUT);
+            save_snapshot(&root, &snapshot).unwrap();
+        }
+        let saved = saved_snapshots(&root, "ssh_a/b");
+        assert_eq!(saved.len(), MAX_SAVED_PER_HOST);
+        assert_eq!(saved[0], MAX_SAVED_PER_HOST as u64 + 2);
+        let loaded = load_snapshot(&root, "ssh_a/b", saved[0]).unwrap();
+        assert_eq!(loaded.packages.len(), 2);
+        assert!(load_snapshot(&root, "ssh_a/b", 0).is_err());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,36 @@
+use super::{reset, sessions, BastionSession};
+use crate::commands::AppState;
+use tauri::State;
+
+#[tauri::command]
+pub async fn jump_sessions_list(state: State<'_, AppState>) -> Result<Vec<BastionSession>, String> {
+    let connections = state.connections.lock().await;
+    Ok(sessions(
+        connections
+            .iter()
+            .map(|(id, handle)| (id.as_str(), &handle.config)),
+    ))
+}
+
+/// Make the next connects through bastion `key` log in to it again. Returns
+/// the connections behind it, for the caller to reconnect.
+#[tauri::command]
+pub async fn jump_session_reset(
+    key: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    let dependents = {
+        let connections = state.connections.lock().await;
+        sessions(
+            connections
+                .iter()
+                .map(|(id, handle)| (id.as_str(), &handle.config)),
+        )
+        .into_iter()
+        .find(|bastion| bastion.key == key)
+        .map(|bastion| bastion.dependents)
+        .unwrap_or_default()
+    };
+    reset(&key).await;
+    Ok(dependents)
+}
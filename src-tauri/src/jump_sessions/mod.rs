@@ -0,0 +1,342 @@
+//! One SSH session per jump host, shared by every connection behind it.
+//!
+//! Targets that jump through the same bastion (same user, host, port and
+//! jump chain) open their `direct-tcpip` channels on one session instead of
+//! each logging in to the bastion again. Each target's handler holds an
+//! `Arc` to the session and the pool only a `Weak`, so the bastion session
+//! is dropped with the last target using it. Connects racing through the
+//! same bastion wait for one handshake, and a closed session is replaced on
+//! the next connect. After a target fails to connect its bastion session is
+//! kept for [`LINGER`], so retrying the target doesn't log in to the bastion
+//! (and ask for its one-time code) again.
+//!
+//! [`sessions`] lists the bastions live connections go through, with their
+//! status and the connections behind each, so a bastion dropping shows as one
+//! cause instead of several unrelated disconnects.
+
+pub mod commands;
+
+use crate::ssh::Client;
+use crate::types::ConnectionConfig;
+use russh::client::Handle;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::sync::{Arc, LazyLock, Mutex as StdMutex, Weak};
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+pub type JumpSession = Arc<Box<Handle<Client>>>;
+
+const LINGER: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BastionStatus {
+    /// A connect is logging in to it now.
+    Connecting,
+    Connected,
+    /// Dropped, or not opened since the app started.
+    Closed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BastionSession {
+    pub key: String,
+    /// The saved connection used as the bastion.
+    pub connection_id: String,
+    pub name: String,
+    /// `user@host:port`.
+    pub endpoint: String,
+    pub status: BastionStatus,
+    /// Open connections reached through this bastion, directly or through
+    /// another jump host behind it.
+    pub dependents: Vec<String>,
+}
+
+type Slot<T> = Arc<Mutex<Weak<T>>>;
+
+struct Pool<T> {
+    slots: StdMutex<HashMap<String, Slot<T>>>,
+}
+
+impl<T> Pool<T> {
+    fn new() -> Self {
+        Self {
+            slots: StdMutex::new(HashMap::new()),
+        }
+    }
+
+    fn slot(&self, key: &str) -> Slot<T> {
+        let mut slots = self
+            .slots
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        // Keep slots in use or being connected; drop the rest.
+        slots.retain(|_, slot| {
+            slot.try_lock()
+                .map_or(true, |session| session.strong_count() > 0)
+        });
+        slots.entry(key.to_string()).or_default().clone()
+    }
+
+    /// The live session for `key`, or a new one from `connect`. The flag is
+    /// whether it was reused.
+    async fn get_or_connect<E, Fut>(
+        &self,
+        key: &str,
+        alive: impl Fn(&T) -> bool,
+        connect: impl FnOnce() -> Fut,
+    ) -> Result<(Arc<T>, bool), E>
+    where
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let slot = self.slot(key);
+        let mut current = slot.lock().await;
+        if let Some(session) = current.upgrade().filter(|session| alive(session)) {
+            return Ok((session, true));
+        }
+        let session = Arc::new(connect().await?);
+        *current = Arc::downgrade(&session);
+        Ok((session, false))
+    }
+
+    fn status(&self, key: &str, alive: impl Fn(&T) -> bool) -> BastionStatus {
+        let slot = self
+            .slots
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(key)
+            .cloned();
+        let Some(slot) = slot else {
+            return BastionStatus::Closed;
+        };
+        let Ok(current) = slot.try_lock() else {
+            return BastionStatus::Connecting;
+        };
+        match current.upgrade() {
+            Some(session) if alive(&session) => BastionStatus::Connected,
+            _ => BastionStatus::Closed,
+        }
+    }
+
+    /// Stop handing out whatever session `key` and the hops behind it have.
+    async fn reset(&self, key: &str) {
+        let behind = format!(" via {key}");
+        let slots: Vec<Slot<T>> = {
+            let slots = self
+                .slots
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            slots
+                .iter()
+                .filter(|(slot_key, _)| *slot_key == key || slot_key.ends_with(&behind))
+                .map(|(_, slot)| slot.clone())
+                .collect()
+        };
+        for slot in slots {
+            *slot.lock().await = Weak::new();
+        }
+    }
+
+    /// Stop handing out `session`, e.g. after it failed to open a channel.
+    async fn evict(&self, key: &str, session: &Arc<T>) {
+        let slot = self.slot(key);
+        let mut current = slot.lock().await;
+        if std::ptr::eq(current.as_ptr(), Arc::as_ptr(session)) {
+            *current = Weak::new();
+        }
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.slots.lock().unwrap().len()
+    }
+}
+
+static POOL: LazyLock<Pool<Box<Handle<Client>>>> = LazyLock::new(Pool::new);
+
+/// `user@host:port`, followed by the hops it is reached through.
+fn pool_key(config: &ConnectionConfig) -> String {
+    let mut key = format!(
+        "{}@{}:{}",
+        config.username,
+        config.host.trim().to_ascii_lowercase(),
+        config.port
+    );
+    if let Some(jump) = &config.jump_host {
+        key.push_str(" via ");
+        key.push_str(&pool_key(jump));
+    }
+    key
+}
+
+/// The session to jump host `config`, shared with other connections using it.
+/// Returns whether an existing session was reused.
+pub async fn shared<Fut>(
+    config: &ConnectionConfig,
+    connect: impl FnOnce() -> Fut,
+) -> anyhow::Result<(JumpSession, bool)>
+where
+    Fut: Future<Output = anyhow::Result<Handle<Client>>>,
+{
+    let key = pool_key(config);
+    let (session, reused) = POOL
+        .get_or_connect(
+            &key,
+            |session| !session.is_closed(),
+            move || async move { connect().await.map(Box::new) },
+        )
+        .await?;
+    if reused {
+        println!(
+            "[SSH] Reusing jump host session {key} ({} connections)",
+            Arc::strong_count(&session) - 1
+        );
+    }
+    Ok((session, reused))
+}
+
+/// Open a fresh session next time, for a shared session that stopped working.
+pub async fn evict(config: &ConnectionConfig, session: &JumpSession) {
+    POOL.evict(&pool_key(config), session).await;
+}
+
+/// Keep `session` open for [`LINGER`] even if nothing else uses it.
+pub fn linger(session: JumpSession) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(LINGER).await;
+        drop(session);
+    });
+}
+
+/// The bastions `connections` (id and config) jump through, by key.
+fn collect<'a>(
+    connections: impl IntoIterator<Item = (&'a str, &'a ConnectionConfig)>,
+    status: impl Fn(&str) -> BastionStatus,
+) -> Vec<BastionSession> {
+    let mut bastions = BTreeMap::<String, BastionSession>::new();
+    for (id, config) in connections {
+        let mut hop = config.jump_host.as_deref();
+        while let Some(jump) = hop {
+            let key = pool_key(jump);
+            bastions
+                .entry(key.clone())
+                .or_insert_with(|| BastionSession {
+                    status: status(&key),
+                    key,
+                    connection_id: jump.id.clone(),
+                    name: jump.name.clone(),
+                    endpoint: format!("{}@{}:{}", jump.username, jump.host, jump.port),
+                    dependents: Vec::new(),
+                })
+                .dependents
+                .push(id.to_string());
+            hop = jump.jump_host.as_deref();
+        }
+    }
+    bastions.into_values().collect()
+}
+
+/// The bastion sessions behind `connections` (id and config).
+pub fn sessions<'a>(
+    connections: impl IntoIterator<Item = (&'a str, &'a ConnectionConfig)>,
+) -> Vec<BastionSession> {
+    collect(connections, |key| {
+        POOL.status(key, |session| !session.is_closed())
+    })
+}
+
+/// Log in to bastion `key`, and the jump hosts behind it, afresh on the next
+/// connect through them.
+pub async fn reset(key: &str) {
+    POOL.reset(key).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(host: &str, jump_host: Option<ConnectionConfig>) -> ConnectionConfig {
+        ConnectionConfig {
+            jump_host: jump_host.map(Box::new),
+            ..ConnectionConfig::for_test(host, "ops")
+        }
+    }
+
+    #[test]
+    fn keys_include_the_jump_chain() {
+        let outer = host("Outer.example.com", None);
+        assert_eq!(pool_key(&outer), "ops@outer.example.com:22");
+        let inner = host("bastion", Some(outer));
+        assert_eq!(
+            pool_key(&inner),
+            "ops@bastion:22 via ops@outer.example.com:22"
+        );
+        assert_ne!(pool_key(&inner), pool_key(&host("bastion", None)));
+    }
+
+    #[tokio::test]
+    async fn shares_sessions_until_the_last_user_drops() {
+        let pool = Pool::<String>::new();
+        let connect = |name: &str| {
+            let name = name.to_string();
+            move || async move { Ok::<_, ()>(name) }
+        };
+        let (first, reused) = pool
+            .get_or_connect("bastion", |_| true, connect("a"))
+            .await
+            .unwrap();
+        assert!(!reused);
+        let (second, reused) = pool
+            .get_or_connect("bastion", |_| true, connect("b"))
+            .await
+            .unwrap();
+        assert!(reused);
+        assert!(Arc::ptr_eq(&first, &second));
+
+        let (closed, reused) = pool
+            .get_or_connect("bastion", |_| false, connect("c"))
+            .await
+            .unwrap();
+        assert!(!reused);
+        assert_eq!(*closed, "c");
+
+        pool.evict("bastion", &closed).await;
+        drop((first, second, closed));
+        let (fresh, _) = pool
+            .get_or_connect("other", |_| true, connect("d"))
+            .await
+            .unwrap();
+        assert_eq!(*fresh, "d");
+        assert_eq!(pool.len(), 1);
+    }
+
+    #[test]
+    fn lists_dependents_of_every_hop() {
+        let outer = host("outer", None);
+        let bastion = host("bastion", Some(outer.clone()));
+        let web = host("web", Some(bastion.clone()));
+        let db = host("db", Some(bastion));
+        let edge = host("edge", Some(outer));
+        let connections = [("web", &web), ("db", &db), ("edge", &edge)];
+
+        let bastions = collect(connections, |_| BastionStatus::Connected);
+        let dependents: Vec<(&str, Vec<&str>)> = bastions
+            .iter()
+            .map(|bastion| {
+                let ids = bastion.dependents.iter().map(String::as_str).collect();
+                (bastion.name.as_str(), ids)
+            })
+            .collect();
+        assert_eq!(
+            dependents,
+            [
+                ("bastion", vec!["web", "db"]),
+                ("outer", vec!["web", "db", "edge"]),
+            ]
+        );
+        assert_eq!(bastions[1].endpoint, "ops@outer:22");
+    }
+}
@@ -0,0 +1,163 @@
+//! Per-session liveness watch, the `ServerAliveInterval` / `ServerAliveCountMax`
+//! of a connection.
+//!
+//! The keep-alives themselves are russh's: `ssh::client_config` sets
+//! `keepalive_interval` / `keepalive_max` from the same options, so the session
+//! sends a `keepalive@openssh.com` global request each interval and closes
+//! itself after `count_max` go unanswered. Global requests take no channel, so
+//! they work on hosts whose `MaxSessions` slots are all in use.
+//!
+//! What russh doesn't do is tell anyone: until a terminal or tunnel next touches
+//! the session, a drop behind a NAT can go unnoticed for hours. [`watch`] runs
+//! one task per connection that checks every [`POLL`] whether the session has
+//! closed and reports it once as `connection:transport-lost`. That is the event
+//! a dropped terminal or tunnel already raises, so the frontend's single
+//! handler suspends tabs, stops tunnels and starts the automatic reconnect; a
+//! separate `connection-lost` event would need the same handling twice. The
+//! task follows the connection across reconnects and ends when it disconnects.
+
+use crate::commands::AppState;
+use crate::ssh::Client;
+use crate::types::SshTransportOptions;
+use russh::client::Handle;
+use std::collections::HashSet;
+use std::sync::{Arc, LazyLock, Mutex as StdMutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::sync::Mutex;
+
+/// Defaults for both this watch and the russh client config in `ssh.rs`.
+const DEFAULT_INTERVAL_SECS: u64 = 60;
+const DEFAULT_COUNT_MAX: usize = 3;
+
+/// How often a watched session is checked; shorter than any sensible keep-alive
+/// interval so a drop is reported soon after russh gives up on it.
+const POLL: Duration = Duration::from_secs(5);
+
+static WATCHED: LazyLock<StdMutex<HashSet<String>>> =
+    LazyLock::new(|| StdMutex::new(HashSet::new()));
+
+fn lock_watched() -> std::sync::MutexGuard<'static, HashSet<String>> {
+    WATCHED
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeepaliveSettings {
+    pub interval: Duration,
+    pub count_max: usize,
+}
+
+impl KeepaliveSettings {
+    /// `None` when the connection turned keep-alives off (`ServerAliveInterval 0`).
+    pub fn from_options(options: Option<&SshTransportOptions>) -> Option<Self> {
+        let interval_secs = options
+            .and_then(|options| options.server_alive_interval_secs)
+            .unwrap_or(DEFAULT_INTERVAL_SECS);
+        if interval_secs == 0 {
+            return None;
+        }
+        let count_max = options
+            .and_then(|options| options.server_alive_count_max)
+            .unwrap_or(DEFAULT_COUNT_MAX)
+            .max(1);
+        Some(Self {
+            interval: Duration::from_secs(interval_secs),
+            count_max,
+        })
+    }
+}
+
+/// The connection's current session and keep-alive settings, while it has both.
+async fn current(
+    app: &AppHandle,
+    connection_id: &str,
+) -> Option<(Arc<Mutex<Handle<Client>>>, KeepaliveSettings)> {
+    let state = app.try_state::<AppState>()?;
+    let connections = state.connections.lock().await;
+    let handle = connections.get(connection_id)?;
+    let settings = KeepaliveSettings::from_options(handle.config.ssh_options.as_ref())?;
+    Some((handle.session.clone()?, settings))
+}
+
+/// Start watching `connection_id` unless a watch is already running for it.
+pub(crate) fn watch(app: &AppHandle, connection_id: &str) {
+    if !lock_watched().insert(connection_id.to_string()) {
+        return;
+    }
+    let app = app.clone();
+    let connection_id = connection_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        run(&app, &connection_id).await;
+        lock_watched().remove(&connection_id);
+    });
+}
+
+async fn run(app: &AppHandle, connection_id: &str) {
+    let mut watched: Option<Arc<Mutex<Handle<Client>>>> = None;
+    let mut reported = false;
+    loop {
+        let Some((_, settings)) = current(app, connection_id).await else {
+            return;
+        };
+        tokio::time::sleep(POLL.min(settings.interval)).await;
+        let Some((session, settings)) = current(app, connection_id).await else {
+            return;
+        };
+        if !watched
+            .as_ref()
+            .is_some_and(|old| Arc::ptr_eq(old, &session))
+        {
+            watched = Some(session.clone());
+            reported = false;
+        }
+        // Reported already, or going down on purpose: wait for a new session.
+        if reported || crate::host_power::expecting_disconnect(connection_id) {
+            continue;
+        }
+        if !session.lock().await.is_closed() {
+            continue;
+        }
+        reported = true;
+        eprintln!(
+            "[KEEPALIVE] {connection_id}: session closed (keep-alive every {}s, max {} missed)",
+            settings.interval.as_secs(),
+            settings.count_max
+        );
+        crate::pty::emit_connection_transport_lost(app, connection_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_default_and_turn_off() {
+        assert_eq!(
+            KeepaliveSettings::from_options(None),
+            Some(KeepaliveSettings {
+                interval: Duration::from_secs(60),
+                count_max: 3,
+            })
+        );
+        let quick = SshTransportOptions {
+            server_alive_interval_secs: Some(15),
+            server_alive_count_max: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(
+            KeepaliveSettings::from_options(Some(&quick)),
+            Some(KeepaliveSettings {
+                interval: Duration::from_secs(15),
+                count_max: 1,
+            })
+        );
+        let off = SshTransportOptions {
+            server_alive_interval_secs: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(KeepaliveSettings::from_options(Some(&off)), None);
+    }
+}
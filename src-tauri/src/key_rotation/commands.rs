@@ -0,0 +1,280 @@
+use super::{
+    generate_key, install_script, key_blob, remove_script, HostRotationStatus, RotationStep,
+};
+use crate::commands::{exec_on_connection, get_data_dir, AppState};
+use crate::fleet::run_bounded;
+use crate::secret_split::commands::KeychainStore;
+use crate::secret_split::SecretStore;
+use crate::sync::domain_hosts::{load_saved_data, save_saved_data_atomic, CONNECTIONS_MUTATION_LOCK};
+use crate::types::AuthMethod;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RotationRequest {
+    pub connection_ids: Vec<String>,
+    /// Defaults to `zync_rotated_<date>_ed25519` in the profile's ssh directory.
+    pub key_path: Option<String>,
+    pub passphrase: Option<String>,
+    pub comment: Option<String>,
+    pub remove_old: bool,
+    /// Old public key line; otherwise read from `<current key>.pub` per host.
+    pub old_public_key: Option<String>,
+    /// Point the saved connections at the new key once it is verified.
+    pub update_connections: bool,
+    pub concurrency: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RotationReport {
+    pub private_key_path: String,
+    pub public_key: String,
+    pub hosts: Vec<HostRotationStatus>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RotationProgress<'a> {
+    connection_id: &'a str,
+    step: RotationStep,
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<&'a str>,
+}
+
+fn emit_progress(app: &AppHandle, connection_id: &str, step: RotationStep, result: &Result<(), String>) {
+    let _ = app.emit(
+        "key-rotation:progress",
+        RotationProgress {
+            connection_id,
+            step,
+            ok: result.is_ok(),
+            error: result.as_ref().err().map(String::as_str),
+        },
+    );
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+fn default_key_path() -> Result<PathBuf, String> {
+    let home = dirs::home_dir().ok_or("Could not resolve the home directory")?;
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    let today = crate::time_tracking::utc_date(now);
+    Ok(crate::user_profiles::ssh_dir(&home)
+        .join(format!("zync_rotated_{}_ed25519", today.replace('-', ""))))
+}
+
+/// The old key blob for a host: the explicit one, else `<key_path>.pub`.
+fn old_blob_for(auth: &AuthMethod, explicit: Option<&str>) -> Option<String> {
+    if let Some(line) = explicit {
+        return key_blob(line).map(str::to_string);
+    }
+    let AuthMethod::PrivateKey { key_path, .. } = auth else {
+        return None;
+    };
+    let mut public_path = expand_home(key_path).into_os_string();
+    public_path.push(".pub");
+    let line = std::fs::read_to_string(public_path).ok()?;
+    key_blob(&line).map(str::to_string)
+}
+
+async fn exec_checked(state: &AppState, connection_id: &str, script: &str) -> Result<(), String> {
+    let output = exec_on_connection(state, connection_id, script).await?;
+    if output.exit_status == 0 {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    Err(if stderr.is_empty() {
+        format!("Remote command exited with status {}", output.exit_status)
+    } else {
+        stderr
+    })
+}
+
+struct Shared {
+    public_line: String,
+    new_blob: String,
+    private_key_path: String,
+    passphrase: Option<String>,
+    old_public_key: Option<String>,
+    remove_old: bool,
+}
+
+async fn rotate_host(app: AppHandle, connection_id: String, shared: Arc<Shared>) -> HostRotationStatus {
+    let state = app.state::<AppState>();
+    let mut status = HostRotationStatus {
+        connection_id: connection_id.clone(),
+        ..Default::default()
+    };
+    let config = {
+        let connections = state.connections.lock().await;
+        connections.get(&connection_id).map(|conn| conn.config.clone())
+    };
+    let Some(config) = config else {
+        status.error = Some("Connection not found".to_string());
+        return status;
+    };
+
+    let result = exec_checked(
+        &state,
+        &connection_id,
+        &install_script(&shared.public_line, &shared.new_blob),
+    )
+    .await;
+    emit_progress(&app, &connection_id, RotationStep::Install, &result);
+    if let Err(error) = result {
+        status.error = Some(format!("Install failed: {error}"));
+        return status;
+    }
+    status.installed = true;
+
+    // A fresh session authenticated only with the new key; the live one is untouched.
+    let mut verify_config = config.clone();
+    verify_config.id = format!("{connection_id}-rotation-verify");
+    verify_config.auth_method = AuthMethod::PrivateKey {
+        key_path: shared.private_key_path.clone(),
+        passphrase: shared.passphrase.clone(),
+    };
+    let result = match state
+        .ssh_manager
+        .connect(verify_config, state.tunnel_manager.clone())
+        .await
+    {
+        Ok(session) => {
+            let _ = session
+                .disconnect(russh::Disconnect::ByApplication, "", "en")
+                .await;
+            Ok(())
+        }
+        Err(error) => Err(error.to_string()),
+    };
+    emit_progress(&app, &connection_id, RotationStep::Verify, &result);
+    if let Err(error) = result {
+        status.error = Some(format!("Login with the new key failed: {error}"));
+        return status;
+    }
+    status.verified = true;
+
+    if shared.remove_old {
+        match old_blob_for(&config.auth_method, shared.old_public_key.as_deref()) {
+            Some(old_blob) if old_blob == shared.new_blob => {
+                status.note = Some("Old and new keys are identical; nothing removed".to_string());
+            }
+            Some(old_blob) => {
+                let result =
+                    exec_checked(&state, &connection_id, &remove_script(&old_blob, &shared.new_blob))
+                        .await;
+                emit_progress(&app, &connection_id, RotationStep::RemoveOld, &result);
+                match result {
+                    Ok(()) => status.old_key_removed = true,
+                    Err(error) => status.error = Some(format!("Removing the old key failed: {error}")),
+                }
+            }
+            None => {
+                status.note = Some(
+                    "Old public key unknown; pass it explicitly to remove it from this host"
+                        .to_string(),
+                );
+            }
+        }
+    }
+    status
+}
+
+/// Point saved connections that were verified with the new key at it.
+/// Vault-backed connections keep their credential reference.
+fn update_saved_connections(
+    app: &AppHandle,
+    statuses: &mut [HostRotationStatus],
+    private_key_path: &str,
+) -> Result<(), String> {
+    let _guard = CONNECTIONS_MUTATION_LOCK
+        .lock()
+        .map_err(|error| error.to_string())?;
+    let path = get_data_dir(app).join("connections.json");
+    let mut data = load_saved_data(&path).map_err(|error| error.to_string())?;
+    let mut changed = false;
+    for status in statuses.iter_mut().filter(|status| status.verified) {
+        let Some(connection) = data
+            .connections
+            .iter_mut()
+            .find(|connection| connection.id == status.connection_id && connection.auth_ref.is_none())
+        else {
+            continue;
+        };
+        connection.private_key_path = Some(private_key_path.to_string());
+        connection.password = None;
+        if connection.secret_ref.take().is_some() {
+            let _ = KeychainStore.delete(&connection.id);
+        }
+        status.connection_updated = true;
+        changed = true;
+    }
+    if changed {
+        save_saved_data_atomic(&path, &data).map_err(|error| error.to_string())?;
+    }
+    Ok(())
+}
+
+/// Generate a new ed25519 key and roll it out to `connectionIds` over their
+/// existing sessions: install, verify a login with the new key, then
+/// optionally remove the old key. Progress is emitted per host and step as
+/// `key-rotation:progress`.
+#[tauri::command]
+pub async fn key_rotation_run(app: AppHandle, request: RotationRequest) -> Result<RotationReport, String> {
+    if request.connection_ids.is_empty() {
+        return Err("Select at least one host".to_string());
+    }
+    if let Some(line) = request.old_public_key.as_deref() {
+        if key_blob(line).is_none() {
+            return Err("Old public key is not a valid OpenSSH public key".to_string());
+        }
+    }
+    let key_path = match request.key_path.as_deref().map(str::trim) {
+        Some(path) if !path.is_empty() => expand_home(path),
+        _ => default_key_path()?,
+    };
+    let comment = request.comment.unwrap_or_else(|| "zync-rotated".to_string());
+    let generated = generate_key(&key_path, request.passphrase.as_deref(), &comment)?;
+    let new_blob = key_blob(&generated.public_line)
+        .ok_or("Generated public key is malformed")?
+        .to_string();
+
+    let shared = Arc::new(Shared {
+        public_line: generated.public_line.clone(),
+        new_blob,
+        private_key_path: generated.private_key_path.clone(),
+        passphrase: request.passphrase,
+        old_public_key: request.old_public_key,
+        remove_old: request.remove_old,
+    });
+    let mut hosts = run_bounded(&app, request.connection_ids, request.concurrency, move |app, connection_id| {
+        rotate_host(app, connection_id, shared.clone())
+    })
+    .await;
+
+    if request.update_connections {
+        update_saved_connections(&app, &mut hosts, &generated.private_key_path)?;
+        for status in hosts.iter().filter(|status| status.connection_updated) {
+            emit_progress(&app, &status.connection_id, RotationStep::UpdateConnection, &Ok(()));
+        }
+    }
+
+    Ok(RotationReport {
+        private_key_path: generated.private_key_path,
+        public_key: generated.public_line,
+        hosts,
+    })
+}
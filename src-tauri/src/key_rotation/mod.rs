@@ -0,0 +1,174 @@
+//! Guided SSH key rotation.
+//!
+//! 1. Generate a new ed25519 key locally.
+//! 2. Append its public key to `authorized_keys` on each host over the existing session.
+//! 3. Verify a fresh login with the new key.
+//! 4. Optionally remove the old key, only on hosts where step 3 succeeded and only
+//!    while the new key is present, so a host can never be left without access.
+
+pub mod commands;
+
+use russh_keys::key::KeyPair;
+use russh_keys::PublicKeyBase64;
+use serde::Serialize;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RotationStep {
+    Install,
+    Verify,
+    RemoveOld,
+    UpdateConnection,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostRotationStatus {
+    pub connection_id: String,
+    pub installed: bool,
+    pub verified: bool,
+    pub old_key_removed: bool,
+    pub connection_updated: bool,
+    /// Set when removal was requested but skipped, e.g. no old key was known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Keep comments shell- and authorized_keys-safe.
+pub fn sanitize_comment(comment: &str) -> String {
+    comment
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '@' | '.' | '_' | '-'))
+        .collect()
+}
+
+/// The base64 key blob (second field) of an OpenSSH public key line.
+pub fn key_blob(public_line: &str) -> Option<&str> {
+    let blob = public_line.split_whitespace().nth(1)?;
+    blob.chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '='))
+        .then_some(blob)
+}
+
+pub struct GeneratedKey {
+    pub private_key_path: String,
+    pub public_line: String,
+}
+
+/// Generate an ed25519 key at `path` (PKCS#8 PEM, encrypted when a passphrase
+/// is given) plus `path.pub`. Refuses to overwrite an existing key.
+pub fn generate_key(path: &Path, passphrase: Option<&str>, comment: &str) -> Result<GeneratedKey, String> {
+    let mut public_path = path.as_os_str().to_owned();
+    public_path.push(".pub");
+    let public_path = std::path::PathBuf::from(public_path);
+    if path.exists() || public_path.exists() {
+        return Err(format!("{} already exists; choose another key path", path.display()));
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let key = KeyPair::generate_ed25519();
+    let mut pem = Vec::new();
+    let encoded = match passphrase.filter(|value| !value.is_empty()) {
+        Some(passphrase) => {
+            russh_keys::encode_pkcs8_pem_encrypted(&key, passphrase.as_bytes(), 100, &mut pem)
+        }
+        None => russh_keys::encode_pkcs8_pem(&key, &mut pem),
+    };
+    encoded.map_err(|e| format!("Failed to encode the new key: {e}"))?;
+    write_private_key(path, &pem)?;
+
+    let public_line = format!(
+        "{} {} {}",
+        key.name(),
+        key.public_key_base64(),
+        sanitize_comment(comment)
+    )
+    .trim_end()
+    .to_string();
+    std::fs::write(&public_path, format!("{public_line}\n")).map_err(|e| e.to_string())?;
+    Ok(GeneratedKey {
+        private_key_path: path.to_string_lossy().into_owned(),
+        public_line,
+    })
+}
+
+fn write_private_key(path: &Path, pem: &[u8]) -> Result<(), String> {
+    use std::io::Write;
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(path)
+        .map_err(|e| format!("Failed to create {}: {e}", path.display()))?;
+    file.write_all(pem).map_err(|e| e.to_string())
+}
+
+/// Append `public_line` to `authorized_keys` unless the key is already there.
+pub fn install_script(public_line: &str, blob: &str) -> String {
+    format!(
+        "umask 077; mkdir -p ~/.ssh && touch ~/.ssh/authorized_keys && chmod 700 ~/.ssh && chmod 600 ~/.ssh/authorized_keys && \
+         if ! grep -qF '{blob}' ~/.ssh/authorized_keys; then \
+         [ -z \"$(tail -c1 ~/.ssh/authorized_keys)\" ] || echo >> ~/.ssh/authorized_keys; \
+         printf '%s\\n' '{public_line}' >> ~/.ssh/authorized_keys; fi"
+    )
+}
+
+/// Remove lines holding `old_blob`, but only while `new_blob` is present.
+/// Rewrites in place (`cat >`) so ownership, mode and SELinux labels survive.
+pub fn remove_script(old_blob: &str, new_blob: &str) -> String {
+    format!(
+        "f=~/.ssh/authorized_keys; grep -qF '{new_blob}' \"$f\" || exit 3; \
+         grep -vF '{old_blob}' \"$f\" > \"$f.zync-rotate\" && cat \"$f.zync-rotate\" > \"$f\"; rc=$?; \
+         rm -f \"$f.zync-rotate\"; exit $rc"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINE: &str = "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIOMqqnkVzrm0SdG6UOoqKLsabgH5C9okWi0dh2l9GKJl zync@laptop";
+
+    #[test]
+    fn extracts_blob_and_rejects_unsafe_text() {
+        assert_eq!(
+            key_blob(LINE),
+            Some("AAAAC3NzaC1lZDI1NTE5AAAAIOMqqnkVzrm0SdG6UOoqKLsabgH5C9okWi0dh2l9GKJl")
+        );
+        assert_eq!(key_blob("ssh-ed25519 abc';rm -rf ~"), None);
+        assert_eq!(sanitize_comment("me@host'; echo pwned"), "me@hostechopwned");
+    }
+
+    #[test]
+    fn scripts_quote_key_material() {
+        let blob = key_blob(LINE).expect("blob");
+        let install = install_script(LINE, blob);
+        assert!(install.contains(&format!("grep -qF '{blob}'")));
+        assert!(install.contains(&format!("printf '%s\\n' '{LINE}'")));
+        let remove = remove_script("OLD", blob);
+        assert!(remove.contains(&format!("grep -qF '{blob}' \"$f\" || exit 3")));
+        assert!(remove.contains("grep -vF 'OLD'"));
+    }
+
+    #[test]
+    fn generates_key_pair_files_once() {
+        let dir = std::env::temp_dir().join(format!("zync-rotation-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("id_new");
+        let generated = generate_key(&path, None, "rotated").expect("generate");
+        assert!(generated.public_line.starts_with("ssh-ed25519 "));
+        assert!(generated.public_line.ends_with(" rotated"));
+        assert!(russh_keys::load_secret_key(&path, None).is_ok());
+        assert!(generate_key(&path, None, "again").is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
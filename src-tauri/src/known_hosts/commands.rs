@@ -0,0 +1,159 @@
+use super::scan::{scan_host_key, ScannedKey};
+use super::{
+    check_key, hash_content, hash_hostname, hosts_match, lines_for_host, lookup_target, parse,
+    read, remove_lines, write_with_backup, KeyStatus, KnownHostEntry,
+};
+use crate::commands::get_data_dir;
+use crate::types::SavedData;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+const SCAN_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub(crate) fn known_hosts_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let home = app.path().home_dir().map_err(|e| e.to_string())?;
+    Ok(crate::user_profiles::default_known_hosts_file(&home))
+}
+
+/// Parse the file and fill in which saved connections each entry covers.
+fn load_entries(app: &AppHandle) -> Result<Vec<KnownHostEntry>, String> {
+    let mut entries = parse(&read(&known_hosts_path(app)?)?);
+    let connections = std::fs::read_to_string(get_data_dir(app).join("connections.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<SavedData>(&content).ok())
+        .map(|saved| saved.connections)
+        .unwrap_or_default();
+    for entry in entries.iter_mut() {
+        entry.connection_ids = connections
+            .iter()
+            .filter(|connection| {
+                let (host, port) = lookup_target(
+                    &connection.host,
+                    connection.port,
+                    connection.host_key_alias.as_deref(),
+                );
+                hosts_match(&entry.hosts, host, port)
+            })
+            .map(|connection| connection.id.clone())
+            .collect();
+    }
+    Ok(entries)
+}
+
+/// Case-insensitive substring match on plain hosts, key type and fingerprint;
+/// hashed entries match only when `query` is exactly the hostname.
+fn matches_query(entry: &KnownHostEntry, query: &str) -> bool {
+    let needle = query.to_ascii_lowercase();
+    entry
+        .hosts
+        .iter()
+        .any(|host| !host.starts_with("|1|") && host.to_ascii_lowercase().contains(&needle))
+        || entry.key_type.to_ascii_lowercase().contains(&needle)
+        || entry.fingerprint.to_ascii_lowercase().contains(&needle)
+        || (entry.hashed && hosts_match(&entry.hosts, query, 22))
+}
+
+#[tauri::command]
+pub async fn known_hosts_list(
+    app: AppHandle,
+    query: Option<String>,
+) -> Result<Vec<KnownHostEntry>, String> {
+    let entries = load_entries(&app)?;
+    Ok(match query.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+        Some(query) => entries
+            .into_iter()
+            .filter(|entry| matches_query(entry, query))
+            .collect(),
+        None => entries,
+    })
+}
+
+/// Delete entries by line number. Returns how many lines were removed.
+#[tauri::command]
+pub async fn known_hosts_delete(app: AppHandle, lines: Vec<usize>) -> Result<usize, String> {
+    let path = known_hosts_path(&app)?;
+    let content = read(&path)?;
+    let (updated, removed) = remove_lines(&content, &lines.into_iter().collect());
+    if removed > 0 {
+        write_with_backup(&path, &updated)?;
+    }
+    Ok(removed)
+}
+
+/// Remove every key recorded for `host:port`, like `ssh-keygen -R`.
+#[tauri::command]
+pub async fn known_hosts_remove_host(
+    app: AppHandle,
+    host: String,
+    port: Option<u16>,
+) -> Result<usize, String> {
+    let path = known_hosts_path(&app)?;
+    let content = read(&path)?;
+    let lines: HashSet<usize> = lines_for_host(&content, &host, port.unwrap_or(22));
+    let (updated, removed) = remove_lines(&content, &lines);
+    if removed > 0 {
+        write_with_backup(&path, &updated)?;
+    }
+    Ok(removed)
+}
+
+/// Hash all plain hostnames, like `ssh-keygen -H`. Returns how many names were hashed.
+#[tauri::command]
+pub async fn known_hosts_hash(app: AppHandle) -> Result<usize, String> {
+    let path = known_hosts_path(&app)?;
+    let content = read(&path)?;
+    let (updated, hashed) = hash_content(&content, hash_hostname);
+    if hashed > 0 {
+        write_with_backup(&path, &updated)?;
+    }
+    Ok(hashed)
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RescanResult {
+    pub scanned: ScannedKey,
+    #[serde(flatten)]
+    pub status: KeyStatus,
+    /// Entries currently recorded for the host.
+    pub recorded: Vec<KnownHostEntry>,
+}
+
+/// Settle an `ssh:host-key-prompt`: `true` trusts and records the key and lets
+/// the connect continue, `false` rejects it.
+#[tauri::command]
+pub async fn ssh_host_key_respond(prompt_id: String, trust: bool) -> Result<(), String> {
+    super::prompt::respond(&prompt_id, trust)
+}
+
+/// Fetch the host's current key and compare it with what known_hosts records.
+/// The scan can be stopped with `cancel_operation(operation_id)`.
+#[tauri::command]
+pub async fn known_hosts_rescan(
+    app: AppHandle,
+    host: String,
+    port: Option<u16>,
+    operation_id: Option<String>,
+) -> Result<RescanResult, String> {
+    let port = port.unwrap_or(22);
+    let operation = crate::operations::begin(
+        crate::operations::OperationKind::Scan,
+        operation_id,
+        format!("Host key scan of {host}:{port}"),
+    );
+    let scanned = operation.run(scan_host_key(&host, port, SCAN_TIMEOUT)).await?;
+    let entries = load_entries(&app)?;
+    let status = check_key(&entries, &host, port, &scanned.key);
+    let recorded = entries
+        .into_iter()
+        .filter(|entry| hosts_match(&entry.hosts, &host, port))
+        .collect();
+    Ok(RescanResult {
+        scanned,
+        status,
+        recorded,
+    })
+}
@@ -0,0 +1,701 @@
+//! `~/.ssh/known_hosts` parsing and editing, compatible with OpenSSH.
+//!
+//! Host fields may be plain names, `[host]:port`, wildcard patterns, negated
+//! patterns (`!host`) or hashed names (`|1|salt|hmac`, see `HashKnownHosts`).
+//! Edits keep comments and unknown lines untouched and leave the previous file
+//! in `known_hosts.old`, like `ssh-keygen -R` / `-H`.
+
+pub mod commands;
+pub mod prompt;
+pub mod scan;
+
+use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD};
+use base64::Engine;
+use rand_core::{OsRng, RngCore};
+use crate::types::StrictHostKeyChecking;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex as StdMutex};
+
+const HASH_MAGIC: &str = "|1|";
+
+/// Serializes appends from concurrent handshakes.
+static APPEND_LOCK: LazyLock<StdMutex<()>> = LazyLock::new(|| StdMutex::new(()));
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KnownHostEntry {
+    /// 1-based line number in the file, used to address the entry for deletion.
+    pub line: usize,
+    /// `@cert-authority` or `@revoked`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub marker: Option<String>,
+    pub hosts: Vec<String>,
+    pub hashed: bool,
+    pub key_type: String,
+    pub key: String,
+    /// `SHA256:...`, as printed by `ssh-keygen -l`.
+    pub fingerprint: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    /// Saved connections whose host/port this entry covers.
+    pub connection_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "kebab-case")]
+pub enum KeyStatus {
+    /// An entry for the host records exactly this key.
+    Known { line: usize },
+    /// The host has an entry for this key type with a different key.
+    Changed { line: usize },
+    /// The key is listed under `@revoked`.
+    Revoked { line: usize },
+    Unknown,
+}
+
+/// Host field as OpenSSH writes it: `host`, or `[host]:port` for non-default ports.
+pub fn host_port(host: &str, port: u16) -> String {
+    if port == 22 {
+        host.to_string()
+    } else {
+        format!("[{host}]:{port}")
+    }
+}
+
+pub fn fingerprint(key_base64: &str) -> Option<String> {
+    use sha2::Digest;
+    let blob = STANDARD.decode(key_base64).ok()?;
+    let digest = sha2::Sha256::digest(&blob);
+    Some(format!("SHA256:{}", STANDARD_NO_PAD.encode(&digest[..])))
+}
+
+/// Key type embedded in the key blob (the first SSH string).
+pub fn key_type_of(key_base64: &str) -> Option<String> {
+    let blob = STANDARD.decode(key_base64).ok()?;
+    let len = u32::from_be_bytes(blob.get(..4)?.try_into().ok()?) as usize;
+    let name = blob.get(4..4 + len)?;
+    String::from_utf8(name.to_vec()).ok()
+}
+
+fn sha1(data: &[&[u8]]) -> [u8; 20] {
+    use sha1::Digest;
+    let mut hasher = sha1::Sha1::new();
+    for part in data {
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+/// HMAC-SHA1 (RFC 2104), the MAC OpenSSH uses for hashed hostnames.
+fn hmac_sha1(key: &[u8], message: &[u8]) -> [u8; 20] {
+    const BLOCK: usize = 64;
+    let mut block = [0u8; BLOCK];
+    if key.len() > BLOCK {
+        block[..20].copy_from_slice(&sha1(&[key]));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let inner_pad = block.map(|byte| byte ^ 0x36);
+    let outer_pad = block.map(|byte| byte ^ 0x5c);
+    let inner = sha1(&[&inner_pad, message]);
+    sha1(&[&outer_pad, &inner])
+}
+
+fn hash_with_salt(host_port: &str, salt: &[u8]) -> String {
+    let mac = hmac_sha1(salt, host_port.as_bytes());
+    format!("{HASH_MAGIC}{}|{}", STANDARD.encode(salt), STANDARD.encode(mac))
+}
+
+pub fn hash_hostname(host_port: &str) -> String {
+    let mut salt = [0u8; 20];
+    OsRng.fill_bytes(&mut salt);
+    hash_with_salt(host_port, &salt)
+}
+
+fn hashed_matches(pattern: &str, host_port: &str) -> bool {
+    let mut parts = pattern[HASH_MAGIC.len()..].split('|');
+    let (Some(salt), Some(mac)) = (parts.next(), parts.next()) else {
+        return false;
+    };
+    let (Ok(salt), Ok(mac)) = (STANDARD.decode(salt), STANDARD.decode(mac)) else {
+        return false;
+    };
+    hmac_sha1(&salt, host_port.as_bytes())[..] == mac[..]
+}
+
+fn glob_match(pattern: &[u8], value: &[u8]) -> bool {
+    match (pattern.first(), value.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            glob_match(&pattern[1..], value) || (!value.is_empty() && glob_match(pattern, &value[1..]))
+        }
+        (Some(b'?'), Some(_)) => glob_match(&pattern[1..], &value[1..]),
+        (Some(p), Some(v)) if p.eq_ignore_ascii_case(v) => glob_match(&pattern[1..], &value[1..]),
+        _ => false,
+    }
+}
+
+/// True when one (non-negated) host pattern matches `host_port`.
+fn pattern_matches(pattern: &str, host_port: &str) -> bool {
+    if pattern.starts_with(HASH_MAGIC) {
+        hashed_matches(pattern, host_port)
+    } else {
+        glob_match(pattern.as_bytes(), host_port.as_bytes())
+    }
+}
+
+/// OpenSSH semantics: any positive match, and no negated (`!pattern`) match.
+pub fn hosts_match(hosts: &[String], host: &str, port: u16) -> bool {
+    let target = host_port(host, port);
+    let mut matched = false;
+    for pattern in hosts {
+        if let Some(negated) = pattern.strip_prefix('!') {
+            if pattern_matches(negated, &target) {
+                return false;
+            }
+        } else if pattern_matches(pattern, &target) {
+            matched = true;
+        }
+    }
+    matched
+}
+
+fn parse_line(line: usize, text: &str) -> Option<KnownHostEntry> {
+    let text = text.trim();
+    if text.is_empty() || text.starts_with('#') {
+        return None;
+    }
+    let mut fields = text.split_whitespace();
+    let mut first = fields.next()?;
+    let marker = if first.starts_with('@') {
+        let marker = first.to_string();
+        first = fields.next()?;
+        Some(marker)
+    } else {
+        None
+    };
+    let key_type = fields.next()?.to_string();
+    let key = fields.next()?.to_string();
+    let comment = fields.collect::<Vec<_>>().join(" ");
+    let hosts: Vec<String> = first.split(',').map(str::to_string).collect();
+    Some(KnownHostEntry {
+        line,
+        marker,
+        hashed: hosts.iter().any(|host| host.starts_with(HASH_MAGIC)),
+        fingerprint: fingerprint(&key).unwrap_or_default(),
+        hosts,
+        key_type,
+        key,
+        comment: (!comment.is_empty()).then_some(comment),
+        connection_ids: Vec::new(),
+    })
+}
+
+pub fn parse(content: &str) -> Vec<KnownHostEntry> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(index, text)| parse_line(index + 1, text))
+        .collect()
+}
+
+/// Compare a server key against the recorded entries for `host:port`.
+pub fn check_key(entries: &[KnownHostEntry], host: &str, port: u16, key_base64: &str) -> KeyStatus {
+    let key_type = key_type_of(key_base64);
+    let mut changed = None;
+    for entry in entries.iter().filter(|entry| hosts_match(&entry.hosts, host, port)) {
+        match entry.marker.as_deref() {
+            Some("@revoked") if entry.key == key_base64 => {
+                return KeyStatus::Revoked { line: entry.line };
+            }
+            Some(_) => continue,
+            None => {}
+        }
+        if entry.key == key_base64 {
+            return KeyStatus::Known { line: entry.line };
+        }
+        if changed.is_none() && key_type_of(&entry.key) == key_type {
+            changed = Some(entry.line);
+        }
+    }
+    match changed {
+        Some(line) => KeyStatus::Changed { line },
+        None => KeyStatus::Unknown,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HostKeyDecision {
+    Accept,
+    /// Accept and append the key to known_hosts.
+    Record,
+    Reject(String),
+}
+
+/// Why [`verify_server_key`] refused a key.
+#[derive(Debug, Clone)]
+pub struct HostKeyRejection {
+    pub reason: String,
+    /// `None` when known_hosts could not be read.
+    pub status: Option<KeyStatus>,
+    /// `path:line` of the other key, for [`KeyStatus::Changed`].
+    pub recorded_at: Option<String>,
+    pub recorded_fingerprint: Option<String>,
+    /// The policy the key was checked under.
+    pub mode: StrictHostKeyChecking,
+}
+
+impl HostKeyRejection {
+    fn unreadable(reason: String, mode: StrictHostKeyChecking) -> Self {
+        Self {
+            reason,
+            status: None,
+            recorded_at: None,
+            recorded_fingerprint: None,
+            mode,
+        }
+    }
+
+    /// Whether the user may still choose to trust the key: it differs from the
+    /// recorded one, or it is new and the policy allows recording new keys.
+    /// `yes` refuses anything not already known, and revoked keys never are.
+    pub fn can_be_trusted(&self) -> bool {
+        match self.status {
+            Some(KeyStatus::Changed { .. }) => true,
+            Some(KeyStatus::Unknown) => self.mode != StrictHostKeyChecking::Yes,
+            _ => false,
+        }
+    }
+}
+
+/// OpenSSH's `StrictHostKeyChecking` outcome for a lookup result. Revoked keys
+/// are refused in every mode.
+pub fn decide(status: &KeyStatus, mode: StrictHostKeyChecking, host_port: &str) -> HostKeyDecision {
+    match (status, mode) {
+        (KeyStatus::Known { .. }, _) => HostKeyDecision::Accept,
+        (KeyStatus::Revoked { line }, _) => HostKeyDecision::Reject(format!(
+            "Host key for {host_port} is marked @revoked in known_hosts (line {line})"
+        )),
+        (KeyStatus::Unknown, StrictHostKeyChecking::Yes) => HostKeyDecision::Reject(format!(
+            "No host key is known for {host_port} and strict host key checking is enabled"
+        )),
+        (KeyStatus::Unknown, _) => HostKeyDecision::Record,
+        (KeyStatus::Changed { line }, StrictHostKeyChecking::No) => {
+            eprintln!(
+                "[SSH] Host key for {host_port} differs from known_hosts line {line}; accepting because strict checking is off"
+            );
+            HostKeyDecision::Accept
+        }
+        (KeyStatus::Changed { line }, _) => HostKeyDecision::Reject(format!(
+            "REMOTE HOST IDENTIFICATION HAS CHANGED for {host_port}: the key differs from known_hosts line {line}. \
+             Remove the old entry if the change is expected"
+        )),
+    }
+}
+
+/// Append `host key_type key` without touching the rest of the file.
+pub fn append_entry(path: &Path, host_port: &str, key_type: &str, key_base64: &str) -> Result<(), String> {
+    use std::io::Write;
+    let _guard = APPEND_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let existing = read(path)?;
+    let separator = if existing.is_empty() || existing.ends_with('\n') { "" } else { "\n" };
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|error| format!("Failed to open {}: {error}", path.display()))?;
+    file.write_all(format!("{separator}{host_port} {key_type} {key_base64}\n").as_bytes())
+        .map_err(|error| format!("Failed to write {}: {error}", path.display()))
+}
+
+/// Host and port a connection's keys are recorded under. Like OpenSSH, a
+/// `HostKeyAlias` replaces both, so it is written without a port.
+pub fn lookup_target<'a>(host: &'a str, port: u16, alias: Option<&'a str>) -> (&'a str, u16) {
+    match alias.map(str::trim).filter(|alias| !alias.is_empty()) {
+        Some(alias) => (alias, 22),
+        None => (host, port),
+    }
+}
+
+/// Files named by a `UserKnownHostsFile` setting (space-separated, `~`
+/// expanded), or `~/.ssh/known_hosts` when unset. `none` means no file.
+pub fn known_hosts_files(home: &Path, setting: Option<&str>) -> Vec<PathBuf> {
+    let setting = setting.map(str::trim).filter(|setting| !setting.is_empty());
+    let Some(setting) = setting else {
+        return vec![home.join(".ssh").join("known_hosts")];
+    };
+    if setting.eq_ignore_ascii_case("none") {
+        return Vec::new();
+    }
+    setting
+        .split_whitespace()
+        .map(|path| match path.strip_prefix("~/") {
+            Some(rest) => home.join(rest),
+            None if path == "~" => home.to_path_buf(),
+            None => PathBuf::from(path),
+        })
+        .collect()
+}
+
+/// Check a server key during the handshake, recording it in the first file
+/// when `mode` allows. A key known in any file is accepted, unless one of them
+/// revokes it.
+pub fn verify_server_key(
+    paths: &[PathBuf],
+    host: &str,
+    port: u16,
+    key_type: &str,
+    key_base64: &str,
+    mode: StrictHostKeyChecking,
+) -> Result<(), HostKeyRejection> {
+    let mut status = KeyStatus::Unknown;
+    let mut recorded = None::<(String, String)>;
+    for path in paths {
+        let entries = parse(&read(path).map_err(|error| HostKeyRejection::unreadable(error, mode))?);
+        let found = check_key(&entries, host, port, key_base64);
+        if status_rank(&found) > status_rank(&status) {
+            if let KeyStatus::Changed { line } = found {
+                recorded = entries
+                    .into_iter()
+                    .find(|entry| entry.line == line)
+                    .map(|entry| (format!("{}:{line}", path.display()), entry.fingerprint));
+            }
+            status = found;
+        }
+    }
+    let host_port = host_port(host, port);
+    match decide(&status, mode, &host_port) {
+        HostKeyDecision::Accept => Ok(()),
+        HostKeyDecision::Record => {
+            let Some(path) = paths.first() else {
+                return Ok(());
+            };
+            // Failing to record must not block a connection the policy allows.
+            if let Err(error) = append_entry(path, &host_port, key_type, key_base64) {
+                eprintln!("[SSH] Could not record host key for {host_port}: {error}");
+            }
+            Ok(())
+        }
+        HostKeyDecision::Reject(reason) => {
+            let (recorded_at, recorded_fingerprint) = recorded.unzip();
+            Err(HostKeyRejection {
+                reason,
+                status: Some(status),
+                recorded_at,
+                recorded_fingerprint,
+                mode,
+            })
+        }
+    }
+}
+
+/// Record a key the user chose to trust despite a rejection. Entries of the
+/// same type holding another key for `host:port` are dropped from every file
+/// (keeping `known_hosts.old`), wildcard patterns excepted, and the key is
+/// appended to the first file.
+pub fn trust_host_key(
+    paths: &[PathBuf],
+    host: &str,
+    port: u16,
+    key_type: &str,
+    key_base64: &str,
+) -> Result<(), String> {
+    let replaced_type = key_type_of(key_base64);
+    for path in paths {
+        let content = read(path)?;
+        let stale: HashSet<usize> = parse(&content)
+            .into_iter()
+            .filter(|entry| {
+                entry.marker.is_none()
+                    && entry.key != key_base64
+                    && key_type_of(&entry.key) == replaced_type
+                    && hosts_match(&entry.hosts, host, port)
+                    && !entry.hosts.iter().any(|pattern| pattern.contains(['*', '?', '!']))
+            })
+            .map(|entry| entry.line)
+            .collect();
+        if !stale.is_empty() {
+            write_with_backup(path, &remove_lines(&content, &stale).0)?;
+        }
+    }
+    match paths.first() {
+        Some(path) => append_entry(path, &host_port(host, port), key_type, key_base64),
+        None => Ok(()),
+    }
+}
+
+fn status_rank(status: &KeyStatus) -> u8 {
+    match status {
+        KeyStatus::Unknown => 0,
+        KeyStatus::Changed { .. } => 1,
+        KeyStatus::Known { .. } => 2,
+        KeyStatus::Revoked { .. } => 3,
+    }
+}
+
+/// Drop the given 1-based lines; returns the new content and how many were removed.
+pub fn remove_lines(content: &str, lines: &HashSet<usize>) -> (String, usize) {
+    let mut removed = 0;
+    let mut kept = String::with_capacity(content.len());
+    for (index, text) in content.lines().enumerate() {
+        if lines.contains(&(index + 1)) {
+            removed += 1;
+            continue;
+        }
+        kept.push_str(text);
+        kept.push('\n');
+    }
+    (kept, removed)
+}
+
+/// Lines that hold a key for `host:port` (what `ssh-keygen -R` deletes).
+pub fn lines_for_host(content: &str, host: &str, port: u16) -> HashSet<usize> {
+    parse(content)
+        .into_iter()
+        .filter(|entry| hosts_match(&entry.hosts, host, port))
+        .map(|entry| entry.line)
+        .collect()
+}
+
+/// Hash every plain hostname, like `ssh-keygen -H`. Lines listing several names
+/// are split into one hashed line per name; wildcard and negated patterns cannot
+/// be hashed and are left as they are. Returns the new content and names hashed.
+pub fn hash_content(content: &str, hash: impl Fn(&str) -> String) -> (String, usize) {
+    let mut hashed = 0;
+    let mut output = String::with_capacity(content.len());
+    for (index, text) in content.lines().enumerate() {
+        let hashable = parse_line(index + 1, text).filter(|entry| {
+            entry.hosts.iter().all(|host| {
+                !host.starts_with(HASH_MAGIC) && !host.starts_with('!') && !host.contains(['*', '?'])
+            })
+        });
+        let Some(entry) = hashable else {
+            output.push_str(text);
+            output.push('\n');
+            continue;
+        };
+        let rest = text
+            .trim()
+            .split_once(char::is_whitespace)
+            .map(|(_, rest)| rest)
+            .unwrap_or_default();
+        // `rest` starts after the host field, or after the marker when there is one.
+        let rest = match entry.marker {
+            Some(_) => rest
+                .trim_start()
+                .split_once(char::is_whitespace)
+                .map(|(_, rest)| rest)
+                .unwrap_or_default(),
+            None => rest,
+        }
+        .trim_start();
+        for host in &entry.hosts {
+            if let Some(marker) = &entry.marker {
+                output.push_str(marker);
+                output.push(' ');
+            }
+            output.push_str(&hash(host));
+            output.push(' ');
+            output.push_str(rest);
+            output.push('\n');
+            hashed += 1;
+        }
+    }
+    (output, hashed)
+}
+
+pub fn read(path: &Path) -> Result<String, String> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(error) => Err(format!("Failed to read {}: {error}", path.display())),
+    }
+}
+
+/// Replace the file, keeping the previous version as `known_hosts.old`.
+pub fn write_with_backup(path: &Path, content: &str) -> Result<(), String> {
+    if path.exists() {
+        std::fs::copy(path, path.with_extension("old"))
+            .map_err(|error| format!("Failed to back up {}: {error}", path.display()))?;
+    }
+    crate::atomic_io::durable_replace(path, content.as_bytes())
+        .map_err(|error| format!("Failed to write {}: {error}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ssh-ed25519 key blob for a throwaway key.
+    const ED25519: &str = "AAAAC3NzaC1lZDI1NTE5AAAAIOMqqnkVzrm0SdG6UOoqKLsabgH5C9okWi0dh2l9GKJl";
+    const OTHER_ED25519: &str =
+        "AAAAC3NzaC1lZDI1NTE5AAAAIBr4uFvd4mrETbsRFWPc6Qo+tr1Z2Y7YTv7Lcsa0Ntmo";
+
+    #[test]
+    fn hmac_sha1_matches_rfc2202() {
+        let mac = hmac_sha1(&[0x0b; 20], b"Hi There");
+        let hex: String = mac.iter().map(|byte| format!("{byte:02x}")).collect();
+        assert_eq!(hex, "b617318655057264e28bc0b6fb378c8ef146be00");
+    }
+
+    #[test]
+    fn matches_plain_bracketed_hashed_and_negated_hosts() {
+        let hashed = hash_with_salt("[db.example.com]:2222", &[7u8; 20]);
+        let content = format!(
+            "# comment\nweb.example.com,10.0.0.5 ssh-ed25519 {ED25519}\n{hashed} ssh-ed25519 {ED25519}\n*.corp,!vpn.corp ssh-ed25519 {ED25519} wildcard\n"
+        );
+        let entries = parse(&content);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].line, 2);
+        assert!(hosts_match(&entries[0].hosts, "10.0.0.5", 22));
+        assert!(!hosts_match(&entries[0].hosts, "web.example.com", 2222));
+        assert!(entries[1].hashed);
+        assert!(hosts_match(&entries[1].hosts, "db.example.com", 2222));
+        assert!(hosts_match(&entries[2].hosts, "git.corp", 22));
+        assert!(!hosts_match(&entries[2].hosts, "vpn.corp", 22));
+        assert_eq!(entries[2].comment.as_deref(), Some("wildcard"));
+    }
+
+    #[test]
+    fn check_key_reports_known_changed_and_unknown() {
+        let content = format!("host1 ssh-ed25519 {ED25519}\n");
+        let entries = parse(&content);
+        assert_eq!(check_key(&entries, "host1", 22, ED25519), KeyStatus::Known { line: 1 });
+        assert_eq!(check_key(&entries, "host1", 22, OTHER_ED25519), KeyStatus::Changed { line: 1 });
+        assert_eq!(check_key(&entries, "host2", 22, ED25519), KeyStatus::Unknown);
+    }
+
+    #[test]
+    fn strict_modes_follow_openssh() {
+        use StrictHostKeyChecking::{AcceptNew, No, Yes};
+        let changed = KeyStatus::Changed { line: 3 };
+        assert_eq!(decide(&KeyStatus::Known { line: 1 }, Yes, "h"), HostKeyDecision::Accept);
+        assert!(matches!(decide(&KeyStatus::Unknown, Yes, "h"), HostKeyDecision::Reject(_)));
+        assert_eq!(decide(&KeyStatus::Unknown, AcceptNew, "h"), HostKeyDecision::Record);
+        assert!(matches!(decide(&changed, AcceptNew, "h"), HostKeyDecision::Reject(_)));
+        assert_eq!(decide(&changed, No, "h"), HostKeyDecision::Accept);
+        assert!(matches!(
+            decide(&KeyStatus::Revoked { line: 2 }, No, "h"),
+            HostKeyDecision::Reject(_)
+        ));
+    }
+
+    #[test]
+    fn accept_new_records_then_recognizes_key() {
+        let dir = std::env::temp_dir().join(format!("zync-known-hosts-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("known_hosts");
+        std::fs::create_dir_all(&dir).expect("dir");
+        std::fs::write(&path, "# keep me").expect("seed");
+        let mode = StrictHostKeyChecking::AcceptNew;
+        let paths = [path.clone()];
+        verify_server_key(&paths, "h", 2222, "ssh-ed25519", ED25519, mode).expect("first use");
+        verify_server_key(&paths, "h", 2222, "ssh-ed25519", ED25519, StrictHostKeyChecking::Yes)
+            .expect("now known");
+        assert!(verify_server_key(&paths, "h", 2222, "ssh-ed25519", OTHER_ED25519, mode).is_err());
+        let content = std::fs::read_to_string(&path).expect("read");
+        assert_eq!(content, format!("# keep me\n[h]:2222 ssh-ed25519 {ED25519}\n"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn host_key_alias_and_per_host_files_choose_the_records() {
+        let dir = std::env::temp_dir().join(format!("zync-known-hosts-alias-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join(".ssh")).expect("dir");
+        let shared = dir.join("shared_hosts");
+        std::fs::write(&shared, format!("app-a ssh-ed25519 {ED25519}\n")).expect("seed");
+
+        let files = known_hosts_files(&dir, Some("~/own_hosts ~/shared_hosts"));
+        assert_eq!(files, vec![dir.join("own_hosts"), shared.clone()]);
+        assert_eq!(known_hosts_files(&dir, None), vec![dir.join(".ssh").join("known_hosts")]);
+        assert!(known_hosts_files(&dir, Some("none")).is_empty());
+
+        let (host, port) = lookup_target("localhost", 2222, Some("app-a"));
+        assert_eq!((host, port), ("app-a", 22));
+        let strict = StrictHostKeyChecking::Yes;
+        verify_server_key(&files, host, port, "ssh-ed25519", ED25519, strict).expect("known via alias");
+        assert!(verify_server_key(&files, "localhost", 2222, "ssh-ed25519", ED25519, strict).is_err());
+
+        // New keys go to the first file.
+        let (host, port) = lookup_target("localhost", 2222, Some("app-b"));
+        verify_server_key(&files, host, port, "ssh-ed25519", OTHER_ED25519, StrictHostKeyChecking::AcceptNew)
+            .expect("recorded");
+        let own = std::fs::read_to_string(dir.join("own_hosts")).expect("read");
+        assert_eq!(own, format!("app-b ssh-ed25519 {OTHER_ED25519}\n"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn trusting_a_changed_key_replaces_only_its_entry() {
+        let dir = std::env::temp_dir().join(format!("zync-known-hosts-trust-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).expect("dir");
+        let path = dir.join("known_hosts");
+        let kept = format!("*.corp ssh-ed25519 {ED25519}\nother ssh-ed25519 {ED25519}\n");
+        std::fs::write(&path, format!("h ssh-ed25519 {ED25519}\n{kept}")).expect("seed");
+        let paths = [path.clone()];
+        let strict = StrictHostKeyChecking::Yes;
+
+        let rejection = verify_server_key(&paths, "h", 22, "ssh-ed25519", OTHER_ED25519, strict)
+            .expect_err("changed");
+        assert!(rejection.can_be_trusted());
+        assert_eq!(rejection.recorded_at, Some(format!("{}:1", path.display())));
+
+        trust_host_key(&paths, "h", 22, "ssh-ed25519", OTHER_ED25519).expect("trust");
+        verify_server_key(&paths, "h", 22, "ssh-ed25519", OTHER_ED25519, strict).expect("trusted");
+        let content = std::fs::read_to_string(&path).expect("read");
+        assert_eq!(content, format!("{kept}h ssh-ed25519 {OTHER_ED25519}\n"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn unknown_host_under_strict_checking_is_not_offered() {
+        let dir = std::env::temp_dir().join(format!("zync-known-hosts-strict-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).expect("dir");
+        let path = dir.join("known_hosts");
+        std::fs::write(&path, format!("other ssh-ed25519 {ED25519}\n")).expect("seed");
+        let paths = [path.clone()];
+
+        let rejection = verify_server_key(&paths, "new", 22, "ssh-ed25519", ED25519, StrictHostKeyChecking::Yes)
+            .expect_err("unknown under yes");
+        assert!(matches!(rejection.status, Some(KeyStatus::Unknown)));
+        assert!(!rejection.can_be_trusted());
+        assert_eq!(std::fs::read_to_string(&path).expect("read"), format!("other ssh-ed25519 {ED25519}\n"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn hashing_splits_names_and_keeps_patterns() {
+        let content = format!("a,b ssh-ed25519 {ED25519} note\n*.corp ssh-ed25519 {ED25519}\n");
+        let (hashed, count) = hash_content(&content, |host| format!("H({host})"));
+        assert_eq!(count, 2);
+        assert_eq!(
+            hashed,
+            format!(
+                "H(a) ssh-ed25519 {ED25519} note\nH(b) ssh-ed25519 {ED25519} note\n*.corp ssh-ed25519 {ED25519}\n"
+            )
+        );
+    }
+
+    #[test]
+    fn removes_lines_for_host() {
+        let content = format!("a ssh-ed25519 {ED25519}\nb ssh-ed25519 {ED25519}\n");
+        let lines = lines_for_host(&content, "b", 22);
+        let (kept, removed) = remove_lines(&content, &lines);
+        assert_eq!(removed, 1);
+        assert_eq!(kept, format!("a ssh-ed25519 {ED25519}\n"));
+    }
+
+    #[test]
+    fn reads_key_type_and_fingerprint_from_blob() {
+        assert_eq!(key_type_of(ED25519).as_deref(), Some("ssh-ed25519"));
+        assert!(fingerprint(ED25519).is_some_and(|fp| fp.starts_with("SHA256:")));
+    }
+}
@@ -0,0 +1,65 @@
+//! Asking the user about a host key the policy refused: a key that differs
+//! from the recorded one, or a new key under strict checking.
+//!
+//! The handshake waits while `ssh:host-key-prompt` is on screen and
+//! `ssh_host_key_respond` settles it; `ssh:host-key-prompt-done` withdraws it
+//! however it ended. Without an answer the key stays rejected.
+
+use crate::prompt_broker::PromptBroker;
+use serde::Serialize;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+/// Below OpenSSH's default `LoginGraceTime`, after which the server hangs up anyway.
+const PROMPT_TIMEOUT: Duration = Duration::from_secs(110);
+
+static BROKER: LazyLock<PromptBroker<bool>> = LazyLock::new(|| {
+    PromptBroker::new(
+        "ssh:host-key-prompt",
+        "ssh:host-key-prompt-done",
+        PROMPT_TIMEOUT,
+    )
+});
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostKeyPrompt {
+    pub prompt_id: String,
+    pub connection_id: String,
+    /// Host field as recorded in known_hosts: `host` or `[host]:port`.
+    pub endpoint: String,
+    pub key_type: String,
+    pub fingerprint: String,
+    /// Fingerprint of the differing recorded key; `None` for a new key.
+    pub recorded_fingerprint: Option<String>,
+    /// `path:line` of that recorded key.
+    pub recorded_at: Option<String>,
+}
+
+/// Whether the user trusts the key in `prompt` (its `prompt_id` is filled in
+/// here). `false` when rejected, timed out, or there is no window to ask in.
+pub async fn ask(prompt: HostKeyPrompt) -> bool {
+    let payload = |prompt_id| HostKeyPrompt {
+        prompt_id,
+        ..prompt
+    };
+    BROKER.ask(payload, None).await.unwrap_or(false)
+}
+
+/// Settle prompt `prompt_id`: `true` trusts and records the key.
+pub fn respond(prompt_id: &str, trust: bool) -> Result<(), String> {
+    BROKER.respond(prompt_id, trust)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn delivers_the_decision_once() {
+        let answer = BROKER.register("changed", None);
+        respond("changed", true).unwrap();
+        assert!(answer.await.unwrap());
+        assert!(respond("changed", false).is_err());
+    }
+}
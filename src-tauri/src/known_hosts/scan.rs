@@ -0,0 +1,57 @@
+//! Fetch a host's current public key without authenticating (like `ssh-keyscan`).
+
+use russh::client;
+use russh_keys::key::PublicKey;
+use russh_keys::PublicKeyBase64;
+use serde::Serialize;
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScannedKey {
+    pub key_type: String,
+    pub key: String,
+    pub fingerprint: String,
+}
+
+/// Records the server key and rejects it, which ends the handshake right there.
+struct KeyCapture {
+    key: Arc<StdMutex<Option<PublicKey>>>,
+}
+
+#[async_trait::async_trait]
+impl client::Handler for KeyCapture {
+    type Error = russh::Error;
+
+    async fn check_server_key(&mut self, server_public_key: &PublicKey) -> Result<bool, Self::Error> {
+        if let Ok(mut slot) = self.key.lock() {
+            *slot = Some(server_public_key.clone());
+        }
+        Ok(false)
+    }
+}
+
+pub async fn scan_host_key(host: &str, port: u16, timeout: Duration) -> Result<ScannedKey, String> {
+    let captured = Arc::new(StdMutex::new(None));
+    let handler = KeyCapture {
+        key: captured.clone(),
+    };
+    let config = Arc::new(client::Config::default());
+    // The connect is expected to fail once the key is rejected.
+    let outcome = tokio::time::timeout(timeout, client::connect(config, (host, port), handler)).await;
+    let key = captured.lock().ok().and_then(|mut slot| slot.take());
+    let Some(key) = key else {
+        return Err(match outcome {
+            Err(_) => format!("Timed out fetching the host key of {host}:{port}"),
+            Ok(Err(error)) => format!("Failed to fetch the host key of {host}:{port}: {error}"),
+            Ok(Ok(_)) => format!("{host}:{port} did not present a host key"),
+        });
+    };
+    let encoded = key.public_key_base64();
+    Ok(ScannedKey {
+        key_type: super::key_type_of(&encoded).unwrap_or_else(|| key.name().to_string()),
+        fingerprint: super::fingerprint(&encoded).unwrap_or_default(),
+        key: encoded,
+    })
+}
@@ -1,28 +1,91 @@
+mod access_proxy;
+mod accounts;
 mod ai;
 mod atomic_io;
+mod auth_prompts;
+mod availability;
+mod bench;
+mod canonical_host;
+mod clipboard_bridge;
+mod clipboard_history;
 mod commands;
+mod crash_reports;
+mod credential_expiry;
+mod cron;
+mod data_format;
+mod data_validation;
+mod dedupe;
+mod deploy_watch;
+mod diagnostics;
+mod doctor;
+mod events;
+mod expect;
+mod firewall;
+mod fleet;
 mod fs;
 mod ghost;
+mod handoff;
+mod host_capabilities;
+mod host_power;
+mod host_snapshot;
+mod jump_sessions;
+mod keepalive;
+mod key_rotation;
+mod known_hosts;
+mod local_command;
+mod log_forwarding;
+mod macros;
+mod metrics;
+mod network;
+mod packages;
+mod notifications;
+mod operations;
+mod output_bookmarks;
 pub mod plugins;
+mod profiles;
+mod prompt_broker;
 mod pty;
+mod pty_flow;
+mod quick_connect;
+mod redaction;
+mod remote_charset;
+mod remote_clock;
+mod remote_git;
+mod remote_helpers;
+mod remote_os;
+mod rendezvous;
+mod safety;
+mod secret_split;
 mod session;
+mod session_channels;
+mod session_guard;
+mod session_refs;
+mod session_state;
 mod shell_icons;
+mod smart_groups;
 mod snippets;
 mod ssh;
 mod ssh_config;
 mod ssh_parser;
+mod startup;
 mod sync;
+mod time_tracking;
+mod tor;
 mod tunnels;
 pub use tunnels::{remote_forward_map_key, tunnel_runtime_id, TunnelManager};
 mod types;
+mod user_profiles;
 mod utils;
 mod vault;
+mod websocket_transport;
 
 use commands::AppState;
 use tauri::{Emitter, Manager};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    crash_reports::install();
+
     // Release builds only: dev (`tauri dev`) shares the same app identifier as the
     // installed app, so single-instance would focus the production window instead of
     // launching the dev instance.
@@ -58,9 +121,27 @@ pub fn run() {
             }
 
             let app_handle = app.handle().clone();
+            user_profiles::init(app.path().config_dir().ok().as_deref());
             let data_dir = commands::get_data_dir(&app_handle);
             let app_state = AppState::new(data_dir.clone(), app_handle.clone());
             app.manage(app_state);
+            crash_reports::start(app_handle.clone());
+            availability::start(app_handle.clone());
+            credential_expiry::start(app_handle.clone());
+            log_forwarding::start(app_handle.clone());
+            metrics::start(app_handle.clone());
+            network::start(app_handle.clone());
+            notifications::start(app_handle.clone());
+            session_refs::start(app_handle.clone());
+            session_state::start();
+            time_tracking::start(app_handle.clone());
+            prompt_broker::configure(&app_handle);
+            data_format::configure(&app_handle);
+            tunnels::relay::configure(&app_handle);
+            tunnels::remote_rearm::start(app_handle.clone());
+            tunnels::schedule::start(app_handle.clone());
+            tunnels::system_proxy::start(app_handle.clone());
+            tunnels::hostnames::start();
             app.manage(tokio::sync::Mutex::new(vault::store::VaultService::new(
                 data_dir,
             )));
@@ -87,6 +168,13 @@ pub fn run() {
                                     cancel.store(true, std::sync::atomic::Ordering::Relaxed);
                                 }
                             });
+                            // Before the graceful disconnect, while sessions and tunnels are still up.
+                            let app = window.app_handle();
+                            if let Err(error) = tauri::async_runtime::block_on(
+                                startup::workspace::record(app, &state),
+                            ) {
+                                eprintln!("[Workspace] Failed to record open workspace: {error}");
+                            }
                         }
                         api.prevent_close();
                         let _ = window.emit("app:request-close", ());
@@ -126,7 +214,12 @@ pub fn run() {
             commands::ssh_disconnect,
             commands::ssh_transport_lost,
             commands::ssh_disconnect_vault_backed,
+            auth_prompts::commands::ssh_auth_respond,
+            jump_sessions::commands::jump_sessions_list,
+            jump_sessions::commands::jump_session_reset,
+            tor::commands::tor_status,
             commands::terminal_write,
+            commands::terminal_ack,
             commands::terminal_navigate,
             commands::terminal_resize,
             commands::terminal_create,
@@ -150,6 +243,9 @@ pub fn run() {
             commands::fs_rename_batch,
             commands::fs_exists,
             tunnels::commands::tunnel_get_all,
+            tunnels::commands::tunnel_schedule_status,
+            tunnels::commands::tunnel_export_ssh_config,
+            tunnels::commands::tunnel_preview_ssh_config_import,
             tunnels::commands::tunnel_start_local,
             tunnels::commands::tunnel_start_remote,
             tunnels::commands::tunnel_stop,
@@ -157,7 +253,70 @@ pub fn run() {
             tunnels::commands::tunnel_save,
             tunnels::commands::tunnel_delete,
             tunnels::commands::tunnel_start,
+            tunnels::commands::tunnel_validate,
+            tunnels::commands::tunnel_next_loopback_alias,
             tunnels::commands::tunnel_reconcile_connection,
+            tunnels::commands::tunnel_restore_connection,
+            tunnels::commands::list_remote_listening_ports,
+            tunnels::commands::suggest_tunnels,
+            tunnels::commands::service_names_list,
+            tunnels::commands::tunnel_open_in_browser,
+            tunnels::commands::tunnel_launch_remote_desktop,
+            tunnels::commands::tunnel_local_ca,
+            tunnels::commands::tunnel_http_log,
+            tunnels::commands::tunnel_pac_info,
+            tunnels::commands::port_reservations_list,
+            tunnels::commands::port_reserve,
+            tunnels::commands::port_release,
+            tunnels::commands::privileged_ports_status,
+            tunnels::commands::privileged_ports_apply,
+            clipboard_bridge::commands::clipboard_push_remote,
+            clipboard_bridge::commands::clipboard_pull_remote,
+            clipboard_history::commands::clipboard_history_add,
+            clipboard_history::commands::clipboard_history_list,
+            clipboard_history::commands::clipboard_history_pin,
+            clipboard_history::commands::clipboard_history_delete,
+            clipboard_history::commands::clipboard_history_clear,
+            clipboard_history::commands::clipboard_history_set_incognito,
+            clipboard_history::commands::clipboard_history_status,
+            output_bookmarks::commands::output_bookmark_add,
+            output_bookmarks::commands::output_bookmarks_list,
+            output_bookmarks::commands::output_bookmark_rename,
+            output_bookmarks::commands::output_bookmark_delete,
+            remote_helpers::commands::remote_helpers_status,
+            remote_helpers::commands::remote_helpers_install,
+            remote_helpers::commands::remote_helpers_remove,
+            remote_helpers::commands::remote_helpers_run,
+            host_capabilities::commands::host_capabilities,
+            host_power::commands::host_power_issue,
+            host_power::commands::host_power_watch,
+            remote_clock::commands::remote_clock,
+            remote_clock::commands::remote_clock_normalize,
+            packages::commands::packages_list_upgradable,
+            packages::commands::packages_install,
+            packages::commands::packages_remove,
+            cron::commands::cron_list,
+            cron::commands::cron_parse,
+            cron::commands::cron_write,
+            accounts::commands::accounts_report,
+            accounts::commands::accounts_add_ssh_user,
+            accounts::commands::accounts_authorize_key,
+            firewall::commands::firewall_rules,
+            firewall::commands::firewall_check_port,
+            remote_git::commands::git_status,
+            remote_git::commands::git_log,
+            remote_git::commands::git_pull,
+            remote_git::commands::git_checkout,
+            deploy_watch::commands::deploy_watch_start,
+            deploy_watch::commands::deploy_watch_stop,
+            deploy_watch::commands::deploy_watch_list,
+            host_snapshot::commands::host_snapshot_capture,
+            host_snapshot::commands::host_snapshot_list,
+            host_snapshot::commands::host_snapshot_load,
+            host_snapshot::commands::host_snapshot_diff,
+            handoff::commands::handoff_export,
+            handoff::commands::handoff_preview,
+            handoff::commands::handoff_import,
             commands::window_is_maximized,
             commands::window_maximize,
             commands::window_minimize,
@@ -171,6 +330,25 @@ pub fn run() {
             commands::snippets_list,
             commands::snippets_save,
             commands::snippets_delete,
+            macros::commands::macros_list,
+            macros::commands::macros_save,
+            macros::commands::macros_delete,
+            expect::commands::expect_run,
+            redaction::commands::redaction_preview,
+            redaction::commands::redaction_scrub_persisted,
+            secret_split::commands::secret_split_check,
+            secret_split::commands::secret_split_apply,
+            data_validation::commands::data_files_validate,
+            data_validation::commands::data_files_repair,
+            crash_reports::commands::crash_reports_list,
+            crash_reports::commands::crash_reports_bundle,
+            crash_reports::commands::crash_reports_clear,
+            doctor::commands::run_doctor,
+            user_profiles::commands::user_profiles_list,
+            user_profiles::commands::user_profiles_create,
+            user_profiles::commands::user_profiles_update,
+            user_profiles::commands::user_profiles_delete,
+            user_profiles::commands::user_profiles_switch,
             commands::save_secret,
             commands::get_secret,
             commands::delete_secret,
@@ -210,6 +388,58 @@ pub fn run() {
             commands::config_select_folder,
             commands::system_install_cli,
             commands::ssh_parse_command,
+            commands::quick_connect_parse,
+            commands::quick_connect,
+            availability::commands::availability_status,
+            availability::commands::availability_probe_now,
+            availability::commands::availability_report,
+            availability::commands::availability_export_report,
+            bench::commands::bench_tunnel_throughput,
+            bench::commands::bench_sftp_throughput,
+            bench::commands::bench_event_latency,
+            credential_expiry::commands::credential_expiry_status,
+            credential_expiry::commands::credential_expiry_reissue,
+            dedupe::commands::connections_find_duplicates,
+            dedupe::commands::connections_merge,
+            smart_groups::commands::smart_groups_evaluate,
+            smart_groups::commands::smart_groups_preview,
+            diagnostics::commands::diagnostics_explain_error,
+            events::commands::connection_events_since,
+            fleet::commands::fleet_maintenance_summary,
+            fleet::commands::fleet_run_command,
+            fleet::commands::fleet_push,
+            key_rotation::commands::key_rotation_run,
+            rendezvous::commands::rendezvous_preview,
+            rendezvous::commands::rendezvous_setup,
+            rendezvous::commands::rendezvous_list,
+            rendezvous::commands::rendezvous_status,
+            rendezvous::commands::rendezvous_remove,
+            known_hosts::commands::known_hosts_list,
+            known_hosts::commands::known_hosts_delete,
+            known_hosts::commands::known_hosts_remove_host,
+            known_hosts::commands::known_hosts_hash,
+            known_hosts::commands::known_hosts_rescan,
+            known_hosts::commands::ssh_host_key_respond,
+            metrics::commands::metrics_render,
+            network::commands::network_status,
+            notifications::commands::notifications_test_webhook,
+            log_forwarding::commands::log_forwarding_test,
+            operations::commands::cancel_operation,
+            operations::commands::operations_list,
+            profiles::commands::profiles_list_themes,
+            profiles::commands::profiles_resolve,
+            profiles::commands::profiles_check_snippet_run,
+            safety::commands::safety_check,
+            safety::commands::safety_confirm_paste,
+            safety::commands::safety_audit_recent,
+            session_refs::commands::session_hold,
+            session_refs::commands::session_release,
+            session_refs::commands::session_usage,
+            startup::commands::startup_auto_connect_plan,
+            startup::commands::startup_workspace_restore_plan,
+            startup::commands::startup_workspace_clear,
+            time_tracking::commands::time_tracking_report,
+            time_tracking::commands::time_tracking_export_csv,
             commands::ai_translate,
             commands::ai_translate_stream,
             commands::ai_check_ollama,
@@ -228,6 +458,8 @@ pub fn run() {
             ghost::commands::ghost_candidates,
             session::session_load,
             session::session_save,
+            session_state::commands::cancel_connect,
+            session_state::commands::session_states,
             vault::commands::vault_status,
             vault::commands::vault_initialize,
             vault::commands::vault_unlock,
@@ -0,0 +1,250 @@
+//! ssh_config `LocalCommand`: a command run on this machine once a connection
+//! is established, only when `PermitLocalCommand yes` is also set.
+//!
+//! Like OpenSSH, a failing command does not affect the connection; the result
+//! is reported to the UI as `connection:local-command`. Supported tokens are
+//! `%%`, `%C`, `%d`, `%h`, `%k`, `%L`, `%l`, `%n`, `%p`, `%r`, `%T` and `%u`.
+
+use crate::types::ConnectionConfig;
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+const LOCAL_COMMAND_TIMEOUT: Duration = Duration::from_secs(60);
+/// Output kept in the report, tail only.
+const MAX_OUTPUT_CHARS: usize = 2000;
+
+/// Values substituted for `%` tokens.
+pub struct TokenContext<'a> {
+    /// Remote host name (`HostName`).
+    pub host: &'a str,
+    pub port: u16,
+    pub remote_user: &'a str,
+    /// Name the connection was opened by (the ssh_config `Host` alias).
+    pub original_host: &'a str,
+    pub host_key_alias: Option<&'a str>,
+    pub local_user: &'a str,
+    pub local_home: &'a str,
+    pub local_hostname: &'a str,
+}
+
+/// `%C`: SHA-1 of local hostname, host, port and user, as OpenSSH computes it.
+pub fn connection_hash(local_hostname: &str, host: &str, port: u16, user: &str) -> String {
+    use sha1::Digest;
+    let mut hasher = sha1::Sha1::new();
+    hasher.update(format!("{local_hostname}{host}{port}{user}").as_bytes());
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Expand `%` tokens; an unknown token is an error, as in OpenSSH.
+pub fn expand_tokens(template: &str, ctx: &TokenContext<'_>) -> Result<String, String> {
+    let mut expanded = String::with_capacity(template.len());
+    let mut chars = template.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            expanded.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('%') => expanded.push('%'),
+            Some('C') => expanded.push_str(&connection_hash(
+                ctx.local_hostname,
+                ctx.host,
+                ctx.port,
+                ctx.remote_user,
+            )),
+            Some('d') => expanded.push_str(ctx.local_home),
+            Some('h') => expanded.push_str(ctx.host),
+            Some('k') => expanded.push_str(ctx.host_key_alias.unwrap_or(ctx.host)),
+            Some('L') => expanded.push_str(
+                ctx.local_hostname
+                    .split('.')
+                    .next()
+                    .unwrap_or(ctx.local_hostname),
+            ),
+            Some('l') => expanded.push_str(ctx.local_hostname),
+            Some('n') => expanded.push_str(ctx.original_host),
+            Some('p') => expanded.push_str(&ctx.port.to_string()),
+            Some('r') => expanded.push_str(ctx.remote_user),
+            // No tunnel device is ever forwarded.
+            Some('T') => expanded.push_str("NONE"),
+            Some('u') => expanded.push_str(ctx.local_user),
+            Some(other) => return Err(format!("Unsupported token %{other} in LocalCommand")),
+            None => return Err("LocalCommand ends with a lone %".to_string()),
+        }
+    }
+    Ok(expanded)
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalCommandReport {
+    pub connection_id: String,
+    pub command: String,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Tail of stdout and stderr.
+    pub output: String,
+}
+
+/// Start the connection's `LocalCommand` in the background, if it has one
+/// and `PermitLocalCommand` allows it.
+pub fn spawn_after_connect(app: &AppHandle, config: &ConnectionConfig) {
+    let Some(options) = config.ssh_options.as_ref() else {
+        return;
+    };
+    let Some(template) = options
+        .local_command
+        .as_deref()
+        .filter(|command| !command.trim().is_empty())
+    else {
+        return;
+    };
+    if options.permit_local_command != Some(true) {
+        return;
+    }
+    let local_user = whoami::username();
+    let local_home = dirs::home_dir()
+        .map(|home| home.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let local_hostname = whoami::fallible::hostname().unwrap_or_default();
+    let expanded = expand_tokens(
+        template,
+        &TokenContext {
+            host: &config.host,
+            port: config.port,
+            remote_user: &config.username,
+            original_host: &config.name,
+            host_key_alias: config.host_key_alias.as_deref(),
+            local_user: &local_user,
+            local_home: &local_home,
+            local_hostname: &local_hostname,
+        },
+    );
+
+    let app = app.clone();
+    let connection_id = config.id.clone();
+    let template = template.to_string();
+    tauri::async_runtime::spawn(async move {
+        let report = match expanded {
+            Ok(command) => run(connection_id, command).await,
+            Err(error) => LocalCommandReport {
+                connection_id,
+                command: template,
+                success: false,
+                exit_code: None,
+                error: Some(error),
+                output: String::new(),
+            },
+        };
+        if !report.success {
+            eprintln!(
+                "[SSH] LocalCommand for {} failed: {}",
+                report.connection_id,
+                report.error.as_deref().unwrap_or_default()
+            );
+        }
+        let _ = app.emit("connection:local-command", &report);
+    });
+}
+
+async fn run(connection_id: String, command: String) -> LocalCommandReport {
+    let mut process = if cfg!(target_os = "windows") {
+        let mut process = tokio::process::Command::new("cmd");
+        process.arg("/C");
+        process
+    } else {
+        let mut process = tokio::process::Command::new("sh");
+        process.arg("-c");
+        process
+    };
+    process.arg(&command).kill_on_drop(true);
+    let outcome = tokio::time::timeout(LOCAL_COMMAND_TIMEOUT, process.output()).await;
+    let mut report = LocalCommandReport {
+        connection_id,
+        command,
+        success: false,
+        exit_code: None,
+        error: None,
+        output: String::new(),
+    };
+    match outcome {
+        Err(_) => {
+            report.error = Some(format!(
+                "Timed out after {}s",
+                LOCAL_COMMAND_TIMEOUT.as_secs()
+            ))
+        }
+        Ok(Err(error)) => report.error = Some(format!("Failed to start: {error}")),
+        Ok(Ok(output)) => {
+            let combined = format!(
+                "{}{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            );
+            let skip = combined.chars().count().saturating_sub(MAX_OUTPUT_CHARS);
+            report.output = combined.chars().skip(skip).collect();
+            report.exit_code = output.status.code();
+            report.success = output.status.success();
+            if !report.success {
+                report.error = Some(match report.exit_code {
+                    Some(code) => format!("Exited with status {code}"),
+                    None => "Terminated by a signal".to_string(),
+                });
+            }
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> TokenContext<'static> {
+        TokenContext {
+            host: "db.example.com",
+            port: 2222,
+            remote_user: "deploy",
+            original_host: "db",
+            host_key_alias: None,
+            local_user: "alice",
+            local_home: "/home/alice",
+            local_hostname: "laptop.local",
+        }
+    }
+
+    #[test]
+    fn expands_openssh_tokens() {
+        let expanded = expand_tokens(
+            "notify %n %r@%h:%p from %u@%L (%l) in %d %k %T 100%%",
+            &context(),
+        )
+        .unwrap();
+        assert_eq!(
+            expanded,
+            "notify db deploy@db.example.com:2222 from alice@laptop (laptop.local) in /home/alice db.example.com NONE 100%"
+        );
+        let hash = expand_tokens("%C", &context()).unwrap();
+        assert_eq!(
+            hash,
+            connection_hash("laptop.local", "db.example.com", 2222, "deploy")
+        );
+        assert_eq!(hash.len(), 40);
+    }
+
+    #[test]
+    fn rejects_unknown_and_dangling_tokens() {
+        assert!(expand_tokens("echo %i", &context())
+            .unwrap_err()
+            .contains("%i"));
+        assert!(expand_tokens("echo 50%", &context()).is_err());
+    }
+}
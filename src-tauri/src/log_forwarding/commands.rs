@@ -0,0 +1,26 @@
+use super::{send, LogForwardingConfig, LogRecord};
+use serde_json::json;
+use tauri::AppHandle;
+
+/// Send a test line with unsaved settings so users can check the target.
+#[tauri::command]
+pub async fn log_forwarding_test(
+    app: AppHandle,
+    config: LogForwardingConfig,
+) -> Result<(), String> {
+    let timestamp_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0);
+    let record = LogRecord {
+        kind: "test".to_string(),
+        timestamp_ms,
+        severity: 6,
+        body: json!({
+            "source": "test",
+            "timestampMs": timestamp_ms,
+            "message": "Zync log forwarding test",
+        }),
+    };
+    send(&app, &config, &record).await
+}
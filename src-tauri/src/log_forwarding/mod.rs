@@ -0,0 +1,313 @@
+//! Forward the connection event bus and audit entries to a log collector.
+//!
+//! Configured in `settings.json` and re-read for every record, like
+//! notifications:
+//!
+//! ```json
+//! "logForwarding": {
+//!   "enabled": true,
+//!   "target": "udp", "address": "logs.internal:514",
+//!   "format": "syslog", "facility": 16,
+//!   "events": ["connected", "tunnel-down", "audit"]
+//! }
+//! ```
+//!
+//! `target` is `file` (appending to `path`, `events.log` in the data dir by
+//! default) or `udp`. `format` is `json` (one object per line) or `syslog`
+//! (RFC 5424 with the JSON object as the message). Audit entries use the event
+//! name `audit`. Lines go through the redaction rules before they leave.
+
+pub mod commands;
+
+use crate::commands::{get_data_dir, read_effective_settings};
+use crate::events::{event_type, ConnectionEvent, ConnectionEventEnvelope};
+use crate::safety::audit::AuditEntry;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+use tauri::AppHandle;
+use tokio::sync::broadcast::error::RecvError;
+
+static FILE_WRITE_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+/// Progress ticks are too chatty for a log; forwarded only when listed.
+const NOISY_EVENTS: &[&str] = &["transfer-progress"];
+
+const APP_NAME: &str = "zync";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogTarget {
+    #[default]
+    File,
+    Udp,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Json,
+    Syslog,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LogForwardingConfig {
+    pub enabled: bool,
+    pub target: LogTarget,
+    pub format: LogFormat,
+    /// File target; defaults to `events.log` in the data dir.
+    pub path: Option<String>,
+    /// UDP target, `host:port`.
+    pub address: Option<String>,
+    /// Syslog facility code (1 = user, 16-23 = local0-local7).
+    pub facility: u8,
+    /// Event names to forward; empty forwards everything but progress ticks.
+    pub events: Vec<String>,
+}
+
+impl Default for LogForwardingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target: LogTarget::File,
+            format: LogFormat::Json,
+            path: None,
+            address: None,
+            facility: 1,
+            events: Vec::new(),
+        }
+    }
+}
+
+impl LogForwardingConfig {
+    fn wants(&self, kind: &str) -> bool {
+        if self.events.is_empty() {
+            !NOISY_EVENTS.contains(&kind)
+        } else {
+            self.events
+                .iter()
+                .any(|event| event == kind || event == "*")
+        }
+    }
+}
+
+pub fn read_log_forwarding_config(app: &AppHandle) -> LogForwardingConfig {
+    read_effective_settings(app)
+        .ok()
+        .and_then(|settings| settings.get("logForwarding").cloned())
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// One forwarded line's worth of data.
+pub(crate) struct LogRecord {
+    pub kind: String,
+    pub timestamp_ms: u64,
+    /// Syslog severity (3 error, 4 warning, 6 informational).
+    pub severity: u8,
+    pub body: Value,
+}
+
+impl LogRecord {
+    pub fn from_event(envelope: &ConnectionEventEnvelope) -> Self {
+        let severity = match &envelope.event {
+            ConnectionEvent::ConnectFailed { .. } | ConnectionEvent::AuthFailed { .. } => 3,
+            ConnectionEvent::TransportLost
+            | ConnectionEvent::KeepaliveTimeout
+            | ConnectionEvent::TunnelDown { error: Some(_), .. } => 4,
+            ConnectionEvent::TransferFinished { outcome, .. } if outcome == "failed" => 4,
+            _ => 6,
+        };
+        Self {
+            kind: event_type(&envelope.event),
+            timestamp_ms: envelope.timestamp_ms,
+            severity,
+            body: tagged("event", serde_json::to_value(envelope).unwrap_or_default()),
+        }
+    }
+
+    pub fn from_audit(entry: &AuditEntry) -> Self {
+        Self {
+            kind: "audit".to_string(),
+            timestamp_ms: entry.timestamp.saturating_mul(1000),
+            // Someone overrode a safety check.
+            severity: 5,
+            body: tagged("audit", serde_json::to_value(entry).unwrap_or_default()),
+        }
+    }
+
+    pub fn line(&self, format: LogFormat, facility: u8, hostname: &str) -> String {
+        let json = self.body.to_string();
+        match format {
+            LogFormat::Json => json,
+            LogFormat::Syslog => format!(
+                "<{}>1 {} {} {APP_NAME} {} {} - {json}",
+                u16::from(facility.min(23)) * 8 + u16::from(self.severity),
+                rfc3339(self.timestamp_ms),
+                if hostname.is_empty() { "-" } else { hostname },
+                std::process::id(),
+                self.kind,
+            ),
+        }
+    }
+}
+
+fn tagged(source: &str, mut body: Value) -> Value {
+    if let Value::Object(map) = &mut body {
+        map.insert("source".to_string(), Value::String(source.to_string()));
+    }
+    body
+}
+
+fn rfc3339(timestamp_ms: u64) -> String {
+    let nanos = i128::from(timestamp_ms) * 1_000_000;
+    let Ok(at) = time::OffsetDateTime::from_unix_timestamp_nanos(nanos) else {
+        return "-".to_string();
+    };
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        at.year(),
+        u8::from(at.month()),
+        at.day(),
+        at.hour(),
+        at.minute(),
+        at.second(),
+        at.millisecond()
+    )
+}
+
+fn default_path(app: &AppHandle) -> PathBuf {
+    get_data_dir(app).join("events.log")
+}
+
+/// Write one line to the configured target.
+pub(crate) async fn send(
+    app: &AppHandle,
+    config: &LogForwardingConfig,
+    record: &LogRecord,
+) -> Result<(), String> {
+    let hostname = whoami::fallible::hostname().unwrap_or_default();
+    let line = record.line(config.format, config.facility, &hostname);
+    let line = crate::redaction::Redactor::from_app(app).redact(&line);
+    match config.target {
+        LogTarget::File => {
+            let path = config
+                .path
+                .as_deref()
+                .map(str::trim)
+                .filter(|path| !path.is_empty())
+                .map(PathBuf::from)
+                .unwrap_or_else(|| default_path(app));
+            let _guard = FILE_WRITE_LOCK.lock().unwrap_or_else(|p| p.into_inner());
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .and_then(|mut file| writeln!(file, "{line}"))
+                .map_err(|error| format!("Failed to append to {}: {error}", path.display()))
+        }
+        LogTarget::Udp => {
+            let address = config
+                .address
+                .as_deref()
+                .map(str::trim)
+                .filter(|address| !address.is_empty())
+                .ok_or_else(|| "No syslog address configured".to_string())?;
+            let target = tokio::net::lookup_host(address)
+                .await
+                .map_err(|error| format!("Cannot resolve {address}: {error}"))?
+                .next()
+                .ok_or_else(|| format!("Cannot resolve {address}"))?;
+            let bind = if target.is_ipv6() {
+                "[::]:0"
+            } else {
+                "0.0.0.0:0"
+            };
+            let socket = tokio::net::UdpSocket::bind(bind)
+                .await
+                .map_err(|error| error.to_string())?;
+            socket
+                .send_to(line.as_bytes(), target)
+                .await
+                .map(|_| ())
+                .map_err(|error| format!("Failed to send to {address}: {error}"))
+        }
+    }
+}
+
+async fn forward(app: &AppHandle, record: LogRecord) {
+    let config = read_log_forwarding_config(app);
+    if !config.enabled || !config.wants(&record.kind) {
+        return;
+    }
+    if let Err(error) = send(app, &config, &record).await {
+        eprintln!("[LOGFWD] {error}");
+    }
+}
+
+/// Forward an audit entry (already redacted by the audit trail).
+pub fn forward_audit(app: &AppHandle, entry: &AuditEntry) {
+    let app = app.clone();
+    let record = LogRecord::from_audit(entry);
+    tauri::async_runtime::spawn(async move { forward(&app, record).await });
+}
+
+pub fn start(app: AppHandle) {
+    let mut events = crate::events::subscribe();
+    crate::crash_reports::spawn_supervised("log-forwarding", async move {
+        loop {
+            match events.recv().await {
+                Ok(envelope) => forward(&app, LogRecord::from_event(&envelope)).await,
+                Err(RecvError::Lagged(skipped)) => {
+                    eprintln!("[LOGFWD] Event subscriber lagged; {skipped} events dropped");
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record() -> LogRecord {
+        LogRecord::from_event(&ConnectionEventEnvelope {
+            seq: 3,
+            timestamp_ms: 1_700_000_000_123,
+            connection_id: "conn-1".to_string(),
+            event: ConnectionEvent::TunnelDown {
+                tunnel_id: "t1".to_string(),
+                error: Some("refused".to_string()),
+            },
+        })
+    }
+
+    #[test]
+    fn formats_syslog_and_json_lines() {
+        let record = record();
+        let json: Value = serde_json::from_str(&record.line(LogFormat::Json, 1, "laptop")).unwrap();
+        assert_eq!(json["type"], "tunnel-down");
+        assert_eq!(json["source"], "event");
+        assert_eq!(json["connectionId"], "conn-1");
+
+        let syslog = record.line(LogFormat::Syslog, 16, "laptop");
+        // local0 (16) * 8 + warning (4)
+        assert!(syslog.starts_with("<132>1 2023-11-14T22:13:20.123Z laptop zync "));
+        assert!(syslog.contains(" tunnel-down - {"));
+    }
+
+    #[test]
+    fn skips_progress_unless_asked_for() {
+        let mut config = LogForwardingConfig::default();
+        assert!(config.wants("tunnel-down"));
+        assert!(!config.wants("transfer-progress"));
+        config.events = vec!["transfer-progress".to_string()];
+        assert!(config.wants("transfer-progress"));
+        assert!(!config.wants("audit"));
+    }
+}
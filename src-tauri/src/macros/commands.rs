@@ -0,0 +1,56 @@
+use super::{
+    read_macros, validate, write_macros_atomic, MacroStep, TerminalMacro, MACROS_FILE,
+    MACROS_MUTATION_LOCK,
+};
+use crate::commands::get_data_dir;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+#[tauri::command]
+pub async fn macros_list(app: AppHandle) -> Result<Vec<TerminalMacro>, String> {
+    let _guard = MACROS_MUTATION_LOCK.lock().map_err(|e| e.to_string())?;
+    Ok(read_macros(&get_data_dir(&app).join(MACROS_FILE))?.macros)
+}
+
+/// Create or replace a macro, returning it as stored: recorded input passes
+/// through the redaction rules first.
+#[tauri::command]
+pub async fn macros_save(app: AppHandle, entry: TerminalMacro) -> Result<TerminalMacro, String> {
+    let _guard = MACROS_MUTATION_LOCK.lock().map_err(|e| e.to_string())?;
+    let path = get_data_dir(&app).join(MACROS_FILE);
+    let mut data = read_macros(&path)?;
+    let mut entry = validate(entry, &data.macros)?;
+    let redactor = crate::redaction::Redactor::from_app(&app);
+    for step in entry.steps.iter_mut() {
+        if let MacroStep::Input { data } = step {
+            *data = redactor.redact(data);
+        }
+    }
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+
+    entry.updated_at = Some(now);
+    match data.macros.iter().position(|m| m.id == entry.id) {
+        Some(pos) => {
+            entry.created_at = data.macros[pos].created_at.or(Some(now));
+            data.macros[pos] = entry.clone();
+        }
+        None => {
+            entry.created_at = entry.created_at.or(Some(now));
+            data.macros.push(entry.clone());
+        }
+    }
+    write_macros_atomic(&path, &data)?;
+    Ok(entry)
+}
+
+#[tauri::command]
+pub async fn macros_delete(app: AppHandle, id: String) -> Result<(), String> {
+    let _guard = MACROS_MUTATION_LOCK.lock().map_err(|e| e.to_string())?;
+    let path = get_data_dir(&app).join(MACROS_FILE);
+    let mut data = read_macros(&path)?;
+    data.macros.retain(|m| m.id != id);
+    write_macros_atomic(&path, &data)
+}
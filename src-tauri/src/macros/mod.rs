@@ -0,0 +1,229 @@
+//! Terminal macros: recorded input sequences that can be replayed on demand or
+//! from a shortcut. Stored in `macros.json` next to `snippets.json`.
+//!
+//! Recording and replay happen in the frontend, which owns the terminal input
+//! pipeline and the rendered screen that prompt waits inspect. The backend
+//! validates and persists.
+
+pub mod commands;
+
+use crate::data_format::to_versioned_json;
+use crate::data_validation::{parse_or_repair, DataFile};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::{LazyLock, Mutex};
+
+pub(crate) static MACROS_MUTATION_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+pub const MACROS_FILE: &str = "macros.json";
+const MAX_STEPS: usize = 500;
+const MAX_INPUT_BYTES: usize = 64 * 1024;
+const MAX_WAIT_MS: u64 = 10 * 60 * 1000;
+const DEFAULT_PROMPT_TIMEOUT_MS: u64 = 30_000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum MacroStep {
+    /// Raw terminal input, exactly as typed (control keys included).
+    #[serde(rename_all = "camelCase")]
+    Input { data: String },
+    /// Wait until the last screen line looks like a prompt. `pattern` is a
+    /// regular expression evaluated by the frontend; `None` uses its default.
+    #[serde(rename_all = "camelCase")]
+    WaitForPrompt {
+        #[serde(default)]
+        pattern: Option<String>,
+        #[serde(default)]
+        timeout_ms: Option<u64>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Delay { ms: u64 },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalMacro {
+    pub id: String,
+    pub name: String,
+    pub steps: Vec<MacroStep>,
+    /// Shortcut string in the keybinding format (`Mod+Alt+1`).
+    #[serde(default)]
+    pub shortcut: Option<String>,
+    /// Only offered on this connection when set, like a scoped snippet.
+    #[serde(default)]
+    pub connection_id: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<u64>,
+    #[serde(default)]
+    pub updated_at: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MacrosData {
+    pub macros: Vec<TerminalMacro>,
+}
+
+/// Check a macro before it is saved and normalize what can be: names and
+/// shortcuts are trimmed, prompt waits get a bounded timeout.
+pub fn validate(
+    mut entry: TerminalMacro,
+    others: &[TerminalMacro],
+) -> Result<TerminalMacro, String> {
+    entry.name = entry.name.trim().to_string();
+    if entry.name.is_empty() {
+        return Err("Macro name is required".to_string());
+    }
+    if entry.steps.is_empty() {
+        return Err("Macro has no steps".to_string());
+    }
+    if entry.steps.len() > MAX_STEPS {
+        return Err(format!("Macros are limited to {MAX_STEPS} steps"));
+    }
+    let input_bytes: usize = entry
+        .steps
+        .iter()
+        .map(|step| match step {
+            MacroStep::Input { data } => data.len(),
+            _ => 0,
+        })
+        .sum();
+    if input_bytes > MAX_INPUT_BYTES {
+        return Err("Macro input is too large".to_string());
+    }
+    for step in &mut entry.steps {
+        match step {
+            MacroStep::Input { data } if data.is_empty() => {
+                return Err("Macro input steps can't be empty".to_string());
+            }
+            MacroStep::WaitForPrompt {
+                pattern,
+                timeout_ms,
+            } => {
+                *pattern = pattern.take().filter(|p| !p.trim().is_empty());
+                *timeout_ms = Some(
+                    timeout_ms
+                        .unwrap_or(DEFAULT_PROMPT_TIMEOUT_MS)
+                        .clamp(100, MAX_WAIT_MS),
+                );
+            }
+            MacroStep::Delay { ms } if *ms > MAX_WAIT_MS => {
+                return Err("Macro delays are limited to 10 minutes".to_string());
+            }
+            _ => {}
+        }
+    }
+
+    entry.shortcut = entry
+        .shortcut
+        .take()
+        .map(|shortcut| shortcut.trim().to_string())
+        .filter(|shortcut| !shortcut.is_empty());
+    if let Some(shortcut) = &entry.shortcut {
+        let normalized = normalize_shortcut(shortcut);
+        if let Some(other) = others.iter().find(|other| {
+            other.id != entry.id
+                && other
+                    .shortcut
+                    .as_deref()
+                    .is_some_and(|s| normalize_shortcut(s) == normalized)
+        }) {
+            return Err(format!("{shortcut} already runs macro '{}'", other.name));
+        }
+    }
+    Ok(entry)
+}
+
+/// Modifier order doesn't matter: `Alt+Mod+1` and `mod+alt+1` are the same key.
+fn normalize_shortcut(shortcut: &str) -> Vec<String> {
+    let mut parts: Vec<String> = shortcut
+        .split('+')
+        .map(|p| p.trim().to_lowercase())
+        .collect();
+    let key = parts.pop().unwrap_or_default();
+    parts.sort();
+    parts.dedup();
+    parts.push(key);
+    parts
+}
+
+pub(crate) fn read_macros(path: &Path) -> Result<MacrosData, String> {
+    if !path.exists() {
+        return Ok(MacrosData::default());
+    }
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    parse_or_repair(DataFile::Macros, path, &content)
+        .map_err(|e| format!("Failed to parse macros file: {e}"))
+}
+
+pub(crate) fn write_macros_atomic(path: &Path, data: &MacrosData) -> Result<(), String> {
+    let json = to_versioned_json(DataFile::Macros, path, data)?;
+    crate::atomic_io::durable_replace(path, json.as_bytes())
+        .map_err(|e| format!("Failed to write macros file: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, shortcut: Option<&str>) -> TerminalMacro {
+        TerminalMacro {
+            id: id.to_string(),
+            name: format!(" {id} "),
+            steps: vec![
+                MacroStep::Input {
+                    data: "sudo -i\r".to_string(),
+                },
+                MacroStep::WaitForPrompt {
+                    pattern: Some(" ".to_string()),
+                    timeout_ms: None,
+                },
+            ],
+            shortcut: shortcut.map(str::to_string),
+            connection_id: None,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn normalizes_steps_and_rejects_shortcut_clashes() {
+        let saved = validate(entry("a", Some(" Mod+Alt+1 ")), &[]).unwrap();
+        assert_eq!(saved.name, "a");
+        assert_eq!(saved.shortcut.as_deref(), Some("Mod+Alt+1"));
+        assert_eq!(
+            saved.steps[1],
+            MacroStep::WaitForPrompt {
+                pattern: None,
+                timeout_ms: Some(DEFAULT_PROMPT_TIMEOUT_MS),
+            }
+        );
+
+        let others = vec![saved.clone()];
+        assert!(validate(entry("b", Some("alt+mod+1")), &others).is_err());
+        assert!(validate(entry("a", Some("alt+mod+1")), &others).is_ok());
+
+        let mut empty = entry("c", None);
+        empty.steps.clear();
+        assert!(validate(empty, &[]).is_err());
+    }
+
+    #[test]
+    fn steps_round_trip_with_a_kind_tag() {
+        let json = r#"[{"kind":"input","data":"ls\r"},{"kind":"waitForPrompt","timeoutMs":500},{"kind":"delay","ms":20}]"#;
+        let steps: Vec<MacroStep> = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            steps[1],
+            MacroStep::WaitForPrompt {
+                pattern: None,
+                timeout_ms: Some(500),
+            }
+        );
+        assert_eq!(
+            serde_json::to_string(&steps)
+                .unwrap()
+                .matches("kind")
+                .count(),
+            3
+        );
+    }
+}
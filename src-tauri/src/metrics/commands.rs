@@ -0,0 +1,7 @@
+use tauri::AppHandle;
+
+/// Render the metrics page on demand, e.g. for a settings preview.
+#[tauri::command]
+pub async fn metrics_render(app: AppHandle) -> Result<String, String> {
+    Ok(super::render(&app).await)
+}
@@ -0,0 +1,338 @@
+//! Opt-in Prometheus metrics (text exposition format 0.0.4).
+//!
+//! Counters are fed from the connection event bus; gauges are read from
+//! `AppState` when the page is rendered. Enabled through `settings.json`:
+//!
+//! ```json
+//! "metrics": { "enabled": true, "bindAddress": "127.0.0.1", "port": 9464, "textfilePath": null }
+//! ```
+//!
+//! `textfilePath` writes the same page periodically for node_exporter's textfile
+//! collector instead of (or in addition to) serving HTTP. Changes apply on restart.
+
+pub mod commands;
+
+use crate::commands::{read_effective_settings, AppState};
+use crate::events::{ConnectionEvent, ConnectionEventEnvelope};
+use serde::Deserialize;
+use std::fmt::Write as _;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::broadcast::error::RecvError;
+
+const TEXTFILE_INTERVAL: Duration = Duration::from_secs(15);
+const REQUEST_READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MetricsConfig {
+    pub enabled: bool,
+    pub bind_address: String,
+    pub port: u16,
+    pub textfile_path: Option<String>,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: "127.0.0.1".to_string(),
+            port: 9464,
+            textfile_path: None,
+        }
+    }
+}
+
+impl MetricsConfig {
+    /// Listen address; `bindAddress` may be IPv4 or IPv6, bracketed or not.
+    pub fn socket_addr(&self) -> Result<SocketAddr, String> {
+        let host = self.bind_address.trim();
+        let host = host
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+            .unwrap_or(host);
+        let ip: IpAddr = host
+            .parse()
+            .map_err(|_| format!("Invalid metrics bind address: {}", self.bind_address))?;
+        Ok(SocketAddr::new(ip, self.port))
+    }
+}
+
+pub fn read_metrics_config(app: &AppHandle) -> MetricsConfig {
+    read_effective_settings(app)
+        .ok()
+        .and_then(|settings| settings.get("metrics").cloned())
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+#[derive(Default)]
+struct Counters {
+    connects: AtomicU64,
+    connect_failures: AtomicU64,
+    auth_failures: AtomicU64,
+    reconnects: AtomicU64,
+    transport_lost: AtomicU64,
+    keepalive_timeouts: AtomicU64,
+    channels_opened: AtomicU64,
+    tunnel_up: AtomicU64,
+    tunnel_down: AtomicU64,
+    tunnel_errors: AtomicU64,
+    transfers_completed: AtomicU64,
+    transfers_failed: AtomicU64,
+    bytes_forwarded: AtomicU64,
+}
+
+static COUNTERS: std::sync::LazyLock<Counters> = std::sync::LazyLock::new(Counters::default);
+
+/// Bytes relayed through a tunnel connection, both directions combined.
+pub(crate) fn record_forwarded_bytes(bytes: u64) {
+    COUNTERS.bytes_forwarded.fetch_add(bytes, Ordering::Relaxed);
+}
+
+fn bump(counter: &AtomicU64) {
+    counter.fetch_add(1, Ordering::Relaxed);
+}
+
+fn observe(envelope: &ConnectionEventEnvelope) {
+    let counters = &*COUNTERS;
+    match &envelope.event {
+        ConnectionEvent::Connected { .. } => bump(&counters.connects),
+        ConnectionEvent::ConnectFailed { .. } => bump(&counters.connect_failures),
+        ConnectionEvent::AuthFailed { .. } => {
+            bump(&counters.connect_failures);
+            bump(&counters.auth_failures);
+        }
+        ConnectionEvent::Reconnected => bump(&counters.reconnects),
+        ConnectionEvent::TransportLost => bump(&counters.transport_lost),
+        ConnectionEvent::KeepaliveTimeout => bump(&counters.keepalive_timeouts),
+        ConnectionEvent::ChannelOpened { .. } => bump(&counters.channels_opened),
+        ConnectionEvent::TunnelUp { .. } => bump(&counters.tunnel_up),
+        ConnectionEvent::TunnelDown { error, .. } => {
+            bump(&counters.tunnel_down);
+            if error.is_some() {
+                bump(&counters.tunnel_errors);
+            }
+        }
+        ConnectionEvent::TransferFinished { outcome, .. } => match outcome.as_str() {
+            "completed" => bump(&counters.transfers_completed),
+            "failed" => bump(&counters.transfers_failed),
+            _ => {}
+        },
+        ConnectionEvent::Disconnected
+        | ConnectionEvent::TransferProgress { .. }
+        | ConnectionEvent::CommandFinished { .. }
+        | ConnectionEvent::StateChanged { .. } => {}
+    }
+}
+
+fn write_metric(out: &mut String, name: &str, kind: &str, help: &str, value: u64) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+    let _ = writeln!(out, "{name} {value}");
+}
+
+struct Gauges {
+    sessions: u64,
+    local_tunnels: u64,
+    remote_tunnels: u64,
+    transfers: u64,
+}
+
+async fn read_gauges(state: &AppState) -> Gauges {
+    let sessions = {
+        let connections = state.connections.lock().await;
+        connections
+            .values()
+            .filter(|handle| handle.session.is_some())
+            .count() as u64
+    };
+    Gauges {
+        sessions,
+        local_tunnels: state.tunnel_manager.local_listeners.lock().await.len() as u64,
+        remote_tunnels: state.tunnel_manager.remote_forwards.lock().await.len() as u64,
+        transfers: crate::operations::count(crate::operations::OperationKind::Transfer) as u64,
+    }
+}
+
+fn render_page(gauges: &Gauges) -> String {
+    let c = &*COUNTERS;
+    let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+    let mut out = String::new();
+
+    write_metric(&mut out, "zync_sessions_active", "gauge", "Connected SSH sessions.", gauges.sessions);
+    let _ = writeln!(out, "# HELP zync_tunnels_active Running tunnel listeners and remote forwards.");
+    let _ = writeln!(out, "# TYPE zync_tunnels_active gauge");
+    let _ = writeln!(out, "zync_tunnels_active{{kind=\"local\"}} {}", gauges.local_tunnels);
+    let _ = writeln!(out, "zync_tunnels_active{{kind=\"remote\"}} {}", gauges.remote_tunnels);
+    write_metric(&mut out, "zync_transfers_active", "gauge", "File transfers in progress.", gauges.transfers);
+
+    write_metric(&mut out, "zync_connects_total", "counter", "Successful SSH connects.", load(&c.connects));
+    write_metric(&mut out, "zync_connect_failures_total", "counter", "Failed SSH connects, including auth failures.", load(&c.connect_failures));
+    write_metric(&mut out, "zync_auth_failures_total", "counter", "SSH authentication failures.", load(&c.auth_failures));
+    write_metric(&mut out, "zync_reconnects_total", "counter", "Automatic reconnects after a dropped session.", load(&c.reconnects));
+    write_metric(&mut out, "zync_transport_lost_total", "counter", "SSH transports that died under open channels.", load(&c.transport_lost));
+    write_metric(&mut out, "zync_keepalive_timeouts_total", "counter", "Session liveness probes that timed out.", load(&c.keepalive_timeouts));
+    write_metric(&mut out, "zync_channels_opened_total", "counter", "SSH session channels opened.", load(&c.channels_opened));
+    write_metric(&mut out, "zync_tunnel_up_total", "counter", "Tunnel starts.", load(&c.tunnel_up));
+    write_metric(&mut out, "zync_tunnel_down_total", "counter", "Tunnel stops, expected or not.", load(&c.tunnel_down));
+    write_metric(&mut out, "zync_tunnel_errors_total", "counter", "Tunnels stopped by an error.", load(&c.tunnel_errors));
+    write_metric(&mut out, "zync_transfers_completed_total", "counter", "Completed file transfers.", load(&c.transfers_completed));
+    write_metric(&mut out, "zync_transfers_failed_total", "counter", "Failed file transfers.", load(&c.transfers_failed));
+    write_metric(&mut out, "zync_tunnel_forwarded_bytes_total", "counter", "Bytes relayed through tunnels, both directions.", load(&c.bytes_forwarded));
+    out
+}
+
+/// Current metrics page in Prometheus text format.
+pub(crate) async fn render(app: &AppHandle) -> String {
+    let gauges = match app.try_state::<AppState>() {
+        Some(state) => read_gauges(&state).await,
+        None => Gauges {
+            sessions: 0,
+            local_tunnels: 0,
+            remote_tunnels: 0,
+            transfers: 0,
+        },
+    };
+    render_page(&gauges)
+}
+
+/// Start counting events and, when enabled, serve/write the metrics page.
+/// Counting always runs so the page is accurate from startup once enabled.
+pub fn start(app: AppHandle) {
+    let mut events = crate::events::subscribe();
+    crate::crash_reports::spawn_supervised("metrics-events", async move {
+        loop {
+            match events.recv().await {
+                Ok(envelope) => observe(&envelope),
+                Err(RecvError::Lagged(skipped)) => {
+                    eprintln!("[METRICS] Event subscriber lagged; {skipped} events not counted");
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+
+    let config = read_metrics_config(&app);
+    if !config.enabled {
+        return;
+    }
+
+    if let Some(path) = config.textfile_path.clone().filter(|path| !path.trim().is_empty()) {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let mut interval = tokio::time::interval(TEXTFILE_INTERVAL);
+            loop {
+                interval.tick().await;
+                let page = render(&app).await;
+                let target = path.clone();
+                let written = tokio::task::spawn_blocking(move || {
+                    crate::atomic_io::durable_replace(std::path::Path::new(&target), page.as_bytes())
+                })
+                .await;
+                if let Ok(Err(error)) = written {
+                    eprintln!("[METRICS] Failed to write {path}: {error}");
+                }
+            }
+        });
+    }
+
+    tauri::async_runtime::spawn(async move {
+        let address = match config.socket_addr() {
+            Ok(address) => address,
+            Err(error) => {
+                eprintln!("[METRICS] {error}");
+                return;
+            }
+        };
+        let listener = match TcpListener::bind(address).await {
+            Ok(listener) => listener,
+            Err(error) => {
+                eprintln!("[METRICS] Failed to bind {address}: {error}");
+                return;
+            }
+        };
+        println!("[METRICS] Serving Prometheus metrics on http://{address}/metrics");
+        loop {
+            let Ok((stream, _)) = listener.accept().await else {
+                continue;
+            };
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let _ = serve_request(&app, stream).await;
+            });
+        }
+    });
+}
+
+async fn serve_request(app: &AppHandle, mut stream: tokio::net::TcpStream) -> std::io::Result<()> {
+    let mut buffer = [0u8; 1024];
+    let read = tokio::time::timeout(REQUEST_READ_TIMEOUT, stream.read(&mut buffer))
+        .await
+        .map_err(|_| std::io::Error::from(std::io::ErrorKind::TimedOut))??;
+    let request_line = String::from_utf8_lossy(&buffer[..read]);
+    let path = request_line.split_whitespace().nth(1).unwrap_or("");
+
+    let (status, content_type, body) = if path == "/metrics" || path.starts_with("/metrics?") {
+        ("200 OK", "text/plain; version=0.0.4; charset=utf-8", render(app).await)
+    } else {
+        ("404 Not Found", "text/plain; charset=utf-8", "Not found\n".to_string())
+    };
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_has_help_type_and_labelled_tunnel_gauge() {
+        let page = render_page(&Gauges {
+            sessions: 2,
+            local_tunnels: 3,
+            remote_tunnels: 1,
+            transfers: 0,
+        });
+        assert!(page.contains("# TYPE zync_sessions_active gauge\nzync_sessions_active 2\n"));
+        assert!(page.contains("zync_tunnels_active{kind=\"local\"} 3\n"));
+        assert!(page.contains("# TYPE zync_tunnel_forwarded_bytes_total counter\n"));
+    }
+
+    #[test]
+    fn config_defaults_to_disabled_loopback() {
+        let config: MetricsConfig = serde_json::from_value(serde_json::json!({ "enabled": true }))
+            .expect("config");
+        assert!(config.enabled);
+        assert_eq!(config.bind_address, "127.0.0.1");
+        assert_eq!(config.port, 9464);
+        assert!(!MetricsConfig::default().enabled);
+    }
+
+    #[test]
+    fn bind_address_accepts_ipv6() {
+        let config = |bind_address: &str| MetricsConfig {
+            bind_address: bind_address.to_string(),
+            ..MetricsConfig::default()
+        };
+        let loopback = config("::1").socket_addr().expect("ipv6");
+        assert_eq!(loopback.to_string(), "[::1]:9464");
+        assert_eq!(
+            config("[::]").socket_addr().expect("bracketed").to_string(),
+            "[::]:9464"
+        );
+        assert_eq!(
+            config("127.0.0.1").socket_addr().expect("ipv4").to_string(),
+            "127.0.0.1:9464"
+        );
+        assert!(config("localhost:80").socket_addr().is_err());
+    }
+}
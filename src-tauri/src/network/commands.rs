@@ -0,0 +1,6 @@
+use super::NetworkStatus;
+
+#[tauri::command]
+pub async fn network_status() -> Result<NetworkStatus, String> {
+    Ok(super::status())
+}
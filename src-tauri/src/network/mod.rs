@@ -0,0 +1,137 @@
+//! Offline detection, so remote work fails fast instead of waiting on timeouts.
+//!
+//! "Online" means the OS has a route to the public internet, checked by
+//! connecting a UDP socket (no packets are sent). Hosts are judged
+//! individually: loopback and routable LAN addresses stay usable even
+//! without a default route. Errors carry the `OFFLINE:` prefix so the
+//! frontend can tell them apart from real connection failures.
+
+pub mod commands;
+
+use serde::Serialize;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket};
+use std::sync::{LazyLock, Mutex as StdMutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+
+/// Machine-readable prefix for offline errors.
+pub const OFFLINE_PREFIX: &str = "OFFLINE:";
+
+const CACHE_TTL: Duration = Duration::from_secs(2);
+const MONITOR_INTERVAL: Duration = Duration::from_secs(5);
+const RESOLVE_TIMEOUT: Duration = Duration::from_secs(1);
+
+const PROBE_V4: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(8, 8, 8, 8)), 53);
+const PROBE_V6: SocketAddr = SocketAddr::new(
+    IpAddr::V6(Ipv6Addr::new(0x2001, 0x4860, 0x4860, 0, 0, 0, 0, 0x8888)),
+    53,
+);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkStatus {
+    pub online: bool,
+    pub checked_at_ms: u64,
+}
+
+static LAST_CHECK: LazyLock<StdMutex<Option<(Instant, NetworkStatus)>>> =
+    LazyLock::new(|| StdMutex::new(None));
+
+/// Whether the OS would route a packet to `addr`.
+fn has_route(addr: SocketAddr) -> bool {
+    let bind: SocketAddr = if addr.is_ipv4() {
+        (Ipv4Addr::UNSPECIFIED, 0).into()
+    } else {
+        (Ipv6Addr::UNSPECIFIED, 0).into()
+    };
+    UdpSocket::bind(bind)
+        .and_then(|socket| socket.connect(addr))
+        .is_ok()
+}
+
+fn check_now() -> NetworkStatus {
+    NetworkStatus {
+        online: has_route(PROBE_V4) || has_route(PROBE_V6),
+        checked_at_ms: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as u64)
+            .unwrap_or(0),
+    }
+}
+
+/// Cached for a couple of seconds; cheap enough to call before every remote operation.
+pub fn status() -> NetworkStatus {
+    let mut last = LAST_CHECK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some((checked, status)) = *last {
+        if checked.elapsed() < CACHE_TTL {
+            return status;
+        }
+    }
+    let status = check_now();
+    *last = Some((Instant::now(), status));
+    status
+}
+
+pub fn offline_error(host: &str) -> String {
+    format!("{OFFLINE_PREFIX}No network route to {host}. Connect to a network and try again.")
+}
+
+/// Fail immediately with an `OFFLINE:` error when `host` cannot be reached.
+pub async fn ensure_reachable(host: &str, port: u16) -> Result<(), String> {
+    let bare = host.trim().trim_start_matches('[').trim_end_matches(']');
+    if let Ok(ip) = bare.parse::<IpAddr>() {
+        return if ip.is_loopback() || has_route(SocketAddr::new(ip, port)) {
+            Ok(())
+        } else {
+            Err(offline_error(host))
+        };
+    }
+    if bare.eq_ignore_ascii_case("localhost") || status().online {
+        return Ok(());
+    }
+    // No default route: the name may still resolve locally (hosts file, LAN DNS).
+    match tokio::time::timeout(RESOLVE_TIMEOUT, tokio::net::lookup_host((bare, port))).await {
+        Ok(Ok(mut addrs)) if addrs.any(has_route) => Ok(()),
+        _ => Err(offline_error(host)),
+    }
+}
+
+/// Reachability of the first hop `config` dials: its outermost jump host, or itself.
+pub async fn ensure_config_reachable(config: &crate::types::ConnectionConfig) -> Result<(), String> {
+    let mut first_hop = config;
+    while let Some(jump) = first_hop.jump_host.as_deref() {
+        first_hop = jump;
+    }
+    ensure_reachable(&first_hop.host, first_hop.port).await
+}
+
+/// Emit `network:status` whenever connectivity flips.
+pub fn start(app: AppHandle) {
+    crate::crash_reports::spawn_supervised("network-monitor", async move {
+        let mut interval = tokio::time::interval(MONITOR_INTERVAL);
+        let mut previous: Option<bool> = None;
+        loop {
+            interval.tick().await;
+            let current = check_now();
+            *LAST_CHECK.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) =
+                Some((Instant::now(), current));
+            if previous.is_some_and(|online| online != current.online) {
+                let _ = app.emit("network:status", current);
+            }
+            previous = Some(current.online);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn loopback_is_always_reachable() {
+        assert!(ensure_reachable("127.0.0.1", 22).await.is_ok());
+        assert!(ensure_reachable("[::1]", 2222).await.is_ok());
+        assert!(ensure_reachable("localhost", 22).await.is_ok());
+        assert!(offline_error("db").starts_with(OFFLINE_PREFIX));
+    }
+}
@@ -0,0 +1,13 @@
+use super::{deliver, WebhookConfig, WebhookKind};
+
+/// Send a test message to a webhook so users can verify it before saving.
+#[tauri::command]
+pub async fn notifications_test_webhook(webhook: WebhookConfig) -> Result<(), String> {
+    let text = "Zync test notification";
+    let body = match webhook.kind {
+        WebhookKind::Slack => serde_json::json!({ "text": text }),
+        WebhookKind::Discord => serde_json::json!({ "content": text }),
+        WebhookKind::Generic => serde_json::json!({ "event": "test", "message": text }),
+    };
+    deliver(&webhook.url, body.to_string()).await
+}
@@ -0,0 +1,371 @@
+//! Webhook notifications for connection events.
+//!
+//! Configured in `settings.json` and re-read on every event, so edits apply
+//! without a restart:
+//!
+//! ```json
+//! "notifications": {
+//!   "longCommandSeconds": 60,
+//!   "webhooks": [
+//!     { "id": "ops", "kind": "slack", "url": "https://hooks.slack.com/...",
+//!       "events": ["tunnel-down", "reconnected"], "template": "{{connection}}: {{message}}" }
+//!   ]
+//! }
+//! ```
+//!
+//! Event names are the connection event bus `type` tags (see `crate::events`).
+
+pub mod commands;
+
+use crate::commands::{read_effective_settings, AppState};
+use crate::events::{event_type, ConnectionEvent, ConnectionEventEnvelope};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast::error::RecvError;
+
+const DELIVERY_ATTEMPTS: u32 = 3;
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(2);
+
+/// Used when a webhook does not list events explicitly.
+const DEFAULT_EVENTS: &[&str] = &["tunnel-down", "reconnected", "auth-failed", "command-finished"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookKind {
+    Slack,
+    Discord,
+    #[default]
+    Generic,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookConfig {
+    #[serde(default)]
+    pub id: String,
+    pub url: String,
+    #[serde(default)]
+    pub kind: WebhookKind,
+    #[serde(default)]
+    pub events: Vec<String>,
+    /// Message text for Slack/Discord, or the full JSON body for generic hooks.
+    #[serde(default)]
+    pub template: Option<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NotificationsConfig {
+    pub webhooks: Vec<WebhookConfig>,
+    /// `command-finished` only fires for commands that ran at least this long.
+    pub long_command_seconds: u64,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            webhooks: Vec::new(),
+            long_command_seconds: 60,
+        }
+    }
+}
+
+pub fn read_notifications_config(app: &AppHandle) -> NotificationsConfig {
+    read_effective_settings(app)
+        .ok()
+        .and_then(|settings| settings.get("notifications").cloned())
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+impl WebhookConfig {
+    fn wants(&self, kind: &str) -> bool {
+        if self.events.is_empty() {
+            DEFAULT_EVENTS.contains(&kind)
+        } else {
+            self.events.iter().any(|event| event == kind || event == "*")
+        }
+    }
+}
+
+/// One-line human summary of an event.
+fn describe(event: &ConnectionEvent) -> String {
+    match event {
+        ConnectionEvent::Connected { host, port, username, .. } => {
+            format!("Connected to {username}@{host}:{port}")
+        }
+        ConnectionEvent::ConnectFailed { error } => format!("Connection failed: {error}"),
+        ConnectionEvent::AuthFailed { error } => format!("Authentication failed: {error}"),
+        ConnectionEvent::Disconnected => "Disconnected".to_string(),
+        ConnectionEvent::Reconnected => "Reconnected after the session dropped".to_string(),
+        ConnectionEvent::TransportLost => "SSH connection lost".to_string(),
+        ConnectionEvent::KeepaliveTimeout => "Server stopped answering keepalives".to_string(),
+        ConnectionEvent::ChannelOpened { open_channels, .. } => {
+            format!("Session channel opened ({open_channels} open)")
+        }
+        ConnectionEvent::TunnelUp { tunnel_id } => format!("Tunnel {tunnel_id} is up"),
+        ConnectionEvent::TunnelDown { tunnel_id, error: Some(error) } => {
+            format!("Tunnel {tunnel_id} went down: {error}")
+        }
+        ConnectionEvent::TunnelDown { tunnel_id, error: None } => {
+            format!("Tunnel {tunnel_id} stopped")
+        }
+        ConnectionEvent::TransferProgress { transfer_id, total, .. } => {
+            format!("Transfer {transfer_id} started ({total} bytes)")
+        }
+        ConnectionEvent::TransferFinished { transfer_id, outcome, .. } => {
+            format!("Transfer {transfer_id} {outcome}")
+        }
+        ConnectionEvent::CommandFinished {
+            command,
+            exit_code,
+            duration_ms,
+        } => format!(
+            "Command finished after {}s (exit {exit_code}): {command}",
+            duration_ms / 1000
+        ),
+        ConnectionEvent::StateChanged { state, previous } => {
+            format!("Session {} → {}", previous.as_str(), state.as_str())
+        }
+    }
+}
+
+struct TemplateValues {
+    event: String,
+    connection: String,
+    message: String,
+    timestamp_ms: u64,
+}
+
+/// Replace `{{event}}`, `{{connection}}`, `{{message}}`, `{{timestamp}}`.
+/// With `json_escape`, values are escaped so they can sit inside JSON strings.
+fn fill_template(template: &str, values: &TemplateValues, json_escape: bool) -> String {
+    let escape = |value: &str| {
+        if json_escape {
+            let quoted = Value::String(value.to_string()).to_string();
+            quoted[1..quoted.len() - 1].to_string()
+        } else {
+            value.to_string()
+        }
+    };
+    template
+        .replace("{{event}}", &escape(&values.event))
+        .replace("{{connection}}", &escape(&values.connection))
+        .replace("{{message}}", &escape(&values.message))
+        .replace("{{timestamp}}", &values.timestamp_ms.to_string())
+}
+
+/// JSON request body for one webhook.
+fn build_payload(
+    webhook: &WebhookConfig,
+    envelope: &ConnectionEventEnvelope,
+    values: &TemplateValues,
+) -> Result<String, String> {
+    let text = || match &webhook.template {
+        Some(template) => fill_template(template, values, false),
+        None => format!("[Zync] {}: {}", values.connection, values.message),
+    };
+    match webhook.kind {
+        WebhookKind::Slack => Ok(serde_json::json!({ "text": text() }).to_string()),
+        WebhookKind::Discord => Ok(serde_json::json!({ "content": text() }).to_string()),
+        WebhookKind::Generic => match &webhook.template {
+            Some(template) => {
+                let body = fill_template(template, values, true);
+                serde_json::from_str::<Value>(&body)
+                    .map_err(|e| format!("Webhook template is not valid JSON: {e}"))?;
+                Ok(body)
+            }
+            None => Ok(serde_json::json!({
+                "event": values.event,
+                "connection": values.connection,
+                "message": values.message,
+                "payload": envelope,
+            })
+            .to_string()),
+        },
+    }
+}
+
+async fn connection_label(app: &AppHandle, connection_id: &str) -> String {
+    let Some(state) = app.try_state::<AppState>() else {
+        return connection_id.to_string();
+    };
+    let connections = state.connections.lock().await;
+    connections
+        .get(connection_id)
+        .map(|handle| {
+            if handle.config.name.trim().is_empty() {
+                handle.config.host.clone()
+            } else {
+                handle.config.name.clone()
+            }
+        })
+        .unwrap_or_else(|| connection_id.to_string())
+}
+
+/// POST with retries on network errors, 429 and 5xx.
+pub(crate) async fn deliver(url: &str, body: String) -> Result<(), String> {
+    let client = reqwest::Client::builder()
+        .timeout(DELIVERY_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let mut last_error = String::new();
+    for attempt in 0..DELIVERY_ATTEMPTS {
+        if attempt > 0 {
+            tokio::time::sleep(RETRY_BASE_DELAY * 2u32.pow(attempt - 1)).await;
+        }
+        match client
+            .post(url)
+            .header("Content-Type", "application/json")
+            .body(body.clone())
+            .send()
+            .await
+        {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            Ok(response) => {
+                let status = response.status();
+                last_error = format!("HTTP {status}");
+                if !(status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS) {
+                    break;
+                }
+            }
+            Err(error) => last_error = error.to_string(),
+        }
+    }
+    Err(last_error)
+}
+
+async fn dispatch(app: &AppHandle, envelope: ConnectionEventEnvelope) {
+    let config = read_notifications_config(app);
+    let kind = event_type(&envelope.event);
+    // A host rebooted or shut down from here drops its session on purpose.
+    if matches!(
+        envelope.event,
+        ConnectionEvent::TransportLost | ConnectionEvent::KeepaliveTimeout
+    ) && crate::host_power::expecting_disconnect(&envelope.connection_id)
+    {
+        return;
+    }
+    if let ConnectionEvent::CommandFinished { duration_ms, .. } = &envelope.event {
+        if *duration_ms < config.long_command_seconds.saturating_mul(1000) {
+            return;
+        }
+    }
+
+    let targets: Vec<WebhookConfig> = config
+        .webhooks
+        .into_iter()
+        .filter(|webhook| webhook.enabled && !webhook.url.trim().is_empty() && webhook.wants(&kind))
+        .collect();
+    if targets.is_empty() {
+        return;
+    }
+
+    let values = TemplateValues {
+        event: kind,
+        connection: connection_label(app, &envelope.connection_id).await,
+        message: describe(&envelope.event),
+        timestamp_ms: envelope.timestamp_ms,
+    };
+    for webhook in targets {
+        let body = match build_payload(&webhook, &envelope, &values) {
+            Ok(body) => body,
+            Err(error) => {
+                eprintln!("[NOTIFY] Skipping webhook {}: {error}", webhook.id);
+                continue;
+            }
+        };
+        // Deliver each hook independently so one slow endpoint can't delay the rest.
+        tauri::async_runtime::spawn(async move {
+            if let Err(error) = deliver(&webhook.url, body).await {
+                eprintln!("[NOTIFY] Webhook {} failed: {error}", webhook.id);
+            }
+        });
+    }
+}
+
+pub fn start(app: AppHandle) {
+    let mut events = crate::events::subscribe();
+    crate::crash_reports::spawn_supervised("notifications", async move {
+        loop {
+            match events.recv().await {
+                Ok(envelope) => dispatch(&app, envelope).await,
+                Err(RecvError::Lagged(skipped)) => {
+                    eprintln!("[NOTIFY] Event subscriber lagged; {skipped} events dropped");
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values() -> TemplateValues {
+        TemplateValues {
+            event: "tunnel-down".to_string(),
+            connection: "db \"primary\"".to_string(),
+            message: "Tunnel t1 stopped".to_string(),
+            timestamp_ms: 42,
+        }
+    }
+
+    fn envelope() -> ConnectionEventEnvelope {
+        ConnectionEventEnvelope {
+            seq: 1,
+            timestamp_ms: 42,
+            connection_id: "conn-1".to_string(),
+            event: ConnectionEvent::TunnelDown {
+                tunnel_id: "t1".to_string(),
+                error: None,
+            },
+        }
+    }
+
+    fn webhook(kind: WebhookKind, template: Option<&str>) -> WebhookConfig {
+        WebhookConfig {
+            id: "hook".to_string(),
+            url: "https://example.invalid".to_string(),
+            kind,
+            events: Vec::new(),
+            template: template.map(str::to_string),
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn generic_template_values_are_json_escaped() {
+        let hook = webhook(WebhookKind::Generic, Some(r#"{"who":"{{connection}}","at":{{timestamp}}}"#));
+        let body = build_payload(&hook, &envelope(), &values()).expect("payload");
+        let parsed: Value = serde_json::from_str(&body).expect("json");
+        assert_eq!(parsed["who"], "db \"primary\"");
+        assert_eq!(parsed["at"], 42);
+    }
+
+    #[test]
+    fn slack_and_discord_use_their_text_fields() {
+        let slack = build_payload(&webhook(WebhookKind::Slack, None), &envelope(), &values()).expect("slack");
+        assert!(slack.contains("\"text\""));
+        let discord = build_payload(&webhook(WebhookKind::Discord, None), &envelope(), &values()).expect("discord");
+        assert!(discord.contains("\"content\""));
+    }
+
+    #[test]
+    fn empty_event_list_uses_defaults() {
+        let hook = webhook(WebhookKind::Generic, None);
+        assert!(hook.wants("tunnel-down"));
+        assert!(!hook.wants("channel-opened"));
+    }
+}
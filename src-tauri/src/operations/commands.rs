@@ -0,0 +1,13 @@
+use super::{cancel, list, OperationInfo};
+
+/// Cancel a running operation by id: a transfer id, a fleet run or push id,
+/// a scan id, or `connect:<connection id>`. Returns whether one was running.
+#[tauri::command]
+pub async fn cancel_operation(id: String) -> Result<bool, String> {
+    Ok(cancel(&id))
+}
+
+#[tauri::command]
+pub async fn operations_list() -> Result<Vec<OperationInfo>, String> {
+    Ok(list())
+}
@@ -0,0 +1,316 @@
+//! Cancellable long-running operations: connects, transfers, scans and
+//! multi-host runs register under an operation id (usually generated by
+//! the frontend) and `cancel_operation(id)` stops them.
+//!
+//! Registration is an RAII [`Operation`]. Dropping it, whether the work
+//! finished, failed, panicked or its future was dropped, cancels its token
+//! and unregisters the id, so a stale id can't be cancelled and parts
+//! started under it (see [`begin_under`]) stop with it. Work driven through
+//! [`CancelToken::run`] is dropped on cancellation, releasing the channels
+//! and permits it holds.
+
+pub mod commands;
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard, Weak};
+use tokio::sync::Notify;
+
+/// Error returned by work stopped through its token. Transfers already
+/// report this string, so the frontend treats it as a cancellation.
+pub const CANCELLED: &str = "Cancelled";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OperationKind {
+    Connect,
+    Transfer,
+    Scan,
+    FleetRun,
+    /// Waiting for a rebooted host to come back; see `host_power`.
+    PowerWatch,
+}
+
+#[derive(Default)]
+struct TokenState {
+    flag: Arc<AtomicBool>,
+    notify: Notify,
+    children: Mutex<Vec<Weak<TokenState>>>,
+}
+
+fn lock_children(state: &TokenState) -> MutexGuard<'_, Vec<Weak<TokenState>>> {
+    state
+        .children
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Cheap to clone; every clone observes the same cancellation.
+#[derive(Clone, Default)]
+pub struct CancelToken {
+    state: Arc<TokenState>,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.state.flag.store(true, Ordering::Relaxed);
+        self.state.notify.notify_waiters();
+        let children = std::mem::take(&mut *lock_children(&self.state));
+        for child in children.iter().filter_map(Weak::upgrade) {
+            CancelToken { state: child }.cancel();
+        }
+    }
+
+    /// Cancel `child` whenever this token is cancelled.
+    fn link(&self, child: &CancelToken) {
+        let mut children = lock_children(&self.state);
+        if self.is_cancelled() {
+            drop(children);
+            child.cancel();
+            return;
+        }
+        children.retain(|existing| existing.strong_count() > 0);
+        children.push(Arc::downgrade(&child.state));
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state.flag.load(Ordering::Relaxed)
+    }
+
+    /// The flag polled by code that predates tokens (transfer loops).
+    pub fn flag(&self) -> Arc<AtomicBool> {
+        self.state.flag.clone()
+    }
+
+    /// Resolves once the token is cancelled.
+    pub async fn cancelled(&self) {
+        loop {
+            let notified = self.state.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// Drive `work` unless the token is cancelled first; the work future is
+    /// dropped on cancellation, releasing whatever it holds.
+    pub async fn run<T>(&self, work: impl Future<Output = Result<T, String>>) -> Result<T, String> {
+        if self.is_cancelled() {
+            return Err(CANCELLED.to_string());
+        }
+        tokio::select! {
+            result = work => result,
+            _ = self.cancelled() => Err(CANCELLED.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationInfo {
+    pub id: String,
+    pub kind: OperationKind,
+    pub label: String,
+    pub started_at: u64,
+}
+
+struct Entry {
+    serial: u64,
+    info: OperationInfo,
+    token: CancelToken,
+}
+
+static OPERATIONS: LazyLock<Mutex<HashMap<String, Entry>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+static NEXT_SERIAL: AtomicU64 = AtomicU64::new(1);
+
+fn lock_operations() -> MutexGuard<'static, HashMap<String, Entry>> {
+    OPERATIONS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// A registered operation; see the module docs for what dropping it does.
+pub struct Operation {
+    id: String,
+    serial: u64,
+    token: CancelToken,
+}
+
+/// Register an operation. Without an id one is generated; reusing the id
+/// of a running operation cancels and replaces it.
+pub fn begin(kind: OperationKind, id: Option<String>, label: impl Into<String>) -> Operation {
+    let id = id
+        .filter(|id| !id.trim().is_empty())
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let serial = NEXT_SERIAL.fetch_add(1, Ordering::Relaxed);
+    let token = CancelToken::new();
+    let entry = Entry {
+        serial,
+        info: OperationInfo {
+            id: id.clone(),
+            kind,
+            label: label.into(),
+            started_at: now_ms(),
+        },
+        token: token.clone(),
+    };
+    if let Some(superseded) = lock_operations().insert(id.clone(), entry) {
+        superseded.token.cancel();
+    }
+    Operation { id, serial, token }
+}
+
+/// [`begin`] for a part of a larger operation: cancelling `parent` cancels
+/// this one too, while this one can still be cancelled on its own.
+pub fn begin_under(
+    parent: &CancelToken,
+    kind: OperationKind,
+    id: Option<String>,
+    label: impl Into<String>,
+) -> Operation {
+    let operation = begin(kind, id, label);
+    parent.link(&operation.token);
+    operation
+}
+
+impl Operation {
+    pub fn token(&self) -> &CancelToken {
+        &self.token
+    }
+
+    pub async fn run<T>(&self, work: impl Future<Output = Result<T, String>>) -> Result<T, String> {
+        self.token.run(work).await
+    }
+}
+
+impl Drop for Operation {
+    fn drop(&mut self) {
+        self.token.cancel();
+        let mut operations = lock_operations();
+        if operations
+            .get(&self.id)
+            .is_some_and(|entry| entry.serial == self.serial)
+        {
+            operations.remove(&self.id);
+        }
+    }
+}
+
+/// Cancel the operation registered as `id`. Returns whether one was running.
+pub fn cancel(id: &str) -> bool {
+    let token = lock_operations().get(id).map(|entry| entry.token.clone());
+    match token {
+        Some(token) => {
+            token.cancel();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Running operations, oldest first.
+pub fn list() -> Vec<OperationInfo> {
+    let mut operations: Vec<OperationInfo> = lock_operations()
+        .values()
+        .map(|entry| entry.info.clone())
+        .collect();
+    operations.sort_by(|a, b| a.started_at.cmp(&b.started_at).then(a.id.cmp(&b.id)));
+    operations
+}
+
+pub fn count(kind: OperationKind) -> usize {
+    lock_operations()
+        .values()
+        .filter(|entry| entry.info.kind == kind)
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn cancel_stops_running_work() {
+        let operation = begin(OperationKind::Scan, Some("op-test-cancel".into()), "scan");
+        let work = std::future::pending::<Result<(), String>>();
+        let (result, cancelled) = tokio::join!(operation.run(work), async {
+            tokio::task::yield_now().await;
+            cancel("op-test-cancel")
+        });
+        assert!(cancelled);
+        assert_eq!(result, Err(CANCELLED.to_string()));
+        assert!(operation.token().flag().load(Ordering::Relaxed));
+        assert_eq!(
+            operation.run(async { Ok(1) }).await,
+            Err(CANCELLED.to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn dropping_the_operation_cleans_up() {
+        let operation = begin(OperationKind::FleetRun, Some("op-test-drop".into()), "run");
+        let token = operation.token().clone();
+        let waiter = tokio::spawn(async move { token.cancelled().await });
+        assert!(list().iter().any(|info| info.id == "op-test-drop"));
+        drop(operation);
+        waiter
+            .await
+            .expect("waiter finishes once the operation is dropped");
+        assert!(!list().iter().any(|info| info.id == "op-test-drop"));
+        assert!(!cancel("op-test-drop"));
+    }
+
+    #[test]
+    fn parts_follow_their_parent() {
+        let parent = begin(
+            OperationKind::FleetRun,
+            Some("op-test-parent".into()),
+            "push",
+        );
+        let first = begin_under(
+            parent.token(),
+            OperationKind::Transfer,
+            Some("op-test-part-a".into()),
+            "host a",
+        );
+        let second = begin_under(parent.token(), OperationKind::Transfer, None, "host b");
+        assert!(cancel("op-test-part-a"));
+        assert!(first.token().is_cancelled());
+        assert!(!parent.token().is_cancelled() && !second.token().is_cancelled());
+        assert!(cancel("op-test-parent"));
+        assert!(second.token().is_cancelled());
+        let late = begin_under(parent.token(), OperationKind::Transfer, None, "host c");
+        assert!(late.token().is_cancelled());
+    }
+
+    #[test]
+    fn reusing_an_id_supersedes_the_running_operation() {
+        let first = begin(OperationKind::Transfer, Some("op-test-reuse".into()), "a");
+        let second = begin(OperationKind::Transfer, Some("op-test-reuse".into()), "b");
+        assert!(first.token().is_cancelled());
+        drop(first);
+        assert!(!second.token().is_cancelled());
+        assert!(list()
+            .iter()
+            .any(|info| info.id == "op-test-reuse" && info.label == "b"));
+        drop(second);
+        assert!(!list().iter().any(|info| info.id == "op-test-reuse"));
+    }
+}
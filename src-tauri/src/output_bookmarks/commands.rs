@@ -0,0 +1,95 @@
+use super::{NewOutputBookmark, OutputBookmark, OutputBookmarks, STORE_FILE};
+use crate::commands::get_data_dir;
+use crate::redaction::Redactor;
+use std::path::Path;
+use std::sync::{LazyLock, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+const DEFAULT_LIST_LIMIT: usize = 200;
+
+static STORE_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+fn load(dir: &Path) -> Result<OutputBookmarks, String> {
+    match std::fs::read_to_string(dir.join(STORE_FILE)) {
+        Ok(content) => serde_json::from_str(&content).map_err(|e| e.to_string()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(OutputBookmarks::default()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn mutate<T>(
+    app: &AppHandle,
+    change: impl FnOnce(&mut OutputBookmarks) -> Result<T, String>,
+) -> Result<T, String> {
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let dir = get_data_dir(app);
+    let mut bookmarks = load(&dir)?;
+    let result = change(&mut bookmarks)?;
+    let json = serde_json::to_vec(&bookmarks).map_err(|e| e.to_string())?;
+    crate::atomic_io::durable_replace(&dir.join(STORE_FILE), &json).map_err(|e| e.to_string())?;
+    Ok(result)
+}
+
+/// Mask `secrets` in the stored bookmarks; returns how many changed.
+pub(crate) fn scrub_secrets(app: &AppHandle, secrets: &[String]) -> Result<usize, String> {
+    mutate(app, |bookmarks| Ok(bookmarks.scrub(secrets)))
+}
+
+/// Save a bookmark. The excerpt and command go through the redaction rules
+/// first, since they are kept on disk indefinitely.
+#[tauri::command]
+pub async fn output_bookmark_add(
+    app: AppHandle,
+    bookmark: NewOutputBookmark,
+) -> Result<OutputBookmark, String> {
+    let redactor = Redactor::from_app(&app);
+    let bookmark = NewOutputBookmark {
+        excerpt: redactor.redact(&bookmark.excerpt),
+        command: bookmark.command.map(|command| redactor.redact(&command)),
+        ..bookmark
+    };
+    mutate(&app, |bookmarks| bookmarks.add(bookmark, now_ms()))
+}
+
+/// Newest first; `connection_id` narrows to one connection, otherwise every
+/// connection's bookmarks are listed.
+#[tauri::command]
+pub async fn output_bookmarks_list(
+    app: AppHandle,
+    connection_id: Option<String>,
+    query: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<OutputBookmark>, String> {
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let bookmarks = load(&get_data_dir(&app))?;
+    Ok(bookmarks.search(
+        connection_id.as_deref(),
+        query.as_deref(),
+        limit.unwrap_or(DEFAULT_LIST_LIMIT),
+    ))
+}
+
+#[tauri::command]
+pub async fn output_bookmark_rename(
+    app: AppHandle,
+    id: String,
+    label: String,
+) -> Result<OutputBookmark, String> {
+    mutate(&app, |bookmarks| bookmarks.rename(&id, &label))
+}
+
+#[tauri::command]
+pub async fn output_bookmark_delete(app: AppHandle, id: String) -> Result<(), String> {
+    mutate(&app, |bookmarks| {
+        bookmarks.remove(&id);
+        Ok(())
+    })
+}
@@ -0,0 +1,248 @@
+//! Bookmarks into terminal output, so "that error I saw yesterday" can be
+//! found again. Each one keeps the command that produced the output and the
+//! cwd (both from the terminal's input tracking and OSC 7), plus an excerpt
+//! of the output itself: the scrollback does not survive a restart, the
+//! excerpt does. Stored per connection in `output_bookmarks.json`.
+
+pub mod commands;
+
+use serde::{Deserialize, Serialize};
+
+pub const STORE_FILE: &str = "output_bookmarks.json";
+/// Longer excerpts keep their last this many bytes (where errors usually are).
+pub const MAX_EXCERPT_BYTES: usize = 64 * 1024;
+/// Oldest bookmarks of a connection go first beyond this.
+const MAX_PER_CONNECTION: usize = 500;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewOutputBookmark {
+    /// Workspace connection id; `local` for local terminals.
+    pub connection_id: String,
+    pub term_id: String,
+    #[serde(default)]
+    pub label: String,
+    #[serde(default)]
+    pub command: Option<String>,
+    #[serde(default)]
+    pub cwd: Option<String>,
+    pub excerpt: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputBookmark {
+    pub id: String,
+    pub connection_id: String,
+    /// Terminal tab the output was in; only meaningful while it is open.
+    pub term_id: String,
+    pub label: String,
+    #[serde(default)]
+    pub command: Option<String>,
+    #[serde(default)]
+    pub cwd: Option<String>,
+    pub excerpt: String,
+    #[serde(default)]
+    pub truncated: bool,
+    pub created_at: u64,
+}
+
+/// Newest first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OutputBookmarks {
+    #[serde(default)]
+    pub bookmarks: Vec<OutputBookmark>,
+}
+
+/// Keep the tail of `text`, cut on a line start when one is close.
+fn tail_of(text: &str, max_bytes: usize) -> (String, bool) {
+    if text.len() <= max_bytes {
+        return (text.to_string(), false);
+    }
+    let mut start = text.len() - max_bytes;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    let tail = &text[start..];
+    let tail = match tail.find('\n') {
+        Some(newline) if newline < 1024 => &tail[newline + 1..],
+        _ => tail,
+    };
+    (tail.to_string(), true)
+}
+
+fn default_label(new: &NewOutputBookmark) -> String {
+    let label = new.label.trim();
+    if !label.is_empty() {
+        return label.to_string();
+    }
+    if let Some(command) = new.command.as_deref().map(str::trim) {
+        if !command.is_empty() {
+            return command.to_string();
+        }
+    }
+    new.excerpt
+        .lines()
+        .map(str::trim)
+        .rfind(|line| !line.is_empty())
+        .unwrap_or("Output")
+        .chars()
+        .take(120)
+        .collect()
+}
+
+impl OutputBookmarks {
+    pub fn add(&mut self, new: NewOutputBookmark, now: u64) -> Result<OutputBookmark, String> {
+        if new.excerpt.trim().is_empty() {
+            return Err("Nothing to bookmark: the output is empty".to_string());
+        }
+        let label = default_label(&new);
+        let (excerpt, truncated) = tail_of(&new.excerpt, MAX_EXCERPT_BYTES);
+        let bookmark = OutputBookmark {
+            id: uuid::Uuid::new_v4().to_string(),
+            connection_id: new.connection_id,
+            term_id: new.term_id,
+            label,
+            command: new.command.filter(|command| !command.trim().is_empty()),
+            cwd: new.cwd.filter(|cwd| !cwd.trim().is_empty()),
+            excerpt,
+            truncated,
+            created_at: now,
+        };
+        self.bookmarks.insert(0, bookmark.clone());
+        let mut kept = 0usize;
+        let connection_id = bookmark.connection_id.clone();
+        self.bookmarks.retain(|existing| {
+            if existing.connection_id != connection_id {
+                return true;
+            }
+            kept += 1;
+            kept <= MAX_PER_CONNECTION
+        });
+        Ok(bookmark)
+    }
+
+    /// Bookmarks of `connection_id` (all when `None`) whose label, command,
+    /// cwd or excerpt contains `query`, case-insensitively; newest first.
+    pub fn search(
+        &self,
+        connection_id: Option<&str>,
+        query: Option<&str>,
+        limit: usize,
+    ) -> Vec<OutputBookmark> {
+        let query = query
+            .map(|query| query.trim().to_lowercase())
+            .filter(|query| !query.is_empty());
+        self.bookmarks
+            .iter()
+            .filter(|bookmark| connection_id.is_none_or(|id| bookmark.connection_id == id))
+            .filter(|bookmark| match &query {
+                Some(query) => [
+                    Some(bookmark.label.as_str()),
+                    bookmark.command.as_deref(),
+                    bookmark.cwd.as_deref(),
+                    Some(bookmark.excerpt.as_str()),
+                ]
+                .into_iter()
+                .flatten()
+                .any(|field| field.to_lowercase().contains(query)),
+                None => true,
+            })
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    pub fn rename(&mut self, id: &str, label: &str) -> Result<OutputBookmark, String> {
+        let bookmark = self
+            .bookmarks
+            .iter_mut()
+            .find(|bookmark| bookmark.id == id)
+            .ok_or_else(|| "Bookmark not found".to_string())?;
+        let label = label.trim();
+        if !label.is_empty() {
+            bookmark.label = label.to_string();
+        }
+        Ok(bookmark.clone())
+    }
+
+    /// Mask `secrets` in every bookmark; returns how many bookmarks changed.
+    pub fn scrub(&mut self, secrets: &[String]) -> usize {
+        use crate::redaction::scrub_literals;
+        self.bookmarks
+            .iter_mut()
+            .map(|bookmark| {
+                let label = scrub_literals(&mut bookmark.label, secrets);
+                let excerpt = scrub_literals(&mut bookmark.excerpt, secrets);
+                let command = bookmark
+                    .command
+                    .as_mut()
+                    .is_some_and(|command| scrub_literals(command, secrets));
+                label || excerpt || command
+            })
+            .filter(|changed| *changed)
+            .count()
+    }
+
+    pub fn remove(&mut self, id: &str) -> bool {
+        let before = self.bookmarks.len();
+        self.bookmarks.retain(|bookmark| bookmark.id != id);
+        self.bookmarks.len() != before
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new(connection_id: &str, command: Option<&str>, excerpt: &str) -> NewOutputBookmark {
+        NewOutputBookmark {
+            connection_id: connection_id.to_string(),
+            term_id: "term-1".to_string(),
+            command: command.map(str::to_string),
+            excerpt: excerpt.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn labels_from_command_or_last_output_line() {
+        let mut store = OutputBookmarks::default();
+        let first = store
+            .add(new("web", Some("systemctl status nginx"), "failed\n"), 1)
+            .unwrap();
+        assert_eq!(first.label, "systemctl status nginx");
+        let second = store
+            .add(new("local", None, "starting\nError: port in use\n\n"), 2)
+            .unwrap();
+        assert_eq!(second.label, "Error: port in use");
+        assert!(store.add(new("local", None, "  \n"), 3).is_err());
+
+        let long = format!("head\n{}\nend", "x".repeat(MAX_EXCERPT_BYTES));
+        let third = store.add(new("local", None, &long), 4).unwrap();
+        assert!(third.truncated);
+        assert!(third.excerpt.ends_with("end"));
+        assert!(third.excerpt.len() <= MAX_EXCERPT_BYTES);
+    }
+
+    #[test]
+    fn searches_per_connection_newest_first() {
+        let mut store = OutputBookmarks::default();
+        store
+            .add(new("web", Some("journalctl -u app"), "OOM killed"), 1)
+            .unwrap();
+        store
+            .add(new("db", Some("psql"), "FATAL: too many clients"), 2)
+            .unwrap();
+        let latest = store
+            .add(new("web", Some("dmesg"), "oom-killer invoked"), 3)
+            .unwrap();
+
+        let web = store.search(Some("web"), Some("OOM"), 10);
+        assert_eq!(web.len(), 2);
+        assert_eq!(web[0].id, latest.id);
+        assert_eq!(store.search(None, Some("fatal"), 10).len(), 1);
+        assert!(store.remove(&latest.id));
+        assert_eq!(store.search(Some("web"), None, 10).len(), 1);
+    }
+}
@@ -0,0 +1,133 @@
+use super::{
+    parse_upgradable, validate_packages, PackageAction, PackageManager, PackageOperationResult,
+    PackageUpdate,
+};
+use crate::commands::{
+    ensure_connection_online, exec_on_connection, exec_on_connection_with_stdin, AppState,
+};
+use crate::host_capabilities::{elevation, Elevation, HostCapabilities};
+use serde::Serialize;
+use tauri::{AppHandle, State};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpgradableList {
+    pub manager: PackageManager,
+    pub updates: Vec<PackageUpdate>,
+}
+
+async fn detect(
+    state: &AppState,
+    connection_id: &str,
+) -> Result<(HostCapabilities, PackageManager), String> {
+    ensure_connection_online(state, connection_id).await?;
+    let capabilities = crate::host_capabilities::probe(state, connection_id, false).await?;
+    let manager = PackageManager::detect(&capabilities)
+        .ok_or("Tool missing: needs apt-get or dnf or yum or apk or pacman")?;
+    Ok((capabilities, manager))
+}
+
+/// Packages with a newer version in the host's cached metadata. Doesn't
+/// refresh the metadata, so it needs no privileges.
+#[tauri::command]
+pub async fn packages_list_upgradable(
+    state: State<'_, AppState>,
+    connection_id: String,
+) -> Result<UpgradableList, String> {
+    let (_, manager) = detect(&state, &connection_id).await?;
+    let output =
+        exec_on_connection(&state, &connection_id, manager.list_upgradable_command()).await?;
+    Ok(UpgradableList {
+        manager,
+        updates: parse_upgradable(manager, &String::from_utf8_lossy(&output.stdout)),
+    })
+}
+
+async fn run_action(
+    app: &AppHandle,
+    state: &AppState,
+    connection_id: &str,
+    action: PackageAction,
+    packages: &[String],
+    sudo_password: Option<&str>,
+    confirmation: Option<&str>,
+) -> Result<PackageOperationResult, String> {
+    validate_packages(packages)?;
+    let (capabilities, manager) = detect(state, connection_id).await?;
+    let elevation = elevation(&capabilities, sudo_password.is_some())?;
+    let command = format!(
+        "{}{}",
+        elevation.prefix(),
+        manager.action_command(action, packages)
+    );
+    crate::safety::enforce(
+        app,
+        connection_id,
+        crate::safety::SafetyAction::Exec,
+        &command,
+        confirmation,
+    )?;
+
+    let started = std::time::Instant::now();
+    let output = match (elevation, sudo_password) {
+        (Elevation::SudoWithPassword, Some(password)) => {
+            let input = format!("{password}\n");
+            exec_on_connection_with_stdin(state, connection_id, &command, input.as_bytes()).await?
+        }
+        _ => exec_on_connection(state, connection_id, &command).await?,
+    };
+    Ok(PackageOperationResult {
+        manager,
+        command,
+        exit_code: output.exit_status,
+        success: output.exit_status == 0,
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+/// Install `packages` non-interactively. Without passwordless sudo the call
+/// fails with `SUDO_PASSWORD_REQUIRED:` until `sudo_password` is given.
+#[tauri::command]
+pub async fn packages_install(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    connection_id: String,
+    packages: Vec<String>,
+    sudo_password: Option<String>,
+    confirmation: Option<String>,
+) -> Result<PackageOperationResult, String> {
+    run_action(
+        &app,
+        &state,
+        &connection_id,
+        PackageAction::Install,
+        &packages,
+        sudo_password.as_deref(),
+        confirmation.as_deref(),
+    )
+    .await
+}
+
+/// Remove `packages`; same sudo flow as [`packages_install`].
+#[tauri::command]
+pub async fn packages_remove(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    connection_id: String,
+    packages: Vec<String>,
+    sudo_password: Option<String>,
+    confirmation: Option<String>,
+) -> Result<PackageOperationResult, String> {
+    run_action(
+        &app,
+        &state,
+        &connection_id,
+        PackageAction::Remove,
+        &packages,
+        sudo_password.as_deref(),
+        confirmation.as_deref(),
+    )
+    .await
+}
@@ -0,0 +1,277 @@
+//! Package maintenance on a remote host: list pending upgrades, install and
+//! remove packages with whichever of apt, dnf, yum, apk or pacman is present.
+//!
+//! The manager comes from the host capability probe, which also says how to
+//! become root: directly, `sudo -n`, or `sudo -S` fed the password the user
+//! typed. Install and remove return the complete output of the run.
+
+pub mod commands;
+
+use crate::host_capabilities::HostCapabilities;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageManager {
+    Apt,
+    Dnf,
+    Yum,
+    Apk,
+    Pacman,
+}
+
+/// Detection order; the tool names are the ones the capability probe reports.
+const MANAGERS: &[(&str, PackageManager)] = &[
+    ("apt-get", PackageManager::Apt),
+    ("dnf", PackageManager::Dnf),
+    ("yum", PackageManager::Yum),
+    ("apk", PackageManager::Apk),
+    ("pacman", PackageManager::Pacman),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackageAction {
+    Install,
+    Remove,
+}
+
+impl PackageManager {
+    pub fn detect(capabilities: &HostCapabilities) -> Option<Self> {
+        if capabilities.windows {
+            return None;
+        }
+        MANAGERS
+            .iter()
+            .find(|(tool, _)| capabilities.has_tool(tool))
+            .map(|(_, manager)| *manager)
+    }
+
+    /// Reads the local package metadata only, so it runs without root.
+    pub fn list_upgradable_command(self) -> &'static str {
+        match self {
+            Self::Apt => "apt list --upgradable 2>/dev/null",
+            Self::Dnf => "dnf -q -C check-update 2>/dev/null",
+            Self::Yum => "yum -q -C check-update 2>/dev/null",
+            Self::Apk => "apk -u list 2>/dev/null",
+            Self::Pacman => "checkupdates 2>/dev/null || pacman -Qu 2>/dev/null",
+        }
+    }
+
+    /// Non-interactive command for `action`, without any privilege prefix.
+    /// Installing a package that is already present upgrades it.
+    pub fn action_command(self, action: PackageAction, packages: &[String]) -> String {
+        let base = match (self, action) {
+            (Self::Apt, PackageAction::Install) => {
+                "env DEBIAN_FRONTEND=noninteractive apt-get install -y"
+            }
+            (Self::Apt, PackageAction::Remove) => {
+                "env DEBIAN_FRONTEND=noninteractive apt-get remove -y"
+            }
+            (Self::Dnf, PackageAction::Install) => "dnf install -y",
+            (Self::Dnf, PackageAction::Remove) => "dnf remove -y",
+            (Self::Yum, PackageAction::Install) => "yum install -y",
+            (Self::Yum, PackageAction::Remove) => "yum remove -y",
+            (Self::Apk, PackageAction::Install) => "apk add -u",
+            (Self::Apk, PackageAction::Remove) => "apk del",
+            (Self::Pacman, PackageAction::Install) => "pacman -S --noconfirm",
+            (Self::Pacman, PackageAction::Remove) => "pacman -R --noconfirm",
+        };
+        format!("{base} {}", packages.join(" "))
+    }
+}
+
+/// Package names (optionally `name=version` or `name@repo`) as the managers
+/// accept them. Anything else would be interpreted by the remote shell.
+pub fn validate_packages(packages: &[String]) -> Result<(), String> {
+    if packages.is_empty() {
+        return Err("No packages given".to_string());
+    }
+    for package in packages {
+        let valid = !package.starts_with('-')
+            && !package.is_empty()
+            && package
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "._+-:=@~".contains(c));
+        if !valid {
+            return Err(format!("Invalid package name: {package:?}"));
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageUpdate {
+    pub name: String,
+    /// Not every manager reports the installed version here.
+    pub current_version: Option<String>,
+    pub new_version: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageOperationResult {
+    pub manager: PackageManager,
+    /// The command as run, including the privilege prefix.
+    pub command: String,
+    pub exit_code: u32,
+    pub success: bool,
+    pub stdout: String,
+    pub stderr: String,
+    pub duration_ms: u64,
+}
+
+/// `curl/jammy-updates 7.81.0-1ubuntu1.16 amd64 [upgradable from: 7.81.0-1ubuntu1.15]`
+fn parse_apt(output: &str) -> Vec<PackageUpdate> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let (name, _) = fields.next()?.split_once('/')?;
+            let new_version = fields.next()?.to_string();
+            let current_version = line
+                .split_once("[upgradable from: ")
+                .map(|(_, rest)| rest.trim_end_matches(']').trim().to_string());
+            Some(PackageUpdate {
+                name: name.to_string(),
+                current_version,
+                new_version,
+            })
+        })
+        .collect()
+}
+
+/// `openssl.x86_64   1:3.0.7-27.el9   baseos`; the section listing obsoleted
+/// packages that follows is skipped.
+fn parse_check_update(output: &str) -> Vec<PackageUpdate> {
+    output
+        .lines()
+        .take_while(|line| !line.starts_with("Obsoleting"))
+        .filter_map(
+            |line| match line.split_whitespace().collect::<Vec<_>>()[..] {
+                [name_arch, version, _repo] => {
+                    let (name, _arch) = name_arch.rsplit_once('.')?;
+                    Some(PackageUpdate {
+                        name: name.to_string(),
+                        current_version: None,
+                        new_version: version.to_string(),
+                    })
+                }
+                _ => None,
+            },
+        )
+        .collect()
+}
+
+/// `musl-1.2.4-r3` → (`musl`, `1.2.4-r3`): apk versions always end in `-rN`.
+pub(crate) fn split_apk_package(package: &str) -> Option<(&str, &str)> {
+    let (rest, release) = package.rsplit_once('-')?;
+    if !release.starts_with('r') {
+        return None;
+    }
+    let (name, _) = rest.rsplit_once('-')?;
+    Some((name, &package[name.len() + 1..]))
+}
+
+/// `musl-1.2.4-r3 x86_64 {musl} (MIT) [upgradable from: musl-1.2.4-r2]`
+fn parse_apk(output: &str) -> Vec<PackageUpdate> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (name, new_version) = split_apk_package(line.split_whitespace().next()?)?;
+            let current_version = line
+                .split_once("[upgradable from: ")
+                .map(|(_, rest)| rest.trim_end_matches(']').trim())
+                .and_then(split_apk_package)
+                .map(|(_, version)| version.to_string());
+            Some(PackageUpdate {
+                name: name.to_string(),
+                current_version,
+                new_version: new_version.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// `linux 6.6.1.arch1-1 -> 6.6.2.arch1-1`
+fn parse_pacman(output: &str) -> Vec<PackageUpdate> {
+    output
+        .lines()
+        .filter_map(
+            |line| match line.split_whitespace().collect::<Vec<_>>()[..] {
+                [name, current, "->", new] => Some(PackageUpdate {
+                    name: name.to_string(),
+                    current_version: Some(current.to_string()),
+                    new_version: new.to_string(),
+                }),
+                _ => None,
+            },
+        )
+        .collect()
+}
+
+pub fn parse_upgradable(manager: PackageManager, output: &str) -> Vec<PackageUpdate> {
+    let mut updates = match manager {
+        PackageManager::Apt => parse_apt(output),
+        PackageManager::Dnf | PackageManager::Yum => parse_check_update(output),
+        PackageManager::Apk => parse_apk(output),
+        PackageManager::Pacman => parse_pacman(output),
+    };
+    updates.sort_by(|a, b| a.name.cmp(&b.name));
+    updates.dedup_by(|a, b| a.name == b.name);
+    updates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_upgradable_lists() {
+        let apt = "Listing...\ncurl/jammy-updates 7.81.0-1ubuntu1.16 amd64 [upgradable from: 7.81.0-1ubuntu1.15]\n";
+        assert_eq!(
+            parse_upgradable(PackageManager::Apt, apt),
+            vec![PackageUpdate {
+                name: "curl".to_string(),
+                current_version: Some("7.81.0-1ubuntu1.15".to_string()),
+                new_version: "7.81.0-1ubuntu1.16".to_string(),
+            }]
+        );
+
+        let dnf = "\nopenssl.x86_64    1:3.0.7-27.el9    baseos\nkernel.x86_64    5.14.0-427.el9    baseos\nObsoleting Packages\ngrub2.noarch    1:2.06-80.el9    baseos\n";
+        let names: Vec<String> = parse_upgradable(PackageManager::Dnf, dnf)
+            .into_iter()
+            .map(|update| update.name)
+            .collect();
+        assert_eq!(names, vec!["kernel", "openssl"]);
+
+        let apk =
+            "py3-yaml-6.0.1-r1 x86_64 {py3-yaml} (MIT) [upgradable from: py3-yaml-6.0.1-r0]\n";
+        let update = &parse_upgradable(PackageManager::Apk, apk)[0];
+        assert_eq!(update.name, "py3-yaml");
+        assert_eq!(update.new_version, "6.0.1-r1");
+        assert_eq!(update.current_version.as_deref(), Some("6.0.1-r0"));
+
+        let pacman = "linux 6.6.1.arch1-1 -> 6.6.2.arch1-1\n";
+        assert_eq!(
+            parse_upgradable(PackageManager::Pacman, pacman)[0]
+                .current_version
+                .as_deref(),
+            Some("6.6.1.arch1-1")
+        );
+    }
+
+    #[test]
+    fn package_names_cannot_inject_shell() {
+        assert!(
+            validate_packages(&["nginx".to_string(), "libssl3=3.0.2-0ubuntu1".to_string()]).is_ok()
+        );
+        assert!(validate_packages(&[]).is_err());
+        assert!(validate_packages(&["--purge".to_string()]).is_err());
+        assert!(validate_packages(&["nginx; rm -rf /".to_string()]).is_err());
+        assert_eq!(
+            PackageManager::Apk.action_command(PackageAction::Remove, &["htop".to_string()]),
+            "apk del htop"
+        );
+    }
+}
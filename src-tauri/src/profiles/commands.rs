@@ -0,0 +1,44 @@
+use super::{resolve_profile, theme_registry, ConnectionProfile, ConnectionTheme};
+use crate::commands::AppState;
+use crate::safety::{enforce, SafetyAction};
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn profiles_list_themes(app: AppHandle) -> Result<Vec<ConnectionTheme>, String> {
+    Ok(theme_registry(&app))
+}
+
+#[tauri::command]
+pub async fn profiles_resolve(app: AppHandle, connection_id: String) -> Result<ConnectionProfile, String> {
+    Ok(resolve_profile(&app, &connection_id))
+}
+
+/// Resolve the command text for running a snippet on a connection.
+///
+/// Guarded connections run the snippet through the safety rules; see
+/// `crate::safety::enforce` for the error prefixes and how `confirmation` works.
+#[tauri::command]
+pub async fn profiles_check_snippet_run(
+    app: AppHandle,
+    snippet_id: String,
+    connection_id: String,
+    confirmation: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let snippet = state
+        .snippets_manager
+        .list()
+        .await?
+        .into_iter()
+        .find(|snippet| snippet.id == snippet_id)
+        .ok_or_else(|| "Snippet not found".to_string())?;
+
+    enforce(
+        &app,
+        &connection_id,
+        SafetyAction::Snippet,
+        &snippet.command,
+        confirmation.as_deref(),
+    )?;
+    Ok(snippet.command)
+}
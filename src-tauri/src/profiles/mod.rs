@@ -0,0 +1,198 @@
+//! Connection theme/profile registry.
+//!
+//! `SavedConnection.theme` stores a theme id. Built-in ids match the color labels
+//! offered in the connection form; `settings.json` can add or override entries:
+//!
+//! ```json
+//! "connectionThemes": [{ "id": "red", "label": "Prod", "color": "#ef4444", "badge": "PROD", "production": true }]
+//! ```
+//!
+//! A connection is production-guarded when its theme has `production: true` or it
+//! carries the `production` tag.
+
+pub mod commands;
+
+use crate::commands::{get_data_dir, read_effective_settings};
+use crate::types::{SavedConnection, SavedData};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+pub const PRODUCTION_TAG: &str = "production";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionTheme {
+    pub id: String,
+    pub label: String,
+    /// CSS color used for tab accents and badges.
+    pub color: String,
+    #[serde(default)]
+    pub badge: Option<String>,
+    /// Destructive actions on hosts using this theme require confirmation.
+    #[serde(default)]
+    pub production: bool,
+}
+
+/// Theme plus guard state, resolved for one connection.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionProfile {
+    pub connection_id: String,
+    pub theme: Option<ConnectionTheme>,
+    pub production: bool,
+}
+
+fn builtin(id: &str, label: &str, color: &str, badge: Option<&str>, production: bool) -> ConnectionTheme {
+    ConnectionTheme {
+        id: id.to_string(),
+        label: label.to_string(),
+        color: color.to_string(),
+        badge: badge.map(str::to_string),
+        production,
+    }
+}
+
+fn builtin_themes() -> Vec<ConnectionTheme> {
+    vec![
+        builtin("red", "Pro", "#ef4444", Some("PROD"), true),
+        builtin("blue", "Dev", "#3b82f6", Some("DEV"), false),
+        builtin("green", "Test", "#10b981", Some("TEST"), false),
+        builtin("orange", "Stg", "#f97316", Some("STG"), false),
+        builtin("purple", "App", "#a855f7", None, false),
+    ]
+}
+
+/// Built-ins overlaid with `connectionThemes` from settings (matched by id).
+pub fn theme_registry(app: &AppHandle) -> Vec<ConnectionTheme> {
+    let custom: Vec<ConnectionTheme> = read_effective_settings(app)
+        .ok()
+        .and_then(|settings| settings.get("connectionThemes").cloned())
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default();
+    merge_themes(builtin_themes(), custom)
+}
+
+fn merge_themes(mut themes: Vec<ConnectionTheme>, custom: Vec<ConnectionTheme>) -> Vec<ConnectionTheme> {
+    for theme in custom.into_iter().filter(|theme| !theme.id.trim().is_empty()) {
+        match themes.iter_mut().find(|existing| existing.id == theme.id) {
+            Some(existing) => *existing = theme,
+            None => themes.push(theme),
+        }
+    }
+    themes
+}
+
+pub(crate) fn load_saved_connection(app: &AppHandle, connection_id: &str) -> Option<SavedConnection> {
+    let path = get_data_dir(app).join("connections.json");
+    let data = std::fs::read_to_string(path).ok()?;
+    let saved: SavedData = serde_json::from_str(&data).ok()?;
+    saved
+        .connections
+        .into_iter()
+        .find(|connection| connection.id == connection_id)
+}
+
+fn profile_for(
+    connection_id: &str,
+    saved: Option<&SavedConnection>,
+    registry: &[ConnectionTheme],
+) -> ConnectionProfile {
+    let theme = saved
+        .and_then(|connection| connection.theme.as_deref())
+        .filter(|id| !id.is_empty())
+        .and_then(|id| registry.iter().find(|theme| theme.id == id).cloned());
+    let tagged = saved
+        .and_then(|connection| connection.tags.as_ref())
+        .is_some_and(|tags| tags.iter().any(|tag| tag.eq_ignore_ascii_case(PRODUCTION_TAG)));
+    ConnectionProfile {
+        connection_id: connection_id.to_string(),
+        production: tagged || theme.as_ref().is_some_and(|theme| theme.production),
+        theme,
+    }
+}
+
+pub fn resolve_profile(app: &AppHandle, connection_id: &str) -> ConnectionProfile {
+    let saved = load_saved_connection(app, connection_id);
+    profile_for(connection_id, saved.as_ref(), &theme_registry(app))
+}
+
+/// Commands that change or destroy state; broader than the AI hard-block list
+/// because they only trigger a confirmation, never a refusal.
+const DESTRUCTIVE_PATTERNS: &[&str] = &[
+    "rm -r",
+    "rm -f",
+    "reboot",
+    "shutdown",
+    "poweroff",
+    "halt",
+    "systemctl stop",
+    "systemctl restart",
+    "kill -9",
+    "killall",
+    "drop table",
+    "drop database",
+    "truncate table",
+    "git push --force",
+    "git push -f",
+    "git reset --hard",
+    "docker rm",
+    "docker system prune",
+    "kubectl delete",
+    "terraform destroy",
+    "iptables -f",
+];
+
+pub fn looks_destructive(command: &str) -> bool {
+    let normalized = command
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    crate::ai::tool_exec_support::is_dangerous_command(command)
+        || DESTRUCTIVE_PATTERNS
+            .iter()
+            .any(|pattern| normalized.contains(pattern))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn saved(theme: Option<&str>, tags: &[&str]) -> SavedConnection {
+        SavedConnection {
+            id: "c1".to_string(),
+            name: "db".to_string(),
+            host: "db.example.com".to_string(),
+            port: 22,
+            username: "root".to_string(),
+            theme: theme.map(str::to_string),
+            tags: Some(tags.iter().map(|tag| tag.to_string()).collect()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn red_theme_or_production_tag_enables_guard() {
+        let registry = builtin_themes();
+        assert!(profile_for("c1", Some(&saved(Some("red"), &[])), &registry).production);
+        assert!(profile_for("c1", Some(&saved(Some("blue"), &["Production"])), &registry).production);
+        assert!(!profile_for("c1", Some(&saved(Some("blue"), &[])), &registry).production);
+    }
+
+    #[test]
+    fn settings_override_builtin_by_id() {
+        let custom = vec![builtin("blue", "QA", "#0000ff", None, true)];
+        let merged = merge_themes(builtin_themes(), custom);
+        let blue = merged.iter().find(|theme| theme.id == "blue").expect("blue");
+        assert_eq!(blue.label, "QA");
+        assert!(blue.production);
+        assert_eq!(merged.len(), builtin_themes().len());
+    }
+
+    #[test]
+    fn destructive_detection_covers_common_admin_commands() {
+        assert!(looks_destructive("sudo  reboot"));
+        assert!(looks_destructive("psql -c 'DROP TABLE users'"));
+        assert!(!looks_destructive("ls -la /var/log"));
+    }
+}
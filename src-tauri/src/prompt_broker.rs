@@ -0,0 +1,156 @@
+//! Questions a connect has to wait on the user for, such as keyboard-interactive
+//! codes (`auth_prompts`) or an unrecognized host key (`known_hosts::prompt`).
+//!
+//! A [`PromptBroker`] emits the question under its event name with a fresh
+//! `promptId`, parks the asker on a oneshot until the matching `respond` call
+//! or its timeout, and always withdraws the question with its `-done` event,
+//! however the wait ended: answered, timed out, or the asker dropped because
+//! its connect was cancelled.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::oneshot;
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// Checks an answer before it is delivered; a rejected answer leaves the prompt waiting.
+pub type Validator<T> = Box<dyn Fn(&T) -> Result<(), String> + Send>;
+
+pub fn configure(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PromptDone {
+    prompt_id: String,
+}
+
+struct Pending<T> {
+    reply: oneshot::Sender<T>,
+    validate: Option<Validator<T>>,
+}
+
+pub struct PromptBroker<T> {
+    event: &'static str,
+    done_event: &'static str,
+    timeout: Duration,
+    pending: Mutex<HashMap<String, Pending<T>>>,
+}
+
+/// Forgets the prompt and withdraws it from the UI when the asker stops waiting.
+struct Outstanding<'a, T> {
+    broker: &'a PromptBroker<T>,
+    app: &'a AppHandle,
+    prompt_id: String,
+}
+
+impl<T> Drop for Outstanding<'_, T> {
+    fn drop(&mut self) {
+        self.broker.lock_pending().remove(&self.prompt_id);
+        let _ = self.app.emit(
+            self.broker.done_event,
+            PromptDone {
+                prompt_id: self.prompt_id.clone(),
+            },
+        );
+    }
+}
+
+impl<T> PromptBroker<T> {
+    /// Questions go out as `event` and are withdrawn with `done_event`.
+    pub fn new(event: &'static str, done_event: &'static str, timeout: Duration) -> Self {
+        Self {
+            event,
+            done_event,
+            timeout,
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn lock_pending(&self) -> MutexGuard<'_, HashMap<String, Pending<T>>> {
+        self.pending
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Wait for an answer to `prompt_id` without showing anything.
+    pub(crate) fn register(
+        &self,
+        prompt_id: &str,
+        validate: Option<Validator<T>>,
+    ) -> oneshot::Receiver<T> {
+        let (reply, answer) = oneshot::channel();
+        self.lock_pending()
+            .insert(prompt_id.to_string(), Pending { reply, validate });
+        answer
+    }
+
+    /// Show the question `payload` builds from the new prompt id and wait for
+    /// its answer. `None` when timed out or there is no window to ask in.
+    pub async fn ask<P: Serialize + Clone>(
+        &self,
+        payload: impl FnOnce(String) -> P,
+        validate: Option<Validator<T>>,
+    ) -> Option<T> {
+        let app = APP.get()?;
+        let prompt_id = uuid::Uuid::new_v4().to_string();
+        let answer = self.register(&prompt_id, validate);
+        let _outstanding = Outstanding {
+            broker: self,
+            app,
+            prompt_id: prompt_id.clone(),
+        };
+        let _ = app.emit(self.event, payload(prompt_id));
+        tokio::time::timeout(self.timeout, answer).await.ok()?.ok()
+    }
+
+    /// Deliver `answer` to the asker waiting on `prompt_id`, once.
+    pub fn respond(&self, prompt_id: &str, answer: T) -> Result<(), String> {
+        let mut pending = self.lock_pending();
+        let Some(waiting) = pending.get(prompt_id) else {
+            return Err("This prompt is no longer waiting for an answer".to_string());
+        };
+        if let Some(validate) = &waiting.validate {
+            validate(&answer)?;
+        }
+        if let Some(waiting) = pending.remove(prompt_id) {
+            let _ = waiting.reply.send(answer);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn broker() -> PromptBroker<u32> {
+        PromptBroker::new("test:prompt", "test:prompt-done", Duration::from_secs(1))
+    }
+
+    #[tokio::test]
+    async fn delivers_an_answer_once() {
+        let broker = broker();
+        let answer = broker.register("p", None);
+        broker.respond("p", 7).unwrap();
+        assert_eq!(answer.await.unwrap(), 7);
+        assert!(broker.respond("p", 8).is_err());
+    }
+
+    #[tokio::test]
+    async fn rejected_answers_leave_the_prompt_waiting() {
+        let broker = broker();
+        let even: Validator<u32> = Box::new(|answer| match answer % 2 {
+            0 => Ok(()),
+            _ => Err("odd".to_string()),
+        });
+        let answer = broker.register("p", Some(even));
+        assert_eq!(broker.respond("p", 3), Err("odd".to_string()));
+        broker.respond("p", 4).unwrap();
+        assert_eq!(answer.await.unwrap(), 4);
+    }
+}
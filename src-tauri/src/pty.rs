@@ -1,8 +1,12 @@
+use crate::pty_flow::{FlowGuard, Spool};
+use crate::remote_charset::{encode_input, OutputDecoder, TerminalCharset};
+use crate::session_channels::SessionChannelPermit;
+use crate::session_refs::{Consumer, SessionRef};
 use anyhow::{anyhow, Result};
 use portable_pty::{native_pty_system, ChildKiller, CommandBuilder, MasterPty, PtySize};
 use russh::client::Msg;
 use russh::{Channel, ChannelMsg};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::io::{Read, Write};
 use std::mem;
@@ -15,9 +19,48 @@ use tokio::sync::{mpsc, Mutex};
 use tokio::time::{Duration, Instant};
 
 /// Maximum time to hold PTY output before emitting a combined frontend event.
-const OUTPUT_BATCH_MS: u64 = 8;
-/// Flush buffered PTY output immediately once it reaches this many bytes.
-const OUTPUT_FLUSH_THRESHOLD: usize = 4096;
+/// One frame per display refresh; tunable as `terminalOutput.batchMs`.
+const DEFAULT_OUTPUT_BATCH_MS: u64 = 16;
+/// Flush buffered PTY output immediately once it reaches this many bytes, and
+/// never send a larger frame; tunable as `terminalOutput.maxFrameBytes`.
+const DEFAULT_OUTPUT_MAX_FRAME_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct OutputBatchConfig {
+    batch_ms: u64,
+    max_frame_bytes: usize,
+}
+
+impl Default for OutputBatchConfig {
+    fn default() -> Self {
+        Self {
+            batch_ms: DEFAULT_OUTPUT_BATCH_MS,
+            max_frame_bytes: DEFAULT_OUTPUT_MAX_FRAME_BYTES,
+        }
+    }
+}
+
+impl OutputBatchConfig {
+    /// Read once per session; new terminals pick up changes.
+    fn read(app: &AppHandle) -> Self {
+        let settings = crate::commands::read_effective_settings(app).ok();
+        Self::from_settings(settings.as_ref().and_then(|s| s.get("terminalOutput")))
+    }
+
+    /// `terminalOutput` as configured, clamped to sane limits; defaults when
+    /// missing or malformed.
+    fn from_settings(terminal_output: Option<&serde_json::Value>) -> Self {
+        let config: Self = terminal_output
+            .cloned()
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default();
+        Self {
+            batch_ms: config.batch_ms.clamp(1, 100),
+            max_frame_bytes: config.max_frame_bytes.clamp(4 * 1024, 1024 * 1024),
+        }
+    }
+}
 
 enum LocalReaderEvent {
     Data(Vec<u8>),
@@ -39,12 +82,6 @@ fn remote_shell_login_flag(shell_override: &str) -> Option<&'static str> {
     }
 }
 
-fn is_remote_windows(remote_os: Option<&str>) -> bool {
-    remote_os
-        .map(|os| os.eq_ignore_ascii_case("windows"))
-        .unwrap_or(false)
-}
-
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum ShellKind {
     Cmd,
@@ -240,27 +277,102 @@ struct TerminalLifecycleEvent {
     exit_code: Option<u32>,
 }
 
-/// Flushes buffered PTY output through the streaming IPC channel.
-///
-/// Frames are `generation` (u32 LE) + raw PTY bytes so the frontend can ignore
-/// stale chunks after suspend/restart races.
-fn flush_pending_output(
-    output_channel: &IpcChannel,
+/// Where a session's output goes: the streaming IPC channel, with batching
+/// limits and flow accounting for one terminal generation.
+struct OutputSink {
+    channel: IpcChannel,
+    flow: FlowGuard,
     generation: u32,
-    pending_output: &mut Vec<u8>,
-) {
-    if pending_output.is_empty() {
-        return;
+    batch_interval: Duration,
+    max_frame_bytes: usize,
+}
+
+impl OutputSink {
+    fn new(app_handle: &AppHandle, channel: IpcChannel, term_id: &str, generation: u32) -> Self {
+        let config = OutputBatchConfig::read(app_handle);
+        Self {
+            channel,
+            flow: crate::pty_flow::register(term_id, generation),
+            generation,
+            batch_interval: Duration::from_millis(config.batch_ms),
+            max_frame_bytes: config.max_frame_bytes,
+        }
+    }
+
+    /// Flushes buffered PTY output through the streaming IPC channel.
+    ///
+    /// Frames are `generation` (u32 LE) + raw PTY bytes so the frontend can ignore
+    /// stale chunks after suspend/restart races.
+    fn flush(&self, pending_output: &mut Vec<u8>) {
+        if pending_output.is_empty() {
+            return;
+        }
+
+        let output = mem::take(pending_output);
+        self.flow.sent(output.len());
+        for frame in output_frames(self.generation, &output, self.max_frame_bytes) {
+            if let Err(e) = self.channel.send(InvokeResponseBody::Raw(frame)) {
+                eprintln!("[PTY] Failed to send output on channel: {}", e);
+            }
+        }
+    }
+
+    /// Send spooled output while the flow window has room.
+    fn replay(&self, spool: &mut Spool) {
+        while !self.flow.is_saturated() {
+            let mut chunk = spool.take_chunk(self.max_frame_bytes);
+            if chunk.is_empty() {
+                break;
+            }
+            self.flush(&mut chunk);
+        }
     }
 
-    let output = mem::take(pending_output);
-    let mut frame = Vec::with_capacity(4 + output.len());
-    frame.extend_from_slice(&generation.to_le_bytes());
-    frame.extend_from_slice(&output);
+    /// Deliver everything still spooled before the session reports its exit.
+    async fn drain(&self, spool: &mut Spool) {
+        loop {
+            self.replay(spool);
+            if spool.is_empty() {
+                return;
+            }
+            self.flow.wait_for_ack().await;
+        }
+    }
+}
+
+/// `output` split into frames of at most `max_frame_bytes` PTY bytes, each
+/// prefixed with `generation` (u32 LE).
+fn output_frames(
+    generation: u32,
+    output: &[u8],
+    max_frame_bytes: usize,
+) -> impl Iterator<Item = Vec<u8>> + '_ {
+    output.chunks(max_frame_bytes).map(move |chunk| {
+        let mut frame = Vec::with_capacity(4 + chunk.len());
+        frame.extend_from_slice(&generation.to_le_bytes());
+        frame.extend_from_slice(chunk);
+        frame
+    })
+}
 
-    if let Err(e) = output_channel.send(InvokeResponseBody::Raw(frame)) {
-        eprintln!("[PTY] Failed to send output on channel: {}", e);
+/// Tell the frontend output is buffering to disk (`spooled_bytes` set) or has caught up (`None`).
+fn emit_output_throttled(app_handle: &AppHandle, term_id: &str, generation: u32, spooled_bytes: Option<u64>) {
+    #[derive(Clone, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct OutputThrottledEvent {
+        generation: u32,
+        active: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        spooled_bytes: Option<u64>,
     }
+    let _ = app_handle.emit(
+        &format!("terminal-output-throttled-{}", term_id),
+        OutputThrottledEvent {
+            generation,
+            active: spooled_bytes.is_some(),
+            spooled_bytes,
+        },
+    );
 }
 
 fn process_tree_has_children(root_pid: u32) -> bool {
@@ -283,7 +395,8 @@ fn emit_terminal_exit(app_handle: &AppHandle, term_id: &str, generation: u32, ex
     }
 }
 
-fn emit_connection_transport_lost(app_handle: &AppHandle, connection_id: &str) {
+pub(crate) fn emit_connection_transport_lost(app_handle: &AppHandle, connection_id: &str) {
+    crate::events::publish(connection_id, crate::events::ConnectionEvent::TransportLost);
     if let Err(e) = app_handle.emit(
         "connection:transport-lost",
         serde_json::json!({ "connectionId": connection_id }),
@@ -310,6 +423,8 @@ pub enum TerminalHandle {
         tx: mpsc::Sender<Vec<u8>>,           // Send input data to the channel task
         resize_tx: mpsc::Sender<(u16, u16)>, // Send resize events
         task_handle: Option<tokio::task::JoinHandle<()>>,
+        /// Input is encoded to this before sending; `None` for UTF-8.
+        encoding: Option<&'static encoding_rs::Encoding>,
     },
 }
 
@@ -320,6 +435,9 @@ pub struct PtySession {
     pub output_channel: IpcChannel,
     pub handle: TerminalHandle,
     navigate_shell: NavigateShellStyle,
+    /// Keeps the SSH session in use while the terminal is open; `None` locally.
+    #[allow(dead_code)]
+    session_ref: Option<SessionRef>,
 }
 
 pub struct PtyManager {
@@ -555,6 +673,7 @@ impl PtyManager {
                 child_pid,
             },
             navigate_shell,
+            session_ref: None,
         };
 
         let mut sessions = self.sessions.lock().await;
@@ -566,7 +685,6 @@ impl PtyManager {
         // avoids orphaning the reader if close() races immediately after insert.
         let term_id_clone = term_id.clone();
         let app_handle_clone = app_handle.clone();
-        let output_channel_clone = output_channel.clone();
         let (reader_start_tx, reader_start_rx) = std_mpsc::channel::<()>();
         let (output_tx, mut output_rx) = tokio::sync::mpsc::channel::<LocalReaderEvent>(64);
         let output_tx_for_wait = output_tx.clone();
@@ -607,26 +725,30 @@ impl PtyManager {
         let sessions_for_exit = self.sessions.clone();
         let term_id_for_exit = term_id.clone();
 
+        let sink = OutputSink::new(&app_handle, output_channel.clone(), &term_id, generation);
         let reader_handle = tokio::spawn(async move {
             let mut pending_output = Vec::new();
             let mut flush_deadline: Option<Instant> = None;
+            // While paused the reader is not drained, so the child blocks on a full PTY.
+            let mut paused = false;
 
             loop {
                 tokio::select! {
-                    event = output_rx.recv() => {
+                    event = output_rx.recv(), if !paused => {
                         match event {
                             Some(LocalReaderEvent::Data(chunk)) => {
                                 pending_output.extend_from_slice(&chunk);
 
-                                if pending_output.len() >= OUTPUT_FLUSH_THRESHOLD {
-                                    flush_pending_output(&output_channel_clone, generation, &mut pending_output);
+                                if pending_output.len() >= sink.max_frame_bytes {
+                                    sink.flush(&mut pending_output);
                                     flush_deadline = None;
+                                    paused = sink.flow.is_saturated();
                                 } else if flush_deadline.is_none() {
-                                    flush_deadline = Some(Instant::now() + Duration::from_millis(OUTPUT_BATCH_MS));
+                                    flush_deadline = Some(Instant::now() + sink.batch_interval);
                                 }
                             }
                             Some(LocalReaderEvent::Finished { exit_code }) => {
-                                flush_pending_output(&output_channel_clone, generation, &mut pending_output);
+                                sink.flush(&mut pending_output);
                                 if !exit_emitted_clone.swap(true, Ordering::SeqCst) {
                                     emit_terminal_exit(
                                         &app_handle_clone,
@@ -656,8 +778,13 @@ impl PtyManager {
                             tokio::time::sleep_until(deadline).await;
                         }
                     }, if flush_deadline.is_some() => {
-                        flush_pending_output(&output_channel_clone, generation, &mut pending_output);
+                        sink.flush(&mut pending_output);
                         flush_deadline = None;
+                        paused = sink.flow.is_saturated();
+                    }
+
+                    _ = sink.flow.wait_for_ack(), if paused => {
+                        paused = !sink.flow.can_resume();
                     }
                 }
             }
@@ -692,13 +819,16 @@ impl PtyManager {
         connection_id: String,
         generation: u32,
         mut channel: Channel<Msg>,
+        channel_permit: SessionChannelPermit,
         cols: u16,
         rows: u16,
         app_handle: AppHandle,
         output_channel: IpcChannel,
         shell_override: Option<String>,
         remote_os: Option<String>,
+        default_shell: Option<String>,
         cwd: Option<String>,
+        charset: TerminalCharset,
     ) -> Result<()> {
         // Clean up any existing dead/stale session with this ID before creating a new one
         let _ = self.close(&term_id).await;
@@ -717,11 +847,23 @@ impl PtyManager {
             .await
             .map_err(|e| anyhow!("Failed to request PTY: {}", e))?;
 
-        let remote_is_windows = is_remote_windows(remote_os.as_deref());
+        let remote_is_windows = crate::remote_os::is_windows(remote_os.as_deref());
+        if !remote_is_windows {
+            // Must precede the shell request; sshd drops names its AcceptEnv doesn't list.
+            for (name, value) in charset.environment() {
+                if let Err(e) = channel.set_env(false, name, value).await {
+                    eprintln!("[PTY] Failed to request {}: {}", name, e);
+                }
+            }
+        }
         let selected_shell = shell_override
             .as_deref()
             .map(str::trim)
             .filter(|s| !s.is_empty() && !s.eq_ignore_ascii_case("default"));
+        // Without an explicit pick, `cd` and navigation on Windows follow the
+        // host's OpenSSH default shell (detected at connect time).
+        let windows_shell = selected_shell
+            .or(default_shell.as_deref().filter(|_| remote_is_windows));
 
         if let Some(shell) = selected_shell {
             // Start explicit remote shell (path or command name) when user selected one.
@@ -755,7 +897,7 @@ impl PtyManager {
         // If cwd is provided, send a cd command immediately.
         if let Some(path) = cwd {
             let cd_cmd = if remote_is_windows {
-                match selected_shell.map(classify_windows_shell).unwrap_or(ShellKind::Other) {
+                match windows_shell.map(classify_windows_shell).unwrap_or(ShellKind::Other) {
                     ShellKind::Cmd => {
                         format!("cd /d \"{}\" && cls\r", windows_double_quote(&path, false))
                     }
@@ -780,8 +922,12 @@ impl PtyManager {
                     format!("cd {} && clear\r", posix_shell_cd_path(trimmed))
                 }
             };
+            let cd_bytes = match charset.encoding {
+                Some(encoding) => encode_input(encoding, &cd_cmd),
+                None => cd_cmd.into_bytes(),
+            };
             channel
-                .data(cd_cmd.as_bytes())
+                .data(&cd_bytes[..])
                 .await
                 .map_err(|e| anyhow!("Failed to send initial cd command: {}", e))?;
         }
@@ -792,9 +938,10 @@ impl PtyManager {
 
         let navigate_shell = remote_navigate_shell_style(
             remote_is_windows,
-            selected_shell,
+            windows_shell,
         );
         let connection_id_for_transport = connection_id.clone();
+        let session_ref = SessionRef::acquire(&connection_id, Consumer::Terminal);
         let session = PtySession {
             connection_id,
             output_channel: output_channel.clone(),
@@ -802,8 +949,10 @@ impl PtyManager {
                 tx,
                 resize_tx,
                 task_handle: None,
+                encoding: charset.encoding,
             },
             navigate_shell,
+            session_ref: Some(session_ref),
         };
 
         let mut sessions = self.sessions.lock().await;
@@ -821,33 +970,59 @@ impl PtyManager {
 
         let term_id_clone = term_id.clone();
         let app_handle_clone = app_handle.clone();
-        let output_channel_clone = output_channel.clone();
         let sessions_for_exit = self.sessions.clone();
         let term_id_for_exit = term_id.clone();
 
         // Spawn the manager task only after ready has been published so same-generation
         // output/exit events can never arrive before the frontend has seen ready.
+        let sink = OutputSink::new(&app_handle, output_channel.clone(), &term_id, generation);
         let task_handle = tokio::task::spawn(async move {
             let app_handle = app_handle_clone;
+            // Hold the MaxSessions slot for as long as the shell channel lives.
+            let _channel_permit = channel_permit;
             let mut pending_output = Vec::new();
             let mut flush_deadline: Option<Instant> = None;
+            // The SSH transport keeps delivering while paused, so output is
+            // spooled and replayed as the frontend catches up.
+            let mut spool = Spool::new();
+            let mut paused = false;
+            let mut throttle_announced = false;
+            let mut decoder = charset.encoding.map(OutputDecoder::new);
 
             loop {
                 tokio::select! {
                     msg = channel.wait() => {
                         match msg {
                             Some(ChannelMsg::Data { ref data }) => {
-                                pending_output.extend_from_slice(data.as_ref());
+                                let mut decoded = Vec::new();
+                                let data: &[u8] = match decoder.as_mut() {
+                                    Some(decoder) => {
+                                        decoder.decode_into(data, &mut decoded);
+                                        &decoded
+                                    }
+                                    None => data.as_ref(),
+                                };
+                                if paused {
+                                    spool.push(data);
+                                    if spool.on_disk() && !throttle_announced {
+                                        throttle_announced = true;
+                                        emit_output_throttled(&app_handle, &term_id_clone, generation, Some(spool.len()));
+                                    }
+                                    continue;
+                                }
+                                pending_output.extend_from_slice(data);
 
-                                if pending_output.len() >= OUTPUT_FLUSH_THRESHOLD {
-                                    flush_pending_output(&output_channel_clone, generation, &mut pending_output);
+                                if pending_output.len() >= sink.max_frame_bytes {
+                                    sink.flush(&mut pending_output);
                                     flush_deadline = None;
+                                    paused = sink.flow.is_saturated();
                                 } else if flush_deadline.is_none() {
-                                    flush_deadline = Some(Instant::now() + Duration::from_millis(OUTPUT_BATCH_MS));
+                                    flush_deadline = Some(Instant::now() + sink.batch_interval);
                                 }
                             }
                             Some(ChannelMsg::ExitStatus { exit_status }) => {
-                                flush_pending_output(&output_channel_clone, generation, &mut pending_output);
+                                sink.flush(&mut pending_output);
+                                sink.drain(&mut spool).await;
                                 emit_terminal_exit(
                                     &app_handle,
                                     &term_id_clone,
@@ -857,13 +1032,15 @@ impl PtyManager {
                                 break;
                             }
                             Some(ChannelMsg::Eof) => {
-                                flush_pending_output(&output_channel_clone, generation, &mut pending_output);
+                                sink.flush(&mut pending_output);
+                                sink.drain(&mut spool).await;
                                 emit_connection_transport_lost(&app_handle, &connection_id_for_transport);
                                 emit_terminal_exit(&app_handle, &term_id_clone, generation, None);
                                 break;
                             }
                             None => {
-                                flush_pending_output(&output_channel_clone, generation, &mut pending_output);
+                                sink.flush(&mut pending_output);
+                                sink.drain(&mut spool).await;
                                 emit_connection_transport_lost(&app_handle, &connection_id_for_transport);
                                 emit_terminal_exit(&app_handle, &term_id_clone, generation, None);
                                 break;
@@ -877,8 +1054,20 @@ impl PtyManager {
                             tokio::time::sleep_until(deadline).await;
                         }
                     }, if flush_deadline.is_some() => {
-                        flush_pending_output(&output_channel_clone, generation, &mut pending_output);
+                        sink.flush(&mut pending_output);
                         flush_deadline = None;
+                        paused = sink.flow.is_saturated();
+                    }
+
+                    _ = sink.flow.wait_for_ack(), if paused => {
+                        if sink.flow.can_resume() {
+                            sink.replay(&mut spool);
+                            paused = !spool.is_empty();
+                            if !paused && throttle_announced {
+                                throttle_announced = false;
+                                emit_output_throttled(&app_handle, &term_id_clone, generation, None);
+                            }
+                        }
                     }
 
                     Some(input) = rx.recv() => {
@@ -901,7 +1090,7 @@ impl PtyManager {
                 }
             }
 
-            flush_pending_output(&output_channel_clone, generation, &mut pending_output);
+            sink.flush(&mut pending_output);
             let _ = channel.close().await;
 
             let mut sessions = sessions_for_exit.lock().await;
@@ -939,7 +1128,10 @@ impl PtyManager {
             
             match &session.handle {
                 TerminalHandle::Local { writer, .. } => (Some(writer.clone()), None),
-                TerminalHandle::Remote { tx, .. } => (None, Some(tx.clone())),
+                TerminalHandle::Remote { tx, encoding, .. } => {
+                    crate::time_tracking::record_activity(&session.connection_id);
+                    (None, Some((tx.clone(), *encoding)))
+                }
             }
         }; // sessions lock is dropped here
 
@@ -951,9 +1143,13 @@ impl PtyManager {
             writer
                 .flush()
                 .map_err(|e| anyhow!("Failed to flush PTY: {}", e))?;
-        } else if let Some(tx) = remote_tx_opt {
+        } else if let Some((tx, encoding)) = remote_tx_opt {
+            let bytes = match encoding {
+                Some(encoding) => encode_input(encoding, data),
+                None => data.as_bytes().to_vec(),
+            };
             // Send data to the manager task
-            tx.send(data.as_bytes().to_vec())
+            tx.send(bytes)
                 .await
                 .map_err(|e| anyhow!("Failed to send input to SSH task: {}", e))?;
         }
@@ -1049,7 +1245,70 @@ impl PtyManager {
 
 #[cfg(test)]
 mod tests {
-    use super::{build_navigate_cd_command, posix_shell_cd_path, NavigateShellStyle};
+    use super::{
+        build_navigate_cd_command, output_frames, posix_shell_cd_path, NavigateShellStyle,
+        OutputBatchConfig, DEFAULT_OUTPUT_BATCH_MS, DEFAULT_OUTPUT_MAX_FRAME_BYTES,
+    };
+    use serde_json::json;
+
+    fn batch_config(terminal_output: serde_json::Value) -> OutputBatchConfig {
+        OutputBatchConfig::from_settings(Some(&terminal_output))
+    }
+
+    #[test]
+    fn output_batch_config_defaults_when_unset_or_malformed() {
+        for config in [
+            OutputBatchConfig::from_settings(None),
+            batch_config(json!({})),
+            batch_config(json!({ "batchMs": "fast" })),
+        ] {
+            assert_eq!(config.batch_ms, DEFAULT_OUTPUT_BATCH_MS);
+            assert_eq!(config.max_frame_bytes, DEFAULT_OUTPUT_MAX_FRAME_BYTES);
+        }
+    }
+
+    #[test]
+    fn output_batch_config_clamps_batch_ms_to_1_through_100() {
+        let batch_ms = |value: u64| batch_config(json!({ "batchMs": value })).batch_ms;
+        assert_eq!(batch_ms(0), 1);
+        assert_eq!(batch_ms(1), 1);
+        assert_eq!(batch_ms(100), 100);
+        assert_eq!(batch_ms(101), 100);
+    }
+
+    #[test]
+    fn output_batch_config_clamps_max_frame_bytes_to_4k_through_1m() {
+        let max_frame_bytes =
+            |value: usize| batch_config(json!({ "maxFrameBytes": value })).max_frame_bytes;
+        assert_eq!(max_frame_bytes(0), 4 * 1024);
+        assert_eq!(max_frame_bytes(4 * 1024 - 1), 4 * 1024);
+        assert_eq!(max_frame_bytes(4 * 1024), 4 * 1024);
+        assert_eq!(max_frame_bytes(1024 * 1024), 1024 * 1024);
+        assert_eq!(max_frame_bytes(1024 * 1024 + 1), 1024 * 1024);
+    }
+
+    #[test]
+    fn output_frames_split_at_the_default_64k_limit() {
+        let limit = DEFAULT_OUTPUT_MAX_FRAME_BYTES;
+        assert_eq!(limit, 64 * 1024);
+        let output: Vec<u8> = (0..2 * limit + 1).map(|i| i as u8).collect();
+        let frames: Vec<Vec<u8>> = output_frames(7, &output, limit).collect();
+        let lengths: Vec<usize> = frames.iter().map(Vec::len).collect();
+        assert_eq!(lengths, vec![4 + limit, 4 + limit, 4 + 1]);
+        for frame in &frames {
+            assert_eq!(frame[..4], 7u32.to_le_bytes());
+        }
+        let rejoined: Vec<u8> = frames.iter().flat_map(|frame| frame[4..].to_vec()).collect();
+        assert_eq!(rejoined, output);
+    }
+
+    #[test]
+    fn output_frames_keep_exactly_64k_in_one_frame() {
+        let output = vec![b'x'; 64 * 1024];
+        let frames: Vec<Vec<u8>> = output_frames(1, &output, 64 * 1024).collect();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].len(), 4 + 64 * 1024);
+    }
 
     #[test]
     fn build_navigate_cd_command_uses_cmd_syntax_for_windows_cmd() {
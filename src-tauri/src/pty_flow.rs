@@ -0,0 +1,319 @@
+//! Flow control for PTY output streamed to the frontend.
+//!
+//! Every byte sent over a terminal's output channel is "in flight" until the
+//! frontend acknowledges that xterm has parsed it (`terminal_ack`). Once too
+//! much is in flight the session pauses:
+//!
+//! - local PTYs stop reading, so the kernel buffer fills and the child blocks;
+//! - remote channels keep draining the SSH transport into a [`Spool`] that
+//!   spills to a temp file, and replay it as acknowledgements come back.
+//!
+//! This bounds memory on both sides of the IPC bridge for `yes`, a huge `cat`
+//! and similar floods, at the cost of output arriving late instead of early.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock, Mutex as StdMutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// Pause once this many bytes are sent but not yet acknowledged.
+const HIGH_WATER: usize = 2 * 1024 * 1024;
+/// Resume once acknowledgements bring in-flight bytes down to this.
+const LOW_WATER: usize = 512 * 1024;
+/// Without any ack for this long, assume the frontend lost track (reload,
+/// hidden webview) and reopen the window rather than stall forever.
+const ACK_STALL_RESET: Duration = Duration::from_secs(10);
+/// Spool bytes kept in memory before spilling to disk.
+const SPOOL_MEMORY_LIMIT: usize = 1024 * 1024;
+/// Disk spool cap; output past it is dropped and reported inline.
+const SPOOL_DISK_LIMIT: u64 = 256 * 1024 * 1024;
+
+static FLOWS: LazyLock<StdMutex<HashMap<String, Arc<OutputFlow>>>> =
+    LazyLock::new(|| StdMutex::new(HashMap::new()));
+
+fn lock_flows() -> std::sync::MutexGuard<'static, HashMap<String, Arc<OutputFlow>>> {
+    FLOWS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[derive(Debug)]
+pub struct OutputFlow {
+    generation: u32,
+    in_flight: AtomicUsize,
+    acked: Notify,
+}
+
+impl OutputFlow {
+    pub fn sent(&self, bytes: usize) {
+        self.in_flight.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    fn ack(&self, bytes: usize) {
+        let _ = self
+            .in_flight
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+                Some(current.saturating_sub(bytes))
+            });
+        self.acked.notify_one();
+    }
+
+    pub fn is_saturated(&self) -> bool {
+        self.in_flight.load(Ordering::Relaxed) >= HIGH_WATER
+    }
+
+    pub fn can_resume(&self) -> bool {
+        self.in_flight.load(Ordering::Relaxed) <= LOW_WATER
+    }
+
+    /// Wait for the next acknowledgement, resetting the window if none arrives.
+    pub async fn wait_for_ack(&self) {
+        if tokio::time::timeout(ACK_STALL_RESET, self.acked.notified())
+            .await
+            .is_err()
+        {
+            eprintln!(
+                "[PTY] No output ack for {}s; resuming output",
+                ACK_STALL_RESET.as_secs()
+            );
+            self.in_flight.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Keeps a terminal generation's flow registered; unregisters on drop, so an
+/// aborted output task cleans up after itself.
+#[derive(Debug)]
+pub struct FlowGuard {
+    term_id: String,
+    flow: Arc<OutputFlow>,
+}
+
+impl std::ops::Deref for FlowGuard {
+    type Target = OutputFlow;
+
+    fn deref(&self) -> &OutputFlow {
+        &self.flow
+    }
+}
+
+impl Drop for FlowGuard {
+    fn drop(&mut self) {
+        let mut flows = lock_flows();
+        if flows
+            .get(&self.term_id)
+            .is_some_and(|flow| Arc::ptr_eq(flow, &self.flow))
+        {
+            flows.remove(&self.term_id);
+        }
+    }
+}
+
+/// Start flow accounting for a terminal generation, replacing any older one.
+pub fn register(term_id: &str, generation: u32) -> FlowGuard {
+    let flow = Arc::new(OutputFlow {
+        generation,
+        in_flight: AtomicUsize::new(0),
+        acked: Notify::new(),
+    });
+    lock_flows().insert(term_id.to_string(), flow.clone());
+    FlowGuard {
+        term_id: term_id.to_string(),
+        flow,
+    }
+}
+
+/// Record that the frontend consumed `bytes` of output. Stale generations are ignored.
+pub fn ack(term_id: &str, generation: u32, bytes: usize) {
+    let flow = lock_flows().get(term_id).cloned();
+    if let Some(flow) = flow.filter(|flow| flow.generation == generation) {
+        flow.ack(bytes);
+    }
+}
+
+/// FIFO of held-back output: memory first, then an append-only temp file.
+#[derive(Debug, Default)]
+pub struct Spool {
+    path: PathBuf,
+    memory: Vec<u8>,
+    file: Option<File>,
+    read_pos: u64,
+    write_pos: u64,
+    dropped: u64,
+}
+
+impl Spool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.memory.is_empty() && self.file.is_none() && self.dropped == 0
+    }
+
+    pub fn on_disk(&self) -> bool {
+        self.file.is_some()
+    }
+
+    pub fn len(&self) -> u64 {
+        self.memory.len() as u64 + (self.write_pos - self.read_pos)
+    }
+
+    pub fn push(&mut self, data: &[u8]) {
+        // Once spilled, everything goes to the file to keep ordering simple.
+        if self.file.is_none() && self.memory.len() + data.len() <= SPOOL_MEMORY_LIMIT {
+            self.memory.extend_from_slice(data);
+            return;
+        }
+        if self.write_pos + data.len() as u64 > SPOOL_DISK_LIMIT {
+            self.dropped += data.len() as u64;
+            return;
+        }
+        if let Err(error) = self.append_to_file(data) {
+            eprintln!("[PTY] Output spool write failed, dropping output: {error}");
+            self.dropped += data.len() as u64;
+        }
+    }
+
+    fn append_to_file(&mut self, data: &[u8]) -> std::io::Result<()> {
+        if self.file.is_none() {
+            // Terminal output can hold secrets: a fresh unguessable name that
+            // must not exist yet (no following a planted symlink), owner-only.
+            self.path = std::env::temp_dir().join(format!(
+                "zync-pty-{}.spool",
+                uuid::Uuid::new_v4().simple()
+            ));
+            let mut options = std::fs::OpenOptions::new();
+            options.create_new(true).read(true).write(true);
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::OpenOptionsExt;
+                options.mode(0o600);
+            }
+            self.file = Some(options.open(&self.path)?);
+        }
+        let Some(file) = self.file.as_mut() else {
+            return Ok(());
+        };
+        file.seek(SeekFrom::Start(self.write_pos))?;
+        file.write_all(data)?;
+        self.write_pos += data.len() as u64;
+        Ok(())
+    }
+
+    /// Next chunk to replay, oldest first, at most `max` bytes.
+    pub fn take_chunk(&mut self, max: usize) -> Vec<u8> {
+        if !self.memory.is_empty() {
+            let len = self.memory.len().min(max);
+            return self.memory.drain(..len).collect();
+        }
+        if self.file.is_some() {
+            let chunk = self.read_from_file(max).unwrap_or_else(|error| {
+                eprintln!("[PTY] Output spool read failed: {error}");
+                self.dropped += self.write_pos - self.read_pos;
+                self.read_pos = self.write_pos;
+                Vec::new()
+            });
+            if self.read_pos >= self.write_pos {
+                self.discard_file();
+            }
+            if !chunk.is_empty() {
+                return chunk;
+            }
+        }
+        if self.dropped > 0 {
+            let notice = format!(
+                "\r\n\x1b[33m[zync] {} bytes of output were dropped because it arrived faster than it could be displayed\x1b[0m\r\n",
+                self.dropped
+            );
+            self.dropped = 0;
+            return notice.into_bytes();
+        }
+        Vec::new()
+    }
+
+    fn read_from_file(&mut self, max: usize) -> std::io::Result<Vec<u8>> {
+        let Some(file) = self.file.as_mut() else {
+            return Ok(Vec::new());
+        };
+        let len = ((self.write_pos - self.read_pos) as usize).min(max);
+        let mut chunk = vec![0u8; len];
+        file.seek(SeekFrom::Start(self.read_pos))?;
+        file.read_exact(&mut chunk)?;
+        self.read_pos += len as u64;
+        Ok(chunk)
+    }
+
+    fn discard_file(&mut self) {
+        if self.file.take().is_some() {
+            let _ = std::fs::remove_file(&self.path);
+        }
+        self.read_pos = 0;
+        self.write_pos = 0;
+    }
+}
+
+impl Drop for Spool {
+    fn drop(&mut self) {
+        self.discard_file();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spool_replays_memory_then_disk_in_order() {
+        let mut spool = Spool::new();
+        let first = vec![b'a'; SPOOL_MEMORY_LIMIT];
+        spool.push(&first);
+        spool.push(b"bcd");
+        assert!(spool.on_disk());
+        assert_eq!(spool.len(), SPOOL_MEMORY_LIMIT as u64 + 3);
+
+        let mut replayed = Vec::new();
+        loop {
+            let chunk = spool.take_chunk(64 * 1024);
+            if chunk.is_empty() {
+                break;
+            }
+            replayed.extend(chunk);
+        }
+        assert_eq!(replayed.len(), SPOOL_MEMORY_LIMIT + 3);
+        assert!(replayed.ends_with(b"abcd"));
+        assert!(spool.is_empty());
+        assert!(!spool.path.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn spool_files_are_private_and_never_reused() {
+        use std::os::unix::fs::PermissionsExt;
+        let mut spool = Spool::new();
+        spool.push(&vec![b'a'; SPOOL_MEMORY_LIMIT + 1]);
+        let first = spool.path.clone();
+        let mode = std::fs::metadata(&first).expect("spool file").permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        while !spool.take_chunk(usize::MAX).is_empty() {}
+        assert!(!first.exists());
+        spool.push(&vec![b'b'; SPOOL_MEMORY_LIMIT + 1]);
+        assert_ne!(spool.path, first);
+    }
+
+    #[test]
+    fn acks_reopen_the_window_for_the_current_generation_only() {
+        let flow = register("flow-test", 2);
+        flow.sent(HIGH_WATER);
+        assert!(flow.is_saturated());
+        ack("flow-test", 1, HIGH_WATER);
+        assert!(flow.is_saturated());
+        ack("flow-test", 2, HIGH_WATER - LOW_WATER);
+        assert!(!flow.is_saturated() && flow.can_resume());
+        drop(flow);
+        assert!(lock_flows().get("flow-test").is_none());
+    }
+}
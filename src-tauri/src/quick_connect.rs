@@ -0,0 +1,344 @@
+//! Quick connect: turn `user@host:port`, `ssh://` URLs, bare IPs, `ssh ...`
+//! command lines or ssh-config aliases into an ephemeral `ConnectionConfig`.
+
+use crate::ssh_config::ParsedSshConnection;
+use crate::types::{AuthMethod, ConnectionConfig};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickTarget {
+    pub username: Option<String>,
+    pub host: String,
+    pub port: Option<u16>,
+    /// `-i` from an `ssh ...` command line.
+    pub identity_file: Option<String>,
+}
+
+fn parse_port(value: &str) -> Result<u16, String> {
+    value
+        .parse::<u16>()
+        .ok()
+        .filter(|port| *port > 0)
+        .ok_or_else(|| format!("Invalid port: {value}"))
+}
+
+/// Split `host[:port]`, handling `[v6]:port` and bare IPv6 literals.
+fn split_host_port(value: &str) -> Result<(String, Option<u16>), String> {
+    if let Some(rest) = value.strip_prefix('[') {
+        let (host, tail) = rest
+            .split_once(']')
+            .ok_or_else(|| format!("Unclosed '[' in {value}"))?;
+        let port = match tail.strip_prefix(':') {
+            Some(port) => Some(parse_port(port)?),
+            None if tail.is_empty() => None,
+            None => return Err(format!("Unexpected text after ']' in {value}")),
+        };
+        return Ok((host.to_string(), port));
+    }
+    match value.matches(':').count() {
+        0 => Ok((value.to_string(), None)),
+        1 => {
+            let (host, port) = value.split_once(':').unwrap_or((value, ""));
+            Ok((host.to_string(), Some(parse_port(port)?)))
+        }
+        // Unbracketed IPv6 literal: no room for a port.
+        _ => Ok((value.to_string(), None)),
+    }
+}
+
+fn parse_user_host(value: &str) -> Result<QuickTarget, String> {
+    let (username, host_part) = match value.rsplit_once('@') {
+        Some((user, host)) if !user.is_empty() => (Some(user.to_string()), host),
+        Some(_) => return Err("Empty username before '@'".to_string()),
+        None => (None, value),
+    };
+    let (host, port) = split_host_port(host_part)?;
+    if host.is_empty() {
+        return Err("Missing host".to_string());
+    }
+    Ok(QuickTarget {
+        username,
+        host,
+        port,
+        identity_file: None,
+    })
+}
+
+/// `ssh [-p port] [-l user] [-i key] [user@]host` — other flags are ignored.
+fn parse_ssh_command(rest: &str) -> Result<QuickTarget, String> {
+    let mut tokens = rest.split_whitespace();
+    let (mut port, mut user, mut identity, mut destination) = (None, None, None, None);
+    while let Some(token) = tokens.next() {
+        match token {
+            "-p" => port = Some(parse_port(tokens.next().ok_or("Missing value for -p")?)?),
+            "-l" => user = tokens.next().map(str::to_string),
+            "-i" => identity = tokens.next().map(str::to_string),
+            // Flags that take an argument we don't use.
+            "-L" | "-R" | "-D" | "-J" | "-o" | "-F" | "-b" | "-c" | "-m" | "-E" | "-W" => {
+                tokens.next();
+            }
+            flag if flag.starts_with('-') => {}
+            target if destination.is_none() => destination = Some(target),
+            // Anything after the destination is a remote command.
+            _ => break,
+        }
+    }
+    let mut target = parse_target(destination.ok_or("Missing destination")?)?;
+    target.port = port.or(target.port);
+    target.username = user.or(target.username);
+    target.identity_file = identity;
+    Ok(target)
+}
+
+pub fn parse_target(input: &str) -> Result<QuickTarget, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("Enter a host, user@host[:port] or ssh:// URL".to_string());
+    }
+    if let Some(rest) = input.strip_prefix("ssh://") {
+        return parse_user_host(rest.trim_end_matches('/'));
+    }
+    if let Some(rest) = input.strip_prefix("ssh ") {
+        return parse_ssh_command(rest);
+    }
+    if input.contains(char::is_whitespace) {
+        return Err(format!("Unrecognized connection string: {input}"));
+    }
+    parse_user_host(input)
+}
+
+fn expand_home(path: &str) -> String {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest).to_string_lossy().to_string(),
+        _ => path.to_string(),
+    }
+}
+
+fn default_identity(ssh_dir: &Path) -> Option<PathBuf> {
+    ["id_ed25519", "id_ecdsa", "id_rsa"]
+        .iter()
+        .map(|name| ssh_dir.join(name))
+        .find(|path| path.is_file())
+}
+
+/// ProxyJump chains longer than this are refused.
+const MAX_JUMP_HOPS: usize = 8;
+
+/// Build the config, applying a matching ssh-config alias. Explicit input wins
+/// over config values; auth falls back to the password, then a default key.
+pub fn build_config(
+    target: &QuickTarget,
+    ssh_hosts: &[ParsedSshConnection],
+    password: Option<String>,
+    ssh_dir: Option<&Path>,
+) -> Result<ConnectionConfig, String> {
+    build_hop(target, ssh_hosts, password, ssh_dir, &[], true)
+}
+
+/// [`build_config`] for one hop. `path` holds the hosts already on the way to
+/// the final host, so a ProxyJump leading back to one of them is an error
+/// rather than endless recursion. `follow_proxy_jump` is false for the later
+/// hops of a `ProxyJump a,b` list, which are reached through the hop before.
+fn build_hop(
+    target: &QuickTarget,
+    ssh_hosts: &[ParsedSshConnection],
+    password: Option<String>,
+    ssh_dir: Option<&Path>,
+    path: &[String],
+    follow_proxy_jump: bool,
+) -> Result<ConnectionConfig, String> {
+    if path.contains(&target.host) {
+        return Err(format!(
+            "ProxyJump loop: {} -> {}",
+            path.join(" -> "),
+            target.host
+        ));
+    }
+    if path.len() > MAX_JUMP_HOPS {
+        return Err(format!(
+            "ProxyJump chain for {} is longer than {MAX_JUMP_HOPS} hops",
+            path[0]
+        ));
+    }
+
+    let alias = ssh_hosts
+        .iter()
+        .find(|host| host.aliases.iter().any(|alias| alias == &target.host));
+
+    let host = alias.map(|entry| entry.host.clone()).unwrap_or_else(|| target.host.clone());
+    let port = target.port.or(alias.map(|entry| entry.port)).unwrap_or(22);
+    let username = target
+        .username
+        .clone()
+        .or_else(|| alias.map(|entry| entry.username.clone()))
+        .unwrap_or_else(whoami::username);
+
+    let identity = target
+        .identity_file
+        .as_deref()
+        .map(expand_home)
+        .or_else(|| alias.and_then(|entry| entry.private_key_path.clone()));
+    let auth_method = match (identity, password) {
+        (Some(key_path), _) => AuthMethod::PrivateKey {
+            key_path,
+            passphrase: None,
+        },
+        (None, Some(password)) => AuthMethod::Password { password },
+        (None, None) => match ssh_dir.and_then(default_identity) {
+            Some(path) => AuthMethod::PrivateKey {
+                key_path: path.to_string_lossy().to_string(),
+                passphrase: None,
+            },
+            None => return Err("No SSH key found for this host; enter a password".to_string()),
+        },
+    };
+
+    let proxy_jump = alias
+        .and_then(|entry| entry.jump_server_alias.as_deref())
+        .filter(|jump| follow_proxy_jump && !jump.eq_ignore_ascii_case("none"));
+    let jump_host = match proxy_jump {
+        Some(jump) => {
+            let mut path = path.to_vec();
+            path.push(target.host.clone());
+            Some(Box::new(build_jump_chain(jump, ssh_hosts, ssh_dir, &path)?))
+        }
+        None => None,
+    };
+
+    Ok(ConnectionConfig {
+        id: format!("quick_{}", uuid::Uuid::new_v4()),
+        name: alias
+            .map(|entry| entry.name.clone())
+            .unwrap_or_else(|| format!("{username}@{host}")),
+        host,
+        port,
+        username,
+        auth_method,
+        jump_host,
+        strict_host_key_checking: Default::default(),
+        encoding: None,
+        locale: None,
+        ssh_options: alias.and_then(|entry| entry.ssh_options.clone()),
+        host_key_alias: alias.and_then(|entry| entry.host_key_alias.clone()),
+        user_known_hosts_file: alias.and_then(|entry| entry.user_known_hosts_file.clone()),
+        session_lifetime: None,
+    })
+}
+
+/// The last hop of `ProxyJump a,b,...`: `a` is reached as its own config says,
+/// each later hop through the one before it.
+fn build_jump_chain(
+    proxy_jump: &str,
+    ssh_hosts: &[ParsedSshConnection],
+    ssh_dir: Option<&Path>,
+    path: &[String],
+) -> Result<ConnectionConfig, String> {
+    let mut path = path.to_vec();
+    let mut previous: Option<ConnectionConfig> = None;
+    for hop in proxy_jump
+        .split(',')
+        .map(str::trim)
+        .filter(|hop| !hop.is_empty())
+    {
+        let target = parse_target(hop)?;
+        // Jump hops authenticate with keys only; passwords are for the final host.
+        let mut config = build_hop(&target, ssh_hosts, None, ssh_dir, &path, previous.is_none())?;
+        if let Some(previous) = previous.take() {
+            config.jump_host = Some(Box::new(previous));
+        }
+        path.push(target.host);
+        previous = Some(config);
+    }
+    previous.ok_or_else(|| "ProxyJump lists no hosts".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_common_forms() {
+        let target = parse_target("deploy@10.0.0.5:2222").expect("user@host:port");
+        assert_eq!(target.username.as_deref(), Some("deploy"));
+        assert_eq!(target.host, "10.0.0.5");
+        assert_eq!(target.port, Some(2222));
+
+        let url = parse_target("ssh://root@[2001:db8::1]:22/").expect("url");
+        assert_eq!(url.host, "2001:db8::1");
+        assert_eq!(url.port, Some(22));
+
+        assert_eq!(parse_target("fe80::1").expect("v6").host, "fe80::1");
+        assert!(parse_target("host:notaport").is_err());
+    }
+
+    #[test]
+    fn parses_ssh_command_flags() {
+        let target = parse_target("ssh -p 2200 -i ~/.ssh/work admin@bastion uptime").expect("ssh cmd");
+        assert_eq!(target.username.as_deref(), Some("admin"));
+        assert_eq!(target.host, "bastion");
+        assert_eq!(target.port, Some(2200));
+        assert_eq!(target.identity_file.as_deref(), Some("~/.ssh/work"));
+    }
+
+    #[test]
+    fn alias_supplies_defaults_but_input_wins() {
+        let hosts = crate::ssh_config::parse_config_text(
+            "Host app\n  HostName 10.1.2.3\n  User ubuntu\n  Port 2222\n  IdentityFile /keys/app\n",
+        )
+        .expect("config");
+        let target = parse_target("admin@app").expect("target");
+        let config = build_config(&target, &hosts, None, None).expect("config");
+        assert_eq!(config.host, "10.1.2.3");
+        assert_eq!(config.port, 2222);
+        assert_eq!(config.username, "admin");
+        assert!(matches!(config.auth_method, AuthMethod::PrivateKey { ref key_path, .. } if key_path == "/keys/app"));
+    }
+
+    #[test]
+    fn requires_password_without_any_key() {
+        let target = parse_target("10.0.0.9").expect("target");
+        assert!(build_config(&target, &[], None, None).is_err());
+        let config = build_config(&target, &[], Some("pw".to_string()), None).expect("config");
+        assert!(matches!(config.auth_method, AuthMethod::Password { .. }));
+    }
+
+    fn jump_hosts(config: &ConnectionConfig) -> Vec<String> {
+        let mut hops = Vec::new();
+        let mut next = config.jump_host.as_deref();
+        while let Some(hop) = next {
+            hops.push(hop.host.clone());
+            next = hop.jump_host.as_deref();
+        }
+        hops
+    }
+
+    #[test]
+    fn proxy_jump_lists_chain_their_hops() {
+        let hosts = crate::ssh_config::parse_config_text(
+            "Host app\n  HostName 10.0.0.3\n  ProxyJump edge,inner\n\
+             Host edge\n  HostName 10.0.0.1\n  IdentityFile /keys/edge\n\
+             Host inner\n  HostName 10.0.0.2\n  IdentityFile /keys/inner\n",
+        )
+        .expect("config");
+        let target = parse_target("app").expect("target");
+        let config = build_config(&target, &hosts, Some("pw".to_string()), None).expect("config");
+        assert_eq!(config.host, "10.0.0.3");
+        assert_eq!(jump_hosts(&config), vec!["10.0.0.2", "10.0.0.1"]);
+    }
+
+    #[test]
+    fn proxy_jump_loops_are_errors() {
+        let hosts = crate::ssh_config::parse_config_text(
+            "Host a\n  HostName 10.0.0.1\n  IdentityFile /keys/a\n  ProxyJump b\n\
+             Host b\n  HostName 10.0.0.2\n  IdentityFile /keys/b\n  ProxyJump a\n\
+             Host self\n  HostName 10.0.0.3\n  IdentityFile /keys/self\n  ProxyJump self\n",
+        )
+        .expect("config");
+        let loop_error = build_config(&parse_target("a").expect("a"), &hosts, None, None)
+            .expect_err("a -> b -> a");
+        assert!(loop_error.contains("a -> b -> a"), "{loop_error}");
+        assert!(build_config(&parse_target("self").expect("self"), &hosts, None, None).is_err());
+    }
+}
@@ -0,0 +1,51 @@
+use super::{read_redaction_settings, RedactionSettings, Redactor};
+use serde::Serialize;
+use tauri::AppHandle;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactionPreview {
+    pub text: String,
+    /// Names of the rules that changed the text.
+    pub matched: Vec<String>,
+    /// Rules that failed to compile and were skipped.
+    pub errors: Vec<String>,
+}
+
+/// Run `text` through the redaction rules, the saved ones unless `settings`
+/// is given (to try rules before saving them).
+#[tauri::command]
+pub async fn redaction_preview(
+    app: AppHandle,
+    text: String,
+    settings: Option<RedactionSettings>,
+) -> Result<RedactionPreview, String> {
+    let settings = settings.unwrap_or_else(|| read_redaction_settings(&app));
+    let (redactor, errors) = Redactor::new(&settings);
+    let (text, matched) = redactor.redact_counted(&text);
+    Ok(RedactionPreview {
+        text,
+        matched,
+        errors,
+    })
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScrubReport {
+    pub clipboard_entries: usize,
+    pub output_bookmarks: usize,
+}
+
+/// Mask secret values spotted in terminal output wherever that output was
+/// already saved: clipboard history and output bookmarks.
+#[tauri::command]
+pub async fn redaction_scrub_persisted(
+    app: AppHandle,
+    secrets: Vec<String>,
+) -> Result<ScrubReport, String> {
+    Ok(ScrubReport {
+        clipboard_entries: crate::clipboard_history::commands::scrub_secrets(&app, &secrets)?,
+        output_bookmarks: crate::output_bookmarks::commands::scrub_secrets(&app, &secrets)?,
+    })
+}
@@ -0,0 +1,192 @@
+//! Character encodings for hosts that don't speak UTF-8.
+//!
+//! A connection can name a remote encoding (`latin1`, `GBK`, `Shift_JIS`, …)
+//! and a locale. Terminal output is transcoded to UTF-8 before it reaches the
+//! frontend, keystrokes are transcoded back, and the locale is offered to the
+//! shell as `LANG` and `LC_ALL` (sshd only applies them when its `AcceptEnv`
+//! allows, which most distributions do for `LANG LC_*`).
+
+use crate::types::ConnectionConfig;
+use encoding_rs::{CoderResult, Decoder, EncoderResult, Encoding};
+
+const MAX_LOCALE_LEN: usize = 64;
+
+/// Terminal text settings of one connection.
+#[derive(Debug, Clone, Default)]
+pub struct TerminalCharset {
+    /// `None` for UTF-8, which passes through untouched.
+    pub encoding: Option<&'static Encoding>,
+    /// Exported as `LANG` and `LC_ALL` before the shell starts.
+    pub locale: Option<String>,
+}
+
+impl TerminalCharset {
+    pub fn from_config(config: &ConnectionConfig) -> Result<Self, String> {
+        Ok(Self {
+            encoding: resolve(config.encoding.as_deref())?,
+            locale: validate_locale(config.locale.as_deref())?,
+        })
+    }
+
+    /// `LANG` and `LC_ALL` to request on the shell channel.
+    pub fn environment(&self) -> Vec<(&'static str, String)> {
+        self.locale
+            .iter()
+            .flat_map(|locale| [("LANG", locale.clone()), ("LC_ALL", locale.clone())])
+            .collect()
+    }
+}
+
+/// Look up an encoding by its WHATWG label. UTF-8 and an empty label need no
+/// transcoding and come back as `None`.
+pub fn resolve(label: Option<&str>) -> Result<Option<&'static Encoding>, String> {
+    let Some(label) = label.map(str::trim).filter(|label| !label.is_empty()) else {
+        return Ok(None);
+    };
+    let encoding = Encoding::for_label(label.as_bytes())
+        .ok_or_else(|| format!("Unknown character encoding '{label}'"))?;
+    if encoding == encoding_rs::UTF_8 {
+        return Ok(None);
+    }
+    // A terminal byte stream must keep ASCII control characters as single bytes.
+    if encoding == encoding_rs::UTF_16LE
+        || encoding == encoding_rs::UTF_16BE
+        || encoding == encoding_rs::REPLACEMENT
+    {
+        return Err(format!("{} can't be used for a terminal", encoding.name()));
+    }
+    Ok(Some(encoding))
+}
+
+/// Accept locale names such as `ja_JP.eucJP` or `ru_RU.KOI8-R@euro`.
+pub fn validate_locale(locale: Option<&str>) -> Result<Option<String>, String> {
+    let Some(locale) = locale.map(str::trim).filter(|locale| !locale.is_empty()) else {
+        return Ok(None);
+    };
+    let valid = locale.len() <= MAX_LOCALE_LEN
+        && locale
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-' | '@'));
+    if !valid {
+        return Err(format!("'{locale}' is not a locale name"));
+    }
+    Ok(Some(locale.to_string()))
+}
+
+/// Streaming decoder for terminal output. A multi-byte character split across
+/// two reads is completed by the next call instead of turning into U+FFFD.
+pub struct OutputDecoder(Decoder);
+
+impl OutputDecoder {
+    pub fn new(encoding: &'static Encoding) -> Self {
+        Self(encoding.new_decoder_without_bom_handling())
+    }
+
+    /// Append `bytes` to `out` as UTF-8.
+    pub fn decode_into(&mut self, bytes: &[u8], out: &mut Vec<u8>) {
+        let mut text = String::new();
+        let mut rest = bytes;
+        loop {
+            let needed = self
+                .0
+                .max_utf8_buffer_length(rest.len())
+                .unwrap_or(rest.len() * 3 + 16);
+            text.reserve(needed);
+            let (result, read, _) = self.0.decode_to_string(rest, &mut text, false);
+            rest = &rest[read..];
+            if result == CoderResult::InputEmpty {
+                break;
+            }
+        }
+        out.extend_from_slice(text.as_bytes());
+    }
+}
+
+/// Encode terminal input for the remote side. Characters the encoding can't
+/// represent are sent as `?`.
+pub fn encode_input(encoding: &'static Encoding, text: &str) -> Vec<u8> {
+    let mut encoder = encoding.new_encoder();
+    let mut out = Vec::new();
+    let mut rest = text;
+    loop {
+        let needed = encoder
+            .max_buffer_length_from_utf8_without_replacement(rest.len())
+            .unwrap_or(rest.len() * 4 + 16);
+        out.reserve(needed);
+        let (result, read) =
+            encoder.encode_from_utf8_to_vec_without_replacement(rest, &mut out, true);
+        rest = &rest[read..];
+        match result {
+            EncoderResult::InputEmpty => break,
+            EncoderResult::OutputFull => {}
+            EncoderResult::Unmappable(_) => out.push(b'?'),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_labels_and_rejects_unusable_ones() {
+        assert_eq!(resolve(None).unwrap(), None);
+        assert_eq!(resolve(Some(" utf8 ")).unwrap(), None);
+        assert_eq!(resolve(Some("GBK")).unwrap(), Some(encoding_rs::GBK));
+        assert_eq!(
+            resolve(Some("latin1")).unwrap(),
+            Some(encoding_rs::WINDOWS_1252)
+        );
+        assert!(resolve(Some("utf-16le")).is_err());
+        assert!(resolve(Some("klingon")).is_err());
+
+        assert_eq!(
+            validate_locale(Some("ja_JP.eucJP")).unwrap().as_deref(),
+            Some("ja_JP.eucJP")
+        );
+        assert!(validate_locale(Some("C; rm -rf /")).is_err());
+        assert_eq!(validate_locale(Some("  ")).unwrap(), None);
+    }
+
+    #[test]
+    fn decodes_characters_split_across_reads() {
+        // "中文" in GBK, delivered one byte at a time.
+        let mut decoder = OutputDecoder::new(encoding_rs::GBK);
+        let mut out = Vec::new();
+        for byte in [0xD6, 0xD0, 0xCE, 0xC4] {
+            decoder.decode_into(&[byte], &mut out);
+        }
+        assert_eq!(String::from_utf8(out).unwrap(), "中文");
+
+        let mut decoder = OutputDecoder::new(encoding_rs::WINDOWS_1252);
+        let mut out = Vec::new();
+        decoder.decode_into(b"caf\xe9\r\n", &mut out);
+        assert_eq!(String::from_utf8(out).unwrap(), "café\r\n");
+    }
+
+    #[test]
+    fn encodes_input_with_placeholders_for_unmappable_characters() {
+        assert_eq!(
+            encode_input(encoding_rs::GBK, "ls 中文\r"),
+            b"ls \xD6\xD0\xCE\xC4\r"
+        );
+        assert_eq!(encode_input(encoding_rs::WINDOWS_1252, "é→"), b"\xe9?");
+    }
+
+    #[test]
+    fn exports_the_locale_twice() {
+        let charset = TerminalCharset {
+            encoding: None,
+            locale: Some("ru_RU.KOI8-R".to_string()),
+        };
+        assert_eq!(
+            charset.environment(),
+            vec![
+                ("LANG", "ru_RU.KOI8-R".to_string()),
+                ("LC_ALL", "ru_RU.KOI8-R".to_string())
+            ]
+        );
+        assert!(TerminalCharset::default().environment().is_empty());
+    }
+}
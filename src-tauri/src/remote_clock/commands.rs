@@ -0,0 +1,39 @@
+use super::{find_timestamp, probe, FoundTimestamp, RemoteClock};
+use crate::commands::{ensure_connection_online, AppState};
+use tauri::State;
+
+/// Lines converted per call; log panels send what is on screen.
+const MAX_LINES: usize = 5_000;
+
+/// Time zone and clock skew of a connected host. Cached per connection;
+/// `refresh` probes again.
+#[tauri::command]
+pub async fn remote_clock(
+    state: State<'_, AppState>,
+    connection_id: String,
+    refresh: Option<bool>,
+) -> Result<RemoteClock, String> {
+    ensure_connection_online(&state, &connection_id).await?;
+    probe(&state, &connection_id, refresh.unwrap_or(false)).await
+}
+
+/// The first timestamp of each line, resolved in the host's zone and
+/// corrected for its clock skew; `null` for lines without one.
+#[tauri::command]
+pub async fn remote_clock_normalize(
+    state: State<'_, AppState>,
+    connection_id: String,
+    lines: Vec<String>,
+) -> Result<Vec<Option<FoundTimestamp>>, String> {
+    if lines.len() > MAX_LINES {
+        return Err(format!(
+            "At most {MAX_LINES} lines can be converted at once"
+        ));
+    }
+    ensure_connection_online(&state, &connection_id).await?;
+    let clock = probe(&state, &connection_id, false).await?;
+    Ok(lines
+        .iter()
+        .map(|line| find_timestamp(line, &clock))
+        .collect())
+}
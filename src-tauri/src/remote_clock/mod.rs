@@ -0,0 +1,436 @@
+//! The remote host's time zone and clock skew, for showing remote log
+//! timestamps in local time.
+//!
+//! One `date` probe per connection records the UTC offset, the zone name and
+//! how far the remote clock is from ours (measured against the midpoint of
+//! the probe's round trip). Results are cached for [`CACHE_TTL`].
+//! [`find_timestamp`] then reads the first ISO 8601, syslog or common log
+//! format timestamp on a line; ones without an offset are taken to be in the
+//! remote zone.
+
+pub mod commands;
+
+use crate::commands::{exec_on_connection, AppState};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex as StdMutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+pub const CACHE_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// `%N` is a GNU extension; where it is printed literally, seconds are the fallback.
+const PROBE_SCRIPT: &str = r#"echo "now=$(date +%s%N 2>/dev/null)"
+echo "offset=$(date +%z 2>/dev/null)"
+echo "abbreviation=$(date +%Z 2>/dev/null)"
+tz="${TZ:-}"
+if [ -z "$tz" ] && command -v timedatectl >/dev/null 2>&1; then
+  tz=$(timedatectl show -p Timezone --value 2>/dev/null)
+fi
+if [ -z "$tz" ] && [ -r /etc/timezone ]; then tz=$(head -n 1 /etc/timezone); fi
+if [ -z "$tz" ]; then tz=$(readlink /etc/localtime 2>/dev/null | sed -n 's|.*zoneinfo/||p'); fi
+echo "zone=$tz"
+exit 0"#;
+
+/// PowerShell counterpart of [`PROBE_SCRIPT`], with the same output format.
+const WINDOWS_PROBE_SCRIPT: &str = r#"$now = [DateTimeOffset]::UtcNow
+$o = [TimeZoneInfo]::Local.GetUtcOffset($now.UtcDateTime)
+"now=$($now.ToUnixTimeMilliseconds())ms"
+"offset=$(if ($o -lt [TimeSpan]::Zero) { '-' } else { '+' })$($o.ToString('hhmm'))"
+"zone=$([TimeZoneInfo]::Local.Id)""#;
+
+static CACHE: LazyLock<StdMutex<HashMap<String, (Instant, RemoteClock)>>> =
+    LazyLock::new(|| StdMutex::new(HashMap::new()));
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+static ISO_8601: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r"\b(\d{4})-(\d{2})-(\d{2})[T ](\d{2}):(\d{2}):(\d{2})(?:[.,](\d{1,9}))?",
+        r"(?:\s?(Z|[+-]\d{2}:?\d{2})\b)?",
+    ))
+    .expect("valid regex")
+});
+static SYSLOG: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r"\b(Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Oct|Nov|Dec)\s+(\d{1,2})",
+        r" (\d{2}):(\d{2}):(\d{2})(?:\.(\d{1,9}))?\b",
+    ))
+    .expect("valid regex")
+});
+/// Apache/nginx access logs: `16/Oct/2026:12:34:56 +0200`.
+static COMMON_LOG: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r"\b(\d{2})/(Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Oct|Nov|Dec)/(\d{4})",
+        r":(\d{2}):(\d{2}):(\d{2}) ([+-]\d{4})\b",
+    ))
+    .expect("valid regex")
+});
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteClock {
+    pub connection_id: String,
+    /// Offset from UTC the remote host is currently on, in seconds.
+    pub utc_offset_seconds: i32,
+    /// `CEST`, `UTC`, …; `None` where `date +%Z` prints nothing useful.
+    pub abbreviation: Option<String>,
+    /// IANA name (`Europe/Berlin`) or Windows zone id, when the host reveals it.
+    pub time_zone: Option<String>,
+    /// Remote clock minus local clock; positive when the remote is ahead.
+    pub skew_ms: i64,
+    /// Uncertainty of `skew_ms`: half the probe's round trip, plus half a
+    /// second when the host only reports whole seconds.
+    pub skew_precision_ms: i64,
+    /// Local time of the probe.
+    pub probed_at_ms: i64,
+}
+
+impl RemoteClock {
+    /// Local clock reading for a remote clock reading.
+    pub fn to_local_ms(&self, remote_ms: i64) -> i64 {
+        remote_ms - self.skew_ms
+    }
+
+    /// The remote wall clock's year now, for timestamps that omit it.
+    fn remote_year(&self) -> i32 {
+        let remote_now = self.probed_at_ms + self.skew_ms;
+        OffsetDateTime::from_unix_timestamp(remote_now.div_euclid(1000))
+            .ok()
+            .zip(UtcOffset::from_whole_seconds(self.utc_offset_seconds).ok())
+            .map(|(at, offset)| at.to_offset(offset).year())
+            .unwrap_or(1970)
+    }
+}
+
+/// A timestamp found in a remote log line.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FoundTimestamp {
+    /// The timestamp as written, for replacing it in the line.
+    pub text: String,
+    /// The moment it names, in Unix milliseconds.
+    pub epoch_ms: i64,
+    /// The same moment on the local clock (skew removed).
+    pub local_epoch_ms: i64,
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
+
+/// `+0530`, `-08:00` or `Z` in seconds.
+pub fn parse_utc_offset(text: &str) -> Option<i32> {
+    let text = text.trim();
+    if text.eq_ignore_ascii_case("z") {
+        return Some(0);
+    }
+    let (sign, digits) = match text.as_bytes().first()? {
+        b'+' => (1, &text[1..]),
+        b'-' => (-1, &text[1..]),
+        _ => return None,
+    };
+    let digits = digits.replace(':', "");
+    if digits.len() != 4 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let hours: i32 = digits[..2].parse().ok()?;
+    let minutes: i32 = digits[2..].parse().ok()?;
+    (hours <= 18 && minutes < 60).then_some(sign * (hours * 3600 + minutes * 60))
+}
+
+/// Remote clock reading in milliseconds and its resolution: `date +%s%N`
+/// nanoseconds, plain seconds, or `…ms` from the Windows probe.
+fn parse_now(value: &str) -> Option<(i64, i64)> {
+    if let Some(millis) = value.strip_suffix("ms") {
+        return Some((millis.parse().ok()?, 1));
+    }
+    // Without `%N` support the digits are followed by `N` or `%N`.
+    let digits = value
+        .find(|c: char| !c.is_ascii_digit())
+        .map_or(value, |end| &value[..end]);
+    if digits.len() > 12 {
+        return Some((digits.parse::<i128>().ok()?.div_euclid(1_000_000) as i64, 1));
+    }
+    // Whole seconds: the true reading lies somewhere in the following second.
+    Some((digits.parse::<i64>().ok()? * 1000 + 500, 1000))
+}
+
+/// Read the probe output. `sent_ms` and `received_ms` bracket the exec.
+pub fn parse_probe(
+    connection_id: &str,
+    output: &str,
+    sent_ms: i64,
+    received_ms: i64,
+) -> Result<RemoteClock, String> {
+    let mut now = None;
+    let mut offset = None;
+    let mut abbreviation = None;
+    let mut time_zone = None;
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        if value.is_empty() {
+            continue;
+        }
+        match key.trim() {
+            "now" => now = parse_now(value),
+            "offset" => offset = parse_utc_offset(value),
+            "abbreviation" if !value.starts_with(['+', '-']) => {
+                abbreviation = Some(value.to_string())
+            }
+            "zone" => time_zone = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    let (remote_ms, resolution_ms) =
+        now.ok_or_else(|| "The host did not report its clock".to_string())?;
+    let round_trip = (received_ms - sent_ms).max(0);
+    Ok(RemoteClock {
+        connection_id: connection_id.to_string(),
+        utc_offset_seconds: offset
+            .ok_or_else(|| "The host did not report its UTC offset".to_string())?,
+        abbreviation,
+        time_zone,
+        skew_ms: remote_ms - (sent_ms + round_trip / 2),
+        skew_precision_ms: round_trip / 2 + resolution_ms / 2,
+        probed_at_ms: received_ms,
+    })
+}
+
+fn month_number(name: &str) -> Option<u8> {
+    MONTHS
+        .iter()
+        .position(|month| *month == name)
+        .map(|index| index as u8 + 1)
+}
+
+/// Fraction digits as nanoseconds: `5` is 500 ms, `123456` is 123.456 ms.
+fn fraction_nanos(digits: Option<&str>) -> u32 {
+    digits
+        .map(|digits| format!("{digits:0<9}"))
+        .and_then(|padded| padded[..9].parse().ok())
+        .unwrap_or(0)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn epoch_ms(
+    year: i32,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+    nanos: u32,
+    offset_seconds: i32,
+) -> Option<i64> {
+    let date = Date::from_calendar_date(year, Month::try_from(month).ok()?, day).ok()?;
+    // Leap seconds (`:60`) fold into the next second's start.
+    let time = Time::from_hms_nano(hour, minute, second.min(59), nanos).ok()?;
+    let offset = UtcOffset::from_whole_seconds(offset_seconds).ok()?;
+    let at = PrimitiveDateTime::new(date, time).assume_offset(offset);
+    Some((at.unix_timestamp_nanos() / 1_000_000) as i64)
+}
+
+fn num<T: std::str::FromStr>(captures: &regex::Captures, index: usize) -> Option<T> {
+    captures.get(index)?.as_str().parse().ok()
+}
+
+/// The first timestamp on `line`, converted with `clock`.
+pub fn find_timestamp(line: &str, clock: &RemoteClock) -> Option<FoundTimestamp> {
+    let mut found: Vec<(usize, &str, i64)> = Vec::new();
+
+    if let Some(c) = ISO_8601.captures(line) {
+        let offset = match c.get(8) {
+            Some(zone) => parse_utc_offset(zone.as_str()),
+            None => Some(clock.utc_offset_seconds),
+        };
+        let whole = c.get(0)?;
+        let at = offset.and_then(|offset| {
+            epoch_ms(
+                num(&c, 1)?,
+                num(&c, 2)?,
+                num(&c, 3)?,
+                num(&c, 4)?,
+                num(&c, 5)?,
+                num(&c, 6)?,
+                fraction_nanos(c.get(7).map(|m| m.as_str())),
+                offset,
+            )
+        });
+        if let Some(at) = at {
+            found.push((whole.start(), whole.as_str(), at));
+        }
+    }
+
+    if let Some(c) = COMMON_LOG.captures(line) {
+        let whole = c.get(0)?;
+        let at = epoch_ms(
+            num(&c, 3)?,
+            month_number(c.get(2)?.as_str())?,
+            num(&c, 1)?,
+            num(&c, 4)?,
+            num(&c, 5)?,
+            num(&c, 6)?,
+            0,
+            parse_utc_offset(c.get(7)?.as_str())?,
+        );
+        if let Some(at) = at {
+            found.push((whole.start(), whole.as_str(), at));
+        }
+    }
+
+    if let Some(c) = SYSLOG.captures(line) {
+        let whole = c.get(0)?;
+        let fields = (
+            month_number(c.get(1)?.as_str())?,
+            num::<u8>(&c, 2)?,
+            num::<u8>(&c, 3)?,
+            num::<u8>(&c, 4)?,
+            num::<u8>(&c, 5)?,
+            fraction_nanos(c.get(6).map(|m| m.as_str())),
+        );
+        let at_year = |year| {
+            let (month, day, hour, minute, second, nanos) = fields;
+            epoch_ms(
+                year,
+                month,
+                day,
+                hour,
+                minute,
+                second,
+                nanos,
+                clock.utc_offset_seconds,
+            )
+        };
+        // No year: this year, unless that lies ahead of the remote clock
+        // (December lines read in January).
+        let year = clock.remote_year();
+        let remote_now = clock.probed_at_ms + clock.skew_ms;
+        let at = match at_year(year) {
+            Some(at) if at > remote_now + 86_400_000 => at_year(year - 1),
+            at => at,
+        };
+        if let Some(at) = at {
+            found.push((whole.start(), whole.as_str(), at));
+        }
+    }
+
+    let (_, text, epoch_ms) = found.into_iter().min_by_key(|(start, _, _)| *start)?;
+    Some(FoundTimestamp {
+        text: text.to_string(),
+        epoch_ms,
+        local_epoch_ms: clock.to_local_ms(epoch_ms),
+    })
+}
+
+/// Time zone and skew of a connected host. Cached per connection; `refresh`
+/// probes again.
+pub async fn probe(
+    state: &AppState,
+    connection_id: &str,
+    refresh: bool,
+) -> Result<RemoteClock, String> {
+    if !refresh {
+        let cache = CACHE
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some((at, clock)) = cache.get(connection_id) {
+            if at.elapsed() < CACHE_TTL {
+                return Ok(clock.clone());
+            }
+        }
+    }
+    let script = if crate::remote_os::connection_is_windows(state, connection_id).await {
+        crate::remote_os::powershell_command(WINDOWS_PROBE_SCRIPT, &[])
+    } else {
+        PROBE_SCRIPT.to_string()
+    };
+    let sent = now_ms();
+    let output = exec_on_connection(state, connection_id, &script).await?;
+    let received = now_ms();
+    let clock = parse_probe(
+        connection_id,
+        &String::from_utf8_lossy(&output.stdout),
+        sent,
+        received,
+    )?;
+    CACHE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .insert(connection_id.to_string(), (Instant::now(), clock.clone()));
+    Ok(clock)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_probe_output_and_skew() {
+        let output = "now=1760610005250000000\noffset=+0530\nabbreviation=IST\nzone=Asia/Kolkata\n";
+        let clock = parse_probe("c1", output, 1_760_610_000_000, 1_760_610_000_200).unwrap();
+        assert_eq!(clock.utc_offset_seconds, 19_800);
+        assert_eq!(clock.time_zone.as_deref(), Some("Asia/Kolkata"));
+        assert_eq!(clock.skew_ms, 5_150);
+        assert_eq!(clock.skew_precision_ms, 100);
+
+        // No %N, numeric %Z.
+        let busybox = parse_probe(
+            "c1",
+            "now=1760610000%N\noffset=-0800\nabbreviation=-08\n",
+            0,
+            0,
+        )
+        .unwrap();
+        assert_eq!(busybox.utc_offset_seconds, -28_800);
+        assert_eq!(busybox.abbreviation, None);
+        assert_eq!(busybox.skew_precision_ms, 500);
+        assert!(parse_probe("c1", "offset=+0000\n", 0, 0).is_err());
+        assert_eq!(parse_utc_offset("-08:00"), Some(-28_800));
+        assert_eq!(parse_utc_offset("0800"), None);
+    }
+
+    #[test]
+    fn finds_iso_syslog_and_common_log_timestamps() {
+        let clock = RemoteClock {
+            connection_id: "c1".to_string(),
+            utc_offset_seconds: 7_200,
+            abbreviation: Some("CEST".to_string()),
+            time_zone: Some("Europe/Berlin".to_string()),
+            skew_ms: 2_000,
+            skew_precision_ms: 50,
+            // 2026-10-16T10:00:00Z
+            probed_at_ms: 1_792_144_800_000,
+        };
+        let iso = find_timestamp("2026-10-16T12:00:00.5+02:00 level=info", &clock).unwrap();
+        assert_eq!(iso.text, "2026-10-16T12:00:00.5+02:00");
+        assert_eq!(iso.epoch_ms, 1_792_144_800_500);
+        assert_eq!(iso.local_epoch_ms, 1_792_144_798_500);
+
+        // No offset: remote zone.
+        let local = find_timestamp("[2026-10-16 12:00:00,250] ERROR", &clock).unwrap();
+        assert_eq!(local.epoch_ms, 1_792_144_800_250);
+
+        let syslog = find_timestamp("Oct 16 12:00:00 web sshd[42]: Accepted", &clock).unwrap();
+        assert_eq!(syslog.epoch_ms, 1_792_144_800_000);
+        // A date ahead of the remote clock belongs to last year.
+        let december = find_timestamp("Dec 31 23:00:00 web cron[1]: run", &clock).unwrap();
+        assert!(december.epoch_ms < clock.probed_at_ms);
+
+        let access = r#"10.0.0.1 - - [16/Oct/2026:10:00:00 +0000] "GET / HTTP/1.1" 200"#;
+        let common = find_timestamp(access, &clock).unwrap();
+        assert_eq!(common.text, "16/Oct/2026:10:00:00 +0000");
+        assert_eq!(common.epoch_ms, 1_792_144_800_000);
+
+        assert_eq!(find_timestamp("no time here", &clock), None);
+    }
+}
@@ -0,0 +1,112 @@
+use super::{
+    checkout_command, log_command, parse_action, parse_log, parse_status, pull_command,
+    status_command, validate_branch, GitActionResult, GitCommit, GitStatus, DEFAULT_LOG_LIMIT,
+};
+use crate::commands::{ensure_connection_online, exec_on_connection, AppState, ExecOutput};
+use tauri::{AppHandle, State};
+
+/// Run a git script; a failure before git itself ran (missing directory,
+/// not a repository, no git) comes back as `Err` with the script's message.
+async fn run(state: &AppState, connection_id: &str, command: &str) -> Result<ExecOutput, String> {
+    ensure_connection_online(state, connection_id).await?;
+    if crate::remote_os::connection_is_windows(state, connection_id).await {
+        return Err("Git helpers are not supported on Windows hosts".to_string());
+    }
+    let output = exec_on_connection(state, connection_id, command).await?;
+    if output.stdout.is_empty() && output.exit_status != 0 {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(output)
+}
+
+/// Branch, HEAD commit, tag description, upstream drift, local changes and
+/// remotes of the work tree containing `path`.
+#[tauri::command]
+pub async fn git_status(
+    state: State<'_, AppState>,
+    connection_id: String,
+    path: String,
+) -> Result<GitStatus, String> {
+    let output = run(&state, &connection_id, &status_command(&path)).await?;
+    Ok(parse_status(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// The most recent `limit` commits reachable from HEAD.
+#[tauri::command]
+pub async fn git_log(
+    state: State<'_, AppState>,
+    connection_id: String,
+    path: String,
+    limit: Option<u32>,
+) -> Result<Vec<GitCommit>, String> {
+    let command = log_command(&path, limit.unwrap_or(DEFAULT_LOG_LIMIT));
+    let output = run(&state, &connection_id, &command).await?;
+    if output.exit_status != 0 {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(parse_log(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Checked against the safety rules like any other exec; a git failure is
+/// reported in the result, not as `Err`.
+async fn run_action(
+    app: &AppHandle,
+    state: &AppState,
+    connection_id: &str,
+    command: &str,
+    confirmation: Option<&str>,
+) -> Result<GitActionResult, String> {
+    crate::safety::enforce(
+        app,
+        connection_id,
+        crate::safety::SafetyAction::Exec,
+        command,
+        confirmation,
+    )?;
+    let output = run(state, connection_id, command).await?;
+    Ok(parse_action(
+        &String::from_utf8_lossy(&output.stdout),
+        output.exit_status,
+    ))
+}
+
+/// `git pull --ff-only` in `path`.
+#[tauri::command]
+pub async fn git_pull(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    connection_id: String,
+    path: String,
+    confirmation: Option<String>,
+) -> Result<GitActionResult, String> {
+    run_action(
+        &app,
+        &state,
+        &connection_id,
+        &pull_command(&path),
+        confirmation.as_deref(),
+    )
+    .await
+}
+
+/// Switch `path` to `branch`, creating a tracking branch when only a remote
+/// has it. git refuses when local changes would be overwritten.
+#[tauri::command]
+pub async fn git_checkout(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    connection_id: String,
+    path: String,
+    branch: String,
+    confirmation: Option<String>,
+) -> Result<GitActionResult, String> {
+    validate_branch(&branch)?;
+    run_action(
+        &app,
+        &state,
+        &connection_id,
+        &checkout_command(&path, &branch),
+        confirmation.as_deref(),
+    )
+    .await
+}
@@ -0,0 +1,58 @@
+use super::{BundleState, HelperBundleStatus};
+use crate::commands::{ensure_connection_online, AppState};
+use tauri::{AppHandle, State};
+
+/// Installed version and checksum state of the helper bundle on a host.
+#[tauri::command]
+pub async fn remote_helpers_status(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    connection_id: String,
+) -> Result<HelperBundleStatus, String> {
+    ensure_connection_online(&state, &connection_id).await?;
+    super::status(&app, &state, &connection_id).await
+}
+
+/// Install the bundle, or reinstall it when `force` is set even if it is current.
+#[tauri::command]
+pub async fn remote_helpers_install(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    connection_id: String,
+    force: Option<bool>,
+) -> Result<HelperBundleStatus, String> {
+    ensure_connection_online(&state, &connection_id).await?;
+    if !force.unwrap_or(false) {
+        let current = super::status(&app, &state, &connection_id).await?;
+        if current.state == BundleState::Current {
+            return Ok(current);
+        }
+    }
+    super::install(&app, &state, &connection_id).await
+}
+
+/// Delete `~/.zync/helpers` from the host.
+#[tauri::command]
+pub async fn remote_helpers_remove(
+    state: State<'_, AppState>,
+    connection_id: String,
+) -> Result<(), String> {
+    ensure_connection_online(&state, &connection_id).await?;
+    super::remove(&state, &connection_id).await
+}
+
+/// Run bundled helper `name` with `args` and return its output, through the
+/// installed bundle when the host allows it and inline otherwise.
+#[tauri::command]
+pub async fn remote_helpers_run(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    connection_id: String,
+    name: String,
+    args: Option<Vec<String>>,
+) -> Result<String, String> {
+    ensure_connection_online(&state, &connection_id).await?;
+    let args = args.unwrap_or_default();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    super::run(&app, &state, &connection_id, &name, &args).await
+}
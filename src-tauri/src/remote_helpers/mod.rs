@@ -0,0 +1,532 @@
+//! Helper script bundle kept on remote hosts in `~/.zync/helpers`.
+//!
+//! Features run these scripts through [`command_for`] (or [`run`]) instead of
+//! shipping long one-off commands on every poll: dashboard monitoring polls
+//! `zync-stats`, and `zync-procs`/`zync-du` list processes and directory sizes.
+//! The bundle is versioned and checksummed: `ensure` installs it when missing,
+//! replaces it when the version differs or a file was changed on the host, and
+//! verifies the SHA-256 sums after every upload (when the host has `sha256sum`
+//! or `shasum`). Hosts where nothing may be written opt out in `settings.json`:
+//!
+//! ```json
+//! "remoteHelpers": { "enabled": true, "optOutTags": ["no-write"], "optOutConnections": [] }
+//! ```
+//!
+//! On opted-out hosts, or when the bundle can't be installed, [`command_for`]
+//! runs the same script inline through `sh -c`. Windows hosts get nothing
+//! installed: the PowerShell versions run inline, with the same output format.
+
+pub mod commands;
+
+use crate::ai::tool_exec_support::{sh_command, shell_quote};
+use crate::commands::{exec_on_connection, read_effective_settings, AppState};
+use crate::remote_os::connection_is_windows;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::sync::{LazyLock, Mutex as StdMutex};
+use tauri::AppHandle;
+
+/// Bump whenever a script changes so hosts pick up the new bundle.
+pub const BUNDLE_VERSION: &str = "3";
+/// Remote install directory, for `sh` (`$HOME` expands at run time).
+pub const HELPERS_DIR: &str = "$HOME/.zync/helpers";
+const HEREDOC_MARKER: &str = "ZYNC_HELPER_EOF";
+
+/// `(file name, contents)` of every script in the bundle.
+pub const BUNDLE: &[(&str, &str)] = &[
+    ("zync-stats", include_str!("scripts/zync-stats.sh")),
+    ("zync-procs", include_str!("scripts/zync-procs.sh")),
+    ("zync-du", include_str!("scripts/zync-du.sh")),
+];
+
+/// PowerShell equivalents used on Windows hosts, by bundle file name.
+pub const WINDOWS_SCRIPTS: &[(&str, &str)] = &[
+    ("zync-stats", include_str!("scripts/zync-stats.ps1")),
+    ("zync-procs", include_str!("scripts/zync-procs.ps1")),
+    ("zync-du", include_str!("scripts/zync-du.ps1")),
+];
+
+/// Hosts verified during this run, so polling features don't re-check each time.
+static VERIFIED: LazyLock<StdMutex<HashSet<String>>> =
+    LazyLock::new(|| StdMutex::new(HashSet::new()));
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RemoteHelpersConfig {
+    /// When false no host gets the bundle.
+    pub enabled: bool,
+    /// Connections carrying one of these tags are never written to.
+    pub opt_out_tags: Vec<String>,
+    pub opt_out_connections: Vec<String>,
+}
+
+impl Default for RemoteHelpersConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            opt_out_tags: vec!["no-write".to_string()],
+            opt_out_connections: Vec::new(),
+        }
+    }
+}
+
+pub fn read_config(app: &AppHandle) -> RemoteHelpersConfig {
+    read_effective_settings(app)
+        .ok()
+        .and_then(|settings| settings.get("remoteHelpers").cloned())
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+pub fn is_opted_out(config: &RemoteHelpersConfig, connection_id: &str, tags: &[String]) -> bool {
+    !config.enabled
+        || config
+            .opt_out_connections
+            .iter()
+            .any(|id| id == connection_id)
+        || tags.iter().any(|tag| {
+            config
+                .opt_out_tags
+                .iter()
+                .any(|opt_out| opt_out.eq_ignore_ascii_case(tag))
+        })
+}
+
+fn opted_out(app: &AppHandle, connection_id: &str) -> bool {
+    let tags = crate::profiles::load_saved_connection(app, connection_id)
+        .and_then(|connection| connection.tags)
+        .unwrap_or_default();
+    is_opted_out(&read_config(app), connection_id, &tags)
+}
+
+pub fn checksum(contents: &str) -> String {
+    Sha256::digest(contents.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// `sha256sum`-compatible manifest of the bundle.
+pub fn manifest() -> String {
+    BUNDLE
+        .iter()
+        .map(|(name, contents)| format!("{}  {name}\n", checksum(contents)))
+        .collect()
+}
+
+fn file_names() -> String {
+    BUNDLE
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Prints `installed=`, `version=` and `checksum_tool=`, then one
+/// `<sha256>  <name>` line per bundled file present on the host.
+pub fn status_script() -> String {
+    format!(
+        r#"dir="{HELPERS_DIR}"
+if [ ! -d "$dir" ]; then echo "installed=0"; exit 0; fi
+echo "installed=1"
+echo "version=$(cat "$dir/VERSION" 2>/dev/null)"
+cd "$dir" || exit 1
+if command -v sha256sum >/dev/null 2>&1; then sum="sha256sum"
+elif command -v shasum >/dev/null 2>&1; then sum="shasum -a 256"
+else echo "checksum_tool="; exit 0; fi
+echo "checksum_tool=${{sum%% *}}"
+for f in {names}; do [ -f "$f" ] && $sum "$f"; done
+exit 0"#,
+        names = file_names()
+    )
+}
+
+/// Writes the bundle to a temporary directory next to the old one, checks the
+/// sums there and only then swaps it in, so a failed upload never leaves a
+/// half-written bundle behind.
+pub fn install_script() -> String {
+    let mut script = String::from(
+        r#"set -e
+umask 022
+base="$HOME/.zync"
+mkdir -p "$base"
+tmp="$base/.helpers.$$"
+rm -rf "$tmp"
+mkdir "$tmp"
+"#,
+    );
+    let mut files: Vec<(&str, String)> = BUNDLE
+        .iter()
+        .map(|(name, contents)| (*name, contents.to_string()))
+        .collect();
+    files.push(("VERSION", format!("{BUNDLE_VERSION}\n")));
+    files.push(("SHA256SUMS", manifest()));
+    for (name, contents) in &files {
+        let newline = if contents.ends_with('\n') { "" } else { "\n" };
+        script.push_str(&format!(
+            "cat > \"$tmp/{name}\" <<'{HEREDOC_MARKER}'\n{contents}{newline}{HEREDOC_MARKER}\n"
+        ));
+    }
+    script.push_str(&format!(
+        r#"cd "$tmp"
+chmod 755 {names}
+if command -v sha256sum >/dev/null 2>&1; then ok=0; sha256sum -c SHA256SUMS >/dev/null 2>&1 || ok=1
+elif command -v shasum >/dev/null 2>&1; then ok=0; shasum -a 256 -c SHA256SUMS >/dev/null 2>&1 || ok=1
+else ok=0; fi
+cd "$base"
+if [ "$ok" -ne 0 ]; then rm -rf "$tmp"; echo "Checksum mismatch after upload" >&2; exit 4; fi
+rm -rf "$base/helpers"
+mv "$tmp" "$base/helpers""#,
+        names = file_names()
+    ));
+    script
+}
+
+pub const REMOVE_SCRIPT: &str = r#"rm -rf "$HOME/.zync/helpers""#;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BundleState {
+    Current,
+    Missing,
+    /// Installed with a different `VERSION`.
+    Outdated,
+    /// Right version, but files are missing or don't match their checksums.
+    Modified,
+    OptedOut,
+    /// Windows host; the PowerShell equivalents run without installing anything.
+    Unsupported,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HelperBundleStatus {
+    pub connection_id: String,
+    pub state: BundleState,
+    pub expected_version: String,
+    pub remote_version: Option<String>,
+    /// False when the host has no checksum tool; `Current` then only means the
+    /// version matched.
+    pub checksums_verified: bool,
+    /// Bundled files missing or differing on the host.
+    pub mismatched: Vec<String>,
+}
+
+/// Compare the output of [`status_script`] with the bundle.
+pub fn parse_status(connection_id: &str, output: &str) -> HelperBundleStatus {
+    let mut installed = false;
+    let mut remote_version = None;
+    let mut checksum_tool = None;
+    let mut remote_sums: Vec<(String, String)> = Vec::new();
+    for line in output.lines().map(str::trim) {
+        if let Some((key, value)) = line.split_once('=') {
+            match key {
+                "installed" => installed = value == "1",
+                "version" => remote_version = Some(value.to_string()).filter(|v| !v.is_empty()),
+                "checksum_tool" => {
+                    checksum_tool = Some(value.to_string()).filter(|v| !v.is_empty())
+                }
+                _ => {}
+            }
+        } else if let Some((sum, name)) = line.split_once("  ") {
+            remote_sums.push((sum.to_ascii_lowercase(), name.trim().to_string()));
+        }
+    }
+
+    let checksums_verified = installed && checksum_tool.is_some();
+    let mismatched: Vec<String> = if checksums_verified {
+        BUNDLE
+            .iter()
+            .filter(|(name, contents)| {
+                let expected = checksum(contents);
+                !remote_sums
+                    .iter()
+                    .any(|(sum, remote)| remote == name && *sum == expected)
+            })
+            .map(|(name, _)| name.to_string())
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let state = if !installed {
+        BundleState::Missing
+    } else if remote_version.as_deref() != Some(BUNDLE_VERSION) {
+        BundleState::Outdated
+    } else if !mismatched.is_empty() {
+        BundleState::Modified
+    } else {
+        BundleState::Current
+    };
+    HelperBundleStatus {
+        connection_id: connection_id.to_string(),
+        state,
+        expected_version: BUNDLE_VERSION.to_string(),
+        remote_version,
+        checksums_verified,
+        mismatched,
+    }
+}
+
+fn unavailable_status(connection_id: &str, state: BundleState) -> HelperBundleStatus {
+    HelperBundleStatus {
+        connection_id: connection_id.to_string(),
+        state,
+        expected_version: BUNDLE_VERSION.to_string(),
+        remote_version: None,
+        checksums_verified: false,
+        mismatched: Vec::new(),
+    }
+}
+
+fn mark_verified(connection_id: &str, verified: bool) {
+    let mut cache = VERIFIED
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if verified {
+        cache.insert(connection_id.to_string());
+    } else {
+        cache.remove(connection_id);
+    }
+}
+
+fn is_verified(connection_id: &str) -> bool {
+    VERIFIED
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .contains(connection_id)
+}
+
+pub(crate) async fn status(
+    app: &AppHandle,
+    state: &AppState,
+    connection_id: &str,
+) -> Result<HelperBundleStatus, String> {
+    if opted_out(app, connection_id) {
+        return Ok(unavailable_status(connection_id, BundleState::OptedOut));
+    }
+    if connection_is_windows(state, connection_id).await {
+        return Ok(unavailable_status(connection_id, BundleState::Unsupported));
+    }
+    let output = exec_on_connection(state, connection_id, &status_script()).await?;
+    if output.exit_status != 0 {
+        return Err(format!(
+            "Helper status check failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let status = parse_status(connection_id, &String::from_utf8_lossy(&output.stdout));
+    mark_verified(connection_id, status.state == BundleState::Current);
+    Ok(status)
+}
+
+/// Upload the bundle (unconditionally) and confirm the host now reports it current.
+pub(crate) async fn install(
+    app: &AppHandle,
+    state: &AppState,
+    connection_id: &str,
+) -> Result<HelperBundleStatus, String> {
+    if opted_out(app, connection_id) {
+        return Err("Remote helpers are disabled for this host".to_string());
+    }
+    if connection_is_windows(state, connection_id).await {
+        return Err(
+            "Windows hosts run the PowerShell helpers inline; nothing to install".to_string(),
+        );
+    }
+    mark_verified(connection_id, false);
+    let output = exec_on_connection(state, connection_id, &install_script()).await?;
+    if output.exit_status != 0 {
+        return Err(format!(
+            "Failed to install helper scripts: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let status = status(app, state, connection_id).await?;
+    if status.state != BundleState::Current {
+        return Err(format!(
+            "Helper scripts did not verify after install ({:?})",
+            status.state
+        ));
+    }
+    println!("[HELPERS] Installed bundle v{BUNDLE_VERSION} on {connection_id}");
+    Ok(status)
+}
+
+pub(crate) async fn remove(state: &AppState, connection_id: &str) -> Result<(), String> {
+    mark_verified(connection_id, false);
+    if connection_is_windows(state, connection_id).await {
+        return Ok(());
+    }
+    let output = exec_on_connection(state, connection_id, REMOVE_SCRIPT).await?;
+    if output.exit_status != 0 {
+        return Err(format!(
+            "Failed to remove helper scripts: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Make sure the current bundle is on the host. Errors when the host opted out,
+/// so callers can fall back to inline commands.
+pub(crate) async fn ensure(
+    app: &AppHandle,
+    state: &AppState,
+    connection_id: &str,
+) -> Result<(), String> {
+    if is_verified(connection_id) && !opted_out(app, connection_id) {
+        return Ok(());
+    }
+    match status(app, state, connection_id).await?.state {
+        BundleState::Current => Ok(()),
+        BundleState::OptedOut => Err("Remote helpers are disabled for this host".to_string()),
+        BundleState::Unsupported => Err("Windows hosts have no helper bundle".to_string()),
+        _ => install(app, state, connection_id).await.map(|_| ()),
+    }
+}
+
+/// Command line running bundled script `name` with `args` (shell-quoted).
+pub fn helper_command(name: &str, args: &[&str]) -> String {
+    let mut command = format!("\"{HELPERS_DIR}/{name}\"");
+    for arg in args {
+        command.push(' ');
+        command.push_str(&shell_quote(arg));
+    }
+    command
+}
+
+/// `name` from [`BUNDLE`] run inline through `sh -c`, for hosts without the
+/// installed bundle. Output is the same as the installed script's.
+pub fn inline_command(name: &str, args: &[&str]) -> Option<String> {
+    let (_, script) = BUNDLE
+        .iter()
+        .find(|(script_name, _)| *script_name == name)?;
+    Some(sh_command(script, args))
+}
+
+/// Command running helper `name` on the host: the installed script (installing
+/// the bundle first if needed), its PowerShell version on Windows, or the
+/// script inline when the host opted out or the bundle can't be installed.
+pub(crate) async fn command_for(
+    app: &AppHandle,
+    state: &AppState,
+    connection_id: &str,
+    name: &str,
+    args: &[&str],
+) -> Result<String, String> {
+    if connection_is_windows(state, connection_id).await {
+        let (_, script) = WINDOWS_SCRIPTS
+            .iter()
+            .find(|(script_name, _)| *script_name == name)
+            .ok_or_else(|| format!("Unknown helper '{name}'"))?;
+        return Ok(crate::remote_os::powershell_command(script, args));
+    }
+    let inline = inline_command(name, args).ok_or_else(|| format!("Unknown helper '{name}'"))?;
+    if opted_out(app, connection_id) {
+        return Ok(inline);
+    }
+    match ensure(app, state, connection_id).await {
+        Ok(()) => Ok(helper_command(name, args)),
+        Err(e) => {
+            eprintln!("[HELPERS] {connection_id}: {e}; running {name} inline");
+            Ok(inline)
+        }
+    }
+}
+
+/// Run helper `name` on the host through [`command_for`] and return its output.
+pub(crate) async fn run(
+    app: &AppHandle,
+    state: &AppState,
+    connection_id: &str,
+    name: &str,
+    args: &[&str],
+) -> Result<String, String> {
+    let command = command_for(app, state, connection_id, name, args).await?;
+    let output = exec_on_connection(state, connection_id, &command).await?;
+    if output.exit_status != 0 {
+        return Err(format!(
+            "{name} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_output_is_compared_with_the_bundle() {
+        let sums: String = BUNDLE
+            .iter()
+            .map(|(name, contents)| format!("{}  {name}\n", checksum(contents)))
+            .collect();
+        let current =
+            format!("installed=1\nversion={BUNDLE_VERSION}\nchecksum_tool=sha256sum\n{sums}");
+        assert_eq!(parse_status("c", &current).state, BundleState::Current);
+
+        let tampered = current.replacen(&checksum(BUNDLE[0].1), &"0".repeat(64), 1);
+        let status = parse_status("c", &tampered);
+        assert_eq!(status.state, BundleState::Modified);
+        assert_eq!(status.mismatched, vec![BUNDLE[0].0.to_string()]);
+
+        assert_eq!(
+            parse_status("c", "installed=0\n").state,
+            BundleState::Missing
+        );
+        assert_eq!(
+            parse_status("c", "installed=1\nversion=0\nchecksum_tool=\n").state,
+            BundleState::Outdated
+        );
+        let unverified = parse_status(
+            "c",
+            &format!("installed=1\nversion={BUNDLE_VERSION}\nchecksum_tool=\n"),
+        );
+        assert_eq!(unverified.state, BundleState::Current);
+        assert!(!unverified.checksums_verified);
+    }
+
+    #[test]
+    fn inline_commands_run_the_bundled_script_with_quoted_args() {
+        let command = inline_command("zync-du", &["/srv/it's"]).expect("bundled");
+        assert!(command.starts_with("sh -c '#!/bin/sh"));
+        assert!(command.ends_with(" sh '/srv/it'\\''s'"));
+        assert_eq!(inline_command("zync-missing", &[]), None);
+    }
+
+    #[test]
+    fn install_script_embeds_every_file_safely() {
+        let script = install_script();
+        for (name, contents) in BUNDLE {
+            assert!(!contents.contains(HEREDOC_MARKER));
+            assert!(script.contains(&format!("cat > \"$tmp/{name}\" <<'{HEREDOC_MARKER}'\n")));
+        }
+        assert!(script.contains(&manifest()));
+    }
+
+    #[test]
+    fn every_helper_has_a_windows_version() {
+        let names: Vec<&str> = BUNDLE.iter().map(|(name, _)| *name).collect();
+        let windows: Vec<&str> = WINDOWS_SCRIPTS.iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, windows);
+    }
+
+    #[test]
+    fn opt_out_by_tag_connection_or_globally() {
+        let config = RemoteHelpersConfig {
+            opt_out_connections: vec!["locked".to_string()],
+            ..Default::default()
+        };
+        assert!(!is_opted_out(&config, "web", &["prod".to_string()]));
+        assert!(is_opted_out(&config, "web", &["No-Write".to_string()]));
+        assert!(is_opted_out(&config, "locked", &[]));
+        let disabled = RemoteHelpersConfig {
+            enabled: false,
+            ..Default::default()
+        };
+        assert!(is_opted_out(&disabled, "web", &[]));
+    }
+}
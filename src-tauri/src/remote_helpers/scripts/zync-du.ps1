@@ -0,0 +1,16 @@
+# zync-du for Windows hosts: KiB per entry under the given directory, largest first.
+$dir = '.'
+if ($ZyncArgs.Count -gt 0) { $dir = $ZyncArgs[0] }
+if (-not (Test-Path -LiteralPath $dir -PathType Container)) {
+  [Console]::Error.WriteLine("zync-du: not a directory: $dir")
+  exit 2
+}
+Get-ChildItem -LiteralPath $dir -Force -ErrorAction SilentlyContinue |
+  ForEach-Object {
+    $bytes = if ($_.PSIsContainer) {
+      (Get-ChildItem -LiteralPath $_.FullName -Recurse -Force -File -ErrorAction SilentlyContinue | Measure-Object -Property Length -Sum).Sum
+    } else { $_.Length }
+    [pscustomobject]@{ Kb = [int64][math]::Ceiling([double]$bytes / 1KB); Name = $_.Name }
+  } |
+  Sort-Object Kb -Descending |
+  ForEach-Object { "$($_.Kb)`t$($_.Name)" }
@@ -0,0 +1,15 @@
+#!/bin/sh
+# zync-du <path>: size in KiB of each entry directly under <path>, largest
+# first, tab separated (disk analyzer). Stays on one filesystem.
+
+dir=${1:-.}
+if [ ! -d "$dir" ]; then
+  echo "zync-du: not a directory: $dir" >&2
+  exit 2
+fi
+
+cd "$dir" || exit 1
+for entry in .[!.]* ..?* *; do
+  [ -e "$entry" ] || [ -L "$entry" ] || continue
+  du -skx -- "$entry" 2>/dev/null
+done | sort -rn | awk -F '\t' 'BEGIN { OFS = "\t" } { print $1, $2 }'
@@ -0,0 +1,18 @@
+# zync-procs for Windows hosts: same columns as zync-procs.sh; `elapsed` is in
+# seconds and `user` is left empty (reading owners needs elevation).
+$limit = 50
+if ($ZyncArgs.Count -gt 0) { $limit = [int]$ZyncArgs[0] }
+$inv = [Globalization.CultureInfo]::InvariantCulture
+$totalKb = (Get-CimInstance Win32_OperatingSystem).TotalVisibleMemorySize
+$parents = @{}
+Get-CimInstance Win32_Process | ForEach-Object { $parents[[int]$_.ProcessId] = $_.ParentProcessId }
+"pid`tppid`tuser`tcpu`tmem`trss_kb`telapsed`tcommand"
+Get-CimInstance Win32_PerfFormattedData_PerfProc_Process |
+  Where-Object { $_.IDProcess -ne 0 } |
+  Sort-Object PercentProcessorTime -Descending |
+  Select-Object -First $limit |
+  ForEach-Object {
+    $rss = [int64]($_.WorkingSet / 1KB)
+    $mem = if ($totalKb) { ($rss * 100.0 / $totalKb).ToString('0.0', $inv) } else { '' }
+    "$($_.IDProcess)`t$($parents[[int]$_.IDProcess])`t`t$($_.PercentProcessorTime)`t$mem`t$rss`t$($_.ElapsedTime)`t$($_.Name)"
+  }
@@ -0,0 +1,32 @@
+#!/bin/sh
+# zync-procs [limit]: processes by CPU use, tab separated with a header
+# (process manager). Defaults to 50 rows. BusyBox `ps` often lacks `-o`;
+# /proc is read directly then, with CPU and memory shares left at 0.
+
+limit=${1:-50}
+case $limit in
+  '' | *[!0-9]*) echo "usage: zync-procs [limit]" >&2; exit 2 ;;
+esac
+
+proc_table() {
+  for dir in /proc/[0-9]*; do
+    read -r stat < "$dir/stat" 2>/dev/null || continue
+    set -- ${stat##*") "}
+    uid=$(awk '/^Uid:/ { print $2; exit }' "$dir/status" 2>/dev/null)
+    rss=$(awk '/^VmRSS:/ { print $2; exit }' "$dir/status" 2>/dev/null)
+    user=$(awk -F: -v uid="$uid" '$3 == uid { print $1; exit }' /etc/passwd 2>/dev/null)
+    echo "${dir#/proc/} $2 ${user:-$uid} 0.0 0.0 ${rss:-0} - $(cat "$dir/comm" 2>/dev/null)"
+  done
+}
+
+table=$(ps -A -o pid= -o ppid= -o user= -o pcpu= -o pmem= -o rss= -o etime= -o comm= 2>/dev/null)
+if [ -z "$table" ] && [ -d /proc/1 ]; then
+  table=$(proc_table)
+fi
+
+printf 'pid\tppid\tuser\tcpu\tmem\trss_kb\telapsed\tcommand\n'
+printf '%s\n' "$table" |
+  awk 'NF' |
+  sort -k4,4rn -k6,6rn |
+  head -n "$limit" |
+  awk 'BEGIN { OFS = "\t" } { command = $8; for (i = 9; i <= NF; i++) command = command " " $i; print $1, $2, $3, $4, $5, $6, $7, command }'
@@ -0,0 +1,21 @@
+# zync-stats for Windows hosts: same keys as zync-stats.sh.
+$inv = [Globalization.CultureInfo]::InvariantCulture
+$os = Get-CimInstance Win32_OperatingSystem
+$cpu = (Get-CimInstance Win32_Processor | Measure-Object -Property LoadPercentage -Average).Average
+"cpu_percent=$(if ($null -ne $cpu) { ([double]$cpu).ToString('0.0', $inv) })"
+"mem_total_kb=$($os.TotalVisibleMemorySize)"
+"mem_available_kb=$($os.FreePhysicalMemory)"
+"swap_total_kb=$($os.SizeStoredInPagingFiles)"
+"swap_free_kb=$($os.FreeSpaceInPagingFiles)"
+"load="
+"uptime_seconds=$([int64]((Get-Date) - $os.LastBootUpTime).TotalSeconds)"
+"cpu_count=$([Environment]::ProcessorCount)"
+$disk = Get-CimInstance Win32_LogicalDisk -Filter "DeviceID='$env:SystemDrive'"
+if ($disk) {
+  "root_total_kb=$([int64]($disk.Size / 1KB))"
+  "root_used_kb=$([int64](($disk.Size - $disk.FreeSpace) / 1KB))"
+}
+"processes=$((Get-Process).Count)"
+"os_name=$($os.Caption)"
+"kernel=$($os.Version)"
+"arch=$env:PROCESSOR_ARCHITECTURE"
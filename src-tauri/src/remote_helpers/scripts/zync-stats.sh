@@ -0,0 +1,41 @@
+#!/bin/sh
+# zync-stats: one resource snapshot and the OS name, kernel and architecture
+# as key=value lines (monitoring).
+# Values that can't be read on this platform are left empty.
+
+cpu_sample() {
+  awk '/^cpu / { idle = $5 + $6; total = 0; for (i = 2; i <= NF; i++) total += $i; print total, idle; exit }' /proc/stat 2>/dev/null
+}
+
+if [ -r /proc/stat ]; then
+  set -- $(cpu_sample)
+  t1=$1 i1=$2
+  sleep 1
+  set -- $(cpu_sample)
+  t2=$1 i2=$2
+  echo "cpu_percent=$(awk -v t="$((t2 - t1))" -v i="$((i2 - i1))" 'BEGIN { if (t > 0) printf "%.1f", (t - i) * 100 / t }')"
+else
+  echo "cpu_percent="
+fi
+
+if [ -r /proc/meminfo ]; then
+  awk '/^MemTotal:/ { total = $2 } /^MemAvailable:/ { avail = $2 } /^SwapTotal:/ { swap = $2 } /^SwapFree:/ { swapfree = $2 }
+    END { print "mem_total_kb=" total; print "mem_available_kb=" avail; print "swap_total_kb=" swap; print "swap_free_kb=" swapfree }' /proc/meminfo
+else
+  echo "mem_total_kb=$( (sysctl -n hw.memsize 2>/dev/null || echo) | awk 'NF { print int($1 / 1024) }')"
+  echo "mem_available_kb="
+fi
+
+if [ -r /proc/loadavg ]; then
+  echo "load=$(cut -d' ' -f1-3 /proc/loadavg)"
+else
+  echo "load=$(uptime 2>/dev/null | sed -n 's/.*load averages*: *//p' | tr -d ',')"
+fi
+echo "uptime_seconds=$(cut -d. -f1 /proc/uptime 2>/dev/null)"
+echo "cpu_count=$(getconf _NPROCESSORS_ONLN 2>/dev/null || grep -c '^processor' /proc/cpuinfo 2>/dev/null)"
+df -Pk / 2>/dev/null | awk 'NR == 2 { print "root_total_kb=" $2; print "root_used_kb=" $3 }'
+echo "processes=$(ps -A 2>/dev/null | tail -n +2 | wc -l | tr -d ' ')"
+os_name=$(sed -n 's/^PRETTY_NAME=//p' /etc/os-release 2>/dev/null | tr -d '"')
+echo "os_name=${os_name:-$(uname -s)}"
+echo "kernel=$(uname -r)"
+echo "arch=$(uname -m)"
@@ -0,0 +1,165 @@
+//! Differences between Unix and Windows (OpenSSH for Windows) remote hosts.
+//!
+//! Windows hosts are detected at connect time (`detected_os == "windows"`).
+//! Their exec channel runs the OpenSSH `DefaultShell` (cmd.exe unless changed),
+//! so probes are sent as `powershell -EncodedCommand`, which behaves the same
+//! under cmd, Windows PowerShell and pwsh. SFTP on Windows expects `/C:/dir`
+//! paths with forward slashes, and text files usually end lines with CRLF.
+
+use crate::commands::AppState;
+use base64::Engine;
+
+/// Prints the OpenSSH `DefaultShell` value; exits non-zero when it isn't set.
+/// `reg.exe` runs the same from cmd and PowerShell.
+pub const DEFAULT_SHELL_QUERY: &str = r#"reg query "HKLM\SOFTWARE\OpenSSH" /v DefaultShell"#;
+
+pub fn is_windows(os: Option<&str>) -> bool {
+    os.is_some_and(|os| os.eq_ignore_ascii_case("windows"))
+}
+
+pub(crate) async fn connection_is_windows(state: &AppState, connection_id: &str) -> bool {
+    let connections = state.connections.lock().await;
+    is_windows(
+        connections
+            .get(connection_id)
+            .and_then(|connection| connection.detected_os.as_deref()),
+    )
+}
+
+/// Shell id (`cmd`, `powershell`, `pwsh`, or the executable's base name) from
+/// [`DEFAULT_SHELL_QUERY`] output. No value means OpenSSH's own default, cmd.
+pub fn parse_default_shell(reg_output: &str) -> String {
+    let value = reg_output.lines().find_map(|line| {
+        let line = line.trim();
+        let rest = line.strip_prefix("DefaultShell")?;
+        let rest = rest.trim_start().strip_prefix("REG_")?;
+        let (_, data) = rest.split_once(char::is_whitespace)?;
+        Some(data.trim().trim_matches('"').to_string())
+    });
+    let Some(value) = value.filter(|value| !value.is_empty()) else {
+        return "cmd".to_string();
+    };
+    let name = value
+        .rsplit(['\\', '/'])
+        .next()
+        .unwrap_or(&value)
+        .to_ascii_lowercase();
+    name.strip_suffix(".exe").unwrap_or(&name).to_string()
+}
+
+/// A PowerShell script as one exec command, independent of the default shell.
+/// `args` are available to the script as `$ZyncArgs`.
+pub fn powershell_command(script: &str, args: &[&str]) -> String {
+    let args: Vec<String> = args
+        .iter()
+        .map(|arg| format!("'{}'", arg.replace('\'', "''")))
+        .collect();
+    let full = format!(
+        "$ProgressPreference = 'SilentlyContinue'\n$ZyncArgs = @({})\n{script}",
+        args.join(", ")
+    );
+    let utf16: Vec<u8> = full.encode_utf16().flat_map(u16::to_le_bytes).collect();
+    format!(
+        "powershell -NoProfile -NonInteractive -ExecutionPolicy Bypass -EncodedCommand {}",
+        base64::engine::general_purpose::STANDARD.encode(utf16)
+    )
+}
+
+/// SFTP form of a path typed Windows-style: `C:\Users\me` → `/C:/Users/me`.
+/// Paths already in SFTP form are returned unchanged.
+pub fn sftp_path(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    let bytes = path.as_bytes();
+    if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+        let rest = &path[2..];
+        let rest = if rest.is_empty() { "/" } else { rest };
+        format!("/{}:{rest}", path[..1].to_ascii_uppercase())
+    } else {
+        path
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    Lf,
+    CrLf,
+}
+
+/// The line ending used by the majority of lines, `None` for single-line text.
+pub fn detect_line_ending(text: &str) -> Option<LineEnding> {
+    let newlines = text.matches('\n').count();
+    if newlines == 0 {
+        return None;
+    }
+    let crlf = text.matches("\r\n").count();
+    Some(if crlf * 2 > newlines {
+        LineEnding::CrLf
+    } else {
+        LineEnding::Lf
+    })
+}
+
+/// `text` with every line ending converted to `ending`.
+pub fn with_line_ending(text: &str, ending: LineEnding) -> String {
+    let lf = text.replace("\r\n", "\n");
+    match ending {
+        LineEnding::Lf => lf,
+        LineEnding::CrLf => lf.replace('\n', "\r\n"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_openssh_default_shell() {
+        let output = "\r\nHKEY_LOCAL_MACHINE\\SOFTWARE\\OpenSSH\r\n    DefaultShell    REG_SZ    C:\\Program Files\\PowerShell\\7\\pwsh.exe\r\n\r\n";
+        assert_eq!(parse_default_shell(output), "pwsh");
+        assert_eq!(
+            parse_default_shell(
+                "    DefaultShell    REG_SZ    C:\\Windows\\System32\\WindowsPowerShell\\v1.0\\powershell.exe"
+            ),
+            "powershell"
+        );
+        assert_eq!(
+            parse_default_shell(
+                "ERROR: The system was unable to find the specified registry key or value."
+            ),
+            "cmd"
+        );
+    }
+
+    #[test]
+    fn windows_paths_become_sftp_paths() {
+        assert_eq!(sftp_path("C:\\Users\\me\\app.log"), "/C:/Users/me/app.log");
+        assert_eq!(sftp_path("d:"), "/D:/");
+        assert_eq!(sftp_path("/C:/Users/me"), "/C:/Users/me");
+        assert_eq!(sftp_path("/home/me"), "/home/me");
+    }
+
+    #[test]
+    fn line_endings_follow_the_existing_file() {
+        assert_eq!(detect_line_ending("a\r\nb\r\nc\n"), Some(LineEnding::CrLf));
+        assert_eq!(detect_line_ending("a\nb\r\n"), Some(LineEnding::Lf));
+        assert_eq!(detect_line_ending("one line"), None);
+        assert_eq!(with_line_ending("a\nb\r\n", LineEnding::CrLf), "a\r\nb\r\n");
+        assert_eq!(with_line_ending("a\r\nb\n", LineEnding::Lf), "a\nb\n");
+    }
+
+    #[test]
+    fn powershell_commands_are_encoded_utf16() {
+        let command = powershell_command("Write-Output $ZyncArgs[0]", &["it's"]);
+        let encoded = command.rsplit(' ').next().expect("payload");
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .expect("base64");
+        let units: Vec<u16> = bytes
+            .chunks(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        let script = String::from_utf16(&units).expect("utf16");
+        assert!(script.contains("$ZyncArgs = @('it''s')\n"));
+        assert!(script.ends_with("Write-Output $ZyncArgs[0]"));
+    }
+}
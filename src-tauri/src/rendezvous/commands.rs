@@ -0,0 +1,279 @@
+use super::{
+    authorized_key_line, install_unit_script, key_marker, key_options, remove_key_script,
+    remove_unit_script, systemd_unit, unit_name, unit_status_command, Rendezvous,
+    RendezvousRequest, KEY_SCRIPT,
+};
+use crate::commands::{exec_on_connection, exec_on_connection_with_stdin, get_data_dir, AppState};
+use serde::Serialize;
+use tauri::{AppHandle, State};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RendezvousPreview {
+    pub rendezvous: Rendezvous,
+    /// Unit file for the home machine (`ssh` path is resolved there on install).
+    pub unit: String,
+    /// `authorized_keys` line for the VPS, with a placeholder key.
+    pub authorized_key: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RendezvousSetup {
+    pub rendezvous: Rendezvous,
+    pub public_key: String,
+    /// The unit only runs while the user is logged in (lingering refused).
+    pub needs_login: bool,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RendezvousStatus {
+    pub id: String,
+    /// Whether the VPS has the port open; `None` when the VPS isn't connected.
+    pub listening: Option<bool>,
+    /// `systemctl --user is-active` on the home machine, when connected.
+    pub service: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+async fn connection_config(
+    state: &AppState,
+    connection_id: &str,
+) -> Result<crate::types::ConnectionConfig, String> {
+    let connections = state.connections.lock().await;
+    connections
+        .get(connection_id)
+        .filter(|handle| handle.session.is_some())
+        .map(|handle| handle.config.clone())
+        .ok_or_else(|| format!("Connect to {connection_id} first"))
+}
+
+async fn is_connected(state: &AppState, connection_id: &str) -> bool {
+    connection_config(state, connection_id).await.is_ok()
+}
+
+/// Resolve a request against the VPS connection's saved address.
+async fn plan(state: &AppState, request: RendezvousRequest) -> Result<Rendezvous, String> {
+    if request.home_connection_id == request.vps_connection_id {
+        return Err("The home machine and the VPS must be different connections".to_string());
+    }
+    if request.remote_port == 0 {
+        return Err("Choose the port the VPS should listen on".to_string());
+    }
+    let vps = connection_config(state, &request.vps_connection_id).await?;
+    let name = request.name.trim();
+    let name = if name.is_empty() {
+        vps.name.trim()
+    } else {
+        name
+    }
+    .to_string();
+    let (vps_host, vps_port) = match request.vps_host.as_deref().map(str::trim) {
+        Some(address) if !address.is_empty() => match address.rsplit_once(':') {
+            Some((host, port)) if !host.contains(':') => (
+                host.to_string(),
+                port.parse()
+                    .map_err(|_| format!("Invalid VPS port in {address}"))?,
+            ),
+            _ => (address.to_string(), vps.port),
+        },
+        _ => (vps.host.clone(), vps.port),
+    };
+    Ok(Rendezvous {
+        id: uuid::Uuid::new_v4().to_string(),
+        unit: unit_name(&name),
+        name,
+        home_connection_id: request.home_connection_id,
+        vps_connection_id: request.vps_connection_id,
+        remote_port: request.remote_port,
+        target_host: request
+            .target_host
+            .map(|host| host.trim().to_string())
+            .filter(|host| !host.is_empty())
+            .unwrap_or_else(|| "localhost".to_string()),
+        target_port: request.target_port.unwrap_or(22),
+        vps_host,
+        vps_port,
+        vps_user: vps.username,
+        created_at: now_ms(),
+    })
+}
+
+fn exec_error(step: &str, output: &crate::commands::ExecOutput) -> String {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    format!(
+        "{step} failed (exit {}): {}",
+        output.exit_status,
+        stderr.trim()
+    )
+}
+
+/// The unit and key line a setup would install, without touching either host.
+#[tauri::command]
+pub async fn rendezvous_preview(
+    request: RendezvousRequest,
+    state: State<'_, AppState>,
+) -> Result<RendezvousPreview, String> {
+    let rendezvous = plan(&state, request).await?;
+    let authorized_key = format!(
+        "{} <key generated on the home machine>",
+        key_options(&rendezvous)
+    );
+    Ok(RendezvousPreview {
+        unit: systemd_unit(&rendezvous, "/usr/bin/ssh"),
+        authorized_key,
+        rendezvous,
+    })
+}
+
+/// Create the key on the home machine, authorize it on the VPS, install and
+/// start the unit, then remember the setup for monitoring.
+#[tauri::command]
+pub async fn rendezvous_setup(
+    app: AppHandle,
+    request: RendezvousRequest,
+    state: State<'_, AppState>,
+) -> Result<RendezvousSetup, String> {
+    let rendezvous = plan(&state, request).await?;
+    connection_config(&state, &rendezvous.home_connection_id).await?;
+
+    let output = exec_on_connection(&state, &rendezvous.home_connection_id, KEY_SCRIPT).await?;
+    if output.exit_status != 0 {
+        return Err(exec_error("Creating the key on the home machine", &output));
+    }
+    let public_key = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let key_line = authorized_key_line(&public_key, &rendezvous)
+        .ok_or_else(|| format!("Unexpected public key from the home machine: {public_key}"))?;
+    let install = crate::key_rotation::install_script(&key_line, &key_marker(&rendezvous.id));
+    let output = exec_on_connection(&state, &rendezvous.vps_connection_id, &install).await?;
+    if output.exit_status != 0 {
+        return Err(exec_error("Authorizing the key on the VPS", &output));
+    }
+
+    let unit = systemd_unit(&rendezvous, "/usr/bin/ssh");
+    let output = exec_on_connection_with_stdin(
+        &state,
+        &rendezvous.home_connection_id,
+        &install_unit_script(&rendezvous.unit),
+        unit.as_bytes(),
+    )
+    .await?;
+    if output.exit_status != 0 {
+        return Err(exec_error("Installing the systemd unit", &output));
+    }
+    let needs_login = String::from_utf8_lossy(&output.stdout).contains("linger=off");
+
+    super::upsert(&get_data_dir(&app), rendezvous.clone())?;
+    Ok(RendezvousSetup {
+        rendezvous,
+        public_key,
+        needs_login,
+    })
+}
+
+#[tauri::command]
+pub async fn rendezvous_list(app: AppHandle) -> Result<Vec<Rendezvous>, String> {
+    super::list(&get_data_dir(&app))
+}
+
+/// Check both ends over whichever sessions are open.
+#[tauri::command]
+pub async fn rendezvous_status(
+    app: AppHandle,
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<RendezvousStatus, String> {
+    use crate::tunnels::listening_ports::{parse_listening_ports, LISTENING_PORTS_SCRIPT};
+
+    let rendezvous = super::list(&get_data_dir(&app))?
+        .into_iter()
+        .find(|r| r.id == id)
+        .ok_or_else(|| "Rendezvous not found".to_string())?;
+    let mut status = RendezvousStatus {
+        id,
+        ..Default::default()
+    };
+    if is_connected(&state, &rendezvous.vps_connection_id).await {
+        match exec_on_connection(
+            &state,
+            &rendezvous.vps_connection_id,
+            LISTENING_PORTS_SCRIPT,
+        )
+        .await
+        {
+            Ok(output) => {
+                let ports = parse_listening_ports(&String::from_utf8_lossy(&output.stdout));
+                status.listening = Some(
+                    ports
+                        .iter()
+                        .any(|entry| entry.port == rendezvous.remote_port),
+                );
+            }
+            Err(error) => status.error = Some(error),
+        }
+    }
+    if is_connected(&state, &rendezvous.home_connection_id).await {
+        let command = unit_status_command(&rendezvous.unit);
+        match exec_on_connection(&state, &rendezvous.home_connection_id, &command).await {
+            Ok(output) => {
+                let active = String::from_utf8_lossy(&output.stdout).trim().to_string();
+                status.service = Some(if active.is_empty() {
+                    "unknown".to_string()
+                } else {
+                    active
+                });
+            }
+            Err(error) => status.error = Some(error),
+        }
+    }
+    Ok(status)
+}
+
+/// Forget a setup; with `teardown`, also stop the unit and revoke the key on
+/// whichever ends are connected. Returns what could not be cleaned up.
+#[tauri::command]
+pub async fn rendezvous_remove(
+    app: AppHandle,
+    id: String,
+    teardown: bool,
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    let Some(rendezvous) = super::remove(&get_data_dir(&app), &id)? else {
+        return Ok(Vec::new());
+    };
+    let mut left = Vec::new();
+    if !teardown {
+        return Ok(left);
+    }
+    let steps = [
+        (
+            &rendezvous.home_connection_id,
+            remove_unit_script(&rendezvous.unit),
+            format!("systemd unit {} on the home machine", rendezvous.unit),
+        ),
+        (
+            &rendezvous.vps_connection_id,
+            remove_key_script(&rendezvous.id),
+            "rendezvous key in the VPS authorized_keys".to_string(),
+        ),
+    ];
+    for (connection_id, script, what) in steps {
+        let done = is_connected(&state, connection_id).await
+            && exec_on_connection(&state, connection_id, &script)
+                .await
+                .is_ok_and(|output| output.exit_status == 0);
+        if !done {
+            left.push(what);
+        }
+    }
+    Ok(left)
+}
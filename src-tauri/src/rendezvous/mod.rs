@@ -0,0 +1,300 @@
+//! Homelab rendezvous: a persistent `ssh -R` from a machine behind NAT to a
+//! VPS the user owns, so the home machine stays reachable through the VPS.
+//!
+//! Set up over two open sessions:
+//!
+//! 1. On the home machine, a dedicated key (`~/.ssh/zync_rendezvous`) is
+//!    created if missing.
+//! 2. On the VPS, its public key is authorized for nothing but listening on
+//!    the one loopback port (`restrict,port-forwarding,permitlisten=...`).
+//! 3. On the home machine, a systemd user unit keeps the forward up the way
+//!    autossh would: keepalives, exit on a failed forward, restart on exit.
+//!
+//! The forward listens on the VPS's loopback only; the home machine is then
+//! reached with the VPS as jump host. Setups are kept in
+//! `<data dir>/rendezvous.json` so Zync can keep checking both ends.
+
+pub mod commands;
+
+use crate::ai::tool_exec_support::shell_quote;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex as StdMutex};
+
+const STORE_FILE: &str = "rendezvous.json";
+pub const KEY_PATH: &str = "~/.ssh/zync_rendezvous";
+/// Marks the VPS `authorized_keys` line so removal can find it.
+const KEY_MARKER: &str = "zync-rendezvous:";
+
+static STORE_LOCK: LazyLock<StdMutex<()>> = LazyLock::new(|| StdMutex::new(()));
+
+/// Creates the key pair unless it exists and prints the public key.
+pub const KEY_SCRIPT: &str = r#"set -e
+k="$HOME/.ssh/zync_rendezvous"
+umask 077
+mkdir -p "$HOME/.ssh"
+[ -f "$k" ] || ssh-keygen -q -t ed25519 -N '' -C "zync-rendezvous@$(hostname)" -f "$k"
+cat "$k.pub""#;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RendezvousRequest {
+    pub name: String,
+    pub home_connection_id: String,
+    pub vps_connection_id: String,
+    /// Port the VPS listens on (loopback).
+    pub remote_port: u16,
+    /// What the forward reaches from the home machine; `localhost:22` by default.
+    pub target_host: Option<String>,
+    pub target_port: Option<u16>,
+    /// VPS address as the home machine sees it, when it differs from the saved host.
+    pub vps_host: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rendezvous {
+    pub id: String,
+    pub name: String,
+    pub home_connection_id: String,
+    pub vps_connection_id: String,
+    pub remote_port: u16,
+    pub target_host: String,
+    pub target_port: u16,
+    pub vps_host: String,
+    pub vps_port: u16,
+    pub vps_user: String,
+    /// systemd user unit on the home machine.
+    pub unit: String,
+    pub created_at: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Store {
+    #[serde(default)]
+    rendezvous: Vec<Rendezvous>,
+}
+
+/// `zync-rendezvous-<slug>.service`.
+pub fn unit_name(name: &str) -> String {
+    let slug: String = name
+        .trim()
+        .to_ascii_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let slug = slug.trim_matches('-');
+    let slug = if slug.is_empty() { "home" } else { slug };
+    format!("zync-rendezvous-{slug}.service")
+}
+
+/// The unit file, with `ssh` at `ssh_path` (absolute, as systemd wants).
+pub fn systemd_unit(rendezvous: &Rendezvous, ssh_path: &str) -> String {
+    format!(
+        "[Unit]
+Description=Zync rendezvous tunnel {name} ({user}@{host} port {remote})
+After=network-online.target
+Wants=network-online.target
+
+[Service]
+ExecStart={ssh_path} -N -i %h/.ssh/zync_rendezvous -p {port} \
+-o BatchMode=yes -o ExitOnForwardFailure=yes \
+-o ServerAliveInterval=30 -o ServerAliveCountMax=3 \
+-o StrictHostKeyChecking=accept-new \
+-R 127.0.0.1:{remote}:{target_host}:{target_port} {user}@{host}
+Restart=always
+RestartSec=10
+
+[Install]
+WantedBy=default.target
+",
+        name = rendezvous.name.replace('\n', " "),
+        user = rendezvous.vps_user,
+        host = rendezvous.vps_host,
+        port = rendezvous.vps_port,
+        remote = rendezvous.remote_port,
+        target_host = rendezvous.target_host,
+        target_port = rendezvous.target_port,
+    )
+}
+
+/// `authorized_keys` options: the home key may only listen on the one
+/// loopback port — no shell, no other forwards.
+pub fn key_options(rendezvous: &Rendezvous) -> String {
+    format!(
+        "restrict,port-forwarding,permitlisten=\"127.0.0.1:{}\"",
+        rendezvous.remote_port
+    )
+}
+
+/// The VPS `authorized_keys` line for the home machine's public key.
+pub fn authorized_key_line(public_line: &str, rendezvous: &Rendezvous) -> Option<String> {
+    let key_type = public_line.split_whitespace().next().filter(|kind| {
+        ["ssh-", "ecdsa-", "sk-"]
+            .iter()
+            .any(|p| kind.starts_with(p))
+    })?;
+    let blob = crate::key_rotation::key_blob(public_line)?;
+    Some(format!(
+        "{} {key_type} {blob} {}",
+        key_options(rendezvous),
+        key_marker(&rendezvous.id)
+    ))
+}
+
+/// Comment that tags the key line for `id`, so setups sharing a home key
+/// each get (and lose) their own line.
+pub fn key_marker(id: &str) -> String {
+    format!("{KEY_MARKER}{id}")
+}
+
+/// Writes the unit (stdin) with the host's `ssh` path, enables it and asks
+/// for lingering so it runs without a login. Prints `linger=off` when that
+/// was refused.
+pub fn install_unit_script(unit: &str) -> String {
+    format!(
+        r#"set -e
+u={unit}
+d="${{XDG_CONFIG_HOME:-$HOME/.config}}/systemd/user"
+s=$(command -v ssh)
+[ -n "$s" ] || {{ echo "ssh is not installed" >&2; exit 2; }}
+mkdir -p "$d"
+sed "s|^ExecStart=[^ ]*|ExecStart=$s|" > "$d/$u"
+systemctl --user daemon-reload
+systemctl --user enable "$u" >/dev/null 2>&1
+systemctl --user restart "$u"
+loginctl enable-linger "$(id -un)" 2>/dev/null || echo linger=off"#,
+        unit = shell_quote(unit)
+    )
+}
+
+/// Stops and deletes the unit; a missing one is not an error.
+pub fn remove_unit_script(unit: &str) -> String {
+    format!(
+        r#"u={unit}
+systemctl --user disable --now "$u" >/dev/null 2>&1
+rm -f "${{XDG_CONFIG_HOME:-$HOME/.config}}/systemd/user/$u"
+systemctl --user daemon-reload 2>/dev/null
+exit 0"#,
+        unit = shell_quote(unit)
+    )
+}
+
+/// Drops the key line added for `id` from the VPS `authorized_keys`.
+pub fn remove_key_script(id: &str) -> String {
+    let marker = shell_quote(&key_marker(id));
+    format!(
+        "f=~/.ssh/authorized_keys; [ -f \"$f\" ] || exit 0; \
+         grep -vF {marker} \"$f\" > \"$f.zync-rendezvous\"; \
+         cat \"$f.zync-rendezvous\" > \"$f\"; rm -f \"$f.zync-rendezvous\""
+    )
+}
+
+/// `active`, `activating`, `failed`, ... as `systemctl --user is-active` prints it.
+pub fn unit_status_command(unit: &str) -> String {
+    format!(
+        "systemctl --user is-active {} 2>/dev/null",
+        shell_quote(unit)
+    )
+}
+
+fn store_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(STORE_FILE)
+}
+
+fn load(path: &Path) -> Result<Store, String> {
+    match std::fs::read(path) {
+        Ok(raw) => {
+            serde_json::from_slice(&raw).map_err(|e| format!("Invalid {}: {e}", path.display()))
+        }
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Store::default()),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
+fn save(path: &Path, store: &Store) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let raw = serde_json::to_vec_pretty(store).map_err(|e| e.to_string())?;
+    crate::atomic_io::durable_replace(path, &raw)
+        .map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+pub(crate) fn list(data_dir: &Path) -> Result<Vec<Rendezvous>, String> {
+    let _guard = STORE_LOCK.lock().unwrap_or_else(|p| p.into_inner());
+    Ok(load(&store_path(data_dir))?.rendezvous)
+}
+
+/// Insert or replace by id.
+pub(crate) fn upsert(data_dir: &Path, rendezvous: Rendezvous) -> Result<(), String> {
+    let _guard = STORE_LOCK.lock().unwrap_or_else(|p| p.into_inner());
+    let path = store_path(data_dir);
+    let mut store = load(&path)?;
+    store
+        .rendezvous
+        .retain(|existing| existing.id != rendezvous.id);
+    store.rendezvous.push(rendezvous);
+    save(&path, &store)
+}
+
+pub(crate) fn remove(data_dir: &Path, id: &str) -> Result<Option<Rendezvous>, String> {
+    let _guard = STORE_LOCK.lock().unwrap_or_else(|p| p.into_inner());
+    let path = store_path(data_dir);
+    let mut store = load(&path)?;
+    let Some(index) = store.rendezvous.iter().position(|r| r.id == id) else {
+        return Ok(None);
+    };
+    let removed = store.rendezvous.remove(index);
+    save(&path, &store)?;
+    Ok(Some(removed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rendezvous() -> Rendezvous {
+        Rendezvous {
+            id: "r1".to_string(),
+            name: "Home NAS".to_string(),
+            home_connection_id: "nas".to_string(),
+            vps_connection_id: "vps".to_string(),
+            remote_port: 2222,
+            target_host: "localhost".to_string(),
+            target_port: 22,
+            vps_host: "vps.example.com".to_string(),
+            vps_port: 22,
+            vps_user: "relay".to_string(),
+            unit: unit_name("Home NAS"),
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn unit_keeps_the_forward_up() {
+        let rendezvous = rendezvous();
+        assert_eq!(rendezvous.unit, "zync-rendezvous-home-nas.service");
+        let unit = systemd_unit(&rendezvous, "/usr/bin/ssh");
+        assert!(unit.contains(
+            "ExecStart=/usr/bin/ssh -N -i %h/.ssh/zync_rendezvous -p 22 -o BatchMode=yes"
+        ));
+        assert!(unit.contains("-R 127.0.0.1:2222:localhost:22 relay@vps.example.com\n"));
+        assert!(unit.contains("Restart=always"));
+    }
+
+    #[test]
+    fn key_line_only_allows_the_one_listener() {
+        let line = authorized_key_line(
+            "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIOMqq zync-rendezvous@nas",
+            &rendezvous(),
+        )
+        .unwrap();
+        assert_eq!(
+            line,
+            "restrict,port-forwarding,permitlisten=\"127.0.0.1:2222\" \
+             ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIOMqq zync-rendezvous:r1"
+        );
+        assert!(authorized_key_line("Permission denied", &rendezvous()).is_none());
+    }
+}
@@ -0,0 +1,82 @@
+//! Append-only audit trail (`audit.jsonl` in the data dir).
+
+use super::SafetyAction;
+use crate::commands::get_data_dir;
+use serde::Serialize;
+use std::io::Write;
+use std::sync::{LazyLock, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+static AUDIT_WRITE_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub timestamp: u64,
+    pub kind: String,
+    pub connection_id: String,
+    pub hostname: String,
+    pub action: Option<SafetyAction>,
+    pub command: Option<String>,
+    pub detail: String,
+}
+
+impl AuditEntry {
+    pub fn safety_override(
+        connection_id: &str,
+        hostname: &str,
+        action: SafetyAction,
+        command: &str,
+        reason: &str,
+    ) -> Self {
+        Self {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or(0),
+            kind: "safety-override".to_string(),
+            connection_id: connection_id.to_string(),
+            hostname: hostname.to_string(),
+            action: Some(action),
+            command: Some(command.to_string()),
+            detail: reason.to_string(),
+        }
+    }
+}
+
+/// Best-effort append, redacted first; failures are logged, never surfaced to
+/// the action being audited.
+pub fn record_override(app: &AppHandle, mut entry: AuditEntry) {
+    let redactor = crate::redaction::Redactor::from_app(app);
+    entry.command = entry.command.map(|command| redactor.redact(&command));
+    entry.detail = redactor.redact(&entry.detail);
+    let path = get_data_dir(app).join("audit.jsonl");
+    let Ok(line) = serde_json::to_string(&entry) else {
+        return;
+    };
+    let _guard = AUDIT_WRITE_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let result = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| writeln!(file, "{line}"));
+    if let Err(error) = result {
+        eprintln!("[AUDIT] Failed to append to {:?}: {}", path, error);
+    }
+    crate::log_forwarding::forward_audit(app, &entry);
+}
+
+/// Most recent entries first, up to `limit`.
+pub fn read_recent(app: &AppHandle, limit: usize) -> Vec<serde_json::Value> {
+    let path = get_data_dir(app).join("audit.jsonl");
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .take(limit)
+        .collect()
+}
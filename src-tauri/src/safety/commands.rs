@@ -0,0 +1,38 @@
+use super::{audit, check, enforce, SafetyAction, SafetyVerdict};
+use tauri::AppHandle;
+
+/// Preview the verdict for a command (e.g. before a terminal paste) without side effects.
+#[tauri::command]
+pub async fn safety_check(
+    app: AppHandle,
+    connection_id: String,
+    command: String,
+) -> Result<SafetyVerdict, String> {
+    Ok(check(&app, &connection_id, &command).0)
+}
+
+/// Approve a terminal paste. Same errors as other guarded actions; a confirmed
+/// override is audited.
+#[tauri::command]
+pub async fn safety_confirm_paste(
+    app: AppHandle,
+    connection_id: String,
+    text: String,
+    confirmation: Option<String>,
+) -> Result<(), String> {
+    enforce(
+        &app,
+        &connection_id,
+        SafetyAction::TerminalPaste,
+        &text,
+        confirmation.as_deref(),
+    )
+}
+
+#[tauri::command]
+pub async fn safety_audit_recent(
+    app: AppHandle,
+    limit: Option<usize>,
+) -> Result<Vec<serde_json::Value>, String> {
+    Ok(audit::read_recent(&app, limit.unwrap_or(100)))
+}
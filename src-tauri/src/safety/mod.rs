@@ -0,0 +1,245 @@
+//! Safety rules evaluated before commands reach guarded (production) connections.
+//!
+//! Applies to backend `ssh_exec`, snippet runs and terminal pastes. Configured in
+//! `settings.json`; every field is optional:
+//!
+//! ```json
+//! "safety": {
+//!   "enabled": true,
+//!   "guardTags": ["production"],
+//!   "blockedCommands": ["rm -rf /", "reboot"],
+//!   "confirmCommands": ["systemctl restart"],
+//!   "requireHostnameConfirmation": true
+//! }
+//! ```
+//!
+//! Blocked commands are refused outright. Confirm-level commands need the caller
+//! to repeat the request with the connection's hostname typed as confirmation;
+//! each such override is appended to the audit trail.
+
+pub mod audit;
+pub mod commands;
+
+use crate::commands::read_effective_settings;
+use crate::profiles::{looks_destructive, resolve_profile, ConnectionProfile};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+/// Machine-readable prefixes — must stay in sync with the TS safety dialog.
+pub const SAFETY_BLOCKED_PREFIX: &str = "SAFETY_BLOCKED:";
+/// The UI asks for the expected text and retries with it as `confirmation`.
+pub const CONFIRMATION_REQUIRED_PREFIX: &str = "CONFIRMATION_REQUIRED:";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SafetyRules {
+    pub enabled: bool,
+    /// Connection tags that turn the guard on, in addition to production themes.
+    pub guard_tags: Vec<String>,
+    pub blocked_commands: Vec<String>,
+    /// Extra patterns needing confirmation; built-in destructive patterns always apply.
+    pub confirm_commands: Vec<String>,
+    /// When false a plain `"yes"` confirms instead of the hostname.
+    pub require_hostname_confirmation: bool,
+}
+
+impl Default for SafetyRules {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            guard_tags: vec![crate::profiles::PRODUCTION_TAG.to_string()],
+            blocked_commands: vec![
+                "rm -rf /".to_string(),
+                "rm -rf /*".to_string(),
+                "mkfs".to_string(),
+                ":(){ :|:& };:".to_string(),
+            ],
+            confirm_commands: Vec::new(),
+            require_hostname_confirmation: true,
+        }
+    }
+}
+
+pub fn read_safety_rules(app: &AppHandle) -> SafetyRules {
+    read_effective_settings(app)
+        .ok()
+        .and_then(|settings| settings.get("safety").cloned())
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SafetyAction {
+    Exec,
+    Snippet,
+    TerminalPaste,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "verdict", rename_all = "camelCase")]
+pub enum SafetyVerdict {
+    Allow,
+    Confirm {
+        reason: String,
+        /// Text the user must type to proceed.
+        expected: String,
+    },
+    Block {
+        reason: String,
+    },
+}
+
+fn normalize(command: &str) -> String {
+    command
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn matching_pattern<'a>(normalized: &str, patterns: &'a [String]) -> Option<&'a str> {
+    patterns
+        .iter()
+        .map(String::as_str)
+        .find(|pattern| {
+            let pattern = normalize(pattern);
+            !pattern.is_empty() && normalized.contains(&pattern)
+        })
+}
+
+pub fn is_guarded(rules: &SafetyRules, profile: &ConnectionProfile, tags: &[String]) -> bool {
+    rules.enabled
+        && (profile.production
+            || tags.iter().any(|tag| {
+                rules
+                    .guard_tags
+                    .iter()
+                    .any(|guard| guard.eq_ignore_ascii_case(tag))
+            }))
+}
+
+pub fn evaluate(rules: &SafetyRules, guarded: bool, hostname: &str, command: &str) -> SafetyVerdict {
+    if !guarded {
+        return SafetyVerdict::Allow;
+    }
+    let normalized = normalize(command);
+    if let Some(pattern) = matching_pattern(&normalized, &rules.blocked_commands) {
+        return SafetyVerdict::Block {
+            reason: format!("`{pattern}` is blocked on {hostname} by safety rules."),
+        };
+    }
+    let confirm_reason = matching_pattern(&normalized, &rules.confirm_commands)
+        .map(|pattern| format!("`{pattern}` requires confirmation on {hostname}."))
+        .or_else(|| {
+            looks_destructive(command)
+                .then(|| format!("This command looks destructive and {hostname} is a guarded host."))
+        });
+    match confirm_reason {
+        Some(reason) => SafetyVerdict::Confirm {
+            reason,
+            expected: if rules.require_hostname_confirmation {
+                hostname.to_string()
+            } else {
+                "yes".to_string()
+            },
+        },
+        None => SafetyVerdict::Allow,
+    }
+}
+
+/// Evaluate the rules for a connection. Returns the verdict and the hostname used.
+pub fn check(app: &AppHandle, connection_id: &str, command: &str) -> (SafetyVerdict, String) {
+    let rules = read_safety_rules(app);
+    let saved = crate::profiles::load_saved_connection(app, connection_id);
+    let hostname = saved
+        .as_ref()
+        .map(|connection| connection.host.clone())
+        .unwrap_or_else(|| connection_id.to_string());
+    let tags = saved
+        .as_ref()
+        .and_then(|connection| connection.tags.clone())
+        .unwrap_or_default();
+    let profile = resolve_profile(app, connection_id);
+    let guarded = is_guarded(&rules, &profile, &tags);
+    (evaluate(&rules, guarded, &hostname, command), hostname)
+}
+
+/// Gate an action: `Ok(())` to proceed, or an error carrying a machine-readable prefix.
+/// A matching `confirmation` lets a confirm-level command through and audits the override.
+pub fn enforce(
+    app: &AppHandle,
+    connection_id: &str,
+    action: SafetyAction,
+    command: &str,
+    confirmation: Option<&str>,
+) -> Result<(), String> {
+    match check(app, connection_id, command) {
+        (SafetyVerdict::Allow, _) => Ok(()),
+        (SafetyVerdict::Block { reason }, _) => Err(format!("{SAFETY_BLOCKED_PREFIX}{reason}")),
+        (SafetyVerdict::Confirm { reason, expected }, hostname) => {
+            let confirmed = confirmation
+                .map(str::trim)
+                .is_some_and(|typed| typed.eq_ignore_ascii_case(&expected));
+            if !confirmed {
+                return Err(format!(
+                    "{CONFIRMATION_REQUIRED_PREFIX}{reason} Type `{expected}` to continue."
+                ));
+            }
+            audit::record_override(
+                app,
+                audit::AuditEntry::safety_override(connection_id, &hostname, action, command, &reason),
+            );
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules() -> SafetyRules {
+        SafetyRules {
+            confirm_commands: vec!["apt upgrade".to_string()],
+            ..SafetyRules::default()
+        }
+    }
+
+    #[test]
+    fn unguarded_hosts_allow_everything() {
+        assert_eq!(evaluate(&rules(), false, "db", "rm -rf /"), SafetyVerdict::Allow);
+    }
+
+    #[test]
+    fn blocked_patterns_win_over_confirmation() {
+        assert!(matches!(
+            evaluate(&rules(), true, "db", "sudo rm  -rf  /"),
+            SafetyVerdict::Block { .. }
+        ));
+    }
+
+    #[test]
+    fn confirm_patterns_expect_hostname() {
+        match evaluate(&rules(), true, "db.example.com", "sudo apt upgrade -y") {
+            SafetyVerdict::Confirm { expected, .. } => assert_eq!(expected, "db.example.com"),
+            other => panic!("unexpected verdict {other:?}"),
+        }
+        assert!(matches!(
+            evaluate(&rules(), true, "db", "sudo reboot"),
+            SafetyVerdict::Confirm { .. }
+        ));
+        assert_eq!(evaluate(&rules(), true, "db", "uptime"), SafetyVerdict::Allow);
+    }
+
+    #[test]
+    fn guard_tags_match_case_insensitively() {
+        let profile = ConnectionProfile {
+            connection_id: "c1".to_string(),
+            theme: None,
+            production: false,
+        };
+        assert!(is_guarded(&rules(), &profile, &["Production".to_string()]));
+        assert!(!is_guarded(&rules(), &profile, &["staging".to_string()]));
+    }
+}
@@ -0,0 +1,156 @@
+use super::{
+    check_integrity, hydrate_secrets, split_secrets, IntegrityIssue, SecretStore, StorageSettings,
+};
+use crate::commands::{get_data_dir, read_effective_settings};
+use crate::snippets::read_snippets_data;
+use crate::sync::domain_hosts::{
+    load_saved_data, save_saved_data_atomic, CONNECTIONS_MUTATION_LOCK,
+};
+use crate::sync::domain_tunnels::load_saved_tunnels;
+use crate::types::{SavedConnection, SavedData};
+use crate::vault::error::VaultError;
+use crate::vault::store::VaultService;
+use serde::Serialize;
+use tauri::{AppHandle, State};
+
+const KEYRING_SERVICE: &str = "Zync Connection Secrets";
+
+/// Connection passwords in the OS keychain, one entry per profile and connection.
+pub struct KeychainStore;
+
+impl KeychainStore {
+    fn entry(id: &str) -> Result<keyring::Entry, String> {
+        let account = format!("{}:connection:{id}", crate::user_profiles::active_id());
+        keyring::Entry::new(KEYRING_SERVICE, &account).map_err(|e| e.to_string())
+    }
+}
+
+impl SecretStore for KeychainStore {
+    fn get(&self, id: &str) -> Result<Option<String>, String> {
+        match Self::entry(id)?.get_password() {
+            Ok(secret) => Ok(Some(secret)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    fn set(&self, id: &str, secret: &str) -> Result<(), String> {
+        Self::entry(id)?
+            .set_password(secret)
+            .map_err(|e| e.to_string())
+    }
+
+    fn delete(&self, id: &str) -> Result<(), String> {
+        match Self::entry(id)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+pub fn read_storage_settings(app: &AppHandle) -> StorageSettings {
+    read_effective_settings(app)
+        .ok()
+        .and_then(|settings| settings.get("storage").cloned())
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// Apply the split to connections about to replace `previous` on disk.
+pub fn prepare_for_save(app: &AppHandle, previous: &[SavedConnection], data: &mut SavedData) {
+    let split = read_storage_settings(app).split_secrets;
+    for warning in split_secrets(previous, &mut data.connections, split, &KeychainStore) {
+        eprintln!("[Secret split] {warning}");
+    }
+}
+
+/// Restore keychain passwords into freshly loaded connections.
+pub fn hydrate(data: &mut SavedData) {
+    for name in hydrate_secrets(data, &KeychainStore) {
+        eprintln!("[Secret split] Password for {name} is missing from the keychain");
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+    pub split_secrets: bool,
+    /// Vault references were only checked when the vault is unlocked.
+    pub vault_checked: bool,
+    pub issues: Vec<IntegrityIssue>,
+}
+
+/// Check references between connections, tunnels, snippets, the keychain
+/// and the vault.
+#[tauri::command]
+pub async fn secret_split_check(
+    app: AppHandle,
+    vault: State<'_, tokio::sync::Mutex<VaultService>>,
+) -> Result<IntegrityReport, String> {
+    let data_dir = get_data_dir(&app);
+    let split = read_storage_settings(&app).split_secrets;
+    let data = {
+        let _guard = CONNECTIONS_MUTATION_LOCK
+            .lock()
+            .map_err(|e| e.to_string())?;
+        load_saved_data(&data_dir.join("connections.json")).map_err(|e| e.to_string())?
+    };
+    let tunnels = load_saved_tunnels(&data_dir.join("tunnels.json"))
+        .map_err(|e| e.to_string())?
+        .tunnels;
+    let snippets = read_snippets_data(&data_dir.join("snippets.json"))?.snippets;
+
+    let vault = vault.lock().await;
+    let vault_checked = vault.vault_id().is_some();
+    let issues = check_integrity(
+        &data,
+        &tunnels,
+        &snippets,
+        split,
+        |id| KeychainStore.get(id).ok().map(|secret| secret.is_some()),
+        |auth_ref| {
+            if !vault_checked {
+                return None;
+            }
+            match vault.item_get(&auth_ref.item_id) {
+                Ok(_) => Some(true),
+                Err(VaultError::RecordNotFound(_)) => match auth_ref.credential_id.as_deref() {
+                    Some(credential_id) => match vault.item_get_by_logical_id(credential_id) {
+                        Ok(_) => Some(true),
+                        Err(VaultError::RecordNotFound(_)) => Some(false),
+                        Err(_) => None,
+                    },
+                    None => Some(false),
+                },
+                Err(_) => None,
+            }
+        },
+    );
+    Ok(IntegrityReport {
+        split_secrets: split,
+        vault_checked,
+        issues,
+    })
+}
+
+/// Rewrite `connections.json` under the current `storage.splitSecrets`
+/// setting: move passwords to the keychain, or back into the file. Returns
+/// the number of connections whose password is kept in the keychain.
+#[tauri::command]
+pub async fn secret_split_apply(app: AppHandle) -> Result<usize, String> {
+    let path = get_data_dir(&app).join("connections.json");
+    let _guard = CONNECTIONS_MUTATION_LOCK
+        .lock()
+        .map_err(|e| e.to_string())?;
+    let mut data = load_saved_data(&path).map_err(|e| e.to_string())?;
+    let previous = data.connections.clone();
+    hydrate(&mut data);
+    prepare_for_save(&app, &previous, &mut data);
+    save_saved_data_atomic(&path, &data).map_err(|e| e.to_string())?;
+    crate::smart_groups::publish_membership(&app, &data);
+    Ok(data
+        .connections
+        .iter()
+        .filter(|connection| connection.secret_ref.is_some())
+        .count())
+}
@@ -0,0 +1,404 @@
+//! Split storage: keep `connections.json`, `tunnels.json` and `snippets.json`
+//! free of secrets so the data directory can be kept in git.
+//!
+//! With `storage.splitSecrets` on, saved connection passwords move to the OS
+//! keychain and the connection keeps only `"secretRef": "keychain"`; vault
+//! backed connections already hold just an `authRef`. Passwords are put back
+//! when connections are loaded, so the rest of the app never sees the split.
+//! `secret_split_check` reports dangling references between the files, the
+//! keychain and the vault.
+
+pub mod commands;
+
+use crate::snippets::Snippet;
+use crate::types::{CredentialRef, SavedConnection, SavedData, SavedTunnel};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// `secretRef` value of a connection whose password is in the OS keychain.
+pub const KEYCHAIN_REF: &str = "keychain";
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct StorageSettings {
+    /// Move connection passwords out of `connections.json`.
+    pub split_secrets: bool,
+}
+
+/// Where split-out connection passwords live, keyed by connection id.
+pub trait SecretStore {
+    fn get(&self, id: &str) -> Result<Option<String>, String>;
+    fn set(&self, id: &str, secret: &str) -> Result<(), String>;
+    fn delete(&self, id: &str) -> Result<(), String>;
+}
+
+fn has_password(connection: &SavedConnection) -> bool {
+    connection
+        .password
+        .as_deref()
+        .is_some_and(|password| !password.is_empty())
+}
+
+/// Prepare connections for writing. With `split` on, passwords go to `store`
+/// and are replaced by a `secretRef`; with it off, passwords sent back in
+/// plain text take over from their keychain copies. Secrets of connections
+/// that were removed, switched to a vault credential or had their password
+/// cleared are deleted. Returns warnings for secrets that stayed in the file.
+pub fn split_secrets(
+    previous: &[SavedConnection],
+    incoming: &mut [SavedConnection],
+    split: bool,
+    store: &dyn SecretStore,
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let previously_split: HashSet<&str> = previous
+        .iter()
+        .filter(|connection| connection.secret_ref.is_some())
+        .map(|connection| connection.id.as_str())
+        .collect();
+    let forget = |id: &str, warnings: &mut Vec<String>| {
+        if let Err(error) = store.delete(id) {
+            warnings.push(format!("{id}: failed to remove keychain secret: {error}"));
+        }
+    };
+
+    for connection in incoming.iter_mut() {
+        let had_secret =
+            connection.secret_ref.is_some() || previously_split.contains(connection.id.as_str());
+        if connection.auth_ref.is_some() {
+            if had_secret {
+                forget(&connection.id, &mut warnings);
+            }
+            connection.secret_ref = None;
+            continue;
+        }
+        if has_password(connection) {
+            if !split {
+                if had_secret {
+                    forget(&connection.id, &mut warnings);
+                }
+                connection.secret_ref = None;
+                continue;
+            }
+            let password = connection.password.clone().unwrap_or_default();
+            match store.set(&connection.id, &password) {
+                Ok(()) => {
+                    connection.password = None;
+                    connection.secret_ref = Some(KEYCHAIN_REF.to_string());
+                }
+                Err(error) => {
+                    connection.secret_ref = None;
+                    warnings.push(format!(
+                        "{}: password kept in connections.json: {error}",
+                        connection.name
+                    ));
+                }
+            }
+        } else if connection.secret_ref.is_none() && had_secret {
+            // The password was cleared in the editor.
+            forget(&connection.id, &mut warnings);
+        }
+    }
+
+    let kept: HashSet<&str> = incoming.iter().map(|c| c.id.as_str()).collect();
+    for id in previously_split.difference(&kept) {
+        forget(id, &mut warnings);
+    }
+    warnings
+}
+
+/// Put split-out passwords back into loaded connections. Returns the names
+/// of connections whose secret is missing from the store.
+pub fn hydrate_secrets(data: &mut SavedData, store: &dyn SecretStore) -> Vec<String> {
+    let mut missing = Vec::new();
+    for connection in data.connections.iter_mut() {
+        if connection.secret_ref.is_none()
+            || connection.auth_ref.is_some()
+            || has_password(connection)
+        {
+            continue;
+        }
+        match store.get(&connection.id) {
+            Ok(Some(password)) => connection.password = Some(password),
+            Ok(None) => missing.push(connection.name.clone()),
+            Err(error) => missing.push(format!("{} ({error})", connection.name)),
+        }
+    }
+    missing
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueSeverity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityIssue {
+    pub severity: IssueSeverity,
+    /// `duplicate-id`, `missing-jump-host`, `plaintext-secret`, `missing-secret`,
+    /// `missing-vault-item`, `orphan-tunnel` or `orphan-snippet`.
+    pub kind: String,
+    /// File the issue is in.
+    pub file: String,
+    pub subject_id: String,
+    pub message: String,
+}
+
+fn issue(
+    severity: IssueSeverity,
+    kind: &str,
+    file: &str,
+    subject_id: &str,
+    message: String,
+) -> IntegrityIssue {
+    IntegrityIssue {
+        severity,
+        kind: kind.to_string(),
+        file: file.to_string(),
+        subject_id: subject_id.to_string(),
+        message,
+    }
+}
+
+/// Check references between the saved files and the secret stores.
+/// `secret_exists` and `vault_item_exists` return `None` when they can't
+/// tell (keychain unavailable, vault locked); such references are skipped.
+pub fn check_integrity(
+    data: &SavedData,
+    tunnels: &[SavedTunnel],
+    snippets: &[Snippet],
+    split: bool,
+    secret_exists: impl Fn(&str) -> Option<bool>,
+    vault_item_exists: impl Fn(&CredentialRef) -> Option<bool>,
+) -> Vec<IntegrityIssue> {
+    use IssueSeverity::{Error, Warning};
+    const CONNECTIONS: &str = "connections.json";
+    let mut issues = Vec::new();
+    let mut ids = HashSet::new();
+    for connection in &data.connections {
+        if !ids.insert(connection.id.as_str()) {
+            issues.push(issue(
+                Error,
+                "duplicate-id",
+                CONNECTIONS,
+                &connection.id,
+                format!("Connection id {} is used more than once", connection.id),
+            ));
+        }
+    }
+
+    for connection in &data.connections {
+        let name = &connection.name;
+        if let Some(jump) = connection.jump_server_id.as_deref() {
+            if jump == connection.id || !ids.contains(jump) {
+                issues.push(issue(
+                    Error,
+                    "missing-jump-host",
+                    CONNECTIONS,
+                    &connection.id,
+                    format!("{name} jumps through {jump}, which is not a saved connection"),
+                ));
+            }
+        }
+        if split && has_password(connection) {
+            issues.push(issue(
+                Warning,
+                "plaintext-secret",
+                CONNECTIONS,
+                &connection.id,
+                format!("{name} still has its password in the file"),
+            ));
+        }
+        if connection.secret_ref.is_some()
+            && connection.auth_ref.is_none()
+            && secret_exists(&connection.id) == Some(false)
+        {
+            issues.push(issue(
+                Error,
+                "missing-secret",
+                CONNECTIONS,
+                &connection.id,
+                format!("{name}'s password is missing from the keychain"),
+            ));
+        }
+        if let Some(auth_ref) = &connection.auth_ref {
+            if vault_item_exists(auth_ref) == Some(false) {
+                issues.push(issue(
+                    Error,
+                    "missing-vault-item",
+                    CONNECTIONS,
+                    &connection.id,
+                    format!("{name} uses a vault credential that no longer exists"),
+                ));
+            }
+        }
+    }
+
+    let mut tunnel_ids = HashSet::new();
+    for tunnel in tunnels {
+        if !tunnel_ids.insert(tunnel.id.as_str()) {
+            issues.push(issue(
+                Error,
+                "duplicate-id",
+                "tunnels.json",
+                &tunnel.id,
+                format!("Tunnel id {} is used more than once", tunnel.id),
+            ));
+        }
+        if !ids.contains(tunnel.connection_id.as_str()) {
+            issues.push(issue(
+                Error,
+                "orphan-tunnel",
+                "tunnels.json",
+                &tunnel.id,
+                format!(
+                    "Tunnel {} belongs to {}, which is not a saved connection",
+                    tunnel.name, tunnel.connection_id
+                ),
+            ));
+        }
+    }
+
+    for snippet in snippets {
+        if let Some(connection_id) = snippet.connection_id.as_deref() {
+            if !ids.contains(connection_id) {
+                issues.push(issue(
+                    Warning,
+                    "orphan-snippet",
+                    "snippets.json",
+                    &snippet.id,
+                    format!(
+                        "Snippet {} is scoped to {connection_id}, which is not a saved connection",
+                        snippet.name
+                    ),
+                ));
+            }
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    #[derive(Default)]
+    struct MemoryStore(RefCell<HashMap<String, String>>);
+
+    impl SecretStore for MemoryStore {
+        fn get(&self, id: &str) -> Result<Option<String>, String> {
+            Ok(self.0.borrow().get(id).cloned())
+        }
+        fn set(&self, id: &str, secret: &str) -> Result<(), String> {
+            self.0
+                .borrow_mut()
+                .insert(id.to_string(), secret.to_string());
+            Ok(())
+        }
+        fn delete(&self, id: &str) -> Result<(), String> {
+            self.0.borrow_mut().remove(id);
+            Ok(())
+        }
+    }
+
+    fn connection(id: &str, password: Option<&str>) -> SavedConnection {
+        SavedConnection {
+            id: id.to_string(),
+            name: id.to_string(),
+            host: format!("{id}.example.com"),
+            port: 22,
+            username: "deploy".to_string(),
+            password: password.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn moves_passwords_to_the_store_and_back() {
+        let store = MemoryStore::default();
+        let mut saved = vec![connection("web", Some("hunter2")), connection("db", None)];
+        assert!(split_secrets(&[], &mut saved, true, &store).is_empty());
+        assert_eq!(saved[0].password, None);
+        assert_eq!(saved[0].secret_ref.as_deref(), Some(KEYCHAIN_REF));
+        assert_eq!(saved[1].secret_ref, None);
+        let json = serde_json::to_string(&saved).unwrap();
+        assert!(!json.contains("hunter2"));
+
+        let mut data = SavedData {
+            connections: saved.clone(),
+            folders: Vec::new(),
+        };
+        assert!(hydrate_secrets(&mut data, &store).is_empty());
+        assert_eq!(data.connections[0].password.as_deref(), Some("hunter2"));
+
+        // Turning the split off writes the password back and drops the copy.
+        let previous = saved.clone();
+        let mut incoming = data.connections.clone();
+        split_secrets(&previous, &mut incoming, false, &store);
+        assert_eq!(incoming[0].password.as_deref(), Some("hunter2"));
+        assert_eq!(incoming[0].secret_ref, None);
+        assert!(store.0.borrow().is_empty());
+    }
+
+    #[test]
+    fn forgets_secrets_of_removed_or_cleared_connections() {
+        let store = MemoryStore::default();
+        let mut saved = vec![connection("a", Some("pa")), connection("b", Some("pb"))];
+        split_secrets(&[], &mut saved, true, &store);
+        let previous = saved.clone();
+
+        // "a" is deleted; "b" comes back from an editor that dropped the
+        // marker along with the password.
+        let mut incoming = vec![connection("b", None)];
+        split_secrets(&previous, &mut incoming, true, &store);
+        assert!(store.0.borrow().is_empty());
+        assert_eq!(incoming[0].secret_ref, None);
+    }
+
+    #[test]
+    fn reports_dangling_references() {
+        let mut web = connection("web", None);
+        web.secret_ref = Some(KEYCHAIN_REF.to_string());
+        web.jump_server_id = Some("bastion".to_string());
+        let plain = connection("legacy", Some("pw"));
+        let data = SavedData {
+            connections: vec![web, plain],
+            folders: Vec::new(),
+        };
+        let tunnel: SavedTunnel = serde_json::from_value(serde_json::json!({
+            "id": "t1", "connectionId": "gone", "name": "pg", "type": "local",
+            "localPort": 5432, "remoteHost": "localhost", "remotePort": 5432,
+            "bindAddress": null, "bindToAny": null, "autoStart": null
+        }))
+        .unwrap();
+        let snippet: Snippet = serde_json::from_value(serde_json::json!({
+            "id": "s1", "name": "logs", "command": "tail -f log",
+            "category": null, "tags": null, "connectionId": "web"
+        }))
+        .unwrap();
+
+        let issues = check_integrity(
+            &data,
+            &[tunnel],
+            &[snippet],
+            true,
+            |_| Some(false),
+            |_| None,
+        );
+        let kinds: Vec<&str> = issues.iter().map(|i| i.kind.as_str()).collect();
+        assert_eq!(
+            kinds,
+            [
+                "missing-jump-host",
+                "missing-secret",
+                "plaintext-secret",
+                "orphan-tunnel"
+            ]
+        );
+    }
+}
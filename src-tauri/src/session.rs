@@ -117,3 +117,59 @@ pub async fn session_save(app: AppHandle, mut data: SessionData) -> Result<(), S
     tokio::fs::write(&tmp, &json).await.map_err(|e| e.to_string())?;
     tokio::fs::rename(&tmp, dir.join("session.json")).await.map_err(|e| e.to_string())
 }
+
+/// The saved session, read synchronously for backend features that build on
+/// it. `None` when there is no session yet or it can't be read.
+pub(crate) fn read_session(app: &AppHandle) -> Option<SessionData> {
+    let path = crate::commands::get_data_dir(app).join("session.json");
+    let raw = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str::<SessionData>(&raw).ok().map(migrate)
+}
+
+// ─── Connection merges ───────────────────────────────────────────────────────
+
+/// Repoint tabs and terminal scopes from `from` to `into` in the saved session.
+/// Returns the number of tabs and terminal scopes that were updated.
+pub(crate) fn reassign_connection(app: &AppHandle, from: &str, into: &str) -> Result<usize, String> {
+    let dir = crate::commands::get_data_dir(app);
+    let path = dir.join("session.json");
+    let raw = match std::fs::read_to_string(&path) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e.to_string()),
+    };
+    let Ok(mut data) = serde_json::from_str::<SessionData>(&raw).map(migrate) else {
+        return Ok(0);
+    };
+
+    let updated = reassign_in_session(&mut data, from, into);
+    if updated == 0 {
+        return Ok(0);
+    }
+    let json = serde_json::to_string_pretty(&data).map_err(|e| e.to_string())?;
+    crate::atomic_io::durable_replace(&path, json.as_bytes()).map_err(|e| e.to_string())?;
+    Ok(updated)
+}
+
+fn reassign_in_session(data: &mut SessionData, from: &str, into: &str) -> usize {
+    let mut updated = 0;
+    for tab in data.tabs.iter_mut() {
+        if tab.connection_id.as_deref() == Some(from) {
+            tab.connection_id = Some(into.to_string());
+            updated += 1;
+        }
+    }
+    if data.active_connection_id.as_deref() == Some(from) {
+        data.active_connection_id = Some(into.to_string());
+    }
+    if let Some(terminals) = data.terminals.remove(from) {
+        let target = data.terminals.entry(into.to_string()).or_default();
+        target.extend(terminals);
+        target.truncate(MAX_TABS_PER_SCOPE);
+        updated += 1;
+    }
+    if let Some(active) = data.active_terminal_ids.remove(from) {
+        data.active_terminal_ids.entry(into.to_string()).or_insert(active);
+    }
+    updated
+}
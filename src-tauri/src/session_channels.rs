@@ -0,0 +1,309 @@
+//! Session-channel accounting per SSH connection (sshd `MaxSessions`).
+//!
+//! Only `session` channels (shell, exec, sftp) count against `MaxSessions`;
+//! `direct-tcpip` tunnel channels do not, so tunnels never go through this gate.
+//!
+//! The limit is not advertised by the server. It is learned the first time a
+//! session open is refused while other channels are open; after that, new opens
+//! queue until a slot is released instead of failing sporadically.
+
+use crate::events::ConnectionEvent;
+use crate::ssh::Client;
+use russh::client::{Handle, Msg};
+use russh::Channel;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex as StdMutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::{Mutex, Notify};
+
+/// How long a queued channel open waits for a free slot before giving up.
+const CHANNEL_QUEUE_WAIT: Duration = Duration::from_secs(30);
+
+static SESSION_GATES: LazyLock<StdMutex<HashMap<String, Arc<SessionChannelGate>>>> =
+    LazyLock::new(|| StdMutex::new(HashMap::new()));
+
+#[derive(Debug, Default)]
+struct GateState {
+    open: usize,
+    /// Learned `MaxSessions` value; `None` until the server refuses an open.
+    cap: Option<usize>,
+}
+
+#[derive(Debug, Default)]
+pub struct SessionChannelGate {
+    state: StdMutex<GateState>,
+    released: Notify,
+}
+
+/// Holds one session-channel slot; the slot is released on drop.
+#[derive(Debug)]
+pub struct SessionChannelPermit {
+    gate: Arc<SessionChannelGate>,
+}
+
+impl Drop for SessionChannelPermit {
+    fn drop(&mut self) {
+        {
+            let mut state = lock_state(&self.gate.state);
+            state.open = state.open.saturating_sub(1);
+        }
+        self.gate.released.notify_waiters();
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionChannelUsage {
+    pub open: usize,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MaxSessionsEvent {
+    connection_id: String,
+    limit: usize,
+    message: String,
+}
+
+fn lock_state(state: &StdMutex<GateState>) -> std::sync::MutexGuard<'_, GateState> {
+    match state.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+impl SessionChannelGate {
+    pub fn usage(&self) -> SessionChannelUsage {
+        let state = lock_state(&self.state);
+        SessionChannelUsage {
+            open: state.open,
+            limit: state.cap,
+        }
+    }
+
+    /// Take a slot immediately, or `None` when the learned limit is reached.
+    pub fn try_acquire(self: &Arc<Self>) -> Option<SessionChannelPermit> {
+        let mut state = lock_state(&self.state);
+        if state.cap.is_some_and(|cap| state.open >= cap) {
+            return None;
+        }
+        state.open += 1;
+        Some(SessionChannelPermit { gate: self.clone() })
+    }
+
+    /// Wait for a slot. Only blocks once a limit has been learned.
+    pub async fn acquire(self: &Arc<Self>, wait: Duration) -> Result<SessionChannelPermit, String> {
+        let deadline = tokio::time::Instant::now() + wait;
+        loop {
+            // Register before checking so a release between check and await is not missed.
+            let released = self.released.notified();
+            if let Some(permit) = self.try_acquire() {
+                return Ok(permit);
+            }
+            if tokio::time::timeout_at(deadline, released).await.is_err() {
+                let usage = self.usage();
+                return Err(format!(
+                    "All {} SSH sessions allowed by the server (MaxSessions) are in use. Close a terminal or wait for running commands to finish.",
+                    usage.limit.unwrap_or(usage.open)
+                ));
+            }
+        }
+    }
+
+    /// Record a refused session open, made after its own slot was given back.
+    fn record_refusal(&self) -> Refusal {
+        let mut state = lock_state(&self.state);
+        if state.open == 0 {
+            // Refused with nothing open: a policy block, not a session limit.
+            return Refusal::NothingOpen;
+        }
+        let learned = state.open;
+        match state.cap {
+            Some(cap) if cap <= learned => Refusal::Known,
+            _ => {
+                state.cap = Some(learned);
+                Refusal::Learned(learned)
+            }
+        }
+    }
+}
+
+/// What a refused session open says about the server's limit.
+#[derive(Debug, PartialEq, Eq)]
+enum Refusal {
+    NothingOpen,
+    /// First evidence of a limit, or of a lower one than learned so far.
+    Learned(usize),
+    /// Already at the learned limit; the open just lost a race for the last slot.
+    Known,
+}
+
+pub fn gate_for(connection_id: &str) -> Arc<SessionChannelGate> {
+    let mut gates = match SESSION_GATES.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    gates
+        .entry(connection_id.to_string())
+        .or_default()
+        .clone()
+}
+
+/// Make `to` count against `from`'s slots; used when two connections share one session.
+pub fn share_gate(from: &str, to: &str) {
+    let gate = gate_for(from);
+    let mut gates = match SESSION_GATES.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    gates.insert(to.to_string(), gate);
+}
+
+/// Forget the learned limit when the user explicitly disconnects.
+pub fn forget_gate(connection_id: &str) {
+    let mut gates = match SESSION_GATES.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    gates.remove(connection_id);
+}
+
+/// True when a session open failed because the server refused another session.
+pub(crate) fn is_session_limit_refusal(error: &impl std::fmt::Display) -> bool {
+    let message = error.to_string().to_ascii_lowercase();
+    message.contains("administrativelyprohibited")
+        || message.contains("administratively prohibited")
+        || message.contains("resourceshortage")
+        || message.contains("resource shortage")
+}
+
+#[derive(Debug)]
+pub enum SessionChannelError {
+    /// Every slot stayed busy for the whole queue wait.
+    Saturated(String),
+    Ssh(russh::Error),
+}
+
+impl std::fmt::Display for SessionChannelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Saturated(message) => f.write_str(message),
+            Self::Ssh(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+/// Open a `session` channel through the connection's gate.
+///
+/// When the server refuses an open while others are in use, the limit is learned
+/// (or lowered), `connection:max-sessions` is emitted, and this call queues for a
+/// free slot; a refusal at the known limit just queues again.
+pub(crate) async fn open_session_channel(
+    app: &AppHandle,
+    connection_id: &str,
+    session: &Arc<Mutex<Handle<Client>>>,
+) -> Result<(Channel<Msg>, SessionChannelPermit), SessionChannelError> {
+    let gate = gate_for(connection_id);
+    let deadline = tokio::time::Instant::now() + CHANNEL_QUEUE_WAIT;
+    loop {
+        let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+        let permit = gate
+            .acquire(remaining)
+            .await
+            .map_err(SessionChannelError::Saturated)?;
+
+        let result = {
+            let guard = session.lock().await;
+            guard.channel_open_session().await
+        };
+        match result {
+            Ok(channel) => {
+                crate::events::publish(
+                    connection_id,
+                    ConnectionEvent::ChannelOpened {
+                        kind: "session".to_string(),
+                        open_channels: gate.usage().open,
+                    },
+                );
+                return Ok((channel, permit));
+            }
+            Err(error) if is_session_limit_refusal(&error) => {
+                drop(permit);
+                let limit = match gate.record_refusal() {
+                    Refusal::NothingOpen => return Err(SessionChannelError::Ssh(error)),
+                    // The gate is full again, so the next acquire waits for a release.
+                    Refusal::Known => continue,
+                    Refusal::Learned(limit) => limit,
+                };
+                eprintln!(
+                    "[SSH] {} refused a session channel with {} open; queuing further opens",
+                    connection_id, limit
+                );
+                let _ = app.emit(
+                    "connection:max-sessions",
+                    MaxSessionsEvent {
+                        connection_id: connection_id.to_string(),
+                        limit,
+                        message: format!(
+                            "Server allows {limit} concurrent SSH sessions (MaxSessions). New terminals and commands will wait for a free slot."
+                        ),
+                    },
+                );
+            }
+            Err(error) => return Err(SessionChannelError::Ssh(error)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refusal_with_open_channels_learns_limit_once() {
+        let gate = Arc::new(SessionChannelGate::default());
+        let _a = gate.try_acquire().expect("slot");
+        let _b = gate.try_acquire().expect("slot");
+        assert_eq!(gate.record_refusal(), Refusal::Learned(2));
+        assert_eq!(gate.record_refusal(), Refusal::Known);
+        assert!(gate.try_acquire().is_none());
+    }
+
+    #[test]
+    fn refusal_with_nothing_open_is_not_a_limit() {
+        let gate = Arc::new(SessionChannelGate::default());
+        assert_eq!(gate.record_refusal(), Refusal::NothingOpen);
+        assert_eq!(gate.usage().limit, None);
+    }
+
+    #[test]
+    fn refusal_below_the_learned_limit_lowers_it() {
+        let gate = Arc::new(SessionChannelGate::default());
+        let a = gate.try_acquire().expect("slot");
+        let _b = gate.try_acquire().expect("slot");
+        assert_eq!(gate.record_refusal(), Refusal::Learned(2));
+        drop(a);
+        assert_eq!(gate.record_refusal(), Refusal::Learned(1));
+        assert_eq!(gate.usage().limit, Some(1));
+    }
+
+    #[test]
+    fn dropping_permit_frees_slot() {
+        let gate = Arc::new(SessionChannelGate::default());
+        let first = gate.try_acquire().expect("slot");
+        gate.record_refusal();
+        assert!(gate.try_acquire().is_none());
+        drop(first);
+        assert!(gate.try_acquire().is_some());
+    }
+
+    #[test]
+    fn classifies_limit_refusals() {
+        assert!(is_session_limit_refusal(&"ChannelOpenFailure(AdministrativelyProhibited)"));
+        assert!(is_session_limit_refusal(&"ChannelOpenFailure(ResourceShortage)"));
+        assert!(!is_session_limit_refusal(&"ChannelOpenFailure(ConnectFailed)"));
+    }
+}
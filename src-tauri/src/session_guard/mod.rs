@@ -0,0 +1,97 @@
+//! Duplicate-session guard for `ssh_connect`.
+//!
+//! Connecting to a target (same user, host, port and jump chain) that another
+//! connection already has a live session to fails with a `DUPLICATE_SESSION:`
+//! error carrying a JSON [`DuplicateSession`]. The UI asks the user and retries
+//! with a [`DuplicateChoice`]:
+//!
+//! - `new-channel` shares the existing session; terminals and SFTP for the new
+//!   connection open channels on it and count against the same `MaxSessions`.
+//! - `separate-connection` opens a second TCP connection, as before.
+//! - `focus-existing` connects nothing and points the UI at the live session.
+
+use crate::types::ConnectionConfig;
+use serde::{Deserialize, Serialize};
+
+/// Machine-readable prefix — must stay in sync with the TS duplicate-session prompt.
+pub const DUPLICATE_SESSION_PREFIX: &str = "DUPLICATE_SESSION:";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DuplicateChoice {
+    NewChannel,
+    SeparateConnection,
+    FocusExisting,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateSession {
+    pub existing_connection_id: String,
+    pub existing_name: String,
+    /// `user@host:port` of the shared target.
+    pub target: String,
+}
+
+/// Whether `a` and `b` reach the same account on the same machine. Jump chains
+/// must match too: the same private address behind two bastions is two hosts.
+pub fn same_target(a: &ConnectionConfig, b: &ConnectionConfig) -> bool {
+    a.host.trim().eq_ignore_ascii_case(b.host.trim())
+        && a.port == b.port
+        && a.username == b.username
+        && match (&a.jump_host, &b.jump_host) {
+            (None, None) => true,
+            (Some(a), Some(b)) => same_target(a, b),
+            _ => false,
+        }
+}
+
+pub fn duplicate_error(existing: &ConnectionConfig) -> String {
+    let duplicate = DuplicateSession {
+        existing_connection_id: existing.id.clone(),
+        existing_name: existing.name.clone(),
+        target: format!("{}@{}:{}", existing.username, existing.host, existing.port),
+    };
+    let payload = serde_json::to_string(&duplicate).unwrap_or_else(|_| "{}".to_string());
+    format!("{DUPLICATE_SESSION_PREFIX}{payload}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(id: &str, host: &str, jump: Option<ConnectionConfig>) -> ConnectionConfig {
+        ConnectionConfig {
+            id: id.to_string(),
+            name: id.to_string(),
+            jump_host: jump.map(Box::new),
+            ..ConnectionConfig::for_test(host, "deploy")
+        }
+    }
+
+    #[test]
+    fn targets_match_on_account_and_jump_chain() {
+        let direct = config("a", "10.0.0.5", None);
+        assert!(same_target(&direct, &config("b", "10.0.0.5", None)));
+        assert!(same_target(
+            &config("a", "Web.Example.com", None),
+            &config("b", "web.example.com ", None)
+        ));
+
+        let via_east = config("c", "10.0.0.5", Some(config("e", "bastion-east", None)));
+        let via_west = config("d", "10.0.0.5", Some(config("w", "bastion-west", None)));
+        assert!(!same_target(&direct, &via_east));
+        assert!(!same_target(&via_east, &via_west));
+    }
+
+    #[test]
+    fn duplicate_error_carries_the_existing_session() {
+        let error = duplicate_error(&config("prod-web", "web.example.com", None));
+        let payload = error
+            .strip_prefix(DUPLICATE_SESSION_PREFIX)
+            .expect("prefixed");
+        let value: serde_json::Value = serde_json::from_str(payload).expect("json payload");
+        assert_eq!(value["existingConnectionId"], "prod-web");
+        assert_eq!(value["target"], "deploy@web.example.com:22");
+    }
+}
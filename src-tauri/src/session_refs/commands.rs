@@ -0,0 +1,21 @@
+use super::{hold, release, usage, Consumer, SessionUsage};
+
+/// Hold a connection's session for a frontend consumer (an open file
+/// browser, say) until [`session_release`]. Returns the key to release.
+#[tauri::command]
+pub async fn session_hold(connection_id: String, consumer: Consumer) -> Result<String, String> {
+    let key = uuid::Uuid::new_v4().to_string();
+    hold(consumer, &key, &connection_id);
+    Ok(key)
+}
+
+#[tauri::command]
+pub async fn session_release(key: String, consumer: Consumer) -> Result<(), String> {
+    release(consumer, &key);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn session_usage(connection_id: String) -> Result<SessionUsage, String> {
+    Ok(usage(&connection_id))
+}
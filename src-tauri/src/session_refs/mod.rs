@@ -0,0 +1,319 @@
+//! Who is using each SSH session, and whether it should stay open.
+//!
+//! Terminals, tunnels, SFTP (an open file browser or a transfer) and exec
+//! calls hold a reference on their connection while they run. A
+//! connection's [`SessionLifetime`] decides what happens as they let go:
+//!
+//! - `manual` (the default) keeps the session until it is disconnected.
+//! - `withTerminals` closes it when the last terminal closes, even if
+//!   tunnels are still running.
+//! - `withTunnels` closes it when the last tunnel stops, even if terminals
+//!   are still open.
+//! - `untilIdle` closes it once nothing uses it.
+//!
+//! SFTP and exec work in flight always delays closing, and exec alone never
+//! starts it, so a background probe can't end a session nobody opened a
+//! terminal or tunnel on. Closing waits [`GRACE`] and checks again, so
+//! reopening a terminal or restarting a tunnel right away keeps the session.
+//! The frontend is told through `connection:session-closed`.
+
+pub mod commands;
+
+use crate::commands::AppState;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{LazyLock, Mutex, MutexGuard, OnceLock};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::mpsc;
+
+const GRACE: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Consumer {
+    Terminal,
+    Tunnel,
+    Sftp,
+    Exec,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SessionLifetime {
+    #[default]
+    Manual,
+    WithTerminals,
+    WithTunnels,
+    UntilIdle,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionUsage {
+    pub terminals: u32,
+    pub tunnels: u32,
+    pub sftp: u32,
+    pub exec: u32,
+}
+
+impl SessionUsage {
+    fn count_mut(&mut self, consumer: Consumer) -> &mut u32 {
+        match consumer {
+            Consumer::Terminal => &mut self.terminals,
+            Consumer::Tunnel => &mut self.tunnels,
+            Consumer::Sftp => &mut self.sftp,
+            Consumer::Exec => &mut self.exec,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Session {
+    lifetime: SessionLifetime,
+    usage: SessionUsage,
+    /// Consumers other than exec that held the session at some point.
+    used: HashSet<Consumer>,
+}
+
+impl Session {
+    /// Why the session should close now, if it should.
+    fn close_reason(&self) -> Option<&'static str> {
+        let usage = &self.usage;
+        if usage.sftp > 0 || usage.exec > 0 {
+            return None;
+        }
+        match self.lifetime {
+            SessionLifetime::Manual => None,
+            SessionLifetime::WithTerminals => (self.used.contains(&Consumer::Terminal)
+                && usage.terminals == 0)
+                .then_some("its last terminal closed"),
+            SessionLifetime::WithTunnels => (self.used.contains(&Consumer::Tunnel)
+                && usage.tunnels == 0)
+                .then_some("its last tunnel stopped"),
+            SessionLifetime::UntilIdle => {
+                (!self.used.is_empty() && usage.terminals == 0 && usage.tunnels == 0)
+                    .then_some("nothing uses it anymore")
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct Registry {
+    sessions: HashMap<String, Session>,
+    /// `(consumer, key)` to the connection it holds.
+    holds: HashMap<(Consumer, String), String>,
+}
+
+impl Registry {
+    fn hold(&mut self, consumer: Consumer, key: &str, connection_id: &str) {
+        let hold = (consumer, key.to_string());
+        if self.holds.contains_key(&hold) {
+            return;
+        }
+        self.holds.insert(hold, connection_id.to_string());
+        let session = self.sessions.entry(connection_id.to_string()).or_default();
+        *session.usage.count_mut(consumer) += 1;
+        if consumer != Consumer::Exec {
+            session.used.insert(consumer);
+        }
+    }
+
+    /// The connection released, when that leaves it to be closed.
+    fn release(&mut self, consumer: Consumer, key: &str) -> Option<String> {
+        let connection_id = self.holds.remove(&(consumer, key.to_string()))?;
+        let session = self.sessions.get_mut(&connection_id)?;
+        let count = session.usage.count_mut(consumer);
+        *count = count.saturating_sub(1);
+        session.close_reason().map(|_| connection_id)
+    }
+
+    fn forget(&mut self, connection_id: &str) {
+        self.sessions.remove(connection_id);
+        self.holds.retain(|_, held| held != connection_id);
+    }
+}
+
+static REGISTRY: LazyLock<Mutex<Registry>> = LazyLock::new(|| Mutex::new(Registry::default()));
+/// Connections that may need closing, for the task started by [`start`].
+static CLOSER: OnceLock<mpsc::UnboundedSender<String>> = OnceLock::new();
+
+fn lock() -> MutexGuard<'static, Registry> {
+    REGISTRY
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Apply the connection's policy; called whenever a session is opened.
+pub fn connected(connection_id: &str, lifetime: Option<SessionLifetime>) {
+    lock()
+        .sessions
+        .entry(connection_id.to_string())
+        .or_default()
+        .lifetime = lifetime.unwrap_or_default();
+}
+
+/// Drop everything known about a connection once it is disconnected, so
+/// consumers torn down with it don't count.
+pub fn forget(connection_id: &str) {
+    lock().forget(connection_id);
+}
+
+/// Record `key` as using the connection. Holding the same key twice counts once.
+pub fn hold(consumer: Consumer, key: &str, connection_id: &str) {
+    lock().hold(consumer, key, connection_id);
+}
+
+/// Let go of `key`; unknown keys are ignored.
+pub fn release(consumer: Consumer, key: &str) {
+    let closing = lock().release(consumer, key);
+    if let (Some(connection_id), Some(closer)) = (closing, CLOSER.get()) {
+        let _ = closer.send(connection_id);
+    }
+}
+
+pub fn usage(connection_id: &str) -> SessionUsage {
+    lock()
+        .sessions
+        .get(connection_id)
+        .map(|session| session.usage)
+        .unwrap_or_default()
+}
+
+/// A hold released on drop, for consumers that live in one scope or struct.
+pub struct SessionRef {
+    consumer: Consumer,
+    key: String,
+}
+
+impl SessionRef {
+    pub fn acquire(connection_id: &str, consumer: Consumer) -> Self {
+        let key = uuid::Uuid::new_v4().to_string();
+        hold(consumer, &key, connection_id);
+        Self { consumer, key }
+    }
+}
+
+impl Drop for SessionRef {
+    fn drop(&mut self) {
+        release(self.consumer, &self.key);
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionClosed {
+    connection_id: String,
+    reason: String,
+}
+
+async fn close_if_unused(app: &AppHandle, connection_id: &str) {
+    tokio::time::sleep(GRACE).await;
+    let Some(reason) = lock()
+        .sessions
+        .get(connection_id)
+        .and_then(Session::close_reason)
+    else {
+        return;
+    };
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    if !state.connections.lock().await.contains_key(connection_id) {
+        return;
+    }
+    println!("[SESSION] Closing {connection_id}: {reason}");
+    if let Err(error) = crate::commands::disconnect_connection(app, &state, connection_id).await {
+        eprintln!("[SESSION] Closing {connection_id} failed: {error}");
+        return;
+    }
+    let _ = app.emit(
+        "connection:session-closed",
+        SessionClosed {
+            connection_id: connection_id.to_string(),
+            reason: reason.to_string(),
+        },
+    );
+}
+
+pub fn start(app: AppHandle) {
+    let (closer, mut closing) = mpsc::unbounded_channel::<String>();
+    if CLOSER.set(closer).is_err() {
+        return;
+    }
+    crate::crash_reports::spawn_supervised("session-lifetime", async move {
+        while let Some(connection_id) = closing.recv().await {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                close_if_unused(&app, &connection_id).await;
+            });
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_lifetime(lifetime: SessionLifetime) -> Registry {
+        let mut registry = Registry::default();
+        registry.sessions.insert(
+            "conn".to_string(),
+            Session {
+                lifetime,
+                ..Session::default()
+            },
+        );
+        registry
+    }
+
+    #[test]
+    fn counts_each_hold_once() {
+        let mut registry = with_lifetime(SessionLifetime::Manual);
+        registry.hold(Consumer::Terminal, "t1", "conn");
+        registry.hold(Consumer::Terminal, "t1", "conn");
+        registry.hold(Consumer::Terminal, "t2", "conn");
+        registry.hold(Consumer::Tunnel, "fwd", "conn");
+        assert_eq!(registry.sessions["conn"].usage.terminals, 2);
+        assert_eq!(registry.sessions["conn"].usage.tunnels, 1);
+
+        assert_eq!(registry.release(Consumer::Terminal, "t1"), None);
+        assert_eq!(registry.release(Consumer::Terminal, "t1"), None);
+        assert_eq!(registry.release(Consumer::Tunnel, "t2"), None);
+        assert_eq!(registry.sessions["conn"].usage.terminals, 1);
+
+        registry.forget("conn");
+        assert!(registry.holds.is_empty());
+        assert_eq!(registry.release(Consumer::Terminal, "t2"), None);
+    }
+
+    #[test]
+    fn closes_per_lifetime() {
+        let closes = Some("conn".to_string());
+
+        let mut registry = with_lifetime(SessionLifetime::WithTerminals);
+        registry.hold(Consumer::Terminal, "term", "conn");
+        registry.hold(Consumer::Tunnel, "fwd", "conn");
+        registry.hold(Consumer::Sftp, "upload", "conn");
+        assert_eq!(registry.release(Consumer::Terminal, "term"), None);
+        assert_eq!(registry.release(Consumer::Sftp, "upload"), closes);
+
+        let mut registry = with_lifetime(SessionLifetime::WithTunnels);
+        registry.hold(Consumer::Terminal, "term", "conn");
+        registry.hold(Consumer::Tunnel, "fwd", "conn");
+        assert_eq!(registry.release(Consumer::Tunnel, "fwd"), closes);
+
+        let mut registry = with_lifetime(SessionLifetime::UntilIdle);
+        registry.hold(Consumer::Exec, "probe", "conn");
+        assert_eq!(registry.release(Consumer::Exec, "probe"), None);
+        registry.hold(Consumer::Terminal, "term", "conn");
+        registry.hold(Consumer::Tunnel, "fwd", "conn");
+        assert_eq!(registry.release(Consumer::Terminal, "term"), None);
+        assert_eq!(registry.release(Consumer::Tunnel, "fwd"), closes);
+
+        let mut registry = with_lifetime(SessionLifetime::Manual);
+        registry.hold(Consumer::Terminal, "term", "conn");
+        assert_eq!(registry.release(Consumer::Terminal, "term"), None);
+    }
+}
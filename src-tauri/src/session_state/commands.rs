@@ -0,0 +1,12 @@
+use super::{all, cancel, SessionStatus};
+
+/// Abort the connect in flight for `connection_id`. Returns whether one was running.
+#[tauri::command]
+pub async fn cancel_connect(connection_id: String) -> Result<bool, String> {
+    Ok(cancel(&connection_id))
+}
+
+#[tauri::command]
+pub async fn session_states() -> Result<Vec<SessionStatus>, String> {
+    Ok(all())
+}
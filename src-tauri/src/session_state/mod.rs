@@ -0,0 +1,395 @@
+//! Per-connection session lifecycle.
+//!
+//! ```text
+//! closed → connecting → authenticating → ready ⇄ degraded
+//!            ↑   └──────────┴──(cancel / failure)──→ closed
+//!            └──────────── reconnect from ready/degraded
+//! ```
+//!
+//! Connect attempts are registered here so they can be cancelled while the
+//! TCP handshake or authentication is still in flight (`cancel_connect`, or
+//! `cancel_operation("connect:<id>")`; see `operations`).
+//! Cancelling drops the attempt's future, which closes the half-open transport.
+//! Ready, degraded and closed follow the event bus; every transition is
+//! published as `ConnectionEvent::StateChanged`.
+
+pub mod commands;
+
+use crate::events::ConnectionEvent;
+use crate::operations::{Operation, OperationKind};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex as StdMutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::{broadcast::error::RecvError, watch};
+
+/// Machine-readable prefix for connects stopped by `cancel_connect`.
+pub const CANCELLED_PREFIX: &str = "CANCELLED:";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SessionState {
+    Connecting,
+    Authenticating,
+    Ready,
+    /// The transport dropped or stopped answering; tabs wait for a reconnect.
+    Degraded,
+    Closed,
+}
+
+impl SessionState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SessionState::Connecting => "connecting",
+            SessionState::Authenticating => "authenticating",
+            SessionState::Ready => "ready",
+            SessionState::Degraded => "degraded",
+            SessionState::Closed => "closed",
+        }
+    }
+
+    fn can_become(self, next: SessionState) -> bool {
+        use SessionState::*;
+        matches!(
+            (self, next),
+            (Closed, Connecting)
+                | (Connecting, Authenticating | Ready | Closed)
+                | (Authenticating, Ready | Closed)
+                | (Ready, Connecting | Degraded | Closed)
+                | (Degraded, Connecting | Ready | Closed)
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionStatus {
+    pub connection_id: String,
+    pub state: SessionState,
+    pub since_ms: u64,
+    /// A connect or reconnect is in flight and can be cancelled.
+    pub cancellable: bool,
+}
+
+struct Entry {
+    state: SessionState,
+    since_ms: u64,
+    attempt: Option<(u64, watch::Sender<bool>)>,
+}
+
+static SESSIONS: LazyLock<StdMutex<HashMap<String, Entry>>> =
+    LazyLock::new(|| StdMutex::new(HashMap::new()));
+static NEXT_ATTEMPT: AtomicU64 = AtomicU64::new(1);
+
+fn lock_sessions() -> std::sync::MutexGuard<'static, HashMap<String, Entry>> {
+    SESSIONS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn publish_change(connection_id: &str, previous: SessionState, state: SessionState) {
+    crate::events::publish(
+        connection_id,
+        ConnectionEvent::StateChanged { state, previous },
+    );
+}
+
+/// Move `connection_id` to `next` if the lifecycle allows it from the current state.
+/// `only_from` restricts the move to a specific current state.
+fn transition(connection_id: &str, next: SessionState, only_from: Option<SessionState>) -> bool {
+    let previous = {
+        let mut sessions = lock_sessions();
+        let entry = sessions
+            .entry(connection_id.to_string())
+            .or_insert_with(|| Entry {
+                state: SessionState::Closed,
+                since_ms: now_ms(),
+                attempt: None,
+            });
+        let previous = entry.state;
+        if only_from.is_some_and(|required| required != previous) || !previous.can_become(next) {
+            return false;
+        }
+        entry.state = next;
+        entry.since_ms = now_ms();
+        // Disconnecting mid-connect abandons the attempt.
+        if next == SessionState::Closed {
+            if let Some((_, cancel)) = entry.attempt.take() {
+                let _ = cancel.send(true);
+            }
+        }
+        previous
+    };
+    publish_change(connection_id, previous, next);
+    true
+}
+
+/// Record that the handshake finished and authentication started.
+/// Ignored unless a connect for `connection_id` is in flight (jump hops, test
+/// connections and key-rotation probes reuse ids without registering).
+pub(crate) fn mark_authenticating(connection_id: &str) {
+    transition(
+        connection_id,
+        SessionState::Authenticating,
+        Some(SessionState::Connecting),
+    );
+}
+
+pub fn status(connection_id: &str) -> Option<SessionStatus> {
+    lock_sessions()
+        .get(connection_id)
+        .map(|entry| SessionStatus {
+            connection_id: connection_id.to_string(),
+            state: entry.state,
+            since_ms: entry.since_ms,
+            cancellable: entry.attempt.is_some(),
+        })
+}
+
+pub fn all() -> Vec<SessionStatus> {
+    let sessions = lock_sessions();
+    let mut statuses: Vec<SessionStatus> = sessions
+        .iter()
+        .map(|(id, entry)| SessionStatus {
+            connection_id: id.clone(),
+            state: entry.state,
+            since_ms: entry.since_ms,
+            cancellable: entry.attempt.is_some(),
+        })
+        .collect();
+    statuses.sort_by(|a, b| a.connection_id.cmp(&b.connection_id));
+    statuses
+}
+
+/// Cancel the in-flight connect for `connection_id`. Returns whether one was running.
+pub fn cancel(connection_id: &str) -> bool {
+    let attempt = lock_sessions()
+        .get_mut(connection_id)
+        .and_then(|entry| entry.attempt.take());
+    match attempt {
+        Some((_, cancel)) => {
+            let _ = cancel.send(true);
+            true
+        }
+        None => false,
+    }
+}
+
+/// A registered connect attempt. Dropping it without [`ConnectAttempt::finish`]
+/// (e.g. the command future was dropped) closes the session state.
+pub struct ConnectAttempt {
+    connection_id: String,
+    id: u64,
+    cancelled: watch::Receiver<bool>,
+    operation: Operation,
+    finished: bool,
+}
+
+/// Register a connect for `connection_id`, superseding (and cancelling) any
+/// attempt already in flight for it.
+pub fn begin_connect(connection_id: &str) -> ConnectAttempt {
+    let id = NEXT_ATTEMPT.fetch_add(1, Ordering::Relaxed);
+    let (sender, cancelled) = watch::channel(false);
+    let previous_state = {
+        let mut sessions = lock_sessions();
+        let entry = sessions
+            .entry(connection_id.to_string())
+            .or_insert_with(|| Entry {
+                state: SessionState::Closed,
+                since_ms: now_ms(),
+                attempt: None,
+            });
+        if let Some((_, superseded)) = entry.attempt.replace((id, sender)) {
+            let _ = superseded.send(true);
+        }
+        let previous = entry.state;
+        entry.state = SessionState::Connecting;
+        entry.since_ms = now_ms();
+        previous
+    };
+    if previous_state != SessionState::Connecting {
+        publish_change(connection_id, previous_state, SessionState::Connecting);
+    }
+    ConnectAttempt {
+        connection_id: connection_id.to_string(),
+        id,
+        cancelled,
+        operation: crate::operations::begin(
+            OperationKind::Connect,
+            Some(format!("connect:{connection_id}")),
+            connection_id,
+        ),
+        finished: false,
+    }
+}
+
+impl ConnectAttempt {
+    /// Drive `connect` unless the attempt is cancelled first.
+    pub async fn run<T>(
+        &self,
+        connect: impl Future<Output = Result<T, String>>,
+    ) -> Result<T, String> {
+        let mut cancelled = self.cancelled.clone();
+        let cancellation = async move {
+            // A dropped sender means the attempt was cleared, not cancelled.
+            if cancelled.wait_for(|cancelled| *cancelled).await.is_err() {
+                std::future::pending::<()>().await;
+            }
+        };
+        tokio::select! {
+            result = connect => result,
+            _ = cancellation => Err(self.cancelled_error()),
+            _ = self.operation.token().cancelled() => Err(self.cancelled_error()),
+        }
+    }
+
+    fn cancelled_error(&self) -> String {
+        format!(
+            "{CANCELLED_PREFIX} Connect to {} was cancelled",
+            self.connection_id
+        )
+    }
+
+    /// Settle the attempt: ready on success, closed on failure or cancellation.
+    pub fn finish(mut self, succeeded: bool) {
+        self.finished = true;
+        self.settle(if succeeded {
+            SessionState::Ready
+        } else {
+            SessionState::Closed
+        });
+    }
+
+    fn settle(&self, state: SessionState) {
+        let previous = {
+            let mut sessions = lock_sessions();
+            let Some(entry) = sessions.get_mut(&self.connection_id) else {
+                return;
+            };
+            // A newer attempt owns the state now.
+            if entry.attempt.as_ref().is_some_and(|(id, _)| *id != self.id) {
+                return;
+            }
+            entry.attempt = None;
+            let previous = entry.state;
+            if previous == state
+                || !matches!(
+                    previous,
+                    SessionState::Connecting | SessionState::Authenticating
+                )
+            {
+                return;
+            }
+            entry.state = state;
+            entry.since_ms = now_ms();
+            previous
+        };
+        publish_change(&self.connection_id, previous, state);
+    }
+}
+
+impl Drop for ConnectAttempt {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.settle(SessionState::Closed);
+        }
+    }
+}
+
+/// Follow the event bus for transitions that happen outside connect attempts.
+pub fn start() {
+    tauri::async_runtime::spawn(async move {
+        let mut events = crate::events::subscribe();
+        loop {
+            let envelope = match events.recv().await {
+                Ok(envelope) => envelope,
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            };
+            let next = match envelope.event {
+                ConnectionEvent::Reconnected => SessionState::Ready,
+                ConnectionEvent::TransportLost | ConnectionEvent::KeepaliveTimeout => {
+                    SessionState::Degraded
+                }
+                ConnectionEvent::Disconnected => SessionState::Closed,
+                _ => continue,
+            };
+            transition(&envelope.connection_id, next, None);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lifecycle_rejects_skipped_steps() {
+        assert!(SessionState::Closed.can_become(SessionState::Connecting));
+        assert!(!SessionState::Closed.can_become(SessionState::Ready));
+        assert!(!SessionState::Authenticating.can_become(SessionState::Degraded));
+        assert!(SessionState::Degraded.can_become(SessionState::Connecting));
+    }
+
+    #[tokio::test]
+    async fn cancel_stops_an_in_flight_connect() {
+        let attempt = begin_connect("state-test-cancel");
+        mark_authenticating("state-test-cancel");
+        assert_eq!(
+            status("state-test-cancel").map(|status| (status.state, status.cancellable)),
+            Some((SessionState::Authenticating, true))
+        );
+
+        let connect = std::future::pending::<Result<(), String>>();
+        let (result, cancelled) = tokio::join!(attempt.run(connect), async {
+            tokio::task::yield_now().await;
+            cancel("state-test-cancel")
+        });
+        assert!(cancelled);
+        assert!(result.is_err_and(|error| error.starts_with(CANCELLED_PREFIX)));
+        attempt.finish(false);
+        assert_eq!(
+            status("state-test-cancel").map(|status| status.state),
+            Some(SessionState::Closed)
+        );
+    }
+
+    #[tokio::test]
+    async fn cancel_operation_stops_a_connect() {
+        let attempt = begin_connect("state-test-operation");
+        let connect = std::future::pending::<Result<(), String>>();
+        let (result, cancelled) = tokio::join!(attempt.run(connect), async {
+            tokio::task::yield_now().await;
+            crate::operations::cancel("connect:state-test-operation")
+        });
+        assert!(cancelled);
+        assert!(result.is_err_and(|error| error.starts_with(CANCELLED_PREFIX)));
+        drop(attempt);
+        assert!(!crate::operations::cancel("connect:state-test-operation"));
+    }
+
+    #[test]
+    fn superseded_attempts_do_not_settle_the_new_one() {
+        let first = begin_connect("state-test-supersede");
+        let second = begin_connect("state-test-supersede");
+        drop(first);
+        assert_eq!(
+            status("state-test-supersede").map(|status| status.state),
+            Some(SessionState::Connecting)
+        );
+        second.finish(true);
+        assert_eq!(
+            status("state-test-supersede").map(|status| status.state),
+            Some(SessionState::Ready)
+        );
+    }
+}
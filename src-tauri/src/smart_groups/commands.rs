@@ -0,0 +1,25 @@
+use super::{evaluate, matching_ids, query, SmartGroupMembership};
+use crate::commands::get_data_dir;
+use crate::sync::domain_hosts::{load_saved_data, CONNECTIONS_MUTATION_LOCK};
+use crate::types::SavedData;
+use tauri::AppHandle;
+
+fn load_connections(app: &AppHandle) -> Result<SavedData, String> {
+    let _guard = CONNECTIONS_MUTATION_LOCK
+        .lock()
+        .map_err(|error| error.to_string())?;
+    load_saved_data(&get_data_dir(app).join("connections.json")).map_err(|error| error.to_string())
+}
+
+/// Current membership of every smart group.
+#[tauri::command]
+pub async fn smart_groups_evaluate(app: AppHandle) -> Result<Vec<SmartGroupMembership>, String> {
+    Ok(evaluate(&load_connections(&app)?))
+}
+
+/// Validate `query` and list the connections it would match, for the group editor.
+#[tauri::command]
+pub async fn smart_groups_preview(app: AppHandle, query: String) -> Result<Vec<String>, String> {
+    let parsed = query::parse(&query)?;
+    Ok(matching_ids(&parsed, &load_connections(&app)?.connections))
+}
@@ -0,0 +1,72 @@
+//! Smart groups: folders whose membership is a saved query instead of a path.
+//!
+//! Membership is recomputed whenever connections are saved or merged, and
+//! `smart-groups:updated` is emitted only when some group actually changed.
+
+pub mod commands;
+pub mod query;
+
+use crate::types::{SavedConnection, SavedData};
+use serde::Serialize;
+use std::sync::{LazyLock, Mutex as StdMutex};
+use tauri::{AppHandle, Emitter};
+
+static LAST_MEMBERSHIP: LazyLock<StdMutex<Option<Vec<SmartGroupMembership>>>> =
+    LazyLock::new(|| StdMutex::new(None));
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmartGroupMembership {
+    pub name: String,
+    pub query: String,
+    pub connection_ids: Vec<String>,
+    /// Parse error for an invalid query; the group then has no members.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+pub fn matching_ids(query: &query::Query, connections: &[SavedConnection]) -> Vec<String> {
+    connections
+        .iter()
+        .filter(|connection| query.matches(connection))
+        .map(|connection| connection.id.clone())
+        .collect()
+}
+
+pub fn evaluate(data: &SavedData) -> Vec<SmartGroupMembership> {
+    data.folders
+        .iter()
+        .filter_map(|folder| {
+            let text = folder.query.as_deref()?.trim();
+            if text.is_empty() {
+                return None;
+            }
+            let (connection_ids, error) = match query::parse(text) {
+                Ok(parsed) => (matching_ids(&parsed, &data.connections), None),
+                Err(error) => (Vec::new(), Some(error)),
+            };
+            Some(SmartGroupMembership {
+                name: folder.name.clone(),
+                query: text.to_string(),
+                connection_ids,
+                error,
+            })
+        })
+        .collect()
+}
+
+/// Recompute membership after `data` was written and notify the UI if it changed.
+pub fn publish_membership(app: &AppHandle, data: &SavedData) {
+    let membership = evaluate(data);
+    {
+        let mut last = match LAST_MEMBERSHIP.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if last.as_ref() == Some(&membership) {
+            return;
+        }
+        *last = Some(membership.clone());
+    }
+    let _ = app.emit("smart-groups:updated", membership);
+}
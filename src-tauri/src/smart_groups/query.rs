@@ -0,0 +1,284 @@
+//! Smart-group query language.
+//!
+//! ```text
+//! tag:prod AND host:*.eu-*
+//! (tag:db OR name:postgres*) NOT user:root
+//! ```
+//!
+//! Terms are `field:pattern` with fields `tag`, `host`, `name`, `user`, `folder`
+//! and `port`; patterns are case-insensitive globs (`*`, `?`) and may be quoted.
+//! A bare word matches name or host as a substring. Adjacent terms are ANDed,
+//! `-term` is shorthand for `NOT term`.
+
+use crate::types::SavedConnection;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Tag,
+    Host,
+    Name,
+    User,
+    Folder,
+    Port,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Query {
+    Term(Field, String),
+    /// Bare word: substring of name or host.
+    Text(String),
+    Not(Box<Query>),
+    And(Vec<Query>),
+    Or(Vec<Query>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Open,
+    Close,
+    And,
+    Or,
+    Not,
+    Word(String),
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&ch) = chars.peek() {
+        match ch {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            '-' => {
+                chars.next();
+                tokens.push(Token::Not);
+            }
+            _ => {
+                let mut word = String::new();
+                let mut quoted = false;
+                while let Some(&c) = chars.peek() {
+                    if c == '"' {
+                        quoted = !quoted;
+                        chars.next();
+                        continue;
+                    }
+                    if !quoted && (c.is_whitespace() || c == '(' || c == ')') {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                if quoted {
+                    return Err("Unclosed quote in query".to_string());
+                }
+                tokens.push(match word.to_ascii_uppercase().as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    _ => Token::Word(word),
+                });
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<Query, String> {
+        let mut parts = vec![self.parse_and()?];
+        while self.peek() == Some(&Token::Or) {
+            self.next();
+            parts.push(self.parse_and()?);
+        }
+        Ok(if parts.len() == 1 { parts.remove(0) } else { Query::Or(parts) })
+    }
+
+    fn parse_and(&mut self) -> Result<Query, String> {
+        let mut parts = vec![self.parse_unary()?];
+        loop {
+            match self.peek() {
+                Some(Token::And) => {
+                    self.next();
+                }
+                Some(Token::Open | Token::Not | Token::Word(_)) => {}
+                _ => break,
+            }
+            parts.push(self.parse_unary()?);
+        }
+        Ok(if parts.len() == 1 { parts.remove(0) } else { Query::And(parts) })
+    }
+
+    fn parse_unary(&mut self) -> Result<Query, String> {
+        match self.next() {
+            Some(Token::Not) => Ok(Query::Not(Box::new(self.parse_unary()?))),
+            Some(Token::Open) => {
+                let inner = self.parse_or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(inner),
+                    _ => Err("Missing ')' in query".to_string()),
+                }
+            }
+            Some(Token::Word(word)) => parse_term(&word),
+            Some(Token::Close) => Err("Unexpected ')' in query".to_string()),
+            Some(Token::And | Token::Or) => Err("AND/OR must sit between two terms".to_string()),
+            None => Err("Query ends unexpectedly".to_string()),
+        }
+    }
+}
+
+fn parse_term(word: &str) -> Result<Query, String> {
+    let Some((field, pattern)) = word.split_once(':') else {
+        return Ok(Query::Text(word.to_lowercase()));
+    };
+    let field = match field.to_ascii_lowercase().as_str() {
+        "tag" => Field::Tag,
+        "host" => Field::Host,
+        "name" => Field::Name,
+        "user" => Field::User,
+        "folder" => Field::Folder,
+        "port" => Field::Port,
+        other => return Err(format!("Unknown field '{other}' (use tag, host, name, user, folder or port)")),
+    };
+    if pattern.is_empty() {
+        return Err(format!("Missing value after '{word}'"));
+    }
+    Ok(Query::Term(field, pattern.to_lowercase()))
+}
+
+pub fn parse(input: &str) -> Result<Query, String> {
+    let tokens = tokenize(input)?;
+    if tokens.is_empty() {
+        return Err("Query is empty".to_string());
+    }
+    let mut parser = Parser { tokens, pos: 0 };
+    let query = parser.parse_or()?;
+    if parser.pos < parser.tokens.len() {
+        return Err("Unexpected ')' in query".to_string());
+    }
+    Ok(query)
+}
+
+/// Case-insensitive glob; `pattern` must already be lowercase.
+fn glob_match(pattern: &str, value: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let value: Vec<char> = value.to_lowercase().chars().collect();
+    let (mut p, mut v) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while v < value.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == value[v]) {
+            p += 1;
+            v += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, v));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            v = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+impl Query {
+    pub fn matches(&self, connection: &SavedConnection) -> bool {
+        match self {
+            Query::Term(field, pattern) => match field {
+                Field::Tag => connection
+                    .tags
+                    .iter()
+                    .flatten()
+                    .any(|tag| glob_match(pattern, tag)),
+                Field::Host => glob_match(pattern, &connection.host),
+                Field::Name => glob_match(pattern, &connection.name),
+                Field::User => glob_match(pattern, &connection.username),
+                Field::Folder => connection
+                    .folder
+                    .as_deref()
+                    .is_some_and(|folder| glob_match(pattern, folder)),
+                Field::Port => glob_match(pattern, &connection.port.to_string()),
+            },
+            Query::Text(text) => {
+                connection.name.to_lowercase().contains(text)
+                    || connection.host.to_lowercase().contains(text)
+            }
+            Query::Not(inner) => !inner.matches(connection),
+            Query::And(parts) => parts.iter().all(|part| part.matches(connection)),
+            Query::Or(parts) => parts.iter().any(|part| part.matches(connection)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn connection(host: &str, tags: &[&str]) -> SavedConnection {
+        SavedConnection {
+            id: host.to_string(),
+            name: host.to_string(),
+            host: host.to_string(),
+            port: 22,
+            username: "deploy".to_string(),
+            tags: Some(tags.iter().map(|tag| tag.to_string()).collect()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn and_of_tag_and_host_glob() {
+        let query = parse("tag:prod AND host:*.eu-*").expect("parse");
+        assert!(query.matches(&connection("db1.eu-west.example.com", &["Prod"])));
+        assert!(!query.matches(&connection("db1.us-east.example.com", &["prod"])));
+        assert!(!query.matches(&connection("db1.eu-west.example.com", &["dev"])));
+    }
+
+    #[test]
+    fn precedence_negation_and_implicit_and() {
+        let query = parse("(tag:db OR tag:cache) -host:*staging*").expect("parse");
+        assert!(query.matches(&connection("redis.prod", &["cache"])));
+        assert!(!query.matches(&connection("redis.staging", &["cache"])));
+        assert!(!query.matches(&connection("web.prod", &["web"])));
+    }
+
+    #[test]
+    fn reports_syntax_errors() {
+        assert!(parse("tag:prod AND").is_err());
+        assert!(parse("(tag:prod").is_err());
+        assert!(parse("colour:red").is_err());
+        assert!(parse("   ").is_err());
+    }
+
+    #[test]
+    fn glob_backtracks() {
+        assert!(glob_match("a*b*c", "aXbYbZc"));
+        assert!(glob_match("db-??", "DB-01"));
+        assert!(!glob_match("db-?", "db-01"));
+    }
+}
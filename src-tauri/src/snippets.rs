@@ -1,3 +1,5 @@
+use crate::data_format::to_versioned_json;
+use crate::data_validation::{parse_or_repair, DataFile};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -89,7 +91,7 @@ impl SnippetsManager {
     }
 }
 
-fn read_snippets_data(path: &Path) -> Result<SnippetsData, String> {
+pub(crate) fn read_snippets_data(path: &Path) -> Result<SnippetsData, String> {
     if !path.exists() {
         let temp_path = path.with_extension("tmp");
         let backup_path = path.with_extension("bak");
@@ -112,11 +114,11 @@ fn parse_snippets_candidate(path: &Path) -> Option<SnippetsData> {
 
 fn parse_snippets_file(path: &Path) -> Result<SnippetsData, String> {
     let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
-    serde_json::from_str(&content).map_err(|e| e.to_string())
+    parse_or_repair(DataFile::Snippets, path, &content)
 }
 
-fn write_snippets_atomic(path: &Path, data: &SnippetsData) -> Result<(), String> {
-    let json = serde_json::to_string_pretty(data).map_err(|e| e.to_string())?;
+pub(crate) fn write_snippets_atomic(path: &Path, data: &SnippetsData) -> Result<(), String> {
+    let json = to_versioned_json(DataFile::Snippets, path, data)?;
     crate::atomic_io::durable_replace(path, json.as_bytes())
         .map_err(|e| format!("Failed to write snippets file: {e}"))
 }
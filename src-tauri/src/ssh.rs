@@ -4,9 +4,12 @@ use russh::*;
 use russh_keys::*; // Re-adding this for key loading
 use std::sync::Arc;
 
+use crate::keepalive::KeepaliveSettings;
 use crate::tunnels::TunnelManager;
-use crate::types::{AuthMethod, ConnectionConfig};
+use crate::types::{AuthMethod, ConnectionConfig, SshTransportOptions, StrictHostKeyChecking};
 use russh::client::Msg;
+use std::borrow::Cow;
+use std::time::Duration;
 use tokio::net::TcpStream;
 
 #[derive(Clone)]
@@ -16,6 +19,47 @@ pub struct Client {
     pub connection_id: String,
     pub kept_alive_session: Option<Arc<Box<client::Handle<Client>>>>,
     pub agent_keys: Arc<std::sync::Mutex<Vec<russh_keys::key::KeyPair>>>,
+    /// Endpoint as recorded in known_hosts.
+    pub host: String,
+    pub port: u16,
+    pub strict_host_key_checking: StrictHostKeyChecking,
+    pub host_key_alias: Option<String>,
+    pub user_known_hosts_file: Option<String>,
+    /// Why the server key was refused; russh itself only reports `UnknownKey`.
+    pub host_key_rejection: Arc<std::sync::Mutex<Option<String>>>,
+}
+
+impl Client {
+    fn new(
+        config: &ConnectionConfig,
+        tunnel_manager: Arc<TunnelManager>,
+        kept_alive_session: Option<Arc<Box<client::Handle<Client>>>>,
+        agent_keys: Arc<std::sync::Mutex<Vec<russh_keys::key::KeyPair>>>,
+    ) -> Self {
+        Self {
+            tunnel_manager,
+            connection_id: config.id.clone(),
+            kept_alive_session,
+            agent_keys,
+            host: config.host.clone(),
+            port: config.port,
+            strict_host_key_checking: config.strict_host_key_checking,
+            host_key_alias: config.host_key_alias.clone(),
+            user_known_hosts_file: config.user_known_hosts_file.clone(),
+            host_key_rejection: Arc::new(std::sync::Mutex::new(None)),
+        }
+    }
+}
+
+/// Prefer the recorded host key rejection over russh's generic error.
+fn explain_handshake_error(
+    rejection: &std::sync::Mutex<Option<String>>,
+    error: russh::Error,
+) -> anyhow::Error {
+    match rejection.lock().ok().and_then(|mut reason| reason.take()) {
+        Some(reason) => anyhow!(reason),
+        None => error.into(),
+    }
 }
 
 impl std::fmt::Debug for Client {
@@ -25,6 +69,11 @@ impl std::fmt::Debug for Client {
             .field("connection_id", &self.connection_id)
             .field("kept_alive_session", &self.kept_alive_session.is_some())
             .field("agent_keys", &"Vec<KeyPair>")
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("strict_host_key_checking", &self.strict_host_key_checking)
+            .field("host_key_alias", &self.host_key_alias)
+            .field("user_known_hosts_file", &self.user_known_hosts_file)
             .finish()
     }
 }
@@ -35,11 +84,62 @@ impl client::Handler for Client {
 
     async fn check_server_key(
         &mut self,
-        _server_public_key: &russh_keys::key::PublicKey,
+        server_public_key: &russh_keys::key::PublicKey,
     ) -> Result<bool, Self::Error> {
-        // Validation is done during connect if strict checking is enabled,
-        // but for now we trust (or could implement known_hosts check here)
-        Ok(true)
+        let Some(home) = dirs::home_dir() else {
+            return Ok(self.strict_host_key_checking == StrictHostKeyChecking::No);
+        };
+        let (host, port) = crate::known_hosts::lookup_target(
+            &self.host,
+            self.port,
+            self.host_key_alias.as_deref(),
+        );
+        let setting = self
+            .user_known_hosts_file
+            .as_deref()
+            .filter(|setting| !setting.trim().is_empty());
+        let files = match setting {
+            None => vec![crate::user_profiles::default_known_hosts_file(&home)],
+            setting => crate::known_hosts::known_hosts_files(&home, setting),
+        };
+        let key_type = server_public_key.name();
+        let key = server_public_key.public_key_base64();
+        let rejected = match crate::known_hosts::verify_server_key(
+            &files,
+            host,
+            port,
+            key_type,
+            &key,
+            self.strict_host_key_checking,
+        ) {
+            Ok(()) => return Ok(true),
+            Err(rejected) => rejected,
+        };
+        if rejected.can_be_trusted() {
+            let prompt = crate::known_hosts::prompt::HostKeyPrompt {
+                prompt_id: String::new(),
+                connection_id: self.connection_id.clone(),
+                endpoint: crate::known_hosts::host_port(host, port),
+                key_type: key_type.to_string(),
+                fingerprint: crate::known_hosts::fingerprint(&key).unwrap_or_default(),
+                recorded_fingerprint: rejected.recorded_fingerprint.clone(),
+                recorded_at: rejected.recorded_at.clone(),
+            };
+            if crate::known_hosts::prompt::ask(prompt).await {
+                // The user decided for this connect; failing to save only means asking again.
+                if let Err(error) =
+                    crate::known_hosts::trust_host_key(&files, host, port, key_type, &key)
+                {
+                    eprintln!("[SSH] Could not record trusted host key: {error}");
+                }
+                return Ok(true);
+            }
+        }
+        eprintln!("[SSH] {}", rejected.reason);
+        if let Ok(mut rejection) = self.host_key_rejection.lock() {
+            *rejection = Some(rejected.reason);
+        }
+        Ok(false)
     }
 
     async fn server_channel_open_agent_forward(
@@ -129,14 +229,19 @@ impl client::Handler for Client {
                 match TcpStream::connect(&target_addr).await {
                     Ok(mut local_stream) => {
                         let mut channel_stream = channel.into_stream();
-                        if let Err(e) =
-                            tokio::io::copy_bidirectional(&mut channel_stream, &mut local_stream)
-                                .await
+                        match crate::tunnels::relay::copy_bidirectional(
+                            &mut channel_stream,
+                            &mut local_stream,
+                        )
+                        .await
                         {
-                            error!(
+                            Ok((sent, received)) => {
+                                crate::metrics::record_forwarded_bytes(sent + received)
+                            }
+                            Err(e) => error!(
                                 "[TUNNEL] copy_bidirectional error between channel_stream and local_stream: {:?}",
                                 e
-                            );
+                            ),
                         }
                     }
                     Err(e) => eprintln!(
@@ -283,6 +388,94 @@ fn handle_agent_request(
     }
 }
 
+/// Keep-alives every 60s unless `ServerAliveInterval` says otherwise, so idle
+/// sessions survive NAT/firewall timeouts. Read through
+/// [`KeepaliveSettings`] so russh and the liveness watch agree.
+fn client_config(options: &SshTransportOptions) -> client::Config {
+    let mut config = client::Config::default();
+    match KeepaliveSettings::from_options(Some(options)) {
+        Some(keepalive) => {
+            config.keepalive_interval = Some(keepalive.interval);
+            config.keepalive_max = keepalive.count_max;
+        }
+        None => config.keepalive_interval = None,
+    }
+    if let Some(enabled) = options.compression {
+        let order: &'static [russh::compression::Name] = if enabled {
+            &[
+                russh::compression::ZLIB_LEGACY,
+                russh::compression::ZLIB,
+                russh::compression::NONE,
+            ]
+        } else {
+            &[russh::compression::NONE]
+        };
+        config.preferred.compression = Cow::Borrowed(order);
+    }
+    config
+}
+
+/// `ConnectTimeout` covers the TCP connect and the key exchange, like OpenSSH.
+async fn with_connect_timeout<T>(
+    limit: Option<Duration>,
+    fut: impl std::future::Future<Output = T>,
+) -> Result<T> {
+    match limit {
+        Some(limit) => tokio::time::timeout(limit, fut)
+            .await
+            .map_err(|_| anyhow!("Connection timed out after {}s", limit.as_secs())),
+        None => Ok(fut.await),
+    }
+}
+
+/// Key exchange over a stream `dial` opens (a bridge or proxy) instead of TCP.
+async fn connect_over<S>(
+    client_config: Arc<client::Config>,
+    dial: impl std::future::Future<Output = Result<S>>,
+    handler: Client,
+    rejection: &std::sync::Mutex<Option<String>>,
+) -> Result<client::Handle<Client>>
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static,
+{
+    let stream = dial.await?;
+    russh::client::connect_stream(client_config, stream, handler)
+        .await
+        .map_err(|e| explain_handshake_error(rejection, e))
+}
+
+/// A `direct-tcpip` channel to `config` through `jump_session`. Errors carry
+/// whether the jump host refused forwarding outright.
+async fn open_jump_channel(
+    jump_session: &client::Handle<Client>,
+    config: &ConnectionConfig,
+    jump_host_config: &ConnectionConfig,
+) -> std::result::Result<Channel<Msg>, (bool, anyhow::Error)> {
+    jump_session
+        .channel_open_direct_tcpip(
+            config.host.clone(),
+            config.port as u32,
+            "0.0.0.0", // Originator IP (dummy)
+            0,         // Originator port (dummy)
+        )
+        .await
+        .map_err(|e| {
+            if crate::tunnels::runtime_notice::is_forwarding_prohibited(&e) {
+                let error = anyhow!(
+                    "Jump host {} {} — cannot reach {}:{}",
+                    jump_host_config.host,
+                    crate::tunnels::runtime_notice::FORWARDING_PROHIBITED_ERROR,
+                    config.host,
+                    config.port
+                );
+                (true, error)
+            } else {
+                let error = anyhow!("Failed to open direct-tcpip channel on jump host: {}", e);
+                (false, error)
+            }
+        })
+}
+
 pub struct SshManager {
     // Shared keys for virtual agent
     pub agent_keys: Arc<std::sync::Mutex<Vec<russh_keys::key::KeyPair>>>,
@@ -300,71 +493,141 @@ impl SshManager {
         config: ConnectionConfig,
         tunnel_manager: Arc<crate::tunnels::TunnelManager>,
     ) -> Result<client::Handle<Client>> {
-        // Keep-alive: send a heartbeat every 60s to prevent NAT/firewall timeouts on idle sessions
-        let client_config = client::Config {
-            keepalive_interval: Some(std::time::Duration::from_secs(60)),
-            keepalive_max: 3,
-            ..Default::default()
-        };
-        let client_config = Arc::new(client_config);
+        self.connect_hop(config, tunnel_manager, None).await
+    }
+
+    /// Connect one hop; `jump_for` names the connection it is the jump host for.
+    async fn connect_hop(
+        &self,
+        mut config: ConnectionConfig,
+        tunnel_manager: Arc<crate::tunnels::TunnelManager>,
+        jump_for: Option<String>,
+    ) -> Result<client::Handle<Client>> {
+        let options = config.ssh_options.clone().unwrap_or_default();
+        // The canonical name is also what known_hosts records are looked up under.
+        // Like a jump host, a bridge, proxy or Tor resolves the name on its side.
+        let resolved_remotely = config.jump_host.is_some()
+            || options.websocket.is_some()
+            || options.access_proxy.is_some()
+            || crate::tor::is_onion(&config.host);
+        config.host = crate::canonical_host::canonicalize(
+            &config.host,
+            config.port,
+            &options,
+            resolved_remotely,
+        )
+        .await
+        .map_err(|e| anyhow!(e))?;
+        let client_config = Arc::new(client_config(&options));
+        let connect_timeout = options.connect_timeout_secs.map(Duration::from_secs);
 
         // Recursive Jump Host Logic
         if let Some(ref jump_host_config) = config.jump_host {
-            // 1. Connect to Jump Host (Recursive)
-            let jump_session =
-                Box::pin(self.connect((**jump_host_config).clone(), tunnel_manager.clone()))
+            // 1. Connect to Jump Host (Recursive), or reuse the session other
+            //    connections already hold to it
+            let target_name = jump_for.clone().unwrap_or_else(|| config.name.clone());
+            let connect_jump = || {
+                Box::pin(self.connect_hop(
+                    (**jump_host_config).clone(),
+                    tunnel_manager.clone(),
+                    Some(target_name.clone()),
+                ))
+            };
+            let jump_error = |e: anyhow::Error| {
+                anyhow!("Failed to connect to jump host {}: {}", jump_host_config.host, e)
+            };
+            let (mut jump_session, reused) =
+                crate::jump_sessions::shared(jump_host_config, connect_jump)
                     .await
-                    .map_err(|e| anyhow!("Failed to connect to jump host: {}", e))?;
+                    .map_err(jump_error)?;
 
             // 2. Open Direct TCP/IP Channel through Jump Host
-            let channel = jump_session
-                .channel_open_direct_tcpip(
-                    config.host.clone(),
-                    config.port as u32,
-                    "0.0.0.0", // Originator IP (dummy)
-                    0,         // Originator port (dummy)
-                )
-                .await
-                .map_err(|e| anyhow!("Failed to open direct-tcpip channel on jump host: {}", e))?;
+            let mut opened = open_jump_channel(&jump_session, &config, jump_host_config).await;
+            if reused && opened.as_ref().is_err_and(|(prohibited, _)| !prohibited) {
+                // The shared session may be dead without having noticed yet.
+                crate::jump_sessions::evict(jump_host_config, &jump_session).await;
+                jump_session = crate::jump_sessions::shared(jump_host_config, connect_jump)
+                    .await
+                    .map_err(jump_error)?
+                    .0;
+                opened = open_jump_channel(&jump_session, &config, jump_host_config).await;
+            }
+            let channel = opened.map_err(|(_, error)| error)?;
 
             // 3. Establish SSH Session over the Channel
             let stream = channel.into_stream();
 
             // 4. Create handler with agent keys
-            let client_handler = Client {
-                tunnel_manager: tunnel_manager.clone(),
-                connection_id: config.id.clone(),
-                kept_alive_session: Some(Arc::new(Box::new(jump_session))),
-                agent_keys: self.agent_keys.clone(),
-            };
-
-            // russh::client::connect_stream takes stream and handler
-            let mut session =
-                russh::client::connect_stream(client_config, stream, client_handler).await?;
+            let client_handler = Client::new(
+                &config,
+                tunnel_manager.clone(),
+                Some(jump_session.clone()),
+                self.agent_keys.clone(),
+            );
+            let rejection = client_handler.host_key_rejection.clone();
+
+            let target = async {
+                // russh::client::connect_stream takes stream and handler
+                let mut session = with_connect_timeout(
+                    connect_timeout,
+                    russh::client::connect_stream(client_config, stream, client_handler),
+                )
+                .await?
+                .map_err(|e| explain_handshake_error(&rejection, e))?;
 
-            // 5. Authenticate (Target)
-            return self
-                .authenticate_session(&mut session, &config)
-                .await
-                .map(|_| session);
+                // 5. Authenticate (Target)
+                crate::session_state::mark_authenticating(&config.id);
+                self.authenticate_session(&mut session, &config, jump_for)
+                    .await
+                    .map(|_| session)
+            };
+            let result = target.await;
+            if result.is_err() {
+                // Retrying this hop shouldn't log in to the jump host again.
+                crate::jump_sessions::linger(jump_session);
+            }
+            return result;
         }
 
         // Direct Connection Logic
-        let client_handler = Client {
-            tunnel_manager: tunnel_manager.clone(),
-            connection_id: config.id.clone(),
-            kept_alive_session: None,
-            agent_keys: self.agent_keys.clone(),
-        };
+        let client_handler = Client::new(
+            &config,
+            tunnel_manager.clone(),
+            None,
+            self.agent_keys.clone(),
+        );
+        let rejection = client_handler.host_key_rejection.clone();
 
-        let mut session = client::connect(
-            client_config,
-            (config.host.as_str(), config.port),
-            client_handler,
-        )
-        .await?;
+        let mut session = match (&options.websocket, &options.access_proxy) {
+            (Some(websocket), _) => {
+                let dial = crate::websocket_transport::connect(websocket);
+                let handshake = connect_over(client_config, dial, client_handler, &rejection);
+                with_connect_timeout(connect_timeout, handshake).await??
+            }
+            (None, Some(proxy)) => {
+                let dial = crate::access_proxy::connect(proxy, &config);
+                let handshake = connect_over(client_config, dial, client_handler, &rejection);
+                with_connect_timeout(connect_timeout, handshake).await??
+            }
+            (None, None) if crate::tor::is_onion(&config.host) => {
+                let dial = crate::tor::connect(&config.host, config.port);
+                let handshake = connect_over(client_config, dial, client_handler, &rejection);
+                with_connect_timeout(connect_timeout, handshake).await??
+            }
+            (None, None) => with_connect_timeout(
+                connect_timeout,
+                client::connect(
+                    client_config,
+                    (config.host.as_str(), config.port),
+                    client_handler,
+                ),
+            )
+            .await?
+            .map_err(|e| explain_handshake_error(&rejection, e))?,
+        };
 
-        self.authenticate_session(&mut session, &config)
+        crate::session_state::mark_authenticating(&config.id);
+        self.authenticate_session(&mut session, &config, jump_for)
             .await
             .map(|_| session)
     }
@@ -373,17 +636,12 @@ impl SshManager {
         &self,
         session: &mut client::Handle<Client>,
         config: &ConnectionConfig,
+        jump_for: Option<String>,
     ) -> Result<()> {
         let auth_res = match &config.auth_method {
+            // Nothing to try first; the server may still ask interactively.
+            AuthMethod::Password { password } if password.trim().is_empty() => false,
             AuthMethod::Password { password } => {
-                if password.trim().is_empty() {
-                    return Err(anyhow!(
-                        "No SSH authentication configured for {}@{}. Add a password, private key, or vault credential before connecting.",
-                        config.username,
-                        config.host
-                    ));
-                }
-
                 session
                     .authenticate_password(&config.username, password.clone())
                     .await?
@@ -401,11 +659,13 @@ impl SshManager {
                 let key_data = tokio::fs::read_to_string(&expanded)
                     .await
                     .map_err(|e| anyhow!("Failed to read private key file: {}", e))?;
+                let certificate_path = crate::credential_expiry::certificate_path_for(&expanded);
                 Self::auth_with_key_data(
                     session,
                     &config.username,
                     &key_data,
                     passphrase.as_deref(),
+                    certificate_path.is_file().then_some(certificate_path.as_path()),
                     &self.agent_keys,
                 )
                 .await?
@@ -419,6 +679,7 @@ impl SshManager {
                     &config.username,
                     key_data,
                     passphrase.as_deref(),
+                    None,
                     &self.agent_keys,
                 )
                 .await?
@@ -431,25 +692,151 @@ impl SshManager {
             }
         };
 
-        if !auth_res {
-            return Err(anyhow!("Authentication failed"));
+        if auth_res {
+            return Ok(());
+        }
+        // Refused, or accepted with a second factor still due.
+        let hop = crate::auth_prompts::Hop {
+            connection_id: config.id.clone(),
+            name: config.name.clone(),
+            endpoint: format!("{}@{}:{}", config.username, config.host, config.port),
+            jump_for,
+        };
+        let saved_password = match &config.auth_method {
+            AuthMethod::Password { password } if !password.trim().is_empty() => {
+                Some(password.as_str())
+            }
+            _ => None,
+        };
+        if Self::auth_keyboard_interactive(session, &config.username, &hop, saved_password).await?
+        {
+            return Ok(());
+        }
+        match &config.auth_method {
+            AuthMethod::Password { password } if password.trim().is_empty() => Err(anyhow!(
+                "No SSH authentication configured for {}@{}. Add a password, private key, or vault credential before connecting.",
+                config.username,
+                config.host
+            )),
+            AuthMethod::Password { .. } => Err(anyhow!("Authentication failed (password)")),
+            _ => Err(anyhow!("Authentication failed (publickey)")),
         }
-        Ok(())
     }
 
+    /// Answer the server's keyboard-interactive questions through the UI,
+    /// asking again after a rejected answer. `false` when the server doesn't
+    /// offer the method or keeps refusing. A saved password answers the first
+    /// plain password question on its own.
+    async fn auth_keyboard_interactive(
+        session: &mut client::Handle<Client>,
+        username: &str,
+        hop: &crate::auth_prompts::Hop,
+        mut saved_password: Option<&str>,
+    ) -> Result<bool> {
+        use russh::client::KeyboardInteractiveAuthResponse as Reply;
+
+        for attempt in 1..=crate::auth_prompts::MAX_ATTEMPTS {
+            let mut reply = session
+                .authenticate_keyboard_interactive_start(username, None::<String>)
+                .await?;
+            let mut asked = false;
+            loop {
+                match reply {
+                    Reply::Success => return Ok(true),
+                    Reply::Failure => break,
+                    Reply::InfoRequest {
+                        name,
+                        instructions,
+                        prompts,
+                    } => {
+                        let fields: Vec<_> = prompts
+                            .into_iter()
+                            .map(|prompt| crate::auth_prompts::PromptField {
+                                prompt: prompt.prompt,
+                                echo: prompt.echo,
+                            })
+                            .collect();
+                        let autofill = match fields.as_slice() {
+                            [field] if crate::auth_prompts::is_password_prompt(field) => {
+                                saved_password.take()
+                            }
+                            _ => None,
+                        };
+                        // Servers may send an empty round to finish; it needs no answers.
+                        let responses = if fields.is_empty() {
+                            Vec::new()
+                        } else if let Some(password) = autofill {
+                            // Counts as an answer, so a wrong saved password leads to asking.
+                            asked = true;
+                            vec![password.to_string()]
+                        } else {
+                            asked = true;
+                            crate::auth_prompts::ask(hop, name, instructions, fields, attempt)
+                                .await
+                                .ok_or_else(|| {
+                                    anyhow!(
+                                        "Authentication failed (keyboard-interactive): \
+                                         cancelled at the prompt for {}",
+                                        hop.endpoint
+                                    )
+                                })?
+                        };
+                        reply = session
+                            .authenticate_keyboard_interactive_respond(responses)
+                            .await?;
+                    }
+                }
+            }
+            if !asked {
+                return Ok(false);
+            }
+            println!(
+                "[SSH] {} rejected keyboard-interactive answers (attempt {attempt}/{})",
+                hop.endpoint,
+                crate::auth_prompts::MAX_ATTEMPTS
+            );
+        }
+        Err(anyhow!(
+            "Authentication failed (keyboard-interactive) for {} after {} attempts",
+            hop.endpoint,
+            crate::auth_prompts::MAX_ATTEMPTS
+        ))
+    }
+
+    /// Public-key auth with `key_data`. With an OpenSSH `certificate` the
+    /// certificate is presented first; if the server rejects it (or it can't be
+    /// read) the plain key is tried, as `ssh` does.
     async fn auth_with_key_data(
         session: &mut client::Handle<Client>,
         username: &str,
         key_data: &str,
         passphrase: Option<&str>,
+        certificate: Option<&std::path::Path>,
         agent_keys: &std::sync::Mutex<Vec<russh_keys::key::KeyPair>>,
     ) -> Result<bool> {
         let privkey = russh_keys::decode_secret_key(key_data, passphrase)
             .map_err(|e| anyhow!("Failed to decode private key: {}", e))?;
         let privkey = Arc::new(privkey);
-        let auth_success = session
-            .authenticate_publickey(username, privkey.clone())
-            .await?;
+        let certificate = certificate.and_then(|path| {
+            russh_keys::load_openssh_certificate(path)
+                .inspect_err(|e| {
+                    error!("[SSH] Ignoring unreadable certificate {}: {}", path.display(), e)
+                })
+                .ok()
+        });
+        let mut auth_success = match certificate {
+            Some(certificate) => {
+                session
+                    .authenticate_openssh_cert(username, privkey.clone(), certificate)
+                    .await?
+            }
+            None => false,
+        };
+        if !auth_success {
+            auth_success = session
+                .authenticate_publickey(username, privkey.clone())
+                .await?;
+        }
         if auth_success {
             let mut keys = match agent_keys.lock() {
                 Ok(keys) => keys,
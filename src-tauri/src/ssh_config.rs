@@ -1,3 +1,4 @@
+use crate::types::{CanonicalizeHostname, SshTransportOptions};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::fs;
@@ -15,6 +16,12 @@ pub struct ParsedSshConnection {
     pub jump_server_alias: Option<String>,
     pub jump_server_id: Option<String>,
     pub aliases: Vec<String>, // Add full alias list
+    #[serde(default)]
+    pub ssh_options: Option<SshTransportOptions>,
+    #[serde(default)]
+    pub host_key_alias: Option<String>,
+    #[serde(default)]
+    pub user_known_hosts_file: Option<String>,
 }
 
 // Helper function to strip wrapping quotes from values
@@ -43,6 +50,13 @@ pub fn parse_config_text(content: &str) -> Result<Vec<ParsedSshConnection>> {
     let mut connections = Vec::new();
 
     let mut current_host: Option<ParsedSshConnection> = None;
+    // Directives before the first `Host` line apply to every host and, as in
+    // OpenSSH where the first value wins, override the host blocks.
+    let mut preamble_options = SshTransportOptions::default();
+    let mut preamble_known_hosts: Option<String> = None;
+    // Options from `Host *`, applied to hosts that don't set their own.
+    let mut wildcard_options = SshTransportOptions::default();
+    let mut wildcard_known_hosts: Option<String> = None;
 
     for line in content.lines() {
         let line = strip_inline_comments(line).trim();
@@ -76,6 +90,9 @@ pub fn parse_config_text(content: &str) -> Result<Vec<ParsedSshConnection>> {
                     // Generate ID
                     host.id = format!("ssh_{}", uuid::Uuid::new_v4());
                     connections.push(host);
+                } else if host.aliases.iter().any(|alias| alias == "*") {
+                    merge_missing(&mut wildcard_options, host.ssh_options.unwrap_or_default());
+                    wildcard_known_hosts = wildcard_known_hosts.or(host.user_known_hosts_file);
                 }
             }
 
@@ -93,6 +110,9 @@ pub fn parse_config_text(content: &str) -> Result<Vec<ParsedSshConnection>> {
                 jump_server_alias: None,
                 jump_server_id: None,
                 aliases, // Store full alias list
+                ssh_options: None,
+                host_key_alias: None,
+                user_known_hosts_file: None,
             });
         } else if let Some(host) = current_host.as_mut() {
             match key_str.to_lowercase().as_str() {
@@ -117,7 +137,25 @@ pub fn parse_config_text(content: &str) -> Result<Vec<ParsedSshConnection>> {
                     host.private_key_path = Some(path);
                 }
                 "proxyjump" => host.jump_server_alias = Some(value_str.to_string()),
-                _ => {}
+                "hostkeyalias" if host.host_key_alias.is_none() => {
+                    host.host_key_alias = Some(value_str.to_string());
+                }
+                "userknownhostsfile" if host.user_known_hosts_file.is_none() => {
+                    host.user_known_hosts_file = Some(value_str.to_string());
+                }
+                key => {
+                    let mut options = host.ssh_options.take().unwrap_or_default();
+                    apply_transport_option(&mut options, key, value_str);
+                    host.ssh_options =
+                        (options != SshTransportOptions::default()).then_some(options);
+                }
+            }
+        } else {
+            match key_str.to_lowercase().as_str() {
+                "userknownhostsfile" if preamble_known_hosts.is_none() => {
+                    preamble_known_hosts = Some(value_str.to_string());
+                }
+                key => apply_transport_option(&mut preamble_options, key, value_str),
             }
         }
     }
@@ -127,9 +165,23 @@ pub fn parse_config_text(content: &str) -> Result<Vec<ParsedSshConnection>> {
         if !host.name.contains('*') && !host.name.contains('?') {
             host.id = format!("ssh_{}", uuid::Uuid::new_v4());
             connections.push(host);
+        } else if host.aliases.iter().any(|alias| alias == "*") {
+            merge_missing(&mut wildcard_options, host.ssh_options.unwrap_or_default());
+            wildcard_known_hosts = wildcard_known_hosts.or(host.user_known_hosts_file);
         }
     }
 
+    for conn in &mut connections {
+        let mut options = preamble_options.clone();
+        merge_missing(&mut options, conn.ssh_options.take().unwrap_or_default());
+        merge_missing(&mut options, wildcard_options.clone());
+        conn.ssh_options = (options != SshTransportOptions::default()).then_some(options);
+        conn.user_known_hosts_file = preamble_known_hosts
+            .clone()
+            .or(conn.user_known_hosts_file.take())
+            .or_else(|| wildcard_known_hosts.clone());
+    }
+
     // Pass 2: Resolve Jump Server Aliases to IDs
     let mut alias_map: std::collections::HashMap<String, String> = std::collections::HashMap::new();
     for connection in &connections {
@@ -152,7 +204,92 @@ pub fn parse_config_text(content: &str) -> Result<Vec<ParsedSshConnection>> {
     Ok(connections)
 }
 
-fn strip_inline_comments(line: &str) -> &str {
+/// Like OpenSSH, the first value seen for a directive wins.
+fn apply_transport_option(options: &mut SshTransportOptions, key: &str, value: &str) {
+    match key {
+        "connecttimeout" if options.connect_timeout_secs.is_none() => {
+            options.connect_timeout_secs = value.parse().ok().filter(|secs| *secs > 0);
+        }
+        "serveraliveinterval" if options.server_alive_interval_secs.is_none() => {
+            options.server_alive_interval_secs = value.parse().ok();
+        }
+        "serveralivecountmax" if options.server_alive_count_max.is_none() => {
+            options.server_alive_count_max = value.parse().ok();
+        }
+        "compression" if options.compression.is_none() => {
+            options.compression = parse_yes_no(value);
+        }
+        "canonicalizehostname" if options.canonicalize_hostname.is_none() => {
+            options.canonicalize_hostname = match value.to_lowercase().as_str() {
+                "always" => Some(CanonicalizeHostname::Always),
+                "yes" => Some(CanonicalizeHostname::Yes),
+                "no" => Some(CanonicalizeHostname::No),
+                _ => None,
+            };
+        }
+        "canonicaldomains" if options.canonical_domains.is_none() => {
+            let domains: Vec<String> = value
+                .split_whitespace()
+                .map(|domain| domain.trim_matches('.').to_string())
+                .filter(|domain| !domain.is_empty())
+                .collect();
+            options.canonical_domains = (!domains.is_empty()).then_some(domains);
+        }
+        "canonicalizemaxdots" if options.canonicalize_max_dots.is_none() => {
+            options.canonicalize_max_dots = value.parse().ok();
+        }
+        "canonicalizefallbacklocal" if options.canonicalize_fallback_local.is_none() => {
+            options.canonicalize_fallback_local = parse_yes_no(value);
+        }
+        "localcommand" if options.local_command.is_none() => {
+            options.local_command = Some(value.to_string()).filter(|command| !command.is_empty());
+        }
+        "permitlocalcommand" if options.permit_local_command.is_none() => {
+            options.permit_local_command = parse_yes_no(value);
+        }
+        _ => {}
+    }
+}
+
+fn parse_yes_no(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "yes" => Some(true),
+        "no" => Some(false),
+        _ => None,
+    }
+}
+
+fn merge_missing(options: &mut SshTransportOptions, fallback: SshTransportOptions) {
+    options.connect_timeout_secs = options
+        .connect_timeout_secs
+        .or(fallback.connect_timeout_secs);
+    options.server_alive_interval_secs = options
+        .server_alive_interval_secs
+        .or(fallback.server_alive_interval_secs);
+    options.server_alive_count_max = options
+        .server_alive_count_max
+        .or(fallback.server_alive_count_max);
+    options.compression = options.compression.or(fallback.compression);
+    options.canonicalize_hostname = options
+        .canonicalize_hostname
+        .or(fallback.canonicalize_hostname);
+    options.canonical_domains = options
+        .canonical_domains
+        .take()
+        .or(fallback.canonical_domains);
+    options.canonicalize_max_dots = options
+        .canonicalize_max_dots
+        .or(fallback.canonicalize_max_dots);
+    options.canonicalize_fallback_local = options
+        .canonicalize_fallback_local
+        .or(fallback.canonicalize_fallback_local);
+    options.local_command = options.local_command.take().or(fallback.local_command);
+    options.permit_local_command = options
+        .permit_local_command
+        .or(fallback.permit_local_command);
+}
+
+pub(crate) fn strip_inline_comments(line: &str) -> &str {
     let mut in_quotes = false;
     let mut quote_char = ' ';
     let mut escaped = false;
@@ -186,6 +323,7 @@ fn strip_inline_comments(line: &str) -> &str {
 #[cfg(test)]
 mod tests {
     use super::parse_config_text;
+    use crate::types::CanonicalizeHostname;
 
     #[test]
     fn parse_config_text_parses_basic_host_block() {
@@ -217,4 +355,95 @@ Host app
         assert_eq!(parsed[0].host, "10.0.0.5 # inside");
         assert_eq!(parsed[0].username, "root");
     }
+
+    #[test]
+    fn parse_config_text_reads_transport_options_with_wildcard_fallback() {
+        let text = r#"
+Host slow
+  HostName 10.0.0.9
+  ConnectTimeout 5
+  ServerAliveInterval 0
+  Compression yes
+
+Host plain
+  HostName 10.0.0.10
+
+Host *
+  ServerAliveInterval 30
+  ServerAliveCountMax 2
+  Compression no
+"#;
+
+        let parsed = parse_config_text(text).expect("should parse");
+        assert_eq!(parsed.len(), 2);
+        let slow = parsed[0].ssh_options.clone().unwrap();
+        assert_eq!(slow.connect_timeout_secs, Some(5));
+        assert_eq!(slow.server_alive_interval_secs, Some(0));
+        assert_eq!(slow.server_alive_count_max, Some(2));
+        assert_eq!(slow.compression, Some(true));
+        let plain = parsed[1].ssh_options.clone().unwrap();
+        assert_eq!(plain.connect_timeout_secs, None);
+        assert_eq!(plain.server_alive_interval_secs, Some(30));
+        assert_eq!(plain.compression, Some(false));
+    }
+
+    #[test]
+    fn parse_config_text_applies_preamble_canonicalization_first() {
+        let text = r#"
+CanonicalizeHostname yes
+CanonicalDomains example.com. corp.example.com
+
+Host web
+  CanonicalizeHostname no
+  CanonicalizeMaxDots 0
+  PermitLocalCommand yes
+  LocalCommand notify-send "connected to %n"
+"#;
+
+        let parsed = parse_config_text(text).expect("should parse");
+        let options = parsed[0].ssh_options.clone().unwrap();
+        assert_eq!(
+            options.canonicalize_hostname,
+            Some(CanonicalizeHostname::Yes)
+        );
+        assert_eq!(
+            options.canonical_domains,
+            Some(vec!["example.com".to_string(), "corp.example.com".to_string()])
+        );
+        assert_eq!(options.canonicalize_max_dots, Some(0));
+        assert_eq!(options.permit_local_command, Some(true));
+        assert_eq!(
+            options.local_command.as_deref(),
+            Some("notify-send \"connected to %n\"")
+        );
+    }
+
+    #[test]
+    fn parse_config_text_reads_host_key_alias_and_known_hosts_file() {
+        let text = r#"
+Host lb-a
+  HostName lb.example.com
+  HostKeyAlias app-a
+  UserKnownHostsFile ~/.ssh/known_hosts.apps
+
+Host tunnel
+  HostName localhost
+  Port 2222
+
+Host *
+  UserKnownHostsFile ~/.ssh/known_hosts.other
+"#;
+
+        let parsed = parse_config_text(text).expect("should parse");
+        assert_eq!(parsed[0].host_key_alias.as_deref(), Some("app-a"));
+        assert_eq!(
+            parsed[0].user_known_hosts_file.as_deref(),
+            Some("~/.ssh/known_hosts.apps")
+        );
+        assert_eq!(parsed[1].host_key_alias, None);
+        assert_eq!(
+            parsed[1].user_known_hosts_file.as_deref(),
+            Some("~/.ssh/known_hosts.other")
+        );
+    }
 }
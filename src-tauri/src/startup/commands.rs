@@ -0,0 +1,47 @@
+use super::workspace::{self, WorkspaceRestorePlan};
+use super::{plan, AutoConnectPlan};
+use crate::commands::get_data_dir;
+use crate::sync::domain_hosts::{load_saved_data, CONNECTIONS_MUTATION_LOCK};
+use crate::sync::domain_tunnels::load_saved_tunnels;
+use tauri::AppHandle;
+
+/// Waves of connections to bring up at launch, jump hosts first.
+#[tauri::command]
+pub async fn startup_auto_connect_plan(app: AppHandle) -> Result<AutoConnectPlan, String> {
+    let data = {
+        let _guard = CONNECTIONS_MUTATION_LOCK
+            .lock()
+            .map_err(|error| error.to_string())?;
+        load_saved_data(&get_data_dir(&app).join("connections.json"))
+            .map_err(|error| error.to_string())?
+    };
+    Ok(plan(&data.connections))
+}
+
+/// What was live when the app last closed, or `None` when there is nothing to
+/// restore.
+#[tauri::command]
+pub async fn startup_workspace_restore_plan(
+    app: AppHandle,
+) -> Result<Option<WorkspaceRestorePlan>, String> {
+    let Some(snapshot) = workspace::load(&app) else {
+        return Ok(None);
+    };
+    let data_dir = get_data_dir(&app);
+    let data = {
+        let _guard = CONNECTIONS_MUTATION_LOCK
+            .lock()
+            .map_err(|error| error.to_string())?;
+        load_saved_data(&data_dir.join("connections.json")).map_err(|error| error.to_string())?
+    };
+    let tunnels =
+        load_saved_tunnels(&data_dir.join("tunnels.json")).map_err(|error| error.to_string())?;
+    let plan = workspace::restore_plan(&snapshot, &data.connections, &tunnels.tunnels);
+    Ok((!plan.is_empty()).then_some(plan))
+}
+
+/// Forget the recorded workspace once it was restored or dismissed.
+#[tauri::command]
+pub async fn startup_workspace_clear(app: AppHandle) -> Result<(), String> {
+    workspace::clear(&app)
+}
@@ -0,0 +1,192 @@
+//! Launch-time auto-connect planning.
+//!
+//! Connections flagged `auto_connect` are brought up when the app starts. The
+//! frontend drives the actual connects (it owns terminals and tunnel
+//! auto-start); this module decides the order. Connections are grouped into
+//! waves: everything in a wave connects concurrently, and a connection whose
+//! jump chain passes through another auto-connect connection waits for it in
+//! an earlier wave, so a failed bastion is reported once instead of once per
+//! host behind it. The same planning brings back the connections of the last
+//! workspace, see [`workspace`].
+
+pub mod commands;
+pub mod workspace;
+
+use crate::types::SavedConnection;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedConnect {
+    pub connection_id: String,
+    /// Auto-connect connection this one jumps through; skip it if that fails.
+    pub depends_on: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedConnect {
+    pub connection_id: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoConnectPlan {
+    pub waves: Vec<Vec<PlannedConnect>>,
+    pub skipped: Vec<SkippedConnect>,
+}
+
+/// Walk the jump chain of `connection` and return the nearest selected hop.
+/// Errors on loops and on hops that no longer exist, which could never connect.
+fn nearest_selected_hop<'a>(
+    connection: &'a SavedConnection,
+    by_id: &HashMap<&'a str, &'a SavedConnection>,
+    is_selected: &impl Fn(&SavedConnection) -> bool,
+) -> Result<Option<&'a str>, String> {
+    let mut seen = HashSet::from([connection.id.as_str()]);
+    let mut nearest = None;
+    let mut next = connection.jump_server_id.as_deref();
+    while let Some(id) = next {
+        if !seen.insert(id) {
+            return Err("Jump host chain loops back on itself".to_string());
+        }
+        let Some(hop) = by_id.get(id) else {
+            return Err(format!("Jump host {id} no longer exists"));
+        };
+        if nearest.is_none() && is_selected(hop) {
+            nearest = Some(hop.id.as_str());
+        }
+        next = hop.jump_server_id.as_deref();
+    }
+    Ok(nearest)
+}
+
+/// Order the auto-connect connections in `connections` into waves.
+pub fn plan(connections: &[SavedConnection]) -> AutoConnectPlan {
+    plan_selected(connections, |connection| {
+        connection.auto_connect == Some(true)
+    })
+}
+
+/// Order the connections `is_selected` picks into waves.
+pub fn plan_selected(
+    connections: &[SavedConnection],
+    is_selected: impl Fn(&SavedConnection) -> bool,
+) -> AutoConnectPlan {
+    let by_id: HashMap<&str, &SavedConnection> = connections
+        .iter()
+        .map(|connection| (connection.id.as_str(), connection))
+        .collect();
+    let selected: Vec<&SavedConnection> = connections
+        .iter()
+        .filter(|connection| is_selected(connection))
+        .collect();
+
+    let mut result = AutoConnectPlan::default();
+    let mut dependencies: HashMap<&str, Option<&str>> = HashMap::new();
+    for connection in &selected {
+        match nearest_selected_hop(connection, &by_id, &is_selected) {
+            Ok(dependency) => {
+                dependencies.insert(connection.id.as_str(), dependency);
+            }
+            Err(reason) => result.skipped.push(SkippedConnect {
+                connection_id: connection.id.clone(),
+                reason,
+            }),
+        }
+    }
+
+    // Chains were checked for loops and missing hops above, so every
+    // dependency is itself planned and the recursion terminates.
+    fn wave_of<'a>(
+        id: &'a str,
+        dependencies: &HashMap<&'a str, Option<&'a str>>,
+        memo: &mut HashMap<&'a str, usize>,
+    ) -> usize {
+        if let Some(known) = memo.get(id) {
+            return *known;
+        }
+        let wave = match dependencies.get(id) {
+            Some(Some(dependency)) => wave_of(dependency, dependencies, memo) + 1,
+            _ => 0,
+        };
+        memo.insert(id, wave);
+        wave
+    }
+
+    let mut memo = HashMap::new();
+    for connection in &selected {
+        let id = connection.id.as_str();
+        let Some(dependency) = dependencies.get(id).copied() else {
+            continue;
+        };
+        let wave = wave_of(id, &dependencies, &mut memo);
+        if result.waves.len() <= wave {
+            result.waves.resize_with(wave + 1, Vec::new);
+        }
+        result.waves[wave].push(PlannedConnect {
+            connection_id: id.to_string(),
+            depends_on: dependency.map(str::to_string),
+        });
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn saved(id: &str, jump: Option<&str>, auto_connect: bool) -> SavedConnection {
+        SavedConnection {
+            id: id.to_string(),
+            name: id.to_string(),
+            host: format!("{id}.example.com"),
+            port: 22,
+            username: "deploy".to_string(),
+            jump_server_id: jump.map(str::to_string),
+            auto_connect: auto_connect.then_some(true),
+            ..Default::default()
+        }
+    }
+
+    fn ids(wave: &[PlannedConnect]) -> Vec<&str> {
+        wave.iter()
+            .map(|entry| entry.connection_id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn jump_hosts_connect_in_an_earlier_wave() {
+        let plan = plan(&[
+            saved("app", Some("inner"), true),
+            saved("inner", Some("bastion"), false),
+            saved("bastion", None, true),
+            saved("db", None, true),
+            saved("idle", None, false),
+        ]);
+        assert_eq!(plan.waves.len(), 2);
+        assert_eq!(ids(&plan.waves[0]), vec!["bastion", "db"]);
+        assert_eq!(ids(&plan.waves[1]), vec!["app"]);
+        assert_eq!(plan.waves[1][0].depends_on.as_deref(), Some("bastion"));
+        assert!(plan.skipped.is_empty());
+    }
+
+    #[test]
+    fn broken_chains_are_skipped_with_their_dependents() {
+        let plan = plan(&[
+            saved("a", Some("b"), true),
+            saved("b", Some("a"), true),
+            saved("orphan", Some("gone"), true),
+            saved("behind-orphan", Some("orphan"), true),
+        ]);
+        assert!(plan.waves.is_empty());
+        let skipped: Vec<&str> = plan
+            .skipped
+            .iter()
+            .map(|entry| entry.connection_id.as_str())
+            .collect();
+        assert_eq!(skipped, vec!["a", "b", "orphan", "behind-orphan"]);
+    }
+}
@@ -0,0 +1,233 @@
+//! Workspace restore: the connections that were live and the tunnels that were
+//! running when the app closed, so the next launch can offer to bring them
+//! back. Tabs and terminal working directories already live in the saved
+//! session; this only adds what was running.
+
+use super::{plan_selected, AutoConnectPlan, SkippedConnect};
+use crate::commands::{get_data_dir, AppState};
+use crate::types::{SavedConnection, SavedTunnel};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+const WORKSPACE_FILE: &str = "workspace.json";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WorkspaceSnapshot {
+    pub saved_at_ms: u64,
+    pub connections: Vec<String>,
+    pub tunnels: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedTunnel {
+    pub tunnel_id: String,
+    pub connection_id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedTunnel {
+    pub tunnel_id: String,
+    pub name: Option<String>,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceRestorePlan {
+    pub saved_at_ms: u64,
+    pub connections: AutoConnectPlan,
+    /// Start once their connection is up; skip when it didn't come back.
+    pub tunnels: Vec<PlannedTunnel>,
+    pub skipped_tunnels: Vec<SkippedTunnel>,
+}
+
+impl WorkspaceRestorePlan {
+    pub fn is_empty(&self) -> bool {
+        self.connections.waves.is_empty()
+            && self.connections.skipped.is_empty()
+            && self.tunnels.is_empty()
+            && self.skipped_tunnels.is_empty()
+    }
+}
+
+/// Resolve a snapshot against what is saved now. Connections and tunnels that
+/// were deleted since are reported as skipped rather than dropped silently.
+pub fn restore_plan(
+    snapshot: &WorkspaceSnapshot,
+    connections: &[SavedConnection],
+    tunnels: &[SavedTunnel],
+) -> WorkspaceRestorePlan {
+    let recorded: HashSet<&str> = snapshot.connections.iter().map(String::as_str).collect();
+    let mut plan = plan_selected(connections, |connection| {
+        recorded.contains(connection.id.as_str())
+    });
+    for id in &snapshot.connections {
+        if !connections.iter().any(|connection| &connection.id == id) {
+            plan.skipped.push(SkippedConnect {
+                connection_id: id.clone(),
+                reason: "Connection no longer exists".to_string(),
+            });
+        }
+    }
+
+    let planned: HashSet<&str> = plan
+        .waves
+        .iter()
+        .flatten()
+        .map(|entry| entry.connection_id.as_str())
+        .collect();
+    let mut result = WorkspaceRestorePlan {
+        saved_at_ms: snapshot.saved_at_ms,
+        ..Default::default()
+    };
+    for id in &snapshot.tunnels {
+        let Some(tunnel) = tunnels.iter().find(|tunnel| &tunnel.id == id) else {
+            result.skipped_tunnels.push(SkippedTunnel {
+                tunnel_id: id.clone(),
+                name: None,
+                reason: "Tunnel no longer exists".to_string(),
+            });
+            continue;
+        };
+        if planned.contains(tunnel.connection_id.as_str()) {
+            result.tunnels.push(PlannedTunnel {
+                tunnel_id: tunnel.id.clone(),
+                connection_id: tunnel.connection_id.clone(),
+                name: tunnel.name.clone(),
+            });
+        } else {
+            result.skipped_tunnels.push(SkippedTunnel {
+                tunnel_id: tunnel.id.clone(),
+                name: Some(tunnel.name.clone()),
+                reason: "Its connection can't be restored".to_string(),
+            });
+        }
+    }
+    result.connections = plan;
+    result
+}
+
+/// Record the live saved connections and their running tunnels. Called when
+/// the main window is asked to close, before the graceful disconnect. With
+/// nothing live the previous snapshot is removed so no restore is offered.
+pub async fn record(app: &AppHandle, state: &AppState) -> Result<(), String> {
+    let data_dir = get_data_dir(app);
+    let path = data_dir.join(WORKSPACE_FILE);
+    let saved = crate::sync::domain_hosts::load_saved_data(&data_dir.join("connections.json"))
+        .map_err(|e| e.to_string())?;
+    let mut connections: Vec<String> = {
+        let live = state.connections.lock().await;
+        saved
+            .connections
+            .iter()
+            .filter(|connection| {
+                live.get(&connection.id)
+                    .is_some_and(|handle| handle.session.is_some())
+            })
+            .map(|connection| connection.id.clone())
+            .collect()
+    };
+    connections.sort();
+    if connections.is_empty() {
+        return match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+            _ => Ok(()),
+        };
+    }
+
+    let snapshot = WorkspaceSnapshot {
+        saved_at_ms: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or(0),
+        connections,
+        tunnels: crate::tunnels::running_tunnel_ids(app, state).await,
+    };
+    let json = serde_json::to_vec_pretty(&snapshot).map_err(|e| e.to_string())?;
+    crate::atomic_io::durable_replace(&path, &json).map_err(|e| e.to_string())
+}
+
+pub fn load(app: &AppHandle) -> Option<WorkspaceSnapshot> {
+    let raw = std::fs::read_to_string(get_data_dir(app).join(WORKSPACE_FILE)).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+pub fn clear(app: &AppHandle) -> Result<(), String> {
+    match std::fs::remove_file(get_data_dir(app).join(WORKSPACE_FILE)) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn saved(id: &str, jump: Option<&str>) -> SavedConnection {
+        SavedConnection {
+            id: id.to_string(),
+            name: id.to_string(),
+            host: format!("{id}.example.com"),
+            port: 22,
+            username: "deploy".to_string(),
+            jump_server_id: jump.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn tunnel(id: &str, connection_id: &str) -> SavedTunnel {
+        SavedTunnel {
+            id: id.to_string(),
+            connection_id: connection_id.to_string(),
+            name: format!("{id} tunnel"),
+            tunnel_type: "local".to_string(),
+            local_port: 8080,
+            remote_host: "127.0.0.1".to_string(),
+            remote_port: 80,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn restores_recorded_connections_behind_their_jump_hosts() {
+        let snapshot = WorkspaceSnapshot {
+            saved_at_ms: 5,
+            connections: vec!["app".into(), "bastion".into(), "deleted".into()],
+            tunnels: vec!["web".into(), "gone".into(), "idle".into()],
+        };
+        let plan = restore_plan(
+            &snapshot,
+            &[
+                saved("app", Some("bastion")),
+                saved("bastion", None),
+                saved("other", None),
+            ],
+            &[tunnel("web", "app"), tunnel("idle", "other")],
+        );
+        assert_eq!(plan.saved_at_ms, 5);
+        let waves: Vec<Vec<&str>> = plan
+            .connections
+            .waves
+            .iter()
+            .map(|wave| wave.iter().map(|e| e.connection_id.as_str()).collect())
+            .collect();
+        assert_eq!(waves, vec![vec!["bastion"], vec!["app"]]);
+        assert_eq!(plan.connections.skipped[0].connection_id, "deleted");
+        assert_eq!(plan.tunnels.len(), 1);
+        assert_eq!(plan.tunnels[0].connection_id, "app");
+        let skipped: Vec<&str> = plan
+            .skipped_tunnels
+            .iter()
+            .map(|t| t.tunnel_id.as_str())
+            .collect();
+        assert_eq!(skipped, vec!["gone", "idle"]);
+        assert!(!plan.is_empty());
+        assert!(restore_plan(&WorkspaceSnapshot::default(), &[], &[]).is_empty());
+    }
+}
@@ -1,6 +1,8 @@
 #![allow(dead_code)]
 
 use super::types::{SyncDomain, SyncError, SyncResult};
+use crate::data_format::to_versioned_json;
+use crate::data_validation::{parse_or_repair, DataFile};
 use crate::types::{CredentialRef, SavedConnection, SavedData};
 use std::collections::BTreeMap;
 use std::path::Path;
@@ -165,6 +167,15 @@ pub fn apply_hosts_restore_records(
             is_favorite: Some(record.is_favorite),
             pinned_features: None,
             auth_ref: record.auth_ref.clone(),
+            strict_host_key_checking: None,
+            auto_connect: None,
+            encoding: None,
+            locale: None,
+            ssh_options: None,
+            host_key_alias: None,
+            user_known_hosts_file: None,
+            secret_ref: None,
+            session_lifetime: None,
         });
         restored = restored.saturating_add(1);
     }
@@ -173,7 +184,7 @@ pub fn apply_hosts_restore_records(
     Ok((restored, updated))
 }
 
-fn load_saved_data(path: &Path) -> SyncResult<SavedData> {
+pub(crate) fn load_saved_data(path: &Path) -> SyncResult<SavedData> {
     if !path.exists() {
         let temp_path = path.with_extension("tmp");
         let backup_path = path.with_extension("bak");
@@ -207,7 +218,7 @@ fn parse_saved_file(path: &Path) -> SyncResult<SavedData> {
             format!("Failed to read hosts file: {e}"),
         )
     })?;
-    serde_json::from_str::<SavedData>(&raw).map_err(|e| {
+    parse_or_repair::<SavedData>(DataFile::Connections, path, &raw).map_err(|e| {
         SyncError::new(
             "sync_hosts_parse_failed",
             format!("Failed to parse hosts file: {e}"),
@@ -215,13 +226,9 @@ fn parse_saved_file(path: &Path) -> SyncResult<SavedData> {
     })
 }
 
-fn save_saved_data_atomic(path: &Path, data: &SavedData) -> SyncResult<()> {
-    let json = serde_json::to_string_pretty(data).map_err(|e| {
-        SyncError::new(
-            "sync_hosts_write_failed",
-            format!("Failed to serialize hosts data: {e}"),
-        )
-    })?;
+pub(crate) fn save_saved_data_atomic(path: &Path, data: &SavedData) -> SyncResult<()> {
+    let json = to_versioned_json(DataFile::Connections, path, data)
+        .map_err(|e| SyncError::new("sync_hosts_write_failed", e))?;
     crate::atomic_io::durable_replace(path, json.as_bytes()).map_err(|e| {
         SyncError::new(
             "sync_hosts_write_failed",
@@ -276,6 +283,15 @@ mod tests {
             is_favorite: None,
             pinned_features: None,
             auth_ref: None,
+            strict_host_key_checking: None,
+            auto_connect: None,
+            encoding: None,
+            locale: None,
+            ssh_options: None,
+            host_key_alias: None,
+            user_known_hosts_file: None,
+            secret_ref: None,
+            session_lifetime: None,
         }
     }
 
@@ -1,6 +1,8 @@
 #![allow(dead_code)]
 
 use super::types::{SyncError, SyncResult};
+use crate::data_format::to_versioned_json;
+use crate::data_validation::{parse_or_repair, DataFile};
 use crate::snippets::{Snippet, SnippetsData, SNIPPETS_MUTATION_LOCK};
 use sha2::{Digest, Sha256};
 use std::collections::BTreeMap;
@@ -182,15 +184,14 @@ fn parse_saved_file(path: &Path) -> SyncResult<SnippetsData> {
     let raw = std::fs::read_to_string(path).map_err(|e| {
         SyncError::new("sync_snippets_read_failed", format!("Failed to read snippets file: {e}"))
     })?;
-    serde_json::from_str::<SnippetsData>(&raw).map_err(|e| {
+    parse_or_repair::<SnippetsData>(DataFile::Snippets, path, &raw).map_err(|e| {
         SyncError::new("sync_snippets_parse_failed", format!("Failed to parse snippets file: {e}"))
     })
 }
 
 fn save_saved_atomic(path: &Path, data: &SnippetsData) -> SyncResult<()> {
-    let json = serde_json::to_string_pretty(data).map_err(|e| {
-        SyncError::new("sync_snippets_write_failed", format!("Failed to serialize snippets data: {e}"))
-    })?;
+    let json = to_versioned_json(DataFile::Snippets, path, data)
+        .map_err(|e| SyncError::new("sync_snippets_write_failed", e))?;
     crate::atomic_io::durable_replace(path, json.as_bytes()).map_err(|e| {
         SyncError::new("sync_snippets_write_failed", format!("Failed to write snippets file: {e}"))
     })
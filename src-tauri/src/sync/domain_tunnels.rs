@@ -1,6 +1,8 @@
 #![allow(dead_code)]
 
 use super::types::{SyncError, SyncResult};
+use crate::data_format::to_versioned_json;
+use crate::data_validation::{parse_or_repair, DataFile};
 use crate::types::{SavedTunnel, SavedTunnelsData};
 use std::collections::BTreeMap;
 use std::path::Path;
@@ -218,6 +220,10 @@ pub fn apply_tunnel_restore_records(data_dir: &Path, records: &[TunnelSyncRecord
             group: record.group.clone(),
             created_at: Some(record.updated_at),
             updated_at: Some(record.updated_at),
+            web: None,
+            pac_domains: None,
+            system_proxy: None,
+            schedule: None,
         });
         restored = restored.saturating_add(1);
     }
@@ -253,15 +259,14 @@ fn parse_saved_tunnels_file(path: &Path) -> SyncResult<SavedTunnelsData> {
     let raw = std::fs::read_to_string(path).map_err(|e| {
         SyncError::new("sync_tunnels_read_failed", format!("Failed to read tunnels file: {e}"))
     })?;
-    serde_json::from_str::<SavedTunnelsData>(&raw).map_err(|e| {
+    parse_or_repair::<SavedTunnelsData>(DataFile::Tunnels, path, &raw).map_err(|e| {
         SyncError::new("sync_tunnels_parse_failed", format!("Failed to parse tunnels file: {e}"))
     })
 }
 
 pub(crate) fn write_saved_tunnels_atomic(path: &Path, data: &SavedTunnelsData) -> SyncResult<()> {
-    let json = serde_json::to_string_pretty(data).map_err(|e| {
-        SyncError::new("sync_tunnels_write_failed", format!("Failed to serialize tunnels data: {e}"))
-    })?;
+    let json = to_versioned_json(DataFile::Tunnels, path, data)
+        .map_err(|e| SyncError::new("sync_tunnels_write_failed", e))?;
     crate::atomic_io::durable_replace(path, json.as_bytes()).map_err(|e| {
         SyncError::new("sync_tunnels_write_failed", format!("Failed to write tunnels file: {e}"))
     })
@@ -315,6 +320,10 @@ mod tests {
                 group: None,
                 created_at: Some(1),
                 updated_at: Some(1),
+                web: None,
+                pac_domains: None,
+                system_proxy: None,
+                schedule: None,
             }],
         };
         std::fs::write(
@@ -362,6 +371,10 @@ mod tests {
                 group: None,
                 created_at: Some(1),
                 updated_at: Some(1),
+                web: None,
+                pac_domains: None,
+                system_proxy: None,
+                schedule: None,
             }],
         };
         std::fs::write(
@@ -407,6 +420,10 @@ mod tests {
                 group: None,
                 created_at: Some(10),
                 updated_at: Some(11),
+                web: None,
+                pac_domains: None,
+                system_proxy: None,
+                schedule: None,
             }],
         };
         let path = dir.join("tunnels.json");
@@ -467,6 +484,10 @@ mod tests {
                 group: None,
                 created_at: Some(12),
                 updated_at: Some(55),
+                web: None,
+                pac_domains: None,
+                system_proxy: None,
+                schedule: None,
             },
             "tun-1".into(),
         );
@@ -500,6 +521,10 @@ mod tests {
             group: None,
             created_at: Some(1),
             updated_at: Some(20),
+            web: None,
+            pac_domains: None,
+            system_proxy: None,
+            schedule: None,
         };
         std::fs::write(
             dir.join(TUNNELS_FILE),
@@ -542,6 +567,10 @@ mod tests {
             group: None,
             created_at: Some(1),
             updated_at: Some(1),
+            web: None,
+            pac_domains: None,
+            system_proxy: None,
+            schedule: None,
         };
         let first_id = tunnel_logical_id(&first);
         first.tunnel_type = "remote".into();
@@ -577,6 +606,10 @@ mod tests {
             group: None,
             created_at: Some(1),
             updated_at: Some(1),
+            web: None,
+            pac_domains: None,
+            system_proxy: None,
+            schedule: None,
         };
         let logical_id = tunnel_logical_id(&existing);
         let initial = SavedTunnelsData {
@@ -638,6 +671,10 @@ mod tests {
             group: None,
             created_at: Some(1),
             updated_at: Some(1),
+            web: None,
+            pac_domains: None,
+            system_proxy: None,
+            schedule: None,
         };
         let legacy_id = legacy_tunnel_fallback_logical_id(
             &existing.connection_id,
@@ -697,6 +734,10 @@ mod tests {
             group: None,
             created_at: Some(1),
             updated_at: Some(1),
+            web: None,
+            pac_domains: None,
+            system_proxy: None,
+            schedule: None,
         };
         let fallback = tunnel_fallback_logical_id(
             &explicit.connection_id,
@@ -0,0 +1,54 @@
+use super::report::{aggregate, in_range, to_csv, GroupBy, ReportRow};
+use super::{flush, load_data, TimeEntry};
+use crate::commands::get_data_dir;
+use crate::types::{SavedConnection, SavedData};
+use std::collections::HashMap;
+use tauri::AppHandle;
+
+fn load_entries(app: &AppHandle, from: Option<&str>, to: Option<&str>) -> Result<Vec<TimeEntry>, String> {
+    flush(app)?;
+    let data = load_data(&get_data_dir(app).join("time_tracking.json"));
+    Ok(data
+        .entries
+        .into_iter()
+        .filter(|entry| in_range(entry, from, to))
+        .collect())
+}
+
+fn load_connections(app: &AppHandle) -> HashMap<String, SavedConnection> {
+    std::fs::read_to_string(get_data_dir(app).join("connections.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<SavedData>(&content).ok())
+        .map(|saved| {
+            saved
+                .connections
+                .into_iter()
+                .map(|connection| (connection.id.clone(), connection))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Tracked seconds grouped by `day`, `host` or `tag`, for inclusive UTC date bounds.
+#[tauri::command]
+pub async fn time_tracking_report(
+    app: AppHandle,
+    group_by: GroupBy,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<Vec<ReportRow>, String> {
+    let entries = load_entries(&app, from.as_deref(), to.as_deref())?;
+    Ok(aggregate(&entries, &load_connections(&app), group_by))
+}
+
+#[tauri::command]
+pub async fn time_tracking_export_csv(
+    app: AppHandle,
+    path: String,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<(), String> {
+    let entries = load_entries(&app, from.as_deref(), to.as_deref())?;
+    let csv = to_csv(&entries, &load_connections(&app));
+    std::fs::write(&path, csv).map_err(|e| format!("Failed to write {path}: {e}"))
+}
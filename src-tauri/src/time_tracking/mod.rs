@@ -0,0 +1,223 @@
+//! Active-time tracking per connection, for billing remote work.
+//!
+//! Time counts between consecutive bits of terminal input on a remote session,
+//! as long as the gap is shorter than the idle threshold; longer gaps are idle
+//! and not billed. Totals are bucketed per UTC day in `time_tracking.json`.
+//! Opt-in via `settings.json`: `"timeTracking": { "enabled": true, "idleMinutes": 5 }`.
+
+pub mod commands;
+pub mod report;
+
+use crate::commands::{get_data_dir, read_effective_settings};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex as StdMutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+const FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TimeTrackingConfig {
+    pub enabled: bool,
+    pub idle_minutes: u64,
+}
+
+impl Default for TimeTrackingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            idle_minutes: 5,
+        }
+    }
+}
+
+pub fn read_time_tracking_config(app: &AppHandle) -> TimeTrackingConfig {
+    read_effective_settings(app)
+        .ok()
+        .and_then(|settings| settings.get("timeTracking").cloned())
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeEntry {
+    /// UTC day, `YYYY-MM-DD`.
+    pub date: String,
+    pub connection_id: String,
+    pub seconds: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TimeTrackingData {
+    pub entries: Vec<TimeEntry>,
+}
+
+#[derive(Default)]
+struct Tracker {
+    last_activity: HashMap<String, Instant>,
+    /// Unflushed seconds keyed by `(date, connection_id)`.
+    pending: HashMap<(String, String), u64>,
+}
+
+static TRACKER: LazyLock<StdMutex<Tracker>> = LazyLock::new(|| StdMutex::new(Tracker::default()));
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static IDLE_SECS: AtomicU64 = AtomicU64::new(300);
+
+fn lock_tracker() -> std::sync::MutexGuard<'static, Tracker> {
+    TRACKER.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// `YYYY-MM-DD` for a unix timestamp (UTC), via the days-from-civil inverse.
+pub(crate) fn utc_date(unix_secs: u64) -> String {
+    let days = (unix_secs / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+fn today() -> String {
+    utc_date(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0),
+    )
+}
+
+/// Note user activity on a remote connection. Cheap; safe to call per keystroke.
+pub(crate) fn record_activity(connection_id: &str) {
+    if !ENABLED.load(Ordering::Relaxed) || connection_id == "local" {
+        return;
+    }
+    let now = Instant::now();
+    let idle = Duration::from_secs(IDLE_SECS.load(Ordering::Relaxed));
+    let mut tracker = lock_tracker();
+    if let Some(previous) = tracker.last_activity.insert(connection_id.to_string(), now) {
+        let gap = now.duration_since(previous);
+        if gap < idle && gap.as_secs() > 0 {
+            *tracker
+                .pending
+                .entry((today(), connection_id.to_string()))
+                .or_insert(0) += gap.as_secs();
+        }
+    }
+}
+
+pub(crate) fn load_data(path: &Path) -> TimeTrackingData {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn merge_pending(data: &mut TimeTrackingData, pending: HashMap<(String, String), u64>) {
+    for ((date, connection_id), seconds) in pending {
+        match data
+            .entries
+            .iter_mut()
+            .find(|entry| entry.date == date && entry.connection_id == connection_id)
+        {
+            Some(entry) => entry.seconds += seconds,
+            None => data.entries.push(TimeEntry {
+                date,
+                connection_id,
+                seconds,
+            }),
+        }
+    }
+}
+
+/// Write pending seconds to disk. Called periodically and before reports.
+pub(crate) fn flush(app: &AppHandle) -> Result<(), String> {
+    let pending = std::mem::take(&mut lock_tracker().pending);
+    if pending.is_empty() {
+        return Ok(());
+    }
+    let path = get_data_dir(app).join("time_tracking.json");
+    let mut data = load_data(&path);
+    merge_pending(&mut data, pending);
+    let json = serde_json::to_string_pretty(&data).map_err(|e| e.to_string())?;
+    crate::atomic_io::durable_replace(&path, json.as_bytes()).map_err(|e| e.to_string())
+}
+
+/// Move tracked time from `from` to `into`, merging rows that land on the same day.
+/// Returns the number of entries that were reassigned.
+pub(crate) fn reassign_connection(app: &AppHandle, from: &str, into: &str) -> Result<usize, String> {
+    flush(app)?;
+    let path = get_data_dir(app).join("time_tracking.json");
+    let mut data = load_data(&path);
+    let mut moved = HashMap::new();
+    data.entries.retain(|entry| {
+        if entry.connection_id != from {
+            return true;
+        }
+        *moved
+            .entry((entry.date.clone(), into.to_string()))
+            .or_insert(0) += entry.seconds;
+        false
+    });
+    let count = moved.len();
+    if count == 0 {
+        return Ok(0);
+    }
+    merge_pending(&mut data, moved);
+    let json = serde_json::to_string_pretty(&data).map_err(|e| e.to_string())?;
+    crate::atomic_io::durable_replace(&path, json.as_bytes()).map_err(|e| e.to_string())?;
+    Ok(count)
+}
+
+pub fn start(app: AppHandle) {
+    crate::crash_reports::spawn_supervised("time-tracking", async move {
+        let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+        loop {
+            interval.tick().await;
+            // Re-read each tick so toggling the setting takes effect without restart.
+            let config = read_time_tracking_config(&app);
+            ENABLED.store(config.enabled, Ordering::Relaxed);
+            IDLE_SECS.store(config.idle_minutes.max(1) * 60, Ordering::Relaxed);
+            if let Err(error) = flush(&app) {
+                eprintln!("[TIME] Failed to persist time tracking: {error}");
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn utc_date_handles_epoch_and_leap_day() {
+        assert_eq!(utc_date(0), "1970-01-01");
+        assert_eq!(utc_date(951_782_400), "2000-02-29");
+        assert_eq!(utc_date(1_790_000_000), "2026-09-21");
+    }
+
+    #[test]
+    fn pending_merges_into_existing_day() {
+        let mut data = TimeTrackingData {
+            entries: vec![TimeEntry {
+                date: "2026-10-16".to_string(),
+                connection_id: "c1".to_string(),
+                seconds: 60,
+            }],
+        };
+        let mut pending = HashMap::new();
+        pending.insert(("2026-10-16".to_string(), "c1".to_string()), 30);
+        pending.insert(("2026-10-16".to_string(), "c2".to_string()), 10);
+        merge_pending(&mut data, pending);
+        assert_eq!(data.entries.len(), 2);
+        assert_eq!(data.entries[0].seconds, 90);
+    }
+}
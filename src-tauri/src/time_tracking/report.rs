@@ -0,0 +1,135 @@
+//! Aggregation of tracked time by day, host or tag, plus CSV rendering.
+
+use super::TimeEntry;
+use crate::types::SavedConnection;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupBy {
+    Day,
+    Host,
+    Tag,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportRow {
+    pub key: String,
+    pub seconds: u64,
+}
+
+/// Inclusive `YYYY-MM-DD` bounds; ISO dates compare correctly as strings.
+pub fn in_range(entry: &TimeEntry, from: Option<&str>, to: Option<&str>) -> bool {
+    from.is_none_or(|from| entry.date.as_str() >= from) && to.is_none_or(|to| entry.date.as_str() <= to)
+}
+
+fn host_label(connection: Option<&SavedConnection>, connection_id: &str) -> String {
+    match connection {
+        Some(connection) if !connection.name.trim().is_empty() => {
+            format!("{} ({})", connection.name, connection.host)
+        }
+        Some(connection) => connection.host.clone(),
+        // Deleted or ephemeral connections keep their time under the raw id.
+        None => connection_id.to_string(),
+    }
+}
+
+pub fn aggregate(
+    entries: &[TimeEntry],
+    connections: &HashMap<String, SavedConnection>,
+    group_by: GroupBy,
+) -> Vec<ReportRow> {
+    let mut totals: BTreeMap<String, u64> = BTreeMap::new();
+    for entry in entries {
+        let connection = connections.get(&entry.connection_id);
+        let keys = match group_by {
+            GroupBy::Day => vec![entry.date.clone()],
+            GroupBy::Host => vec![host_label(connection, &entry.connection_id)],
+            // Time on a host with several tags counts toward each tag.
+            GroupBy::Tag => connection
+                .and_then(|connection| connection.tags.clone())
+                .filter(|tags| !tags.is_empty())
+                .unwrap_or_else(|| vec!["(untagged)".to_string()]),
+        };
+        for key in keys {
+            *totals.entry(key).or_insert(0) += entry.seconds;
+        }
+    }
+    totals
+        .into_iter()
+        .map(|(key, seconds)| ReportRow { key, seconds })
+        .collect()
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// One row per day and connection.
+pub fn to_csv(entries: &[TimeEntry], connections: &HashMap<String, SavedConnection>) -> String {
+    let mut sorted: Vec<&TimeEntry> = entries.iter().collect();
+    sorted.sort_by(|a, b| a.date.cmp(&b.date).then(a.connection_id.cmp(&b.connection_id)));
+
+    let mut csv = String::from("date,connection,host,tags,seconds,hours\n");
+    for entry in sorted {
+        let connection = connections.get(&entry.connection_id);
+        let name = connection
+            .map(|connection| connection.name.clone())
+            .unwrap_or_else(|| entry.connection_id.clone());
+        let host = connection.map(|connection| connection.host.clone()).unwrap_or_default();
+        let tags = connection
+            .and_then(|connection| connection.tags.clone())
+            .unwrap_or_default()
+            .join(";");
+        csv.push_str(&format!(
+            "{},{},{},{},{},{:.2}\n",
+            entry.date,
+            csv_field(&name),
+            csv_field(&host),
+            csv_field(&tags),
+            entry.seconds,
+            entry.seconds as f64 / 3600.0
+        ));
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(date: &str, connection_id: &str, seconds: u64) -> TimeEntry {
+        TimeEntry {
+            date: date.to_string(),
+            connection_id: connection_id.to_string(),
+            seconds,
+        }
+    }
+
+    #[test]
+    fn groups_by_day_and_filters_range() {
+        let entries = vec![
+            entry("2026-10-01", "a", 60),
+            entry("2026-10-01", "b", 30),
+            entry("2026-10-02", "a", 10),
+        ];
+        let filtered: Vec<TimeEntry> = entries
+            .into_iter()
+            .filter(|entry| in_range(entry, Some("2026-10-01"), Some("2026-10-01")))
+            .collect();
+        let rows = aggregate(&filtered, &HashMap::new(), GroupBy::Day);
+        assert_eq!(rows, vec![ReportRow { key: "2026-10-01".to_string(), seconds: 90 }]);
+    }
+
+    #[test]
+    fn unknown_connections_are_untagged() {
+        let rows = aggregate(&[entry("2026-10-01", "gone", 5)], &HashMap::new(), GroupBy::Tag);
+        assert_eq!(rows[0].key, "(untagged)");
+    }
+}
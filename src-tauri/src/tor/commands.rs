@@ -0,0 +1,16 @@
+use serde::Serialize;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TorStatus {
+    /// Address of the Tor SOCKS proxy found, if any.
+    pub proxy: Option<String>,
+}
+
+/// Whether a local Tor proxy is up for `.onion` connections.
+#[tauri::command]
+pub async fn tor_status() -> Result<TorStatus, String> {
+    Ok(TorStatus {
+        proxy: super::find_proxy().await.map(str::to_string),
+    })
+}
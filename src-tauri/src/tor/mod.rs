@@ -0,0 +1,160 @@
+//! `.onion` hosts, reached through the local Tor SOCKS proxy.
+//!
+//! Connections to an onion address dial Tor's SOCKS port instead of the host,
+//! and hand the name to Tor to resolve, since only Tor can. The proxy is
+//! looked for where the tor daemon (9050) and Tor Browser (9150) listen; when
+//! neither answers the connect fails with how to start one.
+
+pub mod commands;
+
+use crate::tunnels::socks5::{
+    ATYP_DOMAIN, ATYP_IPV4, ATYP_IPV6, CMD_CONNECT, METHOD_NO_AUTH, REP_SUCCEEDED, VERSION,
+};
+use anyhow::{anyhow, bail, Result};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// The tor daemon's SOCKS port, then Tor Browser's.
+pub const PROXIES: [&str; 2] = ["127.0.0.1:9050", "127.0.0.1:9150"];
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+pub fn is_onion(host: &str) -> bool {
+    host.trim_end_matches('.')
+        .rsplit_once('.')
+        .is_some_and(|(name, tld)| !name.is_empty() && tld.eq_ignore_ascii_case("onion"))
+}
+
+/// Open `proxy` and agree on no authentication; fails unless it speaks SOCKS5.
+async fn greet(proxy: &str) -> Result<TcpStream> {
+    let mut stream = TcpStream::connect(proxy).await?;
+    stream.write_all(&[VERSION, 1, METHOD_NO_AUTH]).await?;
+    let mut reply = [0u8; 2];
+    stream.read_exact(&mut reply).await?;
+    if reply != [VERSION, METHOD_NO_AUTH] {
+        bail!("{proxy} is not a SOCKS5 proxy without authentication");
+    }
+    Ok(stream)
+}
+
+/// The first of [`PROXIES`] that answers as a SOCKS5 proxy.
+pub async fn find_proxy() -> Option<&'static str> {
+    for proxy in PROXIES {
+        if let Ok(Ok(_)) = tokio::time::timeout(PROBE_TIMEOUT, greet(proxy)).await {
+            return Some(proxy);
+        }
+    }
+    None
+}
+
+fn not_running_error() -> anyhow::Error {
+    anyhow!(
+        "Tor is not running: no Tor SOCKS proxy answered on {}. Start the tor service \
+         (`brew services start tor`, `sudo systemctl start tor`) or open Tor Browser, \
+         then connect again.",
+        PROXIES.join(" or ")
+    )
+}
+
+fn connect_request(host: &str, port: u16) -> Result<Vec<u8>> {
+    let name = host.trim_end_matches('.');
+    let len = u8::try_from(name.len()).map_err(|_| anyhow!("Host name too long: {name}"))?;
+    let mut request = vec![VERSION, CMD_CONNECT, 0x00, ATYP_DOMAIN, len];
+    request.extend_from_slice(name.as_bytes());
+    request.extend_from_slice(&port.to_be_bytes());
+    Ok(request)
+}
+
+/// Tor's reasons for refusing a CONNECT, including its onion-specific codes.
+fn reply_error(code: u8) -> &'static str {
+    match code {
+        0x04 | 0xF2 | 0xF3 | 0xF7 => "the onion service is unreachable; is it online?",
+        0x05 => "the onion service refused the connection; check the port",
+        0x06 => "Tor timed out reaching the onion service",
+        0xF0 => "Tor couldn't find the onion service's descriptor; check the address",
+        0xF1 => "the onion service's descriptor is invalid",
+        0xF4 => "the onion service requires client authorization",
+        0xF5 => "Tor's client authorization for the onion service was rejected",
+        0xF6 => "the onion address is invalid",
+        _ => "Tor could not open the connection",
+    }
+}
+
+/// Connect to `host:port` through the proxy after the SOCKS greeting.
+async fn socks_connect(mut stream: TcpStream, host: &str, port: u16) -> Result<TcpStream> {
+    stream.write_all(&connect_request(host, port)?).await?;
+    let mut head = [0u8; 4];
+    stream.read_exact(&mut head).await?;
+    if head[1] != REP_SUCCEEDED {
+        bail!(
+            "{host}: {} (SOCKS reply {:#04x})",
+            reply_error(head[1]),
+            head[1]
+        );
+    }
+    // The bound address isn't needed, but has to be read past.
+    let address_len = match head[3] {
+        ATYP_IPV4 => 4,
+        ATYP_IPV6 => 16,
+        ATYP_DOMAIN => stream.read_u8().await? as usize,
+        other => bail!("Unexpected SOCKS address type {other:#04x} from Tor"),
+    };
+    let mut bound = vec![0u8; address_len + 2];
+    stream.read_exact(&mut bound).await?;
+    Ok(stream)
+}
+
+/// A stream to `host:port` through the local Tor proxy.
+pub async fn connect(host: &str, port: u16) -> Result<TcpStream> {
+    let proxy = find_proxy().await.ok_or_else(not_running_error)?;
+    println!("[SSH] Reaching {host}:{port} through Tor at {proxy}");
+    socks_connect(greet(proxy).await?, host, port).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn recognizes_onion_hosts() {
+        let address = "2gzyxa5ihm7nsggfxnu52rck2vv4rvmdlkiu3zzui5du4xyclen53wid.onion";
+        assert!(is_onion(address));
+        assert!(is_onion("Example.ONION."));
+        assert!(!is_onion("onion.example.com"));
+        assert!(!is_onion("onion"));
+
+        assert_eq!(
+            connect_request("abc.onion", 22).unwrap(),
+            [5, 1, 0, 3, 9, b'a', b'b', b'c', b'.', b'o', b'n', b'i', b'o', b'n', 0, 22]
+        );
+    }
+
+    #[tokio::test]
+    async fn connects_through_the_proxy() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            for reply in [REP_SUCCEEDED, 0xF0] {
+                let (mut client, _) = listener.accept().await.unwrap();
+                let mut greeting = [0u8; 3];
+                client.read_exact(&mut greeting).await.unwrap();
+                client.write_all(&[VERSION, METHOD_NO_AUTH]).await.unwrap();
+                let mut request = [0u8; 16];
+                client.read_exact(&mut request).await.unwrap();
+                let bound = [VERSION, reply, 0, ATYP_IPV4, 0, 0, 0, 0, 0, 0];
+                client.write_all(&bound).await.unwrap();
+                client.write_all(b"SSH-2.0-OpenSSH_9.6\r\n").await.unwrap();
+            }
+        });
+
+        let stream = socks_connect(greet(&proxy).await.unwrap(), "abc.onion", 22);
+        let mut stream = stream.await.unwrap();
+        let mut banner = [0u8; 21];
+        stream.read_exact(&mut banner).await.unwrap();
+        assert_eq!(&banner, b"SSH-2.0-OpenSSH_9.6\r\n");
+
+        let refused = socks_connect(greet(&proxy).await.unwrap(), "abc.onion", 22).await;
+        assert!(refused.unwrap_err().to_string().contains("descriptor"));
+    }
+}
@@ -1,6 +1,10 @@
 use crate::commands::{get_data_dir, AppState};
 use super::manager::probe_ssh_session;
+use super::loopback_alias;
+use super::privileged_ports::{self, PortFixKind, PrivilegedPortStatus};
+use super::validate::{blocking_error, local_port_taken, validate_tunnel, TunnelIssue};
 use super::{remote_forward_map_key, tunnel_runtime_id};
+use crate::events::ConnectionEvent;
 use crate::types::{SavedTunnel, SavedTunnelsData};
 use serde::Serialize;
 use std::collections::{HashMap, HashSet};
@@ -15,6 +19,26 @@ pub struct TunnelStatusChange {
     pub error: Option<String>,
 }
 
+/// Emit `tunnel:status-change` and mirror it onto the connection event bus.
+pub(crate) fn emit_tunnel_status(app: &AppHandle, connection_id: &str, change: TunnelStatusChange) {
+    let event = if change.status == "active" {
+        ConnectionEvent::TunnelUp {
+            tunnel_id: change.id.clone(),
+        }
+    } else {
+        ConnectionEvent::TunnelDown {
+            tunnel_id: change.id.clone(),
+            error: change.error.clone(),
+        }
+    };
+    crate::events::publish(connection_id, event);
+    if change.status != "active" {
+        super::system_proxy::release_in_background(app, &change.id);
+        super::hostnames::unpublish(&change.id);
+    }
+    let _ = app.emit("tunnel:status-change", change);
+}
+
 fn connection_has_live_session(
     connections: &std::collections::HashMap<String, crate::commands::ConnectionHandle>,
     connection_id: &str,
@@ -95,10 +119,11 @@ pub(crate) async fn reconcile_stale_tunnel_runtime(
 
     for tunnel in stale_tunnels {
         let _ = state.tunnel_manager.stop_tunnel(None, &tunnel).await;
-        let _ = app.emit(
-            "tunnel:status-change",
+        emit_tunnel_status(
+            app,
+            &tunnel.connection_id,
             TunnelStatusChange {
-                id: tunnel.id,
+                id: tunnel.id.clone(),
                 status: "stopped".to_string(),
                 error: None,
             },
@@ -158,7 +183,7 @@ async fn apply_runtime_tunnel_status(
     let mut dead_connections = Vec::new();
     for connection_id in active_connection_ids {
         let usable = match sessions_by_connection.get(&connection_id) {
-            Some(session) => probe_ssh_session(session).await,
+            Some(session) => probe_ssh_session(&connection_id, session).await,
             None => false,
         };
         if !usable {
@@ -265,10 +290,11 @@ pub(crate) async fn stop_tunnels_for_connections(
             Ok(()) => ("stopped".to_string(), None),
             Err(error) => ("error".to_string(), Some(error.to_string())),
         };
-        let _ = app.emit(
-            "tunnel:status-change",
+        emit_tunnel_status(
+            app,
+            &tunnel.connection_id,
             TunnelStatusChange {
-                id: tunnel.id,
+                id: tunnel.id.clone(),
                 status,
                 error,
             },
@@ -296,6 +322,7 @@ pub async fn tunnel_start_local(
     };
 
     let bind_addr = bind_address.unwrap_or_else(|| "127.0.0.1".to_string());
+    loopback_alias::ensure(&bind_addr).await?;
     let runtime_id = format!(
         "local:{}:{}:{}:{}",
         connection_id,
@@ -314,6 +341,7 @@ pub async fn tunnel_start_local(
             local_port,
             remote_host,
             remote_port,
+            super::LocalListenerLayers::default(),
         )
         .await;
     res.map_err(|e| e.to_string())
@@ -397,8 +425,9 @@ pub async fn tunnel_stop(
         .await;
 
     if let Err(ref e) = res {
-        let _ = app.emit(
-            "tunnel:status-change",
+        emit_tunnel_status(
+            &app,
+            &tunnel.connection_id,
             TunnelStatusChange {
                 id: id.clone(),
                 status: "error".to_string(),
@@ -406,8 +435,9 @@ pub async fn tunnel_stop(
             },
         );
     } else {
-        let _ = app.emit(
-            "tunnel:status-change",
+        emit_tunnel_status(
+            &app,
+            &tunnel.connection_id,
             TunnelStatusChange {
                 id: id.clone(),
                 status: "stopped".to_string(),
@@ -455,6 +485,17 @@ pub async fn tunnel_reconcile_connection(
     stop_tunnels_for_connections(&app, &state, &[connection_id]).await
 }
 
+/// Start the tunnels that were running when `connection_id`'s transport dropped,
+/// plus its auto-start tunnels; progress goes out as `tunnel:restore-progress`.
+#[tauri::command]
+pub async fn tunnel_restore_connection(
+    app: AppHandle,
+    connection_id: String,
+    state: State<'_, AppState>,
+) -> Result<super::restore::TunnelRestoreSummary, String> {
+    super::restore::restore_tunnels_for_connection(&app, &state, &connection_id).await
+}
+
 fn tunnel_is_active_runtime(
     tunnel: &SavedTunnel,
     local_runtime_keys: &HashSet<String>,
@@ -468,9 +509,36 @@ fn tunnel_is_active_runtime(
     }
 }
 
+/// Ids of the saved tunnels whose listener or remote forward is running.
+pub(crate) async fn running_tunnel_ids(app: &AppHandle, state: &AppState) -> Vec<String> {
+    let file_path = get_data_dir(app).join("tunnels.json");
+    let Ok(saved) = crate::sync::domain_tunnels::load_saved_tunnels(&file_path) else {
+        return Vec::new();
+    };
+    let (local_runtime_keys, remote_runtime_keys) = {
+        let local_listeners = state.tunnel_manager.local_listeners.lock().await;
+        let remote_forwards = state.tunnel_manager.remote_forwards.lock().await;
+        (
+            local_listeners.keys().cloned().collect::<HashSet<_>>(),
+            remote_forwards.keys().cloned().collect::<HashSet<_>>(),
+        )
+    };
+    saved
+        .tunnels
+        .into_iter()
+        .filter(|tunnel| {
+            tunnel_is_active_runtime(tunnel, &local_runtime_keys, &remote_runtime_keys)
+        })
+        .map(|tunnel| tunnel.id)
+        .collect()
+}
+
 #[tauri::command]
 pub async fn tunnel_save(app: AppHandle, tunnel_val: serde_json::Value) -> Result<(), String> {
     let mut tunnel: SavedTunnel = serde_json::from_value(tunnel_val).map_err(|e| e.to_string())?;
+    if let Some(schedule) = &tunnel.schedule {
+        super::schedule::validate(schedule)?;
+    }
     let data_dir = get_data_dir(&app);
     if !data_dir.exists() {
         std::fs::create_dir_all(&data_dir).map_err(|e| e.to_string())?;
@@ -527,9 +595,82 @@ pub async fn tunnel_delete(app: AppHandle, id: String) -> Result<(), String> {
     crate::sync::domain_tunnels::write_saved_tunnels_atomic(&file_path, &saved)
         .map_err(|error| error.to_string())?;
 
+    if let Err(error) = super::port_catalog::release_for_tunnel(&data_dir, &id) {
+        eprintln!("[TUNNEL] Failed to release port reservations for {id}: {error}");
+    }
+
     Ok(())
 }
 
+/// Layers a saved local forward's `web` settings ask for.
+fn local_listener_layers(
+    data_dir: &std::path::Path,
+    tunnel: &SavedTunnel,
+    bind_addr: &str,
+) -> Result<super::LocalListenerLayers, String> {
+    let web = tunnel.web.clone().unwrap_or_default();
+    let host_header = web
+        .host_header
+        .as_deref()
+        .map(str::trim)
+        .filter(|host| !host.is_empty());
+    let rewrite = host_header.map(|host| {
+        super::http_rewrite::HostRewrite::new(
+            host,
+            web.terminate_tls,
+            tunnel.local_port,
+            web.upstream_tls,
+        )
+    });
+    let sni = rewrite
+        .as_ref()
+        .map(|rewrite| rewrite.hostname().to_string())
+        .unwrap_or_else(|| tunnel.remote_host.clone());
+    let hostname = super::hostnames::tunnel_hostname(tunnel).transpose()?;
+    Ok(super::LocalListenerLayers {
+        tls: if web.terminate_tls {
+            Some(super::local_tls::acceptor(data_dir, bind_addr, hostname.as_deref())?)
+        } else {
+            None
+        },
+        http: (web.inspect || rewrite.is_some()).then(|| super::http_inspect::HttpLayerOptions {
+            log: web.inspect,
+            rewrite,
+        }),
+        upstream_tls: if web.upstream_tls {
+            Some(super::local_tls::upstream(&sni)?)
+        } else {
+            None
+        },
+    })
+}
+
+/// Check an edited, possibly unsaved, tunnel against the saved ones.
+#[tauri::command]
+pub async fn tunnel_validate(
+    app: AppHandle,
+    tunnel_val: serde_json::Value,
+    state: State<'_, AppState>,
+) -> Result<Vec<TunnelIssue>, String> {
+    let tunnel: SavedTunnel = serde_json::from_value(tunnel_val).map_err(|e| e.to_string())?;
+    let tunnels_path = get_data_dir(&app).join("tunnels.json");
+    let saved = crate::sync::domain_tunnels::load_saved_tunnels(&tunnels_path)
+        .map(|saved| saved.tunnels)
+        .unwrap_or_default();
+    let elevated = privileged_ports::needs_elevation(tunnel.local_port);
+    let mut issues = validate_tunnel(&tunnel, &saved, elevated);
+    let running = state
+        .tunnel_manager
+        .local_listeners
+        .lock()
+        .await
+        .contains_key(&tunnel_runtime_id(&tunnel));
+    if !running {
+        issues.extend(local_port_taken(&tunnel));
+    }
+    Ok(issues)
+}
+
 #[tauri::command]
 pub async fn tunnel_start(
     app: AppHandle,
@@ -546,9 +687,19 @@ pub async fn tunnel_start(
 
     let tunnel = saved_data
         .tunnels
-        .into_iter()
+        .iter()
         .find(|t| t.id == id)
+        .cloned()
         .ok_or_else(|| "Tunnel not found".to_string())?;
+    super::schedule::ensure_inside(&tunnel.id, tunnel.schedule.as_ref())?;
+    let elevated = privileged_ports::needs_elevation(tunnel.local_port);
+    let issues = validate_tunnel(&tunnel, &saved_data.tunnels, elevated);
+    if let Some(error) = blocking_error(&issues) {
+        return Err(format!("Invalid forward: {error}"));
+    }
+    if tunnel.tunnel_type != "remote" {
+        loopback_alias::ensure(tunnel.bind_address.as_deref().unwrap_or("127.0.0.1")).await?;
+    }
 
     let session = {
         let connections = state.connections.lock().await;
@@ -584,6 +735,7 @@ pub async fn tunnel_start(
             .bind_address
             .clone()
             .unwrap_or_else(|| "127.0.0.1".to_string());
+        let layers = local_listener_layers(&data_dir, &tunnel, &bind_addr)?;
         state
             .tunnel_manager
             .start_local_forwarding(
@@ -594,6 +746,7 @@ pub async fn tunnel_start(
                 tunnel.local_port,
                 tunnel.remote_host.clone(),
                 tunnel.remote_port,
+                layers,
             )
             .await
     } else {
@@ -616,8 +769,9 @@ pub async fn tunnel_start(
     };
 
     if let Err(ref e) = res {
-        let _ = app.emit(
-            "tunnel:status-change",
+        emit_tunnel_status(
+            &app,
+            &tunnel.connection_id,
             TunnelStatusChange {
                 id: id.clone(),
                 status: "error".to_string(),
@@ -625,19 +779,154 @@ pub async fn tunnel_start(
             },
         );
     } else {
-        let _ = app.emit(
-            "tunnel:status-change",
+        emit_tunnel_status(
+            &app,
+            &tunnel.connection_id,
             TunnelStatusChange {
                 id: id.clone(),
                 status: "active".to_string(),
                 error: None,
             },
         );
+        state
+            .tunnel_manager
+            .forget_for_restore(&tunnel.connection_id, &id)
+            .await;
+        if tunnel.tunnel_type == "dynamic"
+            && tunnel
+                .pac_domains
+                .as_ref()
+                .is_some_and(|domains| !domains.is_empty())
+        {
+            if let Err(error) = super::pac::ensure_server(&app).await {
+                eprintln!("[TUNNEL][PAC] {error}");
+            }
+        }
+        if let Some(Ok(name)) = super::hostnames::tunnel_hostname(&tunnel) {
+            let address = super::hostnames::hosts_address(tunnel.bind_address.as_deref());
+            super::hostnames::publish(&tunnel.id, name, address);
+        }
+        if tunnel.tunnel_type == "dynamic" && tunnel.system_proxy.unwrap_or(false) {
+            let host = super::pac::proxy_host(tunnel.bind_address.as_deref());
+            let host = host.trim_start_matches('[').trim_end_matches(']');
+            if let Err(error) =
+                super::system_proxy::take_over(&data_dir, &tunnel.id, host, tunnel.local_port).await
+            {
+                eprintln!("[TUNNEL][PROXY] {error}");
+            }
+        }
     }
 
     res.map_err(|e| e.to_string())
 }
 
+/// Whether each scheduled tunnel is inside a window and when that changes.
+#[tauri::command]
+pub async fn tunnel_schedule_status(
+    app: AppHandle,
+) -> Result<Vec<super::schedule::ScheduleStatus>, String> {
+    super::schedule::statuses(&app)
+}
+
+fn load_connections_and_tunnels(
+    app: &AppHandle,
+) -> Result<(Vec<crate::types::SavedConnection>, Vec<SavedTunnel>), String> {
+    let data_dir = get_data_dir(app);
+    let connections = {
+        let _guard = crate::sync::domain_hosts::CONNECTIONS_MUTATION_LOCK
+            .lock()
+            .map_err(|error| error.to_string())?;
+        crate::sync::domain_hosts::load_saved_data(&data_dir.join("connections.json"))
+            .map_err(|error| error.to_string())?
+            .connections
+    };
+    let tunnels = crate::sync::domain_tunnels::load_saved_tunnels(&data_dir.join("tunnels.json"))
+        .map_err(|error| error.to_string())?
+        .tunnels;
+    Ok((connections, tunnels))
+}
+
+/// Saved tunnels as ssh_config forward lines, one `Host` block per connection
+/// (all connections with tunnels, or just `connection_ids`).
+#[tauri::command]
+pub async fn tunnel_export_ssh_config(
+    app: AppHandle,
+    connection_ids: Option<Vec<String>>,
+) -> Result<String, String> {
+    let (connections, tunnels) = load_connections_and_tunnels(&app)?;
+    let hosts: Vec<(String, Vec<&SavedTunnel>)> = connections
+        .iter()
+        .filter(|connection| {
+            connection_ids
+                .as_ref()
+                .is_none_or(|ids| ids.contains(&connection.id))
+        })
+        .map(|connection| {
+            let owned: Vec<&SavedTunnel> = tunnels
+                .iter()
+                .filter(|tunnel| tunnel.connection_id == connection.id)
+                .collect();
+            (crate::commands::build_host_alias(connection), owned)
+        })
+        .filter(|(_, owned)| !owned.is_empty())
+        .collect();
+    if hosts.is_empty() {
+        return Err("No saved forwards to export".to_string());
+    }
+    Ok(super::ssh_forwards::render_config(&hosts))
+}
+
+/// Forward lines found in ssh_config text (or `~/.ssh/config` when `content`
+/// is `None`), matched to saved connections and tunnels. Nothing is saved.
+#[tauri::command]
+pub async fn tunnel_preview_ssh_config_import(
+    app: AppHandle,
+    content: Option<String>,
+) -> Result<super::ssh_forwards::ConfigForwardPreview, String> {
+    use super::ssh_forwards::{
+        match_connection, parse_host_forwards, same_forward, ConfigForwardImport,
+        ConfigForwardPreview,
+    };
+    use tauri::Manager;
+
+    let content = match content {
+        Some(content) => content,
+        None => {
+            let home = app.path().home_dir().map_err(|e| e.to_string())?;
+            let path = crate::user_profiles::ssh_dir(&home).join("config");
+            std::fs::read_to_string(&path)
+                .map_err(|error| format!("Failed to read {}: {error}", path.display()))?
+        }
+    };
+    if content.len() > crate::commands::MAX_IMPORT_TEXT_BYTES {
+        return Err("SSH config is too large (max 1 MiB).".to_string());
+    }
+    let (connections, tunnels) = load_connections_and_tunnels(&app)?;
+    let mut preview = ConfigForwardPreview::default();
+    for block in parse_host_forwards(&content) {
+        let connection =
+            match_connection(&block, &connections, crate::commands::build_host_alias);
+        preview.errors.extend(block.errors);
+        for forward in block.forwards {
+            let existing_tunnel_id = connection.and_then(|connection| {
+                tunnels
+                    .iter()
+                    .find(|tunnel| {
+                        tunnel.connection_id == connection.id && same_forward(tunnel, &forward)
+                    })
+                    .map(|tunnel| tunnel.id.clone())
+            });
+            preview.forwards.push(ConfigForwardImport {
+                host_alias: block.aliases[0].clone(),
+                connection_id: connection.map(|connection| connection.id.clone()),
+                forward,
+                existing_tunnel_id,
+            });
+        }
+    }
+    Ok(preview)
+}
+
 #[tauri::command]
 pub async fn tunnel_get_all(
     app: AppHandle,
@@ -658,4 +947,244 @@ pub async fn tunnel_get_all(
     apply_runtime_tunnel_status(&app, &state, &mut tunnels).await;
 
     Ok(tunnels)
-}
\ No newline at end of file
+}
+async fn remote_listening_ports(
+    state: &AppState,
+    connection_id: &str,
+    names: &super::service_names::ServiceNames,
+) -> Result<Vec<super::listening_ports::ListeningPort>, String> {
+    use super::listening_ports::{parse_listening_ports, LISTENING_PORTS_SCRIPT};
+
+    crate::commands::ensure_connection_online(state, connection_id).await?;
+    let output =
+        crate::commands::exec_on_connection(state, connection_id, LISTENING_PORTS_SCRIPT).await?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if stdout.trim().is_empty() {
+        use crate::host_capabilities::{feature, probe, Feature};
+        let missing = probe(state, connection_id, false)
+            .await
+            .ok()
+            .and_then(|capabilities| feature(&capabilities, Feature::ListeningPorts).describe());
+        return Err(missing.unwrap_or_else(|| {
+            "Neither ss nor netstat is available on the remote host".to_string()
+        }));
+    }
+    let mut ports = parse_listening_ports(&stdout);
+    for entry in &mut ports {
+        entry.service = names.name(entry.port).map(str::to_string);
+    }
+    Ok(ports)
+}
+
+/// TCP ports listening on the remote host, for "forward this" suggestions.
+#[tauri::command]
+pub async fn list_remote_listening_ports(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    connection_id: String,
+) -> Result<Vec<super::listening_ports::ListeningPort>, String> {
+    let names = super::service_names::ServiceNames::from_app(&app);
+    remote_listening_ports(&state, &connection_id, &names).await
+}
+
+/// Pre-named local-forward drafts for recognised services (MySQL, Postgres,
+/// Redis, web servers, ...) listening on the remote host.
+#[tauri::command]
+pub async fn suggest_tunnels(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    connection_id: String,
+) -> Result<Vec<super::service_detect::TunnelSuggestion>, String> {
+    let names = super::service_names::ServiceNames::from_app(&app);
+    let ports = remote_listening_ports(&state, &connection_id, &names).await?;
+    let file_path = get_data_dir(&app).join("tunnels.json");
+    let saved = crate::sync::domain_tunnels::load_saved_tunnels(&file_path)
+        .map_err(|error| error.to_string())?;
+    Ok(super::service_detect::suggest_tunnels(
+        &connection_id,
+        &ports,
+        &saved.tunnels,
+        &names,
+    ))
+}
+
+/// The port → service name catalog with the user's `serviceNames` overrides.
+#[tauri::command]
+pub async fn service_names_list(
+    app: AppHandle,
+) -> Result<Vec<super::service_names::ServiceNameEntry>, String> {
+    Ok(super::service_names::ServiceNames::from_app(&app).entries())
+}
+
+/// Wait until a local web forward answers, then open it in the default browser.
+/// Returns the opened URL and any certificate/Host-header pitfalls found.
+#[tauri::command]
+pub async fn tunnel_open_in_browser(
+    app: AppHandle,
+    id: String,
+    timeout_secs: Option<u64>,
+) -> Result<super::web_open::WebOpenReport, String> {
+    use tauri_plugin_opener::OpenerExt;
+
+    let data_dir = get_data_dir(&app);
+    let file_path = data_dir.join("tunnels.json");
+    let saved = crate::sync::domain_tunnels::load_saved_tunnels(&file_path)
+        .map_err(|error| error.to_string())?;
+    let tunnel = saved
+        .tunnels
+        .into_iter()
+        .find(|tunnel| tunnel.id == id)
+        .ok_or_else(|| "Tunnel not found".to_string())?;
+
+    let url = super::web_open::browser_url(&tunnel)?;
+    let local_ca = if tunnel.web.as_ref().is_some_and(|web| web.terminate_tls) {
+        Some(super::local_tls::ca_pem(&data_dir)?)
+    } else {
+        None
+    };
+    let timeout = std::time::Duration::from_secs(timeout_secs.unwrap_or(15).clamp(1, 120));
+    let mut report = super::web_open::wait_until_ready(
+        &url,
+        &tunnel.remote_host,
+        tunnel.local_port,
+        local_ca.as_deref(),
+        timeout,
+    )
+    .await?;
+    // Probed via the listen address; the browser gets the published name.
+    if let Some(name) = super::hostnames::published(&tunnel.id) {
+        if let Ok(mut named) = url::Url::parse(&report.url) {
+            if named.set_host(Some(&name)).is_ok() {
+                report.url = named.to_string();
+            }
+        }
+    }
+    app.opener()
+        .open_url(report.url.clone(), None::<String>)
+        .map_err(|error| error.to_string())?;
+    Ok(report)
+}
+
+/// Forward a VNC/RDP port to a free local port and open the local viewer on
+/// it; the forward is stopped when the viewer exits.
+#[tauri::command]
+pub async fn tunnel_launch_remote_desktop(
+    connection_id: String,
+    kind: super::remote_desktop::RemoteDesktopKind,
+    remote_host: Option<String>,
+    remote_port: Option<u16>,
+    state: State<'_, AppState>,
+) -> Result<super::remote_desktop::RemoteDesktopSession, String> {
+    let remote_host = remote_host
+        .map(|host| host.trim().to_string())
+        .filter(|host| !host.is_empty())
+        .unwrap_or_else(|| "localhost".to_string());
+    let remote_port = remote_port.unwrap_or_else(|| kind.default_port());
+    super::remote_desktop::launch(&state, &connection_id, kind, &remote_host, remote_port).await
+}
+
+/// The local CA used for HTTPS termination (created on first use), so the UI
+/// can point the user at the certificate to trust.
+#[tauri::command]
+pub async fn tunnel_local_ca(app: AppHandle) -> Result<super::local_tls::LocalCaInfo, String> {
+    super::local_tls::ca_info(&get_data_dir(&app))
+}
+
+/// HTTP exchanges logged by the request inspector for a saved local forward.
+#[tauri::command]
+pub async fn tunnel_http_log(
+    app: AppHandle,
+    id: String,
+    clear: Option<bool>,
+) -> Result<Vec<super::http_inspect::HttpExchange>, String> {
+    let file_path = get_data_dir(&app).join("tunnels.json");
+    let saved = crate::sync::domain_tunnels::load_saved_tunnels(&file_path)
+        .map_err(|error| error.to_string())?;
+    let tunnel = saved
+        .tunnels
+        .iter()
+        .find(|tunnel| tunnel.id == id)
+        .ok_or_else(|| "Tunnel not found".to_string())?;
+    let runtime_id = tunnel_runtime_id(tunnel);
+    let exchanges = super::http_inspect::exchanges(&runtime_id);
+    if clear.unwrap_or(false) {
+        super::http_inspect::clear(&runtime_id);
+    }
+    Ok(exchanges)
+}
+
+/// PAC file URL (starting the server on first use) and the routes it carries.
+#[tauri::command]
+pub async fn tunnel_pac_info(app: AppHandle) -> Result<super::pac::PacInfo, String> {
+    super::pac::info(&app).await
+}
+
+/// Named local port reservations, lowest port first.
+#[tauri::command]
+pub async fn port_reservations_list(
+    app: AppHandle,
+) -> Result<Vec<super::port_catalog::PortReservation>, String> {
+    super::port_catalog::list(&get_data_dir(&app))
+}
+
+/// The port reserved under `name`, reserving a free one in
+/// `range_start..=range_end` (default 42000-42999) on first use.
+#[tauri::command]
+pub async fn port_reserve(
+    app: AppHandle,
+    name: String,
+    range_start: Option<u16>,
+    range_end: Option<u16>,
+    tunnel_id: Option<String>,
+) -> Result<super::port_catalog::PortReservation, String> {
+    let data_dir = get_data_dir(&app);
+    let default = super::port_catalog::DEFAULT_RANGE;
+    let range = range_start.unwrap_or(*default.start())..=range_end.unwrap_or(*default.end());
+    let file_path = data_dir.join("tunnels.json");
+    let in_use: HashSet<u16> = if file_path.exists() {
+        crate::sync::domain_tunnels::load_saved_tunnels(&file_path)
+            .map_err(|error| error.to_string())?
+            .tunnels
+            .into_iter()
+            .filter(|tunnel| tunnel_id.as_deref() != Some(tunnel.id.as_str()))
+            .map(|tunnel| tunnel.local_port)
+            .collect()
+    } else {
+        HashSet::new()
+    };
+    super::port_catalog::reserve(&data_dir, &name, range, tunnel_id.as_deref(), &in_use)
+}
+
+#[tauri::command]
+pub async fn port_release(app: AppHandle, name: String) -> Result<bool, String> {
+    super::port_catalog::release(&get_data_dir(&app), &name)
+}
+
+/// The lowest loopback alias (127.0.0.2 and up) free on local `port` for
+/// tunnel `id`, or `None` when every alias is taken.
+#[tauri::command]
+pub async fn tunnel_next_loopback_alias(
+    app: AppHandle,
+    id: String,
+    port: u16,
+) -> Result<Option<String>, String> {
+    let tunnels_path = get_data_dir(&app).join("tunnels.json");
+    let saved = crate::sync::domain_tunnels::load_saved_tunnels(&tunnels_path)
+        .map(|saved| saved.tunnels)
+        .unwrap_or_default();
+    let free = loopback_alias::next_free(&id, port, &saved, |ip| {
+        loopback_alias::port_in_use(ip, port)
+    });
+    Ok(free.map(|ip| ip.to_string()))
+}
+
+#[tauri::command]
+pub async fn privileged_ports_status(port: u16) -> Result<PrivilegedPortStatus, String> {
+    Ok(privileged_ports::status(port))
+}
+
+/// Apply a low-port fix as root; the OS asks for the admin password.
+#[tauri::command]
+pub async fn privileged_ports_apply(kind: PortFixKind, port: u16) -> Result<(), String> {
+    privileged_ports::apply(kind, port).await
+}
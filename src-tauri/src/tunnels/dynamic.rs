@@ -1,6 +1,10 @@
 //! Dynamic (SOCKS5) port forwarding — local proxy through an SSH session.
 
 use crate::ssh::Client;
+use crate::tunnels::relay;
+use crate::tunnels::runtime_notice::{
+    is_forwarding_prohibited, TunnelNoticeSender, TunnelRuntimeNotice, FORWARDING_PROHIBITED_ERROR,
+};
 use crate::tunnels::session_failure::{is_ssh_session_fatal_error, SessionFailureSender};
 use crate::tunnels::socks5::{
     self, connect_success_reply, error_reply, method_selection_reply, parse_connect_request,
@@ -20,7 +24,9 @@ pub async fn handle_socks5_client(
     mut client: TcpStream,
     session: Arc<Mutex<Handle<Client>>>,
     connection_id: String,
+    runtime_id: String,
     failure_tx: SessionFailureSender,
+    notice_tx: TunnelNoticeSender,
     stop_tx: broadcast::Sender<()>,
     mut cancel: broadcast::Receiver<()>,
 ) {
@@ -28,7 +34,9 @@ pub async fn handle_socks5_client(
         &mut client,
         session,
         &connection_id,
+        &runtime_id,
         &failure_tx,
+        &notice_tx,
         &stop_tx,
         &mut cancel,
     )
@@ -57,7 +65,9 @@ async fn run_socks5_client(
     client: &mut TcpStream,
     session: Arc<Mutex<Handle<Client>>>,
     connection_id: &str,
+    runtime_id: &str,
     failure_tx: &SessionFailureSender,
+    notice_tx: &TunnelNoticeSender,
     stop_tx: &broadcast::Sender<()>,
     cancel: &mut broadcast::Receiver<()>,
 ) -> Result<()> {
@@ -112,6 +122,16 @@ async fn run_socks5_client(
         let channel = match channel {
             Ok(channel) => channel,
             Err(error) => {
+                if is_forwarding_prohibited(&error) {
+                    let _ = client
+                        .write_all(&error_reply(socks5::REP_NOT_ALLOWED))
+                        .await;
+                    let _ = notice_tx.send(TunnelRuntimeNotice {
+                        runtime_id: runtime_id.to_string(),
+                        error: FORWARDING_PROHIBITED_ERROR.to_string(),
+                    });
+                    return Err(error.into());
+                }
                 let _ = client
                     .write_all(&error_reply(socks5::REP_GENERAL_FAILURE))
                     .await;
@@ -131,13 +151,14 @@ async fn run_socks5_client(
 
         let mut stream = channel.into_stream();
         tokio::select! {
-            result = tokio::io::copy_bidirectional(client, &mut stream) => {
-                if let Err(error) = result {
-                    eprintln!(
+            result = relay::copy_bidirectional(client, &mut stream) => {
+                match result {
+                    Ok((sent, received)) => crate::metrics::record_forwarded_bytes(sent + received),
+                    Err(error) => eprintln!(
                         "[TUNNEL][SOCKS] relay error to {}:{} — {error}",
                         target.host,
                         target.port
-                    );
+                    ),
                 }
             }
             _ = cancel.recv() => {}
@@ -0,0 +1,311 @@
+//! Publish memorable names for running local forwards in the hosts file.
+//!
+//! A local forward with `web.hostname` set to `myapp` gets a
+//! `myapp.zync.local` entry pointing at its listen address while it runs.
+//! Entries live in a block between `# BEGIN Zync tunnels` and
+//! `# END Zync tunnels` that is rewritten from the set of running forwards,
+//! so stopping one (or quitting) drops its line and a block left behind by a
+//! crash is cleared on the next launch.
+//!
+//! The hosts file is normally writable only by root/Administrator. Writes go
+//! straight to the file when that works and are otherwise copied into place
+//! through `pkexec` (Linux), an administrator `osascript` prompt (macOS) or a
+//! UAC prompt (Windows). Starts and stops close together are coalesced into
+//! one write, so starting a group of forwards asks once.
+
+use crate::types::SavedTunnel;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex, MutexGuard};
+use std::time::Duration;
+
+pub const SUFFIX: &str = "zync.local";
+const BEGIN: &str = "# BEGIN Zync tunnels";
+const END: &str = "# END Zync tunnels";
+/// How long to wait for more starts/stops before writing.
+const SETTLE: Duration = Duration::from_millis(500);
+
+/// Published `(address, name)` per saved tunnel id.
+static PUBLISHED: LazyLock<Mutex<BTreeMap<String, (String, String)>>> =
+    LazyLock::new(|| Mutex::new(BTreeMap::new()));
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+/// Serializes read-modify-write of the hosts file.
+static WRITE_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+fn lock_published() -> MutexGuard<'static, BTreeMap<String, (String, String)>> {
+    PUBLISHED
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// `myapp` (or `myapp.zync.local`) as the full name to publish.
+pub fn published_name(hostname: &str) -> Result<String, String> {
+    let hostname = hostname.trim().trim_end_matches('.').to_ascii_lowercase();
+    let label = hostname
+        .strip_suffix(SUFFIX)
+        .map(|label| label.trim_end_matches('.'))
+        .unwrap_or(&hostname);
+    let valid = !label.is_empty()
+        && label.split('.').all(|part| {
+            !part.is_empty()
+                && part.len() <= 63
+                && !part.starts_with('-')
+                && !part.ends_with('-')
+                && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+    if !valid {
+        return Err(format!(
+            "`{label}` is not a valid name; use letters, digits and hyphens, e.g. myapp"
+        ));
+    }
+    Ok(format!("{label}.{SUFFIX}"))
+}
+
+/// The checked name a local forward asks to be published under, if any.
+pub fn tunnel_hostname(tunnel: &SavedTunnel) -> Option<Result<String, String>> {
+    let hostname = tunnel.web.as_ref()?.hostname.as_deref()?;
+    (tunnel.tunnel_type == "local" && !hostname.trim().is_empty()).then(|| published_name(hostname))
+}
+
+/// The address a name should resolve to for a listener on `bind`.
+pub fn hosts_address(bind: Option<&str>) -> String {
+    let bind = bind.map(str::trim).unwrap_or_default();
+    let bind = bind
+        .strip_prefix('[')
+        .and_then(|inner| inner.strip_suffix(']'))
+        .unwrap_or(bind);
+    match bind {
+        "" | "0.0.0.0" | "localhost" => "127.0.0.1".to_string(),
+        "::" => "::1".to_string(),
+        address => address.to_string(),
+    }
+}
+
+/// `existing` with the Zync block replaced by `entries`, or removed when
+/// there are none. Keeps the file's line endings.
+fn render(existing: &str, entries: &[(String, String)]) -> String {
+    let newline = if existing.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut lines = Vec::new();
+    let mut inside = false;
+    for line in existing.lines() {
+        match line.trim() {
+            BEGIN => inside = true,
+            END => inside = false,
+            _ if inside => {}
+            _ => lines.push(line.to_string()),
+        }
+    }
+    while lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
+    }
+    if !entries.is_empty() {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.push(BEGIN.to_string());
+        lines.extend(
+            entries
+                .iter()
+                .map(|(address, name)| format!("{address}\t{name}")),
+        );
+        lines.push(END.to_string());
+    }
+    let mut rendered = lines.join(newline);
+    rendered.push_str(newline);
+    rendered
+}
+
+fn hosts_path() -> PathBuf {
+    if cfg!(windows) {
+        let root = std::env::var_os("SystemRoot").unwrap_or_else(|| r"C:\Windows".into());
+        PathBuf::from(root).join(r"System32\drivers\etc\hosts")
+    } else {
+        PathBuf::from("/etc/hosts")
+    }
+}
+
+/// Copy `source` over the hosts file as root/Administrator.
+async fn elevated_copy(source: &Path, hosts: &Path) -> Result<(), String> {
+    let (source, hosts) = (source.display(), hosts.display());
+    let mut command = if cfg!(target_os = "macos") {
+        let mut command = tokio::process::Command::new("osascript");
+        command.args([
+            "-e",
+            &format!("do shell script \"cp '{source}' '{hosts}'\" with administrator privileges"),
+        ]);
+        command
+    } else if cfg!(windows) {
+        let copy = format!("Copy-Item -LiteralPath ''{source}'' -Destination ''{hosts}'' -Force");
+        let mut command = tokio::process::Command::new("powershell");
+        command.args([
+            "-NoProfile",
+            "-Command",
+            &format!(
+                "$p = Start-Process powershell -Verb RunAs -Wait -PassThru -WindowStyle Hidden \
+                 -ArgumentList '-NoProfile','-Command','{copy}'; exit $p.ExitCode"
+            ),
+        ]);
+        command
+    } else {
+        let mut command = tokio::process::Command::new("pkexec");
+        command
+            .arg("cp")
+            .arg(source.to_string())
+            .arg(hosts.to_string());
+        command
+    };
+    let output = command
+        .output()
+        .await
+        .map_err(|e| format!("Could not ask for permission to edit {hosts}: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Updating {hosts} was cancelled or failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+async fn write_hosts(path: &Path, contents: &str) -> Result<(), String> {
+    match tokio::fs::write(path, contents).await {
+        Ok(()) => return Ok(()),
+        Err(error) if error.kind() == std::io::ErrorKind::PermissionDenied => {}
+        Err(error) => return Err(format!("Failed to write {}: {error}", path.display())),
+    }
+    let staged = std::env::temp_dir().join(format!("zync-hosts-{}", uuid::Uuid::new_v4()));
+    tokio::fs::write(&staged, contents)
+        .await
+        .map_err(|e| format!("Failed to stage hosts file: {e}"))?;
+    let result = elevated_copy(&staged, path).await;
+    let _ = tokio::fs::remove_file(&staged).await;
+    result
+}
+
+/// Bring the hosts file in line with what is published now.
+async fn sync() -> Result<(), String> {
+    let _guard = WRITE_LOCK.lock().await;
+    let entries: Vec<(String, String)> = lock_published().values().cloned().collect();
+    let path = hosts_path();
+    let existing = tokio::fs::read_to_string(&path)
+        .await
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let updated = render(&existing, &entries);
+    if updated == existing {
+        return Ok(());
+    }
+    write_hosts(&path, &updated).await?;
+    println!(
+        "[TUNNEL][HOSTS] Published {} name(s) in {}",
+        entries.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+/// Write once no other change has arrived for [`SETTLE`].
+fn schedule_sync() {
+    let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(SETTLE).await;
+        if GENERATION.load(Ordering::SeqCst) != generation {
+            return;
+        }
+        if let Err(error) = sync().await {
+            eprintln!("[TUNNEL][HOSTS] {error}");
+        }
+    });
+}
+
+/// Point `name` at `address` while `tunnel_id` runs.
+pub(crate) fn publish(tunnel_id: &str, name: String, address: String) {
+    let previous = lock_published().insert(tunnel_id.to_string(), (address.clone(), name.clone()));
+    if previous != Some((address, name)) {
+        schedule_sync();
+    }
+}
+
+/// The name published for `tunnel_id`, if any.
+pub(crate) fn published(tunnel_id: &str) -> Option<String> {
+    lock_published()
+        .get(tunnel_id)
+        .map(|(_, name)| name.clone())
+}
+
+/// Called for every non-active tunnel status; cheap when nothing is published.
+pub(crate) fn unpublish(tunnel_id: &str) {
+    if lock_published().remove(tunnel_id).is_some() {
+        schedule_sync();
+    }
+}
+
+/// Drop every name now (app exit).
+pub(crate) async fn clear() -> Result<(), String> {
+    if std::mem::take(&mut *lock_published()).is_empty() {
+        return Ok(());
+    }
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+    sync().await
+}
+
+/// Remove a block left behind by a previous run.
+pub fn start() {
+    let has_block = std::fs::read_to_string(hosts_path())
+        .is_ok_and(|hosts| hosts.lines().any(|line| line.trim() == BEGIN));
+    if !has_block {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        if let Err(error) = sync().await {
+            eprintln!("[TUNNEL][HOSTS] {error}");
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_names_and_addresses() {
+        assert_eq!(published_name("MyApp").unwrap(), "myapp.zync.local");
+        assert_eq!(published_name("api.myapp").unwrap(), "api.myapp.zync.local");
+        assert_eq!(
+            published_name("myapp.zync.local.").unwrap(),
+            "myapp.zync.local"
+        );
+        assert!(published_name("my app").is_err());
+        assert!(published_name("-x").is_err());
+        assert!(published_name(".zync.local").is_err());
+
+        assert_eq!(hosts_address(None), "127.0.0.1");
+        assert_eq!(hosts_address(Some("0.0.0.0")), "127.0.0.1");
+        assert_eq!(hosts_address(Some("127.0.0.2")), "127.0.0.2");
+        assert_eq!(hosts_address(Some("[::1]")), "::1");
+        assert_eq!(hosts_address(Some("[::]")), "::1");
+    }
+
+    #[test]
+    fn rewrites_only_the_zync_block() {
+        let original = "127.0.0.1\tlocalhost\n::1\tlocalhost\n";
+        let entries = vec![("127.0.0.2".to_string(), "db.zync.local".to_string())];
+        let published = render(original, &entries);
+        assert_eq!(
+            published,
+            "127.0.0.1\tlocalhost\n::1\tlocalhost\n\n# BEGIN Zync tunnels\n\
+             127.0.0.2\tdb.zync.local\n# END Zync tunnels\n"
+        );
+        assert_eq!(render(&published, &entries), published);
+        assert_eq!(render(&published, &[]), original);
+
+        let windows = "# comment\r\n127.0.0.1 localhost\r\n";
+        let published = render(windows, &entries);
+        assert!(published.contains("\r\n# BEGIN Zync tunnels\r\n"));
+        assert_eq!(render(&published, &[]), windows);
+    }
+}
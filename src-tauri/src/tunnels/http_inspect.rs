@@ -0,0 +1,791 @@
+//! HTTP/1.x layer for local forwards: request inspector and head rewriting.
+//!
+//! When a tunnel's `web.inspect` or `web.hostHeader` is set, the accepted client
+//! stream (after TLS termination, if any) is wrapped in [`HttpLayerStream`].
+//! Message framing is tracked so that request and response heads can be logged
+//! (request lines, status codes, sizes, timings) and optionally rewritten by
+//! [`super::http_rewrite`]. Bodies are relayed as-is and never stored.
+//! Upgrades (WebSocket, `CONNECT`) and anything that doesn't parse as HTTP/1.x
+//! turn the layer into a plain pass-through for that connection.
+
+use super::http_rewrite::HostRewrite;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex as StdMutex};
+use std::task::{ready, Context, Poll};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// Entries kept per tunnel; oldest are dropped first.
+const LOG_CAPACITY: usize = 500;
+/// Heads larger than this are not HTTP we want to parse.
+const MAX_HEAD_BYTES: usize = 64 * 1024;
+const MAX_TARGET_CHARS: usize = 2048;
+const READ_CHUNK: usize = 16 * 1024;
+
+static LOGS: LazyLock<StdMutex<HashMap<String, VecDeque<HttpExchange>>>> =
+    LazyLock::new(|| StdMutex::new(HashMap::new()));
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpExchange {
+    pub id: u64,
+    /// Unix milliseconds when the request head arrived.
+    pub started_at: u64,
+    pub method: String,
+    pub target: String,
+    pub host: Option<String>,
+    /// `None` while waiting for the response (or when the connection closed first).
+    pub status: Option<u16>,
+    /// Until the response head arrived.
+    pub ttfb_ms: Option<u64>,
+    /// Until the response was complete.
+    pub duration_ms: Option<u64>,
+    pub request_bytes: u64,
+    pub response_bytes: u64,
+}
+
+fn with_logs<T>(f: impl FnOnce(&mut HashMap<String, VecDeque<HttpExchange>>) -> T) -> T {
+    let mut logs = LOGS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    f(&mut logs)
+}
+
+fn update(runtime_id: &str, id: u64, f: impl FnOnce(&mut HttpExchange)) {
+    with_logs(|logs| {
+        if let Some(entry) = logs
+            .get_mut(runtime_id)
+            .and_then(|log| log.iter_mut().rev().find(|entry| entry.id == id))
+        {
+            f(entry);
+        }
+    });
+}
+
+/// Recorded exchanges for a tunnel runtime, oldest first.
+pub fn exchanges(runtime_id: &str) -> Vec<HttpExchange> {
+    with_logs(|logs| {
+        logs.get(runtime_id)
+            .map(|log| log.iter().cloned().collect())
+            .unwrap_or_default()
+    })
+}
+
+pub fn clear(runtime_id: &str) {
+    with_logs(|logs| {
+        logs.remove(runtime_id);
+    });
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Framing {
+    Length(u64),
+    Chunked,
+    UntilClose,
+}
+
+#[derive(Debug)]
+struct Head {
+    /// The head exactly as received, terminating blank line included.
+    raw: Vec<u8>,
+    /// `GET /path HTTP/1.1` split in three (request) or `HTTP/1.1 200 OK` (response).
+    first: [String; 3],
+    host: Option<String>,
+    content_length: Option<u64>,
+    chunked: bool,
+}
+
+fn parse_head(raw: &[u8]) -> Option<Head> {
+    let text = std::str::from_utf8(raw).ok()?;
+    let mut lines = text.split("\r\n");
+    let mut parts = lines.next()?.splitn(3, ' ');
+    let first = [
+        parts.next()?.to_string(),
+        parts.next()?.to_string(),
+        parts.next().unwrap_or_default().to_string(),
+    ];
+    let mut head = Head {
+        raw: raw.to_vec(),
+        first,
+        host: None,
+        content_length: None,
+        chunked: false,
+    };
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match name.trim().to_ascii_lowercase().as_str() {
+            "host" => head.host = Some(value.to_string()),
+            "content-length" => head.content_length = value.parse().ok(),
+            "transfer-encoding" => {
+                head.chunked = value.to_ascii_lowercase().contains("chunked");
+            }
+            _ => {}
+        }
+    }
+    Some(head)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Head,
+    Body(u64),
+    ChunkSize,
+    ChunkData(u64),
+    /// CRLF after a chunk's data.
+    ChunkEnd(u8),
+    Trailer,
+    UntilClose,
+    /// Not (or no longer) HTTP/1.x — ignore everything.
+    Off,
+}
+
+enum Step {
+    Head(Head),
+    /// Message complete; carries its total size.
+    Done(u64),
+    /// Held bytes that turned out not to be a head; pass them on unchanged.
+    Raw(Vec<u8>),
+}
+
+/// Incremental HTTP/1.x message framer. Only tracks boundaries.
+struct Framer {
+    state: State,
+    buffer: Vec<u8>,
+    message_bytes: u64,
+}
+
+impl Framer {
+    fn new() -> Self {
+        Self {
+            state: State::Head,
+            buffer: Vec::new(),
+            message_bytes: 0,
+        }
+    }
+
+    fn set_framing(&mut self, framing: Framing) -> Option<Step> {
+        self.state = match framing {
+            Framing::Length(0) => return Some(self.finish()),
+            Framing::Length(length) => State::Body(length),
+            Framing::Chunked => State::ChunkSize,
+            Framing::UntilClose => State::UntilClose,
+        };
+        None
+    }
+
+    /// Bytes held for an incomplete head (flushed at end of stream).
+    fn take_held(&mut self) -> Vec<u8> {
+        if self.state == State::Head {
+            std::mem::take(&mut self.buffer)
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn finish(&mut self) -> Step {
+        self.state = State::Head;
+        Step::Done(std::mem::take(&mut self.message_bytes))
+    }
+
+    /// Consume up to one step from `data`; returns bytes consumed and the step, if any.
+    fn advance(&mut self, data: &[u8]) -> (usize, Option<Step>) {
+        match self.state {
+            State::Off | State::UntilClose => {
+                self.message_bytes += data.len() as u64;
+                (data.len(), None)
+            }
+            State::Head | State::ChunkSize | State::Trailer => {
+                let (used, line_done) = match data.iter().position(|&b| b == b'\n') {
+                    Some(index) => (index + 1, true),
+                    None => (data.len(), false),
+                };
+                self.buffer.extend_from_slice(&data[..used]);
+                self.message_bytes += used as u64;
+                if self.buffer.len() > MAX_HEAD_BYTES {
+                    let held = self.state == State::Head;
+                    self.state = State::Off;
+                    let buffer = std::mem::take(&mut self.buffer);
+                    return (used, held.then_some(Step::Raw(buffer)));
+                }
+                if !line_done {
+                    return (used, None);
+                }
+                (used, self.line_complete())
+            }
+            State::Body(remaining) | State::ChunkData(remaining) => {
+                let used = remaining.min(data.len() as u64);
+                self.message_bytes += used;
+                let left = remaining - used;
+                self.state = match (self.state, left) {
+                    (State::Body(_), 0) => return (used as usize, Some(self.finish())),
+                    (State::Body(_), left) => State::Body(left),
+                    (_, 0) => State::ChunkEnd(2),
+                    (_, left) => State::ChunkData(left),
+                };
+                (used as usize, None)
+            }
+            State::ChunkEnd(remaining) => {
+                let used = (remaining as usize).min(data.len());
+                self.message_bytes += used as u64;
+                let left = remaining - used as u8;
+                self.state = if left == 0 {
+                    State::ChunkSize
+                } else {
+                    State::ChunkEnd(left)
+                };
+                (used, None)
+            }
+        }
+    }
+
+    fn line_complete(&mut self) -> Option<Step> {
+        match self.state {
+            State::Head => {
+                // Tolerate stray CRLFs between messages.
+                if self.buffer == b"\r\n" || self.buffer == b"\n" {
+                    self.message_bytes = 0;
+                    return Some(Step::Raw(std::mem::take(&mut self.buffer)));
+                }
+                if !self.buffer.ends_with(b"\r\n\r\n") {
+                    return None;
+                }
+                let buffer = std::mem::take(&mut self.buffer);
+                match parse_head(&buffer) {
+                    Some(head) => Some(Step::Head(head)),
+                    None => {
+                        self.state = State::Off;
+                        Some(Step::Raw(buffer))
+                    }
+                }
+            }
+            State::ChunkSize => {
+                let line = String::from_utf8_lossy(&self.buffer).to_string();
+                self.buffer.clear();
+                let size = line.trim().split(';').next().unwrap_or_default();
+                match u64::from_str_radix(size.trim(), 16) {
+                    Ok(0) => self.state = State::Trailer,
+                    Ok(size) => self.state = State::ChunkData(size),
+                    Err(_) => self.state = State::Off,
+                }
+                None
+            }
+            State::Trailer => {
+                let blank = self.buffer == b"\r\n" || self.buffer == b"\n";
+                self.buffer.clear();
+                blank.then(|| self.finish())
+            }
+            _ => None,
+        }
+    }
+}
+
+struct Pending {
+    id: u64,
+    method: String,
+    started: Instant,
+}
+
+/// What the layer does besides tracking framing.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct HttpLayerOptions {
+    /// Record exchanges in the per-tunnel inspector log.
+    pub log: bool,
+    pub rewrite: Option<HostRewrite>,
+}
+
+/// Pairs requests read from the client with responses written back to it.
+struct Tap {
+    runtime_id: String,
+    options: HttpLayerOptions,
+    requests: Framer,
+    responses: Framer,
+    pending: VecDeque<Pending>,
+    /// The request currently being read (for its size).
+    reading: Option<u64>,
+    /// The pending exchange whose response is being written, with its status.
+    answering: Option<(Pending, u16)>,
+}
+
+impl Tap {
+    fn new(runtime_id: String, options: HttpLayerOptions) -> Self {
+        Self {
+            runtime_id,
+            options,
+            requests: Framer::new(),
+            responses: Framer::new(),
+            pending: VecDeque::new(),
+            reading: None,
+            answering: None,
+        }
+    }
+
+    fn stop(&mut self) {
+        self.requests.state = State::Off;
+        self.responses.state = State::Off;
+    }
+
+    fn update(&self, id: u64, f: impl FnOnce(&mut HttpExchange)) {
+        if self.options.log {
+            update(&self.runtime_id, id, f);
+        }
+    }
+
+    /// Feed bytes read from the client; what should go upstream lands in `out`.
+    fn on_request_bytes(&mut self, mut data: &[u8], out: &mut Vec<u8>) {
+        while !data.is_empty() {
+            if self.requests.state == State::Off {
+                out.extend_from_slice(data);
+                return;
+            }
+            let holding = self.requests.state == State::Head;
+            let (used, step) = self.requests.advance(data);
+            if !holding {
+                out.extend_from_slice(&data[..used]);
+            }
+            data = &data[used..];
+            let mut step = step;
+            while let Some(current) = step.take() {
+                step = self.on_request_step(current, out);
+            }
+        }
+    }
+
+    fn on_request_step(&mut self, step: Step, out: &mut Vec<u8>) -> Option<Step> {
+        match step {
+            Step::Head(head) => {
+                match &self.options.rewrite {
+                    Some(rewrite) => out.extend_from_slice(&rewrite.request_head(&head.raw)),
+                    None => out.extend_from_slice(&head.raw),
+                }
+                let [method, target, version] = head.first;
+                if !version.starts_with("HTTP/1.") {
+                    self.stop();
+                    return None;
+                }
+                let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+                let started_at = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|elapsed| elapsed.as_millis() as u64)
+                    .unwrap_or_default();
+                let entry = HttpExchange {
+                    id,
+                    started_at,
+                    method: method.clone(),
+                    target: target.chars().take(MAX_TARGET_CHARS).collect(),
+                    host: head.host,
+                    status: None,
+                    ttfb_ms: None,
+                    duration_ms: None,
+                    request_bytes: 0,
+                    response_bytes: 0,
+                };
+                if self.options.log {
+                    with_logs(|logs| {
+                        let log = logs.entry(self.runtime_id.clone()).or_default();
+                        if log.len() >= LOG_CAPACITY {
+                            log.pop_front();
+                        }
+                        log.push_back(entry);
+                    });
+                }
+                let is_connect = method.eq_ignore_ascii_case("CONNECT");
+                self.pending.push_back(Pending {
+                    id,
+                    method,
+                    started: Instant::now(),
+                });
+                self.reading = Some(id);
+                if is_connect {
+                    // Whatever follows is tunnelled, not HTTP.
+                    self.requests.state = State::Off;
+                    return None;
+                }
+                let framing = if head.chunked {
+                    Framing::Chunked
+                } else {
+                    Framing::Length(head.content_length.unwrap_or(0))
+                };
+                self.requests.set_framing(framing)
+            }
+            Step::Done(size) => {
+                if let Some(id) = self.reading.take() {
+                    self.update(id, |entry| entry.request_bytes = size);
+                }
+                None
+            }
+            Step::Raw(bytes) => {
+                out.extend_from_slice(&bytes);
+                None
+            }
+        }
+    }
+
+    /// Feed bytes the backend sent; what should reach the client lands in `out`.
+    fn on_response_bytes(&mut self, mut data: &[u8], out: &mut Vec<u8>) {
+        while !data.is_empty() {
+            if self.responses.state == State::Off {
+                out.extend_from_slice(data);
+                return;
+            }
+            let holding = self.responses.state == State::Head;
+            let (used, step) = self.responses.advance(data);
+            if !holding {
+                out.extend_from_slice(&data[..used]);
+            }
+            data = &data[used..];
+            let mut step = step;
+            while let Some(current) = step.take() {
+                step = self.on_response_step(current, out);
+            }
+        }
+    }
+
+    fn on_response_step(&mut self, step: Step, out: &mut Vec<u8>) -> Option<Step> {
+        match step {
+            Step::Head(head) => {
+                match &self.options.rewrite {
+                    Some(rewrite) => out.extend_from_slice(&rewrite.response_head(&head.raw)),
+                    None => out.extend_from_slice(&head.raw),
+                }
+                let [version, status, _] = head.first;
+                let status = match status.parse::<u16>() {
+                    Ok(status) if version.starts_with("HTTP/1.") => status,
+                    _ => {
+                        self.stop();
+                        return None;
+                    }
+                };
+                // Interim responses (100 Continue) don't answer the request.
+                if (100..200).contains(&status) && status != 101 {
+                    return self.responses.set_framing(Framing::Length(0));
+                }
+                let Some(pending) = self.pending.pop_front() else {
+                    // A response nobody asked for: we lost track of the framing.
+                    self.stop();
+                    return None;
+                };
+                let ttfb = pending.started.elapsed().as_millis() as u64;
+                self.update(pending.id, |entry| {
+                    entry.status = Some(status);
+                    entry.ttfb_ms = Some(ttfb);
+                });
+                let upgraded = status == 101
+                    || (pending.method.eq_ignore_ascii_case("CONNECT")
+                        && (200..300).contains(&status));
+                let bodiless = pending.method.eq_ignore_ascii_case("HEAD")
+                    || status == 204
+                    || status == 304
+                    || upgraded;
+                self.answering = Some((pending, status));
+                let step = self.responses.set_framing(if bodiless {
+                    Framing::Length(0)
+                } else if head.chunked {
+                    Framing::Chunked
+                } else if let Some(length) = head.content_length {
+                    Framing::Length(length)
+                } else {
+                    Framing::UntilClose
+                });
+                if upgraded {
+                    // Complete the exchange, then stop parsing the raw stream.
+                    let done = step.and_then(|step| self.on_response_step(step, out));
+                    self.stop();
+                    return done;
+                }
+                step
+            }
+            Step::Done(size) => {
+                if let Some((pending, _)) = self.answering.take() {
+                    let duration = pending.started.elapsed().as_millis() as u64;
+                    self.update(pending.id, |entry| {
+                        entry.duration_ms = Some(duration);
+                        entry.response_bytes = size;
+                    });
+                }
+                None
+            }
+            Step::Raw(bytes) => {
+                out.extend_from_slice(&bytes);
+                None
+            }
+        }
+    }
+}
+
+impl Drop for Tap {
+    fn drop(&mut self) {
+        // A close-delimited response ends with the connection.
+        if self.responses.state == State::UntilClose {
+            let size = self.responses.message_bytes;
+            self.on_response_step(Step::Done(size), &mut Vec::new());
+        }
+    }
+}
+
+/// Client-side stream wrapper: reads are requests, writes are responses.
+pub(crate) struct HttpLayerStream<S> {
+    inner: S,
+    tap: Tap,
+    /// Request bytes ready for the reader.
+    read_out: Vec<u8>,
+    read_pos: usize,
+    /// Response bytes not yet written to `inner`.
+    write_out: Vec<u8>,
+    write_pos: usize,
+}
+
+impl<S> HttpLayerStream<S> {
+    pub(crate) fn new(inner: S, runtime_id: String, options: HttpLayerOptions) -> Self {
+        Self {
+            inner,
+            tap: Tap::new(runtime_id, options),
+            read_out: Vec::new(),
+            read_pos: 0,
+            write_out: Vec::new(),
+            write_pos: 0,
+        }
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for HttpLayerStream<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        loop {
+            if this.read_pos < this.read_out.len() {
+                let available = &this.read_out[this.read_pos..];
+                let n = available.len().min(buf.remaining());
+                buf.put_slice(&available[..n]);
+                this.read_pos += n;
+                if this.read_pos == this.read_out.len() {
+                    this.read_out.clear();
+                    this.read_pos = 0;
+                }
+                return Poll::Ready(Ok(()));
+            }
+            let mut chunk = [0u8; READ_CHUNK];
+            let mut chunk_buf = ReadBuf::new(&mut chunk);
+            ready!(Pin::new(&mut this.inner).poll_read(cx, &mut chunk_buf))?;
+            let filled = chunk_buf.filled();
+            if filled.is_empty() {
+                // End of stream: release an incomplete head instead of dropping it.
+                this.read_out = this.tap.requests.take_held();
+                if this.read_out.is_empty() {
+                    return Poll::Ready(Ok(()));
+                }
+                continue;
+            }
+            this.tap.on_request_bytes(filled, &mut this.read_out);
+        }
+    }
+}
+
+impl<S: AsyncWrite + Unpin> HttpLayerStream<S> {
+    fn drain(&mut self, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        while self.write_pos < self.write_out.len() {
+            let written = ready!(
+                Pin::new(&mut self.inner).poll_write(cx, &self.write_out[self.write_pos..])
+            )?;
+            if written == 0 {
+                return Poll::Ready(Err(std::io::ErrorKind::WriteZero.into()));
+            }
+            self.write_pos += written;
+        }
+        self.write_out.clear();
+        self.write_pos = 0;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for HttpLayerStream<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        data: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+        ready!(this.drain(cx))?;
+        this.tap.on_response_bytes(data, &mut this.write_out);
+        // Anything left over goes out on the next write or flush.
+        if let Poll::Ready(Err(error)) = this.drain(cx) {
+            return Poll::Ready(Err(error));
+        }
+        Poll::Ready(Ok(data.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.drain(cx))?;
+        Pin::new(&mut this.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        let held = this.tap.responses.take_held();
+        this.write_out.extend_from_slice(&held);
+        ready!(this.drain(cx))?;
+        Pin::new(&mut this.inner).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feed `exchange` byte-by-byte (to exercise every split point) and return
+    /// the log plus what was relayed in each direction.
+    fn relay(
+        runtime_id: &str,
+        rewrite: Option<HostRewrite>,
+        exchange: &[(&str, bool)],
+    ) -> (Vec<HttpExchange>, String, String) {
+        clear(runtime_id);
+        let options = HttpLayerOptions { log: true, rewrite };
+        let mut tap = Tap::new(runtime_id.to_string(), options);
+        let (mut upstream, mut downstream) = (Vec::new(), Vec::new());
+        for (text, is_request) in exchange {
+            for byte in text.as_bytes() {
+                if *is_request {
+                    tap.on_request_bytes(std::slice::from_ref(byte), &mut upstream);
+                } else {
+                    tap.on_response_bytes(std::slice::from_ref(byte), &mut downstream);
+                }
+            }
+        }
+        drop(tap);
+        (
+            exchanges(runtime_id),
+            String::from_utf8(upstream).unwrap(),
+            String::from_utf8(downstream).unwrap(),
+        )
+    }
+
+    fn run(runtime_id: &str, exchange: &[(&str, bool)]) -> Vec<HttpExchange> {
+        let (log, upstream, downstream) = relay(runtime_id, None, exchange);
+        let sent = |requests: bool| -> String {
+            exchange
+                .iter()
+                .filter(|(_, is_request)| *is_request == requests)
+                .map(|(text, _)| *text)
+                .collect()
+        };
+        // Without rewriting the layer is byte-for-byte transparent.
+        assert_eq!(upstream, sent(true));
+        assert_eq!(downstream, sent(false));
+        log
+    }
+
+    #[test]
+    fn pairs_pipelined_requests_with_chunked_and_sized_responses() {
+        let log = run(
+            "inspect-test-pipelined",
+            &[
+                (
+                    "GET /a HTTP/1.1\r\nHost: app\r\n\r\nPOST /b HTTP/1.1\r\nContent-Length: 3\r\n\r\nabc",
+                    true,
+                ),
+                (
+                    "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\nwiki\r\n0\r\n\r\n",
+                    false,
+                ),
+                ("HTTP/1.1 100 Continue\r\n\r\n", false),
+                ("HTTP/1.1 201 Created\r\nContent-Length: 2\r\n\r\nok", false),
+            ],
+        );
+        let summary: Vec<(&str, &str, Option<u16>, bool)> = log
+            .iter()
+            .map(|entry| {
+                (
+                    entry.method.as_str(),
+                    entry.target.as_str(),
+                    entry.status,
+                    entry.duration_ms.is_some(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("GET", "/a", Some(200), true),
+                ("POST", "/b", Some(201), true),
+            ]
+        );
+        assert_eq!(log[0].host.as_deref(), Some("app"));
+        assert_eq!(log[1].request_bytes, 42);
+    }
+
+    #[test]
+    fn head_upgrades_and_close_delimited_bodies() {
+        let log = run(
+            "inspect-test-misc",
+            &[
+                ("HEAD / HTTP/1.1\r\n\r\n", true),
+                ("HTTP/1.1 200 OK\r\nContent-Length: 99\r\n\r\n", false),
+                ("GET /ws HTTP/1.1\r\nUpgrade: websocket\r\n\r\n", true),
+                (
+                    "HTTP/1.1 101 Switching Protocols\r\n\r\n\u{1}\u{5}hello",
+                    false,
+                ),
+            ],
+        );
+        assert_eq!(log.len(), 2);
+        assert_eq!(log[0].status, Some(200));
+        assert!(log[0].duration_ms.is_some());
+        assert_eq!(log[1].status, Some(101));
+
+        let log = run(
+            "inspect-test-close",
+            &[
+                ("GET /old HTTP/1.0\r\n\r\n", true),
+                ("HTTP/1.0 200 OK\r\n\r\nbody until close", false),
+            ],
+        );
+        assert!(log[0].duration_ms.is_some());
+        assert_eq!(log[0].response_bytes, 35);
+    }
+
+    #[test]
+    fn non_http_traffic_is_ignored() {
+        let log = run(
+            "inspect-test-binary",
+            &[("\u{16}\u{3}\u{1}\u{2}\u{0}\u{1}\u{3}\u{3}\r\n\r\n", true)],
+        );
+        assert!(log.is_empty());
+    }
+
+    #[test]
+    fn rewrites_heads_but_not_bodies() {
+        let rewrite = HostRewrite::new("app.internal", false, 18080, false);
+        let (log, upstream, downstream) = relay(
+            "inspect-test-rewrite",
+            Some(rewrite),
+            &[
+                (
+                    "POST /a HTTP/1.1\r\nHost: localhost:18080\r\nContent-Length: 13\r\n\r\nHost: ignored",
+                    true,
+                ),
+                (
+                    "HTTP/1.1 303 See Other\r\nLocation: http://app.internal/b\r\nContent-Length: 0\r\n\r\n",
+                    false,
+                ),
+            ],
+        );
+        assert_eq!(
+            upstream,
+            "POST /a HTTP/1.1\r\nHost: app.internal\r\nContent-Length: 13\r\n\r\nHost: ignored"
+        );
+        assert_eq!(
+            downstream,
+            "HTTP/1.1 303 See Other\r\nLocation: http://localhost:18080/b\r\nContent-Length: 0\r\n\r\n"
+        );
+        assert_eq!(log[0].host.as_deref(), Some("localhost:18080"));
+    }
+}
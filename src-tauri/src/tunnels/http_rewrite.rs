@@ -0,0 +1,189 @@
+//! Host-header rewriting for local web forwards.
+//!
+//! Apps behind a forward often only answer to their real name (virtual hosts,
+//! `ALLOWED_HOSTS`, CSRF origin checks) and build absolute URLs from the Host
+//! they were asked for. With `web.hostHeader` set, request heads get that Host
+//! (and matching `Origin`/`Referer`), while response heads get `Location` and
+//! cookie domains pointed back at the local end. Only heads are touched; the
+//! framing comes from [`super::http_inspect`].
+
+/// Rewrites between the browser-facing origin and the backend's expected name.
+#[derive(Debug, Clone)]
+pub(crate) struct HostRewrite {
+    /// Host header value sent upstream, e.g. `app.internal` or `app.internal:8443`.
+    pub host: String,
+    /// What the browser uses, e.g. `https://localhost:18080`.
+    pub local_origin: String,
+    /// What the backend believes it is, e.g. `https://app.internal`.
+    pub remote_origin: String,
+}
+
+impl HostRewrite {
+    pub(crate) fn new(
+        host: &str,
+        local_https: bool,
+        local_port: u16,
+        upstream_https: bool,
+    ) -> Self {
+        let host = host.trim().to_string();
+        Self {
+            local_origin: format!(
+                "{}://localhost:{local_port}",
+                if local_https { "https" } else { "http" }
+            ),
+            remote_origin: format!("{}://{host}", if upstream_https { "https" } else { "http" }),
+            host,
+        }
+    }
+
+    /// Hostname without port, for SNI and cookie domains.
+    pub(crate) fn hostname(&self) -> &str {
+        hostname(&self.host)
+    }
+
+    pub(crate) fn request_head(&self, raw: &[u8]) -> Vec<u8> {
+        rewrite_headers(raw, |name, value| match name {
+            "host" => Some(self.host.clone()),
+            "origin" if value.eq_ignore_ascii_case(&self.local_origin) => {
+                Some(self.remote_origin.clone())
+            }
+            "referer" => strip_origin(value, &self.local_origin)
+                .map(|rest| format!("{}{rest}", self.remote_origin)),
+            _ => None,
+        })
+    }
+
+    pub(crate) fn response_head(&self, raw: &[u8]) -> Vec<u8> {
+        rewrite_headers(raw, |name, value| match name {
+            "location" => {
+                let url = url::Url::parse(value).ok()?;
+                let target = url.host_str()?;
+                if !target.eq_ignore_ascii_case(self.hostname()) {
+                    return None;
+                }
+                let mut rest = url.path().to_string();
+                if let Some(query) = url.query() {
+                    rest.push('?');
+                    rest.push_str(query);
+                }
+                if let Some(fragment) = url.fragment() {
+                    rest.push('#');
+                    rest.push_str(fragment);
+                }
+                Some(format!("{}{rest}", self.local_origin))
+            }
+            "set-cookie" => self.strip_cookie_domain(value),
+            _ => None,
+        })
+    }
+
+    /// Drop `Domain=<backend>` so the browser keeps the cookie for `localhost`.
+    fn strip_cookie_domain(&self, value: &str) -> Option<String> {
+        let hostname = self.hostname();
+        let mut changed = false;
+        let kept: Vec<&str> = value
+            .split(';')
+            .filter(|attribute| {
+                let matches = attribute
+                    .trim()
+                    .split_once('=')
+                    .filter(|(key, _)| key.trim().eq_ignore_ascii_case("domain"))
+                    .is_some_and(|(_, domain)| {
+                        let domain = domain.trim().trim_start_matches('.');
+                        hostname.eq_ignore_ascii_case(domain)
+                            || hostname
+                                .to_ascii_lowercase()
+                                .ends_with(&format!(".{}", domain.to_ascii_lowercase()))
+                    });
+                changed |= matches;
+                !matches
+            })
+            .collect();
+        changed.then(|| kept.join(";"))
+    }
+}
+
+fn hostname(host: &str) -> &str {
+    if let Some(rest) = host.strip_prefix('[') {
+        return rest.split(']').next().unwrap_or(rest);
+    }
+    match host.rsplit_once(':') {
+        Some((name, port)) if port.parse::<u16>().is_ok() => name,
+        _ => host,
+    }
+}
+
+/// `origin` followed by a path boundary → the rest (possibly empty).
+fn strip_origin<'a>(value: &'a str, origin: &str) -> Option<&'a str> {
+    let head = value.get(..origin.len())?;
+    let rest = &value[origin.len()..];
+    (head.eq_ignore_ascii_case(origin) && (rest.is_empty() || rest.starts_with(['/', '?', '#'])))
+        .then_some(rest)
+}
+
+/// Apply `edit(lowercase name, value)` to each header line of a raw head.
+/// Lines that aren't UTF-8 or that `edit` declines are kept byte-for-byte.
+fn rewrite_headers(raw: &[u8], edit: impl Fn(&str, &str) -> Option<String>) -> Vec<u8> {
+    let mut out = Vec::with_capacity(raw.len() + 32);
+    let mut lines = raw.split(|&b| b == b'\n').peekable();
+    let mut first = true;
+    while let Some(line) = lines.next() {
+        let is_last = lines.peek().is_none();
+        let replaced = (!first)
+            .then(|| std::str::from_utf8(line).ok())
+            .flatten()
+            .and_then(|text| text.strip_suffix('\r'))
+            .and_then(|text| text.split_once(':'))
+            .and_then(|(name, value)| {
+                let value = edit(&name.trim().to_ascii_lowercase(), value.trim())?;
+                Some(format!("{}: {value}\r", name.trim()))
+            });
+        match replaced {
+            Some(text) => out.extend_from_slice(text.as_bytes()),
+            None => out.extend_from_slice(line),
+        }
+        if !is_last {
+            out.push(b'\n');
+        }
+        first = false;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rewrite() -> HostRewrite {
+        HostRewrite::new("app.internal", true, 18080, true)
+    }
+
+    #[test]
+    fn request_heads_get_the_backend_name() {
+        let head = b"POST /login HTTP/1.1\r\nhost: localhost:18080\r\nOrigin: https://localhost:18080\r\nReferer: https://localhost:18080/login?next=%2F\r\nX-Other: keep\r\n\r\n";
+        let rewritten = String::from_utf8(rewrite().request_head(head)).unwrap();
+        assert_eq!(
+            rewritten,
+            "POST /login HTTP/1.1\r\nhost: app.internal\r\nOrigin: https://app.internal\r\nReferer: https://app.internal/login?next=%2F\r\nX-Other: keep\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn response_heads_point_back_at_the_tunnel() {
+        let head = b"HTTP/1.1 302 Found\r\nLocation: https://APP.internal/dashboard?tab=1\r\nSet-Cookie: sid=abc; Domain=.internal; Path=/; Secure\r\nSet-Cookie: theme=dark; Path=/\r\n\r\n";
+        let rewritten = String::from_utf8(rewrite().response_head(head)).unwrap();
+        assert_eq!(
+            rewritten,
+            "HTTP/1.1 302 Found\r\nLocation: https://localhost:18080/dashboard?tab=1\r\nSet-Cookie: sid=abc; Path=/; Secure\r\nSet-Cookie: theme=dark; Path=/\r\n\r\n"
+        );
+        let elsewhere = b"HTTP/1.1 302 Found\r\nLocation: https://sso.example.com/auth\r\n\r\n";
+        assert_eq!(rewrite().response_head(elsewhere), elsewhere.to_vec());
+    }
+
+    #[test]
+    fn hostname_strips_ports() {
+        assert_eq!(hostname("app.internal:8443"), "app.internal");
+        assert_eq!(hostname("[::1]:8080"), "::1");
+        assert_eq!(hostname("app.internal"), "app.internal");
+    }
+}
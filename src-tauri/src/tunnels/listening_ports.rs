@@ -0,0 +1,194 @@
+//! Remote listening-port discovery for the tunnel dialog's "forward this" suggestions.
+//!
+//! Runs `ss -tlnp` where available and falls back to `netstat` (GNU, BSD or
+//! BusyBox output). Process names only appear for sockets the login user may
+//! inspect, and never on BusyBox builds without `netstat -p`.
+
+use serde::Serialize;
+
+/// Prefer `ss`; GNU `netstat -tlnp`; BSD/macOS `netstat -an -p tcp`; BusyBox
+/// `netstat -tln` when the applet was built without `-p`.
+pub(crate) const LISTENING_PORTS_SCRIPT: &str = "if command -v ss >/dev/null 2>&1; then ss -tlnp 2>/dev/null; \
+elif command -v netstat >/dev/null 2>&1; then netstat -tlnp 2>/dev/null || netstat -an -p tcp 2>/dev/null || netstat -tln 2>/dev/null; fi";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListeningPort {
+    pub port: u16,
+    /// Bound address as reported (`0.0.0.0`, `::`, `127.0.0.1`, `*`, ...).
+    pub address: String,
+    pub process: Option<String>,
+    pub pid: Option<u32>,
+    /// Only reachable from the server itself — the typical local-forward target.
+    pub loopback: bool,
+    /// Catalog name for the port (see [`super::service_names`]), filled in by the command.
+    pub service: Option<String>,
+}
+
+/// Split `addr:port` (Linux) or `addr.port` (BSD) into a normalized address and port.
+fn split_address(local: &str) -> Option<(String, u16)> {
+    let (address, port) = local
+        .rsplit_once(':')
+        .filter(|(_, port)| port.parse::<u16>().is_ok())
+        .or_else(|| local.rsplit_once('.'))?;
+    let port = port.parse().ok()?;
+    let address = address.trim_start_matches('[').trim_end_matches(']');
+    // `127.0.0.53%lo` — the interface suffix is noise here.
+    let address = address.split('%').next().unwrap_or(address);
+    let address = if address.is_empty() { "*" } else { address };
+    Some((address.to_string(), port))
+}
+
+fn is_loopback(address: &str) -> bool {
+    address.starts_with("127.") || address == "::1" || address == "localhost"
+}
+
+/// `users:(("sshd",pid=812,fd=3),...)` → (`sshd`, 812).
+fn parse_ss_process(field: &str) -> (Option<String>, Option<u32>) {
+    let process = field
+        .split_once("((\"")
+        .and_then(|(_, rest)| rest.split_once('"'))
+        .map(|(name, _)| name.to_string());
+    let pid = field
+        .split_once("pid=")
+        .and_then(|(_, rest)| rest.split(|c: char| !c.is_ascii_digit()).next())
+        .and_then(|pid| pid.parse().ok());
+    (process, pid)
+}
+
+/// `812/sshd` → (`sshd`, 812); `-` → nothing.
+fn parse_netstat_process(field: &str) -> (Option<String>, Option<u32>) {
+    match field.split_once('/') {
+        Some((pid, name)) => (Some(name.trim().to_string()), pid.parse().ok()),
+        None => (None, None),
+    }
+}
+
+/// Parse `ss -tlnp` or `netstat` output; unknown lines are skipped.
+/// Sorted by port, one entry per (address, port).
+pub fn parse_listening_ports(output: &str) -> Vec<ListeningPort> {
+    let mut ports: Vec<ListeningPort> = Vec::new();
+    for line in output.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let parsed = match fields.as_slice() {
+            ["LISTEN", _, _, local, _, rest @ ..] => split_address(local).map(|(address, port)| {
+                let (process, pid) = parse_ss_process(&rest.join(" "));
+                (address, port, process, pid)
+            }),
+            [proto, _, _, local, _, "LISTEN", rest @ ..] if proto.starts_with("tcp") => {
+                split_address(local).map(|(address, port)| {
+                    let (process, pid) = rest
+                        .first()
+                        .map(|field| parse_netstat_process(field))
+                        .unwrap_or((None, None));
+                    (address, port, process, pid)
+                })
+            }
+            _ => None,
+        };
+        let Some((address, port, process, pid)) = parsed else {
+            continue;
+        };
+        if ports
+            .iter()
+            .any(|entry| entry.port == port && entry.address == address)
+        {
+            continue;
+        }
+        ports.push(ListeningPort {
+            port,
+            loopback: is_loopback(&address),
+            address,
+            process,
+            pid,
+            service: None,
+        });
+    }
+    ports.sort_by(|a, b| a.port.cmp(&b.port).then_with(|| a.address.cmp(&b.address)));
+    ports
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ss_output() {
+        let output = "\
+State  Recv-Q Send-Q Local Address:Port  Peer Address:Port Process
+LISTEN 0      128          0.0.0.0:22         0.0.0.0:*     users:((\"sshd\",pid=812,fd=3))
+LISTEN 0      511        127.0.0.1:6379       0.0.0.0:*     users:((\"redis-server\",pid=901,fd=6))
+LISTEN 0      4096   127.0.0.53%lo:53         0.0.0.0:*
+LISTEN 0      128             [::]:22            [::]:*     users:((\"sshd\",pid=812,fd=4))
+LISTEN 0      244            [::1]:5432          [::]:*
+";
+        let ports = parse_listening_ports(output);
+        let summary: Vec<(u16, &str, Option<&str>, bool)> = ports
+            .iter()
+            .map(|entry| {
+                (
+                    entry.port,
+                    entry.address.as_str(),
+                    entry.process.as_deref(),
+                    entry.loopback,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (22, "0.0.0.0", Some("sshd"), false),
+                (22, "::", Some("sshd"), false),
+                (53, "127.0.0.53", None, true),
+                (5432, "::1", None, true),
+                (6379, "127.0.0.1", Some("redis-server"), true),
+            ]
+        );
+        assert_eq!(ports[0].pid, Some(812));
+    }
+
+    #[test]
+    fn parses_gnu_and_bsd_netstat_output() {
+        let gnu = "\
+Proto Recv-Q Send-Q Local Address           Foreign Address         State       PID/Program name
+tcp        0      0 0.0.0.0:22              0.0.0.0:*               LISTEN      812/sshd
+tcp6       0      0 :::8080                 :::*                    LISTEN      -
+";
+        let ports = parse_listening_ports(gnu);
+        assert_eq!(ports.len(), 2);
+        assert_eq!(
+            (ports[0].process.as_deref(), ports[0].pid),
+            (Some("sshd"), Some(812))
+        );
+        assert_eq!((ports[1].address.as_str(), ports[1].port), ("::", 8080));
+
+        let bsd = "\
+Proto Recv-Q Send-Q  Local Address          Foreign Address        (state)
+tcp4       0      0  127.0.0.1.5432         *.*                    LISTEN
+tcp46      0      0  *.3000                 *.*                    LISTEN
+tcp4       0      0  10.0.0.2.51234         10.0.0.9.443           ESTABLISHED
+";
+        let ports = parse_listening_ports(bsd);
+        let summary: Vec<(u16, &str, bool)> = ports
+            .iter()
+            .map(|entry| (entry.port, entry.address.as_str(), entry.loopback))
+            .collect();
+        assert_eq!(summary, vec![(3000, "*", false), (5432, "127.0.0.1", true)]);
+    }
+
+    #[test]
+    fn parses_busybox_netstat_without_programs() {
+        let busybox = "\
+Active Internet connections (only servers)
+Proto Recv-Q Send-Q Local Address           Foreign Address         State
+tcp        0      0 0.0.0.0:80              0.0.0.0:*               LISTEN
+tcp        0      0 127.0.0.1:53            0.0.0.0:*               LISTEN
+";
+        let ports = parse_listening_ports(busybox);
+        let summary: Vec<(u16, Option<&str>, bool)> = ports
+            .iter()
+            .map(|entry| (entry.port, entry.process.as_deref(), entry.loopback))
+            .collect();
+        assert_eq!(summary, vec![(53, None, true), (80, None, false)]);
+    }
+}
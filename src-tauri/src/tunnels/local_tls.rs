@@ -0,0 +1,287 @@
+//! Local HTTPS termination for local forwards.
+//!
+//! A per-install CA (mkcert-style) lives in `<data dir>/local-ca/`. Tunnels with
+//! `web.terminateTls` wrap their listener in TLS using a leaf for `localhost`,
+//! `127.0.0.1` and `::1` signed by that CA, so a plain-HTTP backend is reachable
+//! at `https://localhost:<port>` (OAuth redirect URIs often insist on HTTPS).
+//! Browsers only accept the leaf once the CA certificate is in the OS or browser
+//! trust store; [`ca_info`] points the user at the file to import.
+//!
+//! [`upstream`] is the other direction: a TLS client towards an HTTPS backend,
+//! run over the SSH channel so the SNI can name the service rather than
+//! whatever the browser asked for.
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use rcgen::{
+    BasicConstraints, Certificate, CertificateParams, DistinguishedName, DnType,
+    ExtendedKeyUsagePurpose, IsCa, KeyPair, KeyUsagePurpose,
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex as StdMutex};
+use tokio_rustls::rustls;
+use tokio_rustls::rustls::client::danger::{
+    HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier,
+};
+use tokio_rustls::rustls::crypto::CryptoProvider;
+use tokio_rustls::rustls::pki_types::{
+    CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer, ServerName, UnixTime,
+};
+use tokio_rustls::rustls::{DigitallySignedStruct, SignatureScheme};
+use tokio_rustls::{TlsAcceptor, TlsConnector};
+
+const CA_DIR: &str = "local-ca";
+const CA_CERT_FILE: &str = "rootCA.pem";
+const CA_KEY_FILE: &str = "rootCA-key.pem";
+const CA_COMMON_NAME: &str = "Zync Local CA";
+/// Apple platforms reject server certificates valid for more than 825 days.
+const LEAF_VALIDITY_DAYS: i64 = 820;
+
+/// Serializes CA creation so two tunnels starting together don't race.
+static CA_LOCK: LazyLock<StdMutex<()>> = LazyLock::new(|| StdMutex::new(()));
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalCaInfo {
+    /// PEM file to import into the OS/browser trust store.
+    pub cert_path: String,
+    /// SHA-256 of the CA certificate, colon-separated hex.
+    pub fingerprint: String,
+}
+
+fn ca_paths(data_dir: &Path) -> (PathBuf, PathBuf) {
+    let dir = data_dir.join(CA_DIR);
+    (dir.join(CA_CERT_FILE), dir.join(CA_KEY_FILE))
+}
+
+fn write_private_key(path: &Path, pem: &[u8]) -> Result<(), String> {
+    use std::io::Write;
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(path)
+        .map_err(|e| format!("Failed to create {}: {e}", path.display()))?;
+    file.write_all(pem).map_err(|e| e.to_string())
+}
+
+fn new_ca(key: &KeyPair) -> Result<Certificate, String> {
+    let mut params = CertificateParams::new(Vec::<String>::new()).map_err(|e| e.to_string())?;
+    let mut name = DistinguishedName::new();
+    name.push(DnType::CommonName, CA_COMMON_NAME);
+    name.push(DnType::OrganizationName, "Zync");
+    params.distinguished_name = name;
+    params.is_ca = IsCa::Ca(BasicConstraints::Constrained(0));
+    params.key_usages = vec![
+        KeyUsagePurpose::KeyCertSign,
+        KeyUsagePurpose::CrlSign,
+        KeyUsagePurpose::DigitalSignature,
+    ];
+    params.self_signed(key).map_err(|e| e.to_string())
+}
+
+/// Load the CA from disk, creating it on first use. Returns the PEM as stored
+/// plus an issuer (same subject and key) usable for signing leaves.
+fn load_or_create_ca(data_dir: &Path) -> Result<(String, Certificate, KeyPair), String> {
+    let _guard = CA_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let (cert_path, key_path) = ca_paths(data_dir);
+
+    if cert_path.exists() && key_path.exists() {
+        let key_pem = std::fs::read_to_string(&key_path).map_err(|e| e.to_string())?;
+        let cert_pem = std::fs::read_to_string(&cert_path).map_err(|e| e.to_string())?;
+        let key = KeyPair::from_pem(&key_pem)
+            .map_err(|e| format!("Invalid local CA key {}: {e}", key_path.display()))?;
+        let issuer = CertificateParams::from_ca_cert_pem(&cert_pem)
+            .and_then(|params| params.self_signed(&key))
+            .map_err(|e| format!("Invalid local CA certificate {}: {e}", cert_path.display()))?;
+        return Ok((cert_pem, issuer, key));
+    }
+
+    if let Some(dir) = cert_path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let key = KeyPair::generate().map_err(|e| e.to_string())?;
+    let cert = new_ca(&key)?;
+    let cert_pem = cert.pem();
+    write_private_key(&key_path, key.serialize_pem().as_bytes())?;
+    crate::atomic_io::durable_replace(&cert_path, cert_pem.as_bytes())
+        .map_err(|e| format!("Failed to write {}: {e}", cert_path.display()))?;
+    Ok((cert_pem, cert, key))
+}
+
+fn fingerprint(cert_pem: &str) -> Result<String, String> {
+    let body: String = cert_pem
+        .lines()
+        .filter(|line| !line.starts_with("-----"))
+        .collect();
+    let der = STANDARD
+        .decode(body.trim())
+        .map_err(|e| format!("Invalid local CA certificate: {e}"))?;
+    Ok(Sha256::digest(&der)
+        .iter()
+        .map(|byte| format!("{byte:02X}"))
+        .collect::<Vec<_>>()
+        .join(":"))
+}
+
+/// The CA certificate (PEM), created on first use.
+pub(crate) fn ca_pem(data_dir: &Path) -> Result<String, String> {
+    load_or_create_ca(data_dir).map(|(pem, _, _)| pem)
+}
+
+pub(crate) fn ca_info(data_dir: &Path) -> Result<LocalCaInfo, String> {
+    let pem = ca_pem(data_dir)?;
+    Ok(LocalCaInfo {
+        cert_path: ca_paths(data_dir).0.to_string_lossy().into_owned(),
+        fingerprint: fingerprint(&pem)?,
+    })
+}
+
+/// TLS acceptor for a local listener on `bind_address`, with a fresh leaf
+/// signed by the local CA. Specific bind addresses and the published
+/// hostname are added to the SANs.
+pub(crate) fn acceptor(
+    data_dir: &Path,
+    bind_address: &str,
+    hostname: Option<&str>,
+) -> Result<TlsAcceptor, String> {
+    let (_, issuer, issuer_key) = load_or_create_ca(data_dir)?;
+
+    let mut names = vec![
+        "localhost".to_string(),
+        "127.0.0.1".to_string(),
+        "::1".to_string(),
+    ];
+    let bind_address = bind_address.trim();
+    if !matches!(bind_address, "" | "0.0.0.0" | "::") && !names.iter().any(|n| n == bind_address) {
+        names.push(bind_address.to_string());
+    }
+    names.extend(hostname.map(str::to_string));
+    let mut params = CertificateParams::new(names).map_err(|e| e.to_string())?;
+    params
+        .distinguished_name
+        .push(DnType::CommonName, "localhost");
+    params.key_usages = vec![KeyUsagePurpose::DigitalSignature];
+    params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ServerAuth];
+    let now = time::OffsetDateTime::now_utc();
+    params.not_before = now - time::Duration::days(1);
+    params.not_after = now + time::Duration::days(LEAF_VALIDITY_DAYS);
+
+    let key = KeyPair::generate().map_err(|e| e.to_string())?;
+    let leaf = params
+        .signed_by(&key, &issuer, &issuer_key)
+        .map_err(|e| e.to_string())?;
+    let private_key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key.serialize_der()));
+
+    let mut config = rustls::ServerConfig::builder_with_provider(Arc::new(
+        rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()
+    .map_err(|e| e.to_string())?
+    .with_no_client_auth()
+    .with_single_cert(vec![leaf.der().clone()], private_key)
+    .map_err(|e| e.to_string())?;
+    // The backend is plain HTTP/1.x; don't let the browser negotiate h2.
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+/// TLS client for an HTTPS backend, presenting `server_name` as SNI.
+#[derive(Clone)]
+pub(crate) struct UpstreamTls {
+    pub connector: TlsConnector,
+    pub server_name: ServerName<'static>,
+}
+
+/// Accepts whatever certificate the backend presents. The hop runs inside the
+/// authenticated SSH channel, and internal services are usually self-signed or
+/// issued for names the client can't validate anyway.
+#[derive(Debug)]
+struct AnyServerCert(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for AnyServerCert {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+pub(crate) fn upstream(server_name: &str) -> Result<UpstreamTls, String> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let mut config = rustls::ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|e| e.to_string())?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(AnyServerCert(provider)))
+        .with_no_client_auth();
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    let server_name = ServerName::try_from(server_name.to_string())
+        .map_err(|e| format!("Invalid TLS server name '{server_name}': {e}"))?;
+    Ok(UpstreamTls {
+        connector: TlsConnector::from(Arc::new(config)),
+        server_name,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ca_is_created_once_and_reused() {
+        let dir = std::env::temp_dir().join(format!("zync-local-ca-test-{}", uuid::Uuid::new_v4()));
+        let first = ca_info(&dir).expect("create CA");
+        let second = ca_info(&dir).expect("reload CA");
+        assert_eq!(first.fingerprint, second.fingerprint);
+        assert!(acceptor(&dir, "127.0.0.1", None).is_ok());
+        assert!(acceptor(&dir, "192.168.1.20", Some("myapp.zync.local")).is_ok());
+        assert!(upstream("app.internal").is_ok());
+        assert!(upstream("10.0.0.5").is_ok());
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+}
@@ -0,0 +1,157 @@
+//! Loopback aliases (127.0.0.2, 127.0.0.3, …) so several forwards can all
+//! listen on the same well-known port, one address each.
+//!
+//! Linux and Windows route the whole of 127.0.0.0/8 to the loopback
+//! interface, so any alias can be bound as is. macOS and the BSDs only
+//! configure 127.0.0.1; other addresses have to be added to `lo0` first,
+//! which needs root. Starting a forward on a missing alias provisions it
+//! (macOS asks for the admin password) and the alias lasts until reboot.
+
+use crate::types::SavedTunnel;
+use std::net::{IpAddr, Ipv4Addr};
+
+/// A 127.0.0.0/8 address other than 127.0.0.1.
+pub fn as_alias(bind: &str) -> Option<Ipv4Addr> {
+    let ip: Ipv4Addr = bind.parse().ok()?;
+    (ip.is_loopback() && ip != Ipv4Addr::LOCALHOST).then_some(ip)
+}
+
+/// Whether this machine has `ip` configured, i.e. something could listen on it.
+fn is_configured(ip: Ipv4Addr) -> bool {
+    match std::net::TcpListener::bind((ip, 0)) {
+        Ok(_) => true,
+        Err(error) => error.kind() != std::io::ErrorKind::AddrNotAvailable,
+    }
+}
+
+fn add_alias_command(ip: Ipv4Addr) -> String {
+    format!("ifconfig lo0 alias {ip} up")
+}
+
+/// Make sure a forward can listen on `bind`, adding the loopback alias when
+/// it is one and the OS lacks it.
+pub async fn ensure(bind: &str) -> Result<(), String> {
+    let Some(ip) = as_alias(bind) else {
+        return Ok(());
+    };
+    if is_configured(ip) {
+        return Ok(());
+    }
+    let command = add_alias_command(ip);
+    if !cfg!(target_os = "macos") {
+        return Err(format!(
+            "Loopback address {ip} is not configured. Add it as root with `{command}`."
+        ));
+    }
+    let script = format!("do shell script \"{command}\" with administrator privileges");
+    let output = tokio::process::Command::new("osascript")
+        .args(["-e", &script])
+        .output()
+        .await
+        .map_err(|e| format!("Could not add loopback address {ip}: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        // osascript reports a dismissed password prompt as error -128.
+        if stderr.contains("-128") {
+            return Err(format!(
+                "Adding loopback address {ip} was cancelled; add it with `sudo {command}`"
+            ));
+        }
+        return Err(format!(
+            "Could not add loopback address {ip}: {}",
+            stderr.trim()
+        ));
+    }
+    println!("[TUNNEL] Added loopback alias {ip}");
+    Ok(())
+}
+
+fn listens_on(tunnel: &SavedTunnel, ip: Ipv4Addr, port: u16) -> bool {
+    matches!(tunnel.tunnel_type.as_str(), "local" | "dynamic")
+        && tunnel.local_port == port
+        && tunnel
+            .bind_address
+            .as_deref()
+            .unwrap_or("127.0.0.1")
+            .parse::<IpAddr>()
+            .is_ok_and(|bind| bind == IpAddr::V4(ip) || bind.is_unspecified())
+}
+
+/// The lowest alias from 127.0.0.2 on where no other saved forward (and,
+/// per `in_use`, no other program) listens on `port`.
+pub fn next_free(
+    tunnel_id: &str,
+    port: u16,
+    saved: &[SavedTunnel],
+    in_use: impl Fn(Ipv4Addr) -> bool,
+) -> Option<Ipv4Addr> {
+    (2..=254)
+        .map(|host| Ipv4Addr::new(127, 0, 0, host))
+        .find(|&ip| {
+            !saved
+                .iter()
+                .any(|other| other.id != tunnel_id && listens_on(other, ip, port))
+                && !in_use(ip)
+        })
+}
+
+/// Whether another program already listens on `ip:port`. Missing aliases
+/// count as free since starting the forward adds them.
+pub fn port_in_use(ip: Ipv4Addr, port: u16) -> bool {
+    matches!(
+        std::net::TcpListener::bind((ip, port)),
+        Err(error) if error.kind() == std::io::ErrorKind::AddrInUse
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listener(id: &str, bind: Option<&str>) -> SavedTunnel {
+        SavedTunnel {
+            id: id.to_string(),
+            connection_id: "conn".to_string(),
+            name: id.to_string(),
+            tunnel_type: "local".to_string(),
+            local_port: 5432,
+            remote_host: "db.internal".to_string(),
+            remote_port: 5432,
+            bind_address: bind.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn recognises_aliases() {
+        assert_eq!(as_alias("127.0.0.2"), Some(Ipv4Addr::new(127, 0, 0, 2)));
+        assert_eq!(as_alias("127.1.2.3"), Some(Ipv4Addr::new(127, 1, 2, 3)));
+        assert_eq!(as_alias("127.0.0.1"), None);
+        assert_eq!(as_alias("0.0.0.0"), None);
+        assert_eq!(as_alias("localhost"), None);
+        assert_eq!(
+            add_alias_command(Ipv4Addr::new(127, 0, 0, 2)),
+            "ifconfig lo0 alias 127.0.0.2 up"
+        );
+    }
+
+    #[test]
+    fn picks_the_lowest_unused_alias() {
+        let saved = vec![
+            listener("a", None),
+            listener("b", Some("127.0.0.2")),
+            listener("self", Some("127.0.0.3")),
+        ];
+        let free = next_free("self", 5432, &saved, |_| false);
+        assert_eq!(free, Some(Ipv4Addr::new(127, 0, 0, 3)));
+        let free = next_free("new", 5432, &saved, |ip| ip.octets()[3] == 4);
+        assert_eq!(free, Some(Ipv4Addr::new(127, 0, 0, 5)));
+        assert_eq!(
+            next_free("new", 8080, &saved, |_| false),
+            Some(Ipv4Addr::new(127, 0, 0, 2))
+        );
+
+        let everywhere = vec![listener("any", Some("0.0.0.0"))];
+        assert_eq!(next_free("new", 5432, &everywhere, |_| false), None);
+    }
+}
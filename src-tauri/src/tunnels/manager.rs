@@ -1,21 +1,43 @@
+use crate::events::ConnectionEvent;
+use crate::session_channels::{gate_for, is_session_limit_refusal};
+use crate::session_refs::{self, Consumer};
 use crate::ssh::Client;
 use crate::tunnels::dynamic;
+use crate::tunnels::http_inspect;
+use crate::tunnels::local_tls;
+use crate::tunnels::privileged_ports;
+use crate::tunnels::relay;
+use crate::tunnels::runtime_notice::{
+    is_forward_request_denied, is_forwarding_prohibited, remote_forward_rejected_error,
+    TunnelNoticeSender, TunnelRuntimeNotice, FORWARDING_PROHIBITED_ERROR,
+};
 use crate::tunnels::session_failure::{is_ssh_session_fatal_error, SessionFailureSender};
 use crate::types::SavedTunnel;
 use anyhow::{anyhow, Result};
 use log::warn;
 use russh::client::Handle;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
 use std::time::Duration;
 use tokio::net::TcpListener;
 use tokio::sync::Mutex;
 
-const SESSION_PROBE_INTERVAL_SECS: u64 = 15;
+pub(crate) const SESSION_PROBE_INTERVAL_SECS: u64 = 15;
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
 pub(crate) const SSH_SESSION_PROBE_TIMEOUT: Duration = Duration::from_secs(8);
 
 /// Bounded liveness probe — opens and closes a session channel without wedging on stalled transports.
-pub(crate) async fn probe_ssh_session(session: &Arc<Mutex<Handle<Client>>>) -> bool {
+///
+/// Counts against the connection's MaxSessions gate. When every slot is taken the
+/// open channels already prove the transport is alive, so the probe is skipped.
+pub(crate) async fn probe_ssh_session(
+    connection_id: &str,
+    session: &Arc<Mutex<Handle<Client>>>,
+) -> bool {
+    let gate = gate_for(connection_id);
+    let Some(permit) = gate.try_acquire() else {
+        return true;
+    };
     let probe = async {
         let channel_result = {
             let guard = session.lock().await;
@@ -26,14 +48,37 @@ pub(crate) async fn probe_ssh_session(session: &Arc<Mutex<Handle<Client>>>) -> b
                 let _ = channel.close().await;
                 true
             }
-            Err(_) => false,
+            // A refused session while others are open means the cap was hit, not a dead link.
+            Err(error) => is_session_limit_refusal(&error) && gate.usage().open > 1,
+        }
+    };
+    let alive = match tokio::time::timeout(SSH_SESSION_PROBE_TIMEOUT, probe).await {
+        Ok(alive) => alive,
+        Err(_) => {
+            crate::events::publish(connection_id, ConnectionEvent::KeepaliveTimeout);
+            false
         }
     };
-    tokio::time::timeout(SSH_SESSION_PROBE_TIMEOUT, probe)
-        .await
-        .unwrap_or(false)
+    drop(permit);
+    alive
 }
 
+/// Optional layers on a local forward's connections, client side first.
+#[derive(Clone, Default)]
+pub struct LocalListenerLayers {
+    /// Terminate TLS locally (see [`crate::tunnels::local_tls`]).
+    pub tls: Option<tokio_rustls::TlsAcceptor>,
+    /// Log and/or rewrite HTTP heads (see [`crate::tunnels::http_inspect`]).
+    pub(crate) http: Option<http_inspect::HttpLayerOptions>,
+    /// Re-encrypt towards an HTTPS backend over the SSH channel.
+    pub(crate) upstream_tls: Option<local_tls::UpstreamTls>,
+}
+
+/// Either end of a forwarded connection, plain or wrapped.
+trait LocalStream: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send {}
+
+impl<T: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send> LocalStream for T {}
+
 /// Stable runtime key for a saved tunnel config (unique per connection + endpoints).
 pub fn tunnel_runtime_id(tunnel: &SavedTunnel) -> String {
     if tunnel.tunnel_type == "dynamic" {
@@ -75,19 +120,93 @@ pub fn remote_forward_map_key(connection_id: &str, remote_port: u16) -> String {
 pub struct TunnelManager {
     /// `{connection_id}:{remote_port}` -> (local_host, local_port, bind_address)
     pub remote_forwards: Arc<Mutex<HashMap<String, (String, u16, String)>>>,
+    /// `{connection_id}:{remote_port}` -> session the forward was registered on
+    /// (see [`crate::tunnels::remote_rearm`]).
+    pub(crate) remote_sessions: Arc<Mutex<HashMap<String, Weak<Mutex<Handle<Client>>>>>>,
     /// `tunnel_runtime_id` -> listener abort handle + cancel sender
     pub local_listeners:
         Arc<Mutex<HashMap<String, (tokio::task::AbortHandle, tokio::sync::broadcast::Sender<()>)>>>,
+    /// `connection_id` -> saved tunnel ids that were running when its transport
+    /// dropped, for [`crate::tunnels::restore`] to start again.
+    restore_after_reconnect: Arc<Mutex<HashMap<String, Vec<String>>>>,
     failure_tx: SessionFailureSender,
+    notice_tx: TunnelNoticeSender,
 }
 
 impl TunnelManager {
-    pub fn new(failure_tx: SessionFailureSender) -> Self {
+    pub fn new(failure_tx: SessionFailureSender, notice_tx: TunnelNoticeSender) -> Self {
         Self {
             remote_forwards: Arc::new(Mutex::new(HashMap::new())),
+            remote_sessions: Arc::new(Mutex::new(HashMap::new())),
             local_listeners: Arc::new(Mutex::new(HashMap::new())),
+            restore_after_reconnect: Arc::new(Mutex::new(HashMap::new())),
             failure_tx,
+            notice_tx,
+        }
+    }
+
+    /// Hand a dead session to the failure watcher, which stops its tunnels.
+    pub(crate) fn report_session_failure(&self, connection_id: &str) {
+        let _ = self.failure_tx.send(connection_id.to_string());
+    }
+
+    /// Start `tunnel_id` again when `connection_id` is restored after a drop.
+    pub(crate) async fn remember_for_restore(&self, connection_id: &str, tunnel_id: &str) {
+        let mut remembered = self.restore_after_reconnect.lock().await;
+        let ids = remembered.entry(connection_id.to_string()).or_default();
+        if !ids.iter().any(|id| id == tunnel_id) {
+            ids.push(tunnel_id.to_string());
+        }
+    }
+
+    /// `tunnel_id` was started some other way; the restore no longer owes it.
+    pub(crate) async fn forget_for_restore(&self, connection_id: &str, tunnel_id: &str) {
+        let mut remembered = self.restore_after_reconnect.lock().await;
+        if let Some(ids) = remembered.get_mut(connection_id) {
+            ids.retain(|id| id != tunnel_id);
+            if ids.is_empty() {
+                remembered.remove(connection_id);
+            }
+        }
+    }
+
+    /// The tunnels remembered for `connection_id`, handed out once.
+    pub(crate) async fn take_for_restore(&self, connection_id: &str) -> Vec<String> {
+        self.restore_after_reconnect
+            .lock()
+            .await
+            .remove(connection_id)
+            .unwrap_or_default()
+    }
+
+    /// Whether the forward under `map_key` was registered on `session`.
+    pub(crate) async fn remote_forward_on(
+        &self,
+        map_key: &str,
+        session: &Arc<Mutex<Handle<Client>>>,
+    ) -> bool {
+        self.remote_sessions
+            .lock()
+            .await
+            .get(map_key)
+            .is_some_and(|registered| std::ptr::eq(registered.as_ptr(), Arc::as_ptr(session)))
+    }
+
+    pub(crate) async fn forget_remote_forward(&self, map_key: &str) {
+        self.remote_forwards.lock().await.remove(map_key);
+        self.remote_sessions.lock().await.remove(map_key);
+        session_refs::release(Consumer::Tunnel, map_key);
+    }
+
+    /// Stop a local/dynamic listener by runtime id (no saved tunnel required).
+    pub async fn stop_local_runtime(&self, runtime_id: &str) {
+        let mut listeners = self.local_listeners.lock().await;
+        if let Some((handle, tx)) = listeners.remove(runtime_id) {
+            let _ = tx.send(());
+            handle.abort();
         }
+        drop(listeners);
+        session_refs::release(Consumer::Tunnel, runtime_id);
     }
 
     pub async fn start_local_forwarding(
@@ -99,6 +218,7 @@ impl TunnelManager {
         local_port: u16,
         remote_host: String,
         remote_port: u16,
+        layers: LocalListenerLayers,
     ) -> Result<String> {
         {
             let listeners = self.local_listeners.lock().await;
@@ -134,15 +254,36 @@ impl TunnelManager {
 
                 return Err(anyhow!(error_msg));
             }
+            Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+                return Err(anyhow!(privileged_ports::permission_denied_error(local_port)));
+            }
             Err(e) => return Err(e.into()),
         };
         let session = session.clone();
         let failure_tx = self.failure_tx.clone();
+        let notice_tx = self.notice_tx.clone();
+        session_refs::hold(Consumer::Tunnel, &runtime_id, &connection_id);
+        let task_runtime_id = runtime_id.clone();
 
         println!(
-            "[TUNNEL] Starting local forwarding {} on port {} to {}:{} (bind {})",
-            runtime_id, local_port, remote_host, remote_port, bind_address
+            "[TUNNEL] Starting local forwarding {} on port {} to {}:{} (bind {}{})",
+            runtime_id,
+            local_port,
+            remote_host,
+            remote_port,
+            bind_address,
+            [
+                layers.tls.as_ref().map(|_| ", TLS"),
+                layers.http.as_ref().map(|_| ", HTTP layer"),
+                layers.upstream_tls.as_ref().map(|_| ", upstream TLS"),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<String>()
         );
+        if layers.http.as_ref().is_some_and(|http| http.log) {
+            http_inspect::clear(&runtime_id);
+        }
 
         let (tx, _rx) = tokio::sync::broadcast::channel(1);
         let tx_for_store = tx.clone();
@@ -157,22 +298,57 @@ impl TunnelManager {
                 let mut rx = tx.subscribe();
 
                 tokio::select! {
-                    Ok((mut incoming_stream, _)) = accept_fut => {
+                    Ok((incoming_stream, _)) = accept_fut => {
+                         let layers = layers.clone();
                          let session = session.clone();
                          let remote_host = remote_host.clone();
                          let mut inner_rx = tx.subscribe();
                          let stop_tx = tx.clone();
                          let failure_tx = failure_tx.clone();
+                         let notice_tx = notice_tx.clone();
+                         let runtime_id = task_runtime_id.clone();
                          let connection_id = connection_id.clone();
 
                          tokio::spawn(async move {
+                            // Finish the handshake before opening a channel so stray
+                            // or failed TLS clients don't consume session slots.
+                            let incoming_stream: Box<dyn LocalStream> = match layers.tls {
+                                Some(acceptor) => {
+                                    match tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, acceptor.accept(incoming_stream)).await {
+                                        Ok(Ok(stream)) => Box::new(stream),
+                                        Ok(Err(e)) => {
+                                            println!("[TUNNEL] TLS handshake failed: {}", e);
+                                            return;
+                                        }
+                                        Err(_) => {
+                                            println!("[TUNNEL] TLS handshake timed out");
+                                            return;
+                                        }
+                                    }
+                                }
+                                None => Box::new(incoming_stream),
+                            };
+                            let mut incoming_stream: Box<dyn LocalStream> = match layers.http {
+                                Some(options) => Box::new(http_inspect::HttpLayerStream::new(
+                                    incoming_stream,
+                                    runtime_id.clone(),
+                                    options,
+                                )),
+                                None => incoming_stream,
+                            };
+
                             let channel = {
                                 let session_guard = session.lock().await;
                                 match session_guard.channel_open_direct_tcpip(remote_host, remote_port as u32, "127.0.0.1", 0).await {
                                      Ok(c) => Some(c),
                                      Err(e) => {
                                          eprintln!("[TUNNEL] Failed to open direct-tcpip channel: {}", e);
-                                         if is_ssh_session_fatal_error(&e) {
+                                         if is_forwarding_prohibited(&e) {
+                                             let _ = notice_tx.send(TunnelRuntimeNotice {
+                                                 runtime_id,
+                                                 error: FORWARDING_PROHIBITED_ERROR.to_string(),
+                                             });
+                                         } else if is_ssh_session_fatal_error(&e) {
                                              println!(
                                                  "[TUNNEL] SSH session lost for {}; stopping tunnels",
                                                  connection_id
@@ -186,12 +362,24 @@ impl TunnelManager {
                             };
 
                             if let Some(channel) = channel {
-                                 let mut stream = channel.into_stream();
+                                 let mut stream: Box<dyn LocalStream> = match layers.upstream_tls {
+                                     Some(upstream) => {
+                                         match upstream.connector.connect(upstream.server_name, channel.into_stream()).await {
+                                             Ok(stream) => Box::new(stream),
+                                             Err(e) => {
+                                                 println!("[TUNNEL] Upstream TLS handshake failed: {}", e);
+                                                 return;
+                                             }
+                                         }
+                                     }
+                                     None => Box::new(channel.into_stream()),
+                                 };
 
                                  tokio::select! {
-                                     res = tokio::io::copy_bidirectional(&mut incoming_stream, &mut stream) => {
-                                         if let Err(e) = res {
-                                             println!("[TUNNEL] Error copying: {}", e);
+                                     res = relay::copy_bidirectional(&mut incoming_stream, &mut stream) => {
+                                         match res {
+                                             Ok((sent, received)) => crate::metrics::record_forwarded_bytes(sent + received),
+                                             Err(e) => println!("[TUNNEL] Error copying: {}", e),
                                          }
                                      }
                                      _ = inner_rx.recv() => {
@@ -206,7 +394,7 @@ impl TunnelManager {
                         break;
                     }
                     _ = session_probe.tick() => {
-                        if !probe_ssh_session(&session).await {
+                        if !probe_ssh_session(&connection_id, &session).await {
                             println!(
                                 "[TUNNEL] SSH session probe failed for {}; stopping tunnels",
                                 connection_id
@@ -271,6 +459,9 @@ impl TunnelManager {
 
                 return Err(anyhow!(error_msg));
             }
+            Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+                return Err(anyhow!(privileged_ports::permission_denied_error(local_port)));
+            }
             Err(e) => return Err(e.into()),
         };
 
@@ -283,6 +474,9 @@ impl TunnelManager {
         let tx_for_store = tx.clone();
         let session = session.clone();
         let failure_tx = self.failure_tx.clone();
+        let notice_tx = self.notice_tx.clone();
+        session_refs::hold(Consumer::Tunnel, &runtime_id, &connection_id);
+        let task_runtime_id = runtime_id.clone();
 
         let handle = tokio::spawn(async move {
             let mut session_probe =
@@ -299,13 +493,17 @@ impl TunnelManager {
                         let client_rx = tx.subscribe();
                         let stop_tx = tx.clone();
                         let failure_tx = failure_tx.clone();
+                        let notice_tx = notice_tx.clone();
+                        let runtime_id = task_runtime_id.clone();
                         let connection_id = connection_id.clone();
                         tokio::spawn(async move {
                             dynamic::handle_socks5_client(
                                 client_stream,
                                 session,
                                 connection_id,
+                                runtime_id,
                                 failure_tx,
+                                notice_tx,
                                 stop_tx,
                                 client_rx,
                             )
@@ -317,7 +515,7 @@ impl TunnelManager {
                         break;
                     }
                     _ = session_probe.tick() => {
-                        if !probe_ssh_session(&session).await {
+                        if !probe_ssh_session(&connection_id, &session).await {
                             println!(
                                 "[TUNNEL] SSH session probe failed for {}; stopping tunnels",
                                 connection_id
@@ -350,15 +548,16 @@ impl TunnelManager {
         local_port: u16,
     ) -> Result<String> {
         let map_key = remote_forward_map_key(&connection_id, remote_port);
+        // A forward left over from a replaced session is registered again.
+        if self.remote_forward_on(&map_key, &session).await {
+            println!(
+                "[TUNNEL] Remote tunnel {} already active",
+                map_key
+            );
+            return Ok(runtime_id);
+        }
         {
             let mut map = self.remote_forwards.lock().await;
-            if map.contains_key(&map_key) {
-                println!(
-                    "[TUNNEL] Remote tunnel {} already active",
-                    map_key
-                );
-                return Ok(runtime_id);
-            }
             map.insert(
                 map_key.clone(),
                 (local_host.clone(), local_port, bind_address.clone()),
@@ -373,10 +572,17 @@ impl TunnelManager {
         };
 
         if let Err(e) = res {
-            let mut map = self.remote_forwards.lock().await;
-            map.remove(&map_key);
+            self.forget_remote_forward(&map_key).await;
+            if is_forward_request_denied(&e) {
+                return Err(anyhow!(remote_forward_rejected_error(&bind_address, remote_port)));
+            }
             return Err(anyhow!("Remote forwarding error: {}", e));
         }
+        self.remote_sessions
+            .lock()
+            .await
+            .insert(map_key.clone(), Arc::downgrade(&session));
+        session_refs::hold(Consumer::Tunnel, &map_key, &connection_id);
 
         println!(
             "[TUNNEL] Remote forwarding {} enabled on remote port {} -> {}:{} (bind {})",
@@ -399,6 +605,7 @@ impl TunnelManager {
             if let Some((handle, tx)) = listeners.remove(&runtime_id) {
                 let _ = tx.send(());
                 handle.abort();
+                session_refs::release(Consumer::Tunnel, &runtime_id);
                 println!("[TUNNEL] Stop signal sent for {}", runtime_id);
             } else {
                 println!(
@@ -424,9 +631,9 @@ impl TunnelManager {
                         .cancel_tcpip_forward(bind_addr.clone(), tunnel.remote_port as u32)
                         .await;
 
-                    if res.is_ok() {
-                        let mut remote_forwards_guard = self.remote_forwards.lock().await;
-                        remote_forwards_guard.remove(&map_key);
+                    // A forward from a replaced session is gone server-side already.
+                    if res.is_ok() || !self.remote_forward_on(&map_key, &session).await {
+                        self.forget_remote_forward(&map_key).await;
                         println!(
                             "[TUNNEL] Cancelled remote forwarding {} (bind {})",
                             map_key, bind_addr
@@ -439,8 +646,7 @@ impl TunnelManager {
                         );
                     }
                 } else {
-                    let mut remote_forwards_guard = self.remote_forwards.lock().await;
-                    remote_forwards_guard.remove(&map_key);
+                    self.forget_remote_forward(&map_key).await;
                 }
             } else if let Some(session) = session {
                 let handle = session.lock().await;
@@ -580,6 +786,10 @@ mod tests {
             group: None,
             created_at: None,
             updated_at: None,
+            web: None,
+            pac_domains: None,
+            system_proxy: None,
+            schedule: None,
         }
     }
 
@@ -604,6 +814,21 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn restore_memory_is_per_connection_and_taken_once() {
+        let (failure_tx, _failures) = crate::tunnels::session_failure::session_failure_channel();
+        let (notice_tx, _notices) = crate::tunnels::runtime_notice::tunnel_notice_channel();
+        let manager = TunnelManager::new(failure_tx, notice_tx);
+        manager.remember_for_restore("conn-a", "t1").await;
+        manager.remember_for_restore("conn-a", "t2").await;
+        manager.remember_for_restore("conn-a", "t1").await;
+        manager.remember_for_restore("conn-b", "t3").await;
+        manager.forget_for_restore("conn-a", "t2").await;
+        assert_eq!(manager.take_for_restore("conn-a").await, vec!["t1".to_string()]);
+        assert!(manager.take_for_restore("conn-a").await.is_empty());
+        assert_eq!(manager.take_for_restore("conn-b").await, vec!["t3".to_string()]);
+    }
+
     #[test]
     fn tunnel_runtime_id_for_dynamic() {
         let mut t = sample_tunnel("dynamic", "conn-d");
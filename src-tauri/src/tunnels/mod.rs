@@ -4,10 +4,33 @@
 
 pub mod commands;
 pub mod dynamic;
+pub(crate) mod hostnames;
+pub(crate) mod http_inspect;
+pub(crate) mod http_rewrite;
+pub mod listening_ports;
+pub(crate) mod local_tls;
+pub(crate) mod loopback_alias;
 pub mod manager;
+pub(crate) mod pac;
+pub(crate) mod port_catalog;
+pub(crate) mod privileged_ports;
+pub(crate) mod relay;
+pub(crate) mod remote_desktop;
+pub(crate) mod remote_rearm;
+pub(crate) mod restore;
+pub(crate) mod runtime_notice;
+pub(crate) mod schedule;
+pub(crate) mod service_detect;
+pub(crate) mod service_names;
 pub(crate) mod session_failure;
 pub(crate) mod socks5;
+pub(crate) mod ssh_forwards;
+pub mod system_proxy;
+pub(crate) mod validate;
+pub(crate) mod web_open;
 
-pub use manager::{remote_forward_map_key, tunnel_runtime_id, TunnelManager};
+pub use manager::{
+    remote_forward_map_key, tunnel_runtime_id, LocalListenerLayers, TunnelManager,
+};
 
-pub(crate) use commands::stop_tunnels_for_connections;
\ No newline at end of file
+pub(crate) use commands::{running_tunnel_ids, stop_tunnels_for_connections};
\ No newline at end of file
@@ -0,0 +1,289 @@
+//! Proxy auto-config (PAC) for SOCKS tunnels.
+//!
+//! Dynamic tunnels with `pacDomains` route those domains (and their
+//! subdomains) through their SOCKS listener; everything else goes `DIRECT`.
+//! The file is rendered per request from the dynamic tunnels that are running,
+//! so a browser pointed at `http://127.0.0.1:<port>/proxy.pac` follows tunnels
+//! as they start and stop without touching its settings.
+
+use crate::commands::{get_data_dir, AppState};
+use crate::types::SavedTunnel;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::OnceCell;
+
+/// Preferred port so the browser setting survives restarts; any free port otherwise.
+const PAC_PORT: u16 = 18_089;
+const PAC_PATH: &str = "/proxy.pac";
+const REQUEST_READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+static SERVER: OnceCell<SocketAddr> = OnceCell::const_new();
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PacRoute {
+    pub tunnel_id: String,
+    pub name: String,
+    /// `host:port` of the SOCKS listener.
+    pub proxy: String,
+    pub domains: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PacInfo {
+    pub url: String,
+    /// Routes currently in the file (running dynamic tunnels with domains).
+    pub routes: Vec<PacRoute>,
+}
+
+/// `example.com`, `.example.com` and `*.example.com` all mean the domain and its
+/// subdomains; other `*`/`?` patterns are matched with `shExpMatch`. Anything
+/// that can't be a hostname pattern is dropped.
+pub(crate) fn normalize_domain(raw: &str) -> Option<String> {
+    let domain = raw.trim().trim_end_matches('.').to_ascii_lowercase();
+    let domain = domain
+        .strip_prefix("*.")
+        .or_else(|| domain.strip_prefix('.'))
+        .unwrap_or(&domain);
+    let valid = !domain.is_empty()
+        && domain
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '*' | '?'));
+    valid.then(|| domain.to_string())
+}
+
+/// Loopback-friendly host for reaching a listener bound to `bind_address`.
+pub(crate) fn proxy_host(bind_address: Option<&str>) -> String {
+    match bind_address.map(str::trim) {
+        None | Some("" | "0.0.0.0" | "localhost") => "127.0.0.1".to_string(),
+        Some("::") => "[::1]".to_string(),
+        Some(address) if address.contains(':') => format!("[{address}]"),
+        Some(address) => address.to_string(),
+    }
+}
+
+/// Routes for the dynamic tunnels in `saved` whose runtime id is in `running`.
+pub(crate) fn routes(saved: &[SavedTunnel], running: &HashSet<String>) -> Vec<PacRoute> {
+    saved
+        .iter()
+        .filter(|tunnel| tunnel.tunnel_type == "dynamic")
+        .filter(|tunnel| running.contains(&super::tunnel_runtime_id(tunnel)))
+        .filter_map(|tunnel| {
+            let mut domains: Vec<String> = Vec::new();
+            for domain in tunnel.pac_domains.iter().flatten() {
+                if let Some(domain) = normalize_domain(domain) {
+                    if !domains.contains(&domain) {
+                        domains.push(domain);
+                    }
+                }
+            }
+            (!domains.is_empty()).then(|| PacRoute {
+                tunnel_id: tunnel.id.clone(),
+                name: tunnel.name.clone(),
+                proxy: format!(
+                    "{}:{}",
+                    proxy_host(tunnel.bind_address.as_deref()),
+                    tunnel.local_port
+                ),
+                domains,
+            })
+        })
+        .collect()
+}
+
+fn condition(domain: &str) -> String {
+    if domain.contains(['*', '?']) {
+        format!("shExpMatch(host, \"{domain}\")")
+    } else {
+        format!("host == \"{domain}\" || dnsDomainIs(host, \".{domain}\")")
+    }
+}
+
+/// The PAC script. Earlier routes win when domains overlap.
+pub(crate) fn render(routes: &[PacRoute]) -> String {
+    let mut script =
+        String::from("function FindProxyForURL(url, host) {\n    host = host.toLowerCase();\n");
+    for route in routes {
+        let name: String = route
+            .name
+            .chars()
+            .map(|c| if c.is_control() { ' ' } else { c })
+            .collect::<String>()
+            .trim()
+            .to_string();
+        let conditions: Vec<String> = route
+            .domains
+            .iter()
+            .map(String::as_str)
+            .map(condition)
+            .collect();
+        script.push_str(&format!(
+            "    // {name}\n    if ({}) {{\n        return \"SOCKS5 {proxy}; SOCKS {proxy}\";\n    }}\n",
+            conditions.join(" ||\n        "),
+            proxy = route.proxy,
+        ));
+    }
+    script.push_str("    return \"DIRECT\";\n}\n");
+    script
+}
+
+async fn current_routes(app: &AppHandle) -> Result<Vec<PacRoute>, String> {
+    let path = get_data_dir(app).join("tunnels.json");
+    let saved = if path.exists() {
+        crate::sync::domain_tunnels::load_saved_tunnels(&path)
+            .map_err(|e| e.to_string())?
+            .tunnels
+    } else {
+        Vec::new()
+    };
+    let running: HashSet<String> = {
+        let state = app.state::<AppState>();
+        let listeners = state.tunnel_manager.local_listeners.lock().await;
+        listeners.keys().cloned().collect()
+    };
+    Ok(routes(&saved, &running))
+}
+
+async fn serve_request(app: &AppHandle, mut stream: tokio::net::TcpStream) -> std::io::Result<()> {
+    let mut buffer = [0u8; 1024];
+    let read = tokio::time::timeout(REQUEST_READ_TIMEOUT, stream.read(&mut buffer))
+        .await
+        .map_err(|_| std::io::Error::from(std::io::ErrorKind::TimedOut))??;
+    let request_line = String::from_utf8_lossy(&buffer[..read]);
+    let path = request_line.split_whitespace().nth(1).unwrap_or("");
+
+    let (status, content_type, body) = if path == PAC_PATH || path.starts_with("/proxy.pac?") {
+        match current_routes(app).await {
+            Ok(routes) => (
+                "200 OK",
+                "application/x-ns-proxy-autoconfig",
+                render(&routes),
+            ),
+            Err(error) => {
+                eprintln!("[TUNNEL][PAC] Failed to load tunnels: {error}");
+                // Keep the browser online rather than failing every request.
+                ("200 OK", "application/x-ns-proxy-autoconfig", render(&[]))
+            }
+        }
+    } else {
+        (
+            "404 Not Found",
+            "text/plain; charset=utf-8",
+            "Not found\n".to_string(),
+        )
+    };
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Start the PAC server on first use; later calls return the same address.
+pub(crate) async fn ensure_server(app: &AppHandle) -> Result<SocketAddr, String> {
+    SERVER
+        .get_or_try_init(|| async {
+            let listener = match TcpListener::bind(("127.0.0.1", PAC_PORT)).await {
+                Ok(listener) => listener,
+                Err(_) => TcpListener::bind(("127.0.0.1", 0))
+                    .await
+                    .map_err(|e| format!("Failed to start PAC server: {e}"))?,
+            };
+            let address = listener.local_addr().map_err(|e| e.to_string())?;
+            println!("[TUNNEL][PAC] Serving http://{address}{PAC_PATH}");
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    let Ok((stream, _)) = listener.accept().await else {
+                        continue;
+                    };
+                    let app = app.clone();
+                    tauri::async_runtime::spawn(async move {
+                        let _ = serve_request(&app, stream).await;
+                    });
+                }
+            });
+            Ok(address)
+        })
+        .await
+        .copied()
+}
+
+pub(crate) async fn info(app: &AppHandle) -> Result<PacInfo, String> {
+    let address = ensure_server(app).await?;
+    Ok(PacInfo {
+        url: format!("http://{address}{PAC_PATH}"),
+        routes: current_routes(app).await?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dynamic(id: &str, port: u16, domains: &[&str]) -> SavedTunnel {
+        SavedTunnel {
+            id: id.to_string(),
+            connection_id: "conn".to_string(),
+            name: format!("SOCKS {port}"),
+            tunnel_type: "dynamic".to_string(),
+            local_port: port,
+            bind_address: Some("127.0.0.1".to_string()),
+            pac_domains: Some(domains.iter().map(|d| d.to_string()).collect()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn only_running_tunnels_with_domains_are_routed() {
+        let saved = [
+            dynamic(
+                "a",
+                1080,
+                &["*.Corp.Example.", "corp.example", "bad domain"],
+            ),
+            dynamic("b", 1081, &["stopped.example"]),
+            dynamic("c", 1082, &[]),
+        ];
+        let running: HashSet<String> = [&saved[0], &saved[2]]
+            .into_iter()
+            .map(crate::tunnels::tunnel_runtime_id)
+            .collect();
+        assert_eq!(
+            routes(&saved, &running),
+            vec![PacRoute {
+                tunnel_id: "a".to_string(),
+                name: "SOCKS 1080".to_string(),
+                proxy: "127.0.0.1:1080".to_string(),
+                domains: vec!["corp.example".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn script_matches_domains_then_falls_back_to_direct() {
+        let script = render(&[PacRoute {
+            tunnel_id: "a".to_string(),
+            name: "Work\n".to_string(),
+            proxy: "127.0.0.1:1080".to_string(),
+            domains: vec!["corp.example".to_string(), "build-??.ci".to_string()],
+        }]);
+        assert!(script.contains(
+            "if (host == \"corp.example\" || dnsDomainIs(host, \".corp.example\") ||\n        shExpMatch(host, \"build-??.ci\")) {"
+        ));
+        assert!(script.contains("return \"SOCKS5 127.0.0.1:1080; SOCKS 127.0.0.1:1080\";"));
+        assert!(script.contains("    // Work\n"));
+        assert!(script.ends_with("    return \"DIRECT\";\n}\n"));
+        assert_eq!(
+            render(&[]),
+            "function FindProxyForURL(url, host) {\n    host = host.toLowerCase();\n    return \"DIRECT\";\n}\n"
+        );
+    }
+}
@@ -0,0 +1,222 @@
+//! Named local port reservations.
+//!
+//! Tunnels and local dev servers coordinated through Zync ask for ports by name
+//! (`grafana`, `api-dev`), optionally within a range. The first request picks a
+//! port that is free right now and neither reserved nor used by a saved tunnel;
+//! later requests for the same name get the same port back. Reservations tied
+//! to a tunnel are dropped when the tunnel is deleted. Stored device-local in
+//! `<data dir>/port-reservations.json`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex as StdMutex};
+
+const CATALOG_FILE: &str = "port-reservations.json";
+pub(crate) const DEFAULT_RANGE: RangeInclusive<u16> = 42000..=42999;
+
+static CATALOG_LOCK: LazyLock<StdMutex<()>> = LazyLock::new(|| StdMutex::new(()));
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortReservation {
+    pub name: String,
+    pub port: u16,
+    /// Saved tunnel the port belongs to; released when it is deleted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tunnel_id: Option<String>,
+    pub reserved_at: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Catalog {
+    #[serde(default)]
+    reservations: Vec<PortReservation>,
+}
+
+fn catalog_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(CATALOG_FILE)
+}
+
+fn load(path: &Path) -> Result<Catalog, String> {
+    match std::fs::read(path) {
+        Ok(raw) => {
+            serde_json::from_slice(&raw).map_err(|e| format!("Invalid {}: {e}", path.display()))
+        }
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Catalog::default()),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
+fn save(path: &Path, catalog: &Catalog) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let raw = serde_json::to_vec_pretty(catalog).map_err(|e| e.to_string())?;
+    crate::atomic_io::durable_replace(path, &raw)
+        .map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+fn is_bindable(port: u16) -> bool {
+    std::net::TcpListener::bind(("127.0.0.1", port)).is_ok()
+}
+
+/// First port in `range` that isn't `taken` and passes `is_free`.
+fn pick_port(
+    range: RangeInclusive<u16>,
+    taken: &HashSet<u16>,
+    is_free: impl Fn(u16) -> bool,
+) -> Option<u16> {
+    range
+        .filter(|port| *port != 0 && !taken.contains(port))
+        .find(|port| is_free(*port))
+}
+
+pub(crate) fn list(data_dir: &Path) -> Result<Vec<PortReservation>, String> {
+    let _guard = CATALOG_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut reservations = load(&catalog_path(data_dir))?.reservations;
+    reservations.sort_by_key(|reservation| reservation.port);
+    Ok(reservations)
+}
+
+/// The port reserved under `name`, reserving one in `range` if needed.
+/// `in_use` are ports saved tunnels already listen on or forward to. An
+/// existing reservation outside `range` is moved into it.
+pub(crate) fn reserve(
+    data_dir: &Path,
+    name: &str,
+    range: RangeInclusive<u16>,
+    tunnel_id: Option<&str>,
+    in_use: &HashSet<u16>,
+) -> Result<PortReservation, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("A reservation needs a name".to_string());
+    }
+    if range.is_empty() {
+        return Err(format!(
+            "Invalid port range {}-{}",
+            range.start(),
+            range.end()
+        ));
+    }
+    let _guard = CATALOG_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let path = catalog_path(data_dir);
+    let mut catalog = load(&path)?;
+
+    let existing = catalog
+        .reservations
+        .iter()
+        .position(|reservation| reservation.name.eq_ignore_ascii_case(name));
+    if let Some(index) = existing {
+        let reservation = &mut catalog.reservations[index];
+        if range.contains(&reservation.port) {
+            if let Some(tunnel_id) = tunnel_id {
+                if reservation.tunnel_id.as_deref() != Some(tunnel_id) {
+                    reservation.tunnel_id = Some(tunnel_id.to_string());
+                    let reservation = reservation.clone();
+                    save(&path, &catalog)?;
+                    return Ok(reservation);
+                }
+            }
+            return Ok(reservation.clone());
+        }
+        catalog.reservations.remove(index);
+    }
+
+    let mut taken: HashSet<u16> = catalog
+        .reservations
+        .iter()
+        .map(|reservation| reservation.port)
+        .collect();
+    taken.extend(in_use);
+    let port = pick_port(range.clone(), &taken, is_bindable)
+        .ok_or_else(|| format!("No free port left in {}-{}", range.start(), range.end()))?;
+    let reservation = PortReservation {
+        name: name.to_string(),
+        port,
+        tunnel_id: tunnel_id.map(str::to_string),
+        reserved_at: now_ms(),
+    };
+    catalog.reservations.push(reservation.clone());
+    save(&path, &catalog)?;
+    Ok(reservation)
+}
+
+/// Drop the reservation named `name`. Returns whether one existed.
+pub(crate) fn release(data_dir: &Path, name: &str) -> Result<bool, String> {
+    release_where(data_dir, |reservation| {
+        reservation.name.eq_ignore_ascii_case(name.trim())
+    })
+}
+
+pub(crate) fn release_for_tunnel(data_dir: &Path, tunnel_id: &str) -> Result<bool, String> {
+    release_where(data_dir, |reservation| {
+        reservation.tunnel_id.as_deref() == Some(tunnel_id)
+    })
+}
+
+fn release_where(
+    data_dir: &Path,
+    matches: impl Fn(&PortReservation) -> bool,
+) -> Result<bool, String> {
+    let _guard = CATALOG_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let path = catalog_path(data_dir);
+    let mut catalog = load(&path)?;
+    let before = catalog.reservations.len();
+    catalog
+        .reservations
+        .retain(|reservation| !matches(reservation));
+    if catalog.reservations.len() == before {
+        return Ok(false);
+    }
+    save(&path, &catalog)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pick_port_skips_taken_and_busy_ports() {
+        let taken: HashSet<u16> = [42000, 42002].into_iter().collect();
+        assert_eq!(
+            pick_port(42000..=42005, &taken, |port| port != 42001),
+            Some(42003)
+        );
+        assert_eq!(pick_port(42000..=42002, &taken, |port| port != 42001), None);
+    }
+
+    #[test]
+    fn reservations_are_stable_by_name_and_released_with_their_tunnel() {
+        let dir = std::env::temp_dir().join(format!("zync-ports-test-{}", uuid::Uuid::new_v4()));
+        let none = HashSet::new();
+        let first = reserve(&dir, "grafana", DEFAULT_RANGE, None, &none).expect("reserve");
+        let again = reserve(&dir, "Grafana", DEFAULT_RANGE, Some("t1"), &none).expect("again");
+        assert_eq!(first.port, again.port);
+        assert_eq!(again.tunnel_id.as_deref(), Some("t1"));
+
+        let other = reserve(&dir, "api-dev", DEFAULT_RANGE, None, &none).expect("other");
+        assert_ne!(other.port, first.port);
+
+        assert!(release_for_tunnel(&dir, "t1").expect("release"));
+        assert_eq!(list(&dir).expect("list"), vec![other]);
+        assert!(!release(&dir, "grafana").expect("already gone"));
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+}
@@ -0,0 +1,281 @@
+//! Listening on local ports below 1024, e.g. to serve a remote 80/443 on the
+//! same ports here.
+//!
+//! - Windows: TCP listeners on low ports need no rights. URL ACLs (`netsh
+//!   http add urlacl`) only govern HTTP.sys, which tunnels don't use.
+//! - macOS 10.14 and later: any user may bind low ports.
+//! - Linux: the process needs CAP_NET_BIND_SERVICE, or the ports must be
+//!   above `net.ipv4.ip_unprivileged_port_start`. Two fixes are offered and
+//!   run through `pkexec` once the user picks one: `setcap` grants the
+//!   capability to the Zync binary (kept until the binary is replaced, and
+//!   effective from the next launch), `sysctl` lowers the threshold for the
+//!   whole machine until reboot.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PortFixKind {
+    Setcap,
+    Sysctl,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortFix {
+    pub kind: PortFixKind,
+    pub title: String,
+    pub description: String,
+    /// What runs as root, shown so it can also be run by hand.
+    pub command: String,
+    #[serde(skip)]
+    argv: Vec<String>,
+    /// Why the fix can't be applied from here, if it can't.
+    pub unavailable: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrivilegedPortStatus {
+    /// The port asked about.
+    pub port: u16,
+    /// Whether Zync can listen on it as it runs now.
+    pub can_bind: bool,
+    pub detail: String,
+    /// Empty where binding never needs elevation.
+    pub fixes: Vec<PortFix>,
+}
+
+/// Effective capability mask from `/proc/self/status`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn effective_capabilities(proc_status: &str) -> Option<u64> {
+    proc_status
+        .lines()
+        .find_map(|line| line.strip_prefix("CapEff:"))
+        .and_then(|mask| u64::from_str_radix(mask.trim(), 16).ok())
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn can_bind_low_ports(proc_status: &str) -> bool {
+    /// `CAP_NET_BIND_SERVICE` in `linux/capability.h`.
+    const CAP_NET_BIND_SERVICE: u32 = 10;
+    effective_capabilities(proc_status).is_some_and(|mask| mask & (1 << CAP_NET_BIND_SERVICE) != 0)
+}
+
+/// Whether binding `port` needs rights this process lacks, given the Linux
+/// threshold and whether the process holds the capability.
+fn needs_elevation_with(port: u16, unprivileged_port_start: u16, privileged: bool) -> bool {
+    port != 0 && port < unprivileged_port_start && !privileged
+}
+
+#[cfg(target_os = "linux")]
+fn linux_state() -> (u16, bool) {
+    let start = std::fs::read_to_string("/proc/sys/net/ipv4/ip_unprivileged_port_start")
+        .ok()
+        .and_then(|raw| raw.trim().parse().ok())
+        .unwrap_or(1024);
+    let privileged = std::fs::read_to_string("/proc/self/status")
+        .map(|status| can_bind_low_ports(&status))
+        .unwrap_or(false);
+    (start, privileged)
+}
+
+/// Whether listening on local `port` needs elevation on this machine.
+pub fn needs_elevation(port: u16) -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        let (start, privileged) = linux_state();
+        needs_elevation_with(port, start, privileged)
+    }
+    #[cfg(any(target_os = "macos", windows))]
+    {
+        needs_elevation_with(port, 0, true)
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+    {
+        needs_elevation_with(port, 1024, false)
+    }
+}
+
+/// Error for a listener refused with `PermissionDenied`.
+pub fn permission_denied_error(port: u16) -> String {
+    if port < 1024 {
+        format!(
+            "Not allowed to listen on port {port}: ports below 1024 are privileged. \
+             Allow them in Settings → General → Privileged ports, or pick a port above 1024."
+        )
+    } else {
+        format!("Not allowed to listen on port {port}")
+    }
+}
+
+/// The fixes for `port`, run as root. `exe` is the Zync binary and
+/// `appimage` whether it runs from an AppImage mount.
+fn linux_fixes(port: u16, exe: &str, appimage: bool) -> Vec<PortFix> {
+    let fix = |kind, title: String, description: &str, argv: Vec<String>| PortFix {
+        kind,
+        title,
+        description: description.to_string(),
+        command: argv
+            .iter()
+            .map(|arg| {
+                if arg.contains(' ') {
+                    format!("'{arg}'")
+                } else {
+                    arg.clone()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+        argv,
+        unavailable: None,
+    };
+    vec![
+        PortFix {
+            unavailable: appimage.then(|| {
+                "AppImages run from a read-only mount; use the system-wide option or the \
+                 distribution package instead"
+                    .to_string()
+            }),
+            ..fix(
+                PortFixKind::Setcap,
+                "Allow Zync to bind low ports".to_string(),
+                "Grants CAP_NET_BIND_SERVICE to the Zync binary only. Takes effect after \
+                 restarting Zync and must be repeated after each update.",
+                vec![
+                    "setcap".to_string(),
+                    "cap_net_bind_service=+ep".to_string(),
+                    exe.to_string(),
+                ],
+            )
+        },
+        fix(
+            PortFixKind::Sysctl,
+            format!("Let every program bind ports from {port}"),
+            "Lowers net.ipv4.ip_unprivileged_port_start for the whole machine until reboot. \
+             Any local user can then listen on these ports.",
+            vec![
+                "sysctl".to_string(),
+                "-w".to_string(),
+                format!("net.ipv4.ip_unprivileged_port_start={port}"),
+            ],
+        ),
+    ]
+}
+
+fn current_exe() -> Result<String, String> {
+    std::env::current_exe()
+        .map(|path| path.to_string_lossy().into_owned())
+        .map_err(|e| format!("Could not locate the Zync binary: {e}"))
+}
+
+pub fn status(port: u16) -> PrivilegedPortStatus {
+    let can_bind = !needs_elevation(port);
+    let (detail, fixes) = if cfg!(target_os = "linux") {
+        let exe = current_exe().unwrap_or_else(|_| "/path/to/zync".to_string());
+        let appimage = std::env::var_os("APPIMAGE").is_some();
+        let detail = if can_bind {
+            format!("Zync can listen on port {port}.")
+        } else {
+            format!(
+                "Port {port} needs CAP_NET_BIND_SERVICE or a lower \
+                 net.ipv4.ip_unprivileged_port_start."
+            )
+        };
+        (detail, linux_fixes(port, &exe, appimage))
+    } else if cfg!(any(target_os = "macos", windows)) {
+        (
+            "This system lets any user listen on ports below 1024.".to_string(),
+            Vec::new(),
+        )
+    } else {
+        (
+            format!("Port {port} needs root on this system."),
+            Vec::new(),
+        )
+    };
+    PrivilegedPortStatus {
+        port,
+        can_bind,
+        detail,
+        fixes,
+    }
+}
+
+/// Run a fix through `pkexec`, which asks for the admin password.
+pub async fn apply(kind: PortFixKind, port: u16) -> Result<(), String> {
+    if !cfg!(target_os = "linux") {
+        return Err("Low ports need no changes on this system".to_string());
+    }
+    if !needs_elevation(port) {
+        return Err(format!("Zync can already listen on port {port}"));
+    }
+    let exe = current_exe()?;
+    let appimage = std::env::var_os("APPIMAGE").is_some();
+    let fix = linux_fixes(port, &exe, appimage)
+        .into_iter()
+        .find(|fix| fix.kind == kind)
+        .ok_or("Unknown fix")?;
+    if let Some(reason) = fix.unavailable {
+        return Err(reason);
+    }
+    let output = tokio::process::Command::new("pkexec")
+        .args(&fix.argv)
+        .output()
+        .await
+        .map_err(|e| {
+            format!(
+                "pkexec is not available ({e}). Run this in a terminal instead: sudo {}",
+                fix.command
+            )
+        })?;
+    if !output.status.success() {
+        // 126: the authentication dialog was dismissed.
+        if output.status.code() == Some(126) {
+            return Err("Cancelled".to_string());
+        }
+        return Err(format!(
+            "{} failed: {}",
+            fix.command,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_bind_capability() {
+        let with = "Name:\tzync\nCapEff:\t0000000000000400\n";
+        let without = "Name:\tzync\nCapEff:\t0000000000000000\n";
+        assert!(can_bind_low_ports(with));
+        assert!(!can_bind_low_ports(without));
+        assert!(!can_bind_low_ports("Name:\tzync\n"));
+
+        assert!(needs_elevation_with(80, 1024, false));
+        assert!(!needs_elevation_with(80, 1024, true));
+        assert!(!needs_elevation_with(80, 80, false));
+        assert!(!needs_elevation_with(8080, 1024, false));
+    }
+
+    #[test]
+    fn appimages_cannot_take_file_capabilities() {
+        let fixes = linux_fixes(443, "/tmp/.mount_zync/zync", true);
+        assert_eq!(
+            fixes[0].command,
+            "setcap cap_net_bind_service=+ep /tmp/.mount_zync/zync"
+        );
+        assert!(fixes[0].unavailable.is_some());
+        assert_eq!(
+            fixes[1].command,
+            "sysctl -w net.ipv4.ip_unprivileged_port_start=443"
+        );
+        let installed = linux_fixes(443, "/opt/My Apps/zync", false);
+        assert!(installed[0].unavailable.is_none());
+        assert_eq!(installed[0].argv[2], "/opt/My Apps/zync");
+        assert!(installed[0].command.ends_with("'/opt/My Apps/zync'"));
+    }
+}
@@ -0,0 +1,159 @@
+//! Byte relay for forwarded tunnel connections.
+//!
+//! `tokio::io::copy_bidirectional` allocates a fresh pair of small buffers for
+//! every accepted connection. Bulk forwards (VNC, database dumps through `-L`)
+//! move more data per syscall with larger buffers, and short-lived connections
+//! (SOCKS browsing) avoid the allocation entirely by reusing them. Buffers come
+//! from a bounded process-wide pool sized by the `tunnelRelay` setting.
+
+use serde::Deserialize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{LazyLock, Mutex as StdMutex};
+use tauri::AppHandle;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+const DEFAULT_BUFFER_KIB: usize = 64;
+const DEFAULT_MAX_POOLED_BUFFERS: usize = 32;
+
+static BUFFER_SIZE: AtomicUsize = AtomicUsize::new(DEFAULT_BUFFER_KIB * 1024);
+static MAX_POOLED_BUFFERS: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_POOLED_BUFFERS);
+static POOL: LazyLock<StdMutex<Vec<Box<[u8]>>>> = LazyLock::new(|| StdMutex::new(Vec::new()));
+
+fn lock_pool() -> std::sync::MutexGuard<'static, Vec<Box<[u8]>>> {
+    POOL.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RelayConfig {
+    /// Size of each direction's copy buffer.
+    pub buffer_kib: usize,
+    /// Idle buffers kept for reuse; `0` disables pooling.
+    pub max_pooled_buffers: usize,
+}
+
+impl Default for RelayConfig {
+    fn default() -> Self {
+        Self {
+            buffer_kib: DEFAULT_BUFFER_KIB,
+            max_pooled_buffers: DEFAULT_MAX_POOLED_BUFFERS,
+        }
+    }
+}
+
+/// Apply the `tunnelRelay` setting. Connections already relaying keep their
+/// buffers; pooled buffers of the old size are released.
+pub fn configure(app: &AppHandle) {
+    let config: RelayConfig = crate::commands::read_effective_settings(app)
+        .ok()
+        .and_then(|settings| settings.get("tunnelRelay").cloned())
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default();
+    let buffer_size = config.buffer_kib.clamp(8, 1024) * 1024;
+    let max_pooled = config.max_pooled_buffers.min(256);
+    BUFFER_SIZE.store(buffer_size, Ordering::Relaxed);
+    MAX_POOLED_BUFFERS.store(max_pooled, Ordering::Relaxed);
+
+    let mut pool = lock_pool();
+    pool.retain(|buffer| buffer.len() == buffer_size);
+    pool.truncate(max_pooled);
+}
+
+/// Copy buffer borrowed from the pool; returned on drop if it still fits.
+struct PooledBuffer {
+    buffer: Box<[u8]>,
+}
+
+impl PooledBuffer {
+    fn acquire() -> Self {
+        let size = BUFFER_SIZE.load(Ordering::Relaxed);
+        let reused = lock_pool().pop().filter(|buffer| buffer.len() == size);
+        Self {
+            buffer: reused.unwrap_or_else(|| vec![0u8; size].into_boxed_slice()),
+        }
+    }
+}
+
+impl Drop for PooledBuffer {
+    fn drop(&mut self) {
+        let buffer = std::mem::take(&mut self.buffer);
+        if buffer.len() != BUFFER_SIZE.load(Ordering::Relaxed) {
+            return;
+        }
+        let mut pool = lock_pool();
+        if pool.len() < MAX_POOLED_BUFFERS.load(Ordering::Relaxed) {
+            pool.push(buffer);
+        }
+    }
+}
+
+/// Copy `reader` into `writer` until EOF, then shut the writer down so the
+/// peer sees the half-close.
+async fn pump<R, W>(reader: &mut R, writer: &mut W) -> std::io::Result<u64>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut buffer = PooledBuffer::acquire();
+    let mut total = 0u64;
+    loop {
+        let read = reader.read(&mut buffer.buffer).await?;
+        if read == 0 {
+            break;
+        }
+        writer.write_all(&buffer.buffer[..read]).await?;
+        writer.flush().await?;
+        total += read as u64;
+    }
+    writer.shutdown().await?;
+    Ok(total)
+}
+
+/// Drop-in replacement for `tokio::io::copy_bidirectional` using pooled
+/// buffers. Returns `(bytes a→b, bytes b→a)`.
+pub(crate) async fn copy_bidirectional<A, B>(a: &mut A, b: &mut B) -> std::io::Result<(u64, u64)>
+where
+    A: AsyncRead + AsyncWrite + Unpin,
+    B: AsyncRead + AsyncWrite + Unpin,
+{
+    let (mut a_read, mut a_write) = tokio::io::split(a);
+    let (mut b_read, mut b_write) = tokio::io::split(b);
+    tokio::try_join!(
+        pump(&mut a_read, &mut b_write),
+        pump(&mut b_read, &mut a_write)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn relays_both_directions_and_returns_buffers_to_the_pool() {
+        let (mut client, mut near) = tokio::io::duplex(1024);
+        let (mut far, mut server) = tokio::io::duplex(1024);
+        let relay = tokio::spawn(async move { copy_bidirectional(&mut near, &mut far).await });
+
+        client.write_all(b"ping").await.expect("client write");
+        client.shutdown().await.expect("client shutdown");
+        let mut received = Vec::new();
+        server
+            .read_to_end(&mut received)
+            .await
+            .expect("server read");
+        assert_eq!(received, b"ping");
+
+        server.write_all(b"pong!").await.expect("server write");
+        server.shutdown().await.expect("server shutdown");
+        let mut replied = Vec::new();
+        client.read_to_end(&mut replied).await.expect("client read");
+        assert_eq!(replied, b"pong!");
+
+        let counts = relay.await.expect("join").expect("relay");
+        assert_eq!(counts, (4, 5));
+        let size = BUFFER_SIZE.load(Ordering::Relaxed);
+        let pool = lock_pool();
+        assert!(!pool.is_empty());
+        assert!(pool.iter().all(|buffer| buffer.len() == size));
+    }
+}
@@ -0,0 +1,168 @@
+//! One-click VNC/RDP: forward the desktop port to a free local port, start
+//! the platform's viewer against it and drop the forward when the viewer
+//! exits.
+//!
+//! The viewer is waited on, so on macOS it goes through `open -W`, which
+//! returns when Screen Sharing / Windows App quits rather than right away.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RemoteDesktopKind {
+    Vnc,
+    Rdp,
+}
+
+impl RemoteDesktopKind {
+    pub fn default_port(self) -> u16 {
+        match self {
+            Self::Vnc => 5900,
+            Self::Rdp => 3389,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Vnc => "VNC",
+            Self::Rdp => "RDP",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteDesktopSession {
+    /// Runtime id of the temporary forward, stopped when the viewer exits.
+    pub runtime_id: String,
+    pub local_port: u16,
+    /// Command line the viewer was started with, for the toast.
+    pub viewer: String,
+}
+
+/// Program and arguments that open `kind` against `localhost:port`.
+pub(crate) fn viewer_command(kind: RemoteDesktopKind, port: u16) -> (String, Vec<String>) {
+    let address = format!("localhost:{port}");
+    let (program, args): (&str, Vec<String>) = match kind {
+        RemoteDesktopKind::Rdp if cfg!(windows) => ("mstsc", vec![format!("/v:{address}")]),
+        RemoteDesktopKind::Rdp if cfg!(target_os = "macos") => (
+            "open",
+            vec![
+                "-W".to_string(),
+                format!("rdp://full%20address=s:{address}"),
+            ],
+        ),
+        RemoteDesktopKind::Rdp => ("xfreerdp", vec![format!("/v:{address}")]),
+        RemoteDesktopKind::Vnc if cfg!(target_os = "macos") => {
+            ("open", vec!["-W".to_string(), format!("vnc://{address}")])
+        }
+        // TigerVNC/TightVNC take a port after a double colon; one colon is a display number.
+        RemoteDesktopKind::Vnc => ("vncviewer", vec![format!("localhost::{port}")]),
+    };
+    (program.to_string(), args)
+}
+
+/// A port nothing listens on right now, for the temporary forward.
+async fn free_local_port() -> Result<u16, String> {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .map_err(|error| format!("No free local port: {error}"))?;
+    listener
+        .local_addr()
+        .map(|address| address.port())
+        .map_err(|error| error.to_string())
+}
+
+/// Start the forward and the viewer; the forward is torn down when the
+/// viewer exits (or fails to start).
+pub(crate) async fn launch(
+    state: &crate::commands::AppState,
+    connection_id: &str,
+    kind: RemoteDesktopKind,
+    remote_host: &str,
+    remote_port: u16,
+) -> Result<RemoteDesktopSession, String> {
+    let session = {
+        let connections = state.connections.lock().await;
+        connections
+            .get(connection_id)
+            .and_then(|c| c.session.clone())
+            .ok_or_else(|| format!("Connection {connection_id} not found or session closed"))?
+    };
+    let local_port = free_local_port().await?;
+    let runtime_id = format!(
+        "desktop:{}:{}:{}:{}",
+        connection_id,
+        local_port,
+        remote_host.replace(':', "_"),
+        remote_port
+    );
+    let manager = state.tunnel_manager.clone();
+    manager
+        .start_local_forwarding(
+            session,
+            connection_id.to_string(),
+            runtime_id.clone(),
+            "127.0.0.1".to_string(),
+            local_port,
+            remote_host.to_string(),
+            remote_port,
+            super::LocalListenerLayers::default(),
+        )
+        .await
+        .map_err(|error| error.to_string())?;
+
+    let (program, args) = viewer_command(kind, local_port);
+    let viewer = format!("{program} {}", args.join(" "));
+    let mut child = match tokio::process::Command::new(&program).args(&args).spawn() {
+        Ok(child) => child,
+        Err(error) => {
+            manager.stop_local_runtime(&runtime_id).await;
+            return Err(format!(
+                "Could not start the {} viewer ({program}): {error}",
+                kind.label()
+            ));
+        }
+    };
+    let task_runtime_id = runtime_id.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(error) = child.wait().await {
+            eprintln!("[TUNNEL][DESKTOP] Waiting for viewer: {error}");
+        }
+        println!("[TUNNEL][DESKTOP] Viewer exited, stopping {task_runtime_id}");
+        manager.stop_local_runtime(&task_runtime_id).await;
+    });
+
+    Ok(RemoteDesktopSession {
+        runtime_id,
+        local_port,
+        viewer,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn viewer_points_at_the_forwarded_port() {
+        let (_, args) = viewer_command(RemoteDesktopKind::Rdp, 49152);
+        assert!(args.iter().any(|arg| arg.ends_with("localhost:49152")));
+
+        let (program, args) = viewer_command(RemoteDesktopKind::Vnc, 49153);
+        if cfg!(target_os = "macos") {
+            assert_eq!(program, "open");
+            assert_eq!(args, ["-W", "vnc://localhost:49153"]);
+        } else {
+            assert_eq!(program, "vncviewer");
+            assert_eq!(args, ["localhost::49153"]);
+        }
+    }
+
+    #[test]
+    fn kind_uses_lowercase_names() {
+        let kind: RemoteDesktopKind = serde_json::from_str("\"rdp\"").unwrap();
+        assert_eq!(kind, RemoteDesktopKind::Rdp);
+        assert_eq!(RemoteDesktopKind::Vnc.default_port(), 5900);
+    }
+}
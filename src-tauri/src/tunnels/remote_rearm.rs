@@ -0,0 +1,302 @@
+//! Background task: re-register remote forwards the server no longer holds.
+//!
+//! A remote forward belongs to the SSH session that requested it. When that
+//! session is replaced underneath a running tunnel (a reactive reconnect after
+//! sshd restarted, say) the server-side listener is gone while
+//! `remote_forwards` still lists it. The watcher notices on `Reconnected` and
+//! on every probe tick, re-issues `tcpip_forward` on the current session with
+//! exponential backoff and jitter, and reports progress as
+//! `tunnel:status-change` (`reconnecting`, then `active` or `error`).
+
+use super::commands::{emit_tunnel_status, TunnelStatusChange};
+use super::manager::{probe_ssh_session, SESSION_PROBE_INTERVAL_SECS, SSH_SESSION_PROBE_TIMEOUT};
+use super::runtime_notice::{is_forward_request_denied, remote_forward_rejected_error};
+use crate::commands::{get_data_dir, AppState};
+use crate::events::ConnectionEvent;
+use crate::ssh::Client;
+use rand_core::{OsRng, RngCore};
+use russh::client::Handle;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::Mutex;
+
+const MAX_ATTEMPTS: u32 = 8;
+const BASE_DELAY: Duration = Duration::from_secs(1);
+const MAX_DELAY: Duration = Duration::from_secs(60);
+
+/// Wait before attempt `attempt` (from 0): doubling from one second up to a
+/// minute, with the upper half spread by `jitter` (0..=1) so forwards on a
+/// restarted server don't all retry in lockstep.
+fn backoff_delay(attempt: u32, jitter: f64) -> Duration {
+    let ceiling = BASE_DELAY
+        .saturating_mul(1 << attempt.min(16))
+        .min(MAX_DELAY);
+    let half = ceiling / 2;
+    half + half.mul_f64(jitter.clamp(0.0, 1.0))
+}
+
+fn jitter() -> f64 {
+    f64::from(OsRng.next_u32()) / f64::from(u32::MAX)
+}
+
+/// `{connection_id}:{remote_port}` back into its parts.
+fn split_map_key(map_key: &str) -> Option<(&str, u16)> {
+    let (connection_id, port) = map_key.rsplit_once(':')?;
+    Some((connection_id, port.parse().ok()?))
+}
+
+struct DroppedForward {
+    map_key: String,
+    connection_id: String,
+    remote_port: u16,
+    bind_address: String,
+}
+
+type Session = Arc<Mutex<Handle<Client>>>;
+
+async fn current_session(state: &AppState, connection_id: &str) -> Option<Session> {
+    state
+        .connections
+        .lock()
+        .await
+        .get(connection_id)
+        .and_then(|handle| handle.session.clone())
+}
+
+/// Saved tunnel ids behind a forward; ad-hoc forwards have none.
+fn saved_tunnel_ids(app: &AppHandle, forward: &DroppedForward) -> Vec<String> {
+    let tunnels_path = get_data_dir(app).join("tunnels.json");
+    crate::sync::domain_tunnels::load_saved_tunnels(&tunnels_path)
+        .map(|saved| {
+            saved
+                .tunnels
+                .into_iter()
+                .filter(|tunnel| {
+                    tunnel.tunnel_type == "remote"
+                        && tunnel.connection_id == forward.connection_id
+                        && tunnel.remote_port == forward.remote_port
+                })
+                .map(|tunnel| tunnel.id)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn emit_status(app: &AppHandle, forward: &DroppedForward, status: &str, error: Option<String>) {
+    for id in saved_tunnel_ids(app, forward) {
+        emit_tunnel_status(
+            app,
+            &forward.connection_id,
+            TunnelStatusChange {
+                id,
+                status: status.to_string(),
+                error: error.clone(),
+            },
+        );
+    }
+}
+
+/// Forwards (optionally of one connection) not registered on their
+/// connection's current session. Dead sessions go to the failure watcher.
+async fn find_dropped(state: &AppState, only: Option<&str>, probe: bool) -> Vec<DroppedForward> {
+    let manager = &state.tunnel_manager;
+    let mut by_connection: HashMap<String, Vec<DroppedForward>> = HashMap::new();
+    for (map_key, (_, _, bind_address)) in manager.remote_forwards.lock().await.iter() {
+        let Some((connection_id, remote_port)) = split_map_key(map_key) else {
+            continue;
+        };
+        if only.is_some_and(|only| only != connection_id) {
+            continue;
+        }
+        by_connection
+            .entry(connection_id.to_string())
+            .or_default()
+            .push(DroppedForward {
+                map_key: map_key.clone(),
+                connection_id: connection_id.to_string(),
+                remote_port,
+                bind_address: bind_address.clone(),
+            });
+    }
+
+    let mut dropped = Vec::new();
+    for (connection_id, forwards) in by_connection {
+        // No session: disconnected or mid-reconnect, which stop or re-arm it.
+        let Some(session) = current_session(state, &connection_id).await else {
+            continue;
+        };
+        let mut live = 0;
+        {
+            let registered_on = manager.remote_sessions.lock().await;
+            for forward in forwards {
+                // Unrecorded forwards are still being started.
+                match registered_on.get(&forward.map_key) {
+                    Some(registered)
+                        if std::ptr::eq(registered.as_ptr(), Arc::as_ptr(&session)) =>
+                    {
+                        live += 1
+                    }
+                    Some(_) => dropped.push(forward),
+                    None => {}
+                }
+            }
+        }
+        // Remote-only connections have no listener probing their session.
+        if probe && live > 0 && !probe_ssh_session(&connection_id, &session).await {
+            println!(
+                "[TUNNEL] SSH session probe failed for {}; stopping tunnels",
+                connection_id
+            );
+            manager.report_session_failure(&connection_id);
+        }
+    }
+    dropped
+}
+
+async fn rearm(app: &AppHandle, forward: &DroppedForward) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let manager = &state.tunnel_manager;
+    println!(
+        "[TUNNEL] Remote forward {} was dropped by the server; re-registering",
+        forward.map_key
+    );
+    emit_status(app, forward, "reconnecting", None);
+
+    let mut last_error = String::from("no live session");
+    for attempt in 0..MAX_ATTEMPTS {
+        tokio::time::sleep(backoff_delay(attempt, jitter())).await;
+        // Stopped while we waited.
+        if !manager
+            .remote_forwards
+            .lock()
+            .await
+            .contains_key(&forward.map_key)
+        {
+            return;
+        }
+        let Some(session) = current_session(&state, &forward.connection_id).await else {
+            continue;
+        };
+        if manager.remote_forward_on(&forward.map_key, &session).await {
+            emit_status(app, forward, "active", None);
+            return;
+        }
+        let registered = tokio::time::timeout(SSH_SESSION_PROBE_TIMEOUT, async {
+            let mut session_handle = session.lock().await;
+            session_handle
+                .tcpip_forward(forward.bind_address.clone(), u32::from(forward.remote_port))
+                .await
+        })
+        .await;
+        match registered {
+            Ok(Ok(_)) => {
+                manager
+                    .remote_sessions
+                    .lock()
+                    .await
+                    .insert(forward.map_key.clone(), Arc::downgrade(&session));
+                println!(
+                    "[TUNNEL] Remote forward {} re-registered (attempt {})",
+                    forward.map_key,
+                    attempt + 1
+                );
+                emit_status(app, forward, "active", None);
+                return;
+            }
+            Ok(Err(error)) if is_forward_request_denied(&error) => {
+                last_error =
+                    remote_forward_rejected_error(&forward.bind_address, forward.remote_port)
+            }
+            Ok(Err(error)) => last_error = error.to_string(),
+            Err(_) => last_error = "timed out".to_string(),
+        }
+        eprintln!(
+            "[TUNNEL] Re-registering {} failed (attempt {}/{}): {}",
+            forward.map_key,
+            attempt + 1,
+            MAX_ATTEMPTS,
+            last_error
+        );
+    }
+
+    manager.forget_remote_forward(&forward.map_key).await;
+    emit_status(
+        app,
+        forward,
+        "error",
+        Some(format!(
+            "Server dropped remote port {} and re-registering it failed: {}",
+            forward.remote_port, last_error
+        )),
+    );
+}
+
+async fn check(app: &AppHandle, in_flight: &Arc<Mutex<HashSet<String>>>, only: Option<&str>) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    for forward in find_dropped(&state, only, only.is_none()).await {
+        if !in_flight.lock().await.insert(forward.map_key.clone()) {
+            continue;
+        }
+        let app = app.clone();
+        let in_flight = in_flight.clone();
+        tauri::async_runtime::spawn(async move {
+            rearm(&app, &forward).await;
+            in_flight.lock().await.remove(&forward.map_key);
+        });
+    }
+}
+
+pub fn start(app: AppHandle) {
+    let mut events = crate::events::subscribe();
+    let in_flight: Arc<Mutex<HashSet<String>>> = Arc::new(Mutex::new(HashSet::new()));
+    crate::crash_reports::spawn_supervised("remote-forward-rearm", async move {
+        let mut ticks = tokio::time::interval(Duration::from_secs(SESSION_PROBE_INTERVAL_SECS));
+        ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            let reconnected = tokio::select! {
+                _ = ticks.tick() => None,
+                event = events.recv() => match event {
+                    Ok(envelope) if envelope.event == ConnectionEvent::Reconnected => {
+                        Some(envelope.connection_id)
+                    }
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(skipped)) => {
+                        eprintln!("[TUNNEL] Event subscriber lagged; {skipped} events dropped");
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+            };
+            check(&app, &in_flight, reconnected.as_deref()).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap_with_jitter() {
+        assert_eq!(backoff_delay(0, 0.0), Duration::from_millis(500));
+        assert_eq!(backoff_delay(0, 1.0), Duration::from_secs(1));
+        assert_eq!(backoff_delay(3, 0.0), Duration::from_secs(4));
+        assert_eq!(backoff_delay(3, 0.5), Duration::from_secs(6));
+        assert_eq!(backoff_delay(20, 1.0), MAX_DELAY);
+        assert_eq!(backoff_delay(20, 0.0), MAX_DELAY / 2);
+    }
+
+    #[test]
+    fn splits_map_keys() {
+        let key = crate::tunnels::remote_forward_map_key("conn:with:colons", 8080);
+        assert_eq!(split_map_key(&key), Some(("conn:with:colons", 8080)));
+        assert_eq!(split_map_key("no-port"), None);
+    }
+}
@@ -0,0 +1,198 @@
+//! Bring a connection's tunnels back after its transport dropped and it
+//! reconnected: the ones that were running at the drop (remembered on the
+//! [`TunnelManager`](super::TunnelManager) before they were stopped) and its
+//! auto-start tunnels. Each tunnel reports `tunnel:restore-progress` as it goes,
+//! so the reconnect toast can say what came back.
+
+use super::commands::{running_tunnel_ids, tunnel_start};
+use crate::commands::{get_data_dir, AppState};
+use crate::types::SavedTunnel;
+use serde::Serialize;
+use std::collections::HashSet;
+use tauri::{AppHandle, Emitter, Manager};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TunnelRestoreProgress {
+    pub connection_id: String,
+    pub tunnel_id: String,
+    pub name: String,
+    /// `starting`, `active`, `skipped` (outside its schedule) or `error`.
+    pub status: &'static str,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TunnelRestoreFailure {
+    pub tunnel_id: String,
+    pub name: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TunnelRestoreSummary {
+    pub restored: Vec<String>,
+    pub skipped: Vec<String>,
+    pub failed: Vec<TunnelRestoreFailure>,
+}
+
+/// Remembered tunnels first, then auto-start ones, each once. Ids that are no
+/// longer saved for `connection_id` are dropped.
+fn restore_plan<'a>(
+    connection_id: &str,
+    remembered: &[String],
+    saved: &'a [SavedTunnel],
+) -> Vec<&'a SavedTunnel> {
+    let ours: Vec<&SavedTunnel> = saved
+        .iter()
+        .filter(|tunnel| tunnel.connection_id == connection_id)
+        .collect();
+    let wanted = remembered
+        .iter()
+        .filter_map(|id| ours.iter().copied().find(|tunnel| &tunnel.id == id))
+        .chain(
+            ours.iter()
+                .copied()
+                .filter(|tunnel| tunnel.auto_start.unwrap_or(false)),
+        );
+    let mut plan: Vec<&SavedTunnel> = Vec::new();
+    for tunnel in wanted {
+        if !plan.iter().any(|planned| planned.id == tunnel.id) {
+            plan.push(tunnel);
+        }
+    }
+    plan
+}
+
+fn report(app: &AppHandle, tunnel: &SavedTunnel, status: &'static str, error: Option<String>) {
+    let _ = app.emit(
+        "tunnel:restore-progress",
+        TunnelRestoreProgress {
+            connection_id: tunnel.connection_id.clone(),
+            tunnel_id: tunnel.id.clone(),
+            name: tunnel.name.clone(),
+            status,
+            error,
+        },
+    );
+}
+
+/// Remember which of `connection_id`'s tunnels are running, before a dropped
+/// transport stops them.
+pub(crate) async fn remember_running(app: &AppHandle, state: &AppState, connection_id: &str) {
+    let file_path = get_data_dir(app).join("tunnels.json");
+    let Ok(saved) = crate::sync::domain_tunnels::load_saved_tunnels(&file_path) else {
+        return;
+    };
+    let running: HashSet<String> = running_tunnel_ids(app, state).await.into_iter().collect();
+    for tunnel in saved
+        .tunnels
+        .iter()
+        .filter(|tunnel| tunnel.connection_id == connection_id && running.contains(&tunnel.id))
+    {
+        state
+            .tunnel_manager
+            .remember_for_restore(connection_id, &tunnel.id)
+            .await;
+    }
+}
+
+/// Start the tunnels [`restore_plan`] picks for `connection_id` that aren't
+/// running yet. Tunnels outside their schedule are left to the scheduler.
+pub(crate) async fn restore_tunnels_for_connection(
+    app: &AppHandle,
+    state: &AppState,
+    connection_id: &str,
+) -> Result<TunnelRestoreSummary, String> {
+    let remembered = state.tunnel_manager.take_for_restore(connection_id).await;
+    let file_path = get_data_dir(app).join("tunnels.json");
+    if !file_path.exists() {
+        return Ok(TunnelRestoreSummary::default());
+    }
+    let saved =
+        crate::sync::domain_tunnels::load_saved_tunnels(&file_path).map_err(|e| e.to_string())?;
+    let running: HashSet<String> = running_tunnel_ids(app, state).await.into_iter().collect();
+
+    let mut summary = TunnelRestoreSummary::default();
+    for tunnel in restore_plan(connection_id, &remembered, &saved.tunnels) {
+        if running.contains(&tunnel.id) {
+            continue;
+        }
+        if super::schedule::ensure_inside(&tunnel.id, tunnel.schedule.as_ref()).is_err() {
+            report(app, tunnel, "skipped", None);
+            summary.skipped.push(tunnel.id.clone());
+            continue;
+        }
+        report(app, tunnel, "starting", None);
+        match tunnel_start(app.clone(), tunnel.id.clone(), app.state::<AppState>()).await {
+            Ok(_) => {
+                report(app, tunnel, "active", None);
+                summary.restored.push(tunnel.id.clone());
+            }
+            Err(error) => {
+                eprintln!("[TUNNEL][RESTORE] {}: {error}", tunnel.id);
+                report(app, tunnel, "error", Some(error.clone()));
+                summary.failed.push(TunnelRestoreFailure {
+                    tunnel_id: tunnel.id.clone(),
+                    name: tunnel.name.clone(),
+                    error,
+                });
+            }
+        }
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tunnel(id: &str, connection_id: &str, auto_start: bool) -> SavedTunnel {
+        SavedTunnel {
+            id: id.to_string(),
+            connection_id: connection_id.to_string(),
+            name: id.to_string(),
+            tunnel_type: "local".to_string(),
+            local_port: 8080,
+            remote_host: "127.0.0.1".to_string(),
+            remote_port: 80,
+            auto_start: Some(auto_start),
+            ..Default::default()
+        }
+    }
+
+    fn ids(plan: Vec<&SavedTunnel>) -> Vec<&str> {
+        plan.into_iter().map(|tunnel| tunnel.id.as_str()).collect()
+    }
+
+    #[test]
+    fn plan_puts_remembered_first_then_auto_start_once() {
+        let saved = vec![
+            tunnel("auto", "conn", true),
+            tunnel("was-running", "conn", false),
+            tunnel("both", "conn", true),
+            tunnel("idle", "conn", false),
+        ];
+        let remembered = vec!["both".to_string(), "was-running".to_string()];
+        assert_eq!(
+            ids(restore_plan("conn", &remembered, &saved)),
+            vec!["both", "was-running", "auto"]
+        );
+    }
+
+    #[test]
+    fn plan_drops_deleted_and_other_connections_tunnels() {
+        let saved = vec![
+            tunnel("mine", "conn", false),
+            tunnel("theirs", "other", true),
+        ];
+        let remembered = vec![
+            "deleted".to_string(),
+            "mine".to_string(),
+            "theirs".to_string(),
+        ];
+        assert_eq!(ids(restore_plan("conn", &remembered, &saved)), vec!["mine"]);
+    }
+}
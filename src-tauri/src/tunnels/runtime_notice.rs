@@ -0,0 +1,149 @@
+//! Per-tunnel runtime notices raised from inside forwarding tasks.
+//!
+//! Forwarding tasks only know their runtime id; the watcher resolves that back to
+//! saved tunnels, tears the listener down, and emits `tunnel:status-change`.
+
+use super::commands::{emit_tunnel_status, TunnelStatusChange};
+use super::tunnel_runtime_id;
+use crate::commands::{get_data_dir, AppState};
+use tauri::{AppHandle, Manager};
+use tokio::sync::mpsc;
+
+/// Reported per tunnel when sshd rejects `direct-tcpip` channels by policy.
+pub const FORWARDING_PROHIBITED_ERROR: &str =
+    "server disallows TCP forwarding (AllowTcpForwarding no)";
+
+#[derive(Debug, Clone)]
+pub struct TunnelRuntimeNotice {
+    pub runtime_id: String,
+    pub error: String,
+}
+
+pub type TunnelNoticeSender = mpsc::UnboundedSender<TunnelRuntimeNotice>;
+
+pub fn tunnel_notice_channel() -> (TunnelNoticeSender, mpsc::UnboundedReceiver<TunnelRuntimeNotice>) {
+    mpsc::unbounded_channel()
+}
+
+/// True when the server answered a channel open with SSH_OPEN_ADMINISTRATIVELY_PROHIBITED.
+///
+/// russh surfaces this as `ChannelOpenFailure(AdministrativelyProhibited)`; the
+/// OpenSSH wording is matched too so errors relayed from jump hosts are caught.
+pub fn is_forwarding_prohibited(error: &impl std::fmt::Display) -> bool {
+    let message = error.to_string().to_ascii_lowercase();
+    message.contains("administrativelyprohibited") || message.contains("administratively prohibited")
+}
+
+/// True when the server answered a `tcpip-forward` request with
+/// SSH_MSG_REQUEST_FAILURE, which russh surfaces as `RequestDenied`.
+pub fn is_forward_request_denied(error: &impl std::fmt::Display) -> bool {
+    let message = error.to_string().to_ascii_lowercase();
+    message.contains("requestdenied") || message.contains("request denied")
+}
+
+/// Error for a rejected remote forward, naming the sshd settings most
+/// likely behind it. The server gives no reason, so these are candidates.
+pub fn remote_forward_rejected_error(bind_address: &str, remote_port: u16) -> String {
+    let mut causes = vec![
+        format!("port {remote_port} is already in use on the server"),
+        "sshd has AllowTcpForwarding set to no or local".to_string(),
+        format!("PermitListen in sshd_config does not allow port {remote_port}"),
+    ];
+    let loopback = matches!(bind_address, "localhost" | "127.0.0.1" | "::1" | "[::1]");
+    if !loopback {
+        causes.push(format!(
+            "binding {bind_address} needs GatewayPorts yes or clientspecified"
+        ));
+    }
+    if remote_port < 1024 {
+        causes.push("ports below 1024 need root on the server".to_string());
+    }
+    format!(
+        "Server rejected remote forward on {bind_address}:{remote_port}. Likely: {}",
+        causes.join("; ")
+    )
+}
+
+pub fn spawn_tunnel_notice_watcher(
+    app: AppHandle,
+    mut receiver: mpsc::UnboundedReceiver<TunnelRuntimeNotice>,
+) {
+    tauri::async_runtime::spawn(async move {
+        while let Some(notice) = receiver.recv().await {
+            let Some(state) = app.try_state::<AppState>() else {
+                continue;
+            };
+
+            // Several queued connections can fail at once; only the first notice acts.
+            let still_running = state
+                .tunnel_manager
+                .local_listeners
+                .lock()
+                .await
+                .contains_key(&notice.runtime_id);
+            if !still_running {
+                continue;
+            }
+
+            eprintln!(
+                "[TUNNEL] Stopping {}: {}",
+                notice.runtime_id, notice.error
+            );
+
+            let tunnels_path = get_data_dir(&app).join("tunnels.json");
+            let matching = crate::sync::domain_tunnels::load_saved_tunnels(&tunnels_path)
+                .map(|saved| {
+                    saved
+                        .tunnels
+                        .into_iter()
+                        .filter(|tunnel| tunnel_runtime_id(tunnel) == notice.runtime_id)
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+
+            // Ad-hoc tunnels (tunnel_start_local) have no saved entry — stop by runtime id.
+            state.tunnel_manager.stop_local_runtime(&notice.runtime_id).await;
+
+            for tunnel in matching {
+                emit_tunnel_status(
+                    &app,
+                    &tunnel.connection_id,
+                    TunnelStatusChange {
+                        id: tunnel.id.clone(),
+                        status: "error".to_string(),
+                        error: Some(notice.error.clone()),
+                    },
+                );
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_forward_request_denied, is_forwarding_prohibited, remote_forward_rejected_error};
+
+    #[test]
+    fn detects_russh_and_openssh_wording() {
+        assert!(is_forwarding_prohibited(&"ChannelOpenFailure(AdministrativelyProhibited)"));
+        assert!(is_forwarding_prohibited(
+            &"channel 3: open failed: administratively prohibited: open failed"
+        ));
+    }
+
+    #[test]
+    fn ignores_connect_failures() {
+        assert!(!is_forwarding_prohibited(&"ChannelOpenFailure(ConnectFailed)"));
+    }
+
+    #[test]
+    fn explains_rejected_remote_forwards() {
+        assert!(is_forward_request_denied(&"RequestDenied"));
+        assert!(!is_forward_request_denied(&"Channel send error"));
+
+        let loopback = remote_forward_rejected_error("127.0.0.1", 8080);
+        assert!(loopback.contains("AllowTcpForwarding") && !loopback.contains("GatewayPorts"));
+        let public = remote_forward_rejected_error("0.0.0.0", 80);
+        assert!(public.contains("GatewayPorts") && public.contains("below 1024"));
+    }
+}
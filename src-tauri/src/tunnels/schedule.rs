@@ -0,0 +1,250 @@
+//! Maintenance windows for tunnels: a saved tunnel with a `schedule` only
+//! runs inside its weekly windows.
+//!
+//! A background task checks every scheduled tunnel twice a minute. When a
+//! window opens it starts the tunnel if its connection is up; outside the
+//! windows it stops the tunnel, and `tunnel_start` refuses it with
+//! `OUTSIDE_SCHEDULE:`. Starts and stops go through the usual commands, so
+//! they show up as `tunnel:status-change`. `tunnel_schedule_status` reports
+//! whether each scheduled tunnel is inside a window and when that changes.
+
+use super::commands::{running_tunnel_ids, tunnel_start, tunnel_stop};
+use crate::commands::{get_data_dir, AppState};
+use crate::types::TunnelSchedule;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+/// Machine-readable prefix — must stay in sync with the TS tunnel actions.
+pub const OUTSIDE_SCHEDULE_PREFIX: &str = "OUTSIDE_SCHEDULE:";
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+const DAY_MS: i64 = 86_400_000;
+const MINUTES_PER_DAY: u16 = 24 * 60;
+/// Days after today that are searched for the next window.
+const HORIZON_DAYS: i64 = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleStatus {
+    pub tunnel_id: String,
+    /// Inside one of the windows right now.
+    pub active: bool,
+    /// When `active` flips next; `None` when it never does (every day, all day).
+    pub next_transition_ms: Option<u64>,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+pub fn validate(schedule: &TunnelSchedule) -> Result<(), String> {
+    if schedule.start_minute >= MINUTES_PER_DAY || schedule.end_minute >= MINUTES_PER_DAY {
+        return Err("Schedule times must be between 00:00 and 23:59".to_string());
+    }
+    if schedule.days.iter().any(|day| *day > 6) {
+        return Err("Schedule days must be 0 (Monday) through 6 (Sunday)".to_string());
+    }
+    if schedule.utc_offset_minutes.abs() > 14 * 60 {
+        return Err("Schedule UTC offset must be within ±14 hours".to_string());
+    }
+    Ok(())
+}
+
+/// The schedule's windows that overlap the days around `now_ms`, merged
+/// where they touch, as `[start, end)` in epoch milliseconds.
+fn windows(schedule: &TunnelSchedule, now_ms: i64) -> Vec<(i64, i64)> {
+    let offset_ms = i64::from(schedule.utc_offset_minutes) * 60_000;
+    let today = (now_ms + offset_ms).div_euclid(DAY_MS);
+    let length_minutes = match schedule.end_minute.cmp(&schedule.start_minute) {
+        std::cmp::Ordering::Greater => schedule.end_minute - schedule.start_minute,
+        _ => MINUTES_PER_DAY - schedule.start_minute + schedule.end_minute,
+    };
+    let mut merged: Vec<(i64, i64)> = Vec::new();
+    for day in today - 1..=today + HORIZON_DAYS {
+        // Day 0 of the epoch was a Thursday.
+        let weekday = (day + 3).rem_euclid(7) as u8;
+        if !schedule.days.is_empty() && !schedule.days.contains(&weekday) {
+            continue;
+        }
+        let start = day * DAY_MS + i64::from(schedule.start_minute) * 60_000 - offset_ms;
+        let end = start + i64::from(length_minutes) * 60_000;
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+pub fn status(tunnel_id: &str, schedule: &TunnelSchedule, now_ms: u64) -> ScheduleStatus {
+    let now = now_ms as i64;
+    let windows = windows(schedule, now);
+    let horizon_end = windows.last().map(|window| window.1);
+    let current = windows
+        .iter()
+        .find(|window| window.0 <= now && now < window.1);
+    let next_transition = match current {
+        // A window running to the end of the search never closes.
+        Some(window) => Some(window.1).filter(|end| Some(*end) != horizon_end),
+        None => windows
+            .iter()
+            .map(|window| window.0)
+            .find(|start| *start > now),
+    };
+    ScheduleStatus {
+        tunnel_id: tunnel_id.to_string(),
+        active: current.is_some(),
+        next_transition_ms: next_transition.map(|at| at as u64),
+    }
+}
+
+/// `Err` for `tunnel_start` when the tunnel is scheduled off right now.
+pub fn ensure_inside(tunnel_id: &str, schedule: Option<&TunnelSchedule>) -> Result<(), String> {
+    match schedule {
+        Some(schedule) if !status(tunnel_id, schedule, now_ms()).active => Err(format!(
+            "{OUTSIDE_SCHEDULE_PREFIX}This tunnel only runs inside its scheduled windows"
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Status of every saved tunnel that has a schedule.
+pub(crate) fn statuses(app: &AppHandle) -> Result<Vec<ScheduleStatus>, String> {
+    let file_path = get_data_dir(app).join("tunnels.json");
+    let saved = crate::sync::domain_tunnels::load_saved_tunnels(&file_path)
+        .map_err(|error| error.to_string())?;
+    let now = now_ms();
+    Ok(saved
+        .tunnels
+        .iter()
+        .filter_map(|tunnel| {
+            let schedule = tunnel.schedule.as_ref()?;
+            Some(status(&tunnel.id, schedule, now))
+        })
+        .collect())
+}
+
+/// One pass: start tunnels whose window just opened, stop those outside their windows.
+async fn enforce(app: &AppHandle, inside: &mut HashMap<String, bool>) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let file_path = get_data_dir(app).join("tunnels.json");
+    let Ok(saved) = crate::sync::domain_tunnels::load_saved_tunnels(&file_path) else {
+        return;
+    };
+    let running: HashSet<String> = running_tunnel_ids(app, &state).await.into_iter().collect();
+    let live: HashSet<String> = {
+        let connections = state.connections.lock().await;
+        connections
+            .iter()
+            .filter(|(_, handle)| handle.session.is_some())
+            .map(|(id, _)| id.clone())
+            .collect()
+    };
+    let now = now_ms();
+    let mut seen = HashSet::new();
+    for tunnel in &saved.tunnels {
+        let Some(schedule) = &tunnel.schedule else {
+            continue;
+        };
+        seen.insert(tunnel.id.clone());
+        let active = status(&tunnel.id, schedule, now).active;
+        let opened = inside.insert(tunnel.id.clone(), active) == Some(false) && active;
+        let is_running = running.contains(&tunnel.id);
+        let result = if opened && !is_running && live.contains(&tunnel.connection_id) {
+            tunnel_start(app.clone(), tunnel.id.clone(), app.state::<AppState>())
+                .await
+                .map(|_| ())
+        } else if !active && is_running {
+            tunnel_stop(app.clone(), tunnel.id.clone(), app.state::<AppState>()).await
+        } else {
+            Ok(())
+        };
+        if let Err(error) = result {
+            eprintln!("[TUNNEL][SCHEDULE] {}: {error}", tunnel.id);
+        }
+    }
+    inside.retain(|id, _| seen.contains(id));
+}
+
+pub fn start(app: AppHandle) {
+    crate::crash_reports::spawn_supervised("tunnel-schedule", async move {
+        let mut inside = HashMap::new();
+        let mut ticks = tokio::time::interval(CHECK_INTERVAL);
+        ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticks.tick().await;
+            enforce(&app, &mut inside).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: u64 = 60_000;
+    /// Monday 2024-01-01 00:00 UTC.
+    const MONDAY: u64 = 1_704_067_200_000;
+
+    fn weekdays_nine_to_five() -> TunnelSchedule {
+        TunnelSchedule {
+            days: vec![0, 1, 2, 3, 4],
+            start_minute: 9 * 60,
+            end_minute: 17 * 60,
+            utc_offset_minutes: 60,
+        }
+    }
+
+    #[test]
+    fn windows_follow_days_and_offset() {
+        let schedule = weekdays_nine_to_five();
+        // 08:30 UTC is 09:30 at +01:00.
+        let inside = status("t", &schedule, MONDAY + 510 * MINUTE);
+        assert!(inside.active);
+        assert_eq!(inside.next_transition_ms, Some(MONDAY + 16 * 60 * MINUTE));
+        // Friday 16:00 UTC: closed until Monday 08:00 UTC.
+        let friday_evening = MONDAY + 4 * 1440 * MINUTE + 16 * 60 * MINUTE;
+        let closed = status("t", &schedule, friday_evening);
+        assert!(!closed.active);
+        assert_eq!(
+            closed.next_transition_ms,
+            Some(MONDAY + 7 * 1440 * MINUTE + 8 * 60 * MINUTE)
+        );
+    }
+
+    #[test]
+    fn overnight_and_all_day_windows() {
+        let overnight = TunnelSchedule {
+            days: vec![6],
+            start_minute: 22 * 60,
+            end_minute: 2 * 60,
+            utc_offset_minutes: 0,
+        };
+        // Monday 01:00 is still Sunday night's window.
+        let early_monday = status("t", &overnight, MONDAY + 60 * MINUTE);
+        assert!(early_monday.active);
+        assert_eq!(early_monday.next_transition_ms, Some(MONDAY + 120 * MINUTE));
+
+        let always = TunnelSchedule::default();
+        assert_eq!(
+            status("t", &always, MONDAY + 5 * MINUTE),
+            ScheduleStatus {
+                tunnel_id: "t".to_string(),
+                active: true,
+                next_transition_ms: None,
+            }
+        );
+        assert!(validate(&TunnelSchedule {
+            start_minute: 1440,
+            ..TunnelSchedule::default()
+        })
+        .is_err());
+    }
+}
@@ -0,0 +1,236 @@
+//! Heuristic service detection on top of [`super::listening_ports`].
+//!
+//! A listening socket is matched by process name first (so Postgres on 5433 is
+//! still Postgres), then by well-known port. Each match becomes a pre-named
+//! local-forward draft the UI can save with one click. Ports the user named in
+//! the service catalog are suggested under that name too.
+
+use super::listening_ports::ListeningPort;
+use super::service_names::ServiceNames;
+use crate::types::SavedTunnel;
+use serde::Serialize;
+use std::collections::HashSet;
+
+struct KnownService {
+    name: &'static str,
+    ports: &'static [u16],
+    processes: &'static [&'static str],
+}
+
+const KNOWN_SERVICES: &[KnownService] = &[
+    KnownService {
+        name: "MySQL",
+        ports: &[3306],
+        processes: &["mysqld", "mariadbd"],
+    },
+    KnownService {
+        name: "PostgreSQL",
+        ports: &[5432],
+        processes: &["postgres", "postmaster"],
+    },
+    KnownService {
+        name: "Redis",
+        ports: &[6379],
+        processes: &["redis-server"],
+    },
+    KnownService {
+        name: "MongoDB",
+        ports: &[27017],
+        processes: &["mongod"],
+    },
+    KnownService {
+        name: "Elasticsearch",
+        ports: &[9200],
+        processes: &[],
+    },
+    KnownService {
+        name: "RabbitMQ Management",
+        ports: &[15672],
+        processes: &[],
+    },
+    KnownService {
+        name: "Web",
+        ports: &[3000, 5000, 8000, 8080, 8888],
+        processes: &[],
+    },
+];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TunnelSuggestion {
+    pub service: String,
+    /// The listening socket the draft forwards to.
+    pub listening: ListeningPort,
+    /// Unsaved tunnel (`status` unset); save it as-is to create the forward.
+    pub draft: SavedTunnel,
+}
+
+fn detect(entry: &ListeningPort) -> Option<&'static KnownService> {
+    let process = entry.process.as_deref().unwrap_or_default();
+    KNOWN_SERVICES
+        .iter()
+        .find(|service| service.processes.contains(&process))
+        .or_else(|| {
+            KNOWN_SERVICES
+                .iter()
+                .find(|service| service.ports.contains(&entry.port))
+        })
+}
+
+/// Host to dial from the server's side; wildcard and IPv4 loopback binds use 127.0.0.1.
+fn forward_target(entry: &ListeningPort) -> String {
+    match entry.address.as_str() {
+        "0.0.0.0" | "::" | "*" => "127.0.0.1".to_string(),
+        address if address.starts_with("127.") => "127.0.0.1".to_string(),
+        address => address.to_string(),
+    }
+}
+
+/// Drafts for every recognised service on `connection_id` that is not already
+/// forwarded. Local ports avoid those used by any saved tunnel.
+pub fn suggest_tunnels(
+    connection_id: &str,
+    ports: &[ListeningPort],
+    saved: &[SavedTunnel],
+    names: &ServiceNames,
+) -> Vec<TunnelSuggestion> {
+    let forwarded: HashSet<u16> = saved
+        .iter()
+        .filter(|tunnel| tunnel.connection_id == connection_id && tunnel.tunnel_type == "local")
+        .map(|tunnel| tunnel.remote_port)
+        .collect();
+    let mut taken_local: HashSet<u16> = saved.iter().map(|tunnel| tunnel.local_port).collect();
+
+    let mut suggestions = Vec::new();
+    let mut suggested_ports = HashSet::new();
+    for entry in ports {
+        if forwarded.contains(&entry.port) || !suggested_ports.insert(entry.port) {
+            continue;
+        }
+        let (service, name) = match detect(entry) {
+            Some(service) if service.ports.contains(&entry.port) => {
+                (service.name.to_string(), service.name.to_string())
+            }
+            Some(service) => (
+                service.name.to_string(),
+                format!("{} :{}", service.name, entry.port),
+            ),
+            None if names.is_custom(entry.port) => {
+                let name = names.name(entry.port).unwrap_or_default().to_string();
+                (name.clone(), name)
+            }
+            None => continue,
+        };
+        let Some(local_port) = (entry.port..=u16::MAX).find(|port| !taken_local.contains(port))
+        else {
+            continue;
+        };
+        taken_local.insert(local_port);
+        suggestions.push(TunnelSuggestion {
+            service,
+            listening: entry.clone(),
+            draft: SavedTunnel {
+                id: uuid::Uuid::new_v4().to_string(),
+                connection_id: connection_id.to_string(),
+                name,
+                tunnel_type: "local".to_string(),
+                local_port,
+                remote_host: forward_target(entry),
+                remote_port: entry.port,
+                bind_address: Some("127.0.0.1".to_string()),
+                bind_to_any: Some(false),
+                auto_start: Some(false),
+                status: None,
+                original_port: None,
+                group: None,
+                created_at: None,
+                updated_at: None,
+                web: None,
+                pac_domains: None,
+                system_proxy: None,
+                schedule: None,
+            },
+        });
+    }
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listening(port: u16, address: &str, process: Option<&str>) -> ListeningPort {
+        ListeningPort {
+            port,
+            address: address.to_string(),
+            process: process.map(str::to_string),
+            pid: None,
+            loopback: address.starts_with("127."),
+            service: None,
+        }
+    }
+
+    #[test]
+    fn detects_by_process_then_port() {
+        let ports = [
+            listening(22, "0.0.0.0", Some("sshd")),
+            listening(3000, "0.0.0.0", Some("node")),
+            listening(5433, "127.0.0.1", Some("postgres")),
+            listening(6379, "10.0.0.4", None),
+        ];
+        let suggestions = suggest_tunnels("web", &ports, &[], &ServiceNames::default());
+        let summary: Vec<(&str, &str, &str, u16)> = suggestions
+            .iter()
+            .map(|suggestion| {
+                (
+                    suggestion.service.as_str(),
+                    suggestion.draft.name.as_str(),
+                    suggestion.draft.remote_host.as_str(),
+                    suggestion.draft.remote_port,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Web", "Web", "127.0.0.1", 3000),
+                ("PostgreSQL", "PostgreSQL :5433", "127.0.0.1", 5433),
+                ("Redis", "Redis", "10.0.0.4", 6379),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_forwarded_ports_and_avoids_taken_local_ports() {
+        let existing = |connection_id: &str, local_port: u16, remote_port: u16| SavedTunnel {
+            id: format!("{connection_id}-{local_port}"),
+            connection_id: connection_id.to_string(),
+            tunnel_type: "local".to_string(),
+            local_port,
+            remote_host: "127.0.0.1".to_string(),
+            remote_port,
+            ..Default::default()
+        };
+        let saved = [existing("web", 13306, 3306), existing("db", 5432, 5432)];
+        let ports = [
+            listening(3306, "127.0.0.1", Some("mysqld")),
+            listening(5432, "127.0.0.1", Some("postgres")),
+        ];
+        let suggestions = suggest_tunnels("web", &ports, &saved, &ServiceNames::default());
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].draft.remote_port, 5432);
+        assert_eq!(suggestions[0].draft.local_port, 5433);
+    }
+    #[test]
+    fn suggests_ports_the_user_named() {
+        let names = ServiceNames::new([(4242, "metrics".to_string())].into());
+        let ports = [
+            listening(4242, "127.0.0.1", None),
+            listening(4343, "127.0.0.1", None),
+        ];
+        let suggestions = suggest_tunnels("web", &ports, &[], &names);
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].service, "metrics");
+        assert_eq!(suggestions[0].draft.name, "metrics");
+    }
+}
@@ -0,0 +1,175 @@
+//! Port → service name catalog, so a port reads the same everywhere.
+//!
+//! Names follow the IANA service registry (lowercase, `postgresql`, `https`),
+//! plus a few de-facto ports it doesn't list. Users can rename or add ports
+//! in `settings.json`; an empty name hides a built-in one:
+//!
+//! ```json
+//! "serviceNames": { "8080": "api", "5433": "postgresql-replica", "3000": "" }
+//! ```
+
+use crate::commands::read_effective_settings;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tauri::AppHandle;
+
+const BUILTIN: &[(u16, &str)] = &[
+    (20, "ftp-data"),
+    (21, "ftp"),
+    (22, "ssh"),
+    (23, "telnet"),
+    (25, "smtp"),
+    (53, "domain"),
+    (80, "http"),
+    (110, "pop3"),
+    (111, "sunrpc"),
+    (123, "ntp"),
+    (143, "imap"),
+    (161, "snmp"),
+    (389, "ldap"),
+    (443, "https"),
+    (445, "microsoft-ds"),
+    (465, "submissions"),
+    (514, "syslog"),
+    (587, "submission"),
+    (631, "ipp"),
+    (636, "ldaps"),
+    (873, "rsync"),
+    (993, "imaps"),
+    (995, "pop3s"),
+    (1080, "socks"),
+    (1433, "ms-sql-s"),
+    (1521, "oracle"),
+    (1883, "mqtt"),
+    (2049, "nfs"),
+    (2375, "docker"),
+    (2376, "docker-s"),
+    (2379, "etcd-client"),
+    (3000, "grafana"),
+    (3306, "mysql"),
+    (3389, "ms-wbt-server"),
+    (4369, "epmd"),
+    (5000, "upnp"),
+    (5432, "postgresql"),
+    (5601, "kibana"),
+    (5672, "amqp"),
+    (5900, "vnc"),
+    (6379, "redis"),
+    (6443, "kubernetes"),
+    (8080, "http-alt"),
+    (8443, "https-alt"),
+    (8888, "jupyter"),
+    (9000, "cslistener"),
+    (9090, "prometheus"),
+    (9092, "kafka"),
+    (9200, "elasticsearch"),
+    (9418, "git"),
+    (11211, "memcache"),
+    (15672, "rabbitmq-mgmt"),
+    (27017, "mongodb"),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceNameEntry {
+    pub port: u16,
+    pub name: String,
+    /// Set by the user rather than built in.
+    pub custom: bool,
+}
+
+/// The built-in catalog with the user's overrides applied.
+#[derive(Debug, Clone, Default)]
+pub struct ServiceNames {
+    overrides: BTreeMap<u16, String>,
+}
+
+impl ServiceNames {
+    pub fn new(overrides: BTreeMap<u16, String>) -> Self {
+        let overrides = overrides
+            .into_iter()
+            .map(|(port, name)| (port, name.trim().to_string()))
+            .collect();
+        Self { overrides }
+    }
+
+    /// Overrides from the `serviceNames` setting; keys that aren't ports are ignored.
+    pub fn from_app(app: &AppHandle) -> Self {
+        let overrides = read_effective_settings(app)
+            .ok()
+            .and_then(|settings| settings.get("serviceNames").cloned())
+            .and_then(|value| serde_json::from_value::<BTreeMap<String, String>>(value).ok())
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(port, name)| Some((port.trim().parse().ok()?, name)))
+            .collect();
+        Self::new(overrides)
+    }
+
+    pub fn name(&self, port: u16) -> Option<&str> {
+        match self.overrides.get(&port) {
+            Some(name) if name.is_empty() => None,
+            Some(name) => Some(name),
+            None => BUILTIN
+                .binary_search_by_key(&port, |(port, _)| *port)
+                .ok()
+                .map(|index| BUILTIN[index].1),
+        }
+    }
+
+    /// Named by the user, not just the built-in catalog.
+    pub fn is_custom(&self, port: u16) -> bool {
+        self.overrides
+            .get(&port)
+            .is_some_and(|name| !name.is_empty())
+    }
+
+    /// Every named port, sorted.
+    pub fn entries(&self) -> Vec<ServiceNameEntry> {
+        let mut ports: Vec<u16> = BUILTIN.iter().map(|(port, _)| *port).collect();
+        ports.extend(self.overrides.keys());
+        ports.sort_unstable();
+        ports.dedup();
+        ports
+            .into_iter()
+            .filter_map(|port| {
+                Some(ServiceNameEntry {
+                    port,
+                    name: self.name(port)?.to_string(),
+                    custom: self.is_custom(port),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_catalog_is_sorted_for_lookup() {
+        assert!(BUILTIN.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        let names = ServiceNames::default();
+        assert_eq!(names.name(5432), Some("postgresql"));
+        assert_eq!(names.name(4242), None);
+    }
+
+    #[test]
+    fn overrides_rename_add_and_hide() {
+        let names = ServiceNames::new(BTreeMap::from([
+            (8080, " api ".to_string()),
+            (4242, "metrics".to_string()),
+            (3000, String::new()),
+        ]));
+        assert_eq!(names.name(8080), Some("api"));
+        assert_eq!(names.name(3000), None);
+        assert!(names.is_custom(4242));
+        let entries = names.entries();
+        assert!(entries
+            .iter()
+            .any(|entry| entry.port == 4242 && entry.custom));
+        assert!(!entries.iter().any(|entry| entry.port == 3000));
+        assert!(entries.windows(2).all(|pair| pair[0].port < pair[1].port));
+    }
+}
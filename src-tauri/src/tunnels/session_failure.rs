@@ -2,6 +2,7 @@
 
 use super::commands::stop_tunnels_for_connections;
 use crate::commands::AppState;
+use crate::events::ConnectionEvent;
 use std::collections::HashSet;
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter, Manager};
@@ -48,7 +49,9 @@ pub fn spawn_session_failure_watcher(
             }
 
             if let Some(state) = app.try_state::<AppState>() {
+                super::restore::remember_running(&app, &state, &connection_id).await;
                 let _ = stop_tunnels_for_connections(&app, &state, &[connection_id.clone()]).await;
+                crate::events::publish(&connection_id, ConnectionEvent::TransportLost);
                 let _ = app.emit(
                     "connection:transport-lost",
                     serde_json::json!({ "connectionId": connection_id }),
@@ -14,6 +14,7 @@ pub const ATYP_DOMAIN: u8 = 0x03;
 pub const ATYP_IPV6: u8 = 0x04;
 pub const REP_SUCCEEDED: u8 = 0x00;
 pub const REP_GENERAL_FAILURE: u8 = 0x01;
+pub const REP_NOT_ALLOWED: u8 = 0x02;
 pub const REP_CMD_NOT_SUPPORTED: u8 = 0x07;
 pub const REP_ATYP_NOT_SUPPORTED: u8 = 0x08;
 
@@ -0,0 +1,368 @@
+//! Saved tunnels as OpenSSH `LocalForward` / `RemoteForward` /
+//! `DynamicForward` lines, both ways.
+//!
+//! Export writes one `Host` block per connection, named like the connection
+//! export (`build_host_alias`), holding only the forward lines. ssh merges
+//! forwards from every matching block, so the output can be appended to a
+//! config that already describes the hosts. Import reads the forward lines
+//! of each `Host` block and matches the block to a saved connection by alias
+//! or by `HostName`/`Port`/`User`; the frontend saves what the user picks.
+
+use crate::types::{SavedConnection, SavedTunnel};
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigForward {
+    /// `local`, `remote` or `dynamic`, as in `SavedTunnel`.
+    #[serde(rename = "type")]
+    pub tunnel_type: String,
+    pub local_port: u16,
+    pub remote_host: String,
+    pub remote_port: u16,
+    pub bind_address: Option<String>,
+}
+
+/// The forward lines of one non-wildcard `Host` block.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HostForwards {
+    pub aliases: Vec<String>,
+    pub hostname: Option<String>,
+    pub user: Option<String>,
+    pub port: Option<u16>,
+    pub forwards: Vec<ConfigForward>,
+    /// Forward lines that couldn't be turned into a tunnel, with the reason.
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigForwardImport {
+    pub host_alias: String,
+    /// Saved connection the block belongs to; `None` when nothing matches.
+    pub connection_id: Option<String>,
+    pub forward: ConfigForward,
+    /// Saved tunnel on that connection with the same ports and target.
+    pub existing_tunnel_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigForwardPreview {
+    pub forwards: Vec<ConfigForwardImport>,
+    pub errors: Vec<String>,
+}
+
+/// `[host:]port`, `[v6]:port` or `host/port` → host and port text.
+fn split_endpoint(spec: &str) -> (Option<&str>, &str) {
+    if let Some(rest) = spec.strip_prefix('[') {
+        if let Some((host, port)) = rest.split_once(']') {
+            let port = port.trim_start_matches([':', '/']);
+            return (Some(host), port);
+        }
+    }
+    match spec.rsplit_once('/').or_else(|| spec.rsplit_once(':')) {
+        Some((host, port)) => (Some(host), port),
+        None => (None, spec),
+    }
+}
+
+fn parse_port(port: &str, spec: &str) -> Result<u16, String> {
+    port.parse::<u16>()
+        .ok()
+        .filter(|port| *port > 0)
+        .ok_or_else(|| format!("`{spec}` has no valid port (Unix sockets aren't supported)"))
+}
+
+/// Listen side of a forward; `*` and an empty host mean every interface.
+fn parse_listen(spec: &str) -> Result<(Option<String>, u16), String> {
+    let (host, port) = split_endpoint(spec);
+    let bind = host.map(|host| match host {
+        "" | "*" => "0.0.0.0".to_string(),
+        host => host.to_string(),
+    });
+    Ok((bind, parse_port(port, spec)?))
+}
+
+fn parse_target(spec: &str) -> Result<(String, u16), String> {
+    match split_endpoint(spec) {
+        (Some(host), port) if !host.is_empty() => Ok((host.to_string(), parse_port(port, spec)?)),
+        _ => Err(format!("`{spec}` needs a host:port target")),
+    }
+}
+
+/// One forward directive (`localforward`, … lowercased) with its value.
+pub fn parse_forward(directive: &str, value: &str) -> Result<ConfigForward, String> {
+    let args: Vec<&str> = value.split_whitespace().collect();
+    match (directive, args.as_slice()) {
+        ("localforward", [listen, target]) => {
+            let (bind_address, local_port) = parse_listen(listen)?;
+            let (remote_host, remote_port) = parse_target(target)?;
+            Ok(ConfigForward {
+                tunnel_type: "local".to_string(),
+                local_port,
+                remote_host,
+                remote_port,
+                bind_address,
+            })
+        }
+        ("remoteforward", [listen, target]) => {
+            let (bind_address, remote_port) = parse_listen(listen)?;
+            let (remote_host, local_port) = parse_target(target)?;
+            Ok(ConfigForward {
+                tunnel_type: "remote".to_string(),
+                local_port,
+                remote_host,
+                remote_port,
+                // Without one, sshd listens on loopback only; Zync's default is every interface.
+                bind_address: Some(bind_address.unwrap_or_else(|| "localhost".to_string())),
+            })
+        }
+        ("remoteforward", [_]) => Err(format!(
+            "RemoteForward {value}: remote SOCKS forwards aren't supported"
+        )),
+        ("dynamicforward", [listen]) => {
+            let (bind_address, local_port) = parse_listen(listen)?;
+            Ok(ConfigForward {
+                tunnel_type: "dynamic".to_string(),
+                local_port,
+                remote_host: "*".to_string(),
+                remote_port: 0,
+                bind_address,
+            })
+        }
+        _ => Err(format!("Can't read `{directive} {value}`")),
+    }
+}
+
+/// Forward lines of every `Host` block without wildcards. Lines under
+/// `Match` and in the preamble are skipped, as they don't name one host.
+pub fn parse_host_forwards(content: &str) -> Vec<HostForwards> {
+    let mut blocks = Vec::new();
+    let mut current: Option<HostForwards> = None;
+    for line in content.lines() {
+        let line = crate::ssh_config::strip_inline_comments(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        let (key, value) = match line.find(|c: char| c.is_whitespace() || c == '=') {
+            Some(index) => (
+                &line[..index],
+                line[index..]
+                    .trim_start_matches(|c: char| c.is_whitespace() || c == '=')
+                    .trim(),
+            ),
+            None => (line, ""),
+        };
+        let value = value.trim_matches('"');
+        let key = key.to_ascii_lowercase();
+        if key == "host" || key == "match" {
+            blocks.extend(
+                current
+                    .take()
+                    .filter(|block| !block.forwards.is_empty() || !block.errors.is_empty()),
+            );
+            let aliases: Vec<String> = value.split_whitespace().map(str::to_string).collect();
+            let named = key == "host"
+                && !aliases.is_empty()
+                && aliases.iter().all(|alias| !alias.contains(['*', '?', '!']));
+            current = named.then(|| HostForwards {
+                aliases,
+                ..HostForwards::default()
+            });
+            continue;
+        }
+        let Some(block) = current.as_mut() else {
+            continue;
+        };
+        match key.as_str() {
+            "hostname" => block.hostname = Some(value.to_string()),
+            "user" => block.user = Some(value.to_string()),
+            "port" => block.port = value.parse().ok(),
+            "localforward" | "remoteforward" | "dynamicforward" => {
+                match parse_forward(&key, value) {
+                    Ok(forward) => block.forwards.push(forward),
+                    Err(error) => block.errors.push(format!("{}: {error}", block.aliases[0])),
+                }
+            }
+            _ => {}
+        }
+    }
+    blocks.extend(current.filter(|block| !block.forwards.is_empty() || !block.errors.is_empty()));
+    blocks
+}
+
+fn bracket_v6(host: &str) -> String {
+    if host.contains(':') {
+        format!("[{host}]")
+    } else {
+        host.to_string()
+    }
+}
+
+/// `LocalForward …` / `RemoteForward …` / `DynamicForward …` for `tunnel`.
+pub fn forward_line(tunnel: &SavedTunnel) -> String {
+    let bind = tunnel.bind_address.as_deref().map(str::trim);
+    let listen = |port: u16, default_bind: Option<&str>| match bind.or(default_bind) {
+        Some(bind) if bind != "localhost" => format!("{}:{port}", bracket_v6(bind)),
+        _ => port.to_string(),
+    };
+    match tunnel.tunnel_type.as_str() {
+        "dynamic" => format!("DynamicForward {}", listen(tunnel.local_port, None)),
+        // Zync binds remote forwards to every interface unless told otherwise.
+        "remote" => format!(
+            "RemoteForward {} {}:{}",
+            listen(tunnel.remote_port, Some("0.0.0.0")),
+            bracket_v6(&tunnel.remote_host),
+            tunnel.local_port
+        ),
+        _ => format!(
+            "LocalForward {} {}:{}",
+            listen(tunnel.local_port, None),
+            bracket_v6(&tunnel.remote_host),
+            tunnel.remote_port
+        ),
+    }
+}
+
+/// ssh_config text with one `Host` block per `(alias, tunnels)` entry.
+pub fn render_config(hosts: &[(String, Vec<&SavedTunnel>)]) -> String {
+    let mut output = String::from("# Port forwards exported from Zync\n");
+    for (alias, tunnels) in hosts {
+        output.push_str(&format!("\nHost {alias}\n"));
+        for tunnel in tunnels {
+            output.push_str(&format!(
+                "  # {}\n  {}\n",
+                tunnel.name,
+                forward_line(tunnel)
+            ));
+        }
+    }
+    output
+}
+
+/// The saved connection a block describes: by alias or name first, then by
+/// address when exactly one connection has it.
+pub fn match_connection<'a>(
+    block: &HostForwards,
+    connections: &'a [SavedConnection],
+    alias_of: impl Fn(&SavedConnection) -> String,
+) -> Option<&'a SavedConnection> {
+    let by_alias = connections.iter().find(|connection| {
+        block.aliases.iter().any(|alias| {
+            *alias == alias_of(connection) || alias.eq_ignore_ascii_case(connection.name.trim())
+        })
+    });
+    if by_alias.is_some() {
+        return by_alias;
+    }
+    let host = block.hostname.as_deref().unwrap_or(&block.aliases[0]);
+    let port = block.port.unwrap_or(22);
+    let mut by_address = connections.iter().filter(|connection| {
+        connection.host.eq_ignore_ascii_case(host)
+            && connection.port == port
+            && block
+                .user
+                .as_deref()
+                .is_none_or(|user| user == connection.username)
+    });
+    match (by_address.next(), by_address.next()) {
+        (Some(connection), None) => Some(connection),
+        _ => None,
+    }
+}
+
+/// Whether `tunnel` already is the forward `forward` describes.
+pub fn same_forward(tunnel: &SavedTunnel, forward: &ConfigForward) -> bool {
+    tunnel.tunnel_type == forward.tunnel_type
+        && tunnel.local_port == forward.local_port
+        && tunnel.remote_port == forward.remote_port
+        && (forward.tunnel_type == "dynamic"
+            || tunnel
+                .remote_host
+                .eq_ignore_ascii_case(&forward.remote_host))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn saved(tunnel_type: &str, local: u16, host: &str, remote: u16) -> SavedTunnel {
+        SavedTunnel {
+            id: "t".to_string(),
+            connection_id: "c".to_string(),
+            name: "Forward".to_string(),
+            tunnel_type: tunnel_type.to_string(),
+            local_port: local,
+            remote_host: host.to_string(),
+            remote_port: remote,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn reads_forward_lines_per_host_block() {
+        let blocks = parse_host_forwards(
+            r#"
+LocalForward 1 a:1
+Host db db.internal
+  HostName 10.0.0.5
+  LocalForward 127.0.0.2:5432 localhost:5432  # postgres
+  RemoteForward 9000 localhost:3000
+  DynamicForward [::1]:1080
+  LocalForward /tmp/sock /run/app.sock
+Host *
+  LocalForward 8080 web:80
+"#,
+        );
+        assert_eq!(blocks.len(), 1);
+        let block = &blocks[0];
+        assert_eq!(block.aliases, ["db", "db.internal"]);
+        assert_eq!(block.hostname.as_deref(), Some("10.0.0.5"));
+        assert_eq!(block.errors.len(), 1);
+        assert_eq!(
+            block.forwards[0],
+            ConfigForward {
+                tunnel_type: "local".to_string(),
+                local_port: 5432,
+                remote_host: "localhost".to_string(),
+                remote_port: 5432,
+                bind_address: Some("127.0.0.2".to_string()),
+            }
+        );
+        assert_eq!(
+            (block.forwards[1].remote_port, block.forwards[1].local_port),
+            (9000, 3000)
+        );
+        assert_eq!(block.forwards[1].bind_address.as_deref(), Some("localhost"));
+        assert_eq!(block.forwards[2].bind_address.as_deref(), Some("::1"));
+    }
+
+    #[test]
+    fn written_lines_read_back_as_the_same_forward() {
+        let mut local = saved("local", 8443, "fd00::10", 443);
+        local.bind_address = Some("127.0.0.3".to_string());
+        let remote = saved("remote", 3000, "localhost", 9000);
+        let dynamic = saved("dynamic", 1080, "*", 0);
+        assert_eq!(
+            forward_line(&local),
+            "LocalForward 127.0.0.3:8443 [fd00::10]:443"
+        );
+        assert_eq!(
+            forward_line(&remote),
+            "RemoteForward 0.0.0.0:9000 localhost:3000"
+        );
+        assert_eq!(forward_line(&dynamic), "DynamicForward 1080");
+
+        let text = render_config(&[("db".to_string(), vec![&local, &remote, &dynamic])]);
+        let blocks = parse_host_forwards(&text);
+        assert_eq!(blocks[0].aliases, ["db"]);
+        for (tunnel, forward) in [&local, &remote, &dynamic].iter().zip(&blocks[0].forwards) {
+            assert!(same_forward(tunnel, forward), "{forward:?}");
+        }
+        assert_eq!(
+            blocks[0].forwards[0].bind_address.as_deref(),
+            Some("127.0.0.3")
+        );
+    }
+}
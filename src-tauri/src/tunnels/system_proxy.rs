@@ -0,0 +1,447 @@
+//! Point the OS proxy settings at a SOCKS tunnel while it runs.
+//!
+//! Dynamic tunnels with `systemProxy` set switch the system SOCKS proxy to
+//! their listener when they start. The settings being replaced are written to
+//! `<data dir>/system-proxy.json` before anything changes, and put back when
+//! that tunnel goes down, when the app exits, or on the next launch if Zync
+//! died in between. The file is only removed once the restore succeeded.
+//!
+//! - macOS: `networksetup` SOCKS proxy on every enabled network service.
+//! - Windows: WinINet `ProxyEnable`/`ProxyServer` under HKCU (`socks=host:port`).
+//! - Linux and other Unix: GNOME `gsettings` (`org.gnome.system.proxy`).
+
+use crate::commands::get_data_dir;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+const STATE_FILE: &str = "system-proxy.json";
+
+/// Serializes snapshot/apply/restore so two tunnels can't interleave them.
+static LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// One platform setting as it was before the takeover; `None` means unset.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct ProxyEntry {
+    key: String,
+    value: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Takeover {
+    /// Saved tunnel currently holding the system proxy.
+    tunnel_id: String,
+    proxy: String,
+    /// The user's own settings, kept across hand-overs between tunnels.
+    previous: Vec<ProxyEntry>,
+}
+
+fn state_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(STATE_FILE)
+}
+
+fn read_takeover(path: &Path) -> Option<Takeover> {
+    let raw = std::fs::read(path).ok()?;
+    serde_json::from_slice(&raw).ok()
+}
+
+async fn run(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = tokio::process::Command::new(program)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| format!("{program} is not available: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{program} {} failed: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Enabled services from `networksetup -listallnetworkservices` (the first line
+/// is a legend; disabled services are prefixed with `*`).
+#[cfg(any(target_os = "macos", test))]
+fn parse_macos_services(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .skip(1)
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('*'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// `(enabled, server, port)` from `networksetup -getsocksfirewallproxy`.
+#[cfg(any(target_os = "macos", test))]
+fn parse_macos_socks(output: &str) -> (bool, String, String) {
+    let field = |name: &str| {
+        output
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .find(|(key, _)| key.trim() == name)
+            .map(|(_, value)| value.trim().to_string())
+            .unwrap_or_default()
+    };
+    (
+        field("Enabled").eq_ignore_ascii_case("yes"),
+        field("Server"),
+        field("Port"),
+    )
+}
+
+/// `(type, data)` of `name` in `reg query` output.
+#[cfg(any(target_os = "windows", test))]
+fn parse_reg_value(output: &str, name: &str) -> Option<(String, String)> {
+    output.lines().find_map(|line| {
+        let line = line.trim();
+        let rest = line
+            .get(..name.len())
+            .filter(|head| head.eq_ignore_ascii_case(name))
+            .map(|_| &line[name.len()..])?;
+        if !rest.starts_with(char::is_whitespace) {
+            return None;
+        }
+        let rest = rest.trim_start();
+        let (kind, data) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        Some((kind.to_string(), data.trim().to_string()))
+    })
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{parse_macos_services, parse_macos_socks, run, ProxyEntry};
+
+    async fn services() -> Result<Vec<String>, String> {
+        Ok(parse_macos_services(
+            &run("networksetup", &["-listallnetworkservices"]).await?,
+        ))
+    }
+
+    pub(super) async fn snapshot() -> Result<Vec<ProxyEntry>, String> {
+        let mut entries = Vec::new();
+        for service in services().await? {
+            let output = run("networksetup", &["-getsocksfirewallproxy", &service]).await?;
+            let (enabled, server, port) = parse_macos_socks(&output);
+            entries.push(ProxyEntry {
+                key: service,
+                value: Some(format!(
+                    "{}|{server}|{port}",
+                    if enabled { "on" } else { "off" }
+                )),
+            });
+        }
+        Ok(entries)
+    }
+
+    pub(super) async fn apply(host: &str, port: u16) -> Result<(), String> {
+        let port = port.to_string();
+        for service in services().await? {
+            run(
+                "networksetup",
+                &["-setsocksfirewallproxy", &service, host, &port],
+            )
+            .await?;
+            run(
+                "networksetup",
+                &["-setsocksfirewallproxystate", &service, "on"],
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    pub(super) async fn restore_entry(entry: &ProxyEntry) -> Result<(), String> {
+        let value = entry.value.clone().unwrap_or_default();
+        let mut parts = value.splitn(3, '|');
+        let state = parts.next().unwrap_or("off");
+        let server = parts.next().unwrap_or_default();
+        let port = parts.next().unwrap_or_default();
+        if !server.is_empty() {
+            run(
+                "networksetup",
+                &["-setsocksfirewallproxy", &entry.key, server, port],
+            )
+            .await?;
+        }
+        run(
+            "networksetup",
+            &["-setsocksfirewallproxystate", &entry.key, state],
+        )
+        .await
+        .map(|_| ())
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::{parse_reg_value, run, ProxyEntry};
+
+    const INTERNET_SETTINGS: &str =
+        r"HKCU\Software\Microsoft\Windows\CurrentVersion\Internet Settings";
+    const VALUES: &[&str] = &["ProxyEnable", "ProxyServer"];
+
+    pub(super) async fn snapshot() -> Result<Vec<ProxyEntry>, String> {
+        let mut entries = Vec::new();
+        for &name in VALUES {
+            // `reg query` fails when the value doesn't exist; that is "unset".
+            let value = run("reg", &["query", INTERNET_SETTINGS, "/v", name])
+                .await
+                .ok()
+                .and_then(|output| parse_reg_value(&output, name))
+                .map(|(kind, data)| format!("{kind}|{data}"));
+            entries.push(ProxyEntry {
+                key: name.to_string(),
+                value,
+            });
+        }
+        Ok(entries)
+    }
+
+    async fn set(name: &str, kind: &str, data: &str) -> Result<(), String> {
+        run(
+            "reg",
+            &[
+                "add",
+                INTERNET_SETTINGS,
+                "/v",
+                name,
+                "/t",
+                kind,
+                "/d",
+                data,
+                "/f",
+            ],
+        )
+        .await
+        .map(|_| ())
+    }
+
+    pub(super) async fn apply(host: &str, port: u16) -> Result<(), String> {
+        let host = if host.contains(':') {
+            format!("[{host}]")
+        } else {
+            host.to_string()
+        };
+        set("ProxyServer", "REG_SZ", &format!("socks={host}:{port}")).await?;
+        set("ProxyEnable", "REG_DWORD", "1").await
+    }
+
+    pub(super) async fn restore_entry(entry: &ProxyEntry) -> Result<(), String> {
+        let Some((kind, data)) = entry.value.as_deref().and_then(|v| v.split_once('|')) else {
+            return run(
+                "reg",
+                &["delete", INTERNET_SETTINGS, "/v", &entry.key, "/f"],
+            )
+            .await
+            .map(|_| ());
+        };
+        // `reg query` prints DWORDs as hex; `reg add` wants decimal.
+        let data = match data.strip_prefix("0x") {
+            Some(hex) if kind == "REG_DWORD" => u32::from_str_radix(hex, 16)
+                .map(|value| value.to_string())
+                .unwrap_or_else(|_| data.to_string()),
+            _ => data.to_string(),
+        };
+        set(&entry.key, kind, &data).await
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use super::{run, ProxyEntry};
+
+    /// `(schema, key)` pairs touched by the takeover.
+    const KEYS: &[(&str, &str)] = &[
+        ("org.gnome.system.proxy", "mode"),
+        ("org.gnome.system.proxy.socks", "host"),
+        ("org.gnome.system.proxy.socks", "port"),
+    ];
+
+    pub(super) async fn snapshot() -> Result<Vec<ProxyEntry>, String> {
+        let mut entries = Vec::new();
+        for &(schema, key) in KEYS {
+            let value = run("gsettings", &["get", schema, key]).await?;
+            entries.push(ProxyEntry {
+                key: format!("{schema} {key}"),
+                value: Some(value.trim().to_string()),
+            });
+        }
+        Ok(entries)
+    }
+
+    pub(super) async fn apply(host: &str, port: u16) -> Result<(), String> {
+        let socks = "org.gnome.system.proxy.socks";
+        run("gsettings", &["set", socks, "host", &format!("'{host}'")]).await?;
+        run("gsettings", &["set", socks, "port", &port.to_string()]).await?;
+        run(
+            "gsettings",
+            &["set", "org.gnome.system.proxy", "mode", "'manual'"],
+        )
+        .await
+        .map(|_| ())
+    }
+
+    pub(super) async fn restore_entry(entry: &ProxyEntry) -> Result<(), String> {
+        let (schema, key) = entry
+            .key
+            .split_once(' ')
+            .ok_or_else(|| format!("Invalid proxy setting '{}'", entry.key))?;
+        let value = entry.value.as_deref().unwrap_or_default();
+        run("gsettings", &["set", schema, key, value])
+            .await
+            .map(|_| ())
+    }
+}
+
+/// Put every entry back, continuing past failures so one bad service doesn't
+/// leave the others pointed at a dead tunnel.
+async fn restore_entries(previous: &[ProxyEntry]) -> Result<(), String> {
+    let mut errors = Vec::new();
+    for entry in previous {
+        if let Err(error) = platform::restore_entry(entry).await {
+            errors.push(format!("{}: {error}", entry.key));
+        }
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Failed to restore proxy settings ({})",
+            errors.join("; ")
+        ))
+    }
+}
+
+async fn restore_locked(path: &Path, takeover: &Takeover) -> Result<(), String> {
+    restore_entries(&takeover.previous).await?;
+    println!(
+        "[TUNNEL][PROXY] Restored system proxy (was {} for tunnel {})",
+        takeover.proxy, takeover.tunnel_id
+    );
+    match std::fs::remove_file(path) {
+        Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(error.to_string()),
+        _ => Ok(()),
+    }
+}
+
+/// Point the system SOCKS proxy at `host:port` on behalf of `tunnel_id`.
+pub(crate) async fn take_over(
+    data_dir: &Path,
+    tunnel_id: &str,
+    host: &str,
+    port: u16,
+) -> Result<(), String> {
+    let _guard = LOCK.lock().await;
+    let path = state_path(data_dir);
+    // An existing takeover (another tunnel, or a crash) already holds the
+    // user's own settings; snapshotting now would capture ours.
+    let previous = match read_takeover(&path) {
+        Some(existing) => existing.previous,
+        None => platform::snapshot().await?,
+    };
+    let takeover = Takeover {
+        tunnel_id: tunnel_id.to_string(),
+        proxy: format!("{host}:{port}"),
+        previous,
+    };
+    let raw = serde_json::to_vec_pretty(&takeover).map_err(|e| e.to_string())?;
+    crate::atomic_io::durable_replace(&path, &raw)
+        .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+
+    if let Err(error) = platform::apply(host, port).await {
+        let _ = restore_locked(&path, &takeover).await;
+        return Err(error);
+    }
+    println!(
+        "[TUNNEL][PROXY] System SOCKS proxy set to {} for tunnel {tunnel_id}",
+        takeover.proxy
+    );
+    Ok(())
+}
+
+/// Restore the system proxy if `tunnel_id` holds it.
+pub(crate) async fn release(data_dir: &Path, tunnel_id: &str) -> Result<(), String> {
+    let _guard = LOCK.lock().await;
+    let path = state_path(data_dir);
+    match read_takeover(&path) {
+        Some(takeover) if takeover.tunnel_id == tunnel_id => restore_locked(&path, &takeover).await,
+        _ => Ok(()),
+    }
+}
+
+/// Restore whatever takeover is on disk (app exit, or left over from a crash).
+pub(crate) async fn restore(data_dir: &Path) -> Result<(), String> {
+    let _guard = LOCK.lock().await;
+    let path = state_path(data_dir);
+    match read_takeover(&path) {
+        Some(takeover) => restore_locked(&path, &takeover).await,
+        None => Ok(()),
+    }
+}
+
+/// Called for every non-active tunnel status; cheap when nothing is held.
+pub(crate) fn release_in_background(app: &AppHandle, tunnel_id: &str) {
+    let data_dir = get_data_dir(app);
+    if !state_path(&data_dir).exists() {
+        return;
+    }
+    let tunnel_id = tunnel_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        if let Err(error) = release(&data_dir, &tunnel_id).await {
+            eprintln!("[TUNNEL][PROXY] {error}");
+        }
+    });
+}
+
+/// Undo a takeover left behind by a previous run.
+pub fn start(app: AppHandle) {
+    let data_dir = get_data_dir(&app);
+    if !state_path(&data_dir).exists() {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        if let Err(error) = restore(&data_dir).await {
+            eprintln!("[TUNNEL][PROXY] {error}");
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_networksetup_output() {
+        let services = "An asterisk (*) denotes that a network service is disabled.\nWi-Fi\n*Thunderbolt Bridge\nUSB 10/100/1000 LAN\n";
+        assert_eq!(
+            parse_macos_services(services),
+            vec!["Wi-Fi".to_string(), "USB 10/100/1000 LAN".to_string()]
+        );
+        let socks = "Enabled: Yes\nServer: 127.0.0.1\nPort: 1080\nAuthenticated Proxy Enabled: 0\n";
+        assert_eq!(
+            parse_macos_socks(socks),
+            (true, "127.0.0.1".to_string(), "1080".to_string())
+        );
+    }
+
+    #[test]
+    fn parses_reg_query_output() {
+        let output = "\r\nHKEY_CURRENT_USER\\Software\\Microsoft\\Windows\\CurrentVersion\\Internet Settings\r\n    ProxyServer    REG_SZ    http=proxy:8080;https=proxy:8080\r\n    ProxyEnable    REG_DWORD    0x1\r\n\r\n";
+        assert_eq!(
+            parse_reg_value(output, "ProxyEnable"),
+            Some(("REG_DWORD".to_string(), "0x1".to_string()))
+        );
+        assert_eq!(
+            parse_reg_value(output, "ProxyServer"),
+            Some((
+                "REG_SZ".to_string(),
+                "http=proxy:8080;https=proxy:8080".to_string()
+            ))
+        );
+        assert_eq!(parse_reg_value(output, "ProxyOverride"), None);
+    }
+}
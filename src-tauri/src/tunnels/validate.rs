@@ -0,0 +1,364 @@
+//! Checks a saved tunnel before it is started, so a bad definition is
+//! reported field by field instead of failing at bind time.
+//!
+//! Errors stop `tunnel_start`; warnings (privileged ports, clashes with other
+//! saved tunnels) are shown in the editor and left to the user.
+
+use super::hostnames::tunnel_hostname;
+use crate::types::SavedTunnel;
+use serde::Serialize;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueSeverity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TunnelIssue {
+    /// `type`, `localPort`, `remoteHost`, `remotePort`, `bindAddress` or
+    /// `hostname`.
+    pub field: &'static str,
+    pub severity: IssueSeverity,
+    pub message: String,
+    /// Id of the saved tunnel this one clashes with.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conflicts_with: Option<String>,
+}
+
+impl TunnelIssue {
+    fn error(field: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            field,
+            severity: IssueSeverity::Error,
+            message: message.into(),
+            conflicts_with: None,
+        }
+    }
+
+    fn warning(field: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            severity: IssueSeverity::Warning,
+            ..Self::error(field, message)
+        }
+    }
+}
+
+/// A DNS name (labels of letters, digits, `-` and `_`) or an IP literal,
+/// IPv6 with or without brackets.
+pub fn is_valid_host(host: &str) -> bool {
+    let literal = host
+        .strip_prefix('[')
+        .and_then(|inner| inner.strip_suffix(']'))
+        .unwrap_or(host);
+    if literal.parse::<IpAddr>().is_ok() {
+        return true;
+    }
+    let name = host.strip_suffix('.').unwrap_or(host);
+    !name.is_empty()
+        && name.len() <= 253
+        && name.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        })
+}
+
+/// The address a bind string listens on locally; `None` when it can't be
+/// bound. IPv6 needs brackets because the listener binds `{address}:{port}`.
+fn local_bind_ip(bind: &str) -> Option<IpAddr> {
+    if bind == "localhost" {
+        return Some(IpAddr::V4(Ipv4Addr::LOCALHOST));
+    }
+    if let Some(inner) = bind.strip_prefix('[').and_then(|b| b.strip_suffix(']')) {
+        return inner.parse::<Ipv6Addr>().ok().map(IpAddr::V6);
+    }
+    bind.parse::<Ipv4Addr>().ok().map(IpAddr::V4)
+}
+
+/// Two local listeners on one port clash when either binds every address.
+fn local_binds_overlap(a: IpAddr, b: IpAddr) -> bool {
+    a == b || a.is_unspecified() || b.is_unspecified()
+}
+
+fn local_bind(tunnel: &SavedTunnel) -> &str {
+    tunnel.bind_address.as_deref().unwrap_or("127.0.0.1")
+}
+
+fn listens_locally(tunnel: &SavedTunnel) -> bool {
+    tunnel.tunnel_type == "local" || tunnel.tunnel_type == "dynamic"
+}
+
+/// Check `tunnel` on its own and against the other saved tunnels.
+/// `local_port_needs_elevation` comes from [`super::privileged_ports`].
+pub fn validate_tunnel(
+    tunnel: &SavedTunnel,
+    saved: &[SavedTunnel],
+    local_port_needs_elevation: bool,
+) -> Vec<TunnelIssue> {
+    let mut issues = Vec::new();
+    let kind = tunnel.tunnel_type.as_str();
+    if !matches!(kind, "local" | "remote" | "dynamic") {
+        issues.push(TunnelIssue::error(
+            "type",
+            format!("Unknown forward type `{kind}`; expected local, remote or dynamic"),
+        ));
+        return issues;
+    }
+
+    if tunnel.local_port == 0 {
+        issues.push(TunnelIssue::error(
+            "localPort",
+            "Local port must be between 1 and 65535",
+        ));
+    }
+    if kind != "dynamic" {
+        if tunnel.remote_port == 0 {
+            issues.push(TunnelIssue::error(
+                "remotePort",
+                "Remote port must be between 1 and 65535",
+            ));
+        }
+        let host = tunnel.remote_host.trim();
+        if !is_valid_host(host) {
+            issues.push(TunnelIssue::error(
+                "remoteHost",
+                format!("`{host}` is not a valid hostname or IP address"),
+            ));
+        }
+    }
+
+    if listens_locally(tunnel) {
+        let bind = local_bind(tunnel);
+        if local_bind_ip(bind).is_none() {
+            let hint = if bind.parse::<Ipv6Addr>().is_ok() {
+                format!("; write IPv6 addresses in brackets, e.g. [{bind}]")
+            } else {
+                String::new()
+            };
+            issues.push(TunnelIssue::error(
+                "bindAddress",
+                format!("`{bind}` is not a local address this machine can listen on{hint}"),
+            ));
+        }
+        if local_port_needs_elevation {
+            issues.push(TunnelIssue::warning(
+                "localPort",
+                format!(
+                    "Port {} is privileged; allow it in Settings → General → Privileged ports",
+                    tunnel.local_port
+                ),
+            ));
+        }
+    } else {
+        let bind = tunnel.bind_address.as_deref().unwrap_or("0.0.0.0");
+        if !(bind.is_empty() || bind == "*" || is_valid_host(bind)) {
+            issues.push(TunnelIssue::error(
+                "bindAddress",
+                format!("`{bind}` is not an address the server can listen on"),
+            ));
+        }
+        if (1..1024).contains(&tunnel.remote_port) {
+            issues.push(TunnelIssue::warning(
+                "remotePort",
+                format!(
+                    "Port {} is privileged on the server; only a root login can forward it",
+                    tunnel.remote_port
+                ),
+            ));
+        }
+    }
+
+    let name = match tunnel_hostname(tunnel) {
+        Some(Err(message)) => {
+            issues.push(TunnelIssue::error("hostname", message));
+            None
+        }
+        Some(Ok(name)) => Some(name),
+        None => None,
+    };
+
+    for other in saved.iter().filter(|other| other.id != tunnel.id) {
+        if let Some(issue) = conflict(tunnel, other) {
+            issues.push(issue);
+        }
+        if name.is_some() && tunnel_hostname(other).and_then(Result::ok) == name {
+            issues.push(TunnelIssue {
+                conflicts_with: Some(other.id.clone()),
+                ..TunnelIssue::warning(
+                    "hostname",
+                    format!(
+                        "`{}` also publishes {}; only one is reachable by name when both run",
+                        other.name,
+                        name.as_deref().unwrap_or_default()
+                    ),
+                )
+            });
+        }
+    }
+    issues
+}
+
+fn conflict(tunnel: &SavedTunnel, other: &SavedTunnel) -> Option<TunnelIssue> {
+    let (field, message) = if listens_locally(tunnel) && listens_locally(other) {
+        let binds = local_bind_ip(local_bind(tunnel)).zip(local_bind_ip(local_bind(other)));
+        if tunnel.local_port == 0
+            || tunnel.local_port != other.local_port
+            || !binds.is_some_and(|(a, b)| local_binds_overlap(a, b))
+        {
+            return None;
+        }
+        (
+            "localPort",
+            format!(
+                "`{}` also listens on local port {}; only one can run at a time unless one \
+                 binds a loopback alias such as 127.0.0.2",
+                other.name, tunnel.local_port
+            ),
+        )
+    } else if tunnel.tunnel_type == "remote" && other.tunnel_type == "remote" {
+        if tunnel.remote_port == 0
+            || tunnel.remote_port != other.remote_port
+            || tunnel.connection_id != other.connection_id
+        {
+            return None;
+        }
+        (
+            "remotePort",
+            format!(
+                "`{}` also forwards server port {} on this host; only one can run at a time",
+                other.name, tunnel.remote_port
+            ),
+        )
+    } else {
+        return None;
+    };
+    Some(TunnelIssue {
+        conflicts_with: Some(other.id.clone()),
+        ..TunnelIssue::warning(field, message)
+    })
+}
+
+/// Warning when another program already listens where `tunnel` would.
+pub fn local_port_taken(tunnel: &SavedTunnel) -> Option<TunnelIssue> {
+    if !listens_locally(tunnel) || tunnel.local_port == 0 {
+        return None;
+    }
+    let ip = local_bind_ip(local_bind(tunnel))?;
+    match std::net::TcpListener::bind((ip, tunnel.local_port)) {
+        Err(error) if error.kind() == std::io::ErrorKind::AddrInUse => Some(TunnelIssue::warning(
+            "localPort",
+            format!(
+                "Local port {} is already in use by another program",
+                tunnel.local_port
+            ),
+        )),
+        _ => None,
+    }
+}
+
+/// The errors in `issues` as one message, or `None` when there are none.
+pub fn blocking_error(issues: &[TunnelIssue]) -> Option<String> {
+    let errors: Vec<&str> = issues
+        .iter()
+        .filter(|issue| issue.severity == IssueSeverity::Error)
+        .map(|issue| issue.message.as_str())
+        .collect();
+    (!errors.is_empty()).then(|| errors.join("; "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tunnel(id: &str, kind: &str, local_port: u16, remote_port: u16) -> SavedTunnel {
+        SavedTunnel {
+            id: id.to_string(),
+            connection_id: "conn".to_string(),
+            name: id.to_string(),
+            tunnel_type: kind.to_string(),
+            local_port,
+            remote_host: "db.internal".to_string(),
+            remote_port,
+            ..Default::default()
+        }
+    }
+
+    fn fields(issues: &[TunnelIssue], severity: IssueSeverity) -> Vec<&'static str> {
+        issues
+            .iter()
+            .filter(|issue| issue.severity == severity)
+            .map(|issue| issue.field)
+            .collect()
+    }
+
+    #[test]
+    fn checks_hosts_and_bind_addresses() {
+        assert!(is_valid_host("db.internal"));
+        assert!(is_valid_host("my_host-1"));
+        assert!(is_valid_host("::1") && is_valid_host("[::1]"));
+        assert!(!is_valid_host("bad host") && !is_valid_host("-x.example") && !is_valid_host(""));
+
+        let mut bad = tunnel("a", "local", 0, 5432);
+        bad.remote_host = "db internal".to_string();
+        bad.bind_address = Some("::1".to_string());
+        let issues = validate_tunnel(&bad, &[], false);
+        assert_eq!(
+            fields(&issues, IssueSeverity::Error),
+            vec!["localPort", "remoteHost", "bindAddress"]
+        );
+        assert!(issues[2].message.contains("[::1]"));
+        assert!(blocking_error(&issues).is_some());
+
+        let mut named = tunnel("n", "local", 8080, 80);
+        named.web = Some(crate::types::TunnelWebSettings {
+            hostname: Some("my app".to_string()),
+            ..Default::default()
+        });
+        let issues = validate_tunnel(&named, &[], false);
+        assert_eq!(fields(&issues, IssueSeverity::Error), vec!["hostname"]);
+
+        let mut remote = tunnel("r", "remote", 3000, 80);
+        remote.bind_address = Some("*".to_string());
+        let issues = validate_tunnel(&remote, &[], false);
+        assert_eq!(fields(&issues, IssueSeverity::Warning), vec!["remotePort"]);
+        assert_eq!(blocking_error(&issues), None);
+    }
+
+    #[test]
+    fn warns_about_privileged_ports_and_conflicts() {
+        let mut web = tunnel("web", "local", 80, 8080);
+        web.bind_address = Some("0.0.0.0".to_string());
+        assert_eq!(
+            fields(&validate_tunnel(&web, &[], true), IssueSeverity::Warning),
+            vec!["localPort"]
+        );
+        assert!(validate_tunnel(&web, &[], false).is_empty());
+
+        let socks = tunnel("socks", "dynamic", 80, 0);
+        let mut other_host = tunnel("remote-a", "remote", 9000, 7000);
+        other_host.connection_id = "other".to_string();
+        let saved = vec![web.clone(), socks.clone(), other_host.clone()];
+        let issues = validate_tunnel(&web, &saved, false);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].conflicts_with.as_deref(), Some("socks"));
+
+        let mut loopback_v6 = socks.clone();
+        loopback_v6.id = "v6".to_string();
+        loopback_v6.bind_address = Some("[::1]".to_string());
+        assert!(validate_tunnel(&loopback_v6, &[socks], false).is_empty());
+
+        let same_server_port = tunnel("remote-b", "remote", 9001, 7000);
+        assert!(validate_tunnel(&same_server_port, &saved, false).is_empty());
+        other_host.connection_id = "conn".to_string();
+        let issues = validate_tunnel(&same_server_port, &[other_host], false);
+        assert_eq!(fields(&issues, IssueSeverity::Warning), vec!["remotePort"]);
+    }
+}
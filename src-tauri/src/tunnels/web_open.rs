@@ -0,0 +1,289 @@
+//! Open a local forward to a web service in the default browser once it answers.
+//!
+//! The URL comes from the tunnel's `web` settings (protocol and path), guessing
+//! HTTPS for the usual TLS ports. Before opening, the local end is polled until
+//! any HTTP response comes back, and the response is checked for the classic
+//! tunnel pitfalls: certificates issued for the real hostname, redirects to the
+//! real hostname (which bypass the tunnel) and backends that reject the
+//! `localhost` Host header.
+
+use crate::types::SavedTunnel;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+const TLS_PORTS: &[u16] = &[443, 8443, 9443];
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+const PROBE_INTERVAL: Duration = Duration::from_millis(300);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebOpenReport {
+    /// The URL that was opened (may differ from the configured one when the
+    /// backend turned out to speak HTTPS).
+    pub url: String,
+    pub status: u16,
+    pub waited_ms: u64,
+    pub warnings: Vec<String>,
+}
+
+/// Browser URL for a local forward, honoring its `web` settings.
+pub(crate) fn browser_url(tunnel: &SavedTunnel) -> Result<String, String> {
+    if tunnel.tunnel_type != "local" {
+        return Err("Only local forwards can be opened in a browser".to_string());
+    }
+    let web = tunnel.web.clone().unwrap_or_default();
+    let protocol = match web.protocol.as_deref().map(str::trim) {
+        _ if web.terminate_tls => "https".to_string(),
+        // The tunnel speaks TLS to the backend; the local end is plain HTTP.
+        _ if web.upstream_tls => "http".to_string(),
+        Some(protocol) if !protocol.is_empty() => protocol.to_ascii_lowercase(),
+        _ if TLS_PORTS.contains(&tunnel.remote_port) => "https".to_string(),
+        _ => "http".to_string(),
+    };
+    if protocol != "http" && protocol != "https" {
+        return Err(format!(
+            "Unsupported protocol '{protocol}'; use http or https"
+        ));
+    }
+    let host = match tunnel.bind_address.as_deref().map(str::trim) {
+        None | Some("" | "127.0.0.1" | "0.0.0.0" | "::" | "localhost") => "localhost".to_string(),
+        Some(address) if address.contains(':') => format!("[{address}]"),
+        Some(address) => address.to_string(),
+    };
+    let path = web.path.as_deref().map(str::trim).unwrap_or_default();
+    let path = if path.starts_with('/') {
+        path.to_string()
+    } else {
+        format!("/{path}")
+    };
+    let url = url::Url::parse(&format!("{protocol}://{host}:{}{path}", tunnel.local_port))
+        .map_err(|error| format!("Invalid browser URL: {error}"))?;
+    Ok(url.to_string())
+}
+
+fn error_chain(error: &dyn std::error::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
+fn is_certificate_error(error: &reqwest::Error) -> bool {
+    let chain = error_chain(error).to_ascii_lowercase();
+    chain.contains("certificate") || chain.contains("self signed") || chain.contains("self-signed")
+}
+
+fn client(
+    accept_invalid_certs: bool,
+    extra_root_pem: Option<&str>,
+) -> Result<reqwest::Client, String> {
+    let mut builder = reqwest::Client::builder();
+    if let Some(pem) = extra_root_pem {
+        let root =
+            reqwest::Certificate::from_pem(pem.as_bytes()).map_err(|error| error.to_string())?;
+        builder = builder.add_root_certificate(root);
+    }
+    builder
+        .timeout(PROBE_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::none())
+        .danger_accept_invalid_certs(accept_invalid_certs)
+        .build()
+        .map_err(|error| error.to_string())
+}
+
+fn is_local_host(host: &str) -> bool {
+    matches!(host, "localhost" | "127.0.0.1" | "[::1]" | "::1")
+}
+
+/// Pitfalls visible in a response that did arrive.
+fn response_warnings(
+    status: u16,
+    location: Option<&str>,
+    remote_host: &str,
+    local_port: u16,
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if (300..400).contains(&status) {
+        let target = location
+            .and_then(|location| url::Url::parse(location).ok())
+            .filter(|target| target.host_str().is_some_and(|host| !is_local_host(host)));
+        if let Some(target) = target {
+            warnings.push(format!(
+                "The service redirects to {}, which bypasses the tunnel. Set the tunnel's Host header to that name so redirects are rewritten, or configure its public URL as localhost:{local_port}.",
+                target.host_str().unwrap_or_default()
+            ));
+        }
+    }
+    if status == 421 {
+        warnings.push(format!(
+            "The service rejected the Host header (421 Misdirected Request); it probably only answers to {remote_host}. Set the tunnel's Host header to that name."
+        ));
+    }
+    warnings
+}
+
+/// Poll `url` until any HTTP response arrives or `timeout` passes, then report
+/// the status and pitfalls. May switch `url` to HTTPS when plain HTTP is refused.
+/// `local_ca_pem` is trusted in addition to the system roots (TLS termination).
+pub(crate) async fn wait_until_ready(
+    url: &str,
+    remote_host: &str,
+    local_port: u16,
+    local_ca_pem: Option<&str>,
+    timeout: Duration,
+) -> Result<WebOpenReport, String> {
+    let started = Instant::now();
+    let strict = client(false, local_ca_pem)?;
+    let lenient = client(true, None)?;
+    let mut url = url.to_string();
+    let mut warnings = Vec::new();
+    let mut last_error = None;
+
+    while started.elapsed() < timeout {
+        let response = match strict.get(&url).send().await {
+            Ok(response) => Ok(response),
+            Err(error) if url.starts_with("https:") && is_certificate_error(&error) => {
+                let warning = format!(
+                    "The certificate is not valid for this address (it is usually issued for {remote_host}); the browser will show a warning you need to accept."
+                );
+                if !warnings.contains(&warning) {
+                    warnings.push(warning);
+                }
+                lenient.get(&url).send().await
+            }
+            Err(error) => Err(error),
+        };
+        match response {
+            Ok(response) => {
+                let status = response.status().as_u16();
+                let location = response
+                    .headers()
+                    .get(reqwest::header::LOCATION)
+                    .and_then(|value| value.to_str().ok())
+                    .map(str::to_string);
+                if status == 400 && url.starts_with("http:") {
+                    let body = response
+                        .text()
+                        .await
+                        .unwrap_or_default()
+                        .to_ascii_lowercase();
+                    if body.contains("https port") || body.contains("ssl") {
+                        url = url.replacen("http:", "https:", 1);
+                        warnings.push(
+                            "The service only speaks HTTPS; set the tunnel's protocol to https."
+                                .to_string(),
+                        );
+                        continue;
+                    }
+                }
+                warnings.extend(response_warnings(
+                    status,
+                    location.as_deref(),
+                    remote_host,
+                    local_port,
+                ));
+                return Ok(WebOpenReport {
+                    url,
+                    status,
+                    waited_ms: started.elapsed().as_millis() as u64,
+                    warnings,
+                });
+            }
+            Err(error) => {
+                last_error = Some(error_chain(&error));
+                tokio::time::sleep(PROBE_INTERVAL).await;
+            }
+        }
+    }
+    Err(format!(
+        "Nothing answered on {url} within {}s. Is the tunnel running?{}",
+        timeout.as_secs(),
+        last_error
+            .map(|error| format!(" Last error: {error}"))
+            .unwrap_or_default()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TunnelWebSettings;
+
+    fn tunnel(remote_port: u16, web: Option<TunnelWebSettings>) -> SavedTunnel {
+        SavedTunnel {
+            id: "web".to_string(),
+            connection_id: "conn".to_string(),
+            name: "Web".to_string(),
+            tunnel_type: "local".to_string(),
+            local_port: 18080,
+            remote_host: "app.internal".to_string(),
+            remote_port,
+            bind_address: Some("127.0.0.1".to_string()),
+            web,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn browser_url_honors_protocol_and_path() {
+        assert_eq!(
+            browser_url(&tunnel(8080, None)).unwrap(),
+            "http://localhost:18080/"
+        );
+        assert_eq!(
+            browser_url(&tunnel(443, None)).unwrap(),
+            "https://localhost:18080/"
+        );
+        let settings = TunnelWebSettings {
+            protocol: Some("HTTPS".to_string()),
+            path: Some("admin/login".to_string()),
+            terminate_tls: false,
+            inspect: false,
+            host_header: None,
+            upstream_tls: false,
+            hostname: None,
+        };
+        assert_eq!(
+            browser_url(&tunnel(8080, Some(settings))).unwrap(),
+            "https://localhost:18080/admin/login"
+        );
+        let terminated = TunnelWebSettings {
+            terminate_tls: true,
+            ..TunnelWebSettings::default()
+        };
+        assert_eq!(
+            browser_url(&tunnel(8080, Some(terminated))).unwrap(),
+            "https://localhost:18080/"
+        );
+        let mut dynamic = tunnel(8080, None);
+        dynamic.tunnel_type = "dynamic".to_string();
+        assert!(browser_url(&dynamic).is_err());
+    }
+
+    #[test]
+    fn redirects_off_the_tunnel_and_421_are_reported() {
+        assert_eq!(
+            response_warnings(
+                302,
+                Some("https://app.internal/login"),
+                "app.internal",
+                18080
+            )
+            .len(),
+            1
+        );
+        assert!(response_warnings(
+            302,
+            Some("http://localhost:18080/login"),
+            "app.internal",
+            18080
+        )
+        .is_empty());
+        assert!(response_warnings(302, Some("/login"), "app.internal", 18080).is_empty());
+        assert_eq!(response_warnings(421, None, "app.internal", 18080).len(), 1);
+    }
+}
@@ -9,6 +9,114 @@ pub struct ConnectionConfig {
     pub username: String,
     pub auth_method: AuthMethod,
     pub jump_host: Option<Box<ConnectionConfig>>,
+    #[serde(default)]
+    pub strict_host_key_checking: StrictHostKeyChecking,
+    /// See [`crate::remote_charset`]. Jump hosts ignore it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    /// Transport settings; jump hosts use their own.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ssh_options: Option<SshTransportOptions>,
+    /// Name looked up in known_hosts instead of `host:port`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host_key_alias: Option<String>,
+    /// Space-separated known_hosts files replacing `~/.ssh/known_hosts`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_known_hosts_file: Option<String>,
+    /// When to close the session as its users go away; see [`crate::session_refs`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_lifetime: Option<crate::session_refs::SessionLifetime>,
+}
+
+#[cfg(test)]
+impl ConnectionConfig {
+    /// Password-auth fixture for `username@host:22`, with `id` and `name` set to `host`.
+    pub(crate) fn for_test(host: &str, username: &str) -> Self {
+        Self {
+            id: host.to_string(),
+            name: host.to_string(),
+            host: host.to_string(),
+            port: 22,
+            username: username.to_string(),
+            auth_method: AuthMethod::Password {
+                password: String::new(),
+            },
+            jump_host: None,
+            strict_host_key_checking: StrictHostKeyChecking::default(),
+            encoding: None,
+            locale: None,
+            ssh_options: None,
+            host_key_alias: None,
+            user_known_hosts_file: None,
+            session_lifetime: None,
+        }
+    }
+}
+
+/// Per-host OpenSSH settings read from `ssh_config` on import: timeouts,
+/// keep-alives, compression, hostname canonicalization and `LocalCommand`,
+/// plus the WebSocket bridge or zero-trust proxy to connect through. Unset
+/// fields keep Zync's defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SshTransportOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connect_timeout_secs: Option<u64>,
+    /// `0` turns keep-alives off.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_alive_interval_secs: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_alive_count_max: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compression: Option<bool>,
+    /// See [`crate::canonical_host`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub canonicalize_hostname: Option<CanonicalizeHostname>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub canonical_domains: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub canonicalize_max_dots: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub canonicalize_fallback_local: Option<bool>,
+    /// See [`crate::local_command`]; runs only with `permit_local_command`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_command: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permit_local_command: Option<bool>,
+    /// Reach the server through a WebSocket bridge instead of TCP; see
+    /// [`crate::websocket_transport`]. Ignored behind a jump host.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub websocket: Option<crate::websocket_transport::WebSocketTransport>,
+    /// Zero-trust proxy to log in to and connect through; see
+    /// [`crate::access_proxy`]. Ignored behind a jump host.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub access_proxy: Option<crate::access_proxy::AccessProxy>,
+}
+
+/// OpenSSH `CanonicalizeHostname`: `yes` skips hosts reached through a jump
+/// host, `always` doesn't.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CanonicalizeHostname {
+    #[default]
+    No,
+    Yes,
+    Always,
+}
+
+/// Host key policy against `~/.ssh/known_hosts`, as OpenSSH's `StrictHostKeyChecking`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StrictHostKeyChecking {
+    /// Only connect to hosts whose key is already recorded.
+    Yes,
+    /// Record keys of new hosts; refuse keys that changed.
+    #[default]
+    AcceptNew,
+    /// Record new keys and accept changed ones (with a warning).
+    No,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,6 +151,11 @@ pub struct ConnectionResponse {
     pub message: String,
     pub term_id: Option<String>,
     pub detected_os: Option<String>,
+    /// Resolved theme and production flag for the connected host.
+    pub profile: Option<crate::profiles::ConnectionProfile>,
+    /// Set when nothing was connected because the user chose to switch to this
+    /// connection's existing session instead.
+    pub focus_connection_id: Option<String>,
 }
 
 /// A reference to a vault item used as SSH credentials.
@@ -84,7 +197,7 @@ pub enum CredentialPurpose {
     SshAuth,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")] // Match TS interface
 pub struct SavedConnection {
     pub id: String,
@@ -105,12 +218,41 @@ pub struct SavedConnection {
     pub pinned_features: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub auth_ref: Option<CredentialRef>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strict_host_key_checking: Option<StrictHostKeyChecking>,
+    /// Connect when the app launches (device-local; not synced).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_connect: Option<bool>,
+    /// Remote character encoding label (`latin1`, `GBK`, …); unset means UTF-8.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+    /// Locale exported as `LANG` and `LC_ALL` in terminals.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ssh_options: Option<SshTransportOptions>,
+    /// OpenSSH `HostKeyAlias`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host_key_alias: Option<String>,
+    /// OpenSSH `UserKnownHostsFile`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_known_hosts_file: Option<String>,
+    /// Where the password lives when it is kept out of this file
+    /// (`keychain`); see `secret_split`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret_ref: Option<String>,
+    /// Close the session with its last terminal, last tunnel or last user.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_lifetime: Option<crate::session_refs::SessionLifetime>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Folder {
     pub name: String,
     pub tags: Option<Vec<String>>,
+    /// Smart-group query (see `smart_groups::query`); membership is computed, not stored.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -119,7 +261,7 @@ pub struct SavedData {
     pub folders: Vec<Folder>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SavedTunnel {
     pub id: String,
@@ -143,6 +285,67 @@ pub struct SavedTunnel {
     pub created_at: Option<u64>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub updated_at: Option<u64>,
+    /// Browser settings for local forwards to web services (device-local; not synced).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub web: Option<TunnelWebSettings>,
+    /// Domains a dynamic tunnel carries in the generated PAC file (device-local; not synced).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pac_domains: Option<Vec<String>>,
+    /// Point the OS proxy settings at this dynamic tunnel while it runs
+    /// (device-local; not synced). See `tunnels::system_proxy`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_proxy: Option<bool>,
+    /// Keep the tunnel up only inside these windows (device-local; not
+    /// synced). See `tunnels::schedule`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<TunnelSchedule>,
+}
+
+/// Browser-facing settings for a local forward: how `tunnel_open_in_browser`
+/// builds the URL and which layers (TLS, inspector, Host rewriting) wrap the listener.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TunnelWebSettings {
+    /// `http` or `https`; guessed from the remote port when unset.
+    pub protocol: Option<String>,
+    /// Appended to the URL, e.g. `/admin`.
+    pub path: Option<String>,
+    /// Serve the local end over HTTPS with a certificate from the local CA
+    /// (see `tunnels::local_tls`); the backend stays plain HTTP.
+    #[serde(default)]
+    pub terminate_tls: bool,
+    /// Log HTTP request lines, statuses and timings (never bodies); see
+    /// `tunnels::http_inspect`.
+    #[serde(default)]
+    pub inspect: bool,
+    /// Host header (and upstream SNI) the backend expects, e.g. `app.internal`;
+    /// redirects and cookie domains for it are pointed back at the tunnel.
+    pub host_header: Option<String>,
+    /// The backend speaks HTTPS: re-encrypt over the SSH channel so the local
+    /// end can stay plain HTTP or use the local CA.
+    #[serde(default)]
+    pub upstream_tls: bool,
+    /// Publish `<hostname>.zync.local` for the listener while the forward
+    /// runs; see `tunnels::hostnames`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+}
+
+/// Weekly windows in which a tunnel may run.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TunnelSchedule {
+    /// Days a window opens on, 0 = Monday through 6 = Sunday; empty means every day.
+    #[serde(default)]
+    pub days: Vec<u8>,
+    /// Minutes after midnight. A window whose end isn't after its start runs
+    /// past midnight; equal times mean a full day.
+    pub start_minute: u16,
+    pub end_minute: u16,
+    /// Offset from UTC the times are written in, fixed so daylight saving
+    /// doesn't move the window.
+    #[serde(default)]
+    pub utc_offset_minutes: i32,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
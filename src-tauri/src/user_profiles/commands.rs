@@ -0,0 +1,152 @@
+use super::{
+    active_id, default_data_dir, profile_root, read_registry, registry_path, settings_dir,
+    write_registry, ProfileRegistry, UserProfile, DEFAULT_PROFILE_ID,
+};
+use crate::commands::{read_effective_settings, AppState};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, State};
+
+static REGISTRY_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfilesOverview {
+    /// The profile this window is running.
+    pub active: String,
+    pub profiles: Vec<UserProfile>,
+}
+
+fn config_root(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path().config_dir().map_err(|e| e.to_string())
+}
+
+fn overview(registry: &ProfileRegistry) -> ProfilesOverview {
+    ProfilesOverview {
+        active: active_id(),
+        profiles: registry.all(),
+    }
+}
+
+#[tauri::command]
+pub async fn user_profiles_list(app: AppHandle) -> Result<ProfilesOverview, String> {
+    let _guard = REGISTRY_LOCK.lock().map_err(|e| e.to_string())?;
+    let path = registry_path(&config_root(&app)?);
+    Ok(overview(&read_registry(&path)))
+}
+
+/// Create a profile. With `copy_settings`, it starts from the current
+/// profile's settings, except the data location.
+#[tauri::command]
+pub async fn user_profiles_create(
+    app: AppHandle,
+    name: String,
+    color: Option<String>,
+    ssh_dir: Option<String>,
+    copy_settings: Option<bool>,
+) -> Result<UserProfile, String> {
+    let _guard = REGISTRY_LOCK.lock().map_err(|e| e.to_string())?;
+    let root = config_root(&app)?;
+    let path = registry_path(&root);
+    let mut registry = read_registry(&path);
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    let profile = registry.create(&name, color, ssh_dir, now)?;
+
+    let settings_path = settings_dir(&root, &profile.id).join("settings.json");
+    if copy_settings.unwrap_or(false) && !settings_path.exists() {
+        let mut settings = read_effective_settings(&app)?;
+        if let Some(object) = settings.as_object_mut() {
+            object.remove("dataPath");
+        }
+        let json = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
+        crate::atomic_io::durable_replace(&settings_path, json.as_bytes())
+            .map_err(|e| e.to_string())?;
+    }
+    write_registry(&path, &registry)?;
+    Ok(profile)
+}
+
+/// Rename a profile or change its color or ssh directory. A new ssh
+/// directory for the running profile applies after the next launch.
+#[tauri::command]
+pub async fn user_profiles_update(
+    app: AppHandle,
+    profile: UserProfile,
+) -> Result<UserProfile, String> {
+    let _guard = REGISTRY_LOCK.lock().map_err(|e| e.to_string())?;
+    let path = registry_path(&config_root(&app)?);
+    let mut registry = read_registry(&path);
+    let profile = registry.update(profile)?;
+    write_registry(&path, &registry)?;
+    Ok(profile)
+}
+
+/// Delete a profile. With `remove_files`, its settings, known_hosts and
+/// default data directory (connections, vault, snippets) are removed too; a
+/// custom `dataPath` is never touched.
+#[tauri::command]
+pub async fn user_profiles_delete(
+    app: AppHandle,
+    id: String,
+    remove_files: Option<bool>,
+) -> Result<(), String> {
+    let _guard = REGISTRY_LOCK.lock().map_err(|e| e.to_string())?;
+    let root = config_root(&app)?;
+    let path = registry_path(&root);
+    let mut registry = read_registry(&path);
+    registry.remove(&id, &active_id())?;
+    write_registry(&path, &registry)?;
+
+    if remove_files.unwrap_or(false) {
+        let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+        let dirs = [
+            profile_root(&root, &id),
+            Some(default_data_dir(&app_data_dir, &id)),
+        ];
+        for dir in dirs.into_iter().flatten().filter(|dir| dir.is_dir()) {
+            std::fs::remove_dir_all(&dir)
+                .map_err(|e| format!("Failed to remove {}: {e}", dir.display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Make `id` the active profile and restart Zync into it. Refuses while
+/// connections are open unless `force` is set.
+#[tauri::command]
+pub async fn user_profiles_switch(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+    force: Option<bool>,
+) -> Result<(), String> {
+    if !force.unwrap_or(false) {
+        let open = state.connections.lock().await.len();
+        if open > 0 {
+            return Err(format!(
+                "{open} connection{} still open; switching profiles restarts Zync",
+                if open == 1 { " is" } else { "s are" }
+            ));
+        }
+    }
+    let _guard = REGISTRY_LOCK.lock().map_err(|e| e.to_string())?;
+    let path = registry_path(&config_root(&app)?);
+    let mut registry = read_registry(&path);
+    let profile = registry
+        .find(&id)
+        .ok_or_else(|| format!("Profile {id} not found"))?;
+    registry.active = (profile.id != DEFAULT_PROFILE_ID).then_some(profile.id);
+    write_registry(&path, &registry)?;
+
+    // Let the reply reach the window before the process goes away.
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        app.restart();
+    });
+    Ok(())
+}
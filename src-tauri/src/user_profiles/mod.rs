@@ -0,0 +1,357 @@
+//! Named user profiles (work, personal, one per client) with isolated storage.
+//!
+//! The built-in `default` profile keeps the original locations. Any other
+//! profile gets its own settings (`<config>/Zync/Profiles/<id>/User`), its own
+//! data directory (`<app data>/profiles/<id>` unless its settings set
+//! `dataPath`) and with it its own connections, vault, snippets and history,
+//! plus its own known_hosts file. A profile may also name the directory its
+//! keys and ssh_config live in instead of `~/.ssh`.
+//!
+//! The active profile is read once at launch; switching restarts the app, so
+//! nothing cached from the previous profile survives.
+
+pub mod commands;
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+pub const DEFAULT_PROFILE_ID: &str = "default";
+const REGISTRY_FILE: &str = "profiles.json";
+const MAX_NAME_LEN: usize = 64;
+const MAX_PROFILES: usize = 50;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserProfile {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub color: Option<String>,
+    /// Directory holding this profile's keys and ssh_config; `~/.ssh` when unset.
+    #[serde(default)]
+    pub ssh_dir: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<u64>,
+}
+
+/// `profiles.json`, shared by all profiles. The default profile is stored
+/// only when renamed or given a color or ssh directory.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileRegistry {
+    #[serde(default)]
+    pub active: Option<String>,
+    #[serde(default)]
+    pub profiles: Vec<UserProfile>,
+}
+
+impl ProfileRegistry {
+    /// Every profile, the default one first.
+    pub fn all(&self) -> Vec<UserProfile> {
+        let mut all = vec![self
+            .profiles
+            .iter()
+            .find(|profile| profile.id == DEFAULT_PROFILE_ID)
+            .cloned()
+            .unwrap_or_else(default_profile)];
+        all.extend(
+            self.profiles
+                .iter()
+                .filter(|profile| profile.id != DEFAULT_PROFILE_ID)
+                .cloned(),
+        );
+        all
+    }
+
+    pub fn find(&self, id: &str) -> Option<UserProfile> {
+        self.all().into_iter().find(|profile| profile.id == id)
+    }
+
+    /// The profile to launch into; an unknown id falls back to the default.
+    pub fn active_profile(&self) -> UserProfile {
+        self.active
+            .as_deref()
+            .and_then(|id| self.find(id))
+            .unwrap_or_else(|| {
+                self.find(DEFAULT_PROFILE_ID)
+                    .unwrap_or_else(default_profile)
+            })
+    }
+
+    /// Add a profile with an id derived from its name.
+    pub fn create(
+        &mut self,
+        name: &str,
+        color: Option<String>,
+        ssh_dir: Option<String>,
+        now: u64,
+    ) -> Result<UserProfile, String> {
+        if self.all().len() >= MAX_PROFILES {
+            return Err(format!("Zync supports up to {MAX_PROFILES} profiles"));
+        }
+        let name = validate_name(name, &self.all(), None)?;
+        let existing: Vec<String> = self.all().into_iter().map(|profile| profile.id).collect();
+        let profile = UserProfile {
+            id: unique_id(&name, &existing),
+            name,
+            color: clean(color),
+            ssh_dir: clean(ssh_dir),
+            created_at: Some(now),
+        };
+        self.profiles.push(profile.clone());
+        Ok(profile)
+    }
+
+    /// Replace a profile's name, color and ssh directory; the id never changes.
+    pub fn update(&mut self, update: UserProfile) -> Result<UserProfile, String> {
+        let current = self
+            .find(&update.id)
+            .ok_or_else(|| format!("Profile {} not found", update.id))?;
+        let profile = UserProfile {
+            name: validate_name(&update.name, &self.all(), Some(&update.id))?,
+            color: clean(update.color),
+            ssh_dir: clean(update.ssh_dir),
+            ..current
+        };
+        match self.profiles.iter_mut().find(|p| p.id == profile.id) {
+            Some(slot) => *slot = profile.clone(),
+            None => self.profiles.push(profile.clone()),
+        }
+        Ok(profile)
+    }
+
+    /// Remove a profile from the registry. The default and the active
+    /// profile can't be removed.
+    pub fn remove(&mut self, id: &str, active_id: &str) -> Result<(), String> {
+        if id == DEFAULT_PROFILE_ID {
+            return Err("The default profile can't be deleted".to_string());
+        }
+        if id == active_id {
+            return Err("Switch to another profile before deleting this one".to_string());
+        }
+        let before = self.profiles.len();
+        self.profiles.retain(|profile| profile.id != id);
+        if self.profiles.len() == before {
+            return Err(format!("Profile {id} not found"));
+        }
+        if self.active.as_deref() == Some(id) {
+            self.active = None;
+        }
+        Ok(())
+    }
+}
+
+pub fn default_profile() -> UserProfile {
+    UserProfile {
+        id: DEFAULT_PROFILE_ID.to_string(),
+        name: "Default".to_string(),
+        color: None,
+        ssh_dir: None,
+        created_at: None,
+    }
+}
+
+fn clean(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn validate_name(name: &str, all: &[UserProfile], own_id: Option<&str>) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Profile name is required".to_string());
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(format!(
+            "Profile names are limited to {MAX_NAME_LEN} characters"
+        ));
+    }
+    if all.iter().any(|profile| {
+        Some(profile.id.as_str()) != own_id && profile.name.eq_ignore_ascii_case(name)
+    }) {
+        return Err(format!("A profile named \"{name}\" already exists"));
+    }
+    Ok(name.to_string())
+}
+
+/// Directory-safe id from a name: lowercase ASCII letters, digits and dashes,
+/// suffixed with a number when taken.
+pub fn unique_id(name: &str, existing: &[String]) -> String {
+    let mut slug = String::new();
+    for c in name.chars().flat_map(char::to_lowercase) {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    let base = if slug.is_empty() { "profile" } else { slug };
+    let taken = |id: &str| existing.iter().any(|e| e.eq_ignore_ascii_case(id));
+    if !taken(base) {
+        return base.to_string();
+    }
+    (2..)
+        .map(|n| format!("{base}-{n}"))
+        .find(|id| !taken(id))
+        .unwrap_or_else(|| base.to_string())
+}
+
+pub fn registry_path(config_root: &Path) -> PathBuf {
+    config_root.join("Zync").join(REGISTRY_FILE)
+}
+
+/// A missing or unreadable registry means only the default profile exists.
+pub fn read_registry(path: &Path) -> ProfileRegistry {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn write_registry(path: &Path, registry: &ProfileRegistry) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(registry).map_err(|e| e.to_string())?;
+    crate::atomic_io::durable_replace(path, json.as_bytes()).map_err(|e| e.to_string())
+}
+
+/// Root of a non-default profile's own files under the config directory.
+pub fn profile_root(config_root: &Path, id: &str) -> Option<PathBuf> {
+    (id != DEFAULT_PROFILE_ID).then(|| config_root.join("Zync").join("Profiles").join(id))
+}
+
+/// Settings directory for a profile.
+pub fn settings_dir(config_root: &Path, id: &str) -> PathBuf {
+    profile_root(config_root, id)
+        .unwrap_or_else(|| config_root.join("Zync"))
+        .join("User")
+}
+
+/// Data directory for a profile whose settings don't set `dataPath`.
+pub fn default_data_dir(app_data_dir: &Path, id: &str) -> PathBuf {
+    if id == DEFAULT_PROFILE_ID {
+        app_data_dir.to_path_buf()
+    } else {
+        app_data_dir.join("profiles").join(id)
+    }
+}
+
+struct ActiveProfile {
+    profile: UserProfile,
+    known_hosts: Option<PathBuf>,
+}
+
+static ACTIVE: OnceLock<ActiveProfile> = OnceLock::new();
+
+/// Resolve the active profile. Must run before the settings or data
+/// directory is first read.
+pub fn init(config_root: Option<&Path>) {
+    let profile = config_root
+        .map(|root| read_registry(&registry_path(root)).active_profile())
+        .unwrap_or_else(default_profile);
+    let known_hosts = config_root
+        .and_then(|root| profile_root(root, &profile.id))
+        .map(|root| root.join("known_hosts"));
+    let _ = ACTIVE.set(ActiveProfile {
+        profile,
+        known_hosts,
+    });
+}
+
+pub fn active() -> UserProfile {
+    ACTIVE
+        .get()
+        .map(|active| active.profile.clone())
+        .unwrap_or_else(default_profile)
+}
+
+pub fn active_id() -> String {
+    active().id
+}
+
+/// The known_hosts file used when a host sets no `UserKnownHostsFile`:
+/// `~/.ssh/known_hosts` for the default profile, the profile's own otherwise.
+pub fn default_known_hosts_file(home: &Path) -> PathBuf {
+    ACTIVE
+        .get()
+        .and_then(|active| active.known_hosts.clone())
+        .unwrap_or_else(|| home.join(".ssh").join("known_hosts"))
+}
+
+/// Directory of the active profile's keys and ssh_config.
+pub fn ssh_dir(home: &Path) -> PathBuf {
+    match active().ssh_dir {
+        Some(dir) => match dir.strip_prefix("~/") {
+            Some(rest) => home.join(rest),
+            None => PathBuf::from(dir),
+        },
+        None => home.join(".ssh"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn creates_unique_ids_and_rejects_duplicate_names() {
+        let mut registry = ProfileRegistry::default();
+        let work = registry.create(" Work ", None, None, 1).unwrap();
+        assert_eq!((work.id.as_str(), work.name.as_str()), ("work", "Work"));
+        assert!(registry.create("work", None, None, 2).is_err());
+        let client = registry
+            .create("Client: ACME/EU", Some("#f00".into()), None, 3)
+            .unwrap();
+        assert_eq!(client.id, "client-acme-eu");
+        assert_eq!(unique_id("Default", &["default".to_string()]), "default-2");
+        assert_eq!(unique_id("日本", &[]), "profile");
+
+        let ids: Vec<String> = registry.all().into_iter().map(|p| p.id).collect();
+        assert_eq!(ids, ["default", "work", "client-acme-eu"]);
+    }
+
+    #[test]
+    fn resolves_the_active_profile_and_guards_removal() {
+        let mut registry = ProfileRegistry::default();
+        let work = registry.create("Work", None, None, 1).unwrap();
+        registry.active = Some(work.id.clone());
+        assert_eq!(registry.active_profile().id, "work");
+        assert!(registry.remove("work", "work").is_err());
+        assert!(registry.remove(DEFAULT_PROFILE_ID, "work").is_err());
+
+        registry.active = Some("gone".to_string());
+        assert_eq!(registry.active_profile().id, DEFAULT_PROFILE_ID);
+
+        let renamed = registry
+            .update(UserProfile {
+                name: "Personal".to_string(),
+                ..default_profile()
+            })
+            .unwrap();
+        assert_eq!(renamed.name, "Personal");
+        assert_eq!(registry.all()[0].name, "Personal");
+        registry.remove("work", DEFAULT_PROFILE_ID).unwrap();
+        assert_eq!(registry.all().len(), 1);
+    }
+
+    #[test]
+    fn isolates_paths_for_non_default_profiles() {
+        let config = Path::new("/cfg");
+        let data = Path::new("/data");
+        assert_eq!(
+            settings_dir(config, DEFAULT_PROFILE_ID),
+            Path::new("/cfg/Zync/User")
+        );
+        assert_eq!(
+            settings_dir(config, "work"),
+            Path::new("/cfg/Zync/Profiles/work/User")
+        );
+        assert_eq!(default_data_dir(data, DEFAULT_PROFILE_ID), data);
+        assert_eq!(
+            default_data_dir(data, "work"),
+            Path::new("/data/profiles/work")
+        );
+        assert!(profile_root(config, DEFAULT_PROFILE_ID).is_none());
+    }
+}
@@ -6,6 +6,8 @@ use tauri::State;
 use tokio::sync::Mutex;
 use zeroize::Zeroize;
 
+use crate::data_format::to_versioned_json;
+use crate::data_validation::{parse_or_repair, DataFile};
 use crate::types::SavedData;
 use crate::vault::credential::validate_secret_values_for_kind;
 use crate::vault::error::VaultError;
@@ -494,12 +496,13 @@ fn load_saved_connections(path: &std::path::Path) -> Result<SavedData, VaultErro
     }
     let raw = std::fs::read_to_string(path)
         .map_err(|e| VaultError::InvalidData(format!("read connections file: {e}")))?;
-    serde_json::from_str(&raw).map_err(VaultError::Serde)
+    parse_or_repair(DataFile::Connections, path, &raw).map_err(VaultError::InvalidData)
 }
 
 fn save_saved_connections(path: &std::path::Path, saved: &SavedData) -> Result<(), VaultError> {
     use std::io::Write;
-    let json = serde_json::to_string_pretty(saved).map_err(VaultError::Serde)?;
+    let json = to_versioned_json(DataFile::Connections, path, saved)
+        .map_err(VaultError::InvalidData)?;
     let unique_suffix = uuid::Uuid::new_v4();
     let tmp = path.with_extension(format!("json.tmp.{unique_suffix}"));
     let mut f = std::fs::OpenOptions::new()
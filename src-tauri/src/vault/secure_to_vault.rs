@@ -3,6 +3,8 @@ use std::path::Path;
 
 use serde::{Deserialize, Serialize};
 
+use crate::data_format::to_versioned_json;
+use crate::data_validation::{parse_or_repair, DataFile};
 use crate::types::{CredentialItemKind, CredentialPurpose, CredentialRef, SavedData};
 use crate::vault::credential::secret_values_from_legacy;
 use crate::vault::error::VaultError;
@@ -270,7 +272,8 @@ pub fn secure(data_dir: &Path, vault: &VaultService) -> Result<SecureToVaultResu
     }
 
     let secured = linked.len() as u32;
-    let updated_json = serde_json::to_string_pretty(&saved).map_err(VaultError::Serde)?;
+    let updated_json = to_versioned_json(DataFile::Connections, &connections_path, &saved)
+        .map_err(VaultError::InvalidData)?;
     if let Err(e) = atomic_write(&connections_path, &updated_json) {
         cleanup_created_items(vault, &created_for_cleanup);
         return Err(e);
@@ -332,7 +335,7 @@ fn load_connections(data_dir: &Path) -> Result<SavedData, VaultError> {
     }
     let raw = std::fs::read_to_string(&path)
         .map_err(|e| VaultError::InvalidData(format!("read connections.json: {e}")))?;
-    serde_json::from_str(&raw).map_err(VaultError::Serde)
+    parse_or_repair(DataFile::Connections, &path, &raw).map_err(VaultError::InvalidData)
 }
 
 fn atomic_write(path: &Path, content: &str) -> Result<(), VaultError> {
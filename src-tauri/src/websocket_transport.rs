@@ -0,0 +1,175 @@
+//! SSH over a WebSocket bridge, for networks that only let HTTPS out.
+//!
+//! The bridge (websockify, wstunnel, a cloud provider's SSH endpoint) relays
+//! binary frames to the SSH server's TCP port. What russh writes goes out as
+//! binary messages and the bridge's binary messages are the server's bytes,
+//! so the rest of the connection (host keys, auth, channels) is unchanged.
+//! `host` and `port` still name the server for known_hosts.
+
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncWriteExt, DuplexStream};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::handshake::client::Request;
+use tokio_tungstenite::tungstenite::http::{HeaderName, HeaderValue};
+use tokio_tungstenite::tungstenite::Message;
+
+const BUFFER: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebSocketTransport {
+    /// `wss://` (or `ws://`) URL of the bridge.
+    pub url: String,
+    /// Sent with the upgrade request, e.g. a token the bridge checks.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<WebSocketHeader>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebSocketHeader {
+    pub name: String,
+    pub value: String,
+}
+
+fn request(transport: &WebSocketTransport) -> Result<Request, String> {
+    let url = transport.url.trim();
+    if !url.starts_with("wss://") && !url.starts_with("ws://") {
+        return Err(format!(
+            "WebSocket URL must start with wss:// or ws://: {url}"
+        ));
+    }
+    let mut request = url
+        .into_client_request()
+        .map_err(|e| format!("Invalid WebSocket URL {url}: {e}"))?;
+    for header in &transport.headers {
+        let name = HeaderName::from_bytes(header.name.trim().as_bytes())
+            .map_err(|_| format!("Invalid WebSocket header name: {:?}", header.name))?;
+        let value = HeaderValue::from_str(&header.value)
+            .map_err(|_| format!("Invalid value for WebSocket header {name}"))?;
+        request.headers_mut().insert(name, value);
+    }
+    Ok(request)
+}
+
+/// Open the bridge and return a byte stream to hand to
+/// `russh::client::connect_stream`.
+pub async fn connect(transport: &WebSocketTransport) -> anyhow::Result<DuplexStream> {
+    let request = request(transport).map_err(anyhow::Error::msg)?;
+    let (socket, _) = tokio_tungstenite::connect_async(request)
+        .await
+        .map_err(|e| anyhow::anyhow!("WebSocket bridge {}: {e}", transport.url.trim()))?;
+    let (ssh_side, bridge_side) = tokio::io::duplex(BUFFER);
+    tauri::async_runtime::spawn(relay(socket, bridge_side));
+    Ok(ssh_side)
+}
+
+/// Copy between the SSH side of the duplex and the socket until either closes.
+async fn relay<S>(socket: S, stream: DuplexStream)
+where
+    S: futures_util::Stream<Item = tokio_tungstenite::tungstenite::Result<Message>>
+        + futures_util::Sink<Message, Error = tokio_tungstenite::tungstenite::Error>
+        + Unpin,
+{
+    let (mut sink, mut source) = socket.split();
+    let (mut reader, mut writer) = tokio::io::split(stream);
+
+    let outbound = async {
+        let mut buf = vec![0u8; BUFFER];
+        loop {
+            let n = reader.read(&mut buf).await.unwrap_or(0);
+            if n == 0 {
+                break;
+            }
+            if sink.send(Message::binary(buf[..n].to_vec())).await.is_err() {
+                return;
+            }
+        }
+        let _ = sink.close().await;
+    };
+    let inbound = async {
+        while let Some(Ok(message)) = source.next().await {
+            let data = match message {
+                Message::Binary(data) => data,
+                Message::Close(_) => break,
+                // Pings are answered by tungstenite; bridges don't send text.
+                _ => continue,
+            };
+            if writer.write_all(&data).await.is_err() {
+                break;
+            }
+        }
+        let _ = writer.shutdown().await;
+    };
+    // Either direction ending means the connection is over.
+    tokio::select! {
+        _ = outbound => {}
+        _ = inbound => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transport(url: &str, headers: &[(&str, &str)]) -> WebSocketTransport {
+        WebSocketTransport {
+            url: url.to_string(),
+            headers: headers
+                .iter()
+                .map(|(name, value)| WebSocketHeader {
+                    name: name.to_string(),
+                    value: value.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn builds_the_upgrade_request() {
+        let upgrade = request(&transport(
+            " wss://bridge.example.com/ssh/web01 ",
+            &[("Authorization", "Bearer abc")],
+        ))
+        .unwrap();
+        assert_eq!(upgrade.uri(), "wss://bridge.example.com/ssh/web01");
+        assert_eq!(upgrade.headers()["authorization"], "Bearer abc");
+
+        assert!(request(&transport("https://bridge.example.com", &[])).is_err());
+        assert!(request(&transport("wss://bridge", &[("bad name", "x")])).is_err());
+    }
+
+    #[tokio::test]
+    async fn relays_bytes_both_ways() {
+        let (client, server) = tokio::io::duplex(BUFFER);
+        let socket = tokio_tungstenite::WebSocketStream::from_raw_socket(
+            client,
+            tokio_tungstenite::tungstenite::protocol::Role::Client,
+            None,
+        )
+        .await;
+        let mut bridge = tokio_tungstenite::WebSocketStream::from_raw_socket(
+            server,
+            tokio_tungstenite::tungstenite::protocol::Role::Server,
+            None,
+        )
+        .await;
+        let (mut ssh, relayed) = tokio::io::duplex(BUFFER);
+        tokio::spawn(relay(socket, relayed));
+
+        ssh.write_all(b"SSH-2.0-zync\r\n").await.unwrap();
+        let sent = bridge.next().await.unwrap().unwrap();
+        assert_eq!(sent.into_data().as_ref(), b"SSH-2.0-zync\r\n");
+
+        bridge
+            .send(Message::binary(b"SSH-2.0-OpenSSH_9.6\r\n".to_vec()))
+            .await
+            .unwrap();
+        let mut received = [0u8; 21];
+        ssh.read_exact(&mut received).await.unwrap();
+        assert_eq!(&received, b"SSH-2.0-OpenSSH_9.6\r\n");
+
+        bridge.close(None).await.unwrap();
+        assert_eq!(ssh.read(&mut received).await.unwrap(), 0);
+    }
+}
@@ -1,5 +1,6 @@
 import { MainLayout } from './components/layout/MainLayout';
 import { UpdateNotification } from './components/UpdateNotification';
+import { WorkspaceRestorePrompt } from './components/WorkspaceRestorePrompt';
 import { ToastContainer } from './components/ui/Toast';
 import { useEffect } from 'react';
 import { useAppStore } from './store/useAppStore';
@@ -17,6 +18,8 @@ function AppContent() {
     const loadSettings = useAppStore((state) => state.loadSettings);
     const loadSession = useAppStore((state) => state.loadSession);
     const fetchSystemInfo = useAppStore((state) => state.fetchSystemInfo);
+    const autoConnectOnLaunch = useAppStore((state) => state.autoConnectOnLaunch);
+    const loadWorkspaceRestorePlan = useAppStore((state) => state.loadWorkspaceRestorePlan);
     const refreshVault = useVaultStore((state) => state.refresh);
 
     useTransferEvents();
@@ -37,6 +40,8 @@ function AppContent() {
                 console.warn('[App] fetchSystemInfo failed:', e);
             }
             refreshVault().catch(e => console.warn('[App] refreshVault failed:', e));
+            autoConnectOnLaunch().catch(e => console.warn('[App] autoConnectOnLaunch failed:', e));
+            loadWorkspaceRestorePlan().catch(e => console.warn('[App] loadWorkspaceRestorePlan failed:', e));
         };
         init().catch(e => console.warn('[App] Initialisation error:', e));
         // eslint-disable-next-line react-hooks/exhaustive-deps -- store actions are stable
@@ -48,6 +53,7 @@ function AppContent() {
                 <WelcomeScreen />
             </MainLayout>
             <UpdateNotification />
+            <WorkspaceRestorePrompt />
 
             <ToastContainer />
         </>
@@ -38,6 +38,7 @@ import { FileBottomToolbar } from './file-manager/FileBottomToolbar';
 import { usePlugins } from '../context/PluginContext';
 import { buildEditorProviderOptions, CODEMIRROR_EDITOR_ID } from './editor/providers';
 import { clearEditorOverlayOpen, markEditorOverlayOpen } from './editor/overlayState';
+import { sessionHoldIpc, sessionReleaseIpc } from '../features/connections/infrastructure/connectionIpc';
 
 export interface Conflict {
   source: string;
@@ -67,6 +68,9 @@ export const FileManager = memo(function FileManager({ connectionId }: { connect
   const connection = !isLocal ? connections.find((c: Connection) => c.id === activeConnectionId) : null;
   // Local is always "connected" for file operations
   const isConnected = isLocal || connection?.status === 'connected';
+  const isFilesViewShown = useAppStore(state => (
+    state.tabs.some(tab => tab.connectionId === activeConnectionId && tab.view === 'files')
+  ));
 
   const settings = useAppStore(state => state.settings);
   const { editorProviders } = usePlugins();
@@ -182,6 +186,15 @@ export const FileManager = memo(function FileManager({ connectionId }: { connect
     }
   }, [currentPath, syncedTerminalId, activeConnectionId]);
 
+  // Browsing counts as using the session, so `sessionLifetime` doesn't close it underneath.
+  useEffect(() => {
+    if (isLocal || !isConnected || !isFilesViewShown || !activeConnectionId) return;
+    const hold = sessionHoldIpc(activeConnectionId, 'sftp').catch(() => null);
+    return () => {
+      void hold.then(key => key && sessionReleaseIpc(key, 'sftp'));
+    };
+  }, [activeConnectionId, isLocal, isConnected, isFilesViewShown]);
+
   // Combine store loading and local processing
   const isLoading = loading || isProcessing;
   const isReconnectPending = !isLocal && connection?.status === 'connecting';
@@ -0,0 +1,61 @@
+import { History, X } from 'lucide-react';
+import { ZPortal } from './ui/ZPortal';
+import { Button } from './ui/Button';
+import { useAppStore } from '../store/useAppStore';
+
+/** Offers to bring back the connections and tunnels that were live when Zync last closed. */
+export function WorkspaceRestorePrompt() {
+    const plan = useAppStore(state => state.workspaceRestorePlan);
+    const connections = useAppStore(state => state.connections);
+    const restoreWorkspace = useAppStore(state => state.restoreWorkspace);
+    const dismissWorkspaceRestore = useAppStore(state => state.dismissWorkspaceRestore);
+
+    if (!plan) return null;
+
+    const planned = plan.connections.waves.flat().map(entry => entry.connectionId);
+    // Auto-connect may already have brought everything back.
+    const pending = planned.filter(id => connections.find(c => c.id === id)?.status !== 'connected');
+    if (pending.length === 0 && plan.tunnels.length === 0) return null;
+
+    const names = planned
+        .map(id => connections.find(c => c.id === id)?.name)
+        .filter((name): name is string => Boolean(name));
+    const shown = names.slice(0, 3).join(', ') + (names.length > 3 ? ` and ${names.length - 3} more` : '');
+    const tunnelCount = plan.tunnels.length;
+
+    return (
+        <ZPortal className="absolute bottom-6 right-6 z-50 max-w-sm w-full animate-in slide-in-from-bottom-5 duration-300">
+            <div className="bg-app-panel border border-app-border rounded-xl shadow-2xl p-4 backdrop-blur-xl bg-opacity-95 ring-1 ring-black/5">
+                <div className="flex items-start gap-4">
+                    <div className="p-2.5 bg-app-accent/10 rounded-xl text-app-accent shrink-0">
+                        <History size={24} />
+                    </div>
+                    <div className="flex-1 min-w-0">
+                        <div className="flex items-start justify-between mb-1">
+                            <h4 className="text-sm font-bold text-app-text">Restore Last Workspace</h4>
+                            <button onClick={dismissWorkspaceRestore} className="text-app-muted hover:text-app-text transition-colors p-0.5 rounded-md hover:bg-app-surface">
+                                <span className="sr-only">Dismiss</span>
+                                <X size={16} />
+                            </button>
+                        </div>
+
+                        <p className="text-xs text-app-muted mb-3 leading-relaxed">
+                            Reconnect {shown || `${planned.length} connections`}
+                            {tunnelCount > 0 && ` and restart ${tunnelCount} tunnel${tunnelCount === 1 ? '' : 's'}`}.
+                            Anything that fails is skipped.
+                        </p>
+
+                        <div className="flex justify-end gap-2 mt-2">
+                            <Button variant="ghost" size="sm" onClick={dismissWorkspaceRestore} className="text-app-muted hover:text-app-text">
+                                Not Now
+                            </Button>
+                            <Button size="sm" onClick={() => void restoreWorkspace()} className="bg-app-accent hover:bg-app-accent/90 text-white border-0 shadow-lg shadow-app-accent/20">
+                                Restore
+                            </Button>
+                        </div>
+                    </div>
+                </div>
+            </div>
+        </ZPortal>
+    );
+}
@@ -7,6 +7,8 @@ import { RadialProgress } from './RadialProgress';
 import { UptimeWidget } from './UptimeWidget';
 import { ProcessWidget } from './ProcessWidget';
 import { QuickActionsWidget } from './QuickActionsWidget';
+import { formatGigabytes, formatUptime, parseHelperStats } from '../../features/remoteHelpers/domain/helperStats';
+import { runRemoteHelperIpc } from '../../features/remoteHelpers/infrastructure/remoteHelpersIpc';
 
 export function Dashboard({ connectionId, isVisible = true }: { connectionId?: string; isVisible?: boolean }) {
   const globalId = useAppStore(state => state.activeConnectionId);
@@ -136,8 +138,31 @@ export function Dashboard({ connectionId, isVisible = true }: { connectionId?: s
         // Race condition check: obtain latest ID from store to ensure we haven't switched tabs
         if (targetId !== useAppStore.getState().activeConnectionId) return true;
 
+      } else if (!isLocal) {
+        // --- Remote hosts: one zync-stats run from the helper bundle (inline
+        // on opted-out and Windows hosts), so one channel per interval. ---
+        const stats = parseHelperStats(await runRemoteHelperIpc(targetId, 'zync-stats'));
+
+        if (targetId !== useAppStore.getState().activeConnectionId) return true;
+
+        cpuLoad = Math.min(stats.cpuPercent ?? 0, 100);
+        if (stats.memTotalKb) {
+          totalMem = Math.round(stats.memTotalKb / 1024);
+          usedMem = Math.round((stats.memTotalKb - (stats.memAvailableKb ?? 0)) / 1024);
+        }
+        if (stats.uptimeSeconds !== undefined) uptimeStr = formatUptime(stats.uptimeSeconds);
+        procCount = stats.processes ?? 0;
+        if (stats.rootTotalKb) {
+          const rootUsedKb = stats.rootUsedKb ?? 0;
+          diskTotal = formatGigabytes(stats.rootTotalKb);
+          diskUsed = formatGigabytes(rootUsedKb);
+          diskPercent = Math.round((rootUsedKb / stats.rootTotalKb) * 100);
+        }
+        if (stats.osName) osName = stats.osName;
+        if (stats.kernel) kernelVer = stats.kernel;
+        if (stats.arch) osArch = stats.arch;
       } else {
-        // --- Linux / Standard SSH Metrics ---
+        // --- Local Linux / macOS Metrics ---
         // Combine ALL commands to reduce channel usage to exactly 1 per interval.
         // We include OS info gathering in the same command if it's missing or just always (parsed cheaply).
 
@@ -6,6 +6,7 @@ import { AddTunnelModal } from '../modals/AddTunnelModal';
 import { DashboardClock } from './welcome/DashboardClock';
 import { QuickConnectBar } from './welcome/QuickConnectBar';
 import { ConnectionCard } from './welcome/ConnectionCard';
+import { BastionSessionsWidget } from './welcome/BastionSessionsWidget';
 import { ContextMenu } from '../ui/ContextMenu';
 import { TopbarDropdown } from '../ui/TopbarDropdown';
 import type { ContextMenuItem } from '../ui/ContextMenu';
@@ -461,6 +462,11 @@ export function WelcomeScreen() {
                         </button>
                     </motion.div>
 
+                    {/* Shared jump host sessions */}
+                    <motion.div variants={item}>
+                        <BastionSessionsWidget />
+                    </motion.div>
+
                     <AddTunnelModal
                         isOpen={isAddTunnelModalOpen}
                         onClose={() => setIsAddTunnelModalOpen(false)}
@@ -0,0 +1,145 @@
+import { useCallback, useEffect, useMemo, useState } from 'react';
+import { RefreshCw, Waypoints } from 'lucide-react';
+import { useAppStore } from '../../../store/useAppStore';
+import { cn } from '../../../lib/utils';
+import type { Connection } from '../../../store/connectionSlice';
+import { groupByBastion, type BastionGroup } from '../../../features/connections/domain';
+import {
+    listBastionSessionsIpc,
+    resetBastionSessionIpc,
+    type BastionSessionPayload,
+} from '../../../features/connections/infrastructure/connectionIpc';
+
+const POLL_MS = 5_000;
+
+type BastionStatus = BastionSessionPayload['status'];
+
+const STATUS_STYLES: Record<BastionStatus, { dot: string; label: string }> = {
+    connected: { dot: 'bg-green-500', label: 'Session up' },
+    connecting: { dot: 'bg-amber-400 animate-pulse', label: 'Logging in' },
+    closed: { dot: 'bg-red-500', label: 'Session down' },
+};
+
+const isLive = (connection: Connection) =>
+    connection.status === 'connected' || connection.status === 'connecting';
+
+/**
+ * Jump hosts shared by open connections, with their session status and the
+ * connections behind each — so several hosts dropping at once reads as one
+ * bastion going down, with a single action to bring them all back.
+ */
+export function BastionSessionsWidget() {
+    const connections = useAppStore(state => state.connections);
+    const terminals = useAppStore(state => state.terminals);
+    const showToast = useAppStore(state => state.showToast);
+    const [sessions, setSessions] = useState<BastionSessionPayload[]>([]);
+    const [reconnecting, setReconnecting] = useState<string | null>(null);
+
+    // Hosts that are up, or went down with terminals waiting to come back.
+    const groups = useMemo(
+        () => groupByBastion(connections, connection =>
+            isLive(connection)
+            || connection.status === 'error'
+            || Boolean(terminals[connection.id]?.some(tab => tab.pendingRestore))),
+        [connections, terminals],
+    );
+
+    const refresh = useCallback(async () => {
+        try {
+            setSessions(await listBastionSessionsIpc());
+        } catch (error) {
+            console.warn('[SSH] Failed to list bastion sessions:', error);
+        }
+    }, []);
+
+    useEffect(() => {
+        if (groups.length === 0) return;
+        void refresh();
+        const intervalId = setInterval(() => void refresh(), POLL_MS);
+        return () => clearInterval(intervalId);
+    }, [groups, refresh]);
+
+    const statusOf = (bastionId: string): BastionStatus => {
+        const statuses = sessions.filter(s => s.connectionId === bastionId).map(s => s.status);
+        if (statuses.includes('connected')) return 'connected';
+        if (statuses.includes('connecting')) return 'connecting';
+        return 'closed';
+    };
+
+    const reconnectAll = async ({ bastion, dependents }: BastionGroup) => {
+        setReconnecting(bastion.id);
+        try {
+            for (const session of sessions.filter(s => s.connectionId === bastion.id)) {
+                await resetBastionSessionIpc(session.key);
+            }
+            const { connect, disconnect } = useAppStore.getState();
+            // The first connect logs in to the bastion again; the rest share that session.
+            await Promise.all(dependents.map(async connection => {
+                if (isLive(connection)) await disconnect(connection.id);
+                await connect(connection.id);
+            }));
+        } catch (error) {
+            showToast('error', `Failed to reconnect through ${bastion.name}: ${error}`);
+        } finally {
+            setReconnecting(null);
+            void refresh();
+        }
+    };
+
+    if (groups.length === 0) return null;
+
+    return (
+        <section aria-labelledby="bastion-heading" className="mb-5 flex flex-col gap-1.5">
+            <h2
+                id="bastion-heading"
+                className="text-[10px] font-semibold uppercase tracking-widest text-app-muted/60 px-3"
+            >
+                Bastions
+            </h2>
+            {groups.map(group => {
+                const { bastion, dependents } = group;
+                const status = STATUS_STYLES[statusOf(bastion.id)];
+                const down = dependents.filter(connection => !isLive(connection));
+                const busy = reconnecting === bastion.id;
+                return (
+                    <div
+                        key={bastion.id}
+                        className="flex items-center gap-3 px-3 py-2.5 rounded-xl border border-app-border/40 bg-app-surface/30"
+                    >
+                        <Waypoints size={14} className="text-app-muted shrink-0" aria-hidden="true" />
+                        <div className="min-w-0 flex-1">
+                            <div className="flex items-center gap-2">
+                                <span className="text-xs font-medium text-app-text truncate">
+                                    {bastion.name || bastion.host}
+                                </span>
+                                <span className="flex items-center gap-1 text-[10px] text-app-muted">
+                                    <span className={cn('w-1.5 h-1.5 rounded-full', status.dot)} aria-hidden="true" />
+                                    {status.label}
+                                </span>
+                            </div>
+                            <p
+                                className="text-[11px] text-app-muted/70 truncate"
+                                title={dependents.map(connection => connection.name || connection.host).join(', ')}
+                            >
+                                {dependents.length} {dependents.length === 1 ? 'connection' : 'connections'} through it
+                                {down.length > 0 && (
+                                    <span className="text-red-400"> · {down.length} down</span>
+                                )}
+                            </p>
+                        </div>
+                        <button
+                            type="button"
+                            onClick={() => void reconnectAll(group)}
+                            disabled={reconnecting !== null}
+                            title="Log in to the bastion again and reconnect every connection behind it"
+                            className="flex items-center gap-1.5 px-2.5 py-1.5 rounded-lg text-[11px] font-medium text-app-text/80 border border-app-border/40 hover:bg-app-surface/60 transition-colors disabled:opacity-40 focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-app-accent/40"
+                        >
+                            <RefreshCw size={12} className={cn(busy && 'animate-spin')} />
+                            Reconnect all
+                        </button>
+                    </div>
+                );
+            })}
+        </section>
+    );
+}
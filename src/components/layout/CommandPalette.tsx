@@ -10,10 +10,13 @@ import {
     Code,
     Network,
     FolderPlus,
+    ClipboardCopy,
+    ClipboardPaste,
 
     Shield
 } from "lucide-react";
 import { useAppStore, Connection } from "../../store/useAppStore";
+import { pullClipboardFromHost, pushClipboardToHost } from "../../features/clipboard/application/clipboardBridgeActions";
 import { useShallow } from 'zustand/react/shallow';
 import { usePlugins } from "../../context/PluginContext";
 import { clsx } from "clsx";
@@ -340,6 +343,30 @@ export function CommandPalette() {
                                         <RefreshCw className="mr-2 h-4 w-4 opacity-70" />
                                         <span>Reload Window</span>
                                     </Command.Item>
+
+                                    <Command.Item
+                                        value="Push Clipboard to Remote Host"
+                                        onSelect={() => runCommand(() => {
+                                            const { activeConnectionId, showToast } = useAppStore.getState();
+                                            void pushClipboardToHost(activeConnectionId, showToast);
+                                        })}
+                                        className="relative flex cursor-pointer select-none items-center rounded-lg px-2 py-1.5 text-sm outline-none data-[selected=true]:bg-app-accent/20 data-[selected=true]:text-app-accent text-app-text transition-colors group mb-0.5"
+                                    >
+                                        <ClipboardPaste className="mr-2 h-4 w-4 opacity-70" />
+                                        <span>Push Clipboard to Remote Host</span>
+                                    </Command.Item>
+
+                                    <Command.Item
+                                        value="Pull Remote Clipboard"
+                                        onSelect={() => runCommand(() => {
+                                            const { activeConnectionId, showToast } = useAppStore.getState();
+                                            void pullClipboardFromHost(activeConnectionId, showToast);
+                                        })}
+                                        className="relative flex cursor-pointer select-none items-center rounded-lg px-2 py-1.5 text-sm outline-none data-[selected=true]:bg-app-accent/20 data-[selected=true]:text-app-accent text-app-text transition-colors group mb-0.5"
+                                    >
+                                        <ClipboardCopy className="mr-2 h-4 w-4 opacity-70" />
+                                        <span>Pull Remote Clipboard</span>
+                                    </Command.Item>
                                 </Command.Group>
 
                                 <Command.Group heading="Tools" className="text-[10px] font-semibold text-app-muted uppercase tracking-wider mb-1 px-2 mt-2">
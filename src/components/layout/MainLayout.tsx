@@ -32,6 +32,11 @@ import {
 } from '../../lib/terminal';
 import { refreshAllCachedTerminalThemes } from '../terminal/terminalTheme';
 import { registerTunnelTransportLostListener } from '../../features/tunnels/application/tunnelTransportLost';
+import { registerLocalCommandListener } from '../../features/connections/application/localCommandListener';
+import { registerSessionClosedListener } from '../../features/connections/application/sessionClosedListener';
+import { registerAuthPromptListener } from '../../features/connections/application/authPromptListener';
+import { registerHostKeyPromptListener } from '../../features/connections/application/hostKeyPromptListener';
+import { registerCrashReportListener } from '../../features/diagnostics/application/crashReportListener';
 
 
 // Side-effect imports — these register each modal into the registry at startup.
@@ -39,6 +44,9 @@ import { registerTunnelTransportLostListener } from '../../features/tunnels/appl
 import '../../components/modals/AddConnectionModal';
 import '../../components/modals/AddTunnelModal';
 import '../../components/modals/ImportSSHCommandModal';
+import '../../components/modals/HomelabRendezvousModal';
+import '../../components/modals/SshAuthPromptModal';
+import '../../components/modals/HostKeyPromptModal';
 
 declare global {
     interface Window {
@@ -641,16 +649,24 @@ export function MainLayout({ children }: { children: ReactNode }) {
     }, []);
 
     useEffect(() => registerTunnelTransportLostListener(), []);
+    useEffect(() => registerLocalCommandListener(), []);
+    useEffect(() => registerSessionClosedListener(), []);
+    useEffect(() => registerAuthPromptListener(), []);
+    useEffect(() => registerHostKeyPromptListener(), []);
+    useEffect(() => registerCrashReportListener(), []);
 
     const showWelcomeScreen = useAppStore(state => state.showWelcomeScreen);
     const isLoadingSettings = useAppStore(state => state.isLoadingSettings);
     const sessionLoaded = useAppStore(state => state.sessionLoaded);
     const loadSnippets = useAppStore(state => state.loadSnippets);
+    const loadMacros = useAppStore(state => state.loadMacros);
 
-    // Pre-load snippets once on app startup so the picker/sidebar always have data
+    // Pre-load snippets and macros once on app startup so the picker/sidebar and
+    // macro shortcuts always have data
     useEffect(() => {
         loadSnippets();
-    }, [loadSnippets]);
+        loadMacros();
+    }, [loadSnippets, loadMacros]);
     const [showWizard, setShowWizard] = useState(false);
     const [isLoading, setIsLoading] = useState(true);
     const [windowWidth, setWindowWidth] = useState(window.innerWidth);
@@ -1,6 +1,6 @@
 import { useState, useCallback, useEffect, useRef, useMemo, lazy, Suspense } from 'react';
 import { useAppStore, Connection, Folder } from '../../store/useAppStore';
-import { Files, Info, Network, Pencil, Plus, Power, RefreshCw, Search, Server, TerminalIcon, Trash2 } from 'lucide-react';
+import { Files, Info, Network, Package, Pencil, Plus, Power, RefreshCw, Rocket, RotateCcw, Search, Server, TerminalIcon, Trash2, X } from 'lucide-react';
 import { cn } from '../../lib/utils';
 import { ContextMenu, type ContextMenuItem } from '../ui/ContextMenu';
 import { ConfirmModal } from '../ui/ConfirmModal';
@@ -32,6 +32,8 @@ import { FEATURE_META, type FeatureId } from './featureMeta';
 // Lazy Load Modals
 const SettingsModal = lazy(() => import('../settings/SettingsModal').then(mod => ({ default: mod.SettingsModal })));
 const ConnectionDetailsModal = lazy(() => import('../modals/ConnectionDetailsModal').then(mod => ({ default: mod.ConnectionDetailsModal })));
+const PackagesModal = lazy(() => import('../modals/PackagesModal').then(mod => ({ default: mod.PackagesModal })));
+const HostPowerModal = lazy(() => import('../modals/HostPowerModal').then(mod => ({ default: mod.HostPowerModal })));
 const ExportConnectionsModal = lazy(() => import('../modals/ExportConnectionsModal').then(mod => ({ default: mod.ExportConnectionsModal })));
 
 const FEATURE_ITEMS: Array<{ id: FeatureId; label: string }> = [
@@ -50,6 +52,8 @@ const HOST_FILTERS: Array<{ id: HostCatalogFilter; label: string }> = [
 
 export function Sidebar({ className }: { className?: string }) {
     const [viewingDetailsId, setViewingDetailsId] = useState<string | null>(null);
+    const [packagesConnectionId, setPackagesConnectionId] = useState<string | null>(null);
+    const [powerConnectionId, setPowerConnectionId] = useState<string | null>(null);
     const [searchTerm, setSearchTerm] = useState('');
 
     // Connection Store Hooks
@@ -65,6 +69,8 @@ export function Sidebar({ className }: { className?: string }) {
     const renameFolder = useAppStore(state => state.renameFolder);
     const connect = useAppStore(state => state.connect);
     const disconnect = useAppStore(state => state.disconnect);
+    const toggleAutoConnect = useAppStore(state => state.toggleAutoConnect);
+    const cancelConnect = useAppStore(state => state.cancelConnect);
     const loadConnections = useAppStore(state => state.loadConnections);
     
     // Settings Store Hooks
@@ -359,9 +365,26 @@ export function Sidebar({ className }: { className?: string }) {
         setConnectionContextMenu(null);
     }, [connectionContextMenu, contextMenuConnection]);
 
+    const packagesConnection = packagesConnectionId
+        ? connections.find((c: Connection) => c.id === packagesConnectionId)
+        : undefined;
+    const powerConnection = powerConnectionId
+        ? connections.find((c: Connection) => c.id === powerConnectionId)
+        : undefined;
+
     const connectionContextMenuItems = useMemo<ContextMenuItem[]>(() => {
         if (!contextMenuConnection) return [];
 
+        if (contextMenuConnection.status === 'connecting') {
+            return [
+                {
+                    label: 'Cancel Connect',
+                    icon: <X size={14} className="text-red-400" />,
+                    action: () => cancelConnect(contextMenuConnection.id),
+                },
+            ];
+        }
+
         return [
             {
                 label: contextMenuConnection.status === 'connected' ? 'Disconnect' : 'Connect',
@@ -385,6 +408,17 @@ export function Sidebar({ className }: { className?: string }) {
                 icon: <Info size={14} />,
                 action: () => setViewingDetailsId(contextMenuConnection.id)
             },
+            ...(contextMenuConnection.status === 'connected' && contextMenuConnection.id !== 'local'
+                ? [{
+                    label: 'Packages',
+                    icon: <Package size={14} />,
+                    action: () => setPackagesConnectionId(contextMenuConnection.id),
+                }, {
+                    label: 'Reboot or Shut Down...',
+                    icon: <RotateCcw size={14} />,
+                    action: () => setPowerConnectionId(contextMenuConnection.id),
+                }]
+                : []),
             { separator: true },
             ...FEATURE_ITEMS.map(({ id, label }) => {
                 const Icon = FEATURE_META[id].icon;
@@ -400,6 +434,11 @@ export function Sidebar({ className }: { className?: string }) {
                 icon: <Pencil size={14} />,
                 action: () => openEditConnection(contextMenuConnection)
             },
+            {
+                label: contextMenuConnection.autoConnect ? 'Don\'t Connect on Launch' : 'Connect on Launch',
+                icon: <Rocket size={14} />,
+                action: () => toggleAutoConnect(contextMenuConnection.id)
+            },
             {
                 label: 'Export...',
                 icon: <Files size={14} />,
@@ -423,7 +462,7 @@ export function Sidebar({ className }: { className?: string }) {
                 action: () => setDeletingConnection(contextMenuConnection)
             }
         ];
-    }, [connect, contextMenuConnection, disconnect, openEditConnection, openTab, toFileBaseName]);
+    }, [cancelConnect, connect, contextMenuConnection, disconnect, openEditConnection, openTab, toFileBaseName, toggleAutoConnect]);
 
     const folderContextMenuItems = useMemo<ContextMenuItem[]>(() => {
         if (!folderContextMenu) return [];
@@ -925,6 +964,22 @@ export function Sidebar({ className }: { className?: string }) {
                     />
                 )}
 
+                {packagesConnection && (
+                    <PackagesModal
+                        isOpen={!!packagesConnection}
+                        onClose={() => setPackagesConnectionId(null)}
+                        connection={packagesConnection}
+                    />
+                )}
+
+                {powerConnection && (
+                    <HostPowerModal
+                        isOpen={!!powerConnection}
+                        onClose={() => setPowerConnectionId(null)}
+                        connection={powerConnection}
+                    />
+                )}
+
                 {isSettingsOpen && <SettingsModal isOpen={isSettingsOpen} onClose={closeSettings} />}
                 {exportModalState && (
                     <ExportConnectionsModal
@@ -204,6 +204,25 @@ export function ShortcutManager() {
                 e.preventDefault();
                 toggleAiSidebar();
             }
+            else if (matchShortcut(e, kb.termRecordMacro || 'Mod+Shift+M')) {
+                e.preventDefault();
+                window.dispatchEvent(new CustomEvent('ssh-ui:macro-record', {
+                    detail: { connectionId: activeConnectionId }
+                }));
+            }
+            else {
+                const macro = useAppStore.getState().macros.find(m =>
+                    m.shortcut
+                    && (!m.connectionId || m.connectionId === activeConnectionId)
+                    && matchShortcut(e, m.shortcut)
+                );
+                if (macro) {
+                    e.preventDefault();
+                    window.dispatchEvent(new CustomEvent('ssh-ui:run-macro', {
+                        detail: { connectionId: activeConnectionId, macroId: macro.id }
+                    }));
+                }
+            }
         };
 
         window.addEventListener('keydown', handleKeyDown, { capture: true });
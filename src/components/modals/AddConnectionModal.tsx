@@ -8,8 +8,9 @@ import { useAppStore, Connection } from '../../store/useAppStore';
 import { open } from '@tauri-apps/plugin-dialog';
 import { cn } from '../../lib/utils';
 import { ShieldCheck, CheckCircle2, AlertCircle, Loader2, FileText, Laptop, Files, ChevronDown, ChevronRight, Shield, KeyRound } from 'lucide-react';
-import { testConnectionIpc, type ConnectionConfigPayload } from '../../features/connections/infrastructure/connectionIpc';
-import { buildConnectionTestPayload } from '../../features/connections/domain';
+import { getTorStatusIpc, testConnectionIpc, type ConnectionConfigPayload } from '../../features/connections/infrastructure/connectionIpc';
+import { buildConnectionTestPayload, formatWebSocketHeaders, isOnionHost, parseWebSocketHeaders } from '../../features/connections/domain';
+import type { AccessProxy, SessionLifetime, WebSocketTransport } from '../../features/connections/domain/types';
 import {
     importConnectionsFromFileIpc,
     type ConnectionExchangeImportFormat,
@@ -46,6 +47,34 @@ const THEMES = [
     { id: 'purple', label: 'App', color: 'bg-purple-500/20 border-purple-500' },
 ];
 
+/** Labels the backend resolves to a transcoder; empty means UTF-8. */
+const ENCODINGS = [
+    { value: '', label: 'UTF-8 (Default)' },
+    { value: 'latin1', label: 'Latin-1 (ISO-8859-1 / Windows-1252)' },
+    { value: 'iso-8859-15', label: 'Latin-9 (ISO-8859-15)' },
+    { value: 'windows-1251', label: 'Cyrillic (Windows-1251)' },
+    { value: 'koi8-r', label: 'Cyrillic (KOI8-R)' },
+    { value: 'gbk', label: 'Simplified Chinese (GBK)' },
+    { value: 'gb18030', label: 'Simplified Chinese (GB18030)' },
+    { value: 'big5', label: 'Traditional Chinese (Big5)' },
+    { value: 'shift_jis', label: 'Japanese (Shift_JIS)' },
+    { value: 'euc-jp', label: 'Japanese (EUC-JP)' },
+    { value: 'euc-kr', label: 'Korean (EUC-KR)' },
+];
+
+const SESSION_LIFETIMES: { value: SessionLifetime; label: string }[] = [
+    { value: 'manual', label: 'When I disconnect (Default)' },
+    { value: 'withTerminals', label: 'With the last terminal' },
+    { value: 'withTunnels', label: 'With the last tunnel' },
+    { value: 'untilIdle', label: 'When nothing uses it' },
+];
+
+const ACCESS_PROXIES: { value: '' | AccessProxy['provider']; label: string }[] = [
+    { value: '', label: 'None' },
+    { value: 'cloudflare', label: 'Cloudflare Access (cloudflared)' },
+    { value: 'gcpIap', label: 'Google Cloud IAP (gcloud)' },
+];
+
 export function AddConnectionModal({ isOpen, onClose, editingConnectionId }: AddConnectionModalProps) {
     const importConnections = useAppStore(state => state.importConnections);
     const showToast = useAppStore(state => state.showToast);
@@ -120,6 +149,39 @@ export function AddConnectionModal({ isOpen, onClose, editingConnectionId }: Add
 
     const needsVaultUnlock = authMethod === 'vault' || Boolean(formData.authRef?.itemId);
 
+    // `undefined` while checking; `null` when Tor isn't running.
+    const [torProxy, setTorProxy] = useState<string | null | undefined>(undefined);
+    const onionHost = isOnionHost(formData.host);
+    useEffect(() => {
+        if (!isOpen || !onionHost) return;
+        let cancelled = false;
+        setTorProxy(undefined);
+        getTorStatusIpc()
+            .then(status => { if (!cancelled) setTorProxy(status.proxy); })
+            .catch(() => { if (!cancelled) setTorProxy(null); });
+        return () => { cancelled = true; };
+    }, [isOpen, onionHost]);
+
+    const webSocket = formData.sshOptions?.websocket;
+    const setWebSocket = (patch: Partial<WebSocketTransport>) => setFormData({
+        ...formData,
+        sshOptions: { ...formData.sshOptions, websocket: { url: '', ...webSocket, ...patch } },
+    });
+    const accessProxy = formData.sshOptions?.accessProxy;
+    const setAccessProxy = (next: AccessProxy | undefined) => setFormData({
+        ...formData,
+        sshOptions: { ...formData.sshOptions, accessProxy: next },
+    });
+    const setSshNumber = (key: 'serverAliveIntervalSecs' | 'serverAliveCountMax', raw: string) => {
+        const value = raw.trim() === '' ? undefined : Math.max(0, Math.floor(Number(raw)));
+        setFormData({
+            ...formData,
+            sshOptions: { ...formData.sshOptions, [key]: Number.isFinite(value) ? value : undefined },
+        });
+    };
+    const webSocketUrl = webSocket?.url.trim() ?? '';
+    const webSocketUrlInvalid = webSocketUrl !== '' && !/^wss?:\/\//i.test(webSocketUrl);
+
     const notifyVaultBlocked = () => {
         if (isVaultInUseError(useVaultStore.getState().error)) {
             showToast('error', VAULT_IN_USE_USER_MESSAGE, 8000);
@@ -370,6 +432,14 @@ export function AddConnectionModal({ isOpen, onClose, editingConnectionId }: Add
                                         error={visiblePortError}
                                     />
                                 </div>
+                                {onionHost && (
+                                    <p className={cn('text-[10px] flex items-center gap-1 -mt-2 pl-1', torProxy === null ? 'text-amber-400/90' : 'text-app-muted/70')}>
+                                        {torProxy === null && <AlertCircle size={10} />}
+                                        {torProxy === undefined && 'Onion address — checking for Tor…'}
+                                        {torProxy === null && 'Onion address, but Tor is not running. Start the tor service or Tor Browser before connecting.'}
+                                        {torProxy && `Onion address — connects through Tor at ${torProxy}.`}
+                                    </p>
+                                )}
                                 <Input
                                     label="Username"
                                     placeholder="root"
@@ -465,7 +535,7 @@ export function AddConnectionModal({ isOpen, onClose, editingConnectionId }: Add
                                                 </div>
                                                 {formData.privateKeyPath && (
                                                     <p className="text-[10px] text-app-muted/70">
-                                                        Key file path is stored and read at connect time.
+                                                        Key file path is stored and read at connect time. A certificate next to it (<span className="font-mono">{formData.privateKeyPath.split(/[/\\]/).pop()}-cert.pub</span>) is offered first.
                                                         {vaultStatus?.status !== 'uninitialized' ? ' Open Vault tab to secure it later.' : ''}
                                                     </p>
                                                 )}
@@ -739,6 +809,134 @@ export function AddConnectionModal({ isOpen, onClose, editingConnectionId }: Add
                                                 <AlertCircle size={10} /> Jump chain creates a loop — this connection will not be reachable.
                                             </p>
                                         )}
+                                        <div className="mt-4">
+                                            <Input
+                                                label="WebSocket Bridge (Optional)"
+                                                placeholder="wss://bridge.example.com/ssh"
+                                                value={webSocket?.url || ''}
+                                                onChange={e => setWebSocket({ url: e.target.value })}
+                                                error={webSocketUrlInvalid ? 'Use a wss:// or ws:// URL.' : undefined}
+                                            />
+                                        </div>
+                                        {webSocketUrl && (
+                                            <textarea
+                                                key={activeEditingConnectionId ?? 'new'}
+                                                defaultValue={formatWebSocketHeaders(webSocket?.headers)}
+                                                className="mt-2 w-full h-16 rounded-lg border border-app-border bg-app-bg px-3 py-2 text-xs font-mono text-app-text placeholder:text-app-muted/50 resize-none focus:outline-none focus:ring-1 focus:ring-app-accent/50"
+                                                placeholder="Authorization: Bearer …"
+                                                aria-label="WebSocket request headers"
+                                                onChange={e => setWebSocket({ headers: parseWebSocketHeaders(e.target.value) })}
+                                                spellCheck={false}
+                                            />
+                                        )}
+                                        <p className="text-[10px] text-app-muted/70 mt-1 pl-1">Tunnel SSH over HTTPS through a WebSocket bridge, for networks that only allow port 443 out. Headers go one per line as Name: value. Ignored when a jump server is set.</p>
+                                        <div className="mt-4">
+                                            <Select
+                                                label="Zero-Trust Proxy"
+                                                value={accessProxy?.provider || ''}
+                                                onChange={(val) => setAccessProxy(
+                                                    val === 'cloudflare' ? { provider: 'cloudflare' }
+                                                        : val === 'gcpIap' ? { provider: 'gcpIap', zone: '' }
+                                                            : undefined,
+                                                )}
+                                                portal
+                                                options={ACCESS_PROXIES}
+                                            />
+                                        </div>
+                                        {accessProxy?.provider === 'cloudflare' && (
+                                            <div className="mt-2">
+                                                <Input
+                                                    label="Access Hostname"
+                                                    placeholder={formData.host || 'ssh.example.com'}
+                                                    value={accessProxy.hostname || ''}
+                                                    onChange={e => setAccessProxy({ ...accessProxy, hostname: e.target.value })}
+                                                />
+                                            </div>
+                                        )}
+                                        {accessProxy?.provider === 'gcpIap' && (
+                                            <div className="mt-2 grid grid-cols-3 gap-3">
+                                                <Input
+                                                    label="Instance"
+                                                    placeholder={formData.host || 'instance-1'}
+                                                    value={accessProxy.instance || ''}
+                                                    onChange={e => setAccessProxy({ ...accessProxy, instance: e.target.value })}
+                                                />
+                                                <Input
+                                                    label="Zone"
+                                                    placeholder="us-central1-a"
+                                                    value={accessProxy.zone}
+                                                    onChange={e => setAccessProxy({ ...accessProxy, zone: e.target.value })}
+                                                    error={accessProxy.zone.trim() ? undefined : 'Required.'}
+                                                />
+                                                <Input
+                                                    label="Project (Optional)"
+                                                    placeholder="gcloud default"
+                                                    value={accessProxy.project || ''}
+                                                    onChange={e => setAccessProxy({ ...accessProxy, project: e.target.value })}
+                                                />
+                                            </div>
+                                        )}
+                                        <p className="text-[10px] text-app-muted/70 mt-1 pl-1">Log in through the provider's CLI before connecting; it gets a short-lived token (opening the browser when needed) and carries the SSH stream. Names left empty use the host above.</p>
+                                        <div className="mt-4 grid grid-cols-2 gap-3">
+                                            <Select
+                                                label="Remote Encoding"
+                                                value={formData.encoding || ''}
+                                                onChange={(val) => setFormData({ ...formData, encoding: val === '' ? undefined : val })}
+                                                portal
+                                                options={ENCODINGS}
+                                            />
+                                            <Input
+                                                label="Locale (Optional)"
+                                                placeholder="e.g. ja_JP.eucJP"
+                                                value={formData.locale || ''}
+                                                onChange={e => setFormData({ ...formData, locale: e.target.value })}
+                                            />
+                                        </div>
+                                        <p className="text-[10px] text-app-muted/70 mt-1 pl-1">For hosts that don't use UTF-8. The locale is sent as LANG and LC_ALL; applies to new terminals after reconnecting.</p>
+                                        <div className="mt-4 grid grid-cols-2 gap-3">
+                                            <Input
+                                                label="Keepalive Interval (s)"
+                                                type="number"
+                                                min={0}
+                                                placeholder="60"
+                                                value={formData.sshOptions?.serverAliveIntervalSecs ?? ''}
+                                                onChange={e => setSshNumber('serverAliveIntervalSecs', e.target.value)}
+                                            />
+                                            <Input
+                                                label="Missed Keepalives"
+                                                type="number"
+                                                min={1}
+                                                placeholder="3"
+                                                value={formData.sshOptions?.serverAliveCountMax ?? ''}
+                                                onChange={e => setSshNumber('serverAliveCountMax', e.target.value)}
+                                            />
+                                        </div>
+                                        <p className="text-[10px] text-app-muted/70 mt-1 pl-1">Like ServerAliveInterval and ServerAliveCountMax: the session is probed this often and reported lost after this many unanswered probes, so drops behind NAT routers don't go unnoticed. 0 turns keepalives off.</p>
+                                        <div className="mt-4 grid grid-cols-2 gap-3">
+                                            <Input
+                                                label="Host Key Alias (Optional)"
+                                                placeholder="e.g. app-server-1"
+                                                value={formData.hostKeyAlias || ''}
+                                                onChange={e => setFormData({ ...formData, hostKeyAlias: e.target.value })}
+                                            />
+                                            <Input
+                                                label="Known Hosts File (Optional)"
+                                                placeholder="~/.ssh/known_hosts"
+                                                value={formData.userKnownHostsFile || ''}
+                                                onChange={e => setFormData({ ...formData, userKnownHostsFile: e.target.value })}
+                                            />
+                                        </div>
+                                        <p className="text-[10px] text-app-muted/70 mt-1 pl-1">Verify the host key under another name, e.g. for hosts behind a shared load balancer or a forwarded localhost port. New keys are recorded in the first listed file.</p>
+                                        <div className="mt-4">
+                                            <Select
+                                                label="Close Session"
+                                                value={formData.sessionLifetime || 'manual'}
+                                                onChange={(val) => setFormData({ ...formData, sessionLifetime: val as SessionLifetime })}
+                                                portal
+                                                options={SESSION_LIFETIMES}
+                                            />
+                                        </div>
+                                        <p className="text-[10px] text-app-muted/70 mt-1 pl-1">Disconnect on its own once terminals or tunnels are gone. File transfers and commands still running always finish first.</p>
                                     </div>
                                 )}
                             </section>
@@ -1,12 +1,20 @@
 import { useState, useEffect } from 'react';
 import { ArrowLeft, Laptop, Server as ServerIcon, Plus, Trash2 } from 'lucide-react';
 import { TunnelTypeStep } from './tunnel/TunnelTypeStep';
+import { RemotePortSuggestions, forwardTargetHost } from './tunnel/RemotePortSuggestions';
+import { TunnelScheduleFields } from './tunnel/TunnelScheduleFields';
+import { getLocalCaIpc, type LocalCaInfo } from '../../features/tunnels/infrastructure/webOpenIpc';
+import { reservePortIpc } from '../../features/tunnels/infrastructure/portCatalogIpc';
+import { useServiceNames } from '../../features/tunnels/application/serviceNames';
+import { nextLoopbackAliasIpc, validateTunnelIpc, type TunnelIssue } from '../../features/tunnels/infrastructure/tunnelValidationIpc';
 import {
     DYNAMIC_REMOTE_HOST,
     DYNAMIC_REMOTE_PORT,
     defaultTunnelName,
+    splitPacDomains,
     type TunnelType,
 } from '../../features/tunnels/domain/tunnelTypes';
+import type { TunnelSchedule } from '../../features/tunnels/domain/tunnelSchedule';
 import { motion, AnimatePresence } from 'framer-motion';
 import { useAppStore, type Connection, type TunnelConfig } from '../../store/useAppStore';
 import { Button } from '../ui/Button';
@@ -41,6 +49,7 @@ export function AddTunnelModal({ isOpen, onClose, initialConnectionId, editingTu
     const connections = useAppStore(state => state.connections);
     const tunnels = useAppStore(state => state.tunnels);
     const showToast = useAppStore((state) => state.showToast);
+    const serviceName = useServiceNames();
     const showHostAddressesInLists = useShowHostAddressesInLists();
 
     const existingGroups = Array.from(new Set(
@@ -61,6 +70,19 @@ export function AddTunnelModal({ isOpen, onClose, initialConnectionId, editingTu
     const [remotePort, setRemotePort] = useState('80');
     const [bindAddress, setBindAddress] = useState('127.0.0.1');
     const [autoStart, setAutoStart] = useState(false);
+    const [webProtocol, setWebProtocol] = useState('');
+    const [webPath, setWebPath] = useState('');
+    const [terminateTls, setTerminateTls] = useState(false);
+    const [inspect, setInspect] = useState(false);
+    const [hostHeader, setHostHeader] = useState('');
+    const [hostname, setHostname] = useState('');
+    const [upstreamTls, setUpstreamTls] = useState(false);
+    const [pacDomains, setPacDomains] = useState('');
+    const [systemProxy, setSystemProxy] = useState(false);
+    const [schedule, setSchedule] = useState<TunnelSchedule | null>(null);
+    const [draftId, setDraftId] = useState('');
+    const [localCa, setLocalCa] = useState<LocalCaInfo | null>(null);
+    const [issues, setIssues] = useState<TunnelIssue[]>([]);
 
     const [bulkRows, setBulkRows] = useState<Array<{ type: 'local' | 'remote', localPort: string, remoteHost: string, remotePort: string }>>([
         { type: 'local', localPort: '8080', remoteHost: '127.0.0.1', remotePort: '80' }
@@ -68,8 +90,34 @@ export function AddTunnelModal({ isOpen, onClose, initialConnectionId, editingTu
 
     const saveTunnel = useAppStore(state => state.saveTunnel);
 
+    const handleReservePort = async () => {
+        try {
+            // Keyed by tunnel id so renaming the tunnel keeps its port.
+            const reservation = await reservePortIpc(`tunnel:${draftId}`, { tunnelId: draftId });
+            setLocalPort(String(reservation.port));
+        } catch (error) {
+            showToast('error', `Could not reserve a port: ${error}`);
+        }
+    };
+
+    const handleLoopbackAlias = async () => {
+        const port = parseInt(localPort);
+        if (!isValidPort(port)) {
+            showToast('error', 'Enter a local port first');
+            return;
+        }
+        try {
+            const alias = await nextLoopbackAliasIpc(draftId, port);
+            if (alias) setBindAddress(alias);
+            else showToast('error', `Every loopback address already has port ${port} in use`);
+        } catch (error) {
+            showToast('error', `Could not pick a loopback address: ${error}`);
+        }
+    };
+
     useEffect(() => {
         if (isOpen) {
+            setDraftId(editingTunnel?.id || crypto.randomUUID());
             if (editingTunnel) {
                 setSelectedConnectionId(editingTunnel.connectionId);
                 setName(editingTunnel.name);
@@ -80,6 +128,16 @@ export function AddTunnelModal({ isOpen, onClose, initialConnectionId, editingTu
                 setRemotePort(editingTunnel.remotePort.toString());
                 setBindAddress(editingTunnel.bindAddress || '127.0.0.1');
                 setAutoStart(editingTunnel.autoStart || false);
+                setWebProtocol(editingTunnel.web?.protocol || '');
+                setWebPath(editingTunnel.web?.path || '');
+                setTerminateTls(editingTunnel.web?.terminateTls || false);
+                setInspect(editingTunnel.web?.inspect || false);
+                setHostHeader(editingTunnel.web?.hostHeader || '');
+                setHostname(editingTunnel.web?.hostname || '');
+                setUpstreamTls(editingTunnel.web?.upstreamTls || false);
+                setPacDomains((editingTunnel.pacDomains || []).join(', '));
+                setSystemProxy(editingTunnel.systemProxy || false);
+                setSchedule(editingTunnel.schedule || null);
                 setMode('single');
                 setStep('form');
             } else {
@@ -97,6 +155,16 @@ export function AddTunnelModal({ isOpen, onClose, initialConnectionId, editingTu
                 setRemotePort('80');
                 setBindAddress('127.0.0.1');
                 setAutoStart(false);
+                setWebProtocol('');
+                setWebPath('');
+                setTerminateTls(false);
+                setInspect(false);
+                setHostHeader('');
+                setHostname('');
+                setUpstreamTls(false);
+                setPacDomains('');
+                setSystemProxy(false);
+                setSchedule(null);
                 setMode('single');
                 setStep('type');
                 setBulkRows([{ type: 'local', localPort: '8080', remoteHost: '127.0.0.1', remotePort: '80' }]);
@@ -104,6 +172,48 @@ export function AddTunnelModal({ isOpen, onClose, initialConnectionId, editingTu
         }
     }, [isOpen, initialConnectionId, editingTunnel]);
 
+    useEffect(() => {
+        if (!terminateTls || localCa) return;
+        getLocalCaIpc()
+            .then(setLocalCa)
+            .catch((error) => console.error('Failed to prepare local CA', error));
+    }, [terminateTls, localCa]);
+
+    useEffect(() => {
+        if (!isOpen || step !== 'form' || mode !== 'single' || !selectedConnectionId) {
+            setIssues([]);
+            return;
+        }
+        const isDynamic = type === 'dynamic';
+        const draft: TunnelConfig = {
+            id: editingTunnel?.id || draftId,
+            connectionId: selectedConnectionId,
+            name,
+            type,
+            localPort: parseInt(localPort) || 0,
+            remoteHost: isDynamic ? DYNAMIC_REMOTE_HOST : remoteHost,
+            remotePort: isDynamic ? DYNAMIC_REMOTE_PORT : parseInt(remotePort) || 0,
+            bindAddress,
+            status: 'stopped',
+            web: type === 'local' && hostname.trim() ? { hostname: hostname.trim() } : undefined,
+        };
+        let cancelled = false;
+        const timer = window.setTimeout(() => {
+            validateTunnelIpc(draft)
+                .then((result) => {
+                    if (!cancelled) setIssues(result);
+                })
+                .catch(() => {
+                    // Out-of-range ports don't deserialize; the save checks report those.
+                    if (!cancelled) setIssues([]);
+                });
+        }, 300);
+        return () => {
+            cancelled = true;
+            window.clearTimeout(timer);
+        };
+    }, [isOpen, step, mode, selectedConnectionId, editingTunnel, draftId, name, type, localPort, remoteHost, remotePort, bindAddress, hostname]);
+
     const handleSave = async () => {
         if (!selectedConnectionId) {
             showToast('error', 'Please select a host');
@@ -132,11 +242,17 @@ export function AddTunnelModal({ isOpen, onClose, initialConnectionId, editingTu
                 rPort = DYNAMIC_REMOTE_PORT;
             }
 
+            const blocking = issues.find((issue) => issue.severity === 'error');
+            if (blocking) {
+                showToast('error', blocking.message);
+                return;
+            }
+
             try {
                 const config: TunnelConfig = {
-                    id: editingTunnel?.id || crypto.randomUUID(),
+                    id: editingTunnel?.id || draftId,
                     connectionId: selectedConnectionId,
-                    name: name || defaultTunnelName(type, lPort, host, rPort),
+                    name: name || defaultTunnelName(type, lPort, host, rPort, serviceName(type === 'remote' ? lPort : rPort)),
                     type,
                     localPort: lPort,
                     remoteHost: host,
@@ -144,7 +260,21 @@ export function AddTunnelModal({ isOpen, onClose, initialConnectionId, editingTu
                     bindAddress,
                     autoStart,
                     status: editingTunnel?.status || 'stopped',
-                    group: group.trim() || undefined
+                    group: group.trim() || undefined,
+                    web: type === 'local' && (webProtocol || webPath.trim() || terminateTls || inspect || hostHeader.trim() || upstreamTls || hostname.trim())
+                        ? {
+                            protocol: (webProtocol || undefined) as 'http' | 'https' | undefined,
+                            path: webPath.trim() || undefined,
+                            terminateTls: terminateTls || undefined,
+                            inspect: inspect || undefined,
+                            hostHeader: hostHeader.trim() || undefined,
+                            upstreamTls: upstreamTls || undefined,
+                            hostname: hostname.trim() || undefined,
+                        }
+                        : undefined,
+                    pacDomains: isDynamic ? splitPacDomains(pacDomains) : undefined,
+                    systemProxy: isDynamic && systemProxy ? true : undefined,
+                    schedule: schedule ?? undefined,
                 };
 
                 await saveTunnel(config);
@@ -171,7 +301,13 @@ export function AddTunnelModal({ isOpen, onClose, initialConnectionId, editingTu
                     const config: TunnelConfig = {
                         id: crypto.randomUUID(),
                         connectionId: selectedConnectionId,
-                        name: defaultTunnelName(row.type, lPort, row.remoteHost, rPort),
+                        name: defaultTunnelName(
+                            row.type,
+                            lPort,
+                            row.remoteHost,
+                            rPort,
+                            serviceName(row.type === 'remote' ? lPort : rPort),
+                        ),
                         type: row.type,
                         localPort: lPort,
                         remoteHost: row.remoteHost,
@@ -439,8 +575,40 @@ export function AddTunnelModal({ isOpen, onClose, initialConnectionId, editingTu
                                                     </div>
                                                 </div>
                                             )}
+                                            <div className="flex flex-wrap gap-x-4 gap-y-1">
+                                                <button
+                                                    type="button"
+                                                    onClick={handleReservePort}
+                                                    className="text-[11px] text-app-muted transition-colors hover:text-app-text"
+                                                >
+                                                    Reserve a free local port (42000–42999)
+                                                </button>
+                                                {type !== 'remote' && (
+                                                    <button
+                                                        type="button"
+                                                        onClick={handleLoopbackAlias}
+                                                        title="Listen on 127.0.0.2, 127.0.0.3 … so several forwards can share this port"
+                                                        className="text-[11px] text-app-muted transition-colors hover:text-app-text"
+                                                    >
+                                                        Keep this port, use a free loopback address
+                                                    </button>
+                                                )}
+                                            </div>
                                         </div>
 
+                                        {type === 'local' && (
+                                            <RemotePortSuggestions
+                                                connectionId={selectedConnectionId}
+                                                connected={connections.find(c => c.id === selectedConnectionId)?.status === 'connected'}
+                                                onPick={(entry) => {
+                                                    setRemoteHost(forwardTargetHost(entry));
+                                                    setRemotePort(String(entry.port));
+                                                    setLocalPort(String(entry.port));
+                                                    if (!name && (entry.service || entry.process)) setName(entry.service || entry.process || '');
+                                                }}
+                                            />
+                                        )}
+
                                         {type !== 'dynamic' ? (
                                             <div className="grid grid-cols-1 gap-4 sm:grid-cols-2">
                                                 <Input
@@ -456,14 +624,109 @@ export function AddTunnelModal({ isOpen, onClose, initialConnectionId, editingTu
                                                     onChange={(e) => setBindAddress(e.target.value)}
                                                     className="font-mono"
                                                 />
+                                                {type === 'local' && (
+                                                    <>
+                                                        <Select
+                                                            label="Browser protocol"
+                                                            value={webProtocol}
+                                                            onChange={setWebProtocol}
+                                                            options={[
+                                                                { value: '', label: 'Auto', description: 'HTTPS on 443/8443/9443' },
+                                                                { value: 'http', label: 'HTTP' },
+                                                                { value: 'https', label: 'HTTPS' },
+                                                            ]}
+                                                            portal={true}
+                                                        />
+                                                        <Input
+                                                            label="Browser path (optional)"
+                                                            placeholder="/admin"
+                                                            value={webPath}
+                                                            onChange={(e) => setWebPath(e.target.value)}
+                                                            className="font-mono"
+                                                        />
+                                                        <Input
+                                                            label="Host header (optional)"
+                                                            placeholder="app.internal"
+                                                            value={hostHeader}
+                                                            onChange={(e) => setHostHeader(e.target.value)}
+                                                            className="font-mono"
+                                                        />
+                                                        <Input
+                                                            label="Local name (optional)"
+                                                            placeholder="myapp → myapp.zync.local"
+                                                            title="Added to the hosts file while the forward runs; may ask for the admin password"
+                                                            value={hostname}
+                                                            onChange={(e) => setHostname(e.target.value)}
+                                                            className="font-mono"
+                                                        />
+                                                        <div className="space-y-1 sm:col-span-2">
+                                                            <label className="flex items-center gap-2.5 cursor-pointer text-xs text-app-muted hover:text-app-text transition-colors">
+                                                                <input
+                                                                    type="checkbox"
+                                                                    checked={terminateTls}
+                                                                    onChange={(e) => setTerminateTls(e.target.checked)}
+                                                                    className="h-3.5 w-3.5 rounded border-app-border bg-app-surface accent-[var(--color-app-accent)]"
+                                                                />
+                                                                Serve locally over HTTPS
+                                                            </label>
+                                                            <label className="flex items-center gap-2.5 cursor-pointer text-xs text-app-muted hover:text-app-text transition-colors">
+                                                                <input
+                                                                    type="checkbox"
+                                                                    checked={upstreamTls}
+                                                                    onChange={(e) => setUpstreamTls(e.target.checked)}
+                                                                    className="h-3.5 w-3.5 rounded border-app-border bg-app-surface accent-[var(--color-app-accent)]"
+                                                                />
+                                                                Backend speaks HTTPS (connect with the Host header as SNI)
+                                                            </label>
+                                                            <label className="flex items-center gap-2.5 cursor-pointer text-xs text-app-muted hover:text-app-text transition-colors">
+                                                                <input
+                                                                    type="checkbox"
+                                                                    checked={inspect}
+                                                                    onChange={(e) => setInspect(e.target.checked)}
+                                                                    className="h-3.5 w-3.5 rounded border-app-border bg-app-surface accent-[var(--color-app-accent)]"
+                                                                />
+                                                                Log HTTP requests (method, path, status, timing; no bodies)
+                                                            </label>
+                                                            {terminateTls && localCa && (
+                                                                <p className="text-[11px] text-app-muted/70">
+                                                                    Trust the Zync Local CA once so browsers accept it:{' '}
+                                                                    <span className="select-all font-mono">{localCa.certPath}</span>
+                                                                </p>
+                                                            )}
+                                                        </div>
+                                                    </>
+                                                )}
                                             </div>
                                         ) : (
-                                            <Input
-                                                label="Name (optional)"
-                                                placeholder="e.g. Dev SOCKS proxy"
-                                                value={name}
-                                                onChange={(e) => setName(e.target.value)}
-                                            />
+                                            <>
+                                                <Input
+                                                    label="Name (optional)"
+                                                    placeholder="e.g. Dev SOCKS proxy"
+                                                    value={name}
+                                                    onChange={(e) => setName(e.target.value)}
+                                                />
+                                                <div className="space-y-1">
+                                                    <Input
+                                                        label="PAC domains (optional)"
+                                                        placeholder="corp.example, *.internal"
+                                                        value={pacDomains}
+                                                        onChange={(e) => setPacDomains(e.target.value)}
+                                                        className="font-mono"
+                                                    />
+                                                    <p className="text-[11px] text-app-muted/70">
+                                                        Only these domains and their subdomains go through the tunnel when the browser uses Zync&apos;s PAC file.
+                                                    </p>
+                                                </div>
+                                                <label className="flex items-center gap-2.5 cursor-pointer text-xs text-app-muted hover:text-app-text transition-colors">
+                                                    <input
+                                                        type="checkbox"
+                                                        checked={systemProxy}
+                                                        onChange={(e) => setSystemProxy(e.target.checked)}
+                                                        className="h-3.5 w-3.5 rounded border-app-border bg-app-surface accent-[var(--color-app-accent)]"
+                                                    />
+                                                    Use as the system proxy while running (restored on stop)
+                                                </label>
+                                            </>
                                         )}
                                     </motion.div>
                                 ) : (
@@ -602,6 +865,9 @@ export function AddTunnelModal({ isOpen, onClose, initialConnectionId, editingTu
                                         Auto-start when connection opens
                                     </label>
                                 )}
+                                {mode === 'single' && (
+                                    <TunnelScheduleFields schedule={schedule} onChange={setSchedule} />
+                                )}
                             </AnimatePresence>
                         </div>
                         </motion.div>
@@ -609,6 +875,19 @@ export function AddTunnelModal({ isOpen, onClose, initialConnectionId, editingTu
                 </AnimatePresence>
             </motion.div>
 
+            {!isTypeStep && mode === 'single' && issues.length > 0 && (
+                <div className="mx-5 mt-4 space-y-1 rounded-lg border border-app-border/40 bg-app-surface/40 px-3 py-2 text-xs">
+                    {issues.map((issue, index) => (
+                        <p
+                            key={`${issue.field}-${index}`}
+                            className={issue.severity === 'error' ? 'text-red-400' : 'text-yellow-400'}
+                        >
+                            {issue.message}
+                        </p>
+                    ))}
+                </div>
+            )}
+
             <AnimatePresence initial={false}>
                 {!isTypeStep && (
                     <motion.div
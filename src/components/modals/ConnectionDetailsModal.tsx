@@ -1,8 +1,11 @@
-import { Monitor, Calendar, Key, Shield, Server, Box, Code } from 'lucide-react';
+import { useEffect, useState } from 'react';
+import { Monitor, Calendar, Key, Shield, Server, Box, Code, Clock } from 'lucide-react';
 import { OSIcon } from '../icons/OSIcon';
 import { Connection } from '../../store/useAppStore'; // Updated Import
 import { Modal } from '../ui/Modal';
 import { cn } from '../../lib/utils';
+import { getRemoteClockIpc } from '../../features/remoteClock/infrastructure/remoteClockIpc';
+import { describeRemoteClock } from '../../features/remoteClock/domain/remoteClock';
 
 interface ConnectionDetailsModalProps {
     connection: Connection | null;
@@ -11,6 +14,20 @@ interface ConnectionDetailsModalProps {
 }
 
 export function ConnectionDetailsModal({ connection, isOpen, onClose }: ConnectionDetailsModalProps) {
+    const [clock, setClock] = useState<string | null>(null);
+    const connectionId = connection?.id;
+    const connected = connection?.status === 'connected';
+
+    useEffect(() => {
+        setClock(null);
+        if (!isOpen || !connectionId || !connected) return;
+        let cancelled = false;
+        getRemoteClockIpc(connectionId)
+            .then(result => { if (!cancelled) setClock(describeRemoteClock(result)); })
+            .catch(() => { /* Hosts without `date` simply don't show a clock. */ });
+        return () => { cancelled = true; };
+    }, [isOpen, connectionId, connected]);
+
     if (!connection) return null;
 
     // const IconMap: any = { Server, Database, Monitor, Cloud, Box, HardDrive, Globe, Code, Terminal };
@@ -65,6 +82,11 @@ export function ConnectionDetailsModal({ connection, isOpen, onClose }: Connecti
                         value={connection.lastConnected ? new Date(connection.lastConnected).toLocaleDateString() : 'Never'}
                         icon={<Calendar size={14} />}
                     />
+                    {clock && (
+                        <div className="col-span-2">
+                            <DetailItem label="Remote Clock" value={clock} icon={<Clock size={14} />} />
+                        </div>
+                    )}
                 </div>
 
                 {/* Additional Technical Info */}
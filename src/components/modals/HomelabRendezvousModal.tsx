@@ -0,0 +1,313 @@
+import { useCallback, useEffect, useState } from 'react';
+import { Modal } from '../ui/Modal';
+import { Button } from '../ui/Button';
+import { Input } from '../ui/Input';
+import { Select } from '../ui/Select';
+import { AlertCircle, CheckCircle2, Home, Server, Trash2 } from 'lucide-react';
+import { useAppStore } from '../../store/useAppStore';
+import {
+    listRendezvousIpc,
+    previewRendezvousIpc,
+    removeRendezvousIpc,
+    rendezvousStatusIpc,
+    setupRendezvousIpc,
+    type Rendezvous,
+    type RendezvousPreview,
+    type RendezvousRequest,
+    type RendezvousStatus,
+} from '../../features/rendezvous/infrastructure/rendezvousIpc';
+
+interface HomelabRendezvousModalProps {
+    isOpen: boolean;
+    onClose: () => void;
+    /** Preselects the home machine. */
+    homeConnectionId?: string;
+}
+
+const STATUS_POLL_MS = 15_000;
+
+const emptyForm = (homeConnectionId?: string) => ({
+    name: '',
+    homeConnectionId: homeConnectionId || '',
+    vpsConnectionId: '',
+    remotePort: '2222',
+    targetHost: '',
+    targetPort: '',
+    vpsHost: '',
+});
+
+function StatusBadge({ status }: { status?: RendezvousStatus }) {
+    if (!status) return <span className="text-[10px] text-app-muted">Checking...</span>;
+    const parts: string[] = [];
+    if (status.listening !== null) parts.push(status.listening ? 'VPS listening' : 'VPS not listening');
+    if (status.service !== null) parts.push(`unit ${status.service}`);
+    if (parts.length === 0) parts.push('Connect either end to check');
+    const healthy = status.listening !== false && (status.service === null || status.service === 'active');
+    return (
+        <span
+            className={`text-[10px] ${healthy ? 'text-app-success' : 'text-amber-400'}`}
+            title={status.error}
+        >
+            {parts.join(' · ')}
+        </span>
+    );
+}
+
+export function HomelabRendezvousModal({ isOpen, onClose, homeConnectionId }: HomelabRendezvousModalProps) {
+    const connections = useAppStore(state => state.connections);
+    const showToast = useAppStore(state => state.showToast);
+
+    const [setups, setSetups] = useState<Rendezvous[]>([]);
+    const [statuses, setStatuses] = useState<Record<string, RendezvousStatus>>({});
+    const [form, setForm] = useState(() => emptyForm(homeConnectionId));
+    const [preview, setPreview] = useState<RendezvousPreview | null>(null);
+    const [busy, setBusy] = useState(false);
+
+    const refresh = useCallback(async () => {
+        try {
+            const list = await listRendezvousIpc();
+            setSetups(list);
+            const results = await Promise.all(list.map(r => rendezvousStatusIpc(r.id).catch(() => undefined)));
+            setStatuses(Object.fromEntries(
+                results.filter((s): s is RendezvousStatus => !!s).map(s => [s.id, s]),
+            ));
+        } catch (error) {
+            console.error('Failed to load rendezvous setups:', error);
+        }
+    }, []);
+
+    useEffect(() => {
+        if (!isOpen) return;
+        setForm(emptyForm(homeConnectionId));
+        setPreview(null);
+        refresh();
+        const timer = setInterval(refresh, STATUS_POLL_MS);
+        return () => clearInterval(timer);
+    }, [isOpen, homeConnectionId, refresh]);
+
+    const update = (patch: Partial<ReturnType<typeof emptyForm>>) => {
+        setForm(current => ({ ...current, ...patch }));
+        setPreview(null);
+    };
+
+    const request = (): RendezvousRequest => ({
+        name: form.name.trim(),
+        homeConnectionId: form.homeConnectionId,
+        vpsConnectionId: form.vpsConnectionId,
+        remotePort: Number(form.remotePort) || 0,
+        targetHost: form.targetHost.trim() || null,
+        targetPort: form.targetPort ? Number(form.targetPort) : null,
+        vpsHost: form.vpsHost.trim() || null,
+    });
+
+    const connectionName = (id: string) => {
+        const connection = connections.find(c => c.id === id);
+        return connection ? connection.name || connection.host : id;
+    };
+    const isConnected = (id: string) => connections.find(c => c.id === id)?.status === 'connected';
+
+    const options = connections.map(c => ({
+        label: `${c.name || c.host}${c.status === 'connected' ? '' : ' (not connected)'}`,
+        value: c.id,
+        icon: c.icon,
+    }));
+    const bothConnected = isConnected(form.homeConnectionId) && isConnected(form.vpsConnectionId);
+
+    const handlePreview = async () => {
+        setBusy(true);
+        try {
+            setPreview(await previewRendezvousIpc(request()));
+        } catch (error) {
+            showToast('error', `${error}`);
+        } finally {
+            setBusy(false);
+        }
+    };
+
+    const handleSetup = async () => {
+        setBusy(true);
+        try {
+            const result = await setupRendezvousIpc(request());
+            const r = result.rendezvous;
+            showToast(
+                'success',
+                `${r.name} listens on ${r.vpsHost} 127.0.0.1:${r.remotePort}` +
+                (result.needsLogin ? '. Lingering was refused, so it only runs while you are logged in.' : ''),
+            );
+            setForm(emptyForm(homeConnectionId));
+            setPreview(null);
+            refresh();
+        } catch (error) {
+            showToast('error', `${error}`);
+        } finally {
+            setBusy(false);
+        }
+    };
+
+    const handleRemove = async (r: Rendezvous, teardown: boolean) => {
+        try {
+            const left = await removeRendezvousIpc(r.id, teardown);
+            if (left.length > 0) {
+                showToast('warning', `Removed ${r.name}; connect to clean up: ${left.join(', ')}`);
+            } else {
+                showToast('success', `Removed ${r.name}`);
+            }
+            refresh();
+        } catch (error) {
+            showToast('error', `${error}`);
+        }
+    };
+
+    return (
+        <Modal
+            isOpen={isOpen}
+            onClose={onClose}
+            title="Homelab Rendezvous"
+            subtitle="Keep a machine behind NAT reachable through a VPS with a persistent reverse tunnel."
+            width="max-w-2xl"
+        >
+            <div className="space-y-5">
+                {setups.length > 0 && (
+                    <div className="space-y-2">
+                        {setups.map(r => (
+                            <div key={r.id} className="flex items-center gap-3 p-2.5 rounded-lg bg-app-surface/50 border border-app-border/30 text-xs">
+                                <div className="flex-1 min-w-0">
+                                    <div className="font-semibold text-app-text truncate">{r.name}</div>
+                                    <div className="font-mono text-[10px] text-app-muted truncate">
+                                        {connectionName(r.homeConnectionId)} {r.targetHost}:{r.targetPort} → {r.vpsUser}@{r.vpsHost} 127.0.0.1:{r.remotePort}
+                                    </div>
+                                    <StatusBadge status={statuses[r.id]} />
+                                </div>
+                                <Button
+                                    variant="ghost"
+                                    className="h-7 px-2 text-[10px] text-app-muted"
+                                    onClick={() => handleRemove(r, false)}
+                                    title="Stop monitoring; leave the unit and key in place"
+                                >
+                                    Forget
+                                </Button>
+                                <Button
+                                    variant="ghost"
+                                    className="h-7 px-2 text-[10px] text-red-400 hover:bg-red-500/10"
+                                    onClick={() => handleRemove(r, true)}
+                                    title="Stop the unit on the home machine and revoke its key on the VPS"
+                                >
+                                    <Trash2 size={12} className="mr-1" /> Remove
+                                </Button>
+                            </div>
+                        ))}
+                    </div>
+                )}
+
+                <div className="grid grid-cols-2 gap-4">
+                    <div>
+                        <label className="text-xs font-semibold text-app-muted uppercase tracking-wider mb-2 px-1 flex items-center gap-1.5">
+                            <Home size={12} /> Home Machine
+                        </label>
+                        <Select
+                            placeholder="Behind NAT..."
+                            value={form.homeConnectionId}
+                            onChange={homeConnectionId => update({ homeConnectionId })}
+                            options={options}
+                        />
+                    </div>
+                    <div>
+                        <label className="text-xs font-semibold text-app-muted uppercase tracking-wider mb-2 px-1 flex items-center gap-1.5">
+                            <Server size={12} /> VPS
+                        </label>
+                        <Select
+                            placeholder="Publicly reachable..."
+                            value={form.vpsConnectionId}
+                            onChange={vpsConnectionId => update({ vpsConnectionId })}
+                            options={options}
+                        />
+                    </div>
+                    <Input
+                        label="Name"
+                        value={form.name}
+                        onChange={e => update({ name: e.target.value })}
+                        placeholder={connectionName(form.homeConnectionId) || 'home'}
+                    />
+                    <Input
+                        label="VPS Port (loopback)"
+                        type="number"
+                        value={form.remotePort}
+                        onChange={e => update({ remotePort: e.target.value })}
+                        placeholder="2222"
+                    />
+                    <Input
+                        label="Forward To (from home)"
+                        value={form.targetHost}
+                        onChange={e => update({ targetHost: e.target.value })}
+                        placeholder="localhost"
+                    />
+                    <Input
+                        label="Target Port"
+                        type="number"
+                        value={form.targetPort}
+                        onChange={e => update({ targetPort: e.target.value })}
+                        placeholder="22"
+                    />
+                    <div className="col-span-2">
+                        <Input
+                            label="VPS Address Seen From Home (optional)"
+                            value={form.vpsHost}
+                            onChange={e => update({ vpsHost: e.target.value })}
+                            placeholder="Saved host of the VPS connection"
+                        />
+                    </div>
+                </div>
+
+                <p className="text-[10px] text-app-muted px-1 opacity-70">
+                    A dedicated key on the home machine is authorized on the VPS for this one listener only, and a
+                    systemd user unit keeps <code>ssh -R</code> running. Reach the home machine with the VPS as jump
+                    host on <code>localhost</code> and the VPS port.
+                </p>
+
+                {!bothConnected && form.homeConnectionId && form.vpsConnectionId && (
+                    <div className="flex items-center gap-2 p-2.5 rounded bg-amber-500/5 border border-amber-500/20 text-xs text-amber-400">
+                        <AlertCircle size={14} /> Connect to both machines to preview or set up.
+                    </div>
+                )}
+
+                {preview && (
+                    <div className="space-y-3 pt-4 border-t border-app-border/30">
+                        <h4 className="text-xs font-semibold text-app-text flex items-center gap-2">
+                            <CheckCircle2 size={14} className="text-app-success" />
+                            <span>~/.config/systemd/user/{preview.rendezvous.unit} on {connectionName(preview.rendezvous.homeConnectionId)}</span>
+                        </h4>
+                        <pre className="p-3 rounded bg-app-surface/50 border border-app-border/20 text-[10px] font-mono text-app-muted whitespace-pre-wrap max-h-48 overflow-y-auto">
+                            {preview.unit}
+                        </pre>
+                        <h4 className="text-xs font-semibold text-app-text">
+                            ~/.ssh/authorized_keys on {connectionName(preview.rendezvous.vpsConnectionId)}
+                        </h4>
+                        <pre className="p-3 rounded bg-app-surface/50 border border-app-border/20 text-[10px] font-mono text-app-muted whitespace-pre-wrap break-all">
+                            {preview.authorizedKey}
+                        </pre>
+                    </div>
+                )}
+
+                <div className="flex justify-end gap-3 pt-2">
+                    <Button variant="ghost" onClick={onClose}>
+                        Close
+                    </Button>
+                    <Button variant="ghost" onClick={handlePreview} disabled={!bothConnected || busy}>
+                        Preview
+                    </Button>
+                    <Button
+                        onClick={handleSetup}
+                        disabled={!bothConnected || busy}
+                        isLoading={busy}
+                        className="bg-app-accent hover:bg-app-accent/90"
+                    >
+                        Set Up
+                    </Button>
+                </div>
+            </div>
+        </Modal>
+    );
+}
+
+import { registerModal } from '../../lib/modalRegistry';
+registerModal('homelabRendezvous', HomelabRendezvousModal);
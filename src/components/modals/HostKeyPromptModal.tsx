@@ -0,0 +1,110 @@
+import { useEffect, useRef } from 'react';
+import { ShieldAlert, ShieldQuestion } from 'lucide-react';
+import { Modal } from '../ui/Modal';
+import { Button } from '../ui/Button';
+import { useAppStore } from '../../store/useAppStore';
+import {
+    respondHostKeyPromptIpc,
+    type HostKeyPromptPayload,
+} from '../../features/connections/infrastructure/connectionIpc';
+
+interface HostKeyPromptModalProps {
+    isOpen: boolean;
+    onClose: () => void;
+    prompt: HostKeyPromptPayload;
+    /** Called once, however the prompt ended, so the next queued prompt can show. */
+    onFinished: () => void;
+}
+
+/** A host key the known_hosts policy refused: changed, or new under strict checking. */
+export function HostKeyPromptModal({ isOpen, onClose, prompt, onFinished }: HostKeyPromptModalProps) {
+    const connections = useAppStore(state => state.connections);
+    const settled = useRef(false);
+    const changed = prompt.recordedFingerprint !== null;
+
+    const finish = () => {
+        if (settled.current) return;
+        settled.current = true;
+        onClose();
+        onFinished();
+    };
+
+    // The backend withdraws the prompt when it times out or the connect is cancelled.
+    useEffect(() => {
+        const handler = (_: unknown, payload: { promptId: string }) => {
+            if (payload?.promptId === prompt.promptId) finish();
+        };
+        window.ipcRenderer.on('ssh:host-key-prompt-done', handler);
+        return () => {
+            window.ipcRenderer.off('ssh:host-key-prompt-done', handler);
+        };
+        // eslint-disable-next-line react-hooks/exhaustive-deps
+    }, [prompt.promptId]);
+
+    const respond = async (trust: boolean) => {
+        if (settled.current) return;
+        try {
+            await respondHostKeyPromptIpc(prompt.promptId, trust);
+        } catch (e) {
+            console.warn('[SSH] Host key decision not delivered:', e);
+        }
+        finish();
+    };
+
+    const connection = connections.find(c => c.id === prompt.connectionId);
+    const name = connection ? connection.name || connection.host : prompt.endpoint;
+
+    return (
+        <Modal
+            isOpen={isOpen}
+            onClose={() => void respond(false)}
+            title={changed ? `Host key changed for ${name}` : `Unknown host key for ${name}`}
+            subtitle={prompt.endpoint}
+            explicitDismissOnly
+        >
+            <div className="space-y-4">
+                {changed ? (
+                    <div className="flex gap-2 text-xs text-red-400 bg-red-500/10 border border-red-500/20 rounded-lg px-3 py-2">
+                        <ShieldAlert size={14} className="shrink-0 mt-0.5" />
+                        <span>
+                            The server presented a different key than the one on record. This happens after a
+                            reinstall or key rotation, but can also mean someone is intercepting the connection.
+                            Only trust it if you know why it changed.
+                        </span>
+                    </div>
+                ) : (
+                    <div className="flex gap-2 text-xs text-amber-400 bg-amber-500/10 border border-amber-500/20 rounded-lg px-3 py-2">
+                        <ShieldQuestion size={14} className="shrink-0 mt-0.5" />
+                        <span>
+                            Strict host key checking is on and this host has no key on record. Compare the
+                            fingerprint with one obtained from the server's administrator.
+                        </span>
+                    </div>
+                )}
+                <dl className="space-y-2 text-xs">
+                    <div>
+                        <dt className="text-app-muted">Presented {prompt.keyType} key</dt>
+                        <dd className="font-mono text-app-text break-all">{prompt.fingerprint}</dd>
+                    </div>
+                    {changed && (
+                        <div>
+                            <dt className="text-app-muted">Recorded key ({prompt.recordedAt})</dt>
+                            <dd className="font-mono text-app-muted break-all">{prompt.recordedFingerprint}</dd>
+                        </div>
+                    )}
+                </dl>
+                <div className="flex justify-end gap-3 pt-2">
+                    <Button variant="ghost" onClick={() => void respond(false)} autoFocus>
+                        Reject
+                    </Button>
+                    <Button variant={changed ? 'danger' : 'primary'} onClick={() => void respond(true)}>
+                        {changed ? 'Replace and Connect' : 'Trust and Connect'}
+                    </Button>
+                </div>
+            </div>
+        </Modal>
+    );
+}
+
+import { registerModal } from '../../lib/modalRegistry';
+registerModal('hostKeyPrompt', HostKeyPromptModal);
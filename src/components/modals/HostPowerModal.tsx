@@ -0,0 +1,123 @@
+import { useState } from 'react';
+import { PowerOff, RotateCcw } from 'lucide-react';
+import { Modal } from '../ui/Modal';
+import { Button } from '../ui/Button';
+import { Input } from '../ui/Input';
+import { cn } from '../../lib/utils';
+import type { Connection } from '../../store/useAppStore';
+import { issueHostPowerIpc, type PowerAction } from '../../features/hostPower/infrastructure/hostPowerIpc';
+import { followHostPower } from '../../features/hostPower/application/hostPowerService';
+import { SUDO_PASSWORD_REQUIRED } from '../../features/packages/infrastructure/packagesIpc';
+
+interface HostPowerModalProps {
+    connection: Connection;
+    isOpen: boolean;
+    onClose: () => void;
+}
+
+const ACTIONS: Array<{ id: PowerAction; label: string; icon: typeof RotateCcw }> = [
+    { id: 'reboot', label: 'Reboot', icon: RotateCcw },
+    { id: 'shutdown', label: 'Shut Down', icon: PowerOff },
+];
+
+/** Reboot or shut down a host; a reboot is followed until SSH is back and then reconnected. */
+export function HostPowerModal({ connection, isOpen, onClose }: HostPowerModalProps) {
+    const [action, setAction] = useState<PowerAction>('reboot');
+    const [confirmation, setConfirmation] = useState('');
+    const [sudoPassword, setSudoPassword] = useState('');
+    const [needsPassword, setNeedsPassword] = useState(false);
+    const [running, setRunning] = useState(false);
+    const [error, setError] = useState<string | null>(null);
+
+    const confirmed = confirmation.trim().toLowerCase() === connection.host.toLowerCase();
+
+    const submit = async () => {
+        if (!confirmed) return;
+        setRunning(true);
+        setError(null);
+        try {
+            const issued = await issueHostPowerIpc(connection.id, action, confirmation, {
+                sudoPassword: needsPassword ? sudoPassword : undefined,
+            });
+            onClose();
+            void followHostPower(connection.id, issued);
+        } catch (err) {
+            const message = err instanceof Error ? err.message : String(err);
+            if (message.startsWith(SUDO_PASSWORD_REQUIRED)) {
+                setNeedsPassword(true);
+            } else {
+                setError(message);
+            }
+        } finally {
+            setRunning(false);
+        }
+    };
+
+    return (
+        <Modal
+            isOpen={isOpen}
+            onClose={onClose}
+            title="Reboot or Shut Down"
+            subtitle={connection.name || connection.host}
+        >
+            <form
+                className="space-y-4"
+                onSubmit={(event) => {
+                    event.preventDefault();
+                    void submit();
+                }}
+            >
+                <div className="grid grid-cols-2 gap-2">
+                    {ACTIONS.map(({ id, label, icon: Icon }) => (
+                        <button
+                            key={id}
+                            type="button"
+                            onClick={() => setAction(id)}
+                            className={cn(
+                                'flex items-center justify-center gap-2 rounded-lg border px-3 py-2 text-sm transition-colors',
+                                action === id
+                                    ? 'border-app-accent bg-app-accent/10 text-app-text'
+                                    : 'border-app-border/50 text-app-muted hover:text-app-text',
+                            )}
+                        >
+                            <Icon size={14} /> {label}
+                        </button>
+                    ))}
+                </div>
+                <p className="text-xs text-app-muted">
+                    {action === 'reboot'
+                        ? 'Open terminals close while the host restarts. Zync waits for SSH to come back and reconnects them.'
+                        : 'The host powers off. Bringing it back needs console or out-of-band access.'}
+                </p>
+                <Input
+                    label={`Type ${connection.host} to confirm`}
+                    autoFocus
+                    value={confirmation}
+                    onChange={(event) => setConfirmation(event.target.value)}
+                />
+                {needsPassword && (
+                    <Input
+                        label="sudo password"
+                        type="password"
+                        value={sudoPassword}
+                        onChange={(event) => setSudoPassword(event.target.value)}
+                    />
+                )}
+                {error && <p className="text-xs text-red-400">{error}</p>}
+                <div className="flex justify-end gap-3 pt-2">
+                    <Button variant="ghost" type="button" onClick={onClose}>
+                        Cancel
+                    </Button>
+                    <Button
+                        variant="danger"
+                        type="submit"
+                        isLoading={running}
+                        disabled={!confirmed || (needsPassword && !sudoPassword)}
+                    >
+                        {action === 'reboot' ? 'Reboot' : 'Shut Down'}
+                    </Button>
+                </div>
+            </form>
+        </Modal>
+    );
+}
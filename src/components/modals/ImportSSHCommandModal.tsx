@@ -7,6 +7,7 @@ import { GroupSelector } from '../ui/GroupSelector';
 import { AlertCircle, CheckCircle2 } from 'lucide-react';
 import { useAppStore } from '../../store/useAppStore';
 import { defaultTunnelName } from '../../features/tunnels/domain/tunnelTypes';
+import { useServiceNames } from '../../features/tunnels/application/serviceNames';
 
 interface ImportSSHCommandModalProps {
     isOpen: boolean;
@@ -44,6 +45,7 @@ export function ImportSSHCommandModal({
     const connections = useAppStore(state => state.connections);
     const tunnels = useAppStore(state => state.tunnels);
     const showToast = useAppStore(state => state.showToast);
+    const serviceName = useServiceNames();
 
     // Reset state when modal opens
     useEffect(() => {
@@ -96,6 +98,7 @@ export function ImportSSHCommandModal({
                         tunnel.localPort,
                         tunnel.remoteHost,
                         tunnel.remotePort,
+                        serviceName(tunnel.type === 'remote' ? tunnel.localPort : tunnel.remotePort),
                     ),
                     type: tunnel.type,
                     localPort: tunnel.localPort,
@@ -103,6 +103,9 @@ const normalizeImportedConnectionPayload = (payload: ImportedConnectionPayload):
     port: payload.port,
     privateKeyPath: payload.privateKeyPath,
     jumpServerId: payload.jumpServerId,
+    sshOptions: payload.sshOptions,
+    hostKeyAlias: payload.hostKeyAlias,
+    userKnownHostsFile: payload.userKnownHostsFile,
     status: 'disconnected',
     icon: 'Server',
     tags: [],
@@ -116,6 +119,9 @@ const toImportedConnectionPayload = (connection: Connection): ImportedConnection
     port: connection.port,
     privateKeyPath: connection.privateKeyPath,
     jumpServerId: connection.jumpServerId,
+    sshOptions: connection.sshOptions,
+    hostKeyAlias: connection.hostKeyAlias,
+    userKnownHostsFile: connection.userKnownHostsFile,
 });
 
 const createDefaultDecisionMap = (
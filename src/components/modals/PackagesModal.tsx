@@ -0,0 +1,232 @@
+import { useCallback, useEffect, useState } from 'react';
+import { Download, Package, RefreshCw, Trash2 } from 'lucide-react';
+import { Modal } from '../ui/Modal';
+import { Button } from '../ui/Button';
+import { Input } from '../ui/Input';
+import { cn } from '../../lib/utils';
+import type { Connection } from '../../store/useAppStore';
+import {
+    installPackagesIpc,
+    listUpgradablePackagesIpc,
+    removePackagesIpc,
+    SUDO_PASSWORD_REQUIRED,
+    type PackageOperationResult,
+    type UpgradableList,
+} from '../../features/packages/infrastructure/packagesIpc';
+
+type PackageAction = 'install' | 'remove';
+
+interface PendingAction {
+    action: PackageAction;
+    packages: string[];
+}
+
+interface PackagesModalProps {
+    connection: Connection;
+    isOpen: boolean;
+    onClose: () => void;
+}
+
+function errorMessage(err: unknown): string {
+    return err instanceof Error ? err.message : String(err);
+}
+
+/** Pending upgrades plus install/remove for the host's package manager, with the full output of each run. */
+export function PackagesModal({ connection, isOpen, onClose }: PackagesModalProps) {
+    const [list, setList] = useState<UpgradableList | null>(null);
+    const [selected, setSelected] = useState<Set<string>>(new Set());
+    const [packageInput, setPackageInput] = useState('');
+    const [loading, setLoading] = useState(false);
+    const [running, setRunning] = useState(false);
+    const [error, setError] = useState<string | null>(null);
+    const [result, setResult] = useState<PackageOperationResult | null>(null);
+    const [passwordFor, setPasswordFor] = useState<PendingAction | null>(null);
+    const [sudoPassword, setSudoPassword] = useState('');
+
+    const refresh = useCallback(async () => {
+        setLoading(true);
+        setError(null);
+        try {
+            const next = await listUpgradablePackagesIpc(connection.id);
+            setList(next);
+            setSelected(new Set());
+        } catch (err) {
+            setError(errorMessage(err));
+        } finally {
+            setLoading(false);
+        }
+    }, [connection.id]);
+
+    useEffect(() => {
+        if (isOpen) void refresh();
+    }, [isOpen, refresh]);
+
+    const run = async ({ action, packages }: PendingAction, password?: string) => {
+        if (packages.length === 0) return;
+        setRunning(true);
+        setError(null);
+        try {
+            const invoke = action === 'install' ? installPackagesIpc : removePackagesIpc;
+            const outcome = await invoke(connection.id, packages, { sudoPassword: password });
+            setResult(outcome);
+            setPasswordFor(null);
+            setSudoPassword('');
+            if (outcome.success) void refresh();
+        } catch (err) {
+            const message = errorMessage(err);
+            if (message.startsWith(SUDO_PASSWORD_REQUIRED)) {
+                setPasswordFor({ action, packages });
+            } else {
+                setError(message);
+            }
+        } finally {
+            setRunning(false);
+        }
+    };
+
+    const typedPackages = packageInput.split(/\s+/).filter(Boolean);
+    const toggle = (name: string) =>
+        setSelected((current) => {
+            const next = new Set(current);
+            if (next.has(name)) next.delete(name);
+            else next.add(name);
+            return next;
+        });
+
+    return (
+        <Modal
+            isOpen={isOpen}
+            onClose={onClose}
+            title="Packages"
+            subtitle={`${connection.name || connection.host}${list ? ` · ${list.manager}` : ''}`}
+            width="max-w-3xl"
+        >
+            <div className="space-y-4">
+                <div className="flex items-end gap-2">
+                    <Input
+                        label="Packages"
+                        placeholder="nginx htop"
+                        value={packageInput}
+                        onChange={(event) => setPackageInput(event.target.value)}
+                    />
+                    <Button
+                        size="sm"
+                        disabled={typedPackages.length === 0 || running}
+                        onClick={() => void run({ action: 'install', packages: typedPackages })}
+                    >
+                        <Download size={14} className="mr-1.5" /> Install
+                    </Button>
+                    <Button
+                        size="sm"
+                        variant="danger"
+                        disabled={typedPackages.length === 0 || running}
+                        onClick={() => void run({ action: 'remove', packages: typedPackages })}
+                    >
+                        <Trash2 size={14} className="mr-1.5" /> Remove
+                    </Button>
+                </div>
+
+                {passwordFor && (
+                    <form
+                        className="flex items-end gap-2 rounded-lg border border-amber-500/30 bg-amber-500/5 p-3"
+                        onSubmit={(event) => {
+                            event.preventDefault();
+                            void run(passwordFor, sudoPassword);
+                        }}
+                    >
+                        <Input
+                            label={`sudo password to ${passwordFor.action} ${passwordFor.packages.join(' ')}`}
+                            type="password"
+                            autoFocus
+                            value={sudoPassword}
+                            onChange={(event) => setSudoPassword(event.target.value)}
+                        />
+                        <Button size="sm" type="submit" isLoading={running} disabled={!sudoPassword}>
+                            Continue
+                        </Button>
+                        <Button size="sm" variant="ghost" type="button" onClick={() => setPasswordFor(null)}>
+                            Cancel
+                        </Button>
+                    </form>
+                )}
+
+                {error && <p className="text-xs text-red-400">{error}</p>}
+
+                <div className="space-y-2">
+                    <div className="flex items-center justify-between text-xs text-app-muted">
+                        <span>
+                            {list ? `${list.updates.length} upgradable package${list.updates.length === 1 ? '' : 's'}` : 'Upgradable packages'}
+                        </span>
+                        <div className="flex items-center gap-3">
+                            <button
+                                type="button"
+                                onClick={() => void run({ action: 'install', packages: [...selected] })}
+                                disabled={selected.size === 0 || running}
+                                className="inline-flex items-center gap-1 transition-colors hover:text-app-text disabled:opacity-50"
+                            >
+                                <Package size={12} /> Upgrade selected
+                            </button>
+                            <button
+                                type="button"
+                                onClick={() => void refresh()}
+                                disabled={loading}
+                                className="inline-flex items-center gap-1 transition-colors hover:text-app-text disabled:opacity-50"
+                            >
+                                <RefreshCw size={12} className={cn(loading && 'animate-spin')} /> Refresh
+                            </button>
+                        </div>
+                    </div>
+                    {list && list.updates.length === 0 ? (
+                        <p className="py-6 text-center text-xs text-app-muted/70">
+                            Everything is up to date according to the host&apos;s cached package lists.
+                        </p>
+                    ) : (
+                        <div className="max-h-[35vh] overflow-auto rounded-lg border border-app-border/40">
+                            <table className="w-full text-left font-mono text-[11px]">
+                                <thead className="sticky top-0 bg-app-panel text-app-muted">
+                                    <tr>
+                                        <th className="w-6 px-2 py-1.5" />
+                                        <th className="px-2 py-1.5 font-medium">Package</th>
+                                        <th className="px-2 py-1.5 font-medium">Installed</th>
+                                        <th className="px-2 py-1.5 font-medium">Available</th>
+                                    </tr>
+                                </thead>
+                                <tbody>
+                                    {list?.updates.map((update) => (
+                                        <tr key={update.name} className="border-t border-app-border/20">
+                                            <td className="px-2 py-1">
+                                                <input
+                                                    type="checkbox"
+                                                    checked={selected.has(update.name)}
+                                                    onChange={() => toggle(update.name)}
+                                                />
+                                            </td>
+                                            <td className="px-2 py-1 text-app-text">{update.name}</td>
+                                            <td className="px-2 py-1 text-app-muted">{update.currentVersion ?? '—'}</td>
+                                            <td className="px-2 py-1 text-app-text">{update.newVersion}</td>
+                                        </tr>
+                                    ))}
+                                </tbody>
+                            </table>
+                        </div>
+                    )}
+                </div>
+
+                {result && (
+                    <div className="space-y-1">
+                        <div className="flex items-center justify-between text-xs">
+                            <span className="truncate font-mono text-app-muted" title={result.command}>$ {result.command}</span>
+                            <span className={cn('shrink-0 pl-2', result.success ? 'text-app-success' : 'text-red-400')}>
+                                exit {result.exitCode} · {(result.durationMs / 1000).toFixed(1)} s
+                            </span>
+                        </div>
+                        <pre className="max-h-[30vh] overflow-auto whitespace-pre-wrap rounded-lg border border-app-border/40 bg-app-bg p-2 font-mono text-[11px] text-app-text">
+                            {result.stdout}
+                            {result.stderr && <span className="text-red-400">{result.stderr}</span>}
+                        </pre>
+                    </div>
+                )}
+            </div>
+        </Modal>
+    );
+}
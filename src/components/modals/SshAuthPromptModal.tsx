@@ -0,0 +1,116 @@
+import { useState, useEffect, useRef } from 'react';
+import { KeyRound } from 'lucide-react';
+import { Modal } from '../ui/Modal';
+import { Button } from '../ui/Button';
+import { Input } from '../ui/Input';
+import {
+    respondAuthPromptIpc,
+    type AuthPromptPayload,
+} from '../../features/connections/infrastructure/connectionIpc';
+
+const MAX_ATTEMPTS = 3;
+
+interface SshAuthPromptModalProps {
+    isOpen: boolean;
+    onClose: () => void;
+    prompt: AuthPromptPayload;
+    /** Called once, however the prompt ended, so the next queued prompt can show. */
+    onFinished: () => void;
+}
+
+/** Keyboard-interactive questions (one-time codes, PAM passwords) for one hop of a connect. */
+export function SshAuthPromptModal({ isOpen, onClose, prompt, onFinished }: SshAuthPromptModalProps) {
+    const [answers, setAnswers] = useState<string[]>(() => prompt.fields.map(() => ''));
+    const settled = useRef(false);
+
+    const finish = () => {
+        if (settled.current) return;
+        settled.current = true;
+        onClose();
+        onFinished();
+    };
+
+    // The backend withdraws the prompt when it times out or the connect is cancelled.
+    useEffect(() => {
+        const handler = (_: unknown, payload: { promptId: string }) => {
+            if (payload?.promptId === prompt.promptId) finish();
+        };
+        window.ipcRenderer.on('ssh:auth-prompt-done', handler);
+        return () => {
+            window.ipcRenderer.off('ssh:auth-prompt-done', handler);
+        };
+        // eslint-disable-next-line react-hooks/exhaustive-deps
+    }, [prompt.promptId]);
+
+    const respond = async (responses: string[] | null) => {
+        if (settled.current) return;
+        try {
+            await respondAuthPromptIpc(prompt.promptId, responses);
+        } catch (e) {
+            // Already withdrawn; nothing is waiting for the answer.
+            console.warn('[SSH] Auth prompt answer not delivered:', e);
+        }
+        finish();
+    };
+
+    const { hop } = prompt;
+    const subtitle = hop.jumpFor
+        ? `Jump host for ${hop.jumpFor} · ${hop.endpoint}`
+        : hop.endpoint;
+
+    return (
+        <Modal
+            isOpen={isOpen}
+            onClose={() => void respond(null)}
+            title={prompt.title.trim() || `Sign in to ${hop.name}`}
+            subtitle={subtitle}
+            explicitDismissOnly
+        >
+            <form
+                className="space-y-4"
+                onSubmit={(e) => {
+                    e.preventDefault();
+                    void respond(answers);
+                }}
+            >
+                {prompt.attempt > 1 && (
+                    <div className="text-xs text-amber-400 bg-amber-500/10 border border-amber-500/20 rounded-lg px-3 py-2">
+                        The previous answer was rejected (attempt {prompt.attempt} of {MAX_ATTEMPTS}).
+                    </div>
+                )}
+                {prompt.instructions.trim() && (
+                    <p className="text-sm text-app-muted whitespace-pre-wrap">{prompt.instructions.trim()}</p>
+                )}
+                {prompt.fields.map((field, i) => (
+                    <Input
+                        key={i}
+                        label={field.prompt.trim().replace(/:$/, '') || 'Response'}
+                        type={field.echo ? 'text' : 'password'}
+                        autoComplete={field.echo ? 'off' : 'one-time-code'}
+                        autoFocus={i === 0}
+                        value={answers[i]}
+                        onChange={(e) => {
+                            const value = e.target.value;
+                            setAnswers(prev => prev.map((a, j) => (j === i ? value : a)));
+                        }}
+                    />
+                ))}
+                <div className="flex items-center justify-between pt-2">
+                    <span className="flex items-center gap-1.5 text-[11px] text-app-muted">
+                        <KeyRound size={12} />
+                        {hop.name}
+                    </span>
+                    <div className="flex gap-3">
+                        <Button type="button" variant="ghost" onClick={() => void respond(null)}>
+                            Cancel
+                        </Button>
+                        <Button type="submit">Continue</Button>
+                    </div>
+                </div>
+            </form>
+        </Modal>
+    );
+}
+
+import { registerModal } from '../../lib/modalRegistry';
+registerModal('sshAuthPrompt', SshAuthPromptModal);
@@ -0,0 +1,244 @@
+import { useEffect, useState } from 'react';
+import { AlertCircle, Copy } from 'lucide-react';
+import { Modal } from '../ui/Modal';
+import { Button } from '../ui/Button';
+import { cn } from '../../lib/utils';
+import { useAppStore, type TunnelConfig } from '../../store/useAppStore';
+import { defaultTunnelName } from '../../features/tunnels/domain/tunnelTypes';
+import { useServiceNames } from '../../features/tunnels/application/serviceNames';
+import {
+    exportSshConfigForwardsIpc,
+    previewSshConfigForwardsIpc,
+    type ConfigForward,
+    type ConfigForwardPreview,
+} from '../../features/tunnels/infrastructure/sshConfigForwardsIpc';
+
+interface SshConfigForwardsModalProps {
+    isOpen: boolean;
+    onClose: () => void;
+    onImport: () => void;
+}
+
+type Tab = 'import' | 'export';
+
+function describeForward(forward: ConfigForward): string {
+    const bind = forward.bindAddress ? `${forward.bindAddress}:` : '';
+    if (forward.type === 'dynamic') return `DynamicForward ${bind}${forward.localPort}`;
+    if (forward.type === 'remote') {
+        return `RemoteForward ${bind}${forward.remotePort} ${forward.remoteHost}:${forward.localPort}`;
+    }
+    return `LocalForward ${bind}${forward.localPort} ${forward.remoteHost}:${forward.remotePort}`;
+}
+
+/** Keep hand-written ssh_config forwards and Zync's tunnel list in step, in either direction. */
+export function SshConfigForwardsModal({ isOpen, onClose, onImport }: SshConfigForwardsModalProps) {
+    const connections = useAppStore((state) => state.connections);
+    const saveTunnel = useAppStore((state) => state.saveTunnel);
+    const showToast = useAppStore((state) => state.showToast);
+    const serviceName = useServiceNames();
+    const [tab, setTab] = useState<Tab>('import');
+    const [pasted, setPasted] = useState('');
+    const [preview, setPreview] = useState<ConfigForwardPreview | null>(null);
+    const [selected, setSelected] = useState<Set<number>>(new Set());
+    const [exported, setExported] = useState('');
+    const [exportError, setExportError] = useState<string | null>(null);
+    const [busy, setBusy] = useState(false);
+
+    const connectionLabel = (id: string | null) => {
+        const connection = connections.find((c) => c.id === id);
+        return connection ? connection.name || connection.host : null;
+    };
+
+    const scan = async (content?: string) => {
+        setBusy(true);
+        try {
+            const result = await previewSshConfigForwardsIpc(content);
+            setPreview(result);
+            setSelected(new Set(result.forwards
+                .map((entry, index) => (entry.connectionId && !entry.existingTunnelId ? index : -1))
+                .filter((index) => index >= 0)));
+        } catch (error) {
+            setPreview({ forwards: [], errors: [String(error)] });
+            setSelected(new Set());
+        } finally {
+            setBusy(false);
+        }
+    };
+
+    useEffect(() => {
+        if (!isOpen || tab !== 'export') return;
+        setExportError(null);
+        exportSshConfigForwardsIpc()
+            .then(setExported)
+            .catch((error) => {
+                setExported('');
+                setExportError(String(error));
+            });
+    }, [isOpen, tab]);
+
+    const toggle = (index: number) => {
+        setSelected((current) => {
+            const next = new Set(current);
+            if (next.has(index)) next.delete(index);
+            else next.add(index);
+            return next;
+        });
+    };
+
+    const importSelected = async () => {
+        if (!preview) return;
+        setBusy(true);
+        let imported = 0;
+        try {
+            for (const index of selected) {
+                const { connectionId, forward } = preview.forwards[index];
+                if (!connectionId) continue;
+                const tunnel: TunnelConfig = {
+                    id: crypto.randomUUID(),
+                    connectionId,
+                    name: defaultTunnelName(
+                        forward.type,
+                        forward.localPort,
+                        forward.remoteHost,
+                        forward.remotePort,
+                        serviceName(forward.type === 'remote' ? forward.localPort : forward.remotePort),
+                    ),
+                    type: forward.type,
+                    localPort: forward.localPort,
+                    remoteHost: forward.remoteHost,
+                    remotePort: forward.remotePort,
+                    bindAddress: forward.bindAddress ?? undefined,
+                    autoStart: false,
+                    status: 'stopped',
+                };
+                await saveTunnel(tunnel);
+                imported += 1;
+            }
+            showToast('success', `Imported ${imported} forward${imported === 1 ? '' : 's'} from SSH config`);
+            onImport();
+            onClose();
+        } catch (error) {
+            showToast('error', `Imported ${imported}, then failed: ${String(error)}`);
+        } finally {
+            setBusy(false);
+        }
+    };
+
+    return (
+        <Modal isOpen={isOpen} onClose={onClose} title="SSH Config Forwards" width="max-w-2xl">
+            <div className="space-y-4">
+                <div className="flex gap-1 rounded-lg bg-app-surface/50 p-1 w-fit">
+                    {(['import', 'export'] as const).map((id) => (
+                        <button
+                            key={id}
+                            type="button"
+                            onClick={() => setTab(id)}
+                            className={cn(
+                                'px-3 py-1 rounded-md text-xs font-medium transition-all',
+                                tab === id ? 'bg-app-accent text-white shadow-sm' : 'text-app-muted hover:text-app-text',
+                            )}
+                        >
+                            {id === 'import' ? 'Import' : 'Export'}
+                        </button>
+                    ))}
+                </div>
+
+                {tab === 'import' ? (
+                    <>
+                        <textarea
+                            value={pasted}
+                            onChange={(e) => setPasted(e.target.value)}
+                            placeholder={'Host db\n  LocalForward 5432 localhost:5432\n\nLeave empty to read ~/.ssh/config'}
+                            className="w-full h-28 px-3 py-2 text-xs font-mono bg-app-surface border border-app-border rounded-xl focus:outline-none focus:border-app-accent/50 resize-none placeholder:text-app-muted/30"
+                        />
+                        <div className="flex justify-end">
+                            <Button variant="secondary" isLoading={busy && !preview} onClick={() => void scan(pasted.trim() ? pasted : undefined)}>
+                                {pasted.trim() ? 'Read Pasted Config' : 'Read ~/.ssh/config'}
+                            </Button>
+                        </div>
+
+                        {preview && (
+                            <div className="space-y-2 border-t border-app-border/30 pt-3">
+                                {preview.forwards.length === 0 && (
+                                    <p className="text-xs text-app-muted">No LocalForward, RemoteForward or DynamicForward lines in named Host blocks.</p>
+                                )}
+                                <div className="max-h-[220px] space-y-1.5 overflow-y-auto pr-1">
+                                    {preview.forwards.map((entry, index) => {
+                                        const host = connectionLabel(entry.connectionId);
+                                        const importable = !!entry.connectionId && !entry.existingTunnelId;
+                                        return (
+                                            <label
+                                                key={index}
+                                                className={cn(
+                                                    'flex items-center gap-3 rounded border border-app-border/20 bg-app-surface/50 p-2 text-xs',
+                                                    importable ? 'cursor-pointer text-app-text' : 'text-app-muted/60',
+                                                )}
+                                            >
+                                                <input
+                                                    type="checkbox"
+                                                    disabled={!importable}
+                                                    checked={selected.has(index)}
+                                                    onChange={() => toggle(index)}
+                                                    className="h-3.5 w-3.5 rounded border-app-border bg-app-surface accent-[var(--color-app-accent)]"
+                                                />
+                                                <span className="font-mono">{describeForward(entry.forward)}</span>
+                                                <span className="ml-auto shrink-0 text-[10px] text-app-muted">
+                                                    {entry.hostAlias}
+                                                    {' → '}
+                                                    {entry.existingTunnelId
+                                                        ? 'already saved'
+                                                        : host ?? 'no matching host'}
+                                                </span>
+                                            </label>
+                                        );
+                                    })}
+                                </div>
+                                {preview.errors.length > 0 && (
+                                    <div className="rounded border border-red-500/10 bg-red-500/5 p-2 text-xs text-red-400">
+                                        {preview.errors.map((error, index) => (
+                                            <p key={index} className="flex items-start gap-1.5">
+                                                <AlertCircle size={12} className="mt-0.5 shrink-0" /> {error}
+                                            </p>
+                                        ))}
+                                    </div>
+                                )}
+                            </div>
+                        )}
+
+                        <div className="flex justify-end gap-3 pt-2">
+                            <Button variant="ghost" onClick={onClose}>Cancel</Button>
+                            <Button onClick={() => void importSelected()} isLoading={busy && !!preview} disabled={selected.size === 0}>
+                                Import {selected.size > 0 ? selected.size : ''} Forward{selected.size === 1 ? '' : 's'}
+                            </Button>
+                        </div>
+                    </>
+                ) : (
+                    <>
+                        <p className="text-xs text-app-muted">
+                            One Host block per server, with only its forward lines. ssh combines forwards from every matching block, so this can be appended to an existing config.
+                        </p>
+                        {exportError ? (
+                            <p className="text-xs text-red-400">{exportError}</p>
+                        ) : (
+                            <pre className="max-h-[300px] overflow-auto rounded-xl border border-app-border bg-app-surface px-3 py-2 text-xs font-mono text-app-text whitespace-pre">
+                                {exported || 'Loading…'}
+                            </pre>
+                        )}
+                        <div className="flex justify-end gap-3 pt-2">
+                            <Button variant="ghost" onClick={onClose}>Close</Button>
+                            <Button
+                                disabled={!exported}
+                                onClick={() => {
+                                    navigator.clipboard.writeText(exported);
+                                    showToast('success', 'SSH config copied');
+                                }}
+                            >
+                                <Copy size={12} className="mr-1" /> Copy
+                            </Button>
+                        </div>
+                    </>
+                )}
+            </div>
+        </Modal>
+    );
+}
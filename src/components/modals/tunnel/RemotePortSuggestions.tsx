@@ -0,0 +1,101 @@
+import { useEffect, useState } from 'react';
+import { RefreshCw } from 'lucide-react';
+import {
+    listRemoteListeningPortsIpc,
+    type RemoteListeningPort,
+} from '../../../features/tunnels/infrastructure/remotePortsIpc';
+import { cn } from '../../../lib/utils';
+
+const MAX_SUGGESTIONS = 12;
+
+const WILDCARD_ADDRESSES = new Set(['0.0.0.0', '::', '*']);
+
+/** Host to forward to from the server's side: wildcard and IPv4 loopback binds are reached via 127.0.0.1. */
+export function forwardTargetHost(entry: RemoteListeningPort): string {
+    if (WILDCARD_ADDRESSES.has(entry.address) || entry.address.startsWith('127.')) {
+        return '127.0.0.1';
+    }
+    return entry.address;
+}
+
+interface RemotePortSuggestionsProps {
+    connectionId: string;
+    /** Listing needs a live session; nothing is fetched until then. */
+    connected: boolean;
+    onPick: (entry: RemoteListeningPort) => void;
+}
+
+export function RemotePortSuggestions({ connectionId, connected, onPick }: RemotePortSuggestionsProps) {
+    const [ports, setPorts] = useState<RemoteListeningPort[]>([]);
+    const [loading, setLoading] = useState(false);
+    const [error, setError] = useState<string | null>(null);
+    const [refreshKey, setRefreshKey] = useState(0);
+
+    useEffect(() => {
+        if (!connectionId || !connected) {
+            setPorts([]);
+            return;
+        }
+        let cancelled = false;
+        setLoading(true);
+        setError(null);
+        listRemoteListeningPortsIpc(connectionId)
+            .then((list) => {
+                if (cancelled) return;
+                // Loopback-only services first: those are the ones a forward is for.
+                const sorted = [...list].sort((a, b) => Number(b.loopback) - Number(a.loopback) || a.port - b.port);
+                const seen = new Set<number>();
+                setPorts(sorted.filter((entry) => !seen.has(entry.port) && seen.add(entry.port)));
+            })
+            .catch((err) => {
+                if (!cancelled) setError(err instanceof Error ? err.message : String(err));
+            })
+            .finally(() => {
+                if (!cancelled) setLoading(false);
+            });
+        return () => {
+            cancelled = true;
+        };
+    }, [connectionId, connected, refreshKey]);
+
+    if (!connectionId || !connected) return null;
+
+    return (
+        <div className="space-y-2">
+            <div className="flex items-center justify-between">
+                <span className="text-xs text-app-muted">Listening on this server</span>
+                <button
+                    type="button"
+                    onClick={() => setRefreshKey((key) => key + 1)}
+                    disabled={loading}
+                    className="inline-flex items-center gap-1 text-xs text-app-muted transition-colors hover:text-app-accent disabled:opacity-50"
+                    title="Refresh"
+                >
+                    <RefreshCw size={12} className={cn(loading && 'animate-spin')} />
+                </button>
+            </div>
+            {error ? (
+                <p className="text-xs text-app-muted/70">{error}</p>
+            ) : ports.length === 0 && !loading ? (
+                <p className="text-xs text-app-muted/70">No listening TCP ports found.</p>
+            ) : (
+                <div className="flex flex-wrap gap-1.5">
+                    {ports.slice(0, MAX_SUGGESTIONS).map((entry) => (
+                        <button
+                            key={entry.port}
+                            type="button"
+                            onClick={() => onPick(entry)}
+                            title={`Forward ${entry.address}:${entry.port}${entry.process ? ` (${entry.process})` : ''}`}
+                            className="inline-flex items-center gap-1.5 rounded-md border border-app-border bg-app-surface/60 px-2 py-1 font-mono text-[11px] text-app-text transition-colors hover:border-app-accent/60 hover:text-app-accent"
+                        >
+                            :{entry.port}
+                            {(entry.service || entry.process) && (
+                                <span className="font-sans text-app-muted">{entry.service || entry.process}</span>
+                            )}
+                        </button>
+                    ))}
+                </div>
+            )}
+        </div>
+    );
+}
@@ -0,0 +1,93 @@
+import { cn } from '../../../lib/utils';
+import {
+    SCHEDULE_DAY_LABELS,
+    describeSchedule,
+    localUtcOffsetMinutes,
+    minuteToTime,
+    timeToMinute,
+    type TunnelSchedule,
+} from '../../../features/tunnels/domain/tunnelSchedule';
+
+interface TunnelScheduleFieldsProps {
+    schedule: TunnelSchedule | null;
+    onChange: (schedule: TunnelSchedule | null) => void;
+}
+
+const WORKING_HOURS: TunnelSchedule = {
+    days: [0, 1, 2, 3, 4],
+    startMinute: 9 * 60,
+    endMinute: 17 * 60,
+    utcOffsetMinutes: 0,
+};
+
+/** Optional weekly maintenance window; the tunnel is started and stopped around it. */
+export function TunnelScheduleFields({ schedule, onChange }: TunnelScheduleFieldsProps) {
+    const toggleDay = (day: number) => {
+        if (!schedule) return;
+        const days = schedule.days.includes(day)
+            ? schedule.days.filter((d) => d !== day)
+            : [...schedule.days, day].sort((a, b) => a - b);
+        onChange({ ...schedule, days });
+    };
+
+    const setTime = (field: 'startMinute' | 'endMinute', value: string) => {
+        const minute = timeToMinute(value);
+        if (schedule && minute !== null) onChange({ ...schedule, [field]: minute });
+    };
+
+    return (
+        <div className="space-y-2">
+            <label className="flex items-center gap-2.5 cursor-pointer text-xs text-app-muted hover:text-app-text transition-colors">
+                <input
+                    type="checkbox"
+                    checked={!!schedule}
+                    onChange={(e) => onChange(e.target.checked
+                        ? { ...WORKING_HOURS, utcOffsetMinutes: localUtcOffsetMinutes() }
+                        : null)}
+                    className="h-3.5 w-3.5 rounded border-app-border bg-app-surface accent-[var(--color-app-accent)]"
+                />
+                Only run inside a maintenance window
+            </label>
+            {schedule && (
+                <div className="space-y-2 rounded-lg border border-app-border/40 bg-app-surface/30 p-3">
+                    <div className="flex flex-wrap gap-1">
+                        {SCHEDULE_DAY_LABELS.map((label, day) => (
+                            <button
+                                key={label}
+                                type="button"
+                                onClick={() => toggleDay(day)}
+                                className={cn(
+                                    'rounded-md border px-2 py-1 text-[11px] transition-colors',
+                                    schedule.days.includes(day)
+                                        ? 'border-app-accent bg-app-accent/10 text-app-text'
+                                        : 'border-app-border/50 text-app-muted hover:text-app-text',
+                                )}
+                            >
+                                {label}
+                            </button>
+                        ))}
+                    </div>
+                    <div className="flex items-center gap-2 text-xs text-app-muted">
+                        <input
+                            type="time"
+                            value={minuteToTime(schedule.startMinute)}
+                            onChange={(e) => setTime('startMinute', e.target.value)}
+                            className="rounded-md border border-app-border/50 bg-app-bg px-2 py-1 font-mono text-app-text"
+                        />
+                        to
+                        <input
+                            type="time"
+                            value={minuteToTime(schedule.endMinute)}
+                            onChange={(e) => setTime('endMinute', e.target.value)}
+                            className="rounded-md border border-app-border/50 bg-app-bg px-2 py-1 font-mono text-app-text"
+                        />
+                    </div>
+                    <p className="text-[11px] text-app-muted/70">
+                        {describeSchedule(schedule)}. No days selected means every day; an end before the start runs past midnight.
+                        Zync stops the forward outside the window and starts it again when the window opens if the host is connected.
+                    </p>
+                </div>
+            )}
+        </div>
+    );
+}
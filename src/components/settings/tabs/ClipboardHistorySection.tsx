@@ -0,0 +1,74 @@
+import { ClipboardList } from 'lucide-react';
+import {
+    DEFAULT_CLIPBOARD_HISTORY_SETTINGS,
+    type ClipboardHistorySettings,
+} from '../../../features/clipboard/infrastructure/clipboardHistoryIpc';
+import type { AppSettings } from '../../../store/settingsSlice';
+import { Input } from '../../ui/Input';
+import { Section } from '../common/Section';
+
+interface ClipboardHistorySectionProps {
+    settings: AppSettings;
+    onUpdateSettings: (updates: Partial<AppSettings>) => Promise<void>;
+}
+
+/** Whether terminal copies are kept, how many, and whether they're encrypted at rest. */
+export function ClipboardHistorySection({ settings, onUpdateSettings }: ClipboardHistorySectionProps) {
+    const current = { ...DEFAULT_CLIPBOARD_HISTORY_SETTINGS, ...settings.clipboardHistory };
+    const update = (patch: Partial<ClipboardHistorySettings>) => {
+        void onUpdateSettings({ clipboardHistory: { ...current, ...patch } });
+    };
+
+    return (
+        <Section title="Clipboard History">
+            <div className="space-y-3 rounded-lg border border-[var(--color-app-border)] bg-[var(--color-app-surface)]/50 p-4">
+                <div className="flex items-start justify-between gap-3">
+                    <div className="flex items-start gap-3">
+                        <div className="p-2 bg-[var(--color-app-bg)] rounded-md border border-[var(--color-app-border)] text-[var(--color-app-accent)]">
+                            <ClipboardList size={20} />
+                        </div>
+                        <div>
+                            <h4 className="text-sm font-medium text-[var(--color-app-text)]">Keep text copied from terminals</h4>
+                            <p className="text-xs text-[var(--color-app-muted)] mt-1 max-w-md">
+                                Open it from the terminal context menu. Pinned copies are never dropped; incognito pauses recording until restart.
+                            </p>
+                        </div>
+                    </div>
+                    <label className="flex shrink-0 items-center gap-2 text-xs text-[var(--color-app-muted)] cursor-pointer">
+                        <input
+                            type="checkbox"
+                            checked={current.enabled}
+                            onChange={(e) => update({ enabled: e.target.checked })}
+                            className="accent-[var(--color-app-accent)]"
+                        />
+                        Enabled
+                    </label>
+                </div>
+                <div className="flex items-center gap-4">
+                    <div className="w-32">
+                        <Input
+                            type="number"
+                            label="Entries"
+                            min={1}
+                            max={5000}
+                            value={current.maxEntries}
+                            onChange={(e) => {
+                                const value = Number(e.target.value);
+                                if (Number.isFinite(value) && value >= 1) update({ maxEntries: Math.min(5000, Math.floor(value)) });
+                            }}
+                        />
+                    </div>
+                    <label className="flex items-center gap-2 text-sm text-[var(--color-app-muted)] cursor-pointer hover:text-[var(--color-app-text)]">
+                        <input
+                            type="checkbox"
+                            checked={current.encrypt}
+                            onChange={(e) => update({ encrypt: e.target.checked })}
+                            className="accent-[var(--color-app-accent)]"
+                        />
+                        Encrypt with a key stored in the OS keychain
+                    </label>
+                </div>
+            </div>
+        </Section>
+    );
+}
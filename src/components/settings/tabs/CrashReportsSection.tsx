@@ -0,0 +1,126 @@
+import { save as saveDialog } from '@tauri-apps/plugin-dialog';
+import { Bug, CheckCircle2 } from 'lucide-react';
+import { useCallback, useEffect, useState } from 'react';
+import {
+    bundleCrashReportsIpc,
+    clearCrashReportsIpc,
+    listCrashReportsIpc,
+    type CrashReportList,
+} from '../../../features/diagnostics/infrastructure/crashReportsIpc';
+import { useAppStore } from '../../../store/useAppStore';
+import { Button } from '../../ui/Button';
+import { Section } from '../common/Section';
+
+const errorMessage = (error: unknown) => (error instanceof Error ? error.message : String(error));
+
+/** Local crash reports: nothing is uploaded, the user bundles them for an issue. */
+export function CrashReportsSection() {
+    const showToast = useAppStore((state) => state.showToast);
+    const [list, setList] = useState<CrashReportList | null>(null);
+    const [busy, setBusy] = useState(false);
+
+    const refresh = useCallback(async () => {
+        try {
+            setList(await listCrashReportsIpc());
+        } catch (error) {
+            console.error('Failed to list crash reports:', error);
+        }
+    }, []);
+
+    useEffect(() => {
+        void refresh();
+    }, [refresh]);
+
+    const handleBundle = async () => {
+        const destination = await saveDialog({
+            defaultPath: `zync-diagnostics-${new Date().toISOString().slice(0, 10)}.zip`,
+            filters: [{ name: 'Zip Archive', extensions: ['zip'] }],
+        });
+        if (!destination) return;
+        setBusy(true);
+        try {
+            const count = await bundleCrashReportsIpc(destination);
+            showToast('success', `Saved ${count} crash report${count === 1 ? '' : 's'} with the recent log. Attach the zip to your issue.`);
+        } catch (error) {
+            showToast('error', `Failed to bundle crash reports: ${errorMessage(error)}`);
+        } finally {
+            setBusy(false);
+        }
+    };
+
+    const handleClear = async () => {
+        setBusy(true);
+        try {
+            const removed = await clearCrashReportsIpc();
+            showToast('success', `Removed ${removed} crash report${removed === 1 ? '' : 's'}`);
+            await refresh();
+        } catch (error) {
+            showToast('error', `Failed to clear crash reports: ${errorMessage(error)}`);
+        } finally {
+            setBusy(false);
+        }
+    };
+
+    const reports = list?.reports ?? [];
+
+    return (
+        <Section title="Crash Reports">
+            <div className="space-y-3 rounded-lg border border-[var(--color-app-border)] bg-[var(--color-app-surface)]/50 p-4">
+                <div className="flex items-start justify-between gap-3">
+                    <div className="flex items-start gap-3">
+                        <div className="p-2 bg-[var(--color-app-bg)] rounded-md border border-[var(--color-app-border)] text-[var(--color-app-accent)]">
+                            <Bug size={20} />
+                        </div>
+                        <div>
+                            <h4 className="text-sm font-medium text-[var(--color-app-text)]">Saved on this device only</h4>
+                            <p className="text-xs text-[var(--color-app-muted)] mt-1 max-w-md">
+                                Crashes are written to a local folder with a backtrace and recent log lines, with secrets redacted.
+                                Nothing is sent anywhere.
+                            </p>
+                            {list && (
+                                <p className="text-[10px] text-[var(--color-app-muted)] mt-1 font-mono break-all">{list.dir}</p>
+                            )}
+                        </div>
+                    </div>
+                    <div className="flex shrink-0 gap-2">
+                        <Button variant="secondary" className="h-8 text-xs" disabled={busy} onClick={() => { void handleBundle(); }}>
+                            Bundle for Issue…
+                        </Button>
+                        <Button
+                            variant="secondary"
+                            className="h-8 text-xs"
+                            disabled={busy || reports.length === 0}
+                            onClick={() => { void handleClear(); }}
+                        >
+                            Clear
+                        </Button>
+                    </div>
+                </div>
+
+                <div className="space-y-1 rounded-md border border-[var(--color-app-border)] bg-[var(--color-app-bg)]/40 px-3 py-2 text-xs">
+                    {reports.length === 0 ? (
+                        <p className="flex items-center gap-2 text-[var(--color-app-text)]">
+                            <CheckCircle2 size={14} className="text-green-400" /> No crashes recorded.
+                        </p>
+                    ) : (
+                        reports.slice(0, 10).map((report) => (
+                            <p key={report.id} className="flex items-start gap-2 text-[var(--color-app-text)]">
+                                <span className="shrink-0 text-[var(--color-app-muted)]">
+                                    {new Date(report.createdAt).toLocaleString()}
+                                </span>
+                                <span className="break-all">
+                                    {report.task && <span className="text-[var(--color-app-muted)]">[{report.task}] </span>}
+                                    {report.message}
+                                    {report.location && <span className="text-[var(--color-app-muted)]"> at {report.location}</span>}
+                                </span>
+                            </p>
+                        ))
+                    )}
+                    {reports.length > 10 && (
+                        <p className="text-[var(--color-app-muted)]">…and {reports.length - 10} older</p>
+                    )}
+                </div>
+            </div>
+        </Section>
+    );
+}
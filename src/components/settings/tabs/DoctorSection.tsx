@@ -0,0 +1,79 @@
+import { AlertTriangle, CheckCircle2, Stethoscope, XCircle } from 'lucide-react';
+import { useState } from 'react';
+import {
+    runDoctorIpc,
+    type DoctorReport,
+    type DoctorStatus,
+} from '../../../features/diagnostics/infrastructure/doctorIpc';
+import { useAppStore } from '../../../store/useAppStore';
+import { Button } from '../../ui/Button';
+import { Section } from '../common/Section';
+
+const errorMessage = (error: unknown) => (error instanceof Error ? error.message : String(error));
+
+function StatusIcon({ status }: { status: DoctorStatus }) {
+    if (status === 'pass') return <CheckCircle2 size={14} className="mt-0.5 shrink-0 text-green-400" />;
+    if (status === 'warn') return <AlertTriangle size={14} className="mt-0.5 shrink-0 text-yellow-400" />;
+    return <XCircle size={14} className="mt-0.5 shrink-0 text-red-400" />;
+}
+
+/** Runs the self-diagnostic checks and lists them with suggested fixes. */
+export function DoctorSection() {
+    const showToast = useAppStore((state) => state.showToast);
+    const [report, setReport] = useState<DoctorReport | null>(null);
+    const [running, setRunning] = useState(false);
+
+    const handleRun = async () => {
+        setRunning(true);
+        try {
+            setReport(await runDoctorIpc());
+        } catch (error) {
+            showToast('error', `Doctor failed: ${errorMessage(error)}`);
+        } finally {
+            setRunning(false);
+        }
+    };
+
+    return (
+        <Section title="Doctor">
+            <div className="space-y-3 rounded-lg border border-[var(--color-app-border)] bg-[var(--color-app-surface)]/50 p-4">
+                <div className="flex items-start justify-between gap-3">
+                    <div className="flex items-start gap-3">
+                        <div className="p-2 bg-[var(--color-app-bg)] rounded-md border border-[var(--color-app-border)] text-[var(--color-app-accent)]">
+                            <Stethoscope size={20} />
+                        </div>
+                        <div>
+                            <h4 className="text-sm font-medium text-[var(--color-app-text)]">Check this installation</h4>
+                            <p className="text-xs text-[var(--color-app-muted)] mt-1 max-w-md">
+                                Data files, the OS keychain, OpenSSH and ssh-agent, Windows terminal support and network access.
+                            </p>
+                        </div>
+                    </div>
+                    <Button variant="secondary" className="h-8 shrink-0 text-xs" disabled={running} onClick={() => { void handleRun(); }}>
+                        {running ? 'Checking…' : 'Run Doctor'}
+                    </Button>
+                </div>
+
+                {report && (
+                    <div className="space-y-2 rounded-md border border-[var(--color-app-border)] bg-[var(--color-app-bg)]/40 px-3 py-2 text-xs">
+                        <p className="text-[var(--color-app-muted)]">
+                            {report.passed} passed · {report.warnings} warning{report.warnings === 1 ? '' : 's'} · {report.failures} failed
+                        </p>
+                        {report.checks.map((check) => (
+                            <div key={check.id} className="flex items-start gap-2">
+                                <StatusIcon status={check.status} />
+                                <div className="min-w-0">
+                                    <p className="text-[var(--color-app-text)]">
+                                        <span className="font-medium">{check.title}</span>
+                                        <span className="text-[var(--color-app-muted)]"> — {check.detail}</span>
+                                    </p>
+                                    {check.fix && <p className="text-[var(--color-app-muted)]">{check.fix}</p>}
+                                </div>
+                            </div>
+                        ))}
+                    </div>
+                )}
+            </div>
+        </Section>
+    );
+}
@@ -5,6 +5,14 @@ import type { AppSettings } from '../../../store/settingsSlice';
 import type { SelectOption } from '../../ui/Select';
 import { Select } from '../../ui/Select';
 import { Section } from '../common/Section';
+import { ProfilesSection } from './ProfilesSection';
+import { ClipboardHistorySection } from './ClipboardHistorySection';
+import { CrashReportsSection } from './CrashReportsSection';
+import { DoctorSection } from './DoctorSection';
+import { LogForwardingSection } from './LogForwardingSection';
+import { PrivilegedPortsSection } from './PrivilegedPortsSection';
+import { RedactionSection } from './RedactionSection';
+import { StorageSection } from './StorageSection';
 
 interface ActiveEditorProvider {
     manifest: {
@@ -71,6 +79,8 @@ export function GeneralTab({
 
     return (
         <div className="space-y-6 animate-in fade-in duration-300">
+            <ProfilesSection />
+
             <Section title="Privacy">
                 <div className="p-4 rounded-lg bg-[var(--color-app-surface)]/50 border border-[var(--color-app-border)]">
                     <div className="flex items-center justify-between">
@@ -110,6 +120,20 @@ export function GeneralTab({
                 </div>
             </Section>
 
+            <RedactionSection settings={settings} onUpdateSettings={onUpdateSettings} />
+
+            <LogForwardingSection settings={settings} onUpdateSettings={onUpdateSettings} />
+
+            <ClipboardHistorySection settings={settings} onUpdateSettings={onUpdateSettings} />
+
+            <StorageSection settings={settings} onUpdateSettings={onUpdateSettings} />
+
+            <DoctorSection />
+
+            <PrivilegedPortsSection />
+
+            <CrashReportsSection />
+
             <Section title="Application">
                 <div className="p-4 rounded-lg bg-[var(--color-app-surface)]/50 border border-[var(--color-app-border)]">
                     <div className="flex items-center justify-between">
@@ -0,0 +1,163 @@
+import { ScrollText } from 'lucide-react';
+import { useState } from 'react';
+import {
+    DEFAULT_LOG_FORWARDING_SETTINGS,
+    testLogForwardingIpc,
+    type LogForwardingSettings,
+} from '../../../features/logForwarding/infrastructure/logForwardingIpc';
+import type { AppSettings } from '../../../store/settingsSlice';
+import { Button } from '../../ui/Button';
+import { Input } from '../../ui/Input';
+import { Section } from '../common/Section';
+import { SegmentedControl } from '../common/SegmentedControl';
+
+interface LogForwardingSectionProps {
+    settings: AppSettings;
+    onUpdateSettings: (updates: Partial<AppSettings>) => Promise<void>;
+}
+
+const TARGETS = [
+    { key: 'file', label: 'File' },
+    { key: 'udp', label: 'UDP Syslog' },
+] as const;
+
+const FORMATS = [
+    { key: 'json', label: 'JSON Lines' },
+    { key: 'syslog', label: 'Syslog (RFC 5424)' },
+] as const;
+
+/** Sends connection, tunnel and transfer events plus audit entries to a file or syslog collector. */
+export function LogForwardingSection({ settings, onUpdateSettings }: LogForwardingSectionProps) {
+    const saved = settings.logForwarding ?? DEFAULT_LOG_FORWARDING_SETTINGS;
+    const [draft, setDraft] = useState<LogForwardingSettings>(saved);
+    // Kept as typed so a trailing comma survives until the next name.
+    const [eventsText, setEventsText] = useState(saved.events.join(', '));
+    const [testResult, setTestResult] = useState<{ ok: boolean; message: string } | null>(null);
+    const isDirty = JSON.stringify(draft) !== JSON.stringify(saved);
+
+    const handleTest = async () => {
+        try {
+            await testLogForwardingIpc(draft);
+            setTestResult({ ok: true, message: 'Test line sent.' });
+        } catch (error) {
+            setTestResult({ ok: false, message: error instanceof Error ? error.message : String(error) });
+        }
+    };
+
+    return (
+        <Section title="Log Forwarding">
+            <div className="space-y-3 rounded-lg border border-[var(--color-app-border)] bg-[var(--color-app-surface)]/50 p-4">
+                <div className="flex items-start justify-between gap-3">
+                    <div className="flex items-start gap-3">
+                        <div className="p-2 bg-[var(--color-app-bg)] rounded-md border border-[var(--color-app-border)] text-[var(--color-app-accent)]">
+                            <ScrollText size={20} />
+                        </div>
+                        <div>
+                            <h4 className="text-sm font-medium text-[var(--color-app-text)]">Forward events to a log collector</h4>
+                            <p className="text-xs text-[var(--color-app-muted)] mt-1 max-w-md">
+                                Connections, tunnels, transfers and audit entries, one line each, masked by the redaction rules above.
+                            </p>
+                        </div>
+                    </div>
+                    <label className="flex shrink-0 items-center gap-2 text-xs text-[var(--color-app-muted)] cursor-pointer">
+                        <input
+                            type="checkbox"
+                            checked={draft.enabled}
+                            onChange={(e) => setDraft({ ...draft, enabled: e.target.checked })}
+                            className="accent-[var(--color-app-accent)]"
+                        />
+                        Enabled
+                    </label>
+                </div>
+
+                <div className="flex flex-wrap items-center gap-3">
+                    <SegmentedControl
+                        options={TARGETS}
+                        value={draft.target}
+                        onChange={(target) => setDraft({
+                            ...draft,
+                            target,
+                            format: target === 'udp' ? 'syslog' : draft.format,
+                        })}
+                        ariaLabel="Log forwarding target"
+                        idPrefix="log-forwarding-target"
+                    />
+                    <SegmentedControl
+                        options={FORMATS}
+                        value={draft.format}
+                        onChange={(format) => setDraft({ ...draft, format })}
+                        ariaLabel="Log line format"
+                        idPrefix="log-forwarding-format"
+                    />
+                </div>
+
+                <div className="grid grid-cols-[2fr_1fr] gap-2">
+                    {draft.target === 'file' ? (
+                        <Input
+                            label="File"
+                            value={draft.path ?? ''}
+                            placeholder="events.log in the data folder"
+                            className="font-mono"
+                            onChange={(e) => setDraft({ ...draft, path: e.target.value || null })}
+                        />
+                    ) : (
+                        <Input
+                            label="Syslog Server"
+                            value={draft.address ?? ''}
+                            placeholder="logs.example.com:514"
+                            className="font-mono"
+                            onChange={(e) => setDraft({ ...draft, address: e.target.value || null })}
+                        />
+                    )}
+                    <Input
+                        label="Facility"
+                        type="number"
+                        min={0}
+                        max={23}
+                        value={draft.facility}
+                        disabled={draft.format !== 'syslog'}
+                        onChange={(e) => setDraft({ ...draft, facility: Math.min(23, Math.max(0, Number(e.target.value) || 0)) })}
+                    />
+                </div>
+                <Input
+                    label="Events"
+                    value={eventsText}
+                    placeholder="All but transfer progress, e.g. connected, tunnel-down, audit"
+                    onChange={(e) => {
+                        setEventsText(e.target.value);
+                        setDraft({
+                            ...draft,
+                            events: e.target.value.split(',').map((event) => event.trim()).filter(Boolean),
+                        });
+                    }}
+                />
+
+                <div className="flex items-center justify-between gap-3">
+                    <div className="flex items-center gap-3 min-w-0">
+                        <Button variant="secondary" className="h-8 text-xs" onClick={() => { void handleTest(); }}>
+                            Send Test
+                        </Button>
+                        {testResult && (
+                            <p className={`text-xs truncate ${testResult.ok ? 'text-[var(--color-app-muted)]' : 'text-red-400'}`}>
+                                {testResult.message}
+                            </p>
+                        )}
+                    </div>
+                    {isDirty && (
+                        <div className="flex gap-2">
+                            <Button variant="ghost" className="h-8 text-xs" onClick={() => {
+                                setDraft(saved);
+                                setEventsText(saved.events.join(', '));
+                            }}>
+                                Discard
+                            </Button>
+                            <Button className="h-8 text-xs" onClick={() => { void onUpdateSettings({ logForwarding: draft }); }}>
+                                Save
+                            </Button>
+                        </div>
+                    )}
+                </div>
+            </div>
+        </Section>
+    );
+}
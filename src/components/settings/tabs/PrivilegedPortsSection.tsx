@@ -0,0 +1,109 @@
+import { CheckCircle2, Lock, XCircle } from 'lucide-react';
+import { useCallback, useEffect, useState } from 'react';
+import {
+    applyPrivilegedPortFixIpc,
+    privilegedPortStatusIpc,
+    type PortFix,
+    type PrivilegedPortStatus,
+} from '../../../features/tunnels/infrastructure/privilegedPortsIpc';
+import { useAppStore } from '../../../store/useAppStore';
+import { Button } from '../../ui/Button';
+import { Section } from '../common/Section';
+
+const errorMessage = (error: unknown) => (error instanceof Error ? error.message : String(error));
+
+/** Checks whether forwards can listen on ports below 1024 and offers the OS-level fixes. */
+export function PrivilegedPortsSection() {
+    const showToast = useAppStore((state) => state.showToast);
+    const [port, setPort] = useState(80);
+    const [status, setStatus] = useState<PrivilegedPortStatus | null>(null);
+    const [applying, setApplying] = useState<PortFix['kind'] | null>(null);
+
+    const refresh = useCallback(async (target: number) => {
+        try {
+            setStatus(await privilegedPortStatusIpc(target));
+        } catch (error) {
+            showToast('error', `Could not check port ${target}: ${errorMessage(error)}`);
+        }
+    }, [showToast]);
+
+    useEffect(() => {
+        if (port < 1 || port > 1023) return;
+        const timer = window.setTimeout(() => { void refresh(port); }, 300);
+        return () => window.clearTimeout(timer);
+    }, [port, refresh]);
+
+    const handleApply = async (fix: PortFix) => {
+        setApplying(fix.kind);
+        try {
+            await applyPrivilegedPortFixIpc(fix.kind, port);
+            showToast(
+                'success',
+                fix.kind === 'setcap' ? 'Applied. Restart Zync to use low ports.' : `Ports from ${port} can now be used.`,
+            );
+            await refresh(port);
+        } catch (error) {
+            const message = errorMessage(error);
+            if (message !== 'Cancelled') showToast('error', `Could not apply the fix: ${message}`);
+        } finally {
+            setApplying(null);
+        }
+    };
+
+    return (
+        <Section title="Privileged ports">
+            <div className="space-y-3 rounded-lg border border-[var(--color-app-border)] bg-[var(--color-app-surface)]/50 p-4">
+                <div className="flex items-start justify-between gap-3">
+                    <div className="flex items-start gap-3">
+                        <div className="p-2 bg-[var(--color-app-bg)] rounded-md border border-[var(--color-app-border)] text-[var(--color-app-accent)]">
+                            <Lock size={20} />
+                        </div>
+                        <div>
+                            <h4 className="text-sm font-medium text-[var(--color-app-text)]">Local ports below 1024</h4>
+                            <p className="text-xs text-[var(--color-app-muted)] mt-1 max-w-md">
+                                Forwarding to local ports like 80 or 443 needs extra rights on some systems.
+                            </p>
+                        </div>
+                    </div>
+                    <input
+                        type="number"
+                        min={1}
+                        max={1023}
+                        value={port}
+                        onChange={(e) => setPort(Number(e.target.value))}
+                        className="h-8 w-20 shrink-0 rounded-md border border-[var(--color-app-border)] bg-[var(--color-app-bg)] px-2 text-xs text-[var(--color-app-text)]"
+                        aria-label="Port to check"
+                    />
+                </div>
+
+                {status && (
+                    <div className="space-y-2 rounded-md border border-[var(--color-app-border)] bg-[var(--color-app-bg)]/40 px-3 py-2 text-xs">
+                        <div className="flex items-start gap-2">
+                            {status.canBind
+                                ? <CheckCircle2 size={14} className="mt-0.5 shrink-0 text-green-400" />
+                                : <XCircle size={14} className="mt-0.5 shrink-0 text-red-400" />}
+                            <p className="text-[var(--color-app-text)]">{status.detail}</p>
+                        </div>
+                        {!status.canBind && status.fixes.map((fix) => (
+                            <div key={fix.kind} className="flex items-start justify-between gap-3 border-t border-[var(--color-app-border)]/40 pt-2">
+                                <div className="min-w-0">
+                                    <p className="font-medium text-[var(--color-app-text)]">{fix.title}</p>
+                                    <p className="text-[var(--color-app-muted)]">{fix.unavailable ?? fix.description}</p>
+                                    <code className="mt-1 block break-all text-[var(--color-app-muted)]">{fix.command}</code>
+                                </div>
+                                <Button
+                                    variant="secondary"
+                                    className="h-8 shrink-0 text-xs"
+                                    disabled={Boolean(fix.unavailable) || applying !== null}
+                                    onClick={() => { void handleApply(fix); }}
+                                >
+                                    {applying === fix.kind ? 'Applying…' : 'Apply'}
+                                </Button>
+                            </div>
+                        ))}
+                    </div>
+                )}
+            </div>
+        </Section>
+    );
+}
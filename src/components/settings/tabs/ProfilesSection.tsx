@@ -0,0 +1,249 @@
+import { Check, Edit2, Plus, Trash2, UserCircle } from 'lucide-react';
+import { useCallback, useEffect, useState } from 'react';
+import {
+    createUserProfileIpc,
+    DEFAULT_USER_PROFILE_ID,
+    deleteUserProfileIpc,
+    listUserProfilesIpc,
+    switchUserProfileIpc,
+    updateUserProfileIpc,
+    type UserProfile,
+} from '../../../features/userProfiles/infrastructure/userProfilesIpc';
+import { useAppStore } from '../../../store/useAppStore';
+import { Button } from '../../ui/Button';
+import { ConfirmModal } from '../../ui/ConfirmModal';
+import { Input } from '../../ui/Input';
+import { Modal } from '../../ui/Modal';
+import { Section } from '../common/Section';
+
+interface ProfileDraft {
+    /** Unset while creating. */
+    id?: string;
+    name: string;
+    color: string;
+    sshDir: string;
+    copySettings: boolean;
+}
+
+const errorMessage = (error: unknown) => (error instanceof Error ? error.message : String(error));
+
+/** Work/personal profiles: each has its own connections, vault, settings and known_hosts. */
+export function ProfilesSection() {
+    const showToast = useAppStore((state) => state.showToast);
+    const [profiles, setProfiles] = useState<UserProfile[]>([]);
+    const [activeId, setActiveId] = useState(DEFAULT_USER_PROFILE_ID);
+    const [draft, setDraft] = useState<ProfileDraft | null>(null);
+    const [deleting, setDeleting] = useState<UserProfile | null>(null);
+    const [removeFiles, setRemoveFiles] = useState(false);
+    const [pendingSwitch, setPendingSwitch] = useState<{ profile: UserProfile; reason: string } | null>(null);
+
+    const refresh = useCallback(async () => {
+        try {
+            const overview = await listUserProfilesIpc();
+            setProfiles(overview.profiles);
+            setActiveId(overview.active);
+        } catch (error) {
+            showToast('error', `Failed to load profiles: ${errorMessage(error)}`);
+        }
+    }, [showToast]);
+
+    useEffect(() => {
+        void refresh();
+    }, [refresh]);
+
+    const handleSwitch = async (profile: UserProfile, force = false) => {
+        try {
+            await switchUserProfileIpc(profile.id, force);
+            showToast('info', `Restarting into "${profile.name}"…`);
+        } catch (error) {
+            const message = errorMessage(error);
+            if (!force && message.includes('still open')) {
+                setPendingSwitch({ profile, reason: message });
+            } else {
+                showToast('error', `Failed to switch profile: ${message}`);
+            }
+        }
+    };
+
+    const handleSave = async () => {
+        if (!draft) return;
+        try {
+            if (draft.id) {
+                await updateUserProfileIpc({
+                    id: draft.id,
+                    name: draft.name,
+                    color: draft.color || null,
+                    sshDir: draft.sshDir || null,
+                });
+            } else {
+                await createUserProfileIpc({
+                    name: draft.name,
+                    color: draft.color || null,
+                    sshDir: draft.sshDir || null,
+                    copySettings: draft.copySettings,
+                });
+            }
+            setDraft(null);
+            await refresh();
+        } catch (error) {
+            showToast('error', errorMessage(error));
+        }
+    };
+
+    return (
+        <Section title="Profiles">
+            <div className="space-y-2 rounded-lg border border-[var(--color-app-border)] bg-[var(--color-app-surface)]/50 p-4">
+                <p className="text-xs text-[var(--color-app-muted)]">
+                    Each profile keeps its own connections, vault, snippets, settings and known_hosts. Switching restarts Zync.
+                </p>
+                {profiles.map((profile) => {
+                    const isActive = profile.id === activeId;
+                    return (
+                        <div
+                            key={profile.id}
+                            className="flex items-center justify-between gap-3 rounded-md border border-[var(--color-app-border)] bg-[var(--color-app-bg)]/40 px-3 py-2"
+                        >
+                            <div className="flex min-w-0 items-center gap-2">
+                                <UserCircle size={16} style={{ color: profile.color || 'var(--color-app-accent)' }} />
+                                <span className="truncate text-sm text-[var(--color-app-text)]">{profile.name}</span>
+                                {isActive && (
+                                    <span className="text-[10px] uppercase font-bold tracking-wider text-[var(--color-app-accent)]">Active</span>
+                                )}
+                                {profile.sshDir && (
+                                    <code className="truncate text-[10px] text-[var(--color-app-muted)]">{profile.sshDir}</code>
+                                )}
+                            </div>
+                            <div className="flex shrink-0 items-center gap-1">
+                                {!isActive && (
+                                    <Button variant="secondary" className="h-7 px-2 text-xs" onClick={() => { void handleSwitch(profile); }}>
+                                        <Check size={12} className="mr-1" /> Switch
+                                    </Button>
+                                )}
+                                <button
+                                    type="button"
+                                    title="Edit"
+                                    className="p-1 text-[var(--color-app-muted)] hover:text-[var(--color-app-accent)]"
+                                    onClick={() => setDraft({
+                                        id: profile.id,
+                                        name: profile.name,
+                                        color: profile.color ?? '',
+                                        sshDir: profile.sshDir ?? '',
+                                        copySettings: false,
+                                    })}
+                                >
+                                    <Edit2 size={14} />
+                                </button>
+                                {!isActive && profile.id !== DEFAULT_USER_PROFILE_ID && (
+                                    <button
+                                        type="button"
+                                        title="Delete"
+                                        className="p-1 text-[var(--color-app-muted)] hover:text-red-400"
+                                        onClick={() => { setRemoveFiles(false); setDeleting(profile); }}
+                                    >
+                                        <Trash2 size={14} />
+                                    </button>
+                                )}
+                            </div>
+                        </div>
+                    );
+                })}
+                <Button
+                    variant="ghost"
+                    className="h-8 text-xs"
+                    onClick={() => setDraft({ name: '', color: '', sshDir: '', copySettings: true })}
+                >
+                    <Plus size={14} className="mr-1" /> New Profile
+                </Button>
+            </div>
+
+            <Modal isOpen={!!draft} onClose={() => setDraft(null)} title={draft?.id ? 'Edit Profile' : 'New Profile'}>
+                {draft && (
+                    <div className="space-y-4">
+                        <Input
+                            label="Name"
+                            value={draft.name}
+                            placeholder="Work"
+                            onChange={(e) => setDraft({ ...draft, name: e.target.value })}
+                        />
+                        <Input
+                            label="Color"
+                            value={draft.color}
+                            placeholder="#3b82f6"
+                            onChange={(e) => setDraft({ ...draft, color: e.target.value })}
+                        />
+                        <Input
+                            label="SSH Directory"
+                            value={draft.sshDir}
+                            placeholder="~/.ssh"
+                            onChange={(e) => setDraft({ ...draft, sshDir: e.target.value })}
+                        />
+                        <p className="text-xs text-[var(--color-app-muted)]">
+                            Keys and ssh_config for quick connect, config import and key rotation. Changes apply on the next launch of this profile.
+                        </p>
+                        {!draft.id && (
+                            <label className="flex items-center gap-2 text-sm text-[var(--color-app-muted)] cursor-pointer hover:text-[var(--color-app-text)]">
+                                <input
+                                    type="checkbox"
+                                    checked={draft.copySettings}
+                                    onChange={(e) => setDraft({ ...draft, copySettings: e.target.checked })}
+                                    className="accent-[var(--color-app-accent)]"
+                                />
+                                Start from the current settings
+                            </label>
+                        )}
+                        <div className="flex justify-end gap-2 pt-2">
+                            <Button variant="ghost" onClick={() => setDraft(null)}>
+                                Cancel
+                            </Button>
+                            <Button onClick={() => { void handleSave(); }}>Save</Button>
+                        </div>
+                    </div>
+                )}
+            </Modal>
+
+            <ConfirmModal
+                isOpen={!!deleting}
+                onClose={() => setDeleting(null)}
+                onConfirm={async () => {
+                    if (!deleting) return;
+                    try {
+                        await deleteUserProfileIpc(deleting.id, removeFiles);
+                        await refresh();
+                    } catch (error) {
+                        showToast('error', `Failed to delete profile: ${errorMessage(error)}`);
+                    }
+                    setDeleting(null);
+                }}
+                title="Delete Profile"
+                message={
+                    <>
+                        Delete profile "{deleting?.name}"?
+                        <label className="mt-3 flex items-center gap-2 cursor-pointer">
+                            <input
+                                type="checkbox"
+                                checked={removeFiles}
+                                onChange={(e) => setRemoveFiles(e.target.checked)}
+                                className="accent-red-400"
+                            />
+                            Also delete its connections, vault, settings and known_hosts
+                        </label>
+                    </>
+                }
+                confirmLabel="Delete"
+                variant="danger"
+            />
+
+            <ConfirmModal
+                isOpen={!!pendingSwitch}
+                onClose={() => setPendingSwitch(null)}
+                onConfirm={() => {
+                    if (pendingSwitch) void handleSwitch(pendingSwitch.profile, true);
+                    setPendingSwitch(null);
+                }}
+                title="Switch Profile"
+                message={`${pendingSwitch?.reason}. Disconnect and switch to "${pendingSwitch?.profile.name}"?`}
+                confirmLabel="Switch"
+            />
+        </Section>
+    );
+}
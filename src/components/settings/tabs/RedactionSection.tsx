@@ -0,0 +1,155 @@
+import { Plus, ShieldOff, Trash2 } from 'lucide-react';
+import { useState } from 'react';
+import {
+    DEFAULT_REDACTION_SETTINGS,
+    previewRedactionIpc,
+    type RedactionPreview,
+    type RedactionRule,
+    type RedactionSettings,
+} from '../../../features/redaction/infrastructure/redactionIpc';
+import type { AppSettings } from '../../../store/settingsSlice';
+import { Button } from '../../ui/Button';
+import { Input } from '../../ui/Input';
+import { Section } from '../common/Section';
+
+interface RedactionSectionProps {
+    settings: AppSettings;
+    onUpdateSettings: (updates: Partial<AppSettings>) => Promise<void>;
+}
+
+/** Rules that mask secrets before macros, expect transcripts and audit entries are stored. */
+export function RedactionSection({ settings, onUpdateSettings }: RedactionSectionProps) {
+    const saved = settings.redaction ?? DEFAULT_REDACTION_SETTINGS;
+    const [draft, setDraft] = useState<RedactionSettings>(saved);
+    const [sample, setSample] = useState('');
+    const [preview, setPreview] = useState<RedactionPreview | null>(null);
+    const isDirty = JSON.stringify(draft) !== JSON.stringify(saved);
+
+    const updateRule = (index: number, patch: Partial<RedactionRule>) => {
+        setDraft({
+            ...draft,
+            rules: draft.rules.map((rule, i) => (i === index ? { ...rule, ...patch } : rule)),
+        });
+    };
+
+    const handleTest = async () => {
+        try {
+            setPreview(await previewRedactionIpc(sample, draft));
+        } catch (error) {
+            setPreview({ text: '', matched: [], errors: [error instanceof Error ? error.message : String(error)] });
+        }
+    };
+
+    return (
+        <Section title="Redaction">
+            <div className="space-y-3 rounded-lg border border-[var(--color-app-border)] bg-[var(--color-app-surface)]/50 p-4">
+                <div className="flex items-start justify-between gap-3">
+                    <div className="flex items-start gap-3">
+                        <div className="p-2 bg-[var(--color-app-bg)] rounded-md border border-[var(--color-app-border)] text-[var(--color-app-accent)]">
+                            <ShieldOff size={20} />
+                        </div>
+                        <div>
+                            <h4 className="text-sm font-medium text-[var(--color-app-text)]">Mask secrets before saving</h4>
+                            <p className="text-xs text-[var(--color-app-muted)] mt-1 max-w-md">
+                                Applied to recorded macros, expect transcripts and the audit log. Input typed at password prompts is never recorded.
+                            </p>
+                        </div>
+                    </div>
+                    <label className="flex shrink-0 items-center gap-2 text-xs text-[var(--color-app-muted)] cursor-pointer">
+                        <input
+                            type="checkbox"
+                            checked={draft.builtIn}
+                            onChange={(e) => setDraft({ ...draft, builtIn: e.target.checked })}
+                            className="accent-[var(--color-app-accent)]"
+                        />
+                        Built-in rules
+                    </label>
+                </div>
+
+                {draft.rules.map((rule, index) => (
+                    <div key={index} className="grid grid-cols-[auto_1fr_2fr_1fr_auto] items-center gap-2">
+                        <input
+                            type="checkbox"
+                            title="Enabled"
+                            checked={rule.enabled}
+                            onChange={(e) => updateRule(index, { enabled: e.target.checked })}
+                            className="accent-[var(--color-app-accent)]"
+                        />
+                        <Input
+                            value={rule.name}
+                            placeholder="Name"
+                            onChange={(e) => updateRule(index, { name: e.target.value })}
+                        />
+                        <Input
+                            value={rule.pattern}
+                            placeholder="Regular expression"
+                            className="font-mono"
+                            onChange={(e) => updateRule(index, { pattern: e.target.value })}
+                        />
+                        <Input
+                            value={rule.replacement ?? ''}
+                            placeholder="[REDACTED]"
+                            onChange={(e) => updateRule(index, { replacement: e.target.value || null })}
+                        />
+                        <button
+                            type="button"
+                            title="Remove"
+                            className="p-1 text-[var(--color-app-muted)] hover:text-red-400"
+                            onClick={() => setDraft({ ...draft, rules: draft.rules.filter((_, i) => i !== index) })}
+                        >
+                            <Trash2 size={14} />
+                        </button>
+                    </div>
+                ))}
+
+                <div className="flex items-center justify-between">
+                    <Button
+                        variant="ghost"
+                        className="h-8 text-xs"
+                        onClick={() => setDraft({
+                            ...draft,
+                            rules: [...draft.rules, { name: `Rule ${draft.rules.length + 1}`, pattern: '', replacement: null, enabled: true }],
+                        })}
+                    >
+                        <Plus size={14} className="mr-1" /> Add Rule
+                    </Button>
+                    {isDirty && (
+                        <div className="flex gap-2">
+                            <Button variant="ghost" className="h-8 text-xs" onClick={() => setDraft(saved)}>
+                                Discard
+                            </Button>
+                            <Button className="h-8 text-xs" onClick={() => { void onUpdateSettings({ redaction: draft }); }}>
+                                Save Rules
+                            </Button>
+                        </div>
+                    )}
+                </div>
+
+                <div className="space-y-2 border-t border-[var(--color-app-border)]/40 pt-3">
+                    <div className="flex gap-2">
+                        <Input
+                            value={sample}
+                            placeholder="Try it: export API_TOKEN=abc123"
+                            className="font-mono"
+                            onChange={(e) => setSample(e.target.value)}
+                        />
+                        <Button variant="secondary" className="h-10 text-xs" disabled={!sample} onClick={() => { void handleTest(); }}>
+                            Test
+                        </Button>
+                    </div>
+                    {preview && (
+                        <div className="rounded-md border border-[var(--color-app-border)] bg-[var(--color-app-bg)]/40 px-3 py-2 text-xs">
+                            <code className="block whitespace-pre-wrap break-all text-[var(--color-app-text)]">{preview.text}</code>
+                            <p className="mt-1 text-[var(--color-app-muted)]">
+                                {preview.matched.length ? `Matched: ${preview.matched.join(', ')}` : 'No rule matched'}
+                            </p>
+                            {preview.errors.map((error) => (
+                                <p key={error} className="mt-1 text-red-400">{error}</p>
+                            ))}
+                        </div>
+                    )}
+                </div>
+            </div>
+        </Section>
+    );
+}
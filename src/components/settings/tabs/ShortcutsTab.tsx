@@ -193,6 +193,11 @@ export function ShortcutsTab({ settings, updateKeybindings }: ShortcutsTabProps)
                         binding={keybindings.termFind}
                         onChange={(val) => handleKeybindingChange({ termFind: val })}
                     />
+                    <KeybindingRow
+                        label="Record Macro"
+                        binding={keybindings.termRecordMacro}
+                        onChange={(val) => handleKeybindingChange({ termRecordMacro: val })}
+                    />
                     <KeybindingRow
                         label="Close Terminal Tab"
                         binding={keybindings.closeTerminalTab}
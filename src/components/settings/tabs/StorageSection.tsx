@@ -0,0 +1,238 @@
+import { AlertTriangle, CheckCircle2, GitBranch, History, Wrench } from 'lucide-react';
+import { useState } from 'react';
+import {
+    repairDataFileIpc,
+    validateDataFilesIpc,
+    type DataFile,
+    type DataFileReport,
+} from '../../../features/storage/infrastructure/dataFilesIpc';
+import {
+    applySecretSplitIpc,
+    checkStorageIntegrityIpc,
+    type IntegrityReport,
+} from '../../../features/storage/infrastructure/secretSplitIpc';
+import type { AppSettings } from '../../../store/settingsSlice';
+import { useAppStore } from '../../../store/useAppStore';
+import { Button } from '../../ui/Button';
+import { Select, type SelectOption } from '../../ui/Select';
+import { Section } from '../common/Section';
+
+interface StorageSectionProps {
+    settings: AppSettings;
+    onUpdateSettings: (updates: Partial<AppSettings>) => Promise<void>;
+}
+
+const errorMessage = (error: unknown) => (error instanceof Error ? error.message : String(error));
+
+const newerDataFilesOptions: SelectOption[] = [
+    { value: 'refuse', label: 'Refuse to save', description: 'Leave the file alone and show an error' },
+    { value: 'keepCopy', label: 'Keep a copy, then save', description: 'Saved as <name>.format-N.json first' },
+];
+
+/** Keeps the data files secret-free (for git), guards files from newer versions, checks references and validates hand edits. */
+export function StorageSection({ settings, onUpdateSettings }: StorageSectionProps) {
+    const showToast = useAppStore((state) => state.showToast);
+    const splitSecrets = settings.storage?.splitSecrets ?? false;
+    const [busy, setBusy] = useState(false);
+    const [report, setReport] = useState<IntegrityReport | null>(null);
+    const [fileReports, setFileReports] = useState<DataFileReport[] | null>(null);
+
+    const handleToggle = async (enabled: boolean) => {
+        setBusy(true);
+        try {
+            await onUpdateSettings({ storage: { ...settings.storage, splitSecrets: enabled } });
+            const inKeychain = await applySecretSplitIpc();
+            showToast(
+                'success',
+                enabled
+                    ? `${inKeychain} password${inKeychain === 1 ? '' : 's'} now kept in the keychain`
+                    : 'Passwords moved back into connections.json',
+            );
+        } catch (error) {
+            showToast('error', `Failed to update secret storage: ${errorMessage(error)}`);
+        } finally {
+            setBusy(false);
+        }
+    };
+
+    const handleCheck = async () => {
+        setBusy(true);
+        try {
+            setReport(await checkStorageIntegrityIpc());
+        } catch (error) {
+            showToast('error', `Integrity check failed: ${errorMessage(error)}`);
+        } finally {
+            setBusy(false);
+        }
+    };
+
+    const handleValidate = async () => {
+        setBusy(true);
+        try {
+            setFileReports(await validateDataFilesIpc());
+        } catch (error) {
+            showToast('error', `Validation failed: ${errorMessage(error)}`);
+        } finally {
+            setBusy(false);
+        }
+    };
+
+    const handleRepair = async (file: DataFile) => {
+        setBusy(true);
+        try {
+            const repaired = await repairDataFileIpc(file);
+            setFileReports((current) => current?.map((entry) => (entry.file === file ? repaired : entry)) ?? null);
+            showToast('success', `${repaired.fileName} repaired; the original was kept as a .pre-repair.json copy`);
+        } catch (error) {
+            showToast('error', `Repair failed: ${errorMessage(error)}`);
+        } finally {
+            setBusy(false);
+        }
+    };
+
+    const problemColor = (severity: DataFileReport['problems'][number]['severity']) =>
+        severity === 'error' ? 'text-red-400' : severity === 'warning' ? 'text-yellow-400' : 'text-[var(--color-app-accent)]';
+
+    return (
+        <Section title="Storage">
+            <div className="space-y-3 rounded-lg border border-[var(--color-app-border)] bg-[var(--color-app-surface)]/50 p-4">
+                <div className="flex items-start justify-between gap-3">
+                    <div className="flex items-start gap-3">
+                        <div className="p-2 bg-[var(--color-app-bg)] rounded-md border border-[var(--color-app-border)] text-[var(--color-app-accent)]">
+                            <GitBranch size={20} />
+                        </div>
+                        <div>
+                            <h4 className="text-sm font-medium text-[var(--color-app-text)]">Keep secrets out of data files</h4>
+                            <p className="text-xs text-[var(--color-app-muted)] mt-1 max-w-md">
+                                Connection passwords go to the OS keychain, so connections, tunnels and snippets can be committed to git.
+                                Vault credentials are always stored as references.
+                            </p>
+                        </div>
+                    </div>
+                    <label className="flex shrink-0 items-center gap-2 text-xs text-[var(--color-app-muted)] cursor-pointer">
+                        <input
+                            type="checkbox"
+                            checked={splitSecrets}
+                            disabled={busy}
+                            onChange={(e) => { void handleToggle(e.target.checked); }}
+                            className="accent-[var(--color-app-accent)]"
+                        />
+                        Enabled
+                    </label>
+                </div>
+
+                <div className="flex items-start justify-between gap-3 border-t border-[var(--color-app-border)]/40 pt-3">
+                    <div className="flex items-start gap-3">
+                        <div className="p-2 bg-[var(--color-app-bg)] rounded-md border border-[var(--color-app-border)] text-[var(--color-app-accent)]">
+                            <History size={20} />
+                        </div>
+                        <div>
+                            <h4 className="text-sm font-medium text-[var(--color-app-text)]">Newer data files</h4>
+                            <p className="text-xs text-[var(--color-app-muted)] mt-1 max-w-md">
+                                After running a beta, connections and tunnels may be in a format this version doesn't fully understand.
+                                They still load, but saving over them would drop what the newer version added.
+                            </p>
+                        </div>
+                    </div>
+                    <div className="w-56 shrink-0">
+                        <Select
+                            value={settings.storage?.newerDataFiles ?? 'refuse'}
+                            onChange={(value) => onUpdateSettings({
+                                storage: {
+                                    splitSecrets,
+                                    ...settings.storage,
+                                    newerDataFiles: value as 'refuse' | 'keepCopy',
+                                },
+                            })}
+                            options={newerDataFilesOptions}
+                            showSearch={false}
+                        />
+                    </div>
+                </div>
+
+                <div className="flex items-center justify-between border-t border-[var(--color-app-border)]/40 pt-3">
+                    <p className="text-xs text-[var(--color-app-muted)]">
+                        Find jump hosts, tunnels, snippets and credentials that point at something missing.
+                    </p>
+                    <Button variant="secondary" className="h-8 text-xs" disabled={busy} onClick={() => { void handleCheck(); }}>
+                        Check Integrity
+                    </Button>
+                </div>
+
+                {report && (
+                    <div className="space-y-1 rounded-md border border-[var(--color-app-border)] bg-[var(--color-app-bg)]/40 px-3 py-2 text-xs">
+                        {report.issues.length === 0 ? (
+                            <p className="flex items-center gap-2 text-[var(--color-app-text)]">
+                                <CheckCircle2 size={14} className="text-green-400" /> No broken references found.
+                            </p>
+                        ) : (
+                            report.issues.map((issue) => (
+                                <p key={`${issue.kind}:${issue.file}:${issue.subjectId}`} className="flex items-start gap-2">
+                                    <AlertTriangle
+                                        size={14}
+                                        className={`mt-0.5 shrink-0 ${issue.severity === 'error' ? 'text-red-400' : 'text-yellow-400'}`}
+                                    />
+                                    <span className="text-[var(--color-app-text)]">
+                                        {issue.message}
+                                        <span className="text-[var(--color-app-muted)]"> ({issue.file})</span>
+                                    </span>
+                                </p>
+                            ))
+                        )}
+                        {!report.vaultChecked && (
+                            <p className="text-[var(--color-app-muted)]">Unlock the vault to check vault credential references too.</p>
+                        )}
+                    </div>
+                )}
+
+                <div className="flex items-center justify-between border-t border-[var(--color-app-border)]/40 pt-3">
+                    <p className="text-xs text-[var(--color-app-muted)]">
+                        Check hand-edited data files for mistakes; common ones can be repaired in place.
+                    </p>
+                    <Button variant="secondary" className="h-8 text-xs" disabled={busy} onClick={() => { void handleValidate(); }}>
+                        Validate Data Files
+                    </Button>
+                </div>
+
+                {fileReports && (
+                    <div className="space-y-2 rounded-md border border-[var(--color-app-border)] bg-[var(--color-app-bg)]/40 px-3 py-2 text-xs">
+                        {fileReports.filter((entry) => entry.exists).map((entry) => (
+                            <div key={entry.file} className="space-y-1">
+                                <div className="flex items-center justify-between gap-2">
+                                    <p className="flex items-center gap-2 font-medium text-[var(--color-app-text)]">
+                                        {entry.problems.length === 0 && <CheckCircle2 size={14} className="text-green-400" />}
+                                        {entry.fileName}
+                                        {entry.problems.length === 0 && <span className="font-normal text-[var(--color-app-muted)]">OK</span>}
+                                    </p>
+                                    {entry.canRepair && (
+                                        <Button
+                                            variant="secondary"
+                                            className="h-7 text-xs"
+                                            disabled={busy}
+                                            onClick={() => { void handleRepair(entry.file); }}
+                                        >
+                                            <Wrench size={12} className="mr-1" /> Repair
+                                        </Button>
+                                    )}
+                                </div>
+                                {entry.problems.map((problem, index) => (
+                                    <p key={`${problem.pointer}:${index}`} className="flex items-start gap-2 pl-1">
+                                        <AlertTriangle size={14} className={`mt-0.5 shrink-0 ${problemColor(problem.severity)}`} />
+                                        <span className="text-[var(--color-app-text)]">
+                                            <span className="font-mono text-[var(--color-app-muted)]">
+                                                {problem.pointer || '/'}
+                                                {problem.line !== null && `:${problem.line}`}
+                                            </span>{' '}
+                                            {problem.message}
+                                            {problem.severity === 'repaired' && !entry.canRepair && ' (after the errors are fixed)'}
+                                        </span>
+                                    </p>
+                                ))}
+                            </div>
+                        ))}
+                    </div>
+                )}
+            </div>
+        </Section>
+    );
+}
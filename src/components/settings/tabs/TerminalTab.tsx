@@ -123,6 +123,32 @@ export function TerminalTab({
 
             <div className="h-px bg-[var(--color-app-border)]/20 my-2" />
 
+            <Section title="Dropped sessions">
+                <div className="rounded-xl border border-[var(--color-app-border)]/60 bg-[var(--color-app-surface)]/40 p-3 space-y-3">
+                    <Toggle
+                        label="Reconnect automatically"
+                        description="When a session drops on its own, retry with growing delays for a few minutes, then reopen its terminals and restart the tunnels that were running. Hosts that need a vault unlock or fail authentication are left for you to reconnect."
+                        checked={settings.terminal.autoReconnect ?? true}
+                        onChange={(value) => { void updateTerminalSettings({ autoReconnect: value }); }}
+                    />
+                </div>
+            </Section>
+
+            <div className="h-px bg-[var(--color-app-border)]/20 my-2" />
+
+            <Section title="Secrets in output">
+                <div className="rounded-xl border border-[var(--color-app-border)]/60 bg-[var(--color-app-surface)]/40 p-3 space-y-3">
+                    <Toggle
+                        label="Warn about secrets in output"
+                        description="Flag private keys, AWS access keys and JWTs as they scroll by, with an offer to mask them in clipboard history and output bookmarks."
+                        checked={settings.terminal.secretOutputWarnings ?? true}
+                        onChange={(value) => { void updateTerminalSettings({ secretOutputWarnings: value }); }}
+                    />
+                </div>
+            </Section>
+
+            <div className="h-px bg-[var(--color-app-border)]/20 my-2" />
+
             <Section title="Ghost suggestions">
                 <div className="space-y-1">
                     <Toggle
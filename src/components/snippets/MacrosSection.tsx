@@ -0,0 +1,160 @@
+import { Circle, Edit2, Trash2 } from 'lucide-react';
+import { useState } from 'react';
+import { useAppStore } from '../../store/useAppStore';
+import { GLOBAL_SNIPPETS_CONNECTION_ID } from '../../features/connections/application/tabService';
+import type { TerminalMacro } from '../../features/macros/infrastructure/macrosIpc';
+import { KeybindingRow } from '../settings/common/KeybindingRow';
+import { Button } from '../ui/Button';
+import { ConfirmModal } from '../ui/ConfirmModal';
+import { Input } from '../ui/Input';
+import { Modal } from '../ui/Modal';
+
+/** Recorded macros listed under the snippets: run, rename, bind a shortcut, delete. */
+export function MacrosSection({
+  connectionId,
+  activeConnectionId,
+}: {
+  connectionId?: string;
+  activeConnectionId: string | null;
+}) {
+  const macros = useAppStore(state => state.macros);
+  const saveMacro = useAppStore(state => state.saveMacro);
+  const deleteMacro = useAppStore(state => state.deleteMacro);
+  const lastRealConnectionId = useAppStore(state => state.lastRealConnectionId);
+  const showToast = useAppStore(state => state.showToast);
+  const recordShortcut = useAppStore(state => state.settings.keybindings?.termRecordMacro || 'Mod+Shift+M');
+
+  const [editing, setEditing] = useState<TerminalMacro | null>(null);
+  const [deleting, setDeleting] = useState<TerminalMacro | null>(null);
+
+  const visible = macros.filter(m => {
+    if (connectionId === GLOBAL_SNIPPETS_CONNECTION_ID) return !m.connectionId;
+    return !m.connectionId || m.connectionId === activeConnectionId;
+  });
+
+  const handleRun = (macro: TerminalMacro) => {
+    const targetConnectionId = connectionId === GLOBAL_SNIPPETS_CONNECTION_ID
+      ? lastRealConnectionId
+      : activeConnectionId;
+    if (!targetConnectionId) {
+      showToast('error', 'No active connection to run macro');
+      return;
+    }
+    window.dispatchEvent(new CustomEvent('ssh-ui:run-macro', {
+      detail: { connectionId: targetConnectionId, macroId: macro.id }
+    }));
+  };
+
+  const handleSave = async () => {
+    if (!editing) return;
+    if (await saveMacro(editing)) {
+      showToast('success', 'Macro saved');
+      setEditing(null);
+    }
+  };
+
+  return (
+    <div>
+      <h3 className="text-xs font-bold text-app-muted uppercase tracking-wider mb-2 ml-1">Macros</h3>
+      {visible.length === 0 ? (
+        <p className="text-xs text-app-muted ml-1">
+          Press {recordShortcut} in a terminal to start recording, and again to stop.
+        </p>
+      ) : (
+        <div className="grid gap-3">
+          {visible.map((macro) => (
+            <div
+              key={macro.id}
+              className="bg-app-panel border border-app-border rounded-lg p-3 hover:border-app-accent transition-colors cursor-pointer group"
+              onClick={() => handleRun(macro)}
+            >
+              <div className="flex justify-between items-start">
+                <div className="flex items-center gap-2">
+                  <Circle size={10} className="text-app-accent" />
+                  <h4 className="font-medium text-app-text text-sm">{macro.name}</h4>
+                  {macro.shortcut && (
+                    <span className="text-[10px] font-mono bg-app-surface px-1.5 py-0.5 rounded">{macro.shortcut}</span>
+                  )}
+                  {macro.connectionId && (
+                    <span className="text-[10px] bg-app-accent/20 text-app-accent px-1.5 py-0.5 rounded-full">Host Only</span>
+                  )}
+                </div>
+                <div className="flex gap-1">
+                  <button
+                    onClick={(e) => { e.stopPropagation(); setEditing(macro); }}
+                    className="text-app-muted hover:text-app-accent opacity-0 group-hover:opacity-100 transition-opacity p-1"
+                    title="Edit"
+                  >
+                    <Edit2 size={14} />
+                  </button>
+                  <button
+                    type="button"
+                    onClick={(e) => { e.stopPropagation(); setDeleting(macro); }}
+                    className="text-app-muted hover:text-red-400 opacity-0 group-hover:opacity-100 transition-opacity p-1"
+                    title="Delete"
+                  >
+                    <Trash2 size={14} />
+                  </button>
+                </div>
+              </div>
+              <div className="text-[10px] text-app-muted/80 mt-1">
+                {macro.steps.length} step{macro.steps.length === 1 ? '' : 's'}
+                {macro.steps.some(step => step.kind === 'waitForPrompt') && ' · waits for prompts'}
+              </div>
+            </div>
+          ))}
+        </div>
+      )}
+
+      <ConfirmModal
+        isOpen={!!deleting}
+        onClose={() => setDeleting(null)}
+        onConfirm={async () => {
+          if (deleting) await deleteMacro(deleting.id);
+          setDeleting(null);
+        }}
+        title="Delete Macro"
+        message={`Delete macro "${deleting?.name}"? This action cannot be undone.`}
+        confirmLabel="Delete"
+        variant="danger"
+      />
+
+      <Modal isOpen={!!editing} onClose={() => setEditing(null)} title="Edit Macro">
+        {editing && (
+          <div className="space-y-4">
+            <Input
+              label="Name"
+              value={editing.name}
+              onChange={(e) => setEditing({ ...editing, name: e.target.value })}
+            />
+            <KeybindingRow
+              label="Shortcut"
+              binding={editing.shortcut ?? ''}
+              onChange={(val) => setEditing({ ...editing, shortcut: val || null })}
+            />
+            {activeConnectionId && connectionId !== GLOBAL_SNIPPETS_CONNECTION_ID && (
+              <label className="flex items-center gap-2 text-sm text-app-muted cursor-pointer hover:text-app-text">
+                <input
+                  type="checkbox"
+                  checked={editing.connectionId === activeConnectionId}
+                  onChange={(e) => setEditing({
+                    ...editing,
+                    connectionId: e.target.checked ? activeConnectionId : null,
+                  })}
+                  className="accent-app-accent"
+                />
+                Only on this host
+              </label>
+            )}
+            <div className="flex justify-end gap-2 pt-2">
+              <Button variant="ghost" onClick={() => setEditing(null)}>
+                Cancel
+              </Button>
+              <Button onClick={handleSave}>Save</Button>
+            </div>
+          </div>
+        )}
+      </Modal>
+    </div>
+  );
+}
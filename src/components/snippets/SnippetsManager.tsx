@@ -6,6 +6,7 @@ import { Button } from '../ui/Button';
 import { ConfirmModal } from '../ui/ConfirmModal';
 import { Input } from '../ui/Input';
 import { Modal } from '../ui/Modal';
+import { MacrosSection } from './MacrosSection';
 import type { Snippet } from '../../store/useAppStore';
 
 // Snippet interface is now imported from store
@@ -213,6 +214,8 @@ export function SnippetsManager({ connectionId }: { connectionId?: string }) {
             </div>
           </div>
         ))}
+
+        <MacrosSection connectionId={connectionId} activeConnectionId={activeConnectionId} />
       </div>
 
       <ConfirmModal
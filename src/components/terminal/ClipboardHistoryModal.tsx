@@ -0,0 +1,187 @@
+import { useCallback, useEffect, useState } from 'react';
+import { ClipboardPaste, Copy, EyeOff, Pin, PinOff, Trash2 } from 'lucide-react';
+import {
+  clearClipboardHistoryIpc,
+  deleteClipboardHistoryIpc,
+  getClipboardHistoryStatusIpc,
+  listClipboardHistoryIpc,
+  pinClipboardHistoryIpc,
+  setClipboardIncognitoIpc,
+  type ClipboardHistoryEntry,
+  type ClipboardHistoryStatus,
+} from '../../features/clipboard/infrastructure/clipboardHistoryIpc.js';
+import { useAppStore } from '../../store/useAppStore';
+import { Button } from '../ui/Button';
+import { Input } from '../ui/Input';
+import { Modal } from '../ui/Modal';
+
+export interface ClipboardHistoryModalProps {
+  isOpen: boolean;
+  onClose: () => void;
+  onPaste: (text: string) => void;
+}
+
+const errorMessage = (error: unknown) => (error instanceof Error ? error.message : String(error));
+
+/** Searchable history of terminal copies with pinning and an incognito switch. */
+export function ClipboardHistoryModal({ isOpen, onClose, onPaste }: ClipboardHistoryModalProps) {
+  const showToast = useAppStore((state) => state.showToast);
+  const [query, setQuery] = useState('');
+  const [entries, setEntries] = useState<ClipboardHistoryEntry[]>([]);
+  const [status, setStatus] = useState<ClipboardHistoryStatus | null>(null);
+
+  const refresh = useCallback(async () => {
+    try {
+      const [list, nextStatus] = await Promise.all([
+        listClipboardHistoryIpc(query),
+        getClipboardHistoryStatusIpc(),
+      ]);
+      setEntries(list);
+      setStatus(nextStatus);
+    } catch (error) {
+      showToast('error', `Failed to load clipboard history: ${errorMessage(error)}`);
+    }
+  }, [query, showToast]);
+
+  useEffect(() => {
+    if (isOpen) {
+      void refresh();
+    }
+  }, [isOpen, refresh]);
+
+  const run = async (action: () => Promise<unknown>) => {
+    try {
+      await action();
+      await refresh();
+    } catch (error) {
+      showToast('error', errorMessage(error));
+    }
+  };
+
+  const copyEntry = async (entry: ClipboardHistoryEntry) => {
+    try {
+      const { writeText } = await import('@tauri-apps/plugin-clipboard-manager');
+      await writeText(entry.text);
+      showToast('success', 'Copied to clipboard');
+    } catch (error) {
+      showToast('error', `Copy failed: ${errorMessage(error)}`);
+    }
+  };
+
+  return (
+    <Modal isOpen={isOpen} onClose={onClose} title="Clipboard History" width="max-w-2xl">
+      <div className="space-y-3">
+        <div className="flex items-center gap-2">
+          <Input
+            value={query}
+            placeholder="Search copied text"
+            autoFocus
+            onChange={(e) => setQuery(e.target.value)}
+          />
+          <label className="flex shrink-0 items-center gap-2 text-xs text-[var(--color-app-muted)] cursor-pointer">
+            <input
+              type="checkbox"
+              checked={status?.incognito ?? false}
+              onChange={(e) => {
+                const enabled = e.target.checked;
+                void run(() => setClipboardIncognitoIpc(enabled));
+              }}
+              className="accent-[var(--color-app-accent)]"
+            />
+            <EyeOff size={14} /> Incognito
+          </label>
+        </div>
+
+        {status && !status.enabled && (
+          <p className="text-xs text-[var(--color-app-muted)]">
+            Clipboard history is turned off in Settings → General.
+          </p>
+        )}
+        {status?.incognito && (
+          <p className="text-xs text-[var(--color-app-muted)]">
+            Copies aren't recorded until incognito is turned off or Zync restarts.
+          </p>
+        )}
+
+        <div className="max-h-96 space-y-2 overflow-y-auto">
+          {entries.length === 0 && (
+            <p className="py-6 text-center text-sm text-[var(--color-app-muted)]">
+              {query ? 'No copies match.' : 'Text copied from terminals shows up here.'}
+            </p>
+          )}
+          {entries.map((entry) => (
+            <div
+              key={entry.id}
+              className="group rounded-md border border-[var(--color-app-border)] bg-[var(--color-app-bg)]/40 px-3 py-2"
+            >
+              <pre className="max-h-24 overflow-hidden whitespace-pre-wrap break-all font-mono text-xs text-[var(--color-app-text)]">
+                {entry.text}
+              </pre>
+              <div className="mt-1 flex items-center justify-between gap-2 text-[10px] text-[var(--color-app-muted)]">
+                <span className="truncate">
+                  {new Date(entry.copiedAt).toLocaleString()}
+                  {entry.connectionId ? ` · ${entry.connectionId}` : ' · local'}
+                  {entry.copyCount > 1 ? ` · copied ${entry.copyCount}×` : ''}
+                  {entry.truncated ? ' · truncated' : ''}
+                </span>
+                <div className="flex shrink-0 items-center gap-1">
+                  <button
+                    type="button"
+                    title={entry.pinned ? 'Unpin' : 'Pin'}
+                    className={entry.pinned
+                      ? 'p-1 text-[var(--color-app-accent)]'
+                      : 'p-1 hover:text-[var(--color-app-accent)]'}
+                    onClick={() => { void run(() => pinClipboardHistoryIpc(entry.id, !entry.pinned)); }}
+                  >
+                    {entry.pinned ? <PinOff size={14} /> : <Pin size={14} />}
+                  </button>
+                  <button
+                    type="button"
+                    title="Copy"
+                    className="p-1 hover:text-[var(--color-app-accent)]"
+                    onClick={() => { void copyEntry(entry); }}
+                  >
+                    <Copy size={14} />
+                  </button>
+                  <button
+                    type="button"
+                    title="Paste into terminal"
+                    className="p-1 hover:text-[var(--color-app-accent)]"
+                    onClick={() => {
+                      onPaste(entry.text);
+                      onClose();
+                    }}
+                  >
+                    <ClipboardPaste size={14} />
+                  </button>
+                  <button
+                    type="button"
+                    title="Delete"
+                    className="p-1 hover:text-red-400"
+                    onClick={() => { void run(() => deleteClipboardHistoryIpc(entry.id)); }}
+                  >
+                    <Trash2 size={14} />
+                  </button>
+                </div>
+              </div>
+            </div>
+          ))}
+        </div>
+
+        <div className="flex justify-between pt-1">
+          <Button
+            variant="ghost"
+            className="h-8 text-xs"
+            disabled={entries.length === 0}
+            onClick={() => { void run(() => clearClipboardHistoryIpc(true)); }}
+          >
+            Clear Unpinned
+          </Button>
+          <Button variant="secondary" className="h-8 text-xs" onClick={onClose}>
+            Close
+          </Button>
+        </div>
+      </div>
+    </Modal>
+  );
+}
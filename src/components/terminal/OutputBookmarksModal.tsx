@@ -0,0 +1,162 @@
+import { useCallback, useEffect, useState } from 'react';
+import { Copy, CornerDownRight, Eye, EyeOff, Trash2 } from 'lucide-react';
+import {
+  deleteOutputBookmarkIpc,
+  listOutputBookmarksIpc,
+  type OutputBookmark,
+} from '../../features/outputBookmarks/infrastructure/outputBookmarksIpc.js';
+import {
+  forgetOutputBookmark,
+  isOutputBookmarkLive,
+  jumpToOutputBookmark,
+} from '../../lib/terminal';
+import { writeTerminalClipboardText } from '../../lib/terminal/terminalClipboard.js';
+import { useAppStore } from '../../store/useAppStore';
+import { Button } from '../ui/Button';
+import { Input } from '../ui/Input';
+import { Modal } from '../ui/Modal';
+
+export interface OutputBookmarksModalProps {
+  isOpen: boolean;
+  onClose: () => void;
+  /** Workspace connection of the terminal the list was opened from. */
+  connectionId: string;
+}
+
+const errorMessage = (error: unknown) => (error instanceof Error ? error.message : String(error));
+
+/** Bookmarked output, searchable; jumps into the scrollback while it is still there. */
+export function OutputBookmarksModal({ isOpen, onClose, connectionId }: OutputBookmarksModalProps) {
+  const showToast = useAppStore((state) => state.showToast);
+  const connections = useAppStore((state) => state.connections);
+  const setActiveTerminal = useAppStore((state) => state.setActiveTerminal);
+  const [query, setQuery] = useState('');
+  const [allHosts, setAllHosts] = useState(false);
+  const [bookmarks, setBookmarks] = useState<OutputBookmark[]>([]);
+  const [expandedId, setExpandedId] = useState<string | null>(null);
+
+  const refresh = useCallback(async () => {
+    try {
+      setBookmarks(await listOutputBookmarksIpc(allHosts ? null : connectionId, query));
+    } catch (error) {
+      showToast('error', `Failed to load bookmarks: ${errorMessage(error)}`);
+    }
+  }, [allHosts, connectionId, query, showToast]);
+
+  useEffect(() => {
+    if (isOpen) void refresh();
+  }, [isOpen, refresh]);
+
+  const hostName = (id: string) => {
+    const connection = connections.find((c) => c.id === id);
+    return connection ? connection.name || connection.host : id === 'local' ? 'Local' : id;
+  };
+
+  const handleJump = (bookmark: OutputBookmark) => {
+    const termId = jumpToOutputBookmark(bookmark.id);
+    if (!termId) {
+      setExpandedId(bookmark.id);
+      return;
+    }
+    setActiveTerminal(bookmark.connectionId, termId);
+    onClose();
+  };
+
+  const handleDelete = async (bookmark: OutputBookmark) => {
+    try {
+      await deleteOutputBookmarkIpc(bookmark.id);
+      forgetOutputBookmark(bookmark.id);
+      setBookmarks((current) => current.filter((b) => b.id !== bookmark.id));
+    } catch (error) {
+      showToast('error', `Failed to delete bookmark: ${errorMessage(error)}`);
+    }
+  };
+
+  return (
+    <Modal isOpen={isOpen} onClose={onClose} title="Output Bookmarks" width="max-w-2xl">
+      <div className="space-y-3">
+        <div className="flex items-center gap-2">
+          <Input
+            value={query}
+            onChange={(e) => setQuery(e.target.value)}
+            placeholder="Search labels, commands and output..."
+            autoFocus
+          />
+          <Button
+            variant="ghost"
+            className="h-10 px-3 text-xs whitespace-nowrap"
+            onClick={() => setAllHosts((value) => !value)}
+          >
+            {allHosts ? 'All hosts' : 'This host'}
+          </Button>
+        </div>
+
+        <div className="space-y-2 max-h-[420px] overflow-y-auto pr-1">
+          {bookmarks.length === 0 && (
+            <p className="text-xs text-app-muted text-center py-8">
+              No bookmarks yet. Right-click the terminal and choose Bookmark Output.
+            </p>
+          )}
+          {bookmarks.map((bookmark) => {
+            const live = bookmark.connectionId === connectionId && isOutputBookmarkLive(bookmark.id);
+            const expanded = expandedId === bookmark.id;
+            return (
+              <div key={bookmark.id} className="rounded-lg bg-app-surface/50 border border-app-border/30 text-xs">
+                <div className="flex items-center gap-2 p-2.5">
+                  <div className="flex-1 min-w-0">
+                    <div className="font-semibold text-app-text truncate">{bookmark.label}</div>
+                    <div className="text-[10px] text-app-muted truncate">
+                      {new Date(bookmark.createdAt).toLocaleString()}
+                      {allHosts && ` · ${hostName(bookmark.connectionId)}`}
+                      {bookmark.cwd && ` · ${bookmark.cwd}`}
+                    </div>
+                    {bookmark.command && bookmark.command !== bookmark.label && (
+                      <div className="font-mono text-[10px] text-app-muted/80 truncate">$ {bookmark.command}</div>
+                    )}
+                  </div>
+                  {live && (
+                    <Button
+                      variant="ghost"
+                      className="h-7 px-2 text-[10px]"
+                      onClick={() => handleJump(bookmark)}
+                      title="Scroll the terminal to this output"
+                    >
+                      <CornerDownRight size={12} className="mr-1" /> Jump
+                    </Button>
+                  )}
+                  <button
+                    onClick={() => setExpandedId(expanded ? null : bookmark.id)}
+                    className="p-1.5 rounded text-app-muted hover:text-app-text hover:bg-app-highlight/30"
+                    title={expanded ? 'Hide saved output' : 'Show saved output'}
+                  >
+                    {expanded ? <EyeOff size={12} /> : <Eye size={12} />}
+                  </button>
+                  <button
+                    onClick={() => void writeTerminalClipboardText(bookmark.excerpt, bookmark.connectionId)}
+                    className="p-1.5 rounded text-app-muted hover:text-app-text hover:bg-app-highlight/30"
+                    title="Copy saved output"
+                  >
+                    <Copy size={12} />
+                  </button>
+                  <button
+                    onClick={() => void handleDelete(bookmark)}
+                    className="p-1.5 rounded text-app-muted hover:text-red-400 hover:bg-red-500/10"
+                    title="Delete bookmark"
+                  >
+                    <Trash2 size={12} />
+                  </button>
+                </div>
+                {expanded && (
+                  <pre className="mx-2.5 mb-2.5 p-2 rounded bg-app-bg border border-app-border/20 text-[10px] font-mono text-app-muted whitespace-pre-wrap break-all max-h-64 overflow-y-auto">
+                    {bookmark.truncated && '…\n'}
+                    {bookmark.excerpt}
+                  </pre>
+                )}
+              </div>
+            );
+          })}
+        </div>
+      </div>
+    </Modal>
+  );
+}
@@ -0,0 +1,80 @@
+import { useEffect, useState } from 'react';
+import { ShieldAlert, X } from 'lucide-react';
+import { scrubPersistedSecretsIpc } from '../../features/redaction/infrastructure/redactionIpc';
+import {
+  SECRET_OUTPUT_DETECTED_EVENT,
+  SECRET_OUTPUT_KIND_LABELS,
+  type SecretOutputDetectedDetail,
+  type SecretOutputMatch,
+} from '../../lib/terminal';
+import { useAppStore } from '../../store/useAppStore';
+
+export interface SecretOutputBannerProps {
+  sessionId: string;
+}
+
+/** Discreet notice when a credential showed up in this terminal's output. */
+export function SecretOutputBanner({ sessionId }: SecretOutputBannerProps) {
+  const showToast = useAppStore((state) => state.showToast);
+  const [matches, setMatches] = useState<SecretOutputMatch[]>([]);
+  const [busy, setBusy] = useState(false);
+
+  useEffect(() => {
+    const handleDetected = (event: Event) => {
+      const detail = (event as CustomEvent<SecretOutputDetectedDetail>).detail;
+      if (detail?.termId !== sessionId) return;
+      setMatches((current) => [...current, ...detail.matches]);
+    };
+    window.addEventListener(SECRET_OUTPUT_DETECTED_EVENT, handleDetected);
+    return () => window.removeEventListener(SECRET_OUTPUT_DETECTED_EVENT, handleDetected);
+  }, [sessionId]);
+
+  if (matches.length === 0) return null;
+
+  const kinds = [...new Set(matches.map((match) => SECRET_OUTPUT_KIND_LABELS[match.kind]))];
+  const summary = matches.length === 1 ? `Possible ${kinds[0]}` : `${matches.length} possible secrets`;
+
+  const handleScrub = async () => {
+    setBusy(true);
+    try {
+      const report = await scrubPersistedSecretsIpc(matches.map((match) => match.value));
+      const changed = report.clipboardEntries + report.outputBookmarks;
+      showToast(
+        'success',
+        changed === 0
+          ? 'No saved copies found'
+          : `Masked in ${report.clipboardEntries} clipboard entries and ${report.outputBookmarks} bookmarks`,
+      );
+      setMatches([]);
+    } catch (error) {
+      showToast('error', `Failed to mask saved copies: ${error instanceof Error ? error.message : String(error)}`);
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  return (
+    <div
+      role="status"
+      className="absolute bottom-3 left-1/2 -translate-x-1/2 z-20 flex items-center gap-2 px-3 py-1.5 rounded-lg bg-app-panel/95 border border-amber-500/30 shadow-lg text-[11px] text-app-text"
+    >
+      <ShieldAlert size={13} className="text-amber-400 shrink-0" />
+      <span title={kinds.join(', ')}>{summary} in output</span>
+      <button
+        onClick={() => void handleScrub()}
+        disabled={busy}
+        className="px-1.5 py-0.5 rounded text-app-accent hover:bg-app-highlight/30 disabled:opacity-50"
+        title="Mask these values in clipboard history and output bookmarks"
+      >
+        Mask saved copies
+      </button>
+      <button
+        onClick={() => setMatches([])}
+        className="p-0.5 rounded text-app-muted hover:text-app-text"
+        title="Dismiss"
+      >
+        <X size={12} />
+      </button>
+    </div>
+  );
+}
@@ -171,6 +171,7 @@ export const TerminalComponent = memo(function TerminalComponent({
   useTerminalGlobalShortcuts({
     isVisible,
     termRef,
+    connectionId: terminalKey,
     onOpenSearch: openSearch,
   });
 
@@ -1,9 +1,10 @@
 import { memo, type RefObject } from 'react';
-import { Copy, Clipboard as ClipboardIcon, Trash2, Scissors } from 'lucide-react';
+import { Bookmark, BookmarkPlus, Copy, Clipboard as ClipboardIcon, History, Trash2, Scissors } from 'lucide-react';
 import type { Terminal as XTerm } from '@xterm/xterm';
 import { ContextMenu } from '../ui/ContextMenu';
 import type { AppSettings } from '../../store/settingsSlice';
-import { terminalCache } from '../../lib/terminal';
+import { bookmarkTerminalOutput, terminalCache } from '../../lib/terminal';
+import { useAppStore } from '../../store/useAppStore';
 import {
   readTerminalClipboardText,
   writeTerminalClipboardText,
@@ -17,9 +18,19 @@ export interface TerminalContextMenuProps {
   termRef: RefObject<XTerm | null>;
   truncateLabel: (label: string, max?: number) => string;
   onAcceptGhostSuffix: (suffix: string) => void;
+  onOpenClipboardHistory: () => void;
+  onOpenBookmarks: () => void;
   onClose: () => void;
 }
 
+function bookmarkOutput(sessionId: string): void {
+  const store = useAppStore.getState();
+  const cwd = Object.values(store.terminals).flat().find((tab) => tab.id === sessionId)?.lastKnownCwd;
+  bookmarkTerminalOutput(sessionId, { cwd })
+    .then((bookmark) => store.showToast('success', `Bookmarked "${bookmark.label}"`))
+    .catch((error) => store.showToast('error', error instanceof Error ? error.message : String(error)));
+}
+
 export const TerminalContextMenu = memo(function TerminalContextMenu({
   position,
   sessionId,
@@ -28,6 +39,8 @@ export const TerminalContextMenu = memo(function TerminalContextMenu({
   termRef,
   truncateLabel,
   onAcceptGhostSuffix,
+  onOpenClipboardHistory,
+  onOpenBookmarks,
   onClose,
 }: TerminalContextMenuProps) {
   const ghostItems = ghostSettings.contextMenuEnabled && ghostSuggestion
@@ -55,7 +68,10 @@ export const TerminalContextMenu = memo(function TerminalContextMenu({
           action: () => {
             const selection = termRef.current?.getSelection();
             if (selection) {
-              void writeTerminalClipboardText(selection).catch(console.error);
+              void writeTerminalClipboardText(
+                selection,
+                terminalCache.get(sessionId)?.connectionId,
+              ).catch(console.error);
             }
           },
           disabled: !termRef.current?.hasSelection(),
@@ -70,6 +86,21 @@ export const TerminalContextMenu = memo(function TerminalContextMenu({
             }
           },
         },
+        {
+          label: 'Clipboard History…',
+          icon: <History className="w-4 h-4" />,
+          action: onOpenClipboardHistory,
+        },
+        {
+          label: termRef.current?.hasSelection() ? 'Bookmark Selection' : 'Bookmark Output',
+          icon: <BookmarkPlus className="w-4 h-4" />,
+          action: () => bookmarkOutput(sessionId),
+        },
+        {
+          label: 'Output Bookmarks…',
+          icon: <Bookmark className="w-4 h-4" />,
+          action: onOpenBookmarks,
+        },
         {
           label: 'Select All',
           icon: <Scissors className="w-4 h-4" />,
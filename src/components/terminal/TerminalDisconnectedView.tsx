@@ -2,6 +2,25 @@ import { memo } from 'react';
 import { Terminal } from 'lucide-react';
 import { Button } from '../ui/Button';
 import type { Connection } from '../../store/useAppStore';
+import {
+  stopFollowingHostPower,
+  useHostPowerStore,
+  type HostPowerWatch,
+} from '../../features/hostPower/application/hostPowerService';
+
+function powerWatchMessage(watch: HostPowerWatch): string {
+  const verb = watch.action === 'reboot' ? 'Rebooting' : 'Shutting down';
+  switch (watch.phase) {
+    case 'going-down':
+      return `${verb}: waiting for the host to go down...`;
+    case 'down':
+      return watch.action === 'reboot'
+        ? 'Rebooting: waiting for SSH to come back...'
+        : 'The host is down.';
+    default:
+      return 'The host is back. Reconnecting...';
+  }
+}
 
 export interface TerminalDisconnectedViewProps {
   connection: Connection | null | undefined;
@@ -18,10 +37,19 @@ export const TerminalDisconnectedView = memo(function TerminalDisconnectedView({
 }: TerminalDisconnectedViewProps) {
   const isConnecting = connection?.status === 'connecting';
   const hasError = connection?.status === 'error';
+  const powerWatch = useHostPowerStore((state) => state.watches[activeConnectionId]);
 
   return (
     <div key="disconnected" className="flex flex-col h-full items-center justify-center p-8 text-app-muted gap-4 bg-app-bg z-10 relative">
-      {isConnecting ? (
+      {powerWatch && !isConnecting ? (
+        <>
+          <div className="animate-spin rounded-full h-5 w-5 border-2 border-app-accent border-t-transparent" />
+          <span>{powerWatchMessage(powerWatch)}</span>
+          <Button variant="ghost" onClick={() => void stopFollowingHostPower(activeConnectionId)}>
+            Stop Waiting
+          </Button>
+        </>
+      ) : isConnecting ? (
         <>
           <div className="animate-spin rounded-full h-5 w-5 border-2 border-app-accent border-t-transparent" />
           <span>Connecting to terminal...</span>
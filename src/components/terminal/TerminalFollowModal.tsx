@@ -0,0 +1,94 @@
+import { useEffect, useState } from 'react';
+import { useAppStore } from '../../store/useAppStore';
+import { parseFollowRules } from '../../lib/terminal';
+import { Button } from '../ui/Button';
+import { Modal } from '../ui/Modal';
+import { Select } from '../ui/Select';
+
+export interface TerminalFollowModalProps {
+  isOpen: boolean;
+  onClose: () => void;
+  primaryConnectionId: string;
+  primaryTermId: string;
+}
+
+/** Opens a second terminal next to the current one that follows its navigation. */
+export function TerminalFollowModal({ isOpen, onClose, primaryConnectionId, primaryTermId }: TerminalFollowModalProps) {
+  const connections = useAppStore((state) => state.connections);
+  const [secondaryConnectionId, setSecondaryConnectionId] = useState('');
+  const [rulesText, setRulesText] = useState('');
+
+  useEffect(() => {
+    if (isOpen) {
+      setSecondaryConnectionId('');
+      setRulesText('');
+    }
+  }, [isOpen]);
+
+  const options = connections
+    .filter((c) => c.status === 'connected')
+    .map((c) => ({
+      label: c.id === primaryConnectionId ? `${c.name || c.host} (this host)` : c.name || c.host,
+      value: c.id,
+      icon: c.icon,
+    }));
+
+  const handleStart = () => {
+    const store = useAppStore.getState();
+    const primaryTitle = store.terminals[primaryConnectionId]?.find((t) => t.id === primaryTermId)?.title;
+    // Opening a tab makes it the active one there; keep the tab that was showing.
+    const previousActive = store.activeTerminalIds[secondaryConnectionId];
+    const secondaryTermId = store.createTerminal(secondaryConnectionId, {
+      title: `Follows ${primaryTitle ?? 'Shell'}`,
+    });
+    if (previousActive) store.setActiveTerminal(secondaryConnectionId, previousActive);
+    store.startTerminalFollow(primaryTermId, {
+      secondaryConnectionId,
+      secondaryTermId,
+      rules: parseFollowRules(rulesText),
+    });
+    onClose();
+  };
+
+  return (
+    <Modal isOpen={isOpen} onClose={onClose} title="Follow in Second Pane" width="max-w-lg">
+      <div className="space-y-4">
+        <div>
+          <label className="text-xs font-semibold text-app-muted uppercase tracking-wider block mb-2 px-1">
+            Secondary Host
+          </label>
+          <Select
+            placeholder="Select a connected host..."
+            value={secondaryConnectionId}
+            onChange={setSecondaryConnectionId}
+            options={options}
+          />
+        </div>
+        <div>
+          <label className="text-xs font-semibold text-app-muted uppercase tracking-wider block mb-2 px-1">
+            Path Rules
+          </label>
+          <textarea
+            value={rulesText}
+            onChange={(e) => setRulesText(e.target.value)}
+            placeholder={'/srv/app-staging => /srv/app-prod\n/home/deploy => /home/ubuntu'}
+            className="w-full h-24 px-3 py-2 text-xs font-mono bg-app-surface border border-app-border rounded-xl focus:outline-none focus:border-app-accent/50 resize-none placeholder:text-app-muted/30"
+          />
+          <p className="text-[10px] text-app-muted mt-2 px-1 opacity-70">
+            <code>cd</code>, <code>pushd</code>, <code>popd</code>, <code>ls</code>, <code>pwd</code> and similar
+            commands typed here are replayed in the second pane, with absolute paths rewritten by the longest
+            matching prefix. Commands with pipes, redirects or <code>;</code>/<code>&amp;&amp;</code> are not mirrored.
+          </p>
+        </div>
+        <div className="flex justify-end gap-3 pt-2">
+          <Button variant="ghost" onClick={onClose}>
+            Cancel
+          </Button>
+          <Button onClick={handleStart} disabled={!secondaryConnectionId}>
+            Open Side by Side
+          </Button>
+        </div>
+      </div>
+    </Modal>
+  );
+}
@@ -1,4 +1,4 @@
-import { memo, type CSSProperties, type RefObject } from 'react';
+import { memo, useState, type CSSProperties, type RefObject } from 'react';
 import { Terminal as XTerm } from '@xterm/xterm';
 import { cn } from '../../lib/utils';
 import type { AppSettings } from '../../store/settingsSlice';
@@ -6,6 +6,11 @@ import type { GhostLayoutHint } from '../../lib/ghostSuggestions/cursorPosition'
 import { GhostSuggestionOverlay } from './GhostSuggestionOverlay';
 import { TerminalSearchBar } from './TerminalSearchBar';
 import { TerminalContextMenu } from './TerminalContextMenu';
+import { ClipboardHistoryModal } from './ClipboardHistoryModal';
+import { OutputBookmarksModal } from './OutputBookmarksModal';
+import { SecretOutputBanner } from './SecretOutputBanner';
+import { terminalCache } from '../../lib/terminal';
+import { LOCAL_TERMINAL_CONNECTION_ID } from '../../lib/terminal/connectionIds.js';
 
 export interface TerminalHostProps {
   containerRef: RefObject<HTMLDivElement | null>;
@@ -56,6 +61,9 @@ export const TerminalHost = memo(function TerminalHost({
   truncateLabel,
   onAcceptGhostSuffix,
 }: TerminalHostProps) {
+  const [isClipboardHistoryOpen, setClipboardHistoryOpen] = useState(false);
+  const [isBookmarksOpen, setBookmarksOpen] = useState(false);
+
   return (
     <div
       key="connected"
@@ -85,10 +93,34 @@ export const TerminalHost = memo(function TerminalHost({
           termRef={termRef}
           truncateLabel={truncateLabel}
           onAcceptGhostSuffix={onAcceptGhostSuffix}
+          onOpenClipboardHistory={() => setClipboardHistoryOpen(true)}
+          onOpenBookmarks={() => setBookmarksOpen(true)}
           onClose={onCloseContextMenu}
         />
       )}
 
+      <ClipboardHistoryModal
+        isOpen={isClipboardHistoryOpen}
+        onClose={() => {
+          setClipboardHistoryOpen(false);
+          termRef.current?.focus();
+        }}
+        onPaste={(text) => termRef.current?.paste(text)}
+      />
+
+      <SecretOutputBanner sessionId={sessionId} />
+
+      {isBookmarksOpen && (
+        <OutputBookmarksModal
+          isOpen={isBookmarksOpen}
+          onClose={() => {
+            setBookmarksOpen(false);
+            termRef.current?.focus();
+          }}
+          connectionId={terminalCache.get(sessionId)?.connectionId ?? LOCAL_TERMINAL_CONNECTION_ID}
+        />
+      )}
+
       <div
         className={cn(
           'absolute inset-0 pointer-events-none',
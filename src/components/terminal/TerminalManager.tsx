@@ -1,12 +1,19 @@
 
-import { useEffect, useRef } from 'react';
+import { useEffect, useRef, useState } from 'react';
 import { TerminalComponent } from './Terminal';
+import { TerminalFollowModal } from './TerminalFollowModal';
 import { useAppStore } from '../../store/useAppStore';
 import { useShallow } from 'zustand/react/shallow';
-import { Terminal as TerminalIcon, Plus, X, Zap } from 'lucide-react';
+import { Terminal as TerminalIcon, Columns2, Plus, X, Zap } from 'lucide-react';
 import { cn } from '../../lib/utils';
 import { once, type UnlistenFn } from '@tauri-apps/api/event';
-import { queueTerminalInput } from '../../lib/terminal';
+import {
+    isRecordingMacro,
+    queueTerminalInput,
+    replayMacro,
+    startMacroRecording,
+    stopMacroRecording,
+} from '../../lib/terminal';
 
 // TerminalTab interface is now in store/terminalSlice
 // export interface TerminalTab ... removed
@@ -35,12 +42,20 @@ export function TerminalManager({
     // Zustand Store Hooks - Optimized
     const tabs = useAppStore(useShallow(state => activeConnectionId ? (state.terminals[activeConnectionId] || []) : []));
     const activeTabId = useAppStore(state => activeConnectionId ? (state.activeTerminalIds[activeConnectionId] || null) : null);
+    const follow = useAppStore(state => activeTabId ? state.terminalFollows[activeTabId] : undefined);
+    const followLabel = useAppStore(state => {
+        if (!follow) return null;
+        const connection = state.connections.find(c => c.id === follow.secondaryConnectionId);
+        return connection ? connection.name || connection.host : follow.secondaryConnectionId;
+    });
+    const [isFollowModalOpen, setIsFollowModalOpen] = useState(false);
 
     // Actions (stable)
     const createTerminal = useAppStore(state => state.createTerminal);
     const ensureTerminal = useAppStore(state => state.ensureTerminal);
     const closeTerminal = useAppStore(state => state.closeTerminal);
     const setActiveTerminal = useAppStore(state => state.setActiveTerminal);
+    const stopTerminalFollow = useAppStore(state => state.stopTerminalFollow);
     const terminalTransparencyEnabled = useAppStore(
         state => state.settings.enableVibrancy && (state.settings.windowOpacity ?? 1) < 1
     );
@@ -147,13 +162,52 @@ export function TerminalManager({
             }
         };
 
+        const handleMacroRecord = (e: Event) => {
+            const { connectionId: targetConnId } = (e as CustomEvent).detail;
+            const termId = activeTabIdRef.current;
+            if (targetConnId !== activeConnectionId || !termId) return;
+
+            const store = useAppStore.getState();
+            if (!isRecordingMacro(termId)) {
+                startMacroRecording(termId);
+                store.showToast('info', 'Recording macro — press the shortcut again to stop');
+                return;
+            }
+            const steps = stopMacroRecording(termId);
+            if (!steps?.some(step => step.kind === 'input')) {
+                store.showToast('info', 'Macro recording discarded (no input)');
+                return;
+            }
+            const name = `Macro ${store.macros.length + 1}`;
+            void store.saveMacro({ id: crypto.randomUUID(), name, steps }).then(saved => {
+                if (saved) store.showToast('success', `Saved "${name}" — bind a shortcut to it in Snippets`);
+            });
+        };
+
+        const handleRunMacro = (e: Event) => {
+            const { connectionId: targetConnId, macroId } = (e as CustomEvent).detail;
+            const termId = activeTabIdRef.current;
+            if (targetConnId !== activeConnectionId || !termId) return;
+
+            const store = useAppStore.getState();
+            const macro = store.macros.find(m => m.id === macroId);
+            if (!macro) return;
+            replayMacro(termId, macro.steps).catch(error => {
+                store.showToast('error', `Macro "${macro.name}" stopped: ${error instanceof Error ? error.message : error}`);
+            });
+        };
+
         window.addEventListener('ssh-ui:run-command', handleRunCommand);
+        window.addEventListener('ssh-ui:macro-record', handleMacroRecord);
+        window.addEventListener('ssh-ui:run-macro', handleRunMacro);
         window.addEventListener('ssh-ui:new-terminal-tab', handleTriggerNewTab);
         window.addEventListener('ssh-ui:close-terminal-tab', handleTriggerCloseTab);
         window.addEventListener('zync:terminal:send', handlePluginTerminalSend);
 
         return () => {
             window.removeEventListener('ssh-ui:run-command', handleRunCommand);
+            window.removeEventListener('ssh-ui:macro-record', handleMacroRecord);
+            window.removeEventListener('ssh-ui:run-macro', handleRunMacro);
             window.removeEventListener('ssh-ui:new-terminal-tab', handleTriggerNewTab);
             window.removeEventListener('ssh-ui:close-terminal-tab', handleTriggerCloseTab);
             window.removeEventListener('zync:terminal:send', handlePluginTerminalSend);
@@ -230,6 +284,21 @@ export function TerminalManager({
                         ))}
                     </div>
 
+                    <button
+                        onClick={() => {
+                            if (!activeTabId) return;
+                            if (follow) stopTerminalFollow(activeTabId);
+                            else setIsFollowModalOpen(true);
+                        }}
+                        disabled={!activeTabId}
+                        className={cn(
+                            "h-6 w-6 flex items-center justify-center rounded transition-colors disabled:opacity-40",
+                            follow ? "text-app-accent bg-app-surface" : "text-app-muted hover:text-white hover:bg-app-surface"
+                        )}
+                        title={follow ? `Stop following in ${followLabel}` : "Follow in Second Pane"}
+                    >
+                        <Columns2 size={13} />
+                    </button>
                     <button
                         onClick={handleNewTab}
                         className="h-6 w-6 flex items-center justify-center rounded text-app-muted hover:text-white hover:bg-app-surface transition-colors"
@@ -252,9 +321,9 @@ export function TerminalManager({
                         <button onClick={handleNewTab} className="mt-4 text-app-accent hover:underline">Open New Terminal</button>
                     </div>
                 ) : activeTabId && terminalView ? (
-                    <div className="absolute inset-0 z-10">
+                    <div className="absolute inset-0 z-10 flex">
                         {/* Mount only the active shell while terminal view is shown — xterm stays in terminalCache. */}
-                        <div className="h-full w-full">
+                        <div className={cn("h-full relative", follow ? "w-1/2 border-r border-app-border" : "w-full")}>
                             <TerminalComponent
                                 connectionId={activeConnectionId}
                                 termId={activeTabId}
@@ -264,9 +333,43 @@ export function TerminalManager({
                                 isVisible={panelVisible}
                             />
                         </div>
+                        {follow && (
+                            <div className="h-full w-1/2 flex flex-col">
+                                <div className="flex items-center gap-1.5 px-2 h-6 shrink-0 text-[10px] text-app-muted bg-app-panel border-b border-app-border">
+                                    <Columns2 size={10} className="text-app-accent" />
+                                    <span className="truncate flex-1">Following in {followLabel}</span>
+                                    <button
+                                        onClick={() => stopTerminalFollow(activeTabId)}
+                                        className="hover:text-app-text"
+                                        title="Stop following; the shell stays open as a tab on that host"
+                                    >
+                                        <X size={10} />
+                                    </button>
+                                </div>
+                                <div className="flex-1 relative">
+                                    <TerminalComponent
+                                        connectionId={follow.secondaryConnectionId}
+                                        termId={follow.secondaryTermId}
+                                        isWorkspaceActive={workspaceActive}
+                                        isTerminalView
+                                        isActiveTab
+                                        isVisible={panelVisible}
+                                    />
+                                </div>
+                            </div>
+                        )}
                     </div>
                 ) : null}
             </div>
+
+            {isFollowModalOpen && activeTabId && (
+                <TerminalFollowModal
+                    isOpen={isFollowModalOpen}
+                    onClose={() => setIsFollowModalOpen(false)}
+                    primaryConnectionId={activeConnectionId}
+                    primaryTermId={activeTabId}
+                />
+            )}
         </div>
     );
 }
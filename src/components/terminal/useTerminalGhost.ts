@@ -26,7 +26,9 @@ import { extractRecentCommands } from '../../lib/ghostSuggestions/recentCommands
 import {
   clearTerminalPendingInput,
   enqueueTerminalInputTask,
+  followCommand,
   getTerminalRecentLines,
+  markTerminalCommand,
   queueTerminalInput,
   isTerminalIdleSuspended,
   spawnTerminalFromStoreContext,
@@ -42,6 +44,14 @@ function getEffectiveShellId(
   return shellOverride ?? (terminalKey === 'local' ? windowsShell : undefined);
 }
 
+/** Replays a committed navigation command in the terminal following this one, if any. */
+function mirrorToFollower(primaryTermId: string, command: string): void {
+  const follow = useAppStore.getState().terminalFollows[primaryTermId];
+  if (!follow) return;
+  const mirrored = followCommand(command, follow.rules);
+  if (mirrored) queueTerminalInput(follow.secondaryTermId, `${mirrored}\r`);
+}
+
 export interface UseTerminalGhostOptions {
   sessionId: string;
   terminalKey: string;
@@ -231,6 +241,8 @@ export function useTerminalGhost({
         },
         onHistoryCommit: (cmd) => {
           commitGhostCommand(cmd, ghostScope).catch(() => {});
+          mirrorToFollower(mountSessionId, cmd);
+          markTerminalCommand(mountSessionId, cmd);
           const termState = useAppStore.getState().terminals[terminalKey]?.find((t) => t.id === mountSessionId);
           const cwd = termState?.lastKnownCwd ?? termState?.initialPath;
           const nextCwd = resolveCdTargetPath(cmd, cwd);
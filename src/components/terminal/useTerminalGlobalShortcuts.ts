@@ -8,12 +8,15 @@ import {
 export interface UseTerminalGlobalShortcutsOptions {
   isVisible: boolean;
   termRef: RefObject<XTerm | null>;
+  /** Recorded with copies in the clipboard history. */
+  connectionId: string;
   onOpenSearch: () => void;
 }
 
 export function useTerminalGlobalShortcuts({
   isVisible,
   termRef,
+  connectionId,
   onOpenSearch,
 }: UseTerminalGlobalShortcutsOptions) {
   useEffect(() => {
@@ -21,7 +24,7 @@ export function useTerminalGlobalShortcuts({
       if (isVisible && termRef.current?.hasSelection()) {
         const selection = termRef.current.getSelection();
         if (selection) {
-          await writeTerminalClipboardText(selection).catch(console.error);
+          await writeTerminalClipboardText(selection, connectionId).catch(console.error);
         }
       }
     };
@@ -59,5 +62,5 @@ export function useTerminalGlobalShortcuts({
       window.removeEventListener('ssh-ui:term-find', handleGlobalFind);
       window.removeEventListener('ssh-ui:term-focus', handleGlobalFocus);
     };
-  }, [isVisible, termRef, onOpenSearch]);
+  }, [isVisible, termRef, connectionId, onOpenSearch]);
 }
\ No newline at end of file
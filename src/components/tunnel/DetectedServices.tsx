@@ -0,0 +1,76 @@
+import { useEffect, useState } from 'react';
+import { Plus, Sparkles } from 'lucide-react';
+import { useAppStore } from '../../store/useAppStore';
+import {
+    suggestTunnelsIpc,
+    type TunnelSuggestion,
+} from '../../features/tunnels/infrastructure/remotePortsIpc';
+
+interface DetectedServicesProps {
+    connectionId: string;
+    connected: boolean;
+    /** Saved tunnel count; a change re-runs detection so added drafts drop out. */
+    tunnelCount: number;
+}
+
+/** One-click local forwards for services found listening on the server. */
+export function DetectedServices({ connectionId, connected, tunnelCount }: DetectedServicesProps) {
+    const saveTunnel = useAppStore((state) => state.saveTunnel);
+    const showToast = useAppStore((state) => state.showToast);
+    const [suggestions, setSuggestions] = useState<TunnelSuggestion[]>([]);
+
+    useEffect(() => {
+        if (!connected) {
+            setSuggestions([]);
+            return;
+        }
+        let cancelled = false;
+        suggestTunnelsIpc(connectionId)
+            .then((next) => {
+                if (!cancelled) setSuggestions(next);
+            })
+            .catch((error) => {
+                console.warn('[Tunnels] Service detection failed:', error);
+                if (!cancelled) setSuggestions([]);
+            });
+        return () => {
+            cancelled = true;
+        };
+    }, [connectionId, connected, tunnelCount]);
+
+    if (suggestions.length === 0) return null;
+
+    const create = async (suggestion: TunnelSuggestion) => {
+        setSuggestions((current) => current.filter((entry) => entry.draft.id !== suggestion.draft.id));
+        try {
+            await saveTunnel({ ...suggestion.draft, status: 'stopped' });
+            showToast('success', `Added forward "${suggestion.draft.name}" on localhost:${suggestion.draft.localPort}`);
+        } catch {
+            // saveTunnel already reported the failure.
+        }
+    };
+
+    return (
+        <div className="mb-6 rounded-lg border border-app-border/40 bg-app-surface/30 px-3 py-2.5">
+            <div className="mb-2 flex items-center gap-1.5 text-[11px] font-medium text-app-muted">
+                <Sparkles size={12} className="text-app-accent/80" />
+                Detected on this server
+            </div>
+            <div className="flex flex-wrap gap-1.5">
+                {suggestions.map((suggestion) => (
+                    <button
+                        key={suggestion.draft.id}
+                        type="button"
+                        onClick={() => void create(suggestion)}
+                        title={`Forward localhost:${suggestion.draft.localPort} → ${suggestion.draft.remoteHost}:${suggestion.draft.remotePort}`}
+                        className="inline-flex items-center gap-1.5 rounded-md border border-app-border bg-app-bg px-2 py-1 text-[11px] text-app-text transition-colors hover:border-app-accent/60 hover:text-app-accent"
+                    >
+                        <Plus size={11} />
+                        {suggestion.draft.name}
+                        <span className="font-mono text-app-muted">:{suggestion.listening.port}</span>
+                    </button>
+                ))}
+            </div>
+        </div>
+    );
+}
@@ -4,17 +4,21 @@ import { Button } from '../ui/Button';
 import { Modal } from '../ui/Modal';
 import { TopbarDropdown } from '../ui/TopbarDropdown';
 import { cn } from '../../lib/utils';
-import { Plus, Network, ChevronDown, FileText, Play, Square, Folder, FolderOpen, LayoutGrid, List, ChevronRight, ArrowRight } from 'lucide-react';
+import { Plus, Network, ChevronDown, FileCode, FileText, Play, Square, Folder, FolderOpen, LayoutGrid, List, ChevronRight, ArrowRight, Home } from 'lucide-react';
 import { TUNNEL_PRESETS, TunnelPreset } from '../../lib/tunnelPresets';
 import { AddTunnelModal } from '../modals/AddTunnelModal';
 import { ImportSSHCommandModal } from '../modals/ImportSSHCommandModal';
+import { HomelabRendezvousModal } from '../modals/HomelabRendezvousModal';
+import { SshConfigForwardsModal } from '../modals/SshConfigForwardsModal';
 import { TunnelCard, TunnelConfig } from './TunnelCard';
+import { outsideScheduleMessage } from '../../features/tunnels/domain/tunnelSchedule';
 import { getConnectionDisplayLabels } from '../../features/connections/domain/connectionDisplay';
 import {
     parsePortConflictError,
     tunnelWithSwappedPort,
 } from '../../features/tunnels/application/tunnelPortConflict';
 import {
+    openTunnelInBrowser,
     revertTunnelOriginalPort,
     stopTunnelConfig,
     startTunnelConfig,
@@ -41,6 +45,8 @@ export function GlobalTunnelList() {
     const [initialConnectionId, setInitialConnectionId] = useState<string | undefined>(undefined);
     const [showPresetDropdown, setShowPresetDropdown] = useState(false);
     const [showImportModal, setShowImportModal] = useState(false);
+    const [showHomelabModal, setShowHomelabModal] = useState(false);
+    const [showSshConfigModal, setShowSshConfigModal] = useState(false);
     const [viewMode, setViewMode] = useState<'grid' | 'list'>('list');
     const [collapsedGroups, setCollapsedGroups] = useState<Set<string>>(new Set());
     const dropdownRef = useRef<HTMLDivElement>(null);
@@ -211,6 +217,11 @@ export function GlobalTunnelList() {
                 showToast('success', `Forwarding started`);
             }
         } catch (error: unknown) {
+            const outside = outsideScheduleMessage(error);
+            if (outside) {
+                showToast('info', outside);
+                return;
+            }
             const conflict = parsePortConflictError(error, tunnel);
             if (conflict) {
                 setPortSuggestion(conflict);
@@ -322,13 +333,7 @@ export function GlobalTunnelList() {
         if (count > 0) showToast('info', `Stopped ${count} forwards in ${groupName === 'Ungrouped' ? 'ungrouped' : groupName}`);
     };
 
-    const handleOpenBrowser = async (port: number) => {
-        try {
-            await window.ipcRenderer.invoke('shell:open', `http://localhost:${port}`);
-        } catch (e) {
-            console.error('Failed to open browser', e);
-        }
-    };
+    const handleOpenBrowser = (tunnel: TunnelConfig) => openTunnelInBrowser(tunnel, showToast);
 
     return (
         <div className="flex flex-col h-full bg-app-bg animate-in fade-in duration-300">
@@ -402,6 +407,22 @@ export function GlobalTunnelList() {
                         >
                             <FileText size={12} className="mr-1" /> Import
                         </Button>
+                        <Button
+                            variant="ghost"
+                            onClick={() => setShowSshConfigModal(true)}
+                            className="h-7 px-2 text-[10px] text-app-muted hover:text-app-text"
+                            title="Import or export LocalForward/RemoteForward lines"
+                        >
+                            <FileCode size={12} className="mr-1" /> SSH Config
+                        </Button>
+                        <Button
+                            variant="ghost"
+                            onClick={() => setShowHomelabModal(true)}
+                            className="h-7 px-2 text-[10px] text-app-muted hover:text-app-text"
+                            title="Reach a machine behind NAT through a VPS"
+                        >
+                            <Home size={12} className="mr-1" /> Homelab
+                        </Button>
                         <div className="relative" ref={dropdownRef}>
                             <div className="flex">
                                 <Button
@@ -429,7 +450,7 @@ export function GlobalTunnelList() {
                                     widthClass="w-56"
                                     className="mt-1 rounded-lg shadow-xl p-0"
                                 >
-                                    {TUNNEL_PRESETS.map((preset) => (
+                                    {TUNNEL_PRESETS.filter(preset => !preset.viewer).map((preset) => (
                                         <button
                                             key={preset.id}
                                             onClick={() => handlePresetSelect(preset)}
@@ -690,6 +711,21 @@ export function GlobalTunnelList() {
                 />
             )}
 
+            {showSshConfigModal && (
+                <SshConfigForwardsModal
+                    isOpen={showSshConfigModal}
+                    onClose={() => setShowSshConfigModal(false)}
+                    onImport={loadTunnels}
+                />
+            )}
+
+            {showHomelabModal && (
+                <HomelabRendezvousModal
+                    isOpen={showHomelabModal}
+                    onClose={() => setShowHomelabModal(false)}
+                />
+            )}
+
             <AddTunnelModal
                 isOpen={isAddModalOpen}
                 editingTunnel={editingTunnel}
@@ -0,0 +1,131 @@
+import { useEffect, useState } from 'react';
+import { Trash2 } from 'lucide-react';
+import { Modal } from '../ui/Modal';
+import { cn } from '../../lib/utils';
+import {
+    getTunnelHttpLogIpc,
+    type HttpExchange,
+} from '../../features/tunnels/infrastructure/httpInspectIpc';
+import type { TunnelConfig } from './TunnelCard';
+
+const POLL_INTERVAL_MS = 2000;
+
+function statusClass(status?: number | null): string {
+    if (!status) return 'text-app-muted';
+    if (status >= 500) return 'text-red-400';
+    if (status >= 400) return 'text-amber-400';
+    if (status >= 300) return 'text-sky-400';
+    return 'text-app-success';
+}
+
+function formatBytes(bytes: number): string {
+    if (bytes < 1024) return `${bytes} B`;
+    if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+    return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+}
+
+interface HttpInspectorModalProps {
+    tunnel: TunnelConfig;
+    isOpen: boolean;
+    onClose: () => void;
+}
+
+/** Live request log for a forward with `web.inspect` on. Bodies are never captured. */
+export function HttpInspectorModal({ tunnel, isOpen, onClose }: HttpInspectorModalProps) {
+    const [exchanges, setExchanges] = useState<HttpExchange[]>([]);
+    const [error, setError] = useState<string | null>(null);
+
+    useEffect(() => {
+        if (!isOpen) return;
+        let cancelled = false;
+        const load = () =>
+            getTunnelHttpLogIpc(tunnel.id)
+                .then((next) => {
+                    if (cancelled) return;
+                    setExchanges(next);
+                    setError(null);
+                })
+                .catch((err) => {
+                    if (!cancelled) setError(err instanceof Error ? err.message : String(err));
+                });
+        load();
+        const timer = window.setInterval(load, POLL_INTERVAL_MS);
+        return () => {
+            cancelled = true;
+            window.clearInterval(timer);
+        };
+    }, [isOpen, tunnel.id]);
+
+    const clear = async () => {
+        await getTunnelHttpLogIpc(tunnel.id, true).catch(() => undefined);
+        setExchanges([]);
+    };
+
+    return (
+        <Modal
+            isOpen={isOpen}
+            onClose={onClose}
+            title="HTTP Requests"
+            subtitle={`${tunnel.name} · localhost:${tunnel.localPort}`}
+            width="max-w-3xl"
+        >
+            <div className="space-y-2">
+                <div className="flex items-center justify-between text-xs text-app-muted">
+                    <span>{exchanges.length} request{exchanges.length === 1 ? '' : 's'} (newest first)</span>
+                    <button
+                        type="button"
+                        onClick={clear}
+                        disabled={exchanges.length === 0}
+                        className="inline-flex items-center gap-1 transition-colors hover:text-app-text disabled:opacity-50"
+                    >
+                        <Trash2 size={12} /> Clear
+                    </button>
+                </div>
+                {error ? (
+                    <p className="text-xs text-red-400">{error}</p>
+                ) : exchanges.length === 0 ? (
+                    <p className="py-8 text-center text-xs text-app-muted/70">
+                        No requests yet. Traffic through this forward shows up here.
+                    </p>
+                ) : (
+                    <div className="max-h-[60vh] overflow-auto rounded-lg border border-app-border/40">
+                        <table className="w-full text-left font-mono text-[11px]">
+                            <thead className="sticky top-0 bg-app-panel text-app-muted">
+                                <tr>
+                                    <th className="px-2 py-1.5 font-medium">Time</th>
+                                    <th className="px-2 py-1.5 font-medium">Method</th>
+                                    <th className="px-2 py-1.5 font-medium">Path</th>
+                                    <th className="px-2 py-1.5 font-medium">Status</th>
+                                    <th className="px-2 py-1.5 text-right font-medium">Duration</th>
+                                    <th className="px-2 py-1.5 text-right font-medium">Size</th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                {[...exchanges].reverse().map((exchange) => (
+                                    <tr key={exchange.id} className="border-t border-app-border/20">
+                                        <td className="whitespace-nowrap px-2 py-1 text-app-muted">
+                                            {new Date(exchange.startedAt).toLocaleTimeString()}
+                                        </td>
+                                        <td className="px-2 py-1 text-app-text">{exchange.method}</td>
+                                        <td className="max-w-[20rem] truncate px-2 py-1 text-app-text" title={exchange.target}>
+                                            {exchange.target}
+                                        </td>
+                                        <td className={cn('px-2 py-1', statusClass(exchange.status))}>
+                                            {exchange.status ?? '…'}
+                                        </td>
+                                        <td className="px-2 py-1 text-right text-app-muted" title={exchange.ttfbMs != null ? `First byte after ${exchange.ttfbMs} ms` : undefined}>
+                                            {exchange.durationMs != null ? `${exchange.durationMs} ms` : '—'}
+                                        </td>
+                                        <td className="px-2 py-1 text-right text-app-muted">
+                                            {formatBytes(exchange.responseBytes)}
+                                        </td>
+                                    </tr>
+                                ))}
+                            </tbody>
+                        </table>
+                    </div>
+                )}
+            </div>
+        </Modal>
+    );
+}
@@ -1,9 +1,11 @@
-import type { ReactNode } from 'react';
+import { useState, type ReactNode } from 'react';
 import {
+    Activity,
     ArrowLeft,
     ArrowRight,
     Copy,
     ExternalLink,
+    FileCode,
     MoreHorizontal,
     Play,
     Square,
@@ -11,6 +13,9 @@ import {
 } from 'lucide-react';
 import { cn } from '../../lib/utils';
 import { OSIcon } from '../icons/OSIcon';
+import { HttpInspectorModal } from './HttpInspectorModal';
+import { getPacInfoIpc } from '../../features/tunnels/infrastructure/pacIpc';
+import { useServiceNames } from '../../features/tunnels/application/serviceNames';
 import { isDynamicTunnel, socks5Url } from '../../features/tunnels/domain/tunnelTypes';
 import {
     formatTunnelFlow,
@@ -18,6 +23,8 @@ import {
     tunnelCopyAddress,
 } from '../../features/tunnels/presentation/tunnelDisplay';
 import type { TunnelType } from '../../features/tunnels/domain/tunnelTypes';
+import { describeSchedule, type TunnelSchedule } from '../../features/tunnels/domain/tunnelSchedule';
+import { useTunnelScheduleStatus } from '../../features/tunnels/application/tunnelScheduleStatus';
 
 export interface TunnelConfig {
     id: string;
@@ -29,11 +36,27 @@ export interface TunnelConfig {
     remotePort: number;
     bindToAny?: boolean;
     bindAddress?: string;
-    status: 'active' | 'error' | 'stopped';
+    /** `reconnecting` while a dropped remote forward is being registered again. */
+    status: 'active' | 'reconnecting' | 'error' | 'stopped';
     autoStart?: boolean;
     group?: string;
     error?: string;
     originalPort?: number;
+    /** Browser settings for web forwards (device-local). */
+    web?: {
+        protocol?: 'http' | 'https';
+        path?: string;
+        terminateTls?: boolean;
+        inspect?: boolean;
+        hostHeader?: string;
+        upstreamTls?: boolean;
+    };
+    /** SOCKS only: domains routed through this tunnel by the PAC file (device-local). */
+    pacDomains?: string[];
+    /** SOCKS only: point the OS proxy settings at this tunnel while it runs (device-local). */
+    systemProxy?: boolean;
+    /** Only run inside these weekly windows (device-local). */
+    schedule?: TunnelSchedule;
 }
 
 interface TunnelCardProps {
@@ -44,7 +67,7 @@ interface TunnelCardProps {
     onToggle: (tunnel: TunnelConfig) => void;
     onEdit: (tunnel: TunnelConfig) => void;
     onDelete: (id: string) => void;
-    onOpenBrowser: (port: number) => void;
+    onOpenBrowser: (tunnel: TunnelConfig) => void;
     onCopy: (text: string) => void;
 }
 
@@ -99,10 +122,13 @@ function HostEndpoint({
     host,
     port,
     tagged,
+    service,
 }: {
     host: string;
     port: number | null;
     tagged: boolean;
+    /** Catalog name of the port, e.g. `postgresql`. */
+    service?: string;
 }) {
     if (port === null) {
         return (
@@ -117,6 +143,7 @@ function HostEndpoint({
             <span className="inline-flex max-w-[11rem] items-center gap-1 rounded-md bg-app-bg/60 py-1 pl-2 pr-1.5 ring-1 ring-inset ring-app-border/35">
                 <span className="truncate text-[10px] font-medium text-app-muted">{host}</span>
                 <span className="shrink-0 font-mono text-[11px] tabular-nums text-app-text/85">:{port}</span>
+                {service && <span className="shrink-0 text-[10px] text-app-muted">{service}</span>}
             </span>
         );
     }
@@ -124,6 +151,7 @@ function HostEndpoint({
     return (
         <PortChip>
             {host}:{port}
+            {service && <span className="ml-1.5 font-sans text-app-muted">{service}</span>}
         </PortChip>
     );
 }
@@ -142,6 +170,7 @@ function FlowLine({
     className?: string;
 }) {
     const flow = formatTunnelFlow(tunnel, hostLabel);
+    const serviceName = useServiceNames();
 
     return (
         <div
@@ -153,7 +182,12 @@ function FlowLine({
         >
             <PortChip active={active}>{flow.source}</PortChip>
             <FlowConnector inbound={flow.inbound} />
-            <HostEndpoint host={flow.targetHost} port={flow.targetPort} tagged={flow.targetTagged} />
+            <HostEndpoint
+                host={flow.targetHost}
+                port={flow.targetPort}
+                tagged={flow.targetTagged}
+                service={serviceName(flow.targetPort)}
+            />
         </div>
     );
 }
@@ -197,6 +231,38 @@ function StatusToggle({
     );
 }
 
+function formatTransition(at: number): string {
+    const date = new Date(at);
+    const time = date.toLocaleTimeString([], { hour: '2-digit', minute: '2-digit' });
+    return date.toDateString() === new Date().toDateString()
+        ? time
+        : `${date.toLocaleDateString([], { weekday: 'short' })} ${time}`;
+}
+
+function ScheduleBadge({ tunnel }: { tunnel: TunnelConfig }) {
+    const status = useTunnelScheduleStatus(tunnel.id, tunnel.schedule);
+    if (!tunnel.schedule) return null;
+    const next = status?.nextTransitionMs;
+    const label = !status
+        ? 'Scheduled'
+        : next == null
+            ? 'Always on'
+            : `${status.active ? 'Closes' : 'Opens'} ${formatTransition(next)}`;
+    return (
+        <span
+            className={cn(
+                'rounded-full border px-2 py-0.5 text-[9px] font-semibold',
+                status && !status.active
+                    ? 'border-app-border/40 bg-app-bg/60 text-app-muted'
+                    : 'border-teal-400/20 bg-teal-400/10 text-teal-400',
+            )}
+            title={`Maintenance window: ${describeSchedule(tunnel.schedule)}`}
+        >
+            {label}
+        </span>
+    );
+}
+
 function TunnelMetaBadges({ tunnel }: { tunnel: TunnelConfig }) {
     const isDynamic = isDynamicTunnel(tunnel.type);
 
@@ -217,6 +283,14 @@ function TunnelMetaBadges({ tunnel }: { tunnel: TunnelConfig }) {
     return (
         <>
             <TypeMetaBadge type={tunnel.type} />
+            {tunnel.status === 'reconnecting' && (
+                <span
+                    className="rounded-full border border-yellow-400/20 bg-yellow-400/10 px-2 py-0.5 text-[9px] font-semibold text-yellow-400"
+                    title="The server dropped this forward; registering it again"
+                >
+                    Reconnecting
+                </span>
+            )}
             {tunnel.bindToAny && (
                 <span className="rounded-full border border-orange-400/20 bg-orange-400/10 px-2 py-0.5 text-[9px] font-semibold text-orange-400">
                     Public
@@ -227,11 +301,17 @@ function TunnelMetaBadges({ tunnel }: { tunnel: TunnelConfig }) {
                     SOCKS
                 </span>
             )}
+            {isDynamic && tunnel.systemProxy && (
+                <span className="rounded-full border border-violet-400/20 bg-violet-400/10 px-2 py-0.5 text-[9px] font-semibold text-violet-400">
+                    System proxy
+                </span>
+            )}
             {tunnel.autoStart && (
                 <span className="rounded-full border border-blue-400/20 bg-blue-400/10 px-2 py-0.5 text-[9px] font-semibold text-blue-400">
                     Auto
                 </span>
             )}
+            <ScheduleBadge tunnel={tunnel} />
         </>
     );
 }
@@ -249,6 +329,7 @@ function CardActions({
     isActive,
     isDynamic,
     onCopy,
+    onCopyPac,
     onOpenBrowser,
     onEdit,
     onDelete,
@@ -257,10 +338,14 @@ function CardActions({
     isActive: boolean;
     isDynamic: boolean;
     onCopy: () => void;
-    onOpenBrowser: (port: number) => void;
+    onCopyPac: () => void;
+    onOpenBrowser: (tunnel: TunnelConfig) => void;
     onEdit: () => void;
     onDelete: () => void;
 }) {
+    const [showInspector, setShowInspector] = useState(false);
+    const inspectable = tunnel.type === 'local' && !!tunnel.web?.inspect;
+
     return (
         <div className="flex items-center gap-0.5">
             {(tunnel.type === 'local' || isDynamic) && (
@@ -273,16 +358,36 @@ function CardActions({
                     <Copy size={13} />
                 </button>
             )}
+            {isDynamic && !!tunnel.pacDomains?.length && (
+                <button
+                    type="button"
+                    onClick={onCopyPac}
+                    className="rounded-md p-1.5 text-app-muted transition-colors hover:bg-app-surface hover:text-app-text"
+                    title="Copy PAC URL"
+                >
+                    <FileCode size={13} />
+                </button>
+            )}
             {tunnel.type === 'local' && isActive && (
                 <button
                     type="button"
-                    onClick={() => onOpenBrowser(tunnel.localPort)}
+                    onClick={() => onOpenBrowser(tunnel)}
                     className="rounded-md p-1.5 text-app-muted transition-colors hover:bg-app-surface hover:text-app-text"
                     title="Open in browser"
                 >
                     <ExternalLink size={13} />
                 </button>
             )}
+            {inspectable && (
+                <button
+                    type="button"
+                    onClick={() => setShowInspector(true)}
+                    className="rounded-md p-1.5 text-app-muted transition-colors hover:bg-app-surface hover:text-app-text"
+                    title="HTTP requests"
+                >
+                    <Activity size={13} />
+                </button>
+            )}
             <button
                 type="button"
                 onClick={onEdit}
@@ -299,6 +404,13 @@ function CardActions({
             >
                 <Trash2 size={13} />
             </button>
+            {inspectable && (
+                <HttpInspectorModal
+                    tunnel={tunnel}
+                    isOpen={showInspector}
+                    onClose={() => setShowInspector(false)}
+                />
+            )}
         </div>
     );
 }
@@ -323,6 +435,11 @@ export function TunnelCard({
     const showHostSubtitle = !!displayHost && !flow.targetTagged;
 
     const copyFlow = () => onCopy(copyText);
+    const copyPac = () => {
+        getPacInfoIpc()
+            .then((info) => onCopy(info.url))
+            .catch((error) => console.error('PAC server unavailable', error));
+    };
 
     const shellClass = cn(
         'group relative overflow-hidden rounded-xl border transition-all duration-200',
@@ -394,6 +511,7 @@ export function TunnelCard({
                         isActive={isActive}
                         isDynamic={isDynamic}
                         onCopy={copyFlow}
+                        onCopyPac={copyPac}
                         onOpenBrowser={onOpenBrowser}
                         onEdit={() => onEdit(tunnel)}
                         onDelete={() => onDelete(tunnel.id)}
@@ -436,6 +554,7 @@ export function TunnelCard({
                         isActive={isActive}
                         isDynamic={isDynamic}
                         onCopy={copyFlow}
+                        onCopyPac={copyPac}
                         onOpenBrowser={onOpenBrowser}
                         onEdit={() => onEdit(tunnel)}
                         onDelete={() => onDelete(tunnel.id)}
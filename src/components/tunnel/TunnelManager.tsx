@@ -9,13 +9,15 @@ import { ImportSSHCommandModal } from '../modals/ImportSSHCommandModal';
 import { Modal } from '../ui/Modal';
 import { TopbarDropdown } from '../ui/TopbarDropdown';
 import { TunnelCard, TunnelConfig } from './TunnelCard';
+import { outsideScheduleMessage } from '../../features/tunnels/domain/tunnelSchedule';
+import { DetectedServices } from './DetectedServices';
 import { getConnectionDisplayLabels } from '../../features/connections/domain/connectionDisplay';
 
 import {
   parsePortConflictError,
   tunnelWithSwappedPort,
 } from '../../features/tunnels/application/tunnelPortConflict';
-import { revertTunnelOriginalPort } from '../../features/tunnels/application/tunnelActions';
+import { launchRemoteDesktop, openTunnelInBrowser, revertTunnelOriginalPort } from '../../features/tunnels/application/tunnelActions';
 
 const EMPTY_TUNNELS: TunnelConfig[] = [];
 
@@ -94,6 +96,14 @@ export function TunnelManager({ connectionId }: { connectionId?: string }) {
   // Handle preset selection
   const handlePresetSelect = (preset: TunnelPreset) => {
     setShowPresetDropdown(false);
+    if (preset.viewer) {
+      if (!activeConnectionId || connectionStatus !== 'connected') {
+        showToast('error', 'Connect to the host before opening a remote desktop');
+        return;
+      }
+      void launchRemoteDesktop(activeConnectionId, preset.viewer, preset.remotePort, showToast);
+      return;
+    }
     setEditingTunnel({
       id: '',
       connectionId: activeConnectionId || '',
@@ -131,6 +141,11 @@ export function TunnelManager({ connectionId }: { connectionId?: string }) {
         showToast('success', 'Forwarding started');
       }
     } catch (error: unknown) {
+      const outside = outsideScheduleMessage(error);
+      if (outside) {
+        showToast('info', outside);
+        return;
+      }
       const conflict = parsePortConflictError(error, tunnel);
       if (conflict) {
         setPortSuggestion(conflict);
@@ -159,9 +174,7 @@ export function TunnelManager({ connectionId }: { connectionId?: string }) {
     }
   };
 
-  const handleOpenBrowser = async (port: number) => {
-    await window.ipcRenderer.invoke('shell:open', `http://localhost:${port}`);
-  };
+  const handleOpenBrowser = (tunnel: TunnelConfig) => openTunnelInBrowser(tunnel, showToast);
 
   const handleDeleteTunnel = async (id: string) => {
     if (!activeConnectionId) return;
@@ -314,7 +327,7 @@ export function TunnelManager({ connectionId }: { connectionId?: string }) {
                         <div className="text-[10px] text-app-muted mt-0.5">{preset.description}</div>
                       </div>
                       <div className="text-[9px] font-mono text-app-muted/60 bg-app-surface/50 px-1.5 py-0.5 rounded">
-                        {preset.localPort}
+                        {preset.viewer ? preset.viewer.toUpperCase() : preset.localPort}
                       </div>
                     </div>
                   </button>
@@ -326,6 +339,13 @@ export function TunnelManager({ connectionId }: { connectionId?: string }) {
       </div>
 
       <div className="flex-1 overflow-auto p-4">
+        {activeConnectionId && (
+          <DetectedServices
+            connectionId={activeConnectionId}
+            connected={connectionStatus === 'connected'}
+            tunnelCount={tunnels.length}
+          />
+        )}
         {tunnels.length === 0 ? (
           <div className="h-full flex flex-col items-center justify-center -mt-20">
             <div className="w-20 h-20 rounded-3xl bg-app-surface/50 border border-app-border/40 flex items-center justify-center mb-6 shadow-sm">
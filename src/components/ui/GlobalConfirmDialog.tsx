@@ -60,6 +60,16 @@ export function GlobalConfirmDialog() {
                         >
                             {dialog.cancelText || 'Cancel'}
                         </button>
+                        {dialog.secondaryText && dialog.onSecondary && (
+                            <button
+                                onClick={() => {
+                                    dialog.onSecondary?.();
+                                }}
+                                className="px-4 py-2 rounded-lg text-sm font-medium text-[var(--color-app-text)] border border-[var(--color-app-border)] hover:bg-[var(--color-app-bg-hover)] transition-colors focus:outline-none focus:ring-2 focus:ring-[var(--color-app-accent)]"
+                            >
+                                {dialog.secondaryText}
+                            </button>
+                        )}
                         <button
                             onClick={() => {
                                 dialog.onConfirm();
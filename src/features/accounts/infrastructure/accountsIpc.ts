@@ -0,0 +1,70 @@
+export interface RemoteUser {
+    name: string;
+    uid: number;
+    gid: number;
+    primaryGroup?: string | null;
+    /** Supplementary groups. */
+    groups: string[];
+    gecos: string;
+    home: string;
+    shell: string;
+    /** Shell isn't `nologin`/`false`. */
+    canLogin: boolean;
+    /** UID below the host's `UID_MIN`: a service account. */
+    system: boolean;
+}
+
+export interface RemoteGroup {
+    name: string;
+    gid: number;
+    members: string[];
+}
+
+export interface LoginRecord {
+    user: string;
+    tty: string;
+    /** Remote address; null for local logins. */
+    host?: string | null;
+    /** Times are as printed by `last`. */
+    started: string;
+    ended?: string | null;
+    stillLoggedIn: boolean;
+}
+
+export interface ActiveSession {
+    user: string;
+    tty: string;
+    since: string;
+    host?: string | null;
+}
+
+export interface AccountsReport {
+    users: RemoteUser[];
+    groups: RemoteGroup[];
+    recentLogins: LoginRecord[];
+    sessions: ActiveSession[];
+    uidMin: number;
+}
+
+export const getAccountsReportIpc = async (connectionId: string): Promise<AccountsReport> =>
+    window.ipcRenderer.invoke('accounts:report', { connectionId });
+
+/**
+ * Creates a login user with a home directory and, optionally, one authorized SSH key. Needs root;
+ * fails with `SUDO_PASSWORD_REQUIRED:` until `sudoPassword` is given on hosts without passwordless sudo.
+ */
+export const addSshUserIpc = async (
+    connectionId: string,
+    username: string,
+    options: { publicKey?: string; shell?: string; sudoPassword?: string } = {},
+): Promise<void> =>
+    window.ipcRenderer.invoke('accounts:addSshUser', { connectionId, username, ...options });
+
+/** Resolves to false when the key was already authorized. */
+export const authorizeSshKeyIpc = async (
+    connectionId: string,
+    username: string,
+    publicKey: string,
+    sudoPassword?: string,
+): Promise<boolean> =>
+    window.ipcRenderer.invoke('accounts:authorizeKey', { connectionId, username, publicKey, sudoPassword });
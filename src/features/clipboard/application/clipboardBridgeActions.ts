@@ -0,0 +1,44 @@
+import type { ToastType } from '../../../store/toastSlice';
+import { pullClipboardIpc, pushClipboardIpc, type ClipboardTransfer } from '../infrastructure/clipboardBridgeIpc';
+
+export type NotifyFn = (type: ToastType, message: string, duration?: number) => void;
+
+function describe(transfer: ClipboardTransfer): string {
+    if (transfer.method === 'file' && transfer.remotePath) {
+        return `${transfer.remotePath} (no clipboard tool usable over SSH)`;
+    }
+    if (transfer.method === 'path' && transfer.remotePath) {
+        return transfer.remotePath;
+    }
+    return `the remote clipboard (${transfer.method})`;
+}
+
+function remoteConnection(connectionId: string | null, notify: NotifyFn): connectionId is string {
+    if (!connectionId || connectionId === 'local') {
+        notify('error', 'Open a remote session first');
+        return false;
+    }
+    return true;
+}
+
+/** Sends the local clipboard to the active host. */
+export async function pushClipboardToHost(connectionId: string | null, notify: NotifyFn): Promise<void> {
+    if (!remoteConnection(connectionId, notify)) return;
+    try {
+        const transfer = await pushClipboardIpc(connectionId);
+        notify('success', `Sent ${transfer.bytes} bytes to ${describe(transfer)}`);
+    } catch (error) {
+        notify('error', `Clipboard push failed: ${error}`);
+    }
+}
+
+/** Copies the active host's clipboard into the local one. */
+export async function pullClipboardFromHost(connectionId: string | null, notify: NotifyFn): Promise<void> {
+    if (!remoteConnection(connectionId, notify)) return;
+    try {
+        const transfer = await pullClipboardIpc(connectionId);
+        notify('success', `Copied ${transfer.bytes} bytes from ${describe(transfer)}`);
+    } catch (error) {
+        notify('error', `Clipboard pull failed: ${error}`);
+    }
+}
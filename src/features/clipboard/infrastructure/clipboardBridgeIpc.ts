@@ -0,0 +1,24 @@
+export interface ClipboardTransfer {
+    /** Remote tool used (`pbcopy`, `xclip`, ...), `file` for ~/.zync/clipboard, or `path`. */
+    method: string;
+    bytes: number;
+    /** Remote file involved, for `file` and `path` transfers. */
+    remotePath?: string | null;
+    /** Pulled text (empty for pushes). */
+    text: string;
+}
+
+/** Sends `text` (or the local clipboard) to the remote clipboard, or to the remote file `path`. */
+export const pushClipboardIpc = async (
+    connectionId: string,
+    options: { text?: string; path?: string } = {},
+): Promise<ClipboardTransfer> =>
+    window.ipcRenderer.invoke('clipboard:pushRemote', {
+        connectionId,
+        text: options.text ?? null,
+        path: options.path ?? null,
+    });
+
+/** Copies the remote clipboard (or the remote file `path`) into the local clipboard. */
+export const pullClipboardIpc = async (connectionId: string, path?: string): Promise<ClipboardTransfer> =>
+    window.ipcRenderer.invoke('clipboard:pullRemote', { connectionId, path: path ?? null });
@@ -0,0 +1,59 @@
+export interface ClipboardHistoryEntry {
+    id: string;
+    text: string;
+    /** Connection whose terminal the text came from; `null` for local terminals. */
+    connectionId?: string | null;
+    copiedAt: number;
+    copyCount: number;
+    pinned: boolean;
+    /** Only the first 256 KiB of the copy were kept. */
+    truncated: boolean;
+}
+
+/** The `clipboardHistory` settings object. */
+export interface ClipboardHistorySettings {
+    enabled: boolean;
+    maxEntries: number;
+    /** Encrypt the stored history with a key kept in the OS keychain. */
+    encrypt: boolean;
+}
+
+export interface ClipboardHistoryStatus {
+    enabled: boolean;
+    /** Copies aren't recorded until incognito is turned off or Zync restarts. */
+    incognito: boolean;
+    encrypted: boolean;
+    count: number;
+}
+
+export const DEFAULT_CLIPBOARD_HISTORY_SETTINGS: ClipboardHistorySettings = {
+    enabled: true,
+    maxEntries: 200,
+    encrypt: false,
+};
+
+/** Records a terminal copy; resolves to `null` when nothing was recorded. */
+export const addClipboardHistoryIpc = async (
+    text: string,
+    connectionId?: string | null,
+): Promise<ClipboardHistoryEntry | null> =>
+    window.ipcRenderer.invoke('clipboardHistory:add', { text, connectionId: connectionId ?? null });
+
+/** Pinned entries first, then newest first. */
+export const listClipboardHistoryIpc = async (query?: string, limit?: number): Promise<ClipboardHistoryEntry[]> =>
+    window.ipcRenderer.invoke('clipboardHistory:list', { query: query || null, limit: limit ?? null });
+
+export const pinClipboardHistoryIpc = async (id: string, pinned: boolean): Promise<ClipboardHistoryEntry> =>
+    window.ipcRenderer.invoke('clipboardHistory:pin', { id, pinned });
+
+export const deleteClipboardHistoryIpc = async (id: string): Promise<void> =>
+    window.ipcRenderer.invoke('clipboardHistory:delete', { id });
+
+export const clearClipboardHistoryIpc = async (keepPinned: boolean): Promise<void> =>
+    window.ipcRenderer.invoke('clipboardHistory:clear', { keepPinned });
+
+export const setClipboardIncognitoIpc = async (enabled: boolean): Promise<void> =>
+    window.ipcRenderer.invoke('clipboardHistory:setIncognito', { enabled });
+
+export const getClipboardHistoryStatusIpc = async (): Promise<ClipboardHistoryStatus> =>
+    window.ipcRenderer.invoke('clipboardHistory:status');
@@ -0,0 +1,42 @@
+import { useModalStore } from '../../../lib/modalRegistry';
+import type { AuthPromptPayload } from '../infrastructure/connectionIpc';
+
+/**
+ * Show keyboard-interactive prompts from the backend, one at a time: hops
+ * connecting in parallel queue behind the prompt on screen.
+ */
+export function registerAuthPromptListener(): () => void {
+    const queue: AuthPromptPayload[] = [];
+    let showing: string | null = null;
+
+    const showNext = () => {
+        const prompt = queue.shift();
+        showing = prompt?.promptId ?? null;
+        if (!prompt) return;
+        useModalStore.getState().open('sshAuthPrompt', {
+            prompt,
+            onFinished: () => {
+                if (showing === prompt.promptId) showNext();
+            },
+        });
+    };
+
+    const onPrompt = (_: unknown, payload: AuthPromptPayload) => {
+        if (!payload?.promptId) return;
+        queue.push(payload);
+        if (!showing) showNext();
+    };
+
+    // The prompt on screen closes itself; queued ones are just dropped.
+    const onDone = (_: unknown, payload: { promptId: string }) => {
+        const index = queue.findIndex(p => p.promptId === payload?.promptId);
+        if (index !== -1) queue.splice(index, 1);
+    };
+
+    window.ipcRenderer.on('ssh:auth-prompt', onPrompt);
+    window.ipcRenderer.on('ssh:auth-prompt-done', onDone);
+    return () => {
+        window.ipcRenderer.off('ssh:auth-prompt', onPrompt);
+        window.ipcRenderer.off('ssh:auth-prompt-done', onDone);
+    };
+}
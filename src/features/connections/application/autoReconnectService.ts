@@ -0,0 +1,104 @@
+import { useAppStore } from '../../../store/useAppStore';
+import { useHostPowerStore } from '../../hostPower/application/hostPowerService';
+import { clearReconnectTunnelIds } from '../../tunnels/application/tunnelReconnectService';
+import { restoreConnectionTunnelsIpc } from '../../tunnels/infrastructure/tunnelRestoreIpc';
+import {
+    cancelReconnectLoop,
+    isReconnectLoopRunning,
+    runReconnectLoop,
+    type ReconnectProgress,
+} from './reconnectLoop';
+
+export const CONNECTION_RECONNECT_EVENT = 'zync:connection-reconnect';
+
+function report(progress: ReconnectProgress): void {
+    window.dispatchEvent(new CustomEvent<ReconnectProgress>(CONNECTION_RECONNECT_EVENT, { detail: progress }));
+}
+
+function connectionLabel(connectionId: string): string {
+    const connection = useAppStore.getState().connections.find((c) => c.id === connectionId);
+    return connection?.name || connection?.host || connectionId;
+}
+
+export function isAutoReconnecting(connectionId: string): boolean {
+    return isReconnectLoopRunning(connectionId);
+}
+
+/** Stop retrying `connectionId`, e.g. because the user disconnected it. */
+export function cancelAutoReconnect(connectionId: string): void {
+    if (cancelReconnectLoop(connectionId)) {
+        report({ connectionId, phase: 'cancelled' });
+    }
+}
+
+/**
+ * Start the tunnels the backend remembered from the drop, plus auto-start ones.
+ * The store's own restart is skipped for these connects so nothing starts twice.
+ */
+async function restoreTunnels(connectionId: string): Promise<number> {
+    clearReconnectTunnelIds(connectionId);
+    try {
+        const summary = await restoreConnectionTunnelsIpc(connectionId);
+        const store = useAppStore.getState();
+        await store.loadTunnels(connectionId);
+        for (const failure of summary.failed) {
+            store.showToast('error', `Tunnel "${failure.name || failure.tunnelId}" failed to start: ${failure.error}`, 6000);
+        }
+        return summary.restored.length;
+    } catch (error) {
+        console.error('[RECONNECT] Failed to restore tunnels:', error);
+        return 0;
+    }
+}
+
+/**
+ * Reconnect a session that dropped on its own: retries with exponential backoff
+ * through the normal connect path, which authenticates again and respawns
+ * terminal tabs, then has the backend restart the tunnels that were running.
+ * Progress goes out as `CONNECTION_RECONNECT_EVENT`; the outcome as a toast.
+ */
+export async function autoReconnect(connectionId: string): Promise<void> {
+    const store = useAppStore.getState();
+    if (
+        connectionId === 'local'
+        || isReconnectLoopRunning(connectionId)
+        || store.settings.terminal.autoReconnect === false
+        || useHostPowerStore.getState().watches[connectionId]
+    ) {
+        return;
+    }
+    const label = connectionLabel(connectionId);
+    store.showToast('info', `Lost the connection to ${label}. Reconnecting…`);
+
+    const outcome = await runReconnectLoop(connectionId, {
+        sleep: (ms) => new Promise((resolve) => setTimeout(resolve, ms)),
+        random: Math.random,
+        now: Date.now,
+        status: () => useAppStore.getState().connections.find((c) => c.id === connectionId) ?? null,
+        connect: () => useAppStore.getState().connect(connectionId, {
+            skipVaultPrompt: true,
+            onDuplicate: 'new-channel',
+            quiet: true,
+            restartTunnels: false,
+        }),
+        restoreTunnels: () => restoreTunnels(connectionId),
+        report,
+    });
+
+    const after = useAppStore.getState();
+    if (outcome?.kind === 'connected') {
+        const { restoredTunnels } = outcome;
+        after.showToast(
+            'success',
+            restoredTunnels > 0
+                ? `Reconnected to ${label} and restored ${restoredTunnels} tunnel${restoredTunnels === 1 ? '' : 's'}`
+                : `Reconnected to ${label}`,
+        );
+    } else if (outcome?.kind === 'failed') {
+        after.showToast(
+            'error',
+            `Couldn't reconnect to ${label}${outcome.error ? `: ${outcome.error}` : ''}. Connect again when it is reachable.`,
+            10000,
+        );
+    }
+}
@@ -0,0 +1,41 @@
+import { useModalStore } from '../../../lib/modalRegistry';
+import type { HostKeyPromptPayload } from '../infrastructure/connectionIpc';
+
+/**
+ * Show host key prompts from the backend one at a time, like the
+ * keyboard-interactive prompts: hops connecting in parallel wait in line.
+ */
+export function registerHostKeyPromptListener(): () => void {
+    const queue: HostKeyPromptPayload[] = [];
+    let showing: string | null = null;
+
+    const showNext = () => {
+        const prompt = queue.shift();
+        showing = prompt?.promptId ?? null;
+        if (!prompt) return;
+        useModalStore.getState().open('hostKeyPrompt', {
+            prompt,
+            onFinished: () => {
+                if (showing === prompt.promptId) showNext();
+            },
+        });
+    };
+
+    const onPrompt = (_: unknown, payload: HostKeyPromptPayload) => {
+        if (!payload?.promptId) return;
+        queue.push(payload);
+        if (!showing) showNext();
+    };
+
+    const onDone = (_: unknown, payload: { promptId: string }) => {
+        const index = queue.findIndex(p => p.promptId === payload?.promptId);
+        if (index !== -1) queue.splice(index, 1);
+    };
+
+    window.ipcRenderer.on('ssh:host-key-prompt', onPrompt);
+    window.ipcRenderer.on('ssh:host-key-prompt-done', onDone);
+    return () => {
+        window.ipcRenderer.off('ssh:host-key-prompt', onPrompt);
+        window.ipcRenderer.off('ssh:host-key-prompt-done', onDone);
+    };
+}
@@ -0,0 +1,27 @@
+import { useAppStore } from '../../../store/useAppStore';
+
+type LocalCommandPayload = {
+    connectionId: string;
+    command: string;
+    success: boolean;
+    exitCode?: number;
+    error?: string;
+    output: string;
+};
+
+/** Surface failed ssh_config `LocalCommand` runs; the connection itself stays up. */
+export function registerLocalCommandListener(): () => void {
+    const handler = (_: unknown, payload: LocalCommandPayload) => {
+        if (!payload || payload.success) return;
+        const store = useAppStore.getState();
+        const connection = store.connections.find(c => c.id === payload.connectionId);
+        const label = connection?.name || connection?.host || payload.connectionId;
+        const detail = payload.output.trim().split('\n').pop() || payload.error || 'failed';
+        store.showToast('error', `LocalCommand for ${label} failed: ${detail}`, 6000);
+    };
+
+    window.ipcRenderer.on('connection:local-command', handler);
+    return () => {
+        window.ipcRenderer.off('connection:local-command', handler);
+    };
+}
@@ -0,0 +1,113 @@
+export const MAX_RECONNECT_ATTEMPTS = 8;
+const BASE_DELAY_MS = 1000;
+const MAX_DELAY_MS = 60_000;
+
+export type ReconnectProgress =
+    | { connectionId: string; phase: 'waiting'; attempt: number; maxAttempts: number; retryAt: number }
+    | { connectionId: string; phase: 'connecting'; attempt: number; maxAttempts: number }
+    | { connectionId: string; phase: 'connected'; attempt: number; restoredTunnels: number }
+    | { connectionId: string; phase: 'failed'; attempt: number; error?: string }
+    | { connectionId: string; phase: 'cancelled' };
+
+export type ReconnectOutcome =
+    | { kind: 'connected'; attempts: number; restoredTunnels: number }
+    | { kind: 'failed'; attempts: number; error?: string }
+    /** Cancelled, deleted, or connected/connecting by some other path meanwhile. */
+    | { kind: 'stopped' };
+
+export interface ReconnectLoopDeps {
+    sleep: (ms: number) => Promise<void>;
+    /** 0..1, spreads the retries. */
+    random: () => number;
+    now: () => number;
+    /** The connection's status right now; null once it is deleted. */
+    status: () => { status: string; lastError?: string } | null;
+    connect: () => Promise<void>;
+    /** Bring the tunnels back after a successful connect; resolves to how many came back. */
+    restoreTunnels: () => Promise<number>;
+    report: (progress: ReconnectProgress) => void;
+}
+
+/** Connection ids with a retry loop running, and whether it has been called off. */
+const running = new Map<string, { cancelled: boolean }>();
+
+/**
+ * Wait before attempt `attempt` (from 0): doubling from one second up to a minute,
+ * with the upper half spread by `jitter` (0..1) so hosts behind the same link
+ * don't all retry in lockstep.
+ */
+export function reconnectDelayMs(attempt: number, jitter: number): number {
+    const ceiling = Math.min(MAX_DELAY_MS, BASE_DELAY_MS * 2 ** Math.min(attempt, 16));
+    const half = ceiling / 2;
+    return Math.round(half + half * Math.min(1, Math.max(0, jitter)));
+}
+
+/** Authentication and vault problems don't fix themselves; stop and let the user look. */
+export function needsUser(error: string | undefined): boolean {
+    return !!error && /authentication failed|permission denied|vault/i.test(error);
+}
+
+export function isReconnectLoopRunning(connectionId: string): boolean {
+    return running.has(connectionId);
+}
+
+/** Call off the loop for `connectionId`; false when none was running. */
+export function cancelReconnectLoop(connectionId: string): boolean {
+    const loop = running.get(connectionId);
+    if (!loop || loop.cancelled) return false;
+    loop.cancelled = true;
+    return true;
+}
+
+/**
+ * Retry `deps.connect` with `reconnectDelayMs` backoff until it connects, runs out
+ * of attempts, hits an error only the user can fix, or is cancelled. `null` when
+ * a loop for `connectionId` is already running.
+ */
+export async function runReconnectLoop(
+    connectionId: string,
+    deps: ReconnectLoopDeps,
+): Promise<ReconnectOutcome | null> {
+    if (running.has(connectionId)) return null;
+    const loop = { cancelled: false };
+    running.set(connectionId, loop);
+
+    let lastError: string | undefined;
+    let attempts = 0;
+    try {
+        for (let attempt = 0; attempt < MAX_RECONNECT_ATTEMPTS; attempt += 1) {
+            const delay = reconnectDelayMs(attempt, deps.random());
+            deps.report({
+                connectionId,
+                phase: 'waiting',
+                attempt: attempt + 1,
+                maxAttempts: MAX_RECONNECT_ATTEMPTS,
+                retryAt: deps.now() + delay,
+            });
+            await deps.sleep(delay);
+
+            const before = deps.status();
+            // Deleted, or the user reconnected or disconnected in the meantime.
+            if (loop.cancelled || !before) return { kind: 'stopped' };
+            if (before.status === 'connected' || before.status === 'connecting') return { kind: 'stopped' };
+
+            attempts = attempt + 1;
+            deps.report({ connectionId, phase: 'connecting', attempt: attempts, maxAttempts: MAX_RECONNECT_ATTEMPTS });
+            await deps.connect();
+            if (loop.cancelled) return { kind: 'stopped' };
+
+            const after = deps.status();
+            if (after?.status === 'connected') {
+                const restoredTunnels = await deps.restoreTunnels();
+                deps.report({ connectionId, phase: 'connected', attempt: attempts, restoredTunnels });
+                return { kind: 'connected', attempts, restoredTunnels };
+            }
+            lastError = after?.lastError;
+            if (needsUser(lastError)) break;
+        }
+        deps.report({ connectionId, phase: 'failed', attempt: attempts, error: lastError });
+        return { kind: 'failed', attempts, error: lastError };
+    } finally {
+        running.delete(connectionId);
+    }
+}
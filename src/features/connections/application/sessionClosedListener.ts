@@ -0,0 +1,23 @@
+import { useAppStore } from '../../../store/useAppStore';
+
+type SessionClosedPayload = {
+    connectionId: string;
+    reason: string;
+};
+
+/** The backend closed a session under its `sessionLifetime`; mirror it as a disconnect. */
+export function registerSessionClosedListener(): () => void {
+    const handler = (_: unknown, payload: SessionClosedPayload) => {
+        if (!payload?.connectionId) return;
+        const store = useAppStore.getState();
+        const connection = store.connections.find(c => c.id === payload.connectionId);
+        const label = connection?.name || connection?.host || payload.connectionId;
+        void store.disconnect(payload.connectionId);
+        store.showToast('info', `Closed the session to ${label}: ${payload.reason}`);
+    };
+
+    window.ipcRenderer.on('connection:session-closed', handler);
+    return () => {
+        window.ipcRenderer.off('connection:session-closed', handler);
+    };
+}
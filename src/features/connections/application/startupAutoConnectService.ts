@@ -0,0 +1,81 @@
+import type { AutoConnectPlanPayload } from '../infrastructure/connectionIpc.js';
+
+export type AutoConnectStatus = 'pending' | 'connecting' | 'connected' | 'failed' | 'skipped';
+
+export interface AutoConnectProgress {
+    connectionId: string;
+    status: AutoConnectStatus;
+    reason?: string;
+}
+
+export interface AutoConnectSummary {
+    connected: number;
+    failed: number;
+    skipped: number;
+}
+
+export interface RunAutoConnectPlanOptions {
+    plan: AutoConnectPlanPayload;
+    /** Establish the session (and its auto-start tunnels); errors are reported by the caller. */
+    connect: (connectionId: string) => Promise<void>;
+    isConnected: (connectionId: string) => boolean;
+    onProgress: (progress: AutoConnectProgress) => void;
+    concurrency?: number;
+}
+
+const DEFAULT_AUTO_CONNECT_CONCURRENCY = 4;
+
+/**
+ * Connect each wave of the plan concurrently (bounded), waiting for a wave to
+ * settle before starting the next. Hosts whose jump host failed are skipped.
+ */
+export const runAutoConnectPlan = async ({
+    plan,
+    connect,
+    isConnected,
+    onProgress,
+    concurrency = DEFAULT_AUTO_CONNECT_CONCURRENCY,
+}: RunAutoConnectPlanOptions): Promise<AutoConnectSummary> => {
+    const summary: AutoConnectSummary = { connected: 0, failed: 0, skipped: 0 };
+    const unavailable = new Set<string>();
+
+    const skip = (connectionId: string, reason: string) => {
+        unavailable.add(connectionId);
+        summary.skipped += 1;
+        onProgress({ connectionId, status: 'skipped', reason });
+    };
+
+    plan.skipped.forEach(({ connectionId, reason }) => skip(connectionId, reason));
+
+    for (const wave of plan.waves) {
+        wave.forEach(({ connectionId }) => onProgress({ connectionId, status: 'pending' }));
+        const queue = [...wave];
+        const worker = async () => {
+            for (let entry = queue.shift(); entry; entry = queue.shift()) {
+                const { connectionId, dependsOn } = entry;
+                if (dependsOn && unavailable.has(dependsOn)) {
+                    skip(connectionId, 'Jump host did not connect');
+                    continue;
+                }
+                onProgress({ connectionId, status: 'connecting' });
+                try {
+                    await connect(connectionId);
+                } catch (error) {
+                    console.error(`[AutoConnect] ${connectionId} failed:`, error);
+                }
+                if (isConnected(connectionId)) {
+                    summary.connected += 1;
+                    onProgress({ connectionId, status: 'connected' });
+                } else {
+                    unavailable.add(connectionId);
+                    summary.failed += 1;
+                    onProgress({ connectionId, status: 'failed' });
+                }
+            }
+        };
+        const workers = Math.max(1, Math.min(concurrency, wave.length));
+        await Promise.all(Array.from({ length: workers }, worker));
+    }
+
+    return summary;
+};
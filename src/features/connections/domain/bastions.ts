@@ -0,0 +1,31 @@
+import type { Connection } from './types.js';
+
+export interface BastionGroup {
+    bastion: Connection;
+    /** Connections jumping through `bastion`, directly or via another jump host. */
+    dependents: Connection[];
+}
+
+/** The bastions that `inUse` connections jump through, each with the connections behind it. */
+export const groupByBastion = (
+    connections: Connection[],
+    inUse: (connection: Connection) => boolean,
+): BastionGroup[] => {
+    const byId = new Map(connections.map((connection) => [connection.id, connection]));
+    const groups = new Map<string, BastionGroup>();
+
+    for (const connection of connections) {
+        if (!connection.jumpServerId || !inUse(connection)) continue;
+        const visited = new Set([connection.id]);
+        let jump = byId.get(connection.jumpServerId);
+        while (jump && !visited.has(jump.id)) {
+            visited.add(jump.id);
+            const group = groups.get(jump.id) ?? { bastion: jump, dependents: [] };
+            group.dependents.push(connection);
+            groups.set(jump.id, group);
+            jump = jump.jumpServerId ? byId.get(jump.jumpServerId) : undefined;
+        }
+    }
+
+    return [...groups.values()];
+};
@@ -1,4 +1,4 @@
-import type { Connection } from './types.js';
+import type { Connection, SessionLifetime, SshTransportOptions, StrictHostKeyChecking } from './types.js';
 
 export interface ConnectAuthMethodPassword {
     type: 'Password';
@@ -31,6 +31,13 @@ export interface ConnectConfig {
     username: string;
     auth_method: ConnectAuthMethod;
     jump_host: ConnectConfig | null;
+    strict_host_key_checking?: StrictHostKeyChecking;
+    encoding?: string;
+    locale?: string;
+    ssh_options?: SshTransportOptions;
+    host_key_alias?: string;
+    user_known_hosts_file?: string;
+    session_lifetime?: SessionLifetime;
 }
 
 type ConnectionWithLegacyAuthFields = Connection & {
@@ -133,6 +140,15 @@ export const buildConnectConfigResult = (
         username: connection.username,
         auth_method: authResult.auth,
         jump_host: null,
+        ...(connection.strictHostKeyChecking
+            ? { strict_host_key_checking: connection.strictHostKeyChecking }
+            : {}),
+        ...(connection.encoding ? { encoding: connection.encoding } : {}),
+        ...(connection.locale ? { locale: connection.locale } : {}),
+        ...(connection.sshOptions ? { ssh_options: connection.sshOptions } : {}),
+        ...(connection.hostKeyAlias ? { host_key_alias: connection.hostKeyAlias } : {}),
+        ...(connection.userKnownHostsFile ? { user_known_hosts_file: connection.userKnownHostsFile } : {}),
+        ...(connection.sessionLifetime ? { session_lifetime: connection.sessionLifetime } : {}),
     };
 
     if (connection.jumpServerId) {
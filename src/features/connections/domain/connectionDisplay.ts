@@ -22,6 +22,11 @@ export function isLikelyIpAddress(host: string): boolean {
     return trimmed.includes(':') && /^[0-9a-f:.]+$/i.test(trimmed);
 }
 
+/** `.onion` addresses, which connect through the local Tor proxy. */
+export function isOnionHost(host: string | undefined): boolean {
+    return /[^.]\.onion\.?$/i.test(host?.trim() ?? '');
+}
+
 function formatUserHostPair(username: string, host: string): string {
     if (username && host) return `${username}@${host}`;
     return username || host;
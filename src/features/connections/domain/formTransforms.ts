@@ -1,4 +1,4 @@
-import type { Connection } from './types.js';
+import type { Connection, SshTransportOptions, WebSocketTransport } from './types.js';
 import { normalizeFolderPath, normalizeTags, normalizeText, parsePort } from './normalization.js';
 
 export type ConnectionAuthMode = 'password' | 'key' | 'vault';
@@ -16,10 +16,37 @@ interface ToBackendConfig {
         | { type: 'PrivateKey'; key_path: string; passphrase: null }
         | { type: 'VaultRef'; item_id: string; credential_id?: string };
     jump_host: ToBackendConfig | null;
+    host_key_alias?: string;
+    user_known_hosts_file?: string;
+    session_lifetime?: Connection['sessionLifetime'];
+    ssh_options?: SshTransportOptions;
 }
 
 type ConfigCandidate = Connection | ConnectionFormDraft;
 
+/** `Name: value` per line, as edited in the connection form. */
+export const parseWebSocketHeaders = (text: string): NonNullable<WebSocketTransport['headers']> =>
+    text.split('\n').flatMap((line) => {
+        const separator = line.indexOf(':');
+        if (separator <= 0) return [];
+        const name = line.slice(0, separator).trim();
+        return name ? [{ name, value: line.slice(separator + 1).trim() }] : [];
+    });
+
+export const formatWebSocketHeaders = (headers: WebSocketTransport['headers']): string =>
+    (headers ?? []).map(({ name, value }) => `${name}: ${value}`).join('\n');
+
+/** Drop a WebSocket bridge left without a URL, and options left empty. */
+const normalizeSshOptions = (options: SshTransportOptions | undefined): SshTransportOptions | undefined => {
+    if (!options) return undefined;
+    const { websocket, ...rest } = options;
+    const url = normalizeText(websocket?.url);
+    const next: SshTransportOptions = url
+        ? { ...rest, websocket: { url, ...(websocket?.headers?.length ? { headers: websocket.headers } : {}) } }
+        : rest;
+    return Object.values(next).some((value) => value !== undefined) ? next : undefined;
+};
+
 const requireNormalizedText = (value: unknown, fieldName: string): string => {
     const normalized = normalizeText(typeof value === 'string' ? value : String(value ?? ''));
     if (!normalized) {
@@ -97,6 +124,10 @@ const toBackendConfig = (
         username,
         auth_method,
         jump_host: null,
+        host_key_alias: normalizeText(candidate.hostKeyAlias) || undefined,
+        user_known_hosts_file: normalizeText(candidate.userKnownHostsFile) || undefined,
+        session_lifetime: candidate.sessionLifetime,
+        ssh_options: normalizeSshOptions(candidate.sshOptions),
     };
 };
 
@@ -153,6 +184,14 @@ export const buildConnectionSavePayload = ({
         theme: formData.theme,
         folder: normalizeFolderPath(formData.folder || ''),
         tags: normalizeTags(formData.tags || []),
+        encoding: normalizeText(formData.encoding) || undefined,
+        locale: normalizeText(formData.locale) || undefined,
+        sshOptions: normalizeSshOptions(formData.sshOptions),
+        hostKeyAlias: normalizeText(formData.hostKeyAlias) || undefined,
+        userKnownHostsFile: normalizeText(formData.userKnownHostsFile) || undefined,
+        sessionLifetime: formData.sessionLifetime && formData.sessionLifetime !== 'manual'
+            ? formData.sessionLifetime
+            : undefined,
     };
 };
 
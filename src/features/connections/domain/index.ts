@@ -11,3 +11,4 @@ export * from './connectionDisplay.js';
 export * from './hostCatalog.js';
 export * from './hostMaterialize.js';
 export * from './types.js';
+export * from './bastions.js';
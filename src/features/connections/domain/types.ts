@@ -16,6 +16,45 @@ export interface CredentialRef {
     purpose: CredentialPurpose;
 }
 
+/** OpenSSH `StrictHostKeyChecking` semantics; the backend defaults to `accept-new`. */
+export type StrictHostKeyChecking = 'yes' | 'accept-new' | 'no';
+
+/** When the SSH session closes on its own: never, with its last terminal, with its last tunnel, or once nothing uses it. */
+export type SessionLifetime = 'manual' | 'withTerminals' | 'withTunnels' | 'untilIdle';
+
+/** A WebSocket bridge relaying the SSH byte stream, for networks that only let HTTPS out. */
+export interface WebSocketTransport {
+    /** `wss://` (or `ws://`) URL of the bridge. */
+    url: string;
+    /** Sent with the upgrade request, e.g. a token the bridge checks. */
+    headers?: { name: string; value: string }[];
+}
+
+/** Zero-trust proxy logged in to before SSH starts; unset names default to the connection's host. */
+export type AccessProxy =
+    | { provider: 'cloudflare'; hostname?: string | null }
+    | { provider: 'gcpIap'; instance?: string | null; zone: string; project?: string | null };
+
+/** Per-host ssh_config settings: timeouts, keep-alives, compression, hostname canonicalization and `LocalCommand`, plus the WebSocket bridge or zero-trust proxy. */
+export interface SshTransportOptions {
+    connectTimeoutSecs?: number;
+    /** `0` turns keep-alives off. */
+    serverAliveIntervalSecs?: number;
+    serverAliveCountMax?: number;
+    compression?: boolean;
+    canonicalizeHostname?: 'no' | 'yes' | 'always';
+    canonicalDomains?: string[];
+    canonicalizeMaxDots?: number;
+    canonicalizeFallbackLocal?: boolean;
+    /** Run locally after connecting, only when `permitLocalCommand` is set. */
+    localCommand?: string;
+    permitLocalCommand?: boolean;
+    /** Connect through this bridge instead of TCP; ignored behind a jump host. */
+    websocket?: WebSocketTransport;
+    /** Cloudflare Access or GCP IAP; ignored behind a jump host. */
+    accessProxy?: AccessProxy;
+}
+
 export interface Connection {
     id: string;
     name: string;
@@ -29,6 +68,7 @@ export interface Connection {
     status: 'disconnected' | 'connecting' | 'connected' | 'error';
     lastError?: string;
     jumpServerId?: string;
+    strictHostKeyChecking?: StrictHostKeyChecking;
     lastConnected?: number;
     icon?: string;
     folder?: string;
@@ -36,6 +76,21 @@ export interface Connection {
     tags?: string[];
     createdAt?: number;
     isFavorite?: boolean;
+    /** Connect when the app launches. Device-local; not synced. */
+    autoConnect?: boolean;
+    /** Remote character encoding label (`latin1`, `GBK`, …); unset means UTF-8. */
+    encoding?: string;
+    /** Locale exported as `LANG`/`LC_ALL` in terminals, e.g. `ja_JP.eucJP`. */
+    locale?: string;
+    sshOptions?: SshTransportOptions;
+    /** OpenSSH `HostKeyAlias`: the name host keys are recorded under instead of host:port. */
+    hostKeyAlias?: string;
+    /** OpenSSH `UserKnownHostsFile`: space-separated files replacing `~/.ssh/known_hosts`. */
+    userKnownHostsFile?: string;
+    /** Unset means `manual`: the session stays open until disconnected. */
+    sessionLifetime?: SessionLifetime;
+    /** Set to `keychain` when the password is stored outside `connections.json` (`storage.splitSecrets`). */
+    secretRef?: string;
     pinnedFeatures?: string[];
     homePath?: string;
 }
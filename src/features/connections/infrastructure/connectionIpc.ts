@@ -1,3 +1,5 @@
+import type { SessionLifetime, SshTransportOptions } from '../domain/types.js';
+
 export interface AuthMethodPassword {
     type: 'Password';
     password: string;
@@ -25,6 +27,15 @@ export interface ConnectionConfigPayload {
     username: string;
     auth_method: AuthMethodPayload;
     jump_host: ConnectionConfigPayload | null;
+    strict_host_key_checking?: 'yes' | 'accept-new' | 'no';
+    /** Remote character encoding label; terminals transcode to and from it. */
+    encoding?: string;
+    /** Exported as `LANG`/`LC_ALL` in terminals. */
+    locale?: string;
+    ssh_options?: SshTransportOptions;
+    host_key_alias?: string;
+    user_known_hosts_file?: string;
+    session_lifetime?: SessionLifetime;
 }
 
 export interface ConnectResponsePayload {
@@ -32,8 +43,31 @@ export interface ConnectResponsePayload {
     message: string;
     term_id?: string | null;
     detected_os?: string | null;
+    /** Set when the user chose to switch to this connection's live session instead. */
+    focus_connection_id?: string | null;
+}
+
+/** How `ssh:connect` handles a target another connection already has a live session to. */
+export type DuplicateSessionChoice = 'new-channel' | 'separate-connection' | 'focus-existing';
+
+export interface DuplicateSessionPayload {
+    existingConnectionId: string;
+    existingName: string;
+    target: string;
 }
 
+/** Prefix of the error `ssh:connect` rejects with when no `DuplicateSessionChoice` was given. */
+export const DUPLICATE_SESSION_PREFIX = 'DUPLICATE_SESSION:';
+
+export const parseDuplicateSessionError = (message: string): DuplicateSessionPayload | null => {
+    if (!message.startsWith(DUPLICATE_SESSION_PREFIX)) return null;
+    try {
+        return JSON.parse(message.slice(DUPLICATE_SESSION_PREFIX.length)) as DuplicateSessionPayload;
+    } catch {
+        return null;
+    }
+};
+
 export interface ImportedConnectionPayload {
     id: string;
     name: string;
@@ -44,6 +78,9 @@ export interface ImportedConnectionPayload {
     jumpServerAlias?: string;
     jumpServerId?: string;
     aliases?: string[];
+    sshOptions?: SshTransportOptions;
+    hostKeyAlias?: string;
+    userKnownHostsFile?: string;
 }
 export type SshImportSourceType = 'default_ssh' | 'file' | 'text';
 export type SshImportSourceRequest =
@@ -71,8 +108,11 @@ export const importSshConfigBySourceIpc = async (
 export const internalizeImportedConnectionsIpc = async (connections: ImportedConnectionPayload[]): Promise<ImportedConnectionPayload[]> =>
     window.ipcRenderer.invoke('ssh:internalize-connections', connections);
 
-export const connectIpc = async (config: ConnectionConfigPayload): Promise<ConnectResponsePayload> =>
-    window.ipcRenderer.invoke('ssh:connect', config);
+export const connectIpc = async (
+    config: ConnectionConfigPayload,
+    onDuplicate?: DuplicateSessionChoice,
+): Promise<ConnectResponsePayload> =>
+    window.ipcRenderer.invoke('ssh:connect', config, onDuplicate);
 
 export const disconnectIpc = async (connectionId: string): Promise<void> =>
     window.ipcRenderer.invoke('ssh:disconnect', connectionId);
@@ -80,7 +120,115 @@ export const disconnectIpc = async (connectionId: string): Promise<void> =>
 export const transportLostIpc = async (connectionId: string): Promise<void> =>
     window.ipcRenderer.invoke('ssh:transportLost', connectionId);
 
+export type SessionConsumer = 'terminal' | 'tunnel' | 'sftp' | 'exec';
+
+/** Count as a user of the connection's session (see `sessionLifetime`); returns the key to release. */
+export const sessionHoldIpc = async (connectionId: string, consumer: SessionConsumer): Promise<string> =>
+    window.ipcRenderer.invoke('session:hold', { connectionId, consumer });
+
+export const sessionReleaseIpc = async (key: string, consumer: SessionConsumer): Promise<void> =>
+    window.ipcRenderer.invoke('session:release', { key, consumer });
+
 export const disconnectVaultBackedIpc = async (): Promise<string[]> =>
     window.ipcRenderer.invoke('ssh:disconnectVaultBacked');
+
+/** Abort an in-flight connect; resolves `false` when none was running. */
+export const cancelConnectIpc = async (connectionId: string): Promise<boolean> =>
+    window.ipcRenderer.invoke('ssh:cancelConnect', { connectionId });
+
+/** Prefix of the error a connect rejects with after `cancelConnectIpc`. */
+export const CONNECT_CANCELLED_PREFIX = 'CANCELLED:';
+export interface PlannedAutoConnectPayload {
+    connectionId: string;
+    dependsOn?: string | null;
+}
+
+export interface AutoConnectPlanPayload {
+    /** Connections in a wave connect concurrently; later waves jump through earlier ones. */
+    waves: PlannedAutoConnectPayload[][];
+    skipped: { connectionId: string; reason: string }[];
+}
+
+export const getAutoConnectPlanIpc = async (): Promise<AutoConnectPlanPayload> =>
+    window.ipcRenderer.invoke('startup:autoConnectPlan');
+
+export interface WorkspaceRestorePlanPayload {
+    savedAtMs: number;
+    /** Connections that were live when the app closed, planned like auto-connect. */
+    connections: AutoConnectPlanPayload;
+    /** Tunnels that were running; start them once their connection is back. */
+    tunnels: { tunnelId: string; connectionId: string; name: string }[];
+    skippedTunnels: { tunnelId: string; name?: string | null; reason: string }[];
+}
+
+/** `null` when nothing was live at the last shutdown. */
+export const getWorkspaceRestorePlanIpc = async (): Promise<WorkspaceRestorePlanPayload | null> =>
+    window.ipcRenderer.invoke('startup:workspaceRestorePlan');
+
+export const clearWorkspaceRestoreIpc = async (): Promise<void> =>
+    window.ipcRenderer.invoke('startup:workspaceClear');
+
 export const getRemoteCwdIpc = async (connectionId: string): Promise<string> =>
     window.ipcRenderer.invoke('fs:cwd', connectionId);
+
+export interface AuthPromptPayload {
+    promptId: string;
+    hop: {
+        connectionId: string;
+        name: string;
+        /** `user@host:port`. */
+        endpoint: string;
+        /** Set when this hop is the jump host for another connection. */
+        jumpFor?: string | null;
+    };
+    title: string;
+    instructions: string;
+    fields: { prompt: string; echo: boolean }[];
+    /** 1 for the first round; later rounds follow a rejected answer. */
+    attempt: number;
+}
+
+/** Answer a keyboard-interactive prompt in field order; `null` cancels that hop's login. */
+export const respondAuthPromptIpc = async (promptId: string, responses: string[] | null): Promise<void> =>
+    window.ipcRenderer.invoke('ssh:authRespond', { promptId, responses });
+
+export interface HostKeyPromptPayload {
+    promptId: string;
+    connectionId: string;
+    /** Host as recorded in known_hosts: `host` or `[host]:port`. */
+    endpoint: string;
+    keyType: string;
+    /** `SHA256:...` of the key the server presented. */
+    fingerprint: string;
+    /** The differing recorded key; null when the host has no key on record. */
+    recordedFingerprint: string | null;
+    /** `path:line` of that recorded key. */
+    recordedAt: string | null;
+}
+
+/** Settle a host key prompt: `true` trusts and records the key, `false` rejects it. */
+export const respondHostKeyPromptIpc = async (promptId: string, trust: boolean): Promise<void> =>
+    window.ipcRenderer.invoke('ssh:hostKeyRespond', { promptId, trust });
+
+export interface BastionSessionPayload {
+    key: string;
+    /** The saved connection used as the bastion. */
+    connectionId: string;
+    name: string;
+    /** `user@host:port`. */
+    endpoint: string;
+    status: 'connecting' | 'connected' | 'closed';
+    /** Open connections reached through this bastion, directly or via another hop. */
+    dependents: string[];
+}
+
+export const listBastionSessionsIpc = async (): Promise<BastionSessionPayload[]> =>
+    window.ipcRenderer.invoke('jump:sessions');
+
+/** Log in to the bastion afresh on the next connect; returns the connections behind it. */
+export const resetBastionSessionIpc = async (key: string): Promise<string[]> =>
+    window.ipcRenderer.invoke('jump:reset', { key });
+
+/** `proxy` is the local Tor SOCKS proxy `.onion` connections will use, or null when Tor isn't running. */
+export const getTorStatusIpc = async (): Promise<{ proxy: string | null }> =>
+    window.ipcRenderer.invoke('tor:status');
@@ -0,0 +1,43 @@
+/** On Windows hosts an Administrator account reports `root`, any other `not-administrator`. */
+export type SudoAccess = 'root' | 'nopasswd' | 'password' | 'denied' | 'missing' | 'not-administrator' | 'unknown';
+
+export type HostFeature = 'serviceControl' | 'processKill' | 'packageStatus' | 'listeningPorts';
+
+/** `constrained` for BusyBox userlands (routers, NAS boxes, IoT) or connections tagged `embedded`. */
+export type HostProfile = 'standard' | 'constrained';
+
+export type FeatureAvailability =
+    | { status: 'available' }
+    | { status: 'requires-sudo'; reason: string }
+    /** Any one of `tools` would do. */
+    | { status: 'tool-missing'; tools: string[] };
+
+export type FeatureReport = FeatureAvailability & { feature: HostFeature };
+
+export interface HostCapabilityReport {
+    connectionId: string;
+    user?: string | null;
+    uid?: number | null;
+    sudo: SudoAccess;
+    tools: string[];
+    windows: boolean;
+    busybox: boolean;
+    profile: HostProfile;
+    features: FeatureReport[];
+}
+
+/** Cached per connection on the backend; pass `refresh` after changing sudoers or installing tools. */
+export const getHostCapabilitiesIpc = async (connectionId: string, refresh = false): Promise<HostCapabilityReport> =>
+    window.ipcRenderer.invoke('host:capabilities', { connectionId, refresh });
+
+/** Status line for a feature that can't run, e.g. "Requires sudo" or "Tool missing: systemctl or service". */
+export function describeFeature(availability: FeatureAvailability): string | null {
+    switch (availability.status) {
+        case 'available':
+            return null;
+        case 'requires-sudo':
+            return `Requires sudo: ${availability.reason}`;
+        case 'tool-missing':
+            return `Tool missing: needs ${availability.tools.join(' or ')}`;
+    }
+}
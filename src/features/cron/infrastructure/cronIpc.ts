@@ -0,0 +1,69 @@
+export type CrontabKind = 'user' | 'system';
+
+export interface CronField {
+    /** As written, e.g. `0-30/5` or `mon-fri`. */
+    expression: string;
+    /** Every value the field matches, ascending; Sunday is 0. */
+    values: number[];
+    any: boolean;
+}
+
+export type CronSchedule =
+    | {
+        kind: 'fields';
+        minute: CronField;
+        hour: CronField;
+        dayOfMonth: CronField;
+        month: CronField;
+        dayOfWeek: CronField;
+    }
+    /** `@reboot`, `@daily`, ... */
+    | { kind: 'special'; name: string };
+
+export interface CronEntry {
+    /** 1-based line in the crontab. */
+    line: number;
+    schedule: CronSchedule;
+    /** Run-as user; system crontabs only. */
+    user?: string | null;
+    command: string;
+}
+
+export interface ParsedCrontab {
+    entries: CronEntry[];
+    variables: { line: number; name: string; value: string }[];
+    errors: { line: number; message: string }[];
+}
+
+export interface Crontab extends ParsedCrontab {
+    kind: CrontabKind;
+    /** File path of a system crontab. */
+    path?: string | null;
+    /** False for system files the login user can't read. */
+    readable: boolean;
+    content: string;
+}
+
+export interface CronWriteResult {
+    /** Where the previous version was saved on the host. */
+    backupPath?: string | null;
+}
+
+/** The login user's crontab first, then `/etc/crontab` and `/etc/cron.d/*`. */
+export const listCrontabsIpc = async (connectionId: string): Promise<Crontab[]> =>
+    window.ipcRenderer.invoke('cron:list', { connectionId });
+
+/** Local parse for live validation while editing. */
+export const parseCrontabIpc = async (content: string, kind: CrontabKind): Promise<ParsedCrontab> =>
+    window.ipcRenderer.invoke('cron:parse', { content, kind });
+
+/**
+ * Validates, backs up and replaces a crontab. Pass `path` for a system crontab; that needs root and
+ * fails with `SUDO_PASSWORD_REQUIRED:` until `sudoPassword` is given on hosts without passwordless sudo.
+ */
+export const writeCrontabIpc = async (
+    connectionId: string,
+    content: string,
+    options: { path?: string; sudoPassword?: string } = {},
+): Promise<CronWriteResult> =>
+    window.ipcRenderer.invoke('cron:write', { connectionId, content, ...options });
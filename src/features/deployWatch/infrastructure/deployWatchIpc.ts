@@ -0,0 +1,64 @@
+export interface DeployWatchConfig {
+    connectionId: string;
+    localDir: string;
+    remoteDir: string;
+    /** Runs after a successful upload, as given, in the login directory. */
+    command?: string | null;
+    /** Quiet period after the last change before deploying (default 800). */
+    debounceMs?: number | null;
+    /** Path component names (`.git`) or `*.ext` suffixes; replaces the defaults. */
+    exclude?: string[] | null;
+}
+
+export interface DeployRun {
+    startedAtMs: number;
+    durationMs: number;
+    success: boolean;
+    /** Relative paths uploaded, capped; `fileCount` is exact. */
+    files: string[];
+    fileCount: number;
+    bytes: number;
+    uploadError?: string | null;
+    commandExitCode?: number | null;
+    commandStdout: string;
+    commandStderr: string;
+    commandError?: string | null;
+}
+
+export interface DeployWatchInfo {
+    id: string;
+    config: DeployWatchConfig;
+    startedAtMs: number;
+    deploying: boolean;
+    /** Set when the watcher stopped on its own. */
+    error?: string | null;
+    /** Newest last. */
+    runs: DeployRun[];
+}
+
+/** `confirmation` is the text typed when the safety rules flag the command. */
+export const startDeployWatchIpc = async (
+    config: DeployWatchConfig,
+    confirmation?: string,
+): Promise<DeployWatchInfo> =>
+    window.ipcRenderer.invoke('deployWatch:start', { config, confirmation });
+
+export const stopDeployWatchIpc = async (id: string): Promise<void> =>
+    window.ipcRenderer.invoke('deployWatch:stop', { id });
+
+export const listDeployWatchesIpc = async (): Promise<DeployWatchInfo[]> =>
+    window.ipcRenderer.invoke('deployWatch:list');
+
+export function onDeployWatchRun(listener: (payload: { id: string; run: DeployRun }) => void): () => void {
+    const handler = (_: unknown, payload: { id: string; run: DeployRun }) => listener(payload);
+    window.ipcRenderer.on('deploy-watch:run', handler);
+    return () => window.ipcRenderer.off('deploy-watch:run', handler);
+}
+
+export function onDeployWatchStopped(
+    listener: (payload: { id: string; error?: string | null }) => void,
+): () => void {
+    const handler = (_: unknown, payload: { id: string; error?: string | null }) => listener(payload);
+    window.ipcRenderer.on('deploy-watch:stopped', handler);
+    return () => window.ipcRenderer.off('deploy-watch:stopped', handler);
+}
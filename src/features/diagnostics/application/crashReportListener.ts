@@ -0,0 +1,20 @@
+import { useAppStore } from '../../../store/useAppStore';
+import type { CrashReportSummary } from '../infrastructure/crashReportsIpc';
+
+/** Tell the user when a background task panicked; the app keeps running without it. */
+export function registerCrashReportListener(): () => void {
+    const handler = (_: unknown, report: CrashReportSummary) => {
+        if (!report) return;
+        const source = report.task ? `Background task "${report.task}"` : 'A background task';
+        useAppStore.getState().showToast(
+            'error',
+            `${source} crashed. A report was saved; see Settings → General → Crash Reports.`,
+            10000,
+        );
+    };
+
+    window.ipcRenderer.on('crash:report', handler);
+    return () => {
+        window.ipcRenderer.off('crash:report', handler);
+    };
+}
@@ -0,0 +1,28 @@
+export interface CrashReportSummary {
+    id: string;
+    /** Unix milliseconds. */
+    createdAt: number;
+    message: string;
+    /** `file:line:column` of the panic. */
+    location: string | null;
+    /** Background task the panic happened in. */
+    task: string | null;
+}
+
+export interface CrashReportList {
+    /** Folder the reports are written to. */
+    dir: string;
+    reports: CrashReportSummary[];
+}
+
+/** Crash reports written by the backend panic hook, newest first. */
+export const listCrashReportsIpc = async (): Promise<CrashReportList> =>
+    window.ipcRenderer.invoke('crashReports:list');
+
+/** Zips the reports, an app summary and the recent log tail; resolves to the number of reports included. */
+export const bundleCrashReportsIpc = async (destination: string): Promise<number> =>
+    window.ipcRenderer.invoke('crashReports:bundle', { destination });
+
+/** Deletes all crash reports; resolves to how many were removed. */
+export const clearCrashReportsIpc = async (): Promise<number> =>
+    window.ipcRenderer.invoke('crashReports:clear');
@@ -0,0 +1,25 @@
+export type DoctorStatus = 'pass' | 'warn' | 'fail';
+
+export interface DoctorCheck {
+    id: string;
+    category: 'data' | 'keychain' | 'ssh' | 'platform' | 'network';
+    title: string;
+    status: DoctorStatus;
+    detail: string;
+    /** Suggested fix for warnings and failures. */
+    fix: string | null;
+}
+
+export interface DoctorReport {
+    generatedAt: number;
+    /** The worst status of any check. */
+    overall: DoctorStatus;
+    passed: number;
+    warnings: number;
+    failures: number;
+    checks: DoctorCheck[];
+}
+
+/** Checks data files, the keychain, OpenSSH tooling, Windows terminal support and the network. */
+export const runDoctorIpc = async (): Promise<DoctorReport> =>
+    window.ipcRenderer.invoke('doctor:run');
@@ -0,0 +1,39 @@
+export type ExpectStep =
+    /** Wait until `pattern` (a regular expression, Rust syntax) matches new output. */
+    | { kind: 'expect'; pattern: string; timeoutMs?: number | null }
+    /** Include `\n` to press Enter. Secret text is masked in the report. */
+    | { kind: 'send'; text: string; secret?: boolean };
+
+export interface ExpectScript {
+    command: string;
+    steps: ExpectStep[];
+    /** For `expect` steps without their own timeout (default 30000). */
+    defaultTimeoutMs?: number | null;
+}
+
+export interface ExpectStepOutcome {
+    index: number;
+    kind: 'expect' | 'send';
+    detail: string;
+    matched?: string;
+    elapsedMs: number;
+}
+
+export interface ExpectReport {
+    ok: boolean;
+    steps: ExpectStepOutcome[];
+    failedStep?: number;
+    error?: string;
+    exitStatus?: number;
+    /** Output without escape sequences, secrets masked. */
+    transcript: string;
+    transcriptTruncated: boolean;
+}
+
+/** Rejects only before the command starts (invalid script, safety rules); step failures are in the report. */
+export const runExpectScriptIpc = async (
+    connectionId: string,
+    script: ExpectScript,
+    confirmation?: string,
+): Promise<ExpectReport> =>
+    window.ipcRenderer.invoke('expect:run', { connectionId, script, confirmation });
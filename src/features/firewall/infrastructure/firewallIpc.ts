@@ -0,0 +1,76 @@
+export type FirewallBackend = 'ufw' | 'nftables' | 'iptables';
+export type RuleAction = 'accept' | 'drop' | 'reject' | 'limit' | 'jump' | 'return' | 'other';
+export type FirewallProtocol = 'tcp' | 'udp';
+
+export interface PortRange {
+    start: number;
+    end: number;
+}
+
+export interface FirewallChain {
+    backend: FirewallBackend;
+    /** `filter`, `inet filter`, …; empty for ufw. */
+    table: string;
+    name: string;
+    /** Built-in `INPUT`, an nft chain hooked on input, or ufw's incoming rules. */
+    input: boolean;
+    /** Null for user chains. */
+    policy?: RuleAction | null;
+}
+
+export interface FirewallRule {
+    backend: FirewallBackend;
+    table: string;
+    chain: string;
+    action: RuleAction;
+    /** Chain jumped to, for `jump`. */
+    target?: string | null;
+    /** Null matches every protocol. */
+    protocol?: string | null;
+    /** Destination ports; empty matches every port. */
+    ports: PortRange[];
+    /** Matches the port check can't evaluate (source, interface, …). */
+    conditions: string[];
+    /** Only matches established/related traffic. */
+    establishedOnly: boolean;
+    /** The line as printed by the tool. */
+    raw: string;
+}
+
+export interface FirewallSection {
+    backend: FirewallBackend;
+    /** Why the rules couldn't be read, usually missing root. */
+    error?: string | null;
+}
+
+export interface FirewallReport {
+    sections: FirewallSection[];
+    chains: FirewallChain[];
+    rules: FirewallRule[];
+    ufwActive?: boolean | null;
+}
+
+export interface PortCheck {
+    port: number;
+    protocol: FirewallProtocol;
+    verdict: 'open' | 'blocked' | 'unknown';
+    backend?: FirewallBackend | null;
+    /** The deciding rule, or `policy <action> (<chain>)`. */
+    decidedBy?: string | null;
+    /** Skipped rules that depend on the client or interface and could change the outcome. */
+    conditionalRules: string[];
+}
+
+/**
+ * Reading rules needs root; fails with `SUDO_PASSWORD_REQUIRED:` until `sudoPassword` is given on
+ * hosts without passwordless sudo. Without sudo at all, each section carries the permission error.
+ */
+export const getFirewallRulesIpc = async (connectionId: string, sudoPassword?: string): Promise<FirewallReport> =>
+    window.ipcRenderer.invoke('firewall:rules', { connectionId, sudoPassword });
+
+export const checkFirewallPortIpc = async (
+    connectionId: string,
+    port: number,
+    options: { protocol?: FirewallProtocol; sudoPassword?: string } = {},
+): Promise<PortCheck> =>
+    window.ipcRenderer.invoke('firewall:checkPort', { connectionId, port, ...options });
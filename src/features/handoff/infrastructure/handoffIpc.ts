@@ -0,0 +1,61 @@
+export interface RecipeEndpoint {
+    name: string;
+    host: string;
+    port: number;
+    username: string;
+}
+
+export interface RecipeTerminal {
+    title: string;
+    cwd?: string | null;
+    shellOverride?: string | null;
+}
+
+export interface RecipeTunnel {
+    name: string;
+    type: 'local' | 'remote' | 'dynamic';
+    localPort: number;
+    remoteHost: string;
+    remotePort: number;
+    bindAddress?: string | null;
+    bindToAny?: boolean | null;
+}
+
+/** Everything a handoff token carries. Never includes credentials. */
+export interface SessionRecipe {
+    createdAtMs: number;
+    connection: RecipeEndpoint;
+    /** The connection's jump host first, then that host's jump host, and so on. */
+    jumpChain: RecipeEndpoint[];
+    terminals: RecipeTerminal[];
+    /** Index into `terminals`. */
+    activeTerminal?: number | null;
+    view?: string | null;
+    tunnels: RecipeTunnel[];
+}
+
+export interface HandoffExport {
+    /** Short enough to render as a QR code. */
+    token: string;
+    recipe: SessionRecipe;
+}
+
+export interface HandoffImport {
+    connectionId: string;
+    connectionsCreated: number;
+    tunnelsAdded: number;
+    terminals: RecipeTerminal[];
+    activeTerminal?: number | null;
+    view?: string | null;
+}
+
+/** Reads tabs from the saved session, so save the session before exporting. */
+export const exportHandoffIpc = async (connectionId: string): Promise<HandoffExport> =>
+    window.ipcRenderer.invoke('handoff:export', { connectionId });
+
+export const previewHandoffIpc = async (token: string): Promise<SessionRecipe> =>
+    window.ipcRenderer.invoke('handoff:preview', { token });
+
+/** Reload connections and tunnels afterwards when anything was created. */
+export const importHandoffIpc = async (token: string): Promise<HandoffImport> =>
+    window.ipcRenderer.invoke('handoff:import', { token });
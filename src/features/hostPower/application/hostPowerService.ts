@@ -0,0 +1,114 @@
+import { create } from 'zustand';
+import { useAppStore } from '../../../store/useAppStore';
+import { cancelOperationIpc } from '../../operations/infrastructure/operationsIpc';
+import {
+    HOST_POWER_PROGRESS_EVENT,
+    watchHostPowerIpc,
+    type PowerAction,
+    type PowerIssued,
+    type PowerPhase,
+    type PowerProgress,
+} from '../infrastructure/hostPowerIpc';
+
+export interface HostPowerWatch {
+    action: PowerAction;
+    /** `reconnecting` once SSH answers and the normal connect path has taken over. */
+    phase: PowerPhase | 'reconnecting';
+    operationId: string;
+    issuedAt: number;
+}
+
+interface HostPowerState {
+    watches: Record<string, HostPowerWatch>;
+}
+
+/** Hosts currently going down or coming back, by connection id. */
+export const useHostPowerStore = create<HostPowerState>(() => ({ watches: {} }));
+
+function setWatch(connectionId: string, watch: HostPowerWatch | null) {
+    useHostPowerStore.setState((state) => {
+        const watches = { ...state.watches };
+        if (watch) watches[connectionId] = watch;
+        else delete watches[connectionId];
+        return { watches };
+    });
+}
+
+export function formatDowntime(ms: number): string {
+    const seconds = Math.max(0, Math.round(ms / 1000));
+    if (seconds < 60) return `${seconds}s`;
+    const minutes = Math.floor(seconds / 60);
+    return `${minutes}m ${String(seconds % 60).padStart(2, '0')}s`;
+}
+
+function connectionLabel(connectionId: string): string {
+    const connection = useAppStore.getState().connections.find((c) => c.id === connectionId);
+    return connection?.name || connection?.host || connectionId;
+}
+
+/** Drop the dead session so tabs wait for the reconnect, unless it was already noticed. */
+async function releaseDeadSession(connectionId: string) {
+    const store = useAppStore.getState();
+    const status = store.connections.find((c) => c.id === connectionId)?.status;
+    if (status === 'connected') await store.handleTransportLost(connectionId);
+}
+
+/**
+ * Follows a host after `issueHostPowerIpc`: waits for it to go down and, for a reboot,
+ * to answer SSH again, then reconnects and reports the downtime.
+ */
+export async function followHostPower(connectionId: string, issued: PowerIssued): Promise<void> {
+    const label = connectionLabel(connectionId);
+    const showToast = useAppStore.getState().showToast;
+    if (!issued.watchable) {
+        showToast(
+            'info',
+            issued.action === 'reboot'
+                ? `${label} is rebooting. It is reached through a jump server, so reconnect once it is back.`
+                : `${label} is shutting down.`,
+            8000,
+        );
+        return;
+    }
+
+    const operationId = crypto.randomUUID();
+    const issuedAt = Date.now();
+    setWatch(connectionId, { action: issued.action, phase: 'going-down', operationId, issuedAt });
+    const handleProgress = (_: unknown, progress: PowerProgress) => {
+        if (progress?.connectionId !== connectionId) return;
+        setWatch(connectionId, { action: issued.action, phase: progress.phase, operationId, issuedAt });
+        if (progress.phase === 'down') void releaseDeadSession(connectionId);
+    };
+    window.ipcRenderer.on(HOST_POWER_PROGRESS_EVENT, handleProgress);
+
+    try {
+        const report = await watchHostPowerIpc(connectionId, operationId);
+        await releaseDeadSession(connectionId);
+        if (report.backAfterMs === null) {
+            showToast('success', `${label} shut down after ${formatDowntime(report.wentDownAfterMs)}`);
+            return;
+        }
+
+        setWatch(connectionId, { action: issued.action, phase: 'reconnecting', operationId, issuedAt });
+        await useAppStore.getState().connect(connectionId);
+        const status = useAppStore.getState().connections.find((c) => c.id === connectionId)?.status;
+        const sshDown = formatDowntime(report.backAfterMs - report.wentDownAfterMs);
+        if (status === 'connected') {
+            showToast('success', `${label} is back after ${formatDowntime(Date.now() - issuedAt)} (SSH down for ${sshDown})`, 8000);
+        } else {
+            showToast('error', `${label} answers SSH again after ${sshDown} down, but reconnecting failed`, 8000);
+        }
+    } catch (error) {
+        const message = error instanceof Error ? error.message : String(error);
+        if (message !== 'Cancelled') showToast('error', `Lost track of ${label}: ${message}`, 8000);
+    } finally {
+        window.ipcRenderer.off(HOST_POWER_PROGRESS_EVENT, handleProgress);
+        setWatch(connectionId, null);
+    }
+}
+
+/** Stop waiting for a host; its session stays closed until reconnected by hand. */
+export async function stopFollowingHostPower(connectionId: string): Promise<void> {
+    const watch = useHostPowerStore.getState().watches[connectionId];
+    if (watch) await cancelOperationIpc(watch.operationId);
+}
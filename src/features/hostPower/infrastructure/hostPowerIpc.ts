@@ -0,0 +1,52 @@
+export type PowerAction = 'reboot' | 'shutdown';
+
+export type PowerPhase = 'going-down' | 'down' | 'up';
+
+export interface PowerIssued {
+    action: PowerAction;
+    /** The command as run, including any `sudo` prefix. */
+    command: string;
+    /** False behind a jump server, where the SSH port can't be watched. */
+    watchable: boolean;
+}
+
+export interface PowerProgress {
+    connectionId: string;
+    phase: PowerPhase;
+    /** Since the command was issued. */
+    elapsedMs: number;
+}
+
+export interface PowerWatchReport {
+    action: PowerAction;
+    wentDownAfterMs: number;
+    /** Null for a shutdown. */
+    backAfterMs: number | null;
+}
+
+/** Backend event carrying a {@link PowerProgress}. */
+export const HOST_POWER_PROGRESS_EVENT = 'host:power-progress';
+
+export interface HostPowerOptions {
+    sudoPassword?: string;
+}
+
+/**
+ * Reboots or shuts down a connected host. `confirmation` must be the host name; fails with
+ * `SUDO_PASSWORD_REQUIRED:` until `sudoPassword` is given on hosts without passwordless sudo.
+ */
+export const issueHostPowerIpc = async (
+    connectionId: string,
+    action: PowerAction,
+    confirmation: string,
+    options: HostPowerOptions = {},
+): Promise<PowerIssued> =>
+    window.ipcRenderer.invoke('host:powerIssue', { connectionId, action, confirmation, ...options });
+
+/** Resolves once a rebooted host answers SSH again (or a shut down one stops); cancellable by `operationId`. */
+export const watchHostPowerIpc = async (
+    connectionId: string,
+    operationId: string,
+    timeoutMinutes?: number,
+): Promise<PowerWatchReport> =>
+    window.ipcRenderer.invoke('host:powerWatch', { connectionId, operationId, timeoutMinutes });
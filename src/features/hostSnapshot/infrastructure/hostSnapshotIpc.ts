@@ -0,0 +1,49 @@
+export interface HostSnapshot {
+    connectionId: string;
+    capturedAtMs: number;
+    hostname: string;
+    /** `PRETTY_NAME` from `/etc/os-release`. */
+    os: string;
+    kernel: string;
+    /** `dpkg`, `rpm`, `apk`, `pacman` or `opkg`. */
+    packageSource?: string | null;
+    /** Name to version. */
+    packages: Record<string, string>;
+    /** Path to SHA-256, or `missing` / `unreadable`. */
+    files: Record<string, string>;
+    /** systemd unit to `active/sub` state, e.g. `active/running`. */
+    services: Record<string, string>;
+}
+
+export type DriftKind = 'only-left' | 'only-right' | 'changed';
+
+export interface Drift {
+    key: string;
+    kind: DriftKind;
+    left?: string | null;
+    right?: string | null;
+}
+
+export interface SnapshotDiff {
+    /** `os` and `kernel`. */
+    system: Drift[];
+    packages: Drift[];
+    files: Drift[];
+    services: Drift[];
+}
+
+export const captureHostSnapshotIpc = async (
+    connectionId: string,
+    options: { extraFiles?: string[]; save?: boolean } = {},
+): Promise<HostSnapshot> =>
+    window.ipcRenderer.invoke('hostSnapshot:capture', { connectionId, ...options });
+
+/** Capture times of the saved snapshots, newest first. */
+export const listHostSnapshotsIpc = async (connectionId: string): Promise<number[]> =>
+    window.ipcRenderer.invoke('hostSnapshot:list', { connectionId });
+
+export const loadHostSnapshotIpc = async (connectionId: string, capturedAtMs: number): Promise<HostSnapshot> =>
+    window.ipcRenderer.invoke('hostSnapshot:load', { connectionId, capturedAtMs });
+
+export const diffHostSnapshotsIpc = async (left: HostSnapshot, right: HostSnapshot): Promise<SnapshotDiff> =>
+    window.ipcRenderer.invoke('hostSnapshot:diff', { left, right });
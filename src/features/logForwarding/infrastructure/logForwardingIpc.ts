@@ -0,0 +1,29 @@
+/** The `logForwarding` settings object: where connection events and audit entries are sent. */
+export interface LogForwardingSettings {
+    enabled: boolean;
+    target: 'file' | 'udp';
+    /** `json` writes one object per line; `syslog` wraps it in an RFC 5424 header. */
+    format: 'json' | 'syslog';
+    /** File target; `events.log` in the data directory when empty. */
+    path?: string | null;
+    /** UDP target, `host:port`. */
+    address?: string | null;
+    /** Syslog facility code (1 = user, 16-23 = local0-local7). */
+    facility: number;
+    /** Event types to forward (`audit` for audit entries); all but transfer progress when empty. */
+    events: string[];
+}
+
+export const DEFAULT_LOG_FORWARDING_SETTINGS: LogForwardingSettings = {
+    enabled: false,
+    target: 'file',
+    format: 'json',
+    path: null,
+    address: null,
+    facility: 1,
+    events: [],
+};
+
+/** Sends one test line with `config`, saved or not. */
+export const testLogForwardingIpc = async (config: LogForwardingSettings): Promise<void> =>
+    window.ipcRenderer.invoke('logForwarding:test', { config });
@@ -0,0 +1,26 @@
+export type MacroStep =
+    | { kind: 'input'; data: string }
+    /** Waits until the last screen line matches `pattern` (default: a shell prompt). */
+    | { kind: 'waitForPrompt'; pattern?: string | null; timeoutMs?: number | null }
+    | { kind: 'delay'; ms: number };
+
+export interface TerminalMacro {
+    id: string;
+    name: string;
+    steps: MacroStep[];
+    /** Keybinding format, e.g. `Mod+Alt+1`. */
+    shortcut?: string | null;
+    connectionId?: string | null;
+    createdAt?: number | null;
+    updatedAt?: number | null;
+}
+
+export const listMacrosIpc = async (): Promise<TerminalMacro[]> =>
+    window.ipcRenderer.invoke('macros:list');
+
+/** Rejects when the shortcut is already bound to another macro. */
+export const saveMacroIpc = async (entry: TerminalMacro): Promise<TerminalMacro> =>
+    window.ipcRenderer.invoke('macros:save', { entry });
+
+export const deleteMacroIpc = async (id: string): Promise<void> =>
+    window.ipcRenderer.invoke('macros:delete', { id });
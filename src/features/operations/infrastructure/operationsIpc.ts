@@ -0,0 +1,16 @@
+export type OperationKind = 'connect' | 'transfer' | 'scan' | 'fleetRun' | 'powerWatch';
+
+export interface OperationInfo {
+    id: string;
+    kind: OperationKind;
+    label: string;
+    startedAt: number;
+}
+
+/** Long-running operations the backend can cancel, oldest first. */
+export const listOperationsIpc = async (): Promise<OperationInfo[]> =>
+    window.ipcRenderer.invoke('operations:list');
+
+/** Cancels the operation registered under `id`; resolves to whether one was running. */
+export const cancelOperationIpc = async (id: string): Promise<boolean> =>
+    window.ipcRenderer.invoke('operations:cancel', { id });
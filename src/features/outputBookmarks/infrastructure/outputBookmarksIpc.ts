@@ -0,0 +1,45 @@
+export interface NewOutputBookmark {
+    /** Workspace connection id; `local` for local terminals. */
+    connectionId: string;
+    termId: string;
+    /** Defaults to the command, or the last line of the excerpt. */
+    label?: string;
+    command?: string | null;
+    cwd?: string | null;
+    excerpt: string;
+}
+
+export interface OutputBookmark {
+    id: string;
+    connectionId: string;
+    /** Terminal tab the output was in; only meaningful while it is open. */
+    termId: string;
+    label: string;
+    command?: string | null;
+    cwd?: string | null;
+    /** Saved output (redacted); all that is left once the scrollback is gone. */
+    excerpt: string;
+    /** Only the last 64 KiB of the output were kept. */
+    truncated: boolean;
+    createdAt: number;
+}
+
+export const addOutputBookmarkIpc = async (bookmark: NewOutputBookmark): Promise<OutputBookmark> =>
+    window.ipcRenderer.invoke('outputBookmarks:add', { bookmark });
+
+/** Newest first; all connections when `connectionId` is omitted. */
+export const listOutputBookmarksIpc = async (
+    connectionId?: string | null,
+    query?: string,
+): Promise<OutputBookmark[]> =>
+    window.ipcRenderer.invoke('outputBookmarks:list', {
+        connectionId: connectionId ?? null,
+        query: query || null,
+        limit: null,
+    });
+
+export const renameOutputBookmarkIpc = async (id: string, label: string): Promise<OutputBookmark> =>
+    window.ipcRenderer.invoke('outputBookmarks:rename', { id, label });
+
+export const deleteOutputBookmarkIpc = async (id: string): Promise<void> =>
+    window.ipcRenderer.invoke('outputBookmarks:delete', { id });
@@ -0,0 +1,50 @@
+export type PackageManager = 'apt' | 'dnf' | 'yum' | 'apk' | 'pacman';
+
+export interface PackageUpdate {
+    name: string;
+    /** Not every manager reports the installed version in its upgrade list. */
+    currentVersion?: string | null;
+    newVersion: string;
+}
+
+export interface UpgradableList {
+    manager: PackageManager;
+    updates: PackageUpdate[];
+}
+
+export interface PackageOperationResult {
+    manager: PackageManager;
+    /** The command as run, including any `sudo` prefix. */
+    command: string;
+    exitCode: number;
+    success: boolean;
+    stdout: string;
+    stderr: string;
+    durationMs: number;
+}
+
+/** Prefix of the error returned when sudo needs a password and none was given. */
+export const SUDO_PASSWORD_REQUIRED = 'SUDO_PASSWORD_REQUIRED:';
+
+export interface PackageActionOptions {
+    sudoPassword?: string;
+    /** Text typed to confirm a command the safety rules flagged. */
+    confirmation?: string;
+}
+
+export const listUpgradablePackagesIpc = async (connectionId: string): Promise<UpgradableList> =>
+    window.ipcRenderer.invoke('packages:listUpgradable', { connectionId });
+
+export const installPackagesIpc = async (
+    connectionId: string,
+    packages: string[],
+    options: PackageActionOptions = {},
+): Promise<PackageOperationResult> =>
+    window.ipcRenderer.invoke('packages:install', { connectionId, packages, ...options });
+
+export const removePackagesIpc = async (
+    connectionId: string,
+    packages: string[],
+    options: PackageActionOptions = {},
+): Promise<PackageOperationResult> =>
+    window.ipcRenderer.invoke('packages:remove', { connectionId, packages, ...options });
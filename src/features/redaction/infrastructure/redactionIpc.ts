@@ -0,0 +1,41 @@
+export interface RedactionRule {
+    name: string;
+    /** Regular expression (Rust `regex` syntax). */
+    pattern: string;
+    /** Defaults to `[REDACTED]`; `${1}` inserts a capture group. */
+    replacement?: string | null;
+    enabled: boolean;
+}
+
+/** The `redaction` settings object, applied to macros, expect transcripts and the audit log. */
+export interface RedactionSettings {
+    /** Built-in rules for AWS keys, tokens, bearer headers, private keys and `password=` values. */
+    builtIn: boolean;
+    rules: RedactionRule[];
+}
+
+export interface RedactionPreview {
+    text: string;
+    /** Names of the rules that changed the text. */
+    matched: string[];
+    /** Rules that failed to compile and are skipped. */
+    errors: string[];
+}
+
+export const DEFAULT_REDACTION_SETTINGS: RedactionSettings = { builtIn: true, rules: [] };
+
+/** Redact `text` with `settings`, or with the saved rules when omitted. */
+export const previewRedactionIpc = async (
+    text: string,
+    settings?: RedactionSettings,
+): Promise<RedactionPreview> =>
+    window.ipcRenderer.invoke('redaction:preview', { text, settings: settings ?? null });
+
+export interface ScrubReport {
+    clipboardEntries: number;
+    outputBookmarks: number;
+}
+
+/** Mask `secrets` wherever terminal output was already saved (clipboard history, output bookmarks). */
+export const scrubPersistedSecretsIpc = async (secrets: string[]): Promise<ScrubReport> =>
+    window.ipcRenderer.invoke('redaction:scrubPersisted', { secrets });
@@ -0,0 +1,34 @@
+import type { FoundTimestamp, RemoteClock } from '../infrastructure/remoteClockIpc';
+
+/** Skews within this are reported as in sync. */
+const IN_SYNC_MS = 1000;
+
+/** `UTC+05:30`. */
+export function formatUtcOffset(seconds: number): string {
+    const sign = seconds < 0 ? '-' : '+';
+    const minutes = Math.abs(seconds) / 60;
+    const hh = String(Math.floor(minutes / 60)).padStart(2, '0');
+    const mm = String(minutes % 60).padStart(2, '0');
+    return `UTC${sign}${hh}:${mm}`;
+}
+
+/** `Asia/Kolkata (IST, UTC+05:30) · 3.2s ahead`. */
+export function describeRemoteClock(clock: RemoteClock): string {
+    const offset = formatUtcOffset(clock.utcOffsetSeconds);
+    const detail = clock.abbreviation ? `${clock.abbreviation}, ${offset}` : offset;
+    const zone = clock.timeZone ? `${clock.timeZone} (${detail})` : detail;
+    const skew = Math.abs(clock.skewMs);
+    if (skew <= Math.max(IN_SYNC_MS, clock.skewPrecisionMs)) return `${zone} · clock in sync`;
+    const amount = skew < 60_000 ? `${(skew / 1000).toFixed(1)}s` : `${Math.round(skew / 60_000)}m`;
+    return `${zone} · ${amount} ${clock.skewMs > 0 ? 'ahead' : 'behind'}`;
+}
+
+/** Replaces the timestamp found on `line` with the same moment in local time. */
+export function localizeLogLine(
+    line: string,
+    found: FoundTimestamp | null,
+    format: (date: Date) => string = (date) => date.toLocaleString(),
+): string {
+    if (!found) return line;
+    return line.replace(found.text, format(new Date(found.localEpochMs)));
+}
@@ -0,0 +1,34 @@
+export interface RemoteClock {
+    connectionId: string;
+    /** Offset from UTC the host is currently on, in seconds. */
+    utcOffsetSeconds: number;
+    /** `CEST`, `UTC`, …; null where the host prints only a numeric zone. */
+    abbreviation: string | null;
+    /** IANA name (`Europe/Berlin`) or Windows zone id, when the host reveals it. */
+    timeZone: string | null;
+    /** Remote clock minus local clock; positive when the remote is ahead. */
+    skewMs: number;
+    /** Uncertainty of `skewMs`. */
+    skewPrecisionMs: number;
+    probedAtMs: number;
+}
+
+/** A timestamp found in a remote log line. */
+export interface FoundTimestamp {
+    /** The timestamp as written on the line. */
+    text: string;
+    epochMs: number;
+    /** The same moment on the local clock (skew removed). */
+    localEpochMs: number;
+}
+
+/** Time zone and clock skew of a connected host; cached per connection unless `refresh`. */
+export const getRemoteClockIpc = async (connectionId: string, refresh = false): Promise<RemoteClock> =>
+    window.ipcRenderer.invoke('host:clock', { connectionId, refresh });
+
+/** The first timestamp of each line in the host's zone and skew; null where a line has none. */
+export const normalizeRemoteTimestampsIpc = async (
+    connectionId: string,
+    lines: string[],
+): Promise<(FoundTimestamp | null)[]> =>
+    window.ipcRenderer.invoke('host:clockNormalize', { connectionId, lines });
@@ -0,0 +1,72 @@
+export interface GitCommit {
+    hash: string;
+    shortHash: string;
+    authorName: string;
+    authorEmail: string;
+    /** Author date, ISO 8601. */
+    date: string;
+    /** Branches and tags pointing here, e.g. `HEAD -> main`, `tag: v1.2`. */
+    refs: string[];
+    subject: string;
+}
+
+export interface GitChange {
+    path: string;
+    /** Source of a rename or copy. */
+    originalPath?: string | null;
+    /** Porcelain status letter for the index (`M`, `A`, `D`, `R`, …; `.` unchanged). */
+    staged: string;
+    unstaged: string;
+    untracked: boolean;
+    conflicted: boolean;
+}
+
+export interface GitRemote {
+    name: string;
+    /** Fetch URL with any password removed. */
+    url: string;
+}
+
+export interface GitStatus {
+    /** Work tree root. */
+    root: string;
+    /** Null when HEAD is detached. */
+    branch?: string | null;
+    /** Null before the first commit. */
+    head?: GitCommit | null;
+    /** `git describe --tags --always --dirty`. */
+    describe?: string | null;
+    upstream?: string | null;
+    ahead: number;
+    behind: number;
+    changes: GitChange[];
+    remotes: GitRemote[];
+}
+
+export interface GitActionResult {
+    success: boolean;
+    exitCode: number;
+    /** git's combined output. */
+    output: string;
+    headBefore?: string | null;
+    headAfter?: string | null;
+}
+
+/** `path` may start with `~/`. Rejects when it isn't inside a git work tree. */
+export const getGitStatusIpc = async (connectionId: string, path: string): Promise<GitStatus> =>
+    window.ipcRenderer.invoke('git:status', { connectionId, path });
+
+export const getGitLogIpc = async (connectionId: string, path: string, limit?: number): Promise<GitCommit[]> =>
+    window.ipcRenderer.invoke('git:log', { connectionId, path, limit });
+
+/** Fast-forward only. `confirmation` is the text typed when the safety rules ask for it. */
+export const gitPullIpc = async (connectionId: string, path: string, confirmation?: string): Promise<GitActionResult> =>
+    window.ipcRenderer.invoke('git:pull', { connectionId, path, confirmation });
+
+export const gitCheckoutIpc = async (
+    connectionId: string,
+    path: string,
+    branch: string,
+    confirmation?: string,
+): Promise<GitActionResult> =>
+    window.ipcRenderer.invoke('git:checkout', { connectionId, path, branch, confirmation });
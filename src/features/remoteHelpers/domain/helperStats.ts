@@ -0,0 +1,58 @@
+/** One `zync-stats` snapshot; fields the host couldn't report are undefined. */
+export interface HelperStats {
+    cpuPercent?: number;
+    memTotalKb?: number;
+    memAvailableKb?: number;
+    uptimeSeconds?: number;
+    rootTotalKb?: number;
+    rootUsedKb?: number;
+    processes?: number;
+    osName?: string;
+    kernel?: string;
+    arch?: string;
+}
+
+function toNumber(value: string | undefined): number | undefined {
+    if (!value) return undefined;
+    const parsed = Number(value);
+    return Number.isFinite(parsed) ? parsed : undefined;
+}
+
+/** Parse the `key=value` lines `zync-stats` prints (same keys on Windows). */
+export function parseHelperStats(output: string): HelperStats {
+    const values = new Map<string, string>();
+    for (const line of output.split(/\r?\n/)) {
+        const separator = line.indexOf('=');
+        if (separator > 0) values.set(line.slice(0, separator).trim(), line.slice(separator + 1).trim());
+    }
+    return {
+        cpuPercent: toNumber(values.get('cpu_percent')),
+        memTotalKb: toNumber(values.get('mem_total_kb')),
+        memAvailableKb: toNumber(values.get('mem_available_kb')),
+        uptimeSeconds: toNumber(values.get('uptime_seconds')),
+        rootTotalKb: toNumber(values.get('root_total_kb')),
+        rootUsedKb: toNumber(values.get('root_used_kb')),
+        processes: toNumber(values.get('processes')),
+        osName: values.get('os_name') || undefined,
+        kernel: values.get('kernel') || undefined,
+        arch: values.get('arch') || undefined,
+    };
+}
+
+/** `uptime -p` style: "3 days, 4 hours, 5 minutes". */
+export function formatUptime(seconds: number): string {
+    const units: [number, string][] = [[86400, 'day'], [3600, 'hour'], [60, 'minute']];
+    let rest = Math.max(0, Math.floor(seconds));
+    const parts: string[] = [];
+    for (const [size, name] of units) {
+        const count = Math.floor(rest / size);
+        rest -= count * size;
+        if (count > 0) parts.push(`${count} ${name}${count === 1 ? '' : 's'}`);
+    }
+    return parts.length > 0 ? parts.join(', ') : '0 minutes';
+}
+
+/** KiB as whole GiB with a `G` suffix, like `df -h`. */
+export function formatGigabytes(kb: number): string {
+    return `${Math.round(kb / (1024 * 1024))}G`;
+}
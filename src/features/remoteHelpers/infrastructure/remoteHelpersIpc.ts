@@ -0,0 +1,30 @@
+/** `unsupported`: Windows host, where the PowerShell helpers run inline instead. */
+export type HelperBundleState = 'current' | 'missing' | 'outdated' | 'modified' | 'opted-out' | 'unsupported';
+
+export interface HelperBundleStatus {
+    connectionId: string;
+    state: HelperBundleState;
+    expectedVersion: string;
+    remoteVersion?: string | null;
+    /** False when the host has no `sha256sum`/`shasum`; only the version was compared. */
+    checksumsVerified: boolean;
+    /** Bundled scripts missing or changed on the host. */
+    mismatched: string[];
+}
+
+export const getRemoteHelpersStatusIpc = async (connectionId: string): Promise<HelperBundleStatus> =>
+    window.ipcRenderer.invoke('remoteHelpers:status', { connectionId });
+
+/** Installs the bundle in ~/.zync/helpers unless it is already current (or `force`). */
+export const installRemoteHelpersIpc = async (connectionId: string, force = false): Promise<HelperBundleStatus> =>
+    window.ipcRenderer.invoke('remoteHelpers:install', { connectionId, force });
+
+export const removeRemoteHelpersIpc = async (connectionId: string): Promise<void> =>
+    window.ipcRenderer.invoke('remoteHelpers:remove', { connectionId });
+
+/**
+ * Runs bundled helper `name` (`zync-stats`, `zync-procs`, `zync-du`) and returns its
+ * output: from ~/.zync/helpers when installed, inline on opted-out and Windows hosts.
+ */
+export const runRemoteHelperIpc = async (connectionId: string, name: string, args: string[] = []): Promise<string> =>
+    window.ipcRenderer.invoke('remoteHelpers:run', { connectionId, name, args });
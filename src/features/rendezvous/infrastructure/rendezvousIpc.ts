@@ -0,0 +1,70 @@
+/** What a homelab rendezvous should forward, before it is resolved against the VPS connection. */
+export interface RendezvousRequest {
+    name: string;
+    homeConnectionId: string;
+    vpsConnectionId: string;
+    /** Port the VPS listens on (loopback only). */
+    remotePort: number;
+    /** Reached from the home machine; `localhost:22` by default. */
+    targetHost?: string | null;
+    targetPort?: number | null;
+    /** VPS address as the home machine sees it, when it differs from the saved host. */
+    vpsHost?: string | null;
+}
+
+export interface Rendezvous {
+    id: string;
+    name: string;
+    homeConnectionId: string;
+    vpsConnectionId: string;
+    remotePort: number;
+    targetHost: string;
+    targetPort: number;
+    vpsHost: string;
+    vpsPort: number;
+    vpsUser: string;
+    /** systemd user unit on the home machine. */
+    unit: string;
+    createdAt: number;
+}
+
+export interface RendezvousPreview {
+    rendezvous: Rendezvous;
+    unit: string;
+    /** VPS `authorized_keys` line, with a placeholder for the key. */
+    authorizedKey: string;
+}
+
+export interface RendezvousSetup {
+    rendezvous: Rendezvous;
+    publicKey: string;
+    /** Lingering was refused, so the unit only runs while the user is logged in. */
+    needsLogin: boolean;
+}
+
+export interface RendezvousStatus {
+    id: string;
+    /** Whether the VPS has the port open; null when the VPS isn't connected. */
+    listening: boolean | null;
+    /** `systemctl --user is-active` on the home machine; null when it isn't connected. */
+    service: string | null;
+    error?: string;
+}
+
+/** Unit file and key line the setup would install; both connections must be open. */
+export const previewRendezvousIpc = async (request: RendezvousRequest): Promise<RendezvousPreview> =>
+    window.ipcRenderer.invoke('rendezvous:preview', { request });
+
+export const setupRendezvousIpc = async (request: RendezvousRequest): Promise<RendezvousSetup> =>
+    window.ipcRenderer.invoke('rendezvous:setup', { request });
+
+export const listRendezvousIpc = async (): Promise<Rendezvous[]> =>
+    window.ipcRenderer.invoke('rendezvous:list');
+
+/** Checks both ends over whichever connections are open. */
+export const rendezvousStatusIpc = async (id: string): Promise<RendezvousStatus> =>
+    window.ipcRenderer.invoke('rendezvous:status', { id });
+
+/** Forgets the setup; with `teardown`, also removes the unit and key. Returns what was left behind. */
+export const removeRendezvousIpc = async (id: string, teardown: boolean): Promise<string[]> =>
+    window.ipcRenderer.invoke('rendezvous:remove', { id, teardown });
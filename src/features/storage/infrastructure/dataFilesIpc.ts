@@ -0,0 +1,27 @@
+export type DataFile = 'connections' | 'tunnels' | 'snippets' | 'macros';
+
+export interface DataFileProblem {
+    /** `error` blocks loading; `repaired` is fixed by the automatic repair. */
+    severity: 'error' | 'warning' | 'repaired';
+    /** JSON pointer to the value, e.g. `/connections/3/port`. */
+    pointer: string;
+    line: number | null;
+    message: string;
+}
+
+export interface DataFileReport {
+    file: DataFile;
+    fileName: string;
+    exists: boolean;
+    problems: DataFileProblem[];
+    /** Every error can be fixed by `repairDataFileIpc`. */
+    canRepair: boolean;
+}
+
+/** Checks connections, tunnels, snippets and macros files without changing them. */
+export const validateDataFilesIpc = async (): Promise<DataFileReport[]> =>
+    window.ipcRenderer.invoke('dataFiles:validate');
+
+/** Applies the automatic repairs to one file; the original is kept as `<name>.pre-repair.json`. */
+export const repairDataFileIpc = async (file: DataFile): Promise<DataFileReport> =>
+    window.ipcRenderer.invoke('dataFiles:repair', { file });
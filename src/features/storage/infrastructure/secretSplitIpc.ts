@@ -0,0 +1,24 @@
+export interface IntegrityIssue {
+    severity: 'error' | 'warning';
+    /** `duplicate-id`, `missing-jump-host`, `plaintext-secret`, `missing-secret`, `missing-vault-item`, `orphan-tunnel` or `orphan-snippet`. */
+    kind: string;
+    /** Data file the issue is in. */
+    file: string;
+    subjectId: string;
+    message: string;
+}
+
+export interface IntegrityReport {
+    splitSecrets: boolean;
+    /** Vault credential references are checked only while the vault is unlocked. */
+    vaultChecked: boolean;
+    issues: IntegrityIssue[];
+}
+
+/** Checks references between connections, tunnels, snippets, the keychain and the vault. */
+export const checkStorageIntegrityIpc = async (): Promise<IntegrityReport> =>
+    window.ipcRenderer.invoke('secretSplit:check');
+
+/** Rewrites connections.json under the saved `storage.splitSecrets`; resolves to the number of keychain-held passwords. */
+export const applySecretSplitIpc = async (): Promise<number> =>
+    window.ipcRenderer.invoke('secretSplit:apply');
@@ -0,0 +1,50 @@
+import { useCallback, useEffect, useState } from 'react';
+import { useAppStore } from '../../../store/useAppStore';
+import { listServiceNamesIpc } from '../infrastructure/serviceNamesIpc';
+
+type ServiceNameMap = ReadonlyMap<number, string>;
+
+const EMPTY: ServiceNameMap = new Map();
+
+// One fetch per distinct `serviceNames` setting, shared by every caller.
+let cached: { key: string; names: Promise<ServiceNameMap>; resolved?: ServiceNameMap } | null = null;
+
+function loadServiceNames(key: string): Promise<ServiceNameMap> {
+    if (cached?.key === key) return cached.names;
+    const entry: NonNullable<typeof cached> = {
+        key,
+        names: listServiceNamesIpc()
+            .then((entries) => {
+                const names = new Map(entries.map((e) => [e.port, e.name]));
+                entry.resolved = names;
+                return names;
+            })
+            .catch((error) => {
+                console.warn('[TUNNEL] Failed to load service names:', error);
+                if (cached === entry) cached = null;
+                return EMPTY;
+            }),
+    };
+    cached = entry;
+    return entry.names;
+}
+
+/** Port → service name (`5432` → `postgresql`), refreshed when the `serviceNames` setting changes. */
+export function useServiceNames(): (port: number | null | undefined) => string | undefined {
+    const overrides = useAppStore((state) => state.settings.serviceNames);
+    const key = JSON.stringify(overrides ?? {});
+    const [names, setNames] = useState<ServiceNameMap>(() =>
+        (cached?.key === key && cached.resolved) || EMPTY);
+
+    useEffect(() => {
+        let cancelled = false;
+        void loadServiceNames(key).then((loaded) => {
+            if (!cancelled) setNames(loaded);
+        });
+        return () => {
+            cancelled = true;
+        };
+    }, [key]);
+
+    return useCallback((port) => (port == null ? undefined : names.get(port)), [names]);
+}
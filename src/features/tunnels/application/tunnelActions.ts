@@ -1,4 +1,7 @@
 import type { TunnelConfig } from '../../../components/tunnel/TunnelCard';
+import type { ToastType } from '../../../store/toastSlice';
+import { openTunnelInBrowserIpc } from '../infrastructure/webOpenIpc';
+import { launchRemoteDesktopIpc, type RemoteDesktopKind } from '../infrastructure/remoteDesktopIpc';
 
 export type StartTunnelById = (tunnelId: string, connectionId: string) => Promise<void>;
 export type StopTunnelById = (tunnelId: string, connectionId: string) => Promise<void>;
@@ -35,4 +38,33 @@ export async function revertTunnelOriginalPort(
 
     await saveTunnel(revertedTunnel);
     return revertedTunnel;
-}
\ No newline at end of file
+}
+export type NotifyFn = (type: ToastType, message: string, duration?: number) => void;
+
+/** Opens a local forward in the browser once it answers, surfacing any HTTPS/Host-header pitfalls. */
+export async function openTunnelInBrowser(tunnel: TunnelConfig, notify: NotifyFn): Promise<void> {
+    notify('info', `Waiting for ${tunnel.name || `port ${tunnel.localPort}`} to respond…`);
+    try {
+        const report = await openTunnelInBrowserIpc(tunnel.id);
+        for (const warning of report.warnings) {
+            notify('warning', warning, 8000);
+        }
+    } catch (error: unknown) {
+        notify('error', error instanceof Error ? error.message : String(error), 6000);
+    }
+}
+
+/** One-click VNC/RDP preset: temporary forward plus the local viewer, torn down when the viewer exits. */
+export async function launchRemoteDesktop(
+    connectionId: string,
+    kind: RemoteDesktopKind,
+    remotePort: number,
+    notify: NotifyFn,
+): Promise<void> {
+    try {
+        const session = await launchRemoteDesktopIpc(connectionId, kind, 'localhost', remotePort);
+        notify('success', `Opened ${kind.toUpperCase()} viewer on localhost:${session.localPort}; the forward closes with it`);
+    } catch (error: unknown) {
+        notify('error', error instanceof Error ? error.message : String(error), 6000);
+    }
+}
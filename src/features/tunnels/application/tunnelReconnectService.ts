@@ -4,6 +4,7 @@ import {
     type StartTunnelFn,
     type TunnelErrorLogger,
 } from '../../connections/application/tunnelAutoStartService.js';
+import { outsideScheduleMessage } from '../domain/tunnelSchedule.js';
 
 const activeBeforeDisconnect = new Map<string, string[]>();
 
@@ -24,7 +25,8 @@ function getReconnectTunnelIds(connectionId: string): string[] {
     return activeBeforeDisconnect.get(connectionId) ?? [];
 }
 
-function clearReconnectTunnelIds(connectionId: string): void {
+/** Drop the remembered tunnels, e.g. because the backend restores them instead. */
+export function clearReconnectTunnelIds(connectionId: string): void {
     activeBeforeDisconnect.delete(connectionId);
 }
 
@@ -35,7 +37,10 @@ export type RestartTunnelsAfterConnectOptions = {
     onTunnelError: TunnelErrorLogger;
 };
 
-/** Restart auto-start tunnels plus any that were active before the last disconnect. */
+/**
+ * Restart auto-start tunnels plus any that were active before the last disconnect.
+ * Tunnels outside their schedule are left for the scheduler to start.
+ */
 export async function restartTunnelsAfterConnect({
     connectionId,
     tunnels,
@@ -61,6 +66,7 @@ export async function restartTunnelsAfterConnect({
             successCount += 1;
             return;
         }
+        if (outsideScheduleMessage(result.reason) !== null) return;
         const tunnel = tunnels.find((entry) => entry.id === toStart[index]);
         onTunnelError(
             tunnel ?? { id: toStart[index], name: toStart[index] },
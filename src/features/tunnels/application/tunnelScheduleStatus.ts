@@ -0,0 +1,60 @@
+import { useEffect, useState } from 'react';
+import type { TunnelSchedule } from '../domain/tunnelSchedule';
+import {
+    getTunnelScheduleStatusIpc,
+    type TunnelScheduleStatus,
+} from '../infrastructure/tunnelScheduleIpc';
+
+const REFRESH_MS = 30_000;
+
+// One status request per refresh, shared by every card on screen.
+let inFlight: Promise<Map<string, TunnelScheduleStatus>> | null = null;
+
+function loadStatuses(): Promise<Map<string, TunnelScheduleStatus>> {
+    inFlight ??= getTunnelScheduleStatusIpc()
+        .then((statuses) => new Map(statuses.map((status) => [status.tunnelId, status])))
+        .catch((error) => {
+            console.warn('[TUNNEL] Failed to load schedule status:', error);
+            return new Map<string, TunnelScheduleStatus>();
+        })
+        .finally(() => {
+            setTimeout(() => {
+                inFlight = null;
+            }, 1000);
+        });
+    return inFlight;
+}
+
+/** Window state of a scheduled tunnel, refreshed every 30s and right after each transition. */
+export function useTunnelScheduleStatus(
+    tunnelId: string,
+    schedule: TunnelSchedule | undefined,
+): TunnelScheduleStatus | null {
+    const [status, setStatus] = useState<TunnelScheduleStatus | null>(null);
+    const key = schedule ? JSON.stringify(schedule) : '';
+
+    useEffect(() => {
+        if (!key) {
+            setStatus(null);
+            return;
+        }
+        let cancelled = false;
+        let timer: ReturnType<typeof setTimeout> | undefined;
+        const refresh = async () => {
+            const next = (await loadStatuses()).get(tunnelId) ?? null;
+            if (cancelled) return;
+            setStatus(next);
+            const untilTransition = next?.nextTransitionMs != null
+                ? Math.max(1000, next.nextTransitionMs - Date.now() + 1000)
+                : REFRESH_MS;
+            timer = setTimeout(() => void refresh(), Math.min(REFRESH_MS, untilTransition));
+        };
+        void refresh();
+        return () => {
+            cancelled = true;
+            clearTimeout(timer);
+        };
+    }, [tunnelId, key]);
+
+    return status;
+}
@@ -1,6 +1,7 @@
 import { markConnectionBackendOffline } from '../../../lib/terminal/connectionBackend';
 import { suspendAllTerminalsForConnection } from '../../../lib/terminal/suspendAllTerminals';
 import { useAppStore } from '../../../store/useAppStore';
+import { autoReconnect } from '../../connections/application/autoReconnectService';
 
 type TransportLostPayload = {
     connectionId?: string;
@@ -8,7 +9,7 @@ type TransportLostPayload = {
 
 const handledConnections = new Set<string>();
 
-/** Stop active tunnels and sync connection state when the SSH transport drops unexpectedly. */
+/** Stop active tunnels and sync connection state when the SSH transport drops unexpectedly, then reconnect. */
 export function handleConnectionTransportLost(payload: TransportLostPayload): void {
     const connectionId = payload?.connectionId;
     if (!connectionId || handledConnections.has(connectionId)) {
@@ -24,6 +25,7 @@ export function handleConnectionTransportLost(payload: TransportLostPayload): vo
 
     void store.handleTransportLost(connectionId).finally(() => {
         handledConnections.delete(connectionId);
+        void autoReconnect(connectionId);
     });
 }
 
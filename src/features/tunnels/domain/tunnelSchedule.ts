@@ -0,0 +1,60 @@
+/** Weekly maintenance window — keep in sync with `TunnelSchedule` (Rust). */
+export interface TunnelSchedule {
+    /** 0 = Monday … 6 = Sunday; empty means every day. */
+    days: number[];
+    /** Minutes after local midnight; an end at or before the start runs past midnight. */
+    startMinute: number;
+    endMinute: number;
+    /** Offset the times are in, e.g. 60 for UTC+01:00. */
+    utcOffsetMinutes: number;
+}
+
+export const SCHEDULE_DAY_LABELS = ['Mon', 'Tue', 'Wed', 'Thu', 'Fri', 'Sat', 'Sun'];
+
+/** Prefix of `tunnel_start` errors for a tunnel scheduled off right now. */
+export const OUTSIDE_SCHEDULE_PREFIX = 'OUTSIDE_SCHEDULE:';
+
+/** The readable part of an outside-schedule start error, or null for any other error. */
+export function outsideScheduleMessage(error: unknown): string | null {
+    const message = error instanceof Error ? error.message : String(error);
+    return message.startsWith(OUTSIDE_SCHEDULE_PREFIX)
+        ? message.slice(OUTSIDE_SCHEDULE_PREFIX.length)
+        : null;
+}
+
+/** This device's current UTC offset in minutes. */
+export function localUtcOffsetMinutes(): number {
+    return -new Date().getTimezoneOffset();
+}
+
+/** `HH:MM` for an `<input type="time">`. */
+export function minuteToTime(minute: number): string {
+    return `${String(Math.floor(minute / 60)).padStart(2, '0')}:${String(minute % 60).padStart(2, '0')}`;
+}
+
+export function timeToMinute(time: string): number | null {
+    const match = /^(\d{1,2}):(\d{2})$/.exec(time.trim());
+    if (!match) return null;
+    const minute = Number(match[1]) * 60 + Number(match[2]);
+    return minute < 24 * 60 ? minute : null;
+}
+
+function formatOffset(offset: number): string {
+    const sign = offset < 0 ? '-' : '+';
+    const absolute = Math.abs(offset);
+    return `UTC${sign}${minuteToTime(absolute)}`;
+}
+
+/** e.g. `Mon–Fri 09:00–17:00 (UTC+01:00)`. */
+export function describeSchedule(schedule: TunnelSchedule): string {
+    const days = schedule.days.length === 0 || schedule.days.length === 7
+        ? 'Every day'
+        : [...schedule.days].sort((a, b) => a - b).map((day) => SCHEDULE_DAY_LABELS[day]).join(', ');
+    const hours = schedule.startMinute === schedule.endMinute
+        ? 'all day'
+        : `${minuteToTime(schedule.startMinute)}–${minuteToTime(schedule.endMinute)}`;
+    const offset = schedule.utcOffsetMinutes === localUtcOffsetMinutes()
+        ? ''
+        : ` (${formatOffset(schedule.utcOffsetMinutes)})`;
+    return `${days} ${hours}${offset}`;
+}
@@ -19,18 +19,30 @@ export function defaultTunnelName(
     localPort: number,
     remoteHost: string,
     remotePort: number,
+    /** Catalog name of the forwarded service (the remote port for -L, the local one for -R). */
+    service?: string,
 ): string {
     if (type === 'dynamic') {
         return `SOCKS ${localPort}`;
     }
     if (type === 'local') {
-        return `Local ${localPort} -> ${remoteHost}:${remotePort}`;
+        const name = `Local ${localPort} -> ${remoteHost}:${remotePort}`;
+        return service ? `${service} (${name})` : name;
     }
-    return `Remote ${remotePort} -> Local ${localPort}`;
+    const name = `Remote ${remotePort} -> Local ${localPort}`;
+    return service ? `${service} (${name})` : name;
 }
 
 export function socks5Url(bindAddress: string | undefined, localPort: number): string {
     const host =
         bindAddress && bindAddress !== '0.0.0.0' ? bindAddress : '127.0.0.1';
     return `socks5://${host}:${localPort}`;
-}
\ No newline at end of file
+}
+/** Comma/whitespace separated domain list → PAC domains, or undefined when empty. */
+export function splitPacDomains(input: string): string[] | undefined {
+    const domains = input
+        .split(/[\s,]+/)
+        .map((domain) => domain.trim().toLowerCase())
+        .filter(Boolean);
+    return domains.length > 0 ? Array.from(new Set(domains)) : undefined;
+}
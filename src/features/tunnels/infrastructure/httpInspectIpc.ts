@@ -0,0 +1,18 @@
+export interface HttpExchange {
+    id: number;
+    /** Unix ms when the request head arrived. */
+    startedAt: number;
+    method: string;
+    target: string;
+    host?: string | null;
+    /** Null while the response is pending (or the connection closed first). */
+    status?: number | null;
+    ttfbMs?: number | null;
+    durationMs?: number | null;
+    requestBytes: number;
+    responseBytes: number;
+}
+
+/** Inspector log for a saved local forward, oldest first. `clear` empties it after reading. */
+export const getTunnelHttpLogIpc = async (id: string, clear = false): Promise<HttpExchange[]> =>
+    window.ipcRenderer.invoke('tunnel:httpLog', { id, clear });
@@ -0,0 +1,17 @@
+export interface PacRoute {
+    tunnelId: string;
+    name: string;
+    /** `host:port` of the SOCKS listener. */
+    proxy: string;
+    domains: string[];
+}
+
+export interface PacInfo {
+    /** Point the browser's automatic proxy configuration at this URL. */
+    url: string;
+    /** Running SOCKS tunnels with domains, in match order. */
+    routes: PacRoute[];
+}
+
+/** PAC file served for running SOCKS tunnels; starts the local server on first call. */
+export const getPacInfoIpc = async (): Promise<PacInfo> => window.ipcRenderer.invoke('tunnel:pacInfo');
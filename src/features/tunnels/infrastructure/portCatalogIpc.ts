@@ -0,0 +1,28 @@
+export interface PortReservation {
+    name: string;
+    port: number;
+    /** Saved tunnel the port belongs to; released when the tunnel is deleted. */
+    tunnelId?: string;
+    reservedAt: number;
+}
+
+export const listPortReservationsIpc = async (): Promise<PortReservation[]> =>
+    window.ipcRenderer.invoke('ports:list');
+
+/**
+ * Port reserved under `name`; the first call picks a free one in the range
+ * (default 42000-42999) and later calls return the same port.
+ */
+export const reservePortIpc = async (
+    name: string,
+    options: { rangeStart?: number; rangeEnd?: number; tunnelId?: string } = {},
+): Promise<PortReservation> =>
+    window.ipcRenderer.invoke('ports:reserve', {
+        name,
+        rangeStart: options.rangeStart ?? null,
+        rangeEnd: options.rangeEnd ?? null,
+        tunnelId: options.tunnelId ?? null,
+    });
+
+export const releasePortIpc = async (name: string): Promise<boolean> =>
+    window.ipcRenderer.invoke('ports:release', { name });
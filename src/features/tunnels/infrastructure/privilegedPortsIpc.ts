@@ -0,0 +1,26 @@
+export type PortFixKind = 'setcap' | 'sysctl';
+
+export interface PortFix {
+    kind: PortFixKind;
+    title: string;
+    description: string;
+    /** What runs as root; can also be run by hand. */
+    command: string;
+    /** Why the fix can't be applied from here, if it can't. */
+    unavailable?: string | null;
+}
+
+export interface PrivilegedPortStatus {
+    port: number;
+    canBind: boolean;
+    detail: string;
+    fixes: PortFix[];
+}
+
+/** Whether local `port` can be listened on, and the fixes if it can't. */
+export const privilegedPortStatusIpc = async (port: number): Promise<PrivilegedPortStatus> =>
+    window.ipcRenderer.invoke('privilegedPorts:status', { port });
+
+/** Runs a fix as root; the OS asks for the admin password. */
+export const applyPrivilegedPortFixIpc = async (kind: PortFixKind, port: number): Promise<void> =>
+    window.ipcRenderer.invoke('privilegedPorts:apply', { kind, port });
@@ -0,0 +1,23 @@
+export type RemoteDesktopKind = 'vnc' | 'rdp';
+
+export interface RemoteDesktopSession {
+    /** Temporary forward; stopped by the backend when the viewer exits. */
+    runtimeId: string;
+    localPort: number;
+    /** Command line the viewer was started with. */
+    viewer: string;
+}
+
+/** Forwards the desktop port (5900/3389 on the host itself by default) and opens the local viewer on it. */
+export const launchRemoteDesktopIpc = async (
+    connectionId: string,
+    kind: RemoteDesktopKind,
+    remoteHost?: string,
+    remotePort?: number,
+): Promise<RemoteDesktopSession> =>
+    window.ipcRenderer.invoke('tunnel:launchRemoteDesktop', {
+        connectionId,
+        kind,
+        remoteHost: remoteHost ?? null,
+        remotePort: remotePort ?? null,
+    });
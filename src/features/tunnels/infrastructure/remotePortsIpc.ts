@@ -0,0 +1,27 @@
+import type { TunnelConfig } from '../../../components/tunnel/TunnelCard';
+
+export interface RemoteListeningPort {
+    port: number;
+    /** Bound address as reported by `ss`/`netstat` (`0.0.0.0`, `::`, `127.0.0.1`, `*`, ...). */
+    address: string;
+    process?: string | null;
+    pid?: number | null;
+    /** Only reachable from the server itself — the usual reason to forward it. */
+    loopback: boolean;
+    /** Service catalog name for the port, e.g. `postgresql`. */
+    service?: string | null;
+}
+
+export const listRemoteListeningPortsIpc = async (connectionId: string): Promise<RemoteListeningPort[]> =>
+    window.ipcRenderer.invoke('tunnel:listRemotePorts', { connectionId });
+
+export interface TunnelSuggestion {
+    /** Detected service, e.g. `PostgreSQL` or `Web`. */
+    service: string;
+    listening: RemoteListeningPort;
+    /** Unsaved local forward; save it to create the tunnel. */
+    draft: Omit<TunnelConfig, 'status'>;
+}
+
+export const suggestTunnelsIpc = async (connectionId: string): Promise<TunnelSuggestion[]> =>
+    window.ipcRenderer.invoke('tunnel:suggest', { connectionId });
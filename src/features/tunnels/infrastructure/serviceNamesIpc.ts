@@ -0,0 +1,11 @@
+export interface ServiceNameEntry {
+    port: number;
+    /** IANA-style lowercase name, e.g. `postgresql`. */
+    name: string;
+    /** From the `serviceNames` setting rather than the built-in catalog. */
+    custom: boolean;
+}
+
+/** Port → service name catalog with the user's overrides applied. */
+export const listServiceNamesIpc = async (): Promise<ServiceNameEntry[]> =>
+    window.ipcRenderer.invoke('tunnel:serviceNames');
@@ -0,0 +1,33 @@
+import type { TunnelType } from '../domain/tunnelTypes';
+
+/** A `LocalForward` / `RemoteForward` / `DynamicForward` line in tunnel terms. */
+export interface ConfigForward {
+    type: TunnelType;
+    localPort: number;
+    remoteHost: string;
+    remotePort: number;
+    bindAddress: string | null;
+}
+
+export interface ConfigForwardImport {
+    hostAlias: string;
+    /** Saved connection the `Host` block matched, if any. */
+    connectionId: string | null;
+    forward: ConfigForward;
+    /** Saved tunnel that already is this forward. */
+    existingTunnelId: string | null;
+}
+
+export interface ConfigForwardPreview {
+    forwards: ConfigForwardImport[];
+    /** Forward lines that couldn't be read, e.g. Unix socket forwards. */
+    errors: string[];
+}
+
+/** Saved tunnels as ssh_config `Host` blocks of forward lines. */
+export const exportSshConfigForwardsIpc = async (connectionIds?: string[]): Promise<string> =>
+    window.ipcRenderer.invoke('tunnel:exportSshConfig', { connectionIds: connectionIds ?? null });
+
+/** Forward lines in `content` (or `~/.ssh/config` when omitted), matched to saved connections. Saves nothing. */
+export const previewSshConfigForwardsIpc = async (content?: string): Promise<ConfigForwardPreview> =>
+    window.ipcRenderer.invoke('tunnel:previewSshConfigImport', { content: content ?? null });
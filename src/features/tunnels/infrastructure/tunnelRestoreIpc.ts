@@ -0,0 +1,23 @@
+export const TUNNEL_RESTORE_PROGRESS_EVENT = 'tunnel:restore-progress';
+
+export interface TunnelRestoreProgress {
+    connectionId: string;
+    tunnelId: string;
+    name: string;
+    /** `skipped` means outside its schedule; the scheduler starts it later. */
+    status: 'starting' | 'active' | 'skipped' | 'error';
+    error?: string | null;
+}
+
+export interface TunnelRestoreSummary {
+    restored: string[];
+    skipped: string[];
+    failed: Array<{ tunnelId: string; name: string; error: string }>;
+}
+
+/**
+ * Start the tunnels that were running when the connection's transport dropped,
+ * plus its auto-start tunnels. Each one reports `TUNNEL_RESTORE_PROGRESS_EVENT`.
+ */
+export const restoreConnectionTunnelsIpc = async (connectionId: string): Promise<TunnelRestoreSummary> =>
+    window.ipcRenderer.invoke('tunnel:restoreConnection', { connectionId });
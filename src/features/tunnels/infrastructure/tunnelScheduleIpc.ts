@@ -0,0 +1,11 @@
+export interface TunnelScheduleStatus {
+    tunnelId: string;
+    /** Inside one of the windows right now. */
+    active: boolean;
+    /** Epoch ms when `active` flips next; null when it never does. */
+    nextTransitionMs: number | null;
+}
+
+/** Window state of every saved tunnel that has a schedule. */
+export const getTunnelScheduleStatusIpc = async (): Promise<TunnelScheduleStatus[]> =>
+    window.ipcRenderer.invoke('tunnel:scheduleStatus');
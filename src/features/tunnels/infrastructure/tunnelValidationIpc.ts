@@ -0,0 +1,20 @@
+import type { TunnelConfig } from '../../../store/useAppStore';
+
+export type TunnelIssueField = 'type' | 'localPort' | 'remoteHost' | 'remotePort' | 'bindAddress' | 'hostname';
+
+export interface TunnelIssue {
+    field: TunnelIssueField;
+    /** Errors stop the forward from starting; warnings are advisory. */
+    severity: 'warning' | 'error';
+    message: string;
+    /** Saved forward this one clashes with. */
+    conflictsWith?: string;
+}
+
+/** Checks ports, hosts and the bind address, and clashes with other saved forwards. */
+export const validateTunnelIpc = async (tunnel: TunnelConfig): Promise<TunnelIssue[]> =>
+    window.ipcRenderer.invoke('tunnel:validate', { tunnelVal: tunnel });
+
+/** Lowest loopback alias (127.0.0.2 and up) no other forward or program listens on at `port`. */
+export const nextLoopbackAliasIpc = async (id: string, port: number): Promise<string | null> =>
+    window.ipcRenderer.invoke('tunnel:nextLoopbackAlias', { id, port });
@@ -0,0 +1,22 @@
+export interface WebOpenReport {
+    /** URL that was opened; may switch to https when the backend refused plain HTTP. */
+    url: string;
+    status: number;
+    waitedMs: number;
+    /** Certificate, redirect and Host-header pitfalls spotted while probing. */
+    warnings: string[];
+}
+
+/** Waits for the forward to answer HTTP, then opens it in the default browser. */
+export const openTunnelInBrowserIpc = async (id: string, timeoutSecs?: number): Promise<WebOpenReport> =>
+    window.ipcRenderer.invoke('tunnel:openInBrowser', { id, timeoutSecs: timeoutSecs ?? null });
+
+export interface LocalCaInfo {
+    /** PEM to import into the OS/browser trust store once. */
+    certPath: string;
+    /** SHA-256, colon-separated hex. */
+    fingerprint: string;
+}
+
+/** Local CA used for HTTPS termination; created on first call. */
+export const getLocalCaIpc = async (): Promise<LocalCaInfo> => window.ipcRenderer.invoke('tunnel:localCa');
@@ -0,0 +1,40 @@
+export interface UserProfile {
+    id: string;
+    name: string;
+    color?: string | null;
+    /** Directory with this profile's keys and ssh_config; `~/.ssh` when unset. */
+    sshDir?: string | null;
+    createdAt?: number | null;
+}
+
+export interface UserProfilesOverview {
+    /** The profile this window is running. */
+    active: string;
+    /** Every profile, the default one first. */
+    profiles: UserProfile[];
+}
+
+export const DEFAULT_USER_PROFILE_ID = 'default';
+
+export const listUserProfilesIpc = async (): Promise<UserProfilesOverview> =>
+    window.ipcRenderer.invoke('userProfiles:list');
+
+/** With `copySettings`, the new profile starts from the current settings (except the data location). */
+export const createUserProfileIpc = async (input: {
+    name: string;
+    color?: string | null;
+    sshDir?: string | null;
+    copySettings?: boolean;
+}): Promise<UserProfile> =>
+    window.ipcRenderer.invoke('userProfiles:create', input);
+
+export const updateUserProfileIpc = async (profile: UserProfile): Promise<UserProfile> =>
+    window.ipcRenderer.invoke('userProfiles:update', { profile });
+
+/** `removeFiles` also deletes the profile's settings, known_hosts and default data directory. */
+export const deleteUserProfileIpc = async (id: string, removeFiles: boolean): Promise<void> =>
+    window.ipcRenderer.invoke('userProfiles:delete', { id, removeFiles });
+
+/** Restarts Zync into the profile. Rejects while connections are open unless `force` is set. */
+export const switchUserProfileIpc = async (id: string, force = false): Promise<void> =>
+    window.ipcRenderer.invoke('userProfiles:switch', { id, force });
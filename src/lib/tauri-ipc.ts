@@ -34,6 +34,7 @@ const ipcRenderer = {
     // We'll call invoke but not wait for the result
     const channelMap: Record<string, string> = {
       'terminal:write': 'terminal_write',
+      'terminal:ack': 'terminal_ack',
       'terminal:resize': 'terminal_resize',
       'terminal:kill': 'terminal_close',
     };
@@ -157,6 +158,9 @@ const ipcRenderer = {
       'fs_copy': 'fs_copy',
       'fs_exists': 'fs_exists',
       'tunnel:getAll': 'tunnel_get_all',
+      'tunnel:scheduleStatus': 'tunnel_schedule_status',
+      'tunnel:exportSshConfig': 'tunnel_export_ssh_config',
+      'tunnel:previewSshConfigImport': 'tunnel_preview_ssh_config_import',
       'tunnel:startLocal': 'tunnel_start_local',
       'tunnel:start_local': 'tunnel_start_local', // Add snake_case mapping
       'tunnel:startRemote': 'tunnel_start_remote',
@@ -175,6 +179,15 @@ const ipcRenderer = {
       'ssh:readConfig': 'ssh_import_config',
       'ssh:internalize-connections': 'ssh_internalize_connections',
       'ssh:disconnectVaultBacked': 'ssh_disconnect_vault_backed',
+      'ssh:cancelConnect': 'cancel_connect',
+      'ssh:authRespond': 'ssh_auth_respond',
+      'ssh:hostKeyRespond': 'ssh_host_key_respond',
+      'jump:sessions': 'jump_sessions_list',
+      'jump:reset': 'jump_session_reset',
+      'tor:status': 'tor_status',
+      'startup:autoConnectPlan': 'startup_auto_connect_plan',
+      'startup:workspaceRestorePlan': 'startup_workspace_restore_plan',
+      'startup:workspaceClear': 'startup_workspace_clear',
       'sftp:put': 'sftp_put',
       'sftp:get': 'sftp_get',
       'sftp:copyToServer': 'sftp_copy_to_server',
@@ -184,6 +197,99 @@ const ipcRenderer = {
       'tunnel:save': 'tunnel_save',
       'tunnel:delete': 'tunnel_delete',
       'tunnel:reconcileConnection': 'tunnel_reconcile_connection',
+      'tunnel:restoreConnection': 'tunnel_restore_connection',
+      'tunnel:listRemotePorts': 'list_remote_listening_ports',
+      'tunnel:suggest': 'suggest_tunnels',
+      'tunnel:serviceNames': 'service_names_list',
+      'tunnel:openInBrowser': 'tunnel_open_in_browser',
+      'tunnel:launchRemoteDesktop': 'tunnel_launch_remote_desktop',
+      'tunnel:localCa': 'tunnel_local_ca',
+      'tunnel:httpLog': 'tunnel_http_log',
+      'tunnel:pacInfo': 'tunnel_pac_info',
+      'tunnel:validate': 'tunnel_validate',
+      'tunnel:nextLoopbackAlias': 'tunnel_next_loopback_alias',
+      'rendezvous:preview': 'rendezvous_preview',
+      'rendezvous:setup': 'rendezvous_setup',
+      'rendezvous:list': 'rendezvous_list',
+      'rendezvous:status': 'rendezvous_status',
+      'rendezvous:remove': 'rendezvous_remove',
+      'ports:list': 'port_reservations_list',
+      'ports:reserve': 'port_reserve',
+      'ports:release': 'port_release',
+      'privilegedPorts:status': 'privileged_ports_status',
+      'privilegedPorts:apply': 'privileged_ports_apply',
+      'clipboard:pushRemote': 'clipboard_push_remote',
+      'clipboard:pullRemote': 'clipboard_pull_remote',
+      'clipboardHistory:add': 'clipboard_history_add',
+      'clipboardHistory:list': 'clipboard_history_list',
+      'clipboardHistory:pin': 'clipboard_history_pin',
+      'clipboardHistory:delete': 'clipboard_history_delete',
+      'clipboardHistory:clear': 'clipboard_history_clear',
+      'clipboardHistory:setIncognito': 'clipboard_history_set_incognito',
+      'clipboardHistory:status': 'clipboard_history_status',
+      'outputBookmarks:add': 'output_bookmark_add',
+      'outputBookmarks:list': 'output_bookmarks_list',
+      'outputBookmarks:rename': 'output_bookmark_rename',
+      'outputBookmarks:delete': 'output_bookmark_delete',
+      'remoteHelpers:status': 'remote_helpers_status',
+      'remoteHelpers:install': 'remote_helpers_install',
+      'remoteHelpers:remove': 'remote_helpers_remove',
+      'remoteHelpers:run': 'remote_helpers_run',
+      'host:capabilities': 'host_capabilities',
+      'host:clock': 'remote_clock',
+      'host:clockNormalize': 'remote_clock_normalize',
+      'host:powerIssue': 'host_power_issue',
+      'host:powerWatch': 'host_power_watch',
+      'packages:listUpgradable': 'packages_list_upgradable',
+      'packages:install': 'packages_install',
+      'packages:remove': 'packages_remove',
+      'cron:list': 'cron_list',
+      'cron:parse': 'cron_parse',
+      'cron:write': 'cron_write',
+      'accounts:report': 'accounts_report',
+      'accounts:addSshUser': 'accounts_add_ssh_user',
+      'accounts:authorizeKey': 'accounts_authorize_key',
+      'firewall:rules': 'firewall_rules',
+      'firewall:checkPort': 'firewall_check_port',
+      'git:status': 'git_status',
+      'git:log': 'git_log',
+      'git:pull': 'git_pull',
+      'git:checkout': 'git_checkout',
+      'deployWatch:start': 'deploy_watch_start',
+      'deployWatch:stop': 'deploy_watch_stop',
+      'deployWatch:list': 'deploy_watch_list',
+      'hostSnapshot:capture': 'host_snapshot_capture',
+      'hostSnapshot:list': 'host_snapshot_list',
+      'hostSnapshot:load': 'host_snapshot_load',
+      'hostSnapshot:diff': 'host_snapshot_diff',
+      'handoff:export': 'handoff_export',
+      'handoff:preview': 'handoff_preview',
+      'handoff:import': 'handoff_import',
+      'macros:list': 'macros_list',
+      'macros:save': 'macros_save',
+      'macros:delete': 'macros_delete',
+      'expect:run': 'expect_run',
+      'redaction:preview': 'redaction_preview',
+      'redaction:scrubPersisted': 'redaction_scrub_persisted',
+      'logForwarding:test': 'log_forwarding_test',
+      'secretSplit:check': 'secret_split_check',
+      'secretSplit:apply': 'secret_split_apply',
+      'dataFiles:validate': 'data_files_validate',
+      'dataFiles:repair': 'data_files_repair',
+      'crashReports:list': 'crash_reports_list',
+      'crashReports:bundle': 'crash_reports_bundle',
+      'crashReports:clear': 'crash_reports_clear',
+      'doctor:run': 'run_doctor',
+      'operations:list': 'operations_list',
+      'operations:cancel': 'cancel_operation',
+      'session:hold': 'session_hold',
+      'session:release': 'session_release',
+      'session:usage': 'session_usage',
+      'userProfiles:list': 'user_profiles_list',
+      'userProfiles:create': 'user_profiles_create',
+      'userProfiles:update': 'user_profiles_update',
+      'userProfiles:delete': 'user_profiles_delete',
+      'userProfiles:switch': 'user_profiles_switch',
       'window:is-maximized': 'window_is_maximized',
       // Dialog commands handled specially below
       'dialog:openFile': 'dialog_open_file',
@@ -352,7 +458,9 @@ const ipcRenderer = {
       let payload = args.length === 1 && args[0] !== null && typeof args[0] === 'object' ? args[0] : { args };
 
       // Manual argument mapping for mismatched commands
-      if (tauriCommand === 'ssh_connect' || tauriCommand === 'ssh_test_connection') {
+      if (tauriCommand === 'ssh_connect') {
+        payload = { config: args[0], onDuplicate: args[1] ?? null };
+      } else if (tauriCommand === 'ssh_test_connection') {
         payload = { config: args[0] };
       } else if (tauriCommand === 'ssh_disconnect' || tauriCommand === 'ssh_transport_lost') {
         payload = { id: args[0] };
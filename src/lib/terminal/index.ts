@@ -97,6 +97,42 @@ export {
   enqueueTerminalInputTask,
 } from './inputQueue.js';
 
+export type { StartMacroRecordingOptions } from './macroRecorder.js';
+export {
+  isRecordingMacro,
+  recordMacroInput,
+  startMacroRecording,
+  stopMacroRecording,
+} from './macroRecorder.js';
+export { replayMacro } from './macroReplay.js';
+
+export {
+  bookmarkTerminalOutput,
+  forgetOutputBookmark,
+  isOutputBookmarkLive,
+  jumpToOutputBookmark,
+  markTerminalCommand,
+} from './outputBookmarks.js';
+
+export type {
+  SecretOutputDetectedDetail,
+  SecretOutputKind,
+  SecretOutputMatch,
+} from './secretOutputDetect.js';
+export {
+  SECRET_OUTPUT_DETECTED_EVENT,
+  SECRET_OUTPUT_KIND_LABELS,
+  detectSecretsInOutput,
+} from './secretOutputDetect.js';
+
+export type { FollowPathRule } from './terminalFollow.js';
+export {
+  followCommand,
+  formatFollowRules,
+  parseFollowRules,
+  translateFollowPath,
+} from './terminalFollow.js';
+
 export type { SpawnTerminalSessionOptions, SuspendTerminalPtyOptions } from './ptyLifecycle.js';
 export {
   isTerminalIdleSuspended,
@@ -11,6 +11,7 @@ import {
 import { terminalCache } from './terminalCache.js';
 import { touchTerminalActivity } from './terminalActivity.js';
 import { clearIdleHostSuspendNotice } from './terminalIdleSuspendNotice.js';
+import { recordMacroInput } from './macroRecorder.js';
 
 const INPUT_BATCH_MS = 4;
 const INPUT_FLUSH_THRESHOLD = 64;
@@ -64,6 +65,7 @@ export function queueTerminalInput(termId: string | null | undefined, data: stri
     return;
   }
 
+  recordMacroInput(termId, data);
   cached.pendingInput += data;
   cached.pendingInputBytes = (cached.pendingInputBytes || 0) + inputByteEncoder.encode(data).length;
   touchTerminalActivity(termId);
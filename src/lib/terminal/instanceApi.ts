@@ -4,6 +4,8 @@ import { clearTerminalRendererSession } from './rendererSession.js';
 import { disposeTerminalLigatures } from './ligatures.js';
 import { clearTerminalPendingInput, terminalCache } from './terminalCache.js';
 import { clearTerminalInputQueue } from './inputQueue.js';
+import { stopMacroRecording } from './macroRecorder.js';
+import { clearSecretOutputDetector } from './secretOutputDetect.js';
 import { silenceTerminalOutputChannel } from './terminalReloadTeardown.js';
 export function getTerminalRecentLines(termId: string, lineCount = 20): string | null {
   if (!termId) {
@@ -33,8 +35,10 @@ export function destroyTerminalInstance(termId: string): void {
 
   clearTerminalPendingInput(termId);
   clearTerminalInputQueue(termId);
+  stopMacroRecording(termId);
   clearPromptCwdSniffer(termId);
   clearSecretInputSniffer(termId);
+  clearSecretOutputDetector(termId);
   cached.ghostTracker?.destroy();
   disposeTerminalLigatures(cached);
   cached.ligaturesEnabled = false;
@@ -0,0 +1,63 @@
+import type { MacroStep } from '../../features/macros/infrastructure/macrosIpc.js';
+
+interface MacroRecording {
+  steps: MacroStep[];
+  waitForPrompts: boolean;
+  /** A password prompt is showing: input up to the next Enter is not recorded. */
+  skippingSecret: boolean;
+}
+
+const recordings = new Map<string, MacroRecording>();
+
+export interface StartMacroRecordingOptions {
+  /** Insert a prompt wait after every Enter so replay paces itself on slow hosts. */
+  waitForPrompts?: boolean;
+}
+
+export function startMacroRecording(termId: string, options?: StartMacroRecordingOptions): void {
+  recordings.set(termId, { steps: [], waitForPrompts: options?.waitForPrompts ?? true, skippingSecret: false });
+}
+
+export function isRecordingMacro(termId: string | null | undefined): boolean {
+  return Boolean(termId && recordings.has(termId));
+}
+
+/** Returns the recorded steps, or null when the terminal wasn't recording. */
+export function stopMacroRecording(termId: string): MacroStep[] | null {
+  const recording = recordings.get(termId);
+  recordings.delete(termId);
+  return recording ? recording.steps : null;
+}
+
+/**
+ * Called when the terminal shows a password/passphrase prompt. What is typed
+ * at it is left out of the recording; on replay the preceding prompt wait
+ * holds until the user has answered the prompt by hand.
+ */
+export function markMacroSecretPrompt(termId: string): void {
+  const recording = recordings.get(termId);
+  if (recording) recording.skippingSecret = true;
+}
+
+/** Called for every input chunk sent to a terminal; no-op unless recording. */
+export function recordMacroInput(termId: string, data: string): void {
+  const recording = recordings.get(termId);
+  if (!recording || !data) return;
+
+  const pieces = data.split(/(?<=\r)/);
+  for (const piece of pieces) {
+    if (recording.skippingSecret) {
+      if (piece.endsWith('\r')) recording.skippingSecret = false;
+      continue;
+    }
+    const last = recording.steps[recording.steps.length - 1];
+    if (last?.kind === 'input') {
+      last.data += piece;
+    } else {
+      recording.steps.push({ kind: 'input', data: piece });
+    }
+    if (recording.waitForPrompts && piece.endsWith('\r')) {
+      recording.steps.push({ kind: 'waitForPrompt' });
+    }
+  }
+}
@@ -0,0 +1,102 @@
+import type { MacroStep } from '../../features/macros/infrastructure/macrosIpc.js';
+import { queueTerminalInput } from './inputPipeline.js';
+import { terminalCache } from './terminalCache.js';
+
+/** Ends in a typical shell prompt character (`$`, `#`, `%`, `>`, `❯`). */
+const DEFAULT_PROMPT_PATTERN = /[$#%>❯]\s*$/;
+const DEFAULT_PROMPT_TIMEOUT_MS = 30_000;
+
+function cursorLine(termId: string): string | null {
+  const term = terminalCache.get(termId)?.term;
+  const buffer = term?.buffer?.active;
+  if (!buffer) return null;
+  return buffer.getLine(buffer.baseY + buffer.cursorY)?.translateToString(true) ?? null;
+}
+
+function delay(ms: number, signal?: AbortSignal): Promise<void> {
+  return new Promise((resolve, reject) => {
+    if (signal?.aborted) {
+      reject(new Error('Macro cancelled'));
+      return;
+    }
+    const timer = setTimeout(() => {
+      signal?.removeEventListener('abort', onAbort);
+      resolve();
+    }, ms);
+    const onAbort = () => {
+      clearTimeout(timer);
+      reject(new Error('Macro cancelled'));
+    };
+    signal?.addEventListener('abort', onAbort, { once: true });
+  });
+}
+
+/**
+ * Resolve once output written after this call leaves the cursor on a line that
+ * matches `pattern`. Requiring fresh output keeps the prompt the command was
+ * typed at from satisfying the wait before the command has even echoed.
+ */
+function waitForPrompt(
+  termId: string,
+  pattern: RegExp,
+  timeoutMs: number,
+  signal?: AbortSignal,
+): Promise<void> {
+  const term = terminalCache.get(termId)?.term;
+  if (!term) {
+    return Promise.reject(new Error('Terminal closed during macro'));
+  }
+  return new Promise((resolve, reject) => {
+    const finish = (error?: Error) => {
+      clearTimeout(timer);
+      writeDisposable.dispose();
+      signal?.removeEventListener('abort', onAbort);
+      if (error) reject(error);
+      else resolve();
+    };
+    const onAbort = () => finish(new Error('Macro cancelled'));
+    const timer = setTimeout(
+      () => finish(new Error(`No prompt after ${Math.round(timeoutMs / 1000)}s`)),
+      timeoutMs,
+    );
+    const writeDisposable = term.onWriteParsed(() => {
+      const line = cursorLine(termId);
+      if (line !== null && pattern.test(line)) finish();
+    });
+    signal?.addEventListener('abort', onAbort, { once: true });
+  });
+}
+
+/** Replay `steps` into a terminal. Rejects on timeout, cancellation or a bad pattern. */
+export async function replayMacro(
+  termId: string,
+  steps: MacroStep[],
+  signal?: AbortSignal,
+): Promise<void> {
+  for (const step of steps) {
+    if (signal?.aborted) throw new Error('Macro cancelled');
+    if (!terminalCache.has(termId)) throw new Error('Terminal closed during macro');
+
+    switch (step.kind) {
+      case 'input':
+        // The following wait subscribes in this same tick, before any echo can be parsed.
+        queueTerminalInput(termId, step.data);
+        break;
+      case 'waitForPrompt': {
+        let pattern = DEFAULT_PROMPT_PATTERN;
+        if (step.pattern) {
+          try {
+            pattern = new RegExp(step.pattern);
+          } catch {
+            throw new Error(`Invalid prompt pattern: ${step.pattern}`);
+          }
+        }
+        await waitForPrompt(termId, pattern, step.timeoutMs ?? DEFAULT_PROMPT_TIMEOUT_MS, signal);
+        break;
+      }
+      case 'delay':
+        await delay(step.ms, signal);
+        break;
+    }
+  }
+}
@@ -0,0 +1,111 @@
+import type { IMarker, Terminal } from '@xterm/xterm';
+import {
+  addOutputBookmarkIpc,
+  type OutputBookmark,
+} from '../../features/outputBookmarks/infrastructure/outputBookmarksIpc.js';
+import { LOCAL_TERMINAL_CONNECTION_ID } from './connectionIds.js';
+import { terminalCache } from './terminalCache.js';
+
+/** Saved when there is neither a selection nor a committed command to start from. */
+const FALLBACK_EXCERPT_LINES = 40;
+/** Upper bound for "everything since the last command". */
+const MAX_EXCERPT_LINES = 2000;
+
+/** Scrollback positions of bookmarks made this session, by bookmark id. */
+const liveMarkers = new Map<string, { termId: string; marker: IMarker }>();
+
+/**
+ * Called when a command line is committed, so a later bookmark knows which
+ * command produced the output and where that output starts.
+ */
+export function markTerminalCommand(termId: string, command: string): void {
+  const cached = terminalCache.get(termId);
+  if (!cached) return;
+  cached.lastCommandMarker?.dispose();
+  cached.lastCommand = command;
+  cached.lastCommandMarker = cached.term.registerMarker(0);
+}
+
+function readLines(term: Terminal, from: number, to: number): string {
+  const buf = term.buffer.active;
+  const lines: string[] = [];
+  for (let i = from; i < to; i++) {
+    const line = buf.getLine(i);
+    if (line) lines.push(line.translateToString(true));
+  }
+  return lines.join('\n').trimEnd();
+}
+
+/** The selection if there is one, else the output since the last command. */
+function captureExcerpt(termId: string): { excerpt: string; line: number } | null {
+  const cached = terminalCache.get(termId);
+  if (!cached) return null;
+  const { term } = cached;
+  const buf = term.buffer.active;
+
+  const selection = term.getSelection();
+  if (selection.trim()) {
+    const position = term.getSelectionPosition();
+    return { excerpt: selection, line: position?.start.y ?? buf.baseY + buf.cursorY };
+  }
+
+  const commandMarker = cached.lastCommandMarker;
+  const start = commandMarker && !commandMarker.isDisposed && commandMarker.line >= 0
+    ? Math.max(commandMarker.line, buf.length - MAX_EXCERPT_LINES)
+    : Math.max(0, buf.length - FALLBACK_EXCERPT_LINES);
+  const excerpt = readLines(term, start, buf.length);
+  return excerpt.trim() ? { excerpt, line: start } : null;
+}
+
+/**
+ * Bookmarks the selected output (or the output of the last command) of
+ * `termId`, with that command and `cwd` as context.
+ */
+export async function bookmarkTerminalOutput(
+  termId: string,
+  options: { cwd?: string; label?: string } = {},
+): Promise<OutputBookmark> {
+  const cached = terminalCache.get(termId);
+  const captured = captureExcerpt(termId);
+  if (!cached || !captured) {
+    throw new Error('Nothing to bookmark: the terminal has no output yet');
+  }
+  const bookmark = await addOutputBookmarkIpc({
+    connectionId: cached.connectionId ?? LOCAL_TERMINAL_CONNECTION_ID,
+    termId,
+    label: options.label,
+    command: cached.lastCommand ?? null,
+    cwd: options.cwd ?? null,
+    excerpt: captured.excerpt,
+  });
+  const buf = cached.term.buffer.active;
+  const marker = cached.term.registerMarker(captured.line - (buf.baseY + buf.cursorY));
+  if (marker) liveMarkers.set(bookmark.id, { termId, marker });
+  return bookmark;
+}
+
+/** Whether the bookmarked output is still in an open terminal's scrollback. */
+export function isOutputBookmarkLive(bookmarkId: string): boolean {
+  const live = liveMarkers.get(bookmarkId);
+  return Boolean(live && !live.marker.isDisposed && terminalCache.has(live.termId));
+}
+
+/**
+ * Scrolls the bookmark's terminal to it. Returns the terminal id, or null
+ * when the scrollback is gone (closed tab, restart, scrolled out).
+ */
+export function jumpToOutputBookmark(bookmarkId: string): string | null {
+  const live = liveMarkers.get(bookmarkId);
+  const cached = live ? terminalCache.get(live.termId) : undefined;
+  if (!live || !cached || live.marker.isDisposed) {
+    liveMarkers.delete(bookmarkId);
+    return null;
+  }
+  cached.term.scrollToLine(Math.max(0, live.marker.line - 2));
+  return live.termId;
+}
+
+export function forgetOutputBookmark(bookmarkId: string): void {
+  liveMarkers.get(bookmarkId)?.marker.dispose();
+  liveMarkers.delete(bookmarkId);
+}
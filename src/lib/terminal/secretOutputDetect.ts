@@ -0,0 +1,101 @@
+import { stripAnsi } from '../ghostSuggestions/promptCwdSniffer.js';
+
+/**
+ * Spots credentials scrolling by in terminal output (private keys, AWS
+ * access keys, JWTs) so the user can be warned and any copy that was already
+ * saved (clipboard history, output bookmarks) can be scrubbed.
+ */
+
+export type SecretOutputKind = 'privateKey' | 'awsAccessKey' | 'jwt';
+
+export interface SecretOutputMatch {
+  kind: SecretOutputKind;
+  value: string;
+}
+
+export interface SecretOutputDetectedDetail {
+  termId: string;
+  matches: SecretOutputMatch[];
+}
+
+/** Window event fired with a {@link SecretOutputDetectedDetail}. */
+export const SECRET_OUTPUT_DETECTED_EVENT = 'zync:terminal:secret-detected';
+
+export const SECRET_OUTPUT_KIND_LABELS: Record<SecretOutputKind, string> = {
+  privateKey: 'private key',
+  awsAccessKey: 'AWS access key',
+  jwt: 'JSON Web Token',
+};
+
+/** Large enough to hold a whole RSA-4096 private key block. */
+const MAX_BUFFER_CHARS = 8192;
+/** Values already reported per terminal; bounded so a noisy log can't grow it forever. */
+const MAX_SEEN_PER_TERM = 200;
+
+const SECRET_OUTPUT_PATTERNS: [SecretOutputKind, RegExp][] = [
+  [
+    'privateKey',
+    /-----BEGIN [A-Z0-9 ]*PRIVATE KEY-----[\s\S]*?-----END [A-Z0-9 ]*PRIVATE KEY-----/g,
+  ],
+  ['awsAccessKey', /\b(?:AKIA|ASIA)[0-9A-Z]{16}\b/g],
+  ['jwt', /\beyJ[\w-]{8,}\.eyJ[\w-]{8,}\.[\w-]{8,}/g],
+];
+
+const detectBuffers = new Map<string, string>();
+const detectDecoders = new Map<string, TextDecoder>();
+const seenSecrets = new Map<string, Set<string>>();
+
+/** Key blocks echo with CRLF line endings; saved copies use whatever the copy produced. */
+function normalizeMatch(kind: SecretOutputKind, value: string): string {
+  return kind === 'privateKey' ? value.replace(/\r\n/g, '\n') : value;
+}
+
+export function detectSecretsInOutput(text: string): SecretOutputMatch[] {
+  const plain = stripAnsi(text);
+  const matches: SecretOutputMatch[] = [];
+  for (const [kind, pattern] of SECRET_OUTPUT_PATTERNS) {
+    for (const match of plain.matchAll(pattern)) {
+      matches.push({ kind, value: normalizeMatch(kind, match[0]) });
+    }
+  }
+  return matches;
+}
+
+/** Feed PTY output; invokes onDetected once per newly seen secret value. */
+export function feedSecretOutputDetector(
+  termId: string,
+  data: Uint8Array,
+  onDetected: (matches: SecretOutputMatch[]) => void,
+): void {
+  if (!data.length) return;
+
+  let decoder = detectDecoders.get(termId);
+  if (!decoder) {
+    decoder = new TextDecoder('utf-8', { fatal: false });
+    detectDecoders.set(termId, decoder);
+  }
+
+  const chunk = decoder.decode(data, { stream: true });
+  const merged = ((detectBuffers.get(termId) ?? '') + chunk).slice(-MAX_BUFFER_CHARS);
+  detectBuffers.set(termId, merged);
+
+  // Only look at finished lines, so a token split across chunks isn't reported half-written.
+  const found = detectSecretsInOutput(merged.slice(0, merged.lastIndexOf('\n') + 1));
+  if (found.length === 0) return;
+
+  const seen = seenSecrets.get(termId) ?? new Set<string>();
+  seenSecrets.set(termId, seen);
+  const fresh = found.filter((match) => !seen.has(match.value));
+  if (fresh.length === 0) return;
+  for (const match of fresh) {
+    if (seen.size >= MAX_SEEN_PER_TERM) seen.delete(seen.values().next().value as string);
+    seen.add(match.value);
+  }
+  onDetected(fresh);
+}
+
+export function clearSecretOutputDetector(termId: string): void {
+  detectBuffers.delete(termId);
+  detectDecoders.delete(termId);
+  seenSecrets.delete(termId);
+}
@@ -1,4 +1,4 @@
-import type { Terminal } from '@xterm/xterm';
+import type { IMarker, Terminal } from '@xterm/xterm';
 import type { FitAddon } from '@xterm/addon-fit';
 import type { SearchAddon } from '@xterm/addon-search';
 import type { Channel } from '@tauri-apps/api/core';
@@ -46,6 +46,9 @@ export interface TerminalCache {
   lastActivityAt?: number;
   /** Idle-suspend banner already written for the current suspend cycle. */
   idleSuspendNoticeShown?: boolean;
+  /** Last committed command line and where it was typed — context for output bookmarks. */
+  lastCommand?: string;
+  lastCommandMarker?: IMarker;
 }
 
 export const terminalCache = new Map<string, TerminalCache>();
@@ -1,5 +1,11 @@
-/** Copy text via Tauri clipboard plugin with browser fallback. */
-export async function writeTerminalClipboardText(text: string): Promise<void> {
+import { addClipboardHistoryIpc } from '../../features/clipboard/infrastructure/clipboardHistoryIpc.js';
+import { LOCAL_TERMINAL_CONNECTION_ID } from './connectionIds.js';
+
+/**
+ * Copy text via Tauri clipboard plugin with browser fallback, and record it in
+ * the clipboard history under `connectionId` (the backend skips it in incognito).
+ */
+export async function writeTerminalClipboardText(text: string, connectionId?: string): Promise<void> {
   try {
     const { writeText } = await import('@tauri-apps/plugin-clipboard-manager');
     await writeText(text);
@@ -7,6 +13,10 @@ export async function writeTerminalClipboardText(text: string): Promise<void> {
     console.error('Tauri copy failed, falling back to navigator:', error);
     await navigator.clipboard.writeText(text);
   }
+  const source = connectionId && connectionId !== LOCAL_TERMINAL_CONNECTION_ID ? connectionId : null;
+  void addClipboardHistoryIpc(text, source).catch((error) => {
+    console.error('Failed to record clipboard history:', error);
+  });
 }
 
 /** Read clipboard text via Tauri with browser fallback. */
@@ -0,0 +1,91 @@
+/**
+ * Primary/secondary follow: navigation commands committed in a primary
+ * terminal are replayed in a secondary one (usually another environment),
+ * with path prefixes rewritten so `cd /srv/app-staging/logs` on one side
+ * becomes `cd /srv/app-prod/logs` on the other.
+ *
+ * Only single navigation commands are mirrored — anything with a pipe,
+ * redirect or command separator stays in the primary.
+ */
+
+export interface FollowPathRule {
+  /** Path prefix as seen in the primary. */
+  from: string;
+  /** Replacement in the secondary. */
+  to: string;
+}
+
+/** Commands that move around or look, but don't change anything. */
+const NAVIGATION_COMMANDS = new Set([
+  'cd', 'pushd', 'popd', 'dirs', 'pwd', 'ls', 'll', 'la', 'l', 'tree', 'exa', 'eza',
+]);
+
+const UNSAFE_SHELL_SYNTAX = /[;&|<>`$()\n]/;
+
+function isPathLike(arg: string): boolean {
+  return arg.startsWith('/') || arg === '~' || arg.startsWith('~/');
+}
+
+function trimTrailingSlash(path: string): string {
+  return path.length > 1 ? path.replace(/\/+$/, '') : path;
+}
+
+/**
+ * Rewrites `path` with the longest matching rule. Prefixes match whole
+ * components: `/srv/app` covers `/srv/app/x` but not `/srv/application`.
+ */
+export function translateFollowPath(path: string, rules: FollowPathRule[]): string {
+  let best: FollowPathRule | null = null;
+  for (const rule of rules) {
+    const from = trimTrailingSlash(rule.from.trim());
+    if (!from) continue;
+    const matches = path === from || path.startsWith(from === '/' ? '/' : `${from}/`);
+    if (matches && (!best || from.length > trimTrailingSlash(best.from.trim()).length)) {
+      best = rule;
+    }
+  }
+  if (!best) return path;
+  const from = trimTrailingSlash(best.from.trim());
+  const to = trimTrailingSlash(best.to.trim());
+  const rest = path.slice(from.length);
+  if (!rest) return to;
+  if (to === '/') return rest.startsWith('/') ? rest : `/${rest}`;
+  return `${to}${rest.startsWith('/') ? '' : '/'}${rest}`;
+}
+
+/**
+ * The command to send to the secondary for `command`, or null when it isn't
+ * a plain navigation command. Relative paths are left alone: both sides
+ * moved together, so they resolve to the matching directory.
+ */
+export function followCommand(command: string, rules: FollowPathRule[]): string | null {
+  const trimmed = command.trim();
+  if (!trimmed || UNSAFE_SHELL_SYNTAX.test(trimmed)) return null;
+  const [name, ...args] = trimmed.split(/\s+/);
+  if (!NAVIGATION_COMMANDS.has(name)) return null;
+  const translated = args.map((arg) => {
+    const quote = arg[0] === '"' || arg[0] === "'" ? arg[0] : '';
+    const bare = quote && arg.endsWith(quote) && arg.length > 1 ? arg.slice(1, -1) : arg;
+    if (!isPathLike(bare)) return arg;
+    const next = translateFollowPath(bare, rules);
+    return quote && bare !== arg ? `${quote}${next}${quote}` : next;
+  });
+  return [name, ...translated].join(' ');
+}
+
+/** Parses `from => to` lines; blank lines and `#` comments are skipped. */
+export function parseFollowRules(text: string): FollowPathRule[] {
+  return text
+    .split('\n')
+    .map((line) => line.trim())
+    .filter((line) => line && !line.startsWith('#'))
+    .map((line) => {
+      const [from, ...rest] = line.split('=>');
+      return { from: from.trim(), to: rest.join('=>').trim() };
+    })
+    .filter((rule) => rule.from && rule.to);
+}
+
+export function formatFollowRules(rules: FollowPathRule[]): string {
+  return rules.map((rule) => `${rule.from} => ${rule.to}`).join('\n');
+}
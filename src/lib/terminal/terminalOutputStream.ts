@@ -5,9 +5,59 @@ import { feedSecretInputSniffer } from '../ghostSuggestions/secretInputDetect.js
 import { useAppStore } from '../../store/useAppStore.js';
 import { terminalCache } from './terminalCache.js';
 import { touchTerminalActivity } from './terminalActivity.js';
+import { markMacroSecretPrompt } from './macroRecorder.js';
+import {
+  feedSecretOutputDetector,
+  SECRET_OUTPUT_DETECTED_EVENT,
+  type SecretOutputDetectedDetail,
+} from './secretOutputDetect.js';
 import { silenceTerminalOutputChannel } from './terminalReloadTeardown.js';
 
 const GENERATION_HEADER_BYTES = 4;
+/** Acknowledge rendered output in batches; the backend pauses at ~2 MiB unacknowledged. */
+const ACK_BATCH_BYTES = 128 * 1024;
+const ACK_FLUSH_MS = 50;
+
+interface PendingAck {
+  generation: number;
+  bytes: number;
+  timer: ReturnType<typeof setTimeout> | null;
+}
+
+const pendingAcks = new Map<string, PendingAck>();
+
+function flushOutputAck(termId: string): void {
+  const pending = pendingAcks.get(termId);
+  if (!pending) {
+    return;
+  }
+  pendingAcks.delete(termId);
+  if (pending.timer) {
+    clearTimeout(pending.timer);
+  }
+  if (pending.bytes > 0) {
+    window.ipcRenderer.send('terminal:ack', { termId, generation: pending.generation, bytes: pending.bytes });
+  }
+}
+
+/** Report output xterm has finished parsing so the backend keeps streaming (flow control). */
+function ackOutput(termId: string, generation: number, bytes: number): void {
+  let pending = pendingAcks.get(termId);
+  if (pending && pending.generation !== generation) {
+    flushOutputAck(termId);
+    pending = undefined;
+  }
+  if (!pending) {
+    pending = { generation, bytes: 0, timer: null };
+    pendingAcks.set(termId, pending);
+  }
+  pending.bytes += bytes;
+  if (pending.bytes >= ACK_BATCH_BYTES) {
+    flushOutputAck(termId);
+  } else if (!pending.timer) {
+    pending.timer = setTimeout(() => flushOutputAck(termId), ACK_FLUSH_MS);
+  }
+}
 
 /** Cheap pre-filter before UTF-8 decode + prompt regex work on PTY output. */
 function outputMayContainPrompt(data: Uint8Array): boolean {
@@ -80,28 +130,34 @@ export function attachTerminalOutputChannel(termId: string, term: XTerm): Channe
   }
 
   const channel = new Channel((message) => {
-    const entry = terminalCache.get(termId);
-    if (!entry) {
-      return;
-    }
-
     const payload = toArrayBuffer(message);
     if (!payload || payload.byteLength < GENERATION_HEADER_BYTES) {
       return;
     }
 
     const { generation, data } = decodeTerminalOutputChannelFrame(payload);
-    if (generation !== entry.generation) {
+    const entry = terminalCache.get(termId);
+    if (!entry || generation !== entry.generation) {
+      // Dropped frames still count as consumed so the backend never stalls on them.
+      ackOutput(termId, generation, data.byteLength);
       return;
     }
 
     touchTerminalActivity(termId);
+    if (useAppStore.getState().settings.terminal.secretOutputWarnings !== false) {
+      feedSecretOutputDetector(termId, data, (matches) => {
+        window.dispatchEvent(new CustomEvent<SecretOutputDetectedDetail>(SECRET_OUTPUT_DETECTED_EVENT, {
+          detail: { termId, matches },
+        }));
+      });
+    }
     if (entry.connectionId) {
       const connectionId = entry.connectionId;
       // Always run the bounded secret sniffer; chunk-boundary prefilters can miss prompts.
       feedSecretInputSniffer(termId, data, () => {
         const live = terminalCache.get(termId);
         live?.ghostTracker?.enterSecretInputMode();
+        markMacroSecretPrompt(termId);
       });
       if (outputMayContainPrompt(data)) {
         feedPromptCwdSniffer(termId, data, (path) => {
@@ -110,7 +166,7 @@ export function attachTerminalOutputChannel(termId: string, term: XTerm): Channe
         });
       }
     }
-    term.write(data);
+    term.write(data, () => ackOutput(termId, generation, data.byteLength));
   });
 
   cached.outputChannel = channel;
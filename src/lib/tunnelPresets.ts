@@ -8,6 +8,8 @@ export interface TunnelPreset {
     remoteHost: string;
     remotePort: number;
     bindToAny?: boolean;
+    /** Launch this viewer on a temporary forward instead of saving a tunnel. */
+    viewer?: 'vnc' | 'rdp';
 }
 
 export const TUNNEL_PRESETS: TunnelPreset[] = [
@@ -81,4 +83,26 @@ export const TUNNEL_PRESETS: TunnelPreset[] = [
         remoteHost: '*',
         remotePort: 0,
     },
+    {
+        id: 'vnc',
+        name: 'VNC Desktop',
+        icon: 'Monitor',
+        description: 'Open a VNC viewer through a temporary forward',
+        type: 'local',
+        localPort: 0,
+        remoteHost: 'localhost',
+        remotePort: 5900,
+        viewer: 'vnc',
+    },
+    {
+        id: 'rdp',
+        name: 'Remote Desktop',
+        icon: 'Monitor',
+        description: 'Open an RDP client through a temporary forward',
+        type: 'local',
+        localPort: 0,
+        remoteHost: 'localhost',
+        remotePort: 3389,
+        viewer: 'rdp',
+    },
 ];
@@ -30,6 +30,11 @@ import {
     reduceTabCloseState,
 } from '../features/connections/application/connectionLifecycleService';
 import { pinFeatureOnConnectionIfNeeded } from '../features/connections/application/tunnelAutoStartService';
+import { cancelAutoReconnect } from '../features/connections/application/autoReconnectService';
+import {
+    runAutoConnectPlan,
+    type AutoConnectProgress,
+} from '../features/connections/application/startupAutoConnectService';
 import {
     restartTunnelsAfterConnect,
     snapshotActiveTunnelsForReconnect,
@@ -46,7 +51,20 @@ import { connectionErrorMessage } from '../features/connections/domain/errorSani
 import { useVaultStore } from '../vault/useVaultStore';
 import { isVaultInUseError, VAULT_IN_USE_USER_MESSAGE } from '../vault/vaultLoading';
 import { isVaultLockedError } from '../vault/vaultUnlockPrompt';
-import { connectIpc, disconnectIpc, getRemoteCwdIpc, transportLostIpc } from '../features/connections/infrastructure/connectionIpc';
+import {
+    cancelConnectIpc,
+    CONNECT_CANCELLED_PREFIX,
+    connectIpc,
+    disconnectIpc,
+    clearWorkspaceRestoreIpc,
+    getAutoConnectPlanIpc,
+    getRemoteCwdIpc,
+    getWorkspaceRestorePlanIpc,
+    parseDuplicateSessionError,
+    transportLostIpc,
+    type DuplicateSessionChoice,
+    type WorkspaceRestorePlanPayload,
+} from '../features/connections/infrastructure/connectionIpc';
 import { seedRemoteGhostHistory } from '../lib/ghostSuggestions/client';
 import { ghostDebug } from '../lib/ghostSuggestions/ghostDebug';
 import { runSerializedConnectionOp } from '../features/connections/infrastructure/connectionOpQueue';
@@ -93,12 +111,32 @@ export interface ConnectionSlice {
     clearConnections: () => void;
 
     // Connection Actions
-    connect: (id: string, options?: { skipVaultPrompt?: boolean }) => Promise<void>;
+    /** `onDuplicate` answers the duplicate-session prompt up front (e.g. for launch auto-connect). */
+    /** `quiet` leaves reporting a failed connect to the caller (see `autoReconnectService`). */
+    /** `restartTunnels: false` leaves the tunnels to the caller, which restores them on the backend. */
+    connect: (id: string, options?: {
+        skipVaultPrompt?: boolean;
+        onDuplicate?: DuplicateSessionChoice;
+        quiet?: boolean;
+        restartTunnels?: boolean;
+    }) => Promise<void>;
     disconnect: (id: string) => Promise<void>;
+    /** Abort a connect that is still handshaking or authenticating. */
+    cancelConnect: (id: string) => Promise<void>;
     /** WiFi drop / SSH EOF — stop active tunnels, keep terminal tabs and scrollback. */
     handleTransportLost: (id: string) => Promise<void>;
     /** Connect when a host tab is opened or restored and the SSH session is not live. */
     autoConnectIfNeeded: (connectionId: string) => void;
+    /** Per-connection progress of the launch-time auto-connect run. */
+    autoConnectProgress: Record<string, AutoConnectProgress>;
+    /** Connect every `autoConnect` host, jump hosts first. Called once after session restore. */
+    autoConnectOnLaunch: () => Promise<void>;
+    /** Connections and tunnels that were live at the last shutdown, offered for restore. */
+    workspaceRestorePlan: WorkspaceRestorePlanPayload | null;
+    loadWorkspaceRestorePlan: () => Promise<void>;
+    /** Reconnect the last workspace and restart its tunnels, skipping whatever fails. */
+    restoreWorkspace: () => Promise<void>;
+    dismissWorkspaceRestore: () => void;
 
     // Tab Actions
     openTab: (connectionId: string, startView?: CoreTabView) => void;
@@ -122,6 +160,7 @@ export interface ConnectionSlice {
 
     // Favorite Actions
     toggleFavorite: (connectionId: string) => void;
+    toggleAutoConnect: (connectionId: string) => void;
 
     // Feature Pinning
     toggleConnectionFeature: (connectionId: string, feature: string) => void;
@@ -160,6 +199,8 @@ export const createConnectionSlice: StateCreator<AppStore, [], [], ConnectionSli
     folders: [],
     isAddConnectionModalOpen: false,
     editingConnectionId: null,
+    autoConnectProgress: {},
+    workspaceRestorePlan: null,
 
     setAddConnectionModalOpen: (open) => set(
         open ? { isAddConnectionModalOpen: true }
@@ -202,6 +243,8 @@ export const createConnectionSlice: StateCreator<AppStore, [], [], ConnectionSli
             }
         } catch (error) {
             console.error('Failed to load connections:', error);
+            const message = error instanceof Error ? error.message : String(error);
+            get().showToast('error', `Failed to load connections: ${message}. See Settings → General → Storage → Validate Data Files.`, 10000);
         }
     },
 
@@ -453,7 +496,30 @@ export const createConnectionSlice: StateCreator<AppStore, [], [], ConnectionSli
                 }
             }
 
-            const response = await connectIpc(fullConfig);
+            const response = await connectIpc(fullConfig, options?.onDuplicate).catch(async (error) => {
+                const duplicate = parseDuplicateSessionError(error instanceof Error ? error.message : String(error));
+                if (!duplicate) throw error;
+                const choice = await get().showChoiceDialog({
+                    title: 'Already connected',
+                    message: `"${duplicate.existingName}" already has a live session to ${duplicate.target}. Open a new channel on it, or a separate connection?`,
+                    confirmText: 'New Channel',
+                    secondaryText: 'Separate Connection',
+                    cancelText: 'Switch to Existing',
+                });
+                const onDuplicate: DuplicateSessionChoice = choice === 'confirm'
+                    ? 'new-channel'
+                    : choice === 'secondary' ? 'separate-connection' : 'focus-existing';
+                return connectIpc(fullConfig, onDuplicate);
+            });
+            if (response?.focus_connection_id) {
+                set(state => ({
+                    connections: markConnectionStatus(state.connections, id, 'disconnected'),
+                }));
+                const pendingTab = findConnectionTab(get().tabs, id);
+                if (pendingTab) get().closeTab(pendingTab.id);
+                get().openTab(response.focus_connection_id);
+                return;
+            }
             markConnectionBackendLive(id);
 
             // Fetch home path after connection
@@ -501,6 +567,8 @@ export const createConnectionSlice: StateCreator<AppStore, [], [], ConnectionSli
                 }).catch(() => {});
             }
 
+            if (options?.restartTunnels === false) return;
+
             try {
                 await get().loadTunnels(id);
                 const tunnels = get().tunnels[id] || [];
@@ -533,6 +601,12 @@ export const createConnectionSlice: StateCreator<AppStore, [], [], ConnectionSli
             }
         } catch (error) {
             const message = connectionErrorMessage(error);
+            if (message.startsWith(CONNECT_CANCELLED_PREFIX)) {
+                set(state => ({
+                    connections: markConnectionStatus(state.connections, id, 'disconnected'),
+                }));
+                return;
+            }
             if (!skipVaultPrompt && isVaultLockedError(message)) {
                 const unlocked = await useVaultStore.getState().requestUnlock();
                 if (unlocked) {
@@ -544,7 +618,7 @@ export const createConnectionSlice: StateCreator<AppStore, [], [], ConnectionSli
                 return;
             }
             console.error('Connection failed:', message);
-            get().showToast('error', `Connection failed: ${message}`, 10000);
+            if (!options?.quiet) get().showToast('error', `Connection failed: ${message}`, 10000);
             // Only update to error state if not already in error to prevent loops
             set(state => {
                 const nextConnections = markConnectionErrorIfNeeded(state.connections, id, message);
@@ -592,6 +666,7 @@ export const createConnectionSlice: StateCreator<AppStore, [], [], ConnectionSli
     },
 
     disconnect: async (id) => {
+        cancelAutoReconnect(id);
         return runSerializedConnectionOp(id, async () => {
         markConnectionBackendOffline(id);
 
@@ -633,6 +708,15 @@ export const createConnectionSlice: StateCreator<AppStore, [], [], ConnectionSli
         });
     },
 
+    // Not serialized: the connect being cancelled holds this connection's op queue.
+    cancelConnect: async (id) => {
+        try {
+            await cancelConnectIpc(id);
+        } catch (error) {
+            console.error('Failed to cancel connect:', error);
+        }
+    },
+
     autoConnectIfNeeded: (connectionId) => {
         if (connectionId === LOCAL_TERMINAL_CONNECTION_ID) {
             return;
@@ -644,6 +728,103 @@ export const createConnectionSlice: StateCreator<AppStore, [], [], ConnectionSli
         }
     },
 
+    autoConnectOnLaunch: async () => {
+        const plan = await getAutoConnectPlanIpc().catch((error) => {
+            console.error('[AutoConnect] Failed to plan launch connections:', error);
+            return null;
+        });
+        if (!plan || plan.waves.length === 0 && plan.skipped.length === 0) return;
+
+        const isConnected = (id: string) =>
+            get().connections.find(c => c.id === id)?.status === 'connected';
+        const summary = await runAutoConnectPlan({
+            plan,
+            connect: async (id) => {
+                // A restored tab may already be connecting this host; wait for it instead of racing.
+                await runSerializedConnectionOp(id, async () => {});
+                if (!isConnected(id)) {
+                    // Nobody is there to answer the duplicate prompt; share the live session.
+                    await get().connect(id, { onDuplicate: 'new-channel' });
+                }
+            },
+            isConnected,
+            onProgress: (progress) => set(state => ({
+                autoConnectProgress: { ...state.autoConnectProgress, [progress.connectionId]: progress },
+            })),
+        });
+
+        const total = summary.connected + summary.failed + summary.skipped;
+        if (summary.connected === total) {
+            get().showToast('success', `Connected ${total} host${total === 1 ? '' : 's'} on launch`);
+        } else {
+            get().showToast(
+                'warning',
+                `Auto-connect: ${summary.connected} connected, ${summary.failed} failed, ${summary.skipped} skipped`,
+                8000,
+            );
+        }
+    },
+
+    loadWorkspaceRestorePlan: async () => {
+        const plan = await getWorkspaceRestorePlanIpc().catch((error) => {
+            console.error('[Workspace] Failed to load the last workspace:', error);
+            return null;
+        });
+        set({ workspaceRestorePlan: plan });
+    },
+
+    restoreWorkspace: async () => {
+        const plan = get().workspaceRestorePlan;
+        if (!plan) return;
+        set({ workspaceRestorePlan: null });
+        clearWorkspaceRestoreIpc().catch(e => console.warn('[Workspace] Failed to clear the last workspace:', e));
+
+        const isConnected = (id: string) =>
+            get().connections.find(c => c.id === id)?.status === 'connected';
+        const summary = await runAutoConnectPlan({
+            plan: plan.connections,
+            connect: async (id) => {
+                await runSerializedConnectionOp(id, async () => {});
+                if (!isConnected(id)) {
+                    await get().connect(id, { onDuplicate: 'new-channel' });
+                }
+            },
+            isConnected,
+            onProgress: (progress) => set(state => ({
+                autoConnectProgress: { ...state.autoConnectProgress, [progress.connectionId]: progress },
+            })),
+        });
+
+        let tunnelsStarted = 0;
+        let tunnelsFailed = plan.skippedTunnels.length;
+        for (const tunnel of plan.tunnels) {
+            if (!isConnected(tunnel.connectionId)) {
+                tunnelsFailed += 1;
+                continue;
+            }
+            try {
+                await get().startTunnel(tunnel.tunnelId, tunnel.connectionId);
+                tunnelsStarted += 1;
+            } catch {
+                tunnelsFailed += 1;
+            }
+        }
+
+        const failed = summary.failed + summary.skipped;
+        const message = `Restored ${summary.connected} connection${summary.connected === 1 ? '' : 's'}`
+            + ` and ${tunnelsStarted} tunnel${tunnelsStarted === 1 ? '' : 's'}`;
+        if (failed === 0 && tunnelsFailed === 0) {
+            get().showToast('success', message);
+        } else {
+            get().showToast('warning', `${message}; ${failed + tunnelsFailed} skipped`, 8000);
+        }
+    },
+
+    dismissWorkspaceRestore: () => {
+        set({ workspaceRestorePlan: null });
+        clearWorkspaceRestoreIpc().catch(e => console.warn('[Workspace] Failed to clear the last workspace:', e));
+    },
+
     openTab: (connectionId, startView: CoreTabView = 'terminal') => {
         let connectAfterOpen: string | null = null;
 
@@ -961,6 +1142,16 @@ export const createConnectionSlice: StateCreator<AppStore, [], [], ConnectionSli
         });
     },
 
+    toggleAutoConnect: (connectionId) => {
+        set(state => {
+            const newConns = state.connections.map(c =>
+                c.id === connectionId ? { ...c, autoConnect: !c.autoConnect || undefined } : c
+            );
+            saveToMain(newConns, state.folders);
+            return { connections: newConns };
+        });
+    },
+
     toggleConnectionFeature: (connectionId, feature) => {
         if (connectionId === 'local') {
             const current = get().settings.localTerm?.pinnedFeatures || [];
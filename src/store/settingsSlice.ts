@@ -15,6 +15,9 @@ import {
 import { resolveTerminalFontWeightBold } from '../lib/terminal/terminalTypography.js';
 import { DEFAULT_SHOW_HOST_ADDRESSES_IN_LISTS } from '../features/connections/domain/connectionDisplay.js';
 import { refreshConnectionTabTitles } from '../features/connections/application/tabService.js';
+import type { ClipboardHistorySettings } from '../features/clipboard/infrastructure/clipboardHistoryIpc';
+import type { RedactionSettings } from '../features/redaction/infrastructure/redactionIpc';
+import type { LogForwardingSettings } from '../features/logForwarding/infrastructure/logForwardingIpc';
 
 export interface AppSettings {
     theme: string;
@@ -48,6 +51,10 @@ export interface AppSettings {
         suspendIdleHostPtys?: boolean;
         /** Minutes before background host PTYs suspend when suspendIdleHostPtys is enabled. */
         idleHostPtySuspendMinutes?: number;
+        /** Warn when private keys, AWS keys or JWTs appear in output (on unless explicitly false). */
+        secretOutputWarnings?: boolean;
+        /** Reconnect dropped SSH sessions with backoff and restore their tunnels (on unless explicitly false). */
+        autoReconnect?: boolean;
         cursorStyle: 'block' | 'underline' | 'bar';
         lineHeight: number;
         padding: number;
@@ -88,6 +95,8 @@ export interface AppSettings {
         termCopy: string;
         termPaste: string;
         termFind: string;
+        /** Start/stop recording a macro in the active terminal. */
+        termRecordMacro: string;
         // View
         zoomIn: string;
         zoomOut: string;
@@ -129,6 +138,20 @@ export interface AppSettings {
         /** When true, browse lists show username@host. When false, show labels/tags (safer for screen share). */
         showHostAddressesInLists: boolean;
     };
+    /** Secret masking for macros, expect transcripts and the audit log; built-in rules when unset. */
+    redaction?: RedactionSettings;
+    /** Copy of connection events and audit entries for a log collector; off when unset. */
+    logForwarding?: LogForwardingSettings;
+    /** Port → service name overrides (`{ "8080": "api" }`); an empty name hides a built-in one. */
+    serviceNames?: Record<string, string>;
+    /** History of terminal copies; enabled, unencrypted and 200 entries when unset. */
+    clipboardHistory?: ClipboardHistorySettings;
+    storage?: {
+        /** Keep connection passwords in the OS keychain so the data files hold no secrets. */
+        splitSecrets: boolean;
+        /** Saving over a data file written by a newer Zync: refuse (default), or keep it as `<name>.format-N.json` first. */
+        newerDataFiles?: 'refuse' | 'keepCopy';
+    };
 }
 
 export const defaultSettings: AppSettings = {
@@ -209,6 +232,7 @@ export const defaultSettings: AppSettings = {
         termCopy: 'Mod+Shift+C',
         termPaste: 'Mod+Shift+V',
         termFind: 'Mod+F',
+        termRecordMacro: 'Mod+Shift+M',
         // View
         zoomIn: 'Mod+=',
         zoomOut: 'Mod+-',
@@ -1,5 +1,11 @@
 import { StateCreator } from 'zustand';
 import type { AppStore } from './useAppStore';
+import {
+    deleteMacroIpc,
+    listMacrosIpc,
+    saveMacroIpc,
+    type TerminalMacro,
+} from '../features/macros/infrastructure/macrosIpc';
 
 export interface Snippet {
     id: string;
@@ -12,11 +18,17 @@ export interface Snippet {
 export interface SnippetsSlice {
     snippets: Snippet[];
     isLoadingSnippets: boolean;
+    /** Recorded input macros, stored next to snippets. */
+    macros: TerminalMacro[];
 
     // Actions
     loadSnippets: () => Promise<void>;
     addSnippet: (snippet: Snippet) => Promise<void>;
     deleteSnippet: (id: string) => Promise<void>;
+    loadMacros: () => Promise<void>;
+    /** Resolves to false when the backend rejected the macro (error already toasted). */
+    saveMacro: (entry: TerminalMacro) => Promise<boolean>;
+    deleteMacro: (id: string) => Promise<void>;
 }
 
 // @ts-ignore
@@ -25,6 +37,7 @@ const ipc = window.ipcRenderer;
 export const createSnippetsSlice: StateCreator<AppStore, [], [], SnippetsSlice> = (set, get) => ({
     snippets: [],
     isLoadingSnippets: false,
+    macros: [],
 
     loadSnippets: async () => {
         set({ isLoadingSnippets: true });
@@ -76,5 +89,38 @@ export const createSnippetsSlice: StateCreator<AppStore, [], [], SnippetsSlice>
             set({ snippets: oldSnippets }); // Revert
             get().showToast('error', 'Failed to delete snippet');
         }
+    },
+
+    loadMacros: async () => {
+        try {
+            set({ macros: await listMacrosIpc() });
+        } catch (error) {
+            console.error('Failed to load macros:', error);
+        }
+    },
+
+    saveMacro: async (entry) => {
+        try {
+            const saved = await saveMacroIpc(entry);
+            const others = get().macros.filter(m => m.id !== saved.id);
+            set({ macros: [...others, saved] });
+            return true;
+        } catch (error) {
+            console.error('Failed to save macro:', error);
+            get().showToast('error', `Failed to save macro: ${error}`);
+            return false;
+        }
+    },
+
+    deleteMacro: async (id) => {
+        const oldMacros = get().macros;
+        set({ macros: oldMacros.filter(m => m.id !== id) });
+        try {
+            await deleteMacroIpc(id);
+        } catch (error) {
+            console.error('Failed to delete macro:', error);
+            set({ macros: oldMacros });
+            get().showToast('error', 'Failed to delete macro');
+        }
     }
 });
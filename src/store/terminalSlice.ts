@@ -1,6 +1,6 @@
 import { StateCreator } from 'zustand';
 import type { AppStore } from './useAppStore';
-import { terminalService } from '../lib/terminal';
+import { terminalService, type FollowPathRule } from '../lib/terminal';
 import type { TerminalTabSnapshot } from './sessionPersistence';
 import { scheduleSaveSession } from './sessionSlice';
 
@@ -17,6 +17,13 @@ export interface TerminalTab {
     shellOverride?: string;
 }
 
+/** A secondary terminal that replays the primary's navigation commands. */
+export interface TerminalFollow {
+    secondaryConnectionId: string;
+    secondaryTermId: string;
+    rules: FollowPathRule[];
+}
+
 export interface TerminalSlice {
     /** Keyed by connectionId, stores the list of terminal tabs for each connection */
     terminals: Record<string, TerminalTab[]>;
@@ -24,6 +31,8 @@ export interface TerminalSlice {
     activeTerminalIds: Record<string, string | null>;
     /** Keyed by connectionId, stores the ID of the terminal that is currently synced with the File Manager */
     syncedTerminalId: Record<string, string | null>;
+    /** Keyed by primary terminal ID; session-only, not persisted */
+    terminalFollows: Record<string, TerminalFollow>;
 
     // Actions
     /**
@@ -93,6 +102,24 @@ export interface TerminalSlice {
      * Called after a successful SSH reconnect so tabs can spawn their PTYs.
      */
     clearPendingRestore: (connectionId: string) => void;
+
+    /**
+     * Shows `follow.secondaryTermId` next to `primaryTermId` and mirrors the
+     * primary's navigation commands into it.
+     */
+    startTerminalFollow: (primaryTermId: string, follow: TerminalFollow) => void;
+
+    /** Stops mirroring; the secondary terminal stays open as a normal tab. */
+    stopTerminalFollow: (primaryTermId: string) => void;
+}
+
+/** Drops follows whose primary or secondary is in `termIds`. */
+function withoutFollowsFor(follows: Record<string, TerminalFollow>, termIds: Set<string>) {
+    return Object.fromEntries(
+        Object.entries(follows).filter(
+            ([primary, follow]) => !termIds.has(primary) && !termIds.has(follow.secondaryTermId),
+        ),
+    );
 }
 
 // @ts-ignore
@@ -102,6 +129,7 @@ export const createTerminalSlice: StateCreator<AppStore, [], [], TerminalSlice>
     terminals: {},
     activeTerminalIds: {},
     syncedTerminalId: {},
+    terminalFollows: {},
 
     /** @inheritdoc */
     createTerminal: (connectionId, opts) => {
@@ -203,6 +231,7 @@ export const createTerminalSlice: StateCreator<AppStore, [], [], TerminalSlice>
                     [connectionId]: newActiveId
                 },
                 syncedTerminalId: nextSyncedIds,
+                terminalFollows: withoutFollowsFor(state.terminalFollows, new Set([termId])),
                 aiConversations: nextConversations,
                 aiDisplayHistory: nextDisplay,
             };
@@ -267,6 +296,7 @@ export const createTerminalSlice: StateCreator<AppStore, [], [], TerminalSlice>
                 terminals: newTerminals,
                 activeTerminalIds: newActiveIds,
                 syncedTerminalId: newSyncedIds,
+                terminalFollows: withoutFollowsFor(state.terminalFollows, termIdsToRemove),
                 aiConversations: nextConversations,
                 aiDisplayHistory: nextDisplay
             };
@@ -371,4 +401,20 @@ export const createTerminalSlice: StateCreator<AppStore, [], [], TerminalSlice>
             };
         });
     },
+
+    /** @inheritdoc */
+    startTerminalFollow: (primaryTermId, follow) => {
+        set(state => ({
+            terminalFollows: { ...state.terminalFollows, [primaryTermId]: follow },
+        }));
+    },
+
+    /** @inheritdoc */
+    stopTerminalFollow: (primaryTermId) => {
+        set(state => {
+            if (!state.terminalFollows[primaryTermId]) return state;
+            const { [primaryTermId]: _, ...rest } = state.terminalFollows;
+            return { terminalFollows: rest };
+        });
+    },
 });
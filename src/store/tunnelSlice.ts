@@ -1,5 +1,6 @@
 import { StateCreator } from 'zustand';
 import type { AppStore } from './useAppStore';
+import { outsideScheduleMessage, type TunnelSchedule } from '../features/tunnels/domain/tunnelSchedule';
 
 export interface TunnelConfig {
     id: string;
@@ -11,10 +12,28 @@ export interface TunnelConfig {
     remotePort: number;
     bindToAny?: boolean;
     bindAddress?: string;
-    status: 'active' | 'error' | 'stopped';
+    /** `reconnecting` while a dropped remote forward is being registered again. */
+    status: 'active' | 'reconnecting' | 'error' | 'stopped';
     autoStart?: boolean;
     error?: string;
     group?: string;
+    /** Browser settings for web forwards (device-local). */
+    web?: {
+        protocol?: 'http' | 'https';
+        path?: string;
+        terminateTls?: boolean;
+        inspect?: boolean;
+        hostHeader?: string;
+        upstreamTls?: boolean;
+        /** Published as `<hostname>.zync.local` in the hosts file while active. */
+        hostname?: string;
+    };
+    /** SOCKS only: domains routed through this tunnel by the PAC file (device-local). */
+    pacDomains?: string[];
+    /** SOCKS only: point the OS proxy settings at this tunnel while it runs (device-local). */
+    systemProxy?: boolean;
+    /** Only run inside these weekly windows (device-local). */
+    schedule?: TunnelSchedule;
 }
 
 export interface TunnelSlice {
@@ -155,6 +174,11 @@ export const createTunnelSlice: StateCreator<AppStore, [], [], TunnelSlice> = (s
             await ipc.invoke('tunnel:start', id);
             // Status update will come from backend event
         } catch (error: any) {
+            // Outside its schedule the tunnel is simply off, not failing.
+            if (outsideScheduleMessage(error) !== null) {
+                get().updateTunnelStatus(id, connectionId, 'stopped');
+                throw error;
+            }
             console.error('Failed to start tunnel:', error);
             get().updateTunnelStatus(id, connectionId, 'error', error.message);
             throw error;
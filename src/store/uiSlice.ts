@@ -6,13 +6,22 @@ export interface ConfirmDialogOpts {
     confirmText?: string;
     cancelText?: string;
     variant?: 'primary' | 'danger';
+    /** Optional third button, shown between cancel and confirm. */
+    secondaryText?: string;
     onConfirm: () => void;
     onCancel: () => void;
+    onSecondary?: () => void;
 }
 
+export type ConfirmDialogChoice = 'confirm' | 'secondary' | 'cancel';
+
 export interface UiSlice {
     confirmDialog: ConfirmDialogOpts | null;
     showConfirmDialog: (opts: Omit<ConfirmDialogOpts, 'onConfirm' | 'onCancel'>) => Promise<boolean>;
+    /** Three-way variant of `showConfirmDialog`; closing the dialog counts as `cancel`. */
+    showChoiceDialog: (
+        opts: Omit<ConfirmDialogOpts, 'onConfirm' | 'onCancel' | 'onSecondary'> & { secondaryText: string },
+    ) => Promise<ConfirmDialogChoice>;
     closeConfirmDialog: () => void;
     _resolveConfirm: ((value: boolean) => void) | null;
     editorDiagnosticsVisible: boolean;
@@ -53,6 +62,29 @@ export const createUiSlice: StateCreator<UiSlice, [], [], UiSlice> = (set, get)
             });
         });
     },
+    showChoiceDialog: (opts) => {
+        return new Promise((resolve) => {
+            const { _resolveConfirm } = get();
+            if (_resolveConfirm) {
+                _resolveConfirm(false);
+            }
+
+            const choose = (choice: ConfirmDialogChoice) => {
+                resolve(choice);
+                get().closeConfirmDialog();
+            };
+            set({
+                confirmDialog: {
+                    ...opts,
+                    onConfirm: () => choose('confirm'),
+                    onSecondary: () => choose('secondary'),
+                    onCancel: () => choose('cancel'),
+                },
+                // Superseded by another dialog: treat as cancelled.
+                _resolveConfirm: () => resolve('cancel'),
+            });
+        });
+    },
     closeConfirmDialog: () => {
         set({ confirmDialog: null, _resolveConfirm: null });
     },
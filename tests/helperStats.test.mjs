@@ -0,0 +1,61 @@
+import assert from 'node:assert/strict';
+import {
+  formatGigabytes,
+  formatUptime,
+  parseHelperStats,
+} from '../.tmp-agent-tests/src/features/remoteHelpers/domain/helperStats.js';
+
+function runTest(name, fn) {
+  try {
+    fn();
+    console.log(`PASS ${name}`);
+  } catch (error) {
+    console.error(`FAIL ${name}`);
+    throw error;
+  }
+}
+
+runTest('parses a zync-stats snapshot', () => {
+  const stats = parseHelperStats([
+    'cpu_percent=12.5',
+    'mem_total_kb=8000000',
+    'mem_available_kb=6000000',
+    'load=0.18 0.21 0.19',
+    'uptime_seconds=90061',
+    'root_total_kb=104857600',
+    'root_used_kb=52428800',
+    'processes=62',
+    'os_name=Debian GNU/Linux 12 (bookworm)',
+    'kernel=6.1.0-18-amd64',
+    'arch=x86_64',
+  ].join('\n'));
+  assert.equal(stats.cpuPercent, 12.5);
+  assert.equal(stats.memTotalKb, 8000000);
+  assert.equal(stats.memAvailableKb, 6000000);
+  assert.equal(stats.uptimeSeconds, 90061);
+  assert.equal(stats.rootUsedKb, 52428800);
+  assert.equal(stats.processes, 62);
+  assert.equal(stats.osName, 'Debian GNU/Linux 12 (bookworm)');
+  assert.equal(stats.kernel, '6.1.0-18-amd64');
+  assert.equal(stats.arch, 'x86_64');
+});
+
+runTest('leaves empty and unreadable values undefined', () => {
+  const stats = parseHelperStats('cpu_percent=\r\nmem_available_kb=\r\nload=\r\narch=AMD64\r\n');
+  assert.equal(stats.cpuPercent, undefined);
+  assert.equal(stats.memAvailableKb, undefined);
+  assert.equal(stats.rootTotalKb, undefined);
+  assert.equal(stats.osName, undefined);
+  assert.equal(stats.arch, 'AMD64');
+});
+
+runTest('formats uptime like uptime -p', () => {
+  assert.equal(formatUptime(90061), '1 day, 1 hour, 1 minute');
+  assert.equal(formatUptime(7320), '2 hours, 2 minutes');
+  assert.equal(formatUptime(30), '0 minutes');
+});
+
+runTest('formats KiB as whole GiB', () => {
+  assert.equal(formatGigabytes(104857600), '100G');
+  assert.equal(formatGigabytes(0), '0G');
+});
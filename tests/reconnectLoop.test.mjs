@@ -0,0 +1,118 @@
+import assert from 'node:assert/strict';
+import {
+  MAX_RECONNECT_ATTEMPTS,
+  cancelReconnectLoop,
+  isReconnectLoopRunning,
+  reconnectDelayMs,
+  runReconnectLoop,
+} from '../.tmp-agent-tests/src/features/connections/application/reconnectLoop.js';
+
+async function runTest(name, fn) {
+  try {
+    await fn();
+    console.log(`PASS ${name}`);
+  } catch (error) {
+    console.error(`FAIL ${name}`);
+    throw error;
+  }
+}
+
+/** Loop deps over a fake connection; `connectResults` are the statuses each connect leaves. */
+function fakeDeps(connectResults, overrides = {}) {
+  const calls = { sleeps: [], connects: 0, restores: 0, progress: [] };
+  let current = { status: 'disconnected' };
+  const deps = {
+    sleep: async (ms) => { calls.sleeps.push(ms); },
+    random: () => 1,
+    now: () => 0,
+    status: () => current,
+    connect: async () => {
+      current = connectResults[Math.min(calls.connects, connectResults.length - 1)];
+      calls.connects += 1;
+    },
+    restoreTunnels: async () => { calls.restores += 1; return 2; },
+    report: (progress) => { calls.progress.push(progress.phase); },
+    ...overrides,
+  };
+  return { deps, calls };
+}
+
+await runTest('reconnectDelayMs doubles from one second and caps at a minute', async () => {
+  const ceilings = Array.from({ length: 9 }, (_, attempt) => reconnectDelayMs(attempt, 1));
+  assert.deepEqual(ceilings, [1000, 2000, 4000, 8000, 16000, 32000, 60000, 60000, 60000]);
+  assert.equal(reconnectDelayMs(40, 1), 60000);
+});
+
+await runTest('reconnectDelayMs jitter spreads over the upper half only', async () => {
+  assert.equal(reconnectDelayMs(0, 0), 500);
+  assert.equal(reconnectDelayMs(3, 0.5), 6000);
+  assert.equal(reconnectDelayMs(6, 0), 30000);
+  assert.equal(reconnectDelayMs(2, -3), 2000);
+  assert.equal(reconnectDelayMs(2, 7), 4000);
+});
+
+await runTest('runReconnectLoop backs off until a connect succeeds, then restores tunnels', async () => {
+  const { deps, calls } = fakeDeps([
+    { status: 'error', lastError: 'Connection refused' },
+    { status: 'error', lastError: 'Connection refused' },
+    { status: 'connected' },
+  ]);
+  const outcome = await runReconnectLoop('conn-ok', deps);
+  assert.deepEqual(outcome, { kind: 'connected', attempts: 3, restoredTunnels: 2 });
+  assert.deepEqual(calls.sleeps, [1000, 2000, 4000]);
+  assert.equal(calls.restores, 1);
+  assert.equal(calls.progress.at(-1), 'connected');
+  assert.equal(isReconnectLoopRunning('conn-ok'), false);
+});
+
+await runTest('runReconnectLoop gives up after the last attempt', async () => {
+  const { deps, calls } = fakeDeps([{ status: 'error', lastError: 'timed out' }]);
+  const outcome = await runReconnectLoop('conn-down', deps);
+  assert.deepEqual(outcome, { kind: 'failed', attempts: MAX_RECONNECT_ATTEMPTS, error: 'timed out' });
+  assert.equal(calls.connects, MAX_RECONNECT_ATTEMPTS);
+  assert.equal(calls.restores, 0);
+});
+
+await runTest('runReconnectLoop stops at errors only the user can fix', async () => {
+  const { deps, calls } = fakeDeps([{ status: 'error', lastError: 'Authentication failed' }]);
+  const outcome = await runReconnectLoop('conn-auth', deps);
+  assert.equal(outcome?.kind, 'failed');
+  assert.equal(calls.connects, 1);
+});
+
+await runTest('a manual disconnect during the wait stops the loop before it connects', async () => {
+  let release;
+  const { deps, calls } = fakeDeps([{ status: 'connected' }], {
+    sleep: () => new Promise((resolve) => { release = resolve; }),
+  });
+  const pending = runReconnectLoop('conn-cancel', deps);
+  assert.equal(isReconnectLoopRunning('conn-cancel'), true);
+  assert.equal(cancelReconnectLoop('conn-cancel'), true);
+  assert.equal(cancelReconnectLoop('conn-cancel'), false);
+  release();
+  assert.deepEqual(await pending, { kind: 'stopped' });
+  assert.equal(calls.connects, 0);
+  assert.equal(isReconnectLoopRunning('conn-cancel'), false);
+});
+
+await runTest('a manual disconnect during a connect skips the tunnel restore', async () => {
+  const { deps, calls } = fakeDeps([{ status: 'connected' }]);
+  const connect = deps.connect;
+  deps.connect = async () => {
+    await connect();
+    cancelReconnectLoop('conn-mid');
+  };
+  assert.deepEqual(await runReconnectLoop('conn-mid', deps), { kind: 'stopped' });
+  assert.equal(calls.restores, 0);
+});
+
+await runTest('only one loop runs per connection', async () => {
+  let release;
+  const { deps } = fakeDeps([{ status: 'connected' }], {
+    sleep: () => new Promise((resolve) => { release = resolve; }),
+  });
+  const first = runReconnectLoop('conn-once', deps);
+  assert.equal(await runReconnectLoop('conn-once', deps), null);
+  release();
+  assert.equal((await first)?.kind, 'connected');
+});
@@ -15,6 +15,8 @@ const tests = [
   'tests/providerCatalog.test.mjs',
   'tests/quickConnectParsing.test.mjs',
   'tests/quickConnectSubcomponents.test.mjs',
+  'tests/reconnectLoop.test.mjs',
+  'tests/helperStats.test.mjs',
   'tests/redactContext.test.mjs',
   'tests/requestContext.test.mjs',
   'tests/sessionPersistence.test.mjs',